@@ -0,0 +1,18 @@
+//! Arbitrary bytes as a chunk file: opening and reading every snapshot must
+//! return errors, never panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rpglot_core::storage::ChunkReader;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(reader) = ChunkReader::from_bytes(data.to_vec(), None) else {
+        return;
+    };
+    for i in 0..reader.snapshot_count() {
+        let _ = reader.read_snapshot(i);
+        let _ = reader.read_blocks(i, &[0]);
+    }
+    let _ = reader.read_interner();
+});
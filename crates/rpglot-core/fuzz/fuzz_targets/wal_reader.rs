@@ -0,0 +1,12 @@
+//! Arbitrary bytes as a WAL file: decoding must stop at the first bad frame,
+//! never panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rpglot_core::storage::StorageManager;
+
+fuzz_target!(|data: &[u8]| {
+    let (_, valid) = StorageManager::decode_wal(data);
+    assert!(valid <= data.len());
+});
@@ -1,8 +1,10 @@
 pub mod recommendations;
 
 use super::{Incident, Severity};
-use crate::storage::model::PgSettingEntry;
+use crate::api::buffercache::BufferCacheReport;
+use crate::api::relations::RelationGrowthReport;
 use crate::storage::model::Snapshot;
+use crate::storage::model::{PgExtensionInfo, PgSettingEntry};
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -22,6 +24,13 @@ pub struct AdvisorContext<'a> {
     pub snapshot: Option<&'a Snapshot>,
     /// Previous snapshot (for delta computation in advisors).
     pub prev_snapshot: Option<&'a Snapshot>,
+    /// Growth of the largest relations between the first and the last
+    /// size sample in the range.
+    pub relation_growth: Option<&'a RelationGrowthReport>,
+    /// Last shared_buffers composition sample in the range (pg_buffercache).
+    pub buffer_cache: Option<&'a BufferCacheReport>,
+    /// Last extension inventory sample in the range, all databases.
+    pub extensions: Option<&'a [PgExtensionInfo]>,
 }
 
 /// Convenient wrapper around pg_settings entries for typed access.
@@ -106,5 +115,11 @@ pub fn all_advisors() -> Vec<Box<dyn Advisor>> {
         Box::new(recommendations::QueryRegressionAdvisor),
         Box::new(recommendations::TempFileSpillAdvisor),
         Box::new(recommendations::PlanRegressionAdvisor),
+        Box::new(recommendations::ConnectionChurnAdvisor),
+        Box::new(recommendations::K8sResourcesAdvisor),
+        Box::new(recommendations::RelationGrowthAdvisor),
+        Box::new(recommendations::SequenceExhaustionAdvisor),
+        Box::new(recommendations::UnencryptedConnectionsAdvisor),
+        Box::new(recommendations::ExtensionsAdvisor),
     ]
 }
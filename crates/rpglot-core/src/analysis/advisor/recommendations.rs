@@ -1,6 +1,9 @@
+use std::collections::BTreeMap;
+
 use crate::analysis::advisor::{Advisor, AdvisorContext, Recommendation};
 use crate::analysis::{Incident, Severity, find_block};
-use crate::storage::model::{DataBlock, PgStatStatementsInfo};
+use crate::api::encryption::connection_encryption;
+use crate::storage::model::{DataBlock, PgExtensionInfo, PgStatStatementsInfo};
 
 // ============================================================
 // Helpers
@@ -578,22 +581,33 @@ impl Advisor for VacuumBlockedAdvisor {
             Some(i) => i,
             None => return Vec::new(),
         };
-        let idle_tx = match find_incident(ctx.incidents, "idle_in_transaction") {
-            Some(i) => i,
-            None => return Vec::new(),
-        };
+        let idle_tx = find_incident(ctx.incidents, "idle_in_transaction");
+        let xmin = find_incident(ctx.incidents, "xmin_horizon");
+        if idle_tx.is_none() && xmin.is_none() {
+            return Vec::new();
+        }
 
-        let mut related = vec![dead, idle_tx];
+        let mut related = vec![dead];
+        related.extend(idle_tx);
+        related.extend(xmin);
         let mut desc = String::from(
-            "Dead tuples accumulating while idle-in-transaction sessions hold the xmin horizon, \
-             preventing VACUUM from reclaiming dead rows. This is the #1 cause of unbounded table bloat.\n\
+            "Dead tuples accumulating while idle-in-transaction sessions or old snapshots hold \
+             the xmin horizon, preventing VACUUM from reclaiming dead rows. This is the #1 cause \
+             of unbounded table bloat.\n\
              \n\
              Fix:\n\
-             \u{2022} Find and fix the idle transactions: PGA tab \u{2192} filter idle in transaction\n\
+             \u{2022} Find the holders: PGA tab \u{2192} Transactions view (v), sort by XMIN_AGE\n\
              \u{2022} Set idle_in_transaction_session_timeout = 30s\u{2013}5min to auto-terminate them\n\
              \u{2022} Fix the application: ensure every BEGIN has a matching COMMIT/ROLLBACK",
         );
 
+        if let Some(x) = xmin {
+            desc.push_str(&format!("\n\nOldest snapshot: {}", x.title));
+            if let Some(ref detail) = x.detail {
+                desc.push_str(&format!("\n{detail}"));
+            }
+        }
+
         if let Some(ref s) = ctx.settings
             && let Some(timeout_ms) = s.get_ms("idle_in_transaction_session_timeout")
         {
@@ -623,7 +637,11 @@ impl Advisor for VacuumBlockedAdvisor {
         vec![Recommendation {
             id: self.id().to_string(),
             severity,
-            title: "VACUUM blocked by idle transactions".to_string(),
+            title: if idle_tx.is_some() {
+                "VACUUM blocked by idle transactions".to_string()
+            } else {
+                "VACUUM blocked by an old snapshot".to_string()
+            },
             description: desc,
             related_incidents: related.iter().map(|i| i.rule_id.clone()).collect(),
         }]
@@ -845,6 +863,11 @@ impl Advisor for WriteAmplificationAdvisor {
 // 14. CacheMissAdvisor
 // ============================================================
 
+/// shared_buffers usage (%) below which misses are not evictions.
+const BUFFERCACHE_FULL_PCT: f64 = 95.0;
+/// Share of hot buffers (%) at which the working set exceeds shared_buffers.
+const BUFFERCACHE_HOT_PCT: f64 = 80.0;
+
 pub struct CacheMissAdvisor;
 
 impl Advisor for CacheMissAdvisor {
@@ -892,6 +915,43 @@ impl Advisor for CacheMissAdvisor {
             }
         }
 
+        if let Some(bc) = ctx.buffer_cache {
+            desc.push_str(&format!(
+                "\n\nshared_buffers contents (pg_buffercache): {:.0}% used, \
+                 {:.0}% hot (usage count \u{2265} 3).",
+                bc.used_pct, bc.hot_pct
+            ));
+            if bc.used_pct < BUFFERCACHE_FULL_PCT {
+                desc.push_str(
+                    " shared_buffers is not full yet: the misses are first reads, \
+                     not evictions \u{2014} a larger shared_buffers will not help.",
+                );
+            } else if bc.hot_pct >= BUFFERCACHE_HOT_PCT {
+                desc.push_str(
+                    " Almost every buffer is re-read before eviction: the working set \
+                     does not fit \u{2014} a larger shared_buffers should help.",
+                );
+            }
+            let top: Vec<String> = bc
+                .relations
+                .iter()
+                .take(3)
+                .map(|r| {
+                    format!(
+                        "{}.{}.{} {:.0}% ({})",
+                        r.database,
+                        r.schema,
+                        r.name,
+                        r.pct_of_cache,
+                        format_bytes(r.bytes as i64)
+                    )
+                })
+                .collect();
+            if !top.is_empty() {
+                desc.push_str(&format!("\nLargest residents: {}.", top.join(", ")));
+            }
+        }
+
         // Add all related cache/io incidents.
         for extra in find_all_incidents(
             ctx.incidents,
@@ -1461,6 +1521,541 @@ impl Advisor for PlanRegressionAdvisor {
     }
 }
 
+// ============================================================
+// 21. ConnectionChurnAdvisor
+// ============================================================
+
+pub struct ConnectionChurnAdvisor;
+
+impl Advisor for ConnectionChurnAdvisor {
+    fn id(&self) -> &'static str {
+        "connection_churn"
+    }
+
+    fn evaluate(&self, ctx: &AdvisorContext<'_>) -> Vec<Recommendation> {
+        let related = find_all_incidents(
+            ctx.incidents,
+            &["connection_rate_high", "short_lived_connections"],
+        );
+        if related.is_empty() {
+            return Vec::new();
+        }
+
+        let mut desc = String::from(
+            "Clients open and close connections at a high rate. Every new connection \
+             forks a backend, authenticates and warms its caches \u{2014} this costs CPU \
+             and adds latency to each request.\n\
+             \n\
+             \u{2022} Use a connection pooler (PgBouncer, pgcat, or an in-app pool) and keep connections open\n\
+             \u{2022} Check PGA tab by application_name / client_addr to find the noisy client\n\
+             \u{2022} Look for retry loops: a failing health check reconnecting in a tight loop looks the same",
+        );
+
+        if let Some(ref s) = ctx.settings {
+            let mut info = Vec::new();
+            for name in ["log_connections", "log_disconnections"] {
+                if let Some(v) = s.get(name) {
+                    info.push(format!("{name}={v}"));
+                }
+            }
+            if !info.is_empty() {
+                desc.push_str(&format!("\n\nCurrent: {}.", info.join(", ")));
+            }
+        }
+
+        let severity = worst_severity(&related);
+
+        vec![Recommendation {
+            id: self.id().to_string(),
+            severity,
+            title: "High connection churn \u{2014} use a connection pooler".to_string(),
+            description: desc,
+            related_incidents: related.iter().map(|i| i.rule_id.clone()).collect(),
+        }]
+    }
+}
+
+// ============================================================
+// 22. K8sResourcesAdvisor
+// ============================================================
+
+pub struct K8sResourcesAdvisor;
+
+impl Advisor for K8sResourcesAdvisor {
+    fn id(&self) -> &'static str {
+        "k8s_resources"
+    }
+
+    fn evaluate(&self, ctx: &AdvisorContext<'_>) -> Vec<Recommendation> {
+        let Some(snap) = ctx.snapshot else {
+            return Vec::new();
+        };
+        let pod = find_block(snap, |b| match b {
+            DataBlock::K8sPod(p) => Some(p),
+            _ => None,
+        });
+        let cgroup = find_block(snap, |b| match b {
+            DataBlock::Cgroup(cg) => Some(cg),
+            _ => None,
+        });
+        let (Some(pod), Some(cgroup)) = (pod, cgroup) else {
+            return Vec::new();
+        };
+
+        let mut findings = Vec::new();
+        let mut severity = Severity::Info;
+
+        if let Some(mem) = &cgroup.memory {
+            if pod.memory_request > 0 && mem.current > pod.memory_request {
+                severity = Severity::Warning;
+                findings.push(format!(
+                    "\u{2022} Memory usage {} is above the request {}: under node memory \
+                     pressure the kubelet evicts pods exceeding their requests first. \
+                     Raise the request to the working set (shared_buffers + connections \
+                     \u{00d7} work_mem) or set request = limit (Guaranteed QoS)",
+                    format_bytes(mem.current as i64),
+                    format_bytes(pod.memory_request as i64),
+                ));
+            }
+            // Without a declared limit the downward API reports node allocatable
+            // while memory.max stays unlimited
+            if pod.memory_limit > 0 && mem.max != u64::MAX && mem.max != pod.memory_limit {
+                findings.push(format!(
+                    "\u{2022} cgroup memory.max {} differs from the declared limit {}: \
+                     the limit was changed outside the pod spec or the collector sees \
+                     another cgroup than the PostgreSQL container",
+                    format_bytes(mem.max as i64),
+                    format_bytes(pod.memory_limit as i64),
+                ));
+            }
+        }
+
+        if let Some(cpu) = &cgroup.cpu {
+            if cpu.quota > 0 && cpu.period > 0 && pod.cpu_limit_millis > 0 {
+                let quota_millis = cpu.quota as u64 * 1000 / cpu.period;
+                if quota_millis.abs_diff(pod.cpu_limit_millis) > 10 {
+                    findings.push(format!(
+                        "\u{2022} cgroup cpu.max allows {quota_millis}m, the declared limit \
+                         is {}m",
+                        pod.cpu_limit_millis
+                    ));
+                }
+            }
+
+            let prev_cpu = ctx
+                .prev_snapshot
+                .and_then(|p| {
+                    find_block(p, |b| match b {
+                        DataBlock::Cgroup(cg) => cg.cpu.as_ref(),
+                        _ => None,
+                    })
+                    .map(|c| (snap.elapsed_secs(p), c))
+                })
+                .filter(|(elapsed, _)| *elapsed > 0);
+            if pod.cpu_request_millis > 0
+                && let Some((elapsed, prev)) = prev_cpu
+            {
+                let used_usec = cpu.usage_usec.saturating_sub(prev.usage_usec);
+                let usage_millis = used_usec / 1000 / elapsed as u64;
+                if usage_millis > pod.cpu_request_millis {
+                    findings.push(format!(
+                        "\u{2022} CPU usage {usage_millis}m is above the request {}m: on a \
+                         busy node the pod only gets CPU time proportional to its request. \
+                         Raise the request to the sustained usage",
+                        pod.cpu_request_millis
+                    ));
+                }
+            }
+        }
+
+        if findings.is_empty() {
+            return Vec::new();
+        }
+
+        let mut related = Vec::new();
+        for rule_id in ["cgroup_throttled", "cgroup_oom_kill"] {
+            if let Some(incident) = find_incident(ctx.incidents, rule_id) {
+                related.push(incident);
+            }
+        }
+        severity = severity.max(worst_severity(&related));
+
+        let desc = format!(
+            "Pod {}/{}: actual usage and cgroup limits do not match the resources \
+             declared in the pod spec.\n\n{}",
+            pod.namespace,
+            pod.pod,
+            findings.join("\n")
+        );
+
+        vec![Recommendation {
+            id: self.id().to_string(),
+            severity,
+            title: "Kubernetes resources do not match usage".to_string(),
+            description: desc,
+            related_incidents: related.iter().map(|i| i.rule_id.clone()).collect(),
+        }]
+    }
+}
+
+// ============================================================
+// 23. RelationGrowthAdvisor
+// ============================================================
+
+/// Table growth per day (TOAST and indexes included) worth a warning.
+const RELATION_GROWTH_WARN_PER_DAY: f64 = 10.0 * 1024.0 * 1024.0 * 1024.0;
+
+/// Shortest distance between size samples to extrapolate from.
+const RELATION_GROWTH_MIN_SPAN_S: i64 = 3600;
+
+/// Tables listed in the description.
+const RELATION_GROWTH_MAX_LISTED: usize = 5;
+
+pub struct RelationGrowthAdvisor;
+
+impl Advisor for RelationGrowthAdvisor {
+    fn id(&self) -> &'static str {
+        "relation_growth"
+    }
+
+    fn evaluate(&self, ctx: &AdvisorContext<'_>) -> Vec<Recommendation> {
+        let Some(growth) = ctx.relation_growth else {
+            return Vec::new();
+        };
+        if growth.span_s() < RELATION_GROWTH_MIN_SPAN_S {
+            return Vec::new();
+        }
+        // Relations are sorted by growth, fastest first
+        let fast: Vec<_> = growth
+            .relations
+            .iter()
+            .filter(|r| r.kind == "table")
+            .filter(|r| {
+                r.bytes_per_day
+                    .is_some_and(|d| d >= RELATION_GROWTH_WARN_PER_DAY)
+            })
+            .collect();
+        if fast.is_empty() {
+            return Vec::new();
+        }
+
+        let mut lines: Vec<String> = fast
+            .iter()
+            .take(RELATION_GROWTH_MAX_LISTED)
+            .map(|r| {
+                format!(
+                    "\u{2022} {}.{}.{}: +{}/day, now {}",
+                    r.database,
+                    r.schema,
+                    r.name,
+                    format_bytes(r.bytes_per_day.unwrap_or(0.0) as i64),
+                    format_bytes(r.bytes as i64),
+                )
+            })
+            .collect();
+        if fast.len() > RELATION_GROWTH_MAX_LISTED {
+            lines.push(format!(
+                "\u{2022} \u{2026} and {} more",
+                fast.len() - RELATION_GROWTH_MAX_LISTED
+            ));
+        }
+
+        let threshold = format_bytes(RELATION_GROWTH_WARN_PER_DAY as i64);
+        let desc = format!(
+            "Tables grow faster than {}/day (sizes include TOAST and indexes, \
+             extrapolated from samples {:.1} h apart):\n\n{}\n\n\
+             \u{2022} Check free space on the data volume against this rate\n\
+             \u{2022} Append-only data: partition by time and drop or archive old partitions\n\
+             \u{2022} Growth without more rows is bloat: check dead tuples and autovacuum in PGT",
+            threshold,
+            growth.span_s() as f64 / 3600.0,
+            lines.join("\n"),
+        );
+
+        vec![Recommendation {
+            id: self.id().to_string(),
+            severity: Severity::Warning,
+            title: format!(
+                "{} table(s) growing faster than {}/day",
+                fast.len(),
+                threshold
+            ),
+            description: desc,
+            related_incidents: Vec::new(),
+        }]
+    }
+}
+
+// ============================================================
+// 24. SequenceExhaustionAdvisor
+// ============================================================
+
+pub struct SequenceExhaustionAdvisor;
+
+impl Advisor for SequenceExhaustionAdvisor {
+    fn id(&self) -> &'static str {
+        "sequence_exhaustion"
+    }
+
+    fn evaluate(&self, ctx: &AdvisorContext<'_>) -> Vec<Recommendation> {
+        // Samples are hourly, so a sequence has an incident per sample:
+        // keep the latest one of each
+        let mut latest: Vec<&Incident> = Vec::new();
+        for inc in ctx
+            .incidents
+            .iter()
+            .filter(|i| i.rule_id == "sequence_exhaustion")
+        {
+            match latest.iter_mut().find(|l| l.merge_key == inc.merge_key) {
+                Some(l) if l.last_ts < inc.last_ts => *l = inc,
+                Some(_) => {}
+                None => latest.push(inc),
+            }
+        }
+        if latest.is_empty() {
+            return Vec::new();
+        }
+        latest.sort_by(|a, b| {
+            b.severity.cmp(&a.severity).then(
+                b.peak_value
+                    .partial_cmp(&a.peak_value)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+        });
+
+        let lines: Vec<String> = latest
+            .iter()
+            .map(|i| {
+                let key = i.merge_key.as_deref().unwrap_or_default();
+                match &i.detail {
+                    Some(detail) => format!("\u{2022} {key} ({detail})"),
+                    None => format!("\u{2022} {key}"),
+                }
+            })
+            .collect();
+        let desc = format!(
+            "Primary key sequences past 70% of their range. nextval() fails once \
+             the limit is reached, and every INSERT into the table fails with it:\n\n{}\n\n\
+             \u{2022} Migrate the column to bigint: ALTER TABLE ... ALTER COLUMN ... TYPE bigint \
+             rewrites the table; on large tables add a bigint column, backfill in batches and swap\n\
+             \u{2022} Raise the sequence too if it was created AS integer: ALTER SEQUENCE ... AS bigint\n\
+             \u{2022} Plan the migration by the days left, not by the percentage",
+            lines.join("\n"),
+        );
+
+        vec![Recommendation {
+            id: self.id().to_string(),
+            severity: worst_severity(&latest),
+            title: format!(
+                "{} primary key sequence(s) close to exhaustion",
+                latest.len()
+            ),
+            description: desc,
+            related_incidents: vec!["sequence_exhaustion".to_string()],
+        }]
+    }
+}
+
+// ============================================================
+// 25. UnencryptedConnectionsAdvisor
+// ============================================================
+
+/// Plaintext client addresses listed in the description.
+const UNENCRYPTED_MAX_LISTED: usize = 5;
+
+pub struct UnencryptedConnectionsAdvisor;
+
+impl Advisor for UnencryptedConnectionsAdvisor {
+    fn id(&self) -> &'static str {
+        "unencrypted_connections"
+    }
+
+    fn evaluate(&self, ctx: &AdvisorContext<'_>) -> Vec<Recommendation> {
+        let Some(info) = ctx.snapshot.and_then(|snap| {
+            find_block(snap, |b| match b {
+                DataBlock::PgStatActivity(v) => connection_encryption(v),
+                _ => None,
+            })
+        }) else {
+            return Vec::new();
+        };
+        if info.plaintext_connections == 0 {
+            return Vec::new();
+        }
+
+        let mut clients: Vec<String> = info
+            .plaintext_clients
+            .iter()
+            .take(UNENCRYPTED_MAX_LISTED)
+            .map(|c| format!("\u{2022} {} ({} sessions)", c.client_addr, c.connections))
+            .collect();
+        let more = info
+            .plaintext_clients
+            .len()
+            .saturating_sub(UNENCRYPTED_MAX_LISTED);
+        if more > 0 {
+            clients.push(format!("\u{2022} ... and {more} more"));
+        }
+        let desc = format!(
+            "{} of {} remote sessions connect without TLS: passwords (unless SCRAM), \
+             queries and results cross the network in plaintext.\n\n{}\n\n\
+             \u{2022} Enable ssl = on with a server certificate\n\
+             \u{2022} Use hostssl instead of host in pg_hba.conf for remote clients, \
+             hostnossl ... reject to refuse the rest\n\
+             \u{2022} Set sslmode=verify-full in client connection strings",
+            info.plaintext_connections,
+            info.plaintext_connections + info.tls_connections,
+            clients.join("\n"),
+        );
+
+        vec![Recommendation {
+            id: self.id().to_string(),
+            severity: Severity::Info,
+            title: format!(
+                "{} remote connection(s) without TLS",
+                info.plaintext_connections
+            ),
+            description: desc,
+            related_incidents: Vec::new(),
+        }]
+    }
+}
+
+// ============================================================
+// 26. ExtensionsAdvisor
+// ============================================================
+
+/// Extensions rpglot reads from, with the tab that needs them.
+const RECOMMENDED_EXTENSIONS: &[(&str, &str, Severity)] = &[
+    ("pg_stat_statements", "PGS", Severity::Warning),
+    ("pg_store_plans", "PGP", Severity::Info),
+];
+
+pub struct ExtensionsAdvisor;
+
+impl Advisor for ExtensionsAdvisor {
+    fn id(&self) -> &'static str {
+        "extensions"
+    }
+
+    fn evaluate(&self, ctx: &AdvisorContext<'_>) -> Vec<Recommendation> {
+        let Some(extensions) = ctx.extensions.filter(|e| !e.is_empty()) else {
+            return Vec::new();
+        };
+        let mut recs = Vec::new();
+        if let Some(rec) = self.missing(ctx, extensions) {
+            recs.push(rec);
+        }
+        if let Some(rec) = self.version_drift(extensions) {
+            recs.push(rec);
+        }
+        recs
+    }
+}
+
+impl ExtensionsAdvisor {
+    /// Recommended extensions installed in none of the databases.
+    fn missing(
+        &self,
+        ctx: &AdvisorContext<'_>,
+        extensions: &[PgExtensionInfo],
+    ) -> Option<Recommendation> {
+        let missing: Vec<_> = RECOMMENDED_EXTENSIONS
+            .iter()
+            .filter(|(name, _, _)| !extensions.iter().any(|e| e.name == *name))
+            .collect();
+        if missing.is_empty() {
+            return None;
+        }
+        let preloaded = ctx
+            .settings
+            .as_ref()
+            .and_then(|s| s.get("shared_preload_libraries"))
+            .unwrap_or_default();
+
+        let lines: Vec<String> = missing
+            .iter()
+            .map(|(name, tab, _)| {
+                let library = if preloaded.split(',').any(|l| l.trim() == *name) {
+                    "already in shared_preload_libraries"
+                } else {
+                    "add to shared_preload_libraries (restart required)"
+                };
+                format!("\u{2022} {name}: {tab} stays empty; {library}")
+            })
+            .collect();
+        let names: Vec<&str> = missing.iter().map(|(name, _, _)| *name).collect();
+        let desc = format!(
+            "Extensions rpglot reads query statistics from are not installed \
+             in any database:\n\n{}\n\n\
+             \u{2022} Run CREATE EXTENSION in the database rpglot connects to \
+             (usually postgres)\n\
+             \u{2022} pg_store_plans is a third-party extension: install the package first",
+            lines.join("\n"),
+        );
+
+        Some(Recommendation {
+            id: self.id().to_string(),
+            severity: missing
+                .iter()
+                .map(|(_, _, severity)| *severity)
+                .max()
+                .unwrap_or(Severity::Info),
+            title: format!("Missing extension(s): {}", names.join(", ")),
+            description: desc,
+            related_incidents: Vec::new(),
+        })
+    }
+
+    /// Extensions installed at different versions in different databases,
+    /// usually left behind by `ALTER EXTENSION ... UPDATE` after a package
+    /// upgrade.
+    fn version_drift(&self, extensions: &[PgExtensionInfo]) -> Option<Recommendation> {
+        // name -> version -> databases, both sorted
+        let mut versions: BTreeMap<&str, BTreeMap<&str, Vec<&str>>> = BTreeMap::new();
+        for e in extensions {
+            versions
+                .entry(&e.name)
+                .or_default()
+                .entry(&e.version)
+                .or_default()
+                .push(&e.datname);
+        }
+        let lines: Vec<String> = versions
+            .iter()
+            .filter(|(_, v)| v.len() > 1)
+            .map(|(name, v)| {
+                let per_version: Vec<String> = v
+                    .iter()
+                    .map(|(version, dbs)| format!("{version} in {}", dbs.join(", ")))
+                    .collect();
+                format!("\u{2022} {name}: {}", per_version.join("; "))
+            })
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        let desc = format!(
+            "The same extension is installed at different versions across databases:\n\n{}\n\n\
+             \u{2022} Run ALTER EXTENSION ... UPDATE in each database lagging behind: \
+             upgrading the package updates only the shared library, not the installed version\n\
+             \u{2022} Check the release notes: some updates rewrite objects and take locks",
+            lines.join("\n"),
+        );
+
+        Some(Recommendation {
+            id: self.id().to_string(),
+            severity: Severity::Info,
+            title: format!(
+                "{} extension(s) with version drift across databases",
+                lines.len()
+            ),
+            description: desc,
+            related_incidents: Vec::new(),
+        })
+    }
+}
+
 // ============================================================
 // Tests
 // ============================================================
@@ -1470,6 +2065,7 @@ mod tests {
     use super::*;
     use crate::analysis::advisor::{AdvisorContext, PgSettings};
     use crate::analysis::{Category, Incident, Severity};
+    use crate::api::buffercache::{BufferCacheRelation, BufferCacheReport};
     use crate::storage::model::PgSettingEntry;
     use crate::storage::model::{DataBlock, PgStatStatementsInfo, Snapshot};
 
@@ -1487,6 +2083,7 @@ mod tests {
             detail: None,
             snapshot_count: 10,
             entity_id: None,
+            planid: None,
         }
     }
 
@@ -1496,6 +2093,9 @@ mod tests {
             settings: None,
             snapshot: None,
             prev_snapshot: None,
+            relation_growth: None,
+            buffer_cache: None,
+            extensions: None,
         }
     }
 
@@ -1618,12 +2218,29 @@ mod tests {
             settings: Some(PgSettings::new(&settings)),
             snapshot: None,
             prev_snapshot: None,
+            relation_growth: None,
+            buffer_cache: None,
+            extensions: None,
         };
         let recs = VacuumBlockedAdvisor.evaluate(&ctx);
         assert_eq!(recs.len(), 1);
         assert!(recs[0].description.contains("disabled"));
     }
 
+    #[test]
+    fn vacuum_blocked_by_xmin_horizon() {
+        let mut xmin = make_incident("xmin_horizon", Severity::Critical);
+        xmin.title = "xmin horizon held back 12.0M transactions by pid 4242".to_string();
+        xmin.detail = Some("idle in transaction for 3600s: SELECT 1".to_string());
+        let incidents = vec![make_incident("dead_tuples_high", Severity::Warning), xmin];
+        let recs = VacuumBlockedAdvisor.evaluate(&make_ctx(&incidents));
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].title, "VACUUM blocked by an old snapshot");
+        assert_eq!(recs[0].severity, Severity::Critical);
+        assert!(recs[0].description.contains("by pid 4242"));
+        assert!(recs[0].description.contains("SELECT 1"));
+    }
+
     #[test]
     fn lock_cascade_fires() {
         let incidents = vec![
@@ -1706,6 +2323,46 @@ mod tests {
         assert_eq!(recs[0].id, "cache_miss_io");
     }
 
+    #[test]
+    fn cache_miss_uses_buffer_cache_residency() {
+        let incidents = vec![
+            make_incident("cache_hit_ratio_drop", Severity::Warning),
+            make_incident("disk_util_high", Severity::Warning),
+        ];
+        let mut report = BufferCacheReport {
+            ts: 1500,
+            used_pct: 99.0,
+            hot_pct: 92.0,
+            relations: vec![BufferCacheRelation {
+                database: "app".to_string(),
+                schema: "public".to_string(),
+                name: "orders".to_string(),
+                kind: "table".to_string(),
+                bytes: 2 * 1024 * 1024 * 1024,
+                pct_of_cache: 50.0,
+                dirty_bytes: 0,
+                avg_usagecount: 4.0,
+            }],
+            ..Default::default()
+        };
+        fn ctx<'a>(incidents: &'a [Incident], report: &'a BufferCacheReport) -> AdvisorContext<'a> {
+            AdvisorContext {
+                buffer_cache: Some(report),
+                ..make_ctx(incidents)
+            }
+        }
+
+        let recs = CacheMissAdvisor.evaluate(&ctx(&incidents, &report));
+        let desc = &recs[0].description;
+        assert!(desc.contains("99% used, 92% hot"));
+        assert!(desc.contains("should help"));
+        assert!(desc.contains("app.public.orders 50% (2.0 GiB)"));
+
+        report.used_pct = 40.0;
+        let recs = CacheMissAdvisor.evaluate(&ctx(&incidents, &report));
+        assert!(recs[0].description.contains("will not help"));
+    }
+
     #[test]
     fn seq_scan_cpu_fires() {
         let incidents = vec![
@@ -1845,6 +2502,9 @@ mod tests {
             settings: None,
             snapshot: Some(&snap),
             prev_snapshot: Some(&prev_snap),
+            relation_growth: None,
+            buffer_cache: None,
+            extensions: None,
         };
         let recs = TempFileSpillAdvisor.evaluate(&ctx);
         assert_eq!(recs.len(), 1);
@@ -1867,6 +2527,9 @@ mod tests {
             settings: None,
             snapshot: Some(&snap),
             prev_snapshot: Some(&prev_snap),
+            relation_growth: None,
+            buffer_cache: None,
+            extensions: None,
         };
         let recs = TempFileSpillAdvisor.evaluate(&ctx);
         assert!(recs.is_empty(), "should skip when temp_blks did not grow");
@@ -1920,4 +2583,263 @@ mod tests {
         assert!(recs[0].title.contains("Latent"));
         assert!(recs[0].description.contains("not yet caused"));
     }
+
+    #[test]
+    fn connection_churn_fires_on_either_rule() {
+        let incidents = vec![make_incident("short_lived_connections", Severity::Warning)];
+        let recs = ConnectionChurnAdvisor.evaluate(&make_ctx(&incidents));
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].id, "connection_churn");
+
+        let incidents = vec![
+            make_incident("connection_rate_high", Severity::Critical),
+            make_incident("short_lived_connections", Severity::Warning),
+        ];
+        let recs = ConnectionChurnAdvisor.evaluate(&make_ctx(&incidents));
+        assert_eq!(recs[0].severity, Severity::Critical);
+        assert_eq!(recs[0].related_incidents.len(), 2);
+
+        assert!(ConnectionChurnAdvisor.evaluate(&make_ctx(&[])).is_empty());
+    }
+
+    #[test]
+    fn k8s_resources_compares_usage_with_declared() {
+        use crate::storage::model::{CgroupCpuInfo, CgroupInfo, CgroupMemoryInfo, K8sPodInfo};
+
+        let snapshot = |timestamp, usage_usec, current| Snapshot {
+            timestamp,
+            blocks: vec![
+                DataBlock::Cgroup(CgroupInfo {
+                    cpu: Some(CgroupCpuInfo {
+                        quota: 200_000,
+                        period: 100_000,
+                        usage_usec,
+                        ..Default::default()
+                    }),
+                    memory: Some(CgroupMemoryInfo {
+                        max: 4 << 30,
+                        current,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                DataBlock::K8sPod(K8sPodInfo {
+                    pod: "pg-0".into(),
+                    namespace: "db".into(),
+                    cpu_request_millis: 500,
+                    cpu_limit_millis: 2000,
+                    memory_request: 1 << 30,
+                    memory_limit: 4 << 30,
+                    ..Default::default()
+                }),
+            ],
+        };
+        let prev = snapshot(1000, 0, 1 << 29);
+        let ctx = |snap| AdvisorContext {
+            incidents: &[],
+            settings: None,
+            snapshot: Some(snap),
+            prev_snapshot: Some(&prev),
+            relation_growth: None,
+            buffer_cache: None,
+            extensions: None,
+        };
+
+        // Within the requests
+        let calm = snapshot(1010, 4_000_000, 1 << 29);
+        assert!(K8sResourcesAdvisor.evaluate(&ctx(&calm)).is_empty());
+
+        // 1.5 cores and 2 GiB used against 500m / 1 GiB requested
+        let busy = snapshot(1010, 15_000_000, 2 << 30);
+        let recs = K8sResourcesAdvisor.evaluate(&ctx(&busy));
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].severity, Severity::Warning);
+        assert!(recs[0].description.contains("db/pg-0"));
+        assert!(recs[0].description.contains("CPU usage 1500m"));
+        assert!(recs[0].description.contains("evicts"));
+    }
+
+    #[test]
+    fn relation_growth_flags_fast_tables() {
+        use crate::api::relations::{RelationGrowth, RelationGrowthReport};
+
+        let gib = 1u64 << 30;
+        let rel = |name: &str, kind: &str, per_day: Option<f64>| RelationGrowth {
+            database: "app".into(),
+            schema: "public".into(),
+            name: name.into(),
+            kind: kind.into(),
+            bytes: 200 * gib,
+            delta_bytes: per_day.map(|d| (d / 4.0) as i64),
+            bytes_per_day: per_day,
+        };
+        let mut growth = RelationGrowthReport {
+            from_ts: 0,
+            to_ts: 6 * 3600,
+            relations: vec![
+                rel("events_pkey", "index", Some(40.0 * gib as f64)),
+                rel("events", "table", Some(24.0 * gib as f64)),
+                rel("orders", "table", Some(2.0 * gib as f64)),
+                rel("new_table", "table", None),
+            ],
+        };
+        fn ctx(growth: &RelationGrowthReport) -> AdvisorContext<'_> {
+            AdvisorContext {
+                incidents: &[],
+                settings: None,
+                snapshot: None,
+                prev_snapshot: None,
+                relation_growth: Some(growth),
+                buffer_cache: None,
+                extensions: None,
+            }
+        }
+
+        let recs = RelationGrowthAdvisor.evaluate(&ctx(&growth));
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].severity, Severity::Warning);
+        assert_eq!(recs[0].title, "1 table(s) growing faster than 10.0 GiB/day");
+        assert!(
+            recs[0]
+                .description
+                .contains("app.public.events: +24.0 GiB/day, now 200.0 GiB")
+        );
+        assert!(!recs[0].description.contains("orders"));
+
+        // Too short to extrapolate
+        growth.to_ts = 600;
+        assert!(RelationGrowthAdvisor.evaluate(&ctx(&growth)).is_empty());
+    }
+
+    #[test]
+    fn sequence_exhaustion_lists_latest_sample_per_sequence() {
+        let seq = |key: &str, last_ts: i64, severity: Severity, detail: &str| Incident {
+            merge_key: Some(key.to_string()),
+            last_ts,
+            detail: Some(detail.to_string()),
+            ..make_incident("sequence_exhaustion", severity)
+        };
+        let incidents = vec![
+            seq("app.public.orders_id_seq", 1000, Severity::Warning, "75%"),
+            seq("app.public.orders_id_seq", 4600, Severity::Critical, "91%"),
+            seq("app.public.users_id_seq", 1000, Severity::Warning, "71%"),
+        ];
+        let recs = SequenceExhaustionAdvisor.evaluate(&make_ctx(&incidents));
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].severity, Severity::Critical);
+        assert_eq!(
+            recs[0].title,
+            "2 primary key sequence(s) close to exhaustion"
+        );
+        assert!(
+            recs[0]
+                .description
+                .contains("app.public.orders_id_seq (91%)")
+        );
+        assert!(!recs[0].description.contains("(75%)"));
+        assert!(
+            recs[0]
+                .description
+                .contains("app.public.users_id_seq (71%)")
+        );
+
+        assert!(
+            SequenceExhaustionAdvisor
+                .evaluate(&make_ctx(&[]))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn unencrypted_connections_lists_plaintext_clients() {
+        use crate::storage::model::PgStatActivityInfo;
+
+        let session = |addr: &str, ssl: bool| PgStatActivityInfo {
+            client_addr: addr.to_string(),
+            ssl: Some(ssl),
+            ..Default::default()
+        };
+        let mut snap = Snapshot {
+            timestamp: 1000,
+            blocks: vec![DataBlock::PgStatActivity(vec![
+                session("", false),
+                session("10.0.0.5/32", true),
+                session("10.0.0.7/32", false),
+            ])],
+        };
+        let incidents = [];
+        let recs = UnencryptedConnectionsAdvisor.evaluate(&AdvisorContext {
+            snapshot: Some(&snap),
+            ..make_ctx(&incidents)
+        });
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].severity, Severity::Info);
+        assert_eq!(recs[0].title, "1 remote connection(s) without TLS");
+        assert!(recs[0].description.starts_with("1 of 2 remote sessions"));
+        assert!(recs[0].description.contains("10.0.0.7 (1 sessions)"));
+
+        // Local sessions alone are not reported
+        snap.blocks = vec![DataBlock::PgStatActivity(vec![session("", false)])];
+        let recs = UnencryptedConnectionsAdvisor.evaluate(&AdvisorContext {
+            snapshot: Some(&snap),
+            ..make_ctx(&incidents)
+        });
+        assert!(recs.is_empty());
+    }
+
+    #[test]
+    fn extensions_flags_missing_and_version_drift() {
+        let ext = |db: &str, name: &str, version: &str| PgExtensionInfo {
+            datname: db.to_string(),
+            name: name.to_string(),
+            version: version.to_string(),
+            schema: "public".to_string(),
+        };
+        let extensions = vec![
+            ext("app", "pg_trgm", "1.6"),
+            ext("postgres", "pg_stat_statements", "1.10"),
+            ext("postgres", "pg_trgm", "1.5"),
+            ext("reports", "pg_trgm", "1.6"),
+        ];
+        let settings = vec![PgSettingEntry {
+            name: "shared_preload_libraries".to_string(),
+            setting: "pg_stat_statements, pg_store_plans".to_string(),
+            unit: String::new(),
+        }];
+        let incidents = [];
+        let recs = ExtensionsAdvisor.evaluate(&AdvisorContext {
+            settings: Some(PgSettings::new(&settings)),
+            extensions: Some(&extensions),
+            ..make_ctx(&incidents)
+        });
+        assert_eq!(recs.len(), 2);
+        assert_eq!(recs[0].severity, Severity::Info);
+        assert_eq!(recs[0].title, "Missing extension(s): pg_store_plans");
+        assert!(
+            recs[0]
+                .description
+                .contains("pg_store_plans: PGP stays empty; already in shared_preload_libraries")
+        );
+        assert_eq!(
+            recs[1].title,
+            "1 extension(s) with version drift across databases"
+        );
+        assert!(
+            recs[1]
+                .description
+                .contains("pg_trgm: 1.5 in postgres; 1.6 in app, reports")
+        );
+
+        // Without pg_stat_statements the warning wins
+        let recs = ExtensionsAdvisor.evaluate(&AdvisorContext {
+            extensions: Some(&extensions[..1]),
+            ..make_ctx(&incidents)
+        });
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].severity, Severity::Warning);
+        assert!(recs[0].description.contains("restart required"));
+
+        // No inventory yet: nothing to report
+        assert!(ExtensionsAdvisor.evaluate(&make_ctx(&incidents)).is_empty());
+    }
 }
@@ -1,12 +1,24 @@
 pub mod advisor;
+pub mod plan_change;
 pub mod rules;
 
 use crate::api::snapshot::HealthBreakdown;
+#[cfg(feature = "api")]
 use crate::provider::HistoryProvider;
 use crate::storage::StringInterner;
-use crate::storage::model::{DataBlock, PgSettingEntry, ProcessInfo, Snapshot};
+#[cfg(feature = "api")]
+use crate::storage::annotations::Annotation;
+#[cfg(feature = "api")]
+use crate::storage::gaps::TimelineGap;
+use crate::storage::heatmap::Hotspot;
+#[cfg(feature = "api")]
+use crate::storage::heatmap::find_hotspots;
+use crate::storage::model::{ClockInfo, DataBlock, PgSequenceInfo, ProcessInfo, Snapshot};
+#[cfg(feature = "api")]
+use crate::storage::model::{PgExtensionInfo, PgRelationSizeInfo, PgSettingEntry};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
+#[cfg(feature = "api")]
 use std::mem;
 
 // ============================================================
@@ -30,6 +42,12 @@ pub enum Category {
     PgEvents,
     PgErrors,
     Cgroup,
+    /// User or CI annotations (deployments, maintenance).
+    Annotation,
+    /// Intervals without snapshots (collector down, host rebooted).
+    DataGap,
+    /// Configuration changes (pg_settings).
+    Settings,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
@@ -72,6 +90,8 @@ pub struct Incident {
     pub snapshot_count: usize,
     /// Entity identifier for navigation (PID, queryid, relid, indexrelid).
     pub entity_id: Option<i64>,
+    /// pg_store_plans planid of plan incidents (the new plan of a plan change).
+    pub planid: Option<i64>,
 }
 
 #[derive(Serialize)]
@@ -100,6 +120,11 @@ pub struct AnalysisReport {
     pub recommendations: Vec<advisor::Recommendation>,
     pub summary: AnalysisSummary,
     pub health_scores: Vec<HealthPoint>,
+    /// Stretches where CPU, I/O, sessions, TPS or WAL stand out, found from
+    /// heatmap series before snapshots are decompressed.
+    pub hotspots: Vec<Hotspot>,
+    /// Queryids that switched to a slower dominant plan, with both plan texts.
+    pub plan_changes: Vec<plan_change::PlanChange>,
 }
 
 #[derive(Serialize)]
@@ -126,6 +151,9 @@ pub struct AnalysisContext<'a> {
     /// OS-level page cache hit % for PG backends: (rchar - read_bytes) / rchar.
     /// None when /proc/pid/io data is unavailable (e.g. macOS, permissions).
     pub backend_io_hit_pct: Option<f64>,
+    /// Previous `PgSequences` sample (hourly) and its timestamp, for
+    /// consumption rates.
+    pub prev_sequences: Option<(i64, &'a [PgSequenceInfo])>,
 }
 
 // ============================================================
@@ -520,6 +548,8 @@ pub fn compute_health_score(
 
 pub struct PrevSample {
     pub timestamp: i64,
+    /// Monotonic clock of the snapshot, for intervals across clock steps.
+    pub clock: Option<ClockInfo>,
     pub cpu_total: u64,
     pub cpu_idle: u64,
     pub cpu_iowait: u64,
@@ -550,6 +580,7 @@ impl PrevSample {
     pub fn extract(snapshot: &Snapshot) -> Self {
         let mut s = Self {
             timestamp: snapshot.timestamp,
+            clock: snapshot.clock().copied(),
             cpu_total: 0,
             cpu_idle: 0,
             cpu_iowait: 0,
@@ -685,6 +716,7 @@ fn sum_cpu_ticks(cpu: &crate::storage::model::SystemCpuInfo) -> u64 {
 // Merge anomalies into incidents
 // ============================================================
 
+#[cfg(feature = "api")]
 fn merge_anomalies(mut anomalies: Vec<Anomaly>) -> Vec<Incident> {
     anomalies.sort_by(|a, b| {
         a.rule_id
@@ -735,6 +767,7 @@ fn merge_anomalies(mut anomalies: Vec<Anomaly>) -> Vec<Incident> {
                 detail: anomaly.detail,
                 snapshot_count: 1,
                 entity_id: anomaly.entity_id,
+                planid: None,
             });
         }
     }
@@ -747,6 +780,106 @@ fn merge_anomalies(mut anomalies: Vec<Anomaly>) -> Vec<Incident> {
     incidents
 }
 
+/// Info incident marking an annotation (zero duration, no snapshots).
+#[cfg(feature = "api")]
+fn annotation_incident(annotation: &Annotation) -> Incident {
+    Incident {
+        rule_id: "annotation".to_string(),
+        category: Category::Annotation,
+        severity: Severity::Info,
+        first_ts: annotation.ts,
+        last_ts: annotation.ts,
+        merge_key: Some(annotation.id.to_string()),
+        peak_ts: annotation.ts,
+        peak_value: 0.0,
+        title: annotation.text.clone(),
+        detail: (!annotation.source.is_empty()).then(|| format!("source: {}", annotation.source)),
+        snapshot_count: 0,
+        entity_id: None,
+        planid: None,
+    }
+}
+
+/// Info incident for one configuration change (zero duration).
+#[cfg(feature = "api")]
+fn setting_change_incident(change: &crate::api::settings::SettingChange) -> Incident {
+    let old = change.old_value.as_deref().unwrap_or("-");
+    let new = change.new_value.as_deref().unwrap_or("-");
+    Incident {
+        rule_id: "setting_change".to_string(),
+        category: Category::Settings,
+        severity: Severity::Info,
+        first_ts: change.ts,
+        last_ts: change.ts,
+        merge_key: Some(format!("{}:{}", change.name, change.ts)),
+        peak_ts: change.ts,
+        peak_value: 0.0,
+        title: change.summary(),
+        detail: Some(if change.unit.is_empty() {
+            format!("old: {old}, new: {new}")
+        } else {
+            format!("old: {old}, new: {new}, unit: {}", change.unit)
+        }),
+        snapshot_count: 0,
+        entity_id: None,
+        planid: None,
+    }
+}
+
+/// Info incident covering an interval without snapshots, so that missing
+/// data is not read as an idle period.
+#[cfg(feature = "api")]
+fn gap_incident(gap: &TimelineGap) -> Incident {
+    let duration = crate::fmt::format_duration(gap.duration(), crate::fmt::FmtStyle::Detail);
+    Incident {
+        rule_id: "data_gap".to_string(),
+        category: Category::DataGap,
+        severity: Severity::Info,
+        first_ts: gap.start,
+        last_ts: gap.end,
+        merge_key: Some(gap.start.to_string()),
+        peak_ts: gap.start,
+        peak_value: gap.duration() as f64,
+        title: format!("No data for {duration}"),
+        detail: Some("collector was not running or the host was down".to_string()),
+        snapshot_count: 0,
+        entity_id: None,
+        planid: None,
+    }
+}
+
+/// Incident of a queryid switching to a slower plan, linked to the queryid
+/// and the new planid.
+#[cfg(feature = "api")]
+fn plan_change_incident(change: &plan_change::PlanChange) -> Incident {
+    let slowdown = change.slowdown();
+    Incident {
+        rule_id: "plan_change".to_string(),
+        category: Category::PgStatements,
+        severity: if change.is_critical() {
+            Severity::Critical
+        } else {
+            Severity::Warning
+        },
+        first_ts: change.switched_at,
+        last_ts: change.last_ts,
+        merge_key: Some(format!("{}:{}", change.queryid, change.to_planid)),
+        peak_ts: change.switched_at,
+        peak_value: slowdown,
+        title: format!(
+            "Plan change: queryid {} plan {} -> {}, {slowdown:.1}x slower",
+            change.queryid, change.from_planid, change.to_planid
+        ),
+        detail: Some(format!(
+            "mean {:.1} ms -> {:.1} ms",
+            change.from_mean_ms, change.to_mean_ms
+        )),
+        snapshot_count: 0,
+        entity_id: Some(change.queryid),
+        planid: Some(change.to_planid),
+    }
+}
+
 // ============================================================
 // Correlate incidents into groups
 // ============================================================
@@ -756,6 +889,7 @@ fn merge_anomalies(mut anomalies: Vec<Anomaly>) -> Vec<Incident> {
 /// - Persistent incidents (duration > 30% of the analysis window) get their own group.
 /// - Short incidents are grouped by interval merging with a 30s gap.
 /// - Every incident ends up in exactly one group (even singletons).
+#[cfg(feature = "api")]
 fn correlate_incidents(incidents: Vec<Incident>, start_ts: i64, end_ts: i64) -> Vec<IncidentGroup> {
     let window = (end_ts - start_ts).max(1);
     let persistent_threshold = window * 30 / 100; // 30% of hour
@@ -852,6 +986,7 @@ fn correlate_incidents(incidents: Vec<Incident>, start_ts: i64, end_ts: i64) ->
     groups
 }
 
+#[cfg(feature = "api")]
 fn flush_group(incidents: &mut Vec<Incident>, id: u32, persistent: bool) -> IncidentGroup {
     let mut taken = mem::take(incidents);
     // Sort within group: severity desc, peak_value desc
@@ -883,17 +1018,20 @@ fn flush_group(incidents: &mut Vec<Incident>, id: u32, persistent: bool) -> Inci
 // Analyzer — orchestrator
 // ============================================================
 
+#[cfg(feature = "api")]
 pub struct Analyzer {
     rules: Vec<Box<dyn rules::AnalysisRule>>,
     advisors: Vec<Box<dyn advisor::Advisor>>,
 }
 
+#[cfg(feature = "api")]
 impl Default for Analyzer {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[cfg(feature = "api")]
 impl Analyzer {
     pub fn new() -> Self {
         Self {
@@ -902,19 +1040,24 @@ impl Analyzer {
         }
     }
 
+    /// Analyze snapshots in `[start_ts, end_ts]`. Annotations in the range
+    /// become Info incidents so they correlate with the anomalies around them.
     pub fn analyze(
         &self,
         provider: &mut HistoryProvider,
         start_ts: i64,
         end_ts: i64,
+        annotations: &[Annotation],
     ) -> AnalysisReport {
         let timestamps = provider.timestamps().to_vec();
         let start_pos = timestamps.partition_point(|&ts| ts < start_ts);
         let end_pos = timestamps.partition_point(|&ts| ts <= end_ts);
 
-        // Pre-load health scores from heatmap (already computed during heatmap build)
-        let heatmap_health: HashMap<i64, u8> = provider
-            .load_heatmap_range(start_ts, end_ts)
+        // Pre-pass over the heatmap: health scores (already computed during
+        // heatmap build) and hotspots of the per-category series
+        let heatmap = provider.load_heatmap_range(start_ts, end_ts);
+        let hotspots = find_hotspots(&heatmap);
+        let heatmap_health: HashMap<i64, u8> = heatmap
             .into_iter()
             .map(|(ts, entry)| (ts, entry.health_score))
             .collect();
@@ -927,6 +1070,14 @@ impl Analyzer {
         let mut health_scores: Vec<HealthPoint> = Vec::new();
         let mut snapshots_analyzed: usize = 0;
         let mut pg_settings_data: Option<Vec<PgSettingEntry>> = None;
+        // First and last relation size samples (hourly) for growth rates
+        let mut first_relation_sizes: Option<(i64, Vec<PgRelationSizeInfo>)> = None;
+        let mut last_relation_sizes: Option<(i64, Vec<PgRelationSizeInfo>)> = None;
+        let mut prev_sequences: Option<(i64, Vec<PgSequenceInfo>)> = None;
+        let mut last_buffer_cache = None;
+        let mut last_extensions: Option<Vec<PgExtensionInfo>> = None;
+        let mut plan_changes = plan_change::PlanChangeTracker::new();
+        let mut settings_changes = crate::api::settings::SettingsTracker::new();
 
         for pos in start_pos..end_pos {
             let Some((snapshot, interner)) = provider.snapshot_with_interner_at(pos) else {
@@ -935,7 +1086,7 @@ impl Analyzer {
 
             let dt = prev_sample
                 .as_ref()
-                .map(|p| (snapshot.timestamp - p.timestamp) as f64)
+                .map(|p| snapshot.elapsed_since(p.timestamp, p.clock.as_ref()) as f64)
                 .unwrap_or(0.0);
 
             ewma.update(&snapshot, prev_sample.as_ref(), dt);
@@ -951,6 +1102,7 @@ impl Analyzer {
                 prev: prev_sample.as_ref(),
                 dt,
                 backend_io_hit_pct,
+                prev_sequences: prev_sequences.as_ref().map(|(ts, v)| (*ts, v.as_slice())),
             };
 
             for rule in &self.rules {
@@ -977,27 +1129,114 @@ impl Analyzer {
                 pg_settings_data = Some(settings);
             }
 
+            if let Some(settings) = find_block(&snapshot, |b| match b {
+                DataBlock::PgSettings(v) => Some(v.as_slice()),
+                _ => None,
+            }) {
+                let pending = find_block(&snapshot, |b| match b {
+                    DataBlock::PgPendingRestart(v) => Some(v.as_slice()),
+                    _ => None,
+                });
+                settings_changes.observe(snapshot.timestamp, settings, pending.unwrap_or(&[]));
+            }
+
+            if let Some(sizes) = find_block(&snapshot, |b| match b {
+                DataBlock::PgRelationSizes(v) => Some(v.clone()),
+                _ => None,
+            }) {
+                let sample = Some((snapshot.timestamp, sizes));
+                if first_relation_sizes.is_none() {
+                    first_relation_sizes = sample;
+                } else {
+                    last_relation_sizes = sample;
+                }
+            }
+
+            if let Some(sequences) = find_block(&snapshot, |b| match b {
+                DataBlock::PgSequences(v) => Some(v.clone()),
+                _ => None,
+            }) {
+                prev_sequences = Some((snapshot.timestamp, sequences));
+            }
+
+            if let Some(extensions) = find_block(&snapshot, |b| match b {
+                DataBlock::PgExtensions(v) => Some(v.clone()),
+                _ => None,
+            }) {
+                last_extensions = Some(extensions);
+            }
+
+            if let Some(plans) = find_block(&snapshot, |b| match b {
+                DataBlock::PgStorePlans(v) => Some(v.as_slice()),
+                _ => None,
+            }) {
+                plan_changes.observe(snapshot.timestamp, plans, &interner);
+            }
+
+            if let Some(info) = find_block(&snapshot, |b| match b {
+                DataBlock::PgBufferCache(v) => Some(v),
+                _ => None,
+            }) {
+                last_buffer_cache = Some(crate::api::buffercache::buffer_cache_report(
+                    snapshot.timestamp,
+                    info,
+                ));
+            }
+
             prev_sample = Some(PrevSample::extract(&snapshot));
             prev_prev_snap = prev_snap.take();
             prev_snap = Some(snapshot);
             snapshots_analyzed += 1;
         }
 
-        // Layer 2: merge
-        let incidents = merge_anomalies(anomalies);
+        // Layer 2: merge; plan changes span the window, so they are found
+        // after the pass rather than by a per-snapshot rule
+        let mut incidents = merge_anomalies(anomalies);
+        let plan_changes = plan_changes.finish();
+        incidents.extend(plan_changes.iter().map(plan_change_incident));
 
         // Layer 3: advisors (run before correlate consumes incidents)
+        let relation_growth = first_relation_sizes.as_ref().map(|(from_ts, first)| {
+            let (to_ts, last) = last_relation_sizes
+                .as_ref()
+                .map_or((*from_ts, first), |(ts, v)| (*ts, v));
+            crate::api::relations::relation_growth((*from_ts, first), (to_ts, last))
+        });
         let advisor_ctx = advisor::AdvisorContext {
             incidents: &incidents,
             settings: pg_settings_data.as_deref().map(advisor::PgSettings::new),
             snapshot: prev_snap.as_ref(),
             prev_snapshot: prev_prev_snap.as_ref(),
+            relation_growth: relation_growth.as_ref(),
+            buffer_cache: last_buffer_cache.as_ref(),
+            extensions: last_extensions.as_deref(),
         };
         let mut recommendations = Vec::new();
         for adv in &self.advisors {
             recommendations.extend(adv.evaluate(&advisor_ctx));
         }
-        recommendations.sort_by(|a, b| b.severity.cmp(&a.severity));
+        recommendations.sort_by_key(|r| std::cmp::Reverse(r.severity));
+
+        // Annotations, configuration changes and gaps join after advisors:
+        // they are context, not symptoms
+        incidents.extend(
+            annotations
+                .iter()
+                .filter(|a| a.ts >= start_ts && a.ts <= end_ts)
+                .map(annotation_incident),
+        );
+        incidents.extend(
+            settings_changes
+                .finish()
+                .iter()
+                .map(setting_change_incident),
+        );
+        incidents.extend(
+            provider
+                .gaps_in_range(start_ts, end_ts)
+                .iter()
+                .map(gap_incident),
+        );
 
         let summary = AnalysisSummary {
             total_incidents: incidents.len(),
@@ -1041,6 +1280,7 @@ impl Analyzer {
                 detail: i.detail.clone(),
                 snapshot_count: i.snapshot_count,
                 entity_id: i.entity_id,
+                planid: i.planid,
             })
             .collect();
 
@@ -1053,6 +1293,8 @@ impl Analyzer {
             recommendations,
             summary,
             health_scores,
+            hotspots,
+            plan_changes,
         }
     }
 }
@@ -0,0 +1,311 @@
+//! Plan change detection over an analysis window (pg_store_plans).
+//!
+//! Per-snapshot rules only see two neighbouring samples. A plan change is a
+//! window-level event: a queryid that ran mostly one planid starts running
+//! mostly another one, and the new plan is slower than the old one was.
+//! [`PlanChangeTracker`] follows the dominant plan of every queryid across
+//! collected samples and reports such switches with both plan texts.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::storage::StringInterner;
+use crate::storage::model::PgStorePlansInfo;
+
+/// Slowdown (new mean / old mean) from which a switch is reported.
+const MIN_SLOWDOWN: f64 = 2.0;
+/// Slowdown from which a switch is critical.
+const CRITICAL_SLOWDOWN: f64 = 10.0;
+/// Mean time (ms) the new plan needs to be worth reporting; a 3x slowdown
+/// of a 1ms query is noise.
+const MIN_SLOW_MEAN_MS: f64 = 5.0;
+
+/// A queryid that switched to a slower dominant plan.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanChange {
+    pub queryid: i64,
+    /// Plan that ran most calls before the switch.
+    pub from_planid: i64,
+    /// Plan that ran most calls after the switch.
+    pub to_planid: i64,
+    /// First sample dominated by the new plan.
+    pub switched_at: i64,
+    /// Last sample dominated by the new plan.
+    pub last_ts: i64,
+    /// Mean execution time (ms) of the old plan while it was dominant.
+    pub from_mean_ms: f64,
+    /// Mean execution time (ms) of the new plan since the switch.
+    pub to_mean_ms: f64,
+    pub from_plan: Option<String>,
+    pub to_plan: Option<String>,
+}
+
+impl PlanChange {
+    /// How many times slower the new plan is.
+    pub fn slowdown(&self) -> f64 {
+        self.to_mean_ms / self.from_mean_ms
+    }
+
+    pub fn is_critical(&self) -> bool {
+        self.slowdown() >= CRITICAL_SLOWDOWN
+    }
+}
+
+/// Time during which one plan ran the majority of a queryid's calls.
+struct Stint {
+    planid: i64,
+    plan_hash: u64,
+    first_ts: i64,
+    last_ts: i64,
+    calls: i64,
+    time_ms: f64,
+}
+
+impl Stint {
+    fn mean_ms(&self) -> f64 {
+        if self.calls > 0 {
+            self.time_ms / self.calls as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Switch to the current stint's plan, judged once that stint ends.
+struct OpenSwitch {
+    from_planid: i64,
+    from_mean_ms: f64,
+    from_plan: Option<String>,
+    to_plan: Option<String>,
+}
+
+#[derive(Default)]
+struct QueryPlans {
+    stint: Option<Stint>,
+    switch: Option<OpenSwitch>,
+}
+
+/// Follows the dominant plan per queryid across PgStorePlans samples.
+#[derive(Default)]
+pub struct PlanChangeTracker {
+    /// Cumulative (calls, total_time) per (queryid, planid) of the last sample.
+    prev: HashMap<(i64, i64), (i64, f64)>,
+    prev_collected_at: i64,
+    queries: HashMap<i64, QueryPlans>,
+    changes: Vec<PlanChange>,
+}
+
+impl PlanChangeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the PgStorePlans block of a snapshot. Cached samples (same
+    /// `collected_at` as the previous one) are skipped.
+    pub fn observe(&mut self, ts: i64, plans: &[PgStorePlansInfo], interner: &StringInterner) {
+        let collected_at = plans.iter().map(|p| p.collected_at).max().unwrap_or(0);
+        if collected_at == 0 || collected_at == self.prev_collected_at {
+            return;
+        }
+        let first_sample = self.prev.is_empty();
+
+        // Call and time deltas per plan, grouped by queryid
+        let mut deltas: HashMap<i64, Vec<(&PgStorePlansInfo, i64, f64)>> = HashMap::new();
+        for p in plans {
+            if p.stmt_queryid == 0 {
+                continue;
+            }
+            let Some(&(calls, time)) = self.prev.get(&(p.stmt_queryid, p.planid)) else {
+                continue;
+            };
+            let d_calls = p.calls - calls;
+            let d_time = p.total_time - time;
+            if d_calls > 0 && d_time >= 0.0 {
+                deltas
+                    .entry(p.stmt_queryid)
+                    .or_default()
+                    .push((p, d_calls, d_time));
+            }
+        }
+        self.prev = plans
+            .iter()
+            .map(|p| ((p.stmt_queryid, p.planid), (p.calls, p.total_time)))
+            .collect();
+        self.prev_collected_at = collected_at;
+        if first_sample {
+            return;
+        }
+
+        for (queryid, group) in deltas {
+            let total: i64 = group.iter().map(|(_, calls, _)| calls).sum();
+            let Some(&(plan, calls, time)) = group.iter().max_by_key(|(_, calls, _)| *calls) else {
+                continue;
+            };
+            // No plan ran the majority of calls: nothing dominates
+            if calls * 2 <= total {
+                continue;
+            }
+            let query = self.queries.entry(queryid).or_default();
+            match &mut query.stint {
+                Some(stint) if stint.planid == plan.planid => {
+                    stint.last_ts = ts;
+                    stint.calls += calls;
+                    stint.time_ms += time;
+                }
+                _ => {
+                    let old = query.stint.take();
+                    if let Some(old) = &old {
+                        Self::close_switch(&mut self.changes, queryid, query.switch.take(), old);
+                        query.switch = Some(OpenSwitch {
+                            from_planid: old.planid,
+                            from_mean_ms: old.mean_ms(),
+                            from_plan: resolve(interner, old.plan_hash),
+                            to_plan: resolve(interner, plan.plan_hash),
+                        });
+                    }
+                    query.stint = Some(Stint {
+                        planid: plan.planid,
+                        plan_hash: plan.plan_hash,
+                        first_ts: ts,
+                        last_ts: ts,
+                        calls,
+                        time_ms: time,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Switches to a slower plan, in order of the switch.
+    pub fn finish(mut self) -> Vec<PlanChange> {
+        for (queryid, query) in self.queries {
+            if let Some(stint) = &query.stint {
+                Self::close_switch(&mut self.changes, queryid, query.switch, stint);
+            }
+        }
+        self.changes
+            .sort_by_key(|c| (c.switched_at, c.queryid, c.to_planid));
+        self.changes
+    }
+
+    /// Records `switch` to the plan of `stint` if that plan was slower.
+    fn close_switch(
+        changes: &mut Vec<PlanChange>,
+        queryid: i64,
+        switch: Option<OpenSwitch>,
+        stint: &Stint,
+    ) {
+        let Some(switch) = switch else {
+            return;
+        };
+        let to_mean_ms = stint.mean_ms();
+        if switch.from_mean_ms <= 0.0
+            || to_mean_ms < MIN_SLOW_MEAN_MS
+            || to_mean_ms < switch.from_mean_ms * MIN_SLOWDOWN
+        {
+            return;
+        }
+        changes.push(PlanChange {
+            queryid,
+            from_planid: switch.from_planid,
+            to_planid: stint.planid,
+            switched_at: stint.first_ts,
+            last_ts: stint.last_ts,
+            from_mean_ms: switch.from_mean_ms,
+            to_mean_ms,
+            from_plan: switch.from_plan,
+            to_plan: switch.to_plan,
+        });
+    }
+}
+
+fn resolve(interner: &StringInterner, hash: u64) -> Option<String> {
+    (hash != 0)
+        .then(|| interner.resolve(hash))
+        .flatten()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(
+        queryid: i64,
+        planid: i64,
+        plan_hash: u64,
+        calls: i64,
+        total_time: f64,
+        collected_at: i64,
+    ) -> PgStorePlansInfo {
+        PgStorePlansInfo {
+            stmt_queryid: queryid,
+            planid,
+            plan_hash,
+            calls,
+            total_time,
+            collected_at,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_switch_to_slower_plan() {
+        let mut interner = StringInterner::new();
+        let index_scan = interner.intern("Index Scan using orders_pkey on orders");
+        let seq_scan = interner.intern("Seq Scan on orders");
+        let mut tracker = PlanChangeTracker::new();
+
+        // 10 calls per sample at 2ms on plan 1, then plan 2 at 40ms
+        tracker.observe(0, &[plan(7, 1, index_scan, 100, 200.0, 1)], &interner);
+        tracker.observe(300, &[plan(7, 1, index_scan, 110, 220.0, 2)], &interner);
+        let both = |calls1, time1, calls2, time2, at| {
+            vec![
+                plan(7, 1, index_scan, calls1, time1, at),
+                plan(7, 2, seq_scan, calls2, time2, at),
+            ]
+        };
+        tracker.observe(600, &both(120, 240.0, 0, 0.0, 3), &interner);
+        tracker.observe(900, &both(121, 242.0, 10, 400.0, 4), &interner);
+        // Cached sample: ignored
+        tracker.observe(910, &both(121, 242.0, 10, 400.0, 4), &interner);
+        tracker.observe(1200, &both(121, 242.0, 20, 800.0, 5), &interner);
+
+        let changes = tracker.finish();
+        assert_eq!(changes.len(), 1);
+        let change = &changes[0];
+        assert_eq!(change.queryid, 7);
+        assert_eq!((change.from_planid, change.to_planid), (1, 2));
+        assert_eq!((change.switched_at, change.last_ts), (900, 1200));
+        assert_eq!(change.from_mean_ms, 2.0);
+        assert_eq!(change.to_mean_ms, 40.0);
+        assert_eq!(change.slowdown(), 20.0);
+        assert!(change.is_critical());
+        assert_eq!(
+            change.from_plan.as_deref(),
+            Some("Index Scan using orders_pkey on orders")
+        );
+        assert_eq!(change.to_plan.as_deref(), Some("Seq Scan on orders"));
+    }
+
+    #[test]
+    fn ignores_faster_and_mixed_plans() {
+        let interner = StringInterner::new();
+        let mut tracker = PlanChangeTracker::new();
+        // Query 1 switches to a faster plan; query 2 runs both plans evenly
+        let sample = |c1: i64, c2: i64, c3: i64, c4: i64, at: i64| {
+            vec![
+                plan(1, 10, 0, c1, c1 as f64 * 50.0, at),
+                plan(1, 11, 0, c2, c2 as f64 * 5.0, at),
+                plan(2, 20, 0, c3, c3 as f64 * 1.0, at),
+                plan(2, 21, 0, c4, c4 as f64 * 30.0, at),
+            ]
+        };
+        tracker.observe(0, &sample(10, 0, 10, 10, 1), &interner);
+        tracker.observe(60, &sample(20, 0, 20, 20, 2), &interner);
+        tracker.observe(120, &sample(20, 10, 30, 30, 3), &interner);
+        tracker.observe(180, &sample(20, 20, 40, 40, 4), &interner);
+        assert!(tracker.finish().is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::analysis::rules::AnalysisRule;
+use crate::analysis::{AnalysisContext, Anomaly, Category, Severity, find_block};
+use crate::storage::model::{DataBlock, KernelEventInfo, KernelEventKind};
+
+fn kernel_events<'a>(ctx: &AnalysisContext<'a>) -> &'a [KernelEventInfo] {
+    find_block(ctx.snapshot, |b| match b {
+        DataBlock::KernelEvents(v) => Some(v.as_slice()),
+        _ => None,
+    })
+    .unwrap_or(&[])
+}
+
+// ============================================================
+// KernelOomKillRule — OOM killer events from /dev/kmsg
+// ============================================================
+
+/// Critical when the OOM killer hit the postmaster or a backend (the
+/// postmaster then restarts all sessions), Warning for other processes.
+pub struct KernelOomKillRule;
+
+impl AnalysisRule for KernelOomKillRule {
+    fn id(&self) -> &'static str {
+        "kernel_oom_kill"
+    }
+
+    fn evaluate(&self, ctx: &AnalysisContext) -> Vec<Anomaly> {
+        let kills: Vec<&KernelEventInfo> = kernel_events(ctx)
+            .iter()
+            .filter(|e| e.kind == KernelEventKind::OomKill)
+            .collect();
+        if kills.is_empty() {
+            return Vec::new();
+        }
+
+        let pg_kills: Vec<&KernelEventInfo> = kills
+            .iter()
+            .copied()
+            .filter(|e| e.is_pg_oom_kill())
+            .collect();
+        let (severity, title, shown) = if pg_kills.is_empty() {
+            let title = match kills.as_slice() {
+                [e] => format!("OOM killer terminated {} (pid {})", e.comm, e.pid),
+                _ => format!("OOM killer terminated {} processes", kills.len()),
+            };
+            (Severity::Warning, title, &kills)
+        } else {
+            let postmaster = pg_kills
+                .iter()
+                .any(|e| e.pg_role.as_deref() == Some("postmaster"));
+            let title = if postmaster {
+                "OOM killer terminated the postmaster".to_string()
+            } else {
+                match pg_kills.as_slice() {
+                    [e] => format!(
+                        "OOM killer terminated PostgreSQL {} (pid {})",
+                        e.pg_role.as_deref().unwrap_or_default(),
+                        e.pid
+                    ),
+                    _ => format!(
+                        "OOM killer terminated {} PostgreSQL processes",
+                        pg_kills.len()
+                    ),
+                }
+            };
+            (Severity::Critical, title, &pg_kills)
+        };
+
+        let detail = shown
+            .iter()
+            .map(|e| match &e.pg_role {
+                Some(role) => format!("pid {} ({}, {})", e.pid, e.comm, role),
+                None => format!("pid {} ({})", e.pid, e.comm),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        vec![Anomaly {
+            timestamp: ctx.timestamp,
+            rule_id: "kernel_oom_kill",
+            category: Category::Memory,
+            severity,
+            title,
+            detail: Some(detail),
+            value: kills.len() as f64,
+            merge_key: None,
+            entity_id: None,
+        }]
+    }
+}
+
+// ============================================================
+// KernelDiskErrorRule — I/O errors and read-only remounts
+// ============================================================
+
+/// Critical when a filesystem went read-only (PostgreSQL cannot write WAL),
+/// Warning for I/O errors.
+pub struct KernelDiskErrorRule;
+
+impl AnalysisRule for KernelDiskErrorRule {
+    fn id(&self) -> &'static str {
+        "kernel_disk_error"
+    }
+
+    fn evaluate(&self, ctx: &AnalysisContext) -> Vec<Anomaly> {
+        let events = kernel_events(ctx);
+        let read_only = events
+            .iter()
+            .find(|e| e.kind == KernelEventKind::FsReadOnly);
+        let io_errors: Vec<&KernelEventInfo> = events
+            .iter()
+            .filter(|e| e.kind == KernelEventKind::IoError)
+            .collect();
+
+        let (severity, title, first) = match (read_only, io_errors.first()) {
+            (Some(e), _) => (
+                Severity::Critical,
+                "Filesystem remounted read-only".to_string(),
+                e,
+            ),
+            (None, Some(e)) => (
+                Severity::Warning,
+                format!("Kernel I/O errors ({} events)", io_errors.len()),
+                *e,
+            ),
+            (None, None) => return Vec::new(),
+        };
+
+        vec![Anomaly {
+            timestamp: ctx.timestamp,
+            rule_id: "kernel_disk_error",
+            category: Category::Disk,
+            severity,
+            title,
+            detail: Some(first.message.clone()),
+            value: io_errors.len() as f64,
+            merge_key: None,
+            entity_id: None,
+        }]
+    }
+}
@@ -1,20 +1,26 @@
 pub mod cgroup;
 pub mod cpu;
 pub mod disk;
+pub mod kernel;
 pub mod load;
 pub mod memory;
 pub mod network;
 pub mod pg_activity;
+pub mod pg_archiver;
 pub mod pg_bgwriter;
+pub mod pg_connections;
 pub mod pg_errors;
 pub mod pg_events;
 pub mod pg_indexes;
 pub mod pg_locks;
 pub mod pg_plans;
+pub mod pg_sequences;
 pub mod pg_statements;
+pub mod pg_stats_reset;
 pub mod pg_tables;
 pub mod process_blkdelay;
 pub mod process_io;
+pub mod systemd;
 
 use super::{AnalysisContext, Anomaly};
 
@@ -42,10 +48,14 @@ pub fn all_rules() -> Vec<Box<dyn AnalysisRule>> {
         // PG Activity
         Box::new(pg_activity::IdleInTransactionRule),
         Box::new(pg_activity::LongQueryRule),
+        Box::new(pg_activity::XminHorizonRule),
         Box::new(pg_activity::WaitSyncReplicaRule),
         Box::new(pg_activity::WaitLockRule),
         Box::new(pg_activity::HighActiveSessionsRule),
         Box::new(pg_activity::TpsSpikeRule),
+        // PG Connections (log + activity churn)
+        Box::new(pg_connections::ConnectionRateRule),
+        Box::new(pg_connections::ShortLivedConnectionsRule),
         // PG Statements
         Box::new(pg_statements::MeanTimeSpikeRule),
         Box::new(pg_statements::QueryCallSpikeRule),
@@ -59,6 +69,8 @@ pub fn all_rules() -> Vec<Box<dyn AnalysisRule>> {
         Box::new(pg_tables::HeapReadSpikeRule),
         Box::new(pg_tables::TableWriteSpikeRule),
         Box::new(pg_tables::CacheHitRatioDropRule),
+        // PG Sequences
+        Box::new(pg_sequences::SequenceExhaustionRule),
         // PG Indexes
         Box::new(pg_indexes::IndexReadSpikeRule),
         Box::new(pg_indexes::IndexCacheHitDropRule),
@@ -67,12 +79,21 @@ pub fn all_rules() -> Vec<Box<dyn AnalysisRule>> {
         // PG BGWriter
         Box::new(pg_bgwriter::CheckpointSpikeRule),
         Box::new(pg_bgwriter::BackendBuffersRule),
+        // PG Archiver
+        Box::new(pg_archiver::ArchiverFailingRule),
         // PG Errors
         Box::new(pg_errors::ErrorsRule),
         Box::new(pg_errors::FatalPanicRule),
+        // PG service (systemd unit)
+        Box::new(systemd::PgServiceRule),
+        // PG stats resets
+        Box::new(pg_stats_reset::StatsResetRule),
         // Cgroup
         Box::new(cgroup::ThrottledRule),
         Box::new(cgroup::OomKillRule),
+        // Kernel events (/dev/kmsg)
+        Box::new(kernel::KernelOomKillRule),
+        Box::new(kernel::KernelDiskErrorRule),
         // Process-level
         Box::new(process_io::ProcessIoHogRule),
         Box::new(process_blkdelay::HighBlkDelayRule),
@@ -21,10 +21,22 @@ fn effective_cpus(snapshot: &Snapshot) -> f64 {
     .max(1) as f64
 }
 
+/// First 100 characters of an interned query text.
+fn query_excerpt(ctx: &AnalysisContext, query_hash: u64) -> Option<String> {
+    if query_hash == 0 {
+        return None;
+    }
+    ctx.interner
+        .resolve(query_hash)
+        .map(|q| q.chars().take(100).collect())
+}
+
 // ============================================================
 // IdleInTransactionRule
 // ============================================================
 
+/// Sessions idle in a transaction for more than 30s; the detail carries the
+/// longest one's last query.
 pub struct IdleInTransactionRule;
 
 impl AnalysisRule for IdleInTransactionRule {
@@ -44,6 +56,7 @@ impl AnalysisRule for IdleInTransactionRule {
 
         let mut count = 0u64;
         let mut max_duration: f64 = 0.0;
+        let mut worst = None;
 
         for s in sessions {
             if s.state_hash == idle_in_tx_hash && s.xact_start > 0.0 {
@@ -52,6 +65,7 @@ impl AnalysisRule for IdleInTransactionRule {
                     count += 1;
                     if duration > max_duration {
                         max_duration = duration;
+                        worst = Some(s);
                     }
                 }
             }
@@ -74,10 +88,13 @@ impl AnalysisRule for IdleInTransactionRule {
             category: Category::PgActivity,
             severity,
             title: format!("{count} idle-in-transaction session(s), longest {max_dur_display}s"),
-            detail: None,
+            detail: worst.map(|s| match query_excerpt(ctx, s.query_hash) {
+                Some(q) => format!("pid {}: {q}", s.pid),
+                None => format!("pid {}", s.pid),
+            }),
             value: count as f64,
             merge_key: None,
-            entity_id: None,
+            entity_id: worst.map(|s| s.pid as i64),
         }]
     }
 }
@@ -142,14 +159,7 @@ impl AnalysisRule for LongQueryRule {
             Severity::Warning
         };
 
-        let detail = if longest_query_hash != 0 {
-            ctx.interner.resolve(longest_query_hash).map(|q| {
-                let truncated: String = q.chars().take(100).collect();
-                truncated
-            })
-        } else {
-            None
-        };
+        let detail = query_excerpt(ctx, longest_query_hash);
 
         vec![Anomaly {
             timestamp: ctx.timestamp,
@@ -165,6 +175,83 @@ impl AnalysisRule for LongQueryRule {
     }
 }
 
+// ============================================================
+// XminHorizonRule — a session's snapshot holds back the xmin horizon
+// ============================================================
+
+/// xmin age (in transactions) at which the held-back horizon is reported.
+const XMIN_AGE_WARNING: i32 = 1_000_000;
+/// xmin age that is critical: bloat builds up and freezing falls behind.
+const XMIN_AGE_CRITICAL: i32 = 10_000_000;
+
+/// The session with the oldest `backend_xmin`, when its snapshot is more
+/// than 1M transactions old. Walsenders are included: with
+/// `hot_standby_feedback` they carry the standby's horizon.
+pub struct XminHorizonRule;
+
+impl AnalysisRule for XminHorizonRule {
+    fn id(&self) -> &'static str {
+        "xmin_horizon"
+    }
+
+    fn evaluate(&self, ctx: &AnalysisContext) -> Vec<Anomaly> {
+        let Some(sessions) = find_block(ctx.snapshot, |b| match b {
+            DataBlock::PgStatActivity(v) => Some(v.as_slice()),
+            _ => None,
+        }) else {
+            return Vec::new();
+        };
+
+        // VACUUM's own snapshot does not hold back other vacuums
+        let autovacuum_hash = xxhash_rust::xxh3::xxh3_64(b"autovacuum worker");
+        let Some(oldest) = sessions
+            .iter()
+            .filter(|s| s.backend_type_hash != autovacuum_hash)
+            .max_by_key(|s| s.backend_xmin_age)
+            .filter(|s| s.backend_xmin_age >= XMIN_AGE_WARNING)
+        else {
+            return Vec::new();
+        };
+
+        let severity = if oldest.backend_xmin_age >= XMIN_AGE_CRITICAL {
+            Severity::Critical
+        } else {
+            Severity::Warning
+        };
+
+        let resolve = |hash| ctx.interner.resolve(hash).unwrap_or("-");
+        let state = match resolve(oldest.state_hash) {
+            "" | "-" => resolve(oldest.backend_type_hash),
+            state => state,
+        };
+        let mut detail = if oldest.xact_start > 0.0 {
+            let xact = ctx.timestamp as f64 - oldest.xact_start;
+            format!("{state} for {}s", xact.max(0.0) as i64)
+        } else {
+            state.to_string()
+        };
+        if let Some(q) = query_excerpt(ctx, oldest.query_hash) {
+            detail.push_str(&format!(": {q}"));
+        }
+
+        vec![Anomaly {
+            timestamp: ctx.timestamp,
+            rule_id: "xmin_horizon",
+            category: Category::PgActivity,
+            severity,
+            title: format!(
+                "xmin horizon held back {:.1}M transactions by pid {}",
+                oldest.backend_xmin_age as f64 / 1e6,
+                oldest.pid
+            ),
+            detail: Some(detail),
+            value: oldest.backend_xmin_age as f64,
+            merge_key: None,
+            entity_id: Some(oldest.pid as i64),
+        }]
+    }
+}
+
 // ============================================================
 // WaitSyncReplicaRule
 // ============================================================
@@ -380,3 +467,145 @@ impl AnalysisRule for TpsSpikeRule {
         }]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::EwmaState;
+    use crate::storage::interner::StringInterner;
+    use crate::storage::model::PgStatActivityInfo;
+
+    fn session(
+        interner: &mut StringInterner,
+        pid: i32,
+        state: &str,
+        query: &str,
+        xact_start: f64,
+        backend_xmin_age: i32,
+    ) -> PgStatActivityInfo {
+        PgStatActivityInfo {
+            pid,
+            state_hash: interner.intern(state),
+            query_hash: interner.intern(query),
+            backend_type_hash: interner.intern("client backend"),
+            xact_start,
+            backend_xmin_age,
+            ..Default::default()
+        }
+    }
+
+    fn evaluate(
+        rule: &dyn AnalysisRule,
+        snapshot: &Snapshot,
+        interner: &StringInterner,
+    ) -> Vec<Anomaly> {
+        let ewma = EwmaState::new(0.1);
+        let ctx = AnalysisContext {
+            snapshot,
+            prev_snapshot: None,
+            interner,
+            timestamp: snapshot.timestamp,
+            ewma: &ewma,
+            prev: None,
+            dt: 0.0,
+            backend_io_hit_pct: None,
+            prev_sequences: None,
+        };
+        rule.evaluate(&ctx)
+    }
+
+    #[test]
+    fn idle_in_transaction_names_the_longest_session() {
+        let mut interner = StringInterner::new();
+        let snapshot = Snapshot {
+            timestamp: 1_000,
+            blocks: vec![DataBlock::PgStatActivity(vec![
+                session(
+                    &mut interner,
+                    10,
+                    "idle in transaction",
+                    "SELECT 1",
+                    960.0,
+                    0,
+                ),
+                session(
+                    &mut interner,
+                    11,
+                    "idle in transaction",
+                    "UPDATE t SET x = 1",
+                    400.0,
+                    0,
+                ),
+                session(&mut interner, 12, "active", "SELECT pg_sleep(60)", 100.0, 0),
+            ])],
+        };
+
+        let anomalies = evaluate(&IdleInTransactionRule, &snapshot, &interner);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(
+            anomalies[0].title,
+            "2 idle-in-transaction session(s), longest 600s"
+        );
+        assert_eq!(
+            anomalies[0].detail.as_deref(),
+            Some("pid 11: UPDATE t SET x = 1")
+        );
+        assert_eq!(anomalies[0].entity_id, Some(11));
+    }
+
+    #[test]
+    fn xmin_horizon_reports_the_oldest_snapshot() {
+        let mut interner = StringInterner::new();
+        let mut vacuum = session(
+            &mut interner,
+            30,
+            "active",
+            "autovacuum: VACUUM t",
+            0.0,
+            50_000_000,
+        );
+        vacuum.backend_type_hash = interner.intern("autovacuum worker");
+        let snapshot = Snapshot {
+            timestamp: 4_000,
+            blocks: vec![DataBlock::PgStatActivity(vec![
+                session(
+                    &mut interner,
+                    20,
+                    "idle in transaction",
+                    "SELECT * FROM orders",
+                    400.0,
+                    12_000_000,
+                ),
+                session(&mut interner, 21, "active", "SELECT 1", 3_990.0, 2_000),
+                vacuum,
+            ])],
+        };
+
+        let anomalies = evaluate(&XminHorizonRule, &snapshot, &interner);
+        assert_eq!(anomalies.len(), 1);
+        let a = &anomalies[0];
+        assert_eq!(a.severity, Severity::Critical);
+        assert_eq!(
+            a.title,
+            "xmin horizon held back 12.0M transactions by pid 20"
+        );
+        assert_eq!(
+            a.detail.as_deref(),
+            Some("idle in transaction for 3600s: SELECT * FROM orders")
+        );
+        assert_eq!(a.entity_id, Some(20));
+
+        let young = Snapshot {
+            timestamp: 4_000,
+            blocks: vec![DataBlock::PgStatActivity(vec![session(
+                &mut interner,
+                21,
+                "active",
+                "SELECT 1",
+                3_990.0,
+                2_000,
+            )])],
+        };
+        assert!(evaluate(&XminHorizonRule, &young, &interner).is_empty());
+    }
+}
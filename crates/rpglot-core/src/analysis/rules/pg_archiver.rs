@@ -0,0 +1,154 @@
+use crate::analysis::rules::AnalysisRule;
+use crate::analysis::{AnalysisContext, Anomaly, Category, Severity, find_block};
+use crate::storage::model::DataBlock;
+
+// ============================================================
+// ArchiverFailingRule — WAL archiving failing or behind
+// ============================================================
+
+/// Completed WAL segments waiting for archive_command that are critical
+/// (1 GiB with the default 16 MiB segments).
+const ARCHIVE_LAG_SEGMENTS: u64 = 64;
+
+/// Fires when the last archive attempt failed, or when more than
+/// [`ARCHIVE_LAG_SEGMENTS`] completed segments are waiting to be archived.
+/// Either way WAL piles up in pg_wal and point-in-time recovery falls behind.
+pub struct ArchiverFailingRule;
+
+impl AnalysisRule for ArchiverFailingRule {
+    fn id(&self) -> &'static str {
+        "archiver_failing"
+    }
+
+    fn evaluate(&self, ctx: &AnalysisContext) -> Vec<Anomaly> {
+        let Some(arc) = find_block(ctx.snapshot, |b| match b {
+            DataBlock::PgStatArchiver(info) => Some(info),
+            _ => None,
+        }) else {
+            return Vec::new();
+        };
+
+        let pending = arc.pending_segments();
+        let failing = arc.is_failing();
+        if !failing && pending.is_none_or(|p| p <= ARCHIVE_LAG_SEGMENTS) {
+            return Vec::new();
+        }
+
+        let backlog = match pending {
+            Some(p) => format!("{p} segments waiting"),
+            None => "backlog unknown".to_string(),
+        };
+        let last_success = if arc.last_archived_time > 0 {
+            format!(
+                "last archived {} {}s ago",
+                arc.last_archived_wal,
+                (ctx.timestamp - arc.last_archived_time).max(0)
+            )
+        } else {
+            "nothing archived yet".to_string()
+        };
+        let (title, detail) = if failing {
+            (
+                format!("WAL archiving failing on {}", arc.last_failed_wal),
+                format!(
+                    "{} failed attempts in total; {last_success}; {backlog}",
+                    arc.failed_count
+                ),
+            )
+        } else {
+            (
+                format!("WAL archiving {backlog}"),
+                format!("archive_command is not keeping up; {last_success}"),
+            )
+        };
+
+        vec![Anomaly {
+            timestamp: ctx.timestamp,
+            rule_id: "archiver_failing",
+            category: Category::PgBgwriter,
+            severity: Severity::Critical,
+            title,
+            detail: Some(detail),
+            value: pending.unwrap_or(0) as f64,
+            merge_key: None,
+            entity_id: None,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::EwmaState;
+    use crate::storage::interner::StringInterner;
+    use crate::storage::model::{PgStatArchiverInfo, Snapshot};
+
+    const SEGMENT: u64 = 16 * 1024 * 1024;
+
+    fn evaluate(info: PgStatArchiverInfo) -> Vec<Anomaly> {
+        let snapshot = Snapshot {
+            timestamp: 1_000,
+            blocks: vec![DataBlock::PgStatArchiver(info)],
+        };
+        let interner = StringInterner::new();
+        let ewma = EwmaState::new(0.1);
+        let ctx = AnalysisContext {
+            snapshot: &snapshot,
+            prev_snapshot: None,
+            interner: &interner,
+            timestamp: snapshot.timestamp,
+            ewma: &ewma,
+            prev: None,
+            dt: 0.0,
+            backend_io_hit_pct: None,
+            prev_sequences: None,
+        };
+        ArchiverFailingRule.evaluate(&ctx)
+    }
+
+    #[test]
+    fn flags_failing_archive_command() {
+        let anomalies = evaluate(PgStatArchiverInfo {
+            archived_count: 10,
+            failed_count: 3,
+            last_archived_wal: "00000001000000000000000A".to_string(),
+            last_archived_time: 900,
+            last_failed_wal: "00000001000000000000000B".to_string(),
+            last_failed_time: 990,
+            current_wal: "00000001000000000000000D".to_string(),
+            wal_segment_size: SEGMENT,
+        });
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].severity, Severity::Critical);
+        assert_eq!(
+            anomalies[0].title,
+            "WAL archiving failing on 00000001000000000000000B"
+        );
+        assert_eq!(
+            anomalies[0].detail.as_deref(),
+            Some(
+                "3 failed attempts in total; last archived 00000001000000000000000A 100s ago; \
+                 2 segments waiting"
+            )
+        );
+    }
+
+    #[test]
+    fn flags_backlog_across_log_files() {
+        // 0x100 segments per log file with 16 MiB segments
+        let mut info = PgStatArchiverInfo {
+            archived_count: 500,
+            last_archived_wal: "0000000100000001000000F0".to_string(),
+            last_archived_time: 900,
+            current_wal: "000000010000000200000030".to_string(),
+            wal_segment_size: SEGMENT,
+            ..Default::default()
+        };
+        assert_eq!(info.pending_segments(), Some(63));
+        assert!(evaluate(info.clone()).is_empty());
+
+        info.current_wal = "000000010000000200000032".to_string();
+        let anomalies = evaluate(info);
+        assert_eq!(anomalies[0].title, "WAL archiving 65 segments waiting");
+    }
+}
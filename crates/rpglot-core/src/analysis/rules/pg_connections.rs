@@ -0,0 +1,113 @@
+use crate::analysis::rules::AnalysisRule;
+use crate::analysis::{AnalysisContext, Anomaly, Category, Severity, find_block};
+use crate::storage::model::DataBlock;
+
+/// Minimum disconnections in an interval before the short-lived ratio is trusted.
+const MIN_DISCONNECTIONS_FOR_RATIO: u32 = 10;
+
+// ============================================================
+// ConnectionRateRule — connection storm (new connections per second)
+// ============================================================
+
+pub struct ConnectionRateRule;
+
+impl AnalysisRule for ConnectionRateRule {
+    fn id(&self) -> &'static str {
+        "connection_rate_high"
+    }
+
+    fn evaluate(&self, ctx: &AnalysisContext) -> Vec<Anomaly> {
+        let Some(churn) = find_block(ctx.snapshot, |b| match b {
+            DataBlock::PgConnectionChurn(c) => Some(c),
+            _ => None,
+        }) else {
+            return Vec::new();
+        };
+
+        let rate = churn.connections_per_sec();
+        if rate < 20.0 {
+            return Vec::new();
+        }
+
+        let severity = if rate >= 100.0 {
+            Severity::Critical
+        } else {
+            Severity::Warning
+        };
+
+        let detail = match churn.short_lived_ratio() {
+            Some(ratio) => format!(
+                "{} new connections in {:.0}s, {:.0}% of sessions lived < 1s",
+                churn.connections(),
+                churn.interval_s,
+                ratio * 100.0
+            ),
+            None => format!(
+                "{} new connections in {:.0}s",
+                churn.connections(),
+                churn.interval_s
+            ),
+        };
+
+        vec![Anomaly {
+            timestamp: ctx.timestamp,
+            rule_id: "connection_rate_high",
+            category: Category::PgActivity,
+            severity,
+            title: format!("Connection storm: {rate:.0} conn/s"),
+            detail: Some(detail),
+            value: rate,
+            merge_key: None,
+            entity_id: None,
+        }]
+    }
+}
+
+// ============================================================
+// ShortLivedConnectionsRule — connect-per-query pattern
+// ============================================================
+
+pub struct ShortLivedConnectionsRule;
+
+impl AnalysisRule for ShortLivedConnectionsRule {
+    fn id(&self) -> &'static str {
+        "short_lived_connections"
+    }
+
+    fn evaluate(&self, ctx: &AnalysisContext) -> Vec<Anomaly> {
+        let Some(churn) = find_block(ctx.snapshot, |b| match b {
+            DataBlock::PgConnectionChurn(c) => Some(c),
+            _ => None,
+        }) else {
+            return Vec::new();
+        };
+
+        if churn.disconnections < MIN_DISCONNECTIONS_FOR_RATIO {
+            return Vec::new();
+        }
+        let Some(ratio) = churn.short_lived_ratio() else {
+            return Vec::new();
+        };
+        if ratio < 0.5 {
+            return Vec::new();
+        }
+
+        let pct = ratio * 100.0;
+        let avg_session_ms = churn.total_session_s / churn.disconnections as f64 * 1000.0;
+
+        vec![Anomaly {
+            timestamp: ctx.timestamp,
+            rule_id: "short_lived_connections",
+            category: Category::PgActivity,
+            severity: Severity::Warning,
+            title: format!("{pct:.0}% of sessions shorter than 1s"),
+            detail: Some(format!(
+                "{} of {} disconnected sessions were short-lived, avg session {avg_session_ms:.0} ms",
+                churn.short_lived, churn.disconnections
+            )),
+            value: pct,
+            merge_key: None,
+            entity_id: None,
+        }]
+    }
+}
@@ -220,6 +220,7 @@ mod tests {
             prev: None,
             dt: 0.0,
             backend_io_hit_pct: None,
+            prev_sequences: None,
         }
     }
 
@@ -238,6 +239,7 @@ mod tests {
             prev: None,
             dt: 10.0,
             backend_io_hit_pct: None,
+            prev_sequences: None,
         }
     }
 
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use crate::analysis::rules::AnalysisRule;
+use crate::analysis::{AnalysisContext, Anomaly, Category, Severity, find_block};
+use crate::storage::model::{DataBlock, PgSequenceInfo};
+
+// ============================================================
+// SequenceExhaustionRule — primary key sequence close to its limit
+// ============================================================
+
+/// Used share of the range worth a warning.
+const SEQUENCE_WARN_USED: f64 = 0.7;
+/// Used share of the range that is critical regardless of the rate.
+const SEQUENCE_CRIT_USED: f64 = 0.9;
+/// Days left at the observed rate below which the warning becomes critical.
+const SEQUENCE_CRIT_DAYS: f64 = 30.0;
+
+/// Fires on every hourly `PgSequences` sample for sequences past 70% of
+/// their range. Days to exhaustion come from the consumption since the
+/// previous sample, when the range has one.
+pub struct SequenceExhaustionRule;
+
+/// `db.schema.sequence`, the merge key of the sequence's incidents.
+fn sequence_key(s: &PgSequenceInfo) -> String {
+    format!("{}.{}.{}", s.datname, s.schemaname, s.sequencename)
+}
+
+fn fmt_count(v: f64) -> String {
+    if v >= 1e9 {
+        format!("{:.2}B", v / 1e9)
+    } else if v >= 1e6 {
+        format!("{:.1}M", v / 1e6)
+    } else if v >= 1e3 {
+        format!("{:.1}K", v / 1e3)
+    } else {
+        format!("{v:.0}")
+    }
+}
+
+impl AnalysisRule for SequenceExhaustionRule {
+    fn id(&self) -> &'static str {
+        "sequence_exhaustion"
+    }
+
+    fn evaluate(&self, ctx: &AnalysisContext) -> Vec<Anomaly> {
+        let Some(sequences) = find_block(ctx.snapshot, |b| match b {
+            DataBlock::PgSequences(v) => Some(v.as_slice()),
+            _ => None,
+        }) else {
+            return Vec::new();
+        };
+
+        let prev: HashMap<String, i64> = ctx
+            .prev_sequences
+            .map(|(_, v)| v.iter().map(|s| (sequence_key(s), s.last_value)).collect())
+            .unwrap_or_default();
+        let span_days = ctx
+            .prev_sequences
+            .map_or(0.0, |(ts, _)| (ctx.timestamp - ts) as f64 / 86_400.0);
+
+        let mut out = Vec::new();
+        for s in sequences {
+            let used = s.used_fraction();
+            if used < SEQUENCE_WARN_USED {
+                continue;
+            }
+            let key = sequence_key(s);
+            let per_day = prev
+                .get(&key)
+                .filter(|_| span_days > 0.0)
+                .map(|&p| (s.last_value - p) as f64 / span_days)
+                .filter(|&r| r > 0.0);
+            let days_left = per_day.map(|r| (s.max_value - s.last_value) as f64 / r);
+
+            let severity = if used >= SEQUENCE_CRIT_USED
+                || days_left.is_some_and(|d| d < SEQUENCE_CRIT_DAYS)
+            {
+                Severity::Critical
+            } else {
+                Severity::Warning
+            };
+            let rate = match (per_day, days_left) {
+                (Some(r), Some(d)) => format!("{}/day, ~{d:.0} days left", fmt_count(r)),
+                _ => "consumption rate unknown".to_string(),
+            };
+
+            out.push(Anomaly {
+                timestamp: ctx.timestamp,
+                rule_id: "sequence_exhaustion",
+                category: Category::PgTables,
+                severity,
+                title: format!("Sequence {key} at {:.0}% of its range", used * 100.0),
+                detail: Some(format!(
+                    "{}.{} {}: {} of {} used; {rate}",
+                    s.relname,
+                    s.attname,
+                    s.data_type,
+                    fmt_count(s.last_value as f64),
+                    fmt_count(s.max_value as f64),
+                )),
+                value: used * 100.0,
+                merge_key: Some(key),
+                entity_id: None,
+            });
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::EwmaState;
+    use crate::storage::interner::StringInterner;
+    use crate::storage::model::Snapshot;
+
+    fn sequence(name: &str, last_value: i64) -> PgSequenceInfo {
+        PgSequenceInfo {
+            datname: "app".to_string(),
+            schemaname: "public".to_string(),
+            sequencename: name.to_string(),
+            relname: "orders".to_string(),
+            attname: "id".to_string(),
+            data_type: "integer".to_string(),
+            last_value,
+            max_value: 2_147_483_647,
+        }
+    }
+
+    #[test]
+    fn flags_sequences_past_70_percent_with_days_left() {
+        let snapshot = Snapshot {
+            timestamp: 86_400,
+            blocks: vec![DataBlock::PgSequences(vec![
+                sequence("orders_id_seq", 1_600_000_000),
+                sequence("users_id_seq", 1_000_000),
+            ])],
+        };
+        let prev = vec![sequence("orders_id_seq", 1_590_000_000)];
+        let interner = StringInterner::new();
+        let ewma = EwmaState::new(0.1);
+        let mut ctx = AnalysisContext {
+            snapshot: &snapshot,
+            prev_snapshot: None,
+            interner: &interner,
+            timestamp: snapshot.timestamp,
+            ewma: &ewma,
+            prev: None,
+            dt: 0.0,
+            backend_io_hit_pct: None,
+            prev_sequences: Some((0, &prev)),
+        };
+
+        let anomalies = SequenceExhaustionRule.evaluate(&ctx);
+        assert_eq!(anomalies.len(), 1);
+        let a = &anomalies[0];
+        assert_eq!(a.severity, Severity::Warning);
+        assert_eq!(
+            a.title,
+            "Sequence app.public.orders_id_seq at 75% of its range"
+        );
+        assert_eq!(
+            a.detail.as_deref(),
+            Some("orders.id integer: 1.60B of 2.15B used; 10.0M/day, ~55 days left")
+        );
+
+        // Without a previous sample the level alone decides
+        ctx.prev_sequences = None;
+        let anomalies = SequenceExhaustionRule.evaluate(&ctx);
+        assert!(
+            anomalies[0]
+                .detail
+                .as_deref()
+                .unwrap()
+                .ends_with("consumption rate unknown")
+        );
+    }
+
+    #[test]
+    fn critical_when_exhausted_within_a_month() {
+        let snapshot = Snapshot {
+            timestamp: 86_400,
+            blocks: vec![DataBlock::PgSequences(vec![sequence(
+                "orders_id_seq",
+                1_600_000_000,
+            )])],
+        };
+        let prev = vec![sequence("orders_id_seq", 1_550_000_000)];
+        let interner = StringInterner::new();
+        let ewma = EwmaState::new(0.1);
+        let ctx = AnalysisContext {
+            snapshot: &snapshot,
+            prev_snapshot: None,
+            interner: &interner,
+            timestamp: snapshot.timestamp,
+            ewma: &ewma,
+            prev: None,
+            dt: 0.0,
+            backend_io_hit_pct: None,
+            prev_sequences: Some((0, &prev)),
+        };
+
+        let anomalies = SequenceExhaustionRule.evaluate(&ctx);
+        assert_eq!(anomalies[0].severity, Severity::Critical);
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use crate::analysis::rules::AnalysisRule;
+use crate::analysis::{AnalysisContext, Anomaly, Category, Severity, find_block};
+use crate::rates::{pgi_reset, pgp_reset, pgs_reset, pgt_reset};
+use crate::storage::model::{DataBlock, Snapshot};
+
+// ============================================================
+// StatsResetRule — cumulative PG statistics were reset
+// ============================================================
+
+/// Reports `pg_stat_statements_reset()` / `pg_stat_reset()` calls detected
+/// from counter decreases between consecutive snapshots. Rates are not
+/// computed across the reset, so the incident explains the gap.
+pub struct StatsResetRule;
+
+fn reset_anomaly(ctx: &AnalysisContext, view: &'static str, category: Category) -> Anomaly {
+    Anomaly {
+        timestamp: ctx.timestamp,
+        rule_id: "stats_reset",
+        category,
+        severity: Severity::Info,
+        title: format!("{view} reset"),
+        detail: Some("Counters decreased; rates restart from this snapshot".to_string()),
+        value: 0.0,
+        merge_key: Some(view.to_string()),
+        entity_id: None,
+    }
+}
+
+/// Runs `detect` on a view's entries in `curr` and `prev`, keyed by `key`.
+fn view_reset<'a, T: Clone + 'a, K: std::hash::Hash + Eq>(
+    curr: &'a Snapshot,
+    prev: &'a Snapshot,
+    extract: impl Fn(&'a DataBlock) -> Option<&'a [T]>,
+    key: impl Fn(&T) -> K,
+    detect: impl Fn(&[T], &HashMap<K, T>) -> bool,
+) -> bool {
+    let (Some(curr), Some(prev)) = (find_block(curr, &extract), find_block(prev, &extract)) else {
+        return false;
+    };
+    let prev: HashMap<K, T> = prev.iter().map(|e| (key(e), e.clone())).collect();
+    detect(curr, &prev)
+}
+
+impl AnalysisRule for StatsResetRule {
+    fn id(&self) -> &'static str {
+        "stats_reset"
+    }
+
+    fn evaluate(&self, ctx: &AnalysisContext) -> Vec<Anomaly> {
+        let Some(prev) = ctx.prev_snapshot else {
+            return Vec::new();
+        };
+        let mut out = Vec::new();
+
+        if view_reset(
+            ctx.snapshot,
+            prev,
+            |b| match b {
+                DataBlock::PgStatStatements(v) => Some(v.as_slice()),
+                _ => None,
+            },
+            |s| s.queryid,
+            pgs_reset,
+        ) {
+            out.push(reset_anomaly(
+                ctx,
+                "pg_stat_statements",
+                Category::PgStatements,
+            ));
+        }
+        if view_reset(
+            ctx.snapshot,
+            prev,
+            |b| match b {
+                DataBlock::PgStorePlans(v) => Some(v.as_slice()),
+                _ => None,
+            },
+            |p| p.planid,
+            pgp_reset,
+        ) {
+            out.push(reset_anomaly(ctx, "pg_store_plans", Category::PgStatements));
+        }
+        if view_reset(
+            ctx.snapshot,
+            prev,
+            |b| match b {
+                DataBlock::PgStatUserTables(v) => Some(v.as_slice()),
+                _ => None,
+            },
+            |t| t.relid,
+            pgt_reset,
+        ) {
+            out.push(reset_anomaly(
+                ctx,
+                "pg_stat_user_tables",
+                Category::PgTables,
+            ));
+        }
+        if view_reset(
+            ctx.snapshot,
+            prev,
+            |b| match b {
+                DataBlock::PgStatUserIndexes(v) => Some(v.as_slice()),
+                _ => None,
+            },
+            |i| i.indexrelid,
+            pgi_reset,
+        ) {
+            out.push(reset_anomaly(
+                ctx,
+                "pg_stat_user_indexes",
+                Category::PgIndexes,
+            ));
+        }
+
+        out
+    }
+}
@@ -0,0 +1,98 @@
+use crate::analysis::rules::AnalysisRule;
+use crate::analysis::{AnalysisContext, Anomaly, Category, Severity, find_block};
+use crate::storage::model::{DataBlock, Snapshot, SystemdUnitInfo};
+
+fn systemd_unit(snapshot: &Snapshot) -> Option<&SystemdUnitInfo> {
+    find_block(snapshot, |b| match b {
+        DataBlock::SystemdUnit(u) => Some(u),
+        _ => None,
+    })
+}
+
+// ============================================================
+// PgServiceRule — PostgreSQL systemd unit failed or restarted
+// ============================================================
+
+/// Reports the PostgreSQL unit in the `failed` state (Critical), restarts
+/// between snapshots (Critical for automatic restarts after a crash, Warning
+/// otherwise) and other non-active states (Warning). Restarts are reported
+/// at the time the unit became active again.
+pub struct PgServiceRule;
+
+impl AnalysisRule for PgServiceRule {
+    fn id(&self) -> &'static str {
+        "pg_service"
+    }
+
+    fn evaluate(&self, ctx: &AnalysisContext) -> Vec<Anomaly> {
+        let Some(unit) = systemd_unit(ctx.snapshot) else {
+            return Vec::new();
+        };
+        let anomaly = |timestamp, severity, title: String, detail: String, kind: &str| Anomaly {
+            timestamp,
+            rule_id: "pg_service",
+            category: Category::PgEvents,
+            severity,
+            title,
+            detail: Some(detail),
+            value: unit.n_restarts as f64,
+            merge_key: Some(kind.to_string()),
+            entity_id: None,
+        };
+        let mut out = Vec::new();
+
+        match unit.active_state.as_str() {
+            "active" | "reloading" => {}
+            "failed" => out.push(anomaly(
+                ctx.timestamp,
+                Severity::Critical,
+                format!("PostgreSQL unit {} failed", unit.unit),
+                format!("result: {}", unit.result),
+                "failed",
+            )),
+            state => out.push(anomaly(
+                ctx.timestamp,
+                Severity::Warning,
+                format!("PostgreSQL unit {} is {state}", unit.unit),
+                format!("{state} ({})", unit.sub_state),
+                "state",
+            )),
+        }
+
+        if let Some(prev) = ctx.prev_snapshot.and_then(systemd_unit)
+            && prev.unit == unit.unit
+            && unit.active_enter_ts > 0
+            && unit.active_enter_ts != prev.active_enter_ts
+        {
+            let automatic = unit.n_restarts > prev.n_restarts;
+            let (severity, title) = if automatic {
+                (
+                    Severity::Critical,
+                    format!("PostgreSQL unit {} restarted after a crash", unit.unit),
+                )
+            } else {
+                (
+                    Severity::Warning,
+                    format!("PostgreSQL unit {} restarted", unit.unit),
+                )
+            };
+            // The unit became active during the interval; keep the incident
+            // inside it if the clocks disagree.
+            let timestamp = unit
+                .active_enter_ts
+                .clamp(ctx.prev_snapshot.map_or(0, |p| p.timestamp), ctx.timestamp);
+            out.push(anomaly(
+                timestamp,
+                severity,
+                title,
+                format!(
+                    "main pid {} -> {}, last result: {}",
+                    prev.main_pid, unit.main_pid, unit.result
+                ),
+                "restart",
+            ));
+        }
+
+        out
+    }
+}
@@ -0,0 +1,180 @@
+//! Resource usage of rpglotd recorded in [`DataBlock::AgentStats`].
+//!
+//! The current sample comes from one snapshot; the summary covers a history
+//! range and answers "how much does the agent cost" over a day or a week.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::provider::HistoryProvider;
+use crate::storage::Snapshot;
+use crate::storage::model::{AgentPhaseTiming, AgentStatsInfo, DataBlock};
+
+/// Agent diagnostics: the current sample and a summary over a range.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AgentReport {
+    /// Sample of the current snapshot (absent for data not written by rpglotd).
+    pub current: Option<ApiAgentStats>,
+    /// Summary over the requested range (history mode only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<AgentSummary>,
+}
+
+/// One sample of rpglotd resource usage.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiAgentStats {
+    /// Snapshot timestamp (epoch seconds).
+    pub timestamp: i64,
+    /// Daemon process ID.
+    pub pid: u32,
+    /// CPU usage since the previous snapshot (percent of one core).
+    pub cpu_pct: Option<f64>,
+    pub cpu_user_ms: u32,
+    pub cpu_system_ms: u32,
+    pub rss_bytes: u64,
+    pub rss_peak_bytes: u64,
+    /// Collection time of the snapshot.
+    pub collect_ms: f64,
+    /// Time to write the previous snapshot to storage.
+    pub storage_write_ms: f64,
+    /// Collection time per subsystem.
+    pub phases: Vec<ApiAgentPhase>,
+    /// Elapsed time of the PostgreSQL collection queries that ran.
+    pub queries: Vec<ApiAgentPhase>,
+    /// Collector warnings (queries paused after repeated timeouts).
+    pub warnings: Vec<String>,
+}
+
+/// Collection time of one subsystem.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiAgentPhase {
+    pub name: String,
+    pub ms: f64,
+}
+
+/// Aggregates over the samples of a history range.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct AgentSummary {
+    /// Snapshots with agent stats in the range.
+    pub samples: usize,
+    pub cpu_pct_avg: f64,
+    pub cpu_pct_max: f64,
+    pub rss_bytes_max: u64,
+    pub collect_ms_avg: f64,
+    pub collect_ms_max: f64,
+    pub storage_write_ms_avg: f64,
+    pub storage_write_ms_max: f64,
+}
+
+fn agent_stats(snapshot: &Snapshot) -> Option<&AgentStatsInfo> {
+    snapshot.blocks.iter().find_map(|b| match b {
+        DataBlock::AgentStats(a) => Some(a),
+        _ => None,
+    })
+}
+
+/// Converts the agent stats of `snapshot`, if it has any.
+pub fn convert_agent_stats(snapshot: &Snapshot) -> Option<ApiAgentStats> {
+    let a = agent_stats(snapshot)?;
+    Some(ApiAgentStats {
+        timestamp: snapshot.timestamp,
+        pid: a.pid,
+        cpu_pct: a.cpu_pct(),
+        cpu_user_ms: a.cpu_user_ms,
+        cpu_system_ms: a.cpu_system_ms,
+        rss_bytes: a.rss_kb * 1024,
+        rss_peak_bytes: a.rss_peak_kb * 1024,
+        collect_ms: a.collect_us as f64 / 1000.0,
+        storage_write_ms: a.storage_write_us as f64 / 1000.0,
+        phases: a.phases.iter().map(api_phase).collect(),
+        queries: a.queries.iter().map(api_phase).collect(),
+        warnings: a.warnings.clone(),
+    })
+}
+
+fn api_phase(p: &AgentPhaseTiming) -> ApiAgentPhase {
+    ApiAgentPhase {
+        name: p.name.clone(),
+        ms: p.us as f64 / 1000.0,
+    }
+}
+
+/// Loads the agent stats block of every snapshot in `[start_ts, end_ts]`
+/// and summarizes it.
+pub fn agent_summary(provider: &mut HistoryProvider, start_ts: i64, end_ts: i64) -> AgentSummary {
+    let timestamps = provider.timestamps().to_vec();
+    let start_pos = timestamps.partition_point(|&ts| ts < start_ts);
+    let end_pos = timestamps.partition_point(|&ts| ts <= end_ts);
+
+    let samples: Vec<AgentStatsInfo> = (start_pos..end_pos)
+        .filter_map(|pos| match provider.snapshot_block_at(pos, "agent_stats") {
+            Some(DataBlock::AgentStats(a)) => Some(a),
+            _ => None,
+        })
+        .collect();
+    summarize(&samples)
+}
+
+/// Averages and maxima over `samples`. CPU is averaged over samples that
+/// have a previous one to compare with.
+pub fn summarize(samples: &[AgentStatsInfo]) -> AgentSummary {
+    if samples.is_empty() {
+        return AgentSummary::default();
+    }
+    let n = samples.len() as f64;
+    let cpu: Vec<f64> = samples.iter().filter_map(AgentStatsInfo::cpu_pct).collect();
+    let collect_ms = samples.iter().map(|s| s.collect_us as f64 / 1000.0);
+    let write_ms = samples.iter().map(|s| s.storage_write_us as f64 / 1000.0);
+
+    AgentSummary {
+        samples: samples.len(),
+        cpu_pct_avg: if cpu.is_empty() {
+            0.0
+        } else {
+            cpu.iter().sum::<f64>() / cpu.len() as f64
+        },
+        cpu_pct_max: cpu.iter().copied().fold(0.0, f64::max),
+        rss_bytes_max: samples.iter().map(|s| s.rss_kb * 1024).max().unwrap_or(0),
+        collect_ms_avg: collect_ms.clone().sum::<f64>() / n,
+        collect_ms_max: collect_ms.fold(0.0, f64::max),
+        storage_write_ms_avg: write_ms.clone().sum::<f64>() / n,
+        storage_write_ms_max: write_ms.fold(0.0, f64::max),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(interval_ms: u32, cpu_ms: u32, rss_kb: u64, collect_us: u64) -> AgentStatsInfo {
+        AgentStatsInfo {
+            interval_ms,
+            cpu_user_ms: cpu_ms,
+            rss_kb,
+            collect_us,
+            storage_write_us: 500,
+            ..AgentStatsInfo::default()
+        }
+    }
+
+    #[test]
+    fn summarize_skips_first_sample_cpu() {
+        let summary = summarize(&[
+            sample(0, 0, 30_000, 40_000),
+            sample(10_000, 200, 32_000, 20_000),
+            sample(10_000, 600, 31_000, 30_000),
+        ]);
+        assert_eq!(summary.samples, 3);
+        assert_eq!(summary.cpu_pct_avg, 4.0);
+        assert_eq!(summary.cpu_pct_max, 6.0);
+        assert_eq!(summary.rss_bytes_max, 32_000 * 1024);
+        assert_eq!(summary.collect_ms_avg, 30.0);
+        assert_eq!(summary.collect_ms_max, 40.0);
+        assert_eq!(summary.storage_write_ms_max, 0.5);
+    }
+
+    #[test]
+    fn summarize_empty_range() {
+        assert_eq!(summarize(&[]), AgentSummary::default());
+    }
+}
@@ -0,0 +1,140 @@
+//! Composition of shared_buffers at a point in time.
+//!
+//! Built from [`DataBlock::PgBufferCache`], sampled every 5 minutes when the
+//! `pg_buffercache` extension is installed: the report shows the latest
+//! sample at or before the requested moment.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[cfg(feature = "provider")]
+use crate::provider::HistoryProvider;
+#[cfg(feature = "provider")]
+use crate::storage::model::DataBlock;
+use crate::storage::model::PgBufferCacheInfo;
+
+/// Oldest sample (relative to the requested moment) still shown.
+pub const BUFFERCACHE_MAX_AGE_S: i64 = 900;
+
+/// Contents of shared_buffers by relation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct BufferCacheReport {
+    /// Timestamp of the sample.
+    pub ts: i64,
+    /// Size of shared_buffers.
+    pub total_bytes: u64,
+    /// Bytes of buffers holding a page.
+    pub used_bytes: u64,
+    /// Bytes of modified buffers not yet written out.
+    pub dirty_bytes: u64,
+    /// Share of shared_buffers holding a page, 0..100.
+    pub used_pct: f64,
+    /// Share of shared_buffers with usage count >= 3 (re-read pages), 0..100.
+    pub hot_pct: f64,
+    /// Relations holding the most buffers, largest first.
+    pub relations: Vec<BufferCacheRelation>,
+}
+
+/// Buffers of one relation.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BufferCacheRelation {
+    pub database: String,
+    pub schema: String,
+    pub name: String,
+    /// `table` or `index`.
+    pub kind: String,
+    /// Bytes of shared_buffers held.
+    pub bytes: u64,
+    /// Share of shared_buffers held, 0..100.
+    pub pct_of_cache: f64,
+    pub dirty_bytes: u64,
+    /// Mean usage count (0..5) of the relation's buffers.
+    pub avg_usagecount: f64,
+}
+
+fn pct(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
+
+/// Converts the sample taken at `ts`.
+pub fn buffer_cache_report(ts: i64, info: &PgBufferCacheInfo) -> BufferCacheReport {
+    let block = info.block_size as u64;
+    BufferCacheReport {
+        ts,
+        total_bytes: info.total_buffers * block,
+        used_bytes: info.used_buffers * block,
+        dirty_bytes: info.dirty_buffers * block,
+        used_pct: pct(info.used_buffers, info.total_buffers),
+        hot_pct: pct(info.hot_buffers, info.total_buffers),
+        relations: info
+            .relations
+            .iter()
+            .map(|r| BufferCacheRelation {
+                database: r.datname.clone(),
+                schema: r.schemaname.clone(),
+                name: r.relname.clone(),
+                kind: r.kind.as_str().to_string(),
+                bytes: r.buffers * block,
+                pct_of_cache: pct(r.buffers, info.total_buffers),
+                dirty_bytes: r.dirty_buffers * block,
+                avg_usagecount: r.avg_usagecount,
+            })
+            .collect(),
+    }
+}
+
+/// The latest buffer cache sample at or before `ts`, at most
+/// [`BUFFERCACHE_MAX_AGE_S`] older. Only the buffer cache block is read.
+#[cfg(feature = "provider")]
+pub fn buffer_cache_at(provider: &mut HistoryProvider, ts: i64) -> Option<BufferCacheReport> {
+    let timestamps = provider.timestamps().to_vec();
+    let end_pos = timestamps.partition_point(|&t| t <= ts);
+    let start_pos = timestamps.partition_point(|&t| t < ts - BUFFERCACHE_MAX_AGE_S);
+    (start_pos..end_pos).rev().find_map(|pos| {
+        match provider.snapshot_block_at(pos, "pg_buffercache") {
+            Some(DataBlock::PgBufferCache(info)) => {
+                Some(buffer_cache_report(timestamps[pos], &info))
+            }
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::{PgBufferCacheRelation, PgRelationKind};
+
+    #[test]
+    fn converts_buffers_to_bytes_and_shares() {
+        let info = PgBufferCacheInfo {
+            total_buffers: 1000,
+            used_buffers: 900,
+            dirty_buffers: 50,
+            hot_buffers: 300,
+            block_size: 8192,
+            relations: vec![PgBufferCacheRelation {
+                datname: "app".to_string(),
+                schemaname: "public".to_string(),
+                relname: "orders".to_string(),
+                kind: PgRelationKind::Table,
+                buffers: 250,
+                dirty_buffers: 10,
+                avg_usagecount: 3.5,
+            }],
+        };
+        let report = buffer_cache_report(100, &info);
+        assert_eq!(report.total_bytes, 8_192_000);
+        assert_eq!(report.used_pct, 90.0);
+        assert_eq!(report.hot_pct, 30.0);
+        let orders = &report.relations[0];
+        assert_eq!(orders.kind, "table");
+        assert_eq!(orders.bytes, 250 * 8192);
+        assert_eq!(orders.pct_of_cache, 25.0);
+        assert_eq!(orders.dirty_bytes, 10 * 8192);
+    }
+}
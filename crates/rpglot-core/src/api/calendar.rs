@@ -0,0 +1,134 @@
+//! Per-day health summary for a month-view calendar picker.
+//!
+//! Computed from heatmap entries only (`.heatmap` sidecars, no snapshot
+//! decompression), so a month of history is summarized in milliseconds.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::fmt::DisplayTz;
+use crate::provider::HistoryProvider;
+use crate::storage::heatmap::HeatmapEntry;
+
+/// Health score below which a snapshot is critical (red in the timeline).
+const CRITICAL_HEALTH: u8 = 50;
+
+/// Summary of one stored day in the requested time zone.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CalendarDay {
+    /// Date (YYYY-MM-DD).
+    pub date: String,
+    pub snapshots: usize,
+    /// Worst health score of the day (0..100).
+    pub health_min: u8,
+    /// Mean health score of the day.
+    pub health_avg: f64,
+    /// Stretches of consecutive snapshots with a critical health score.
+    pub critical_incidents: usize,
+}
+
+/// Summarizes every stored day of `provider`, with day boundaries in `tz`.
+pub fn calendar(provider: &mut HistoryProvider, tz: DisplayTz) -> Vec<CalendarDay> {
+    let (Some(&start), Some(&end)) = (provider.timestamps().first(), provider.timestamps().last())
+    else {
+        return Vec::new();
+    };
+    summarize_days(&provider.load_heatmap_range(start, end), tz)
+}
+
+/// Groups timestamp-sorted heatmap entries by their date in `tz`.
+pub fn summarize_days(entries: &[(i64, HeatmapEntry)], tz: DisplayTz) -> Vec<CalendarDay> {
+    struct DayAcc {
+        snapshots: usize,
+        health_min: u8,
+        health_sum: u64,
+        critical_incidents: usize,
+        in_critical: bool,
+    }
+
+    let mut days: BTreeMap<String, DayAcc> = BTreeMap::new();
+    for (ts, entry) in entries {
+        let Some(date) = tz.date_key(*ts) else {
+            continue;
+        };
+        let day = days.entry(date).or_insert(DayAcc {
+            snapshots: 0,
+            health_min: 100,
+            health_sum: 0,
+            critical_incidents: 0,
+            in_critical: false,
+        });
+        let critical = entry.health_score < CRITICAL_HEALTH;
+        if critical && !day.in_critical {
+            day.critical_incidents += 1;
+        }
+        day.in_critical = critical;
+        day.snapshots += 1;
+        day.health_min = day.health_min.min(entry.health_score);
+        day.health_sum += entry.health_score as u64;
+    }
+
+    days.into_iter()
+        .map(|(date, acc)| CalendarDay {
+            date,
+            snapshots: acc.snapshots,
+            health_min: acc.health_min,
+            health_avg: acc.health_sum as f64 / acc.snapshots as f64,
+            critical_incidents: acc.critical_incidents,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(ts: i64, health_score: u8) -> (i64, HeatmapEntry) {
+        (
+            ts,
+            HeatmapEntry {
+                health_score,
+                ..HeatmapEntry::default()
+            },
+        )
+    }
+
+    #[test]
+    fn summarizes_days_and_critical_stretches() {
+        // 2026-02-07 23:59:40 .. 2026-02-08 00:00:20 UTC
+        let day = 1_770_508_800;
+        let entries = [
+            entry(day - 20, 40),
+            entry(day - 10, 90),
+            entry(day, 30),
+            entry(day + 10, 20),
+            entry(day + 20, 100),
+        ];
+        let days = summarize_days(&entries, DisplayTz::UTC);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, "2026-02-07");
+        assert_eq!(days[0].snapshots, 2);
+        assert_eq!(days[0].health_min, 40);
+        assert_eq!(days[0].health_avg, 65.0);
+        assert_eq!(days[0].critical_incidents, 1);
+        assert_eq!(days[1].date, "2026-02-08");
+        assert_eq!(days[1].snapshots, 3);
+        assert_eq!(days[1].health_min, 20);
+        assert_eq!(days[1].health_avg, 50.0);
+        // 30 and 20 are one stretch
+        assert_eq!(days[1].critical_incidents, 1);
+
+        // Moscow (UTC+3): all five are in the evening of Feb 8
+        let moscow = summarize_days(&entries, "Europe/Moscow".parse().unwrap());
+        assert_eq!(moscow.len(), 1);
+        assert_eq!(moscow[0].date, "2026-02-08");
+        assert_eq!(moscow[0].snapshots, 5);
+    }
+
+    #[test]
+    fn empty_history() {
+        assert!(summarize_days(&[], DisplayTz::Local).is_empty());
+    }
+}
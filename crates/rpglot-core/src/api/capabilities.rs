@@ -0,0 +1,186 @@
+//! Capabilities of this server, for clients talking to servers of other
+//! versions.
+//!
+//! A fleet is rarely upgraded at once: a frontend bundled with one rpglot-web
+//! may be pointed at an older or newer server. Instead of guessing from the
+//! version string, clients read [`Capabilities`] from `GET /api/v1/schema`
+//! and hide what the server does not provide. Tables and columns are taken
+//! from the OpenAPI schema of [`ApiSnapshot`] (the converter's output
+//! types), so they cannot drift from what the server actually sends.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::{PartialSchema, ToSchema};
+
+use super::select::{SUMMARY_FIELDS, TABLE_FIELDS};
+use super::snapshot::ApiSnapshot;
+use crate::storage::chunk;
+use crate::storage::model::DataBlock;
+
+/// What this server supports.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Capabilities {
+    /// Snapshot tables (`fields` names) with the columns of their rows.
+    pub tables: Vec<TableCapability>,
+    /// Summary fields of the snapshot.
+    pub summary: Vec<String>,
+    /// API paths served (e.g. "/api/v1/buffercache"). Empty when the
+    /// schema is generated outside a server.
+    pub endpoints: Vec<String>,
+    /// Chunk format version written by this server (e.g. 8 for RPG8).
+    pub chunk_format: u16,
+    /// Schema version of each stored data block kind (see
+    /// [`DataBlock::SCHEMA_VERSIONS`]).
+    pub blocks: BTreeMap<String, u8>,
+}
+
+/// One snapshot table and the JSON fields of its rows.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TableCapability {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+impl Capabilities {
+    /// Capabilities of this build, without endpoints.
+    pub fn generate() -> Self {
+        let snapshot = schema_json(ApiSnapshot::schema());
+        let mut components = Vec::new();
+        ApiSnapshot::schemas(&mut components);
+        let components: HashMap<String, Value> = components
+            .into_iter()
+            .map(|(name, schema)| (name, schema_json(schema)))
+            .collect();
+
+        let properties = snapshot.get("properties");
+        let tables = TABLE_FIELDS
+            .iter()
+            .filter_map(|&name| {
+                let items = properties?.get(name)?.get("items")?;
+                Some(TableCapability {
+                    name: name.to_string(),
+                    columns: columns(items, &components),
+                })
+            })
+            .collect();
+        let summary = SUMMARY_FIELDS
+            .iter()
+            .filter(|&&name| properties.is_some_and(|p| p.get(name).is_some()))
+            .map(|name| name.to_string())
+            .collect();
+        let blocks = DataBlock::KIND_NAMES
+            .iter()
+            .zip(DataBlock::SCHEMA_VERSIONS)
+            .map(|(name, version)| (name.to_string(), *version))
+            .collect();
+
+        Self {
+            tables,
+            summary,
+            endpoints: Vec::new(),
+            chunk_format: chunk::VERSION,
+            blocks,
+        }
+    }
+}
+
+fn schema_json(schema: impl Serialize) -> Value {
+    serde_json::to_value(schema).unwrap_or_default()
+}
+
+/// Property names of an object schema, following `$ref` into `components`
+/// and merging `allOf` parts (flattened fields).
+fn columns(schema: &Value, components: &HashMap<String, Value>) -> Vec<String> {
+    if let Some(name) = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.rsplit('/').next())
+    {
+        return components
+            .get(name)
+            .map(|s| columns(s, components))
+            .unwrap_or_default();
+    }
+    let mut out: Vec<String> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|p| p.keys().cloned().collect())
+        .unwrap_or_default();
+    for part in schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        out.extend(columns(part, components));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::convert::{ConvertContext, convert};
+    use crate::api::schema::{ApiMode, ApiSchema};
+    use crate::storage::model::Snapshot;
+
+    fn table<'a>(caps: &'a Capabilities, name: &str) -> &'a TableCapability {
+        caps.tables.iter().find(|t| t.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_every_table_and_summary_field_listed() {
+        let caps = Capabilities::generate();
+        let names: Vec<&str> = caps.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, TABLE_FIELDS);
+        assert_eq!(caps.summary, SUMMARY_FIELDS);
+        assert!(caps.tables.iter().all(|t| !t.columns.is_empty()));
+        assert!(table(&caps, "pgs").columns.iter().any(|c| c == "queryid"));
+        assert_eq!(caps.chunk_format, chunk::VERSION);
+        assert_eq!(caps.blocks.len(), DataBlock::KIND_NAMES.len());
+    }
+
+    #[test]
+    fn test_tab_columns_are_produced_by_converter() {
+        // A frontend renders the tabs of the schema it is given: every
+        // column a tab declares must exist in the rows the server sends.
+        let caps = Capabilities::generate();
+        let schema = ApiSchema::generate(ApiMode::History, None, None);
+        let tabs = serde_json::to_value(&schema.tabs).unwrap();
+        for (name, tab) in tabs.as_object().unwrap() {
+            let produced = &table(&caps, name).columns;
+            for column in tab["columns"].as_array().unwrap() {
+                let key = column["key"].as_str().unwrap();
+                assert!(
+                    produced.iter().any(|c| c == key),
+                    "tab {name}: column {key} is not in the rows"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_old_snapshot_converts_with_every_table() {
+        // Data from an older daemon lacks newer blocks: the snapshot still
+        // carries every table (empty), so clients need not special-case it.
+        let snapshot = Snapshot {
+            timestamp: 100,
+            blocks: Vec::new(),
+        };
+        let api = convert(&ConvertContext {
+            snapshot: &snapshot,
+            prev_snapshot: None,
+            interner: None,
+            pgs_rates: &HashMap::new(),
+            pgp_rates: &HashMap::new(),
+            pgt_rates: &HashMap::new(),
+            pgi_rates: &HashMap::new(),
+        });
+        let json = serde_json::to_value(&api).unwrap();
+        for name in TABLE_FIELDS {
+            assert!(json[*name].is_array(), "{name} missing");
+        }
+    }
+}
@@ -12,10 +12,13 @@ use crate::analysis::{
 use crate::models::{PgIndexesRates, PgStatementsRates, PgStorePlansRates, PgTablesRates};
 use crate::storage::StringInterner;
 use crate::storage::model::{
-    CgroupCpuInfo, DataBlock, ErrorCategory, PgLogEventType, PgLogSeverity, PgStatBgwriterInfo,
-    PgStatDatabaseInfo, ProcessInfo, Snapshot, SystemCpuInfo, SystemDiskInfo, SystemNetInfo,
+    CgroupCpuInfo, CustomColumnType, DataBlock, DegradedReason, ErrorCategory, PgConnectionState,
+    PgLogEventType, PgLogSeverity, PgStatBgwriterInfo, PgStatDatabaseInfo, ProcessInfo, Snapshot,
+    SystemCpuInfo, SystemDiskInfo, SystemNetInfo, pg_pss_total,
 };
+use crate::workload::{ForeignRole, foreign_role};
 
+use super::encryption::connection_encryption;
 use super::snapshot::*;
 
 /// Strip CIDR mask suffix (e.g. `/32`, `/128`) from an IP address string.
@@ -69,6 +72,18 @@ pub fn convert(ctx: &ConvertContext<'_>) -> ApiSnapshot {
         delta_time,
     );
 
+    let foreign_roles: Vec<Option<ForeignRole>> = pga
+        .iter()
+        .map(|r| {
+            foreign_role(
+                &r.application_name,
+                &r.state,
+                &r.wait_event_type,
+                &r.wait_event,
+                &r.query,
+            )
+        })
+        .collect();
     let session_counts = SessionCounts {
         active: pga.iter().filter(|r| r.state == "active").count() as u32,
         idle: pga.iter().filter(|r| r.state == "idle").count() as u32,
@@ -76,6 +91,14 @@ pub fn convert(ctx: &ConvertContext<'_>) -> ApiSnapshot {
             .iter()
             .filter(|r| r.state.starts_with("idle in transaction"))
             .count() as u32,
+        fdw_outgoing: foreign_roles
+            .iter()
+            .filter(|r| **r == Some(ForeignRole::Outgoing))
+            .count() as u32,
+        fdw_incoming: foreign_roles
+            .iter()
+            .filter(|r| **r == Some(ForeignRole::Incoming))
+            .count() as u32,
         total: pga.len() as u32,
     };
 
@@ -94,10 +117,34 @@ pub fn convert(ctx: &ConvertContext<'_>) -> ApiSnapshot {
         pge: extract_pge(snap, ctx.interner),
         pgl: extract_pgl(snap, ctx.interner),
         pgv: extract_pgv(snap, ctx.interner),
+        pgr: workload_rows(crate::workload::aggregate_roles(
+            snap,
+            ctx.pgs_rates,
+            ctx.interner,
+        )),
+        pgw: workload_rows(crate::workload::aggregate_applications(
+            snap,
+            ctx.pgs_rates,
+            ctx.interner,
+        )),
+        custom: extract_custom(snap),
+        pgc: super::settings::settings_rows(snap),
         health_score,
         health_breakdown,
         session_counts,
         replication: extract_replication(snap),
+        encryption: find_block(snap, |b| match b {
+            DataBlock::PgStatActivity(v) => connection_encryption(v),
+            _ => None,
+        }),
+        instances: snap
+            .instance_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        instance: None,
+        degraded: extract_degraded(snap),
+        pg_connection: extract_pg_connection(snap),
     }
 }
 
@@ -108,7 +155,7 @@ pub fn convert(ctx: &ConvertContext<'_>) -> ApiSnapshot {
 fn get_delta_time(current: &Snapshot, previous: Option<&Snapshot>) -> f64 {
     previous
         .map(|p| {
-            let dt = current.timestamp - p.timestamp;
+            let dt = current.elapsed_secs(p);
             if dt > 0 { dt as f64 } else { 1.0 }
         })
         .unwrap_or(1.0)
@@ -170,6 +217,10 @@ fn extract_system_summary(
                 cached_kb: m.cached,
                 buffers_kb: m.buffers,
                 slab_kb: m.slab,
+                pg_pss_kb: find_block(snap, |b| match b {
+                    DataBlock::Processes(processes) => pg_pss_total(processes),
+                    _ => None,
+                }),
             })
         } else {
             None
@@ -627,6 +678,14 @@ fn extract_pg_summary(snap: &Snapshot, prev: Option<&Snapshot>, delta_time: f64)
         (total, critical, warning, info)
     };
 
+    let churn = find_block(snap, |b| {
+        if let DataBlock::PgConnectionChurn(c) = b {
+            Some(c)
+        } else {
+            None
+        }
+    });
+
     PgSummary {
         tps: db_rates.as_ref().map(|r| r.0),
         hit_ratio_pct: db_rates.as_ref().map(|r| r.1),
@@ -651,6 +710,8 @@ fn extract_pg_summary(snap: &Snapshot, prev: Option<&Snapshot>, delta_time: f64)
         } else {
             None
         },
+        connections_s: churn.map(|c| c.connections_per_sec()),
+        short_lived_conn_pct: churn.and_then(|c| c.short_lived_ratio()).map(|r| r * 100.0),
     }
 }
 
@@ -926,7 +987,7 @@ fn extract_prc(
 
             // Memory %
             let mem_pct = if total_mem_kb > 0 {
-                p.mem.rmem as f64 / total_mem_kb as f64 * 100.0
+                p.mem.resident() as f64 / total_mem_kb as f64 * 100.0
             } else {
                 0.0
             };
@@ -1045,6 +1106,16 @@ fn extract_prc(
                 total_read_ops: p.dsk.rio,
                 total_write_ops: p.dsk.wio,
                 cancelled_write_bytes: p.dsk.cwsz,
+                blk_lat_avg_ms: p
+                    .blk_lat
+                    .as_ref()
+                    .and_then(|l| l.avg_us())
+                    .map(|us| us as f64 / 1000.0),
+                blk_lat_p99_ms: p
+                    .blk_lat
+                    .as_ref()
+                    .and_then(|l| l.quantile_us(0.99))
+                    .map(|us| us as f64 / 1000.0),
                 uid: p.uid,
                 euid: p.euid,
                 gid: p.gid,
@@ -1310,6 +1381,8 @@ fn extract_pga(
                 stmt_max_exec_time_ms,
                 stmt_calls_s,
                 stmt_hit_pct,
+                backend_xid: a.backend_xid,
+                backend_xmin_age: a.backend_xmin_age,
             }
         })
         .collect()
@@ -1907,6 +1980,65 @@ fn extract_pgl(snap: &Snapshot, interner: Option<&StringInterner>) -> Vec<PgLock
         .collect()
 }
 
+fn workload_rows(groups: Vec<crate::workload::WorkloadGroup>) -> Vec<PgWorkloadRow> {
+    groups
+        .into_iter()
+        .map(|r| PgWorkloadRow {
+            name: r.name,
+            sessions: r.sessions,
+            active: r.active,
+            idle_in_transaction: r.idle_in_transaction,
+            waiting: r.waiting,
+            active_time_s: r.active_time_s,
+            remote_waiting: r.remote_waiting,
+            remote_time_s: r.remote_time_s,
+            statements: r.statements,
+            calls_s: r.calls_s,
+            rows_s: r.rows_s,
+            exec_time_ms_s: r.exec_time_ms_s,
+        })
+        .collect()
+}
+
+fn extract_custom(snap: &Snapshot) -> Vec<CustomQuerySection> {
+    let Some(queries) = find_block(snap, |b| {
+        if let DataBlock::CustomQuery(v) = b {
+            Some(v.as_slice())
+        } else {
+            None
+        }
+    }) else {
+        return Vec::new();
+    };
+    queries
+        .iter()
+        .map(|q| CustomQuerySection {
+            name: q.name.clone(),
+            columns: q
+                .columns
+                .iter()
+                .map(|c| CustomQueryColumn {
+                    name: c.name.clone(),
+                    kind: match c.kind {
+                        CustomColumnType::Key => "key",
+                        CustomColumnType::Int => "int",
+                        CustomColumnType::Float => "float",
+                    }
+                    .to_string(),
+                })
+                .collect(),
+            rows: q
+                .rows
+                .iter()
+                .map(|r| CustomQueryResultRow {
+                    keys: r.keys.clone(),
+                    values: r.values.clone(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
 fn extract_pgv(snap: &Snapshot, interner: Option<&StringInterner>) -> Vec<PgProgressVacuumRow> {
     let Some(vacuums) = find_block(snap, |b| {
         if let DataBlock::PgStatProgressVacuum(v) = b {
@@ -2002,6 +2134,7 @@ fn extract_replication(snap: &Snapshot) -> Option<ReplicationInfo> {
                 } else {
                     Some(r.sender_host.clone())
                 },
+                archiver: extract_archiver(snap),
             })
         } else {
             None
@@ -2009,6 +2142,54 @@ fn extract_replication(snap: &Snapshot) -> Option<ReplicationInfo> {
     })
 }
 
+fn extract_archiver(snap: &Snapshot) -> Option<ArchiverInfo> {
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+    let non_zero = |t: i64| (t > 0).then_some(t);
+    find_block(snap, |b| match b {
+        DataBlock::PgStatArchiver(a) => Some(ArchiverInfo {
+            archived_count: a.archived_count,
+            failed_count: a.failed_count,
+            failing: a.is_failing(),
+            last_archived_wal: non_empty(&a.last_archived_wal),
+            last_archived_time: non_zero(a.last_archived_time),
+            last_failed_wal: non_empty(&a.last_failed_wal),
+            last_failed_time: non_zero(a.last_failed_time),
+            pending_segments: a.pending_segments(),
+        }),
+        _ => None,
+    })
+}
+
+fn extract_degraded(snap: &Snapshot) -> Option<DegradedInfo> {
+    find_block(snap, |b| match b {
+        DataBlock::CollectionDegraded(d) => Some(DegradedInfo {
+            reason: match d.reason {
+                DegradedReason::SlowCollection => "slow_collection",
+                DegradedReason::Pressure => "pressure",
+            }
+            .to_string(),
+            collection_ms: d.collection_ms,
+            psi_some_avg10: d.psi_some_avg10,
+        }),
+        _ => None,
+    })
+}
+
+fn extract_pg_connection(snap: &Snapshot) -> Option<PgConnectionInfo> {
+    find_block(snap, |b| match b {
+        DataBlock::PgConnection(c) if c.state != PgConnectionState::Connected => {
+            Some(PgConnectionInfo {
+                state: c.state.as_str().to_string(),
+                since: c.since,
+                failures: c.failures,
+                next_attempt: c.next_attempt,
+                error: c.error.clone(),
+            })
+        }
+        _ => None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
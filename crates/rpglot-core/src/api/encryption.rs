@@ -0,0 +1,130 @@
+//! Connection encryption overview.
+//!
+//! Built from the `ssl` flag of [`PgStatActivityInfo`] (`pg_stat_ssl`
+//! joined with pg_stat_activity): how many sessions use TLS and which
+//! remote addresses connect in plaintext. Unix socket and loopback
+//! sessions never leave the host and are counted apart.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::storage::model::PgStatActivityInfo;
+
+/// Plaintext client addresses listed, by session count.
+pub const PLAINTEXT_CLIENTS_MAX: usize = 20;
+
+/// TLS usage of the sessions in one snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct EncryptionInfo {
+    /// Remote sessions using TLS.
+    pub tls_connections: u32,
+    /// Remote sessions without TLS.
+    pub plaintext_connections: u32,
+    /// Unix socket and loopback sessions (background workers included).
+    pub local_connections: u32,
+    /// Remote addresses connecting without TLS, most sessions first.
+    pub plaintext_clients: Vec<PlaintextClient>,
+}
+
+/// Sessions from one address without TLS.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PlaintextClient {
+    pub client_addr: String,
+    pub connections: u32,
+}
+
+/// Address without the `/32` or `/128` mask of `inet::text`.
+fn host(addr: &str) -> &str {
+    addr.split_once('/').map_or(addr, |(h, _)| h)
+}
+
+/// Unix socket (no address) or loopback.
+fn is_local(addr: &str) -> bool {
+    addr.is_empty()
+        || host(addr)
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Summarizes TLS usage. `None` when the snapshot predates the `ssl` flag.
+pub fn connection_encryption(sessions: &[PgStatActivityInfo]) -> Option<EncryptionInfo> {
+    if !sessions.iter().any(|s| s.ssl.is_some()) {
+        return None;
+    }
+    let mut info = EncryptionInfo::default();
+    let mut plaintext: HashMap<&str, u32> = HashMap::new();
+    for s in sessions {
+        if is_local(&s.client_addr) {
+            info.local_connections += 1;
+        } else if s.ssl == Some(true) {
+            info.tls_connections += 1;
+        } else {
+            info.plaintext_connections += 1;
+            *plaintext.entry(host(&s.client_addr)).or_default() += 1;
+        }
+    }
+    let mut clients: Vec<PlaintextClient> = plaintext
+        .into_iter()
+        .map(|(addr, connections)| PlaintextClient {
+            client_addr: addr.to_string(),
+            connections,
+        })
+        .collect();
+    clients.sort_by(|a, b| {
+        b.connections
+            .cmp(&a.connections)
+            .then_with(|| a.client_addr.cmp(&b.client_addr))
+    });
+    clients.truncate(PLAINTEXT_CLIENTS_MAX);
+    info.plaintext_clients = clients;
+    Some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(client_addr: &str, ssl: Option<bool>) -> PgStatActivityInfo {
+        PgStatActivityInfo {
+            client_addr: client_addr.to_string(),
+            ssl,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn counts_remote_plaintext_by_address() {
+        let sessions = vec![
+            session("", Some(false)),
+            session("127.0.0.1/32", Some(false)),
+            session("::1/128", Some(false)),
+            session("10.0.0.5/32", Some(true)),
+            session("10.0.0.7/32", Some(false)),
+            session("10.0.0.7/32", Some(false)),
+            session("10.0.0.9/32", Some(false)),
+        ];
+        let info = connection_encryption(&sessions).unwrap();
+        assert_eq!(info.local_connections, 3);
+        assert_eq!(info.tls_connections, 1);
+        assert_eq!(info.plaintext_connections, 3);
+        assert_eq!(
+            info.plaintext_clients,
+            vec![
+                PlaintextClient {
+                    client_addr: "10.0.0.7".to_string(),
+                    connections: 2
+                },
+                PlaintextClient {
+                    client_addr: "10.0.0.9".to_string(),
+                    connections: 1
+                },
+            ]
+        );
+
+        // Snapshots taken before pg_stat_ssl was collected
+        assert_eq!(connection_encryption(&[session("10.0.0.7", None)]), None);
+    }
+}
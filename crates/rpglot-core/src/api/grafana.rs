@@ -0,0 +1,271 @@
+//! Grafana simple JSON datasource contract, served by rpglot-web under
+//! `/grafana/*`: metric search, time series of instance-wide metrics read
+//! from heatmap entries, and annotations.
+//!
+//! Grafana sends time ranges as RFC 3339 strings and expects datapoints as
+//! `[value, epoch_ms]` pairs.
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::sample_indices;
+use crate::storage::annotations::Annotation;
+use crate::storage::heatmap::HeatmapEntry;
+
+/// Datapoints per series when the panel does not send `maxDataPoints`.
+pub const DEFAULT_MAX_DATA_POINTS: usize = 1000;
+
+/// Extracts a metric value from a heatmap entry.
+pub type MetricExtractor = fn(&HeatmapEntry) -> f64;
+
+/// Metrics a panel can chart, extracted from heatmap entries.
+pub const GRAFANA_METRICS: &[(&str, MetricExtractor)] = &[
+    ("cpu_pct", |e| e.cpu_pct_x10 as f64 / 10.0),
+    ("cgroup_cpu_pct", |e| e.cgroup_cpu_pct_x10 as f64 / 10.0),
+    ("cgroup_mem_pct", |e| e.cgroup_mem_pct_x10 as f64 / 10.0),
+    ("disk_util_pct", |e| e.io_pct_x10 as f64 / 10.0),
+    ("active_sessions", |e| e.active_sessions as f64),
+    ("sessions", |e| e.total_sessions as f64),
+    ("tps", |e| e.tps as f64),
+    ("wal_bytes_s", |e| e.wal_kib_per_sec as f64 * 1024.0),
+    ("health", |e| e.health_score as f64),
+    ("errors_critical", |e| e.errors_critical as f64),
+    ("errors_warning", |e| e.errors_warning as f64),
+    ("errors_info", |e| e.errors_info as f64),
+    ("checkpoints", |e| e.checkpoint_count as f64),
+    ("autovacuums", |e| e.autovacuum_count as f64),
+    ("slow_queries", |e| e.slow_query_count as f64),
+];
+
+/// Time range of a request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GrafanaRange {
+    pub from: String,
+    pub to: String,
+}
+
+impl GrafanaRange {
+    /// Start and end in epoch seconds; `None` if either bound is not
+    /// RFC 3339 or the range is empty.
+    pub fn bounds(&self) -> Option<(i64, i64)> {
+        let from = DateTime::parse_from_rfc3339(&self.from).ok()?.timestamp();
+        let to = DateTime::parse_from_rfc3339(&self.to).ok()?.timestamp();
+        (from < to).then_some((from, to))
+    }
+}
+
+/// Body of `POST /grafana/search`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchRequest {
+    /// Text typed in the metric picker.
+    #[serde(default)]
+    pub target: String,
+}
+
+/// Body of `POST /grafana/query`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRequest {
+    pub range: GrafanaRange,
+    #[serde(default)]
+    pub max_data_points: Option<usize>,
+    #[serde(default)]
+    pub targets: Vec<QueryTarget>,
+}
+
+/// A metric of a query.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueryTarget {
+    #[serde(default)]
+    pub target: String,
+    /// Hidden queries are not evaluated.
+    #[serde(default)]
+    pub hide: bool,
+}
+
+/// A series of a query response.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeSeries {
+    pub target: String,
+    /// `[value, epoch_ms]` pairs.
+    pub datapoints: Vec<(f64, i64)>,
+}
+
+/// Body of `POST /grafana/annotations`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnnotationsRequest {
+    pub range: GrafanaRange,
+    /// Annotation query as configured in the dashboard; echoed back.
+    #[serde(default)]
+    pub annotation: serde_json::Value,
+}
+
+/// An annotation of an annotations response.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnnotationEvent {
+    pub annotation: serde_json::Value,
+    /// Epoch milliseconds.
+    pub time: i64,
+    pub title: String,
+    pub text: String,
+    pub tags: Vec<String>,
+}
+
+/// Metric names containing `target` (case-insensitive), in catalogue order.
+pub fn search(target: &str) -> Vec<&'static str> {
+    let target = target.trim().to_lowercase();
+    GRAFANA_METRICS
+        .iter()
+        .map(|&(name, _)| name)
+        .filter(|name| name.contains(&target))
+        .collect()
+}
+
+/// Series of the visible targets of `request` over `entries` (sorted by
+/// timestamp), sampled down to `maxDataPoints`. Unknown metrics are skipped.
+pub fn query(entries: &[(i64, HeatmapEntry)], request: &QueryRequest) -> Vec<TimeSeries> {
+    let max_points = request
+        .max_data_points
+        .filter(|&n| n > 0)
+        .unwrap_or(DEFAULT_MAX_DATA_POINTS);
+    let indices = sample_indices(entries.len(), max_points);
+    request
+        .targets
+        .iter()
+        .filter(|t| !t.hide)
+        .filter_map(|t| {
+            let &(name, extract) = GRAFANA_METRICS.iter().find(|(n, _)| *n == t.target)?;
+            let datapoints = indices
+                .iter()
+                .map(|&i| {
+                    let (ts, entry) = &entries[i];
+                    (extract(entry), ts * 1000)
+                })
+                .collect();
+            Some(TimeSeries {
+                target: name.to_string(),
+                datapoints,
+            })
+        })
+        .collect()
+}
+
+/// Annotation events of `annotations` for `request`. A non-empty `query` in
+/// the dashboard's annotation settings keeps only texts containing it.
+pub fn annotation_events(
+    annotations: &[Annotation],
+    request: &AnnotationsRequest,
+) -> Vec<AnnotationEvent> {
+    let filter = request
+        .annotation
+        .get("query")
+        .and_then(|q| q.as_str())
+        .map(|q| q.trim().to_lowercase())
+        .unwrap_or_default();
+    annotations
+        .iter()
+        .filter(|a| filter.is_empty() || a.text.to_lowercase().contains(&filter))
+        .map(|a| AnnotationEvent {
+            annotation: request.annotation.clone(),
+            time: a.ts * 1000,
+            title: a.text.clone(),
+            text: a.text.clone(),
+            tags: if a.source.is_empty() {
+                Vec::new()
+            } else {
+                vec![a.source.clone()]
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(from: &str, to: &str) -> GrafanaRange {
+        GrafanaRange {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_rfc3339_range() {
+        let r = range("1970-01-01T00:01:00.000Z", "1970-01-01T00:02:00Z");
+        assert_eq!(r.bounds(), Some((60, 120)));
+        assert_eq!(range("now-1h", "now").bounds(), None);
+        assert_eq!(
+            range("1970-01-01T00:02:00Z", "1970-01-01T00:01:00Z").bounds(),
+            None
+        );
+    }
+
+    #[test]
+    fn search_filters_metric_names() {
+        assert_eq!(search("").len(), GRAFANA_METRICS.len());
+        assert_eq!(search("SESSIONS"), vec!["active_sessions", "sessions"]);
+    }
+
+    #[test]
+    fn query_samples_known_targets() {
+        let entries: Vec<(i64, HeatmapEntry)> = (0..10)
+            .map(|i| {
+                let entry = HeatmapEntry {
+                    tps: i as u32 * 10,
+                    cpu_pct_x10: 505,
+                    ..Default::default()
+                };
+                (i, entry)
+            })
+            .collect();
+        let request: QueryRequest = serde_json::from_value(serde_json::json!({
+            "range": { "from": "1970-01-01T00:00:00Z", "to": "1970-01-01T00:00:10Z" },
+            "maxDataPoints": 2,
+            "targets": [
+                { "target": "tps", "refId": "A" },
+                { "target": "cpu_pct", "hide": true },
+                { "target": "nope" }
+            ]
+        }))
+        .unwrap();
+        let series = query(&entries, &request);
+        assert_eq!(
+            series,
+            vec![TimeSeries {
+                target: "tps".to_string(),
+                datapoints: vec![(0.0, 0), (90.0, 9000)],
+            }]
+        );
+    }
+
+    #[test]
+    fn annotations_echo_query_and_filter_text() {
+        let annotations = vec![
+            Annotation {
+                id: 1,
+                ts: 100,
+                text: "deployed v2.3".to_string(),
+                source: "ci".to_string(),
+                created_at: 100,
+            },
+            Annotation {
+                id: 2,
+                ts: 200,
+                text: "vacuum full".to_string(),
+                source: String::new(),
+                created_at: 200,
+            },
+        ];
+        let request: AnnotationsRequest = serde_json::from_value(serde_json::json!({
+            "range": { "from": "1970-01-01T00:00:00Z", "to": "1970-01-01T01:00:00Z" },
+            "annotation": { "name": "deploys", "query": "deploy" }
+        }))
+        .unwrap();
+        let events = annotation_events(&annotations, &request);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].time, 100_000);
+        assert_eq!(events[0].tags, vec!["ci".to_string()]);
+        assert_eq!(events[0].annotation["name"], "deploys");
+    }
+}
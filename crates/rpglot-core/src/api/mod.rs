@@ -4,6 +4,21 @@
 //! All interned strings are resolved, rates are pre-computed by the server.
 //! Clients use the companion schema to interpret units, formats, and views.
 
+#[cfg(feature = "api")]
+pub mod agent;
+pub mod buffercache;
+#[cfg(feature = "api")]
+pub mod calendar;
+pub mod capabilities;
 pub mod convert;
+pub mod encryption;
+pub mod grafana;
+pub mod plan_diff;
+pub mod relations;
 pub mod schema;
+pub mod select;
+pub mod settings;
 pub mod snapshot;
+pub mod top_consumers;
+#[cfg(feature = "api")]
+pub mod workload;
@@ -0,0 +1,283 @@
+//! Line diff of two pg_store_plans plans of the same queryid.
+//!
+//! Plan lines are matched by their node (operator, relation, index,
+//! conditions) with cost, row and timing estimates ignored, so a node whose
+//! estimates moved is `changed` rather than removed and added again. The
+//! result is aligned for side-by-side display; unified output takes the
+//! `left` of removed lines and the `right` of the rest.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::storage::StringInterner;
+use crate::storage::model::{DataBlock, PgStorePlansInfo, Snapshot};
+
+/// Plans longer than this (lines) are not aligned: all lines of the old plan
+/// are removed and all of the new one added.
+const MAX_DIFF_LINES: usize = 2000;
+
+/// How a line differs between the two plans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanDiffKind {
+    Same,
+    /// Same node with different estimates.
+    Changed,
+    /// Only in the old plan.
+    Removed,
+    /// Only in the new plan.
+    Added,
+}
+
+/// One aligned row of the diff.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PlanDiffLine {
+    pub kind: PlanDiffKind,
+    /// Line of the old plan (absent for added lines).
+    pub left: Option<String>,
+    /// Line of the new plan (absent for removed lines).
+    pub right: Option<String>,
+}
+
+/// One of the two compared plans.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PlanDiffSide {
+    pub planid: i64,
+    pub calls: i64,
+    pub mean_time_ms: f64,
+}
+
+/// Diff of two plans of a queryid.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PlanDiff {
+    pub queryid: i64,
+    pub from: PlanDiffSide,
+    pub to: PlanDiffSide,
+    pub lines: Vec<PlanDiffLine>,
+}
+
+impl PlanDiff {
+    /// Number of lines that are not `same`.
+    pub fn changed_lines(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|l| l.kind != PlanDiffKind::Same)
+            .count()
+    }
+}
+
+/// Diffs two plans of `queryid` in the PgStorePlans block of `snapshot`.
+///
+/// Plans are taken by calls, most first: `from` defaults to the first plan
+/// other than `to`, and `to` to the first plan other than `from`. `None`
+/// when the queryid has fewer than two plans or a given planid is unknown.
+pub fn plan_diff(
+    snapshot: &Snapshot,
+    interner: Option<&StringInterner>,
+    queryid: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Option<PlanDiff> {
+    let mut plans: Vec<&PgStorePlansInfo> = snapshot
+        .blocks
+        .iter()
+        .find_map(|b| match b {
+            DataBlock::PgStorePlans(v) => Some(v.iter().filter(|p| p.stmt_queryid == queryid)),
+            _ => None,
+        })?
+        .collect();
+    plans.sort_by_key(|p| std::cmp::Reverse(p.calls));
+
+    let pick = |planid: Option<i64>, other: Option<i64>| match planid {
+        Some(id) => plans.iter().copied().find(|p| p.planid == id),
+        None => plans.iter().copied().find(|p| Some(p.planid) != other),
+    };
+    let from_plan = pick(from, to)?;
+    let to_plan = pick(to, Some(from_plan.planid))?;
+    if from_plan.planid == to_plan.planid {
+        return None;
+    }
+
+    let text = |p: &PgStorePlansInfo| {
+        interner
+            .and_then(|i| i.resolve(p.plan_hash))
+            .unwrap_or_default()
+            .replace('\t', "    ")
+    };
+    let side = |p: &PgStorePlansInfo| PlanDiffSide {
+        planid: p.planid,
+        calls: p.calls,
+        mean_time_ms: p.mean_time,
+    };
+    Some(PlanDiff {
+        queryid,
+        from: side(from_plan),
+        to: side(to_plan),
+        lines: diff_plan_text(&text(from_plan), &text(to_plan)),
+    })
+}
+
+/// Aligns the lines of two plan texts.
+pub fn diff_plan_text(old: &str, new: &str) -> Vec<PlanDiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let old_keys: Vec<String> = old.iter().map(|l| node_key(l)).collect();
+    let new_keys: Vec<String> = new.iter().map(|l| node_key(l)).collect();
+
+    let line = |kind, left: Option<&str>, right: Option<&str>| PlanDiffLine {
+        kind,
+        left: left.map(str::to_string),
+        right: right.map(str::to_string),
+    };
+
+    if old.len() > MAX_DIFF_LINES || new.len() > MAX_DIFF_LINES {
+        return old
+            .iter()
+            .map(|l| line(PlanDiffKind::Removed, Some(l), None))
+            .chain(new.iter().map(|l| line(PlanDiffKind::Added, None, Some(l))))
+            .collect();
+    }
+
+    // Longest common subsequence of node keys, filled from the end
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_keys[i] == new_keys[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_keys[i] == new_keys[j] {
+            let kind = if old[i] == new[j] {
+                PlanDiffKind::Same
+            } else {
+                PlanDiffKind::Changed
+            };
+            lines.push(line(kind, Some(old[i]), Some(new[j])));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(line(PlanDiffKind::Removed, Some(old[i]), None));
+            i += 1;
+        } else {
+            lines.push(line(PlanDiffKind::Added, None, Some(new[j])));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// A plan line without its estimates: parenthesized groups starting with
+/// `cost=`, `actual ` or `rows=` are dropped and whitespace is collapsed.
+fn node_key(line: &str) -> String {
+    let mut key = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(open) = rest.find('(') {
+        let inner = &rest[open + 1..];
+        let is_estimate = ["cost=", "actual ", "rows="]
+            .iter()
+            .any(|p| inner.starts_with(p));
+        match inner.find(')') {
+            Some(close) if is_estimate => {
+                key.push_str(&rest[..open]);
+                rest = &inner[close + 1..];
+            }
+            _ => {
+                key.push_str(&rest[..=open]);
+                rest = inner;
+            }
+        }
+    }
+    key.push_str(rest);
+    key.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX_PLAN: &str = "\
+Limit  (cost=0.43..8.45 rows=1 width=40)
+  ->  Index Scan using orders_customer_idx on orders  (cost=0.43..8.45 rows=1 width=40)
+        Index Cond: (customer_id = $1)";
+
+    const SEQ_PLAN: &str = "\
+Limit  (cost=0.00..1843.00 rows=1 width=40)
+  ->  Seq Scan on orders  (cost=0.00..1843.00 rows=1 width=40)
+        Filter: (customer_id = $1)";
+
+    #[test]
+    fn matches_nodes_ignoring_estimates() {
+        let lines = diff_plan_text(INDEX_PLAN, SEQ_PLAN);
+        let kinds: Vec<PlanDiffKind> = lines.iter().map(|l| l.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                PlanDiffKind::Changed,
+                PlanDiffKind::Removed,
+                PlanDiffKind::Removed,
+                PlanDiffKind::Added,
+                PlanDiffKind::Added,
+            ]
+        );
+        assert_eq!(lines[1].right, None);
+        assert_eq!(
+            lines[3].right.as_deref(),
+            Some("  ->  Seq Scan on orders  (cost=0.00..1843.00 rows=1 width=40)")
+        );
+        // Conditions in parentheses are part of the node
+        assert_eq!(node_key("Filter: (a = 1)"), "Filter: (a = 1)");
+        assert_eq!(
+            node_key(
+                "Seq Scan on t  (cost=0.00..1.00 rows=1 width=4) (actual time=0.1..0.2 rows=1 loops=1)"
+            ),
+            "Seq Scan on t"
+        );
+        assert!(
+            diff_plan_text(SEQ_PLAN, SEQ_PLAN)
+                .iter()
+                .all(|l| l.kind == PlanDiffKind::Same)
+        );
+    }
+
+    #[test]
+    fn picks_plans_by_calls() {
+        let mut interner = StringInterner::new();
+        let plan = |planid, calls, text: &str, interner: &mut StringInterner| PgStorePlansInfo {
+            stmt_queryid: 7,
+            planid,
+            calls,
+            plan_hash: interner.intern(text),
+            mean_time: calls as f64,
+            ..Default::default()
+        };
+        let snapshot = Snapshot {
+            timestamp: 0,
+            blocks: vec![DataBlock::PgStorePlans(vec![
+                plan(1, 500, INDEX_PLAN, &mut interner),
+                plan(2, 20, SEQ_PLAN, &mut interner),
+                plan(3, 100, SEQ_PLAN, &mut interner),
+            ])],
+        };
+
+        let diff = plan_diff(&snapshot, Some(&interner), 7, None, None).unwrap();
+        assert_eq!((diff.from.planid, diff.to.planid), (1, 3));
+        assert_eq!(diff.changed_lines(), 5);
+
+        let diff = plan_diff(&snapshot, Some(&interner), 7, None, Some(2)).unwrap();
+        assert_eq!((diff.from.planid, diff.to.planid), (1, 2));
+        let diff = plan_diff(&snapshot, Some(&interner), 7, Some(2), None).unwrap();
+        assert_eq!((diff.from.planid, diff.to.planid), (2, 1));
+
+        assert!(plan_diff(&snapshot, Some(&interner), 7, Some(2), Some(2)).is_none());
+        assert!(plan_diff(&snapshot, Some(&interner), 7, Some(9), None).is_none());
+        assert!(plan_diff(&snapshot, Some(&interner), 8, None, None).is_none());
+    }
+}
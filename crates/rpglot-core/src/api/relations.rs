@@ -0,0 +1,197 @@
+//! Growth of the largest relations over a history range.
+//!
+//! Built from [`DataBlock::PgRelationSizes`], sampled hourly: the first and
+//! the last sample of the range are compared, so the range has to span at
+//! least two samples for sizes to have a delta.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[cfg(feature = "api")]
+use crate::provider::HistoryProvider;
+#[cfg(feature = "api")]
+use crate::storage::model::DataBlock;
+use crate::storage::model::PgRelationSizeInfo;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Relation sizes at the end of a range and their change since its start.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct RelationGrowthReport {
+    /// Timestamp of the first size sample in the range (0 without samples).
+    pub from_ts: i64,
+    /// Timestamp of the last size sample in the range (0 without samples).
+    pub to_ts: i64,
+    /// Relations of the last sample, fastest growing first.
+    pub relations: Vec<RelationGrowth>,
+}
+
+impl RelationGrowthReport {
+    /// Seconds between the compared samples.
+    pub fn span_s(&self) -> i64 {
+        self.to_ts - self.from_ts
+    }
+}
+
+/// Size of one relation and its change over the range.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RelationGrowth {
+    pub database: String,
+    pub schema: String,
+    pub name: String,
+    /// `table` (size includes TOAST and indexes) or `index`.
+    pub kind: String,
+    /// Size in the last sample.
+    pub bytes: u64,
+    /// Change since the first sample; absent when the relation was not
+    /// among the largest then, or the range has a single sample.
+    pub delta_bytes: Option<i64>,
+    /// `delta_bytes` extrapolated to a day.
+    pub bytes_per_day: Option<f64>,
+}
+
+/// Compares two samples taken at `first.0` and `last.0`.
+pub fn relation_growth(
+    first: (i64, &[PgRelationSizeInfo]),
+    last: (i64, &[PgRelationSizeInfo]),
+) -> RelationGrowthReport {
+    let (from_ts, first) = first;
+    let (to_ts, last) = last;
+    let span_days = (to_ts - from_ts) as f64 / SECONDS_PER_DAY;
+    let before: HashMap<(&str, u32), u64> = first
+        .iter()
+        .map(|r| ((r.datname.as_str(), r.relid), r.bytes))
+        .collect();
+
+    let mut relations: Vec<RelationGrowth> = last
+        .iter()
+        .map(|r| {
+            let delta_bytes = before
+                .get(&(r.datname.as_str(), r.relid))
+                .filter(|_| span_days > 0.0)
+                .map(|&b| r.bytes as i64 - b as i64);
+            RelationGrowth {
+                database: r.datname.clone(),
+                schema: r.schemaname.clone(),
+                name: r.relname.clone(),
+                kind: r.kind.as_str().to_string(),
+                bytes: r.bytes,
+                delta_bytes,
+                bytes_per_day: delta_bytes.map(|d| d as f64 / span_days),
+            }
+        })
+        .collect();
+    relations.sort_by(|a, b| {
+        b.delta_bytes
+            .cmp(&a.delta_bytes)
+            .then(b.bytes.cmp(&a.bytes))
+    });
+
+    RelationGrowthReport {
+        from_ts,
+        to_ts,
+        relations,
+    }
+}
+
+/// Finds the first and the last relation size sample in `[start_ts, end_ts]`
+/// and compares them. Only the relation sizes block is read, scanning in
+/// from both ends of the range.
+#[cfg(feature = "api")]
+pub fn relation_growth_in_range(
+    provider: &mut HistoryProvider,
+    start_ts: i64,
+    end_ts: i64,
+) -> RelationGrowthReport {
+    let timestamps = provider.timestamps().to_vec();
+    let start_pos = timestamps.partition_point(|&ts| ts < start_ts);
+    let end_pos = timestamps.partition_point(|&ts| ts <= end_ts);
+
+    let Some((first_pos, first)) =
+        (start_pos..end_pos).find_map(|pos| Some((pos, sizes_at(provider, pos)?)))
+    else {
+        return RelationGrowthReport::default();
+    };
+    let (last_pos, last) = (first_pos + 1..end_pos)
+        .rev()
+        .find_map(|pos| Some((pos, sizes_at(provider, pos)?)))
+        .unwrap_or((first_pos, first.clone()));
+
+    relation_growth(
+        (timestamps[first_pos], &first),
+        (timestamps[last_pos], &last),
+    )
+}
+
+#[cfg(feature = "api")]
+fn sizes_at(provider: &mut HistoryProvider, pos: usize) -> Option<Vec<PgRelationSizeInfo>> {
+    match provider.snapshot_block_at(pos, "pg_relation_sizes") {
+        Some(DataBlock::PgRelationSizes(v)) => Some(v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::PgRelationKind;
+
+    fn relation(datname: &str, relid: u32, kind: PgRelationKind, bytes: u64) -> PgRelationSizeInfo {
+        PgRelationSizeInfo {
+            datname: datname.to_string(),
+            schemaname: "public".to_string(),
+            relname: format!("rel{relid}"),
+            relid,
+            kind,
+            bytes,
+        }
+    }
+
+    #[test]
+    fn compares_first_and_last_sample() {
+        let gib = 1 << 30;
+        let first = vec![
+            relation("app", 1, PgRelationKind::Table, 100 * gib),
+            relation("app", 2, PgRelationKind::Index, 10 * gib),
+            // Same OID in another database
+            relation("billing", 1, PgRelationKind::Table, gib),
+        ];
+        let last = vec![
+            relation("app", 1, PgRelationKind::Table, 110 * gib),
+            relation("app", 2, PgRelationKind::Index, 9 * gib),
+            relation("billing", 1, PgRelationKind::Table, 2 * gib),
+            relation("app", 3, PgRelationKind::Table, 50 * gib),
+        ];
+        let report = relation_growth((0, &first), (43_200, &last));
+        assert_eq!(report.span_s(), 43_200);
+
+        let rows: Vec<_> = report
+            .relations
+            .iter()
+            .map(|r| (r.database.as_str(), r.name.as_str(), r.delta_bytes))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("app", "rel1", Some(10 * gib as i64)),
+                ("billing", "rel1", Some(gib as i64)),
+                ("app", "rel2", Some(-(gib as i64))),
+                ("app", "rel3", None),
+            ]
+        );
+        // 10 GiB in half a day
+        assert_eq!(report.relations[0].bytes_per_day, Some(20.0 * gib as f64));
+        assert_eq!(report.relations[2].kind, "index");
+    }
+
+    #[test]
+    fn single_sample_has_no_deltas() {
+        let sample = vec![relation("app", 1, PgRelationKind::Table, 1000)];
+        let report = relation_growth((100, &sample), (100, &sample));
+        assert_eq!(report.relations.len(), 1);
+        assert_eq!(report.relations[0].delta_bytes, None);
+        assert_eq!(report.relations[0].bytes_per_day, None);
+    }
+}
@@ -6,6 +6,13 @@
 use serde::Serialize;
 use utoipa::ToSchema;
 
+use super::capabilities::Capabilities;
+use crate::fmt::FmtConfig;
+use crate::storage::annotations::Annotation;
+use crate::storage::gaps::TimelineGap;
+use crate::storage::model::{K8sPodInfo, PgExtensionInfo};
+use crate::thresholds::{Thresholds, column_thresholds};
+
 /// Top-level API schema returned by `GET /api/v1/schema`.
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct ApiSchema {
@@ -19,10 +26,18 @@ pub struct ApiSchema {
     /// PostgreSQL instance metadata (live mode only).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub instance: Option<InstanceInfo>,
+    /// Query text redaction applied by the collector ("strip", "hash" or
+    /// "drop"). Absent when texts are stored as-is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<String>,
+    /// Number and time formatting the server is configured with.
+    pub format: FmtConfig,
     /// Summary panel field descriptions.
     pub summary: SummarySchema,
     /// Tab descriptions.
     pub tabs: TabsSchema,
+    /// Tables, columns and endpoints this server supports.
+    pub capabilities: Capabilities,
 }
 
 /// PostgreSQL instance metadata.
@@ -38,6 +53,64 @@ pub struct InstanceInfo {
     /// Machine hostname.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hostname: Option<String>,
+    /// Kubernetes pod the collector runs in (container mode only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kubernetes: Option<KubernetesInfo>,
+    /// Installed extensions of every database, from the last hourly sample.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<ExtensionInfo>,
+}
+
+/// Extension installed in one database.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ExtensionInfo {
+    pub database: String,
+    pub name: String,
+    pub version: String,
+    pub schema: String,
+}
+
+impl From<&PgExtensionInfo> for ExtensionInfo {
+    fn from(ext: &PgExtensionInfo) -> Self {
+        Self {
+            database: ext.datname.clone(),
+            name: ext.name.clone(),
+            version: ext.version.clone(),
+            schema: ext.schema.clone(),
+        }
+    }
+}
+
+/// Kubernetes pod metadata from the downward API. Zero resources are not
+/// declared in the pod spec.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct KubernetesInfo {
+    pub pod: String,
+    pub namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub node: String,
+    /// CPU request, millicores.
+    pub cpu_request_millis: u64,
+    /// CPU limit, millicores.
+    pub cpu_limit_millis: u64,
+    /// Memory request, bytes.
+    pub memory_request: u64,
+    /// Memory limit, bytes.
+    pub memory_limit: u64,
+}
+
+impl From<&K8sPodInfo> for KubernetesInfo {
+    fn from(pod: &K8sPodInfo) -> Self {
+        Self {
+            pod: pod.pod.clone(),
+            namespace: pod.namespace.clone(),
+            node: pod.node.clone(),
+            cpu_request_millis: pod.cpu_request_millis,
+            cpu_limit_millis: pod.cpu_limit_millis,
+            memory_request: pod.memory_request,
+            memory_limit: pod.memory_limit,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, ToSchema)]
@@ -58,6 +131,13 @@ pub struct TimelineInfo {
     /// Per-date index for efficient navigation. Present in timeline endpoint.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dates: Option<Vec<DateInfo>>,
+    /// Deployment/maintenance annotations. Present in timeline endpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Vec<Annotation>>,
+    /// Intervals without snapshots (collector down, host rebooted).
+    /// Present in timeline endpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gaps: Option<Vec<TimelineGap>>,
 }
 
 /// Information about snapshots available on a specific date.
@@ -136,6 +216,9 @@ pub struct ColumnSchema {
     pub sortable: bool,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub filterable: bool,
+    /// Warning/critical levels for coloring cells and rows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thresholds: Option<Thresholds>,
 }
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
@@ -278,8 +361,11 @@ impl ApiSchema {
             mode,
             timeline,
             instance,
+            redaction: None,
+            format: crate::fmt::config(),
             summary: generate_summary_schema(),
             tabs: generate_tabs_schema(),
+            capabilities: Capabilities::generate(),
         }
     }
 }
@@ -489,6 +575,13 @@ fn generate_summary_schema() -> SummarySchema {
                         Some(Unit::Kb),
                         Some(Format::Bytes),
                     ),
+                    field(
+                        "pg_pss_kb",
+                        "PostgreSQL PSS",
+                        DataType::Integer,
+                        Some(Unit::Kb),
+                        Some(Format::Bytes),
+                    ),
                 ],
             },
             SummarySection {
@@ -665,6 +758,20 @@ fn generate_summary_schema() -> SummarySchema {
                         None,
                     ),
                     field("errors_info", "Err Info", DataType::Integer, None, None),
+                    field(
+                        "connections_s",
+                        "Conn/s",
+                        DataType::Number,
+                        Some(Unit::PerSec),
+                        Some(Format::Rate),
+                    ),
+                    field(
+                        "short_lived_conn_pct",
+                        "Short Conns",
+                        DataType::Number,
+                        Some(Unit::Percent),
+                        Some(Format::Percent),
+                    ),
                 ],
             },
             SummarySection {
@@ -721,16 +828,25 @@ fn generate_summary_schema() -> SummarySchema {
 
 fn generate_tabs_schema() -> TabsSchema {
     TabsSchema {
-        prc: generate_prc_schema(),
-        pga: generate_pga_schema(),
-        pgs: generate_pgs_schema(),
-        pgp: generate_pgp_schema(),
-        pgt: generate_pgt_schema(),
-        pgi: generate_pgi_schema(),
-        pge: generate_pge_schema(),
-        pgl: generate_pgl_schema(),
-        pgv: generate_pgv_schema(),
+        prc: with_thresholds("prc", generate_prc_schema()),
+        pga: with_thresholds("pga", generate_pga_schema()),
+        pgs: with_thresholds("pgs", generate_pgs_schema()),
+        pgp: with_thresholds("pgp", generate_pgp_schema()),
+        pgt: with_thresholds("pgt", generate_pgt_schema()),
+        pgi: with_thresholds("pgi", generate_pgi_schema()),
+        pge: with_thresholds("pge", generate_pge_schema()),
+        pgl: with_thresholds("pgl", generate_pgl_schema()),
+        pgv: with_thresholds("pgv", generate_pgv_schema()),
+    }
+}
+
+/// Attaches the thresholds configured in [`crate::thresholds`] to the
+/// columns of tab `tab`.
+fn with_thresholds(tab: &str, mut schema: TabSchema) -> TabSchema {
+    for col in &mut schema.columns {
+        col.thresholds = column_thresholds(tab, &col.key);
     }
+    schema
 }
 
 fn generate_prc_schema() -> TabSchema {
@@ -1019,6 +1135,24 @@ fn generate_prc_schema() -> TabSchema {
                 true,
                 false,
             ),
+            col(
+                "blk_lat_avg_ms",
+                "Lat",
+                DataType::Number,
+                Some(Unit::Ms),
+                None,
+                true,
+                false,
+            ),
+            col(
+                "blk_lat_p99_ms",
+                "Lat p99",
+                DataType::Number,
+                Some(Unit::Ms),
+                None,
+                true,
+                false,
+            ),
             col("tty", "TTY", DataType::Integer, None, None, true, false),
             col(
                 "exit_signal",
@@ -1129,6 +1263,8 @@ fn generate_prc_schema() -> TabSchema {
                     "write_ops_s",
                     "blkdelay",
                     "cancelled_write_bytes",
+                    "blk_lat_avg_ms",
+                    "blk_lat_p99_ms",
                     "cmdline",
                 ]
                 .into_iter()
@@ -1411,6 +1547,24 @@ fn generate_pga_schema() -> TabSchema {
                 true,
                 false,
             ),
+            col(
+                "backend_xid",
+                "XID",
+                DataType::Integer,
+                None,
+                None,
+                true,
+                false,
+            ),
+            col(
+                "backend_xmin_age",
+                "Xmin Age",
+                DataType::Integer,
+                None,
+                None,
+                true,
+                false,
+            ),
         ],
         views: vec![
             ViewSchema {
@@ -1470,6 +1624,29 @@ fn generate_pga_schema() -> TabSchema {
                 default_sort_desc: true,
                 column_overrides: vec![],
             },
+            ViewSchema {
+                key: "transactions".into(),
+                label: "Transactions".into(),
+                columns: vec![
+                    "pid",
+                    "database",
+                    "user",
+                    "state",
+                    "backend_xid",
+                    "backend_xmin_age",
+                    "xact_duration_s",
+                    "query_duration_s",
+                    "backend_type",
+                    "query",
+                ]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+                default: false,
+                default_sort: Some("xact_duration_s".into()),
+                default_sort_desc: true,
+                column_overrides: vec![],
+            },
         ],
         drill_downs: vec![
             DrillDown {
@@ -3503,5 +3680,37 @@ fn col(
         format,
         sortable,
         filterable,
+        thresholds: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thresholds::COLUMN_THRESHOLDS;
+
+    #[test]
+    fn every_threshold_names_a_schema_column() {
+        let tabs = generate_tabs_schema();
+        let by_key = [
+            ("pga", &tabs.pga),
+            ("pgs", &tabs.pgs),
+            ("pgp", &tabs.pgp),
+            ("pgt", &tabs.pgt),
+            ("pgi", &tabs.pgi),
+        ];
+        for (tab, key, thresholds) in COLUMN_THRESHOLDS {
+            let (_, schema) = by_key
+                .iter()
+                .find(|(t, _)| t == tab)
+                .unwrap_or_else(|| panic!("no tab {tab}"));
+            let col = schema.columns.iter().find(|c| c.key == *key);
+            assert_eq!(
+                col.and_then(|c| c.thresholds),
+                Some(*thresholds),
+                "{tab}.{key}"
+            );
+        }
+        assert!(tabs.pgs.columns.iter().any(|c| c.thresholds.is_none()));
     }
 }
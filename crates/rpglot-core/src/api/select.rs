@@ -0,0 +1,477 @@
+//! Field selection, server-side sorting and pagination of [`ApiSnapshot`].
+//!
+//! A snapshot with 10k+ pg_stat_statements rows serializes into several MB,
+//! while the frontend shows one tab at a time. [`SelectedSnapshot`]
+//! serializes only the requested fields and a window of each table, without
+//! copying the snapshot. Tables can be sorted by any column first
+//! (`pgs_sort=exec_time_ms_s&pgs_limit=100` = top 100 statements).
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+
+use super::snapshot::ApiSnapshot;
+
+/// Table fields, paginated by `limit` / `offset`.
+pub const TABLE_FIELDS: &[&str] = &[
+    "prc", "pga", "pgs", "pgt", "pgi", "pge", "pgl", "pgp", "pgv", "pgr", "pgw", "custom", "pgc",
+];
+
+/// Summary fields; `summary` selects all of them.
+pub const SUMMARY_FIELDS: &[&str] = &[
+    "system",
+    "pg",
+    "health_score",
+    "health_breakdown",
+    "session_counts",
+    "replication",
+    "degraded",
+];
+
+/// Sorting and paging of one table (`<table>_sort`, `<table>_order`,
+/// `<table>_limit`, `<table>_offset` query parameters).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableParams {
+    /// Column (JSON field name of the row) to sort by.
+    pub sort: Option<String>,
+    /// Ascending order; descending (top-N) by default.
+    pub ascending: bool,
+    /// Overrides the selection-wide limit.
+    pub limit: Option<usize>,
+    /// Overrides the selection-wide offset.
+    pub offset: Option<usize>,
+}
+
+/// Which fields of an [`ApiSnapshot`] to serialize and which rows of each table.
+///
+/// Timestamps and instance fields are always included.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotSelection {
+    /// Selected field names (`None` = all fields).
+    fields: Option<Vec<&'static str>>,
+    /// Maximum rows per table (`None` = all rows).
+    pub limit: Option<usize>,
+    /// Rows skipped at the start of each table.
+    pub offset: usize,
+    /// Per-table sorting and paging.
+    pub tables: BTreeMap<&'static str, TableParams>,
+}
+
+impl SnapshotSelection {
+    /// Parses a comma-separated list of field names (`"pgs,summary"`).
+    pub fn parse(
+        fields: Option<&str>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Self, String> {
+        let fields = match fields {
+            None => None,
+            Some(list) => {
+                let mut selected = Vec::new();
+                for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                    if name == "summary" {
+                        selected.extend_from_slice(SUMMARY_FIELDS);
+                    } else if let Some(&field) = TABLE_FIELDS
+                        .iter()
+                        .chain(SUMMARY_FIELDS)
+                        .find(|&&f| f == name)
+                    {
+                        selected.push(field);
+                    } else {
+                        return Err(format!(
+                            "unknown field '{}', expected summary or one of: {}, {}",
+                            name,
+                            SUMMARY_FIELDS.join(", "),
+                            TABLE_FIELDS.join(", ")
+                        ));
+                    }
+                }
+                Some(selected)
+            }
+        };
+        Ok(Self {
+            fields,
+            limit,
+            offset: offset.unwrap_or(0),
+            tables: BTreeMap::new(),
+        })
+    }
+
+    /// Applies per-table query parameters such as `pgs_sort=exec_time_ms_s`,
+    /// `pgs_order=asc`, `pgs_limit=100`, `pgs_offset=100`. Other parameters
+    /// are ignored.
+    pub fn apply_table_params<'a>(
+        &mut self,
+        params: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<(), String> {
+        for (key, value) in params {
+            let Some((table, param)) = key.rsplit_once('_') else {
+                continue;
+            };
+            let Some(&table) = TABLE_FIELDS.iter().find(|&&t| t == table) else {
+                continue;
+            };
+            let parse_count = || {
+                value
+                    .parse::<usize>()
+                    .map_err(|e| format!("invalid {}: {}", key, e))
+            };
+            match param {
+                "sort" => self.tables.entry(table).or_default().sort = Some(value.to_string()),
+                "order" => {
+                    self.tables.entry(table).or_default().ascending = match value {
+                        "asc" => true,
+                        "desc" => false,
+                        _ => return Err(format!("invalid {}: expected asc or desc", key)),
+                    }
+                }
+                "limit" => self.tables.entry(table).or_default().limit = Some(parse_count()?),
+                "offset" => self.tables.entry(table).or_default().offset = Some(parse_count()?),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the snapshot is serialized as is.
+    pub fn is_full(&self) -> bool {
+        self.fields.is_none() && self.limit.is_none() && self.offset == 0 && self.tables.is_empty()
+    }
+
+    fn wants(&self, field: &str) -> bool {
+        self.fields
+            .as_ref()
+            .is_none_or(|fields| fields.contains(&field))
+    }
+
+    fn paginated(&self) -> bool {
+        self.limit.is_some()
+            || self.offset > 0
+            || self
+                .tables
+                .values()
+                .any(|t| t.limit.is_some() || t.offset.is_some())
+    }
+
+    /// Rows of table `name` to serialize: sorted by `order` if given, then
+    /// windowed by offset/limit.
+    fn window<'a, T>(&self, name: &str, rows: &'a [T], order: Option<&[usize]>) -> Vec<&'a T> {
+        let params = self.tables.get(name);
+        let offset = params.and_then(|p| p.offset).unwrap_or(self.offset);
+        let limit = params.and_then(|p| p.limit).or(self.limit);
+        let start = offset.min(rows.len());
+        let end = match limit {
+            Some(limit) => start.saturating_add(limit).min(rows.len()),
+            None => rows.len(),
+        };
+        match order {
+            Some(order) => order[start..end].iter().map(|&i| &rows[i]).collect(),
+            None => rows[start..end].iter().collect(),
+        }
+    }
+}
+
+/// Row indices of `rows` sorted by JSON field `column`. Rows without the
+/// field sort last in both directions.
+fn sort_order<T: Serialize>(
+    rows: &[T],
+    column: &str,
+    ascending: bool,
+) -> Result<Vec<usize>, String> {
+    let keys: Vec<Option<Value>> = rows
+        .iter()
+        .map(|row| match serde_json::to_value(row) {
+            Ok(Value::Object(mut fields)) => fields.remove(column).filter(|v| !v.is_null()),
+            _ => None,
+        })
+        .collect();
+    if !rows.is_empty() && keys.iter().all(Option::is_none) {
+        return Err(format!("unknown sort column '{}'", column));
+    }
+
+    let mut order: Vec<usize> = (0..rows.len()).collect();
+    order.sort_by(|&a, &b| match (&keys[a], &keys[b]) {
+        (Some(a), Some(b)) => {
+            let ord = compare_values(a, b);
+            if ascending { ord } else { ord.reverse() }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    Ok(order)
+}
+
+/// Numbers compare numerically, everything else by its string form.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => match (a.as_str(), b.as_str()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            _ => a.to_string().cmp(&b.to_string()),
+        },
+    }
+}
+
+/// Serializes the selected part of a snapshot. With a full selection the
+/// output is identical to serializing the [`ApiSnapshot`] itself.
+///
+/// When paginating, a `totals` object holds the full row count of each
+/// selected table.
+pub struct SelectedSnapshot<'a> {
+    snapshot: &'a ApiSnapshot,
+    selection: &'a SnapshotSelection,
+    /// Sorted row indices of tables with a sort column.
+    orders: BTreeMap<&'static str, Vec<usize>>,
+}
+
+impl<'a> SelectedSnapshot<'a> {
+    /// Sorts the tables that have a sort column. Fails on a column that no
+    /// row of the table has.
+    pub fn new(
+        snapshot: &'a ApiSnapshot,
+        selection: &'a SnapshotSelection,
+    ) -> Result<Self, String> {
+        let mut orders = BTreeMap::new();
+        for (&table, params) in &selection.tables {
+            let Some(column) = &params.sort else {
+                continue;
+            };
+            if !selection.wants(table) {
+                continue;
+            }
+            let order = match table {
+                "prc" => sort_order(&snapshot.prc, column, params.ascending),
+                "pga" => sort_order(&snapshot.pga, column, params.ascending),
+                "pgs" => sort_order(&snapshot.pgs, column, params.ascending),
+                "pgt" => sort_order(&snapshot.pgt, column, params.ascending),
+                "pgi" => sort_order(&snapshot.pgi, column, params.ascending),
+                "pge" => sort_order(&snapshot.pge, column, params.ascending),
+                "pgl" => sort_order(&snapshot.pgl, column, params.ascending),
+                "pgp" => sort_order(&snapshot.pgp, column, params.ascending),
+                "pgv" => sort_order(&snapshot.pgv, column, params.ascending),
+                "pgr" => sort_order(&snapshot.pgr, column, params.ascending),
+                "pgw" => sort_order(&snapshot.pgw, column, params.ascending),
+                "custom" => sort_order(&snapshot.custom, column, params.ascending),
+                "pgc" => sort_order(&snapshot.pgc, column, params.ascending),
+                _ => continue,
+            }
+            .map_err(|e| format!("{}: {}", table, e))?;
+            orders.insert(table, order);
+        }
+        Ok(Self {
+            snapshot,
+            selection,
+            orders,
+        })
+    }
+}
+
+impl Serialize for SelectedSnapshot<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let snap = self.snapshot;
+        let sel = self.selection;
+        let mut map = serializer.serialize_map(None)?;
+        let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+
+        macro_rules! field {
+            ($name:ident) => {
+                if sel.wants(stringify!($name)) {
+                    map.serialize_entry(stringify!($name), &snap.$name)?;
+                }
+            };
+        }
+        macro_rules! optional {
+            ($name:ident) => {
+                if sel.wants(stringify!($name))
+                    && let Some(value) = &snap.$name
+                {
+                    map.serialize_entry(stringify!($name), value)?;
+                }
+            };
+        }
+        macro_rules! table {
+            ($name:ident) => {
+                if sel.wants(stringify!($name)) {
+                    let name = stringify!($name);
+                    let order = self.orders.get(name).map(Vec::as_slice);
+                    map.serialize_entry(name, &sel.window(name, &snap.$name, order))?;
+                    totals.insert(stringify!($name), snap.$name.len());
+                }
+            };
+        }
+
+        // Same order as the fields of ApiSnapshot
+        map.serialize_entry("timestamp", &snap.timestamp)?;
+        if let Some(ts) = snap.prev_timestamp {
+            map.serialize_entry("prev_timestamp", &ts)?;
+        }
+        if let Some(ts) = snap.next_timestamp {
+            map.serialize_entry("next_timestamp", &ts)?;
+        }
+        field!(system);
+        field!(pg);
+        table!(prc);
+        table!(pga);
+        table!(pgs);
+        table!(pgt);
+        table!(pgi);
+        table!(pge);
+        table!(pgl);
+        table!(pgp);
+        table!(pgv);
+        table!(pgr);
+        table!(pgw);
+        table!(custom);
+        table!(pgc);
+        field!(health_score);
+        field!(health_breakdown);
+        field!(session_counts);
+        optional!(replication);
+        if !snap.instances.is_empty() {
+            map.serialize_entry("instances", &snap.instances)?;
+        }
+        if let Some(instance) = &snap.instance {
+            map.serialize_entry("instance", instance)?;
+        }
+        optional!(degraded);
+        if sel.paginated() {
+            map.serialize_entry("totals", &totals)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::api::convert::{ConvertContext, convert};
+    use crate::storage::model::{DataBlock, ProcessInfo, Snapshot};
+
+    fn api_snapshot() -> ApiSnapshot {
+        let snapshot = Snapshot {
+            timestamp: 1000,
+            blocks: vec![DataBlock::Processes(
+                (1..=5)
+                    .map(|pid| ProcessInfo {
+                        pid,
+                        ..ProcessInfo::default()
+                    })
+                    .collect(),
+            )],
+        };
+        convert(&ConvertContext {
+            snapshot: &snapshot,
+            prev_snapshot: None,
+            interner: None,
+            pgs_rates: &HashMap::new(),
+            pgp_rates: &HashMap::new(),
+            pgt_rates: &HashMap::new(),
+            pgi_rates: &HashMap::new(),
+        })
+    }
+
+    fn to_value(snapshot: &ApiSnapshot, selection: &SnapshotSelection) -> serde_json::Value {
+        serde_json::to_value(SelectedSnapshot::new(snapshot, selection).unwrap()).unwrap()
+    }
+
+    fn pids(value: &serde_json::Value) -> Vec<u64> {
+        value["prc"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["pid"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn full_selection_matches_api_snapshot() {
+        let snapshot = api_snapshot();
+        let selection = SnapshotSelection::parse(None, None, None).unwrap();
+        assert!(selection.is_full());
+        assert_eq!(
+            serde_json::to_string(&SelectedSnapshot::new(&snapshot, &selection).unwrap()).unwrap(),
+            serde_json::to_string(&snapshot).unwrap()
+        );
+    }
+
+    #[test]
+    fn selects_fields_and_pages_tables() {
+        let snapshot = api_snapshot();
+        let selection = SnapshotSelection::parse(Some("prc,summary"), Some(2), Some(1)).unwrap();
+        let value = to_value(&snapshot, &selection);
+        let object = value.as_object().unwrap();
+
+        assert!(object.contains_key("timestamp"));
+        assert!(object.contains_key("system"));
+        assert!(object.contains_key("health_score"));
+        assert!(!object.contains_key("pgs"));
+        assert_eq!(pids(&value).len(), 2);
+        assert_eq!(value["totals"]["prc"], 5);
+        assert!(value["totals"].get("pgs").is_none());
+
+        // Offset past the end yields an empty page
+        let selection = SnapshotSelection::parse(Some("prc"), None, Some(10)).unwrap();
+        assert_eq!(
+            to_value(&snapshot, &selection)["prc"],
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_field() {
+        assert!(SnapshotSelection::parse(Some("pgs,bogus"), None, None).is_err());
+    }
+
+    #[test]
+    fn sorts_and_pages_per_table() {
+        let snapshot = api_snapshot();
+        let mut selection = SnapshotSelection::parse(Some("prc"), Some(100), None).unwrap();
+        selection
+            .apply_table_params([
+                ("prc_sort", "pid"),
+                ("prc_limit", "3"),
+                ("prc_offset", "1"),
+                ("timestamp", "1000"),
+            ])
+            .unwrap();
+        let value = to_value(&snapshot, &selection);
+        // Descending by default, table limit overrides the global one
+        assert_eq!(pids(&value), vec![4, 3, 2]);
+        assert_eq!(value["totals"]["prc"], 5);
+
+        selection
+            .apply_table_params([("prc_order", "asc"), ("prc_offset", "0")])
+            .unwrap();
+        assert_eq!(pids(&to_value(&snapshot, &selection)), vec![1, 2, 3]);
+
+        selection
+            .apply_table_params([("prc_sort", "no_such_column")])
+            .unwrap();
+        assert!(SelectedSnapshot::new(&snapshot, &selection).is_err());
+
+        assert!(selection.apply_table_params([("pgs_order", "up")]).is_err());
+        assert!(selection.apply_table_params([("pgs_limit", "-1")]).is_err());
+    }
+
+    #[test]
+    fn missing_values_sort_last() {
+        let rows = [
+            serde_json::json!({ "v": 2 }),
+            serde_json::json!({ "v": null }),
+            serde_json::json!({ "v": 5 }),
+            serde_json::json!({}),
+        ];
+        assert_eq!(sort_order(&rows, "v", false).unwrap(), vec![2, 0, 1, 3]);
+        assert_eq!(sort_order(&rows, "v", true).unwrap(), vec![0, 2, 1, 3]);
+        let rows = [
+            serde_json::json!({ "s": "b" }),
+            serde_json::json!({ "s": "a" }),
+        ];
+        assert_eq!(sort_order(&rows, "s", true).unwrap(), vec![1, 0]);
+    }
+}
@@ -0,0 +1,384 @@
+//! pg_settings of a snapshot and configuration changes between snapshots.
+//!
+//! Built from [`DataBlock::PgSettings`], [`DataBlock::PgSettingsMeta`] and
+//! [`DataBlock::PgPendingRestart`]. [`settings_rows`] joins them for the PGC
+//! tab and the `pgc` API section; [`SettingsTracker`] keeps the last observed
+//! value of every GUC and reports what differs in the next observation. The
+//! first observation is the baseline and produces no changes.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::snapshot::PgSettingsRow;
+#[cfg(feature = "api")]
+use crate::provider::HistoryProvider;
+use crate::storage::model::{DataBlock, PgSettingEntry, Snapshot};
+
+/// What happened to a setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingChangeKind {
+    /// The value changed (reload, `ALTER SYSTEM`, restart).
+    Changed,
+    /// The setting appeared, e.g. a library in `shared_preload_libraries`
+    /// registered its GUCs.
+    Added,
+    /// The setting disappeared.
+    Removed,
+    /// The configuration files hold a new value that needs a restart.
+    PendingRestart,
+}
+
+/// One configuration change, found between two consecutive observations.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SettingChange {
+    /// Timestamp of the first snapshot showing the change.
+    pub ts: i64,
+    pub name: String,
+    pub kind: SettingChangeKind,
+    /// Value before the change (absent for `added`). For `pending_restart`
+    /// the value still in effect.
+    pub old_value: Option<String>,
+    /// Value after the change (absent for `removed` and `pending_restart`:
+    /// pg_settings does not expose the pending value).
+    pub new_value: Option<String>,
+    /// Unit of both values as reported by pg_settings (may be empty).
+    pub unit: String,
+}
+
+impl SettingChange {
+    /// One-line description, e.g. `work_mem changed: 4096 -> 65536 kB`.
+    pub fn summary(&self) -> String {
+        let unit = if self.unit.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", self.unit)
+        };
+        let old = self.old_value.as_deref().unwrap_or("");
+        let new = self.new_value.as_deref().unwrap_or("");
+        match self.kind {
+            SettingChangeKind::Changed => {
+                format!("{} changed: {old} -> {new}{unit}", self.name)
+            }
+            SettingChangeKind::Added => format!("{} added: {new}{unit}", self.name),
+            SettingChangeKind::Removed => format!("{} removed (was {old}{unit})", self.name),
+            SettingChangeKind::PendingRestart => {
+                format!("{} pending restart (running {old}{unit})", self.name)
+            }
+        }
+    }
+}
+
+/// Configuration changes over a history range.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct SettingsHistory {
+    pub start_ts: i64,
+    pub end_ts: i64,
+    /// Changes in time order; changes of one snapshot are sorted by name.
+    pub changes: Vec<SettingChange>,
+}
+
+/// Joins the settings of a snapshot with their metadata and pending restart
+/// flags, ordered by category and name. Settings without metadata (older
+/// data) keep empty category and source and count as defaults.
+pub fn settings_rows(snapshot: &Snapshot) -> Vec<PgSettingsRow> {
+    let mut settings: &[PgSettingEntry] = &[];
+    let mut meta = HashMap::new();
+    let mut pending = HashSet::new();
+    for block in &snapshot.blocks {
+        match block {
+            DataBlock::PgSettings(v) => settings = v,
+            DataBlock::PgSettingsMeta(v) => meta = v.iter().map(|m| (m.name.as_str(), m)).collect(),
+            DataBlock::PgPendingRestart(v) => pending = v.iter().map(String::as_str).collect(),
+            _ => {}
+        }
+    }
+    let mut rows: Vec<PgSettingsRow> = settings
+        .iter()
+        .map(|s| {
+            let meta = meta.get(s.name.as_str());
+            PgSettingsRow {
+                name: s.name.clone(),
+                setting: s.setting.clone(),
+                unit: s.unit.clone(),
+                category: meta.map(|m| m.category.clone()).unwrap_or_default(),
+                source: meta.map(|m| m.source.clone()).unwrap_or_default(),
+                boot_val: meta.map(|m| m.boot_val.clone()).unwrap_or_default(),
+                non_default: meta.is_some_and(|m| m.is_non_default()),
+                pending_restart: pending.contains(s.name.as_str()),
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        a.category
+            .cmp(&b.category)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    rows
+}
+
+/// Follows settings across snapshots and collects the differences.
+#[derive(Default)]
+pub struct SettingsTracker {
+    /// name → (setting, unit) of the last observation.
+    last: Option<BTreeMap<String, (String, String)>>,
+    pending: BTreeSet<String>,
+    changes: Vec<SettingChange>,
+}
+
+impl SettingsTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the settings of one snapshot. `pending_restart` is the content
+    /// of the pending restart block (empty when the block is absent).
+    pub fn observe(&mut self, ts: i64, settings: &[PgSettingEntry], pending_restart: &[String]) {
+        if settings.is_empty() {
+            return;
+        }
+        let current: BTreeMap<String, (String, String)> = settings
+            .iter()
+            .map(|s| (s.name.clone(), (s.setting.clone(), s.unit.clone())))
+            .collect();
+        let pending: BTreeSet<String> = pending_restart.iter().cloned().collect();
+
+        if let Some(last) = &self.last {
+            self.changes.extend(diff_settings(ts, last, &current));
+            self.changes.extend(
+                pending
+                    .difference(&self.pending)
+                    .map(|name| match current.get(name) {
+                        Some((value, unit)) => (name, Some(value.clone()), unit.clone()),
+                        None => (name, None, String::new()),
+                    })
+                    .map(|(name, old_value, unit)| SettingChange {
+                        ts,
+                        name: name.clone(),
+                        kind: SettingChangeKind::PendingRestart,
+                        old_value,
+                        new_value: None,
+                        unit,
+                    }),
+            );
+        }
+        self.last = Some(current);
+        self.pending = pending;
+    }
+
+    pub fn finish(self) -> Vec<SettingChange> {
+        self.changes
+    }
+}
+
+/// Changed, added and removed settings between two observations, by name.
+fn diff_settings(
+    ts: i64,
+    old: &BTreeMap<String, (String, String)>,
+    new: &BTreeMap<String, (String, String)>,
+) -> Vec<SettingChange> {
+    let change = |name: &str, kind, old_value, new_value, unit: &str| SettingChange {
+        ts,
+        name: name.to_string(),
+        kind,
+        old_value,
+        new_value,
+        unit: unit.to_string(),
+    };
+    let mut changes: Vec<SettingChange> = new
+        .iter()
+        .filter_map(|(name, (value, unit))| match old.get(name) {
+            Some((old_value, _)) if old_value == value => None,
+            Some((old_value, _)) => Some(change(
+                name,
+                SettingChangeKind::Changed,
+                Some(old_value.clone()),
+                Some(value.clone()),
+                unit,
+            )),
+            None => Some(change(
+                name,
+                SettingChangeKind::Added,
+                None,
+                Some(value.clone()),
+                unit,
+            )),
+        })
+        .collect();
+    changes.extend(old.iter().filter(|(name, _)| !new.contains_key(*name)).map(
+        |(name, (value, unit))| {
+            change(
+                name,
+                SettingChangeKind::Removed,
+                Some(value.clone()),
+                None,
+                unit,
+            )
+        },
+    ));
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    changes
+}
+
+/// Scans the settings of every snapshot in `[start_ts, end_ts]`. The last
+/// snapshot before the range is the baseline, so a change in the first
+/// snapshot of the range is reported too. Only the settings blocks are read.
+#[cfg(feature = "api")]
+pub fn settings_history(
+    provider: &mut HistoryProvider,
+    start_ts: i64,
+    end_ts: i64,
+) -> SettingsHistory {
+    let timestamps = provider.timestamps().to_vec();
+    let start_pos = timestamps.partition_point(|&ts| ts < start_ts);
+    let end_pos = timestamps.partition_point(|&ts| ts <= end_ts);
+
+    let mut tracker = SettingsTracker::new();
+    for (pos, &ts) in timestamps
+        .iter()
+        .enumerate()
+        .take(end_pos)
+        .skip(start_pos.saturating_sub(1))
+    {
+        let Some(snapshot) =
+            provider.snapshot_blocks_at(pos, &["pg_settings", "pg_pending_restart"])
+        else {
+            continue;
+        };
+        let mut settings: &[PgSettingEntry] = &[];
+        let mut pending: &[String] = &[];
+        for block in &snapshot.blocks {
+            match block {
+                DataBlock::PgSettings(v) => settings = v,
+                DataBlock::PgPendingRestart(v) => pending = v,
+                _ => {}
+            }
+        }
+        tracker.observe(ts, settings, pending);
+    }
+
+    SettingsHistory {
+        start_ts,
+        end_ts,
+        changes: tracker.finish(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::PgSettingMeta;
+
+    fn setting(name: &str, value: &str, unit: &str) -> PgSettingEntry {
+        PgSettingEntry {
+            name: name.to_string(),
+            setting: value.to_string(),
+            unit: unit.to_string(),
+        }
+    }
+
+    #[test]
+    fn reports_changes_after_the_baseline() {
+        let mut tracker = SettingsTracker::new();
+        tracker.observe(
+            100,
+            &[
+                setting("jit", "on", ""),
+                setting("old_guc", "1", ""),
+                setting("work_mem", "4096", "kB"),
+            ],
+            &[],
+        );
+        tracker.observe(
+            110,
+            &[
+                setting("jit", "on", ""),
+                setting("old_guc", "1", ""),
+                setting("work_mem", "4096", "kB"),
+            ],
+            &[],
+        );
+        tracker.observe(
+            120,
+            &[
+                setting("jit", "off", ""),
+                setting("new_guc", "x", ""),
+                setting("work_mem", "65536", "kB"),
+            ],
+            &[],
+        );
+
+        let changes = tracker.finish();
+        let summary: Vec<_> = changes.iter().map(|c| (c.ts, c.summary())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (120, "jit changed: on -> off".to_string()),
+                (120, "new_guc added: x".to_string()),
+                (120, "old_guc removed (was 1)".to_string()),
+                (120, "work_mem changed: 4096 -> 65536 kB".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn joins_metadata_and_pending_restart() {
+        let meta = |name: &str, category: &str, source: &str| PgSettingMeta {
+            name: name.to_string(),
+            category: category.to_string(),
+            source: source.to_string(),
+            boot_val: "0".to_string(),
+        };
+        let snapshot = Snapshot {
+            timestamp: 0,
+            blocks: vec![
+                DataBlock::PgSettings(vec![
+                    setting("jit", "on", ""),
+                    setting("shared_buffers", "16384", "8kB"),
+                    setting("work_mem", "65536", "kB"),
+                ]),
+                DataBlock::PgSettingsMeta(vec![
+                    meta("jit", "Query Tuning", "default"),
+                    meta(
+                        "shared_buffers",
+                        "Resource Usage / Memory",
+                        "configuration file",
+                    ),
+                    meta("work_mem", "Resource Usage / Memory", "database"),
+                ]),
+                DataBlock::PgPendingRestart(vec!["shared_buffers".to_string()]),
+            ],
+        };
+
+        let rows = settings_rows(&snapshot);
+        let names: Vec<_> = rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["jit", "shared_buffers", "work_mem"]);
+        assert!(!rows[0].non_default);
+        assert!(rows[1].non_default && rows[1].pending_restart);
+        assert!(rows[2].non_default && !rows[2].pending_restart);
+        assert_eq!(rows[2].category, "Resource Usage / Memory");
+    }
+
+    #[test]
+    fn reports_new_pending_restart_once() {
+        let settings = [setting("shared_buffers", "16384", "8kB")];
+        let pending = ["shared_buffers".to_string()];
+        let mut tracker = SettingsTracker::new();
+        tracker.observe(100, &settings, &[]);
+        tracker.observe(110, &settings, &pending);
+        tracker.observe(120, &settings, &pending);
+        // Missing settings (collector disconnected) keep the last state
+        tracker.observe(130, &[], &[]);
+        tracker.observe(140, &[setting("shared_buffers", "32768", "8kB")], &[]);
+
+        let changes = tracker.finish();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].kind, SettingChangeKind::PendingRestart);
+        assert_eq!(changes[0].ts, 110);
+        assert_eq!(changes[0].old_value.as_deref(), Some("16384"));
+        assert_eq!(changes[1].kind, SettingChangeKind::Changed);
+        assert_eq!(changes[1].ts, 140);
+    }
+}
@@ -6,6 +6,8 @@
 use serde::{Serialize, Serializer};
 use utoipa::ToSchema;
 
+use super::encryption::EncryptionInfo;
+
 /// Serialize i64 as a JSON string to avoid JavaScript Number precision loss for 64-bit values.
 fn i64_as_string<S: Serializer>(val: &i64, s: S) -> Result<S::Ok, S::Error> {
     s.serialize_str(&val.to_string())
@@ -44,6 +46,14 @@ pub struct ApiSnapshot {
     pub pgp: Vec<PgStorePlansRow>,
     /// pg_stat_progress_vacuum — currently running VACUUM operations.
     pub pgv: Vec<PgProgressVacuumRow>,
+    /// pg_stat_activity and pg_stat_statements aggregated by role.
+    pub pgr: Vec<PgWorkloadRow>,
+    /// pg_stat_activity and pg_stat_statements aggregated by application_name.
+    pub pgw: Vec<PgWorkloadRow>,
+    /// Results of user-defined SQL metric queries, one section per query.
+    pub custom: Vec<CustomQuerySection>,
+    /// pg_settings, ordered by category and name.
+    pub pgc: Vec<PgSettingsRow>,
     /// Health score 0..100 (100 = fully healthy).
     pub health_score: u8,
     /// Breakdown of health score penalties by category.
@@ -53,6 +63,49 @@ pub struct ApiSnapshot {
     /// Replication status (primary/standby).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replication: Option<ReplicationInfo>,
+    /// TLS vs plaintext sessions (pg_stat_ssl).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<EncryptionInfo>,
+    /// Names of additional PostgreSQL instances collected by the daemon.
+    /// Absent when only one instance is collected.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub instances: Vec<String>,
+    /// Instance the PostgreSQL data belongs to. Absent for the primary one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Present when the collector skipped expensive collectors under load:
+    /// PGS, PGP, PGT and PGI repeat the last collected data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub degraded: Option<DegradedInfo>,
+    /// Present while the collector cannot connect to PostgreSQL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pg_connection: Option<PgConnectionInfo>,
+}
+
+/// Outage of the collector's PostgreSQL connection.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PgConnectionInfo {
+    /// "reconnecting" or "down".
+    pub state: String,
+    /// Unix time the connection was lost.
+    pub since: i64,
+    /// Consecutive failed connection attempts.
+    pub failures: u32,
+    /// Unix time of the next connection attempt.
+    pub next_attempt: i64,
+    /// Last connection error.
+    pub error: String,
+}
+
+/// Degraded (throttled) collection of the snapshot.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DegradedInfo {
+    /// "slow_collection" or "pressure".
+    pub reason: String,
+    /// Collection time of the snapshot that triggered throttling (ms).
+    pub collection_ms: u32,
+    /// Highest CPU / I/O PSI some avg10 at that time (%).
+    pub psi_some_avg10: f32,
 }
 
 /// Replication status of the PostgreSQL instance.
@@ -70,6 +123,35 @@ pub struct ReplicationInfo {
     /// Primary host address (standby only, from pg_stat_wal_receiver).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sender_host: Option<String>,
+    /// WAL archiver status (only when archive_mode is on).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archiver: Option<ArchiverInfo>,
+}
+
+/// WAL archiver status from pg_stat_archiver.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ArchiverInfo {
+    /// WAL files archived since the last stats reset.
+    pub archived_count: i64,
+    /// Failed archive attempts since the last stats reset.
+    pub failed_count: i64,
+    /// The most recent archive attempt failed.
+    pub failing: bool,
+    /// Last WAL file archived.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_archived_wal: Option<String>,
+    /// Time of the last successful archive (epoch seconds).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_archived_time: Option<i64>,
+    /// WAL file of the last failed attempt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failed_wal: Option<String>,
+    /// Time of the last failed attempt (epoch seconds).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failed_time: Option<i64>,
+    /// Completed WAL segments not archived yet (primary only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_segments: Option<u64>,
 }
 
 /// Detail of a connected streaming replica.
@@ -111,6 +193,10 @@ pub struct SessionCounts {
     pub idle: u32,
     /// Number of sessions in 'idle in transaction' (including aborted).
     pub idle_in_transaction: u32,
+    /// Active sessions waiting for a foreign server (postgres_fdw, dblink).
+    pub fdw_outgoing: u32,
+    /// Sessions opened by postgres_fdw or dblink of another server.
+    pub fdw_incoming: u32,
     /// Total number of sessions.
     pub total: u32,
 }
@@ -181,6 +267,9 @@ pub struct MemorySummary {
     pub buffers_kb: u64,
     /// Slab in KB.
     pub slab_kb: u64,
+    /// PSS sum of PostgreSQL processes in KB (shared_buffers counted once).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pg_pss_kb: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
@@ -336,6 +425,11 @@ pub struct PgSummary {
     pub errors_warning: Option<u32>,
     /// Info-severity errors (lock, constraint, serialization).
     pub errors_info: Option<u32>,
+    /// New connections per second (log_connections or pg_stat_activity backend_start).
+    pub connections_s: Option<f64>,
+    /// Share of disconnected sessions shorter than 1s (0..100).
+    /// Requires log_disconnections.
+    pub short_lived_conn_pct: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
@@ -396,7 +490,7 @@ pub struct ApiProcessRow {
     pub nvcsw_s: Option<f64>,
     /// Involuntary context switches/s.
     pub nivcsw_s: Option<f64>,
-    /// Memory percentage (0..100).
+    /// Memory percentage (0..100), from PSS when sampled, else RSS.
     pub mem_pct: f64,
     /// Virtual memory (KB).
     pub vsize_kb: u64,
@@ -442,6 +536,10 @@ pub struct ApiProcessRow {
     pub total_write_ops: u64,
     /// Cancelled write bytes.
     pub cancelled_write_bytes: u64,
+    /// Mean block I/O latency since the previous snapshot (ms, eBPF collector only).
+    pub blk_lat_avg_ms: Option<f64>,
+    /// p99 block I/O latency, histogram bucket upper bound (ms, eBPF collector only).
+    pub blk_lat_p99_ms: Option<f64>,
     /// Real user ID.
     pub uid: u32,
     /// Effective user ID.
@@ -511,6 +609,10 @@ pub struct PgActivityRow {
     pub stmt_calls_s: Option<f64>,
     /// Buffer hit % from pg_stat_statements.
     pub stmt_hit_pct: Option<f64>,
+    /// Transaction ID assigned to the session's transaction (0 until it writes).
+    pub backend_xid: u32,
+    /// Age in transactions of the session's xmin horizon (0 if no snapshot).
+    pub backend_xmin_age: i32,
 }
 
 /// pg_stat_statements row with pre-computed rates.
@@ -787,6 +889,81 @@ pub struct PgLocksRow {
     pub state_change: f64,
 }
 
+/// Workload row: pg_stat_activity + pg_stat_statements grouped by role or application.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PgWorkloadRow {
+    /// Role name or application_name (empty for sessions without one).
+    pub name: String,
+    /// Backends in this group.
+    pub sessions: u32,
+    pub active: u32,
+    pub idle_in_transaction: u32,
+    /// Backends waiting on a heavyweight lock.
+    pub waiting: u32,
+    /// Sum of running times of active queries (seconds).
+    pub active_time_s: f64,
+    /// Active backends waiting for a foreign server (postgres_fdw, dblink).
+    pub remote_waiting: u32,
+    /// Running time of their queries (seconds), part of `active_time_s`.
+    pub remote_time_s: f64,
+    /// Number of pg_stat_statements entries. For applications, statements are
+    /// attributed via the query_id of their sessions.
+    pub statements: u32,
+    pub calls_s: Option<f64>,
+    pub rows_s: Option<f64>,
+    pub exec_time_ms_s: Option<f64>,
+}
+
+/// Result of a user-defined SQL metric query. Columns describe the rows,
+/// so clients render sections without knowing the queries in advance.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CustomQuerySection {
+    /// Query name from the custom queries file.
+    pub name: String,
+    /// Declared columns in order; `keys` and `values` follow the key and
+    /// value columns of this list respectively.
+    pub columns: Vec<CustomQueryColumn>,
+    pub rows: Vec<CustomQueryResultRow>,
+}
+
+/// Column of a user-defined query.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CustomQueryColumn {
+    pub name: String,
+    /// "key" (row label), "int" or "float".
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// Row of a user-defined query.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CustomQueryResultRow {
+    /// Key column values (empty string for NULL).
+    pub keys: Vec<String>,
+    /// Value column values (null for NULL).
+    pub values: Vec<Option<f64>>,
+}
+
+/// pg_settings row with its category and value source.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PgSettingsRow {
+    pub name: String,
+    /// Value in base units (pg_settings.setting).
+    pub setting: String,
+    /// Unit of `setting` and `boot_val` (may be empty).
+    pub unit: String,
+    /// pg_settings.category; empty when the collector did not record it.
+    pub category: String,
+    /// pg_settings.source (e.g. "default", "configuration file").
+    pub source: String,
+    /// Built-in default (pg_settings.boot_val).
+    pub boot_val: String,
+    /// Set by the configuration rather than a default (see `source`).
+    pub non_default: bool,
+    /// Changed in the configuration files, takes effect after a restart.
+    pub pending_restart: bool,
+}
+
 /// pg_stat_progress_vacuum row (PG 9.6+).
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct PgProgressVacuumRow {
@@ -0,0 +1,435 @@
+//! Top queries, tables and indexes over a history range.
+//!
+//! Counters are summed from the per-interval rates of [`crate::rates`]
+//! (rate × interval), so stats resets, gaps and evicted entries are handled
+//! the way the TUI and the web UI handle them. Names are resolved at the end,
+//! only for the entries that made it into the report.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[cfg(feature = "api")]
+use crate::provider::HistoryProvider;
+use crate::rates::{
+    PgiRateState, PgsRateState, PgtRateState, update_pgi_rates, update_pgs_rates, update_pgt_rates,
+};
+use crate::storage::StringInterner;
+use crate::storage::model::{DataBlock, Snapshot};
+
+/// PostgreSQL block size the block counters are converted with.
+const BLOCK_SIZE: f64 = 8192.0;
+
+/// Block kinds read from each snapshot.
+pub const TOP_CONSUMERS_BLOCKS: &[&str] = &[
+    "pg_stat_statements",
+    "pg_stat_user_tables",
+    "pg_stat_user_indexes",
+];
+
+/// Heaviest consumers over `[start_ts, end_ts]`, each list largest first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct TopConsumersReport {
+    pub start_ts: i64,
+    pub end_ts: i64,
+    /// Seconds of the range covered by pg_stat_statements rates.
+    pub statements_covered_s: f64,
+    /// Queries by total execution time.
+    pub queries_by_time: Vec<QueryTotals>,
+    /// Queries by blocks read from disk or the OS cache and written out.
+    pub queries_by_io: Vec<QueryTotals>,
+    /// Queries by temp blocks read and written.
+    pub queries_by_temp: Vec<QueryTotals>,
+    /// Tables by rows read and modified.
+    pub tables: Vec<TableTotals>,
+    /// Indexes by scans.
+    pub indexes: Vec<IndexTotals>,
+}
+
+/// pg_stat_statements counters of one query summed over the range.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct QueryTotals {
+    pub queryid: i64,
+    pub database: String,
+    pub user: String,
+    pub query: String,
+    pub calls: f64,
+    pub rows: f64,
+    pub exec_time_ms: f64,
+    pub shared_read_bytes: f64,
+    pub shared_written_bytes: f64,
+    pub temp_bytes: f64,
+}
+
+impl QueryTotals {
+    /// Bytes read into and written out of shared buffers.
+    pub fn io_bytes(&self) -> f64 {
+        self.shared_read_bytes + self.shared_written_bytes
+    }
+}
+
+/// pg_stat_user_tables counters of one table summed over the range.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct TableTotals {
+    pub relid: u32,
+    pub database: String,
+    pub schema: String,
+    pub name: String,
+    pub seq_scan: f64,
+    pub seq_tup_read: f64,
+    pub idx_scan: f64,
+    pub idx_tup_fetch: f64,
+    /// Rows inserted, updated and deleted.
+    pub tup_modified: f64,
+    /// Heap, index and TOAST bytes read from disk or the OS cache.
+    pub read_bytes: f64,
+}
+
+impl TableTotals {
+    /// Rows read by scans plus rows modified: the ranking key.
+    pub fn activity(&self) -> f64 {
+        self.seq_tup_read + self.idx_tup_fetch + self.tup_modified
+    }
+}
+
+/// pg_stat_user_indexes counters of one index summed over the range.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct IndexTotals {
+    pub indexrelid: u32,
+    pub database: String,
+    pub schema: String,
+    pub table: String,
+    pub name: String,
+    pub idx_scan: f64,
+    pub idx_tup_read: f64,
+    /// Bytes read from disk or the OS cache.
+    pub read_bytes: f64,
+}
+
+/// Name hashes of an entry and the position of the last snapshot that had it,
+/// whose interner resolves them.
+#[derive(Debug, Clone, Copy, Default)]
+struct Names {
+    pos: usize,
+    hashes: [u64; 4],
+}
+
+/// Accumulates counters snapshot by snapshot; see [`top_consumers`].
+#[derive(Default)]
+pub struct TopConsumers {
+    pgs: PgsRateState,
+    pgt: PgtRateState,
+    pgi: PgiRateState,
+    statements_covered_s: f64,
+    queries: HashMap<i64, (QueryTotals, Names)>,
+    tables: HashMap<u32, (TableTotals, Names)>,
+    indexes: HashMap<u32, (IndexTotals, Names)>,
+}
+
+impl TopConsumers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the interval ending at `snapshot` (the snapshot at `pos`).
+    /// The first snapshot only sets the baseline.
+    pub fn observe(&mut self, pos: usize, snapshot: &Snapshot) {
+        for block in &snapshot.blocks {
+            match block {
+                DataBlock::PgStatStatements(stmts) => {
+                    let prev_ts = self.pgs.prev_ts;
+                    update_pgs_rates(&mut self.pgs, snapshot);
+                    // Statements are cached by the collector: unchanged
+                    // samples keep the previous rates, which are counted once
+                    if self.pgs.prev_ts == prev_ts || self.pgs.rates.is_empty() {
+                        continue;
+                    }
+                    let mut dt = 0.0;
+                    for s in stmts {
+                        let Some(r) = self.pgs.rates.get(&s.queryid) else {
+                            continue;
+                        };
+                        let Some(calls_s) = r.calls_s else {
+                            continue;
+                        };
+                        dt = r.dt_secs;
+                        let (q, names) = self.queries.entry(s.queryid).or_default();
+                        q.queryid = s.queryid;
+                        q.calls += calls_s * dt;
+                        q.rows += r.rows_s.unwrap_or(0.0) * dt;
+                        q.exec_time_ms += r.exec_time_ms_s.unwrap_or(0.0) * dt;
+                        q.shared_read_bytes +=
+                            r.shared_blks_read_s.unwrap_or(0.0) * dt * BLOCK_SIZE;
+                        q.shared_written_bytes +=
+                            r.shared_blks_written_s.unwrap_or(0.0) * dt * BLOCK_SIZE;
+                        q.temp_bytes += (r.temp_blks_read_s.unwrap_or(0.0)
+                            + r.temp_blks_written_s.unwrap_or(0.0))
+                            * dt
+                            * BLOCK_SIZE;
+                        *names = Names {
+                            pos,
+                            hashes: [s.datname_hash, s.usename_hash, s.query_hash, 0],
+                        };
+                    }
+                    self.statements_covered_s += dt;
+                }
+                DataBlock::PgStatUserTables(tables) => {
+                    let prev_ts = self.pgt.prev_ts;
+                    update_pgt_rates(&mut self.pgt, snapshot);
+                    if self.pgt.prev_ts == prev_ts {
+                        continue;
+                    }
+                    for t in tables {
+                        let Some(r) = self.pgt.rates.get(&t.relid) else {
+                            continue;
+                        };
+                        if r.seq_scan_s.is_none() {
+                            continue;
+                        }
+                        let dt = r.dt_secs;
+                        let sum = |v: &[Option<f64>]| v.iter().flatten().sum::<f64>() * dt;
+                        let (e, names) = self.tables.entry(t.relid).or_default();
+                        e.relid = t.relid;
+                        e.seq_scan += sum(&[r.seq_scan_s]);
+                        e.seq_tup_read += sum(&[r.seq_tup_read_s]);
+                        e.idx_scan += sum(&[r.idx_scan_s]);
+                        e.idx_tup_fetch += sum(&[r.idx_tup_fetch_s]);
+                        e.tup_modified += sum(&[r.n_tup_ins_s, r.n_tup_upd_s, r.n_tup_del_s]);
+                        e.read_bytes += sum(&[
+                            r.heap_blks_read_s,
+                            r.idx_blks_read_s,
+                            r.toast_blks_read_s,
+                            r.tidx_blks_read_s,
+                        ]) * BLOCK_SIZE;
+                        *names = Names {
+                            pos,
+                            hashes: [t.datname_hash, t.schemaname_hash, t.relname_hash, 0],
+                        };
+                    }
+                }
+                DataBlock::PgStatUserIndexes(indexes) => {
+                    let prev_ts = self.pgi.prev_ts;
+                    update_pgi_rates(&mut self.pgi, snapshot);
+                    if self.pgi.prev_ts == prev_ts {
+                        continue;
+                    }
+                    for i in indexes {
+                        let Some(r) = self.pgi.rates.get(&i.indexrelid) else {
+                            continue;
+                        };
+                        let Some(idx_scan_s) = r.idx_scan_s else {
+                            continue;
+                        };
+                        let dt = r.dt_secs;
+                        let (e, names) = self.indexes.entry(i.indexrelid).or_default();
+                        e.indexrelid = i.indexrelid;
+                        e.idx_scan += idx_scan_s * dt;
+                        e.idx_tup_read += r.idx_tup_read_s.unwrap_or(0.0) * dt;
+                        e.read_bytes += r.idx_blks_read_s.unwrap_or(0.0) * dt * BLOCK_SIZE;
+                        *names = Names {
+                            pos,
+                            hashes: [
+                                i.datname_hash,
+                                i.schemaname_hash,
+                                i.relname_hash,
+                                i.indexrelname_hash,
+                            ],
+                        };
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Keeps the `top` entries of each list and resolves their names with
+    /// `interner_at(pos)`, called once per distinct snapshot position.
+    pub fn finish(
+        self,
+        start_ts: i64,
+        end_ts: i64,
+        top: usize,
+        mut interner_at: impl FnMut(usize) -> Option<StringInterner>,
+    ) -> TopConsumersReport {
+        let queries: Vec<_> = self.queries.into_values().collect();
+        let mut queries_by_time = largest(&queries, top, |q| q.exec_time_ms);
+        let mut queries_by_io = largest(&queries, top, QueryTotals::io_bytes);
+        let mut queries_by_temp = largest(&queries, top, |q| q.temp_bytes);
+        let tables: Vec<_> = self.tables.into_values().collect();
+        let mut tables = largest(&tables, top, TableTotals::activity);
+        let indexes: Vec<_> = self.indexes.into_values().collect();
+        let mut indexes = largest(&indexes, top, |i| i.idx_scan);
+
+        let mut interners: HashMap<usize, Option<StringInterner>> = HashMap::new();
+        let mut names = |n: &Names| -> [String; 4] {
+            let interner = interners.entry(n.pos).or_insert_with(|| interner_at(n.pos));
+            n.hashes.map(|h| {
+                interner
+                    .as_ref()
+                    .and_then(|i| i.resolve(h))
+                    .unwrap_or_default()
+                    .to_string()
+            })
+        };
+        for (q, n) in queries_by_time
+            .iter_mut()
+            .chain(&mut queries_by_io)
+            .chain(&mut queries_by_temp)
+        {
+            [q.database, q.user, q.query, _] = names(n);
+        }
+        for (t, n) in &mut tables {
+            [t.database, t.schema, t.name, _] = names(n);
+        }
+        for (i, n) in &mut indexes {
+            [i.database, i.schema, i.table, i.name] = names(n);
+        }
+
+        TopConsumersReport {
+            start_ts,
+            end_ts,
+            statements_covered_s: self.statements_covered_s,
+            queries_by_time: strip(queries_by_time),
+            queries_by_io: strip(queries_by_io),
+            queries_by_temp: strip(queries_by_temp),
+            tables: strip(tables),
+            indexes: strip(indexes),
+        }
+    }
+}
+
+fn strip<T>(entries: Vec<(T, Names)>) -> Vec<T> {
+    entries.into_iter().map(|(e, _)| e).collect()
+}
+
+/// The `n` entries with the largest positive `key`, largest first.
+fn largest<T: Clone>(entries: &[(T, Names)], n: usize, key: impl Fn(&T) -> f64) -> Vec<(T, Names)> {
+    let mut sorted: Vec<_> = entries.iter().filter(|(e, _)| key(e) > 0.0).collect();
+    sorted.sort_by(|a, b| key(&b.0).total_cmp(&key(&a.0)));
+    sorted.into_iter().take(n).cloned().collect()
+}
+
+/// Top `top` queries, tables and indexes over `[start_ts, end_ts]`. Only the
+/// statement and table/index statistics blocks are read; the snapshot
+/// before the range is the baseline of the first interval.
+#[cfg(feature = "api")]
+pub fn top_consumers(
+    provider: &mut HistoryProvider,
+    start_ts: i64,
+    end_ts: i64,
+    top: usize,
+) -> TopConsumersReport {
+    let timestamps = provider.timestamps().to_vec();
+    let start_pos = timestamps.partition_point(|&ts| ts < start_ts);
+    let end_pos = timestamps.partition_point(|&ts| ts <= end_ts);
+
+    let mut acc = TopConsumers::new();
+    for pos in start_pos.saturating_sub(1)..end_pos {
+        if let Some(snapshot) = provider.snapshot_blocks_at(pos, TOP_CONSUMERS_BLOCKS) {
+            acc.observe(pos, &snapshot);
+        }
+    }
+    acc.finish(start_ts, end_ts, top, |pos| provider.interner_at(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::{PgStatStatementsInfo, PgStatUserIndexesInfo};
+
+    fn stmt(
+        interner: &mut StringInterner,
+        queryid: i64,
+        calls: i64,
+        time_ms: f64,
+        temp_blks: i64,
+        ts: i64,
+    ) -> PgStatStatementsInfo {
+        PgStatStatementsInfo {
+            queryid,
+            datname_hash: interner.intern("app"),
+            usename_hash: interner.intern("web"),
+            query_hash: interner.intern(&format!("SELECT {queryid}")),
+            calls,
+            total_exec_time: time_ms,
+            temp_blks_written: temp_blks,
+            collected_at: ts,
+            ..Default::default()
+        }
+    }
+
+    fn snapshot(ts: i64, blocks: Vec<DataBlock>) -> Snapshot {
+        Snapshot {
+            timestamp: ts,
+            blocks,
+        }
+    }
+
+    #[test]
+    fn sums_rates_over_the_range_once_per_sample() {
+        let mut interner = StringInterner::new();
+        let mut acc = TopConsumers::new();
+        let samples = [
+            (1000, vec![(1, 10, 100.0, 0), (2, 5, 10.0, 0)]),
+            // The collector cache returned the same sample: not counted again
+            (1000, vec![(1, 10, 100.0, 0), (2, 5, 10.0, 0)]),
+            (1030, vec![(1, 40, 400.0, 0), (2, 6, 2010.0, 64)]),
+            (1060, vec![(1, 70, 700.0, 0), (2, 7, 4010.0, 128)]),
+        ];
+        for (pos, (ts, stmts)) in samples.into_iter().enumerate() {
+            let stmts = stmts
+                .into_iter()
+                .map(|(id, calls, time, temp)| stmt(&mut interner, id, calls, time, temp, ts))
+                .collect();
+            acc.observe(
+                pos,
+                &snapshot(ts + pos as i64, vec![DataBlock::PgStatStatements(stmts)]),
+            );
+        }
+
+        let report = acc.finish(1000, 1060, 1, |_| Some(interner.clone()));
+        assert_eq!(report.statements_covered_s, 60.0);
+        assert_eq!(report.queries_by_time.len(), 1);
+        let top = &report.queries_by_time[0];
+        assert_eq!(top.queryid, 2);
+        assert_eq!(top.query, "SELECT 2");
+        assert_eq!(top.database, "app");
+        assert!((top.exec_time_ms - 4000.0).abs() < 1e-6);
+        assert!((top.calls - 2.0).abs() < 1e-6);
+        assert_eq!(report.queries_by_temp[0].queryid, 2);
+        assert!((report.queries_by_temp[0].temp_bytes - 128.0 * BLOCK_SIZE).abs() < 1e-6);
+        // No query read or wrote shared blocks
+        assert!(report.queries_by_io.is_empty());
+    }
+
+    #[test]
+    fn stats_reset_starts_a_new_baseline() {
+        let mut interner = StringInterner::new();
+        let mut acc = TopConsumers::new();
+        let index = |scans: i64, ts: i64, interner: &mut StringInterner| {
+            DataBlock::PgStatUserIndexes(vec![PgStatUserIndexesInfo {
+                indexrelid: 7,
+                relname_hash: interner.intern("orders"),
+                indexrelname_hash: interner.intern("orders_pkey"),
+                idx_scan: scans,
+                collected_at: ts,
+                ..Default::default()
+            }])
+        };
+        for (pos, (ts, scans)) in [(1000, 100), (1010, 150), (1020, 5), (1030, 25)]
+            .into_iter()
+            .enumerate()
+        {
+            let block = index(scans, ts, &mut interner);
+            acc.observe(pos, &snapshot(ts, vec![block]));
+        }
+
+        let report = acc.finish(1000, 1030, 20, |_| Some(interner.clone()));
+        assert_eq!(report.indexes.len(), 1);
+        assert_eq!(report.indexes[0].name, "orders_pkey");
+        assert_eq!(report.indexes[0].table, "orders");
+        // 50 before the reset, 20 after it; the drop itself is not counted
+        assert!((report.indexes[0].idx_scan - 70.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,166 @@
+//! Stacked time series of active sessions by `application_name`.
+//!
+//! Built from history snapshots for a time range, bucketed like the heatmap.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::provider::HistoryProvider;
+use crate::workload::active_sessions_by_application;
+
+/// Series name for applications outside the top N.
+pub const OTHER_APPLICATIONS: &str = "(other)";
+
+/// Active sessions by application over a time range.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApplicationSeries {
+    /// Bucket start timestamps (epoch seconds).
+    pub timestamps: Vec<i64>,
+    /// One series per application, largest first; the rest are summed into `(other)`.
+    pub series: Vec<ApplicationSeriesLine>,
+}
+
+/// Mean active sessions of one application per bucket.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApplicationSeriesLine {
+    /// application_name (empty for sessions without one).
+    pub application: String,
+    /// Mean active sessions per bucket, aligned with `timestamps`.
+    pub active: Vec<f64>,
+}
+
+/// Loads every snapshot in `[start_ts, end_ts]` and buckets active sessions
+/// by application.
+pub fn application_series(
+    provider: &mut HistoryProvider,
+    start_ts: i64,
+    end_ts: i64,
+    num_buckets: usize,
+    top: usize,
+) -> ApplicationSeries {
+    let timestamps = provider.timestamps().to_vec();
+    let start_pos = timestamps.partition_point(|&ts| ts < start_ts);
+    let end_pos = timestamps.partition_point(|&ts| ts <= end_ts);
+
+    let samples: Vec<(i64, HashMap<String, u32>)> = (start_pos..end_pos)
+        .filter_map(|pos| provider.snapshot_with_interner_at(pos))
+        .map(|(snapshot, interner)| {
+            (
+                snapshot.timestamp,
+                active_sessions_by_application(&snapshot, Some(&interner)),
+            )
+        })
+        .collect();
+
+    bucket_application_series(&samples, start_ts, end_ts, num_buckets, top)
+}
+
+/// Averages per-snapshot active session counts into `num_buckets` buckets
+/// and keeps the `top` applications by total, summing the rest.
+pub fn bucket_application_series(
+    samples: &[(i64, HashMap<String, u32>)],
+    start_ts: i64,
+    end_ts: i64,
+    num_buckets: usize,
+    top: usize,
+) -> ApplicationSeries {
+    if samples.is_empty() || num_buckets == 0 || end_ts <= start_ts {
+        return ApplicationSeries {
+            timestamps: Vec::new(),
+            series: Vec::new(),
+        };
+    }
+
+    let range = (end_ts - start_ts) as f64;
+    let timestamps: Vec<i64> = (0..num_buckets)
+        .map(|i| start_ts + (range * i as f64 / num_buckets as f64) as i64)
+        .collect();
+
+    let mut sample_counts = vec![0u32; num_buckets];
+    let mut sums: HashMap<&str, Vec<f64>> = HashMap::new();
+    for (ts, counts) in samples {
+        let idx = ((ts - start_ts) as f64 / range * num_buckets as f64) as usize;
+        let idx = idx.min(num_buckets - 1);
+        sample_counts[idx] += 1;
+        for (app, &n) in counts {
+            sums.entry(app.as_str())
+                .or_insert_with(|| vec![0.0; num_buckets])[idx] += n as f64;
+        }
+    }
+
+    let mut lines: Vec<ApplicationSeriesLine> = sums
+        .into_iter()
+        .map(|(app, values)| ApplicationSeriesLine {
+            application: app.to_string(),
+            active: values
+                .iter()
+                .zip(&sample_counts)
+                .map(|(&sum, &n)| if n > 0 { sum / n as f64 } else { 0.0 })
+                .collect(),
+        })
+        .collect();
+    let total = |l: &ApplicationSeriesLine| l.active.iter().sum::<f64>();
+    lines.sort_by(|a, b| {
+        total(b)
+            .total_cmp(&total(a))
+            .then_with(|| a.application.cmp(&b.application))
+    });
+
+    if lines.len() > top {
+        let rest = lines.split_off(top);
+        let mut other = vec![0.0; num_buckets];
+        for line in rest {
+            for (o, v) in other.iter_mut().zip(line.active) {
+                *o += v;
+            }
+        }
+        lines.push(ApplicationSeriesLine {
+            application: OTHER_APPLICATIONS.to_string(),
+            active: other,
+        });
+    }
+
+    ApplicationSeries {
+        timestamps,
+        series: lines,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_averages_and_groups_other() {
+        let sample = |ts: i64, apps: &[(&str, u32)]| {
+            (
+                ts,
+                apps.iter()
+                    .map(|&(a, n)| (a.to_string(), n))
+                    .collect::<HashMap<_, _>>(),
+            )
+        };
+        let samples = vec![
+            sample(0, &[("web", 4), ("batch", 1)]),
+            sample(5, &[("web", 2)]),
+            sample(10, &[("web", 1), ("batch", 3), ("psql", 1)]),
+        ];
+
+        let s = bucket_application_series(&samples, 0, 20, 2, 2);
+        assert_eq!(s.timestamps, [0, 10]);
+        let names: Vec<&str> = s.series.iter().map(|l| l.application.as_str()).collect();
+        assert_eq!(names, ["web", "batch", OTHER_APPLICATIONS]);
+        // Bucket 0 has two snapshots: web (4 + 2) / 2, batch 1 / 2
+        assert_eq!(s.series[0].active, [3.0, 1.0]);
+        assert_eq!(s.series[1].active, [0.5, 3.0]);
+        assert_eq!(s.series[2].active, [0.0, 1.0]);
+
+        assert!(
+            bucket_application_series(&[], 0, 20, 2, 2)
+                .series
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Read-only access to recorded rpglot data for other Rust tools.
+//!
+//! A thin, stable facade over [`HistoryProvider`], [`convert`] and
+//! [`Analyzer`]: open a storage directory, then read converted snapshots,
+//! instance-wide metric series or an analysis report for a time range.
+//!
+//! ```no_run
+//! let mut data = rpglot_core::client::open("/var/lib/rpglot")?;
+//! let (first, last) = data.time_range();
+//! for snapshot in data.snapshots(last - 600..=last) {
+//!     println!("{} sessions: {}", snapshot.timestamp, snapshot.session_counts.total);
+//! }
+//! let cpu = data.series("cpu_pct", first..=last);
+//! let report = data.analyze(..);
+//! # Ok::<(), rpglot_core::provider::ProviderError>(())
+//! ```
+//!
+//! Ranges are epoch seconds, inclusive or open (`..`). Everything here is
+//! read-only; the storage directory may be written by a running rpglotd.
+
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+
+use crate::analysis::{AnalysisReport, Analyzer};
+use crate::api::convert::{ConvertContext, convert};
+use crate::api::grafana::GRAFANA_METRICS;
+use crate::api::snapshot::ApiSnapshot;
+use crate::provider::{HistoryProvider, ProviderError};
+use crate::rates::{self, PgiRateState, PgpRateState, PgsRateState, PgtRateState};
+use crate::storage::Snapshot;
+use crate::storage::annotations::AnnotationStore;
+
+/// Opens the storage directory `dir` (chunks, heatmaps and wal.log written
+/// by rpglotd). Fails if it holds no snapshots.
+pub fn open(dir: impl AsRef<Path>) -> Result<Client, ProviderError> {
+    let dir = dir.as_ref();
+    let mut provider = HistoryProvider::from_path_lazy(dir)?;
+    provider.ensure_initialized()?;
+    let annotations = AnnotationStore::open(dir).ok();
+    Ok(Client {
+        provider,
+        annotations,
+    })
+}
+
+/// Metric names accepted by [`Client::series`].
+pub fn metrics() -> impl Iterator<Item = &'static str> {
+    GRAFANA_METRICS.iter().map(|&(name, _)| name)
+}
+
+/// An opened storage directory.
+pub struct Client {
+    provider: HistoryProvider,
+    annotations: Option<AnnotationStore>,
+}
+
+impl Client {
+    /// Timestamps of all stored snapshots, ascending.
+    pub fn timestamps(&self) -> &[i64] {
+        self.provider.timestamps()
+    }
+
+    /// First and last stored timestamp.
+    pub fn time_range(&self) -> (i64, i64) {
+        self.provider.timestamp_range()
+    }
+
+    /// Picks up chunks and WAL entries written since opening. Returns the
+    /// number of new snapshots.
+    pub fn refresh(&mut self) -> Result<usize, ProviderError> {
+        let path = self.provider.storage_path().to_path_buf();
+        self.provider.refresh(path)
+    }
+
+    /// Snapshots in `range` converted to [`ApiSnapshot`]: strings resolved,
+    /// rates and deltas computed against the preceding snapshot (also the
+    /// one just before the range). Snapshots are decoded one at a time.
+    pub fn snapshots(&mut self, range: impl RangeBounds<i64>) -> Snapshots<'_> {
+        let (start, end) = self.positions(range);
+        let mut iter = Snapshots {
+            provider: &mut self.provider,
+            pos: start,
+            end,
+            prev: None,
+            pgs: PgsRateState::default(),
+            pgp: PgpRateState::default(),
+            pgt: PgtRateState::default(),
+            pgi: PgiRateState::default(),
+        };
+        if start > 0
+            && start < end
+            && let Some(prev) = iter.provider.snapshot_at(start - 1)
+        {
+            iter.update_rates(&prev);
+            iter.prev = Some(prev);
+        }
+        iter
+    }
+
+    /// `(timestamp, value)` points of an instance-wide metric in `range`,
+    /// read from the heatmaps (see [`metrics`]). `None` for an unknown metric.
+    pub fn series(
+        &mut self,
+        metric: &str,
+        range: impl RangeBounds<i64>,
+    ) -> Option<Vec<(i64, f64)>> {
+        let &(_, extract) = GRAFANA_METRICS.iter().find(|(name, _)| *name == metric)?;
+        let (start, end) = bounds(range, self.time_range());
+        Some(
+            self.provider
+                .load_heatmap_range(start, end)
+                .iter()
+                .map(|(ts, entry)| (*ts, extract(entry)))
+                .collect(),
+        )
+    }
+
+    /// Runs the anomaly analysis over `range`, with the directory's
+    /// annotations as context.
+    pub fn analyze(&mut self, range: impl RangeBounds<i64>) -> AnalysisReport {
+        let (start, end) = bounds(range, self.time_range());
+        let annotations = self
+            .annotations
+            .as_ref()
+            .map_or(&[][..], |store| store.in_range(start, end));
+        Analyzer::new().analyze(&mut self.provider, start, end, annotations)
+    }
+
+    /// Snapshot positions `start..end` covered by `range`.
+    fn positions(&self, range: impl RangeBounds<i64>) -> (usize, usize) {
+        let (start, end) = bounds(range, self.time_range());
+        let timestamps = self.provider.timestamps();
+        (
+            timestamps.partition_point(|&ts| ts < start),
+            timestamps.partition_point(|&ts| ts <= end),
+        )
+    }
+}
+
+/// Inclusive `(start, end)` timestamps of `range`; open ends are the
+/// stored `(first, last)`.
+fn bounds(range: impl RangeBounds<i64>, (first, last): (i64, i64)) -> (i64, i64) {
+    let start = match range.start_bound() {
+        Bound::Included(&ts) => ts,
+        Bound::Excluded(&ts) => ts.saturating_add(1),
+        Bound::Unbounded => first,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&ts) => ts,
+        Bound::Excluded(&ts) => ts.saturating_sub(1),
+        Bound::Unbounded => last,
+    };
+    (start, end)
+}
+
+/// Iterator over converted snapshots, see [`Client::snapshots`].
+pub struct Snapshots<'a> {
+    provider: &'a mut HistoryProvider,
+    pos: usize,
+    end: usize,
+    prev: Option<Snapshot>,
+    pgs: PgsRateState,
+    pgp: PgpRateState,
+    pgt: PgtRateState,
+    pgi: PgiRateState,
+}
+
+impl Snapshots<'_> {
+    fn update_rates(&mut self, snapshot: &Snapshot) {
+        rates::update_pgs_rates(&mut self.pgs, snapshot);
+        rates::update_pgp_rates(&mut self.pgp, snapshot);
+        rates::update_pgt_rates(&mut self.pgt, snapshot);
+        rates::update_pgi_rates(&mut self.pgi, snapshot);
+    }
+}
+
+impl Iterator for Snapshots<'_> {
+    type Item = ApiSnapshot;
+
+    fn next(&mut self) -> Option<ApiSnapshot> {
+        while self.pos < self.end {
+            let pos = self.pos;
+            self.pos += 1;
+            // Unreadable snapshots (damaged chunk) are skipped
+            let Some((snapshot, interner)) = self.provider.snapshot_with_interner_at(pos) else {
+                continue;
+            };
+            self.update_rates(&snapshot);
+            let mut api = convert(&ConvertContext {
+                snapshot: &snapshot,
+                prev_snapshot: self.prev.as_ref(),
+                interner: Some(&interner),
+                pgs_rates: &self.pgs.rates,
+                pgp_rates: &self.pgp.rates,
+                pgt_rates: &self.pgt.rates,
+                pgi_rates: &self.pgi.rates,
+            });
+            let timestamps = self.provider.timestamps();
+            api.prev_timestamp = pos.checked_sub(1).map(|i| timestamps[i]);
+            api.next_timestamp = timestamps.get(pos + 1).copied();
+            self.prev = Some(snapshot);
+            return Some(api);
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.pos))
+    }
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("storage_path", &self.provider.storage_path())
+            .field("snapshots", &self.provider.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::StringInterner;
+    use crate::storage::chunk::write_chunk;
+    use crate::storage::model::{DataBlock, ProcessInfo};
+    use tempfile::tempdir;
+
+    fn storage() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        let mut interner = StringInterner::new();
+        let name_hash = interner.intern("postgres");
+        let snapshots: Vec<Snapshot> = (0..5)
+            .map(|i| Snapshot {
+                timestamp: 1000 + i * 10,
+                blocks: vec![DataBlock::Processes(vec![ProcessInfo {
+                    pid: 42,
+                    name_hash,
+                    ..Default::default()
+                }])],
+            })
+            .collect();
+        write_chunk(&dir.path().join("rpglot_1000.zst"), &snapshots, &interner).unwrap();
+        dir
+    }
+
+    #[test]
+    fn snapshots_in_range() {
+        let dir = storage();
+        let mut client = open(dir.path()).unwrap();
+        assert_eq!(client.time_range(), (1000, 1040));
+
+        let snapshots: Vec<ApiSnapshot> = client.snapshots(1010..=1030).collect();
+        let timestamps: Vec<i64> = snapshots.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, [1010, 1020, 1030]);
+        assert_eq!(snapshots[0].prev_timestamp, Some(1000));
+        assert_eq!(snapshots[2].next_timestamp, Some(1040));
+        assert_eq!(snapshots[1].prc[0].name, "postgres");
+
+        assert_eq!(client.snapshots(1010..1030).count(), 2);
+        assert_eq!(client.snapshots(..).count(), 5);
+        assert_eq!(client.snapshots(2000..).count(), 0);
+    }
+
+    #[test]
+    fn series_and_analysis() {
+        let dir = storage();
+        let mut client = open(dir.path()).unwrap();
+        assert!(metrics().any(|m| m == "cpu_pct"));
+        assert!(client.series("nope", ..).is_none());
+        let series = client.series("active_sessions", ..=1020).unwrap();
+        assert_eq!(
+            series.iter().map(|(ts, _)| *ts).collect::<Vec<_>>(),
+            [1000, 1010, 1020]
+        );
+
+        let report = client.analyze(..);
+        assert_eq!(report.snapshots_analyzed, 5);
+    }
+
+    #[test]
+    fn open_fails_without_data() {
+        let dir = tempdir().unwrap();
+        assert!(open(dir.path()).is_err());
+    }
+}
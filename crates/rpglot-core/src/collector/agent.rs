@@ -0,0 +1,153 @@
+//! Resource usage of the collecting process itself.
+//!
+//! rpglotd runs next to the database it watches, so its own CPU, memory and
+//! collection cost are recorded in every snapshot: a slow or growing daemon
+//! is visible in the same history as the load it observes.
+
+use std::time::{Duration, Instant};
+
+use crate::storage::model::{AgentPhaseTiming, AgentStatsInfo};
+
+use super::CollectorTiming;
+
+/// Clock ticks per second (USER_HZ), as in `/proc/[pid]/stat`.
+const CLK_TCK: u64 = 100;
+
+/// Parses `utime` and `stime` (clock ticks) from `/proc/[pid]/stat` content.
+///
+/// Fields are counted after the closing parenthesis of `comm`, which may
+/// itself contain spaces and parentheses.
+pub fn parse_self_stat(content: &str) -> Option<(u64, u64)> {
+    let rest = &content[content.rfind(')')? + 1..];
+    // rest starts at field 3 (state); utime and stime are fields 14 and 15
+    let mut fields = rest.split_whitespace().skip(11);
+    let utime = fields.next()?.parse().ok()?;
+    let stime = fields.next()?.parse().ok()?;
+    Some((utime, stime))
+}
+
+/// Parses `VmRSS` and `VmHWM` (KiB) from `/proc/[pid]/status` content.
+/// Missing fields are 0.
+pub fn parse_vm_rss(content: &str) -> (u64, u64) {
+    let field = |name: &str| {
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|v| v.split_whitespace().next())
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+    };
+    (field("VmRSS:"), field("VmHWM:"))
+}
+
+/// Samples the resource usage of the current process.
+#[derive(Debug, Default)]
+pub struct AgentMonitor {
+    /// Time and CPU ticks (user, system) of the previous sample.
+    prev: Option<(Instant, u64, u64)>,
+}
+
+impl AgentMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes a sample. `timing` is the collection that produced the current
+    /// snapshot, `storage_write` the time the previous snapshot took to store.
+    pub fn sample(&mut self, timing: &CollectorTiming, storage_write: Duration) -> AgentStatsInfo {
+        let now = Instant::now();
+        let (utime, stime) = std::fs::read_to_string("/proc/self/stat")
+            .ok()
+            .and_then(|s| parse_self_stat(&s))
+            .unwrap_or_default();
+        let (rss_kb, rss_peak_kb) = std::fs::read_to_string("/proc/self/status")
+            .map(|s| parse_vm_rss(&s))
+            .unwrap_or_default();
+
+        let ticks_ms = |ticks: u64| (ticks * 1000 / CLK_TCK).min(u32::MAX as u64) as u32;
+        let (interval_ms, cpu_user_ms, cpu_system_ms) = match self.prev {
+            Some((at, prev_utime, prev_stime)) => (
+                now.duration_since(at).as_millis().min(u32::MAX as u128) as u32,
+                ticks_ms(utime.saturating_sub(prev_utime)),
+                ticks_ms(stime.saturating_sub(prev_stime)),
+            ),
+            None => (0, 0, 0),
+        };
+        self.prev = Some((now, utime, stime));
+
+        AgentStatsInfo {
+            pid: std::process::id(),
+            interval_ms,
+            cpu_user_ms,
+            cpu_system_ms,
+            rss_kb,
+            rss_peak_kb,
+            collect_us: timing.total.as_micros() as u64,
+            phases: timing
+                .phases()
+                .into_iter()
+                .filter(|(_, d)| !d.is_zero())
+                .map(|(name, d)| AgentPhaseTiming {
+                    name: name.to_string(),
+                    us: d.as_micros() as u64,
+                })
+                .collect(),
+            storage_write_us: storage_write.as_micros() as u64,
+            queries: timing
+                .pg_queries
+                .iter()
+                .map(|(name, d)| AgentPhaseTiming {
+                    name: name.to_string(),
+                    us: d.as_micros() as u64,
+                })
+                .collect(),
+            warnings: timing.warnings.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_self_stat() {
+        let stat = "4242 (rpglotd (w) x) S 1 4242 4242 0 -1 4194560 9120 0 0 0 \
+                    153 47 0 0 20 0 5 0 1234 123456789 4321 18446744073709551615";
+        assert_eq!(parse_self_stat(stat), Some((153, 47)));
+        assert_eq!(parse_self_stat("4242 (rpglotd) S 1"), None);
+        assert_eq!(parse_self_stat(""), None);
+    }
+
+    #[test]
+    fn parses_vm_rss() {
+        let status = "Name:\trpglotd\nVmPeak:\t  812345 kB\nVmHWM:\t   40960 kB\n\
+                      VmRSS:\t   38912 kB\nThreads:\t5\n";
+        assert_eq!(parse_vm_rss(status), (38912, 40960));
+        assert_eq!(parse_vm_rss("Name:\tkthreadd\n"), (0, 0));
+    }
+
+    #[test]
+    fn sample_reports_nonzero_phases_and_cpu_delta() {
+        let mut monitor = AgentMonitor::new();
+        let timing = CollectorTiming {
+            total: Duration::from_millis(12),
+            processes: Duration::from_millis(7),
+            pg_activity: Duration::from_millis(3),
+            pg_queries: vec![("pg_stat_user_tables", Duration::from_millis(900))],
+            warnings: vec!["pg_locks: timed out 3 times in a row, paused for 300s".to_string()],
+            ..CollectorTiming::default()
+        };
+        let first = monitor.sample(&timing, Duration::from_micros(800));
+        assert_eq!(first.interval_ms, 0);
+        assert_eq!(first.cpu_pct(), None);
+        assert_eq!(first.collect_us, 12_000);
+        assert_eq!(first.storage_write_us, 800);
+        let names: Vec<&str> = first.phases.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["processes", "pg_activity"]);
+        assert_eq!(first.pid, std::process::id());
+        assert_eq!(first.queries[0].name, "pg_stat_user_tables");
+        assert_eq!(first.queries[0].us, 900_000);
+        assert_eq!(first.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,158 @@
+//! Per-process block I/O latency collector (eBPF).
+//!
+//! The kernel side (`crates/rpglot-ebpf`) attaches to the
+//! `block:block_rq_issue` and `block:block_rq_complete` tracepoints and
+//! accumulates a log2 latency histogram per process in the `BLK_LAT` hash map.
+//! Each snapshot drains the map, so values cover the interval since the
+//! previous snapshot and exited processes do not pile up.
+//!
+//! Requests are attributed to the process that issued them to the device.
+//! Writes flushed by kernel threads (writeback, jbd2) show up under those
+//! threads, not under the PostgreSQL backend that dirtied the pages.
+//!
+//! Loading the object requires the `ebpf` feature, Linux and `CAP_BPF` +
+//! `CAP_PERFMON` (or root).
+
+use crate::storage::model::ProcessBlkLatInfo;
+
+/// Number of histogram slots; the last slot covers `[2^31, ∞)` microseconds.
+pub const BLK_LAT_SLOTS: usize = 32;
+
+/// Name of the per-process histogram map in the BPF object.
+pub const BLK_LAT_MAP: &str = "BLK_LAT";
+
+/// Raw `BLK_LAT` map value, laid out exactly as in the BPF program.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct BlkLatHist {
+    /// Completed requests.
+    pub count: u64,
+    /// Sum of latencies (nanoseconds).
+    pub total_ns: u64,
+    /// Log2 histogram of latencies in microseconds.
+    pub slots: [u64; BLK_LAT_SLOTS],
+}
+
+impl BlkLatHist {
+    /// Converts the raw map value into the stored representation.
+    pub fn to_info(&self) -> ProcessBlkLatInfo {
+        let used = self.slots.iter().rposition(|&n| n > 0).map_or(0, |i| i + 1);
+        ProcessBlkLatInfo {
+            count: self.count,
+            total_us: self.total_ns / 1000,
+            slots: self.slots[..used].to_vec(),
+        }
+    }
+}
+
+#[cfg(all(feature = "ebpf", target_os = "linux"))]
+pub use self::loader::BlkLatencyCollector;
+
+#[cfg(all(feature = "ebpf", target_os = "linux"))]
+mod loader {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use aya::Ebpf;
+    use aya::maps::{HashMap as BpfHashMap, MapData};
+    use aya::programs::TracePoint;
+    use tracing::warn;
+
+    use super::{BLK_LAT_MAP, BlkLatHist};
+    use crate::storage::model::ProcessBlkLatInfo;
+
+    // SAFETY: `BlkLatHist` is `#[repr(C)]`, `Copy` and consists of plain integers.
+    unsafe impl aya::Pod for BlkLatHist {}
+
+    /// Tracepoints attached by the collector (category, name = program name).
+    const TRACEPOINTS: &[(&str, &str)] =
+        &[("block", "block_rq_issue"), ("block", "block_rq_complete")];
+
+    /// Loaded and attached block latency BPF programs.
+    pub struct BlkLatencyCollector {
+        // Owns the programs; dropping it detaches them.
+        _ebpf: Ebpf,
+        hist: BpfHashMap<MapData, u32, BlkLatHist>,
+    }
+
+    impl BlkLatencyCollector {
+        /// Loads the BPF object from `path` and attaches its tracepoints.
+        pub fn load(path: &Path) -> Result<Self, String> {
+            let mut ebpf = Ebpf::load_file(path)
+                .map_err(|e| format!("failed to load {}: {e}", path.display()))?;
+
+            for &(category, name) in TRACEPOINTS {
+                let program: &mut TracePoint = ebpf
+                    .program_mut(name)
+                    .ok_or_else(|| format!("program {name} not found in BPF object"))?
+                    .try_into()
+                    .map_err(|e| format!("program {name}: {e}"))?;
+                program
+                    .load()
+                    .map_err(|e| format!("failed to load program {name}: {e}"))?;
+                program
+                    .attach(category, name)
+                    .map_err(|e| format!("failed to attach {category}:{name}: {e}"))?;
+            }
+
+            let map = ebpf
+                .take_map(BLK_LAT_MAP)
+                .ok_or_else(|| format!("map {BLK_LAT_MAP} not found in BPF object"))?;
+            let hist = BpfHashMap::try_from(map).map_err(|e| format!("map {BLK_LAT_MAP}: {e}"))?;
+
+            Ok(Self { _ebpf: ebpf, hist })
+        }
+
+        /// Reads and clears the per-process histograms.
+        ///
+        /// Requests completing between the read and the removal of a key are
+        /// lost; at snapshot intervals this is negligible.
+        pub fn drain(&mut self) -> HashMap<u32, ProcessBlkLatInfo> {
+            let entries: Vec<(u32, BlkLatHist)> =
+                self.hist.iter().filter_map(|entry| entry.ok()).collect();
+            let mut result = HashMap::with_capacity(entries.len());
+            for (pid, hist) in entries {
+                if let Err(e) = self.hist.remove(&pid) {
+                    warn!(pid, error = %e, "failed to clear block latency entry");
+                }
+                result.insert(pid, hist.to_info());
+            }
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hist_to_info_trims_slots() {
+        let mut slots = [0u64; BLK_LAT_SLOTS];
+        slots[3] = 5; // 8..16 us
+        slots[10] = 1; // ~1..2 ms
+        let info = BlkLatHist {
+            count: 6,
+            total_ns: 1_540_000,
+            slots,
+        }
+        .to_info();
+
+        assert_eq!(info.count, 6);
+        assert_eq!(info.total_us, 1540);
+        assert_eq!(info.slots.len(), 11);
+        assert_eq!(info.avg_us(), Some(256));
+        assert_eq!(info.quantile_us(0.5), Some(16));
+        assert_eq!(info.quantile_us(0.99), Some(2048));
+
+        let empty = BlkLatHist {
+            count: 0,
+            total_ns: 0,
+            slots: [0; BLK_LAT_SLOTS],
+        }
+        .to_info();
+        assert!(empty.slots.is_empty());
+        assert_eq!(empty.avg_us(), None);
+        assert_eq!(empty.quantile_us(0.99), None);
+    }
+}
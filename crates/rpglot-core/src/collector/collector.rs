@@ -3,15 +3,30 @@
 //! The `Collector` struct provides a unified interface for collecting
 //! all system metrics into a `Snapshot` for storage.
 
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
 use std::path::Path;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+#[cfg(all(feature = "ebpf", target_os = "linux"))]
+use crate::collector::blk_latency::BlkLatencyCollector;
 use crate::collector::cgroup::CgroupCollector;
+#[cfg(target_os = "linux")]
+use crate::collector::discovery::{InstanceDiscovery, find_postmasters};
+use crate::collector::k8s::K8sCollector;
+#[cfg(target_os = "linux")]
+use crate::collector::kmsg::KmsgCollector;
 use crate::collector::pg_collector::PostgresCollector;
+use crate::collector::procfs::process::SMAPS_ROLLUP_MAX_PROCESSES;
 use crate::collector::procfs::{CollectError, ProcessCollector, SystemCollector, UserResolver};
+#[cfg(target_os = "linux")]
+use crate::collector::systemd::{SystemdCollector, unit_from_cgroup};
+use crate::collector::throttle::LoadThrottle;
 use crate::collector::traits::FileSystem;
 use crate::storage::interner::StringInterner;
-use crate::storage::model::{DataBlock, Snapshot};
+use crate::storage::model::{
+    ClockInfo, DataBlock, PgConnectionChurnInfo, PgInstanceBlocks, PgStatActivityInfo, Snapshot,
+};
 use crate::util::is_container;
 
 /// Timing information for each collector phase.
@@ -41,6 +56,8 @@ pub struct CollectorTiming {
     pub stat: Duration,
     /// Time to collect network SNMP statistics.
     pub netsnmp: Duration,
+    /// Time to collect TCP connections to the PostgreSQL port.
+    pub tcp_conn: Duration,
     /// Time to collect PostgreSQL activity.
     pub pg_activity: Duration,
     /// Time to collect PostgreSQL statements.
@@ -49,7 +66,7 @@ pub struct CollectorTiming {
     pub pg_store_plans: Duration,
     /// Time to collect PostgreSQL database stats.
     pub pg_database: Duration,
-    /// Time to collect PostgreSQL bgwriter stats.
+    /// Time to collect PostgreSQL bgwriter and archiver stats.
     pub pg_bgwriter: Duration,
     /// Time to collect PostgreSQL user tables stats.
     pub pg_tables: Duration,
@@ -61,10 +78,92 @@ pub struct CollectorTiming {
     pub pg_log: Duration,
     /// Time to collect pg_stat_progress_vacuum.
     pub pg_progress_vacuum: Duration,
+    /// Time to run user-defined queries.
+    pub pg_custom: Duration,
     /// Time to collect cgroup metrics.
     pub cgroup: Duration,
+    /// Time to read kernel events from /dev/kmsg.
+    pub kernel_events: Duration,
+    /// Time to query the PostgreSQL systemd unit state.
+    pub systemd: Duration,
     /// PostgreSQL statements caching interval (Duration::ZERO = no caching).
     pub pg_stmts_cache_interval: Option<Duration>,
+    /// Elapsed time of the guarded PostgreSQL queries that ran.
+    pub pg_queries: Vec<(&'static str, Duration)>,
+    /// Collection queries paused after repeated timeouts.
+    pub warnings: Vec<String>,
+}
+
+impl CollectorTiming {
+    /// Per-subsystem durations with snake_case names, in collection order.
+    pub fn phases(&self) -> [(&'static str, Duration); 25] {
+        [
+            ("stat", self.stat),
+            ("processes", self.processes),
+            ("meminfo", self.meminfo),
+            ("cpuinfo", self.cpuinfo),
+            ("loadavg", self.loadavg),
+            ("diskstats", self.diskstats),
+            ("netdev", self.netdev),
+            ("psi", self.psi),
+            ("vmstat", self.vmstat),
+            ("netsnmp", self.netsnmp),
+            ("tcp_conn", self.tcp_conn),
+            ("pg_activity", self.pg_activity),
+            ("pg_statements", self.pg_statements),
+            ("pg_store_plans", self.pg_store_plans),
+            ("pg_database", self.pg_database),
+            ("pg_bgwriter", self.pg_bgwriter),
+            ("pg_progress_vacuum", self.pg_progress_vacuum),
+            ("pg_tables", self.pg_tables),
+            ("pg_indexes", self.pg_indexes),
+            ("pg_locks", self.pg_locks),
+            ("pg_log", self.pg_log),
+            ("pg_custom", self.pg_custom),
+            ("cgroup", self.cgroup),
+            ("kernel_events", self.kernel_events),
+            ("systemd", self.systemd),
+        ]
+    }
+}
+
+/// Numbers the snapshots of a collector run and measures their time with
+/// a monotonic clock, stored as [`DataBlock::Clock`].
+struct RunClock {
+    run_id: u64,
+    started: Instant,
+    seq: u64,
+}
+
+impl RunClock {
+    fn new() -> Self {
+        let run_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+        Self {
+            run_id,
+            started: Instant::now(),
+            seq: 0,
+        }
+    }
+
+    fn tick(&mut self) -> ClockInfo {
+        self.seq += 1;
+        ClockInfo {
+            run_id: self.run_id,
+            seq: self.seq,
+            monotonic_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// An additional PostgreSQL instance, stored as [`DataBlock::PgInstance`].
+struct PgInstance {
+    name: String,
+    collector: PostgresCollector,
+    /// Timestamp of the instance's previous log read.
+    last_log_ts: Option<i64>,
 }
 
 /// Main collector that gathers all system metrics.
@@ -73,14 +172,40 @@ pub struct CollectorTiming {
 /// that produces complete snapshots for storage.
 pub struct Collector<F: FileSystem + Clone> {
     fs: F,
+    #[cfg(target_os = "linux")]
+    proc_path: String,
     process_collector: ProcessCollector<F>,
     system_collector: SystemCollector<F>,
     user_resolver: UserResolver,
     postgres_collector: Option<PostgresCollector>,
+    /// Additional PostgreSQL instances on the same host.
+    pg_instances: Vec<PgInstance>,
     pg_last_error: Option<String>,
     cgroup_collector: Option<CgroupCollector<F>>,
+    #[cfg(all(feature = "ebpf", target_os = "linux"))]
+    blk_latency: Option<BlkLatencyCollector>,
+    #[cfg(target_os = "linux")]
+    kmsg: Option<KmsgCollector>,
+    #[cfg(target_os = "linux")]
+    systemd: Option<SystemdCollector>,
+    k8s: Option<K8sCollector>,
+    /// Adds PostgreSQL instances found in the process list.
+    #[cfg(target_os = "linux")]
+    discovery: Option<InstanceDiscovery>,
+    /// PostgreSQL processes of the previous snapshot (pid -> role), used to
+    /// attribute OOM kills that happened during the interval.
+    #[cfg(target_os = "linux")]
+    prev_pg_roles: HashMap<u32, String>,
     /// Timing information from the last collect_snapshot call.
     last_timing: Option<CollectorTiming>,
+    /// Minimum time between PostgreSQL log reads (zero = every snapshot).
+    log_interval: Duration,
+    /// Timestamp of the previous log read (for per-interval connection churn).
+    last_log_ts: Option<i64>,
+    /// Skips expensive collectors while the host is overloaded.
+    throttle: Option<LoadThrottle>,
+    /// Sequence numbers and monotonic time of the snapshots.
+    clock: RunClock,
 }
 
 impl<F: FileSystem + Clone> Collector<F> {
@@ -113,13 +238,31 @@ impl<F: FileSystem + Clone> Collector<F> {
 
         Self {
             fs: fs.clone(),
+            #[cfg(target_os = "linux")]
+            proc_path: proc_path.clone(),
             process_collector: ProcessCollector::new(fs.clone(), &proc_path),
             system_collector: SystemCollector::new(fs.clone(), &proc_path),
             user_resolver,
             postgres_collector: None,
+            pg_instances: Vec::new(),
             pg_last_error: None,
             cgroup_collector,
+            #[cfg(all(feature = "ebpf", target_os = "linux"))]
+            blk_latency: None,
+            #[cfg(target_os = "linux")]
+            kmsg: None,
+            #[cfg(target_os = "linux")]
+            systemd: None,
+            k8s: None,
+            #[cfg(target_os = "linux")]
+            discovery: None,
+            #[cfg(target_os = "linux")]
+            prev_pg_roles: HashMap::new(),
             last_timing: None,
+            log_interval: Duration::ZERO,
+            last_log_ts: None,
+            throttle: None,
+            clock: RunClock::new(),
         }
     }
 
@@ -160,6 +303,107 @@ impl<F: FileSystem + Clone> Collector<F> {
         self
     }
 
+    /// Replaces (or removes) the PostgreSQL collector.
+    pub fn set_postgres(&mut self, pg_collector: Option<PostgresCollector>) {
+        self.postgres_collector = pg_collector;
+        self.last_log_ts = None;
+    }
+
+    /// Returns the PostgreSQL collector, if enabled.
+    pub fn postgres_mut(&mut self) -> Option<&mut PostgresCollector> {
+        self.postgres_collector.as_mut()
+    }
+
+    /// Adds another PostgreSQL instance on the same host.
+    ///
+    /// Its blocks are stored in [`DataBlock::PgInstance`] under `name`; the
+    /// collector set with [`with_postgres`](Self::with_postgres) stays the
+    /// primary instance with top-level blocks.
+    pub fn with_postgres_instance(
+        mut self,
+        name: impl Into<String>,
+        pg_collector: PostgresCollector,
+    ) -> Self {
+        self.pg_instances.push(PgInstance {
+            name: name.into(),
+            collector: pg_collector,
+            last_log_ts: None,
+        });
+        self
+    }
+
+    /// Replaces the additional PostgreSQL instances.
+    pub fn set_postgres_instances(&mut self, instances: Vec<(String, PostgresCollector)>) {
+        self.pg_instances = instances
+            .into_iter()
+            .map(|(name, collector)| PgInstance {
+                name,
+                collector,
+                last_log_ts: None,
+            })
+            .collect();
+    }
+
+    /// Returns all PostgreSQL collectors: the primary one and the additional instances.
+    pub fn postgres_collectors_mut(&mut self) -> impl Iterator<Item = &mut PostgresCollector> {
+        self.postgres_collector
+            .iter_mut()
+            .chain(self.pg_instances.iter_mut().map(|i| &mut i.collector))
+    }
+
+    /// Sets the minimum time between PostgreSQL log reads.
+    ///
+    /// Default: zero (every snapshot). Log errors and events accumulate
+    /// between reads and are stored in the snapshot that reads them.
+    pub fn set_log_interval(&mut self, interval: Duration) {
+        self.log_interval = interval;
+    }
+
+    /// Enables per-process block I/O latency collection via eBPF.
+    ///
+    /// Latency histograms are merged into `ProcessInfo::blk_lat`.
+    #[cfg(all(feature = "ebpf", target_os = "linux"))]
+    pub fn with_blk_latency(mut self, blk_latency: BlkLatencyCollector) -> Self {
+        self.blk_latency = Some(blk_latency);
+        self
+    }
+
+    /// Enables collection of kernel events (OOM kills, I/O errors,
+    /// read-only remounts) from `/dev/kmsg`.
+    #[cfg(target_os = "linux")]
+    pub fn with_kmsg(mut self, kmsg: KmsgCollector) -> Self {
+        self.kmsg = Some(kmsg);
+        self
+    }
+
+    /// Enables collection of the PostgreSQL systemd unit state.
+    #[cfg(target_os = "linux")]
+    pub fn with_systemd(mut self, systemd: SystemdCollector) -> Self {
+        self.systemd = Some(systemd);
+        self
+    }
+
+    /// Enables collection of Kubernetes pod metadata (container mode).
+    pub fn with_k8s(mut self, k8s: K8sCollector) -> Self {
+        self.k8s = Some(k8s);
+        self
+    }
+
+    /// Enables or disables automatic discovery of PostgreSQL instances.
+    ///
+    /// Postmasters found in the process list whose port is not collected yet
+    /// become the primary instance (when none is set) or additional
+    /// instances. Instances that stop later stay configured.
+    #[cfg(target_os = "linux")]
+    pub fn set_discovery(&mut self, discovery: Option<InstanceDiscovery>) {
+        self.discovery = discovery;
+    }
+
+    /// Enables or disables adaptive throttling of expensive collectors.
+    pub fn set_throttle(&mut self, throttle: Option<LoadThrottle>) {
+        self.throttle = throttle;
+    }
+
     /// Returns the last PostgreSQL error message, if any.
     pub fn pg_last_error(&self) -> Option<&str> {
         self.pg_last_error.as_deref()
@@ -223,7 +467,12 @@ impl<F: FileSystem + Clone> Collector<F> {
             .map(|d| d.as_secs() as i64)
             .unwrap_or(0);
 
-        let mut blocks = Vec::new();
+        let mut blocks = vec![DataBlock::Clock(self.clock.tick())];
+
+        let degraded = self.throttle.as_mut().and_then(LoadThrottle::begin);
+        for pg_collector in self.postgres_collectors_mut() {
+            pg_collector.set_throttled(degraded.is_some());
+        }
 
         // Collect global stat first to get boot time for process start time calculation
         let start = Instant::now();
@@ -232,11 +481,27 @@ impl<F: FileSystem + Clone> Collector<F> {
         if let Some(ref stat) = stat {
             self.process_collector.set_boot_time(stat.btime);
         }
+        #[cfg(target_os = "linux")]
+        let boot_time = stat.as_ref().map_or(0, |s| s.btime);
 
         // Collect process information (now with correct boot time)
         let start = Instant::now();
-        let processes = self.process_collector.collect_all_processes()?;
+        let mut processes = self.process_collector.collect_all_processes()?;
+        // PSS walks page tables; skip it while the host is struggling
+        if degraded.is_none() {
+            self.process_collector
+                .collect_smaps_rollup(&mut processes, SMAPS_ROLLUP_MAX_PROCESSES);
+        }
+        #[cfg(all(feature = "ebpf", target_os = "linux"))]
+        if let Some(ref mut blk_latency) = self.blk_latency {
+            let mut latencies = blk_latency.drain();
+            for p in &mut processes {
+                p.blk_lat = latencies.remove(&p.pid);
+            }
+        }
         timing.processes = start.elapsed();
+        #[cfg(target_os = "linux")]
+        self.discover_instances(&processes);
         blocks.push(DataBlock::Processes(processes));
 
         // Collect system memory info
@@ -322,133 +587,428 @@ impl<F: FileSystem + Clone> Collector<F> {
         }
         timing.netsnmp = start.elapsed();
 
-        // Collect PostgreSQL activity (if configured)
-        if let Some(ref mut pg_collector) = self.postgres_collector {
+        // Collect TCP connections to the PostgreSQL port
+        if let Some(ref pg_collector) = self.postgres_collector {
             let start = Instant::now();
-            let activities = pg_collector.collect(self.process_collector.interner_mut());
-            timing.pg_activity = start.elapsed();
-            if !activities.is_empty() {
-                blocks.push(DataBlock::PgStatActivity(activities));
-            }
-
-            let start = Instant::now();
-            let statements = pg_collector.collect_statements(self.process_collector.interner_mut());
-            timing.pg_statements = start.elapsed();
-            if !statements.is_empty() {
-                blocks.push(DataBlock::PgStatStatements(statements));
+            if let Ok(tcp) = self
+                .system_collector
+                .collect_tcp_connections(pg_collector.port())
+            {
+                blocks.push(DataBlock::SystemTcpConn(tcp));
             }
+            timing.tcp_conn = start.elapsed();
+        }
 
-            let start = Instant::now();
-            let store_plans =
-                pg_collector.collect_store_plans(self.process_collector.interner_mut());
-            timing.pg_store_plans = start.elapsed();
-            if !store_plans.is_empty() {
-                blocks.push(DataBlock::PgStorePlans(store_plans));
-            }
+        // Collect PostgreSQL activity (if configured)
+        if let Some(ref mut pg_collector) = self.postgres_collector {
+            blocks.extend(collect_pg_blocks(
+                pg_collector,
+                self.process_collector.interner_mut(),
+                &mut timing,
+                timestamp,
+                self.log_interval,
+                &mut self.last_log_ts,
+            ));
 
-            let start = Instant::now();
-            let databases = pg_collector.collect_database(self.process_collector.interner_mut());
-            timing.pg_database = start.elapsed();
-            if !databases.is_empty() {
-                blocks.push(DataBlock::PgStatDatabase(databases));
-            }
+            // Store last error for TUI display
+            self.pg_last_error = pg_collector.last_error().map(|s| s.to_string());
 
-            let start = Instant::now();
-            if let Some(bgwriter) = pg_collector.collect_bgwriter() {
-                blocks.push(DataBlock::PgStatBgwriter(bgwriter));
-            }
-            timing.pg_bgwriter = start.elapsed();
+            // Store caching interval for debugging
+            timing.pg_stmts_cache_interval = Some(pg_collector.statements_cache_interval());
+            timing.pg_queries = pg_collector.take_query_timings();
+            timing.warnings = pg_collector.query_warnings();
+        } else {
+            self.pg_last_error = Some("PostgreSQL collector not configured".to_string());
+        }
 
-            let start = Instant::now();
-            let progress_vacuum =
-                pg_collector.collect_progress_vacuum(self.process_collector.interner_mut());
-            timing.pg_progress_vacuum = start.elapsed();
-            if !progress_vacuum.is_empty() {
-                blocks.push(DataBlock::PgStatProgressVacuum(progress_vacuum));
+        // Collect additional PostgreSQL instances (timings are not recorded)
+        for instance in &mut self.pg_instances {
+            let mut instance_timing = CollectorTiming::default();
+            let mut instance_blocks = collect_pg_blocks(
+                &mut instance.collector,
+                self.process_collector.interner_mut(),
+                &mut instance_timing,
+                timestamp,
+                self.log_interval,
+                &mut instance.last_log_ts,
+            );
+            instance.collector.take_query_timings();
+            timing.warnings.extend(
+                instance
+                    .collector
+                    .query_warnings()
+                    .into_iter()
+                    .map(|w| format!("{}: {}", instance.name, w)),
+            );
+            let port = instance.collector.port();
+            if let Ok(tcp) = self.system_collector.collect_tcp_connections(port) {
+                instance_blocks.push(DataBlock::SystemTcpConn(tcp));
             }
+            blocks.push(DataBlock::PgInstance(PgInstanceBlocks {
+                name: instance.name.clone(),
+                port,
+                blocks: instance_blocks,
+            }));
+        }
 
-            // Ensure per-database connections are established for tables/indexes.
-            pg_collector.ensure_db_clients();
-
-            let start = Instant::now();
-            match pg_collector.collect_tables(self.process_collector.interner_mut()) {
-                Ok(tables) if !tables.is_empty() => {
-                    blocks.push(DataBlock::PgStatUserTables(tables));
-                }
-                _ => {}
-            }
-            timing.pg_tables = start.elapsed();
+        // Collect cgroup metrics (if collector is configured)
+        let start = Instant::now();
+        if let Some(ref cgroup_collector) = self.cgroup_collector
+            && let Some(cgroup_info) = cgroup_collector.collect()
+        {
+            blocks.push(DataBlock::Cgroup(cgroup_info));
+        }
+        timing.cgroup = start.elapsed();
 
+        // Collect kernel events (if enabled)
+        #[cfg(target_os = "linux")]
+        if let Some(ref mut kmsg) = self.kmsg {
             let start = Instant::now();
-            match pg_collector.collect_indexes(self.process_collector.interner_mut()) {
-                Ok(indexes) if !indexes.is_empty() => {
-                    blocks.push(DataBlock::PgStatUserIndexes(indexes));
+            let pg_roles = pg_process_roles(&blocks, self.process_collector.interner());
+            let mut events = kmsg.drain(boot_time);
+            for e in &mut events {
+                if e.kind == crate::storage::model::KernelEventKind::OomKill {
+                    e.pg_role = self
+                        .prev_pg_roles
+                        .get(&e.pid)
+                        .or_else(|| pg_roles.get(&e.pid))
+                        .cloned();
                 }
-                _ => {}
             }
-            timing.pg_indexes = start.elapsed();
-
-            let start = Instant::now();
-            let lock_tree = pg_collector.collect_lock_tree(self.process_collector.interner_mut());
-            timing.pg_locks = start.elapsed();
-            if !lock_tree.is_empty() {
-                blocks.push(DataBlock::PgLockTree(lock_tree));
+            self.prev_pg_roles = pg_roles;
+            if !events.is_empty() {
+                blocks.push(DataBlock::KernelEvents(events));
             }
+            timing.kernel_events = start.elapsed();
+        }
 
+        // Collect PostgreSQL systemd unit state (if enabled)
+        #[cfg(target_os = "linux")]
+        if let Some(ref mut systemd) = self.systemd {
             let start = Instant::now();
-            let log_result = pg_collector.collect_log_data(self.process_collector.interner_mut());
-            timing.pg_log = start.elapsed();
-            if !log_result.errors.is_empty() {
-                blocks.push(DataBlock::PgLogErrors(log_result.errors));
-            }
-            if log_result.checkpoint_count > 0
-                || log_result.autovacuum_count > 0
-                || log_result.slow_query_count > 0
+            if systemd.needs_unit()
+                && let Some(pid) = pg_process_roles(&blocks, self.process_collector.interner())
+                    .into_iter()
+                    .filter(|(_, role)| role == "postmaster")
+                    .map(|(pid, _)| pid)
+                    .min()
+                && let Ok(cgroup) = self
+                    .fs
+                    .read_to_string(Path::new(&format!("{}/{}/cgroup", self.proc_path, pid)))
+                && let Some(unit) = unit_from_cgroup(&cgroup)
             {
-                blocks.push(DataBlock::PgLogEvents(
-                    crate::storage::model::PgLogEventsInfo {
-                        checkpoint_count: log_result.checkpoint_count,
-                        autovacuum_count: log_result.autovacuum_count,
-                        slow_query_count: log_result.slow_query_count,
-                    },
-                ));
+                systemd.set_unit(unit);
             }
-            if !log_result.events.is_empty() {
-                blocks.push(DataBlock::PgLogDetailedEvents(log_result.events));
+            if let Some(unit) = systemd.collect(boot_time) {
+                blocks.push(DataBlock::SystemdUnit(unit));
             }
+            timing.systemd = start.elapsed();
+        }
 
-            let settings = pg_collector.collect_settings();
-            if !settings.is_empty() {
-                blocks.push(DataBlock::PgSettings(settings));
-            }
+        if let Some(pod) = self.k8s.as_ref().and_then(K8sCollector::collect) {
+            blocks.push(DataBlock::K8sPod(pod));
+        }
 
-            if let Some(repl_status) = pg_collector.collect_replication_status() {
-                blocks.push(DataBlock::ReplicationStatus(repl_status));
-            }
+        if let Some(info) = degraded {
+            blocks.push(DataBlock::CollectionDegraded(info));
+        }
 
-            // Store last error for TUI display
-            self.pg_last_error = pg_collector.last_error().map(|s| s.to_string());
+        timing.total = total_start.elapsed();
+        if let Some(ref mut throttle) = self.throttle {
+            let psi = blocks
+                .iter()
+                .find_map(|b| match b {
+                    DataBlock::SystemPsi(psi) => Some(psi.as_slice()),
+                    _ => None,
+                })
+                .unwrap_or(&[]);
+            throttle.observe(timing.total, psi);
+        }
+        self.last_timing = Some(timing);
 
-            // Store caching interval for debugging
-            timing.pg_stmts_cache_interval = Some(pg_collector.statements_cache_interval());
-        } else {
-            self.pg_last_error = Some("PostgreSQL collector not configured".to_string());
+        Ok(Snapshot { timestamp, blocks })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl<F: FileSystem + Clone> Collector<F> {
+    /// Adds collectors for newly found postmasters (when a scan is due).
+    fn discover_instances(&mut self, processes: &[crate::storage::model::ProcessInfo]) {
+        let Some(discovery) = self.discovery.as_mut() else {
+            return;
+        };
+        if !discovery.due() {
+            return;
+        }
+        let found = find_postmasters(
+            &self.fs,
+            &self.proc_path,
+            processes,
+            self.process_collector.interner(),
+        );
+        let known_ports: Vec<u16> = self
+            .postgres_collector
+            .iter()
+            .chain(self.pg_instances.iter().map(|i| &i.collector))
+            .map(PostgresCollector::port)
+            .collect();
+        let known_names: Vec<&str> = self.pg_instances.iter().map(|i| i.name.as_str()).collect();
+        let mut built = discovery
+            .build(&found, &known_ports, &known_names)
+            .into_iter();
+        if self.postgres_collector.is_none()
+            && let Some((_, primary)) = built.next()
+        {
+            self.postgres_collector = Some(primary);
+            self.last_log_ts = None;
+        }
+        for (name, collector) in built {
+            self.pg_instances.push(PgInstance {
+                name,
+                collector,
+                last_log_ts: None,
+            });
         }
+    }
+}
 
-        // Collect cgroup metrics (if collector is configured)
+/// Collects the instance-scoped blocks of one PostgreSQL instance.
+///
+/// `last_log_ts` is the instance's previous log read, updated when logs are
+/// read (every `log_interval`).
+fn collect_pg_blocks(
+    pg_collector: &mut PostgresCollector,
+    interner: &mut StringInterner,
+    timing: &mut CollectorTiming,
+    timestamp: i64,
+    log_interval: Duration,
+    last_log_ts: &mut Option<i64>,
+) -> Vec<DataBlock> {
+    let mut blocks = Vec::new();
+
+    let start = Instant::now();
+    let activities = pg_collector.collect(interner);
+    timing.pg_activity = start.elapsed();
+    if let Some(status) = pg_collector.connection_status() {
+        blocks.push(DataBlock::PgConnection(status));
+    }
+    let new_backends = match *last_log_ts {
+        Some(prev_ts) => {
+            let client_backend = interner.intern("client backend");
+            count_new_backends(&activities, client_backend, prev_ts as f64)
+        }
+        None => 0,
+    };
+    if !activities.is_empty() {
+        blocks.push(DataBlock::PgStatActivity(activities));
+    }
+
+    let start = Instant::now();
+    let statements = pg_collector.collect_statements(interner);
+    timing.pg_statements = start.elapsed();
+    if !statements.is_empty() {
+        blocks.push(DataBlock::PgStatStatements(statements));
+    }
+
+    let start = Instant::now();
+    let store_plans = pg_collector.collect_store_plans(interner);
+    timing.pg_store_plans = start.elapsed();
+    if !store_plans.is_empty() {
+        blocks.push(DataBlock::PgStorePlans(store_plans));
+    }
+
+    let start = Instant::now();
+    let databases = pg_collector.collect_database(interner);
+    timing.pg_database = start.elapsed();
+    if !databases.is_empty() {
+        blocks.push(DataBlock::PgStatDatabase(databases));
+    }
+
+    let start = Instant::now();
+    if let Some(bgwriter) = pg_collector.collect_bgwriter() {
+        blocks.push(DataBlock::PgStatBgwriter(bgwriter));
+    }
+    if let Some(archiver) = pg_collector.collect_archiver() {
+        blocks.push(DataBlock::PgStatArchiver(archiver));
+    }
+    timing.pg_bgwriter = start.elapsed();
+
+    let start = Instant::now();
+    let progress_vacuum = pg_collector.collect_progress_vacuum(interner);
+    timing.pg_progress_vacuum = start.elapsed();
+    if !progress_vacuum.is_empty() {
+        blocks.push(DataBlock::PgStatProgressVacuum(progress_vacuum));
+    }
+
+    // Ensure per-database connections are established for tables/indexes.
+    pg_collector.ensure_db_clients();
+
+    let start = Instant::now();
+    match pg_collector.collect_tables(interner) {
+        Ok(tables) if !tables.is_empty() => {
+            blocks.push(DataBlock::PgStatUserTables(tables));
+        }
+        _ => {}
+    }
+    timing.pg_tables = start.elapsed();
+
+    let start = Instant::now();
+    match pg_collector.collect_indexes(interner) {
+        Ok(indexes) if !indexes.is_empty() => {
+            blocks.push(DataBlock::PgStatUserIndexes(indexes));
+        }
+        _ => {}
+    }
+    timing.pg_indexes = start.elapsed();
+
+    let start = Instant::now();
+    let lock_tree = pg_collector.collect_lock_tree(interner);
+    timing.pg_locks = start.elapsed();
+    if !lock_tree.is_empty() {
+        blocks.push(DataBlock::PgLockTree(lock_tree));
+    }
+
+    // Collect PostgreSQL log data (every log_interval)
+    // A clock stepped back makes the previous read look in the future: read now
+    let logs_due =
+        last_log_ts.is_none_or(|t| timestamp < t || timestamp - t >= log_interval.as_secs() as i64);
+    if logs_due {
         let start = Instant::now();
-        if let Some(ref cgroup_collector) = self.cgroup_collector
-            && let Some(cgroup_info) = cgroup_collector.collect()
+        let log_result = pg_collector.collect_log_data(interner);
+        timing.pg_log = start.elapsed();
+        if !log_result.errors.is_empty() {
+            blocks.push(DataBlock::PgLogErrors(log_result.errors));
+        }
+        if log_result.checkpoint_count > 0
+            || log_result.autovacuum_count > 0
+            || log_result.slow_query_count > 0
         {
-            blocks.push(DataBlock::Cgroup(cgroup_info));
+            blocks.push(DataBlock::PgLogEvents(
+                crate::storage::model::PgLogEventsInfo {
+                    checkpoint_count: log_result.checkpoint_count,
+                    autovacuum_count: log_result.autovacuum_count,
+                    slow_query_count: log_result.slow_query_count,
+                },
+            ));
         }
-        timing.cgroup = start.elapsed();
+        if !log_result.events.is_empty() {
+            blocks.push(DataBlock::PgLogDetailedEvents(log_result.events));
+        }
+        if !log_result.slow_plans.is_empty() {
+            blocks.push(DataBlock::PgSlowPlans(log_result.slow_plans));
+        }
+        if let Some(prev_ts) = *last_log_ts {
+            let conns = log_result.connections;
+            let churn = PgConnectionChurnInfo {
+                interval_s: timestamp.saturating_sub(prev_ts).max(0) as f64,
+                received: conns.received,
+                authorized: conns.authorized,
+                disconnections: conns.disconnections,
+                short_lived: conns.short_lived,
+                total_session_s: conns.total_session_s,
+                new_backends,
+            };
+            if churn.connections() > 0 || churn.disconnections > 0 {
+                blocks.push(DataBlock::PgConnectionChurn(churn));
+            }
+        }
+        *last_log_ts = Some(timestamp);
+    }
 
-        timing.total = total_start.elapsed();
-        self.last_timing = Some(timing);
+    let settings = pg_collector.collect_settings();
+    if !settings.is_empty() {
+        blocks.push(DataBlock::PgSettings(settings));
+    }
+    let settings_meta = pg_collector.settings_meta();
+    if !settings_meta.is_empty() {
+        blocks.push(DataBlock::PgSettingsMeta(settings_meta.to_vec()));
+    }
+    let pending_restart = pg_collector.pending_restart();
+    if !pending_restart.is_empty() {
+        blocks.push(DataBlock::PgPendingRestart(pending_restart.to_vec()));
+    }
 
-        Ok(Snapshot { timestamp, blocks })
+    let relation_sizes = pg_collector.collect_relation_sizes();
+    if !relation_sizes.is_empty() {
+        blocks.push(DataBlock::PgRelationSizes(relation_sizes));
+    }
+
+    let sequences = pg_collector.collect_sequences();
+    if !sequences.is_empty() {
+        blocks.push(DataBlock::PgSequences(sequences));
     }
+
+    let extensions = pg_collector.collect_extensions();
+    if !extensions.is_empty() {
+        blocks.push(DataBlock::PgExtensions(extensions));
+    }
+
+    if let Some(buffercache) = pg_collector.collect_buffercache() {
+        blocks.push(DataBlock::PgBufferCache(buffercache));
+    }
+
+    if let Some(repl_status) = pg_collector.collect_replication_status() {
+        blocks.push(DataBlock::ReplicationStatus(repl_status));
+    }
+
+    let start = Instant::now();
+    let custom = pg_collector.collect_custom_queries();
+    timing.pg_custom = start.elapsed();
+    if !custom.is_empty() {
+        blocks.push(DataBlock::CustomQuery(custom));
+    }
+
+    blocks
+}
+
+/// Counts client backends started after `since` (Unix epoch seconds).
+fn count_new_backends(activities: &[PgStatActivityInfo], client_backend: u64, since: f64) -> u32 {
+    activities
+        .iter()
+        .filter(|a| a.backend_type_hash == client_backend && a.backend_start > since)
+        .count() as u32
+}
+
+/// PostgreSQL processes of a snapshot by PID: `postmaster` for the server
+/// process, the `pg_stat_activity.backend_type` for backends it reports and
+/// `backend` for other children of the postmaster.
+#[cfg(target_os = "linux")]
+fn pg_process_roles(blocks: &[DataBlock], interner: &StringInterner) -> HashMap<u32, String> {
+    let mut roles = HashMap::new();
+    for block in blocks {
+        match block {
+            DataBlock::Processes(processes) => {
+                let is_postgres = |name_hash: u64| {
+                    matches!(interner.resolve(name_hash), Some("postgres" | "postmaster"))
+                };
+                let postgres_pids: HashMap<u32, u32> = processes
+                    .iter()
+                    .filter(|p| is_postgres(p.name_hash))
+                    .map(|p| (p.pid, p.ppid))
+                    .collect();
+                for (&pid, ppid) in &postgres_pids {
+                    let role = if postgres_pids.contains_key(ppid) {
+                        "backend"
+                    } else {
+                        "postmaster"
+                    };
+                    roles.entry(pid).or_insert_with(|| role.to_string());
+                }
+            }
+            DataBlock::PgStatActivity(activities) => {
+                for a in activities {
+                    if let Some(backend_type) = interner.resolve(a.backend_type_hash)
+                        && !backend_type.is_empty()
+                        && a.pid > 0
+                    {
+                        roles.insert(a.pid as u32, backend_type.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    roles
 }
 
 #[cfg(test)]
@@ -579,4 +1139,62 @@ mod tests {
         // Same process should have same name hash
         assert_eq!(proc1.name_hash, proc2.name_hash);
     }
+
+    #[test]
+    fn test_count_new_backends() {
+        let mut interner = StringInterner::new();
+        let client = interner.intern("client backend");
+        let walsender = interner.intern("walsender");
+        let row = |backend_type_hash, backend_start| PgStatActivityInfo {
+            backend_type_hash,
+            backend_start,
+            ..Default::default()
+        };
+        let activities = vec![
+            row(client, 1000.0),
+            row(client, 1005.5),
+            row(client, 1009.0),
+            row(walsender, 1008.0),
+        ];
+
+        assert_eq!(count_new_backends(&activities, client, 1004.0), 2);
+        assert_eq!(count_new_backends(&activities, client, 1010.0), 0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_pg_process_roles() {
+        use crate::storage::model::ProcessInfo;
+
+        let mut interner = StringInterner::new();
+        let postgres = interner.intern("postgres");
+        let bash = interner.intern("bash");
+        let client = interner.intern("client backend");
+        let process = |pid, ppid, name_hash| ProcessInfo {
+            pid,
+            ppid,
+            name_hash,
+            ..Default::default()
+        };
+        let blocks = vec![
+            DataBlock::Processes(vec![
+                process(1, 0, bash),
+                process(100, 1, postgres),
+                process(101, 100, postgres),
+                process(102, 100, postgres),
+                process(200, 1, bash),
+            ]),
+            DataBlock::PgStatActivity(vec![PgStatActivityInfo {
+                pid: 102,
+                backend_type_hash: client,
+                ..Default::default()
+            }]),
+        ];
+
+        let roles = pg_process_roles(&blocks, &interner);
+        assert_eq!(roles.len(), 3);
+        assert_eq!(roles[&100], "postmaster");
+        assert_eq!(roles[&101], "backend");
+        assert_eq!(roles[&102], "client backend");
+    }
 }
@@ -0,0 +1,153 @@
+//! User-defined SQL metric queries.
+//!
+//! A TOML file declares named queries, their collection interval and the
+//! type of each result column:
+//!
+//! ```toml
+//! [[query]]
+//! name = "replication_slots"
+//! sql = """
+//! SELECT slot_name, active::int AS active,
+//!        pg_wal_lsn_diff(pg_current_wal_lsn(), restart_lsn)::float8 AS retained_bytes
+//! FROM pg_replication_slots
+//! """
+//! interval = 60
+//! columns = [
+//!     { name = "slot_name", type = "key" },
+//!     { name = "active", type = "int" },
+//!     { name = "retained_bytes", type = "float" },
+//! ]
+//! ```
+//!
+//! `key` columns label a row (text or integer); `int` and `float` columns are
+//! numeric values (cast `numeric` results to `float8`). Results are stored as
+//! [`DataBlock::CustomQuery`](crate::storage::model::DataBlock::CustomQuery).
+
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::storage::model::{CustomColumn, CustomColumnType};
+
+/// Default interval between runs of a custom query.
+pub const DEFAULT_CUSTOM_QUERY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A named SQL query declared in the custom queries file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomQueryDef {
+    pub name: String,
+    pub sql: String,
+    /// Seconds between runs (default: 30). Between runs the last result is reused.
+    #[serde(default = "default_interval_secs")]
+    pub interval: u64,
+    pub columns: Vec<CustomColumn>,
+}
+
+impl CustomQueryDef {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval)
+    }
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_CUSTOM_QUERY_INTERVAL.as_secs()
+}
+
+#[derive(Deserialize)]
+struct CustomQueryFile {
+    #[serde(default)]
+    query: Vec<CustomQueryDef>,
+}
+
+/// Parses and validates the custom queries file contents.
+pub fn parse_custom_queries(text: &str) -> Result<Vec<CustomQueryDef>, String> {
+    let file: CustomQueryFile = toml::from_str(text).map_err(|e| e.to_string())?;
+    let mut names = HashSet::new();
+    for q in &file.query {
+        if q.name.is_empty() {
+            return Err("query name must not be empty".to_string());
+        }
+        if !names.insert(q.name.as_str()) {
+            return Err(format!("duplicate query name '{}'", q.name));
+        }
+        if q.interval == 0 {
+            return Err(format!("query '{}': interval must be positive", q.name));
+        }
+        let mut columns = HashSet::new();
+        for c in &q.columns {
+            if !columns.insert(c.name.as_str()) {
+                return Err(format!("query '{}': duplicate column '{}'", q.name, c.name));
+            }
+        }
+        if !q.columns.iter().any(|c| c.kind != CustomColumnType::Key) {
+            return Err(format!(
+                "query '{}': needs at least one int or float column",
+                q.name
+            ));
+        }
+    }
+    Ok(file.query)
+}
+
+/// Loads the custom queries file.
+pub fn load_custom_queries(path: &Path) -> io::Result<Vec<CustomQueryDef>> {
+    let text = std::fs::read_to_string(path)?;
+    parse_custom_queries(&text).map_err(|msg| io::Error::new(io::ErrorKind::InvalidData, msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_queries_with_defaults() {
+        let text = r#"
+            [[query]]
+            name = "slots"
+            sql = "SELECT slot_name, 1::float8 AS lag FROM pg_replication_slots"
+            columns = [
+                { name = "slot_name", type = "key" },
+                { name = "lag", type = "float" },
+            ]
+
+            [[query]]
+            name = "xid_age"
+            sql = "SELECT age(datfrozenxid) AS age FROM pg_database"
+            interval = 300
+            columns = [{ name = "age", type = "int" }]
+        "#;
+        let queries = parse_custom_queries(text).unwrap();
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[0].interval(), DEFAULT_CUSTOM_QUERY_INTERVAL);
+        assert_eq!(queries[0].columns[0].kind, CustomColumnType::Key);
+        assert_eq!(queries[1].interval, 300);
+        assert!(parse_custom_queries("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_queries() {
+        let query = |name: &str, interval: u64, columns: &str| {
+            format!(
+                "[[query]]\nname = \"{name}\"\nsql = \"SELECT 1\"\ninterval = {interval}\ncolumns = [{columns}]\n"
+            )
+        };
+        let value = r#"{ name = "v", type = "int" }"#;
+        let key = r#"{ name = "k", type = "key" }"#;
+
+        assert!(parse_custom_queries(&query("", 30, value)).is_err());
+        assert!(parse_custom_queries(&query("q", 0, value)).is_err());
+        assert!(parse_custom_queries(&query("q", 30, key)).is_err());
+        assert!(parse_custom_queries(&query("q", 30, &format!("{value}, {value}"))).is_err());
+        let twice = query("q", 30, value).repeat(2);
+        assert!(
+            parse_custom_queries(&twice)
+                .unwrap_err()
+                .contains("duplicate")
+        );
+        let bad_type = query("q", 30, r#"{ name = "v", type = "text" }"#);
+        assert!(parse_custom_queries(&bad_type).is_err());
+    }
+}
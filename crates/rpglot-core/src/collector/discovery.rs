@@ -0,0 +1,411 @@
+//! Automatic discovery of local PostgreSQL instances.
+//!
+//! Postmasters are found in the collected process list: a `postgres` (or
+//! `postmaster`) process whose parent is not one. The data directory comes
+//! from `-D` on the command line or `PGDATA` in `/proc/[pid]/environ`; the
+//! port and socket directory from `postmaster.pid` in the data directory,
+//! falling back to `-p` / `-c port=` / `PGPORT` when it is unreadable.
+//!
+//! Found instances that pass the allow/deny filters and are not collected
+//! yet (by port) get a [`PostgresCollector`] from the factory supplied by
+//! the binary, so the connection settings of configured instances apply.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+use crate::collector::pg_collector::{PgCollectError, PgConnectionParams, PostgresCollector};
+use crate::collector::traits::FileSystem;
+use crate::storage::StringInterner;
+use crate::storage::model::ProcessInfo;
+
+/// Default time between process list scans.
+pub const DEFAULT_RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A running postmaster.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredInstance {
+    pub pid: u32,
+    /// Absolute data directory; empty when unknown.
+    pub data_dir: String,
+    pub port: u16,
+    /// Unix socket directory or listen address; `None` leaves the default.
+    pub host: Option<String>,
+}
+
+impl DiscoveredInstance {
+    /// Instance name: the last two components of the data directory
+    /// (`16/main` for `/var/lib/postgresql/16/main`), or the port.
+    pub fn name(&self) -> String {
+        let components: Vec<&str> = self.data_dir.split('/').filter(|c| !c.is_empty()).collect();
+        match components.len() {
+            0 => self.port.to_string(),
+            1 => components[0].to_string(),
+            n => components[n - 2..].join("/"),
+        }
+    }
+
+    /// Connection parameters; the user, password and database come from
+    /// the environment like for configured instances.
+    pub fn params(&self) -> PgConnectionParams {
+        PgConnectionParams {
+            host: self.host.clone(),
+            port: Some(self.port),
+            ..Default::default()
+        }
+    }
+}
+
+/// Which discovered instances to collect. Patterns match the port, the
+/// data directory or the name; `*` matches any run of characters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiscoveryFilter {
+    /// Only these instances; empty allows all.
+    pub allow: Vec<String>,
+    /// Never these instances, even when allowed.
+    pub deny: Vec<String>,
+}
+
+impl DiscoveryFilter {
+    pub fn matches(&self, instance: &DiscoveredInstance) -> bool {
+        let port = instance.port.to_string();
+        let name = instance.name();
+        let hit = |pattern: &String| {
+            [port.as_str(), instance.data_dir.as_str(), name.as_str()]
+                .iter()
+                .any(|text| wildcard_match(pattern, text))
+        };
+        (self.allow.is_empty() || self.allow.iter().any(hit)) && !self.deny.iter().any(hit)
+    }
+}
+
+/// Glob match where `*` is the only special character.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Parses `postmaster.pid`: data directory (line 2), port (line 4), socket
+/// directory (line 5) and listen address (line 6).
+pub fn parse_postmaster_pid(content: &str) -> Option<(String, u16, Option<String>)> {
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let data_dir = lines.get(1)?.to_string();
+    let port = lines.get(3)?.parse().ok()?;
+    let host = match (lines.get(4), lines.get(5)) {
+        (Some(socket_dir), _) if !socket_dir.is_empty() => Some(socket_dir.to_string()),
+        (_, Some(&"*")) => Some("localhost".to_string()),
+        (_, Some(addr)) if !addr.is_empty() => {
+            Some(addr.split(',').next().unwrap_or(addr).trim().to_string())
+        }
+        _ => None,
+    };
+    Some((data_dir, port, host))
+}
+
+/// Data directory and port from the postmaster arguments (`-D`, `-p`,
+/// `-c port=`) and environment (`PGDATA`, `PGPORT`), both NUL-separated
+/// as in `/proc/[pid]/cmdline` and `/proc/[pid]/environ`.
+pub fn parse_postmaster_args(cmdline: &str, environ: &str) -> (Option<String>, Option<u16>) {
+    let mut data_dir = None;
+    let mut port = None;
+    let mut args = cmdline.split('\0').skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| match arg.strip_prefix(flag) {
+            Some("") => args.next().map(str::to_string),
+            Some(inline) => Some(inline.to_string()),
+            None => None,
+        };
+        if let Some(dir) = value("-D") {
+            data_dir = Some(dir);
+        } else if let Some(p) = value("-p") {
+            port = p.parse().ok();
+        } else if let Some(setting) = value("-c")
+            && let Some(p) = setting.strip_prefix("port=")
+        {
+            port = p.parse().ok();
+        }
+    }
+    for var in environ.split('\0') {
+        match var.split_once('=') {
+            Some(("PGDATA", dir)) if data_dir.is_none() => data_dir = Some(dir.to_string()),
+            Some(("PGPORT", p)) if port.is_none() => port = p.parse().ok(),
+            _ => {}
+        }
+    }
+    (data_dir.filter(|d| !d.is_empty()), port)
+}
+
+/// Finds the postmasters among `processes`.
+pub fn find_postmasters<F: FileSystem>(
+    fs: &F,
+    proc_path: &str,
+    processes: &[ProcessInfo],
+    interner: &StringInterner,
+) -> Vec<DiscoveredInstance> {
+    let is_postgres = |p: &ProcessInfo| {
+        matches!(
+            interner.resolve(p.name_hash),
+            Some("postgres" | "postmaster")
+        )
+    };
+    let postgres_pids: HashSet<u32> = processes
+        .iter()
+        .filter(|p| is_postgres(p))
+        .map(|p| p.pid)
+        .collect();
+    let mut found: Vec<DiscoveredInstance> = processes
+        .iter()
+        .filter(|p| is_postgres(p) && !postgres_pids.contains(&p.ppid))
+        .map(|p| {
+            let read = |file: &str| {
+                fs.read_to_string(Path::new(&format!("{}/{}/{}", proc_path, p.pid, file)))
+                    .unwrap_or_default()
+            };
+            let (data_dir, arg_port) = parse_postmaster_args(&read("cmdline"), &read("environ"));
+            let data_dir = data_dir.filter(|d| d.starts_with('/'));
+            let pid_file = data_dir.as_ref().and_then(|dir| {
+                fs.read_to_string(&Path::new(dir).join("postmaster.pid"))
+                    .ok()
+                    .and_then(|content| parse_postmaster_pid(&content))
+            });
+            let (data_dir, port, host) = match pid_file {
+                Some(parsed) => parsed,
+                None => (data_dir.unwrap_or_default(), arg_port.unwrap_or(5432), None),
+            };
+            DiscoveredInstance {
+                pid: p.pid,
+                data_dir,
+                port,
+                host,
+            }
+        })
+        .collect();
+    found.sort_by_key(|i| i.port);
+    found
+}
+
+/// Builds the collector of a discovered instance.
+pub type CollectorFactory =
+    Box<dyn Fn(PgConnectionParams) -> Result<PostgresCollector, PgCollectError> + Send>;
+
+/// Periodically turns discovered instances into collectors.
+pub struct InstanceDiscovery {
+    filter: DiscoveryFilter,
+    factory: CollectorFactory,
+    rescan_interval: Duration,
+    last_scan: Option<Instant>,
+}
+
+impl InstanceDiscovery {
+    pub fn new(filter: DiscoveryFilter, factory: CollectorFactory) -> Self {
+        Self {
+            filter,
+            factory,
+            rescan_interval: DEFAULT_RESCAN_INTERVAL,
+            last_scan: None,
+        }
+    }
+
+    /// Sets the time between process list scans.
+    pub fn with_rescan_interval(mut self, interval: Duration) -> Self {
+        self.rescan_interval = interval;
+        self
+    }
+
+    /// Whether a scan is due; the first one happens immediately.
+    pub(crate) fn due(&mut self) -> bool {
+        if self
+            .last_scan
+            .is_some_and(|t| t.elapsed() < self.rescan_interval)
+        {
+            return false;
+        }
+        self.last_scan = Some(Instant::now());
+        true
+    }
+
+    /// Collectors for the `found` instances that pass the filter and whose
+    /// port is not in `known_ports`, named uniquely against `known_names`.
+    pub(crate) fn build(
+        &self,
+        found: &[DiscoveredInstance],
+        known_ports: &[u16],
+        known_names: &[&str],
+    ) -> Vec<(String, PostgresCollector)> {
+        let mut names: Vec<String> = known_names.iter().map(|n| n.to_string()).collect();
+        let mut ports = known_ports.to_vec();
+        let mut built = Vec::new();
+        for instance in found {
+            if ports.contains(&instance.port) || !self.filter.matches(instance) {
+                continue;
+            }
+            ports.push(instance.port);
+            let mut name = instance.name();
+            if names.contains(&name) {
+                name = format!("{}:{}", name, instance.port);
+            }
+            match (self.factory)(instance.params()) {
+                Ok(collector) => {
+                    info!(
+                        name,
+                        port = instance.port,
+                        data_dir = instance.data_dir,
+                        "discovered PostgreSQL instance"
+                    );
+                    names.push(name.clone());
+                    built.push((name, collector));
+                }
+                Err(e) => {
+                    warn!(port = instance.port, error = %e, "discovered PostgreSQL instance skipped")
+                }
+            }
+        }
+        built
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::mock::MockFs;
+
+    #[test]
+    fn parses_postmaster_pid() {
+        let content = "812\n/var/lib/postgresql/16/main\n1700000000\n5433\n/var/run/postgresql\n*\n  5433001  0\nready   \n";
+        assert_eq!(
+            parse_postmaster_pid(content),
+            Some((
+                "/var/lib/postgresql/16/main".to_string(),
+                5433,
+                Some("/var/run/postgresql".to_string())
+            ))
+        );
+        // unix_socket_directories = '' and a specific listen address
+        let tcp_only = "812\n/data\n1700000000\n5432\n\n10.0.0.5,127.0.0.1\n";
+        assert_eq!(
+            parse_postmaster_pid(tcp_only).unwrap().2.as_deref(),
+            Some("10.0.0.5")
+        );
+        assert_eq!(parse_postmaster_pid("812\n"), None);
+    }
+
+    #[test]
+    fn parses_postmaster_args() {
+        let cmdline = "/usr/lib/postgresql/16/bin/postgres\0-D\0/var/lib/postgresql/16/main\0\
+                       -c\0config_file=/etc/postgresql/16/main/postgresql.conf\0";
+        assert_eq!(
+            parse_postmaster_args(cmdline, ""),
+            (Some("/var/lib/postgresql/16/main".to_string()), None)
+        );
+        assert_eq!(
+            parse_postmaster_args("postgres\0-Ddata\0-c\0port=5434\0", ""),
+            (Some("data".to_string()), Some(5434))
+        );
+        assert_eq!(
+            parse_postmaster_args("postgres\0-p5435\0", "HOME=/x\0PGDATA=/pgdata\0PGPORT=1\0"),
+            (Some("/pgdata".to_string()), Some(5435))
+        );
+    }
+
+    #[test]
+    fn filters_by_port_dir_and_name() {
+        let instance = DiscoveredInstance {
+            pid: 1,
+            data_dir: "/var/lib/postgresql/16/main".to_string(),
+            port: 5433,
+            host: None,
+        };
+        assert_eq!(instance.name(), "16/main");
+        let filter = |allow: &[&str], deny: &[&str]| DiscoveryFilter {
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+        };
+        assert!(filter(&[], &[]).matches(&instance));
+        assert!(filter(&["5433"], &[]).matches(&instance));
+        assert!(filter(&["/var/lib/postgresql/*"], &[]).matches(&instance));
+        assert!(filter(&["16/*"], &[]).matches(&instance));
+        assert!(!filter(&["5432"], &[]).matches(&instance));
+        assert!(!filter(&[], &["*/main"]).matches(&instance));
+
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*b*c", "aXbYc"));
+        assert!(!wildcard_match("a*b", "ab-"));
+        assert!(!wildcard_match("aa*a", "aa"));
+    }
+
+    #[test]
+    fn finds_postmasters() {
+        let mut interner = StringInterner::new();
+        let postgres = interner.intern("postgres");
+        let bash = interner.intern("bash");
+        let process = |pid, ppid, name_hash| ProcessInfo {
+            pid,
+            ppid,
+            name_hash,
+            ..Default::default()
+        };
+        let processes = vec![
+            process(100, 1, postgres),
+            process(101, 100, postgres), // checkpointer
+            process(200, 1, postgres),
+            process(300, 1, bash),
+        ];
+        let mut fs = MockFs::new();
+        fs.add_file("/proc/100/cmdline", "postgres\0-D\0/srv/pg/a\0");
+        fs.add_file(
+            "/srv/pg/a/postmaster.pid",
+            "100\n/srv/pg/a\n1700000000\n5433\n/tmp\nlocalhost\n",
+        );
+        // Data directory not readable: port from the arguments
+        fs.add_file("/proc/200/cmdline", "postgres\0-D\0/srv/pg/b\0-p\x005432\0");
+
+        let found = find_postmasters(&fs, "/proc", &processes, &interner);
+        assert_eq!(
+            found,
+            vec![
+                DiscoveredInstance {
+                    pid: 200,
+                    data_dir: "/srv/pg/b".to_string(),
+                    port: 5432,
+                    host: None,
+                },
+                DiscoveredInstance {
+                    pid: 100,
+                    data_dir: "/srv/pg/a".to_string(),
+                    port: 5433,
+                    host: Some("/tmp".to_string()),
+                },
+            ]
+        );
+
+        let discovery = InstanceDiscovery::new(
+            DiscoveryFilter::default(),
+            Box::new(|params| {
+                PostgresCollector::from_params(&PgConnectionParams {
+                    user: Some("rpglot".to_string()),
+                    ..params
+                })
+            }),
+        );
+        let built = discovery.build(&found, &[5432], &["pg/a"]);
+        let names: Vec<&str> = built.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["pg/a:5433"]);
+        assert_eq!(built[0].1.port(), 5433);
+    }
+}
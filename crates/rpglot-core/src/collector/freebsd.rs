@@ -0,0 +1,786 @@
+//! System collector backend for FreeBSD, which has no Linux `/proc`.
+//!
+//! [`SysctlFs`] samples the kernel through sysctl — `kern.cp_times`,
+//! `vm.stats.vm.*`, `vm.loadavg`, `kern.devstat.all`, `kern.proc.proc` —
+//! plus `getifaddrs` for interface counters, and renders the samples as the
+//! `/proc` files the collectors parse. Paths outside `/proc` (`/etc/passwd`)
+//! are read from the real filesystem.
+//!
+//! Linux-only data has no FreeBSD source and is absent: PSI, vmstat, SNMP,
+//! per-process byte I/O (block operation counts are reported as syscr/syscw).
+
+use std::collections::HashSet;
+
+use super::mock::MockFs;
+
+/// Clock ticks per second and page size the `/proc` parsers assume.
+const CLK_TCK: u64 = 100;
+const PAGE_SIZE: u64 = 4096;
+
+/// One sample of the host.
+#[derive(Debug, Default)]
+struct Sample {
+    /// Per-CPU `[user, nice, system, interrupt, idle]` in `stathz` ticks.
+    cp_times: Vec<[u64; 5]>,
+    stathz: u64,
+    boot_time: u64,
+    uptime: u64,
+    loadavg: [f64; 3],
+    mem: MemStats,
+    disks: Vec<DiskStat>,
+    interfaces: Vec<InterfaceStat>,
+    processes: Vec<ProcStat>,
+}
+
+/// Memory counters in bytes.
+#[derive(Debug, Default)]
+struct MemStats {
+    total: u64,
+    free: u64,
+    active: u64,
+    inactive: u64,
+    laundry: u64,
+    buffers: u64,
+    swap_total: u64,
+    swap_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct DiskStat {
+    name: String,
+    reads: u64,
+    writes: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+    read_ms: u64,
+    write_ms: u64,
+    busy_ms: u64,
+}
+
+#[derive(Debug, Default)]
+struct InterfaceStat {
+    name: String,
+    rx_bytes: u64,
+    rx_packets: u64,
+    rx_errors: u64,
+    tx_bytes: u64,
+    tx_packets: u64,
+    tx_errors: u64,
+}
+
+#[derive(Debug, Default)]
+struct ProcStat {
+    pid: u32,
+    ppid: u32,
+    uid: u32,
+    comm: String,
+    state: char,
+    utime_us: u64,
+    stime_us: u64,
+    /// Epoch seconds.
+    start_time: u64,
+    vsize: u64,
+    rss_bytes: u64,
+    threads: u64,
+    minflt: u64,
+    majflt: u64,
+    inblock: u64,
+    oublock: u64,
+    nvcsw: u64,
+    nivcsw: u64,
+    /// NUL-separated arguments, as in `/proc/[pid]/cmdline`.
+    cmdline: String,
+}
+
+/// `/proc` files of `sample`.
+fn render(sample: &Sample) -> MockFs {
+    let mut files = MockFs::new();
+    files.add_file("/proc/stat", render_stat(sample));
+    files.add_file("/proc/meminfo", render_meminfo(&sample.mem));
+    let [one, five, fifteen] = sample.loadavg;
+    let running = sample.processes.iter().filter(|p| p.state == 'R').count();
+    files.add_file(
+        "/proc/loadavg",
+        format!(
+            "{one:.2} {five:.2} {fifteen:.2} {running}/{} 0\n",
+            sample.processes.len()
+        ),
+    );
+    files.add_file("/proc/uptime", format!("{}.00 0.00\n", sample.uptime));
+    files.add_file("/proc/diskstats", render_diskstats(&sample.disks));
+    files.add_file("/proc/net/dev", render_net_dev(&sample.interfaces));
+    for process in &sample.processes {
+        add_process(&mut files, process, sample.boot_time);
+    }
+    files
+}
+
+fn render_stat(sample: &Sample) -> String {
+    let stathz = sample.stathz.max(1);
+    let line = |name: &str, t: &[u64; 5]| {
+        let [user, nice, system, intr, idle] = t.map(|v| v * CLK_TCK / stathz);
+        format!("{name} {user} {nice} {system} {idle} 0 {intr} 0 0 0 0\n")
+    };
+    let total = sample.cp_times.iter().fold([0u64; 5], |mut acc, t| {
+        for (a, v) in acc.iter_mut().zip(t) {
+            *a += v;
+        }
+        acc
+    });
+    let mut out = line("cpu ", &total);
+    for (i, t) in sample.cp_times.iter().enumerate() {
+        out.push_str(&line(&format!("cpu{i}"), t));
+    }
+    let running = sample.processes.iter().filter(|p| p.state == 'R').count();
+    let blocked = sample.processes.iter().filter(|p| p.state == 'D').count();
+    let ctxt: u64 = sample.processes.iter().map(|p| p.nvcsw + p.nivcsw).sum();
+    out.push_str(&format!(
+        "ctxt {ctxt}\nbtime {}\nprocesses {}\nprocs_running {running}\nprocs_blocked {blocked}\n",
+        sample.boot_time,
+        sample.processes.len()
+    ));
+    out
+}
+
+fn render_meminfo(mem: &MemStats) -> String {
+    let kb = |bytes: u64| bytes / 1024;
+    // Inactive and laundry pages are reclaimable, like Linux page cache
+    let cached = mem.inactive + mem.laundry;
+    format!(
+        "MemTotal: {} kB\nMemFree: {} kB\nMemAvailable: {} kB\nBuffers: {} kB\n\
+         Cached: {} kB\nActive: {} kB\nInactive: {} kB\nSwapTotal: {} kB\nSwapFree: {} kB\n",
+        kb(mem.total),
+        kb(mem.free),
+        kb(mem.free + cached),
+        kb(mem.buffers),
+        kb(cached),
+        kb(mem.active),
+        kb(mem.inactive),
+        kb(mem.swap_total),
+        kb(mem.swap_total.saturating_sub(mem.swap_used))
+    )
+}
+
+fn render_diskstats(disks: &[DiskStat]) -> String {
+    disks
+        .iter()
+        .enumerate()
+        .map(|(minor, d)| {
+            format!(
+                "   0 {minor:7} {} {} 0 {} {} {} 0 {} {} 0 {} 0\n",
+                d.name,
+                d.reads,
+                d.read_bytes / 512,
+                d.read_ms,
+                d.writes,
+                d.write_bytes / 512,
+                d.write_ms,
+                d.busy_ms
+            )
+        })
+        .collect()
+}
+
+fn render_net_dev(interfaces: &[InterfaceStat]) -> String {
+    let mut out = String::from(
+        "Inter-|   Receive                                                |  Transmit\n \
+         face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n",
+    );
+    for i in interfaces {
+        out.push_str(&format!(
+            "{:>6}: {} {} {} 0 0 0 0 0 {} {} {} 0 0 0 0 0\n",
+            i.name, i.rx_bytes, i.rx_packets, i.rx_errors, i.tx_bytes, i.tx_packets, i.tx_errors
+        ));
+    }
+    out
+}
+
+fn add_process(files: &mut MockFs, p: &ProcStat, boot_time: u64) {
+    let ticks = |us: u64| us * CLK_TCK / 1_000_000;
+    let start_ticks = p.start_time.saturating_sub(boot_time) * CLK_TCK;
+    let stat = format!(
+        "{pid} ({comm}) {state} {ppid} {pid} {pid} 0 -1 0 {minflt} 0 {majflt} 0 {utime} {stime} \
+         0 0 20 0 {threads} 0 {start_ticks} {vsize} {rss} 18446744073709551615 0 0 0 0 0 0 0 0 \
+         0 0 0 0 17 0 0 0 0 0 0 0 0 0 0 0 0 0 0",
+        pid = p.pid,
+        comm = p.comm,
+        state = p.state,
+        ppid = p.ppid,
+        minflt = p.minflt,
+        majflt = p.majflt,
+        utime = ticks(p.utime_us),
+        stime = ticks(p.stime_us),
+        threads = p.threads.max(1),
+        vsize = p.vsize,
+        rss = p.rss_bytes / PAGE_SIZE
+    );
+    let status = format!(
+        "Name:\t{}\nPid:\t{}\nPPid:\t{}\nUid:\t{uid}\t{uid}\t{uid}\t{uid}\nVmSize:\t{} kB\n\
+         VmRSS:\t{} kB\nThreads:\t{}\nvoluntary_ctxt_switches:\t{}\n\
+         nonvoluntary_ctxt_switches:\t{}\n",
+        p.comm,
+        p.pid,
+        p.ppid,
+        p.vsize / 1024,
+        p.rss_bytes / 1024,
+        p.threads.max(1),
+        p.nvcsw,
+        p.nivcsw,
+        uid = p.uid
+    );
+    let io = format!(
+        "rchar: 0\nwchar: 0\nsyscr: {}\nsyscw: {}\nread_bytes: 0\nwrite_bytes: 0\n\
+         cancelled_write_bytes: 0\n",
+        p.inblock, p.oublock
+    );
+    files.add_process(
+        p.pid,
+        &stat,
+        &status,
+        &io,
+        &p.cmdline,
+        &format!("{}\n", p.comm),
+    );
+}
+
+/// `comm` as a single `/proc/[pid]/stat` token.
+fn comm_token(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != ')' && !c.is_whitespace())
+        .take(19)
+        .collect()
+}
+
+/// Drops repeated names (a disk under several GEOM providers).
+fn dedup_disks(disks: &mut Vec<DiskStat>) {
+    let mut seen = HashSet::new();
+    disks.retain(|d| seen.insert(d.name.clone()));
+}
+
+#[cfg(target_os = "freebsd")]
+pub use self::sysctl_fs::SysctlFs;
+
+#[cfg(target_os = "freebsd")]
+mod sysctl_fs {
+    use std::ffi::{CStr, CString};
+    use std::io;
+    use std::mem::size_of;
+    use std::path::{Path, PathBuf};
+    use std::ptr;
+    use std::sync::{Arc, Mutex, MutexGuard};
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    use libc::{c_char, c_int, c_long, c_void};
+
+    use super::*;
+    use crate::collector::traits::{FileSystem, RealFs};
+
+    /// Rendered files are reused for reads within this interval, so all
+    /// reads of one snapshot see the same sample.
+    const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// [`FileSystem`] serving `/proc` files rendered from sysctl.
+    ///
+    /// Clones share the sampled state, like collectors sharing one `RealFs`.
+    #[derive(Clone)]
+    pub struct SysctlFs {
+        state: Arc<Mutex<(Option<Instant>, MockFs)>>,
+    }
+
+    impl SysctlFs {
+        /// Samples the host once; later reads resample when the previous
+        /// sample is older than 500ms.
+        pub fn new() -> Self {
+            let fs = Self {
+                state: Arc::new(Mutex::new((None, MockFs::new()))),
+            };
+            drop(fs.files());
+            fs
+        }
+
+        fn files(&self) -> MutexGuard<'_, (Option<Instant>, MockFs)> {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if state.0.is_none_or(|at| at.elapsed() >= REFRESH_INTERVAL) {
+                *state = (Some(Instant::now()), render(&sample()));
+            }
+            state
+        }
+    }
+
+    impl Default for SysctlFs {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl FileSystem for SysctlFs {
+        fn read_to_string(&self, path: &Path) -> io::Result<String> {
+            if !path.starts_with("/proc") {
+                return RealFs.read_to_string(path);
+            }
+            self.files().1.read_to_string(path)
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            if !path.starts_with("/proc") {
+                return RealFs.exists(path);
+            }
+            self.files().1.exists(path)
+        }
+
+        fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+            if !path.starts_with("/proc") {
+                return RealFs.read_dir(path);
+            }
+            self.files().1.read_dir(path)
+        }
+    }
+
+    /// Samples everything; parts that fail to read stay empty.
+    fn sample() -> Sample {
+        let boot_time =
+            read_struct::<libc::timeval>("kern.boottime").map_or(0, |tv| tv.tv_sec.max(0) as u64);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let mut disks = disks().unwrap_or_default();
+        dedup_disks(&mut disks);
+        Sample {
+            cp_times: cp_times().unwrap_or_default(),
+            stathz: read_struct::<libc::clockinfo>("kern.clockrate")
+                .map_or(CLK_TCK, |c| c.stathz.max(1) as u64),
+            boot_time,
+            uptime: now.saturating_sub(boot_time),
+            loadavg: loadavg().unwrap_or_default(),
+            mem: mem_stats(),
+            disks,
+            interfaces: interfaces().unwrap_or_default(),
+            processes: processes().unwrap_or_default(),
+        }
+    }
+
+    fn cp_times() -> io::Result<Vec<[u64; 5]>> {
+        let values = read_longs("kern.cp_times")?;
+        Ok(values
+            .chunks_exact(5)
+            .map(|c| std::array::from_fn(|i| c[i].max(0) as u64))
+            .collect())
+    }
+
+    /// `struct loadavg` of `<sys/resource.h>`.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Loadavg {
+        ldavg: [u32; 3],
+        fscale: c_long,
+    }
+
+    fn loadavg() -> io::Result<[f64; 3]> {
+        let load = read_struct::<Loadavg>("vm.loadavg")?;
+        let scale = load.fscale.max(1) as f64;
+        Ok(load.ldavg.map(|v| f64::from(v) / scale))
+    }
+
+    fn mem_stats() -> MemStats {
+        let page = read_uint("hw.pagesize").unwrap_or(PAGE_SIZE);
+        let pages = |name: &str| read_uint(name).unwrap_or(0) * page;
+        let (swap_total, swap_used) = swap().unwrap_or_default();
+        MemStats {
+            total: read_uint("hw.physmem").unwrap_or(0),
+            free: pages("vm.stats.vm.v_free_count"),
+            active: pages("vm.stats.vm.v_active_count"),
+            inactive: pages("vm.stats.vm.v_inactive_count"),
+            laundry: pages("vm.stats.vm.v_laundry_count"),
+            buffers: read_uint("vfs.bufspace").unwrap_or(0),
+            swap_total,
+            swap_used,
+        }
+    }
+
+    /// `struct xswdev` of `<vm/vm_param.h>`.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Xswdev {
+        version: u32,
+        dev: libc::dev_t,
+        flags: c_int,
+        nblks: c_int,
+        used: c_int,
+    }
+
+    /// `(total, used)` swap in bytes over all swap devices.
+    fn swap() -> io::Result<(u64, u64)> {
+        let page = read_uint("hw.pagesize").unwrap_or(PAGE_SIZE);
+        let mut mib = name_to_mib("vm.swap_info")?;
+        mib.push(0);
+        let (mut total, mut used) = (0u64, 0u64);
+        for index in 0.. {
+            *mib.last_mut().unwrap() = index;
+            let Ok(bytes) = read_mib(&mib) else {
+                break;
+            };
+            let Some(dev) = from_bytes::<Xswdev>(&bytes) else {
+                break;
+            };
+            total += dev.nblks.max(0) as u64 * page;
+            used += dev.used.max(0) as u64 * page;
+        }
+        Ok((total, used))
+    }
+
+    /// `struct bintime` of `<sys/_bintime.h>`.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Bintime {
+        sec: libc::time_t,
+        frac: u64,
+    }
+
+    impl Bintime {
+        fn millis(self) -> u64 {
+            self.sec.max(0) as u64 * 1000 + (((self.frac >> 32) * 1000) >> 32)
+        }
+    }
+
+    /// `struct devstat` of `<sys/devicestat.h>` (DEVSTAT_VERSION 6), with
+    /// the enum fields as plain integers.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Devstat {
+        sequence0: u32,
+        allocated: c_int,
+        start_count: u32,
+        end_count: u32,
+        busy_from: Bintime,
+        dev_links: *mut c_void,
+        device_number: u32,
+        device_name: [c_char; 16],
+        unit_number: c_int,
+        bytes: [u64; 4],
+        operations: [u64; 4],
+        duration: [Bintime; 4],
+        busy_time: Bintime,
+        creation_time: Bintime,
+        block_size: u32,
+        tag_types: [u64; 3],
+        flags: u32,
+        device_type: u32,
+        priority: u32,
+        id: *const c_void,
+        sequence1: u32,
+    }
+
+    /// Pass-through devices (`pass0`) duplicate the disks behind them.
+    const DEVSTAT_TYPE_PASS: u32 = 0x100;
+    const DEVSTAT_READ: usize = 1;
+    const DEVSTAT_WRITE: usize = 2;
+
+    fn disks() -> io::Result<Vec<DiskStat>> {
+        // A generation number precedes the array
+        let bytes = read_sysctl("kern.devstat.all")?;
+        let records = bytes.get(size_of::<c_long>()..).unwrap_or_default();
+        Ok(records
+            .chunks_exact(size_of::<Devstat>())
+            .filter_map(from_bytes::<Devstat>)
+            .filter(|d| d.device_type & DEVSTAT_TYPE_PASS == 0)
+            .map(|d| {
+                // SAFETY: device_name is a NUL-terminated array from the kernel
+                let name = unsafe { CStr::from_ptr(d.device_name.as_ptr()) };
+                DiskStat {
+                    name: format!("{}{}", name.to_string_lossy(), d.unit_number),
+                    reads: d.operations[DEVSTAT_READ],
+                    writes: d.operations[DEVSTAT_WRITE],
+                    read_bytes: d.bytes[DEVSTAT_READ],
+                    write_bytes: d.bytes[DEVSTAT_WRITE],
+                    read_ms: d.duration[DEVSTAT_READ].millis(),
+                    write_ms: d.duration[DEVSTAT_WRITE].millis(),
+                    busy_ms: d.busy_time.millis(),
+                }
+            })
+            .collect())
+    }
+
+    fn interfaces() -> io::Result<Vec<InterfaceStat>> {
+        let mut head: *mut libc::ifaddrs = ptr::null_mut();
+        // SAFETY: getifaddrs fills `head` with a list freed below
+        if unsafe { libc::getifaddrs(&mut head) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut interfaces = Vec::new();
+        let mut cur = head;
+        while !cur.is_null() {
+            // SAFETY: `cur` is a node of the list returned by getifaddrs;
+            // AF_LINK entries carry a `struct if_data` in ifa_data
+            unsafe {
+                let ifa = &*cur;
+                if !ifa.ifa_addr.is_null()
+                    && c_int::from((*ifa.ifa_addr).sa_family) == libc::AF_LINK
+                    && !ifa.ifa_data.is_null()
+                {
+                    let data = &*(ifa.ifa_data as *const libc::if_data);
+                    interfaces.push(InterfaceStat {
+                        name: CStr::from_ptr(ifa.ifa_name).to_string_lossy().into_owned(),
+                        rx_bytes: data.ifi_ibytes,
+                        rx_packets: data.ifi_ipackets,
+                        rx_errors: data.ifi_ierrors,
+                        tx_bytes: data.ifi_obytes,
+                        tx_packets: data.ifi_opackets,
+                        tx_errors: data.ifi_oerrors,
+                    });
+                }
+                cur = ifa.ifa_next;
+            }
+        }
+        // SAFETY: `head` came from getifaddrs and is freed once
+        unsafe { libc::freeifaddrs(head) };
+        Ok(interfaces)
+    }
+
+    fn processes() -> io::Result<Vec<ProcStat>> {
+        let bytes = read_mib(&[libc::CTL_KERN, libc::KERN_PROC, libc::KERN_PROC_PROC, 0])?;
+        let micros = |tv: libc::timeval| tv.tv_sec.max(0) as u64 * 1_000_000 + tv.tv_usec as u64;
+        Ok(bytes
+            .chunks_exact(size_of::<libc::kinfo_proc>())
+            .filter_map(from_bytes::<libc::kinfo_proc>)
+            .map(|k| {
+                // SAFETY: ki_comm is a NUL-terminated array from the kernel
+                let name = unsafe { CStr::from_ptr(k.ki_comm.as_ptr()) };
+                let state = match k.ki_stat {
+                    libc::SRUN => 'R',
+                    libc::SSTOP => 'T',
+                    libc::SZOMB => 'Z',
+                    libc::SIDL | libc::SWAIT => 'I',
+                    libc::SLOCK => 'D',
+                    _ => 'S',
+                };
+                let cmdline = read_mib(&[
+                    libc::CTL_KERN,
+                    libc::KERN_PROC,
+                    libc::KERN_PROC_ARGS,
+                    k.ki_pid,
+                ])
+                .map(|args| String::from_utf8_lossy(&args).into_owned())
+                .unwrap_or_default();
+                let ru = k.ki_rusage;
+                ProcStat {
+                    pid: k.ki_pid as u32,
+                    ppid: k.ki_ppid as u32,
+                    uid: k.ki_ruid,
+                    comm: comm_token(&name.to_string_lossy()),
+                    state,
+                    utime_us: micros(ru.ru_utime),
+                    stime_us: micros(ru.ru_stime),
+                    start_time: k.ki_start.tv_sec.max(0) as u64,
+                    vsize: k.ki_size as u64,
+                    rss_bytes: k.ki_rssize.max(0) as u64 * PAGE_SIZE,
+                    threads: k.ki_numthreads.max(0) as u64,
+                    minflt: ru.ru_minflt.max(0) as u64,
+                    majflt: ru.ru_majflt.max(0) as u64,
+                    inblock: ru.ru_inblock.max(0) as u64,
+                    oublock: ru.ru_oublock.max(0) as u64,
+                    nvcsw: ru.ru_nvcsw.max(0) as u64,
+                    nivcsw: ru.ru_nivcsw.max(0) as u64,
+                    cmdline,
+                }
+            })
+            .collect())
+    }
+
+    /// Reads a `T` from the start of `bytes`, if long enough.
+    fn from_bytes<T: Copy>(bytes: &[u8]) -> Option<T> {
+        // SAFETY: callers only use plain C structs, for which any bit
+        // pattern of the right size is valid
+        (bytes.len() >= size_of::<T>())
+            .then(|| unsafe { ptr::read_unaligned(bytes.as_ptr().cast::<T>()) })
+    }
+
+    fn read_struct<T: Copy>(name: &str) -> io::Result<T> {
+        from_bytes(&read_sysctl(name)?)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, name.to_string()))
+    }
+
+    /// An unsigned integer sysctl of either width.
+    fn read_uint(name: &str) -> io::Result<u64> {
+        let bytes = read_sysctl(name)?;
+        match bytes.len() {
+            4 => Ok(u64::from(u32::from_ne_bytes(
+                bytes[..4].try_into().unwrap(),
+            ))),
+            8 => Ok(u64::from_ne_bytes(bytes[..8].try_into().unwrap())),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, name.to_string())),
+        }
+    }
+
+    fn read_longs(name: &str) -> io::Result<Vec<i64>> {
+        Ok(read_sysctl(name)?
+            .chunks_exact(size_of::<c_long>())
+            .filter_map(from_bytes::<c_long>)
+            .map(i64::from)
+            .collect())
+    }
+
+    fn read_sysctl(name: &str) -> io::Result<Vec<u8>> {
+        read_mib(&name_to_mib(name)?)
+    }
+
+    fn name_to_mib(name: &str) -> io::Result<Vec<c_int>> {
+        let cname = CString::new(name).map_err(io::Error::other)?;
+        let mut mib = vec![0 as c_int; 24];
+        let mut len = mib.len();
+        // SAFETY: `mib` has room for `len` entries
+        if unsafe { libc::sysctlnametomib(cname.as_ptr(), mib.as_mut_ptr(), &mut len) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        mib.truncate(len);
+        Ok(mib)
+    }
+
+    /// Reads a sysctl value, retrying while it grows between the size
+    /// query and the read (process list).
+    fn read_mib(mib: &[c_int]) -> io::Result<Vec<u8>> {
+        for _ in 0..4 {
+            let mut len = 0;
+            // SAFETY: a size query with a null buffer
+            let rc = unsafe {
+                libc::sysctl(
+                    mib.as_ptr(),
+                    mib.len() as u32,
+                    ptr::null_mut(),
+                    &mut len,
+                    ptr::null(),
+                    0,
+                )
+            };
+            if rc != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            len += len / 8;
+            let mut buf = vec![0u8; len];
+            // SAFETY: `buf` holds `len` bytes
+            let rc = unsafe {
+                libc::sysctl(
+                    mib.as_ptr(),
+                    mib.len() as u32,
+                    buf.as_mut_ptr().cast(),
+                    &mut len,
+                    ptr::null(),
+                    0,
+                )
+            };
+            if rc == 0 {
+                buf.truncate(len);
+                return Ok(buf);
+            }
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() != Some(libc::ENOMEM) {
+                return Err(err);
+            }
+        }
+        Err(io::Error::other("sysctl value keeps growing"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::Collector;
+    use crate::storage::model::DataBlock;
+
+    fn sample() -> Sample {
+        Sample {
+            cp_times: vec![[254, 0, 127, 0, 889], [127, 127, 0, 0, 1016]],
+            stathz: 127,
+            boot_time: 1_700_000_000,
+            uptime: 3600,
+            loadavg: [0.5, 0.25, 0.1],
+            mem: MemStats {
+                total: 8 << 30,
+                free: 2 << 30,
+                inactive: 1 << 30,
+                swap_total: 2 << 30,
+                swap_used: 1 << 20,
+                ..Default::default()
+            },
+            disks: vec![DiskStat {
+                name: "ada0".into(),
+                reads: 10,
+                read_bytes: 5120,
+                ..Default::default()
+            }],
+            interfaces: vec![InterfaceStat {
+                name: "em0".into(),
+                rx_bytes: 1000,
+                ..Default::default()
+            }],
+            processes: vec![ProcStat {
+                pid: 42,
+                ppid: 1,
+                uid: 770,
+                comm: comm_token("postgres"),
+                state: 'S',
+                utime_us: 2_000_000,
+                start_time: 1_700_000_100,
+                rss_bytes: 8 << 20,
+                threads: 1,
+                cmdline: "postgres\0-D\0/var/db/postgres\0".into(),
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn collector_parses_rendered_files() {
+        let mut collector = Collector::new(render(&sample()), "/proc");
+        let snapshot = collector.collect_snapshot().unwrap();
+        let block = |f: fn(&DataBlock) -> bool| snapshot.blocks.iter().find(|b| f(b)).unwrap();
+
+        let DataBlock::Processes(processes) = block(|b| matches!(b, DataBlock::Processes(_)))
+        else {
+            unreachable!()
+        };
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].pid, 42);
+        assert_eq!(processes[0].ppid, 1);
+        assert_eq!(processes[0].uid, 770);
+
+        let DataBlock::SystemMem(mem) = block(|b| matches!(b, DataBlock::SystemMem(_))) else {
+            unreachable!()
+        };
+        assert_eq!(mem.total, 8 << 20);
+        assert_eq!(mem.available, 3 << 20);
+
+        let DataBlock::SystemCpu(cpus) = block(|b| matches!(b, DataBlock::SystemCpu(_))) else {
+            unreachable!()
+        };
+        // Aggregate line plus one per CPU, converted from stathz to 100 Hz
+        assert_eq!(cpus.len(), 3);
+        assert_eq!(cpus[1].user, 200);
+        assert_eq!(cpus[1].system, 100);
+
+        assert!(matches!(
+            block(|b| matches!(b, DataBlock::SystemDisk(_))),
+            DataBlock::SystemDisk(d) if d[0].rio == 10 && d[0].rsz == 10
+        ));
+        assert!(matches!(
+            block(|b| matches!(b, DataBlock::SystemNet(_))),
+            DataBlock::SystemNet(n) if n[0].rx_bytes == 1000
+        ));
+    }
+
+    #[test]
+    fn disks_deduplicated_by_name() {
+        let mut disks = vec![
+            DiskStat {
+                name: "ada0".into(),
+                ..Default::default()
+            },
+            DiskStat {
+                name: "ada0".into(),
+                ..Default::default()
+            },
+        ];
+        dedup_disks(&mut disks);
+        assert_eq!(disks.len(), 1);
+        assert_eq!(comm_token("my proc)"), "myproc");
+    }
+}
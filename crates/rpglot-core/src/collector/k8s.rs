@@ -0,0 +1,178 @@
+//! Kubernetes pod metadata for container mode.
+//!
+//! Kubernetes does not tell a container which pod it belongs to unless the
+//! manifest asks for it through the downward API. Both forms are read:
+//!
+//! - environment variables `POD_NAME`, `POD_NAMESPACE`, `NODE_NAME`,
+//!   `CPU_REQUEST`, `CPU_LIMIT`, `MEMORY_REQUEST`, `MEMORY_LIMIT`;
+//! - a `downwardAPI` volume (default `/etc/podinfo`) with the files `name`,
+//!   `namespace`, `nodename`, `cpu_request`, `cpu_limit`, `mem_request`,
+//!   `mem_limit`.
+//!
+//! CPU values are expected with `divisor: 1m` (millicores); quantities with
+//! a suffix (`500m`, `0.5`, `2`) are understood too. Without a declared
+//! limit the downward API reports the node's allocatable resources, so such
+//! limits look declared.
+
+use std::path::{Path, PathBuf};
+
+use crate::storage::model::K8sPodInfo;
+
+/// Service account namespace file, mounted in every pod by default.
+const SERVICE_ACCOUNT_NAMESPACE: &str = "/var/run/secrets/kubernetes.io/serviceaccount/namespace";
+
+/// Parses a CPU quantity into millicores: `500` (downward API with
+/// `divisor: 1m`), `500m` or `0.5`.
+pub fn parse_cpu_millis(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if let Some(millis) = value.strip_suffix('m') {
+        return millis.parse().ok();
+    }
+    if value.contains('.') {
+        let cores: f64 = value.parse().ok()?;
+        return (cores >= 0.0).then(|| (cores * 1000.0).round() as u64);
+    }
+    value.parse().ok()
+}
+
+/// Parses a memory quantity into bytes: `536870912`, `512Mi`, `1G`.
+pub fn parse_memory_bytes(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(split);
+    let multiplier: u64 = match suffix {
+        "" => 1,
+        "Ki" => 1 << 10,
+        "Mi" => 1 << 20,
+        "Gi" => 1 << 30,
+        "Ti" => 1 << 40,
+        "k" | "K" => 1_000,
+        "M" => 1_000_000,
+        "G" => 1_000_000_000,
+        "T" => 1_000_000_000_000,
+        _ => return None,
+    };
+    number.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Builds the pod info from `env` (variable lookup) and the downward API
+/// volume in `podinfo_dir`. Returns `None` when the pod name or namespace
+/// is unknown.
+pub fn read_pod_info(
+    env: impl Fn(&str) -> Option<String>,
+    podinfo_dir: &Path,
+) -> Option<K8sPodInfo> {
+    let value = |var: &str, file: &str| {
+        env(var)
+            .or_else(|| std::fs::read_to_string(podinfo_dir.join(file)).ok())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let pod = value("POD_NAME", "name").or_else(|| env("HOSTNAME"))?;
+    let namespace = value("POD_NAMESPACE", "namespace")
+        .or_else(|| {
+            std::fs::read_to_string(SERVICE_ACCOUNT_NAMESPACE)
+                .ok()
+                .map(|ns| ns.trim().to_string())
+        })
+        .filter(|ns| !ns.is_empty())?;
+    let cpu = |var, file| {
+        value(var, file)
+            .and_then(|v| parse_cpu_millis(&v))
+            .unwrap_or(0)
+    };
+    let memory = |var, file| {
+        value(var, file)
+            .and_then(|v| parse_memory_bytes(&v))
+            .unwrap_or(0)
+    };
+    Some(K8sPodInfo {
+        pod,
+        namespace,
+        node: value("NODE_NAME", "nodename").unwrap_or_default(),
+        cpu_request_millis: cpu("CPU_REQUEST", "cpu_request"),
+        cpu_limit_millis: cpu("CPU_LIMIT", "cpu_limit"),
+        memory_request: memory("MEMORY_REQUEST", "mem_request"),
+        memory_limit: memory("MEMORY_LIMIT", "mem_limit"),
+    })
+}
+
+/// Collects the metadata of the pod rpglotd runs in.
+pub struct K8sCollector {
+    podinfo_dir: PathBuf,
+}
+
+impl K8sCollector {
+    /// Collector reading the downward API volume in `podinfo_dir`, or `None`
+    /// when not running in Kubernetes (`KUBERNETES_SERVICE_HOST` unset).
+    pub fn detect(podinfo_dir: impl Into<PathBuf>) -> Option<Self> {
+        std::env::var_os("KUBERNETES_SERVICE_HOST")?;
+        Some(Self {
+            podinfo_dir: podinfo_dir.into(),
+        })
+    }
+
+    /// Reads the pod metadata. Volume files are re-read every time: the
+    /// kubelet updates them on in-place resource resize.
+    pub fn collect(&self) -> Option<K8sPodInfo> {
+        read_pod_info(|var| std::env::var(var).ok(), &self.podinfo_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parses_quantities() {
+        assert_eq!(parse_cpu_millis("500"), Some(500));
+        assert_eq!(parse_cpu_millis("250m"), Some(250));
+        assert_eq!(parse_cpu_millis("1.5"), Some(1500));
+        assert_eq!(parse_cpu_millis("lots"), None);
+
+        assert_eq!(parse_memory_bytes("536870912\n"), Some(536_870_912));
+        assert_eq!(parse_memory_bytes("512Mi"), Some(512 << 20));
+        assert_eq!(parse_memory_bytes("2Gi"), Some(2 << 30));
+        assert_eq!(parse_memory_bytes("1G"), Some(1_000_000_000));
+        assert_eq!(parse_memory_bytes("1Xi"), None);
+    }
+
+    #[test]
+    fn reads_env_and_volume() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("name"), "pg-0\n").unwrap();
+        std::fs::write(dir.path().join("namespace"), "db\n").unwrap();
+        std::fs::write(dir.path().join("cpu_limit"), "2000\n").unwrap();
+        std::fs::write(dir.path().join("mem_limit"), "4294967296\n").unwrap();
+        let env: HashMap<&str, &str> = [
+            ("HOSTNAME", "ignored"),
+            ("NODE_NAME", "node-3"),
+            ("CPU_REQUEST", "500m"),
+            ("MEMORY_REQUEST", "1Gi"),
+        ]
+        .into();
+        let lookup = |var: &str| env.get(var).map(|v| v.to_string());
+
+        let info = read_pod_info(lookup, dir.path()).unwrap();
+        assert_eq!(
+            info,
+            K8sPodInfo {
+                pod: "pg-0".into(),
+                namespace: "db".into(),
+                node: "node-3".into(),
+                cpu_request_millis: 500,
+                cpu_limit_millis: 2000,
+                memory_request: 1 << 30,
+                memory_limit: 4 << 30,
+            }
+        );
+
+        // Pod name falls back to the hostname
+        std::fs::remove_file(dir.path().join("name")).unwrap();
+        assert_eq!(read_pod_info(lookup, dir.path()).unwrap().pod, "ignored");
+    }
+}
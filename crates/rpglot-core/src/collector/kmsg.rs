@@ -0,0 +1,225 @@
+//! Kernel log events from `/dev/kmsg`.
+//!
+//! Each read of `/dev/kmsg` returns one record:
+//! `priority,sequence,timestamp_us,flags;message` followed by optional
+//! `KEY=value` continuation lines. The collector opens the device once,
+//! skips the records already in the ring buffer and on every snapshot reads
+//! the new ones, keeping only the events that matter for a database host:
+//! OOM kills, I/O errors and filesystems remounted read-only.
+//!
+//! Reading `/dev/kmsg` requires `CAP_SYSLOG` when `kernel.dmesg_restrict=1`.
+
+use crate::storage::model::{KernelEventInfo, KernelEventKind};
+
+/// Maximum events kept per snapshot; a failing disk can log thousands of
+/// I/O errors per second.
+pub const MAX_KERNEL_EVENTS: usize = 100;
+
+/// A parsed `/dev/kmsg` record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KmsgRecord<'a> {
+    /// Syslog priority and facility (`facility << 3 | level`).
+    pub priority: u32,
+    /// Sequence number.
+    pub seq: u64,
+    /// Monotonic timestamp since boot (microseconds).
+    pub timestamp_us: u64,
+    /// First line of the message text.
+    pub message: &'a str,
+}
+
+/// Parses one `/dev/kmsg` record. Returns `None` for malformed records.
+pub fn parse_kmsg_record(record: &str) -> Option<KmsgRecord<'_>> {
+    let (header, text) = record.split_once(';')?;
+    let mut fields = header.split(',');
+    let priority = fields.next()?.parse().ok()?;
+    let seq = fields.next()?.parse().ok()?;
+    let timestamp_us = fields.next()?.parse().ok()?;
+    let message = text.lines().next().unwrap_or("").trim_end();
+    Some(KmsgRecord {
+        priority,
+        seq,
+        timestamp_us,
+        message,
+    })
+}
+
+/// Classifies a kernel message. Returns the event kind and, for OOM kills,
+/// the killed process ID and name.
+pub fn classify_kernel_message(message: &str) -> Option<(KernelEventKind, u32, String)> {
+    // "Out of memory: Killed process 1234 (postgres) total-vm:..." and
+    // "Memory cgroup out of memory: Killed process 1234 (postgres) ..."
+    if let Some(pos) = message.find("Killed process ") {
+        let rest = &message[pos + "Killed process ".len()..];
+        let (pid, rest) = rest.split_once(' ')?;
+        let pid = pid.parse().ok()?;
+        let comm = rest
+            .strip_prefix('(')
+            .and_then(|r| r.split_once(')'))
+            .map(|(comm, _)| comm.to_string())
+            .unwrap_or_default();
+        return Some((KernelEventKind::OomKill, pid, comm));
+    }
+
+    let lower = message.to_ascii_lowercase();
+    // ext4: "Remounting filesystem read-only"; btrfs: "forced readonly";
+    // xfs: "Filesystem has been shut down due to log error"
+    if lower.contains("remounting filesystem read-only")
+        || lower.contains("forced readonly")
+        || lower.contains("filesystem has been shut down")
+    {
+        return Some((KernelEventKind::FsReadOnly, 0, String::new()));
+    }
+    // "I/O error, dev sda, sector ..." and "Buffer I/O error on dev sda1, ..."
+    if message.contains("I/O error") {
+        return Some((KernelEventKind::IoError, 0, String::new()));
+    }
+    None
+}
+
+/// Converts a record into an event if it is one of the tracked kinds.
+///
+/// `boot_time` is the system boot time (seconds since epoch) from `/proc/stat`.
+pub fn kernel_event(record: &KmsgRecord<'_>, boot_time: u64) -> Option<KernelEventInfo> {
+    let (kind, pid, comm) = classify_kernel_message(record.message)?;
+    Some(KernelEventInfo {
+        kind,
+        timestamp: (boot_time + record.timestamp_us / 1_000_000) as i64,
+        pid,
+        comm,
+        message: record.message.to_string(),
+        pg_role: None,
+    })
+}
+
+#[cfg(target_os = "linux")]
+pub use self::reader::KmsgCollector;
+
+#[cfg(target_os = "linux")]
+mod reader {
+    use std::fs::{File, OpenOptions};
+    use std::io::{ErrorKind, Read, Seek, SeekFrom};
+    use std::os::unix::fs::OpenOptionsExt;
+
+    use tracing::warn;
+
+    use super::{MAX_KERNEL_EVENTS, kernel_event, parse_kmsg_record};
+    use crate::storage::model::KernelEventInfo;
+
+    const KMSG_PATH: &str = "/dev/kmsg";
+
+    /// Records are limited to 1 KiB of text plus continuation lines.
+    const RECORD_BUF_SIZE: usize = 8192;
+
+    /// Non-blocking reader of new `/dev/kmsg` records.
+    pub struct KmsgCollector {
+        file: File,
+        buf: Vec<u8>,
+    }
+
+    impl KmsgCollector {
+        /// Opens `/dev/kmsg` and skips the records already in the ring buffer.
+        pub fn open() -> Result<Self, String> {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .custom_flags(libc::O_NONBLOCK)
+                .open(KMSG_PATH)
+                .map_err(|e| format!("failed to open {KMSG_PATH}: {e}"))?;
+            file.seek(SeekFrom::End(0))
+                .map_err(|e| format!("failed to seek {KMSG_PATH}: {e}"))?;
+            Ok(Self {
+                file,
+                buf: vec![0; RECORD_BUF_SIZE],
+            })
+        }
+
+        /// Reads all records logged since the previous call and returns the
+        /// tracked events (at most [`MAX_KERNEL_EVENTS`]).
+        pub fn drain(&mut self, boot_time: u64) -> Vec<KernelEventInfo> {
+            let mut events = Vec::new();
+            loop {
+                match self.file.read(&mut self.buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        let text = String::from_utf8_lossy(&self.buf[..n]);
+                        if let Some(event) =
+                            parse_kmsg_record(&text).and_then(|r| kernel_event(&r, boot_time))
+                            && events.len() < MAX_KERNEL_EVENTS
+                        {
+                            events.push(event);
+                        }
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                    // Records were overwritten before we read them; the next
+                    // read continues from the oldest available one.
+                    Err(e) if e.kind() == ErrorKind::BrokenPipe => continue,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        warn!(error = %e, "failed to read {KMSG_PATH}");
+                        break;
+                    }
+                }
+            }
+            events
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_record_header_and_first_line() {
+        let r = parse_kmsg_record(
+            "3,1234,5140900,-;Out of memory: Killed process 4242 (postgres) total-vm:812344kB\n SUBSYSTEM=memory\n",
+        )
+        .unwrap();
+        assert_eq!(r.priority, 3);
+        assert_eq!(r.seq, 1234);
+        assert_eq!(r.timestamp_us, 5_140_900);
+        assert_eq!(
+            r.message,
+            "Out of memory: Killed process 4242 (postgres) total-vm:812344kB"
+        );
+
+        assert!(parse_kmsg_record("garbage").is_none());
+        assert!(parse_kmsg_record("x,1,2,-;msg").is_none());
+    }
+
+    #[test]
+    fn classifies_tracked_messages() {
+        assert_eq!(
+            classify_kernel_message(
+                "Memory cgroup out of memory: Killed process 77 (postgres: writer) total-vm:1kB"
+            ),
+            Some((KernelEventKind::OomKill, 77, "postgres: writer".to_string()))
+        );
+        assert_eq!(
+            classify_kernel_message("Killed process 5 (java)"),
+            Some((KernelEventKind::OomKill, 5, "java".to_string()))
+        );
+        assert_eq!(
+            classify_kernel_message(
+                "blk_update_request: I/O error, dev sdb, sector 2048 op 0x1:(WRITE)"
+            )
+            .map(|e| e.0),
+            Some(KernelEventKind::IoError)
+        );
+        assert_eq!(
+            classify_kernel_message("EXT4-fs (sdb1): Remounting filesystem read-only").map(|e| e.0),
+            Some(KernelEventKind::FsReadOnly)
+        );
+        assert_eq!(classify_kernel_message("eth0: link up"), None);
+    }
+
+    #[test]
+    fn event_timestamp_is_wall_clock() {
+        let r = parse_kmsg_record("6,1,90500000,-;Buffer I/O error on dev sdb1").unwrap();
+        let e = kernel_event(&r, 1_700_000_000).unwrap();
+        assert_eq!(e.kind, KernelEventKind::IoError);
+        assert_eq!(e.timestamp, 1_700_000_090);
+        assert_eq!(e.pid, 0);
+        assert!(e.pg_role.is_none());
+    }
+}
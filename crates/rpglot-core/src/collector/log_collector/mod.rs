@@ -1,8 +1,8 @@
 //! PostgreSQL log file collector.
 //!
-//! Reads PostgreSQL log files (stderr or csvlog), parses ERROR/FATAL/PANIC
+//! Reads PostgreSQL logs (stderr or csvlog files, syslog, journald), parses ERROR/FATAL/PANIC
 //! entries, normalizes messages into patterns, and groups them for storage
-//! in snapshots.
+//! in snapshots. Plans logged by `auto_explain` are kept per query.
 
 pub mod normalize;
 pub mod parser;
@@ -15,12 +15,13 @@ use std::time::Instant;
 
 use postgres::Client;
 
+use crate::collector::RedactMode;
 use crate::storage::interner::StringInterner;
-use crate::storage::model::{PgLogEventEntry, PgLogEventType, PgLogSeverity};
+use crate::storage::model::{PgLogEventEntry, PgLogEventType, PgLogSeverity, PgSlowPlanInfo};
 
 use normalize::{MAX_LOG_MESSAGE_LEN, normalize_error};
-use parser::{CsvlogParser, EventData, LogEventKind, ParsedLogLine, StderrParser};
-use tailer::FileTailer;
+use parser::{ConnectionStage, CsvlogParser, EventData, LogEventKind, ParsedLogLine, StderrParser};
+use tailer::{FileTailer, LogSource, LogSourceConfig};
 
 /// Result of a log collection cycle.
 #[derive(Default)]
@@ -35,8 +36,37 @@ pub struct LogCollectResult {
     pub slow_query_count: u16,
     /// Detailed checkpoint/autovacuum/slow query event entries for snapshot storage.
     pub events: Vec<PgLogEventEntry>,
+    /// Connection lifecycle counters (requires `log_connections`/`log_disconnections`).
+    pub connections: ConnectionCounts,
+    /// Plans captured by auto_explain, slowest first.
+    pub slow_plans: Vec<PgSlowPlanInfo>,
 }
 
+/// Connection lifecycle counters accumulated between snapshots.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConnectionCounts {
+    /// `connection received` lines.
+    pub received: u32,
+    /// `connection authorized` lines.
+    pub authorized: u32,
+    /// `disconnection` lines.
+    pub disconnections: u32,
+    /// Disconnections with session time below [`SHORT_LIVED_SESSION_SECS`].
+    pub short_lived: u32,
+    /// Sum of session times of disconnected sessions (seconds).
+    pub total_session_s: f64,
+}
+
+impl ConnectionCounts {
+    /// Returns true if no connection lines were seen.
+    pub fn is_empty(&self) -> bool {
+        self.received == 0 && self.authorized == 0 && self.disconnections == 0
+    }
+}
+
+/// Sessions shorter than this are counted as short-lived (connect-per-query pattern).
+pub const SHORT_LIVED_SESSION_SECS: f64 = 1.0;
+
 /// Maximum number of unique error patterns kept per snapshot interval.
 const MAX_LOG_PATTERNS_PER_SNAPSHOT: usize = 32;
 
@@ -46,6 +76,12 @@ const MAX_SLOW_QUERY_LEN: usize = 1024;
 /// Maximum number of slow queries kept per snapshot (top-N by duration).
 const MAX_SLOW_QUERIES_PER_SNAPSHOT: usize = 16;
 
+/// Maximum plan text length stored per auto_explain plan (bytes).
+const MAX_SLOW_PLAN_LEN: usize = 16 * 1024;
+
+/// Maximum number of auto_explain plans kept per snapshot (top-N by duration).
+const MAX_SLOW_PLANS_PER_SNAPSHOT: usize = 16;
+
 /// How often to re-check pg_current_logfile() for rotation (seconds).
 const LOG_ROTATION_CHECK_SECS: u64 = 60;
 
@@ -77,6 +113,82 @@ struct GroupedSlowQuery {
     count: u16,
 }
 
+/// auto_explain plan being accumulated from continuation lines.
+#[derive(Default)]
+struct PendingSlowPlan {
+    duration_ms: f64,
+    queryid: i64,
+    query: String,
+    plan: String,
+    /// Continuation lines still belong to the multiline `Query Text:`.
+    in_query_text: bool,
+    truncated: bool,
+}
+
+impl PendingSlowPlan {
+    /// Consumes one continuation line of the text-format auto_explain output:
+    /// `Query Text:` (possibly multiline, up to the first plan node),
+    /// `Query Identifier:` and the plan itself.
+    fn push_line(&mut self, line: &str) {
+        // stderr indents every line of a multiline message with a tab
+        let line = line.strip_prefix('\t').unwrap_or(line).trim_end();
+        if let Some(text) = line.strip_prefix("Query Text: ") {
+            self.query = text.trim().to_string();
+            self.query.truncate(MAX_SLOW_QUERY_LEN);
+            self.in_query_text = true;
+            return;
+        }
+        if let Some(id) = line.strip_prefix("Query Identifier: ") {
+            self.queryid = id.trim().parse().unwrap_or(0);
+            self.in_query_text = false;
+            return;
+        }
+        // The top plan node always carries cost estimates
+        if self.in_query_text && !line.contains("(cost=") {
+            let trimmed = line.trim();
+            if self.query.len() + 1 + trimmed.len() <= MAX_SLOW_QUERY_LEN {
+                self.query.push(' ');
+                self.query.push_str(trimmed);
+            }
+            return;
+        }
+        self.in_query_text = false;
+        // JSON format: the plan is kept as-is, the identifier is still useful
+        if let Some(id) = line.trim_start().strip_prefix("\"Query Identifier\": ") {
+            self.queryid = id.trim_end_matches(',').parse().unwrap_or(0);
+        }
+        if self.truncated {
+            return;
+        }
+        if self.plan.len() + 1 + line.len() > MAX_SLOW_PLAN_LEN {
+            self.plan.push_str("\n...");
+            self.truncated = true;
+        } else {
+            if !self.plan.is_empty() {
+                self.plan.push('\n');
+            }
+            self.plan.push_str(line);
+        }
+    }
+}
+
+/// Grouping key of auto_explain plans: queryid when logged, else normalized text.
+#[derive(Clone, Hash, PartialEq, Eq)]
+enum SlowPlanKey {
+    QueryId(i64),
+    Text(String),
+}
+
+/// auto_explain plans of one query in the interval.
+struct GroupedSlowPlan {
+    queryid: i64,
+    /// Slowest execution (ms); `query` and `plan` belong to it.
+    duration_ms: f64,
+    count: u32,
+    query: String,
+    plan: String,
+}
+
 /// Log format detected from `log_destination` setting.
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum LogFormat {
@@ -89,13 +201,16 @@ enum LogFormat {
 /// Reads the current log file via tail, parses error lines, normalizes
 /// messages into patterns, and returns grouped entries for each snapshot.
 pub struct LogCollector {
-    tailer: Option<FileTailer>,
+    /// Where log lines come from (file, syslog or journald).
+    source_config: LogSourceConfig,
+    source: Option<LogSource>,
     stderr_parser: Option<StderrParser>,
     csvlog_parser: Option<CsvlogParser>,
     log_format: Option<LogFormat>,
     /// Cached PG settings
     data_directory: Option<String>,
     log_directory: Option<String>,
+    log_filename: Option<String>,
     log_line_prefix: Option<String>,
     /// Last time we refreshed settings from PG
     settings_last_check: Option<Instant>,
@@ -109,6 +224,8 @@ pub struct LogCollector {
     pending_autovacuums: u16,
     /// Accumulated detailed event entries between snapshots.
     pending_events: Vec<PgLogEventEntry>,
+    /// Accumulated connection lifecycle counters between snapshots.
+    pending_connections: ConnectionCounts,
     /// Index of the last autovacuum/checkpoint event in pending_events.
     /// Used to patch-in metrics from continuation lines (stderr multiline messages).
     /// Reset to None when a non-continuation line arrives.
@@ -128,8 +245,14 @@ pub struct LogCollector {
     slow_queries: HashMap<String, GroupedSlowQuery>,
     /// Total count of slow queries detected in this interval.
     slow_query_count: u16,
+    /// auto_explain plan being accumulated (plan on continuation lines).
+    pending_slow_plan: Option<PendingSlowPlan>,
+    /// Top-N auto_explain plans grouped by query (bounded to MAX_SLOW_PLANS_PER_SNAPSHOT).
+    slow_plans: HashMap<SlowPlanKey, GroupedSlowPlan>,
     /// Last initialization error (for diagnostics)
     last_error: Option<String>,
+    /// Redaction of SQL statements and error samples.
+    redact: RedactMode,
 }
 
 impl Default for LogCollector {
@@ -142,12 +265,14 @@ impl LogCollector {
     /// Create a new uninitialized log collector.
     pub fn new() -> Self {
         Self {
-            tailer: None,
+            source_config: LogSourceConfig::default(),
+            source: None,
             stderr_parser: None,
             csvlog_parser: None,
             log_format: None,
             data_directory: None,
             log_directory: None,
+            log_filename: None,
             log_line_prefix: None,
             settings_last_check: None,
             rotation_last_check: None,
@@ -155,6 +280,7 @@ impl LogCollector {
             pending_checkpoints: 0,
             pending_autovacuums: 0,
             pending_events: Vec::new(),
+            pending_connections: ConnectionCounts::default(),
             last_event_idx: None,
             last_error_key: None,
             error_held_back: false,
@@ -162,7 +288,24 @@ impl LogCollector {
             pending_slow_query: None,
             slow_queries: HashMap::new(),
             slow_query_count: 0,
+            pending_slow_plan: None,
+            slow_plans: HashMap::new(),
             last_error: None,
+            redact: RedactMode::Off,
+        }
+    }
+
+    /// Set how SQL statements and raw error messages are redacted.
+    /// With redaction active, the error sample is the normalized pattern.
+    pub fn set_redaction(&mut self, mode: RedactMode) {
+        self.redact = mode;
+    }
+
+    /// Set the log line source. Takes effect on the next `init()`.
+    pub fn set_source(&mut self, config: LogSourceConfig) {
+        if self.source_config != config {
+            self.source_config = config;
+            self.source = None;
         }
     }
 
@@ -176,17 +319,19 @@ impl LogCollector {
         // Read PG settings
         let data_directory = show_setting(client, "data_directory");
         let log_directory = show_setting(client, "log_directory");
+        let log_filename = show_setting(client, "log_filename");
         let log_line_prefix = show_setting(client, "log_line_prefix");
         let log_destination = show_setting(client, "log_destination");
 
         self.data_directory = data_directory;
         self.log_directory = log_directory;
+        self.log_filename = log_filename;
         self.log_line_prefix = log_line_prefix.clone();
         self.settings_last_check = Some(Instant::now());
 
-        // Determine log format
+        // Determine log format (syslog/journald always carry stderr-formatted lines)
         let dest = log_destination.as_deref().unwrap_or("stderr");
-        self.log_format = if dest.contains("csvlog") {
+        self.log_format = if self.source_config.is_file() && dest.contains("csvlog") {
             Some(LogFormat::Csvlog)
         } else {
             Some(LogFormat::Stderr)
@@ -206,9 +351,17 @@ impl LogCollector {
             None => {}
         }
 
-        // Locate current log file
-        if let Err(e) = self.locate_log_file(client) {
-            self.last_error = Some(e);
+        // Locate current log file, or open the configured non-file source once
+        if self.source_config.is_file() {
+            if let Err(e) = self.locate_log_file(client) {
+                self.last_error = Some(e);
+            }
+        } else if self.source.is_none() {
+            match self.source_config.open() {
+                Some(Ok(source)) => self.source = Some(source),
+                Some(Err(e)) => self.last_error = Some(format!("log source: {}", e)),
+                None => {}
+            }
         }
 
         self.rotation_last_check = Some(Instant::now());
@@ -227,6 +380,7 @@ impl LogCollector {
     ) -> LogCollectResult {
         // Periodic rotation check
         if let Some(last) = self.rotation_last_check
+            && self.source_config.is_file()
             && last.elapsed().as_secs() >= LOG_ROTATION_CHECK_SECS
         {
             let _ = self.check_log_rotation(client);
@@ -240,9 +394,9 @@ impl LogCollector {
             self.init(client);
         }
 
-        // Read new lines from log file
-        let lines = match &mut self.tailer {
-            Some(tailer) => match tailer.read_new_lines() {
+        // Read new lines from log source
+        let lines = match &mut self.source {
+            Some(source) => match source.read_new_lines() {
                 Ok(lines) => lines,
                 Err(_) => return LogCollectResult::default(),
             },
@@ -251,81 +405,24 @@ impl LogCollector {
 
         // Parse and accumulate
         for line in &lines {
-            // Continuation line (starts with whitespace): try to patch last event in-place
-            if is_continuation_line(line) {
-                // Multiline STATEMENT continuation — append SQL text to pending error
-                if let Some(ref key) = self.pending_statement_key {
-                    if let Some(entry) = self.pending_errors.get_mut(key) {
-                        let trimmed = line.trim();
-                        if entry.statement.len() + 1 + trimmed.len() <= MAX_LOG_MESSAGE_LEN {
-                            if !entry.statement.is_empty() {
-                                entry.statement.push(' ');
-                            }
-                            entry.statement.push_str(trimmed);
-                        }
-                    }
-                    continue;
-                }
-                // Slow query multiline continuation — append SQL text
-                if let Some(ref mut pending) = self.pending_slow_query {
-                    if !pending.truncated {
-                        let trimmed = line.trim();
-                        if pending.sql.len() + 1 + trimmed.len() > MAX_SLOW_QUERY_LEN {
-                            pending.sql.truncate(MAX_SLOW_QUERY_LEN);
-                            pending.truncated = true;
-                        } else {
-                            pending.sql.push(' ');
-                            pending.sql.push_str(trimmed);
-                        }
-                    }
-                    continue;
-                }
-                if let Some(idx) = self.last_event_idx
-                    && let Some(entry) = self.pending_events.get_mut(idx)
-                {
-                    patch_event_from_continuation(entry, line);
-                }
-                continue;
-            }
-
-            // New primary line — flush pending slow query and reset continuation tracking
-            self.flush_pending_slow_query();
-            self.pending_statement_key = None;
-            self.last_event_idx = None;
-
-            let parsed = self.parse_line(line);
-            let Some(parsed) = parsed else {
-                // Unrecognized line (WARNING, NOTICE, other LOG, etc.) — reset error tracking
-                self.last_error_key = None;
-                continue;
-            };
-
-            // Remember index for events that have multiline continuations
-            let will_have_continuations = matches!(
-                parsed.event_kind,
-                LogEventKind::Autovacuum | LogEventKind::Checkpoint
-            );
-
-            self.accumulate(parsed);
-
-            if will_have_continuations {
-                self.last_event_idx = Some(self.pending_events.len() - 1);
-            }
+            self.process_line(line);
         }
 
-        // Flush last pending slow query before drain
+        // Flush last pending slow query and plan before drain
         self.flush_pending_slow_query();
+        self.flush_pending_slow_plan();
 
         // Drain accumulated data
         let errors = self.drain_pending(interner);
         let checkpoint_count = self.pending_checkpoints;
         let autovacuum_count = self.pending_autovacuums;
         let slow_query_count = self.slow_query_count;
+        let connections = mem::take(&mut self.pending_connections);
         let mut events = mem::take(&mut self.pending_events);
         for (_, group) in self.slow_queries.drain() {
             events.push(PgLogEventEntry {
                 event_type: PgLogEventType::SlowQuery,
-                message: group.sample_sql,
+                message: self.redact.apply(&group.sample_sql).into_owned(),
                 table_name: String::new(),
                 elapsed_s: group.max_elapsed_s,
                 extra_num1: 0,
@@ -344,6 +441,7 @@ impl LogCollector {
                 count: group.count,
             });
         }
+        let slow_plans = self.drain_slow_plans(interner);
         self.pending_checkpoints = 0;
         self.pending_autovacuums = 0;
         self.slow_query_count = 0;
@@ -357,6 +455,79 @@ impl LogCollector {
             autovacuum_count,
             slow_query_count,
             events,
+            connections,
+            slow_plans,
+        }
+    }
+
+    /// Parse one log line, or attach a continuation line to the pending
+    /// multiline message.
+    fn process_line(&mut self, line: &str) {
+        // Continuation line (starts with whitespace): try to patch last event in-place
+        if is_continuation_line(line) {
+            // Multiline STATEMENT continuation — append SQL text to pending error
+            if let Some(ref key) = self.pending_statement_key {
+                if let Some(entry) = self.pending_errors.get_mut(key) {
+                    let trimmed = line.trim();
+                    if entry.statement.len() + 1 + trimmed.len() <= MAX_LOG_MESSAGE_LEN {
+                        if !entry.statement.is_empty() {
+                            entry.statement.push(' ');
+                        }
+                        entry.statement.push_str(trimmed);
+                    }
+                }
+                return;
+            }
+            // Slow query multiline continuation — append SQL text
+            if let Some(ref mut pending) = self.pending_slow_query {
+                if !pending.truncated {
+                    let trimmed = line.trim();
+                    if pending.sql.len() + 1 + trimmed.len() > MAX_SLOW_QUERY_LEN {
+                        pending.sql.truncate(MAX_SLOW_QUERY_LEN);
+                        pending.truncated = true;
+                    } else {
+                        pending.sql.push(' ');
+                        pending.sql.push_str(trimmed);
+                    }
+                }
+                return;
+            }
+            // auto_explain plan body
+            if let Some(ref mut pending) = self.pending_slow_plan {
+                pending.push_line(line);
+                return;
+            }
+            if let Some(idx) = self.last_event_idx
+                && let Some(entry) = self.pending_events.get_mut(idx)
+            {
+                patch_event_from_continuation(entry, line);
+            }
+            return;
+        }
+
+        // New primary line — flush pending slow query/plan and reset continuation tracking
+        self.flush_pending_slow_query();
+        self.flush_pending_slow_plan();
+        self.pending_statement_key = None;
+        self.last_event_idx = None;
+
+        let parsed = self.parse_line(line);
+        let Some(parsed) = parsed else {
+            // Unrecognized line (WARNING, NOTICE, other LOG, etc.) — reset error tracking
+            self.last_error_key = None;
+            return;
+        };
+
+        // Remember index for events that have multiline continuations
+        let will_have_continuations = matches!(
+            parsed.event_kind,
+            LogEventKind::Autovacuum | LogEventKind::Checkpoint
+        );
+
+        self.accumulate(parsed);
+
+        if will_have_continuations {
+            self.last_event_idx = Some(self.pending_events.len() - 1);
         }
     }
 
@@ -417,17 +588,59 @@ impl LogCollector {
             LogEventKind::Checkpoint => {
                 self.last_error_key = None;
                 self.pending_checkpoints = self.pending_checkpoints.saturating_add(1);
-                if let Some(event_data) = parsed.event_data {
-                    self.pending_events
-                        .push(event_data_to_entry(event_data, &parsed.message));
+                if let Some(entry) = parsed
+                    .event_data
+                    .and_then(|data| event_data_to_entry(data, &parsed.message))
+                {
+                    self.pending_events.push(entry);
                 }
             }
             LogEventKind::Autovacuum => {
                 self.last_error_key = None;
                 self.pending_autovacuums = self.pending_autovacuums.saturating_add(1);
-                if let Some(event_data) = parsed.event_data {
-                    self.pending_events
-                        .push(event_data_to_entry(event_data, &parsed.message));
+                if let Some(entry) = parsed
+                    .event_data
+                    .and_then(|data| event_data_to_entry(data, &parsed.message))
+                {
+                    self.pending_events.push(entry);
+                }
+            }
+            LogEventKind::Connection => {
+                self.last_error_key = None;
+                if let Some(EventData::Connection {
+                    stage,
+                    session_time_s,
+                }) = parsed.event_data
+                {
+                    let conns = &mut self.pending_connections;
+                    match stage {
+                        ConnectionStage::Received => {
+                            conns.received = conns.received.saturating_add(1);
+                        }
+                        ConnectionStage::Authorized => {
+                            conns.authorized = conns.authorized.saturating_add(1);
+                        }
+                        ConnectionStage::Disconnection => {
+                            conns.disconnections = conns.disconnections.saturating_add(1);
+                            if let Some(t) = session_time_s {
+                                conns.total_session_s += t;
+                                if t < SHORT_LIVED_SESSION_SECS {
+                                    conns.short_lived = conns.short_lived.saturating_add(1);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            LogEventKind::SlowPlan => {
+                self.last_error_key = None;
+                self.last_event_idx = None;
+                self.flush_pending_slow_plan();
+                if let Some(EventData::SlowPlan { duration_ms }) = parsed.event_data {
+                    self.pending_slow_plan = Some(PendingSlowPlan {
+                        duration_ms,
+                        ..Default::default()
+                    });
                 }
             }
             LogEventKind::SlowQuery => {
@@ -448,6 +661,78 @@ impl LogCollector {
         }
     }
 
+    /// Flush the pending auto_explain plan into the grouped collection (top-N by duration).
+    fn flush_pending_slow_plan(&mut self) {
+        let Some(pending) = self.pending_slow_plan.take() else {
+            return;
+        };
+        if pending.plan.is_empty() {
+            return;
+        }
+        let key = if pending.queryid != 0 {
+            SlowPlanKey::QueryId(pending.queryid)
+        } else {
+            SlowPlanKey::Text(normalize_error(&pending.query))
+        };
+
+        if let Some(group) = self.slow_plans.get_mut(&key) {
+            group.count = group.count.saturating_add(1);
+            if pending.duration_ms > group.duration_ms {
+                group.duration_ms = pending.duration_ms;
+                group.query = pending.query;
+                group.plan = pending.plan;
+            }
+            return;
+        }
+
+        // No room — evict the fastest group if this plan is slower
+        if self.slow_plans.len() >= MAX_SLOW_PLANS_PER_SNAPSHOT {
+            let fastest = self
+                .slow_plans
+                .iter()
+                .min_by(|(_, a), (_, b)| a.duration_ms.total_cmp(&b.duration_ms))
+                .filter(|(_, g)| g.duration_ms < pending.duration_ms)
+                .map(|(k, _)| k.clone());
+            match fastest {
+                Some(k) => {
+                    self.slow_plans.remove(&k);
+                }
+                None => return,
+            }
+        }
+        self.slow_plans.insert(
+            key,
+            GroupedSlowPlan {
+                queryid: pending.queryid,
+                duration_ms: pending.duration_ms,
+                count: 1,
+                query: pending.query,
+                plan: pending.plan,
+            },
+        );
+    }
+
+    /// Drain grouped auto_explain plans, slowest first, interning texts.
+    fn drain_slow_plans(&mut self, interner: &mut StringInterner) -> Vec<PgSlowPlanInfo> {
+        let mut plans: Vec<PgSlowPlanInfo> = self
+            .slow_plans
+            .drain()
+            .map(|(_, group)| PgSlowPlanInfo {
+                queryid: group.queryid,
+                duration_ms: group.duration_ms,
+                count: group.count,
+                query_hash: if group.query.is_empty() {
+                    0
+                } else {
+                    interner.intern(&self.redact.apply(&group.query))
+                },
+                plan_hash: interner.intern(&self.redact.apply_plan(&group.plan)),
+            })
+            .collect();
+        plans.sort_by(|a, b| b.duration_ms.total_cmp(&a.duration_ms));
+        plans
+    }
+
     /// Flush the pending slow query into the grouped collection (top-N by max duration).
     fn flush_pending_slow_query(&mut self) {
         let Some(pending) = self.pending_slow_query.take() else {
@@ -547,7 +832,7 @@ impl LogCollector {
         }
 
         // Sort by count descending — keep top patterns
-        entries.sort_by(|a, b| b.1.count.cmp(&a.1.count));
+        entries.sort_by_key(|e| std::cmp::Reverse(e.1.count));
         entries.truncate(MAX_LOG_PATTERNS_PER_SNAPSHOT);
 
         entries
@@ -559,13 +844,18 @@ impl LogCollector {
                 let statement_hash = if pending.statement.is_empty() {
                     0
                 } else {
-                    interner.intern(&pending.statement)
+                    interner.intern(&self.redact.apply(&pending.statement))
+                };
+                let sample = if self.redact.is_active() {
+                    &pattern_str
+                } else {
+                    &pending.sample
                 };
                 crate::storage::model::PgLogEntry {
                     pattern_hash: interner.intern(&pattern_str),
                     severity,
                     count: pending.count,
-                    sample_hash: interner.intern(&pending.sample),
+                    sample_hash: interner.intern(sample),
                     statement_hash,
                     category: normalize::classify_error(&pattern_str, severity),
                 }
@@ -593,18 +883,25 @@ impl LogCollector {
             }
         };
 
-        match &mut self.tailer {
-            Some(tailer) => {
+        match &mut self.source {
+            Some(LogSource::File(tailer)) => {
                 if tailer.path() != full_path {
                     tailer
                         .switch_file(full_path)
                         .map_err(|e| format!("switch_file: {}", e))?;
                 }
             }
-            None => {
-                self.tailer = Some(
-                    FileTailer::new(full_path).map_err(|e| format!("FileTailer::new: {}", e))?,
-                );
+            _ => {
+                let mut tailer =
+                    FileTailer::new(full_path).map_err(|e| format!("FileTailer::new: {}", e))?;
+                if let Some(pattern) = self
+                    .log_filename
+                    .as_deref()
+                    .map(|f| log_file_pattern(f, self.log_format))
+                {
+                    tailer = tailer.with_rotation_pattern(&pattern);
+                }
+                self.source = Some(LogSource::File(tailer));
             }
         }
 
@@ -704,8 +1001,11 @@ fn patch_event_from_continuation(entry: &mut PgLogEventEntry, line: &str) {
 }
 
 /// Convert parser `EventData` into storage `PgLogEventEntry`.
-fn event_data_to_entry(data: EventData, message: &str) -> PgLogEventEntry {
-    match data {
+///
+/// Connection events are aggregated into counters and auto_explain plans
+/// are stored separately; neither has an entry.
+fn event_data_to_entry(data: EventData, message: &str) -> Option<PgLogEventEntry> {
+    let entry = match data {
         EventData::CheckpointStarting { .. } => PgLogEventEntry {
             event_type: PgLogEventType::CheckpointStarting,
             message: message.to_string(),
@@ -820,6 +1120,22 @@ fn event_data_to_entry(data: EventData, message: &str) -> PgLogEventEntry {
             wal_bytes: 0,
             count: 0,
         },
+        EventData::Connection { .. } | EventData::SlowPlan { .. } => return None,
+    };
+    Some(entry)
+}
+
+/// File name pattern of log files for the given format.
+///
+/// Mirrors PostgreSQL's `logfile_getname()`: csvlog files replace a trailing
+/// `.log` with `.csv` (or append `.csv`).
+fn log_file_pattern(log_filename: &str, format: Option<LogFormat>) -> String {
+    match format {
+        Some(LogFormat::Csvlog) => {
+            let base = log_filename.strip_suffix(".log").unwrap_or(log_filename);
+            format!("{}.csv", base)
+        }
+        _ => log_filename.to_string(),
     }
 }
 
@@ -1190,4 +1506,101 @@ mod tests {
         assert_eq!(select_group.count, 1);
         assert!((select_group.max_elapsed_s - 5.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_auto_explain_plans() {
+        let mut collector = LogCollector::new();
+        collector.stderr_parser = Some(StderrParser::new("%t [%p]: "));
+        collector.log_format = Some(LogFormat::Stderr);
+        let mut interner = StringInterner::new();
+
+        let lines = [
+            "2024-01-15 14:30:00 UTC [1]: LOG:  duration: 120.5 ms  plan:",
+            "\tQuery Text: SELECT *",
+            "\t  FROM orders WHERE customer_id = 42",
+            "\tQuery Identifier: -815",
+            "\tSeq Scan on orders  (cost=0.00..1843.00 rows=1 width=40)",
+            "\t  Filter: (customer_id = 42)",
+            "2024-01-15 14:30:01 UTC [2]: LOG:  duration: 300.0 ms  plan:",
+            "\tQuery Text: SELECT * FROM orders WHERE customer_id = 7",
+            "\tQuery Identifier: -815",
+            "\tIndex Scan using orders_customer_idx on orders  (cost=0.43..8.45 rows=1 width=40)",
+            "\t  Index Cond: (customer_id = 7)",
+            // No identifier: grouped by query text
+            "2024-01-15 14:30:02 UTC [3]: LOG:  duration: 50.0 ms  plan:",
+            "\tQuery Text: SELECT pg_sleep(0.05)",
+            "\tResult  (cost=0.00..0.01 rows=1 width=4)",
+            "2024-01-15 14:30:03 UTC [4]: LOG:  checkpoint starting: time",
+        ];
+        for line in lines {
+            collector.process_line(line);
+        }
+        collector.flush_pending_slow_plan();
+
+        let plans = collector.drain_slow_plans(&mut interner);
+        assert_eq!(plans.len(), 2);
+        assert_eq!(plans[0].queryid, -815);
+        assert_eq!(plans[0].count, 2);
+        assert_eq!(plans[0].duration_ms, 300.0);
+        assert_eq!(
+            interner.resolve(plans[0].query_hash),
+            Some("SELECT * FROM orders WHERE customer_id = 7")
+        );
+        assert_eq!(
+            interner.resolve(plans[0].plan_hash),
+            Some(
+                "Index Scan using orders_customer_idx on orders  (cost=0.43..8.45 rows=1 width=40)\n  Index Cond: (customer_id = 7)"
+            )
+        );
+        assert_eq!(plans[1].queryid, 0);
+        assert_eq!(
+            interner.resolve(plans[1].query_hash),
+            Some("SELECT pg_sleep(0.05)")
+        );
+        // The checkpoint line ended the last plan and is still counted
+        assert_eq!(collector.pending_checkpoints, 1);
+        assert!(collector.slow_plans.is_empty());
+    }
+
+    #[test]
+    fn test_connection_counts() {
+        let mut collector = LogCollector::new();
+        collector.stderr_parser = Some(StderrParser::new("%t [%p]: "));
+        collector.log_format = Some(LogFormat::Stderr);
+
+        let lines = [
+            "2024-01-15 14:30:00 UTC [1]: LOG:  connection received: host=10.0.0.5 port=51234",
+            "2024-01-15 14:30:00 UTC [1]: LOG:  connection authorized: user=app database=shop",
+            "2024-01-15 14:30:00 UTC [1]: LOG:  disconnection: session time: 0:00:00.015 user=app database=shop host=10.0.0.5 port=51234",
+            "2024-01-15 14:30:00 UTC [2]: LOG:  connection received: host=10.0.0.6 port=40000",
+            "2024-01-15 14:30:05 UTC [3]: LOG:  disconnection: session time: 0:10:00.000 user=app database=shop host=10.0.0.7 port=40001",
+        ];
+        for line in lines {
+            let parsed = collector.parse_line(line).unwrap();
+            collector.accumulate(parsed);
+        }
+
+        let conns = collector.pending_connections;
+        assert_eq!(conns.received, 2);
+        assert_eq!(conns.authorized, 1);
+        assert_eq!(conns.disconnections, 2);
+        assert_eq!(conns.short_lived, 1);
+        assert!((conns.total_session_s - 600.015).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_log_file_pattern() {
+        assert_eq!(
+            log_file_pattern("postgresql-%Y-%m-%d_%H%M%S.log", Some(LogFormat::Stderr)),
+            "postgresql-%Y-%m-%d_%H%M%S.log"
+        );
+        assert_eq!(
+            log_file_pattern("postgresql-%Y-%m-%d_%H%M%S.log", Some(LogFormat::Csvlog)),
+            "postgresql-%Y-%m-%d_%H%M%S.csv"
+        );
+        assert_eq!(
+            log_file_pattern("postgresql-%a", Some(LogFormat::Csvlog)),
+            "postgresql-%a.csv"
+        );
+    }
 }
@@ -19,10 +19,27 @@ pub enum LogEventKind {
     Autovacuum,
     /// Slow query: `duration: X ms  statement: SQL` (LOG level).
     SlowQuery,
+    /// Plan logged by auto_explain: `duration: X ms  plan:`, the plan follows
+    /// on continuation lines (LOG level).
+    SlowPlan,
     /// STATEMENT: line following an error (contains the SQL that caused it).
     Statement,
     /// DETAIL: or CONTEXT: continuation line (skipped but recognized).
     DetailContext,
+    /// Connection received/authorized or disconnection (LOG level,
+    /// requires `log_connections` / `log_disconnections`).
+    Connection,
+}
+
+/// Stage of a client connection reported by `log_connections`/`log_disconnections`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionStage {
+    /// `connection received: host=... port=...`
+    Received,
+    /// `connection authorized: user=... database=...`
+    Authorized,
+    /// `disconnection: session time: H:MM:SS.mmm ...`
+    Disconnection,
 }
 
 /// Extracted structured data from checkpoint/autovacuum LOG messages.
@@ -55,6 +72,14 @@ pub enum EventData {
         duration_ms: f64,
         sql: String,
     },
+    SlowPlan {
+        duration_ms: f64,
+    },
+    Connection {
+        stage: ConnectionStage,
+        /// Session duration in seconds (disconnection only).
+        session_time_s: Option<f64>,
+    },
     Autovacuum {
         table_name: String,
         is_analyze: bool,
@@ -282,6 +307,16 @@ impl CsvlogParser {
 // LOG message classification and data extraction
 // ============================================================
 
+/// Connection lifecycle message prefixes (English + Russian locale).
+const CONNECTION_PREFIXES: &[(&str, ConnectionStage)] = &[
+    ("connection received:", ConnectionStage::Received),
+    ("connection authorized:", ConnectionStage::Authorized),
+    ("disconnection:", ConnectionStage::Disconnection),
+    ("принято подключение:", ConnectionStage::Received),
+    ("подключение авторизовано:", ConnectionStage::Authorized),
+    ("отключение:", ConnectionStage::Disconnection),
+];
+
 /// Duration + statement prefixes for slow query detection (EN + RU).
 ///
 /// PostgreSQL emits `LOG:  duration: X ms  statement: SQL` when
@@ -292,6 +327,13 @@ const DURATION_PREFIXES: &[(&str, &str, &str)] = &[
     ("продолжительность: ", " мс  оператор: ", " мс"),
 ];
 
+/// auto_explain header: `duration: X ms  plan:` (EN + RU). Only the header is
+/// translated; `Query Text:` and the plan itself are not.
+const PLAN_MARKERS: &[(&str, &str)] = &[
+    ("duration: ", " ms  plan:"),
+    ("продолжительность: ", " мс  план:"),
+];
+
 /// Classify a LOG-level message as checkpoint, autovacuum or slow query event.
 /// Returns `None` if the message is not a known operational event.
 fn classify_log_message(message: &str) -> Option<ParsedLogLine> {
@@ -335,14 +377,59 @@ fn classify_log_message(message: &str) -> Option<ParsedLogLine> {
         }
     }
 
+    // Connection received / authorized / disconnection
+    for &(prefix, stage) in CONNECTION_PREFIXES {
+        if message.starts_with(prefix) {
+            let session_time_s = match stage {
+                ConnectionStage::Disconnection => parse_session_time(message),
+                _ => None,
+            };
+            return Some(ParsedLogLine {
+                severity: PgLogSeverity::Error, // placeholder, not used for grouping
+                message: String::new(),
+                event_kind: LogEventKind::Connection,
+                event_data: Some(EventData::Connection {
+                    stage,
+                    session_time_s,
+                }),
+            });
+        }
+    }
+
     // Slow query: "duration: X ms  statement: SQL"
     if let Some(parsed) = parse_slow_query(message) {
         return Some(parsed);
     }
 
+    // auto_explain: "duration: X ms  plan:"
+    if let Some(parsed) = parse_slow_plan(message) {
+        return Some(parsed);
+    }
+
     None
 }
 
+/// Parse session duration from a disconnection message.
+///
+/// EN: `disconnection: session time: 0:00:01.234 user=app database=db host=10.0.0.1 port=5432`
+/// RU: `отключение: время сеанса: 0:00:01.234 пользователь=app база данных=db ...`
+///
+/// Returns the duration in seconds.
+fn parse_session_time(message: &str) -> Option<f64> {
+    let pos = ["session time: ", "время сеанса: "]
+        .iter()
+        .find_map(|marker| message.find(marker).map(|p| p + marker.len()))?;
+    let rest = &message[pos..];
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != ':' && c != '.')
+        .unwrap_or(rest.len());
+    let mut parts = rest[..end].splitn(3, ':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
 /// Parse a `duration: X ms  statement: SQL` LOG message.
 ///
 /// Returns `None` if the message doesn't match (e.g. plain `log_duration=on`
@@ -382,6 +469,21 @@ fn parse_slow_query(message: &str) -> Option<ParsedLogLine> {
     None
 }
 
+/// Parse an auto_explain `duration: X ms  plan:` header. The plan text is
+/// on the following continuation lines (stderr format).
+fn parse_slow_plan(message: &str) -> Option<ParsedLogLine> {
+    PLAN_MARKERS.iter().find_map(|&(dur_prefix, plan_marker)| {
+        let rest = message.strip_prefix(dur_prefix)?;
+        let duration_ms: f64 = rest[..rest.find(plan_marker)?].parse().ok()?;
+        Some(ParsedLogLine {
+            severity: PgLogSeverity::Error, // placeholder, not used for display
+            message: String::new(),
+            event_kind: LogEventKind::SlowPlan,
+            event_data: Some(EventData::SlowPlan { duration_ms }),
+        })
+    })
+}
+
 /// Parse checkpoint complete message fields.
 ///
 /// EN: `checkpoint complete: wrote 123 buffers (0.1%); ... write=1.234 s, sync=0.567 s, total=2.345 s; ... distance=12345 kB, estimate=67890 kB`
@@ -1176,6 +1278,24 @@ system usage: CPU: user: 0.12 s, system: 0.34 s, elapsed: 5.67 s"#;
         }
     }
 
+    #[test]
+    fn test_stderr_auto_explain_header() {
+        let parser = StderrParser::new("%t [%p]: ");
+        for line in [
+            "2024-01-15 14:30:00 UTC [12345]: LOG:  duration: 1500.250 ms  plan:",
+            "2024-01-15 14:30:00 UTC [12345]: СООБЩЕНИЕ:  продолжительность: 1500.250 мс  план:",
+        ] {
+            let parsed = parser.parse_line(line).unwrap();
+            assert_eq!(parsed.event_kind, LogEventKind::SlowPlan);
+            assert_eq!(
+                parsed.event_data,
+                Some(EventData::SlowPlan {
+                    duration_ms: 1500.25
+                })
+            );
+        }
+    }
+
     #[test]
     fn test_log_duration_only_ignored() {
         let parser = StderrParser::new("%t [%p]: ");
@@ -1201,4 +1321,56 @@ system usage: CPU: user: 0.12 s, system: 0.34 s, elapsed: 5.67 s"#;
             other => panic!("expected SlowQuery, got {:?}", other),
         }
     }
+
+    #[test]
+    fn test_stderr_connection_lifecycle() {
+        let parser = StderrParser::new("%t [%p]: ");
+        let received =
+            "2024-01-15 14:30:00 UTC [12345]: LOG:  connection received: host=10.0.0.5 port=51234";
+        let authorized = "2024-01-15 14:30:00 UTC [12345]: LOG:  connection authorized: user=app database=shop application_name=psql";
+        let disconnected = "2024-01-15 14:30:01 UTC [12345]: LOG:  disconnection: session time: 0:00:01.250 user=app database=shop host=10.0.0.5 port=51234";
+
+        let parsed = parser.parse_line(received).unwrap();
+        assert_eq!(parsed.event_kind, LogEventKind::Connection);
+        assert_eq!(
+            parsed.event_data,
+            Some(EventData::Connection {
+                stage: ConnectionStage::Received,
+                session_time_s: None,
+            })
+        );
+
+        let parsed = parser.parse_line(authorized).unwrap();
+        assert!(matches!(
+            parsed.event_data,
+            Some(EventData::Connection {
+                stage: ConnectionStage::Authorized,
+                ..
+            })
+        ));
+
+        let parsed = parser.parse_line(disconnected).unwrap();
+        match parsed.event_data {
+            Some(EventData::Connection {
+                stage: ConnectionStage::Disconnection,
+                session_time_s: Some(t),
+            }) => assert!((t - 1.25).abs() < 0.001),
+            other => panic!("expected Disconnection, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_csvlog_disconnection_long_session() {
+        let parser = CsvlogParser;
+        let line = r#"2024-01-15 14:30:00.123 UTC,"appuser","mydb",12345,"127.0.0.1:5432","6789",1,"idle","2024-01-15 12:00:00 UTC","",0,LOG,00000,"disconnection: session time: 2:30:05.500 user=appuser database=mydb host=127.0.0.1 port=5432",,,,,"",,"#;
+        let parsed = parser.parse_line(line).unwrap();
+        assert_eq!(parsed.event_kind, LogEventKind::Connection);
+        match parsed.event_data {
+            Some(EventData::Connection {
+                session_time_s: Some(t),
+                ..
+            }) => assert!((t - 9005.5).abs() < 0.001),
+            other => panic!("expected Disconnection, got {:?}", other),
+        }
+    }
 }
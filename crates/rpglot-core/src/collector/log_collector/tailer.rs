@@ -1,11 +1,19 @@
-//! File tailer for reading new lines from a growing log file.
+//! Log line sources: file tailer, syslog listener and journald reader.
 //!
-//! Supports log rotation detection via inode tracking (Linux)
-//! and file size comparison.
+//! The file tailer supports log rotation detection via inode tracking (Linux)
+//! and file size comparison, reads the tail of the previous file after
+//! rotation and can follow timestamped `log_filename` patterns. Syslog and journald sources are used for
+//! clusters that do not write log files; they yield plain PostgreSQL log
+//! lines (syslog headers stripped) for the stderr parser.
 
 use std::fs::{self, File};
 use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::mem;
+use std::net::{SocketAddr, UdpSocket};
 use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
 
 /// Maximum number of lines to read in a single `read_new_lines()` call.
 /// Prevents unbounded memory usage if the log file has a huge backlog.
@@ -15,11 +23,21 @@ const MAX_LINES_PER_READ: usize = 10_000;
 ///
 /// On creation, seeks to the end of the file (does not read old content).
 /// On each `read_new_lines()` call, reads from the last position to current EOF.
-/// Detects log rotation via inode change or file truncation.
+///
+/// The file handle is kept open, so when the file is rotated (renamed or
+/// replaced — detected via inode change) the remaining tail of the old file
+/// is still read before continuing with the new one. With a rotation pattern
+/// (`log_filename`), the tailer also follows newer files appearing in the
+/// log directory without waiting for `switch_file()`.
 pub struct FileTailer {
     path: PathBuf,
+    file: Option<File>,
     offset: u64,
     inode: u64,
+    /// `log_filename` pattern for discovering rotated-to files in the same directory.
+    pattern: Option<Vec<PatternToken>>,
+    /// Lines drained from the previous file by `switch_file()`.
+    pending: Vec<String>,
 }
 
 impl FileTailer {
@@ -27,76 +45,90 @@ impl FileTailer {
     ///
     /// Returns `Err` if the file does not exist or cannot be stat'd.
     pub fn new(path: PathBuf) -> io::Result<Self> {
-        let metadata = fs::metadata(&path)?;
+        let file = File::open(&path)?;
+        let metadata = file.metadata()?;
         let inode = get_inode(&metadata);
         let offset = metadata.len();
 
         Ok(Self {
             path,
+            file: Some(file),
             offset,
             inode,
+            pattern: None,
+            pending: Vec::new(),
         })
     }
 
+    /// Follow newer files matching a PostgreSQL `log_filename` pattern
+    /// (strftime escapes, e.g. `postgresql-%Y-%m-%d_%H%M%S.log`) in the
+    /// directory of the current file.
+    pub fn with_rotation_pattern(mut self, log_filename: &str) -> Self {
+        self.pattern = Some(parse_log_filename(log_filename));
+        self
+    }
+
     /// Read new lines appended since the last call.
     ///
-    /// If the file was rotated (inode changed or size decreased),
-    /// re-opens from the beginning of the new file.
+    /// If the file was rotated (inode changed or size decreased), reads the
+    /// rest of the old file and re-opens from the beginning of the new one.
     ///
     /// Returns at most `MAX_LINES_PER_READ` lines per call.
     pub fn read_new_lines(&mut self) -> io::Result<Vec<String>> {
-        let metadata = match fs::metadata(&self.path) {
-            Ok(m) => m,
+        let mut lines = mem::take(&mut self.pending);
+
+        match fs::metadata(&self.path) {
+            Ok(metadata) => {
+                let current_inode = get_inode(&metadata);
+                if current_inode != self.inode {
+                    // Rotated: finish the old file, then start the new one
+                    self.drain_old_file(&mut lines)?;
+                    self.file = Some(File::open(&self.path)?);
+                    self.inode = current_inode;
+                    self.offset = 0;
+                } else if metadata.len() < self.offset {
+                    // Truncated in place
+                    self.offset = 0;
+                }
+            }
             Err(e) if e.kind() == io::ErrorKind::NotFound => {
-                // File gone (rotation in progress) — return empty, try next time
-                return Ok(Vec::new());
+                // File gone (rotation in progress) — read what is left in the
+                // old handle; continue with a newer file if one exists.
+                self.drain_old_file(&mut lines)?;
+                if !self.follow_newer_file(&mut lines)? {
+                    return Ok(lines);
+                }
             }
             Err(e) => return Err(e),
-        };
-
-        let current_inode = get_inode(&metadata);
-        let current_size = metadata.len();
-
-        // Detect rotation: inode changed or file truncated
-        if current_inode != self.inode || current_size < self.offset {
-            self.inode = current_inode;
-            self.offset = 0; // Read from beginning of new file
         }
 
-        // Nothing new to read
-        if current_size <= self.offset {
-            return Ok(Vec::new());
-        }
-
-        let mut file = File::open(&self.path)?;
-        file.seek(SeekFrom::Start(self.offset))?;
-
-        let reader = BufReader::new(&file);
-        let mut lines = Vec::new();
-
-        for line_result in reader.lines() {
-            let line = line_result?;
-            lines.push(line);
-            if lines.len() >= MAX_LINES_PER_READ {
-                break;
-            }
+        if self.file.is_none() {
+            self.file = Some(File::open(&self.path)?);
         }
+        self.read_available(&mut lines, true)?;
 
-        // Update offset to current position
-        self.offset = file.stream_position()?;
+        // Newer files in the directory (rotation not yet reported by PG)
+        while lines.len() < MAX_LINES_PER_READ && self.follow_newer_file(&mut lines)? {}
 
         Ok(lines)
     }
 
     /// Switch to a different file (after log rotation detection from PG).
     ///
-    /// Starts reading from the beginning of the new file.
+    /// The unread tail of the current file is returned by the next
+    /// `read_new_lines()`; the new file is read from the start.
     pub fn switch_file(&mut self, new_path: PathBuf) -> io::Result<()> {
         if new_path == self.path {
             return Ok(());
         }
 
-        let metadata = fs::metadata(&new_path)?;
+        let file = File::open(&new_path)?;
+        let metadata = file.metadata()?;
+        let mut drained = mem::take(&mut self.pending);
+        self.drain_old_file(&mut drained)?;
+        self.pending = drained;
+
+        self.file = Some(file);
         self.inode = get_inode(&metadata);
         self.offset = 0; // Read new file from the start
         self.path = new_path;
@@ -108,6 +140,531 @@ impl FileTailer {
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Read the rest of the currently open (rotated-away) file and close it.
+    fn drain_old_file(&mut self, lines: &mut Vec<String>) -> io::Result<()> {
+        if self.file.is_some() {
+            self.read_available(lines, false)?;
+        }
+        self.file = None;
+        Ok(())
+    }
+
+    /// Read complete lines from the open file starting at `offset`.
+    ///
+    /// For the active file (`hold_partial`), an unterminated last line is
+    /// left for the next call since PostgreSQL may still be writing it.
+    fn read_available(&mut self, lines: &mut Vec<String>, hold_partial: bool) -> io::Result<()> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut reader = BufReader::new(file);
+        let mut buf = Vec::new();
+
+        while lines.len() < MAX_LINES_PER_READ {
+            buf.clear();
+            let n = reader.read_until(b'\n', &mut buf)?;
+            if n == 0 {
+                break;
+            }
+            let complete = buf.last() == Some(&b'\n');
+            if !complete && hold_partial {
+                break;
+            }
+            self.offset += n as u64;
+            let line = String::from_utf8_lossy(&buf);
+            lines.push(line.trim_end_matches(['\n', '\r']).to_string());
+        }
+        Ok(())
+    }
+
+    /// Switch to the next file matching the rotation pattern that is newer
+    /// than the current one, reading it from the start.
+    ///
+    /// Returns false if there is no pattern or no newer file.
+    fn follow_newer_file(&mut self, lines: &mut Vec<String>) -> io::Result<bool> {
+        let Some(pattern) = &self.pattern else {
+            return Ok(false);
+        };
+        let Some(next) = find_next_log_file(&self.path, pattern) else {
+            return Ok(false);
+        };
+        // Finish the current file before moving on
+        self.read_available(lines, false)?;
+
+        let file = File::open(&next)?;
+        self.inode = get_inode(&file.metadata()?);
+        self.file = Some(file);
+        self.offset = 0;
+        self.path = next;
+        self.read_available(lines, true)?;
+        Ok(true)
+    }
+}
+
+/// Token of a parsed `log_filename` pattern.
+#[derive(Debug, Clone, PartialEq)]
+enum PatternToken {
+    Literal(char),
+    /// Exactly N ASCII digits (`%Y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%j`, ...).
+    Digits(usize),
+    /// Any non-empty run of characters (`%a`, `%b`, `%Z`, unknown escapes).
+    Any,
+}
+
+/// Parse a strftime-style `log_filename` into tokens.
+fn parse_log_filename(pattern: &str) -> Vec<PatternToken> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            tokens.push(PatternToken::Literal(c));
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => tokens.push(PatternToken::Digits(4)),
+            Some('m' | 'd' | 'H' | 'M' | 'S' | 'y' | 'I' | 'U' | 'W' | 'V') => {
+                tokens.push(PatternToken::Digits(2))
+            }
+            Some('j') => tokens.push(PatternToken::Digits(3)),
+            Some('u' | 'w') => tokens.push(PatternToken::Digits(1)),
+            Some('%') => tokens.push(PatternToken::Literal('%')),
+            Some(_) => tokens.push(PatternToken::Any),
+            None => tokens.push(PatternToken::Literal('%')),
+        }
+    }
+    tokens
+}
+
+/// Check whether a file name matches a parsed `log_filename` pattern.
+fn matches_log_filename(tokens: &[PatternToken], name: &str) -> bool {
+    let chars: Vec<char> = name.chars().collect();
+    match_tokens(tokens, &chars)
+}
+
+fn match_tokens(tokens: &[PatternToken], name: &[char]) -> bool {
+    let Some((first, rest)) = tokens.split_first() else {
+        return name.is_empty();
+    };
+    match first {
+        PatternToken::Literal(c) => name.first() == Some(c) && match_tokens(rest, &name[1..]),
+        PatternToken::Digits(n) => {
+            name.len() >= *n
+                && name[..*n].iter().all(|c| c.is_ascii_digit())
+                && match_tokens(rest, &name[*n..])
+        }
+        PatternToken::Any => (1..=name.len()).any(|i| match_tokens(rest, &name[i..])),
+    }
+}
+
+/// Find the oldest file in the directory of `current` that matches the
+/// pattern and is newer than `current` (by modification time, then name).
+fn find_next_log_file(current: &Path, pattern: &[PatternToken]) -> Option<PathBuf> {
+    let dir = current.parent()?;
+    let current_name = current.file_name()?.to_os_string();
+    let current_key = fs::metadata(current)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(|t| (t, current_name));
+
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name();
+            if !matches_log_filename(pattern, name.to_str()?) {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            Some(((metadata.modified().ok()?, name), entry.path()))
+        })
+        .filter(|(key, path)| {
+            path != current
+                && match &current_key {
+                    Some(current_key) => key > current_key,
+                    // Current file is gone: any matching file is a candidate
+                    None => true,
+                }
+        })
+        .min_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, path)| path)
+}
+
+/// Maximum syslog datagram size accepted (RFC 5426 recommends up to 64 KiB).
+const MAX_SYSLOG_DATAGRAM: usize = 65_536;
+
+/// Where PostgreSQL log lines are read from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum LogSourceConfig {
+    /// Tail the file reported by `pg_current_logfile()` (default).
+    #[default]
+    File,
+    /// Listen for syslog datagrams (`log_destination = 'syslog'`).
+    Syslog(SyslogAddr),
+    /// Follow the journal of a systemd unit via `journalctl`.
+    Journald { unit: String },
+}
+
+/// Address of the syslog listener.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyslogAddr {
+    /// UDP socket, e.g. `127.0.0.1:5514`.
+    Udp(SocketAddr),
+    /// Unix datagram socket path.
+    Unix(PathBuf),
+}
+
+impl LogSourceConfig {
+    /// Returns true for the default file-based source.
+    pub fn is_file(&self) -> bool {
+        matches!(self, Self::File)
+    }
+
+    /// Open a non-file source. Returns `None` for [`LogSourceConfig::File`],
+    /// which is located via `pg_current_logfile()` instead.
+    pub fn open(&self) -> Option<io::Result<LogSource>> {
+        match self {
+            Self::File => None,
+            Self::Syslog(addr) => Some(SyslogListener::bind(addr).map(LogSource::Syslog)),
+            Self::Journald { unit } => Some(JournaldReader::new(unit).map(LogSource::Journald)),
+        }
+    }
+}
+
+/// An open log line source.
+pub enum LogSource {
+    File(FileTailer),
+    Syslog(SyslogListener),
+    Journald(JournaldReader),
+}
+
+impl LogSource {
+    /// Read lines received since the last call (at most `MAX_LINES_PER_READ`).
+    pub fn read_new_lines(&mut self) -> io::Result<Vec<String>> {
+        match self {
+            Self::File(tailer) => tailer.read_new_lines(),
+            Self::Syslog(listener) => listener.read_new_lines(),
+            Self::Journald(reader) => reader.read_new_lines(),
+        }
+    }
+}
+
+/// Non-blocking syslog receiver (UDP or unix datagram socket).
+///
+/// Each datagram is one syslog message. The RFC 3164 / RFC 5424 header and
+/// PostgreSQL's `[seq-line]` marker are stripped; continuation chunks of a
+/// multiline message are indented so the collector treats them as
+/// continuation lines, as with stderr output.
+pub struct SyslogListener {
+    socket: SyslogSocket,
+    buf: Vec<u8>,
+}
+
+enum SyslogSocket {
+    Udp(UdpSocket),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixDatagram, PathBuf),
+}
+
+impl SyslogListener {
+    /// Bind the listener. A stale unix socket at the path is replaced; any
+    /// other file there is an error rather than being deleted.
+    pub fn bind(addr: &SyslogAddr) -> io::Result<Self> {
+        let socket = match addr {
+            SyslogAddr::Udp(addr) => {
+                let socket = UdpSocket::bind(addr)?;
+                socket.set_nonblocking(true)?;
+                SyslogSocket::Udp(socket)
+            }
+            #[cfg(unix)]
+            SyslogAddr::Unix(path) => {
+                use std::os::unix::fs::FileTypeExt;
+                match fs::symlink_metadata(path) {
+                    Ok(meta) if meta.file_type().is_socket() => fs::remove_file(path)?,
+                    Ok(_) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::AlreadyExists,
+                            format!("{} exists and is not a socket", path.display()),
+                        ));
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e),
+                }
+                let socket = std::os::unix::net::UnixDatagram::bind(path)?;
+                socket.set_nonblocking(true)?;
+                SyslogSocket::Unix(socket, path.clone())
+            }
+            #[cfg(not(unix))]
+            SyslogAddr::Unix(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "unix sockets are not supported on this platform",
+                ));
+            }
+        };
+        Ok(Self {
+            socket,
+            buf: vec![0; MAX_SYSLOG_DATAGRAM],
+        })
+    }
+
+    /// Local address of a UDP listener (useful when bound to port 0).
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        match &self.socket {
+            SyslogSocket::Udp(s) => s.local_addr().ok(),
+            #[cfg(unix)]
+            SyslogSocket::Unix(..) => None,
+        }
+    }
+
+    /// Drain all queued datagrams without blocking.
+    pub fn read_new_lines(&mut self) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        while lines.len() < MAX_LINES_PER_READ {
+            let received = match &self.socket {
+                SyslogSocket::Udp(s) => s.recv(&mut self.buf),
+                #[cfg(unix)]
+                SyslogSocket::Unix(s, _) => s.recv(&mut self.buf),
+            };
+            let len = match received {
+                Ok(len) => len,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            let datagram = String::from_utf8_lossy(&self.buf[..len]);
+            for message in datagram.split('\n') {
+                let message = message.trim_end_matches(['\r', '\0']);
+                if message.is_empty() {
+                    continue;
+                }
+                lines.push(strip_syslog_header(message));
+            }
+        }
+        Ok(lines)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SyslogListener {
+    fn drop(&mut self) {
+        if let SyslogSocket::Unix(_, path) = &self.socket {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Strip the syslog header from a message, returning the PostgreSQL log line.
+///
+/// Handles RFC 5424 (`<PRI>1 TS HOST APP PROCID MSGID SD MSG`) and
+/// RFC 3164 (`<PRI>Mmm dd hh:mm:ss [HOST] TAG[PID]: MSG`). Messages without
+/// a recognizable header are returned as-is.
+pub fn strip_syslog_header(message: &str) -> String {
+    let body = match strip_priority(message) {
+        Some(rest) if rest.starts_with("1 ") => strip_rfc5424(&rest[2..]),
+        Some(rest) => strip_rfc3164(rest),
+        None => strip_rfc3164(message),
+    };
+    strip_pg_sequence(body)
+}
+
+/// Strip `<PRI>` prefix.
+fn strip_priority(message: &str) -> Option<&str> {
+    let rest = message.strip_prefix('<')?;
+    let end = rest.find('>')?;
+    if end == 0 || end > 3 || !rest[..end].bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(&rest[end + 1..])
+}
+
+/// Skip TIMESTAMP HOSTNAME APP-NAME PROCID MSGID and STRUCTURED-DATA.
+fn strip_rfc5424(rest: &str) -> &str {
+    let mut rest = rest;
+    for _ in 0..5 {
+        match rest.find(' ') {
+            Some(pos) => rest = &rest[pos + 1..],
+            None => return "",
+        }
+    }
+    // STRUCTURED-DATA: "-" or one or more "[...]" elements
+    if let Some(r) = rest.strip_prefix('-') {
+        rest = r;
+    } else {
+        while rest.starts_with('[') {
+            match find_sd_element_end(rest) {
+                Some(end) => rest = &rest[end + 1..],
+                None => return "",
+            }
+        }
+    }
+    let rest = rest.strip_prefix(' ').unwrap_or(rest);
+    rest.strip_prefix('\u{feff}').unwrap_or(rest)
+}
+
+/// Index of the closing `]` of an SD-ELEMENT, honoring `\]` escapes in values.
+fn find_sd_element_end(s: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            ']' if !escaped => return Some(i),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+/// Skip `Mmm dd hh:mm:ss` timestamp and `[HOST] TAG[PID]:` up to the message.
+fn strip_rfc3164(rest: &str) -> &str {
+    let is_timestamp = rest.len() >= 16
+        && rest.is_char_boundary(15)
+        && rest.as_bytes()[3] == b' '
+        && rest.as_bytes()[9] == b':'
+        && rest.as_bytes()[12] == b':'
+        && rest.as_bytes()[15] == b' ';
+    if !is_timestamp {
+        return rest;
+    }
+    let after_ts = &rest[16..];
+    // The tag is terminated by ": "; hostname (if any) contains no colon.
+    match after_ts.find(": ") {
+        Some(pos) => &after_ts[pos + 2..],
+        None => after_ts,
+    }
+}
+
+/// Strip PostgreSQL's `[seq-chunk] ` marker. Chunks after the first are
+/// continuations of a multiline message and are indented with a tab.
+fn strip_pg_sequence(body: &str) -> String {
+    let parsed = body.strip_prefix('[').and_then(|rest| {
+        let end = rest.find("] ")?;
+        let (seq, chunk) = rest[..end].split_once('-')?;
+        if seq.is_empty() || !seq.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let chunk: u32 = chunk.parse().ok()?;
+        Some((chunk, &rest[end + 2..]))
+    });
+    match parsed {
+        Some((chunk, text)) if chunk > 1 && !text.starts_with(['\t', ' ']) => {
+            format!("\t{text}")
+        }
+        Some((_, text)) => text.to_string(),
+        None => body.to_string(),
+    }
+}
+
+/// Follows a systemd unit's journal via `journalctl --follow --output=cat`.
+///
+/// A background thread reads journalctl's stdout into a channel; reads
+/// drain the channel without blocking. If journalctl exits, it is
+/// restarted on the next read (from the current end of the journal).
+pub struct JournaldReader {
+    unit: String,
+    child: Option<Child>,
+    rx: Option<Receiver<String>>,
+}
+
+impl JournaldReader {
+    /// Start following the journal of `unit` (e.g. `postgresql.service`).
+    pub fn new(unit: &str) -> io::Result<Self> {
+        let mut reader = Self {
+            unit: unit.to_string(),
+            child: None,
+            rx: None,
+        };
+        reader.spawn(journalctl_command(unit))?;
+        Ok(reader)
+    }
+
+    fn spawn(&mut self, mut command: Command) -> io::Result<()> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("journalctl stdout not captured"))?;
+        let (tx, rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("journald-reader".to_string())
+            .spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else { break };
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        self.child = Some(child);
+        self.rx = Some(rx);
+        Ok(())
+    }
+
+    /// Drain lines received since the last call.
+    pub fn read_new_lines(&mut self) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        let mut disconnected = false;
+        if let Some(rx) = &self.rx {
+            while lines.len() < MAX_LINES_PER_READ {
+                match rx.try_recv() {
+                    Ok(line) => lines.push(line),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        disconnected = true;
+                        break;
+                    }
+                }
+            }
+        } else {
+            disconnected = true;
+        }
+
+        if disconnected {
+            self.stop();
+            // Keep already received lines even if the restart fails
+            if let Err(e) = self.spawn(journalctl_command(&self.unit))
+                && lines.is_empty()
+            {
+                return Err(e);
+            }
+        }
+        Ok(lines)
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        self.rx = None;
+    }
+}
+
+impl Drop for JournaldReader {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn journalctl_command(unit: &str) -> Command {
+    let mut command = Command::new("journalctl");
+    command.args([
+        "--follow",
+        "--lines=0",
+        "--output=cat",
+        "--no-pager",
+        "--unit",
+    ]);
+    command.arg(unit);
+    command
 }
 
 /// Extract inode from file metadata (Linux-specific).
@@ -200,6 +757,237 @@ mod tests {
         assert!(lines.is_empty());
     }
 
+    #[test]
+    fn test_tailer_holds_partial_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.log");
+
+        std::fs::write(&path, "").unwrap();
+        let mut tailer = FileTailer::new(path.clone()).unwrap();
+
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        write!(f, "complete\npart").unwrap();
+        assert_eq!(tailer.read_new_lines().unwrap(), vec!["complete"]);
+
+        writeln!(f, "ial").unwrap();
+        assert_eq!(tailer.read_new_lines().unwrap(), vec!["partial"]);
+    }
+
+    #[test]
+    fn test_tailer_reads_tail_after_rename_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("postgresql.log");
+        let rotated = dir.path().join("postgresql.log.1");
+
+        std::fs::write(&path, "").unwrap();
+        let mut tailer = FileTailer::new(path.clone()).unwrap();
+
+        // Lines written right before rotation, not yet read
+        std::fs::write(&path, "before rotation\n").unwrap();
+        std::fs::rename(&path, &rotated).unwrap();
+        std::fs::write(&path, "after rotation\n").unwrap();
+
+        let lines = tailer.read_new_lines().unwrap();
+        assert_eq!(lines, vec!["before rotation", "after rotation"]);
+    }
+
+    #[test]
+    fn test_tailer_switch_file_keeps_old_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path1 = dir.path().join("log1.log");
+        let path2 = dir.path().join("log2.log");
+
+        std::fs::write(&path1, "").unwrap();
+        let mut tailer = FileTailer::new(path1.clone()).unwrap();
+        std::fs::write(&path1, "last of old\n").unwrap();
+        std::fs::write(&path2, "first of new\n").unwrap();
+
+        tailer.switch_file(path2).unwrap();
+        let lines = tailer.read_new_lines().unwrap();
+        assert_eq!(lines, vec!["last of old", "first of new"]);
+    }
+
+    #[test]
+    fn test_tailer_follows_rotation_pattern() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("postgresql-2026-10-16_120000.log");
+        let new = dir.path().join("postgresql-2026-10-16_130000.log");
+        let other = dir.path().join("unrelated.txt");
+
+        std::fs::write(&old, "").unwrap();
+        let mut tailer = FileTailer::new(old.clone())
+            .unwrap()
+            .with_rotation_pattern("postgresql-%Y-%m-%d_%H%M%S.log");
+
+        std::fs::write(&old, "old tail\n").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(&other, "ignored\n").unwrap();
+        std::fs::write(&new, "new head\n").unwrap();
+
+        let lines = tailer.read_new_lines().unwrap();
+        assert_eq!(lines, vec!["old tail", "new head"]);
+        assert_eq!(tailer.path(), new);
+
+        // Old file is not picked up again
+        assert!(tailer.read_new_lines().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_log_filename_matching() {
+        let tokens = parse_log_filename("postgresql-%Y-%m-%d_%H%M%S.log");
+        assert!(matches_log_filename(
+            &tokens,
+            "postgresql-2026-10-16_120000.log"
+        ));
+        assert!(!matches_log_filename(
+            &tokens,
+            "postgresql-2026-10-16_120000.csv"
+        ));
+        assert!(!matches_log_filename(&tokens, "postgresql-2026-10-16.log"));
+
+        let tokens = parse_log_filename("postgresql-%a.log");
+        assert!(matches_log_filename(&tokens, "postgresql-Mon.log"));
+        assert!(!matches_log_filename(&tokens, "postgresql-.log"));
+
+        let tokens = parse_log_filename("pg_%%_%j.log");
+        assert!(matches_log_filename(&tokens, "pg_%_289.log"));
+    }
+
+    #[test]
+    fn test_strip_syslog_header_rfc3164() {
+        assert_eq!(
+            strip_syslog_header(
+                "<134>Oct 16 12:00:01 db1 postgres[4242]: [7-1] 2026-10-16 12:00:01 UTC [4242] ERROR:  boom"
+            ),
+            "2026-10-16 12:00:01 UTC [4242] ERROR:  boom"
+        );
+        // Local socket messages have no hostname
+        assert_eq!(
+            strip_syslog_header(
+                "<134>Oct  6 12:00:01 postgres[4242]: [7-1] LOG:  checkpoint starting: time"
+            ),
+            "LOG:  checkpoint starting: time"
+        );
+    }
+
+    #[test]
+    fn test_strip_syslog_header_rfc5424() {
+        assert_eq!(
+            strip_syslog_header(
+                "<134>1 2026-10-16T12:00:01Z db1 postgres 4242 - - [8-1] LOG:  disconnection: session time: 0:00:00.010"
+            ),
+            "LOG:  disconnection: session time: 0:00:00.010"
+        );
+        assert_eq!(
+            strip_syslog_header(
+                r#"<134>1 2026-10-16T12:00:01Z db1 postgres 4242 - [meta x="a\]b"] [8-1] LOG:  x"#
+            ),
+            "LOG:  x"
+        );
+    }
+
+    #[test]
+    fn test_strip_syslog_header_continuation_chunk() {
+        assert_eq!(
+            strip_syslog_header("<134>Oct 16 12:00:01 db1 postgres[1]: [9-2] pages: 0 removed"),
+            "\tpages: 0 removed"
+        );
+        assert_eq!(
+            strip_syslog_header(
+                "<134>Oct 16 12:00:01 db1 postgres[1]: [9-3] \tbuffer usage: 1 hits"
+            ),
+            "\tbuffer usage: 1 hits"
+        );
+        // No header at all — passed through
+        assert_eq!(strip_syslog_header("plain line"), "plain line");
+    }
+
+    #[test]
+    fn test_syslog_udp_listener() {
+        let addr = SyslogAddr::Udp("127.0.0.1:0".parse().unwrap());
+        let mut listener = SyslogListener::bind(&addr).unwrap();
+        let target = listener.local_addr().unwrap();
+
+        assert!(listener.read_new_lines().unwrap().is_empty());
+
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        sender
+            .send_to(
+                b"<134>Oct 16 12:00:01 db1 postgres[1]: [1-1] ERROR:  a",
+                target,
+            )
+            .unwrap();
+        sender
+            .send_to(
+                b"<134>Oct 16 12:00:01 db1 postgres[1]: [2-1] ERROR:  b\n",
+                target,
+            )
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        let lines = listener.read_new_lines().unwrap();
+        assert_eq!(lines, vec!["ERROR:  a", "ERROR:  b"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_syslog_unix_listener() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("syslog.sock");
+        let mut listener = SyslogListener::bind(&SyslogAddr::Unix(path.clone())).unwrap();
+
+        let sender = std::os::unix::net::UnixDatagram::unbound().unwrap();
+        sender
+            .send_to(b"<134>Oct 16 12:00:01 postgres[1]: [1-1] FATAL:  c", &path)
+            .unwrap();
+
+        let lines = listener.read_new_lines().unwrap();
+        assert_eq!(lines, vec!["FATAL:  c"]);
+
+        drop(listener);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_syslog_unix_listener_replaces_only_sockets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("syslog.sock");
+
+        // Left behind by a previous run that did not clean up
+        let stale = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+        drop(stale);
+        assert!(SyslogListener::bind(&SyslogAddr::Unix(path.clone())).is_ok());
+
+        let file = dir.path().join("postgresql.conf");
+        fs::write(&file, "listen_addresses = '*'").unwrap();
+        let err = SyslogListener::bind(&SyslogAddr::Unix(file.clone()))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "listen_addresses = '*'");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_journald_reader_drains_command_output() {
+        let mut reader = JournaldReader {
+            unit: "postgresql".to_string(),
+            child: None,
+            rx: None,
+        };
+        let mut command = Command::new("printf");
+        command.arg("LOG:  one\\n\\tcontinued\\n");
+        reader.spawn(command).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+
+        let lines = reader.read_new_lines().unwrap();
+        assert_eq!(lines, vec!["LOG:  one", "\tcontinued"]);
+    }
+
     #[test]
     fn test_tailer_switch_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -52,20 +52,61 @@
 //! assert!(!snapshot.blocks.is_empty());
 //! ```
 
+pub mod agent;
+pub mod blk_latency;
 pub mod cgroup;
 #[allow(clippy::module_inception)]
 mod collector;
+pub mod custom_query;
+pub mod discovery;
+#[cfg(any(target_os = "freebsd", test))]
+mod freebsd;
+pub mod k8s;
+pub mod kmsg;
 pub mod log_collector;
 pub mod mock;
 mod pg_collector;
 pub mod procfs;
+pub mod redact;
+#[cfg(feature = "sysinfo")]
+pub mod sysinfo_fs;
+pub mod systemd;
+pub mod throttle;
 pub mod traits;
 
 // Re-exports for public API (will be used by consumers of this library)
+pub use agent::AgentMonitor;
+#[cfg(all(feature = "ebpf", target_os = "linux"))]
+pub use blk_latency::BlkLatencyCollector;
 pub use cgroup::CgroupCollector;
 pub use collector::{Collector, CollectorTiming};
+pub use custom_query::{CustomQueryDef, load_custom_queries};
+pub use discovery::{DiscoveryFilter, InstanceDiscovery};
+#[cfg(target_os = "freebsd")]
+pub use freebsd::SysctlFs;
+pub use k8s::K8sCollector;
+#[cfg(target_os = "linux")]
+pub use kmsg::KmsgCollector;
 pub use mock::MockFs;
-pub use pg_collector::{PgCollectError, PostgresCollector};
+pub use pg_collector::{PgCollectError, PgConnectionParams, PostgresCollector, SslMode};
 pub use procfs::CollectError;
 pub use procfs::UserResolver;
+pub use redact::RedactMode;
+#[cfg(feature = "sysinfo")]
+pub use sysinfo_fs::SysinfoFs;
+pub use systemd::SystemdCollector;
+pub use throttle::{LoadThrottle, ThrottleConfig};
 pub use traits::{FileSystem, RealFs};
+
+/// The [`FileSystem`] backend reading this host: `/proc` on Linux, sysctl on
+/// FreeBSD, sysinfo on other platforms with the `sysinfo` feature. Builds
+/// without one fall back to [`MockFs`].
+#[cfg(target_os = "linux")]
+pub type HostFs = RealFs;
+#[cfg(target_os = "freebsd")]
+pub type HostFs = SysctlFs;
+#[cfg(all(
+    not(any(target_os = "linux", target_os = "freebsd")),
+    feature = "sysinfo"
+))]
+pub type HostFs = SysinfoFs;
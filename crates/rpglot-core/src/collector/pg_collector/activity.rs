@@ -18,6 +18,7 @@ impl PostgresCollector {
             return Vec::new();
         }
 
+        let redact = self.redact;
         let client = self.client.as_mut().unwrap();
         let query = build_stat_activity_query(self.server_version_num);
 
@@ -42,7 +43,7 @@ impl PostgresCollector {
                             application_name_hash: interner.intern(&application_name),
                             client_addr: row.get("client_addr"),
                             state_hash: interner.intern(&state),
-                            query_hash: interner.intern(&query_text),
+                            query_hash: interner.intern(&redact.apply(&query_text)),
                             query_id: row.get("query_id"),
                             wait_event_type_hash: interner.intern(&wait_event_type),
                             wait_event_hash: interner.intern(&wait_event),
@@ -51,6 +52,9 @@ impl PostgresCollector {
                             xact_start: row.get("xact_start"),
                             query_start: row.get("query_start"),
                             collected_at: row.get("collected_at"),
+                            backend_xid: row.get::<_, i64>("backend_xid") as u32,
+                            backend_xmin_age: row.get("backend_xmin_age"),
+                            ssl: Some(row.get("ssl")),
                         }
                     })
                     .collect()
@@ -0,0 +1,54 @@
+//! pg_stat_archiver collection.
+
+use tracing::debug;
+
+use crate::storage::model::PgStatArchiverInfo;
+
+use super::PostgresCollector;
+
+/// `pg_walfile_name()` is not allowed during recovery, so the current WAL
+/// file is left empty on a standby.
+const STAT_ARCHIVER_QUERY: &str = "\
+SELECT current_setting('archive_mode') AS archive_mode,
+    archived_count,
+    failed_count,
+    coalesce(last_archived_wal, '') AS last_archived_wal,
+    coalesce(extract(epoch FROM last_archived_time)::bigint, 0) AS last_archived_time,
+    coalesce(last_failed_wal, '') AS last_failed_wal,
+    coalesce(extract(epoch FROM last_failed_time)::bigint, 0) AS last_failed_time,
+    CASE WHEN pg_is_in_recovery() THEN ''
+        ELSE pg_walfile_name(pg_current_wal_lsn()) END AS current_wal,
+    pg_size_bytes(current_setting('wal_segment_size')) AS wal_segment_size
+FROM pg_stat_archiver";
+
+impl PostgresCollector {
+    /// Collects pg_stat_archiver data.
+    ///
+    /// Returns `None` when `archive_mode` is off, so the block is only
+    /// written for instances that archive WAL. Errors are logged and do not
+    /// affect the connection: bgwriter collection runs first on the same
+    /// client and handles connection loss.
+    pub fn collect_archiver(&mut self) -> Option<PgStatArchiverInfo> {
+        let client = self.client.as_mut()?;
+        let row = match client.query_one(STAT_ARCHIVER_QUERY, &[]) {
+            Ok(row) => row,
+            Err(e) => {
+                debug!(error = %super::format_postgres_error(&e), "failed to collect pg_stat_archiver");
+                return None;
+            }
+        };
+        if row.get::<_, String>("archive_mode") == "off" {
+            return None;
+        }
+        Some(PgStatArchiverInfo {
+            archived_count: row.get("archived_count"),
+            failed_count: row.get("failed_count"),
+            last_archived_wal: row.get("last_archived_wal"),
+            last_archived_time: row.get("last_archived_time"),
+            last_failed_wal: row.get("last_failed_wal"),
+            last_failed_time: row.get("last_failed_time"),
+            current_wal: row.get("current_wal"),
+            wal_segment_size: row.get::<_, i64>("wal_segment_size").max(0) as u64,
+        })
+    }
+}
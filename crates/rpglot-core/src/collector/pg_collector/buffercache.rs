@@ -0,0 +1,140 @@
+//! Collector for the contents of shared_buffers (`pg_buffercache`).
+//!
+//! Sampled every 5 minutes from the databases that have the extension
+//! installed, via `db_clients`: the view locks every buffer header in turn,
+//! and relfilenodes only resolve to names inside their own database. The
+//! cluster-wide totals are read once per sample, the relations holding the
+//! most buffers once per database.
+
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+use super::PostgresCollector;
+use super::format_postgres_error;
+use super::guard::is_timeout;
+use crate::storage::model::{PgBufferCacheInfo, PgBufferCacheRelation, PgRelationKind};
+
+/// Interval between buffer cache samples.
+const BUFFERCACHE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Relations kept per database, by buffers held.
+const BUFFERCACHE_TOP_N: i64 = 50;
+
+const BUFFERCACHE_EXTENSION_QUERY: &str =
+    "SELECT 1 FROM pg_extension WHERE extname = 'pg_buffercache'";
+
+const BUFFERCACHE_SUMMARY_QUERY: &str = "\
+SELECT count(*) AS total,
+    count(relfilenode) AS used,
+    count(*) FILTER (WHERE isdirty) AS dirty,
+    count(*) FILTER (WHERE usagecount >= 3) AS hot,
+    current_setting('block_size')::int AS block_size
+FROM pg_buffercache";
+
+const BUFFERCACHE_RELATIONS_QUERY: &str = "\
+SELECT n.nspname, c.relname, c.relkind IN ('i', 'I') AS is_index,
+    count(*) AS buffers,
+    count(*) FILTER (WHERE b.isdirty) AS dirty,
+    avg(b.usagecount)::double precision AS avg_usagecount
+FROM pg_buffercache b
+JOIN pg_class c ON b.relfilenode = pg_relation_filenode(c.oid)
+JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE b.reldatabase = (SELECT oid FROM pg_database WHERE datname = current_database())
+GROUP BY n.nspname, c.relname, c.relkind
+ORDER BY buffers DESC
+LIMIT $1";
+
+impl PostgresCollector {
+    /// Samples shared_buffers composition if `pg_buffercache` is installed.
+    ///
+    /// Returns `None` between samples and when no database has the
+    /// extension, so each sample is stored once.
+    pub fn collect_buffercache(&mut self) -> Option<PgBufferCacheInfo> {
+        if self.throttled
+            || self
+                .buffercache_time
+                .is_some_and(|t| t.elapsed() < BUFFERCACHE_INTERVAL)
+        {
+            return None;
+        }
+        let now = Instant::now();
+        if self.db_clients.is_empty() || !self.guard.allow("buffercache", now) {
+            return None;
+        }
+
+        let mut info: Option<PgBufferCacheInfo> = None;
+        let mut timed_out = false;
+        for db_client in &mut self.db_clients {
+            let installed = match db_client.client.query(BUFFERCACHE_EXTENSION_QUERY, &[]) {
+                Ok(rows) => !rows.is_empty(),
+                Err(e) => {
+                    timed_out |= is_timeout(&e);
+                    warn!(database = %db_client.datname, error = %format_postgres_error(&e),
+                        "failed to check pg_buffercache");
+                    false
+                }
+            };
+            if !installed {
+                continue;
+            }
+
+            // Totals are cluster-wide: read them from the first such database
+            if info.is_none() {
+                match db_client.client.query_one(BUFFERCACHE_SUMMARY_QUERY, &[]) {
+                    Ok(row) => {
+                        info = Some(PgBufferCacheInfo {
+                            total_buffers: row.get::<_, i64>(0).max(0) as u64,
+                            used_buffers: row.get::<_, i64>(1).max(0) as u64,
+                            dirty_buffers: row.get::<_, i64>(2).max(0) as u64,
+                            hot_buffers: row.get::<_, i64>(3).max(0) as u64,
+                            block_size: row.get::<_, i32>(4).max(0) as u32,
+                            relations: Vec::new(),
+                        })
+                    }
+                    Err(e) => {
+                        timed_out |= is_timeout(&e);
+                        warn!(database = %db_client.datname, error = %format_postgres_error(&e),
+                            "failed to collect buffer cache summary");
+                        continue;
+                    }
+                }
+            }
+            let rows = match db_client
+                .client
+                .query(BUFFERCACHE_RELATIONS_QUERY, &[&BUFFERCACHE_TOP_N])
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    timed_out |= is_timeout(&e);
+                    warn!(database = %db_client.datname, error = %format_postgres_error(&e),
+                        "failed to collect buffer cache");
+                    continue;
+                }
+            };
+            if let Some(info) = &mut info {
+                info.relations
+                    .extend(rows.iter().map(|row| PgBufferCacheRelation {
+                        datname: db_client.datname.clone(),
+                        schemaname: row.get(0),
+                        relname: row.get(1),
+                        kind: if row.get(2) {
+                            PgRelationKind::Index
+                        } else {
+                            PgRelationKind::Table
+                        },
+                        buffers: row.get::<_, i64>(3).max(0) as u64,
+                        dirty_buffers: row.get::<_, i64>(4).max(0) as u64,
+                        avg_usagecount: row.get(5),
+                    }));
+            }
+        }
+        self.guard
+            .record("buffercache", now.elapsed(), timed_out, Instant::now());
+        self.buffercache_time = Some(Instant::now());
+        if let Some(info) = &mut info {
+            info.relations.sort_by_key(|r| std::cmp::Reverse(r.buffers));
+        }
+        info
+    }
+}
@@ -0,0 +1,126 @@
+//! User-defined SQL metric queries collector.
+//!
+//! Runs each query from the custom queries file on the main connection at
+//! its own interval; between runs the last result is returned again.
+
+use std::time::Instant;
+
+use postgres::Row;
+use tracing::warn;
+
+use super::PostgresCollector;
+use super::format_postgres_error;
+use crate::collector::custom_query::CustomQueryDef;
+use crate::storage::model::{CustomColumnType, CustomQueryInfo, CustomQueryRow};
+
+/// A custom query with its last result.
+pub(crate) struct CustomQueryState {
+    pub def: CustomQueryDef,
+    pub last_run: Option<Instant>,
+    pub last_result: Option<CustomQueryInfo>,
+}
+
+impl PostgresCollector {
+    /// Sets the user-defined queries to collect (see
+    /// [`crate::collector::custom_query`]).
+    pub fn with_custom_queries(mut self, queries: Vec<CustomQueryDef>) -> Self {
+        self.custom_queries = queries
+            .into_iter()
+            .map(|def| CustomQueryState {
+                def,
+                last_run: None,
+                last_result: None,
+            })
+            .collect();
+        self
+    }
+
+    /// Runs the custom queries that are due and returns the latest result of
+    /// every query that has succeeded at least once.
+    ///
+    /// A failing query keeps its previous result.
+    pub fn collect_custom_queries(&mut self) -> Vec<CustomQueryInfo> {
+        let Some(ref mut client) = self.client else {
+            return Vec::new();
+        };
+
+        let mut results = Vec::new();
+        for q in &mut self.custom_queries {
+            let due = q.last_run.is_none_or(|t| t.elapsed() >= q.def.interval());
+            if due {
+                q.last_run = Some(Instant::now());
+                match client.query(q.def.sql.as_str(), &[]) {
+                    Ok(rows) => q.last_result = Some(build_result(&q.def, &rows)),
+                    Err(e) => warn!(
+                        query = %q.def.name, error = %format_postgres_error(&e),
+                        "failed to run custom query"
+                    ),
+                }
+            }
+            if let Some(ref result) = q.last_result {
+                results.push(result.clone());
+            }
+        }
+        results
+    }
+}
+
+fn build_result(def: &CustomQueryDef, rows: &[Row]) -> CustomQueryInfo {
+    let rows = rows
+        .iter()
+        .map(|row| {
+            let mut out = CustomQueryRow::default();
+            for c in &def.columns {
+                let idx = row.columns().iter().position(|rc| rc.name() == c.name);
+                match c.kind {
+                    CustomColumnType::Key => out
+                        .keys
+                        .push(idx.and_then(|i| read_key(row, i)).unwrap_or_default()),
+                    CustomColumnType::Int | CustomColumnType::Float => {
+                        out.values.push(idx.and_then(|i| read_value(row, i)))
+                    }
+                }
+            }
+            out
+        })
+        .collect();
+    CustomQueryInfo {
+        name: def.name.clone(),
+        columns: def.columns.clone(),
+        rows,
+    }
+}
+
+/// Reads a text or integer column as a label.
+fn read_key(row: &Row, idx: usize) -> Option<String> {
+    if let Ok(v) = row.try_get::<_, Option<String>>(idx) {
+        return v;
+    }
+    if let Ok(v) = row.try_get::<_, Option<i64>>(idx) {
+        return v.map(|v| v.to_string());
+    }
+    row.try_get::<_, Option<i32>>(idx)
+        .ok()
+        .flatten()
+        .map(|v| v.to_string())
+}
+
+/// Reads an integer or floating point column as `f64`.
+fn read_value(row: &Row, idx: usize) -> Option<f64> {
+    if let Ok(v) = row.try_get::<_, Option<f64>>(idx) {
+        return v;
+    }
+    if let Ok(v) = row.try_get::<_, Option<i64>>(idx) {
+        return v.map(|v| v as f64);
+    }
+    if let Ok(v) = row.try_get::<_, Option<i32>>(idx) {
+        return v.map(f64::from);
+    }
+    if let Ok(v) = row.try_get::<_, Option<f32>>(idx) {
+        return v.map(f64::from);
+    }
+    row.try_get::<_, Option<i16>>(idx)
+        .ok()
+        .flatten()
+        .map(f64::from)
+}
@@ -0,0 +1,66 @@
+//! Collector for the extensions installed in each database.
+//!
+//! Sampled once per hour from all databases via `db_clients`: extensions
+//! are installed and upgraded by hand, and the inventory is small.
+
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+use super::PostgresCollector;
+use super::format_postgres_error;
+use super::guard::is_timeout;
+use crate::storage::model::PgExtensionInfo;
+
+/// Interval between extension samples.
+const EXTENSIONS_INTERVAL: Duration = Duration::from_secs(3600);
+
+const EXTENSIONS_QUERY: &str = "\
+SELECT e.extname, e.extversion, n.nspname
+FROM pg_extension e
+JOIN pg_namespace n ON n.oid = e.extnamespace
+ORDER BY e.extname";
+
+impl PostgresCollector {
+    /// Samples the installed extensions of every database.
+    ///
+    /// Returns an empty list between hourly samples, so each sample is
+    /// stored once.
+    pub fn collect_extensions(&mut self) -> Vec<PgExtensionInfo> {
+        if self.throttled
+            || self
+                .extensions_time
+                .is_some_and(|t| t.elapsed() < EXTENSIONS_INTERVAL)
+        {
+            return Vec::new();
+        }
+        let now = Instant::now();
+        if self.db_clients.is_empty() || !self.guard.allow("extensions", now) {
+            return Vec::new();
+        }
+
+        let mut extensions = Vec::new();
+        let mut timed_out = false;
+        for db_client in &mut self.db_clients {
+            let rows = match db_client.client.query(EXTENSIONS_QUERY, &[]) {
+                Ok(rows) => rows,
+                Err(e) => {
+                    timed_out |= is_timeout(&e);
+                    warn!(database = %db_client.datname, error = %format_postgres_error(&e),
+                        "failed to collect extensions");
+                    continue;
+                }
+            };
+            extensions.extend(rows.iter().map(|row| PgExtensionInfo {
+                datname: db_client.datname.clone(),
+                name: row.get(0),
+                version: row.get(1),
+                schema: row.get(2),
+            }));
+        }
+        self.guard
+            .record("extensions", now.elapsed(), timed_out, Instant::now());
+        self.extensions_time = Some(Instant::now());
+        extensions
+    }
+}
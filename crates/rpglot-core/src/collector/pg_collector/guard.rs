@@ -0,0 +1,161 @@
+//! Cost guards for collection queries.
+//!
+//! Every collector connection runs with `statement_timeout`, `lock_timeout`
+//! and `idle_in_transaction_session_timeout` (see [`SESSION_OPTIONS`]), so a
+//! collection query never runs long or waits on a lock. A query that times
+//! out several times in a row (pg_stat_user_tables on an instance with a
+//! million tables) would still pay the full timeout on every attempt; the
+//! guard then pauses it, starting at [`PAUSE_MIN`] and doubling up to
+//! [`PAUSE_MAX`] while it keeps timing out. Paused queries are reported as
+//! agent warnings.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use postgres::error::SqlState;
+
+/// `options` of every collector connection.
+pub(crate) const SESSION_OPTIONS: &str = "-c statement_timeout=1000 -c lock_timeout=500 \
+     -c idle_in_transaction_session_timeout=5000";
+
+/// Consecutive timeouts after which a query is paused.
+const TIMEOUTS_BEFORE_PAUSE: u32 = 3;
+/// First pause.
+const PAUSE_MIN: Duration = Duration::from_secs(5 * 60);
+/// Longest pause.
+const PAUSE_MAX: Duration = Duration::from_secs(60 * 60);
+
+/// Whether the error is a cancellation by `statement_timeout` or a
+/// `lock_timeout`.
+pub(crate) fn is_timeout(e: &postgres::Error) -> bool {
+    matches!(
+        e.code(),
+        Some(&SqlState::QUERY_CANCELED) | Some(&SqlState::LOCK_NOT_AVAILABLE)
+    )
+}
+
+#[derive(Debug, Default)]
+struct QueryState {
+    /// Elapsed time of the last run, taken by [`QueryGuard::take_timings`].
+    elapsed: Option<Duration>,
+    consecutive_timeouts: u32,
+    /// Pauses in a row; the next pause is twice as long.
+    pauses: u32,
+    paused_until: Option<Instant>,
+    pause: Duration,
+}
+
+/// Elapsed time and timeout state of the guarded collection queries.
+#[derive(Debug, Default)]
+pub(crate) struct QueryGuard {
+    queries: BTreeMap<&'static str, QueryState>,
+}
+
+impl QueryGuard {
+    /// Whether `name` may run now (it is not paused).
+    pub fn allow(&self, name: &str, now: Instant) -> bool {
+        self.queries
+            .get(name)
+            .and_then(|q| q.paused_until)
+            .is_none_or(|until| now >= until)
+    }
+
+    /// Records a run of `name` that took `elapsed`.
+    pub fn record(&mut self, name: &'static str, elapsed: Duration, timed_out: bool, now: Instant) {
+        let q = self.queries.entry(name).or_default();
+        q.elapsed = Some(elapsed);
+        if !timed_out {
+            q.consecutive_timeouts = 0;
+            q.pauses = 0;
+            q.paused_until = None;
+            return;
+        }
+        q.consecutive_timeouts += 1;
+        // After a pause one more timeout is enough to pause again
+        if q.consecutive_timeouts >= TIMEOUTS_BEFORE_PAUSE || q.pauses > 0 {
+            q.pause = PAUSE_MIN
+                .saturating_mul(1 << q.pauses.min(16))
+                .min(PAUSE_MAX);
+            q.paused_until = Some(now + q.pause);
+            q.pauses += 1;
+        }
+    }
+
+    /// Elapsed times of the queries that ran since the last call.
+    pub fn take_timings(&mut self) -> Vec<(&'static str, Duration)> {
+        self.queries
+            .iter_mut()
+            .filter_map(|(name, q)| Some((*name, q.elapsed.take()?)))
+            .collect()
+    }
+
+    /// One message per paused query.
+    pub fn warnings(&self, now: Instant) -> Vec<String> {
+        self.queries
+            .iter()
+            .filter(|(_, q)| q.paused_until.is_some_and(|until| now < until))
+            .map(|(name, q)| {
+                format!(
+                    "{}: timed out {} times in a row, paused for {}s",
+                    name,
+                    q.consecutive_timeouts,
+                    q.pause.as_secs()
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pauses_query_that_keeps_timing_out() {
+        let mut guard = QueryGuard::default();
+        let now = Instant::now();
+        let second = Duration::from_secs(1);
+
+        for _ in 0..TIMEOUTS_BEFORE_PAUSE - 1 {
+            guard.record("pg_stat_user_tables", second, true, now);
+        }
+        assert!(guard.allow("pg_stat_user_tables", now));
+        assert!(guard.warnings(now).is_empty());
+
+        guard.record("pg_stat_user_tables", second, true, now);
+        assert!(!guard.allow("pg_stat_user_tables", now));
+        assert!(guard.allow("pg_locks", now));
+        assert_eq!(
+            guard.warnings(now),
+            ["pg_stat_user_tables: timed out 3 times in a row, paused for 300s"]
+        );
+
+        // Still timing out after the pause: the next pause is twice as long
+        let later = now + PAUSE_MIN;
+        assert!(guard.allow("pg_stat_user_tables", later));
+        guard.record("pg_stat_user_tables", second, true, later);
+        assert!(!guard.allow("pg_stat_user_tables", later + PAUSE_MIN));
+        assert!(guard.allow("pg_stat_user_tables", later + PAUSE_MIN * 2));
+
+        // A successful run clears the state
+        guard.record("pg_stat_user_tables", second, false, later + PAUSE_MIN * 2);
+        assert!(guard.allow("pg_stat_user_tables", later + PAUSE_MIN * 2));
+        assert!(guard.warnings(later).is_empty());
+    }
+
+    #[test]
+    fn takes_timings_once() {
+        let mut guard = QueryGuard::default();
+        let now = Instant::now();
+        guard.record("pg_stat_statements", Duration::from_millis(40), false, now);
+        guard.record("pg_locks", Duration::from_millis(2), false, now);
+        assert_eq!(
+            guard.take_timings(),
+            [
+                ("pg_locks", Duration::from_millis(2)),
+                ("pg_stat_statements", Duration::from_millis(40)),
+            ]
+        );
+        assert!(guard.take_timings().is_empty());
+    }
+}
@@ -10,6 +10,7 @@ use crate::storage::model::PgStatUserIndexesInfo;
 
 use super::PgCollectError;
 use super::PostgresCollector;
+use super::guard::is_timeout;
 use super::queries::{build_stat_user_indexes_query, build_statio_user_indexes_query};
 
 /// Cache entry for pg_stat_user_indexes rows.
@@ -27,21 +28,29 @@ pub(crate) struct PgStatUserIndexesCacheEntry {
 impl PostgresCollector {
     /// Collects pg_stat_user_indexes statistics from all connected databases.
     ///
-    /// Uses 30-second caching (same interval as pg_stat_statements).
+    /// Uses 30-second caching by default (see `with_tables_interval`).
     /// Returns cached data with re-interned strings if cache is fresh.
     pub fn collect_indexes(
         &mut self,
         interner: &mut StringInterner,
     ) -> Result<Vec<PgStatUserIndexesInfo>, PgCollectError> {
         // Return cached filtered data if fresh (re-intern strings for current interner state)
+        if self.throttled {
+            return Ok(self.return_filtered_indexes_cached(interner));
+        }
         if let Some(cache_time) = self.indexes_cache_time
-            && self.statements_collect_interval > std::time::Duration::ZERO
-            && cache_time.elapsed() < self.statements_collect_interval
+            && self.tables_collect_interval > std::time::Duration::ZERO
+            && cache_time.elapsed() < self.tables_collect_interval
             && !self.indexes_cache.is_empty()
         {
             return Ok(self.return_filtered_indexes_cached(interner));
         }
 
+        let now = Instant::now();
+        if !self.guard.allow("pg_stat_user_indexes", now) {
+            return Ok(self.return_filtered_indexes_cached(interner));
+        }
+
         let collected_at = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_secs() as i64)
@@ -53,12 +62,17 @@ impl PostgresCollector {
         let mut all_results = Vec::new();
         let mut all_cache = Vec::new();
 
+        let mut timed_out = false;
         for db_client in &mut self.db_clients {
             let datname = db_client.datname.clone();
 
             let rows = match db_client.client.query(query, &[]) {
                 Ok(rows) => rows,
-                Err(_) => continue, // skip this database on error
+                Err(e) => {
+                    // skip this database on error
+                    timed_out |= is_timeout(&e);
+                    continue;
+                }
             };
 
             let mut results = Vec::with_capacity(rows.len());
@@ -103,6 +117,12 @@ impl PostgresCollector {
             all_results.extend(results);
             all_cache.extend(cache);
         }
+        self.guard.record(
+            "pg_stat_user_indexes",
+            now.elapsed(),
+            timed_out,
+            Instant::now(),
+        );
 
         self.indexes_cache = all_cache;
         self.indexes_cache_time = Some(Instant::now());
@@ -1,9 +1,13 @@
 //! PostgreSQL lock tree collection.
 
+use std::time::Instant;
+
+use crate::collector::RedactMode;
 use crate::storage::interner::StringInterner;
 use crate::storage::model::PgLockTreeNode;
 
 use super::PostgresCollector;
+use super::guard::is_timeout;
 use super::queries::build_lock_tree_query;
 
 impl PostgresCollector {
@@ -13,19 +17,33 @@ impl PostgresCollector {
     /// Returns an empty vector when there are no blocking chains or on error.
     /// No caching — lock state changes rapidly.
     pub fn collect_lock_tree(&mut self, interner: &mut StringInterner) -> Vec<PgLockTreeNode> {
+        if !self.guard.allow("pg_locks", Instant::now()) {
+            return Vec::new();
+        }
         if let Err(e) = self.ensure_connected() {
             self.last_error = Some(e.to_string());
             return Vec::new();
         }
 
+        let redact = self.redact;
         let client = self.client.as_mut().unwrap();
         let query = build_lock_tree_query();
 
-        match client.query(query, &[]) {
+        let started = Instant::now();
+        let result = client.query(query, &[]);
+        let timed_out = result.as_ref().is_err_and(is_timeout);
+        self.guard
+            .record("pg_locks", started.elapsed(), timed_out, Instant::now());
+
+        match result {
             Ok(rows) => rows
                 .iter()
-                .filter_map(|row| parse_lock_tree_row(row, interner))
+                .filter_map(|row| parse_lock_tree_row(row, interner, redact))
                 .collect(),
+            Err(e) if timed_out => {
+                self.last_error = Some(super::format_postgres_error(&e));
+                Vec::new()
+            }
             Err(e) => {
                 let msg = super::format_postgres_error(&e);
                 self.last_error = Some(msg);
@@ -44,6 +62,7 @@ impl PostgresCollector {
 fn parse_lock_tree_row(
     row: &postgres::Row,
     interner: &mut StringInterner,
+    redact: RedactMode,
 ) -> Option<PgLockTreeNode> {
     let pid: i32 = row.try_get(0).ok()?;
     let depth: i32 = row.try_get(1).unwrap_or(1);
@@ -76,7 +95,7 @@ fn parse_lock_tree_row(
         state_hash: interner.intern(&state),
         wait_event_type_hash: interner.intern(&wait_event_type),
         wait_event_hash: interner.intern(&wait_event),
-        query_hash: interner.intern(&query),
+        query_hash: interner.intern(&redact.apply(&query)),
         application_name_hash: interner.intern(&application_name),
         backend_type_hash: interner.intern(&backend_type),
         xact_start,
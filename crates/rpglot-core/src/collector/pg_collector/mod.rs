@@ -6,10 +6,14 @@
 //! - `pg_stat_database` — per-database statistics (instance-level)
 //! - `pg_stat_user_tables` — per-database table statistics
 //! - `pg_stat_user_indexes` — per-database index statistics
+//! - `pg_total_relation_size()` — sizes of the largest relations (hourly)
+//! - `pg_sequences` — sequences of integer primary keys (hourly)
+//! - `pg_buffercache` — relations occupying shared_buffers (every 5 minutes, requires extension)
+//! - user-defined queries from the custom queries file
 //!
 //! ## Multi-database collection
 //!
-//! Instance-level metrics (activity, statements, database, bgwriter, locks, logs) are
+//! Instance-level metrics (activity, statements, database, bgwriter, archiver, locks, logs) are
 //! collected via a single "main" connection to any database.
 //!
 //! Per-database metrics (tables, indexes) require a separate connection to each database.
@@ -21,38 +25,59 @@
 //! the specified database is used for both instance-level and per-database metrics.
 
 mod activity;
+mod archiver;
 mod bgwriter;
+mod buffercache;
+mod custom;
 mod database;
+mod extensions;
+mod guard;
 mod indexes;
 mod locks;
 mod progress_vacuum;
 mod queries;
+mod relation_sizes;
 mod replication;
+mod sequences;
 mod settings;
 mod statements;
 mod store_plans;
+mod supervisor;
 mod tables;
+mod tls;
 
-use postgres::{Client, NoTls};
+use postgres::Client;
 use std::collections::{HashMap, HashSet};
 use std::env;
-use std::time::{Duration, Instant};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, info, warn};
 
+use super::RedactMode;
 use super::log_collector::LogCollector;
+use super::log_collector::tailer::LogSourceConfig;
 use crate::storage::model::{
-    ActivityFiltered, PgSettingEntry, PgStatStatementsInfo, PgStatUserIndexesInfo,
-    PgStatUserTablesInfo, PgStorePlansInfo, ReplicationStatus,
+    ActivityFiltered, PgConnectionStatus, PgSettingEntry, PgSettingMeta, PgStatStatementsInfo,
+    PgStatUserIndexesInfo, PgStatUserTablesInfo, PgStorePlansInfo, ReplicationStatus,
 };
+use custom::CustomQueryState;
+use guard::{QueryGuard, SESSION_OPTIONS};
 use indexes::PgStatUserIndexesCacheEntry;
 use queries::StorePlansFork;
 use statements::{PgStatStatementsCacheEntry, STATEMENTS_COLLECT_INTERVAL};
 use store_plans::PgStorePlansCacheEntry;
+use supervisor::ConnectionSupervisor;
 use tables::PgStatUserTablesCacheEntry;
+use tls::PgTls;
+pub use tls::SslMode;
 
 /// Interval between database pool refresh checks.
 const DB_POOL_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
 
+/// Connection timeout when neither the parameters nor `PGCONNECT_TIMEOUT`
+/// set one: an unreachable server must not stall the snapshot loop.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Error type for PostgreSQL collection.
 #[derive(Debug)]
 pub enum PgCollectError {
@@ -62,6 +87,8 @@ pub enum PgCollectError {
     ConnectionError(String),
     /// Query execution failed.
     QueryError(String),
+    /// Invalid TLS settings (sslmode, root certificates).
+    TlsError(String),
 }
 
 impl std::fmt::Display for PgCollectError {
@@ -70,12 +97,32 @@ impl std::fmt::Display for PgCollectError {
             PgCollectError::EnvNotSet(var) => write!(f, "PostgreSQL: {} not set", var),
             PgCollectError::ConnectionError(msg) => write!(f, "PostgreSQL: {}", msg),
             PgCollectError::QueryError(msg) => write!(f, "PostgreSQL query error: {}", msg),
+            PgCollectError::TlsError(msg) => write!(f, "PostgreSQL TLS: {}", msg),
         }
     }
 }
 
 impl std::error::Error for PgCollectError {}
 
+/// PostgreSQL connection parameters.
+///
+/// Unset fields fall back to the libpq environment variables (see
+/// [`PostgresCollector::from_env`]). A host starting with `/` is a unix
+/// socket directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgConnectionParams {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub database: Option<String>,
+    pub sslmode: Option<SslMode>,
+    /// PEM file with the root certificates for `verify-ca` / `verify-full`,
+    /// or `system` for the operating system trust store.
+    pub sslrootcert: Option<PathBuf>,
+    pub connect_timeout: Option<Duration>,
+}
+
 /// A connection to a specific database for per-database metric collection.
 pub(crate) struct DatabaseClient {
     pub datname: String,
@@ -111,8 +158,15 @@ pub(crate) fn filter_active<T: ActivityFiltered>(
 /// - PGUSER (default: $USER)
 /// - PGPASSWORD (default: empty)
 /// - PGDATABASE (default: same as PGUSER)
+/// - PGSSLMODE (default: prefer), PGSSLROOTCERT (default: ~/.postgresql/root.crt)
+/// - PGCONNECT_TIMEOUT (default: 5 seconds)
 pub struct PostgresCollector {
     connection_string: String,
+    tls: PgTls,
+    /// Reconnect backoff and connection health.
+    supervisor: ConnectionSupervisor,
+    /// Elapsed time and timeout pauses of the expensive queries.
+    pub(crate) guard: QueryGuard,
     /// Main connection for instance-level metrics.
     pub(crate) client: Option<Client>,
     pub(crate) last_error: Option<String>,
@@ -129,12 +183,31 @@ pub struct PostgresCollector {
     /// Interval for pg_stat_statements caching. Default: 30 seconds.
     /// Set to Duration::ZERO to disable caching (fetch fresh data every call).
     pub(crate) statements_collect_interval: Duration,
+    /// Interval for pg_stat_user_tables / pg_stat_user_indexes caching. Default: 30 seconds.
+    pub(crate) tables_collect_interval: Duration,
+    /// Host is overloaded: statements, store plans, tables and indexes are
+    /// served from the cache regardless of its age.
+    pub(crate) throttled: bool,
     pub(crate) tables_cache: Vec<PgStatUserTablesCacheEntry>,
     pub(crate) tables_cache_time: Option<Instant>,
     pub(crate) indexes_cache: Vec<PgStatUserIndexesCacheEntry>,
     pub(crate) indexes_cache_time: Option<Instant>,
     pub(crate) settings_cache: Vec<PgSettingEntry>,
     pub(crate) settings_cache_time: Option<Instant>,
+    /// `pg_conf_load_time()` seen by the last settings check.
+    pub(crate) conf_load_time: Option<String>,
+    /// Settings waiting for a restart, refreshed with the settings cache.
+    pub(crate) pending_restart: Vec<String>,
+    /// Category, source and boot value of the cached settings.
+    pub(crate) settings_meta_cache: Vec<PgSettingMeta>,
+    /// Last relation size sample (taken hourly).
+    pub(crate) relation_sizes_time: Option<Instant>,
+    /// Last primary key sequence sample (taken hourly).
+    pub(crate) sequences_time: Option<Instant>,
+    /// Last extension inventory sample (taken hourly).
+    pub(crate) extensions_time: Option<Instant>,
+    /// Last shared_buffers composition sample (taken every 5 minutes).
+    pub(crate) buffercache_time: Option<Instant>,
     // --- Activity-only storage: prev snapshots for filtering unchanged rows ---
     /// Previous full pg_stat_statements snapshot (by queryid), used to filter unchanged rows.
     pub(crate) pgs_prev: HashMap<i64, PgStatStatementsInfo>,
@@ -178,12 +251,19 @@ pub struct PostgresCollector {
     pub(crate) replication_cache_time: Option<Instant>,
     /// PostgreSQL log file collector.
     log_collector: LogCollector,
+    /// Redaction applied to query texts and plans before interning.
+    pub(crate) redact: RedactMode,
+    /// User-defined queries with their last results.
+    pub(crate) custom_queries: Vec<CustomQueryState>,
 }
 
 impl PostgresCollector {
-    fn new_inner(connection_string: String, explicit_database: bool) -> Self {
+    fn new_inner(connection_string: String, tls: PgTls, explicit_database: bool) -> Self {
         Self {
             connection_string,
+            tls,
+            supervisor: ConnectionSupervisor::default(),
+            guard: QueryGuard::default(),
             client: None,
             last_error: None,
             server_version_num: None,
@@ -194,12 +274,21 @@ impl PostgresCollector {
             statements_cache: Vec::new(),
             statements_cache_time: None,
             statements_collect_interval: STATEMENTS_COLLECT_INTERVAL,
+            tables_collect_interval: STATEMENTS_COLLECT_INTERVAL,
+            throttled: false,
             tables_cache: Vec::new(),
             tables_cache_time: None,
             indexes_cache: Vec::new(),
             indexes_cache_time: None,
             settings_cache: Vec::new(),
             settings_cache_time: None,
+            conf_load_time: None,
+            pending_restart: Vec::new(),
+            settings_meta_cache: Vec::new(),
+            relation_sizes_time: None,
+            sequences_time: None,
+            extensions_time: None,
+            buffercache_time: None,
             pgs_prev: HashMap::new(),
             pgs_first_collect: true,
             pgs_filtered_cache: Vec::new(),
@@ -222,6 +311,8 @@ impl PostgresCollector {
             replication_cache: None,
             replication_cache_time: None,
             log_collector: LogCollector::new(),
+            redact: RedactMode::Off,
+            custom_queries: Vec::new(),
         }
     }
 
@@ -229,36 +320,90 @@ impl PostgresCollector {
     ///
     /// Uses $USER as default if PGUSER is not set.
     pub fn from_env() -> Result<Self, PgCollectError> {
-        let user = env::var("PGUSER")
-            .or_else(|_| env::var("USER"))
-            .map_err(|_| PgCollectError::EnvNotSet("PGUSER or USER".to_string()))?;
-
-        let host = env::var("PGHOST").unwrap_or_else(|_| "localhost".to_string());
-        let port = env::var("PGPORT").unwrap_or_else(|_| "5432".to_string());
-        let password = env::var("PGPASSWORD").unwrap_or_default();
-        let explicit_database = env::var("PGDATABASE").is_ok();
-        let database = env::var("PGDATABASE").unwrap_or_else(|_| user.clone());
-
-        let connection_string = if password.is_empty() {
-            format!(
-                "host={} port={} user={} dbname={} application_name=rpglot options='-c statement_timeout=1000'",
-                host, port, user, database
-            )
-        } else {
-            format!(
-                "host={} port={} user={} password={} dbname={} application_name=rpglot options='-c statement_timeout=1000'",
-                host, port, user, password, database
-            )
-        };
+        Self::from_params(&PgConnectionParams::default())
+    }
 
-        Ok(Self::new_inner(connection_string, explicit_database))
+    /// Creates a new PostgreSQL collector from explicit parameters.
+    ///
+    /// Parameters that are `None` are taken from the environment like in
+    /// [`from_env`](Self::from_env).
+    pub fn from_params(params: &PgConnectionParams) -> Result<Self, PgCollectError> {
+        let user = params
+            .user
+            .clone()
+            .or_else(|| env::var("PGUSER").ok())
+            .or_else(|| env::var("USER").ok())
+            .ok_or_else(|| PgCollectError::EnvNotSet("PGUSER or USER".to_string()))?;
+
+        let host = params
+            .host
+            .clone()
+            .unwrap_or_else(|| env::var("PGHOST").unwrap_or_else(|_| "localhost".to_string()));
+        let port = params
+            .port
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| env::var("PGPORT").unwrap_or_else(|_| "5432".to_string()));
+        let password = params
+            .password
+            .clone()
+            .unwrap_or_else(|| env::var("PGPASSWORD").unwrap_or_default());
+        let database = params
+            .database
+            .clone()
+            .or_else(|| env::var("PGDATABASE").ok());
+        let explicit_database = database.is_some();
+        let database = database.unwrap_or_else(|| user.clone());
+        let sslmode = match params.sslmode {
+            Some(mode) => mode,
+            None => match env::var("PGSSLMODE") {
+                Ok(mode) => SslMode::parse(&mode).map_err(PgCollectError::TlsError)?,
+                Err(_) => SslMode::default(),
+            },
+        };
+        let sslrootcert = params
+            .sslrootcert
+            .clone()
+            .or_else(|| env::var_os("PGSSLROOTCERT").map(PathBuf::from));
+        let connect_timeout = params
+            .connect_timeout
+            .or_else(|| {
+                env::var("PGCONNECT_TIMEOUT")
+                    .ok()
+                    .and_then(|secs| secs.trim().parse().ok())
+                    .map(Duration::from_secs)
+            })
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+
+        let mut connection_string = format!(
+            "host={} port={} user={}",
+            quote_value(&host),
+            port,
+            quote_value(&user)
+        );
+        if !password.is_empty() {
+            connection_string.push_str(&format!(" password={}", quote_value(&password)));
+        }
+        connection_string.push_str(&format!(
+            " dbname={} sslmode={} connect_timeout={} application_name=rpglot options='{}'",
+            quote_value(&database),
+            sslmode.connection_value(),
+            connect_timeout.as_secs().max(1),
+            SESSION_OPTIONS
+        ));
+
+        let tls = PgTls::new(sslmode, sslrootcert.as_deref()).map_err(PgCollectError::TlsError)?;
+        Ok(Self::new_inner(connection_string, tls, explicit_database))
     }
 
     /// Creates a collector with explicit connection string.
     ///
     /// Multi-database collection is disabled (treated as explicit database).
+    /// TLS is negotiated per the string's `sslmode` without verifying the
+    /// server certificate; use [`from_params`](Self::from_params) for
+    /// `verify-ca` / `verify-full`.
     pub fn with_connection_string(connection_string: String) -> Self {
-        Self::new_inner(connection_string, true)
+        let tls = PgTls::new(SslMode::Prefer, None).unwrap_or(PgTls::Plain);
+        Self::new_inner(connection_string, tls, true)
     }
 
     /// Sets the interval for pg_stat_statements caching.
@@ -266,10 +411,57 @@ impl PostgresCollector {
     /// Default: 30 seconds. Set to `Duration::ZERO` to disable caching
     /// and fetch fresh data on every call.
     pub fn with_statements_interval(mut self, interval: Duration) -> Self {
+        self.set_statements_interval(interval);
+        self
+    }
+
+    /// Changes the pg_stat_statements caching interval.
+    pub fn set_statements_interval(&mut self, interval: Duration) {
         self.statements_collect_interval = interval;
+    }
+
+    /// Sets the interval for pg_stat_user_tables / pg_stat_user_indexes caching.
+    ///
+    /// Default: 30 seconds. Set to `Duration::ZERO` to disable caching.
+    pub fn with_tables_interval(mut self, interval: Duration) -> Self {
+        self.set_tables_interval(interval);
         self
     }
 
+    /// Changes the tables / indexes caching interval.
+    pub fn set_tables_interval(&mut self, interval: Duration) {
+        self.tables_collect_interval = interval;
+    }
+
+    /// Skips refreshing pg_stat_statements, pg_store_plans and table / index
+    /// statistics while `throttled`; the cached data is returned instead.
+    pub fn set_throttled(&mut self, throttled: bool) {
+        self.throttled = throttled;
+    }
+
+    /// Sets where PostgreSQL log lines are read from.
+    ///
+    /// Default: the file reported by `pg_current_logfile()`.
+    pub fn with_log_source(mut self, source: LogSourceConfig) -> Self {
+        self.log_collector.set_source(source);
+        self
+    }
+
+    /// Sets how query texts (activity, locks, statements, plans, log
+    /// statements) are redacted before interning.
+    ///
+    /// Default: [`RedactMode::Off`].
+    pub fn with_redaction(mut self, mode: RedactMode) -> Self {
+        self.set_redaction(mode);
+        self
+    }
+
+    /// Changes the redaction mode; applies to texts collected from now on.
+    pub fn set_redaction(&mut self, mode: RedactMode) {
+        self.redact = mode;
+        self.log_collector.set_redaction(mode);
+    }
+
     /// Attempts to connect to PostgreSQL.
     ///
     /// Returns `Ok(())` if connection succeeds, or an error describing the failure.
@@ -290,13 +482,46 @@ impl PostgresCollector {
         self.statements_collect_interval
     }
 
+    /// Elapsed time of the guarded queries (statements, plans, tables,
+    /// indexes, locks) that ran since the last call.
+    pub fn take_query_timings(&mut self) -> Vec<(&'static str, Duration)> {
+        self.guard.take_timings()
+    }
+
+    /// Queries paused after repeated timeouts, one message each.
+    pub fn query_warnings(&self) -> Vec<String> {
+        self.guard.warnings(Instant::now())
+    }
+
+    /// Connection state to record in the snapshot: every snapshot while the
+    /// connection is down, once after it is (re)established.
+    pub fn connection_status(&mut self) -> Option<PgConnectionStatus> {
+        self.supervisor.report()
+    }
+
     /// Ensures the main connection is established, reconnecting if needed.
+    ///
+    /// After a failed attempt no new one is made until the backoff of the
+    /// [`ConnectionSupervisor`] expires; until then the last error is returned
+    /// right away.
     pub(crate) fn ensure_connected(&mut self) -> Result<(), PgCollectError> {
         if self.client.is_some() {
             return Ok(());
         }
+        let now = Instant::now();
+        if let Some(wait) = self.supervisor.retry_in(now) {
+            return Err(PgCollectError::ConnectionError(format!(
+                "{} (retry in {}s)",
+                self.supervisor.last_error(),
+                wait.as_secs().max(1)
+            )));
+        }
+        let unix_now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
 
-        match Client::connect(&self.connection_string, NoTls) {
+        match self.tls.connect(&self.connection_string) {
             Ok(client) => {
                 let mut client = client;
 
@@ -335,12 +560,14 @@ impl PostgresCollector {
 
                 self.client = Some(client);
                 self.last_error = None;
+                self.supervisor.connected(unix_now);
 
                 Ok(())
             }
             Err(e) => {
                 let msg = format_postgres_error(&e);
                 self.last_error = Some(msg.clone());
+                self.supervisor.failed(msg.clone(), now, unix_now);
                 self.server_version_num = None;
                 self.largest_dbname = None;
                 self.clear_caches();
@@ -376,7 +603,7 @@ impl PostgresCollector {
                 if let Ok(row) = main_client.query_one("SELECT current_database()", &[]) {
                     let datname: String = row.get(0);
                     let conn_str = replace_dbname(&self.connection_string, &datname);
-                    match Client::connect(&conn_str, NoTls) {
+                    match self.tls.connect(&conn_str) {
                         Ok(client) => {
                             self.db_clients.push(DatabaseClient {
                                 datname: datname.clone(),
@@ -446,7 +673,7 @@ impl PostgresCollector {
             }
 
             let conn_str = replace_dbname(&self.connection_string, db);
-            match Client::connect(&conn_str, NoTls) {
+            match self.tls.connect(&conn_str) {
                 Ok(client) => {
                     self.db_clients.push(DatabaseClient {
                         datname: db.clone(),
@@ -496,6 +723,9 @@ impl PostgresCollector {
         self.indexes_cache_time = None;
         self.settings_cache.clear();
         self.settings_cache_time = None;
+        self.conf_load_time = None;
+        self.pending_restart.clear();
+        self.settings_meta_cache.clear();
         self.store_plans_ext_version = None;
         self.store_plans_last_check = None;
         self.store_plans_fork = None;
@@ -523,6 +753,11 @@ impl PostgresCollector {
         Some(format!("{}.{}", v / 10000, (v / 100) % 100))
     }
 
+    /// Server port from the connection string (default 5432).
+    pub fn port(&self) -> u16 {
+        connection_port(&self.connection_string)
+    }
+
     /// Returns instance metadata (database name + PG version).
     pub fn instance_info(&self) -> Option<(String, String)> {
         Some((self.largest_dbname.clone()?, self.pg_version_string()?))
@@ -579,6 +814,27 @@ fn replace_dbname(connection_string: &str, new_db: &str) -> String {
     }
 }
 
+/// Quotes a libpq key=value connection string value when needed.
+fn quote_value(value: &str) -> String {
+    if !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '\'' || c == '\\')
+    {
+        return value.to_string();
+    }
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Port from a libpq key=value connection string, 5432 if absent or invalid.
+fn connection_port(connection_string: &str) -> u16 {
+    connection_string
+        .split_whitespace()
+        .find_map(|token| token.strip_prefix("port="))
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(5432)
+}
+
 /// Formats PostgreSQL error message for display.
 pub(crate) fn format_postgres_error(e: &postgres::Error) -> String {
     if let Some(db_error) = e.as_db_error() {
@@ -623,6 +879,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn connection_port_parses_or_defaults() {
+        assert_eq!(connection_port("host=/tmp port=6432 user=app"), 6432);
+        assert_eq!(connection_port("host=localhost user=app"), 5432);
+        assert_eq!(connection_port("host=localhost port=abc"), 5432);
+    }
+
+    #[test]
+    fn builds_connection_string_from_params() {
+        let collector = PostgresCollector::from_params(&PgConnectionParams {
+            host: Some("/var/run/postgresql".to_string()),
+            port: Some(5433),
+            user: Some("rpglot".to_string()),
+            password: Some("it's secret".to_string()),
+            database: Some("app".to_string()),
+            sslmode: Some(SslMode::Require),
+            connect_timeout: Some(Duration::from_secs(3)),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            collector.connection_string,
+            "host=/var/run/postgresql port=5433 user=rpglot password='it\\'s secret' dbname=app \
+             sslmode=require connect_timeout=3 application_name=rpglot \
+             options='-c statement_timeout=1000 -c lock_timeout=500 -c idle_in_transaction_session_timeout=5000'"
+        );
+        assert!(matches!(collector.tls, PgTls::Rustls(_)));
+        assert_eq!(connection_port(&collector.connection_string), 5433);
+
+        let plain = PostgresCollector::from_params(&PgConnectionParams {
+            user: Some("rpglot".to_string()),
+            sslmode: Some(SslMode::Disable),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(matches!(plain.tls, PgTls::Plain));
+        assert!(plain.connection_string.contains(" sslmode=disable "));
+    }
+
+    #[test]
+    fn quotes_connection_values() {
+        assert_eq!(quote_value("app"), "app");
+        assert_eq!(quote_value(""), "''");
+        assert_eq!(quote_value("a b"), "'a b'");
+        assert_eq!(quote_value("a\\b'"), "'a\\\\b\\''");
+    }
+
     #[test]
     fn replace_dbname_handles_dbname_at_start() {
         let conn = "dbname=old host=localhost user=app";
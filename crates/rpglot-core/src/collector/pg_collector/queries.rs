@@ -11,7 +11,7 @@ pub(super) fn build_stat_activity_query(server_version_num: Option<i32>) -> Stri
     format!(
         r#"
             SELECT
-                pid,
+                a.pid,
                 COALESCE(datname, '') as datname,
                 COALESCE(usename, '') as usename,
                 COALESCE(application_name, '') as application_name,
@@ -25,8 +25,12 @@ pub(super) fn build_stat_activity_query(server_version_num: Option<i32>) -> Stri
                 COALESCE(EXTRACT(EPOCH FROM backend_start)::double precision, 0) as backend_start,
                 COALESCE(EXTRACT(EPOCH FROM xact_start)::double precision, 0) as xact_start,
                 COALESCE(EXTRACT(EPOCH FROM query_start)::double precision, 0) as query_start,
-                EXTRACT(EPOCH FROM now())::double precision as collected_at
-            FROM pg_stat_activity
+                EXTRACT(EPOCH FROM now())::double precision as collected_at,
+                COALESCE(backend_xid::text::bigint, 0) as backend_xid,
+                COALESCE(age(backend_xmin), 0) as backend_xmin_age,
+                COALESCE(s.ssl, false) as ssl
+            FROM pg_stat_activity a
+            LEFT JOIN pg_stat_ssl s ON s.pid = a.pid
         "#
     )
 }
@@ -537,6 +541,21 @@ mod tests {
         assert!(!q.contains("COALESCE(query_id"));
     }
 
+    #[test]
+    fn stat_activity_query_includes_transaction_ids() {
+        let q = build_stat_activity_query(Some(130000));
+        assert!(q.contains("COALESCE(backend_xid::text::bigint, 0) as backend_xid"));
+        assert!(q.contains("COALESCE(age(backend_xmin), 0) as backend_xmin_age"));
+    }
+
+    #[test]
+    fn stat_activity_query_joins_pg_stat_ssl() {
+        let q = build_stat_activity_query(Some(130000));
+        assert!(q.contains("a.pid,"));
+        assert!(q.contains("COALESCE(s.ssl, false) as ssl"));
+        assert!(q.contains("LEFT JOIN pg_stat_ssl s ON s.pid = a.pid"));
+    }
+
     #[test]
     fn stat_statements_query_uses_exec_time_columns_on_pg13_plus() {
         let q = build_stat_statements_query(Some(130000));
@@ -0,0 +1,148 @@
+//! Collector for the sizes of the largest tables and indexes.
+//!
+//! Sampled once per hour from all databases via `db_clients`: sizes change
+//! slowly and `pg_total_relation_size` stats every fork of every relation it
+//! looks at. Candidates are preselected by `relpages` (as of the last
+//! VACUUM/ANALYZE), so only a few hundred relations per database are sized.
+
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+use super::PostgresCollector;
+use super::format_postgres_error;
+use super::guard::is_timeout;
+use crate::storage::model::{PgRelationKind, PgRelationSizeInfo};
+
+/// Interval between relation size samples.
+const RELATION_SIZES_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Tables and indexes kept per database (each).
+const RELATION_SIZES_TOP_N: usize = 50;
+
+/// Candidates sized per database, by `relpages` including TOAST.
+const RELATION_SIZES_CANDIDATES: i64 = 4 * RELATION_SIZES_TOP_N as i64;
+
+const RELATION_SIZES_QUERY: &str = "\
+WITH candidates AS (
+    SELECT c.oid, n.nspname, c.relname, c.relkind
+    FROM pg_class c
+    JOIN pg_namespace n ON n.oid = c.relnamespace
+    LEFT JOIN pg_class t ON t.oid = c.reltoastrelid
+    WHERE c.relkind IN ('r', 'm', 'i')
+      AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+      AND n.nspname !~ '^pg_toast'
+    ORDER BY c.relpages::bigint + COALESCE(t.relpages, 0) DESC
+    LIMIT $1
+)
+SELECT oid, nspname, relname, relkind = 'i' AS is_index,
+    CASE WHEN relkind = 'i' THEN pg_relation_size(oid)
+         ELSE pg_total_relation_size(oid) END AS bytes
+FROM candidates";
+
+impl PostgresCollector {
+    /// Samples the sizes of the largest tables and indexes of every database.
+    ///
+    /// Returns an empty list between hourly samples, so each sample is
+    /// stored once.
+    pub fn collect_relation_sizes(&mut self) -> Vec<PgRelationSizeInfo> {
+        if self.throttled
+            || self
+                .relation_sizes_time
+                .is_some_and(|t| t.elapsed() < RELATION_SIZES_INTERVAL)
+        {
+            return Vec::new();
+        }
+        let now = Instant::now();
+        if self.db_clients.is_empty() || !self.guard.allow("relation_sizes", now) {
+            return Vec::new();
+        }
+
+        let mut sizes = Vec::new();
+        let mut timed_out = false;
+        for db_client in &mut self.db_clients {
+            let rows = match db_client
+                .client
+                .query(RELATION_SIZES_QUERY, &[&RELATION_SIZES_CANDIDATES])
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    timed_out |= is_timeout(&e);
+                    warn!(database = %db_client.datname, error = %format_postgres_error(&e),
+                        "failed to collect relation sizes");
+                    continue;
+                }
+            };
+            let relations = rows.iter().filter_map(|row| {
+                // NULL when the relation was dropped meanwhile
+                let bytes: i64 = row.get::<_, Option<i64>>(4)?;
+                Some(PgRelationSizeInfo {
+                    datname: db_client.datname.clone(),
+                    schemaname: row.get(1),
+                    relname: row.get(2),
+                    relid: row.get(0),
+                    kind: if row.get(3) {
+                        PgRelationKind::Index
+                    } else {
+                        PgRelationKind::Table
+                    },
+                    bytes: bytes.max(0) as u64,
+                })
+            });
+            sizes.extend(largest(relations.collect(), RELATION_SIZES_TOP_N));
+        }
+        self.guard
+            .record("relation_sizes", now.elapsed(), timed_out, Instant::now());
+        self.relation_sizes_time = Some(Instant::now());
+        sizes
+    }
+}
+
+/// The `n` largest tables and the `n` largest indexes, largest first.
+fn largest(mut relations: Vec<PgRelationSizeInfo>, n: usize) -> Vec<PgRelationSizeInfo> {
+    relations.sort_by_key(|r| std::cmp::Reverse(r.bytes));
+    let mut tables = 0;
+    let mut indexes = 0;
+    relations.retain(|r| {
+        let count = match r.kind {
+            PgRelationKind::Table => &mut tables,
+            PgRelationKind::Index => &mut indexes,
+        };
+        *count += 1;
+        *count <= n
+    });
+    relations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relation(relname: &str, kind: PgRelationKind, bytes: u64) -> PgRelationSizeInfo {
+        PgRelationSizeInfo {
+            datname: "app".to_string(),
+            schemaname: "public".to_string(),
+            relname: relname.to_string(),
+            relid: 0,
+            kind,
+            bytes,
+        }
+    }
+
+    #[test]
+    fn keeps_largest_tables_and_indexes() {
+        let relations = vec![
+            relation("small", PgRelationKind::Table, 10),
+            relation("orders_pkey", PgRelationKind::Index, 500),
+            relation("orders", PgRelationKind::Table, 1000),
+            relation("events", PgRelationKind::Table, 2000),
+            relation("events_pkey", PgRelationKind::Index, 100),
+            relation("orders_created_idx", PgRelationKind::Index, 50),
+        ];
+        let names: Vec<_> = largest(relations, 2)
+            .into_iter()
+            .map(|r| r.relname)
+            .collect();
+        assert_eq!(names, ["events", "orders", "orders_pkey", "events_pkey"]);
+    }
+}
@@ -0,0 +1,89 @@
+//! Collector for the sequences behind integer primary keys.
+//!
+//! Sampled once per hour from all databases via `db_clients`: sequences are
+//! exhausted over weeks, and hourly samples are enough for the consumption
+//! rate. Only sequences owned by a primary key column (`serial` or identity)
+//! are reported, with the column type's maximum as their effective limit:
+//! an `integer` column runs out long before its `bigint` sequence.
+
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+use super::PostgresCollector;
+use super::format_postgres_error;
+use super::guard::is_timeout;
+use crate::storage::model::PgSequenceInfo;
+
+/// Interval between sequence samples.
+const SEQUENCES_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// `pg_sequences` (PG 10+) hides `last_value` of never used sequences and
+/// of sequences the role may not read; both are skipped.
+const SEQUENCES_QUERY: &str = "\
+SELECT s.schemaname, s.sequencename, c.relname, a.attname,
+    format_type(a.atttypid, NULL) AS data_type,
+    s.last_value,
+    LEAST(s.max_value, CASE a.atttypid
+        WHEN 'int2'::regtype THEN 32767
+        WHEN 'int4'::regtype THEN 2147483647
+        ELSE 9223372036854775807::bigint END) AS max_value
+FROM pg_sequences s
+JOIN pg_namespace sn ON sn.nspname = s.schemaname
+JOIN pg_class sc ON sc.relnamespace = sn.oid AND sc.relname = s.sequencename
+JOIN pg_depend d ON d.classid = 'pg_class'::regclass AND d.objid = sc.oid
+    AND d.refclassid = 'pg_class'::regclass AND d.deptype IN ('a', 'i')
+JOIN pg_class c ON c.oid = d.refobjid
+JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = d.refobjsubid
+JOIN pg_index i ON i.indrelid = c.oid AND i.indisprimary AND a.attnum = ANY(i.indkey)
+WHERE s.last_value IS NOT NULL
+  AND s.increment_by > 0
+  AND a.atttypid IN ('int2'::regtype, 'int4'::regtype, 'int8'::regtype)";
+
+impl PostgresCollector {
+    /// Samples the primary key sequences of every database.
+    ///
+    /// Returns an empty list between hourly samples, so each sample is
+    /// stored once.
+    pub fn collect_sequences(&mut self) -> Vec<PgSequenceInfo> {
+        if self.throttled
+            || self
+                .sequences_time
+                .is_some_and(|t| t.elapsed() < SEQUENCES_INTERVAL)
+        {
+            return Vec::new();
+        }
+        let now = Instant::now();
+        if self.db_clients.is_empty() || !self.guard.allow("sequences", now) {
+            return Vec::new();
+        }
+
+        let mut sequences = Vec::new();
+        let mut timed_out = false;
+        for db_client in &mut self.db_clients {
+            let rows = match db_client.client.query(SEQUENCES_QUERY, &[]) {
+                Ok(rows) => rows,
+                Err(e) => {
+                    timed_out |= is_timeout(&e);
+                    warn!(database = %db_client.datname, error = %format_postgres_error(&e),
+                        "failed to collect sequences");
+                    continue;
+                }
+            };
+            sequences.extend(rows.iter().map(|row| PgSequenceInfo {
+                datname: db_client.datname.clone(),
+                schemaname: row.get(0),
+                sequencename: row.get(1),
+                relname: row.get(2),
+                attname: row.get(3),
+                data_type: row.get(4),
+                last_value: row.get(5),
+                max_value: row.get(6),
+            }));
+        }
+        self.guard
+            .record("sequences", now.elapsed(), timed_out, Instant::now());
+        self.sequences_time = Some(Instant::now());
+        sequences
+    }
+}
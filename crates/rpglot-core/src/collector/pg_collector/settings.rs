@@ -1,7 +1,9 @@
 //! PostgreSQL settings collector.
 //!
 //! Collects all entries from `pg_settings` view. Cached for 1 hour since
-//! settings rarely change at runtime.
+//! settings rarely change at runtime; a configuration reload (a new
+//! `pg_conf_load_time()`) invalidates the cache early, so changes show up
+//! in the next snapshot.
 
 use std::time::{Duration, Instant};
 
@@ -9,20 +11,26 @@ use tracing::warn;
 
 use super::PostgresCollector;
 use super::format_postgres_error;
-use crate::storage::model::PgSettingEntry;
+use crate::storage::model::{PgSettingEntry, PgSettingMeta};
 
 /// Interval between pg_settings re-collection.
 const SETTINGS_COLLECT_INTERVAL: Duration = Duration::from_secs(3600);
 
-const SETTINGS_QUERY: &str =
-    "SELECT name, setting, COALESCE(unit, '') AS unit FROM pg_settings ORDER BY name";
+const SETTINGS_QUERY: &str = "SELECT name, setting, COALESCE(unit, '') AS unit, pending_restart, \
+     category, source, COALESCE(boot_val, '') AS boot_val \
+     FROM pg_settings ORDER BY name";
+
+const CONF_LOAD_TIME_QUERY: &str = "SELECT pg_conf_load_time()::text";
 
 impl PostgresCollector {
     /// Collects all PostgreSQL settings from `pg_settings` view.
     ///
-    /// Results are cached for 1 hour. Returns cached data if fresh.
+    /// Results are cached for 1 hour or until the configuration is reloaded.
+    /// Returns cached data if fresh.
     pub fn collect_settings(&mut self) -> Vec<PgSettingEntry> {
-        if let Some(ref cache_time) = self.settings_cache_time
+        let reloaded = self.conf_reloaded();
+        if !reloaded
+            && let Some(ref cache_time) = self.settings_cache_time
             && cache_time.elapsed() < SETTINGS_COLLECT_INTERVAL
             && !self.settings_cache.is_empty()
         {
@@ -43,6 +51,20 @@ impl PostgresCollector {
                         unit: row.get(2),
                     })
                     .collect();
+                self.pending_restart = rows
+                    .iter()
+                    .filter(|row| row.get::<_, Option<bool>>(3).unwrap_or(false))
+                    .map(|row| row.get(0))
+                    .collect();
+                self.settings_meta_cache = rows
+                    .iter()
+                    .map(|row| PgSettingMeta {
+                        name: row.get(0),
+                        category: row.get(4),
+                        source: row.get(5),
+                        boot_val: row.get(6),
+                    })
+                    .collect();
                 self.settings_cache = entries.clone();
                 self.settings_cache_time = Some(Instant::now());
                 entries
@@ -54,4 +76,34 @@ impl PostgresCollector {
             }
         }
     }
+
+    /// Settings changed in the configuration files that only take effect
+    /// after a restart (`pg_settings.pending_restart`), as of the last
+    /// [`collect_settings`](Self::collect_settings).
+    pub fn pending_restart(&self) -> &[String] {
+        &self.pending_restart
+    }
+
+    /// Category, source and boot value of the settings returned by the last
+    /// [`collect_settings`](Self::collect_settings).
+    pub fn settings_meta(&self) -> &[PgSettingMeta] {
+        &self.settings_meta_cache
+    }
+
+    /// Whether `pg_conf_load_time()` moved since the last check.
+    fn conf_reloaded(&mut self) -> bool {
+        let Some(ref mut client) = self.client else {
+            return false;
+        };
+        let load_time: Option<String> = match client.query_one(CONF_LOAD_TIME_QUERY, &[]) {
+            Ok(row) => row.get(0),
+            Err(e) => {
+                warn!(error = %format_postgres_error(&e), "failed to query pg_conf_load_time");
+                return false;
+            }
+        };
+        let reloaded = self.conf_load_time.is_some() && self.conf_load_time != load_time;
+        self.conf_load_time = load_time;
+        reloaded
+    }
 }
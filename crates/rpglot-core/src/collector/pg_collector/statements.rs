@@ -9,6 +9,7 @@ use crate::storage::interner::StringInterner;
 use crate::storage::model::PgStatStatementsInfo;
 
 use super::PostgresCollector;
+use super::guard::is_timeout;
 use super::queries::build_stat_statements_query;
 
 pub(super) const STATEMENTS_EXT_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
@@ -107,12 +108,18 @@ impl PostgresCollector {
         interner: &mut StringInterner,
     ) -> Vec<PgStatStatementsInfo> {
         let now = Instant::now();
-        if !statements_collect_due(
-            self.statements_cache_time,
-            now,
-            self.statements_collect_interval,
-        ) {
-            // Cache is fresh — return previously filtered result (re-intern strings).
+        if self.throttled
+            || !statements_collect_due(
+                self.statements_cache_time,
+                now,
+                self.statements_collect_interval,
+            )
+        {
+            // Cache is fresh or collection is throttled — return previously
+            // filtered result (re-intern strings).
+            return self.return_filtered_cached(interner);
+        }
+        if !self.guard.allow("pg_stat_statements", now) {
             return self.return_filtered_cached(interner);
         }
 
@@ -145,13 +152,22 @@ impl PostgresCollector {
         };
 
         let query = build_stat_statements_query(self.server_version_num);
+        let redact = self.redact;
 
+        let started = Instant::now();
         let result = if using_db_client {
             let idx = self.statements_client_idx.unwrap();
             self.db_clients[idx].client.query(&query, &[])
         } else {
             self.client.as_mut().unwrap().query(&query, &[])
         };
+        let timed_out = result.as_ref().is_err_and(is_timeout);
+        self.guard.record(
+            "pg_stat_statements",
+            started.elapsed(),
+            timed_out,
+            Instant::now(),
+        );
 
         match result {
             Ok(rows) => {
@@ -160,7 +176,7 @@ impl PostgresCollector {
                 let mut entries = Vec::with_capacity(rows.len());
                 let mut out = Vec::with_capacity(rows.len());
                 for row in rows {
-                    let query_text: String = row.get("query");
+                    let query_text = redact.apply(row.get("query")).into_owned();
                     let datname: String = row.get("datname");
                     let usename: String = row.get("usename");
                     let collected_at = SystemTime::now()
@@ -228,7 +244,9 @@ impl PostgresCollector {
                 let msg = super::format_postgres_error(&e);
                 self.last_error = Some(msg);
 
-                if using_db_client {
+                if timed_out {
+                    // Cancelled by statement_timeout, the connection is fine.
+                } else if using_db_client {
                     // Error on a per-database client — reset statements discovery
                     // so we re-search on next cycle. Don't touch main client.
                     self.statements_client_idx = None;
@@ -347,6 +365,32 @@ mod tests {
         assert_eq!(interner.resolve(rows[0].usename_hash), Some("testuser"));
     }
 
+    #[test]
+    fn collect_statements_returns_stale_cache_when_throttled() {
+        let mut collector = PostgresCollector::with_connection_string("host=invalid".to_string());
+        collector.set_throttled(true);
+        // Cache is due for a refresh, but throttling keeps it
+        collector.statements_cache_time = Some(Instant::now() - STATEMENTS_COLLECT_INTERVAL * 2);
+        let info = PgStatStatementsInfo {
+            queryid: 7,
+            ..PgStatStatementsInfo::default()
+        };
+        collector.statements_cache = vec![PgStatStatementsCacheEntry {
+            info: info.clone(),
+            query_text: "SELECT 7".to_string(),
+            datname: "testdb".to_string(),
+            usename: "testuser".to_string(),
+        }];
+        collector.pgs_filtered_cache = vec![info];
+
+        let mut interner = StringInterner::new();
+        let rows = collector.collect_statements(&mut interner);
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].queryid, 7);
+        assert!(collector.last_error.is_none());
+    }
+
     #[test]
     fn filter_active_first_collect_returns_all() {
         use crate::collector::pg_collector::filter_active;
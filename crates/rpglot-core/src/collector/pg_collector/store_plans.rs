@@ -8,6 +8,7 @@ use crate::storage::interner::StringInterner;
 use crate::storage::model::PgStorePlansInfo;
 
 use super::PostgresCollector;
+use super::guard::is_timeout;
 use super::queries::{StorePlansFork, build_store_plans_query};
 
 pub(super) const STORE_PLANS_EXT_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
@@ -101,7 +102,10 @@ impl PostgresCollector {
     /// at most once per 5 minutes.
     pub fn collect_store_plans(&mut self, interner: &mut StringInterner) -> Vec<PgStorePlansInfo> {
         let now = Instant::now();
-        if !store_plans_collect_due(self.store_plans_cache_time, now) {
+        if self.throttled || !store_plans_collect_due(self.store_plans_cache_time, now) {
+            return self.return_pgp_cached(interner);
+        }
+        if !self.guard.allow("pg_store_plans", now) {
             return self.return_pgp_cached(interner);
         }
 
@@ -132,13 +136,22 @@ impl PostgresCollector {
 
         let fork = self.store_plans_fork.unwrap_or(StorePlansFork::OsscDb);
         let query = build_store_plans_query(fork);
+        let redact = self.redact;
 
+        let started = Instant::now();
         let result = if using_db_client {
             let idx = self.store_plans_client_idx.unwrap();
             self.db_clients[idx].client.query(&query, &[])
         } else {
             self.client.as_mut().unwrap().query(&query, &[])
         };
+        let timed_out = result.as_ref().is_err_and(is_timeout);
+        self.guard.record(
+            "pg_store_plans",
+            started.elapsed(),
+            timed_out,
+            Instant::now(),
+        );
 
         match result {
             Ok(rows) => {
@@ -148,7 +161,7 @@ impl PostgresCollector {
                 let mut out = Vec::with_capacity(rows.len());
                 let mut plan_lens_sample: Vec<usize> = Vec::new();
                 for row in rows {
-                    let plan_text: String = row.get("plan");
+                    let plan_text = redact.apply_plan(row.get("plan")).into_owned();
                     if plan_lens_sample.len() < 5 {
                         plan_lens_sample.push(plan_text.len());
                     }
@@ -216,7 +229,9 @@ impl PostgresCollector {
                 let msg = super::format_postgres_error(&e);
                 self.last_error = Some(msg);
 
-                if using_db_client {
+                if timed_out {
+                    // Cancelled by statement_timeout, the connection is fine.
+                } else if using_db_client {
                     self.store_plans_client_idx = None;
                     self.store_plans_ext_version = None;
                     self.store_plans_last_check = None;
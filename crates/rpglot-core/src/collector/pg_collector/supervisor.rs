@@ -0,0 +1,174 @@
+//! Connection supervision: reconnect backoff and health state.
+//!
+//! While PostgreSQL is unreachable every `collect_*` call would otherwise
+//! try to connect and wait up to `connect_timeout`, stalling the snapshot.
+//! After a failed attempt the supervisor refuses new attempts until the
+//! backoff expires (doubling from [`BACKOFF_MIN`] up to [`BACKOFF_MAX`]), so
+//! an outage costs at most one connection attempt per backoff period.
+//!
+//! The state is reported as a
+//! [`DataBlock::PgConnection`](crate::storage::model::DataBlock::PgConnection)
+//! while the connection is not healthy and once on every transition, so
+//! outages stay visible in history.
+
+use std::time::{Duration, Instant};
+
+use crate::storage::model::{PgConnectionState, PgConnectionStatus};
+
+/// Delay after the first failed attempt.
+const BACKOFF_MIN: Duration = Duration::from_secs(2);
+/// Longest delay between attempts.
+const BACKOFF_MAX: Duration = Duration::from_secs(120);
+/// Consecutive failed attempts after which the instance is reported down.
+const DOWN_AFTER_FAILURES: u32 = 3;
+
+/// Tracks connection attempts of one [`PostgresCollector`](super::PostgresCollector).
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionSupervisor {
+    /// `None` until the first attempt.
+    state: Option<PgConnectionState>,
+    /// Unix time the connection was established or lost.
+    since: i64,
+    failures: u32,
+    next_attempt: Option<Instant>,
+    /// `next_attempt` as unix time, for the report.
+    next_attempt_unix: i64,
+    last_error: String,
+    /// A transition happened since the last [`report`](Self::report).
+    changed: bool,
+}
+
+impl ConnectionSupervisor {
+    /// Time left until the next connection attempt is allowed.
+    pub fn retry_in(&self, now: Instant) -> Option<Duration> {
+        self.next_attempt
+            .map(|at| at.saturating_duration_since(now))
+            .filter(|wait| !wait.is_zero())
+    }
+
+    /// Last connection error.
+    pub fn last_error(&self) -> &str {
+        &self.last_error
+    }
+
+    /// Records a successful connection. Called while already connected, it
+    /// means the connection was lost and immediately re-established, which is
+    /// reported as a new connection.
+    pub fn connected(&mut self, unix_now: i64) {
+        self.failures = 0;
+        self.next_attempt = None;
+        self.next_attempt_unix = 0;
+        self.last_error.clear();
+        if self.state == Some(PgConnectionState::Connected) {
+            self.since = unix_now;
+            self.changed = true;
+        }
+        self.transition(PgConnectionState::Connected, unix_now);
+    }
+
+    /// Records a failed connection attempt and schedules the next one.
+    pub fn failed(&mut self, error: String, now: Instant, unix_now: i64) {
+        self.failures = self.failures.saturating_add(1);
+        let delay = backoff(self.failures);
+        self.next_attempt = Some(now + delay);
+        self.next_attempt_unix = unix_now + delay.as_secs() as i64;
+        self.last_error = error;
+        let state = if self.failures >= DOWN_AFTER_FAILURES {
+            PgConnectionState::Down
+        } else {
+            PgConnectionState::Reconnecting
+        };
+        self.transition(state, unix_now);
+    }
+
+    fn transition(&mut self, state: PgConnectionState, unix_now: i64) {
+        if self.state == Some(state) {
+            return;
+        }
+        // Reconnecting -> Down continues the same outage
+        let connected = state == PgConnectionState::Connected;
+        if self
+            .state
+            .is_none_or(|prev| (prev == PgConnectionState::Connected) != connected)
+        {
+            self.since = unix_now;
+        }
+        self.state = Some(state);
+        self.changed = true;
+    }
+
+    /// Status to record in the snapshot: always while not connected, and
+    /// once after reconnecting.
+    pub fn report(&mut self) -> Option<PgConnectionStatus> {
+        let state = self.state?;
+        if state == PgConnectionState::Connected && !self.changed {
+            return None;
+        }
+        self.changed = false;
+        Some(PgConnectionStatus {
+            state,
+            since: self.since,
+            failures: self.failures,
+            next_attempt: self.next_attempt_unix,
+            error: self.last_error.clone(),
+        })
+    }
+}
+
+/// Delay before the next attempt after `failures` consecutive failures.
+fn backoff(failures: u32) -> Duration {
+    let shift = failures.saturating_sub(1).min(16);
+    BACKOFF_MIN.saturating_mul(1 << shift).min(BACKOFF_MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        assert_eq!(backoff(1), Duration::from_secs(2));
+        assert_eq!(backoff(2), Duration::from_secs(4));
+        assert_eq!(backoff(6), Duration::from_secs(64));
+        assert_eq!(backoff(7), BACKOFF_MAX);
+        assert_eq!(backoff(u32::MAX), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn reports_transitions_and_outages() {
+        let mut s = ConnectionSupervisor::default();
+        let now = Instant::now();
+        assert!(s.retry_in(now).is_none());
+        assert!(s.report().is_none());
+
+        s.connected(1000);
+        let status = s.report().unwrap();
+        assert_eq!(status.state, PgConnectionState::Connected);
+        assert_eq!(status.since, 1000);
+        assert!(s.report().is_none());
+        // Lost and re-established within a snapshot
+        s.connected(1005);
+        assert_eq!(s.report().unwrap().since, 1005);
+
+        for i in 0..DOWN_AFTER_FAILURES {
+            s.failed("connection refused".to_string(), now, 1020 + i as i64);
+        }
+        assert_eq!(s.retry_in(now), Some(Duration::from_secs(8)));
+        assert!(s.retry_in(now + Duration::from_secs(8)).is_none());
+        let status = s.report().unwrap();
+        assert_eq!(status.state, PgConnectionState::Down);
+        assert_eq!(status.since, 1020);
+        assert_eq!(status.failures, 3);
+        assert_eq!(status.error, "connection refused");
+        assert_eq!(status.next_attempt, 1022 + 8);
+        // Reported every snapshot while down
+        assert!(s.report().is_some());
+
+        s.connected(1100);
+        let status = s.report().unwrap();
+        assert_eq!(status.state, PgConnectionState::Connected);
+        assert_eq!((status.failures, status.next_attempt), (0, 0));
+        assert!(s.retry_in(now).is_none());
+        assert!(s.report().is_none());
+    }
+}
@@ -0,0 +1,307 @@
+//! TLS for PostgreSQL connections (`sslmode`).
+//!
+//! Modes follow libpq. `disable` never encrypts; `allow`, `prefer` and
+//! `require` encrypt without checking the server certificate (`allow` is
+//! treated as `prefer`); `verify-ca` checks the certificate chain against the
+//! root certificates, `verify-full` also checks that it was issued for the
+//! host. Roots are read from `sslrootcert` (`PGSSLROOTCERT`, default
+//! `~/.postgresql/root.crt`); `system` uses the operating system trust store.
+//!
+//! Unix socket connections never use TLS, like in libpq.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use postgres::config::Host;
+use postgres::{Client, Config, NoTls};
+use rustls::client::WebPkiServerVerifier;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{CryptoProvider, verify_tls12_signature, verify_tls13_signature};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, Error, RootCertStore, SignatureScheme,
+};
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+/// Value of `sslrootcert` selecting the operating system trust store.
+const SYSTEM_ROOTS: &str = "system";
+
+/// libpq `sslmode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SslMode {
+    Disable,
+    Allow,
+    #[default]
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
+
+impl SslMode {
+    /// Parses a libpq `sslmode` value.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim() {
+            "disable" => Ok(Self::Disable),
+            "allow" => Ok(Self::Allow),
+            "prefer" => Ok(Self::Prefer),
+            "require" => Ok(Self::Require),
+            "verify-ca" => Ok(Self::VerifyCa),
+            "verify-full" => Ok(Self::VerifyFull),
+            other => Err(format!(
+                "unknown sslmode '{}' (expected disable, allow, prefer, require, verify-ca or verify-full)",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Disable => "disable",
+            Self::Allow => "allow",
+            Self::Prefer => "prefer",
+            Self::Require => "require",
+            Self::VerifyCa => "verify-ca",
+            Self::VerifyFull => "verify-full",
+        }
+    }
+
+    /// The mode as understood by the `postgres` crate, which only knows
+    /// whether TLS is off, optional or mandatory.
+    pub(crate) fn connection_value(self) -> &'static str {
+        match self {
+            Self::Disable => "disable",
+            Self::Allow | Self::Prefer => "prefer",
+            Self::Require | Self::VerifyCa | Self::VerifyFull => "require",
+        }
+    }
+}
+
+/// Connector used for every connection of a collector.
+#[derive(Clone)]
+pub(crate) enum PgTls {
+    Plain,
+    Rustls(MakeRustlsConnect),
+}
+
+impl PgTls {
+    /// Builds the connector for `mode`; verifying modes load the root
+    /// certificates from `root_cert`.
+    pub(crate) fn new(mode: SslMode, root_cert: Option<&Path>) -> Result<Self, String> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let verifier: Arc<dyn ServerCertVerifier> = match mode {
+            SslMode::Disable => return Ok(Self::Plain),
+            SslMode::Allow | SslMode::Prefer | SslMode::Require => {
+                Arc::new(AcceptAnyCert(provider.clone()))
+            }
+            SslMode::VerifyCa | SslMode::VerifyFull => {
+                let roots = load_roots(root_cert)?;
+                let webpki =
+                    WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                        .build()
+                        .map_err(|e| e.to_string())?;
+                if mode == SslMode::VerifyCa {
+                    Arc::new(SkipHostname(webpki))
+                } else {
+                    webpki
+                }
+            }
+        };
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| e.to_string())?
+            .dangerous()
+            .with_custom_certificate_verifier(verifier)
+            .with_no_client_auth();
+        Ok(Self::Rustls(MakeRustlsConnect::new(config)))
+    }
+
+    /// Connects with a libpq key=value connection string.
+    pub(crate) fn connect(&self, connection_string: &str) -> Result<Client, postgres::Error> {
+        let config = Config::from_str(connection_string)?;
+        let tcp = config.get_hosts().iter().all(|h| matches!(h, Host::Tcp(_)));
+        match self {
+            Self::Rustls(tls) if tcp => config.connect(tls.clone()),
+            _ => config.connect(NoTls),
+        }
+    }
+}
+
+/// Root certificates from a PEM file, or the system trust store.
+fn load_roots(path: Option<&Path>) -> Result<RootCertStore, String> {
+    let path = path
+        .map(Path::to_path_buf)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".postgresql/root.crt"))
+        })
+        .ok_or_else(|| "sslrootcert not set and HOME unknown".to_string())?;
+
+    let mut roots = RootCertStore::empty();
+    if path.as_os_str() == SYSTEM_ROOTS {
+        let native = rustls_native_certs::load_native_certs();
+        let (added, _) = roots.add_parsable_certificates(native.certs);
+        if added == 0 {
+            return Err("no system root certificates found".to_string());
+        }
+        return Ok(roots);
+    }
+
+    let certs = CertificateDer::pem_file_iter(&path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    for cert in certs {
+        roots
+            .add(cert)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    if roots.is_empty() {
+        return Err(format!("{}: no certificates", path.display()));
+    }
+    Ok(roots)
+}
+
+/// Encrypts without authenticating the server (`sslmode=require` and weaker).
+/// Handshake signatures are still checked against the presented certificate.
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Checks the certificate chain but not the host name (`sslmode=verify-ca`).
+/// The chain is validated before the name, so a name mismatch means the
+/// chain itself is trusted.
+#[derive(Debug)]
+struct SkipHostname(Arc<WebPkiServerVerifier>);
+
+impl ServerCertVerifier for SkipHostname {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, Error> {
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        {
+            Err(Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            result => result,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parses_sslmode() {
+        for mode in [
+            SslMode::Disable,
+            SslMode::Allow,
+            SslMode::Prefer,
+            SslMode::Require,
+            SslMode::VerifyCa,
+            SslMode::VerifyFull,
+        ] {
+            assert_eq!(SslMode::parse(mode.as_str()), Ok(mode));
+        }
+        assert!(SslMode::parse("verify_full").is_err());
+        assert_eq!(SslMode::VerifyCa.connection_value(), "require");
+        assert_eq!(SslMode::Allow.connection_value(), "prefer");
+    }
+
+    #[test]
+    fn verifying_modes_need_roots() {
+        assert!(matches!(
+            PgTls::new(SslMode::Disable, None),
+            Ok(PgTls::Plain)
+        ));
+        assert!(matches!(
+            PgTls::new(SslMode::Require, None),
+            Ok(PgTls::Rustls(_))
+        ));
+
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("root.crt");
+        let err = PgTls::new(SslMode::VerifyFull, Some(&missing))
+            .err()
+            .unwrap();
+        assert!(err.contains("root.crt"), "{}", err);
+
+        std::fs::write(&missing, "not a certificate\n").unwrap();
+        assert!(PgTls::new(SslMode::VerifyCa, Some(&missing)).is_err());
+    }
+}
@@ -4,6 +4,7 @@
 //! into structured data. They are designed to be easily testable with string inputs.
 
 use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 /// Error type for parsing failures.
 #[derive(Debug, Clone, PartialEq)]
@@ -277,6 +278,57 @@ pub fn parse_proc_io(content: &str) -> Result<ProcIo, ParseError> {
     Ok(io)
 }
 
+/// Parsed data from `/proc/[pid]/smaps_rollup` (values in KB).
+#[derive(Debug, Clone, Default)]
+pub struct ProcSmapsRollup {
+    pub rss: u64,
+    pub pss: u64,
+    pub shared_clean: u64,
+    pub shared_dirty: u64,
+    pub private_clean: u64,
+    pub private_dirty: u64,
+    pub swap: u64,
+    pub swap_pss: u64,
+}
+
+/// Parses `/proc/[pid]/smaps_rollup` content.
+///
+/// Format is a `[rollup]` mapping header followed by `Key:   value kB` lines.
+pub fn parse_smaps_rollup(content: &str) -> Result<ProcSmapsRollup, ParseError> {
+    let mut smaps = ProcSmapsRollup::default();
+    let mut found = false;
+
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(value) = value
+            .split_whitespace()
+            .next()
+            .and_then(|v| v.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        found = true;
+        match key.trim() {
+            "Rss" => smaps.rss = value,
+            "Pss" => smaps.pss = value,
+            "Shared_Clean" => smaps.shared_clean = value,
+            "Shared_Dirty" => smaps.shared_dirty = value,
+            "Private_Clean" => smaps.private_clean = value,
+            "Private_Dirty" => smaps.private_dirty = value,
+            "Swap" => smaps.swap = value,
+            "SwapPss" => smaps.swap_pss = value,
+            _ => {}
+        }
+    }
+
+    if !found {
+        return Err(ParseError::new("empty smaps_rollup"));
+    }
+    Ok(smaps)
+}
+
 /// Parsed data from `/proc/meminfo`.
 #[derive(Debug, Clone, Default)]
 pub struct MemInfo {
@@ -979,10 +1031,108 @@ pub fn parse_netstat(content: &str) -> Result<NetstatStats, ParseError> {
     Ok(stats)
 }
 
+/// TCP socket states as printed in `/proc/net/tcp` (`include/net/tcp_states.h`).
+pub const TCP_ESTABLISHED: u8 = 0x01;
+pub const TCP_SYN_RECV: u8 = 0x03;
+pub const TCP_TIME_WAIT: u8 = 0x06;
+pub const TCP_CLOSE_WAIT: u8 = 0x08;
+pub const TCP_LISTEN: u8 = 0x0A;
+
+/// One socket from `/proc/net/tcp` or `/proc/net/tcp6`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TcpSocket {
+    pub local_port: u16,
+    pub remote_addr: IpAddr,
+    pub remote_port: u16,
+    pub state: u8,
+}
+
+/// Parses `/proc/net/tcp` or `/proc/net/tcp6` content.
+///
+/// Format (after a header line):
+/// `sl local_address rem_address st ...`, addresses as `HEXIP:HEXPORT`.
+/// The IP is printed as 32-bit words in host byte order; IPv4-mapped IPv6
+/// addresses are returned as IPv4. Malformed lines are skipped.
+pub fn parse_net_tcp(content: &str) -> Vec<TcpSocket> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            let (_, local_port) = parse_tcp_address(fields.next()?)?;
+            let (remote_addr, remote_port) = parse_tcp_address(fields.next()?)?;
+            let state = u8::from_str_radix(fields.next()?, 16).ok()?;
+            Some(TcpSocket {
+                local_port,
+                remote_addr,
+                remote_port,
+                state,
+            })
+        })
+        .collect()
+}
+
+fn parse_tcp_address(s: &str) -> Option<(IpAddr, u16)> {
+    let (ip, port) = s.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let word = |i: usize| -> Option<[u8; 4]> {
+        let w = u32::from_str_radix(ip.get(i * 8..(i + 1) * 8)?, 16).ok()?;
+        Some(w.to_ne_bytes())
+    };
+    let addr = match ip.len() {
+        8 => IpAddr::V4(Ipv4Addr::from(word(0)?)),
+        32 => {
+            let mut bytes = [0u8; 16];
+            for i in 0..4 {
+                bytes[i * 4..(i + 1) * 4].copy_from_slice(&word(i)?);
+            }
+            let v6 = Ipv6Addr::from(bytes);
+            match v6.to_ipv4_mapped() {
+                Some(v4) => IpAddr::V4(v4),
+                None => IpAddr::V6(v6),
+            }
+        }
+        _ => return None,
+    };
+    Some((addr, port))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_parse_net_tcp() {
+        let tcp = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000   113        0 1 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1538 0100007F:D2F0 01 00000000:00000000 00:00000000 00000000   113        0 2 1 0000000000000000 20 4 30 10 -1
+   2: 0500000A:1538 0700000A:C350 06 00000000:00000000 03:00000E2B 00000000     0        0 0 3 0000000000000000
+garbage
+";
+        let sockets = parse_net_tcp(tcp);
+        assert_eq!(sockets.len(), 3);
+        assert_eq!(sockets[0].local_port, 5432);
+        assert_eq!(sockets[0].state, TCP_LISTEN);
+        assert_eq!(sockets[1].remote_addr, IpAddr::from([127, 0, 0, 1]));
+        assert_eq!(sockets[1].remote_port, 54000);
+        assert_eq!(sockets[1].state, TCP_ESTABLISHED);
+        assert_eq!(sockets[2].remote_addr, IpAddr::from([10, 0, 0, 7]));
+        assert_eq!(sockets[2].state, TCP_TIME_WAIT);
+
+        let tcp6 = "\
+  sl  local_address                         remote_address                        st
+   0: 0000000000000000FFFF00000500000A:1538 0000000000000000FFFF00000800000A:D431 03 00000000:00000000
+   1: 00000000000000000000000001000000:1538 00000000000000000000000001000000:A1B2 01 00000000:00000000
+";
+        let sockets = parse_net_tcp(tcp6);
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[0].remote_addr, IpAddr::from([10, 0, 0, 8]));
+        assert_eq!(sockets[0].state, TCP_SYN_RECV);
+        assert_eq!(sockets[1].remote_addr, "::1".parse::<IpAddr>().unwrap());
+    }
+
     #[test]
     fn test_parse_passwd() {
         let content = "\
@@ -1111,6 +1261,37 @@ cancelled_write_bytes: 1000
         assert_eq!(io.cancelled_write_bytes, 1000);
     }
 
+    #[test]
+    fn test_parse_smaps_rollup() {
+        let content = "\
+5581a6bfe000-7ffd3c5f1000 ---p 00000000 00:00 0                          [rollup]
+Rss:             1069932 kB
+Pss:               41563 kB
+Pss_Anon:           2140 kB
+Pss_File:           1009 kB
+Pss_Shmem:         38414 kB
+Shared_Clean:       5424 kB
+Shared_Dirty:    1061008 kB
+Private_Clean:       100 kB
+Private_Dirty:      3400 kB
+Referenced:      1069932 kB
+Swap:                512 kB
+SwapPss:             256 kB
+Locked:                0 kB
+";
+        let smaps = parse_smaps_rollup(content).unwrap();
+
+        assert_eq!(smaps.rss, 1069932);
+        assert_eq!(smaps.pss, 41563);
+        assert_eq!(smaps.shared_clean, 5424);
+        assert_eq!(smaps.shared_dirty, 1061008);
+        assert_eq!(smaps.private_dirty, 3400);
+        assert_eq!(smaps.swap, 512);
+        assert_eq!(smaps.swap_pss, 256);
+
+        assert!(parse_smaps_rollup("").is_err());
+    }
+
     #[test]
     fn test_parse_meminfo() {
         let content = "\
@@ -1,6 +1,8 @@
 //! Process collector for gathering per-process metrics from `/proc/[pid]/`.
 
-use crate::collector::procfs::parser::{parse_proc_io, parse_proc_stat, parse_proc_status};
+use crate::collector::procfs::parser::{
+    parse_proc_io, parse_proc_stat, parse_proc_status, parse_smaps_rollup,
+};
 use crate::collector::traits::FileSystem;
 use crate::storage::interner::StringInterner;
 use crate::storage::model::{ProcessCpuInfo, ProcessDskInfo, ProcessInfo, ProcessMemInfo};
@@ -9,6 +11,11 @@ use std::path::Path;
 /// Clock ticks per second (USER_HZ). Standard value for Linux.
 const CLK_TCK: u64 = 100;
 
+/// Maximum PostgreSQL processes whose `/proc/[pid]/smaps_rollup` is read per
+/// snapshot. The kernel walks every mapped page, which is expensive for
+/// backends mapping a large shared_buffers.
+pub const SMAPS_ROLLUP_MAX_PROCESSES: usize = 100;
+
 /// Error type for collection failures.
 #[derive(Debug)]
 pub enum CollectError {
@@ -185,12 +192,14 @@ impl<F: FileSystem> ProcessCollector<F> {
                 vexec: 0, // Would need to parse /proc/[pid]/maps
                 vmem,
                 rmem,
-                pmem: 0, // Would need to parse /proc/[pid]/smaps
+                pmem: 0, // Filled by collect_smaps_rollup() for PostgreSQL processes
                 vdata: status.vm_data,
                 vstack: status.vm_stk,
                 vlibs: status.vm_lib,
                 vswap: status.vm_swap,
                 vlock: status.vm_lck,
+                pshared: 0,
+                pswap: 0,
             },
             cpu: ProcessCpuInfo {
                 utime: stat.utime,
@@ -215,6 +224,7 @@ impl<F: FileSystem> ProcessCollector<F> {
                 wsz: io.write_bytes,
                 cwsz: io.cancelled_write_bytes,
             },
+            blk_lat: None, // Filled in by the eBPF collector when enabled
         })
     }
 
@@ -248,6 +258,50 @@ impl<F: FileSystem> ProcessCollector<F> {
 
         Ok(processes)
     }
+
+    /// Fills PSS, shared and swap PSS from `/proc/[pid]/smaps_rollup` for
+    /// PostgreSQL processes, largest RSS first, at most `max_processes`.
+    ///
+    /// Returns the number of processes read. Processes that disappeared or
+    /// are not readable keep zeros.
+    pub fn collect_smaps_rollup(
+        &mut self,
+        processes: &mut [ProcessInfo],
+        max_processes: usize,
+    ) -> usize {
+        let mut candidates: Vec<usize> = processes
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| {
+                matches!(
+                    self.interner.resolve(p.name_hash),
+                    Some("postgres" | "postmaster")
+                )
+            })
+            .map(|(i, _)| i)
+            .collect();
+        candidates.sort_by_key(|&i| std::cmp::Reverse(processes[i].mem.rmem));
+        candidates.truncate(max_processes);
+
+        let mut read = 0;
+        for i in candidates {
+            let p = &mut processes[i];
+            let path = format!("{}/{}/smaps_rollup", self.proc_path, p.pid);
+            let Some(smaps) = self
+                .fs
+                .read_to_string(Path::new(&path))
+                .ok()
+                .and_then(|content| parse_smaps_rollup(&content).ok())
+            else {
+                continue;
+            };
+            p.mem.pmem = smaps.pss;
+            p.mem.pshared = smaps.shared_clean + smaps.shared_dirty;
+            p.mem.pswap = smaps.swap_pss;
+            read += 1;
+        }
+        read
+    }
 }
 
 #[cfg(test)]
@@ -299,6 +353,48 @@ mod tests {
         assert!(pids.contains(&1001));
     }
 
+    #[test]
+    fn test_collect_smaps_rollup_for_postgres() {
+        let mut fs = MockFs::new();
+        // (pid, ppid, comm, rss pages)
+        for (pid, ppid, comm, rss) in [
+            (100, 1, "postgres", 500),
+            (101, 100, "postgres", 900),
+            (102, 100, "postgres", 100),
+            (200, 1, "bash", 1000),
+        ] {
+            fs.add_process(
+                pid,
+                &format!(
+                    "{pid} ({comm}) S {ppid} 1 1 0 -1 4194304 0 0 0 0 0 0 0 0 20 0 1 0 100 1000000 {rss} 18446744073709551615 0 0 0 0 0 0 0 0 0 0 0 0 17 0 0 0 0 0 0 0 0 0 0 0 0 0"
+                ),
+                "Uid:\t0\t0\t0\t0\nGid:\t0\t0\t0\t0\n",
+                "",
+                comm,
+                comm,
+            );
+            fs.add_file(
+                format!("/proc/{pid}/smaps_rollup"),
+                "Rss: 1000 kB\nPss: 40 kB\nShared_Dirty: 900 kB\nSwapPss: 2 kB\n",
+            );
+        }
+
+        let mut collector = ProcessCollector::new(fs, "/proc");
+        let mut processes = collector.collect_all_processes().unwrap();
+        let read = collector.collect_smaps_rollup(&mut processes, 2);
+        assert_eq!(read, 2);
+
+        let pss = |pid: u32| processes.iter().find(|p| p.pid == pid).unwrap().mem.pmem;
+        // Two largest postgres processes only
+        assert_eq!(pss(101), 40);
+        assert_eq!(pss(100), 40);
+        assert_eq!(pss(102), 0);
+        assert_eq!(pss(200), 0);
+        let p101 = processes.iter().find(|p| p.pid == 101).unwrap();
+        assert_eq!(p101.mem.pshared, 900);
+        assert_eq!(p101.mem.pswap, 2);
+    }
+
     #[test]
     fn test_collect_process_gone() {
         let mut fs = MockFs::new();
@@ -1,19 +1,23 @@
 //! System collector for gathering global system metrics from `/proc/`.
 
 use crate::collector::procfs::parser::{
-    parse_diskstats, parse_global_stat, parse_loadavg, parse_meminfo, parse_mountinfo_device_ids,
-    parse_net_dev, parse_net_snmp, parse_netstat, parse_psi, parse_vmstat,
+    TCP_CLOSE_WAIT, TCP_ESTABLISHED, TCP_LISTEN, TCP_SYN_RECV, TCP_TIME_WAIT, parse_diskstats,
+    parse_global_stat, parse_loadavg, parse_meminfo, parse_mountinfo_device_ids, parse_net_dev,
+    parse_net_snmp, parse_net_tcp, parse_netstat, parse_psi, parse_vmstat,
 };
 use crate::collector::procfs::process::CollectError;
 use crate::collector::traits::FileSystem;
 use crate::storage::interner::StringInterner;
 use crate::storage::model::{
     SystemCpuInfo, SystemDiskInfo, SystemLoadInfo, SystemMemInfo, SystemNetInfo, SystemNetSnmpInfo,
-    SystemPsiInfo, SystemStatInfo, SystemVmstatInfo,
+    SystemPsiInfo, SystemStatInfo, SystemTcpConnInfo, SystemTcpRemoteInfo, SystemVmstatInfo,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
+/// Maximum number of remote hosts kept in [`SystemTcpConnInfo::remotes`].
+const MAX_TCP_REMOTES: usize = 100;
+
 /// Collects system-wide metrics from `/proc/`.
 pub struct SystemCollector<F: FileSystem> {
     fs: F,
@@ -311,6 +315,78 @@ impl<F: FileSystem> SystemCollector<F> {
 
         Ok(info)
     }
+
+    /// Collects TCP connections to local `port` from `/proc/net/tcp` and
+    /// `/proc/net/tcp6`, counted by state and by remote host.
+    ///
+    /// Fails only if neither file can be read.
+    pub fn collect_tcp_connections(&self, port: u16) -> Result<SystemTcpConnInfo, CollectError> {
+        let mut info = SystemTcpConnInfo {
+            port,
+            ..Default::default()
+        };
+        let mut remotes: HashMap<String, SystemTcpRemoteInfo> = HashMap::new();
+        let mut last_err = None;
+        let mut read_any = false;
+
+        for file in ["tcp", "tcp6"] {
+            let path = format!("{}/net/{}", self.proc_path, file);
+            let content = match self.fs.read_to_string(Path::new(&path)) {
+                Ok(c) => c,
+                Err(e) => {
+                    last_err = Some(e);
+                    continue;
+                }
+            };
+            read_any = true;
+
+            for socket in parse_net_tcp(&content) {
+                if socket.local_port != port || socket.state == TCP_LISTEN {
+                    continue;
+                }
+                let addr = socket.remote_addr.to_string();
+                let remote = remotes
+                    .entry(addr)
+                    .or_insert_with_key(|addr| SystemTcpRemoteInfo {
+                        addr: addr.clone(),
+                        ..Default::default()
+                    });
+                match socket.state {
+                    TCP_ESTABLISHED => {
+                        info.established += 1;
+                        remote.established += 1;
+                    }
+                    TCP_SYN_RECV => {
+                        info.syn_recv += 1;
+                        remote.syn_recv += 1;
+                    }
+                    TCP_TIME_WAIT => {
+                        info.time_wait += 1;
+                        remote.time_wait += 1;
+                    }
+                    state => {
+                        if state == TCP_CLOSE_WAIT {
+                            info.close_wait += 1;
+                        } else {
+                            info.other += 1;
+                        }
+                        remote.other += 1;
+                    }
+                }
+            }
+        }
+
+        if !read_any && let Some(e) = last_err {
+            return Err(e.into());
+        }
+
+        let mut remotes: Vec<SystemTcpRemoteInfo> = remotes.into_values().collect();
+        remotes.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.addr.cmp(&b.addr)));
+        remotes.truncate(MAX_TCP_REMOTES);
+        info.remotes = remotes;
+
+        Ok(info)
+    }
 }
 
 #[cfg(test)]
@@ -535,4 +611,40 @@ mod tests {
         assert_eq!(stat.procs_blocked, 0);
         assert_eq!(stat.btime, 1700000000);
     }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_collect_tcp_connections() {
+        let mut fs = MockFs::new();
+        fs.add_file(
+            "/proc/net/tcp",
+            "\
+  sl  local_address rem_address   st
+   0: 00000000:1538 00000000:0000 0A
+   1: 0500000A:1538 0700000A:C350 01
+   2: 0500000A:1538 0700000A:C351 01
+   3: 0500000A:1538 0700000A:C352 06
+   4: 0500000A:1538 0800000A:C353 03
+   5: 0500000A:1538 0800000A:C354 08
+   6: 0500000A:0016 0900000A:C355 01
+",
+        );
+        let collector = SystemCollector::new(fs, "/proc");
+
+        let info = collector.collect_tcp_connections(5432).unwrap();
+        assert_eq!(info.port, 5432);
+        assert_eq!(info.established, 2);
+        assert_eq!(info.time_wait, 1);
+        assert_eq!(info.syn_recv, 1);
+        assert_eq!(info.close_wait, 1);
+        assert_eq!(info.other, 0);
+        // ssh connection on port 22 and the listen socket are not counted
+        let addrs: Vec<&str> = info.remotes.iter().map(|r| r.addr.as_str()).collect();
+        assert_eq!(addrs, ["10.0.0.7", "10.0.0.8"]);
+        assert_eq!(info.remotes[0].established, 2);
+        assert_eq!(info.remotes[1].other, 1);
+
+        let empty = SystemCollector::new(MockFs::new(), "/proc");
+        assert!(empty.collect_tcp_connections(5432).is_err());
+    }
 }
@@ -0,0 +1,287 @@
+//! SQL text redaction for collected query texts.
+//!
+//! Query texts from pg_stat_activity, pg_locks, pg_stat_statements,
+//! pg_store_plans and the PostgreSQL log may contain PII in literals.
+//! The collector applies a [`RedactMode`] before interning, so redacted
+//! values never reach the WAL or chunk files.
+
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Marker file in the data directory recording the daemon's redaction mode,
+/// so readers of the history can report that redaction is active.
+pub const MARKER_FILE: &str = "redaction";
+
+/// Replacement for query texts in [`RedactMode::Drop`].
+pub const REDACTED_TEXT: &str = "<redacted>";
+
+/// How query texts are redacted before interning.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RedactMode {
+    /// Texts are stored as-is.
+    #[default]
+    Off,
+    /// String and numeric literals are replaced with `?`.
+    Strip,
+    /// Literals are replaced with a short hash, so equal values stay comparable.
+    Hash,
+    /// Texts are replaced with [`REDACTED_TEXT`].
+    Drop,
+}
+
+impl RedactMode {
+    /// Parses `off`, `strip`, `hash` or `drop`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim() {
+            "off" | "none" => Ok(Self::Off),
+            "strip" => Ok(Self::Strip),
+            "hash" => Ok(Self::Hash),
+            "drop" => Ok(Self::Drop),
+            other => Err(format!(
+                "unknown redaction mode '{}' (expected strip, hash, drop or off)",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Strip => "strip",
+            Self::Hash => "hash",
+            Self::Drop => "drop",
+        }
+    }
+
+    pub fn is_active(self) -> bool {
+        self != Self::Off
+    }
+
+    /// Redacts a SQL text according to the mode.
+    pub fn apply(self, sql: &str) -> Cow<'_, str> {
+        self.redact(sql, true)
+    }
+
+    /// Redacts a query plan: like [`RedactMode::apply`], but numbers are kept
+    /// (costs and row estimates are not PII, string literals in filters are).
+    pub fn apply_plan(self, plan: &str) -> Cow<'_, str> {
+        self.redact(plan, false)
+    }
+
+    fn redact(self, text: &str, numbers: bool) -> Cow<'_, str> {
+        match self {
+            Self::Off => Cow::Borrowed(text),
+            Self::Drop if text.is_empty() => Cow::Borrowed(text),
+            Self::Drop => Cow::Borrowed(REDACTED_TEXT),
+            Self::Strip | Self::Hash => {
+                Cow::Owned(replace_literals(text, self == Self::Hash, numbers))
+            }
+        }
+    }
+
+    /// Records the mode in `dir` ([`MARKER_FILE`]); removes the marker when off.
+    pub fn write_marker(self, dir: &Path) -> io::Result<()> {
+        let path = dir.join(MARKER_FILE);
+        if self.is_active() {
+            fs::write(path, self.as_str())
+        } else {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Reads the mode recorded in `dir`. Missing or unreadable marker means off.
+    pub fn read_marker(dir: &Path) -> Self {
+        fs::read_to_string(dir.join(MARKER_FILE))
+            .ok()
+            .and_then(|s| Self::parse(&s).ok())
+            .unwrap_or_default()
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Replaces string literals (`'...'`, `E'...'`, `$tag$...$tag$`) and, with
+/// `numbers`, numeric literals with `?` or, with `hash`, with `'#xxxxxxxx'`.
+/// Identifiers, comments and parameters (`$1`) are kept. An unterminated
+/// literal (query truncated by `track_activity_query_size`) is redacted to the end.
+fn replace_literals(sql: &str, hash: bool, numbers: bool) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = String::with_capacity(sql.len());
+    let mut i = 0;
+
+    let push_literal = |out: &mut String, literal: &[char]| {
+        if hash {
+            let text: String = literal.iter().collect();
+            out.push_str(&format!("'#{:08x}'", xxh3_64(text.as_bytes()) as u32));
+        } else {
+            out.push('?');
+        }
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+        match c {
+            // Line comment: copy to end of line
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+            }
+            // Block comment: copy to closing */
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let end = (i + 2..chars.len().saturating_sub(1))
+                    .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+                    .map_or(chars.len(), |j| j + 2);
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            // Quoted identifier: copy verbatim
+            '"' => {
+                let end = (i + 1..chars.len())
+                    .find(|&j| chars[j] == '"')
+                    .map_or(chars.len(), |j| j + 1);
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            '\'' => {
+                // E'...' allows backslash escapes; drop the E/B/X prefix from output
+                let prefixed = matches!(prev, Some('e' | 'E' | 'b' | 'B' | 'x' | 'X'))
+                    && !(i >= 2 && is_ident_char(chars[i - 2]));
+                let escapes = prefixed && matches!(prev, Some('e' | 'E'));
+                if prefixed {
+                    out.pop();
+                }
+                let start = i + 1;
+                let mut j = start;
+                while j < chars.len() {
+                    if escapes && chars[j] == '\\' {
+                        j += 2;
+                        continue;
+                    }
+                    if chars[j] == '\'' {
+                        if chars.get(j + 1) == Some(&'\'') {
+                            j += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    j += 1;
+                }
+                let end = j.min(chars.len());
+                push_literal(&mut out, &chars[start..end]);
+                i = (j + 1).min(chars.len());
+            }
+            // Dollar-quoted string: $$...$$ or $tag$...$tag$ ($1 is a parameter)
+            '$' if !prev.is_some_and(is_ident_char) => {
+                let tag_end =
+                    (i + 1..chars.len()).find(|&j| !is_ident_char(chars[j]) || chars[j] == '$');
+                let is_tag = tag_end.is_some_and(|t| {
+                    chars[t] == '$' && !chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())
+                });
+                if let (true, Some(t)) = (is_tag, tag_end) {
+                    let tag = &chars[i..=t];
+                    let body_start = t + 1;
+                    let close = (body_start..chars.len()).find(|&j| chars[j..].starts_with(tag));
+                    let body_end = close.unwrap_or(chars.len());
+                    push_literal(&mut out, &chars[body_start..body_end]);
+                    i = close.map_or(chars.len(), |j| j + tag.len());
+                } else {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+            // Numeric literal not part of an identifier or parameter
+            '0'..='9' if numbers && !prev.is_some_and(is_ident_char) => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && matches!(chars[j], '+' | '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        i = j;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                push_literal(&mut out, &chars[start..i]);
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_literals() {
+        let strip = |s: &str| RedactMode::Strip.apply(s).into_owned();
+        assert_eq!(
+            strip("SELECT * FROM users WHERE email = 'a@b.c' AND id = 42"),
+            "SELECT * FROM users WHERE email = ? AND id = ?"
+        );
+        assert_eq!(strip("select 'it''s', E'a\\'b', 1.5e3"), "select ?, ?, ?");
+        assert_eq!(
+            strip("select $1, t2.c3, \"Col 5\" from t2 -- id = 7\nwhere x = $q$5$q$"),
+            "select $1, t2.c3, \"Col 5\" from t2 -- id = 7\nwhere x = ?"
+        );
+        assert_eq!(strip("/* user 'x' */ select 1"), "/* user 'x' */ select ?");
+        // Truncated query: unterminated literal is redacted to the end
+        assert_eq!(
+            strip("insert into t values ('secr"),
+            "insert into t values (?"
+        );
+    }
+
+    #[test]
+    fn hash_and_drop() {
+        let a = RedactMode::Hash.apply("where email = 'a@b.c' or email = 'a@b.c'");
+        assert!(!a.contains("a@b.c"));
+        let parts: Vec<&str> = a.split('\'').collect();
+        assert_eq!(parts[1], parts[3], "equal literals hash equally");
+        assert!(parts[1].starts_with('#'));
+
+        assert_eq!(
+            RedactMode::Strip
+                .apply_plan("Filter: (email = 'a@b.c'::text)  (cost=0.00..35.50 rows=13)"),
+            "Filter: (email = ?::text)  (cost=0.00..35.50 rows=13)"
+        );
+        assert_eq!(RedactMode::Drop.apply("select 1"), REDACTED_TEXT);
+        assert_eq!(RedactMode::Drop.apply(""), "");
+        assert_eq!(RedactMode::Off.apply("select 1"), "select 1");
+        assert_eq!(RedactMode::parse("hash"), Ok(RedactMode::Hash));
+        assert!(RedactMode::parse("foo").is_err());
+    }
+
+    #[test]
+    fn marker_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(RedactMode::read_marker(dir.path()), RedactMode::Off);
+        RedactMode::Strip.write_marker(dir.path()).unwrap();
+        assert_eq!(RedactMode::read_marker(dir.path()), RedactMode::Strip);
+        RedactMode::Off.write_marker(dir.path()).unwrap();
+        assert!(!dir.path().join(MARKER_FILE).exists());
+    }
+}
@@ -0,0 +1,388 @@
+//! Host metrics for platforms without `/proc` (macOS, Windows) through the
+//! `sysinfo` crate (feature `sysinfo`).
+//!
+//! [`SysinfoFs`] renders what sysinfo reports as the `/proc` files the
+//! collectors parse: `stat`, `meminfo`, `loadavg`, `uptime`, `diskstats`,
+//! `net/dev`, per-process `stat`/`status`/`io`/`cmdline`/`comm`, plus
+//! `/etc/passwd` on Unix. The regular collection pipeline then runs on top
+//! of it unchanged.
+//!
+//! Values the platforms do not expose are rendered as zero: CPU time has no
+//! user/system split (busy time is reported as user), disks have byte
+//! counters but no operation counts or latencies, processes have no page
+//! faults or context switches. Files without a source (PSI, vmstat, SNMP)
+//! are absent, so their blocks are skipped.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use sysinfo::{
+    Disks, Networks, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind,
+};
+
+use super::mock::MockFs;
+use super::traits::FileSystem;
+
+/// Rendered files are reused for reads within this interval, so all reads
+/// of one snapshot see the same sample.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Clock ticks per second and page size the `/proc` parsers assume.
+const CLK_TCK: f64 = 100.0;
+const PAGE_SIZE: u64 = 4096;
+
+/// [`FileSystem`] serving `/proc` files rendered from sysinfo.
+///
+/// Clones share the sampled state, like collectors sharing one `RealFs`.
+#[derive(Clone)]
+pub struct SysinfoFs {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    system: System,
+    disks: Disks,
+    networks: Networks,
+    files: MockFs,
+    refreshed_at: Option<Instant>,
+    /// Cumulative `(busy, idle)` ticks per CPU, accumulated from usage
+    /// percentages between refreshes.
+    cpu_ticks: Vec<(f64, f64)>,
+}
+
+impl SysinfoFs {
+    /// Samples the host once; later reads resample when the previous
+    /// sample is older than 500ms.
+    pub fn new() -> Self {
+        let mut state = State {
+            system: System::new(),
+            disks: Disks::new_with_refreshed_list(),
+            networks: Networks::new_with_refreshed_list(),
+            files: MockFs::new(),
+            refreshed_at: None,
+            cpu_ticks: Vec::new(),
+        };
+        state.refresh();
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// The rendered files, resampled first if stale.
+    fn files(&self) -> MutexGuard<'_, State> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state
+            .refreshed_at
+            .is_none_or(|at| at.elapsed() >= REFRESH_INTERVAL)
+        {
+            state.refresh();
+        }
+        state
+    }
+}
+
+impl Default for SysinfoFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileSystem for SysinfoFs {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.files().files.read_to_string(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files().files.exists(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        self.files().files.read_dir(path)
+    }
+}
+
+impl State {
+    fn refresh(&mut self) {
+        let now = Instant::now();
+        let elapsed = self
+            .refreshed_at
+            .map_or(0.0, |at| now.duration_since(at).as_secs_f64());
+        self.refreshed_at = Some(now);
+
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_memory()
+                .with_cpu()
+                .with_disk_usage()
+                .with_tasks()
+                .with_cmd(UpdateKind::OnlyIfNotSet)
+                .with_user(UpdateKind::OnlyIfNotSet),
+        );
+        self.disks.refresh(true);
+        self.networks.refresh(true);
+
+        let cpus = self.system.cpus();
+        self.cpu_ticks.resize(cpus.len(), (0.0, 0.0));
+        for (ticks, cpu) in self.cpu_ticks.iter_mut().zip(cpus) {
+            let total = elapsed * CLK_TCK;
+            let busy = total * f64::from(cpu.cpu_usage().clamp(0.0, 100.0)) / 100.0;
+            ticks.0 += busy;
+            ticks.1 += total - busy;
+        }
+
+        let mut files = MockFs::new();
+        files.add_file("/proc/stat", self.render_stat());
+        files.add_file("/proc/meminfo", self.render_meminfo());
+        let load = System::load_average();
+        files.add_file(
+            "/proc/loadavg",
+            format!(
+                "{:.2} {:.2} {:.2} 1/{} {}\n",
+                load.one,
+                load.five,
+                load.fifteen,
+                self.system.processes().len(),
+                std::process::id()
+            ),
+        );
+        files.add_file("/proc/uptime", format!("{}.00 0.00\n", System::uptime()));
+        files.add_file("/proc/diskstats", self.render_diskstats());
+        files.add_file("/proc/net/dev", self.render_net_dev());
+        #[cfg(unix)]
+        files.add_file("/etc/passwd", render_passwd());
+        self.add_processes(&mut files);
+        self.files = files;
+    }
+
+    fn render_stat(&self) -> String {
+        let line = |name: &str, (busy, idle): (f64, f64)| {
+            format!("{name} {} 0 0 {} 0 0 0 0 0 0\n", busy as u64, idle as u64)
+        };
+        let total = self
+            .cpu_ticks
+            .iter()
+            .fold((0.0, 0.0), |acc, t| (acc.0 + t.0, acc.1 + t.1));
+        let mut out = line("cpu ", total);
+        for (i, &ticks) in self.cpu_ticks.iter().enumerate() {
+            out.push_str(&line(&format!("cpu{i}"), ticks));
+        }
+        let processes = self.system.processes();
+        let running = processes
+            .values()
+            .filter(|p| p.status() == ProcessStatus::Run)
+            .count();
+        out.push_str(&format!(
+            "ctxt 0\nbtime {}\nprocesses {}\nprocs_running {}\nprocs_blocked 0\n",
+            System::boot_time(),
+            processes.len(),
+            running
+        ));
+        out
+    }
+
+    fn render_meminfo(&self) -> String {
+        let kb = |bytes: u64| bytes / 1024;
+        let total = self.system.total_memory();
+        let free = self.system.free_memory();
+        let available = self.system.available_memory();
+        // Reclaimable memory shows as cache, so used = total - available
+        format!(
+            "MemTotal: {} kB\nMemFree: {} kB\nMemAvailable: {} kB\nBuffers: 0 kB\n\
+             Cached: {} kB\nSwapTotal: {} kB\nSwapFree: {} kB\n",
+            kb(total),
+            kb(free),
+            kb(available),
+            kb(available.saturating_sub(free)),
+            kb(self.system.total_swap()),
+            kb(self.system.free_swap())
+        )
+    }
+
+    /// One line per device; volumes sharing a device are counted once.
+    fn render_diskstats(&self) -> String {
+        let mut seen = Vec::new();
+        let mut out = String::new();
+        for (minor, disk) in self.disks.iter().enumerate() {
+            let name = device_name(&disk.name().to_string_lossy(), "disk", minor);
+            if seen.contains(&name) {
+                continue;
+            }
+            let usage = disk.usage();
+            out.push_str(&format!(
+                "   0 {minor:7} {name} 0 0 {} 0 0 0 {} 0 0 0 0\n",
+                usage.total_read_bytes / 512,
+                usage.total_written_bytes / 512
+            ));
+            seen.push(name);
+        }
+        out
+    }
+
+    fn render_net_dev(&self) -> String {
+        let mut out = String::from(
+            "Inter-|   Receive                                                |  Transmit\n \
+             face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n",
+        );
+        for (i, (name, data)) in self.networks.iter().enumerate() {
+            out.push_str(&format!(
+                "{:>6}: {} {} {} 0 0 0 0 0 {} {} {} 0 0 0 0 0\n",
+                device_name(name, "net", i),
+                data.total_received(),
+                data.total_packets_received(),
+                data.total_errors_on_received(),
+                data.total_transmitted(),
+                data.total_packets_transmitted(),
+                data.total_errors_on_transmitted()
+            ));
+        }
+        out
+    }
+
+    fn add_processes(&self, files: &mut MockFs) {
+        let boot_time = System::boot_time();
+        for (pid, process) in self.system.processes() {
+            let pid = pid.as_u32();
+            let ppid = process.parent().map_or(0, |p| p.as_u32());
+            // comm is truncated to 15 bytes by the kernel; keep the limit
+            let comm: String = process
+                .name()
+                .to_string_lossy()
+                .chars()
+                .filter(|c| *c != ')' && !c.is_whitespace())
+                .take(15)
+                .collect();
+            let state = match process.status() {
+                ProcessStatus::Run => 'R',
+                ProcessStatus::Idle => 'I',
+                ProcessStatus::Stop => 'T',
+                ProcessStatus::Zombie => 'Z',
+                ProcessStatus::Dead => 'X',
+                ProcessStatus::UninterruptibleDiskSleep => 'D',
+                _ => 'S',
+            };
+            let utime = process.accumulated_cpu_time() / 10;
+            let threads = process.tasks().map_or(1, |t| t.len().max(1));
+            let start_ticks = process.start_time().saturating_sub(boot_time) * CLK_TCK as u64;
+            let stat = format!(
+                "{pid} ({comm}) {state} {ppid} {pid} {pid} 0 -1 0 0 0 0 0 {utime} 0 0 0 20 0 \
+                 {threads} 0 {start_ticks} {} {} 18446744073709551615 0 0 0 0 0 0 0 0 0 0 0 0 \
+                 17 0 0 0 0 0 0 0 0 0 0 0 0 0 0",
+                process.virtual_memory(),
+                process.memory() / PAGE_SIZE
+            );
+
+            #[cfg(unix)]
+            let uid = process.user_id().map_or(0, |uid| **uid);
+            #[cfg(not(unix))]
+            let uid = 0;
+            let status = format!(
+                "Name:\t{comm}\nPid:\t{pid}\nPPid:\t{ppid}\nUid:\t{uid}\t{uid}\t{uid}\t{uid}\n\
+                 VmSize:\t{} kB\nVmRSS:\t{} kB\nThreads:\t{threads}\n",
+                process.virtual_memory() / 1024,
+                process.memory() / 1024
+            );
+
+            let disk = process.disk_usage();
+            let io = format!(
+                "rchar: {r}\nwchar: {w}\nsyscr: 0\nsyscw: 0\nread_bytes: {r}\n\
+                 write_bytes: {w}\ncancelled_write_bytes: 0\n",
+                r = disk.total_read_bytes,
+                w = disk.total_written_bytes
+            );
+
+            let mut cmdline = String::new();
+            for arg in process.cmd() {
+                cmdline.push_str(&arg.to_string_lossy());
+                cmdline.push('\0');
+            }
+
+            files.add_process(pid, &stat, &status, &io, &cmdline, &format!("{comm}\n"));
+        }
+    }
+}
+
+/// A `/proc`-style device name: one token, `fallback<index>` when empty.
+/// `/dev/disk1s1` becomes `disk1s1`, `Ethernet 2` becomes `Ethernet_2`.
+fn device_name(name: &str, fallback: &str, index: usize) -> String {
+    let name: String = name
+        .trim_start_matches("/dev/")
+        .trim_end_matches(['\\', ':'])
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || c == ':' {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        format!("{fallback}{index}")
+    } else {
+        name
+    }
+}
+
+#[cfg(unix)]
+fn render_passwd() -> String {
+    let users = sysinfo::Users::new_with_refreshed_list();
+    users
+        .iter()
+        .map(|user| {
+            format!(
+                "{}:x:{}:{}::/:\n",
+                user.name(),
+                **user.id(),
+                *user.group_id()
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::Collector;
+    use crate::storage::model::DataBlock;
+
+    #[test]
+    fn device_names_are_single_tokens() {
+        assert_eq!(device_name("/dev/disk1s1", "disk", 0), "disk1s1");
+        assert_eq!(device_name("Ethernet 2", "net", 0), "Ethernet_2");
+        assert_eq!(device_name("C:\\", "disk", 0), "C");
+        assert_eq!(device_name("", "disk", 3), "disk3");
+    }
+
+    #[test]
+    fn collects_host_snapshot() {
+        let fs = SysinfoFs::new();
+        let mut collector = Collector::new(fs, "/proc");
+        let snapshot = collector.collect_snapshot().unwrap();
+
+        let processes = snapshot
+            .blocks
+            .iter()
+            .find_map(|b| match b {
+                DataBlock::Processes(p) => Some(p),
+                _ => None,
+            })
+            .unwrap();
+        assert!(processes.iter().any(|p| p.pid == std::process::id()));
+        assert!(snapshot.blocks.iter().any(|b| matches!(
+            b,
+            DataBlock::SystemMem(mem) if mem.total > 0
+        )));
+        assert!(
+            snapshot
+                .blocks
+                .iter()
+                .any(|b| matches!(b, DataBlock::SystemCpu(cpus) if !cpus.is_empty()))
+        );
+    }
+}
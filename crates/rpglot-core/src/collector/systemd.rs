@@ -0,0 +1,174 @@
+//! State of the PostgreSQL systemd unit.
+//!
+//! Runs `systemctl show` on every snapshot. The unit is either given
+//! explicitly or detected from the postmaster's `/proc/[pid]/cgroup`
+//! (`.../system.slice/postgresql@16-main.service`), so distribution specific
+//! unit names need no configuration.
+
+use std::process::Command;
+
+use tracing::{debug, warn};
+
+use crate::storage::model::SystemdUnitInfo;
+
+/// Properties requested from `systemctl show`.
+const PROPERTIES: &str = "Id,LoadState,ActiveState,SubState,Result,MainPID,NRestarts,\
+                          ActiveEnterTimestampMonotonic,StateChangeTimestampMonotonic";
+
+/// Parses `systemctl show --property=...` output (`Key=Value` lines).
+///
+/// Timestamps are reported on the monotonic clock (microseconds since boot)
+/// and converted with `boot_time` (seconds since epoch). Returns `None` for
+/// units systemd does not know.
+pub fn parse_systemctl_show(output: &str, boot_time: u64) -> Option<SystemdUnitInfo> {
+    let mut info = SystemdUnitInfo::default();
+    let to_epoch = |us: &str| match us.parse::<u64>() {
+        Ok(us) if us > 0 => (boot_time + us / 1_000_000) as i64,
+        _ => 0,
+    };
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key {
+            "Id" => info.unit = value.to_string(),
+            "LoadState" if value == "not-found" => return None,
+            "ActiveState" => info.active_state = value.to_string(),
+            "SubState" => info.sub_state = value.to_string(),
+            "Result" => info.result = value.to_string(),
+            "MainPID" => info.main_pid = value.parse().unwrap_or(0),
+            "NRestarts" => info.n_restarts = value.parse().unwrap_or(0),
+            "ActiveEnterTimestampMonotonic" => info.active_enter_ts = to_epoch(value),
+            "StateChangeTimestampMonotonic" => info.state_change_ts = to_epoch(value),
+            _ => {}
+        }
+    }
+    if info.unit.is_empty() || info.active_state.is_empty() {
+        return None;
+    }
+    Some(info)
+}
+
+/// Extracts the service unit from `/proc/[pid]/cgroup` content
+/// (cgroup v2 `0::/...` line or the v1 `name=systemd` hierarchy).
+pub fn unit_from_cgroup(content: &str) -> Option<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ':');
+            let (_, controllers, path) = (parts.next()?, parts.next()?, parts.next()?);
+            (controllers.is_empty() || controllers == "name=systemd").then_some(path)
+        })
+        .flat_map(|path| path.split('/'))
+        .find(|component| component.ends_with(".service"))
+        .map(str::to_string)
+}
+
+/// Collects the state of the PostgreSQL systemd unit via `systemctl`.
+pub struct SystemdCollector {
+    /// Unit name; `None` until detected from the postmaster's cgroup.
+    unit: Option<String>,
+    /// `systemctl` failed to run; stop trying.
+    disabled: bool,
+}
+
+impl SystemdCollector {
+    /// Collector for `unit`, or with automatic detection when `None`.
+    pub fn new(unit: Option<String>) -> Self {
+        Self {
+            unit,
+            disabled: false,
+        }
+    }
+
+    /// Whether the unit still has to be detected.
+    pub fn needs_unit(&self) -> bool {
+        self.unit.is_none() && !self.disabled
+    }
+
+    /// Sets the unit detected from the postmaster's cgroup.
+    pub fn set_unit(&mut self, unit: String) {
+        debug!(unit, "detected PostgreSQL systemd unit");
+        self.unit = Some(unit);
+    }
+
+    /// Queries the unit state. `boot_time` is from `/proc/stat`.
+    pub fn collect(&mut self, boot_time: u64) -> Option<SystemdUnitInfo> {
+        if self.disabled {
+            return None;
+        }
+        let unit = self.unit.as_deref()?;
+        let output = match Command::new("systemctl")
+            .args(["show", "--property", PROPERTIES, unit])
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) => {
+                warn!(error = %e, "failed to run systemctl, unit state collection disabled");
+                self.disabled = true;
+                return None;
+            }
+        };
+        if !output.status.success() {
+            debug!(unit, status = %output.status, "systemctl show failed");
+            return None;
+        }
+        parse_systemctl_show(&String::from_utf8_lossy(&output.stdout), boot_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_systemctl_show() {
+        let output = "\
+Id=postgresql@16-main.service
+LoadState=loaded
+ActiveState=active
+SubState=running
+Result=success
+MainPID=812
+NRestarts=2
+ActiveEnterTimestampMonotonic=90500000
+StateChangeTimestampMonotonic=90600000
+";
+        let info = parse_systemctl_show(output, 1_700_000_000).unwrap();
+        assert_eq!(info.unit, "postgresql@16-main.service");
+        assert_eq!(info.active_state, "active");
+        assert_eq!(info.sub_state, "running");
+        assert_eq!(info.main_pid, 812);
+        assert_eq!(info.n_restarts, 2);
+        assert_eq!(info.active_enter_ts, 1_700_000_090);
+        assert_eq!(info.state_change_ts, 1_700_000_090);
+
+        let failed = "Id=postgresql.service\nActiveState=failed\nResult=signal\n\
+                      ActiveEnterTimestampMonotonic=0\n";
+        let info = parse_systemctl_show(failed, 1_700_000_000).unwrap();
+        assert_eq!(info.result, "signal");
+        assert_eq!(info.active_enter_ts, 0);
+
+        let missing = "Id=nope.service\nLoadState=not-found\nActiveState=inactive\n";
+        assert_eq!(parse_systemctl_show(missing, 0), None);
+    }
+
+    #[test]
+    fn detects_unit_from_cgroup() {
+        assert_eq!(
+            unit_from_cgroup("0::/system.slice/postgresql@16-main.service\n").as_deref(),
+            Some("postgresql@16-main.service")
+        );
+        let v1 = "12:memory:/system.slice/postgresql.service\n\
+                  1:name=systemd:/system.slice/postgresql-15.service\n";
+        assert_eq!(
+            unit_from_cgroup(v1).as_deref(),
+            Some("postgresql-15.service")
+        );
+        // Postgres started by hand in a user session
+        assert_eq!(
+            unit_from_cgroup("0::/user.slice/user-1000.slice/session-3.scope\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Adaptive throttling of expensive collectors under load.
+//!
+//! When a snapshot takes more than a share of the snapshot interval, or CPU
+//! or I/O pressure is critical, pg_stat_statements, pg_store_plans, table /
+//! index statistics and per-backend PSS are not refreshed for the next few
+//! snapshots: they are the costliest part of a collection and the least
+//! urgent one while the host struggles. Those snapshots carry
+//! [`DataBlock::CollectionDegraded`](crate::storage::model::DataBlock::CollectionDegraded)
+//! so the stale data can be explained later.
+
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use crate::storage::model::{CollectionDegradedInfo, DegradedReason, SystemPsiInfo};
+
+/// PSI resources that trigger throttling: CPU (0) and I/O (2).
+const PSI_RESOURCES: [u8; 2] = [0, 2];
+
+/// Thresholds of [`LoadThrottle`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThrottleConfig {
+    /// Collection time, as a percentage of the snapshot interval, above
+    /// which collection is throttled.
+    pub budget_pct: u32,
+    /// CPU or I/O `some avg10` pressure (percent) above which collection is
+    /// throttled.
+    pub psi_critical: f32,
+    /// Snapshots collected without the expensive collectors once triggered.
+    pub cycles: u32,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            budget_pct: 50,
+            psi_critical: 40.0,
+            cycles: 3,
+        }
+    }
+}
+
+/// Decides which snapshots skip the expensive collectors.
+#[derive(Debug, Clone)]
+pub struct LoadThrottle {
+    config: ThrottleConfig,
+    interval: Duration,
+    /// Throttled snapshots left.
+    remaining: u32,
+    /// Observation that started (or extended) throttling.
+    trigger: Option<CollectionDegradedInfo>,
+}
+
+impl LoadThrottle {
+    /// Throttle for snapshots taken every `interval`.
+    pub fn new(interval: Duration, config: ThrottleConfig) -> Self {
+        Self {
+            config,
+            interval,
+            remaining: 0,
+            trigger: None,
+        }
+    }
+
+    /// Starts a snapshot. Returns the marker to store when the expensive
+    /// collectors have to be skipped, `None` otherwise.
+    pub fn begin(&mut self) -> Option<CollectionDegradedInfo> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let mut info = self.trigger?;
+        info.remaining = self.remaining;
+        Some(info)
+    }
+
+    /// Records a finished snapshot: its collection time and PSI block.
+    pub fn observe(&mut self, collection: Duration, psi: &[SystemPsiInfo]) {
+        let pressure = psi
+            .iter()
+            .filter(|p| PSI_RESOURCES.contains(&p.resource))
+            .map(|p| p.some_avg10)
+            .fold(0.0, f32::max);
+        let budget = self.interval * self.config.budget_pct / 100;
+        let reason = if pressure >= self.config.psi_critical {
+            DegradedReason::Pressure
+        } else if collection > budget {
+            DegradedReason::SlowCollection
+        } else {
+            if self.remaining == 0 && self.trigger.take().is_some() {
+                info!("collection load is back to normal, expensive collectors resumed");
+            }
+            return;
+        };
+
+        if self.remaining == 0 {
+            warn!(
+                ?reason,
+                collection_ms = collection.as_millis() as u64,
+                psi = pressure,
+                cycles = self.config.cycles,
+                "host under load, skipping expensive collectors"
+            );
+        }
+        self.remaining = self.config.cycles;
+        self.trigger = Some(CollectionDegradedInfo {
+            reason,
+            collection_ms: collection.as_millis().min(u32::MAX as u128) as u32,
+            psi_some_avg10: pressure,
+            remaining: 0,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn psi(resource: u8, some_avg10: f32) -> SystemPsiInfo {
+        SystemPsiInfo {
+            resource,
+            some_avg10,
+            ..SystemPsiInfo::default()
+        }
+    }
+
+    fn throttle() -> LoadThrottle {
+        LoadThrottle::new(Duration::from_secs(10), ThrottleConfig::default())
+    }
+
+    #[test]
+    fn slow_collection_skips_next_cycles() {
+        let mut t = throttle();
+        assert_eq!(t.begin(), None);
+        t.observe(Duration::from_secs(6), &[]);
+
+        let first = t.begin().unwrap();
+        assert_eq!(first.reason, DegradedReason::SlowCollection);
+        assert_eq!(first.collection_ms, 6000);
+        assert_eq!(first.remaining, 2);
+        t.observe(Duration::from_millis(100), &[]);
+        assert_eq!(t.begin().unwrap().remaining, 1);
+        t.observe(Duration::from_millis(100), &[]);
+        assert_eq!(t.begin().unwrap().remaining, 0);
+        t.observe(Duration::from_millis(100), &[]);
+        assert_eq!(t.begin(), None);
+    }
+
+    #[test]
+    fn critical_pressure_extends_throttling() {
+        let mut t = throttle();
+        // Memory pressure alone does not throttle
+        t.observe(Duration::from_millis(100), &[psi(1, 90.0)]);
+        assert_eq!(t.begin(), None);
+
+        t.observe(Duration::from_millis(100), &[psi(0, 12.0), psi(2, 55.0)]);
+        let info = t.begin().unwrap();
+        assert_eq!(info.reason, DegradedReason::Pressure);
+        assert_eq!(info.psi_some_avg10, 55.0);
+
+        // Still under pressure: the countdown restarts
+        t.observe(Duration::from_millis(100), &[psi(2, 60.0)]);
+        assert_eq!(t.begin().unwrap().remaining, 2);
+    }
+
+    #[test]
+    fn fast_collection_is_not_throttled() {
+        let mut t = throttle();
+        t.observe(Duration::from_secs(5), &[psi(0, 39.0)]);
+        assert_eq!(t.begin(), None);
+    }
+}
@@ -0,0 +1,623 @@
+//! Metric trajectory of one entity — a backend PID or a statement queryid —
+//! through a history range (session-level drill-down).
+//!
+//! [`EntityTracker`] turns consecutive snapshots into [`EntityPoint`]s and
+//! backend state changes. [`entity_history`] feeds it from a
+//! [`HistoryProvider`](crate::provider::HistoryProvider) reading only the
+//! blocks the entity lives in. Both the TUI (`#` popup) and the Web API
+//! (`/api/v1/entity/history`) delegate here.
+
+#[cfg(feature = "provider")]
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rates::{MAX_RATE_DT_SECS, df64, di64};
+use crate::storage::model::{DataBlock, PgStatActivityInfo, ProcessInfo, Snapshot};
+
+/// Maximum number of snapshots sampled for one trajectory.
+pub const MAX_ENTITY_POINTS: usize = 720;
+
+/// State recorded when a tracked backend disappears.
+pub const GONE_STATE: &str = "(gone)";
+
+/// Kind of entity to follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    /// Backend process ID (pg_stat_activity + OS process).
+    Pid,
+    /// pg_stat_statements queryid.
+    Queryid,
+}
+
+impl EntityKind {
+    /// Blocks read from each snapshot (see [`DataBlock::KIND_NAMES`]).
+    pub fn blocks(self) -> &'static [&'static str] {
+        match self {
+            Self::Pid => &["processes", "pg_stat_activity", "system_cpu"],
+            Self::Queryid => &["pg_stat_statements", "pg_stat_activity"],
+        }
+    }
+}
+
+/// Metrics of the entity in one snapshot. Fields of the other kind are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct EntityPoint {
+    /// Snapshot timestamp (epoch seconds).
+    pub timestamp: i64,
+    /// Backend state (`active`, `idle in transaction`, ...).
+    pub state: Option<String>,
+    /// Wait event as `type:event`.
+    pub wait_event: Option<String>,
+    /// queryid of the statement the backend runs.
+    pub query_id: Option<i64>,
+    /// Process CPU usage since the previous sample (% of total CPU time).
+    pub cpu_pct: Option<f64>,
+    /// Resident memory (KB).
+    pub rss_kb: Option<u64>,
+    pub read_bytes_s: Option<f64>,
+    pub write_bytes_s: Option<f64>,
+    pub calls_s: Option<f64>,
+    /// Execution time per second of wall clock (ms/s).
+    pub exec_time_ms_s: Option<f64>,
+    /// Mean execution time of calls within the interval (ms).
+    pub mean_ms: Option<f64>,
+    pub rows_s: Option<f64>,
+    /// Backends in state `active` running this queryid.
+    pub active_sessions: Option<u32>,
+}
+
+/// A backend state transition (PID only).
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct EntityStateChange {
+    /// First sample with the new state (epoch seconds).
+    pub timestamp: i64,
+    /// New state, or `(gone)` when the backend disappeared.
+    pub state: String,
+    pub wait_event: Option<String>,
+    pub query_id: Option<i64>,
+}
+
+/// Trajectory of one entity through a time range.
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct EntityHistory {
+    pub kind: EntityKind,
+    pub id: i64,
+    /// application_name or process name (PID), query text (queryid).
+    pub label: Option<String>,
+    /// Samples where the entity was present, oldest first.
+    pub points: Vec<EntityPoint>,
+    pub changes: Vec<EntityStateChange>,
+    /// The range held more than [`MAX_ENTITY_POINTS`] snapshots and was sampled.
+    pub sampled: bool,
+}
+
+/// Cumulative counters of a backend at the previous sample.
+#[derive(Debug, Clone, Copy)]
+struct BackendCounters {
+    timestamp: i64,
+    cpu_ticks: u64,
+    total_ticks: Option<u64>,
+    rsz: u64,
+    wsz: u64,
+}
+
+/// Cumulative counters of a statement at the previous sample.
+#[derive(Debug, Clone, Copy)]
+struct StatementCounters {
+    collected_at: i64,
+    calls: i64,
+    total_exec_time: f64,
+    rows: i64,
+}
+
+/// Interval rates of a statement, carried over samples with cached counters.
+#[derive(Debug, Clone, Copy)]
+struct StatementRates {
+    calls_s: f64,
+    exec_time_ms_s: f64,
+    mean_ms: f64,
+    rows_s: f64,
+}
+
+/// Builds an [`EntityHistory`] from snapshots pushed oldest first.
+///
+/// Samples do not need to be adjacent snapshots: rates are computed from
+/// cumulative counters between consecutive samples.
+#[derive(Debug)]
+pub struct EntityTracker {
+    history: EntityHistory,
+    backend: Option<BackendCounters>,
+    statement: Option<StatementCounters>,
+    rates: Option<StatementRates>,
+    /// (state, wait_event, query_id) of the last change.
+    last_state: Option<(String, Option<String>, Option<i64>)>,
+}
+
+impl EntityTracker {
+    pub fn new(kind: EntityKind, id: i64) -> Self {
+        Self {
+            history: EntityHistory {
+                kind,
+                id,
+                label: None,
+                points: Vec::new(),
+                changes: Vec::new(),
+                sampled: false,
+            },
+            backend: None,
+            statement: None,
+            rates: None,
+            last_state: None,
+        }
+    }
+
+    /// Adds the next sample. `resolve` maps interned hashes to strings.
+    pub fn push(&mut self, snapshot: &Snapshot, resolve: &mut dyn FnMut(u64) -> Option<String>) {
+        match self.history.kind {
+            EntityKind::Pid => self.push_backend(snapshot, resolve),
+            EntityKind::Queryid => self.push_statement(snapshot, resolve),
+        }
+    }
+
+    pub fn finish(self) -> EntityHistory {
+        self.history
+    }
+
+    fn push_backend(
+        &mut self,
+        snapshot: &Snapshot,
+        resolve: &mut dyn FnMut(u64) -> Option<String>,
+    ) {
+        let pid = self.history.id;
+        let activity = activities(snapshot).iter().find(|a| a.pid as i64 == pid);
+        let process = processes(snapshot).iter().find(|p| p.pid as i64 == pid);
+        let ts = snapshot.timestamp;
+
+        if activity.is_none() && process.is_none() {
+            self.backend = None;
+            if self.last_state.as_ref().is_some_and(|s| s.0 != GONE_STATE) {
+                self.change(ts, GONE_STATE.to_string(), None, None);
+            }
+            return;
+        }
+
+        let mut point = EntityPoint {
+            timestamp: ts,
+            ..EntityPoint::default()
+        };
+        if let Some(a) = activity {
+            point.state = resolve_nonempty(resolve, a.state_hash);
+            point.wait_event = match (
+                resolve_nonempty(resolve, a.wait_event_type_hash),
+                resolve_nonempty(resolve, a.wait_event_hash),
+            ) {
+                (Some(t), Some(e)) => Some(format!("{t}:{e}")),
+                (t, e) => t.or(e),
+            };
+            point.query_id = (a.query_id != 0).then_some(a.query_id);
+            if let Some(label) = resolve_nonempty(resolve, a.application_name_hash)
+                .or_else(|| resolve_nonempty(resolve, a.backend_type_hash))
+            {
+                self.history.label = Some(label);
+            }
+        }
+
+        if let Some(p) = process {
+            let cur = BackendCounters {
+                timestamp: ts,
+                cpu_ticks: p.cpu.utime + p.cpu.stime,
+                total_ticks: total_cpu_ticks(snapshot),
+                rsz: p.dsk.rsz,
+                wsz: p.dsk.wsz,
+            };
+            point.rss_kb = Some(p.mem.rmem);
+            if let Some(prev) = self.backend {
+                if let (Some(total), Some(prev_total)) = (cur.total_ticks, prev.total_ticks)
+                    && total > prev_total
+                {
+                    point.cpu_pct = Some(
+                        cur.cpu_ticks.saturating_sub(prev.cpu_ticks) as f64
+                            / (total - prev_total) as f64
+                            * 100.0,
+                    );
+                }
+                let dt = (cur.timestamp - prev.timestamp) as f64;
+                if dt > 0.0 && dt <= MAX_RATE_DT_SECS {
+                    point.read_bytes_s = Some(cur.rsz.saturating_sub(prev.rsz) as f64 / dt);
+                    point.write_bytes_s = Some(cur.wsz.saturating_sub(prev.wsz) as f64 / dt);
+                }
+            }
+            self.backend = Some(cur);
+            if self.history.label.is_none() {
+                self.history.label = resolve_nonempty(resolve, p.name_hash);
+            }
+        } else {
+            self.backend = None;
+        }
+
+        // Plain OS processes (no pg_stat_activity row) have no state
+        if activity.is_some() {
+            let state = point.state.clone().unwrap_or_default();
+            if self
+                .last_state
+                .as_ref()
+                .is_none_or(|(s, w, q)| (s, w, q) != (&state, &point.wait_event, &point.query_id))
+            {
+                self.change(ts, state, point.wait_event.clone(), point.query_id);
+            }
+        }
+        self.history.points.push(point);
+    }
+
+    fn push_statement(
+        &mut self,
+        snapshot: &Snapshot,
+        resolve: &mut dyn FnMut(u64) -> Option<String>,
+    ) {
+        let queryid = self.history.id;
+        let stmt = snapshot.blocks.iter().find_map(|b| match b {
+            DataBlock::PgStatStatements(v) => v.iter().find(|s| s.queryid == queryid),
+            _ => None,
+        });
+        let has_activity = snapshot
+            .blocks
+            .iter()
+            .any(|b| matches!(b, DataBlock::PgStatActivity(_)));
+        let active = activities(snapshot)
+            .iter()
+            .filter(|a| a.query_id == queryid)
+            .filter(|a| resolve(a.state_hash).as_deref() == Some("active"))
+            .count() as u32;
+
+        match stmt {
+            Some(stmt) => {
+                let cur = StatementCounters {
+                    collected_at: if stmt.collected_at > 0 {
+                        stmt.collected_at
+                    } else {
+                        snapshot.timestamp
+                    },
+                    calls: stmt.calls,
+                    total_exec_time: stmt.total_exec_time,
+                    rows: stmt.rows,
+                };
+                match self.statement {
+                    // Collector cache: same counters, keep the last rates
+                    Some(prev) if prev.collected_at == cur.collected_at => {}
+                    Some(prev) => {
+                        self.rates = statement_rates(prev, cur);
+                        self.statement = Some(cur);
+                    }
+                    None => {
+                        self.rates = None;
+                        self.statement = Some(cur);
+                    }
+                }
+                if self.history.label.is_none() {
+                    self.history.label = resolve_nonempty(resolve, stmt.query_hash);
+                }
+            }
+            None => {
+                self.statement = None;
+                self.rates = None;
+                if active == 0 {
+                    return;
+                }
+            }
+        }
+
+        let rates = self.rates;
+        self.history.points.push(EntityPoint {
+            timestamp: snapshot.timestamp,
+            calls_s: rates.map(|r| r.calls_s),
+            exec_time_ms_s: rates.map(|r| r.exec_time_ms_s),
+            mean_ms: rates.map(|r| r.mean_ms),
+            rows_s: rates.map(|r| r.rows_s),
+            active_sessions: has_activity.then_some(active),
+            ..EntityPoint::default()
+        });
+    }
+
+    fn change(
+        &mut self,
+        timestamp: i64,
+        state: String,
+        wait_event: Option<String>,
+        query_id: Option<i64>,
+    ) {
+        self.last_state = Some((state.clone(), wait_event.clone(), query_id));
+        self.history.changes.push(EntityStateChange {
+            timestamp,
+            state,
+            wait_event,
+            query_id,
+        });
+    }
+}
+
+/// Rates between two statement samples; `None` after a gap or a stats reset.
+fn statement_rates(prev: StatementCounters, cur: StatementCounters) -> Option<StatementRates> {
+    let dt = (cur.collected_at - prev.collected_at) as f64;
+    if dt <= 0.0 || dt > MAX_RATE_DT_SECS {
+        return None;
+    }
+    let calls = di64(cur.calls, prev.calls)?;
+    let time = df64(cur.total_exec_time, prev.total_exec_time)?;
+    let rows = di64(cur.rows, prev.rows)?;
+    Some(StatementRates {
+        calls_s: calls as f64 / dt,
+        exec_time_ms_s: time / dt,
+        mean_ms: if calls > 0 { time / calls as f64 } else { 0.0 },
+        rows_s: rows as f64 / dt,
+    })
+}
+
+fn resolve_nonempty(resolve: &mut dyn FnMut(u64) -> Option<String>, hash: u64) -> Option<String> {
+    if hash == 0 {
+        return None;
+    }
+    resolve(hash).filter(|s| !s.is_empty())
+}
+
+fn activities(snapshot: &Snapshot) -> &[PgStatActivityInfo] {
+    snapshot
+        .blocks
+        .iter()
+        .find_map(|b| match b {
+            DataBlock::PgStatActivity(v) => Some(v.as_slice()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn processes(snapshot: &Snapshot) -> &[ProcessInfo] {
+    snapshot
+        .blocks
+        .iter()
+        .find_map(|b| match b {
+            DataBlock::Processes(v) => Some(v.as_slice()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn total_cpu_ticks(snapshot: &Snapshot) -> Option<u64> {
+    snapshot.blocks.iter().find_map(|b| match b {
+        DataBlock::SystemCpu(cpus) => cpus
+            .iter()
+            .find(|c| c.cpu_id == -1)
+            .map(|c| c.user + c.nice + c.system + c.idle + c.iowait + c.irq + c.softirq + c.steal),
+        _ => None,
+    })
+}
+
+/// Evenly spaced indices for sampling `len` items into at most `max` samples,
+/// always keeping the first and the last.
+pub fn sample_indices(len: usize, max: usize) -> Vec<usize> {
+    if len <= max || max < 2 {
+        return (0..len.min(max)).collect();
+    }
+    let step = (len - 1) as f64 / (max - 1) as f64;
+    let mut indices: Vec<usize> = (0..max)
+        .map(|i| ((i as f64 * step).round() as usize).min(len - 1))
+        .collect();
+    indices.dedup();
+    indices
+}
+
+/// Follows `id` through the snapshots of `provider` within `[start, end]`
+/// (epoch seconds), sampling at most [`MAX_ENTITY_POINTS`] snapshots.
+///
+/// Only the blocks listed by [`EntityKind::blocks`] are decoded, and each
+/// chunk's interner is loaded once, on the first hash not seen before.
+#[cfg(feature = "provider")]
+pub fn entity_history(
+    provider: &mut crate::provider::HistoryProvider,
+    kind: EntityKind,
+    id: i64,
+    start: i64,
+    end: i64,
+) -> EntityHistory {
+    let timestamps = provider.timestamps();
+    let from = timestamps.partition_point(|&t| t < start);
+    let to = timestamps.partition_point(|&t| t <= end);
+    let len = to.saturating_sub(from);
+
+    let mut tracker = EntityTracker::new(kind, id);
+    let mut strings: HashMap<u64, Option<String>> = HashMap::new();
+    for i in sample_indices(len, MAX_ENTITY_POINTS) {
+        let position = from + i;
+        let Some(snapshot) = provider.snapshot_blocks_at(position, kind.blocks()) else {
+            continue;
+        };
+        let mut interner = None;
+        tracker.push(&snapshot, &mut |hash| {
+            strings
+                .entry(hash)
+                .or_insert_with(|| {
+                    interner
+                        .get_or_insert_with(|| provider.interner_at(position))
+                        .as_ref()
+                        .and_then(|i| i.resolve(hash))
+                        .map(str::to_string)
+                })
+                .clone()
+        });
+    }
+
+    let mut history = tracker.finish();
+    history.sampled = len > MAX_ENTITY_POINTS;
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::{
+        PgStatStatementsInfo, ProcessCpuInfo, ProcessDskInfo, ProcessMemInfo, SystemCpuInfo,
+    };
+
+    const ACTIVE: u64 = 1;
+    const IDLE: u64 = 2;
+    const APP: u64 = 3;
+
+    fn resolver() -> impl FnMut(u64) -> Option<String> {
+        |hash| match hash {
+            ACTIVE => Some("active".to_string()),
+            IDLE => Some("idle".to_string()),
+            APP => Some("billing".to_string()),
+            _ => None,
+        }
+    }
+
+    fn backend(ts: i64, state_hash: u64, cpu: u64, rsz: u64) -> Snapshot {
+        Snapshot {
+            timestamp: ts,
+            blocks: vec![
+                DataBlock::Processes(vec![ProcessInfo {
+                    pid: 42,
+                    cpu: ProcessCpuInfo {
+                        utime: cpu,
+                        ..ProcessCpuInfo::default()
+                    },
+                    mem: ProcessMemInfo {
+                        rmem: 1024,
+                        ..ProcessMemInfo::default()
+                    },
+                    dsk: ProcessDskInfo {
+                        rsz,
+                        ..ProcessDskInfo::default()
+                    },
+                    ..ProcessInfo::default()
+                }]),
+                DataBlock::PgStatActivity(vec![PgStatActivityInfo {
+                    pid: 42,
+                    state_hash,
+                    application_name_hash: APP,
+                    query_id: 7,
+                    ..PgStatActivityInfo::default()
+                }]),
+                DataBlock::SystemCpu(vec![SystemCpuInfo {
+                    cpu_id: -1,
+                    user: ts as u64 * 10,
+                    ..SystemCpuInfo::default()
+                }]),
+            ],
+        }
+    }
+
+    fn statement(ts: i64, calls: i64, total_exec_time: f64) -> Snapshot {
+        Snapshot {
+            timestamp: ts,
+            blocks: vec![
+                DataBlock::PgStatStatements(vec![PgStatStatementsInfo {
+                    queryid: 7,
+                    calls,
+                    total_exec_time,
+                    rows: calls,
+                    collected_at: ts,
+                    ..PgStatStatementsInfo::default()
+                }]),
+                DataBlock::PgStatActivity(vec![PgStatActivityInfo {
+                    pid: 42,
+                    state_hash: ACTIVE,
+                    query_id: 7,
+                    ..PgStatActivityInfo::default()
+                }]),
+            ],
+        }
+    }
+
+    #[test]
+    fn backend_trajectory_and_state_changes() {
+        let mut tracker = EntityTracker::new(EntityKind::Pid, 42);
+        let mut resolve = resolver();
+        tracker.push(&backend(100, ACTIVE, 0, 0), &mut resolve);
+        tracker.push(&backend(110, ACTIVE, 50, 1000), &mut resolve);
+        tracker.push(&backend(120, IDLE, 50, 1000), &mut resolve);
+        tracker.push(
+            &Snapshot {
+                timestamp: 130,
+                blocks: vec![],
+            },
+            &mut resolve,
+        );
+        let h = tracker.finish();
+
+        assert_eq!(h.label.as_deref(), Some("billing"));
+        assert_eq!(h.points.len(), 3);
+        assert_eq!(h.points[0].cpu_pct, None);
+        // 50 of 100 total ticks
+        assert_eq!(h.points[1].cpu_pct, Some(50.0));
+        assert_eq!(h.points[1].read_bytes_s, Some(100.0));
+        assert_eq!(h.points[1].rss_kb, Some(1024));
+        assert_eq!(h.points[2].state.as_deref(), Some("idle"));
+
+        let states: Vec<(i64, &str)> = h
+            .changes
+            .iter()
+            .map(|c| (c.timestamp, c.state.as_str()))
+            .collect();
+        assert_eq!(
+            states,
+            vec![(100, "active"), (120, "idle"), (130, GONE_STATE)]
+        );
+        assert_eq!(h.changes[0].query_id, Some(7));
+    }
+
+    #[test]
+    fn statement_rates_carry_over_cached_samples() {
+        let mut tracker = EntityTracker::new(EntityKind::Queryid, 7);
+        let mut resolve = resolver();
+        tracker.push(&statement(100, 10, 100.0), &mut resolve);
+        tracker.push(&statement(110, 30, 300.0), &mut resolve);
+        // Cached counters: same collected_at
+        let mut cached = statement(110, 30, 300.0);
+        cached.timestamp = 120;
+        tracker.push(&cached, &mut resolve);
+        // Stats reset breaks the series
+        tracker.push(&statement(130, 5, 10.0), &mut resolve);
+        let h = tracker.finish();
+
+        assert_eq!(h.points.len(), 4);
+        assert_eq!(h.points[0].calls_s, None);
+        assert_eq!(h.points[1].calls_s, Some(2.0));
+        assert_eq!(h.points[1].exec_time_ms_s, Some(20.0));
+        assert_eq!(h.points[1].mean_ms, Some(10.0));
+        assert_eq!(h.points[2].calls_s, Some(2.0));
+        assert_eq!(h.points[3].calls_s, None);
+        assert_eq!(h.points[3].active_sessions, Some(1));
+        assert!(h.changes.is_empty());
+    }
+
+    #[test]
+    fn sample_indices_keep_ends() {
+        assert_eq!(sample_indices(3, 10), vec![0, 1, 2]);
+        assert_eq!(sample_indices(0, 10), Vec::<usize>::new());
+        let s = sample_indices(10_000, 100);
+        assert_eq!(s.len(), 100);
+        assert_eq!((s[0], *s.last().unwrap()), (0, 9_999));
+    }
+
+    #[cfg(feature = "provider")]
+    #[test]
+    fn entity_history_reads_range_from_provider() {
+        let snapshots = vec![
+            backend(100, ACTIVE, 0, 0),
+            backend(110, ACTIVE, 50, 0),
+            backend(120, IDLE, 50, 0),
+        ];
+        let mut provider = crate::provider::HistoryProvider::from_snapshots(snapshots).unwrap();
+        let h = entity_history(&mut provider, EntityKind::Pid, 42, 105, 200);
+        assert_eq!(h.points.len(), 2);
+        assert_eq!(h.points[0].timestamp, 110);
+        assert!(!h.sampled);
+        assert!(
+            entity_history(&mut provider, EntityKind::Pid, 1, 0, 200)
+                .points
+                .is_empty()
+        );
+    }
+}
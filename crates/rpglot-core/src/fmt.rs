@@ -3,9 +3,196 @@
 //! All pure formatting functions (no ratatui styles, no UI layout) live here.
 //! Functions that differ between compact table columns and verbose detail popups
 //! are parameterized via [`FmtStyle`].
+//!
+//! The decimal separator, byte unit base and display time zone come from the
+//! process-wide [`FmtConfig`], set once at startup with [`set_config`]. The API
+//! schema exposes it so the web frontend renders numbers the same way.
 
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use chrono::{Local, NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Number and time formatting options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+pub struct FmtConfig {
+    /// Decimal comma instead of a decimal point (`"1,5G"`).
+    pub decimal_comma: bool,
+    /// SI byte units (1 kB = 1000 B) instead of binary ones (1 KiB = 1024 B).
+    pub si_units: bool,
+    /// Time zone of formatted timestamps and of day boundaries.
+    #[schema(value_type = String, example = "Europe/Moscow")]
+    pub timezone: DisplayTz,
+}
+
+impl FmtConfig {
+    fn byte_base(&self) -> f64 {
+        if self.si_units { 1000.0 } else { 1024.0 }
+    }
+}
+
+static CONFIG: RwLock<FmtConfig> = RwLock::new(FmtConfig {
+    decimal_comma: false,
+    si_units: false,
+    timezone: DisplayTz::Local,
+});
+
+#[cfg(test)]
+thread_local! {
+    /// Per-test override, so tests do not race on the global config.
+    static TEST_CONFIG: std::cell::Cell<Option<FmtConfig>> = const { std::cell::Cell::new(None) };
+}
+
+/// Replaces the formatting options of the process.
+pub fn set_config(config: FmtConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+/// Current formatting options.
+pub fn config() -> FmtConfig {
+    #[cfg(test)]
+    if let Some(config) = TEST_CONFIG.with(|c| c.get()) {
+        return config;
+    }
+    *CONFIG.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Applies the configured decimal separator to formatted numbers.
+fn localize(s: String, config: &FmtConfig) -> String {
+    if config.decimal_comma {
+        s.replace('.', ",")
+    } else {
+        s
+    }
+}
+
+/// Scales `bytes` down to the largest unit it reaches, up to `max_power`
+/// (1 = K, 2 = M, 3 = G, 4 = T). Returns the scaled value and its power.
+fn scale_bytes(bytes: f64, max_power: usize, config: &FmtConfig) -> (f64, usize) {
+    let base = config.byte_base();
+    let mut value = bytes;
+    let mut power = 0;
+    while value.abs() >= base && power < max_power {
+        value /= base;
+        power += 1;
+    }
+    (value, power)
+}
+
+/// Unit suffix of a byte power: `"G"` (compact), `" GiB"` or `" GB"` (detail).
+fn byte_suffix(power: usize, style: FmtStyle, config: &FmtConfig) -> &'static str {
+    const COMPACT: [&str; 5] = ["B", "K", "M", "G", "T"];
+    const BINARY: [&str; 5] = [" B", " KiB", " MiB", " GiB", " TiB"];
+    const SI: [&str; 5] = [" B", " kB", " MB", " GB", " TB"];
+    match style {
+        FmtStyle::Compact => COMPACT[power],
+        FmtStyle::Detail if config.si_units => SI[power],
+        FmtStyle::Detail => BINARY[power],
+    }
+}
+
+/// Time zone timestamps are shown in and days are bucketed by.
+///
+/// Written as `local` or an IANA name (`UTC`, `Europe/Moscow`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisplayTz {
+    /// Time zone of the process (`TZ`, `/etc/localtime`).
+    #[default]
+    Local,
+    Zone(Tz),
+}
+
+impl DisplayTz {
+    pub const UTC: DisplayTz = DisplayTz::Zone(Tz::UTC);
+
+    /// `local` or the IANA name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisplayTz::Local => "local",
+            DisplayTz::Zone(tz) => tz.name(),
+        }
+    }
+
+    /// Formats a timestamp with a chrono `pattern`. `None` for out-of-range
+    /// timestamps.
+    pub fn format(&self, ts: i64, pattern: &str) -> Option<String> {
+        match self {
+            DisplayTz::Local => Some(
+                Local
+                    .timestamp_opt(ts, 0)
+                    .single()?
+                    .format(pattern)
+                    .to_string(),
+            ),
+            DisplayTz::Zone(tz) => Some(
+                tz.timestamp_opt(ts, 0)
+                    .single()?
+                    .format(pattern)
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// Calendar day of a timestamp.
+    pub fn date(&self, ts: i64) -> Option<NaiveDate> {
+        match self {
+            DisplayTz::Local => Some(Local.timestamp_opt(ts, 0).single()?.date_naive()),
+            DisplayTz::Zone(tz) => Some(tz.timestamp_opt(ts, 0).single()?.date_naive()),
+        }
+    }
+
+    /// Calendar day of a timestamp as `YYYY-MM-DD`.
+    pub fn date_key(&self, ts: i64) -> Option<String> {
+        self.date(ts).map(|d| d.format("%Y-%m-%d").to_string())
+    }
+}
+
+impl FromStr for DisplayTz {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(DisplayTz::Local);
+        }
+        if s.eq_ignore_ascii_case("utc") {
+            return Ok(DisplayTz::UTC);
+        }
+        s.parse::<Tz>()
+            .map(DisplayTz::Zone)
+            .map_err(|_| format!("unknown time zone '{s}' (expected 'local' or an IANA name)"))
+    }
+}
+
+impl fmt::Display for DisplayTz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for DisplayTz {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for DisplayTz {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Formats a timestamp with a chrono `pattern` in the configured time zone.
+/// `None` for out-of-range timestamps.
+pub fn format_timestamp(ts: i64, pattern: &str) -> Option<String> {
+    config().timezone.format(ts, pattern)
+}
+
 /// Controls compact (table columns) vs verbose (detail popups) output.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum FmtStyle {
@@ -19,24 +206,43 @@ pub enum FmtStyle {
 // Style-parameterized functions
 // ---------------------------------------------------------------------------
 
+/// Parses a human-readable size string (e.g., "1G", "500M", "1024K") into bytes,
+/// as taken by `--max-size` options.
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err("empty size string".to_string());
+    }
+
+    let (num_str, multiplier) = if let Some(num) = s.strip_suffix('G') {
+        (num, 1024 * 1024 * 1024)
+    } else if let Some(num) = s.strip_suffix('M') {
+        (num, 1024 * 1024)
+    } else if let Some(num) = s.strip_suffix('K') {
+        (num, 1024)
+    } else {
+        (s, 1)
+    };
+
+    num_str
+        .trim()
+        .parse::<u64>()
+        .map(|n| n * multiplier)
+        .map_err(|e| format!("invalid size '{}': {}", s, e))
+}
+
 /// Format byte count as human-readable size.
 ///
 /// Compact: `"1.5G"`, `"100.3M"`, `"50.0K"`, `"512B"`
 /// Detail:  `"1.5 GiB"`, `"100.3 MiB"`, `"50.0 KiB"`, `"512 B"`
 pub fn format_bytes(bytes: u64, style: FmtStyle) -> String {
-    let (g, m, k, b) = match style {
-        FmtStyle::Compact => ("G", "M", "K", "B"),
-        FmtStyle::Detail => (" GiB", " MiB", " KiB", " B"),
-    };
-    let f = bytes as f64;
-    if bytes >= 1024 * 1024 * 1024 {
-        format!("{:.1}{}", f / (1024.0 * 1024.0 * 1024.0), g)
-    } else if bytes >= 1024 * 1024 {
-        format!("{:.1}{}", f / (1024.0 * 1024.0), m)
-    } else if bytes >= 1024 {
-        format!("{:.1}{}", f / 1024.0, k)
-    } else {
-        format!("{}{}", bytes, b)
+    let config = config();
+    match scale_bytes(bytes as f64, 3, &config) {
+        (_, 0) => format!("{}{}", bytes, byte_suffix(0, style, &config)),
+        (value, power) => localize(
+            format!("{:.1}{}", value, byte_suffix(power, style, &config)),
+            &config,
+        ),
     }
 }
 
@@ -85,18 +291,13 @@ pub fn format_bytes_rate(rate: f64, style: FmtStyle) -> String {
     if rate < 1.0 {
         return "0".to_string();
     }
-    let (g, m, k, b) = match style {
-        FmtStyle::Compact => ("G/s", "M/s", "K/s", "B/s"),
-        FmtStyle::Detail => (" GiB/s", " MiB/s", " KiB/s", " B/s"),
-    };
-    if rate >= 1024.0 * 1024.0 * 1024.0 {
-        format!("{:.1}{}", rate / (1024.0 * 1024.0 * 1024.0), g)
-    } else if rate >= 1024.0 * 1024.0 {
-        format!("{:.1}{}", rate / (1024.0 * 1024.0), m)
-    } else if rate >= 1024.0 {
-        format!("{:.1}{}", rate / 1024.0, k)
-    } else {
-        format!("{:.0}{}", rate, b)
+    let config = config();
+    match scale_bytes(rate, 3, &config) {
+        (value, 0) => format!("{:.0}{}/s", value, byte_suffix(0, style, &config)),
+        (value, power) => localize(
+            format!("{:.1}{}/s", value, byte_suffix(power, style, &config)),
+            &config,
+        ),
     }
 }
 
@@ -108,6 +309,10 @@ pub fn format_rate(rate: f64, style: FmtStyle) -> String {
     if rate < 0.01 {
         return "0".to_string();
     }
+    localize(format_rate_raw(rate, style), &config())
+}
+
+fn format_rate_raw(rate: f64, style: FmtStyle) -> String {
     if rate >= 1_000_000.0 {
         format!("{:.1}M/s", rate / 1_000_000.0)
     } else if rate >= 1_000.0 {
@@ -131,7 +336,7 @@ pub fn format_rate(rate: f64, style: FmtStyle) -> String {
 /// Compact: has minute case (`>= 60_000` -> `"1.5m"`)
 /// Detail:  no minute case, has sub-ms case (`< 1.0` -> `"0.5ms"`)
 pub fn format_ms(ms: f64, style: FmtStyle) -> String {
-    match style {
+    let s = match style {
         FmtStyle::Compact => {
             if ms >= 60_000.0 {
                 format!("{:.1}m", ms / 60_000.0)
@@ -150,7 +355,8 @@ pub fn format_ms(ms: f64, style: FmtStyle) -> String {
                 format!("{:.1}ms", ms)
             }
         }
-    }
+    };
+    localize(s, &config())
 }
 
 // ---------------------------------------------------------------------------
@@ -212,14 +418,18 @@ pub fn format_age(epoch_secs: i64) -> String {
 pub fn format_bytes_signed(bytes: i64) -> String {
     let sign = if bytes >= 0 { "+" } else { "-" };
     let abs = bytes.unsigned_abs();
-    if abs >= 1024 * 1024 * 1024 {
-        format!("{}{:.1} GiB", sign, abs as f64 / (1024.0 * 1024.0 * 1024.0))
-    } else if abs >= 1024 * 1024 {
-        format!("{}{:.1} MiB", sign, abs as f64 / (1024.0 * 1024.0))
-    } else if abs >= 1024 {
-        format!("{}{:.1} KiB", sign, abs as f64 / 1024.0)
-    } else {
-        format!("{}{} B", sign, abs)
+    let config = config();
+    match scale_bytes(abs as f64, 3, &config) {
+        (_, 0) => format!("{}{} B", sign, abs),
+        (value, power) => localize(
+            format!(
+                "{}{:.1}{}",
+                sign,
+                value,
+                byte_suffix(power, FmtStyle::Detail, &config)
+            ),
+            &config,
+        ),
     }
 }
 
@@ -228,12 +438,24 @@ pub fn format_kb(kb: u64) -> String {
     if kb == 0 {
         return "0".to_string();
     }
-    if kb >= 1024 * 1024 {
-        format!("{:.1} GiB", kb as f64 / (1024.0 * 1024.0))
-    } else if kb >= 1024 {
-        format!("{:.1} MiB", kb as f64 / 1024.0)
-    } else {
-        format!("{} KiB", kb)
+    format_kib(kb, "")
+}
+
+/// Detail-style size of `kib` KiB with a sign prefix. Whole KiB stay exact
+/// with binary units.
+fn format_kib(kib: u64, sign: &str) -> String {
+    let config = config();
+    match scale_bytes(kib as f64 * 1024.0, 3, &config) {
+        (_, 1) if !config.si_units => format!("{}{} KiB", sign, kib),
+        (value, power) => localize(
+            format!(
+                "{}{:.1}{}",
+                sign,
+                value,
+                byte_suffix(power, FmtStyle::Detail, &config)
+            ),
+            &config,
+        ),
     }
 }
 
@@ -249,7 +471,7 @@ pub fn format_ticks(ticks: u64) -> String {
     } else if secs > 60 {
         format!("{}m{}s", secs / 60, secs % 60)
     } else if secs > 0 {
-        format!("{}.{}s", secs, ms / 100)
+        localize(format!("{}.{}s", secs, ms / 100), &config())
     } else {
         format!("{}ms", ms)
     }
@@ -262,7 +484,7 @@ pub fn format_ns(ns: u64) -> String {
     }
     let ms = ns / 1_000_000;
     if ms > 1000 {
-        format!("{:.1}s", ms as f64 / 1000.0)
+        localize(format!("{:.1}s", ms as f64 / 1000.0), &config())
     } else if ms > 0 {
         format!("{}ms", ms)
     } else {
@@ -275,15 +497,8 @@ pub fn format_delta_kb(delta: i64) -> String {
     if delta == 0 {
         return "0".to_string();
     }
-    let abs = delta.unsigned_abs();
     let sign = if delta < 0 { "-" } else { "+" };
-    if abs >= 1024 * 1024 {
-        format!("{}{:.1} GiB", sign, abs as f64 / (1024.0 * 1024.0))
-    } else if abs >= 1024 {
-        format!("{}{:.1} MiB", sign, abs as f64 / 1024.0)
-    } else {
-        format!("{}{} KiB", sign, abs)
-    }
+    format_kib(delta.unsigned_abs(), sign)
 }
 
 // ---------------------------------------------------------------------------
@@ -292,6 +507,10 @@ pub fn format_delta_kb(delta: i64) -> String {
 
 /// Format i64 with K/M/G suffix for table columns.
 pub fn format_i64(v: i64, width: usize) -> String {
+    localize(format_i64_raw(v, width), &config())
+}
+
+fn format_i64_raw(v: i64, width: usize) -> String {
     if v >= 1_000_000_000 {
         format!("{:>width$.1}G", v as f64 / 1e9, width = width - 1)
     } else if v >= 1_000_000 {
@@ -308,24 +527,27 @@ pub fn format_size(bytes: i64) -> String {
     if bytes <= 0 {
         return format!("{:>9}", "-");
     }
-    let b = bytes as f64;
-    if b >= 1_099_511_627_776.0 {
-        format!("{:>8.1}T", b / 1_099_511_627_776.0)
-    } else if b >= 1_073_741_824.0 {
-        format!("{:>8.1}G", b / 1_073_741_824.0)
-    } else if b >= 1_048_576.0 {
-        format!("{:>8.1}M", b / 1_048_576.0)
-    } else if b >= 1024.0 {
-        format!("{:>8.1}K", b / 1024.0)
-    } else {
-        format!("{:>8}B", bytes)
+    let config = config();
+    match scale_bytes(bytes as f64, 4, &config) {
+        (_, 0) => format!("{:>8}B", bytes),
+        (value, power) => localize(
+            format!(
+                "{:>8.1}{}",
+                value,
+                byte_suffix(power, FmtStyle::Compact, &config)
+            ),
+            &config,
+        ),
     }
 }
 
 /// Format `Option<f64>` with width and precision, `"--"` for `None`.
 pub fn format_opt_f64(v: Option<f64>, width: usize, precision: usize) -> String {
     match v {
-        Some(v) => format!("{:>width$.prec$}", v, width = width, prec = precision),
+        Some(v) => localize(
+            format!("{:>width$.prec$}", v, width = width, prec = precision),
+            &config(),
+        ),
         None => format!("{:>width$}", "--", width = width),
     }
 }
@@ -336,17 +558,21 @@ pub fn format_blks_rate(blks_per_sec: Option<f64>, width: usize) -> String {
     match blks_per_sec {
         None => format!("{:>width$}", "--", width = width),
         Some(v) => {
-            let bytes = v * 8192.0;
-            if bytes >= 1_073_741_824.0 {
-                format!("{:>width$.1}G", bytes / 1_073_741_824.0, width = width - 1)
-            } else if bytes >= 1_048_576.0 {
-                format!("{:>width$.1}M", bytes / 1_048_576.0, width = width - 1)
-            } else if bytes >= 1024.0 {
-                format!("{:>width$.1}K", bytes / 1024.0, width = width - 1)
-            } else if bytes >= 1.0 {
-                format!("{:>width$.0}B", bytes, width = width - 1)
-            } else {
-                format!("{:>width$}", "0", width = width)
+            let config = config();
+            match scale_bytes(v * 8192.0, 3, &config) {
+                (bytes, 0) if bytes >= 1.0 => {
+                    format!("{:>width$.0}B", bytes, width = width - 1)
+                }
+                (_, 0) => format!("{:>width$}", "0", width = width),
+                (value, power) => localize(
+                    format!(
+                        "{:>width$.1}{}",
+                        value,
+                        byte_suffix(power, FmtStyle::Compact, &config),
+                        width = width - 1
+                    ),
+                    &config,
+                ),
             }
         }
     }
@@ -355,19 +581,20 @@ pub fn format_blks_rate(blks_per_sec: Option<f64>, width: usize) -> String {
 /// Format bytes count (from blocks * 8192) to human-readable.
 #[allow(dead_code)]
 pub(crate) fn format_blks_as_bytes(bytes: f64) -> String {
-    let abs = bytes.abs();
-    if abs >= 1_099_511_627_776.0 {
-        format!("{:.1} TB", bytes / 1_099_511_627_776.0)
-    } else if abs >= 1_073_741_824.0 {
-        format!("{:.1} GB", bytes / 1_073_741_824.0)
-    } else if abs >= 1_048_576.0 {
-        format!("{:.1} MB", bytes / 1_048_576.0)
-    } else if abs >= 1024.0 {
-        format!("{:.1} KB", bytes / 1024.0)
-    } else if abs >= 1.0 {
-        format!("{:.0} B", bytes)
-    } else {
-        "0".to_string()
+    if bytes.abs() < 1.0 {
+        return "0".to_string();
+    }
+    let config = config();
+    match scale_bytes(bytes, 4, &config) {
+        (value, 0) => format!("{:.0} B", value),
+        (value, power) => localize(
+            format!(
+                "{:.1}{}",
+                value,
+                byte_suffix(power, FmtStyle::Detail, &config)
+            ),
+            &config,
+        ),
     }
 }
 
@@ -409,3 +636,93 @@ pub fn normalize_for_display(s: &str) -> String {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_config<T>(config: FmtConfig, f: impl FnOnce() -> T) -> T {
+        TEST_CONFIG.with(|c| c.set(Some(config)));
+        let result = f();
+        TEST_CONFIG.with(|c| c.set(None));
+        result
+    }
+
+    #[test]
+    fn default_config_uses_binary_units_and_point() {
+        let config = FmtConfig::default();
+        with_config(config, || {
+            assert_eq!(format_bytes(1536, FmtStyle::Compact), "1.5K");
+            assert_eq!(format_bytes(1536, FmtStyle::Detail), "1.5 KiB");
+            assert_eq!(format_bytes(512, FmtStyle::Detail), "512 B");
+            assert_eq!(
+                format_bytes_rate(3.0 * 1024.0 * 1024.0, FmtStyle::Compact),
+                "3.0M/s"
+            );
+            assert_eq!(format_kb(512), "512 KiB");
+            assert_eq!(format_delta_kb(-2048), "-2.0 MiB");
+            assert_eq!(format_size(2 * 1024_i64.pow(4)), "     2.0T");
+            assert_eq!(format_blks_rate(Some(0.0), 6), "     0");
+            assert_eq!(format_ms(1500.0, FmtStyle::Compact), "1.5s");
+        });
+    }
+
+    #[test]
+    fn si_units_and_decimal_comma() {
+        let config = FmtConfig {
+            decimal_comma: true,
+            si_units: true,
+            timezone: DisplayTz::Local,
+        };
+        with_config(config, || {
+            assert_eq!(format_bytes(1500, FmtStyle::Compact), "1,5K");
+            assert_eq!(format_bytes(1500, FmtStyle::Detail), "1,5 kB");
+            assert_eq!(format_bytes(1000, FmtStyle::Detail), "1,0 kB");
+            assert_eq!(format_kb(512), "524,3 kB");
+            assert_eq!(format_bytes_signed(-2_500_000), "-2,5 MB");
+            assert_eq!(format_rate(2_500.0, FmtStyle::Compact), "2,5K/s");
+            assert_eq!(format_opt_f64(Some(99.5), 6, 1), "  99,5");
+            assert_eq!(format_i64(12_345, 7), "  12,3K");
+        });
+    }
+
+    #[test]
+    fn timestamps_follow_time_zone() {
+        let utc = FmtConfig {
+            timezone: DisplayTz::UTC,
+            ..Default::default()
+        };
+        with_config(utc, || {
+            assert_eq!(
+                format_timestamp(0, "%Y-%m-%d %H:%M:%S").as_deref(),
+                Some("1970-01-01 00:00:00")
+            );
+            assert_eq!(format_timestamp(i64::MAX, "%H"), None);
+        });
+        let moscow = FmtConfig {
+            timezone: "Europe/Moscow".parse().unwrap(),
+            ..Default::default()
+        };
+        with_config(moscow, || {
+            assert_eq!(
+                format_timestamp(0, "%Y-%m-%d %H:%M %Z").as_deref(),
+                Some("1970-01-01 03:00 MSK")
+            );
+        });
+    }
+
+    #[test]
+    fn display_tz_parses_names_and_buckets_days() {
+        assert_eq!("local".parse::<DisplayTz>(), Ok(DisplayTz::Local));
+        assert_eq!("utc".parse::<DisplayTz>(), Ok(DisplayTz::UTC));
+        let tokyo: DisplayTz = "Asia/Tokyo".parse().unwrap();
+        assert_eq!(tokyo.name(), "Asia/Tokyo");
+        assert!("Mars/Olympus".parse::<DisplayTz>().is_err());
+
+        // 2026-02-07 20:00 UTC is already Feb 8 in Tokyo
+        let ts = 1_770_494_400;
+        assert_eq!(DisplayTz::UTC.date_key(ts).as_deref(), Some("2026-02-07"));
+        assert_eq!(tokyo.date_key(ts).as_deref(), Some("2026-02-08"));
+        assert_eq!(serde_json::to_string(&tokyo).unwrap(), "\"Asia/Tokyo\"");
+    }
+}
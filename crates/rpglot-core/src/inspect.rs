@@ -0,0 +1,132 @@
+//! Chunk inspection from memory: the web UI can open a `.zst` chunk dropped
+//! into the page without a server (air-gapped hosts), through a wasm32
+//! build of this crate without default features.
+//!
+//! Snapshots are converted like the web API does, so the frontend renders
+//! them with its usual views.
+
+use std::io;
+
+use crate::api::convert::{ConvertContext, convert};
+use crate::api::snapshot::ApiSnapshot;
+use crate::rates::{self, PgiRateState, PgpRateState, PgsRateState, PgtRateState};
+use crate::storage::crypto::EncryptionKey;
+use crate::storage::{ChunkReader, StringInterner};
+
+/// A chunk file held in memory.
+pub struct ChunkInspector {
+    reader: ChunkReader,
+    interner: StringInterner,
+    timestamps: Vec<i64>,
+}
+
+impl ChunkInspector {
+    /// Parses the chunk in `bytes`; encrypted chunks need `key`.
+    pub fn new(bytes: Vec<u8>, key: Option<&EncryptionKey>) -> io::Result<Self> {
+        let reader = ChunkReader::from_bytes(bytes, key)?;
+        let interner = reader.read_interner()?;
+        let timestamps = reader.timestamps();
+        Ok(Self {
+            reader,
+            interner,
+            timestamps,
+        })
+    }
+
+    /// Number of snapshots.
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// Snapshot timestamps, ascending.
+    pub fn timestamps(&self) -> &[i64] {
+        &self.timestamps
+    }
+
+    /// Snapshot `idx` as the web API serves it: strings resolved, rates and
+    /// deltas against the previous snapshot of the chunk.
+    pub fn snapshot(&self, idx: usize) -> io::Result<ApiSnapshot> {
+        let snapshot = self.reader.read_snapshot(idx)?;
+        let prev = match idx.checked_sub(1) {
+            Some(prev) => Some(self.reader.read_snapshot(prev)?),
+            None => None,
+        };
+
+        let mut pgs = PgsRateState::default();
+        let mut pgp = PgpRateState::default();
+        let mut pgt = PgtRateState::default();
+        let mut pgi = PgiRateState::default();
+        for sample in prev.iter().chain([&snapshot]) {
+            rates::update_pgs_rates(&mut pgs, sample);
+            rates::update_pgp_rates(&mut pgp, sample);
+            rates::update_pgt_rates(&mut pgt, sample);
+            rates::update_pgi_rates(&mut pgi, sample);
+        }
+
+        let mut api = convert(&ConvertContext {
+            snapshot: &snapshot,
+            prev_snapshot: prev.as_ref(),
+            interner: Some(&self.interner),
+            pgs_rates: &pgs.rates,
+            pgp_rates: &pgp.rates,
+            pgt_rates: &pgt.rates,
+            pgi_rates: &pgi.rates,
+        });
+        api.prev_timestamp = idx.checked_sub(1).map(|i| self.timestamps[i]);
+        api.next_timestamp = self.timestamps.get(idx + 1).copied();
+        Ok(api)
+    }
+
+    /// [`snapshot`](Self::snapshot) serialized as JSON, the form handed to
+    /// JavaScript.
+    pub fn snapshot_json(&self, idx: usize) -> io::Result<String> {
+        serde_json::to_string(&self.snapshot(idx)?).map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Snapshot;
+    use crate::storage::chunk::write_chunk;
+    use crate::storage::model::{DataBlock, ProcessInfo};
+    use tempfile::tempdir;
+
+    #[test]
+    fn converts_snapshots_from_memory() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("chunk.zst");
+        let mut interner = StringInterner::new();
+        let name_hash = interner.intern("postgres");
+        let snapshots: Vec<Snapshot> = (0..3)
+            .map(|i| Snapshot {
+                timestamp: 1000 + i * 10,
+                blocks: vec![DataBlock::Processes(vec![ProcessInfo {
+                    pid: 7,
+                    name_hash,
+                    ..Default::default()
+                }])],
+            })
+            .collect();
+        write_chunk(&path, &snapshots, &interner).unwrap();
+
+        let inspector = ChunkInspector::new(std::fs::read(&path).unwrap(), None).unwrap();
+        assert_eq!(inspector.timestamps(), [1000, 1010, 1020]);
+        let snapshot = inspector.snapshot(1).unwrap();
+        assert_eq!(snapshot.prc[0].name, "postgres");
+        assert_eq!(snapshot.prev_timestamp, Some(1000));
+        assert_eq!(snapshot.next_timestamp, Some(1020));
+        assert!(
+            inspector
+                .snapshot_json(2)
+                .unwrap()
+                .contains("\"timestamp\":1020")
+        );
+        assert!(inspector.snapshot(3).is_err());
+        assert!(ChunkInspector::new(vec![0; 10], None).is_err());
+    }
+}
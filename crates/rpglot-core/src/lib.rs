@@ -1,18 +1,29 @@
 //! rpglot-core — shared library for rpglot ecosystem.
 //!
 //! Provides:
-//! - `collector` — system and PostgreSQL metrics collection
 //! - `storage` — data persistence, models, string interner
+//! - `api` — JSON-serializable API types (snapshot, schema), conversion
+//! - `analysis` — health score, anomaly detection rules and advisors
 //! - `util` — helper utilities
 //! - `fmt` — shared formatting helpers (bytes, duration, rate, etc.)
 //! - `models` — shared data models (view modes, rates, rows)
 //! - `table` — generic table state (sorting, selection tracking)
+//! - `thresholds` — warning/critical levels of numeric columns (TUI and API)
+//! - `entity` — per-PID / per-queryid metric trajectories
+//!
+//! With `collector` feature (enabled by `provider`):
+//! - `collector` — system and PostgreSQL metrics collection
 //!
 //! With `provider` feature (default):
 //! - `provider` — snapshot source abstraction (live, history)
 //!
 //! With `api` feature:
-//! - `api` — JSON-serializable API types (snapshot, schema)
+//! - history-backed API helpers (`api::agent`, `api::calendar`,
+//!   `api::workload`), `analysis::Analyzer`
+//! - `client` — read-only access to recorded data for other Rust tools
+//!
+//! With `wasm32` feature (without default features):
+//! - `inspect` — chunk files read from memory, for the browser
 //!
 //! With `tui` feature (default):
 //! - `tui` — TUI rendering (ratatui/crossterm), state, input, widgets
@@ -21,22 +32,28 @@
 /// Version string including short git SHA, e.g. `"0.1.9-abc1234"`.
 pub const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "-", env!("GIT_SHA"));
 
+pub mod analysis;
+pub mod api;
+#[cfg(feature = "collector")]
 pub mod collector;
+pub mod entity;
 pub mod fmt;
 pub mod models;
 pub mod rates;
 pub mod storage;
 pub mod table;
+pub mod thresholds;
 pub mod util;
+pub mod workload;
 
 #[cfg(feature = "provider")]
 pub mod provider;
 
 #[cfg(feature = "api")]
-pub mod analysis;
+pub mod client;
 
-#[cfg(feature = "api")]
-pub mod api;
+#[cfg(feature = "wasm32")]
+pub mod inspect;
 
 #[cfg(feature = "tui")]
 pub mod tui;
@@ -33,6 +33,14 @@ pub(crate) fn format_delay(ns: u64) -> String {
     }
 }
 
+/// Format optional latency (microseconds); `-` when not measured.
+pub(crate) fn format_latency_us(us: Option<u64>) -> String {
+    match us {
+        Some(us) => format_delay(us * 1000),
+        None => "-".to_string(),
+    }
+}
+
 /// Format memory size (KB) as human-readable.
 pub(crate) fn format_memory(kb: u64) -> String {
     if kb >= 1024 * 1024 {
@@ -2,8 +2,10 @@
 
 mod formatting;
 mod process_row;
+mod process_tree;
 
 pub use process_row::*;
+pub use process_tree::*;
 
 /// Process table view mode (similar to atop).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -15,7 +17,7 @@ pub enum ProcessViewMode {
     Command,
     /// Memory view: PID TID MINFLT MAJFLT VSTEXT VSLIBS VDATA VSTACK LOCKSZ VSIZE RSIZE PSIZE VGROW RGROW SWAPSZ RUID EUID MEM CMD
     Memory,
-    /// Disk I/O view: PID RDDSK WRDSK WCANCL DSK CMD
+    /// Disk I/O view: PID RDDSK WRDSK WCANCL DSK LAT LAT99 CMD
     Disk,
 }
 
@@ -64,14 +66,18 @@ pub enum PgActivityViewMode {
     /// Stats view: PID, DB, USER, STATE, QDUR, MEAN, MAX, CALL/s, HIT%, QUERY
     /// Shows pg_stat_statements metrics for the current query (linked by query_id).
     Stats,
+    /// Transactions view: PID, DB, USER, STATE, XID, XMIN_AGE, XDUR, QDUR, BTYPE, QUERY
+    /// Only sessions with an open transaction or a held snapshot.
+    Transactions,
 }
 
 impl PgActivityViewMode {
     /// Default sort column index for this view mode.
     pub fn default_sort_column(&self) -> usize {
         match self {
-            Self::Generic => 7, // QDUR
-            Self::Stats => 4,   // QDUR
+            Self::Generic => 7,      // QDUR
+            Self::Stats => 4,        // QDUR
+            Self::Transactions => 6, // XDUR
         }
     }
 
@@ -80,6 +86,7 @@ impl PgActivityViewMode {
         match self {
             Self::Generic => 12, // PID CPU% RSS DB USER STATE WAIT QDUR XDUR BDUR BTYPE QUERY
             Self::Stats => 10,   // PID DB USER STATE QDUR MEAN MAX CALL/s HIT% QUERY
+            Self::Transactions => 10, // PID DB USER STATE XID XMIN_AGE XDUR QDUR BTYPE QUERY
         }
     }
 }
@@ -197,6 +204,76 @@ impl PgStorePlansViewMode {
     }
 }
 
+/// PostgreSQL log (PGE) view modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PgErrorsViewMode {
+    /// Errors view: SEVERITY, COUNT, PATTERN, SAMPLE
+    #[default]
+    Errors,
+    /// Events view: TIME, TYPE, ELAPSED, COUNT, TABLE, MESSAGE
+    /// (checkpoints, autovacuum/autoanalyze, slow queries)
+    Events,
+}
+
+impl PgErrorsViewMode {
+    /// Default sort column index for this view mode.
+    pub fn default_sort_column(&self) -> usize {
+        match self {
+            Self::Errors => 0, // SEVERITY
+            Self::Events => 0, // TIME
+        }
+    }
+
+    /// Number of columns in this view mode.
+    pub fn column_count(&self) -> usize {
+        match self {
+            Self::Errors => 4, // SEVERITY COUNT PATTERN SAMPLE
+            Self::Events => 6, // TIME TYPE ELAPSED COUNT TABLE MESSAGE
+        }
+    }
+}
+
+/// PostgreSQL workload (PGR) view modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PgRolesViewMode {
+    /// Grouped by role: USER, SESS, ACTIVE, IDLE_TX, WAIT, ACT_TIME, STMTS, CALLS/s, TIME/s, ROWS/s,
+    /// RMT_TIME
+    #[default]
+    Roles,
+    /// Grouped by application_name, same columns with APPLICATION first
+    Applications,
+    /// Grouped by client host: CLIENT, ESTAB, SYN_RECV, TIME_WAIT, OTHER, SESS, ACTIVE, IDLE_TX
+    Clients,
+}
+
+impl PgRolesViewMode {
+    /// Default sort column index for this view mode.
+    pub fn default_sort_column(&self) -> usize {
+        match self {
+            Self::Roles | Self::Applications => 8, // TIME/s
+            Self::Clients => 1,                    // ESTAB
+        }
+    }
+
+    /// Number of columns in this view mode.
+    pub fn column_count(&self) -> usize {
+        match self {
+            Self::Roles | Self::Applications => 11,
+            Self::Clients => 8,
+        }
+    }
+}
+
+/// PostgreSQL settings (PGC) view modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PgSettingsViewMode {
+    /// Every setting: CATEGORY, NAME, SETTING, UNIT, SOURCE, DEFAULT
+    #[default]
+    All,
+    /// Only settings set by the configuration, same columns
+    NonDefault,
+}
+
 /// Rate metrics for a single `pg_store_plans` entry.
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct PgStorePlansRates {
@@ -1,7 +1,7 @@
 //! ProcessRow struct, view-mode specific columns, and TableRow impl.
 
-use super::ProcessViewMode;
 use super::formatting::*;
+use super::{ProcessSubtree, ProcessViewMode};
 use crate::table::{ColumnType, SortKey, TableRow};
 
 /// Process row for the process table.
@@ -11,6 +11,7 @@ pub struct ProcessRow {
     // Identity
     pub pid: u32,
     pub tid: u32,
+    pub ppid: u32,
     pub name: String,
     pub cmdline: String,
 
@@ -54,12 +55,19 @@ pub struct ProcessRow {
     // PostgreSQL integration
     pub query: Option<String>, // Query from pg_stat_activity if PID matches
     pub backend_type: Option<String>, // Backend type from pg_stat_activity if PID matches
+    pub pg_label: Option<String>, // PostgreSQL role shown in tree mode (backend type, user@db)
 
     // Disk I/O metrics (rates in bytes per second)
-    pub rddsk: i64,       // Read bytes/s (delta from rsz)
-    pub wrdsk: i64,       // Write bytes/s (delta from wsz)
-    pub wcancl: i64,      // Cancelled write bytes/s (delta from cwsz)
-    pub dsk_percent: f64, // % of total system disk I/O
+    pub rddsk: i64,                  // Read bytes/s (delta from rsz)
+    pub wrdsk: i64,                  // Write bytes/s (delta from wsz)
+    pub wcancl: i64,                 // Cancelled write bytes/s (delta from cwsz)
+    pub dsk_percent: f64,            // % of total system disk I/O
+    pub blk_lat_avg_us: Option<u64>, // Mean block I/O latency (eBPF)
+    pub blk_lat_p99_us: Option<u64>, // p99 block I/O latency, histogram bucket bound (eBPF)
+
+    // Process tree (PRC tree mode)
+    pub subtree: ProcessSubtree,     // Own usage plus all descendants
+    pub tree_prefix: Option<String>, // Branch drawn before CMD; Some = shown in tree mode
 }
 
 impl ProcessRow {
@@ -101,12 +109,14 @@ impl ProcessRow {
 
     /// Returns headers for Disk view mode.
     pub fn headers_disk() -> Vec<&'static str> {
-        vec!["PID", "RDDSK", "WRDSK", "WCANCL", "DSK", "CMD"]
+        vec![
+            "PID", "RDDSK", "WRDSK", "WCANCL", "DSK", "LAT", "LAT99", "CMD",
+        ]
     }
 
     /// Returns column widths for Disk view mode.
     pub fn widths_disk() -> Vec<u16> {
-        vec![8, 10, 10, 10, 6, 20]
+        vec![8, 10, 10, 10, 6, 7, 7, 20]
     }
 
     /// Returns cells for Generic view mode.
@@ -125,7 +135,7 @@ impl ProcessRow {
             self.num_threads.to_string(),
             self.state.clone(),
             self.cpunr.to_string(),
-            format!("{:.1}%", self.cpu_percent),
+            format!("{:.1}%", self.shown_cpu_percent()),
             self.format_cmd_with_query(),
         ]
     }
@@ -136,8 +146,8 @@ impl ProcessRow {
             self.pid.to_string(),
             self.tid.to_string(),
             self.state.clone(),
-            format!("{:.1}%", self.cpu_percent),
-            format_memory(self.rsize),
+            format!("{:.1}%", self.shown_cpu_percent()),
+            format_memory(self.shown_rsize()),
             self.format_cmdline_with_query(),
         ]
     }
@@ -155,14 +165,14 @@ impl ProcessRow {
             format_memory(self.vstack),
             format_memory(self.vlock),
             format_memory(self.vsize),
-            format_memory(self.rsize),
+            format_memory(self.shown_rsize()),
             format_memory(self.psize),
             format_size_delta(self.vgrow),
             format_size_delta(self.rgrow),
             format_memory(self.vswap),
             self.ruser.clone(),
             self.euser.clone(),
-            format!("{:.1}%", self.mem_percent),
+            format!("{:.1}%", self.shown_mem_percent()),
             self.format_cmd_with_query(),
         ]
     }
@@ -171,10 +181,12 @@ impl ProcessRow {
     pub fn cells_disk(&self) -> Vec<String> {
         vec![
             self.pid.to_string(),
-            format_bytes_rate(self.rddsk),
-            format_bytes_rate(self.wrdsk),
+            format_bytes_rate(self.shown_rddsk()),
+            format_bytes_rate(self.shown_wrdsk()),
             format_bytes_rate(self.wcancl),
             format!("{:.1}%", self.dsk_percent),
+            format_latency_us(self.blk_lat_avg_us),
+            format_latency_us(self.blk_lat_p99_us),
             self.format_cmd_with_query(),
         ]
     }
@@ -184,6 +196,9 @@ impl ProcessRow {
     /// "name [backend_type]" if only backend_type is present,
     /// otherwise just "name".
     fn format_cmd_with_query(&self) -> String {
+        if let Some(prefix) = &self.tree_prefix {
+            return self.format_tree_cmd(prefix, &self.name);
+        }
         match &self.query {
             Some(q) if !q.is_empty() => format!("{} [{}]", self.name, q),
             _ => match &self.backend_type {
@@ -193,6 +208,60 @@ impl ProcessRow {
         }
     }
 
+    /// Tree mode CMD: branch prefix, command and the PostgreSQL role label.
+    fn format_tree_cmd(&self, prefix: &str, base: &str) -> String {
+        match &self.pg_label {
+            Some(label) => format!("{prefix}{base} [{label}]"),
+            None => format!("{prefix}{base}"),
+        }
+    }
+
+    /// Whether the row is shown in tree mode, with usage of its descendants.
+    fn in_tree(&self) -> bool {
+        self.tree_prefix.is_some()
+    }
+
+    /// CPU% shown in the table: the subtree total in tree mode.
+    pub fn shown_cpu_percent(&self) -> f64 {
+        if self.in_tree() {
+            self.subtree.cpu_percent
+        } else {
+            self.cpu_percent
+        }
+    }
+
+    fn shown_mem_percent(&self) -> f64 {
+        if self.in_tree() {
+            self.subtree.mem_percent
+        } else {
+            self.mem_percent
+        }
+    }
+
+    fn shown_rsize(&self) -> u64 {
+        if self.in_tree() {
+            self.subtree.rsize
+        } else {
+            self.rsize
+        }
+    }
+
+    fn shown_rddsk(&self) -> i64 {
+        if self.in_tree() {
+            self.subtree.rddsk
+        } else {
+            self.rddsk
+        }
+    }
+
+    fn shown_wrdsk(&self) -> i64 {
+        if self.in_tree() {
+            self.subtree.wrdsk
+        } else {
+            self.wrdsk
+        }
+    }
+
     /// Formats COMMAND-LINE column with optional query or backend_type from pg_stat_activity.
     /// Returns "cmdline [query]" if query is present and non-empty,
     /// "cmdline [backend_type]" if only backend_type is present,
@@ -203,6 +272,9 @@ impl ProcessRow {
         } else {
             &self.cmdline
         };
+        if let Some(prefix) = &self.tree_prefix {
+            return self.format_tree_cmd(prefix, base);
+        }
         match &self.query {
             Some(q) if !q.is_empty() => format!("{} [{}]", base, q),
             _ => match &self.backend_type {
@@ -231,7 +303,7 @@ impl ProcessRow {
                     10 => SortKey::Integer(self.num_threads as i64),
                     11 => SortKey::String(self.state.clone()),
                     12 => SortKey::Integer(self.cpunr as i64),
-                    13 => SortKey::Float(self.cpu_percent),
+                    13 => SortKey::Float(self.shown_cpu_percent()),
                     14 => SortKey::String(self.name.clone()),
                     _ => SortKey::Integer(0),
                 }
@@ -242,8 +314,8 @@ impl ProcessRow {
                     0 => SortKey::Integer(self.pid as i64),
                     1 => SortKey::Integer(self.tid as i64),
                     2 => SortKey::String(self.state.clone()),
-                    3 => SortKey::Float(self.cpu_percent),
-                    4 => SortKey::Integer(self.rsize as i64), // MEM = rsize
+                    3 => SortKey::Float(self.shown_cpu_percent()),
+                    4 => SortKey::Integer(self.shown_rsize() as i64), // MEM = rsize
                     5 => SortKey::String(if self.cmdline.is_empty() {
                         self.name.clone()
                     } else {
@@ -265,27 +337,29 @@ impl ProcessRow {
                     7 => SortKey::Integer(self.vstack as i64),
                     8 => SortKey::Integer(self.vlock as i64),
                     9 => SortKey::Integer(self.vsize as i64),
-                    10 => SortKey::Integer(self.rsize as i64),
+                    10 => SortKey::Integer(self.shown_rsize() as i64),
                     11 => SortKey::Integer(self.psize as i64),
                     12 => SortKey::Integer(self.vgrow),
                     13 => SortKey::Integer(self.rgrow),
                     14 => SortKey::Integer(self.vswap as i64),
                     15 => SortKey::Integer(self.ruid as i64),
                     16 => SortKey::Integer(self.euid as i64),
-                    17 => SortKey::Float(self.mem_percent),
+                    17 => SortKey::Float(self.shown_mem_percent()),
                     18 => SortKey::String(self.name.clone()),
                     _ => SortKey::Integer(0),
                 }
             }
             ProcessViewMode::Disk => {
-                // PID RDDSK WRDSK WCANCL DSK CMD
+                // PID RDDSK WRDSK WCANCL DSK LAT LAT99 CMD
                 match column {
                     0 => SortKey::Integer(self.pid as i64),
-                    1 => SortKey::Integer(self.rddsk),
-                    2 => SortKey::Integer(self.wrdsk),
+                    1 => SortKey::Integer(self.shown_rddsk()),
+                    2 => SortKey::Integer(self.shown_wrdsk()),
                     3 => SortKey::Integer(self.wcancl),
                     4 => SortKey::Float(self.dsk_percent),
-                    5 => SortKey::String(self.name.clone()),
+                    5 => SortKey::Integer(self.blk_lat_avg_us.map_or(-1, |v| v as i64)),
+                    6 => SortKey::Integer(self.blk_lat_p99_us.map_or(-1, |v| v as i64)),
+                    7 => SortKey::String(self.name.clone()),
                     _ => SortKey::Integer(0),
                 }
             }
@@ -342,8 +416,8 @@ impl ProcessRow {
 
     /// Returns minimum column widths for Disk view mode.
     pub fn min_widths_disk() -> Vec<u16> {
-        // PID RDDSK WRDSK WCANCL DSK CMD
-        vec![5, 6, 6, 6, 4, 8]
+        // PID RDDSK WRDSK WCANCL DSK LAT LAT99 CMD
+        vec![5, 6, 6, 6, 4, 4, 5, 8]
     }
 
     /// Returns minimum column widths for the specified view mode.
@@ -423,13 +497,15 @@ impl ProcessRow {
     /// Returns column types for Disk view mode.
     pub fn column_types_disk() -> Vec<ColumnType> {
         use ColumnType::*;
-        // PID RDDSK WRDSK WCANCL DSK CMD
+        // PID RDDSK WRDSK WCANCL DSK LAT LAT99 CMD
         vec![
             Fixed,      // PID
             Flexible,   // RDDSK
             Flexible,   // WRDSK
             Flexible,   // WCANCL
             Flexible,   // DSK
+            Flexible,   // LAT
+            Flexible,   // LAT99
             Expandable, // CMD
         ]
     }
@@ -479,7 +555,7 @@ impl TableRow for ProcessRow {
             10 => SortKey::Integer(self.num_threads as i64),
             11 => SortKey::String(self.state.clone()),
             12 => SortKey::Integer(self.cpunr as i64),
-            13 => SortKey::Float(self.cpu_percent),
+            13 => SortKey::Float(self.shown_cpu_percent()),
             14 => SortKey::String(self.name.clone()),
             _ => SortKey::Integer(0),
         }
@@ -585,4 +661,28 @@ mod filter_tests {
             "Should find systemd with substring filter 'sys'"
         );
     }
+
+    #[test]
+    fn test_pinned_rows_stay_on_top() {
+        let mut table: TableState<ProcessRow> = TableState::new();
+        let rows = |pids: &[u32]| -> Vec<ProcessRow> {
+            pids.iter()
+                .map(|&pid| ProcessRow {
+                    pid,
+                    ..Default::default()
+                })
+                .collect()
+        };
+        table.update(rows(&[1, 2, 3]));
+        // Default sort: PID descending
+        table.selected = 2;
+        assert_eq!(table.toggle_pin_selected(), Some(true));
+        table.update(rows(&[3, 1, 2, 4]));
+        let pids: Vec<u32> = table.filtered_items().iter().map(|r| r.pid).collect();
+        assert_eq!(pids, vec![1, 4, 3, 2]);
+
+        table.toggle_sort_direction();
+        assert_eq!(table.filtered_items()[0].pid, 1);
+        assert_eq!(table.filtered_items()[1].pid, 2);
+    }
 }
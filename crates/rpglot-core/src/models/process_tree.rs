@@ -0,0 +1,205 @@
+//! Process tree for the PRC tab: parent/child arrangement and subtree totals.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::ProcessRow;
+
+/// Resource usage of a process together with all its descendants.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessSubtree {
+    pub cpu_percent: f64,
+    pub mem_percent: f64,
+    /// Resident memory (KB).
+    pub rsize: u64,
+    pub rddsk: i64,
+    pub wrdsk: i64,
+}
+
+/// Root indices and children (by parent PID) of `rows`. A row is a root when
+/// its parent is not among the rows.
+fn process_children(rows: &[ProcessRow]) -> (Vec<usize>, HashMap<u32, Vec<usize>>) {
+    let pids: HashMap<u32, usize> = rows.iter().enumerate().map(|(i, r)| (r.pid, i)).collect();
+    let mut roots = Vec::new();
+    let mut children: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        if row.ppid != row.pid && pids.contains_key(&row.ppid) {
+            children.entry(row.ppid).or_default().push(i);
+        } else {
+            roots.push(i);
+        }
+    }
+    (roots, children)
+}
+
+/// Fills [`ProcessRow::subtree`] with each process's usage plus that of all
+/// its descendants.
+pub fn aggregate_process_subtrees(rows: &mut [ProcessRow]) {
+    let (roots, children) = process_children(rows);
+
+    // Pre-order walk, then fold children into parents bottom-up
+    let mut order = Vec::with_capacity(rows.len());
+    let mut stack = roots;
+    while let Some(i) = stack.pop() {
+        order.push(i);
+        if let Some(kids) = children.get(&rows[i].pid) {
+            stack.extend(kids);
+        }
+    }
+    let pids: HashMap<u32, usize> = rows.iter().enumerate().map(|(i, r)| (r.pid, i)).collect();
+
+    for row in rows.iter_mut() {
+        row.subtree = ProcessSubtree {
+            cpu_percent: row.cpu_percent,
+            mem_percent: row.mem_percent,
+            rsize: row.rsize,
+            rddsk: row.rddsk,
+            wrdsk: row.wrdsk,
+        };
+    }
+    for &i in order.iter().rev() {
+        let Some(&parent) = pids.get(&rows[i].ppid) else {
+            continue;
+        };
+        if parent == i {
+            continue;
+        }
+        let child = rows[i].subtree.clone();
+        let p = &mut rows[parent].subtree;
+        p.cpu_percent += child.cpu_percent;
+        p.mem_percent += child.mem_percent;
+        p.rsize += child.rsize;
+        p.rddsk += child.rddsk;
+        p.wrdsk += child.wrdsk;
+    }
+}
+
+/// Reorders `rows` depth-first by parent PID, siblings ordered by `cmp`, and
+/// sets [`ProcessRow::tree_prefix`] to the indentation drawn before the
+/// command. `cmp` sees rows already marked as tree rows, so it compares
+/// subtree totals.
+pub fn arrange_process_tree(
+    rows: &mut Vec<ProcessRow>,
+    cmp: impl Fn(&ProcessRow, &ProcessRow) -> Ordering,
+) {
+    for row in rows.iter_mut() {
+        row.tree_prefix = Some(String::new());
+    }
+    let (mut roots, mut children) = process_children(rows);
+    roots.sort_by(|&a, &b| cmp(&rows[a], &rows[b]));
+    for kids in children.values_mut() {
+        kids.sort_by(|&a, &b| cmp(&rows[a], &rows[b]));
+    }
+
+    // (row, prefix of the row, indentation inherited by its children)
+    let mut order: Vec<(usize, String)> = Vec::with_capacity(rows.len());
+    let mut stack: Vec<(usize, String, String)> = roots
+        .iter()
+        .rev()
+        .map(|&i| (i, String::new(), String::new()))
+        .collect();
+    let mut visited = vec![false; rows.len()];
+    while let Some((i, prefix, indent)) = stack.pop() {
+        visited[i] = true;
+        order.push((i, prefix));
+        if let Some(kids) = children.get(&rows[i].pid) {
+            for (n, &kid) in kids.iter().enumerate().rev() {
+                let last = n + 1 == kids.len();
+                let (branch, next) = if last {
+                    ("└─ ", "   ")
+                } else {
+                    ("├─ ", "│  ")
+                };
+                stack.push((kid, format!("{indent}{branch}"), format!("{indent}{next}")));
+            }
+        }
+    }
+    // Parent cycles have no root: append them flat
+    order.extend(
+        (0..rows.len())
+            .filter(|&i| !visited[i])
+            .map(|i| (i, String::new())),
+    );
+
+    let mut slots: Vec<Option<ProcessRow>> = rows.drain(..).map(Some).collect();
+    for (i, prefix) in order {
+        if let Some(mut row) = slots[i].take() {
+            row.tree_prefix = Some(prefix);
+            rows.push(row);
+        }
+    }
+}
+
+/// Leaves tree mode: rows show their own usage again.
+pub fn clear_process_tree(rows: &mut [ProcessRow]) {
+    for row in rows {
+        row.tree_prefix = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pid: u32, ppid: u32, cpu: f64) -> ProcessRow {
+        ProcessRow {
+            pid,
+            ppid,
+            name: format!("p{pid}"),
+            cpu_percent: cpu,
+            rsize: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn subtrees_include_descendants() {
+        let mut rows = vec![
+            row(1, 0, 1.0),
+            row(10, 1, 2.0),
+            row(11, 10, 4.0),
+            row(20, 1, 8.0),
+        ];
+        aggregate_process_subtrees(&mut rows);
+        assert_eq!(rows[0].subtree.cpu_percent, 15.0);
+        assert_eq!(rows[0].subtree.rsize, 400);
+        assert_eq!(rows[1].subtree.cpu_percent, 6.0);
+        assert_eq!(rows[3].subtree.cpu_percent, 8.0);
+    }
+
+    #[test]
+    fn tree_orders_children_under_parents() {
+        let mut rows = vec![
+            row(11, 10, 4.0),
+            row(20, 1, 8.0),
+            row(1, 0, 1.0),
+            row(10, 1, 2.0),
+            // Parent not in the snapshot: a root
+            row(30, 999, 0.0),
+        ];
+        aggregate_process_subtrees(&mut rows);
+        arrange_process_tree(&mut rows, |a, b| {
+            b.shown_cpu_percent().total_cmp(&a.shown_cpu_percent())
+        });
+        let tree: Vec<(u32, &str)> = rows
+            .iter()
+            .map(|r| (r.pid, r.tree_prefix.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            tree,
+            vec![(1, ""), (20, "├─ "), (10, "└─ "), (11, "   └─ "), (30, ""),]
+        );
+        assert_eq!(rows[0].shown_cpu_percent(), 15.0);
+
+        clear_process_tree(&mut rows);
+        assert_eq!(rows[0].shown_cpu_percent(), 1.0);
+    }
+
+    #[test]
+    fn parent_cycle_is_kept() {
+        let mut rows = vec![row(1, 2, 0.0), row(2, 1, 0.0)];
+        aggregate_process_subtrees(&mut rows);
+        arrange_process_tree(&mut rows, |a, b| a.pid.cmp(&b.pid));
+        assert_eq!(rows.len(), 2);
+    }
+}
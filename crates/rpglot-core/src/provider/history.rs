@@ -3,21 +3,24 @@
 //! Only chunk metadata (timestamps, counts, file paths) is kept in RAM permanently.
 //! Snapshot data is loaded on demand from disk via ChunkReader (per-snapshot zstd frames).
 //! Per-chunk StringInterners are cached (one at a time, ~50 KB) since multiple
-//! snapshots from the same chunk share the same interner.
+//! snapshots from the same chunk share the same interner. Decoded snapshots
+//! are kept in a size-bounded LRU cache with hit/miss counters
+//! ([`HistoryProvider::set_snapshot_cache_bytes`]).
 
 use std::any::Any;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use tracing::warn;
 
 use crate::storage::chunk::{ChunkReader, read_chunk_metadata};
+use crate::storage::gaps::{TimelineGap, expected_interval, gaps_with_interval};
 use crate::storage::heatmap::{self, HeatmapEntry};
-use crate::storage::model::Snapshot;
+use crate::storage::model::{DataBlock, Snapshot};
 use crate::storage::{StorageManager, StringInterner};
 
-use super::{ProviderError, SnapshotProvider};
+use super::{ProviderError, SnapshotProvider, select_instance};
 
 // ============================================================
 // CachedInterner — lightweight cache for one chunk's interner
@@ -31,6 +34,164 @@ struct CachedInterner {
     interner: StringInterner,
 }
 
+// ============================================================
+// SnapshotCache — size-bounded LRU of decoded snapshots
+// ============================================================
+
+/// Default memory budget of the decoded snapshot cache.
+pub const DEFAULT_SNAPSHOT_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Decoded snapshots keyed by their location in chunks or the WAL.
+///
+/// Entries are accounted by their serialized size (uncompressed chunk frame
+/// or WAL frame); the least recently used ones are evicted once `max_bytes`
+/// is exceeded. `max_bytes == 0` disables the cache. WAL entries also keep
+/// their interner, which is stored per snapshot there.
+struct SnapshotCache {
+    max_bytes: usize,
+    used_bytes: usize,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+    entries: HashMap<SnapshotLocation, CachedSnapshot>,
+}
+
+struct CachedSnapshot {
+    snapshot: Snapshot,
+    interner: Option<StringInterner>,
+    bytes: usize,
+    last_used: u64,
+}
+
+/// Counters of the decoded snapshot cache
+/// ([`HistoryProvider::snapshot_cache_stats`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SnapshotCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub used_bytes: usize,
+    pub max_bytes: usize,
+}
+
+impl SnapshotCacheStats {
+    /// Share of lookups served from the cache (0.0 without lookups).
+    pub fn hit_ratio(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+impl SnapshotCache {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            used_bytes: 0,
+            tick: 0,
+            hits: 0,
+            misses: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Looks up `key`, counting a hit or a miss (nothing while disabled).
+    fn get(&mut self, key: SnapshotLocation) -> Option<&CachedSnapshot> {
+        if self.max_bytes == 0 {
+            return None;
+        }
+        self.tick += 1;
+        match self.entries.get_mut(&key) {
+            Some(entry) => {
+                self.hits += 1;
+                entry.last_used = self.tick;
+                Some(entry)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(
+        &mut self,
+        key: SnapshotLocation,
+        snapshot: Snapshot,
+        interner: Option<StringInterner>,
+        bytes: usize,
+    ) {
+        if bytes > self.max_bytes {
+            return;
+        }
+        self.tick += 1;
+        if let Some(old) = self.entries.insert(
+            key,
+            CachedSnapshot {
+                snapshot,
+                interner,
+                bytes,
+                last_used: self.tick,
+            },
+        ) {
+            self.used_bytes -= old.bytes;
+        }
+        self.used_bytes += bytes;
+        self.shrink_to(self.max_bytes);
+    }
+
+    /// Evicts least recently used entries until at most `max_bytes` are used.
+    fn shrink_to(&mut self, max_bytes: usize) {
+        while self.used_bytes > max_bytes {
+            let Some(&key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k)
+            else {
+                break;
+            };
+            if let Some(e) = self.entries.remove(&key) {
+                self.used_bytes -= e.bytes;
+            }
+        }
+    }
+
+    fn set_max_bytes(&mut self, max_bytes: usize) {
+        self.max_bytes = max_bytes;
+        self.shrink_to(max_bytes);
+    }
+
+    /// Drops WAL entries: WAL indices shift when the WAL is flushed.
+    fn remove_wal(&mut self) {
+        self.entries.retain(|key, e| {
+            let keep = !matches!(key, SnapshotLocation::Wal(_));
+            if !keep {
+                self.used_bytes -= e.bytes;
+            }
+            keep
+        });
+    }
+
+    fn clear(&mut self) {
+        self.entries = HashMap::new();
+        self.used_bytes = 0;
+    }
+
+    fn stats(&self) -> SnapshotCacheStats {
+        SnapshotCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+            used_bytes: self.used_bytes,
+            max_bytes: self.max_bytes,
+        }
+    }
+}
+
 // ============================================================
 // ChunkMeta — metadata about one chunk file on disk
 // ============================================================
@@ -94,6 +255,15 @@ impl WalIndex {
         }
     }
 
+    /// Frame size of a file-backed entry, used for cache accounting.
+    /// In-memory snapshots are not cached.
+    fn entry_len(&self, idx: usize) -> Option<usize> {
+        match &self.source {
+            WalSource::File { entries, .. } => entries.get(idx).map(|e| e.byte_length as usize),
+            WalSource::InMemory { .. } => None,
+        }
+    }
+
     fn load_snapshot_with_interner(&self, idx: usize) -> Option<(Snapshot, StringInterner)> {
         match &self.source {
             WalSource::File { path, entries } => {
@@ -112,15 +282,31 @@ impl WalIndex {
     }
 }
 
+/// Turns wall-clock timestamps in storage order into navigation timestamps.
+///
+/// When the host clock is stepped back, snapshots written afterwards carry
+/// timestamps earlier than their predecessors. Sorting would break the
+/// mapping between `timestamps[i]` and global position `i`, so such a
+/// timestamp is moved to one second after its predecessor instead: the list
+/// stays sorted for binary search and every snapshot stays addressable.
+fn make_monotonic(timestamps: &mut [i64]) {
+    for i in 1..timestamps.len() {
+        if timestamps[i] < timestamps[i - 1] {
+            timestamps[i] = timestamps[i - 1] + 1;
+        }
+    }
+}
+
 // ============================================================
-// HistoryProvider — lazy loading, no cache
+// HistoryProvider — lazy loading, bounded snapshot cache
 // ============================================================
 
 /// Provider for historical data from storage files.
 ///
 /// Only metadata (timestamps, chunk paths) is kept in RAM permanently.
 /// Snapshot data is loaded on demand via ChunkReader (per-snapshot zstd frames).
-/// Per-chunk interners (~50 KB) are cached one at a time.
+/// Per-chunk interners (~50 KB) are cached one at a time; decoded snapshots
+/// go through a size-bounded LRU cache.
 pub struct HistoryProvider {
     /// Path to the storage directory (kept for lazy init and re-initialization).
     storage_path: PathBuf,
@@ -143,6 +329,10 @@ pub struct HistoryProvider {
     /// Cached interner for one chunk (~50 KB) — avoids re-reading when
     /// multiple snapshots are requested from the same chunk.
     interner_cache: Option<CachedInterner>,
+    /// Decoded chunk snapshots (disabled unless configured).
+    snapshot_cache: SnapshotCache,
+    /// Selected PostgreSQL instance (`None` = primary).
+    instance: Option<String>,
 
     last_error: Option<ProviderError>,
 }
@@ -172,6 +362,8 @@ impl HistoryProvider {
             current_buffer: None,
             current_interner: None,
             interner_cache: None,
+            snapshot_cache: SnapshotCache::new(DEFAULT_SNAPSHOT_CACHE_BYTES),
+            instance: None,
             last_error: None,
         })
     }
@@ -219,7 +411,8 @@ impl HistoryProvider {
         }
 
         let total = snapshots.len();
-        let timestamps: Vec<i64> = snapshots.iter().map(|s| s.timestamp).collect();
+        let mut timestamps: Vec<i64> = snapshots.iter().map(|s| s.timestamp).collect();
+        make_monotonic(&mut timestamps);
         let first_snapshot = snapshots[0].clone();
 
         let wal = WalIndex {
@@ -238,6 +431,8 @@ impl HistoryProvider {
             current_buffer: Some(first_snapshot),
             current_interner: Some(StringInterner::new()),
             interner_cache: None,
+            snapshot_cache: SnapshotCache::new(0),
+            instance: None,
             last_error: None,
         })
     }
@@ -364,11 +559,11 @@ impl HistoryProvider {
 
         let total = global_offset;
 
-        // Sort timestamps — do NOT dedup, timestamps[i] must correspond to global position i.
+        // Do NOT sort or dedup, timestamps[i] must correspond to global position i.
         // Duplicate timestamps are possible (daemon writes snapshots every ~10s, two may
-        // land on the same second). Dedup would break the 1:1 mapping between
-        // timestamps index and global position.
-        all_timestamps.sort();
+        // land on the same second); timestamps going back after a clock step are
+        // moved forward instead.
+        make_monotonic(&mut all_timestamps);
 
         Ok((chunks, wal, total, all_timestamps))
     }
@@ -413,9 +608,12 @@ impl HistoryProvider {
     /// Load a single snapshot + interner from a chunk file.
     /// Uses ChunkReader for O(1) random access (per-snapshot zstd frame).
     /// The interner is cached per-chunk (~50 KB) to avoid re-reading when
-    /// multiple snapshots from the same chunk are requested.
+    /// multiple snapshots from the same chunk are requested; decoded snapshots
+    /// go through the LRU `snapshot_cache`. The file is not opened when both
+    /// are cached.
     fn load_from_chunk(
         interner_cache: &mut Option<CachedInterner>,
+        snapshot_cache: &mut SnapshotCache,
         chunks: &[ChunkMeta],
         chunk_idx: usize,
         offset_in_chunk: usize,
@@ -429,6 +627,19 @@ impl HistoryProvider {
             )));
         }
 
+        let key = SnapshotLocation::Chunk {
+            chunk_idx,
+            offset_in_chunk,
+        };
+        let cached = snapshot_cache.get(key).map(|e| e.snapshot.clone());
+        let cached_interner = interner_cache
+            .as_ref()
+            .filter(|ci| ci.chunk_idx == chunk_idx)
+            .map(|ci| ci.interner.clone());
+        if let (Some(snapshot), Some(interner)) = (&cached, &cached_interner) {
+            return Ok((snapshot.clone(), interner.clone()));
+        }
+
         let reader = ChunkReader::open(&meta.path).map_err(|e| {
             ProviderError::Io(format!(
                 "Failed to open chunk {}: {}",
@@ -437,61 +648,81 @@ impl HistoryProvider {
             ))
         })?;
 
-        let snapshot = reader.read_snapshot(offset_in_chunk).map_err(|e| {
-            ProviderError::Io(format!(
-                "Failed to read snapshot at offset {} in {}: {}",
-                offset_in_chunk,
-                meta.path.display(),
-                e
-            ))
-        })?;
+        let snapshot = match cached {
+            Some(snapshot) => snapshot,
+            None => {
+                let snapshot = reader.read_snapshot(offset_in_chunk).map_err(|e| {
+                    ProviderError::Io(format!(
+                        "Failed to read snapshot at offset {} in {}: {}",
+                        offset_in_chunk,
+                        meta.path.display(),
+                        e
+                    ))
+                })?;
+                let bytes = reader.frame_len(offset_in_chunk).unwrap_or(0);
+                snapshot_cache.insert(key, snapshot.clone(), None, bytes);
+                snapshot
+            }
+        };
 
         // Use cached interner if available for this chunk, otherwise read from file
-        let interner = if let Some(ci) = interner_cache.as_ref()
-            && ci.chunk_idx == chunk_idx
-        {
-            ci.interner.clone()
-        } else {
-            let i = reader.read_interner().map_err(|e| {
-                ProviderError::Io(format!(
-                    "Failed to read interner from {}: {}",
-                    meta.path.display(),
-                    e
-                ))
-            })?;
-            *interner_cache = Some(CachedInterner {
-                chunk_idx,
-                interner: i.clone(),
-            });
-            i
+        let interner = match cached_interner {
+            Some(interner) => interner,
+            None => {
+                let i = reader.read_interner().map_err(|e| {
+                    ProviderError::Io(format!(
+                        "Failed to read interner from {}: {}",
+                        meta.path.display(),
+                        e
+                    ))
+                })?;
+                *interner_cache = Some(CachedInterner {
+                    chunk_idx,
+                    interner: i.clone(),
+                });
+                i
+            }
         };
 
         Ok((snapshot, interner))
     }
 
+    /// Load a snapshot + interner from the WAL, through the snapshot cache.
+    fn load_from_wal(&mut self, wal_idx: usize) -> Option<(Snapshot, StringInterner)> {
+        let key = SnapshotLocation::Wal(wal_idx);
+        if let Some(entry) = self.snapshot_cache.get(key) {
+            return Some((
+                entry.snapshot.clone(),
+                entry.interner.clone().unwrap_or_default(),
+            ));
+        }
+        let wal = self.wal.as_ref()?;
+        let (snapshot, interner) = wal.load_snapshot_with_interner(wal_idx)?;
+        if let Some(bytes) = wal.entry_len(wal_idx) {
+            self.snapshot_cache
+                .insert(key, snapshot.clone(), Some(interner.clone()), bytes);
+        }
+        Some((snapshot, interner))
+    }
+
     /// Load snapshot at global position into the internal buffer,
     /// along with the per-chunk/WAL interner for string resolution.
     fn load_into_buffer(&mut self, position: usize) {
         let (snapshot, interner) = match self.resolve_position(position) {
-            Some(SnapshotLocation::Wal(wal_idx)) => {
-                match self
-                    .wal
-                    .as_ref()
-                    .and_then(|w| w.load_snapshot_with_interner(wal_idx))
-                {
-                    Some((s, i)) => (Some(s), Some(i)),
-                    None => {
-                        warn!(position, wal_idx, "failed to load snapshot from WAL");
-                        (None, None)
-                    }
+            Some(SnapshotLocation::Wal(wal_idx)) => match self.load_from_wal(wal_idx) {
+                Some((s, i)) => (Some(s), Some(i)),
+                None => {
+                    warn!(position, wal_idx, "failed to load snapshot from WAL");
+                    (None, None)
                 }
-            }
+            },
             Some(SnapshotLocation::Chunk {
                 chunk_idx,
                 offset_in_chunk,
             }) => {
                 match Self::load_from_chunk(
                     &mut self.interner_cache,
+                    &mut self.snapshot_cache,
                     &self.chunks,
                     chunk_idx,
                     offset_in_chunk,
@@ -510,15 +741,15 @@ impl HistoryProvider {
             }
         };
 
-        self.current_buffer = snapshot;
+        self.current_buffer = snapshot.map(|s| select_instance(self.instance.as_deref(), s));
         self.current_interner = interner;
     }
 
     /// Get a cloned snapshot at the given position (for external use).
     fn snapshot_cloned(&mut self, position: usize) -> Option<Snapshot> {
-        match self.resolve_position(position) {
+        let snapshot = match self.resolve_position(position) {
             Some(SnapshotLocation::Wal(wal_idx)) => {
-                let result = self.wal.as_ref().and_then(|w| w.load_snapshot(wal_idx));
+                let result = self.load_from_wal(wal_idx).map(|(s, _)| s);
                 if result.is_none() {
                     warn!(
                         position,
@@ -533,6 +764,7 @@ impl HistoryProvider {
             }) => {
                 match Self::load_from_chunk(
                     &mut self.interner_cache,
+                    &mut self.snapshot_cache,
                     &self.chunks,
                     chunk_idx,
                     offset_in_chunk,
@@ -546,7 +778,8 @@ impl HistoryProvider {
                 }
             }
             None => None,
-        }
+        };
+        snapshot.map(|s| select_instance(self.instance.as_deref(), s))
     }
 
     // ========== Public API ==========
@@ -557,6 +790,19 @@ impl HistoryProvider {
         self.current_buffer = None;
         self.current_interner = None;
         self.interner_cache = None;
+        self.snapshot_cache.clear();
+    }
+
+    /// Sets the memory budget of the decoded snapshot LRU cache
+    /// (serialized snapshot bytes, 0 disables it; default
+    /// [`DEFAULT_SNAPSHOT_CACHE_BYTES`]). Shrinking evicts at once.
+    pub fn set_snapshot_cache_bytes(&mut self, max_bytes: usize) {
+        self.snapshot_cache.set_max_bytes(max_bytes);
+    }
+
+    /// Hit/miss counters and size of the decoded snapshot cache.
+    pub fn snapshot_cache_stats(&self) -> SnapshotCacheStats {
+        self.snapshot_cache.stats()
     }
 
     /// Full eviction: drop ALL in-memory data (chunks, timestamps, buffers).
@@ -566,6 +812,7 @@ impl HistoryProvider {
         self.current_buffer = None;
         self.current_interner = None;
         self.interner_cache = None;
+        self.snapshot_cache.clear();
         self.chunks.clear();
         self.chunks.shrink_to_fit();
         self.wal = None;
@@ -576,6 +823,11 @@ impl HistoryProvider {
         self.initialized = false;
     }
 
+    /// Returns the storage directory this provider reads from.
+    pub fn storage_path(&self) -> &Path {
+        &self.storage_path
+    }
+
     /// Returns the total number of snapshots available.
     pub fn len(&self) -> usize {
         self.total_snapshots
@@ -617,29 +869,122 @@ impl HistoryProvider {
         self.snapshot_cloned(position)
     }
 
+    /// Returns only the block of kind `kind` (a [`DataBlock::KIND_NAMES`] entry)
+    /// of the snapshot at `position`, without changing the cursor.
+    ///
+    /// Chunk frames carry a block index, so only that block is deserialized.
+    /// Used by series queries that need one block per snapshot. WAL snapshots
+    /// and instance selection fall back to loading the whole snapshot.
+    pub fn snapshot_block_at(&mut self, position: usize, kind: &str) -> Option<DataBlock> {
+        DataBlock::kind_by_name(kind)?;
+        self.snapshot_blocks_at(position, &[kind])?
+            .blocks
+            .into_iter()
+            .next()
+    }
+
+    /// Returns the snapshot at `position` reduced to the blocks of the given
+    /// kinds (unknown names are ignored), without changing the cursor.
+    ///
+    /// The selective counterpart of [`HistoryProvider::snapshot_at`] for
+    /// range scans that follow one entity through many snapshots: the chunk
+    /// frame is decompressed once and only the requested blocks are
+    /// deserialized. WAL snapshots and instance selection fall back to
+    /// loading the whole snapshot.
+    pub fn snapshot_blocks_at(&mut self, position: usize, kinds: &[&str]) -> Option<Snapshot> {
+        let kinds: Vec<usize> = kinds
+            .iter()
+            .filter_map(|k| DataBlock::kind_by_name(k))
+            .collect();
+        let location = self.resolve_position(position)?;
+        if let SnapshotLocation::Chunk {
+            chunk_idx,
+            offset_in_chunk,
+        } = location
+            && self.instance.is_none()
+            && self.snapshot_cache.get(location).is_none()
+        {
+            let meta = &self.chunks[chunk_idx];
+            if !meta.available {
+                return None;
+            }
+            let blocks = ChunkReader::open(&meta.path)
+                .and_then(|reader| reader.read_blocks(offset_in_chunk, &kinds));
+            return match blocks {
+                Ok(blocks) => Some(Snapshot {
+                    timestamp: self.timestamps[position],
+                    blocks,
+                }),
+                Err(e) => {
+                    warn!(error = %e, position, "failed to load blocks");
+                    self.chunks[chunk_idx].available = false;
+                    None
+                }
+            };
+        }
+        let mut snapshot = self.snapshot_cloned(position)?;
+        snapshot.blocks.retain(|b| kinds.contains(&b.kind_index()));
+        Some(snapshot)
+    }
+
+    /// Returns the StringInterner of the snapshot at `position` without
+    /// loading the snapshot when it lives in a chunk (the chunk's interner is
+    /// cached). Pairs with [`HistoryProvider::snapshot_blocks_at`].
+    pub fn interner_at(&mut self, position: usize) -> Option<StringInterner> {
+        match self.resolve_position(position)? {
+            SnapshotLocation::Wal(wal_idx) => self.load_from_wal(wal_idx).map(|(_, i)| i),
+            SnapshotLocation::Chunk { chunk_idx, .. } => {
+                if let Some(cached) = self
+                    .interner_cache
+                    .as_ref()
+                    .filter(|ci| ci.chunk_idx == chunk_idx)
+                {
+                    return Some(cached.interner.clone());
+                }
+                let meta = &self.chunks[chunk_idx];
+                if !meta.available {
+                    return None;
+                }
+                match ChunkReader::open(&meta.path).and_then(|reader| reader.read_interner()) {
+                    Ok(interner) => {
+                        self.interner_cache = Some(CachedInterner {
+                            chunk_idx,
+                            interner: interner.clone(),
+                        });
+                        Some(interner)
+                    }
+                    Err(e) => {
+                        warn!(error = %e, position, "failed to load interner");
+                        self.chunks[chunk_idx].available = false;
+                        None
+                    }
+                }
+            }
+        }
+    }
+
     /// Returns an owned snapshot together with its StringInterner at the given position.
     /// Used by the analysis module which needs to resolve interned strings.
     pub fn snapshot_with_interner_at(
         &mut self,
         position: usize,
     ) -> Option<(Snapshot, StringInterner)> {
-        match self.resolve_position(position) {
-            Some(SnapshotLocation::Wal(wal_idx)) => self
-                .wal
-                .as_ref()
-                .and_then(|w| w.load_snapshot_with_interner(wal_idx)),
+        let loaded = match self.resolve_position(position) {
+            Some(SnapshotLocation::Wal(wal_idx)) => self.load_from_wal(wal_idx),
             Some(SnapshotLocation::Chunk {
                 chunk_idx,
                 offset_in_chunk,
             }) => Self::load_from_chunk(
                 &mut self.interner_cache,
+                &mut self.snapshot_cache,
                 &self.chunks,
                 chunk_idx,
                 offset_in_chunk,
             )
             .ok(),
             None => None,
-        }
+        };
+        loaded.map(|(s, i)| (select_instance(self.instance.as_deref(), s), i))
     }
 
     /// Refreshes snapshot metadata from disk, discovering new chunk files and WAL entries.
@@ -715,6 +1060,7 @@ impl HistoryProvider {
         }
 
         // Reload WAL metadata lazily
+        self.snapshot_cache.remove_wal();
         let wal_path = storage_path.join("wal.log");
         let wal_entries = StorageManager::scan_wal_metadata(&wal_path)
             .map_err(|e| ProviderError::Io(format!("Failed to scan WAL: {}", e)))?;
@@ -756,13 +1102,14 @@ impl HistoryProvider {
         }
         // WAL timestamps were already collected in new_timestamps above
         all_timestamps.extend(new_timestamps);
-        all_timestamps.sort();
+        make_monotonic(&mut all_timestamps);
         self.timestamps = all_timestamps;
 
         Ok(self.total_snapshots - old_total)
     }
 
-    /// Returns the sorted list of all snapshot timestamps.
+    /// Returns the sorted list of all snapshot timestamps (wall-clock times
+    /// moved forward where the clock was stepped back, see `make_monotonic`).
     /// Useful for building date indices without loading snapshot data.
     pub fn timestamps(&self) -> &[i64] {
         &self.timestamps
@@ -800,6 +1147,31 @@ impl HistoryProvider {
         (first, last)
     }
 
+    /// Gaps in the recorded history that overlap `[start_ts, end_ts]`.
+    ///
+    /// The expected interval is taken from the whole history so that a short
+    /// range around a gap is judged the same way as the full timeline.
+    pub fn gaps_in_range(&self, start_ts: i64, end_ts: i64) -> Vec<TimelineGap> {
+        let Some(expected) = expected_interval(&self.timestamps) else {
+            return Vec::new();
+        };
+        // Include the snapshots just outside the range: a gap may start
+        // before it or end after it.
+        let from = self
+            .timestamps
+            .partition_point(|&ts| ts < start_ts)
+            .saturating_sub(1);
+        let to =
+            (self.timestamps.partition_point(|&ts| ts <= end_ts) + 1).min(self.timestamps.len());
+        if from >= to {
+            return Vec::new();
+        }
+        gaps_with_interval(&self.timestamps[from..to], expected)
+            .into_iter()
+            .filter(|g| g.end > start_ts && g.start < end_ts)
+            .collect()
+    }
+
     // ========== Heatmap ==========
 
     /// Load lightweight heatmap data for a timestamp range (for timeline visualization).
@@ -859,6 +1231,7 @@ impl HistoryProvider {
 
         // 2. WAL entries overlapping the range
         if let Some(ref wal) = self.wal {
+            let mut builder = heatmap::HeatmapBuilder::new();
             match &wal.source {
                 WalSource::File { entries, .. } => {
                     for (wal_idx, entry_meta) in entries.iter().enumerate() {
@@ -866,58 +1239,14 @@ impl HistoryProvider {
                             && entry_meta.timestamp <= end_ts
                             && let Some(snap) = wal.load_snapshot(wal_idx)
                         {
-                            let active = heatmap::count_active_sessions(&snap);
-                            let (errors_critical, errors_warning, errors_info) =
-                                heatmap::count_error_entries_by_severity(&snap);
-                            let checkpoints = heatmap::count_checkpoint_events(&snap);
-                            let autovacuums = heatmap::count_autovacuum_events(&snap);
-                            let slow_queries = heatmap::count_slow_query_events(&snap);
-                            // CPU% for WAL: simplified to 0 (WAL entries will be flushed
-                            // to chunk with full CPU% soon, < 1 hour of data)
-                            result.push((
-                                entry_meta.timestamp,
-                                HeatmapEntry {
-                                    active_sessions: active,
-                                    cpu_pct_x10: 0,
-                                    cgroup_cpu_pct_x10: 0,
-                                    cgroup_mem_pct_x10: 0,
-                                    errors_critical,
-                                    errors_warning,
-                                    errors_info,
-                                    checkpoint_count: checkpoints,
-                                    autovacuum_count: autovacuums,
-                                    slow_query_count: slow_queries,
-                                    health_score: 100,
-                                },
-                            ));
+                            result.push((entry_meta.timestamp, builder.push(&snap)));
                         }
                     }
                 }
                 WalSource::InMemory { snapshots } => {
                     for snap in snapshots {
                         if snap.timestamp >= start_ts && snap.timestamp <= end_ts {
-                            let active = heatmap::count_active_sessions(snap);
-                            let (errors_critical, errors_warning, errors_info) =
-                                heatmap::count_error_entries_by_severity(snap);
-                            let checkpoints = heatmap::count_checkpoint_events(snap);
-                            let autovacuums = heatmap::count_autovacuum_events(snap);
-                            let slow_queries = heatmap::count_slow_query_events(snap);
-                            result.push((
-                                snap.timestamp,
-                                HeatmapEntry {
-                                    active_sessions: active,
-                                    cpu_pct_x10: 0,
-                                    cgroup_cpu_pct_x10: 0,
-                                    cgroup_mem_pct_x10: 0,
-                                    errors_critical,
-                                    errors_warning,
-                                    errors_info,
-                                    checkpoint_count: checkpoints,
-                                    autovacuum_count: autovacuums,
-                                    slow_query_count: slow_queries,
-                                    health_score: 100,
-                                },
-                            ));
+                            result.push((snap.timestamp, builder.push(snap)));
                         }
                     }
                 }
@@ -993,6 +1322,7 @@ impl HistoryProvider {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum SnapshotLocation {
     Chunk {
         chunk_idx: usize,
@@ -1051,6 +1381,20 @@ impl SnapshotProvider for HistoryProvider {
     fn interner(&self) -> Option<&StringInterner> {
         self.current_interner.as_ref()
     }
+
+    fn set_instance(&mut self, name: Option<String>) {
+        if self.instance == name {
+            return;
+        }
+        self.instance = name;
+        if self.current_buffer.is_some() {
+            self.load_into_buffer(self.cursor);
+        }
+    }
+
+    fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
 }
 
 #[cfg(test)]
@@ -1097,6 +1441,46 @@ mod tests {
         assert_eq!(provider.position(), 0);
     }
 
+    #[test]
+    fn test_clock_stepped_back_keeps_positions() {
+        let snapshots: Vec<Snapshot> = [100, 110, 50, 60, 120]
+            .into_iter()
+            .map(|timestamp| Snapshot {
+                timestamp,
+                blocks: Vec::new(),
+            })
+            .collect();
+        let mut provider = HistoryProvider::from_snapshots(snapshots).unwrap();
+
+        assert_eq!(provider.timestamps(), &[100, 110, 111, 112, 120]);
+        assert_eq!(provider.jump_to_timestamp_floor(111).unwrap().timestamp, 50);
+        assert_eq!(provider.position(), 2);
+        assert_eq!(provider.advance().unwrap().timestamp, 60);
+        assert_eq!(provider.next_timestamp(), Some(120));
+    }
+
+    #[test]
+    fn test_gaps_in_range() {
+        let snapshots: Vec<Snapshot> = [100, 110, 120, 130, 900, 910, 920]
+            .into_iter()
+            .map(|timestamp| Snapshot {
+                timestamp,
+                blocks: Vec::new(),
+            })
+            .collect();
+        let provider = HistoryProvider::from_snapshots(snapshots).unwrap();
+        let gap = TimelineGap {
+            start: 130,
+            end: 900,
+        };
+
+        assert_eq!(provider.gaps_in_range(0, 1000), vec![gap]);
+        // A range inside the gap still reports it
+        assert_eq!(provider.gaps_in_range(300, 400), vec![gap]);
+        assert!(provider.gaps_in_range(100, 130).is_empty());
+        assert!(provider.gaps_in_range(900, 920).is_empty());
+    }
+
     #[test]
     fn test_history_provider_empty_error() {
         let result = HistoryProvider::from_snapshots(vec![]);
@@ -1216,4 +1600,162 @@ mod tests {
         assert_eq!(provider.prev_timestamp(), Some(110));
         assert_eq!(provider.next_timestamp(), None);
     }
+
+    #[test]
+    fn test_history_provider_instance_selection() {
+        use crate::storage::model::{PgInstanceBlocks, PgStatActivityInfo};
+
+        let activity = |pid| {
+            DataBlock::PgStatActivity(vec![PgStatActivityInfo {
+                pid,
+                ..PgStatActivityInfo::default()
+            }])
+        };
+        let snapshots: Vec<Snapshot> = [100, 110]
+            .into_iter()
+            .map(|timestamp| Snapshot {
+                timestamp,
+                blocks: vec![
+                    activity(10),
+                    DataBlock::PgInstance(PgInstanceBlocks {
+                        name: "billing".to_string(),
+                        port: 5433,
+                        blocks: vec![activity(20)],
+                    }),
+                ],
+            })
+            .collect();
+        let mut provider = HistoryProvider::from_snapshots(snapshots).unwrap();
+        let pids = |s: &Snapshot| -> Vec<i32> {
+            s.blocks
+                .iter()
+                .filter_map(|b| match b {
+                    DataBlock::PgStatActivity(v) => Some(v[0].pid),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(pids(provider.current().unwrap()), vec![10]);
+        provider.set_instance(Some("billing".to_string()));
+        assert_eq!(provider.instance(), Some("billing"));
+        assert_eq!(pids(provider.current().unwrap()), vec![20]);
+        assert_eq!(pids(&provider.snapshot_at(1).unwrap()), vec![20]);
+        assert_eq!(
+            provider.snapshot_block_at(1, "pg_stat_activity"),
+            Some(activity(20))
+        );
+        provider.set_instance(None);
+        assert_eq!(pids(provider.current().unwrap()), vec![10]);
+        assert_eq!(
+            provider.snapshot_block_at(1, "pg_stat_activity"),
+            Some(activity(10))
+        );
+    }
+
+    #[test]
+    fn test_snapshot_cache_lru_eviction() {
+        let snapshots = create_test_snapshots();
+        let key = |offset_in_chunk| SnapshotLocation::Chunk {
+            chunk_idx: 0,
+            offset_in_chunk,
+        };
+        let mut cache = SnapshotCache::new(100);
+        cache.insert(key(0), snapshots[0].clone(), None, 40);
+        cache.insert(key(1), snapshots[1].clone(), None, 40);
+        assert!(cache.get(key(0)).is_some());
+        // key(1) is least recently used
+        cache.insert(SnapshotLocation::Wal(0), snapshots[0].clone(), None, 40);
+        assert!(cache.get(key(1)).is_none());
+        assert!(cache.get(key(0)).is_some());
+        assert_eq!(cache.used_bytes, 80);
+
+        // Larger than the whole budget: not cached
+        cache.insert(key(2), snapshots[0].clone(), None, 101);
+        assert!(cache.get(key(2)).is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 2));
+        assert_eq!(stats.hit_ratio(), 0.5);
+
+        cache.remove_wal();
+        assert_eq!(cache.used_bytes, 40);
+        cache.set_max_bytes(0);
+        assert_eq!(cache.stats().entries, 0);
+        // Disabled cache counts nothing
+        assert!(cache.get(key(0)).is_none());
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[test]
+    fn test_snapshot_cache_serves_without_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rpglot_2026-01-01_00.zst");
+        let snapshots = create_test_snapshots();
+        crate::storage::chunk::write_chunk(&path, &snapshots, &StringInterner::new()).unwrap();
+
+        let mut provider = HistoryProvider::from_path(dir.path()).unwrap();
+        assert_eq!(provider.snapshot_at(1), Some(snapshots[1].clone()));
+        let stats = provider.snapshot_cache_stats();
+        assert!(stats.used_bytes > 0);
+        assert_eq!(stats.max_bytes, DEFAULT_SNAPSHOT_CACHE_BYTES);
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(provider.snapshot_at(1), Some(snapshots[1].clone()));
+        assert!(provider.snapshot_cache_stats().hits >= 1);
+
+        provider.evict_buffers();
+        assert_eq!(provider.snapshot_cache_stats().used_bytes, 0);
+        assert_eq!(provider.snapshot_at(1), None);
+    }
+
+    #[test]
+    fn test_snapshot_cache_wal_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = StorageManager::new(dir.path());
+        let snapshots = create_test_snapshots();
+        for snapshot in &snapshots {
+            storage.add_snapshot(snapshot.clone(), &StringInterner::new());
+        }
+
+        // from_path buffers position 0 through the cache
+        let mut provider = HistoryProvider::from_path(dir.path()).unwrap();
+        assert_eq!(provider.snapshot_at(0), Some(snapshots[0].clone()));
+        let stats = provider.snapshot_cache_stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert!(stats.used_bytes > 0);
+
+        // WAL positions may shift after a flush
+        provider.refresh(dir.path()).unwrap();
+        assert_eq!(provider.snapshot_cache_stats().used_bytes, 0);
+    }
+
+    #[test]
+    fn test_snapshot_block_at_from_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots = create_test_snapshots();
+        crate::storage::chunk::write_chunk(
+            &dir.path().join("rpglot_2026-01-01_00.zst"),
+            &snapshots,
+            &StringInterner::new(),
+        )
+        .unwrap();
+
+        let mut provider = HistoryProvider::from_path(dir.path()).unwrap();
+        assert_eq!(
+            provider.snapshot_block_at(1, "processes"),
+            Some(snapshots[1].blocks[0].clone())
+        );
+        assert_eq!(provider.snapshot_block_at(1, "agent_stats"), None);
+        assert_eq!(provider.snapshot_block_at(1, "no_such_block"), None);
+        assert_eq!(provider.snapshot_block_at(5, "processes"), None);
+
+        let partial = provider
+            .snapshot_blocks_at(2, &["processes", "no_such_block"])
+            .unwrap();
+        assert_eq!(partial.timestamp, snapshots[2].timestamp);
+        assert_eq!(partial.blocks, vec![snapshots[2].blocks[0].clone()]);
+        assert!(provider.interner_at(2).is_some());
+        assert!(provider.interner_at(5).is_none());
+    }
 }
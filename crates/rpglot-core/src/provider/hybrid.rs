@@ -0,0 +1,508 @@
+//! Combined live + history provider.
+//!
+//! Keeps the most recent live snapshots in an in-memory ring buffer and
+//! falls back to on-disk history (e.g. the directory live snapshots are
+//! recorded to) for anything older, so one timeline reaches from "now" into
+//! the past without switching between providers.
+
+use std::any::Any;
+use std::collections::VecDeque;
+
+use crate::collector::{CollectorTiming, UserResolver};
+use crate::storage::model::Snapshot;
+use crate::storage::{StorageManager, StringInterner};
+
+use super::{HistoryProvider, ProviderError, SnapshotProvider};
+
+/// Snapshot selected by navigation instead of the newest collected one.
+struct Browsed {
+    snapshot: Snapshot,
+    /// Loaded from disk: strings resolve through the history interner.
+    from_history: bool,
+}
+
+/// Provider serving recent snapshots from memory and older ones from disk.
+///
+/// `advance()` collects a new snapshot while following the newest one and
+/// steps forward while browsing; `rewind()` steps back through the buffer
+/// and then through history. Reaching the newest snapshot again resumes
+/// following. Callers that keep collecting while a client browses (the web
+/// server) use [`collect`](Self::collect) and the timestamp lookups instead.
+pub struct HybridProvider {
+    live: Box<dyn SnapshotProvider + Send>,
+    history: Option<HistoryProvider>,
+    /// Recently collected snapshots, oldest first.
+    recent: VecDeque<Snapshot>,
+    capacity: usize,
+    /// `None` follows the newest collected snapshot.
+    browsed: Option<Browsed>,
+}
+
+impl HybridProvider {
+    /// Creates a hybrid provider.
+    ///
+    /// # Arguments
+    /// * `live` - Provider collecting new snapshots
+    /// * `history` - Optional on-disk history with older snapshots
+    /// * `capacity` - Number of recent snapshots kept in memory
+    pub fn new(
+        live: Box<dyn SnapshotProvider + Send>,
+        mut history: Option<HistoryProvider>,
+        capacity: usize,
+    ) -> Self {
+        if let Some(hp) = &mut history {
+            // An empty directory is indexed by the first refresh that finds data
+            hp.ensure_initialized().ok();
+        }
+        let capacity = capacity.max(1);
+        Self {
+            live,
+            history,
+            recent: VecDeque::with_capacity(capacity),
+            capacity,
+            browsed: None,
+        }
+    }
+
+    /// Collects a new live snapshot into the buffer, evicting the oldest one
+    /// when full. Navigation is not affected.
+    ///
+    /// Snapshots are addressed by timestamp, so after the clock was stepped
+    /// back the buffered snapshots at or after the new timestamp are dropped
+    /// (history on disk keeps them).
+    pub fn collect(&mut self) -> Option<&Snapshot> {
+        let snapshot = self.live.advance()?.clone();
+        while self
+            .recent
+            .back()
+            .is_some_and(|s| s.timestamp >= snapshot.timestamp)
+        {
+            self.recent.pop_back();
+        }
+        if self.recent.len() == self.capacity {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(snapshot);
+        self.recent.back()
+    }
+
+    /// Returns to the newest collected snapshot.
+    pub fn follow(&mut self) {
+        self.browsed = None;
+    }
+
+    /// Returns `true` while showing the newest collected snapshot.
+    pub fn is_following(&self) -> bool {
+        self.browsed.is_none()
+    }
+
+    /// Number of snapshots in the in-memory buffer.
+    pub fn recent_len(&self) -> usize {
+        self.recent.len()
+    }
+
+    /// The on-disk part, for refreshing or range queries.
+    pub fn history_mut(&mut self) -> Option<&mut HistoryProvider> {
+        self.history.as_mut()
+    }
+
+    /// Discovers snapshots written to the history directory since the last
+    /// refresh. Returns the number of new snapshots.
+    pub fn refresh_history(&mut self) -> Result<usize, ProviderError> {
+        match &mut self.history {
+            Some(hp) if !hp.is_initialized() => hp.ensure_initialized().map(|()| hp.len()),
+            Some(hp) => {
+                let path = hp.storage_path().to_path_buf();
+                hp.refresh(path)
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// History snapshots older than the buffer (newer ones are duplicates of
+    /// buffered snapshots when history is the recording directory).
+    fn history_timestamps(&self) -> &[i64] {
+        let Some(hp) = &self.history else {
+            return &[];
+        };
+        let timestamps = hp.timestamps();
+        match self.recent.front() {
+            Some(oldest) => &timestamps[..timestamps.partition_point(|&ts| ts < oldest.timestamp)],
+            None => timestamps,
+        }
+    }
+
+    /// All snapshot timestamps, oldest first: history, then the buffer.
+    pub fn timestamps(&self) -> Vec<i64> {
+        let mut timestamps = self.history_timestamps().to_vec();
+        timestamps.extend(self.recent.iter().map(|s| s.timestamp));
+        timestamps
+    }
+
+    /// First and last snapshot timestamps.
+    pub fn timestamp_range(&self) -> Option<(i64, i64)> {
+        let first = self
+            .history_timestamps()
+            .first()
+            .copied()
+            .or_else(|| self.recent.front().map(|s| s.timestamp))?;
+        let last = self
+            .recent
+            .back()
+            .map(|s| s.timestamp)
+            .or_else(|| self.history_timestamps().last().copied())?;
+        Some((first, last))
+    }
+
+    /// Timestamp of the snapshot nearest to `target`: the latest one at or
+    /// before it, or with `ceil` the earliest one at or after it. Targets
+    /// outside the range resolve to the first or last snapshot.
+    pub fn resolve_timestamp(&self, target: i64, ceil: bool) -> Option<i64> {
+        let timestamps = self.timestamps();
+        let idx = if ceil {
+            timestamps.partition_point(|&ts| ts < target)
+        } else {
+            timestamps
+                .partition_point(|&ts| ts <= target)
+                .saturating_sub(1)
+        };
+        timestamps
+            .get(idx.min(timestamps.len().saturating_sub(1)))
+            .copied()
+    }
+
+    /// Timestamps of the snapshots before and after the one at `ts`.
+    pub fn neighbours(&self, ts: i64) -> (Option<i64>, Option<i64>) {
+        let timestamps = self.timestamps();
+        let idx = timestamps.partition_point(|&t| t < ts);
+        let prev = idx.checked_sub(1).map(|i| timestamps[i]);
+        let next = timestamps.get(idx + usize::from(timestamps.get(idx) == Some(&ts)));
+        (prev, next.copied())
+    }
+
+    /// Returns an owned snapshot with timestamp `ts` without changing what
+    /// `current()` shows.
+    ///
+    /// Loading from history moves its cursor, so [`interner_for`](Self::interner_for)
+    /// resolves the strings of the snapshot loaded last.
+    pub fn snapshot_at_timestamp(&mut self, ts: i64) -> Option<Snapshot> {
+        if let Ok(idx) = self.recent.binary_search_by_key(&ts, |s| s.timestamp) {
+            return self.recent.get(idx).cloned();
+        }
+        let position = self.history_timestamps().binary_search(&ts).ok()?;
+        self.history.as_mut()?.jump_to(position).cloned()
+    }
+
+    /// Interner resolving the strings of the snapshot at `ts`.
+    pub fn interner_for(&self, ts: i64) -> Option<&StringInterner> {
+        if self.is_buffered(ts) {
+            self.live.interner()
+        } else {
+            self.history.as_ref()?.interner()
+        }
+    }
+
+    fn is_buffered(&self, ts: i64) -> bool {
+        self.recent.front().is_some_and(|s| ts >= s.timestamp)
+    }
+
+    /// Shows the snapshot at `ts`; the newest one resumes following.
+    fn browse(&mut self, ts: i64) -> Option<&Snapshot> {
+        if self.recent.back().is_some_and(|s| s.timestamp == ts) {
+            self.browsed = None;
+        } else {
+            let snapshot = self.snapshot_at_timestamp(ts)?;
+            let from_history = !self.is_buffered(ts);
+            self.browsed = Some(Browsed {
+                snapshot,
+                from_history,
+            });
+        }
+        self.current()
+    }
+
+    /// Shows the snapshot nearest to `target` (see
+    /// [`resolve_timestamp`](Self::resolve_timestamp)).
+    pub fn jump_to_timestamp(&mut self, target: i64, ceil: bool) -> Option<&Snapshot> {
+        let ts = self.resolve_timestamp(target, ceil)?;
+        self.browse(ts)
+    }
+}
+
+impl SnapshotProvider for HybridProvider {
+    fn current(&self) -> Option<&Snapshot> {
+        match &self.browsed {
+            Some(browsed) => Some(&browsed.snapshot),
+            None => self.recent.back(),
+        }
+    }
+
+    fn advance(&mut self) -> Option<&Snapshot> {
+        let Some(ts) = self.browsed.as_ref().map(|b| b.snapshot.timestamp) else {
+            return self.collect();
+        };
+        match self.neighbours(ts).1 {
+            Some(next) => self.browse(next),
+            None => {
+                self.browsed = None;
+                self.current()
+            }
+        }
+    }
+
+    fn rewind(&mut self) -> Option<&Snapshot> {
+        let ts = self.current()?.timestamp;
+        match self.neighbours(ts).0 {
+            Some(prev) => self.browse(prev),
+            // At the start, keep the current snapshot
+            None => self.current(),
+        }
+    }
+
+    fn can_rewind(&self) -> bool {
+        true
+    }
+
+    /// Live while following the newest snapshot.
+    fn is_live(&self) -> bool {
+        self.browsed.is_none()
+    }
+
+    fn last_error(&self) -> Option<&ProviderError> {
+        self.live.last_error()
+    }
+
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+
+    fn interner(&self) -> Option<&StringInterner> {
+        match &self.browsed {
+            Some(browsed) if browsed.from_history => self.history.as_ref()?.interner(),
+            _ => self.live.interner(),
+        }
+    }
+
+    fn user_resolver(&self) -> Option<&UserResolver> {
+        self.live.user_resolver()
+    }
+
+    fn pg_last_error(&self) -> Option<&str> {
+        self.live.pg_last_error()
+    }
+
+    fn collector_timing(&self) -> Option<&CollectorTiming> {
+        self.live.collector_timing()
+    }
+
+    fn instance_info(&self) -> Option<(String, String)> {
+        self.live.instance_info()
+    }
+
+    fn is_in_recovery(&self) -> Option<bool> {
+        self.live.is_in_recovery()
+    }
+
+    /// Takes effect from the next collected snapshot; history snapshots are
+    /// loaded with the new selection.
+    fn set_instance(&mut self, name: Option<String>) {
+        if let Some(hp) = &mut self.history {
+            hp.set_instance(name.clone());
+        }
+        self.live.set_instance(name);
+    }
+
+    fn instance(&self) -> Option<&str> {
+        self.live.instance()
+    }
+
+    fn storage_mut(&mut self) -> Option<&mut StorageManager> {
+        self.live.storage_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::{DataBlock, ProcessInfo};
+
+    /// Live source yielding snapshots 10 seconds apart.
+    struct Ticker {
+        next_ts: i64,
+        current: Option<Snapshot>,
+    }
+
+    impl Ticker {
+        fn starting_at(ts: i64) -> Box<Self> {
+            Box::new(Self {
+                next_ts: ts,
+                current: None,
+            })
+        }
+    }
+
+    impl SnapshotProvider for Ticker {
+        fn current(&self) -> Option<&Snapshot> {
+            self.current.as_ref()
+        }
+
+        fn advance(&mut self) -> Option<&Snapshot> {
+            self.current = Some(snapshot(self.next_ts));
+            self.next_ts += 10;
+            self.current.as_ref()
+        }
+
+        fn rewind(&mut self) -> Option<&Snapshot> {
+            None
+        }
+
+        fn can_rewind(&self) -> bool {
+            false
+        }
+
+        fn is_live(&self) -> bool {
+            true
+        }
+
+        fn last_error(&self) -> Option<&ProviderError> {
+            None
+        }
+    }
+
+    /// Live source yielding the given timestamps.
+    struct Replay {
+        timestamps: std::vec::IntoIter<i64>,
+        current: Option<Snapshot>,
+    }
+
+    impl SnapshotProvider for Replay {
+        fn current(&self) -> Option<&Snapshot> {
+            self.current.as_ref()
+        }
+
+        fn advance(&mut self) -> Option<&Snapshot> {
+            self.current = Some(snapshot(self.timestamps.next()?));
+            self.current.as_ref()
+        }
+
+        fn rewind(&mut self) -> Option<&Snapshot> {
+            None
+        }
+
+        fn can_rewind(&self) -> bool {
+            false
+        }
+
+        fn is_live(&self) -> bool {
+            true
+        }
+
+        fn last_error(&self) -> Option<&ProviderError> {
+            None
+        }
+    }
+
+    fn snapshot(timestamp: i64) -> Snapshot {
+        Snapshot {
+            timestamp,
+            blocks: vec![DataBlock::Processes(vec![ProcessInfo {
+                pid: timestamp as u32,
+                ..ProcessInfo::default()
+            }])],
+        }
+    }
+
+    /// History at 100..=140, live from 130 (overlapping the recording).
+    fn hybrid(capacity: usize) -> HybridProvider {
+        let history =
+            HistoryProvider::from_snapshots((100..=140).step_by(10).map(snapshot).collect())
+                .unwrap();
+        HybridProvider::new(Ticker::starting_at(130), Some(history), capacity)
+    }
+
+    #[test]
+    fn test_hybrid_ring_buffer_evicts_oldest() {
+        let mut provider = HybridProvider::new(Ticker::starting_at(0), None, 3);
+        for _ in 0..5 {
+            provider.collect();
+        }
+        assert_eq!(provider.recent_len(), 3);
+        assert_eq!(provider.timestamps(), vec![20, 30, 40]);
+        assert_eq!(provider.current().unwrap().timestamp, 40);
+        assert!(provider.is_live());
+    }
+
+    #[test]
+    fn test_hybrid_timeline_skips_recorded_duplicates() {
+        let mut provider = hybrid(10);
+        for _ in 0..3 {
+            provider.collect();
+        }
+        // 130 and 140 are both recorded and buffered
+        assert_eq!(provider.timestamps(), vec![100, 110, 120, 130, 140, 150]);
+        assert_eq!(provider.timestamp_range(), Some((100, 150)));
+        assert_eq!(provider.neighbours(130), (Some(120), Some(140)));
+        assert_eq!(provider.neighbours(100), (None, Some(110)));
+        assert_eq!(provider.resolve_timestamp(125, false), Some(120));
+        assert_eq!(provider.resolve_timestamp(125, true), Some(130));
+        assert_eq!(provider.resolve_timestamp(999, true), Some(150));
+    }
+
+    #[test]
+    fn test_hybrid_rewinds_from_buffer_into_history() {
+        let mut provider = hybrid(2);
+        for _ in 0..3 {
+            provider.advance();
+        }
+        // Buffer holds 140 and 150
+        assert_eq!(provider.current().unwrap().timestamp, 150);
+
+        assert_eq!(provider.rewind().unwrap().timestamp, 140);
+        assert!(!provider.is_live());
+        assert_eq!(provider.rewind().unwrap().timestamp, 130);
+        assert_eq!(provider.rewind().unwrap().timestamp, 120);
+        assert_eq!(provider.jump_to_timestamp(0, false).unwrap().timestamp, 100);
+        assert_eq!(provider.rewind().unwrap().timestamp, 100);
+
+        // Stepping forward past the buffer's newest snapshot resumes following
+        provider.jump_to_timestamp(140, false);
+        assert_eq!(provider.advance().unwrap().timestamp, 150);
+        assert!(provider.is_live());
+        assert_eq!(provider.advance().unwrap().timestamp, 160);
+    }
+
+    #[test]
+    fn test_hybrid_collect_keeps_browsed_snapshot() {
+        let mut provider = hybrid(10);
+        provider.collect();
+        provider.jump_to_timestamp(110, false);
+        provider.collect();
+        assert_eq!(provider.current().unwrap().timestamp, 110);
+        assert_eq!(provider.snapshot_at_timestamp(140).unwrap().timestamp, 140);
+        assert_eq!(provider.current().unwrap().timestamp, 110);
+        assert!(provider.snapshot_at_timestamp(145).is_none());
+
+        provider.follow();
+        assert_eq!(provider.current().unwrap().timestamp, 140);
+    }
+
+    #[test]
+    fn test_hybrid_clock_stepped_back() {
+        let replay = Replay {
+            timestamps: vec![100, 110, 120, 105, 115].into_iter(),
+            current: None,
+        };
+        let mut provider = HybridProvider::new(Box::new(replay), None, 10);
+        for _ in 0..5 {
+            provider.collect();
+        }
+        // 110 and 120 were collected before the clock went back to 105
+        assert_eq!(provider.timestamps(), vec![100, 105, 115]);
+        assert_eq!(provider.current().unwrap().timestamp, 115);
+        assert_eq!(provider.neighbours(115), (Some(105), None));
+    }
+}
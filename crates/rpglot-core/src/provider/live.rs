@@ -6,18 +6,28 @@ use crate::storage::StorageManager;
 use crate::storage::StringInterner;
 use crate::storage::model::Snapshot;
 
-use super::{ProviderError, SnapshotProvider};
+use super::{ProviderError, SnapshotProvider, select_instance};
 use std::any::Any;
+use std::collections::VecDeque;
 
 /// Provider for real-time system data collection.
 ///
 /// Collects snapshots from the system using the `Collector` and optionally
-/// writes them to storage for later analysis.
+/// writes them to storage for later analysis. With a buffer
+/// ([`with_buffer`](Self::with_buffer)) the last snapshots stay in memory and
+/// `rewind()` / `advance()` step through them.
 pub struct LiveProvider<F: FileSystem + Clone> {
     collector: Collector<F>,
     storage: Option<StorageManager>,
     current: Option<Snapshot>,
     last_error: Option<ProviderError>,
+    /// Selected PostgreSQL instance (`None` = primary).
+    instance: Option<String>,
+    /// Recently collected snapshots, oldest first; the newest is `current`.
+    buffer: VecDeque<Snapshot>,
+    buffer_capacity: usize,
+    /// Index into `buffer` while browsing back, `None` at the newest snapshot.
+    cursor: Option<usize>,
 }
 
 impl<F: FileSystem + Clone> LiveProvider<F> {
@@ -32,24 +42,53 @@ impl<F: FileSystem + Clone> LiveProvider<F> {
             storage,
             current: None,
             last_error: None,
+            instance: None,
+            buffer: VecDeque::new(),
+            buffer_capacity: 0,
+            cursor: None,
         }
     }
+
+    /// Keeps the last `capacity` collected snapshots in memory for browsing
+    /// back (0 disables the buffer).
+    pub fn with_buffer(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity;
+        self.buffer = VecDeque::with_capacity(capacity);
+        self
+    }
 }
 
 impl<F: FileSystem + Clone + 'static> SnapshotProvider for LiveProvider<F> {
     fn current(&self) -> Option<&Snapshot> {
-        self.current.as_ref()
+        match self.cursor {
+            Some(idx) => self.buffer.get(idx),
+            None => self.current.as_ref(),
+        }
     }
 
+    /// Collects a new snapshot, or steps forward while browsing the buffer
+    /// (reaching the newest snapshot resumes collection on the next call).
     fn advance(&mut self) -> Option<&Snapshot> {
         self.last_error = None;
 
+        if let Some(idx) = self.cursor {
+            self.cursor = Some(idx + 1).filter(|&next| next + 1 < self.buffer.len());
+            return self.current();
+        }
+
         match self.collector.collect_snapshot() {
             Ok(snapshot) => {
                 // Optionally save to storage
                 if let Some(storage) = &mut self.storage {
                     storage.add_snapshot(snapshot.clone(), self.collector.interner());
                 }
+                let snapshot = select_instance(self.instance.as_deref(), snapshot);
+                if self.buffer_capacity > 0 {
+                    if self.buffer.len() == self.buffer_capacity {
+                        self.buffer.pop_front();
+                    }
+                    self.buffer.push_back(snapshot.clone());
+                }
                 self.current = Some(snapshot);
                 self.current.as_ref()
             }
@@ -60,13 +99,42 @@ impl<F: FileSystem + Clone + 'static> SnapshotProvider for LiveProvider<F> {
         }
     }
 
+    /// Steps back through the buffer; stays at the oldest buffered snapshot.
     fn rewind(&mut self) -> Option<&Snapshot> {
-        // Live provider does not support rewinding
-        None
+        if self.buffer_capacity == 0 {
+            return None;
+        }
+        let idx = self
+            .cursor
+            .unwrap_or_else(|| self.buffer.len().saturating_sub(1));
+        if idx > 0 {
+            self.cursor = Some(idx - 1);
+        }
+        self.current()
     }
 
     fn can_rewind(&self) -> bool {
-        false
+        self.buffer_capacity > 0
+    }
+
+    fn previous(&self) -> Option<&Snapshot> {
+        let idx = self
+            .cursor
+            .unwrap_or_else(|| self.buffer.len().saturating_sub(1));
+        self.buffer.get(idx.checked_sub(1)?)
+    }
+
+    fn buffer_position(&self) -> Option<(usize, usize)> {
+        self.cursor.map(|idx| (idx, self.buffer.len()))
+    }
+
+    /// Takes effect from the next collected snapshot.
+    fn set_instance(&mut self, name: Option<String>) {
+        self.instance = name;
+    }
+
+    fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
     }
 
     fn is_live(&self) -> bool {
@@ -89,6 +157,10 @@ impl<F: FileSystem + Clone + 'static> SnapshotProvider for LiveProvider<F> {
         Some(self.collector.interner())
     }
 
+    fn storage_mut(&mut self) -> Option<&mut StorageManager> {
+        self.storage.as_mut()
+    }
+
     fn user_resolver(&self) -> Option<&UserResolver> {
         Some(self.collector.user_resolver())
     }
@@ -142,6 +214,32 @@ mod tests {
         assert!(provider.rewind().is_none());
     }
 
+    #[test]
+    fn test_live_provider_buffer_rewind() {
+        let fs = MockFs::typical_system();
+        let collector = Collector::new(fs, "/proc");
+        let mut provider = LiveProvider::new(collector, None).with_buffer(2);
+        assert!(provider.can_rewind());
+
+        for _ in 0..3 {
+            provider.advance();
+        }
+        assert_eq!(provider.buffer_position(), None);
+        assert!(provider.previous().is_some());
+
+        assert!(provider.rewind().is_some());
+        assert_eq!(provider.buffer_position(), Some((0, 2)));
+        assert!(provider.previous().is_none());
+
+        // The oldest buffered snapshot stays
+        assert!(provider.rewind().is_some());
+        assert_eq!(provider.buffer_position(), Some((0, 2)));
+
+        // Stepping forward to the newest snapshot resumes collection
+        assert!(provider.advance().is_some());
+        assert_eq!(provider.buffer_position(), None);
+    }
+
     #[test]
     fn test_live_provider_is_live() {
         let fs = MockFs::typical_system();
@@ -5,16 +5,18 @@
 //! a unified interface.
 
 mod history;
+mod hybrid;
 mod live;
 
-pub use history::HistoryProvider;
+pub use history::{DEFAULT_SNAPSHOT_CACHE_BYTES, HistoryProvider, SnapshotCacheStats};
+pub use hybrid::HybridProvider;
 pub use live::LiveProvider;
 
 use std::any::Any;
 
 use crate::collector::{CollectorTiming, UserResolver};
-use crate::storage::StringInterner;
 use crate::storage::model::Snapshot;
+use crate::storage::{StorageManager, StringInterner};
 
 /// Error types that can occur during snapshot operations.
 #[derive(Debug, Clone)]
@@ -41,6 +43,7 @@ impl std::error::Error for ProviderError {}
 /// This trait allows TUI to work with different data sources:
 /// - `LiveProvider`: Real-time data collection from the system
 /// - `HistoryProvider`: Historical data from storage files
+/// - `HybridProvider`: Recent live data in memory, older data from storage
 ///
 /// The trait is object-safe and designed to be used with `Box<dyn SnapshotProvider>`.
 pub trait SnapshotProvider {
@@ -60,7 +63,7 @@ pub trait SnapshotProvider {
 
     /// Moves to the previous snapshot.
     ///
-    /// - In live mode: not supported, returns `None`
+    /// - In live mode: steps back through the in-memory buffer, if any
     /// - In history mode: moves cursor backward in time
     ///
     /// Returns `None` if at the beginning of history or if rewind is not supported.
@@ -68,7 +71,8 @@ pub trait SnapshotProvider {
 
     /// Returns `true` if this provider supports rewinding (going back in time).
     ///
-    /// Live providers return `false`, history providers return `true`.
+    /// Live providers return `true` only with a snapshot buffer, history
+    /// providers always do.
     fn can_rewind(&self) -> bool;
 
     /// Returns `true` if this provider is collecting live data.
@@ -133,4 +137,42 @@ pub trait SnapshotProvider {
     fn is_in_recovery(&self) -> Option<bool> {
         None
     }
+
+    /// Shows PostgreSQL instance `name` instead of the primary one: snapshots
+    /// are returned with its blocks at the top level (see
+    /// [`Snapshot::select_instance`]). `None` shows the primary instance.
+    fn set_instance(&mut self, _name: Option<String>) {}
+
+    /// Returns the selected PostgreSQL instance (`None` = primary).
+    fn instance(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the buffered snapshot before the current one, the diff
+    /// baseline after stepping through a live buffer.
+    ///
+    /// Only available for live providers with a buffer.
+    fn previous(&self) -> Option<&Snapshot> {
+        None
+    }
+
+    /// Returns the position in the live buffer as (index, buffered count)
+    /// while browsing back, `None` at the newest snapshot.
+    fn buffer_position(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Returns the storage collected snapshots are recorded to, if any
+    /// (for rotation and the final flush).
+    fn storage_mut(&mut self) -> Option<&mut StorageManager> {
+        None
+    }
+}
+
+/// Applies the instance selection of a provider to a loaded snapshot.
+fn select_instance(instance: Option<&str>, mut snapshot: Snapshot) -> Snapshot {
+    if let Some(name) = instance {
+        snapshot.select_instance(name);
+    }
+    snapshot
 }
@@ -30,6 +30,11 @@ pub const MAX_PGS_STALE_SECS: i64 = 300;
 /// Maximum age (seconds) for stale PGP entries in prev_sample (3× 300s cache).
 pub const MAX_PGP_STALE_SECS: i64 = 900;
 
+/// Share of entries (present in both samples) whose counters must decrease
+/// for the whole view to be treated as reset rather than a few entries being
+/// evicted and re-added.
+pub const RESET_MIN_FRACTION: f64 = 0.5;
+
 // ---------------------------------------------------------------------------
 // Delta helpers
 // ---------------------------------------------------------------------------
@@ -44,6 +49,73 @@ pub fn df64(curr: f64, prev: f64) -> Option<f64> {
     (curr >= prev).then_some(curr - prev)
 }
 
+// ---------------------------------------------------------------------------
+// Stats reset detection
+// ---------------------------------------------------------------------------
+
+/// Detects a stats reset from `(current, previous)` activity counters of the
+/// entries present in both samples: more than [`RESET_MIN_FRACTION`] of them
+/// decreased.
+pub fn is_stats_reset(pairs: impl IntoIterator<Item = (i64, i64)>) -> bool {
+    let (mut common, mut decreased) = (0usize, 0usize);
+    for (curr, prev) in pairs {
+        common += 1;
+        if curr < prev {
+            decreased += 1;
+        }
+    }
+    decreased > 0 && decreased as f64 > common as f64 * RESET_MIN_FRACTION
+}
+
+/// `pg_stat_statements_reset()`: calls decreased for most statements.
+pub fn pgs_reset(curr: &[PgStatStatementsInfo], prev: &HashMap<i64, PgStatStatementsInfo>) -> bool {
+    is_stats_reset(
+        curr.iter()
+            .filter_map(|s| prev.get(&s.queryid).map(|p| (s.calls, p.calls))),
+    )
+}
+
+/// `pg_store_plans_reset()`: calls decreased for most plans.
+pub fn pgp_reset(curr: &[PgStorePlansInfo], prev: &HashMap<i64, PgStorePlansInfo>) -> bool {
+    is_stats_reset(
+        curr.iter()
+            .filter_map(|p| prev.get(&p.planid).map(|q| (p.calls, q.calls))),
+    )
+}
+
+fn table_activity(t: &PgStatUserTablesInfo) -> i64 {
+    t.seq_scan
+        + t.idx_scan
+        + t.n_tup_ins
+        + t.n_tup_upd
+        + t.n_tup_del
+        + t.heap_blks_read
+        + t.heap_blks_hit
+}
+
+/// `pg_stat_reset()`: scan, tuple and block counters decreased for most tables.
+pub fn pgt_reset(curr: &[PgStatUserTablesInfo], prev: &HashMap<u32, PgStatUserTablesInfo>) -> bool {
+    is_stats_reset(curr.iter().filter_map(|t| {
+        prev.get(&t.relid)
+            .map(|p| (table_activity(t), table_activity(p)))
+    }))
+}
+
+fn index_activity(i: &PgStatUserIndexesInfo) -> i64 {
+    i.idx_scan + i.idx_blks_read + i.idx_blks_hit
+}
+
+/// `pg_stat_reset()`: scan and block counters decreased for most indexes.
+pub fn pgi_reset(
+    curr: &[PgStatUserIndexesInfo],
+    prev: &HashMap<u32, PgStatUserIndexesInfo>,
+) -> bool {
+    is_stats_reset(curr.iter().filter_map(|i| {
+        prev.get(&i.indexrelid)
+            .map(|p| (index_activity(i), index_activity(p)))
+    }))
+}
+
 // ---------------------------------------------------------------------------
 // Rate state structs
 // ---------------------------------------------------------------------------
@@ -54,6 +126,8 @@ pub struct PgsRateState {
     pub rates: HashMap<i64, PgStatementsRates>,
     pub prev_sample: HashMap<i64, PgStatStatementsInfo>,
     pub prev_ts: Option<i64>,
+    /// Sample time of the last detected stats reset (rates restarted from it).
+    pub last_reset: Option<i64>,
 }
 
 impl PgsRateState {
@@ -61,6 +135,7 @@ impl PgsRateState {
         self.rates.clear();
         self.prev_sample.clear();
         self.prev_ts = None;
+        self.last_reset = None;
     }
 
     pub fn shrink_to_fit(&mut self) {
@@ -75,6 +150,8 @@ pub struct PgpRateState {
     pub rates: HashMap<i64, PgStorePlansRates>,
     pub prev_sample: HashMap<i64, PgStorePlansInfo>,
     pub prev_ts: Option<i64>,
+    /// Sample time of the last detected stats reset (rates restarted from it).
+    pub last_reset: Option<i64>,
 }
 
 impl PgpRateState {
@@ -82,6 +159,7 @@ impl PgpRateState {
         self.rates.clear();
         self.prev_sample.clear();
         self.prev_ts = None;
+        self.last_reset = None;
     }
 
     pub fn shrink_to_fit(&mut self) {
@@ -96,6 +174,8 @@ pub struct PgtRateState {
     pub rates: HashMap<u32, PgTablesRates>,
     pub prev_sample: HashMap<u32, PgStatUserTablesInfo>,
     pub prev_ts: Option<i64>,
+    /// Sample time of the last detected stats reset (rates restarted from it).
+    pub last_reset: Option<i64>,
 }
 
 impl PgtRateState {
@@ -103,6 +183,7 @@ impl PgtRateState {
         self.rates.clear();
         self.prev_sample.clear();
         self.prev_ts = None;
+        self.last_reset = None;
     }
 
     pub fn shrink_to_fit(&mut self) {
@@ -117,6 +198,8 @@ pub struct PgiRateState {
     pub rates: HashMap<u32, PgIndexesRates>,
     pub prev_sample: HashMap<u32, PgStatUserIndexesInfo>,
     pub prev_ts: Option<i64>,
+    /// Sample time of the last detected stats reset (rates restarted from it).
+    pub last_reset: Option<i64>,
 }
 
 impl PgiRateState {
@@ -124,6 +207,7 @@ impl PgiRateState {
         self.rates.clear();
         self.prev_sample.clear();
         self.prev_ts = None;
+        self.last_reset = None;
     }
 
     pub fn shrink_to_fit(&mut self) {
@@ -190,6 +274,15 @@ pub fn update_pgs_rates(state: &mut PgsRateState, snapshot: &Snapshot) {
         return;
     }
 
+    // Stats reset: this sample is the new baseline
+    if pgs_reset(stmts, &state.prev_sample) {
+        state.prev_ts = Some(now_ts);
+        state.prev_sample = stmts.iter().map(|s| (s.queryid, s.clone())).collect();
+        state.rates.clear();
+        state.last_reset = Some(now_ts);
+        return;
+    }
+
     let mut rates = HashMap::with_capacity(stmts.len());
     for s in stmts {
         let mut r = PgStatementsRates {
@@ -295,6 +388,15 @@ pub fn update_pgp_rates(state: &mut PgpRateState, snapshot: &Snapshot) {
         return;
     }
 
+    // Stats reset: this sample is the new baseline
+    if pgp_reset(plans, &state.prev_sample) {
+        state.prev_ts = Some(now_ts);
+        state.prev_sample = plans.iter().map(|p| (p.planid, p.clone())).collect();
+        state.rates.clear();
+        state.last_reset = Some(now_ts);
+        return;
+    }
+
     let mut rates = HashMap::with_capacity(plans.len());
     for p in plans {
         let mut r = PgStorePlansRates {
@@ -374,6 +476,15 @@ pub fn update_pgt_rates(state: &mut PgtRateState, snapshot: &Snapshot) {
         return;
     }
 
+    // Stats reset: this sample is the new baseline
+    if pgt_reset(tables, &state.prev_sample) {
+        state.prev_ts = Some(now_ts);
+        state.prev_sample = tables.iter().map(|t| (t.relid, t.clone())).collect();
+        state.rates.clear();
+        state.last_reset = Some(now_ts);
+        return;
+    }
+
     let mut rates = HashMap::with_capacity(tables.len());
     for t in tables {
         let mut r = PgTablesRates {
@@ -455,6 +566,15 @@ pub fn update_pgi_rates(state: &mut PgiRateState, snapshot: &Snapshot) {
         return;
     }
 
+    // Stats reset: this sample is the new baseline
+    if pgi_reset(indexes, &state.prev_sample) {
+        state.prev_ts = Some(now_ts);
+        state.prev_sample = indexes.iter().map(|i| (i.indexrelid, i.clone())).collect();
+        state.rates.clear();
+        state.last_reset = Some(now_ts);
+        return;
+    }
+
     let mut rates = HashMap::with_capacity(indexes.len());
     for i in indexes {
         let mut r = PgIndexesRates {
@@ -490,6 +610,7 @@ mod tests {
 
     // -- helpers --
 
+    #[allow(clippy::too_many_arguments)]
     fn pgs_stmt(
         queryid: i64,
         calls: i64,
@@ -554,6 +675,7 @@ mod tests {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn pgt_table(
         relid: u32,
         seq_scan: i64,
@@ -670,7 +792,7 @@ mod tests {
     }
 
     #[test]
-    fn pgs_counter_regression_yields_none() {
+    fn pgs_reset_starts_new_baseline() {
         let mut st = PgsRateState::default();
         let s1 = pgs_snapshot(
             100,
@@ -690,9 +812,8 @@ mod tests {
             vec![pgs_stmt(1, 5, 50.0, 3, 2, 10, 0, 0, 0, 0, 0, 0, 120)],
         );
         update_pgs_rates(&mut st, &s3);
-        let r = st.rates.get(&1).expect("entry should exist");
-        assert_eq!(r.calls_s, None);
-        assert_eq!(r.exec_time_ms_s, None);
+        assert!(st.rates.is_empty(), "reset sample is a baseline");
+        assert_eq!(st.last_reset, Some(120));
 
         // Recovery after reset
         let s4 = pgs_snapshot(
@@ -705,6 +826,35 @@ mod tests {
         assert!((r.exec_time_ms_s.unwrap() - 2.0).abs() < 1e-9);
     }
 
+    #[test]
+    fn pgs_single_evicted_entry_is_not_reset() {
+        let mut st = PgsRateState::default();
+        let stmt = |id, calls, ts| pgs_stmt(id, calls, 0.0, 0, 0, 0, 0, 0, 0, 0, 0, 0, ts);
+        let s1 = pgs_snapshot(
+            100,
+            vec![stmt(1, 10, 100), stmt(2, 10, 100), stmt(3, 10, 100)],
+        );
+        update_pgs_rates(&mut st, &s1);
+
+        // Entry 3 was evicted and re-added; the others keep growing
+        let s2 = pgs_snapshot(
+            110,
+            vec![stmt(1, 20, 110), stmt(2, 20, 110), stmt(3, 2, 110)],
+        );
+        update_pgs_rates(&mut st, &s2);
+        assert_eq!(st.last_reset, None);
+        assert!((st.rates[&1].calls_s.unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(st.rates[&3].calls_s, None);
+    }
+
+    #[test]
+    fn stats_reset_threshold() {
+        assert!(!is_stats_reset([]));
+        assert!(!is_stats_reset([(5, 5), (6, 5)]));
+        assert!(!is_stats_reset([(1, 5), (6, 5)]));
+        assert!(is_stats_reset([(1, 5), (0, 5), (6, 5)]));
+    }
+
     #[test]
     fn pgs_time_regression_clears_rates() {
         let mut st = PgsRateState::default();
@@ -1030,4 +1180,30 @@ mod tests {
         update_pgi_rates(&mut st, &s2);
         assert!(!st.prev_sample.contains_key(&2));
     }
+
+    #[test]
+    fn pgt_pgi_reset_starts_new_baseline() {
+        let mut pgt = PgtRateState::default();
+        update_pgt_rates(
+            &mut pgt,
+            &pgt_snapshot(100, vec![pgt_table(1, 10, 5, 100, 50, 10, 2, 100)]),
+        );
+        update_pgt_rates(
+            &mut pgt,
+            &pgt_snapshot(110, vec![pgt_table(1, 0, 0, 1, 0, 0, 0, 110)]),
+        );
+        assert!(pgt.rates.is_empty());
+        assert_eq!(pgt.last_reset, Some(110));
+
+        let mut pgi = PgiRateState::default();
+        update_pgi_rates(
+            &mut pgi,
+            &pgi_snapshot(100, vec![pgi_index(1, 10, 100, 100)]),
+        );
+        update_pgi_rates(&mut pgi, &pgi_snapshot(110, vec![pgi_index(1, 0, 0, 110)]));
+        assert!(pgi.rates.is_empty());
+        assert_eq!(pgi.last_reset, Some(110));
+        update_pgi_rates(&mut pgi, &pgi_snapshot(120, vec![pgi_index(1, 5, 50, 120)]));
+        assert!((pgi.rates[&1].idx_scan_s.unwrap() - 0.5).abs() < 1e-9);
+    }
 }
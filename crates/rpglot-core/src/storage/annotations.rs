@@ -0,0 +1,165 @@
+//! Annotations: deployments, maintenance and other events worth correlating
+//! with metrics ("deployed v2.3", "vacuum full ran").
+//!
+//! Posted by users or CI through the web API and stored as a JSON array in
+//! `annotations.json` inside the data directory. Unlike bookmarks, several
+//! annotations may share a timestamp, so each one gets an id.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Sidecar file name inside the data directory.
+pub const ANNOTATIONS_FILE: &str = "annotations.json";
+
+/// Maximum text length in characters; longer texts are truncated.
+pub const MAX_TEXT_LEN: usize = 500;
+
+/// Maximum source length in characters.
+pub const MAX_SOURCE_LEN: usize = 64;
+
+/// An event at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Annotation {
+    /// Unique id within the data directory.
+    pub id: u64,
+    /// Event timestamp (epoch seconds).
+    pub ts: i64,
+    /// What happened.
+    pub text: String,
+    /// Who reported it ("ci", "deploy-bot", user name). Empty if unknown.
+    #[serde(default)]
+    pub source: String,
+    /// When the annotation was created (epoch seconds).
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+/// Annotations of one data directory, sorted by timestamp then id.
+#[derive(Debug, Clone)]
+pub struct AnnotationStore {
+    path: PathBuf,
+    annotations: Vec<Annotation>,
+}
+
+impl AnnotationStore {
+    /// Opens the annotations of `data_dir`. A missing file means none.
+    pub fn open(data_dir: &Path) -> io::Result<Self> {
+        let mut store = Self {
+            path: data_dir.join(ANNOTATIONS_FILE),
+            annotations: Vec::new(),
+        };
+        store.reload()?;
+        Ok(store)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Re-reads the file (picks up annotations added by other processes).
+    pub fn reload(&mut self) -> io::Result<()> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.annotations.clear();
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        let mut annotations: Vec<Annotation> = serde_json::from_str(&text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        annotations.sort_by_key(|a| (a.ts, a.id));
+        self.annotations = annotations;
+        Ok(())
+    }
+
+    /// Annotations sorted by timestamp.
+    pub fn list(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Annotations with `start <= ts <= end`.
+    pub fn in_range(&self, start: i64, end: i64) -> &[Annotation] {
+        let from = self.annotations.partition_point(|a| a.ts < start);
+        let to = self.annotations.partition_point(|a| a.ts <= end);
+        &self.annotations[from..to.max(from)]
+    }
+
+    /// Adds an annotation and returns it with its assigned id.
+    pub fn add(
+        &mut self,
+        ts: i64,
+        text: &str,
+        source: &str,
+        created_at: i64,
+    ) -> io::Result<Annotation> {
+        self.reload()?;
+        let annotation = Annotation {
+            id: self.annotations.iter().map(|a| a.id).max().unwrap_or(0) + 1,
+            ts,
+            text: text.trim().chars().take(MAX_TEXT_LEN).collect(),
+            source: source.trim().chars().take(MAX_SOURCE_LEN).collect(),
+            created_at,
+        };
+        let idx = self.annotations.partition_point(|a| a.ts <= ts);
+        self.annotations.insert(idx, annotation.clone());
+        self.save()?;
+        Ok(annotation)
+    }
+
+    /// Removes the annotation with `id`. Returns false if there was none.
+    pub fn remove(&mut self, id: u64) -> io::Result<bool> {
+        self.reload()?;
+        let Some(idx) = self.annotations.iter().position(|a| a.id == id) else {
+            return Ok(false);
+        };
+        self.annotations.remove(idx);
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Writes the file atomically (temp file + rename).
+    fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.annotations)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotations_get_ids_and_stay_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ci = AnnotationStore::open(dir.path()).unwrap();
+        assert!(ci.list().is_empty());
+
+        let deploy = ci.add(300, " deployed v2.3 ", "ci", 1).unwrap();
+        assert_eq!(deploy.id, 1);
+        assert_eq!(deploy.text, "deployed v2.3");
+        ci.add(100, "vacuum full ran", "", 2).unwrap();
+
+        // Another process shares the file; same timestamp is allowed
+        let mut web = AnnotationStore::open(dir.path()).unwrap();
+        let config = web.add(300, "work_mem raised", "alice", 3).unwrap();
+        assert_eq!(config.id, 3);
+
+        ci.reload().unwrap();
+        let ids: Vec<u64> = ci.list().iter().map(|a| a.id).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        let in_range: Vec<u64> = ci.in_range(200, 300).iter().map(|a| a.id).collect();
+        assert_eq!(in_range, vec![1, 3]);
+        assert!(ci.in_range(400, 500).is_empty());
+
+        assert!(ci.remove(1).unwrap());
+        assert!(!web.remove(1).unwrap());
+        assert_eq!(web.list().len(), 2);
+    }
+}
@@ -0,0 +1,169 @@
+//! Bookmarks: user-marked snapshot timestamps for incident review.
+//!
+//! Stored as a JSON array in `bookmarks.json` inside the data directory so
+//! the TUI and the web server share them. Every change re-reads the file
+//! first, so concurrent writers only race on the same bookmark.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Sidecar file name inside the data directory.
+pub const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// Maximum label length in characters; longer labels are truncated.
+pub const MAX_LABEL_LEN: usize = 200;
+
+/// A bookmarked snapshot timestamp.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Bookmark {
+    /// Snapshot timestamp (epoch seconds).
+    pub ts: i64,
+    /// Free-form note ("failover started", "vacuum full ran").
+    #[serde(default)]
+    pub label: String,
+    /// When the bookmark was created (epoch seconds).
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+/// Bookmarks of one data directory, sorted by timestamp (one per timestamp).
+#[derive(Debug, Clone)]
+pub struct BookmarkStore {
+    path: PathBuf,
+    bookmarks: Vec<Bookmark>,
+}
+
+impl BookmarkStore {
+    /// Opens the bookmarks of `data_dir`. A missing file means no bookmarks.
+    pub fn open(data_dir: &Path) -> io::Result<Self> {
+        let mut store = Self {
+            path: data_dir.join(BOOKMARKS_FILE),
+            bookmarks: Vec::new(),
+        };
+        store.reload()?;
+        Ok(store)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Re-reads the file (picks up bookmarks added elsewhere).
+    pub fn reload(&mut self) -> io::Result<()> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.bookmarks.clear();
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        let mut bookmarks: Vec<Bookmark> = serde_json::from_str(&text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        bookmarks.sort_by_key(|b| b.ts);
+        bookmarks.dedup_by_key(|b| b.ts);
+        self.bookmarks = bookmarks;
+        Ok(())
+    }
+
+    /// Bookmarks sorted by timestamp.
+    pub fn list(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    /// Adds a bookmark at `ts`, replacing the label of an existing one.
+    pub fn add(&mut self, ts: i64, label: &str, created_at: i64) -> io::Result<()> {
+        self.reload()?;
+        let label: String = label.trim().chars().take(MAX_LABEL_LEN).collect();
+        match self.bookmarks.binary_search_by_key(&ts, |b| b.ts) {
+            Ok(idx) => self.bookmarks[idx].label = label,
+            Err(idx) => self.bookmarks.insert(
+                idx,
+                Bookmark {
+                    ts,
+                    label,
+                    created_at,
+                },
+            ),
+        }
+        self.save()
+    }
+
+    /// Removes the bookmark at `ts`. Returns false if there was none.
+    pub fn remove(&mut self, ts: i64) -> io::Result<bool> {
+        self.reload()?;
+        let Ok(idx) = self.bookmarks.binary_search_by_key(&ts, |b| b.ts) else {
+            return Ok(false);
+        };
+        self.bookmarks.remove(idx);
+        self.save()?;
+        Ok(true)
+    }
+
+    /// First bookmark after `ts`.
+    pub fn next_after(&self, ts: i64) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|b| b.ts > ts)
+    }
+
+    /// Last bookmark before `ts`.
+    pub fn prev_before(&self, ts: i64) -> Option<&Bookmark> {
+        self.bookmarks.iter().rev().find(|b| b.ts < ts)
+    }
+
+    /// Writes the file atomically (temp file + rename).
+    fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.bookmarks)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bookmarks_roundtrip_sorted_and_shared() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tui = BookmarkStore::open(dir.path()).unwrap();
+        assert!(tui.list().is_empty());
+
+        tui.add(300, "checkpoint storm", 1).unwrap();
+        tui.add(100, "  deploy v2.3  ", 2).unwrap();
+
+        // A second writer sees and extends the same file
+        let mut web = BookmarkStore::open(dir.path()).unwrap();
+        assert_eq!(web.list().len(), 2);
+        web.add(200, "failover", 3).unwrap();
+        web.add(300, "checkpoint storm (fixed)", 4).unwrap();
+
+        tui.add(400, "", 5).unwrap();
+        let ts: Vec<i64> = tui.list().iter().map(|b| b.ts).collect();
+        assert_eq!(ts, vec![100, 200, 300, 400]);
+        assert_eq!(tui.list()[0].label, "deploy v2.3");
+        assert_eq!(tui.list()[2].label, "checkpoint storm (fixed)");
+        assert_eq!(tui.list()[2].created_at, 1);
+
+        assert_eq!(tui.next_after(200).map(|b| b.ts), Some(300));
+        assert_eq!(tui.prev_before(200).map(|b| b.ts), Some(100));
+        assert!(tui.next_after(400).is_none());
+
+        assert!(tui.remove(200).unwrap());
+        assert!(!tui.remove(200).unwrap());
+        web.reload().unwrap();
+        assert_eq!(web.list().len(), 3);
+    }
+
+    #[test]
+    fn broken_bookmarks_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(BOOKMARKS_FILE), "not json").unwrap();
+        let err = BookmarkStore::open(dir.path()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -9,14 +9,19 @@
 //! ```text
 //! ┌─────────────────────────────────────────────────────────┐
 //! │ HEADER (48 bytes, uncompressed)                         │
-//! │   magic: [u8; 4]              = b"RPG5"                 │
-//! │   version: u16                = 4                       │
+//! │   magic: [u8; 4]              = b"RPG8"                 │
+//! │   version: u16                = 8                       │
 //! │   snapshot_count: u16                                   │
 //! │   interner_offset: u64        (byte offset in file)     │
 //! │   interner_compressed_len: u64                          │
 //! │   dict_offset: u64            (byte offset in file)     │
 //! │   dict_len: u64               (raw dict size in bytes)  │
-//! │   _reserved: [u8; 4]          = [0; 4]                  │
+//! │   stripped_blocks: u32        (DataBlock kinds removed  │
+//! │                                by block retention)      │
+//! │   flags: u8                   (bit 0: encrypted)        │
+//! │   min_reader_version: u8      (oldest format version    │
+//! │                                that can read the chunk) │
+//! │   stripped_blocks_high: u16   (kinds 32..47)            │
 //! ├─────────────────────────────────────────────────────────┤
 //! │ INDEX TABLE (snapshot_count × 28 bytes, uncompressed)   │
 //! │   Per snapshot:                                         │
@@ -29,27 +34,160 @@
 //! │   zstd trained dictionary (~64-112 KB)                  │
 //! ├─────────────────────────────────────────────────────────┤
 //! │ SNAPSHOT FRAMES (each compressed WITH dictionary)       │
-//! │   zstd_dict(postcard(Snapshot_0))                        │
-//! │   zstd_dict(postcard(Snapshot_1))                       │
+//! │   zstd_dict(frame(Snapshot_0))                          │
+//! │   zstd_dict(frame(Snapshot_1))                          │
 //! │   ...                                                   │
 //! ├─────────────────────────────────────────────────────────┤
 //! │ INTERNER FRAME (one zstd frame, WITHOUT dictionary)     │
 //! │   zstd(postcard(StringInterner))                        │
 //! └─────────────────────────────────────────────────────────┘
 //! ```
+//!
+//! An uncompressed snapshot frame starts with a block index so that one
+//! block can be deserialized without decoding the others:
+//! ```text
+//! block_count: u16
+//! block_count × (kind: u8, schema_version: u8, start: u32, end: u32)
+//!                                                  (offsets into payload)
+//! payload: postcard(Snapshot)
+//! ```
+//! Postcard is not self-describing: a block of a kind or layout this build
+//! does not know cannot be decoded, and inside a single `postcard(Snapshot)`
+//! it would fail the whole snapshot. Readers therefore decode block by block
+//! through the index and skip blocks of unknown kinds or of a schema version
+//! newer than [`DataBlock::SCHEMA_VERSIONS`], so data written by a newer
+//! daemon stays readable by older builds (minus the blocks they do not
+//! understand). Chunks of a newer format version are read as long as their
+//! `min_reader_version` is not above this build's version.
+//!
+//! RPG7 chunks (block index without schema versions, all version 1) and
+//! RPG6 chunks (frames without the block index) are still readable; single
+//! block reads in RPG6 fall back to decoding the whole snapshot.
+//!
+//! When the chunk is encrypted (see [`crate::storage::crypto`]), the
+//! dictionary, every snapshot frame and the interner frame are AES-GCM
+//! encrypted after compression. Header and index stay in clear text, so
+//! timestamps are readable without the key.
+//!
+//! Chunks are written to a `.tmp` file and renamed into place; see
+//! [`ChunkWriteOptions`] for fsync and O_DIRECT.
+//!
+//! [`ChunkReader`] memory-maps the file instead of reading it whole: only the
+//! pages of the frames actually decoded become resident, and they are page
+//! cache rather than heap, so serving a large history directory does not
+//! grow the allocator's RSS. A published chunk is never modified in place
+//! (rewrites go through `.tmp` + rename), which keeps the mapping valid.
 
+use crate::storage::crypto::{self, EncryptionKey};
 use crate::storage::interner::StringInterner;
-use crate::storage::model::Snapshot;
+use crate::storage::model::{DataBlock, Snapshot, legacy};
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
 use std::fs;
 use std::io::{self, Read as _, Seek, SeekFrom, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tracing::warn;
 
-const MAGIC: [u8; 4] = *b"RPG6";
-const VERSION: u16 = 6;
+/// Alignment of O_DIRECT writes (logical block size of common devices).
+const DIRECT_IO_ALIGN: usize = 4096;
+
+/// Default zstd level for snapshot and interner frames.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// Default number of snapshots sampled for dictionary training.
+pub const DEFAULT_DICT_SAMPLES: usize = 20;
+
+/// How chunk files reach the disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkWriteOptions {
+    /// Fsync the file before renaming it into place.
+    pub sync: bool,
+    /// Also fsync the directory after the rename, so the new name survives
+    /// a power failure.
+    pub sync_dir: bool,
+    /// Write with O_DIRECT, bypassing the page cache so that chunk writes do
+    /// not evict PostgreSQL's cached pages. The chunk is built in memory
+    /// first. Falls back to buffered writes where O_DIRECT is unsupported.
+    pub direct_io: bool,
+    /// zstd compression level (1-22). Higher levels trade CPU for size.
+    pub level: i32,
+    /// Snapshots sampled (evenly spaced) to train the chunk dictionary;
+    /// 0 writes chunks without a dictionary.
+    pub dict_samples: usize,
+}
+
+impl Default for ChunkWriteOptions {
+    fn default() -> Self {
+        Self {
+            sync: true,
+            sync_dir: false,
+            direct_io: false,
+            level: DEFAULT_COMPRESSION_LEVEL,
+            dict_samples: DEFAULT_DICT_SAMPLES,
+        }
+    }
+}
+
+impl ChunkWriteOptions {
+    /// Every how many snapshots of `count` one is sampled for the dictionary.
+    pub fn sample_interval(&self, count: usize) -> usize {
+        (count / self.dict_samples.max(1)).max(1)
+    }
+}
+
+/// Trains a chunk dictionary on serialized snapshots. Empty when there are
+/// no samples or training fails (frames are then compressed without one).
+pub fn train_dictionary(samples: &[Vec<u8>]) -> Vec<u8> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    zstd::dict::from_samples(samples, DICT_MAX_SIZE).unwrap_or_default()
+}
+
+const MAGIC: [u8; 4] = *b"RPG8";
+/// Chunk format version written by this build.
+pub const VERSION: u16 = 8;
+/// Oldest format version whose readers can decode chunks written by this
+/// build. Raise it only when the frame layout changes in a way a reader of
+/// that version cannot skip.
+const MIN_READER_VERSION: u8 = 8;
+/// Previous format: same layout, block index without schema versions.
+const MAGIC_V7: [u8; 4] = *b"RPG7";
+const VERSION_V7: u16 = 7;
+/// Format before RPG7: frames without the block index.
+const MAGIC_V6: [u8; 4] = *b"RPG6";
+const VERSION_V6: u16 = 6;
+const BLOCK_INDEX_ENTRY_SIZE: usize = 10; // kind: u8 + schema_version: u8 + start: u32 + end: u32
+const BLOCK_INDEX_ENTRY_SIZE_V7: usize = 9; // kind: u8 + start: u32 + end: u32
+/// Upper bound for one decompressed snapshot frame. Caps the buffer
+/// allocated from the index `uncompressed_len` of a damaged chunk.
+const MAX_FRAME_LEN: usize = 256 * 1024 * 1024;
 const HEADER_SIZE: usize = 48;
 const INDEX_ENTRY_SIZE: usize = 28; // offset: u64 + compressed_len: u64 + timestamp: i64 + uncompressed_len: u32
-pub(crate) const DICT_MAX_SIZE: usize = 112 * 1024; // 112 KB
+pub const DICT_MAX_SIZE: usize = 112 * 1024; // 112 KB
+const FLAGS_OFFSET: usize = 44;
+const FLAG_ENCRYPTED: u8 = 1;
+const MIN_READER_VERSION_OFFSET: usize = 45;
+const STRIPPED_HIGH_OFFSET: usize = 46;
+
+/// Reads the stripped block mask: bits 0..32 at 40..44, bits 32..48 after the
+/// flags byte (zero in chunks written when there were fewer block kinds).
+fn read_stripped_blocks(header: &[u8]) -> u64 {
+    let low = u32::from_le_bytes(header[40..44].try_into().unwrap()) as u64;
+    let high = u16::from_le_bytes(
+        header[STRIPPED_HIGH_OFFSET..STRIPPED_HIGH_OFFSET + 2]
+            .try_into()
+            .unwrap(),
+    ) as u64;
+    low | high << 32
+}
+
+fn write_stripped_blocks(header: &mut [u8], mask: u64) {
+    header[40..44].copy_from_slice(&(mask as u32).to_le_bytes());
+    header[STRIPPED_HIGH_OFFSET..STRIPPED_HIGH_OFFSET + 2]
+        .copy_from_slice(&((mask >> 32) as u16).to_le_bytes());
+}
 
 /// Lightweight metadata from a chunk's header + index table.
 /// Reading this requires only ~10 KB of I/O (48-byte header + N × 28-byte index),
@@ -57,37 +195,163 @@ pub(crate) const DICT_MAX_SIZE: usize = 112 * 1024; // 112 KB
 pub struct ChunkMetadata {
     pub snapshot_count: usize,
     pub timestamps: Vec<i64>,
+    /// Bitmask of [`DataBlock::kind_index`](crate::storage::model::DataBlock::kind_index)
+    /// values stripped from this chunk by block retention.
+    pub stripped_blocks: u64,
+    /// Frames are encrypted; reading snapshots requires the key.
+    pub encrypted: bool,
 }
 
-/// Read only the chunk header + index table from disk.
-///
-/// Returns snapshot count and per-snapshot timestamps without loading the
-/// dictionary, snapshot data, or interner. This is ~1000× cheaper than
-/// `ChunkReader::open()` which reads the entire file via `fs::read()`.
-pub fn read_chunk_metadata(path: &Path) -> io::Result<ChunkMetadata> {
-    let mut file = fs::File::open(path)?;
-
-    // Read header (48 bytes)
-    let mut header = [0u8; HEADER_SIZE];
-    file.read_exact(&mut header)?;
+/// Layout of the uncompressed snapshot frames of a chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameLayout {
+    /// RPG6: plain `postcard(Snapshot)`.
+    Plain,
+    /// RPG7: block index without schema versions.
+    Indexed,
+    /// RPG8 and later: block index with a schema version per block.
+    Versioned,
+}
 
+/// Validates magic and version. Returns the layout of the chunk's frames.
+///
+/// Chunks of a newer format are accepted when their `min_reader_version`
+/// allows this build to read them.
+fn check_format(header: &[u8]) -> io::Result<FrameLayout> {
     let magic = &header[0..4];
-    if magic != MAGIC {
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if magic == MAGIC && version == VERSION {
+        return Ok(FrameLayout::Versioned);
+    }
+    if magic == MAGIC_V7 && version == VERSION_V7 {
+        return Ok(FrameLayout::Indexed);
+    }
+    if magic == MAGIC_V6 && version == VERSION_V6 {
+        return Ok(FrameLayout::Plain);
+    }
+    if magic[0..3] != *b"RPG" {
         return Err(io::Error::other(format!(
-            "invalid magic: expected RPG6, got {:?}",
+            "invalid magic: expected RPG8, got {:?}",
             magic
         )));
     }
-
-    let version = u16::from_le_bytes([header[4], header[5]]);
-    if version != VERSION {
+    let min_reader = header[MIN_READER_VERSION_OFFSET];
+    if version > VERSION && min_reader != 0 && min_reader as u16 <= VERSION {
+        return Ok(FrameLayout::Versioned);
+    }
+    if version > VERSION {
         return Err(io::Error::other(format!(
-            "unsupported version: {}",
-            version
+            "chunk format RPG{} requires a reader of RPG{} or newer (this build reads up to RPG{})",
+            version, min_reader, VERSION
         )));
     }
+    Err(io::Error::other(format!(
+        "unsupported version: {}",
+        version
+    )))
+}
+
+/// Serializes a snapshot into an uncompressed chunk frame: block index
+/// followed by `postcard(Snapshot)`.
+///
+/// Postcard encodes a `Vec` as a varint length followed by its elements, so
+/// serializing the timestamp, the block count and each block separately
+/// yields exactly `postcard(Snapshot)` while recording where each block lies.
+pub fn encode_snapshot_frame(snapshot: &Snapshot) -> io::Result<Vec<u8>> {
+    let mut payload = postcard::to_allocvec(&snapshot.timestamp).map_err(io::Error::other)?;
+    payload.extend(postcard::to_allocvec(&snapshot.blocks.len()).map_err(io::Error::other)?);
+    let mut entries = Vec::with_capacity(snapshot.blocks.len());
+    for block in &snapshot.blocks {
+        let start = payload.len() as u32;
+        payload.extend(postcard::to_allocvec(block).map_err(io::Error::other)?);
+        entries.push(BlockIndexEntry {
+            kind: block.kind_index() as u8,
+            version: block.schema_version(),
+            start,
+            end: payload.len() as u32,
+        });
+    }
+
+    let count = u16::try_from(entries.len())
+        .map_err(|_| io::Error::other("too many blocks in snapshot"))?;
+    let mut frame = Vec::with_capacity(2 + entries.len() * BLOCK_INDEX_ENTRY_SIZE + payload.len());
+    frame.extend_from_slice(&count.to_le_bytes());
+    for entry in entries {
+        frame.push(entry.kind);
+        frame.push(entry.version);
+        frame.extend_from_slice(&entry.start.to_le_bytes());
+        frame.extend_from_slice(&entry.end.to_le_bytes());
+    }
+    frame.extend(payload);
+    Ok(frame)
+}
+
+/// One block of a frame's block index, with offsets into the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BlockIndexEntry {
+    kind: u8,
+    /// Schema version of the block (see [`DataBlock::SCHEMA_VERSIONS`]).
+    version: u8,
+    start: u32,
+    end: u32,
+}
+
+impl BlockIndexEntry {
+    /// Whether this build can decode the block: its kind is known and its
+    /// schema version is not newer than ours.
+    fn is_readable(&self) -> bool {
+        DataBlock::SCHEMA_VERSIONS
+            .get(self.kind as usize)
+            .is_some_and(|&known| self.version <= known)
+    }
+}
+
+/// Splits an uncompressed frame of an indexed `layout` into its block index
+/// and postcard payload. RPG7 entries carry no schema version and get 1.
+fn split_frame(frame: &[u8], layout: FrameLayout) -> io::Result<(Vec<BlockIndexEntry>, &[u8])> {
+    let entry_size = match layout {
+        FrameLayout::Versioned => BLOCK_INDEX_ENTRY_SIZE,
+        FrameLayout::Indexed => BLOCK_INDEX_ENTRY_SIZE_V7,
+        FrameLayout::Plain => return Err(io::Error::other("frame has no block index")),
+    };
+    let too_small = || io::Error::other("snapshot frame too small for block index");
+    let count = u16::from_le_bytes(frame.get(0..2).ok_or_else(too_small)?.try_into().unwrap());
+    let index_end = 2 + count as usize * entry_size;
+    let index = frame.get(2..index_end).ok_or_else(too_small)?;
+    let entries = index
+        .chunks_exact(entry_size)
+        .map(|e| {
+            let (version, offsets) = match layout {
+                FrameLayout::Versioned => (e[1], &e[2..]),
+                _ => (1, &e[1..]),
+            };
+            BlockIndexEntry {
+                kind: e[0],
+                version,
+                start: u32::from_le_bytes(offsets[0..4].try_into().unwrap()),
+                end: u32::from_le_bytes(offsets[4..8].try_into().unwrap()),
+            }
+        })
+        .collect();
+    Ok((entries, &frame[index_end..]))
+}
+
+/// Read only the chunk header + index table from disk.
+///
+/// Returns snapshot count and per-snapshot timestamps without loading the
+/// dictionary, snapshot data, or interner. Cheaper than `ChunkReader::open()`,
+/// which maps the file and prepares the decoder dictionary.
+pub fn read_chunk_metadata(path: &Path) -> io::Result<ChunkMetadata> {
+    let mut file = fs::File::open(path)?;
+
+    // Read header (48 bytes)
+    let mut header = [0u8; HEADER_SIZE];
+    file.read_exact(&mut header)?;
+    check_format(&header)?;
 
     let snapshot_count = u16::from_le_bytes([header[6], header[7]]) as usize;
+    let stripped_blocks = read_stripped_blocks(&header);
+    let encrypted = header[FLAGS_OFFSET] & FLAG_ENCRYPTED != 0;
 
     // Read index table (snapshot_count × 28 bytes)
     let index_size = snapshot_count * INDEX_ENTRY_SIZE;
@@ -105,6 +369,8 @@ pub fn read_chunk_metadata(path: &Path) -> io::Result<ChunkMetadata> {
     Ok(ChunkMetadata {
         snapshot_count,
         timestamps,
+        stripped_blocks,
+        encrypted,
     })
 }
 
@@ -115,44 +381,89 @@ pub struct ChunkReader {
     index: Vec<(u64, u64, i64, u32)>,
     interner_offset: u64,
     interner_compressed_len: u64,
+    stripped_blocks: u64,
+    /// Layout of the snapshot frames.
+    layout: FrameLayout,
+    /// Blocks this build cannot decode were already reported.
+    warned_skipped: AtomicBool,
     /// Prepared decoder dictionary for fast repeated decompression.
     decoder_dict: zstd::dict::DecoderDictionary<'static>,
-    /// Raw file data kept in memory for reading individual frames.
-    data: Vec<u8>,
+    /// Key for decrypting frames (set only for encrypted chunks).
+    key: Option<EncryptionKey>,
+    /// The chunk file bytes.
+    data: ChunkData,
+}
+
+/// Bytes of an opened chunk: a read-only mapping of the file, or an owned
+/// buffer ([`ChunkReader::from_bytes`], and files in builds without `mmap`).
+enum ChunkData {
+    #[cfg(feature = "mmap")]
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl std::ops::Deref for ChunkData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            #[cfg(feature = "mmap")]
+            Self::Mapped(map) => map,
+            Self::Owned(bytes) => bytes,
+        }
+    }
 }
 
 impl ChunkReader {
     /// Opens a chunk file: reads header + index + dictionary (no snapshot decompression).
+    ///
+    /// Encrypted chunks are decrypted with the process-wide key
+    /// ([`crypto::installed_key`]).
     pub fn open(path: &Path) -> io::Result<Self> {
-        let data = fs::read(path)?;
+        Self::open_with_key(path, crypto::installed_key())
+    }
+
+    /// Same as [`ChunkReader::open`] with an explicit encryption key.
+    pub fn open_with_key(path: &Path, key: Option<&EncryptionKey>) -> io::Result<Self> {
+        #[cfg(feature = "mmap")]
+        let data = {
+            let file = fs::File::open(path)?;
+            // SAFETY: chunk files are immutable once renamed into place; rotation
+            // unlinks them, which keeps the mapped pages valid.
+            let data = unsafe { Mmap::map(&file)? };
+            #[cfg(unix)]
+            data.advise(memmap2::Advice::Random)?;
+            ChunkData::Mapped(data)
+        };
+        #[cfg(not(feature = "mmap"))]
+        let data = ChunkData::Owned(fs::read(path)?);
+        Self::parse(data, key)
+    }
+
+    /// Reads a chunk held in memory (e.g. a file dropped into the browser).
+    pub fn from_bytes(bytes: Vec<u8>, key: Option<&EncryptionKey>) -> io::Result<Self> {
+        Self::parse(ChunkData::Owned(bytes), key)
+    }
 
+    fn parse(data: ChunkData, key: Option<&EncryptionKey>) -> io::Result<Self> {
         if data.len() < HEADER_SIZE {
             return Err(io::Error::other("file too small for header"));
         }
 
         // Parse header
-        let magic = &data[0..4];
-        if magic != MAGIC {
-            return Err(io::Error::other(format!(
-                "invalid magic: expected RPG6, got {:?}",
-                magic
-            )));
-        }
-
-        let version = u16::from_le_bytes([data[4], data[5]]);
-        if version != VERSION {
-            return Err(io::Error::other(format!(
-                "unsupported version: {}",
-                version
-            )));
-        }
+        let layout = check_format(&data)?;
 
         let snapshot_count = u16::from_le_bytes([data[6], data[7]]) as usize;
         let interner_offset = u64::from_le_bytes(data[8..16].try_into().unwrap());
         let interner_compressed_len = u64::from_le_bytes(data[16..24].try_into().unwrap());
         let dict_offset = u64::from_le_bytes(data[24..32].try_into().unwrap());
         let dict_len = u64::from_le_bytes(data[32..40].try_into().unwrap());
-        // bytes 40..44 = reserved
+        let stripped_blocks = read_stripped_blocks(&data);
+        let key = if data[FLAGS_OFFSET] & FLAG_ENCRYPTED != 0 {
+            Some(crypto::require_key(key)?.clone())
+        } else {
+            None
+        };
 
         let index_size = snapshot_count * INDEX_ENTRY_SIZE;
         let expected_min = HEADER_SIZE + index_size;
@@ -174,34 +485,62 @@ impl ChunkReader {
 
         // Load dictionary
         let dict_start = dict_offset as usize;
-        let dict_end = dict_start + dict_len as usize;
-        if dict_end > data.len() {
-            return Err(io::Error::other("dictionary extends past end of file"));
-        }
-        let decoder_dict = zstd::dict::DecoderDictionary::copy(&data[dict_start..dict_end]);
+        let dict_end = dict_start
+            .checked_add(dict_len as usize)
+            .filter(|&end| end <= data.len())
+            .ok_or_else(|| io::Error::other("dictionary extends past end of file"))?;
+        let decoder_dict = match &key {
+            Some(key) => {
+                zstd::dict::DecoderDictionary::copy(&key.decrypt(&data[dict_start..dict_end])?)
+            }
+            None => zstd::dict::DecoderDictionary::copy(&data[dict_start..dict_end]),
+        };
 
         Ok(Self {
             snapshot_count,
             index,
             interner_offset,
             interner_compressed_len,
+            stripped_blocks,
+            layout,
+            warned_skipped: AtomicBool::new(false),
             decoder_dict,
+            key,
             data,
         })
     }
 
+    /// Frame bytes in `start..end`, decrypted if the chunk is encrypted.
+    fn frame(&self, start: usize, end: usize) -> io::Result<std::borrow::Cow<'_, [u8]>> {
+        let raw = &self.data[start..end];
+        match &self.key {
+            Some(key) => Ok(key.decrypt(raw)?.into()),
+            None => Ok(raw.into()),
+        }
+    }
+
     /// Returns the number of snapshots in this chunk.
     pub fn snapshot_count(&self) -> usize {
         self.snapshot_count
     }
 
+    /// Bitmask of DataBlock kinds stripped from this chunk by block retention.
+    pub fn stripped_blocks(&self) -> u64 {
+        self.stripped_blocks
+    }
+
+    /// Uncompressed size of snapshot frame `idx` in bytes (from the index).
+    pub fn frame_len(&self, idx: usize) -> Option<usize> {
+        self.index.get(idx).map(|(_, _, _, len)| *len as usize)
+    }
+
     /// Returns timestamps of all snapshots from the index table (no decompression).
     pub fn timestamps(&self) -> Vec<i64> {
         self.index.iter().map(|(_, _, ts, _)| *ts).collect()
     }
 
-    /// Reads and decompresses a single snapshot at the given index using the dictionary.
-    pub fn read_snapshot(&self, idx: usize) -> io::Result<Snapshot> {
+    /// Decompresses the raw frame of snapshot `idx` using the dictionary.
+    fn decompress_frame(&self, idx: usize) -> io::Result<Vec<u8>> {
         if idx >= self.snapshot_count {
             return Err(io::Error::other(format!(
                 "snapshot index {} out of range (count={})",
@@ -211,41 +550,140 @@ impl ChunkReader {
 
         let (offset, compressed_len, _timestamp, uncompressed_len) = self.index[idx];
         let start = offset as usize;
-        let end = start + compressed_len as usize;
-
-        if end > self.data.len() {
-            return Err(io::Error::other("snapshot frame extends past end of file"));
+        let end = start
+            .checked_add(compressed_len as usize)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| io::Error::other("snapshot frame extends past end of file"))?;
+        if uncompressed_len as usize > MAX_FRAME_LEN {
+            return Err(io::Error::other(format!(
+                "snapshot frame too large: {} bytes",
+                uncompressed_len
+            )));
         }
 
         let mut decompressor =
             zstd::bulk::Decompressor::with_prepared_dictionary(&self.decoder_dict)?;
-        let decompressed =
-            decompressor.decompress(&self.data[start..end], uncompressed_len as usize)?;
-        let snapshot: Snapshot = postcard::from_bytes(&decompressed).map_err(|e| {
+        decompressor.decompress(&self.frame(start, end)?, uncompressed_len as usize)
+    }
+
+    /// Reads and decompresses a single snapshot at the given index using the dictionary.
+    ///
+    /// In block-indexed chunks blocks are decoded one by one: blocks of
+    /// unknown kinds or newer schema versions, and blocks that fail to
+    /// decode, are left out of the snapshot instead of failing it.
+    pub fn read_snapshot(&self, idx: usize) -> io::Result<Snapshot> {
+        let decompressed = self.decompress_frame(idx)?;
+        if self.layout == FrameLayout::Plain {
+            let decoded =
+                legacy::take_snapshot(&decompressed, |rest| rest.is_empty().then_some(()));
+            let (snapshot, ()) = decoded.map_err(|e| {
+                warn!(
+                    idx,
+                    decompressed_len = decompressed.len(),
+                    error = %e,
+                    "chunk: snapshot deserialization failed"
+                );
+                io::Error::other(e)
+            })?;
+            snapshot.validate().map_err(|e| {
+                warn!(idx, error = %e, "chunk: invalid snapshot");
+                io::Error::other(e)
+            })?;
+            return Ok(snapshot);
+        }
+
+        let (entries, payload) = split_frame(&decompressed, self.layout)?;
+        let (timestamp, _) = postcard::take_from_bytes::<i64>(payload).map_err(|e| {
+            warn!(idx, error = %e, "chunk: snapshot timestamp deserialization failed");
+            io::Error::other(e)
+        })?;
+        let mut blocks = Vec::with_capacity(entries.len());
+        for entry in entries {
+            if !entry.is_readable() {
+                self.warn_skipped(idx, entry);
+                continue;
+            }
+            // decode_block logs the failure
+            if let Ok(block) = decode_block(idx, payload, entry) {
+                blocks.push(block);
+            }
+        }
+        Ok(Snapshot { timestamp, blocks })
+    }
+
+    /// Logs (once per chunk) a block this build cannot decode.
+    fn warn_skipped(&self, idx: usize, entry: BlockIndexEntry) {
+        if !self.warned_skipped.swap(true, Ordering::Relaxed) {
             warn!(
                 idx,
-                compressed_len = compressed_len,
-                uncompressed_len,
-                decompressed_len = decompressed.len(),
-                error = %e,
-                "chunk: snapshot deserialization failed"
+                kind = entry.kind,
+                schema_version = entry.version,
+                "chunk: skipping blocks written by a newer rpglot"
             );
-            io::Error::other(e)
-        })?;
+        }
+    }
 
-        Ok(snapshot)
+    /// Reads only the first block of kind `kind` (see
+    /// [`DataBlock::kind_index`]) of snapshot `idx`, skipping deserialization
+    /// of the other blocks. RPG6 chunks decode the whole snapshot. A block
+    /// of a newer schema version than this build knows reads as absent.
+    pub fn read_block(&self, idx: usize, kind: usize) -> io::Result<Option<DataBlock>> {
+        if self.layout == FrameLayout::Plain {
+            let snapshot = self.read_snapshot(idx)?;
+            return Ok(snapshot.blocks.into_iter().find(|b| b.kind_index() == kind));
+        }
+
+        let decompressed = self.decompress_frame(idx)?;
+        let (entries, payload) = split_frame(&decompressed, self.layout)?;
+        let Some(&entry) = entries.iter().find(|e| e.kind as usize == kind) else {
+            return Ok(None);
+        };
+        if !entry.is_readable() {
+            self.warn_skipped(idx, entry);
+            return Ok(None);
+        }
+        decode_block(idx, payload, entry).map(Some)
+    }
+
+    /// Reads the blocks of the given kinds of snapshot `idx` in frame order,
+    /// decompressing the frame once and skipping deserialization of the other
+    /// blocks. RPG6 chunks decode the whole snapshot. Blocks of a newer
+    /// schema version than this build knows are left out.
+    pub fn read_blocks(&self, idx: usize, kinds: &[usize]) -> io::Result<Vec<DataBlock>> {
+        if self.layout == FrameLayout::Plain {
+            let snapshot = self.read_snapshot(idx)?;
+            return Ok(snapshot
+                .blocks
+                .into_iter()
+                .filter(|b| kinds.contains(&b.kind_index()))
+                .collect());
+        }
+
+        let decompressed = self.decompress_frame(idx)?;
+        let (entries, payload) = split_frame(&decompressed, self.layout)?;
+        entries
+            .into_iter()
+            .filter(|e| kinds.contains(&(e.kind as usize)))
+            .filter(|&e| {
+                let readable = e.is_readable();
+                if !readable {
+                    self.warn_skipped(idx, e);
+                }
+                readable
+            })
+            .map(|e| decode_block(idx, payload, e))
+            .collect()
     }
 
     /// Reads and decompresses the interner frame (no dictionary — different data structure).
     pub fn read_interner(&self) -> io::Result<StringInterner> {
         let start = self.interner_offset as usize;
-        let end = start + self.interner_compressed_len as usize;
+        let end = start
+            .checked_add(self.interner_compressed_len as usize)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| io::Error::other("interner frame extends past end of file"))?;
 
-        if end > self.data.len() {
-            return Err(io::Error::other("interner frame extends past end of file"));
-        }
-
-        let decompressed = zstd::decode_all(&self.data[start..end])?;
+        let decompressed = zstd::decode_all(&self.frame(start, end)?[..])?;
         let interner: StringInterner = postcard::from_bytes(&decompressed).map_err(|e| {
             warn!(
                 decompressed_len = decompressed.len(),
@@ -259,17 +697,45 @@ impl ChunkReader {
     }
 }
 
+/// Deserializes the block of `entry` from a frame payload.
+fn decode_block(idx: usize, payload: &[u8], entry: BlockIndexEntry) -> io::Result<DataBlock> {
+    let bytes = payload
+        .get(entry.start as usize..entry.end as usize)
+        .ok_or_else(|| {
+            warn!(
+                idx,
+                kind = entry.kind,
+                "chunk: block extends past end of frame"
+            );
+            io::Error::other("block extends past end of frame")
+        })?;
+    let block = legacy::decode_block(entry.kind as usize, entry.version, bytes).map_err(|e| {
+        warn!(idx, kind = entry.kind, error = %e, "chunk: block deserialization failed");
+        io::Error::other(e)
+    })?;
+    block.validate().map_err(|e| {
+        warn!(idx, kind = entry.kind, error = %e, "chunk: invalid block");
+        io::Error::other(e)
+    })?;
+    Ok(block)
+}
+
 /// Internal: writes a chunk file given a pre-trained dictionary and a callback
 /// that provides serialized snapshot data one at a time.
 ///
 /// `get_raw_snapshot(index)` must return `(serialized_bytes, timestamp)`.
+/// With `key`, the dictionary and all frames are encrypted after compression.
 /// The file is written atomically via a `.tmp` intermediate file.
+#[allow(clippy::too_many_arguments)]
 fn write_chunk_inner<F>(
     path: &Path,
     snapshot_count: usize,
     dictionary: &[u8],
-    mut get_raw_snapshot: F,
+    get_raw_snapshot: F,
     interner: &StringInterner,
+    stripped_blocks: u64,
+    key: Option<&EncryptionKey>,
+    options: ChunkWriteOptions,
 ) -> io::Result<()>
 where
     F: FnMut(usize) -> io::Result<(Vec<u8>, i64)>,
@@ -282,9 +748,68 @@ where
     }
 
     let tmp_path = path.with_extension("tmp");
-    let mut file = fs::File::create(&tmp_path)?;
+    if options.direct_io {
+        let mut buf = io::Cursor::new(Vec::new());
+        write_chunk_body(
+            &mut buf,
+            snapshot_count,
+            dictionary,
+            get_raw_snapshot,
+            interner,
+            stripped_blocks,
+            key,
+            options.level,
+        )?;
+        write_direct(&tmp_path, buf.get_ref(), options.sync)?;
+    } else {
+        let mut file = fs::File::create(&tmp_path)?;
+        write_chunk_body(
+            &mut file,
+            snapshot_count,
+            dictionary,
+            get_raw_snapshot,
+            interner,
+            stripped_blocks,
+            key,
+            options.level,
+        )?;
+        if options.sync {
+            file.sync_all()?;
+        }
+    }
 
+    // Atomic rename
+    fs::rename(tmp_path, path)?;
+    if options.sync_dir
+        && let Some(dir) = path.parent()
+    {
+        fs::File::open(dir)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+/// Writes the chunk layout (header, index, dictionary, frames, interner) to `file`.
+#[allow(clippy::too_many_arguments)]
+fn write_chunk_body<W, F>(
+    file: &mut W,
+    snapshot_count: usize,
+    dictionary: &[u8],
+    mut get_raw_snapshot: F,
+    interner: &StringInterner,
+    stripped_blocks: u64,
+    key: Option<&EncryptionKey>,
+    level: i32,
+) -> io::Result<()>
+where
+    W: Write + Seek,
+    F: FnMut(usize) -> io::Result<(Vec<u8>, i64)>,
+{
     let count = snapshot_count as u16;
+    let seal = |frame: Vec<u8>| match key {
+        Some(key) => key.encrypt(&frame),
+        None => Ok(frame),
+    };
 
     // Write placeholder header (will be updated later)
     file.write_all(&[0u8; HEADER_SIZE])?;
@@ -295,17 +820,18 @@ where
 
     // Write dictionary (raw bytes, not zstd compressed)
     let dict_offset = file.stream_position()?;
-    let dict_len = dictionary.len() as u64;
-    file.write_all(dictionary)?;
+    let stored_dict = seal(dictionary.to_vec())?;
+    let dict_len = stored_dict.len() as u64;
+    file.write_all(&stored_dict)?;
 
     // Compress and write each snapshot WITH dictionary
-    let mut compressor = zstd::bulk::Compressor::with_dictionary(3, dictionary)?;
+    let mut compressor = zstd::bulk::Compressor::with_dictionary(level, dictionary)?;
     let mut index_entries: Vec<(u64, u64, i64, u32)> = Vec::with_capacity(snapshot_count);
 
     for i in 0..snapshot_count {
         let (raw, timestamp) = get_raw_snapshot(i)?;
         let offset = file.stream_position()?;
-        let compressed = compressor.compress(&raw)?;
+        let compressed = seal(compressor.compress(&raw)?)?;
         file.write_all(&compressed)?;
         index_entries.push((offset, compressed.len() as u64, timestamp, raw.len() as u32));
     }
@@ -313,7 +839,7 @@ where
     // Write interner frame (without dictionary)
     let interner_offset = file.stream_position()?;
     let raw_interner = postcard::to_allocvec(interner).map_err(io::Error::other)?;
-    let compressed_interner = zstd::encode_all(&raw_interner[..], 3)?;
+    let compressed_interner = seal(zstd::encode_all(&raw_interner[..], level)?)?;
     let interner_compressed_len = compressed_interner.len() as u64;
     file.write_all(&compressed_interner)?;
 
@@ -323,11 +849,16 @@ where
     let mut header = [0u8; HEADER_SIZE];
     header[0..4].copy_from_slice(&MAGIC);
     header[4..6].copy_from_slice(&VERSION.to_le_bytes());
+    header[MIN_READER_VERSION_OFFSET] = MIN_READER_VERSION;
     header[6..8].copy_from_slice(&count.to_le_bytes());
     header[8..16].copy_from_slice(&interner_offset.to_le_bytes());
     header[16..24].copy_from_slice(&interner_compressed_len.to_le_bytes());
     header[24..32].copy_from_slice(&dict_offset.to_le_bytes());
     header[32..40].copy_from_slice(&dict_len.to_le_bytes());
+    write_stripped_blocks(&mut header, stripped_blocks);
+    if key.is_some() {
+        header[FLAGS_OFFSET] = FLAG_ENCRYPTED;
+    }
     file.write_all(&header)?;
 
     // Write real index
@@ -338,12 +869,55 @@ where
         file.write_all(&uncompressed_len.to_le_bytes())?;
     }
 
-    file.sync_all()?;
-    drop(file);
+    Ok(())
+}
 
-    // Atomic rename
-    fs::rename(tmp_path, path)?;
+/// Writes `data` to `path` with O_DIRECT from a block-aligned buffer, then
+/// trims the padding of the last block.
+#[cfg(target_os = "linux")]
+fn write_direct(path: &Path, data: &[u8], sync: bool) -> io::Result<()> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = match fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(path)
+    {
+        Ok(file) => file,
+        // tmpfs and some network filesystems reject O_DIRECT
+        Err(e) if e.raw_os_error() == Some(libc::EINVAL) => {
+            warn!(path = %path.display(), "O_DIRECT not supported, writing chunk buffered");
+            return write_buffered(path, data, sync);
+        }
+        Err(e) => return Err(e),
+    };
+
+    let padded_len = data.len().next_multiple_of(DIRECT_IO_ALIGN);
+    let mut buf = vec![0u8; padded_len + DIRECT_IO_ALIGN];
+    let start = buf.as_ptr().align_offset(DIRECT_IO_ALIGN);
+    let aligned = &mut buf[start..start + padded_len];
+    aligned[..data.len()].copy_from_slice(data);
+    file.write_all(aligned)?;
+    file.set_len(data.len() as u64)?;
+    if sync {
+        file.sync_all()?;
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn write_direct(path: &Path, data: &[u8], sync: bool) -> io::Result<()> {
+    write_buffered(path, data, sync)
+}
 
+fn write_buffered(path: &Path, data: &[u8], sync: bool) -> io::Result<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(data)?;
+    if sync {
+        file.sync_all()?;
+    }
     Ok(())
 }
 
@@ -351,7 +925,8 @@ where
 ///
 /// A zstd dictionary is trained on all snapshots, then each snapshot is compressed
 /// with that dictionary for O(1) random access with cross-snapshot redundancy.
-/// The interner is compressed without the dictionary.
+/// The interner is compressed without the dictionary. Frames are encrypted
+/// if a process-wide key is installed.
 ///
 /// The file is written atomically via a `.tmp` intermediate file.
 pub fn write_chunk(
@@ -359,10 +934,10 @@ pub fn write_chunk(
     snapshots: &[Snapshot],
     interner: &StringInterner,
 ) -> io::Result<()> {
-    // Serialize all snapshots to postcard
+    // Serialize all snapshots into block-indexed frames
     let raw_snapshots: Vec<Vec<u8>> = snapshots
         .iter()
-        .map(|s| postcard::to_allocvec(s).map_err(io::Error::other))
+        .map(encode_snapshot_frame)
         .collect::<Result<_, _>>()?;
 
     // Train dictionary on all serialized snapshots.
@@ -376,6 +951,9 @@ pub fn write_chunk(
         &dictionary,
         |i| Ok((raw_snapshots[i].clone(), snapshots[i].timestamp)),
         interner,
+        0,
+        crypto::installed_key(),
+        ChunkWriteOptions::default(),
     )
 }
 
@@ -387,11 +965,37 @@ pub fn write_chunk(
 /// dictionary, and written sequentially. The file is written atomically via a `.tmp`
 /// intermediate file.
 pub fn write_chunk_with_trained_dict<F>(
+    path: &Path,
+    snapshot_count: usize,
+    dictionary: &[u8],
+    load_snapshot: F,
+    interner: &StringInterner,
+    options: ChunkWriteOptions,
+) -> io::Result<()>
+where
+    F: FnMut(usize) -> io::Result<Snapshot>,
+{
+    write_stripped_chunk(
+        path,
+        snapshot_count,
+        dictionary,
+        load_snapshot,
+        interner,
+        0,
+        options,
+    )
+}
+
+/// Same as [`write_chunk_with_trained_dict`], recording in the header which
+/// DataBlock kinds (bitmask of `kind_index`) were stripped from the snapshots.
+pub fn write_stripped_chunk<F>(
     path: &Path,
     snapshot_count: usize,
     dictionary: &[u8],
     mut load_snapshot: F,
     interner: &StringInterner,
+    stripped_blocks: u64,
+    options: ChunkWriteOptions,
 ) -> io::Result<()>
 where
     F: FnMut(usize) -> io::Result<Snapshot>,
@@ -402,17 +1006,299 @@ where
         dictionary,
         |i| {
             let snapshot = load_snapshot(i)?;
-            let raw = postcard::to_allocvec(&snapshot).map_err(io::Error::other)?;
+            let raw = encode_snapshot_frame(&snapshot)?;
             Ok((raw, snapshot.timestamp))
         },
         interner,
+        stripped_blocks,
+        crypto::installed_key(),
+        options,
     )
 }
 
+/// Outcome of [`repair_chunk`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkRepair {
+    /// Snapshots listed in the header index (0 if the header is unreadable).
+    pub indexed: usize,
+    /// Snapshots written to the repaired chunk.
+    pub recovered: usize,
+    /// The index was damaged and frames were located by scanning the file.
+    pub scanned: bool,
+    /// The interner frame was unreadable: recovered snapshots keep their
+    /// string hashes but the strings are gone.
+    pub interner_lost: bool,
+}
+
+/// zstd frame magic number (little endian).
+const ZSTD_FRAME_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+/// zstd dictionary magic number (little endian).
+const ZSTD_DICT_MAGIC: [u8; 4] = [0x37, 0xA4, 0x30, 0xEC];
+
+/// Decompresses one zstd frame (with `dictionary`, possibly empty) without
+/// trusting any stored length.
+fn decompress_unsized(frame: &[u8], dictionary: &[u8]) -> io::Result<Vec<u8>> {
+    let decoder = zstd::stream::read::Decoder::with_dictionary(frame, dictionary)?;
+    let mut out = Vec::new();
+    decoder.take(MAX_FRAME_LEN as u64).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+/// Deserializes `bytes` as a whole: trailing bytes mean the frame holds
+/// something else (a scanned frame may be a snapshot or the interner).
+fn decode_exact<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    match postcard::take_from_bytes(bytes) {
+        Ok((value, [])) => Some(value),
+        _ => None,
+    }
+}
+
+/// Decodes a snapshot filling `bytes`, in any block layout this build reads.
+fn decode_snapshot_exact(bytes: &[u8]) -> Option<Snapshot> {
+    legacy::take_snapshot(bytes, |rest| rest.is_empty().then_some(()))
+        .ok()
+        .map(|(snapshot, ())| snapshot)
+}
+
+/// Decodes an uncompressed snapshot frame of the given layout, or of any
+/// layout when unknown (newest first).
+fn decode_any_frame(raw: &[u8], layout: Option<FrameLayout>) -> Option<Snapshot> {
+    let layouts = match layout {
+        Some(layout) => vec![layout],
+        None => vec![
+            FrameLayout::Versioned,
+            FrameLayout::Indexed,
+            FrameLayout::Plain,
+        ],
+    };
+    layouts
+        .into_iter()
+        .find_map(|layout| match layout {
+            FrameLayout::Plain => decode_snapshot_exact(raw),
+            _ => decode_snapshot_exact(split_frame(raw, layout).ok()?.1),
+        })
+        .filter(|snapshot: &Snapshot| snapshot.validate().is_ok())
+}
+
+/// Salvages the readable snapshots of a damaged chunk at `path` and writes
+/// them, with the interner and stripped-blocks mask, as a consistent chunk at
+/// `out` (atomically, via `.tmp`).
+///
+/// Frames listed in the header index are decoded first. When some of them
+/// are unreadable (or the header is), an unencrypted chunk is also scanned
+/// for zstd frames, so snapshots behind a damaged index entry are found by
+/// content; the scan wins if it recovers more. Encrypted frames carry no
+/// visible magic, so encrypted chunks are salvaged through the index only.
+/// Fails if no snapshot could be recovered.
+pub fn repair_chunk(path: &Path, out: &Path) -> io::Result<ChunkRepair> {
+    let data = fs::read(path)?;
+    let mut repair = ChunkRepair::default();
+
+    let header_ok = data.len() >= HEADER_SIZE && check_format(&data).is_ok();
+    let layout = if header_ok {
+        check_format(&data).ok()
+    } else {
+        None
+    };
+    let field = |range: std::ops::Range<usize>| u64::from_le_bytes(data[range].try_into().unwrap());
+    let key = if header_ok && data[FLAGS_OFFSET] & FLAG_ENCRYPTED != 0 {
+        Some(crypto::require_key(crypto::installed_key())?.clone())
+    } else {
+        None
+    };
+    let open = |range: std::ops::Range<usize>| -> Option<Vec<u8>> {
+        let raw = data.get(range)?;
+        match &key {
+            Some(key) => key.decrypt(raw).ok(),
+            None => Some(raw.to_vec()),
+        }
+    };
+    let stripped_blocks = if header_ok {
+        read_stripped_blocks(&data)
+    } else {
+        0
+    };
+
+    // Dictionary: from the header, else the first dictionary magic in the file
+    let header_dict = header_ok
+        .then(|| {
+            let start = field(24..32) as usize;
+            let len = field(32..40) as usize;
+            open(start..start.checked_add(len)?)
+        })
+        .flatten();
+    let dictionary = match header_dict {
+        Some(dict) => dict,
+        None if key.is_none() => data
+            .windows(4)
+            .position(|w| w == ZSTD_DICT_MAGIC)
+            .map(|start| {
+                let end = data[start..]
+                    .windows(4)
+                    .position(|w| w == ZSTD_FRAME_MAGIC)
+                    .map_or(data.len(), |len| start + len);
+                data[start..end].to_vec()
+            })
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    // Pass 1: the header index
+    let mut indexed: Vec<Snapshot> = Vec::new();
+    if header_ok {
+        repair.indexed = u16::from_le_bytes([data[6], data[7]]) as usize;
+        for i in 0..repair.indexed {
+            let base = HEADER_SIZE + i * INDEX_ENTRY_SIZE;
+            let Some(entry) = data.get(base..base + INDEX_ENTRY_SIZE) else {
+                break;
+            };
+            let offset = u64::from_le_bytes(entry[0..8].try_into().unwrap()) as usize;
+            let len = u64::from_le_bytes(entry[8..16].try_into().unwrap()) as usize;
+            let snapshot = offset
+                .checked_add(len)
+                .and_then(|end| open(offset..end))
+                .and_then(|frame| decompress_unsized(&frame, &dictionary).ok())
+                .and_then(|raw| decode_any_frame(&raw, layout));
+            indexed.extend(snapshot);
+        }
+    }
+    let mut interner = header_ok
+        .then(|| {
+            let start = field(8..16) as usize;
+            let frame = open(start..start.checked_add(field(16..24) as usize)?)?;
+            let raw = zstd::decode_all(&frame[..]).ok()?;
+            decode_exact::<StringInterner>(&raw)
+        })
+        .flatten();
+
+    // Pass 2: scan for frames by magic
+    let mut snapshots = indexed;
+    if key.is_none() && (!header_ok || snapshots.len() < repair.indexed || interner.is_none()) {
+        let mut scanned: Vec<Snapshot> = Vec::new();
+        let mut pos = 0;
+        while let Some(found) = data[pos..].windows(4).position(|w| w == ZSTD_FRAME_MAGIC) {
+            let start = pos + found;
+            let len = zstd::zstd_safe::find_frame_compressed_size(&data[start..])
+                .ok()
+                .filter(|&len| len > 0);
+            let Some(len) = len else {
+                pos = start + 1;
+                continue;
+            };
+            let frame = &data[start..start + len];
+            if let Some(snapshot) = decompress_unsized(frame, &dictionary)
+                .ok()
+                .and_then(|raw| decode_any_frame(&raw, layout))
+            {
+                scanned.push(snapshot);
+            } else if interner.is_none()
+                && let Ok(raw) = zstd::decode_all(frame)
+                && let Some(found) = decode_exact::<StringInterner>(&raw)
+            {
+                interner = Some(found);
+            }
+            pos = start + len;
+        }
+        if scanned.len() > snapshots.len() {
+            repair.scanned = true;
+            snapshots = scanned;
+        }
+    }
+
+    snapshots.sort_by_key(|s| s.timestamp);
+    snapshots.dedup_by_key(|s| s.timestamp);
+    if snapshots.is_empty() {
+        return Err(io::Error::other("no readable snapshots in chunk"));
+    }
+    repair.recovered = snapshots.len();
+    repair.interner_lost = interner.is_none();
+    let interner = interner.unwrap_or_default();
+    let dictionary = if dictionary.is_empty() {
+        let samples: Vec<Vec<u8>> = snapshots
+            .iter()
+            .map(encode_snapshot_frame)
+            .collect::<Result<_, _>>()?;
+        train_dictionary(&samples)
+    } else {
+        dictionary
+    };
+
+    write_chunk_inner(
+        out,
+        snapshots.len(),
+        &dictionary,
+        |i| {
+            Ok((
+                encode_snapshot_frame(&snapshots[i])?,
+                snapshots[i].timestamp,
+            ))
+        },
+        &interner,
+        stripped_blocks,
+        key.as_ref(),
+        ChunkWriteOptions::default(),
+    )?;
+    Ok(repair)
+}
+
+/// Upgrades the chunk at `path` to the current format, writing it to `out`
+/// (atomically, via `.tmp`; `out` may equal `path` for an in-place upgrade).
+///
+/// Returns the format version the chunk was upgraded from, or `None` if it
+/// is already current (nothing is written then). Snapshots are re-encoded
+/// with a freshly trained dictionary; the interner, stripped-blocks mask and
+/// encryption are kept. RPG6 is the oldest format this build reads: earlier
+/// chunks are rejected, and so are chunks of a newer format (downgrading
+/// would drop the blocks this build does not know).
+pub fn migrate_chunk(path: &Path, out: &Path) -> io::Result<Option<u16>> {
+    let mut header = [0u8; HEADER_SIZE];
+    fs::File::open(path)?.read_exact(&mut header)?;
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if header[0..3] == *b"RPG" && version < VERSION_V6 {
+        return Err(io::Error::other(format!(
+            "chunk format RPG{} predates RPG{}, the oldest format this build reads",
+            version, VERSION_V6
+        )));
+    }
+    check_format(&header)?;
+    if version > VERSION {
+        return Err(io::Error::other(format!(
+            "chunk format RPG{} is newer than this build (RPG{})",
+            version, VERSION
+        )));
+    }
+    if version == VERSION {
+        return Ok(None);
+    }
+
+    let reader = ChunkReader::open(path)?;
+    let snapshots = (0..reader.snapshot_count())
+        .map(|i| reader.read_snapshot(i))
+        .collect::<io::Result<Vec<_>>>()?;
+    let interner = reader.read_interner()?;
+    let frames: Vec<Vec<u8>> = snapshots
+        .iter()
+        .map(encode_snapshot_frame)
+        .collect::<Result<_, _>>()?;
+    let dictionary = train_dictionary(&frames);
+
+    write_chunk_inner(
+        out,
+        snapshots.len(),
+        &dictionary,
+        |i| Ok((frames[i].clone(), snapshots[i].timestamp)),
+        &interner,
+        reader.stripped_blocks(),
+        reader.key.as_ref(),
+        ChunkWriteOptions::default(),
+    )?;
+    Ok(Some(version))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::storage::model::{DataBlock, ProcessInfo};
+    use crate::storage::model::{AgentStatsInfo, ProcessInfo};
     use tempfile::tempdir;
 
     fn create_test_snapshots(count: usize) -> Vec<Snapshot> {
@@ -561,7 +1447,7 @@ mod tests {
         // Path B: write_chunk_with_trained_dict (pre-train dict, callback)
         let raw_snapshots: Vec<Vec<u8>> = snapshots
             .iter()
-            .map(|s| postcard::to_allocvec(s).unwrap())
+            .map(|s| encode_snapshot_frame(s).unwrap())
             .collect();
         let dictionary =
             zstd::dict::from_samples(&raw_snapshots, DICT_MAX_SIZE).unwrap_or_default();
@@ -571,6 +1457,7 @@ mod tests {
             &dictionary,
             |i| Ok(snapshots[i].clone()),
             &interner,
+            ChunkWriteOptions::default(),
         )
         .unwrap();
 
@@ -638,4 +1525,541 @@ mod tests {
             assert_eq!(snap.timestamp, 1000 + i as i64 * 10);
         }
     }
+
+    #[test]
+    fn test_compression_level_roundtrip() {
+        let dir = tempdir().unwrap();
+        let snapshots = create_test_snapshots(5);
+        let interner = StringInterner::new();
+        let raw: Vec<Vec<u8>> = snapshots
+            .iter()
+            .map(|s| encode_snapshot_frame(s).unwrap())
+            .collect();
+        let dictionary = train_dictionary(&raw);
+
+        for level in [1, 19] {
+            let path = dir.path().join(format!("level{level}.zst"));
+            let options = ChunkWriteOptions {
+                level,
+                ..ChunkWriteOptions::default()
+            };
+            write_chunk_with_trained_dict(
+                &path,
+                snapshots.len(),
+                &dictionary,
+                |i| Ok(snapshots[i].clone()),
+                &interner,
+                options,
+            )
+            .unwrap();
+            let reader = ChunkReader::open(&path).unwrap();
+            for (i, expected) in snapshots.iter().enumerate() {
+                assert_eq!(&reader.read_snapshot(i).unwrap(), expected);
+            }
+        }
+
+        assert!(train_dictionary(&[]).is_empty());
+        let options = ChunkWriteOptions::default();
+        assert_eq!(options.sample_interval(100), 5);
+        assert_eq!(options.sample_interval(7), 1);
+        let none = ChunkWriteOptions {
+            dict_samples: 0,
+            ..options
+        };
+        assert_eq!(none.sample_interval(100), 100);
+    }
+
+    fn multi_block_snapshot(ts: i64) -> Snapshot {
+        Snapshot {
+            timestamp: ts,
+            blocks: vec![
+                DataBlock::Processes(vec![ProcessInfo {
+                    pid: 7,
+                    ..ProcessInfo::default()
+                }]),
+                DataBlock::AgentStats(AgentStatsInfo {
+                    pid: 42,
+                    rss_kb: ts as u64,
+                    ..AgentStatsInfo::default()
+                }),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_frame_payload_is_postcard_snapshot() {
+        let snapshot = multi_block_snapshot(-5);
+        let frame = encode_snapshot_frame(&snapshot).unwrap();
+        let (entries, payload) = split_frame(&frame, FrameLayout::Versioned).unwrap();
+        assert_eq!(payload, postcard::to_allocvec(&snapshot).unwrap());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, 0);
+        assert_eq!(entries[1].kind, 35);
+        assert_eq!(entries[1].version, snapshot.blocks[1].schema_version());
+    }
+
+    /// Builds a frame from `(kind, schema_version, block bytes)` entries.
+    fn build_frame(timestamp: i64, blocks: &[(u8, u8, Vec<u8>)]) -> Vec<u8> {
+        let mut payload = postcard::to_allocvec(&timestamp).unwrap();
+        payload.extend(postcard::to_allocvec(&blocks.len()).unwrap());
+        let mut frame = (blocks.len() as u16).to_le_bytes().to_vec();
+        for (kind, version, bytes) in blocks {
+            frame.extend([*kind, *version]);
+            frame.extend((payload.len() as u32).to_le_bytes());
+            payload.extend(bytes);
+            frame.extend((payload.len() as u32).to_le_bytes());
+        }
+        frame.extend(payload);
+        frame
+    }
+
+    #[test]
+    fn test_read_snapshot_skips_unreadable_blocks() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let snapshot = multi_block_snapshot(100);
+        let encoded: Vec<Vec<u8>> = snapshot
+            .blocks
+            .iter()
+            .map(|b| postcard::to_allocvec(b).unwrap())
+            .collect();
+        let frame = build_frame(
+            100,
+            &[
+                (0, 1, encoded[0].clone()),
+                // Kind unknown to this build
+                (200, 1, vec![1, 2, 3]),
+                // Known kind with a newer schema version
+                (35, 99, encoded[1].clone()),
+                // Known kind that fails to decode
+                (8, 1, vec![0xff]),
+                (35, 1, encoded[1].clone()),
+            ],
+        );
+        write_chunk_inner(
+            &path,
+            1,
+            &[],
+            |_| Ok((frame.clone(), 100)),
+            &StringInterner::new(),
+            0,
+            None,
+            ChunkWriteOptions::default(),
+        )
+        .unwrap();
+
+        let reader = ChunkReader::open(&path).unwrap();
+        assert_eq!(reader.read_snapshot(0).unwrap(), snapshot);
+        assert_eq!(
+            reader.read_blocks(0, &[200, 0]).unwrap(),
+            vec![snapshot.blocks[0].clone()]
+        );
+        // The first kind-35 block is newer than this build
+        assert_eq!(reader.read_block(0, 35).unwrap(), None);
+        assert!(reader.read_block(0, 8).is_err());
+    }
+
+    #[test]
+    fn test_read_newer_format_chunk() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let snapshots: Vec<Snapshot> = (0..2).map(|i| multi_block_snapshot(100 + i)).collect();
+        write_chunk(&path, &snapshots, &StringInterner::new()).unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes[MIN_READER_VERSION_OFFSET], MIN_READER_VERSION);
+
+        // A newer writer that keeps the frame layout readable by this build
+        bytes[0..4].copy_from_slice(b"RPG9");
+        bytes[4..6].copy_from_slice(&9u16.to_le_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+        let reader = ChunkReader::open(&path).unwrap();
+        assert_eq!(reader.read_snapshot(1).unwrap(), snapshots[1]);
+        let out = dir.path().join("out.zst");
+        assert!(migrate_chunk(&path, &out).is_err());
+
+        // A newer writer that requires a newer reader
+        bytes[MIN_READER_VERSION_OFFSET] = 9;
+        std::fs::write(&path, &bytes).unwrap();
+        let err = ChunkReader::open(&path).err().unwrap();
+        assert!(err.to_string().contains("requires a reader of RPG9"));
+    }
+
+    #[test]
+    fn test_read_block() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let snapshots: Vec<Snapshot> = (0..3).map(|i| multi_block_snapshot(100 + i)).collect();
+        write_chunk(&path, &snapshots, &StringInterner::new()).unwrap();
+
+        let reader = ChunkReader::open(&path).unwrap();
+        assert_eq!(
+            reader.read_block(1, 35).unwrap(),
+            Some(snapshots[1].blocks[1].clone())
+        );
+        assert_eq!(
+            reader.read_block(2, 0).unwrap(),
+            Some(snapshots[2].blocks[0].clone())
+        );
+        assert_eq!(reader.read_block(0, 2).unwrap(), None);
+        assert!(reader.read_block(3, 0).is_err());
+
+        assert_eq!(
+            reader.read_blocks(1, &[35, 0, 2]).unwrap(),
+            snapshots[1].blocks
+        );
+        assert_eq!(
+            reader.read_blocks(2, &[35]).unwrap(),
+            vec![snapshots[2].blocks[1].clone()]
+        );
+        assert!(reader.read_blocks(0, &[2]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_v6_chunk() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let snapshots: Vec<Snapshot> = (0..3).map(|i| multi_block_snapshot(100 + i)).collect();
+        // RPG6 frames are plain postcard(Snapshot)
+        let raw: Vec<Vec<u8>> = snapshots
+            .iter()
+            .map(|s| postcard::to_allocvec(s).unwrap())
+            .collect();
+        write_chunk_inner(
+            &path,
+            snapshots.len(),
+            &[],
+            |i| Ok((raw[i].clone(), snapshots[i].timestamp)),
+            &StringInterner::new(),
+            0,
+            None,
+            ChunkWriteOptions::default(),
+        )
+        .unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[0..4].copy_from_slice(&MAGIC_V6);
+        bytes[4..6].copy_from_slice(&VERSION_V6.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+
+        assert_eq!(read_chunk_metadata(&path).unwrap().snapshot_count, 3);
+        let reader = ChunkReader::open(&path).unwrap();
+        assert_eq!(reader.read_snapshot(2).unwrap(), snapshots[2]);
+        assert_eq!(
+            reader.read_block(0, 35).unwrap(),
+            Some(snapshots[0].blocks[1].clone())
+        );
+    }
+
+    #[test]
+    fn test_read_and_migrate_v7_chunk() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("old.zst");
+        let snapshots: Vec<Snapshot> = (0..3).map(|i| multi_block_snapshot(100 + i)).collect();
+        // RPG7 frames: block index entries without the schema version byte
+        let raw: Vec<Vec<u8>> = snapshots
+            .iter()
+            .map(|s| {
+                let frame = encode_snapshot_frame(s).unwrap();
+                let (entries, payload) = split_frame(&frame, FrameLayout::Versioned).unwrap();
+                let mut v7 = (entries.len() as u16).to_le_bytes().to_vec();
+                for e in entries {
+                    v7.push(e.kind);
+                    v7.extend(e.start.to_le_bytes());
+                    v7.extend(e.end.to_le_bytes());
+                }
+                v7.extend(payload);
+                v7
+            })
+            .collect();
+        write_chunk_inner(
+            &path,
+            snapshots.len(),
+            &[],
+            |i| Ok((raw[i].clone(), snapshots[i].timestamp)),
+            &StringInterner::new(),
+            0,
+            None,
+            ChunkWriteOptions::default(),
+        )
+        .unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[0..4].copy_from_slice(&MAGIC_V7);
+        bytes[4..6].copy_from_slice(&VERSION_V7.to_le_bytes());
+        bytes[MIN_READER_VERSION_OFFSET] = 0;
+        std::fs::write(&path, bytes).unwrap();
+
+        let reader = ChunkReader::open(&path).unwrap();
+        assert_eq!(reader.read_snapshot(2).unwrap(), snapshots[2]);
+        assert_eq!(
+            reader.read_block(1, 35).unwrap(),
+            Some(snapshots[1].blocks[1].clone())
+        );
+
+        let out = dir.path().join("new.zst");
+        assert_eq!(migrate_chunk(&path, &out).unwrap(), Some(VERSION_V7));
+        let reader = ChunkReader::open(&out).unwrap();
+        assert_eq!(reader.read_snapshot(0).unwrap(), snapshots[0]);
+    }
+
+    #[test]
+    fn test_corrupted_chunk_does_not_panic() {
+        // Deterministic stand-in for the chunk_reader fuzz target: damaged
+        // bytes must surface as errors or skipped blocks, never as panics.
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let snapshots: Vec<Snapshot> = (0..3).map(|i| multi_block_snapshot(100 + i)).collect();
+        let mut interner = StringInterner::new();
+        interner.intern("postgres");
+        write_chunk(&path, &snapshots, &interner).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..500 {
+            let mut damaged = bytes.clone();
+            for _ in 0..4 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let pos = state as usize % damaged.len();
+                damaged[pos] ^= (state >> 32) as u8 | 1;
+            }
+            if let Ok(reader) = ChunkReader::from_bytes(damaged, None) {
+                for i in 0..reader.snapshot_count().min(8) {
+                    let _ = reader.read_snapshot(i);
+                    let _ = reader.read_blocks(i, &[0, 35]);
+                }
+                let _ = reader.read_interner();
+            }
+        }
+    }
+
+    #[test]
+    fn test_reader_from_bytes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let snapshots = create_test_snapshots(3);
+        write_chunk(&path, &snapshots, &StringInterner::new()).unwrap();
+
+        let reader = ChunkReader::from_bytes(std::fs::read(&path).unwrap(), None).unwrap();
+        assert_eq!(
+            reader.timestamps(),
+            ChunkReader::open(&path).unwrap().timestamps()
+        );
+        assert_eq!(reader.read_snapshot(2).unwrap(), snapshots[2]);
+        assert!(ChunkReader::from_bytes(b"RPG7".to_vec(), None).is_err());
+    }
+
+    #[test]
+    fn test_migrate_v6_chunk() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("old.zst");
+        let snapshots: Vec<Snapshot> = (0..3).map(|i| multi_block_snapshot(100 + i)).collect();
+        let raw: Vec<Vec<u8>> = snapshots
+            .iter()
+            .map(|s| postcard::to_allocvec(s).unwrap())
+            .collect();
+        let mut interner = StringInterner::new();
+        interner.intern("postgres");
+        write_chunk_inner(
+            &path,
+            snapshots.len(),
+            &[],
+            |i| Ok((raw[i].clone(), snapshots[i].timestamp)),
+            &interner,
+            1 << 3,
+            None,
+            ChunkWriteOptions::default(),
+        )
+        .unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[0..4].copy_from_slice(&MAGIC_V6);
+        bytes[4..6].copy_from_slice(&VERSION_V6.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+
+        let out = dir.path().join("new.zst");
+        assert_eq!(migrate_chunk(&path, &out).unwrap(), Some(VERSION_V6));
+        let migrated = std::fs::read(&out).unwrap();
+        assert_eq!(&migrated[0..4], &MAGIC);
+        let reader = ChunkReader::open(&out).unwrap();
+        assert_eq!(reader.stripped_blocks(), 1 << 3);
+        assert_eq!(reader.read_snapshot(1).unwrap(), snapshots[1]);
+        assert_eq!(reader.read_interner().unwrap().len(), 1);
+
+        // Already current: nothing to do
+        assert_eq!(migrate_chunk(&out, &out).unwrap(), None);
+
+        let mut bytes = migrated;
+        bytes[0..4].copy_from_slice(b"RPG3");
+        bytes[4..6].copy_from_slice(&3u16.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        let err = migrate_chunk(&path, &out).unwrap_err();
+        assert!(err.to_string().contains("RPG3"));
+    }
+
+    #[test]
+    fn test_direct_io_chunk_roundtrip() {
+        let dir = tempdir().unwrap();
+        let buffered = dir.path().join("buffered.zst");
+        let direct = dir.path().join("direct.zst");
+        let snapshots = create_test_snapshots(7);
+        let interner = StringInterner::new();
+
+        for (path, direct_io) in [(&buffered, false), (&direct, true)] {
+            let options = ChunkWriteOptions {
+                sync: true,
+                sync_dir: true,
+                direct_io,
+                ..ChunkWriteOptions::default()
+            };
+            write_chunk_with_trained_dict(
+                path,
+                snapshots.len(),
+                &[],
+                |i| Ok(snapshots[i].clone()),
+                &interner,
+                options,
+            )
+            .unwrap();
+        }
+
+        // Block padding is trimmed: the files are byte-identical
+        assert_eq!(
+            std::fs::read(&buffered).unwrap(),
+            std::fs::read(&direct).unwrap()
+        );
+        let reader = ChunkReader::open(&direct).unwrap();
+        assert_eq!(reader.read_snapshot(6).unwrap(), snapshots[6]);
+        assert!(!direct.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_stripped_blocks_above_32_bits() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let snapshots = create_test_snapshots(2);
+        let mask = (1u64 << 3) | (1u64 << 32) | (1u64 << 47);
+
+        write_stripped_chunk(
+            &path,
+            snapshots.len(),
+            &[],
+            |i| Ok(snapshots[i].clone()),
+            &StringInterner::new(),
+            mask,
+            ChunkWriteOptions::default(),
+        )
+        .unwrap();
+
+        let meta = read_chunk_metadata(&path).unwrap();
+        assert_eq!(meta.stripped_blocks, mask);
+        assert!(!meta.encrypted);
+        assert_eq!(ChunkReader::open(&path).unwrap().stripped_blocks(), mask);
+    }
+
+    #[test]
+    fn test_encrypted_chunk_roundtrip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let snapshots = create_test_snapshots(5);
+        let mut interner = StringInterner::new();
+        let h = interner.intern("select secret_column from users");
+        let key = EncryptionKey::from_bytes(&[9u8; crypto::KEY_LEN]);
+
+        let raw: Vec<Vec<u8>> = snapshots
+            .iter()
+            .map(|s| encode_snapshot_frame(s).unwrap())
+            .collect();
+        write_chunk_inner(
+            &path,
+            snapshots.len(),
+            &[],
+            |i| Ok((raw[i].clone(), snapshots[i].timestamp)),
+            &interner,
+            0,
+            Some(&key),
+            ChunkWriteOptions::default(),
+        )
+        .unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(!bytes.windows(13).any(|w| w == b"secret_column"));
+
+        // Timestamps are readable without the key
+        let meta = read_chunk_metadata(&path).unwrap();
+        assert!(meta.encrypted);
+        assert_eq!(meta.timestamps[0], snapshots[0].timestamp);
+
+        let err = ChunkReader::open_with_key(&path, None).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let wrong = EncryptionKey::from_bytes(&[1u8; crypto::KEY_LEN]);
+        assert!(
+            ChunkReader::open_with_key(&path, Some(&wrong))
+                .and_then(|r| r.read_interner())
+                .is_err()
+        );
+
+        let reader = ChunkReader::open_with_key(&path, Some(&key)).unwrap();
+        for (i, expected) in snapshots.iter().enumerate() {
+            assert_eq!(
+                reader.read_snapshot(i).unwrap().timestamp,
+                expected.timestamp
+            );
+        }
+        let loaded = reader.read_interner().unwrap();
+        assert_eq!(loaded.resolve(h), Some("select secret_column from users"));
+    }
+
+    #[test]
+    fn test_repair_chunk_with_damaged_index() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let out = dir.path().join("repaired.zst");
+        let snapshots = create_test_snapshots(10);
+        let mut interner = StringInterner::new();
+        let hash = interner.intern("postgres");
+        write_chunk(&path, &snapshots, &interner).unwrap();
+
+        let mut data = fs::read(&path).unwrap();
+        data[HEADER_SIZE..HEADER_SIZE + 10 * INDEX_ENTRY_SIZE].fill(0xFF);
+        fs::write(&path, &data).unwrap();
+        assert!(ChunkReader::open(&path).unwrap().read_snapshot(0).is_err());
+
+        let repair = repair_chunk(&path, &out).unwrap();
+        assert_eq!(repair.indexed, 10);
+        assert_eq!(repair.recovered, 10);
+        assert!(repair.scanned);
+        assert!(!repair.interner_lost);
+
+        let reader = ChunkReader::open(&out).unwrap();
+        assert_eq!(
+            reader.timestamps(),
+            (0..10).map(|i| 100 + i * 10).collect::<Vec<_>>()
+        );
+        assert_eq!(reader.read_snapshot(3).unwrap(), snapshots[3]);
+        assert_eq!(
+            reader.read_interner().unwrap().resolve(hash),
+            Some("postgres")
+        );
+    }
+
+    #[test]
+    fn test_repair_chunk_drops_corrupt_frame() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.zst");
+        let out = dir.path().join("repaired.zst");
+        let snapshots = create_test_snapshots(5);
+        write_chunk(&path, &snapshots, &StringInterner::new()).unwrap();
+
+        let reader = ChunkReader::open(&path).unwrap();
+        let (offset, len, _, _) = reader.index[2];
+        drop(reader);
+        let mut data = fs::read(&path).unwrap();
+        let mid = (offset + len / 2) as usize;
+        data[mid..mid + 4].fill(0xAA);
+        fs::write(&path, &data).unwrap();
+
+        let repair = repair_chunk(&path, &out).unwrap();
+        assert_eq!(repair.recovered, 4);
+        let reader = ChunkReader::open(&out).unwrap();
+        assert_eq!(reader.timestamps(), vec![100, 110, 130, 140]);
+    }
 }
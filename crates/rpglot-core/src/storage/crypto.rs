@@ -0,0 +1,188 @@
+//! Optional encryption at rest for chunk files and WAL entries (AES-256-GCM).
+//!
+//! Snapshots may contain query texts with literals, so data directories on
+//! shared disks can be encrypted. The key is installed once per process
+//! ([`install_key`]); writers encrypt and readers decrypt transparently.
+//! Each encrypted frame is `nonce (12 bytes) || ciphertext || tag (16 bytes)`.
+//! Timestamps in chunk index tables and heatmap sidecars stay in clear text.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+/// Environment variable with the key as 64 hex characters.
+pub const KEY_ENV: &str = "RPGLOT_ENCRYPTION_KEY";
+
+/// Key length in bytes (AES-256).
+pub const KEY_LEN: usize = 32;
+
+const NONCE_LEN: usize = 12;
+
+static INSTALLED_KEY: OnceLock<EncryptionKey> = OnceLock::new();
+
+/// AES-256-GCM key for storage encryption.
+#[derive(Clone)]
+pub struct EncryptionKey {
+    cipher: Aes256Gcm,
+}
+
+impl EncryptionKey {
+    pub fn from_bytes(bytes: &[u8; KEY_LEN]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(bytes)),
+        }
+    }
+
+    /// Parses a key written as 64 hex characters (surrounding whitespace ignored).
+    pub fn from_hex(text: &str) -> io::Result<Self> {
+        let text = text.trim();
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("encryption key must be {} hex characters", KEY_LEN * 2),
+            )
+        };
+        if text.len() != KEY_LEN * 2 || !text.is_ascii() {
+            return Err(invalid());
+        }
+        let mut bytes = [0u8; KEY_LEN];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(Self::from_bytes(&bytes))
+    }
+
+    /// Reads a key file: 32 raw bytes or 64 hex characters.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let data = fs::read(path)?;
+        if let Ok(bytes) = <[u8; KEY_LEN]>::try_from(data.as_slice()) {
+            return Ok(Self::from_bytes(&bytes));
+        }
+        Self::from_hex(&String::from_utf8_lossy(&data))
+    }
+
+    /// Key from `file` if given, else from [`KEY_ENV`], else none.
+    pub fn load(file: Option<&Path>) -> io::Result<Option<Self>> {
+        if let Some(path) = file {
+            return Self::from_file(path).map(Some);
+        }
+        match std::env::var(KEY_ENV) {
+            Ok(text) => Self::from_hex(&text).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Encrypts with a random nonce: `nonce || ciphertext || tag`.
+    pub fn encrypt(&self, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// Decrypts data produced by [`EncryptionKey::encrypt`]. Fails on a wrong
+    /// key or tampered data.
+    pub fn decrypt(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        if data.len() < NONCE_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "encrypted frame too short",
+            ));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "decryption failed (wrong encryption key or corrupted data)",
+                )
+            })
+    }
+}
+
+/// Installs the process-wide key used by storage writers and readers.
+/// Returns false if a key was already installed.
+pub fn install_key(key: EncryptionKey) -> bool {
+    INSTALLED_KEY.set(key).is_ok()
+}
+
+/// Loads the key ([`EncryptionKey::load`]) and installs it process-wide.
+/// Returns whether encryption is enabled. Called once at binary startup.
+pub fn install_from(file: Option<&Path>) -> io::Result<bool> {
+    match EncryptionKey::load(file)? {
+        Some(key) => Ok(install_key(key)),
+        None => Ok(false),
+    }
+}
+
+/// The process-wide key, if one was installed.
+pub fn installed_key() -> Option<&'static EncryptionKey> {
+    INSTALLED_KEY.get()
+}
+
+/// The key needed to read encrypted data, or an error explaining how to set it.
+pub(crate) fn require_key(key: Option<&EncryptionKey>) -> io::Result<&EncryptionKey> {
+    key.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "data is encrypted: set {} or pass --encryption-key-file",
+                KEY_ENV
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_roundtrip_and_wrong_key() {
+        let key = EncryptionKey::from_hex(&"ab".repeat(KEY_LEN)).unwrap();
+        let frame = key
+            .encrypt(b"select * from users where email = 'a@b.c'")
+            .unwrap();
+        assert!(!frame.windows(5).any(|w| w == b"email"));
+        assert_eq!(
+            key.decrypt(&frame).unwrap(),
+            b"select * from users where email = 'a@b.c'"
+        );
+        // Random nonce: same plaintext encrypts differently
+        assert_ne!(key.encrypt(b"x").unwrap(), key.encrypt(b"x").unwrap());
+
+        let other = EncryptionKey::from_bytes(&[7u8; KEY_LEN]);
+        assert_eq!(
+            other.decrypt(&frame).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(EncryptionKey::from_hex("abcd").is_err());
+        assert!(require_key(None).is_err());
+    }
+
+    #[test]
+    fn key_file_raw_or_hex() {
+        let dir = tempfile::tempdir().unwrap();
+        let raw = dir.path().join("raw.key");
+        fs::write(&raw, [1u8; KEY_LEN]).unwrap();
+        let hex = dir.path().join("hex.key");
+        fs::write(&hex, format!("{}\n", "01".repeat(KEY_LEN))).unwrap();
+
+        let frame = EncryptionKey::from_file(&raw)
+            .unwrap()
+            .encrypt(b"x")
+            .unwrap();
+        let key = EncryptionKey::load(Some(&hex)).unwrap().unwrap();
+        assert_eq!(key.decrypt(&frame).unwrap(), b"x");
+    }
+}
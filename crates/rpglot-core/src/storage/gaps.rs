@@ -0,0 +1,105 @@
+//! Gaps in recorded history.
+//!
+//! A gap is an interval between two consecutive snapshots that is much longer
+//! than the usual collection interval: the daemon was stopped, the host was
+//! rebooted or suspended. Gaps are reported so that missing data is not
+//! mistaken for an idle period.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Spacing above this many expected intervals is a gap.
+const GAP_FACTOR: i64 = 3;
+/// Spacing up to this many seconds is never a gap (jitter, slow collections).
+const MIN_GAP_SECS: i64 = 60;
+
+/// An interval without snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TimelineGap {
+    /// Timestamp of the last snapshot before the gap (epoch seconds).
+    pub start: i64,
+    /// Timestamp of the first snapshot after the gap (epoch seconds).
+    pub end: i64,
+}
+
+impl TimelineGap {
+    /// Length of the gap in seconds.
+    pub fn duration(&self) -> i64 {
+        self.end - self.start
+    }
+}
+
+/// Expected collection interval: the median spacing of consecutive
+/// snapshots. Duplicate timestamps are ignored. `None` with fewer than two
+/// distinct timestamps.
+pub fn expected_interval(timestamps: &[i64]) -> Option<i64> {
+    let mut diffs: Vec<i64> = timestamps
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .filter(|&d| d > 0)
+        .collect();
+    if diffs.is_empty() {
+        return None;
+    }
+    let mid = diffs.len() / 2;
+    Some(*diffs.select_nth_unstable(mid).1)
+}
+
+/// Finds gaps in sorted `timestamps`: spacings longer than
+/// `GAP_FACTOR` expected intervals and at least `MIN_GAP_SECS`.
+pub fn find_gaps(timestamps: &[i64]) -> Vec<TimelineGap> {
+    let Some(expected) = expected_interval(timestamps) else {
+        return Vec::new();
+    };
+    gaps_with_interval(timestamps, expected)
+}
+
+/// Finds gaps in sorted `timestamps` against a known expected interval.
+pub fn gaps_with_interval(timestamps: &[i64], expected: i64) -> Vec<TimelineGap> {
+    let threshold = (expected * GAP_FACTOR).max(MIN_GAP_SECS);
+    timestamps
+        .windows(2)
+        .filter(|w| w[1] - w[0] > threshold)
+        .map(|w| TimelineGap {
+            start: w[0],
+            end: w[1],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_interval_ignores_duplicates_and_outliers() {
+        let ts = [0, 10, 10, 20, 30, 1000, 1010];
+        assert_eq!(expected_interval(&ts), Some(10));
+        assert_eq!(expected_interval(&[5]), None);
+        assert_eq!(expected_interval(&[5, 5]), None);
+    }
+
+    #[test]
+    fn finds_gaps_above_threshold() {
+        // 10s interval: a 50s hole is jitter (below 60s), 970s is a gap
+        let ts = [0, 10, 20, 70, 80, 1050, 1060];
+        assert_eq!(
+            find_gaps(&ts),
+            vec![TimelineGap {
+                start: 80,
+                end: 1050
+            }]
+        );
+
+        // 60s interval: the threshold is three intervals
+        let ts = [0, 60, 120, 300, 360, 600];
+        assert_eq!(
+            find_gaps(&ts),
+            vec![TimelineGap {
+                start: 360,
+                end: 600
+            }]
+        );
+        assert!(find_gaps(&[]).is_empty());
+    }
+}
@@ -1,14 +1,17 @@
 //! Lightweight per-snapshot heatmap data for timeline visualization.
 //!
-//! Each snapshot produces a 14-byte `HeatmapEntry` (active_sessions, host CPU%,
-//! cgroup CPU%, cgroup memory%, errors by severity, checkpoint/autovacuum/slow counts).
-//! These are stored in `.heatmap` sidecar files alongside `.zst` chunk files
-//! and read without decompressing snapshots — enabling O(1) access to activity
-//! data for arbitrary time ranges.
+//! Each snapshot produces a 27-byte `HeatmapEntry` (active_sessions, host CPU%,
+//! cgroup CPU%, cgroup memory%, errors by severity, checkpoint/autovacuum/slow
+//! counts, health score, and the per-category series: disk I/O%, total sessions,
+//! TPS, WAL rate). These are stored in `.heatmap` sidecar files alongside `.zst`
+//! chunk files and read without decompressing snapshots — enabling O(1) access
+//! to activity data for arbitrary time ranges.
 //!
 //! ## File format
 //!
-//! 4-byte magic `b"HM04"` followed by 15-byte little-endian entries.
+//! 4-byte magic `b"HM05"` followed by 27-byte little-endian entries.
+//! `b"HM04"` files (15-byte entries, without the series) are still read;
+//! their series are zero.
 
 use std::path::{Path, PathBuf};
 use std::{fs, io};
@@ -23,11 +26,15 @@ use super::model::{
     SystemCpuInfo,
 };
 
-/// Magic bytes identifying heatmap sidecar files (v4: 15 bytes per entry, +health_score).
-const HEATMAP_MAGIC: &[u8; 4] = b"HM04";
+/// Magic bytes identifying heatmap sidecar files (v5: 27 bytes per entry, +series).
+const HEATMAP_MAGIC: &[u8; 4] = b"HM05";
 
 /// Entry size in bytes.
-const ENTRY_SIZE: usize = 15;
+const ENTRY_SIZE: usize = 27;
+
+/// Magic and entry size of v4 files, read for chunks written before v5.
+const HEATMAP_MAGIC_V4: &[u8; 4] = b"HM04";
+const ENTRY_SIZE_V4: usize = 15;
 
 /// Local severity mapping for error categories in heatmap context.
 /// Same logic as in pg_errors.rs and convert.rs (intentionally duplicated — 5 lines).
@@ -50,7 +57,7 @@ enum ErrorSeverityGroup {
 }
 
 /// Lightweight per-snapshot heatmap entry.
-/// 15 bytes per entry.
+/// 27 bytes per entry.
 #[derive(Clone, Copy, Debug, Default)]
 #[repr(C)]
 pub struct HeatmapEntry {
@@ -76,6 +83,14 @@ pub struct HeatmapEntry {
     pub slow_query_count: u8,
     /// Health score (0..100, where 100 = perfectly healthy).
     pub health_score: u8,
+    /// Busiest disk utilization * 10 (0..1000).
+    pub io_pct_x10: u16,
+    /// Number of pg_stat_activity rows (all states).
+    pub total_sessions: u16,
+    /// Committed + rolled back transactions per second.
+    pub tps: u32,
+    /// WAL generated, KiB/s (pg_stat_statements `wal_bytes`, PG 13+).
+    pub wal_kib_per_sec: u32,
 }
 
 /// A bucketed heatmap data point for frontend display.
@@ -105,6 +120,16 @@ pub struct HeatmapBucket {
     pub slow_queries: u8,
     /// Min (worst) health score in this bucket (0..100).
     pub health: u8,
+    /// Annotations (deployments, maintenance) in this bucket.
+    pub annotations: u8,
+    /// Max busiest-disk utilization * 10 in this bucket (0..1000).
+    pub io: u16,
+    /// Max total sessions in this bucket.
+    pub sessions: u16,
+    /// Max TPS in this bucket.
+    pub tps: u32,
+    /// Max WAL rate (KiB/s) in this bucket.
+    pub wal: u32,
 }
 
 // ---------------------------------------------------------------------------
@@ -117,7 +142,7 @@ pub fn heatmap_path(chunk_path: &Path) -> PathBuf {
 }
 
 /// Writes heatmap entries to a `.heatmap` sidecar file.
-/// Format: 4-byte magic `b"HM04"` + 15-byte little-endian entries.
+/// Format: 4-byte magic `b"HM05"` + 27-byte little-endian entries.
 pub fn write_heatmap(path: &Path, entries: &[HeatmapEntry]) -> io::Result<()> {
     let mut buf = Vec::with_capacity(4 + entries.len() * ENTRY_SIZE);
     buf.extend_from_slice(HEATMAP_MAGIC);
@@ -133,31 +158,43 @@ pub fn write_heatmap(path: &Path, entries: &[HeatmapEntry]) -> io::Result<()> {
         buf.push(e.autovacuum_count);
         buf.push(e.slow_query_count);
         buf.push(e.health_score);
+        buf.extend_from_slice(&e.io_pct_x10.to_le_bytes());
+        buf.extend_from_slice(&e.total_sessions.to_le_bytes());
+        buf.extend_from_slice(&e.tps.to_le_bytes());
+        buf.extend_from_slice(&e.wal_kib_per_sec.to_le_bytes());
     }
     fs::write(path, buf)
 }
 
-/// Reads heatmap entries from a `.heatmap` sidecar file.
+/// Reads heatmap entries from a `.heatmap` sidecar file (v5 or v4).
 pub fn read_heatmap(path: &Path) -> io::Result<Vec<HeatmapEntry>> {
     let data = fs::read(path)?;
 
-    if data.len() < 4 || data[0..4] != *HEATMAP_MAGIC {
+    if data.len() < 4 {
         return Err(io::Error::other("invalid heatmap file magic"));
     }
+    let entry_size = match &data[0..4] {
+        m if m == HEATMAP_MAGIC => ENTRY_SIZE,
+        m if m == HEATMAP_MAGIC_V4 => ENTRY_SIZE_V4,
+        _ => return Err(io::Error::other("invalid heatmap file magic")),
+    };
 
     let payload = &data[4..];
-    if payload.len() % ENTRY_SIZE != 0 {
+    if payload.len() % entry_size != 0 {
         return Err(io::Error::other("invalid heatmap file size"));
     }
-    let count = payload.len() / ENTRY_SIZE;
+    let count = payload.len() / entry_size;
+    let u16_at = |off: usize| u16::from_le_bytes([payload[off], payload[off + 1]]);
+    let u32_at = |off: usize| u32::from_le_bytes(payload[off..off + 4].try_into().unwrap());
     let mut entries = Vec::with_capacity(count);
     for i in 0..count {
-        let off = i * ENTRY_SIZE;
+        let off = i * entry_size;
+        let v5 = entry_size == ENTRY_SIZE;
         entries.push(HeatmapEntry {
-            active_sessions: u16::from_le_bytes([payload[off], payload[off + 1]]),
-            cpu_pct_x10: u16::from_le_bytes([payload[off + 2], payload[off + 3]]),
-            cgroup_cpu_pct_x10: u16::from_le_bytes([payload[off + 4], payload[off + 5]]),
-            cgroup_mem_pct_x10: u16::from_le_bytes([payload[off + 6], payload[off + 7]]),
+            active_sessions: u16_at(off),
+            cpu_pct_x10: u16_at(off + 2),
+            cgroup_cpu_pct_x10: u16_at(off + 4),
+            cgroup_mem_pct_x10: u16_at(off + 6),
             errors_critical: payload[off + 8],
             errors_warning: payload[off + 9],
             errors_info: payload[off + 10],
@@ -165,6 +202,10 @@ pub fn read_heatmap(path: &Path) -> io::Result<Vec<HeatmapEntry>> {
             autovacuum_count: payload[off + 12],
             slow_query_count: payload[off + 13],
             health_score: payload[off + 14],
+            io_pct_x10: if v5 { u16_at(off + 15) } else { 0 },
+            total_sessions: if v5 { u16_at(off + 17) } else { 0 },
+            tps: if v5 { u32_at(off + 19) } else { 0 },
+            wal_kib_per_sec: if v5 { u32_at(off + 23) } else { 0 },
         });
     }
     Ok(entries)
@@ -313,6 +354,35 @@ pub fn count_active_sessions(snapshot: &Snapshot) -> u16 {
     count.min(u16::MAX as usize) as u16
 }
 
+/// Count all PGA sessions in a snapshot.
+pub fn count_total_sessions(snapshot: &Snapshot) -> u16 {
+    let count: usize = snapshot
+        .blocks
+        .iter()
+        .filter_map(|b| {
+            if let DataBlock::PgStatActivity(rows) = b {
+                Some(rows.len())
+            } else {
+                None
+            }
+        })
+        .sum();
+    count.min(u16::MAX as usize) as u16
+}
+
+/// Total `wal_bytes` over pg_stat_statements and its collection time.
+/// `None` without the block (or before PG 13, when all values are zero).
+fn statements_wal_bytes(snapshot: &Snapshot) -> Option<(i64, i64)> {
+    snapshot.blocks.iter().find_map(|b| {
+        if let DataBlock::PgStatStatements(rows) = b {
+            let collected_at = rows.first()?.collected_at;
+            Some((collected_at, rows.iter().map(|r| r.wal_bytes).sum()))
+        } else {
+            None
+        }
+    })
+}
+
 /// Extract aggregate SystemCpuInfo (cpu_id == -1) from snapshot.
 fn extract_system_cpu(snapshot: &Snapshot) -> Option<&SystemCpuInfo> {
     snapshot.blocks.iter().find_map(|b| {
@@ -401,30 +471,71 @@ fn compute_cgroup_mem_pct(mem: &CgroupMemoryInfo) -> u16 {
     pct_x10.min(1000)
 }
 
-/// Build HeatmapEntry array from a sequence of snapshots.
-/// Host CPU% and cgroup CPU% are computed as deltas between consecutive snapshots.
-/// First snapshot gets cpu values = 0 (no previous data).
-pub fn build_heatmap_from_snapshots(snapshots: &[Snapshot]) -> Vec<HeatmapEntry> {
-    let mut entries = Vec::with_capacity(snapshots.len());
-    let mut prev_cpu: Option<&SystemCpuInfo> = None;
-    let mut prev_cgroup_cpu: Option<&CgroupCpuInfo> = None;
-    let mut prev_timestamp: Option<i64> = None;
-    let mut prev_sample: Option<PrevSample> = None;
+/// Busiest disk utilization * 10 (0..1000) between two samples.
+fn compute_io_pct(prev: &PrevSample, curr: &PrevSample, delta_time_secs: f64) -> u16 {
+    if delta_time_secs <= 0.0 {
+        return 0;
+    }
+    let busiest_ms = curr
+        .disk_io_ms_per_dev
+        .iter()
+        .filter_map(|(dev, &io_ms)| Some(io_ms.saturating_sub(*prev.disk_io_ms_per_dev.get(dev)?)))
+        .max()
+        .unwrap_or(0);
+    // io_ms / (dt * 1000) * 100% * 10
+    ((busiest_ms as f64 / delta_time_secs) as u16).min(1000)
+}
+
+/// Transactions per second between two samples.
+fn compute_tps(prev: &PrevSample, curr: &PrevSample, delta_time_secs: f64) -> u32 {
+    if delta_time_secs <= 0.0 {
+        return 0;
+    }
+    let xacts = (curr.pg_xact_commit + curr.pg_xact_rollback)
+        .saturating_sub(prev.pg_xact_commit + prev.pg_xact_rollback)
+        .max(0);
+    (xacts as f64 / delta_time_secs) as u32
+}
+
+/// Builds heatmap entries from consecutive snapshots.
+///
+/// Keeps only the small previous-snapshot state needed for deltas, so it works
+/// both over a slice and while streaming a chunk. The first snapshot gets rate
+/// values = 0 (no previous data).
+#[derive(Default)]
+pub struct HeatmapBuilder {
+    prev_cpu: Option<SystemCpuInfo>,
+    prev_cgroup_cpu: Option<CgroupCpuInfo>,
+    prev_sample: Option<PrevSample>,
+    /// (collected_at, total wal_bytes) of the last pg_stat_statements collection.
+    prev_wal: Option<(i64, i64)>,
+    /// pg_stat_statements is cached between collections: the rate of the
+    /// last collection is repeated until the next one.
+    wal_kib_per_sec: u32,
+}
 
-    for snap in snapshots {
-        let active = count_active_sessions(snap);
+impl HeatmapBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Computes the entry for `snap`, the snapshot after the previously pushed one.
+    pub fn push(&mut self, snap: &Snapshot) -> HeatmapEntry {
+        let sample = PrevSample::extract(snap);
 
         // Host CPU%
-        let cpu = match (prev_cpu, extract_system_cpu(snap)) {
+        let cpu = match (self.prev_cpu.as_ref(), extract_system_cpu(snap)) {
             (Some(prev), Some(curr)) => compute_cpu_pct(prev, curr),
             _ => 0,
         };
 
         // Cgroup CPU% (needs wall-clock delta for usage_usec → %)
-        let delta_time = prev_timestamp
-            .map(|pt| (snap.timestamp - pt) as f64)
+        let delta_time = self
+            .prev_sample
+            .as_ref()
+            .map(|p| snap.elapsed_since(p.timestamp, p.clock.as_ref()) as f64)
             .unwrap_or(0.0);
-        let cgroup_cpu = match (prev_cgroup_cpu, extract_cgroup_cpu(snap)) {
+        let cgroup_cpu = match (self.prev_cgroup_cpu.as_ref(), extract_cgroup_cpu(snap)) {
             (Some(prev), Some(curr)) => compute_cgroup_cpu_pct(prev, curr, delta_time),
             _ => 0,
         };
@@ -437,34 +548,64 @@ pub fn build_heatmap_from_snapshots(snapshots: &[Snapshot]) -> Vec<HeatmapEntry>
         // PostgreSQL log error counts by severity
         let (errors_critical, errors_warning, errors_info) = count_error_entries_by_severity(snap);
 
-        // Checkpoint / autovacuum / slow query events
-        let checkpoints = count_checkpoint_events(snap);
-        let autovacuums = count_autovacuum_events(snap);
-        let slow_queries = count_slow_query_events(snap);
+        // Disk I/O% and TPS
+        let (io, tps) = match self.prev_sample.as_ref() {
+            Some(prev) => (
+                compute_io_pct(prev, &sample, delta_time),
+                compute_tps(prev, &sample, delta_time),
+            ),
+            None => (0, 0),
+        };
+
+        // WAL rate, updated when pg_stat_statements was re-collected
+        if let Some((collected_at, wal_bytes)) = statements_wal_bytes(snap) {
+            match self.prev_wal {
+                Some((prev_at, prev_bytes)) if collected_at > prev_at => {
+                    let bytes = wal_bytes.saturating_sub(prev_bytes).max(0);
+                    self.wal_kib_per_sec =
+                        (bytes as f64 / 1024.0 / (collected_at - prev_at) as f64) as u32;
+                    self.prev_wal = Some((collected_at, wal_bytes));
+                }
+                Some(_) => {}
+                None => self.prev_wal = Some((collected_at, wal_bytes)),
+            }
+        }
 
         // Health score
-        let health_score = compute_health_score(snap, prev_sample.as_ref(), delta_time).0;
+        let health_score = compute_health_score(snap, self.prev_sample.as_ref(), delta_time).0;
 
-        entries.push(HeatmapEntry {
-            active_sessions: active,
+        let entry = HeatmapEntry {
+            active_sessions: count_active_sessions(snap),
             cpu_pct_x10: cpu,
             cgroup_cpu_pct_x10: cgroup_cpu,
             cgroup_mem_pct_x10: cgroup_mem,
             errors_critical,
             errors_warning,
             errors_info,
-            checkpoint_count: checkpoints,
-            autovacuum_count: autovacuums,
-            slow_query_count: slow_queries,
+            checkpoint_count: count_checkpoint_events(snap),
+            autovacuum_count: count_autovacuum_events(snap),
+            slow_query_count: count_slow_query_events(snap),
             health_score,
-        });
+            io_pct_x10: io,
+            total_sessions: count_total_sessions(snap),
+            tps,
+            wal_kib_per_sec: self.wal_kib_per_sec,
+        };
 
-        prev_cpu = extract_system_cpu(snap);
-        prev_cgroup_cpu = extract_cgroup_cpu(snap);
-        prev_timestamp = Some(snap.timestamp);
-        prev_sample = Some(PrevSample::extract(snap));
+        // Keep only small prev state — snapshot may be dropped
+        self.prev_cpu = extract_system_cpu(snap).cloned();
+        self.prev_cgroup_cpu = extract_cgroup_cpu(snap).cloned();
+        self.prev_sample = Some(sample);
+        entry
     }
-    entries
+}
+
+/// Build HeatmapEntry array from a sequence of snapshots.
+/// Host CPU% and cgroup CPU% are computed as deltas between consecutive snapshots.
+/// First snapshot gets cpu values = 0 (no previous data).
+pub fn build_heatmap_from_snapshots(snapshots: &[Snapshot]) -> Vec<HeatmapEntry> {
+    let mut builder = HeatmapBuilder::new();
+    snapshots.iter().map(|snap| builder.push(snap)).collect()
 }
 
 /// Build heatmap entries by reading snapshots one at a time from a ChunkReader.
@@ -475,10 +616,7 @@ pub fn build_heatmap_from_snapshots(snapshots: &[Snapshot]) -> Vec<HeatmapEntry>
 pub fn build_heatmap_streaming(reader: &super::chunk::ChunkReader) -> Option<Vec<HeatmapEntry>> {
     let count = reader.snapshot_count();
     let mut entries = Vec::with_capacity(count);
-    let mut prev_cpu: Option<SystemCpuInfo> = None;
-    let mut prev_cgroup_cpu: Option<CgroupCpuInfo> = None;
-    let mut prev_timestamp: Option<i64> = None;
-    let mut prev_sample: Option<PrevSample> = None;
+    let mut builder = HeatmapBuilder::new();
 
     for i in 0..count {
         let snap = match reader.read_snapshot(i) {
@@ -493,53 +631,7 @@ pub fn build_heatmap_streaming(reader: &super::chunk::ChunkReader) -> Option<Vec
                 return None;
             }
         };
-
-        let active = count_active_sessions(&snap);
-
-        let cpu = match (prev_cpu.as_ref(), extract_system_cpu(&snap)) {
-            (Some(prev), Some(curr)) => compute_cpu_pct(prev, curr),
-            _ => 0,
-        };
-
-        let delta_time = prev_timestamp
-            .map(|pt| (snap.timestamp - pt) as f64)
-            .unwrap_or(0.0);
-        let cgroup_cpu = match (prev_cgroup_cpu.as_ref(), extract_cgroup_cpu(&snap)) {
-            (Some(prev), Some(curr)) => compute_cgroup_cpu_pct(prev, curr, delta_time),
-            _ => 0,
-        };
-
-        let cgroup_mem = extract_cgroup_memory(&snap)
-            .map(compute_cgroup_mem_pct)
-            .unwrap_or(0);
-
-        let (errors_critical, errors_warning, errors_info) = count_error_entries_by_severity(&snap);
-        let checkpoints = count_checkpoint_events(&snap);
-        let autovacuums = count_autovacuum_events(&snap);
-        let slow_queries = count_slow_query_events(&snap);
-
-        // Health score
-        let health_score = compute_health_score(&snap, prev_sample.as_ref(), delta_time).0;
-
-        entries.push(HeatmapEntry {
-            active_sessions: active,
-            cpu_pct_x10: cpu,
-            cgroup_cpu_pct_x10: cgroup_cpu,
-            cgroup_mem_pct_x10: cgroup_mem,
-            errors_critical,
-            errors_warning,
-            errors_info,
-            checkpoint_count: checkpoints,
-            autovacuum_count: autovacuums,
-            slow_query_count: slow_queries,
-            health_score,
-        });
-
-        // Keep only small prev state — snapshot is dropped
-        prev_cpu = extract_system_cpu(&snap).cloned();
-        prev_cgroup_cpu = extract_cgroup_cpu(&snap).cloned();
-        prev_timestamp = Some(snap.timestamp);
-        prev_sample = Some(PrevSample::extract(&snap));
+        entries.push(builder.push(&snap));
     }
 
     Some(entries)
@@ -578,6 +670,11 @@ pub fn bucket_heatmap(
                 autovacuums: 0,
                 slow_queries: 0,
                 health: 100,
+                annotations: 0,
+                io: 0,
+                sessions: 0,
+                tps: 0,
+                wal: 0,
             }
         })
         .collect();
@@ -602,11 +699,146 @@ pub fn bucket_heatmap(
             .slow_queries
             .saturating_add(entry.slow_query_count);
         buckets[idx].health = buckets[idx].health.min(entry.health_score);
+        buckets[idx].io = buckets[idx].io.max(entry.io_pct_x10);
+        buckets[idx].sessions = buckets[idx].sessions.max(entry.total_sessions);
+        buckets[idx].tps = buckets[idx].tps.max(entry.tps);
+        buckets[idx].wal = buckets[idx].wal.max(entry.wal_kib_per_sec);
     }
 
     buckets
 }
 
+/// Count annotation timestamps into buckets built by [`bucket_heatmap`]
+/// (the last bucket ends at `end_ts`). Counts are replaced, not added, so
+/// cached buckets can be re-annotated.
+pub fn count_annotations(
+    buckets: &mut [HeatmapBucket],
+    end_ts: i64,
+    timestamps: impl IntoIterator<Item = i64>,
+) {
+    for bucket in buckets.iter_mut() {
+        bucket.annotations = 0;
+    }
+    let Some(first_ts) = buckets.first().map(|b| b.ts) else {
+        return;
+    };
+    for ts in timestamps {
+        if ts < first_ts || ts > end_ts {
+            continue;
+        }
+        let idx = buckets.partition_point(|b| b.ts <= ts) - 1;
+        buckets[idx].annotations = buckets[idx].annotations.saturating_add(1);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Hotspots (analysis pre-pass)
+// ---------------------------------------------------------------------------
+
+/// Consecutive entries a hotspot must span.
+const HOTSPOT_MIN_ENTRIES: usize = 2;
+
+/// Per-category series of a heatmap entry.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapSeries {
+    Cpu,
+    Io,
+    Sessions,
+    Tps,
+    Wal,
+}
+
+impl HeatmapSeries {
+    pub const ALL: [HeatmapSeries; 5] = [
+        HeatmapSeries::Cpu,
+        HeatmapSeries::Io,
+        HeatmapSeries::Sessions,
+        HeatmapSeries::Tps,
+        HeatmapSeries::Wal,
+    ];
+
+    /// Raw value of the series (percentages * 10).
+    fn value(self, e: &HeatmapEntry) -> u32 {
+        match self {
+            HeatmapSeries::Cpu => e.cpu_pct_x10.max(e.cgroup_cpu_pct_x10) as u32,
+            HeatmapSeries::Io => e.io_pct_x10 as u32,
+            HeatmapSeries::Sessions => e.active_sessions as u32,
+            HeatmapSeries::Tps => e.tps,
+            HeatmapSeries::Wal => e.wal_kib_per_sec,
+        }
+    }
+
+    /// Value from which an entry is hot: an absolute level for percentages,
+    /// otherwise 3x the median of the range with a floor against idle noise.
+    fn threshold(self, values: &[u32]) -> u32 {
+        let floor = match self {
+            HeatmapSeries::Cpu | HeatmapSeries::Io => return 900,
+            HeatmapSeries::Sessions => 20,
+            HeatmapSeries::Tps => 100,
+            HeatmapSeries::Wal => 1024,
+        };
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+        let median = sorted.get(sorted.len() / 2).copied().unwrap_or(0);
+        median.saturating_mul(3).max(floor)
+    }
+
+    /// Value in display units (percent, sessions, TPS, KiB/s).
+    fn display(self, raw: u32) -> f64 {
+        match self {
+            HeatmapSeries::Cpu | HeatmapSeries::Io => raw as f64 / 10.0,
+            _ => raw as f64,
+        }
+    }
+}
+
+/// A run of consecutive heatmap entries where one series stands out.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Hotspot {
+    pub series: HeatmapSeries,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub peak_ts: i64,
+    /// Peak value in display units (percent, sessions, TPS, KiB/s).
+    pub peak: f64,
+}
+
+/// Finds obvious hotspots from heatmap entries alone, without decompressing
+/// snapshots. Sorted by start time.
+pub fn find_hotspots(entries: &[(i64, HeatmapEntry)]) -> Vec<Hotspot> {
+    let mut hotspots = Vec::new();
+    for series in HeatmapSeries::ALL {
+        let values: Vec<u32> = entries.iter().map(|(_, e)| series.value(e)).collect();
+        let threshold = series.threshold(&values);
+        // (first index, peak index) of the current run
+        let mut run: Option<(usize, usize)> = None;
+        for i in 0..=values.len() {
+            let hot = values.get(i).is_some_and(|&v| v >= threshold);
+            run = match (hot, run) {
+                (true, None) => Some((i, i)),
+                (true, Some((start, peak))) if values[i] > values[peak] => Some((start, i)),
+                (true, run) => run,
+                (false, Some((start, peak))) => {
+                    if i - start >= HOTSPOT_MIN_ENTRIES {
+                        hotspots.push(Hotspot {
+                            series,
+                            start_ts: entries[start].0,
+                            end_ts: entries[i - 1].0,
+                            peak_ts: entries[peak].0,
+                            peak: series.display(values[peak]),
+                        });
+                    }
+                    None
+                }
+                (false, None) => None,
+            };
+        }
+    }
+    hotspots.sort_by_key(|h| h.start_ts);
+    hotspots
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -626,6 +858,10 @@ mod tests {
                 autovacuum_count: 2,
                 slow_query_count: 4,
                 health_score: 85,
+                io_pct_x10: 730,
+                total_sessions: 120,
+                tps: 70_000,
+                wal_kib_per_sec: 20_480,
             },
             HeatmapEntry {
                 active_sessions: 0,
@@ -639,6 +875,7 @@ mod tests {
                 autovacuum_count: 0,
                 slow_query_count: 0,
                 health_score: 100,
+                ..HeatmapEntry::default()
             },
             HeatmapEntry {
                 active_sessions: 100,
@@ -652,6 +889,7 @@ mod tests {
                 autovacuum_count: 7,
                 slow_query_count: 16,
                 health_score: 30,
+                ..HeatmapEntry::default()
             },
         ];
         let dir = std::env::temp_dir().join("rpglot_test_heatmap");
@@ -672,6 +910,11 @@ mod tests {
         assert_eq!(loaded[0].autovacuum_count, 2);
         assert_eq!(loaded[0].slow_query_count, 4);
         assert_eq!(loaded[0].health_score, 85);
+        assert_eq!(loaded[0].io_pct_x10, 730);
+        assert_eq!(loaded[0].total_sessions, 120);
+        assert_eq!(loaded[0].tps, 70_000);
+        assert_eq!(loaded[0].wal_kib_per_sec, 20_480);
+        assert_eq!(loaded[1].tps, 0);
         assert_eq!(loaded[2].active_sessions, 100);
         assert_eq!(loaded[2].cpu_pct_x10, 999);
         assert_eq!(loaded[2].cgroup_cpu_pct_x10, 500);
@@ -705,6 +948,7 @@ mod tests {
                     autovacuum_count: 0,
                     slow_query_count: 2,
                     health_score: 90,
+                    ..HeatmapEntry::default()
                 },
             ),
             (
@@ -721,6 +965,7 @@ mod tests {
                     autovacuum_count: 2,
                     slow_query_count: 1,
                     health_score: 60,
+                    ..HeatmapEntry::default()
                 },
             ),
             (
@@ -737,6 +982,7 @@ mod tests {
                     autovacuum_count: 3,
                     slow_query_count: 3,
                     health_score: 40,
+                    ..HeatmapEntry::default()
                 },
             ),
         ];
@@ -766,6 +1012,104 @@ mod tests {
         assert_eq!(buckets[1].autovacuums, 5); // 2 + 3
         assert_eq!(buckets[1].slow_queries, 4); // 1 + 3
         assert_eq!(buckets[1].health, 40); // min(60, 40)
+
+        let mut buckets = buckets;
+        count_annotations(&mut buckets, 200, [99, 100, 149, 150, 200, 201]);
+        assert_eq!(buckets[0].annotations, 2);
+        assert_eq!(buckets[1].annotations, 2);
+        // Re-annotating a cached copy replaces the counts
+        count_annotations(&mut buckets, 200, [120]);
+        assert_eq!(buckets[0].annotations, 1);
+        assert_eq!(buckets[1].annotations, 0);
+    }
+
+    #[test]
+    fn test_read_v4_heatmap() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("v4.heatmap");
+        let mut data = b"HM04".to_vec();
+        data.extend_from_slice(&[5, 0, 194, 1, 0, 0, 0, 0, 1, 2, 3, 0, 0, 0, 77]);
+        std::fs::write(&path, data).unwrap();
+
+        let loaded = read_heatmap(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].active_sessions, 5);
+        assert_eq!(loaded[0].cpu_pct_x10, 450);
+        assert_eq!(loaded[0].errors_info, 3);
+        assert_eq!(loaded[0].health_score, 77);
+        assert_eq!(loaded[0].tps, 0);
+        assert_eq!(loaded[0].wal_kib_per_sec, 0);
+    }
+
+    #[test]
+    fn test_builder_series() {
+        use crate::storage::model::{PgStatDatabaseInfo, PgStatStatementsInfo, SystemDiskInfo};
+
+        let snap = |ts: i64, io_ms: u64, commits: i64, collected_at: i64, wal: i64| Snapshot {
+            timestamp: ts,
+            blocks: vec![
+                DataBlock::SystemDisk(vec![SystemDiskInfo {
+                    device_name: "sda".into(),
+                    device_hash: 1,
+                    major: 8,
+                    io_ms,
+                    ..Default::default()
+                }]),
+                DataBlock::PgStatDatabase(vec![PgStatDatabaseInfo {
+                    xact_commit: commits,
+                    ..Default::default()
+                }]),
+                DataBlock::PgStatStatements(vec![PgStatStatementsInfo {
+                    wal_bytes: wal,
+                    collected_at,
+                    ..Default::default()
+                }]),
+            ],
+        };
+        let entries = build_heatmap_from_snapshots(&[
+            snap(100, 1_000, 5_000, 100, 0),
+            snap(110, 6_000, 6_000, 100, 0),
+            snap(120, 6_000, 6_000, 120, 20 * 1024 * 1024),
+        ]);
+        assert_eq!(entries[0].tps, 0);
+        assert_eq!(entries[1].io_pct_x10, 500); // 5 s busy in 10 s
+        assert_eq!(entries[1].tps, 100);
+        assert_eq!(entries[1].wal_kib_per_sec, 0);
+        assert_eq!(entries[2].io_pct_x10, 0);
+        assert_eq!(entries[2].wal_kib_per_sec, 1024); // 20 MiB in 20 s
+    }
+
+    #[test]
+    fn test_find_hotspots() {
+        let entry = |cpu: u16, tps: u32| HeatmapEntry {
+            cpu_pct_x10: cpu,
+            tps,
+            ..HeatmapEntry::default()
+        };
+        let entries: Vec<(i64, HeatmapEntry)> = [
+            (100, 400),
+            (950, 400),
+            (980, 2_000),
+            (300, 3_000),
+            (300, 400),
+            (990, 400),
+            (300, 400),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, &(cpu, tps))| (i as i64 * 10, entry(cpu, tps)))
+        .collect();
+
+        let hotspots = find_hotspots(&entries);
+        assert_eq!(hotspots.len(), 2);
+        assert_eq!(hotspots[0].series, HeatmapSeries::Cpu);
+        assert_eq!((hotspots[0].start_ts, hotspots[0].end_ts), (10, 20));
+        assert_eq!(hotspots[0].peak_ts, 20);
+        assert_eq!(hotspots[0].peak, 98.0);
+        // TPS median 400 -> threshold 1200; the single CPU spike at 50 is too short
+        assert_eq!(hotspots[1].series, HeatmapSeries::Tps);
+        assert_eq!((hotspots[1].start_ts, hotspots[1].end_ts), (20, 30));
+        assert_eq!(hotspots[1].peak, 3000.0);
     }
 
     #[test]
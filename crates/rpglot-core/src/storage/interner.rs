@@ -1,7 +1,18 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use xxhash_rust::xxh3::xxh3_64;
 
+/// Hashes that [`StringInterner::resolve`] could not find, process-wide.
+static UNRESOLVED: AtomicU64 = AtomicU64::new(0);
+
+/// Number of non-zero string hashes missing from their interner since the
+/// process started. A growing count means damaged or mismatched data:
+/// callers fall back to a placeholder instead of failing.
+pub fn unresolved_count() -> u64 {
+    UNRESOLVED.load(Ordering::Relaxed)
+}
+
 #[derive(Default, Serialize, Deserialize, Clone)]
 pub struct StringInterner {
     // Map hash to the actual string
@@ -20,9 +31,14 @@ impl StringInterner {
         hash
     }
 
-    /// Resolves a hash back to a string.
+    /// Resolves a hash back to a string. Misses of non-zero hashes (0 is
+    /// the empty string by convention) are counted, see [`unresolved_count`].
     pub fn resolve(&self, hash: u64) -> Option<&str> {
-        self.strings.get(&hash).map(|s| s.as_str())
+        let found = self.strings.get(&hash).map(|s| s.as_str());
+        if found.is_none() && hash != 0 {
+            UNRESOLVED.fetch_add(1, Ordering::Relaxed);
+        }
+        found
     }
 
     pub fn clear(&mut self) {
@@ -49,6 +65,11 @@ impl StringInterner {
         StringInterner { strings }
     }
 
+    /// Iterates over (hash, string) pairs in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &str)> {
+        self.strings.iter().map(|(h, s)| (*h, s.as_str()))
+    }
+
     /// Returns the number of interned strings.
     pub fn len(&self) -> usize {
         self.strings.len()
@@ -75,4 +96,12 @@ mod tests {
         assert_eq!(interner.resolve(h1), Some(s1));
         assert_eq!(interner.strings.len(), 1);
     }
+
+    #[test]
+    fn test_unresolved_counted() {
+        let interner = StringInterner::new();
+        let before = unresolved_count();
+        assert_eq!(interner.resolve(42), None);
+        assert!(unresolved_count() > before);
+    }
 }
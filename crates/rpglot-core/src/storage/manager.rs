@@ -1,19 +1,28 @@
+use crate::storage::chunk::{
+    ChunkWriteOptions, DEFAULT_COMPRESSION_LEVEL, DEFAULT_DICT_SAMPLES, encode_snapshot_frame,
+};
+use crate::storage::crypto::{self, EncryptionKey};
 use crate::storage::interner::StringInterner;
-use crate::storage::model::{DataBlock, Snapshot};
+use crate::storage::model::{DataBlock, Snapshot, legacy};
 use chrono::{DateTime, NaiveDate, Timelike, Utc};
 use std::collections::HashSet;
 use std::fmt;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tracing::warn;
 
 /// WAL frame format: [u32 LE length][u32 LE crc32][payload bytes]
 const WAL_FRAME_HEADER_SIZE: usize = 8;
 /// Sanity limit for a single WAL entry (256 MB).
 const MAX_WAL_ENTRY_SIZE: u32 = 256 * 1024 * 1024;
+/// Set in the length field of frames whose payload is encrypted
+/// (see [`crate::storage::crypto`]); the CRC covers the stored payload.
+pub const WAL_ENCRYPTED_FLAG: u32 = 1 << 31;
 
 /// Errors that can occur when reading a single WAL frame.
+#[derive(Debug)]
 enum WalFrameError {
     /// Not enough bytes for frame header.
     TruncatedHeader,
@@ -25,6 +34,12 @@ enum WalFrameError {
     CrcMismatch { expected: u32, actual: u32 },
     /// Postcard deserialization failed.
     DeserializationFailed(String),
+    /// The decoded snapshot failed [`Snapshot::validate`].
+    Invalid(String),
+    /// Frame is encrypted and no key is installed.
+    MissingKey,
+    /// Decryption failed (wrong key).
+    DecryptionFailed(String),
 }
 
 impl fmt::Display for WalFrameError {
@@ -43,6 +58,9 @@ impl fmt::Display for WalFrameError {
                 )
             }
             Self::DeserializationFailed(msg) => write!(f, "deserialization failed: {}", msg),
+            Self::Invalid(msg) => write!(f, "invalid snapshot: {}", msg),
+            Self::MissingKey => write!(f, "frame is encrypted, no encryption key set"),
+            Self::DecryptionFailed(msg) => write!(f, "decryption failed: {}", msg),
         }
     }
 }
@@ -54,6 +72,8 @@ pub struct RotationConfig {
     pub max_total_size: u64,
     /// Maximum retention period in days. Default: 7 days.
     pub max_retention_days: u32,
+    /// Shorter retention for selected DataBlock kinds. Default: none.
+    pub block_retention: Vec<BlockRetention>,
 }
 
 impl Default for RotationConfig {
@@ -61,6 +81,7 @@ impl Default for RotationConfig {
         Self {
             max_total_size: 1_073_741_824, // 1GB
             max_retention_days: 7,
+            block_retention: Vec::new(),
         }
     }
 }
@@ -71,8 +92,123 @@ impl RotationConfig {
         Self {
             max_total_size,
             max_retention_days,
+            block_retention: Vec::new(),
         }
     }
+
+    /// Sets per-block retention rules.
+    pub fn with_block_retention(mut self, block_retention: Vec<BlockRetention>) -> Self {
+        self.block_retention = block_retention;
+        self
+    }
+
+    /// Bitmask of block kinds to strip from a chunk of `file_date`.
+    fn blocks_to_strip(&self, file_date: NaiveDate, today: NaiveDate) -> u64 {
+        self.block_retention
+            .iter()
+            .filter(|r| file_date < today - chrono::Duration::days(r.days as i64))
+            .fold(0, |mask, r| mask | r.blocks)
+    }
+}
+
+/// Retention rule for selected DataBlock kinds: chunks older than `days`
+/// are rewritten without those blocks instead of being deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockRetention {
+    /// Bitmask of [`DataBlock::kind_index`] values.
+    pub blocks: u64,
+    /// Retention period in days for these blocks.
+    pub days: u32,
+}
+
+impl BlockRetention {
+    /// Parses `"processes,pg_stat_statements=7"` (names from [`DataBlock::KIND_NAMES`]).
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (names, days) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected BLOCK[,BLOCK...]=DAYS, got '{}'", s))?;
+        let days = days
+            .trim()
+            .parse::<u32>()
+            .map_err(|e| format!("invalid days '{}': {}", days, e))?;
+        let mut blocks = 0u64;
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let idx = DataBlock::kind_by_name(name).ok_or_else(|| {
+                format!(
+                    "unknown block '{}', expected one of: {}",
+                    name,
+                    DataBlock::KIND_NAMES.join(", ")
+                )
+            })?;
+            blocks |= 1 << idx;
+        }
+        if blocks == 0 {
+            return Err(format!("no block names in '{}'", s));
+        }
+        Ok(Self { blocks, days })
+    }
+}
+
+/// When storage writes are made durable with fsync: a trade between I/O
+/// overhead and the monitoring data lost on power failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Durability {
+    /// Never fsync; the kernel writes data back on its own schedule.
+    None,
+    /// Fsync the WAL at most once per interval (after every frame when
+    /// zero) and chunk files before they are renamed into place.
+    WalFsync(Duration),
+    /// Fsync every WAL frame, every chunk file and the data directory after
+    /// each chunk rename.
+    Always,
+}
+
+impl Default for Durability {
+    fn default() -> Self {
+        Self::WalFsync(Duration::ZERO)
+    }
+}
+
+impl Durability {
+    /// Parses `none`, `wal-fsync` or `always`; `wal_fsync_interval` applies
+    /// to `wal-fsync`.
+    pub fn parse(mode: &str, wal_fsync_interval: Duration) -> Result<Self, String> {
+        match mode {
+            "none" => Ok(Self::None),
+            "wal-fsync" => Ok(Self::WalFsync(wal_fsync_interval)),
+            "always" => Ok(Self::Always),
+            _ => Err(format!(
+                "unknown durability '{}', expected none, wal-fsync or always",
+                mode
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::WalFsync(_) => "wal-fsync",
+            Self::Always => "always",
+        }
+    }
+}
+
+/// Outcome of WAL validation when a [`StorageManager`] is opened.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalRecovery {
+    /// Snapshots recovered from the WAL.
+    pub recovered: usize,
+    /// Frames at and after the first corrupt one, dropped with it
+    /// (a partially written frame counts as one).
+    pub lost: usize,
+    /// Bytes truncated from the end of the WAL.
+    pub truncated_bytes: u64,
+    /// Why the scan stopped before the end of the WAL.
+    pub error: Option<String>,
+    /// Encrypted frames were kept as is: no encryption key is installed.
+    pub undecryptable: bool,
+    /// Timestamp of the last recovered snapshot.
+    pub last_timestamp: Option<i64>,
 }
 
 /// WAL entry containing a snapshot and its string interner.
@@ -83,6 +219,27 @@ pub(crate) struct WalEntry {
     pub(crate) interner: StringInterner,
 }
 
+impl WalEntry {
+    /// Decodes a WAL frame payload. The snapshot may be in the block layout
+    /// of an earlier build (see `model::legacy`); the interner follows it.
+    pub(crate) fn decode(payload: &[u8]) -> Result<Self, postcard::Error> {
+        let (snapshot, interner) = legacy::take_snapshot(payload, |rest| {
+            match postcard::take_from_bytes::<StringInterner>(rest) {
+                Ok((interner, [])) => Some(interner),
+                _ => None,
+            }
+        })?;
+        Ok(Self { snapshot, interner })
+    }
+}
+
+/// Decodes a WAL frame payload (decrypted) into its snapshot and interner.
+pub fn decode_wal_payload(payload: &[u8]) -> Option<(Snapshot, StringInterner)> {
+    WalEntry::decode(payload)
+        .ok()
+        .map(|entry| (entry.snapshot, entry.interner))
+}
+
 pub struct StorageManager {
     base_path: PathBuf,
     chunk_size_limit: usize,
@@ -93,6 +250,18 @@ pub struct StorageManager {
     current_hour: Option<u32>,
     /// Current date for hourly file segmentation
     current_date: Option<NaiveDate>,
+    /// WAL validation result from startup
+    wal_recovery: WalRecovery,
+    /// Fsync policy for WAL and chunk writes
+    durability: Durability,
+    /// Write chunks with O_DIRECT
+    direct_io: bool,
+    /// zstd level of chunk frames
+    compression_level: i32,
+    /// Snapshots sampled for chunk dictionary training
+    dict_samples: usize,
+    /// Last WAL fsync, for [`Durability::WalFsync`] intervals
+    last_wal_sync: Option<Instant>,
 }
 
 impl StorageManager {
@@ -124,6 +293,12 @@ impl StorageManager {
             wal_entries_count: 0,
             current_hour: None,
             current_date: None,
+            wal_recovery: WalRecovery::default(),
+            durability: Durability::default(),
+            direct_io: false,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+            dict_samples: DEFAULT_DICT_SAMPLES,
+            last_wal_sync: None,
         };
 
         manager.recover_from_wal();
@@ -131,7 +306,8 @@ impl StorageManager {
     }
 
     /// Recovers WAL state on startup.
-    /// Counts valid entries and truncates any corrupted data at the end.
+    /// Counts valid entries and truncates the WAL at the first corrupt frame,
+    /// so that snapshots appended later are not hidden behind it.
     fn recover_from_wal(&mut self) {
         let wal_path = self.base_path.join("wal.log");
 
@@ -149,15 +325,17 @@ impl StorageManager {
         let mut pos = 0usize;
         let mut valid_end_position = 0usize;
         let mut recovered_count = 0usize;
+        let mut last_timestamp = None;
         let mut last_error: Option<WalFrameError> = None;
 
         // Count valid WAL entries and find valid end position
         loop {
-            match Self::read_wal_frame_validated(&data, pos) {
-                Ok((_entry, next_pos)) => {
+            match Self::read_wal_frame_validated(&data, pos, crypto::installed_key()) {
+                Ok((entry, next_pos)) => {
                     pos = next_pos;
                     valid_end_position = pos;
                     recovered_count += 1;
+                    last_timestamp = Some(entry.snapshot.timestamp);
                 }
                 Err(WalFrameError::TruncatedHeader) if pos == data.len() => {
                     // Exact end of file — not an error
@@ -181,31 +359,146 @@ impl StorageManager {
         }
 
         self.wal_entries_count = recovered_count;
+        self.wal_recovery = WalRecovery {
+            recovered: recovered_count,
+            error: last_error.as_ref().map(ToString::to_string),
+            last_timestamp,
+            ..WalRecovery::default()
+        };
 
         // Check if there's garbage after valid records (corruption detected)
+        // Encrypted frames without a key are not corruption: keep them.
         let file_size = data.len();
-        if valid_end_position < file_size && valid_end_position > 0 {
+        if matches!(last_error, Some(WalFrameError::MissingKey)) {
+            warn!("WAL contains encrypted entries but no encryption key is set");
+            self.wal_recovery.undecryptable = true;
+        } else if valid_end_position < file_size {
             let garbage_bytes = file_size - valid_end_position;
+            let lost = Self::count_frames_from(&data, valid_end_position);
             warn!(
-                "WAL corruption detected: {} garbage bytes after {} valid records. Truncating WAL.",
-                garbage_bytes, recovered_count
+                "WAL corruption detected: {} garbage bytes ({} frames) after {} valid records. Truncating WAL.",
+                garbage_bytes, lost, recovered_count
             );
 
             // Truncate WAL to remove corrupted data
-            if let Err(e) = OpenOptions::new()
+            match OpenOptions::new()
                 .write(true)
                 .open(&wal_path)
                 .and_then(|f| f.set_len(valid_end_position as u64))
             {
-                warn!("Failed to truncate WAL: {}", e);
+                Ok(()) => {
+                    self.wal_recovery.lost = lost;
+                    self.wal_recovery.truncated_bytes = garbage_bytes as u64;
+                }
+                Err(e) => warn!("Failed to truncate WAL: {}", e),
             }
         }
     }
 
-    /// Collects all string hashes used in a single snapshot.
-    fn collect_snapshot_hashes(snapshot: &Snapshot) -> HashSet<u64> {
+    /// Counts frames in `data` from `pos` by following their length headers,
+    /// without validating payloads. A trailing partial frame counts as one.
+    fn count_frames_from(data: &[u8], mut pos: usize) -> usize {
+        let mut count = 0;
+        while pos < data.len() {
+            count += 1;
+            if pos + WAL_FRAME_HEADER_SIZE > data.len() {
+                break;
+            }
+            let length =
+                u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()) & !WAL_ENCRYPTED_FLAG;
+            if length > MAX_WAL_ENTRY_SIZE {
+                break;
+            }
+            pos += WAL_FRAME_HEADER_SIZE + length as usize;
+        }
+        count
+    }
+
+    /// Sets when WAL frames and chunk files are fsynced.
+    pub fn set_durability(&mut self, durability: Durability) {
+        self.durability = durability;
+    }
+
+    /// Writes chunk files with O_DIRECT, keeping them out of the page cache.
+    pub fn set_direct_io(&mut self, direct_io: bool) {
+        self.direct_io = direct_io;
+    }
+
+    /// Sets the zstd level of chunk frames and the number of snapshots sampled
+    /// to train each chunk's dictionary (0 disables the dictionary).
+    pub fn set_compression(&mut self, level: i32, dict_samples: usize) {
+        self.compression_level = level;
+        self.dict_samples = dict_samples;
+    }
+
+    fn chunk_write_options(&self) -> ChunkWriteOptions {
+        ChunkWriteOptions {
+            sync: self.durability != Durability::None,
+            sync_dir: self.durability == Durability::Always,
+            direct_io: self.direct_io,
+            level: self.compression_level,
+            dict_samples: self.dict_samples,
+        }
+    }
+
+    /// Fsyncs the WAL after a frame was appended, as the durability policy asks.
+    fn sync_wal(&mut self) {
+        let due = match self.durability {
+            Durability::None => false,
+            Durability::Always => true,
+            Durability::WalFsync(interval) => self
+                .last_wal_sync
+                .is_none_or(|last| last.elapsed() >= interval),
+        };
+        if !due {
+            return;
+        }
+        if let Err(e) = self.wal_file.sync_data() {
+            warn!("Failed to fsync WAL: {}", e);
+        }
+        self.last_wal_sync = Some(Instant::now());
+    }
+
+    /// Result of WAL validation performed when the manager was opened.
+    pub fn wal_recovery(&self) -> &WalRecovery {
+        &self.wal_recovery
+    }
+
+    /// Finalizes snapshots recovered from the WAL. When they belong to an
+    /// hour that is already over, they are flushed into that hour's chunk
+    /// right away instead of ending up in the chunk of the current hour;
+    /// otherwise the current hour's chunk continues. Returns the chunk path
+    /// when a chunk was written.
+    pub fn finalize_recovered_wal(&mut self, now: DateTime<Utc>) -> io::Result<Option<PathBuf>> {
+        if self.wal_entries_count == 0 || self.wal_recovery.undecryptable {
+            return Ok(None);
+        }
+        let Some(time) = self
+            .wal_recovery
+            .last_timestamp
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+        else {
+            return Ok(None);
+        };
+        let (date, hour) = (time.date_naive(), time.hour());
+        if date == now.date_naive() && hour == now.hour() {
+            self.current_date = Some(date);
+            self.current_hour = Some(hour);
+            return Ok(None);
+        }
+        self.flush_chunk_with_time(date, hour).map(Some)
+    }
+
+    /// Collects all string hashes used in a single snapshot (the interner
+    /// entries it needs to be read back).
+    pub fn collect_snapshot_hashes(snapshot: &Snapshot) -> HashSet<u64> {
         let mut hashes = HashSet::new();
-        for block in &snapshot.blocks {
+        Self::collect_block_hashes(&snapshot.blocks, &mut hashes);
+        hashes
+    }
+
+    fn collect_block_hashes(blocks: &[DataBlock], hashes: &mut HashSet<u64>) {
+        for block in blocks {
             match block {
                 DataBlock::Processes(procs) => {
                     for p in procs {
@@ -310,6 +603,12 @@ impl StorageManager {
                         hashes.insert(e.statement_hash);
                     }
                 }
+                DataBlock::PgSlowPlans(plans) => {
+                    for p in plans {
+                        hashes.insert(p.query_hash);
+                        hashes.insert(p.plan_hash);
+                    }
+                }
                 // Variants without string hashes — listed explicitly so the
                 // compiler forces us to handle new variants.
                 DataBlock::PgStatBgwriter(_)
@@ -325,10 +624,29 @@ impl StorageManager {
                 | DataBlock::PgLogEvents(_)
                 | DataBlock::PgLogDetailedEvents(_)
                 | DataBlock::PgSettings(_)
-                | DataBlock::ReplicationStatus(_) => {}
+                | DataBlock::PgPendingRestart(_)
+                | DataBlock::PgSettingsMeta(_)
+                | DataBlock::PgExtensions(_)
+                | DataBlock::ReplicationStatus(_)
+                | DataBlock::PgConnectionChurn(_)
+                | DataBlock::CustomQuery(_)
+                | DataBlock::SystemTcpConn(_)
+                | DataBlock::KernelEvents(_)
+                | DataBlock::SystemdUnit(_)
+                | DataBlock::CollectionDegraded(_)
+                | DataBlock::AgentStats(_)
+                | DataBlock::K8sPod(_)
+                | DataBlock::PgConnection(_)
+                | DataBlock::PgRelationSizes(_)
+                | DataBlock::PgSequences(_)
+                | DataBlock::PgBufferCache(_)
+                | DataBlock::PgStatArchiver(_)
+                | DataBlock::Clock(_) => {}
+                DataBlock::PgInstance(instance) => {
+                    Self::collect_block_hashes(&instance.blocks, hashes);
+                }
             }
         }
-        hashes
     }
 
     /// Adds a snapshot to storage with hourly segmentation.
@@ -369,7 +687,8 @@ impl StorageManager {
             snapshot,
             interner: wal_interner,
         };
-        Self::write_wal_frame(&mut self.wal_file, &wal_entry);
+        Self::write_wal_frame(&mut self.wal_file, &wal_entry, crypto::installed_key());
+        self.sync_wal();
         self.wal_entries_count += 1;
 
         // Check if size limit reached
@@ -382,14 +701,18 @@ impl StorageManager {
     }
 
     /// Writes a single WAL entry with CRC32 framing: [u32 length][u32 crc32][payload].
-    fn write_wal_frame(file: &mut File, entry: &WalEntry) {
-        let encoded = postcard::to_allocvec(entry).expect("WAL entry serialization failed");
-        let length = encoded.len() as u32;
+    /// With `key`, the payload is encrypted and the length carries [`WAL_ENCRYPTED_FLAG`].
+    fn write_wal_frame(file: &mut File, entry: &WalEntry, key: Option<&EncryptionKey>) {
+        let mut encoded = postcard::to_allocvec(entry).expect("WAL entry serialization failed");
+        let mut length = encoded.len() as u32;
+        if let Some(key) = key {
+            encoded = key.encrypt(&encoded).expect("WAL entry encryption failed");
+            length = encoded.len() as u32 | WAL_ENCRYPTED_FLAG;
+        }
         let crc = crc32fast::hash(&encoded);
         file.write_all(&length.to_le_bytes()).unwrap();
         file.write_all(&crc.to_le_bytes()).unwrap();
         file.write_all(&encoded).unwrap();
-        file.sync_all().unwrap();
     }
 
     /// Flushes the current chunk using current time for filename.
@@ -397,13 +720,13 @@ impl StorageManager {
         let now = Utc::now();
         let date = self.current_date.unwrap_or_else(|| now.date_naive());
         let hour = self.current_hour.unwrap_or_else(|| now.hour());
-        self.flush_chunk_with_time(date, hour)
+        self.flush_chunk_with_time(date, hour).map(|_| ())
     }
 
     /// Flushes WAL to a compressed chunk file using a streaming two-pass approach.
     ///
     /// Pass 1: scan WAL entries one at a time — merge interners, collect used hashes,
-    /// and sample ~20 snapshots for zstd dictionary training. Only one entry is held
+    /// and sample snapshots for zstd dictionary training (20 by default). Only one entry is held
     /// in memory at a time (plus the samples).
     ///
     /// Pass 2: re-read each WAL entry by stored offset, compress with the trained
@@ -411,7 +734,7 @@ impl StorageManager {
     ///
     /// Peak memory: WAL raw bytes + dict samples (~10 MB) + one snapshot at a time,
     /// instead of all snapshots deserialized in memory.
-    fn flush_chunk_with_time(&mut self, date: NaiveDate, hour: u32) -> io::Result<()> {
+    fn flush_chunk_with_time(&mut self, date: NaiveDate, hour: u32) -> io::Result<PathBuf> {
         if self.wal_entries_count == 0 {
             return Err(io::Error::other("Empty WAL"));
         }
@@ -426,8 +749,9 @@ impl StorageManager {
         let mut frame_offsets: Vec<usize> = Vec::new();
         let mut merged_interner = StringInterner::new();
         let mut used_hashes = HashSet::new();
+        let options = self.chunk_write_options();
         let mut dict_samples: Vec<Vec<u8>> = Vec::new();
-        let sample_interval = (self.wal_entries_count / 20).max(1);
+        let sample_interval = options.sample_interval(self.wal_entries_count);
 
         let mut pos = 0usize;
         let mut scan_idx = 0usize;
@@ -436,10 +760,10 @@ impl StorageManager {
             merged_interner.merge(&entry.interner);
             used_hashes.extend(Self::collect_snapshot_hashes(&entry.snapshot));
 
-            // Keep ~20 evenly-spaced samples for dictionary training
+            // Keep evenly-spaced samples for dictionary training
             if scan_idx.is_multiple_of(sample_interval)
-                && dict_samples.len() < 20
-                && let Ok(raw) = postcard::to_allocvec(&entry.snapshot)
+                && dict_samples.len() < options.dict_samples
+                && let Ok(raw) = encode_snapshot_frame(&entry.snapshot)
             {
                 dict_samples.push(raw);
             }
@@ -460,12 +784,7 @@ impl StorageManager {
         drop(used_hashes);
 
         // Train zstd dictionary on sampled snapshots
-        let dictionary = if !dict_samples.is_empty() {
-            zstd::dict::from_samples(&dict_samples, crate::storage::chunk::DICT_MAX_SIZE)
-                .unwrap_or_default()
-        } else {
-            Vec::new()
-        };
+        let dictionary = crate::storage::chunk::train_dictionary(&dict_samples);
         drop(dict_samples);
 
         // ---- Resolve output path ----
@@ -494,6 +813,7 @@ impl StorageManager {
                     .ok_or_else(|| io::Error::other("WAL frame re-read failed"))
             },
             &filtered_interner,
+            options,
         )?;
 
         // Release WAL data before opening chunk for heatmap
@@ -521,7 +841,7 @@ impl StorageManager {
         self.wal_file.sync_all()?;
         self.wal_entries_count = 0;
 
-        Ok(())
+        Ok(final_path)
     }
 
     /// Loads unflushed snapshots and their interners from WAL file.
@@ -536,29 +856,41 @@ impl StorageManager {
         let mut snapshots = Vec::new();
         let mut merged_interner = StringInterner::new();
 
-        if let Ok(data) = fs::read(&wal_path)
-            && !data.is_empty()
-        {
-            let mut pos = 0usize;
-            while let Some((entry, next_pos)) = Self::read_wal_frame(&data, pos) {
-                merged_interner.merge(&entry.interner);
-                snapshots.push(entry.snapshot);
-                pos = next_pos;
+        if let Ok(data) = fs::read(&wal_path) {
+            for (snapshot, interner) in Self::decode_wal(&data).0 {
+                merged_interner.merge(&interner);
+                snapshots.push(snapshot);
             }
         }
 
         Ok((snapshots, merged_interner))
     }
 
+    /// Decodes the WAL entries in `data` up to the first unreadable frame.
+    /// Returns each snapshot with its interner and the length of the
+    /// readable prefix.
+    pub fn decode_wal(data: &[u8]) -> (Vec<(Snapshot, StringInterner)>, usize) {
+        let mut entries = Vec::new();
+        let mut pos = 0usize;
+        while let Some((entry, next_pos)) = Self::read_wal_frame(data, pos) {
+            entries.push((entry.snapshot, entry.interner));
+            pos = next_pos;
+        }
+        (entries, pos)
+    }
+
     /// Reads a single WAL frame from `data` at `pos` with detailed error reporting.
     fn read_wal_frame_validated(
         data: &[u8],
         pos: usize,
+        key: Option<&EncryptionKey>,
     ) -> Result<(WalEntry, usize), WalFrameError> {
         if pos + WAL_FRAME_HEADER_SIZE > data.len() {
             return Err(WalFrameError::TruncatedHeader);
         }
-        let length = u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap());
+        let length_field = u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap());
+        let encrypted = length_field & WAL_ENCRYPTED_FLAG != 0;
+        let length = length_field & !WAL_ENCRYPTED_FLAG;
         let expected_crc = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap());
 
         if length > MAX_WAL_ENTRY_SIZE {
@@ -582,15 +914,27 @@ impl StorageManager {
             });
         }
 
-        let entry: WalEntry = postcard::from_bytes(payload)
+        let decrypted;
+        let payload = if encrypted {
+            let key = key.ok_or(WalFrameError::MissingKey)?;
+            decrypted = key
+                .decrypt(payload)
+                .map_err(|e| WalFrameError::DecryptionFailed(e.to_string()))?;
+            &decrypted[..]
+        } else {
+            payload
+        };
+
+        let entry = WalEntry::decode(payload)
             .map_err(|e| WalFrameError::DeserializationFailed(e.to_string()))?;
+        entry.snapshot.validate().map_err(WalFrameError::Invalid)?;
         Ok((entry, payload_end))
     }
 
     /// Reads a single WAL frame from `data` at `pos`.
     /// Returns `Some((entry, next_pos))` on success, `None` on any error.
     fn read_wal_frame(data: &[u8], pos: usize) -> Option<(WalEntry, usize)> {
-        Self::read_wal_frame_validated(data, pos).ok()
+        Self::read_wal_frame_validated(data, pos, crypto::installed_key()).ok()
     }
 
     /// Scans WAL file and returns entry metadata (byte_offset, frame_length, timestamp)
@@ -716,6 +1060,9 @@ impl StorageManager {
     /// Removes files based on two criteria:
     /// 1. Files older than `max_retention_days`
     /// 2. Oldest files if total size exceeds `max_total_size`
+    ///
+    /// Between the two, chunks past a `block_retention` period are rewritten
+    /// without the expired blocks. Heatmap sidecars are kept as they are.
     pub fn rotate(&self, config: &RotationConfig) -> io::Result<RotationResult> {
         let mut result = RotationResult::default();
 
@@ -760,6 +1107,28 @@ impl StorageManager {
             remaining_files.push(file);
         }
 
+        // Strip expired blocks (chunks already stripped are skipped via header)
+        for file in &mut remaining_files {
+            let Some(file_date) = file.date else {
+                continue;
+            };
+            let mask = config.blocks_to_strip(file_date, now);
+            if mask == 0 {
+                continue;
+            }
+            match Self::strip_chunk_blocks(&file.path, mask, self.chunk_write_options()) {
+                Ok(Some(new_size)) => {
+                    result.files_stripped += 1;
+                    result.bytes_freed += file.size.saturating_sub(new_size);
+                    file.size = new_size;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(path = %file.path.display(), error = %e, "failed to strip chunk blocks")
+                }
+            }
+        }
+
         // Calculate total size of remaining files
         let mut total_size: u64 = remaining_files.iter().map(|f| f.size).sum();
 
@@ -779,6 +1148,56 @@ impl StorageManager {
         Ok(result)
     }
 
+    /// Rewrites a chunk without the blocks in `mask`.
+    /// Returns the new file size, or None if they were already stripped.
+    fn strip_chunk_blocks(
+        path: &Path,
+        mask: u64,
+        options: ChunkWriteOptions,
+    ) -> io::Result<Option<u64>> {
+        let reader = crate::storage::chunk::ChunkReader::open(path)?;
+        let stripped = reader.stripped_blocks();
+        if stripped & mask == mask {
+            return Ok(None);
+        }
+        let stripped = stripped | mask;
+        let read_stripped = |idx: usize| -> io::Result<Snapshot> {
+            let mut snapshot = reader.read_snapshot(idx)?;
+            DataBlock::strip_kinds(&mut snapshot.blocks, stripped);
+            Ok(snapshot)
+        };
+
+        // Pass 1: used string hashes + dictionary samples (as in flush)
+        let count = reader.snapshot_count();
+        let sample_interval = options.sample_interval(count);
+        let mut used_hashes = HashSet::new();
+        let mut dict_samples: Vec<Vec<u8>> = Vec::new();
+        for idx in 0..count {
+            let snapshot = read_stripped(idx)?;
+            used_hashes.extend(Self::collect_snapshot_hashes(&snapshot));
+            if idx.is_multiple_of(sample_interval)
+                && dict_samples.len() < options.dict_samples
+                && let Ok(raw) = encode_snapshot_frame(&snapshot)
+            {
+                dict_samples.push(raw);
+            }
+        }
+        let interner = reader.read_interner()?.filter(&used_hashes);
+        let dictionary = crate::storage::chunk::train_dictionary(&dict_samples);
+
+        // Pass 2: rewrite (atomic via .tmp + rename)
+        crate::storage::chunk::write_stripped_chunk(
+            path,
+            count,
+            &dictionary,
+            read_stripped,
+            &interner,
+            stripped,
+            options,
+        )?;
+        Ok(Some(fs::metadata(path)?.len()))
+    }
+
     /// Parses date from filename format: rpglot_YYYY-MM-DD_HH.zst or chunk_*.zst
     fn parse_date_from_filename(filename: &str) -> Option<NaiveDate> {
         // Try new format: rpglot_YYYY-MM-DD_HH.zst
@@ -819,6 +1238,8 @@ pub struct RotationResult {
     pub files_removed_by_age: usize,
     /// Number of files removed due to size limit.
     pub files_removed_by_size: usize,
+    /// Number of chunks rewritten without blocks past their block retention.
+    pub files_stripped: usize,
     /// Total bytes freed by rotation.
     pub bytes_freed: u64,
     /// Total size of remaining files after rotation.
@@ -862,7 +1283,7 @@ mod tests {
         let entries: Vec<_> = fs::read_dir(dir.path())
             .unwrap()
             .filter_map(|e| e.ok())
-            .filter(|e| e.path().extension().map_or(false, |ext| ext == "zst"))
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "zst"))
             .collect();
         assert!(!entries.is_empty());
 
@@ -945,7 +1366,7 @@ mod tests {
         let entries: Vec<_> = fs::read_dir(dir.path())
             .unwrap()
             .filter_map(|e| e.ok())
-            .filter(|e| e.path().extension().map_or(false, |ext| ext == "zst"))
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "zst"))
             .collect();
         assert!(!entries.is_empty());
 
@@ -1013,7 +1434,7 @@ mod tests {
         let entries: Vec<_> = fs::read_dir(dir.path())
             .unwrap()
             .filter_map(|e| e.ok())
-            .filter(|e| e.path().extension().map_or(false, |ext| ext == "zst"))
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "zst"))
             .collect();
         assert!(!entries.is_empty());
 
@@ -1065,6 +1486,70 @@ mod tests {
         assert!(recent_file.exists());
     }
 
+    #[test]
+    fn test_rotation_strips_expired_blocks() {
+        use crate::storage::chunk::{read_chunk_metadata, write_chunk};
+        use crate::storage::model::SystemLoadInfo;
+
+        let dir = tempdir().unwrap();
+        let manager = StorageManager::new(dir.path());
+        let now = Utc::now().date_naive();
+
+        let mut interner = StringInterner::new();
+        let name_hash = interner.intern("postgres");
+        let snapshot = |ts: i64| Snapshot {
+            timestamp: ts,
+            blocks: vec![
+                DataBlock::Processes(vec![ProcessInfo {
+                    pid: 1,
+                    name_hash,
+                    ..ProcessInfo::default()
+                }]),
+                DataBlock::SystemLoad(SystemLoadInfo::default()),
+            ],
+        };
+        let chunk = |days: i64| {
+            let date = now - chrono::Duration::days(days);
+            let path = dir
+                .path()
+                .join(format!("rpglot_{}_12.zst", date.format("%Y-%m-%d")));
+            write_chunk(&path, &[snapshot(100), snapshot(110)], &interner).unwrap();
+            path
+        };
+        let old = chunk(10);
+        let recent = chunk(2);
+
+        let rule = BlockRetention::parse("processes, pg_stat_statements=7").unwrap();
+        assert_eq!(rule.days, 7);
+        assert!(BlockRetention::parse("processes").is_err());
+        assert!(BlockRetention::parse("bogus=7").is_err());
+        let config = RotationConfig::new(1_000_000_000, 30).with_block_retention(vec![rule]);
+
+        let result = manager.rotate(&config).unwrap();
+        assert_eq!(result.files_stripped, 1);
+        assert_eq!(result.files_remaining, 2);
+
+        let reader = ChunkReader::open(&old).unwrap();
+        assert_eq!(reader.snapshot_count(), 2);
+        assert_eq!(reader.timestamps(), vec![100, 110]);
+        let stripped = reader.read_snapshot(1).unwrap();
+        assert_eq!(stripped.blocks.len(), 1);
+        assert!(matches!(stripped.blocks[0], DataBlock::SystemLoad(_)));
+        // Strings only referenced by stripped blocks are dropped
+        assert!(reader.read_interner().unwrap().is_empty());
+        assert_ne!(read_chunk_metadata(&old).unwrap().stripped_blocks, 0);
+
+        let kept = ChunkReader::open(&recent)
+            .unwrap()
+            .read_snapshot(0)
+            .unwrap();
+        assert_eq!(kept.blocks.len(), 2);
+
+        // Already stripped chunks are not rewritten again
+        let result = manager.rotate(&config).unwrap();
+        assert_eq!(result.files_stripped, 0);
+    }
+
     #[test]
     fn test_rotation_by_size() {
         let dir = tempdir().unwrap();
@@ -1285,6 +1770,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_wal_corrupted_entries_do_not_panic() {
+        // Deterministic stand-in for the wal_decode fuzz target: payloads
+        // damaged before checksumming pass the CRC and must still decode to
+        // an error, never a panic.
+        let entry = WalEntry {
+            snapshot: test_snapshot(100),
+            interner: StringInterner::new(),
+        };
+        let encoded = postcard::to_allocvec(&entry).unwrap();
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..2000 {
+            let mut payload = encoded.clone();
+            for _ in 0..3 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let pos = state as usize % payload.len();
+                payload[pos] ^= (state >> 32) as u8 | 1;
+            }
+            let mut buf = Vec::new();
+            buf.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            buf.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+            buf.extend_from_slice(&payload);
+            let (entries, valid) = StorageManager::decode_wal(&buf);
+            assert!(entries.len() <= 1 && valid <= buf.len());
+        }
+    }
+
+    #[test]
+    fn test_wal_invalid_snapshot_rejected() {
+        let mut buf = Vec::new();
+        write_test_wal_frame(&mut buf, &test_snapshot(100));
+        let valid = buf.len();
+        // Host blocks never belong to an instance
+        let invalid = Snapshot {
+            timestamp: 200,
+            blocks: vec![DataBlock::PgInstance(
+                crate::storage::model::PgInstanceBlocks {
+                    name: "billing".to_string(),
+                    port: 5433,
+                    blocks: vec![DataBlock::Processes(Vec::new())],
+                },
+            )],
+        };
+        write_test_wal_frame(&mut buf, &invalid);
+
+        let (entries, end) = StorageManager::decode_wal(&buf);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(end, valid);
+        assert!(matches!(
+            StorageManager::read_wal_frame_validated(&buf, valid, None),
+            Err(WalFrameError::Invalid(_))
+        ));
+    }
+
     #[test]
     fn test_wal_crc_framing_roundtrip() {
         let dir = tempdir().unwrap();
@@ -1333,6 +1874,10 @@ mod tests {
         // Manager should recover only the first valid entry
         let manager = StorageManager::new(dir.path());
         assert_eq!(manager.current_chunk_size(), 1);
+        let recovery = manager.wal_recovery();
+        assert_eq!((recovery.recovered, recovery.lost), (1, 1));
+        assert!(recovery.error.as_deref().unwrap().contains("CRC mismatch"));
+        assert_eq!(recovery.last_timestamp, Some(100));
 
         let (snapshots, _) = manager.load_all_snapshots_with_interner().unwrap();
         assert_eq!(snapshots.len(), 1);
@@ -1382,11 +1927,68 @@ mod tests {
         assert_eq!(wal_size as usize, expected_size);
     }
 
+    #[test]
+    fn test_wal_corrupt_first_frame_truncated() {
+        let dir = tempdir().unwrap();
+        let wal_path = dir.path().join("wal.log");
+
+        let mut buf = Vec::new();
+        write_test_wal_frame(&mut buf, &test_snapshot(100));
+        write_test_wal_frame(&mut buf, &test_snapshot(200));
+        buf[WAL_FRAME_HEADER_SIZE] ^= 0xFF; // corrupt the first payload
+        buf.extend_from_slice(&[0x10, 0x00]); // partial header of a third frame
+        std::fs::write(&wal_path, &buf).unwrap();
+
+        let mut manager = StorageManager::new(dir.path());
+        assert_eq!(manager.current_chunk_size(), 0);
+        let recovery = manager.wal_recovery();
+        assert_eq!((recovery.recovered, recovery.lost), (0, 3));
+        assert_eq!(recovery.truncated_bytes, buf.len() as u64);
+        assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+
+        // New snapshots are no longer hidden behind the corrupt frame
+        manager.add_snapshot(test_snapshot(300), &StringInterner::new());
+        let (snapshots, _) = manager.load_wal_snapshots().unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].timestamp, 300);
+    }
+
+    #[test]
+    fn test_finalize_recovered_wal() {
+        let dir = tempdir().unwrap();
+        let wal_path = dir.path().join("wal.log");
+        let hour_start = 1_700_002_800; // 2023-11-14 23:00:00 UTC
+        let mut buf = Vec::new();
+        write_test_wal_frame(&mut buf, &test_snapshot(hour_start + 10));
+        write_test_wal_frame(&mut buf, &test_snapshot(hour_start + 20));
+        std::fs::write(&wal_path, &buf).unwrap();
+
+        // Same hour: keep collecting into the recovered chunk
+        let mut manager = StorageManager::new(dir.path());
+        let now = DateTime::from_timestamp(hour_start + 600, 0).unwrap();
+        assert_eq!(manager.finalize_recovered_wal(now).unwrap(), None);
+        assert_eq!(manager.current_chunk_size(), 2);
+        assert_eq!(manager.current_hour, Some(23));
+        drop(manager);
+
+        // Next day: the recovered snapshots get their own hour's chunk
+        let mut manager = StorageManager::new(dir.path());
+        let now = DateTime::from_timestamp(hour_start + 7200, 0).unwrap();
+        let path = manager.finalize_recovered_wal(now).unwrap().unwrap();
+        assert_eq!(
+            path.file_name().unwrap().to_str().unwrap(),
+            "rpglot_2023-11-14_23.zst"
+        );
+        assert_eq!(manager.current_chunk_size(), 0);
+        assert_eq!(ChunkReader::open(&path).unwrap().snapshot_count(), 2);
+        assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+    }
+
     #[test]
     fn test_wal_empty_file_handled() {
         let dir = tempdir().unwrap();
         let wal_path = dir.path().join("wal.log");
-        std::fs::write(&wal_path, &[]).unwrap();
+        std::fs::write(&wal_path, []).unwrap();
 
         let manager = StorageManager::new(dir.path());
         assert_eq!(manager.current_chunk_size(), 0);
@@ -1408,4 +2010,51 @@ mod tests {
         let manager = StorageManager::new(dir.path());
         assert_eq!(manager.current_chunk_size(), 0);
     }
+
+    #[test]
+    fn test_wal_encrypted_frames() {
+        let dir = tempdir().unwrap();
+        let wal_path = dir.path().join("wal.log");
+        let key = EncryptionKey::from_bytes(&[5u8; crypto::KEY_LEN]);
+
+        let mut file = File::create(&wal_path).unwrap();
+        for ts in [100, 200] {
+            let entry = WalEntry {
+                snapshot: test_snapshot(ts),
+                interner: StringInterner::new(),
+            };
+            StorageManager::write_wal_frame(&mut file, &entry, Some(&key));
+        }
+        drop(file);
+        let data = std::fs::read(&wal_path).unwrap();
+        let length = u32::from_le_bytes(data[0..4].try_into().unwrap());
+        assert_ne!(length & WAL_ENCRYPTED_FLAG, 0);
+
+        let (entry, next) = StorageManager::read_wal_frame_validated(&data, 0, Some(&key)).unwrap();
+        assert_eq!(entry.snapshot.timestamp, 100);
+        let (entry, _) = StorageManager::read_wal_frame_validated(&data, next, Some(&key)).unwrap();
+        assert_eq!(entry.snapshot.timestamp, 200);
+
+        let wrong = EncryptionKey::from_bytes(&[6u8; crypto::KEY_LEN]);
+        assert!(matches!(
+            StorageManager::read_wal_frame_validated(&data, 0, Some(&wrong)),
+            Err(WalFrameError::DecryptionFailed(_))
+        ));
+        assert!(matches!(
+            StorageManager::read_wal_frame_validated(&data, 0, None),
+            Err(WalFrameError::MissingKey)
+        ));
+
+        // Recovery without a key must not treat encrypted frames as garbage
+        let mut buf = Vec::new();
+        write_test_wal_frame(&mut buf, &test_snapshot(50));
+        buf.extend_from_slice(&data);
+        std::fs::write(&wal_path, &buf).unwrap();
+        let manager = StorageManager::new(dir.path());
+        assert_eq!(manager.current_chunk_size(), 1);
+        assert_eq!(
+            std::fs::metadata(&wal_path).unwrap().len(),
+            buf.len() as u64
+        );
+    }
 }
@@ -1,4 +1,9 @@
+pub mod annotations;
+#[cfg(any(feature = "tui", feature = "api"))]
+pub mod bookmarks;
 pub mod chunk;
+pub mod crypto;
+pub mod gaps;
 pub mod heatmap;
 pub mod interner;
 pub mod manager;
@@ -6,5 +11,7 @@ pub mod model;
 
 pub use chunk::ChunkReader;
 pub use interner::StringInterner;
-pub use manager::{RotationConfig, RotationResult, StorageManager};
+pub use manager::{
+    BlockRetention, Durability, RotationConfig, RotationResult, StorageManager, WalRecovery,
+};
 pub use model::Snapshot;
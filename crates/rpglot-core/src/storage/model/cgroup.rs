@@ -123,3 +123,31 @@ pub struct CgroupInfo {
     #[serde(default)]
     pub io: Vec<CgroupIoInfo>,
 }
+
+/// Kubernetes pod the collector runs in, with the resources it declares.
+///
+/// Source: downward API (environment variables or a `downwardAPI` volume)
+/// and the service account namespace file.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Default)]
+pub struct K8sPodInfo {
+    /// Pod name.
+    pub pod: String,
+
+    /// Namespace of the pod.
+    pub namespace: String,
+
+    /// Node the pod is scheduled on (empty if not exposed).
+    pub node: String,
+
+    /// Declared CPU request in millicores (0 = not declared).
+    pub cpu_request_millis: u64,
+
+    /// Declared CPU limit in millicores (0 = not declared).
+    pub cpu_limit_millis: u64,
+
+    /// Declared memory request in bytes (0 = not declared).
+    pub memory_request: u64,
+
+    /// Declared memory limit in bytes (0 = not declared).
+    pub memory_limit: u64,
+}
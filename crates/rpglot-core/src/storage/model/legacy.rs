@@ -0,0 +1,686 @@
+//! Block layouts written by earlier builds.
+//!
+//! postcard is not self-describing: a stored struct that gains a field cannot
+//! decode data written without it, `#[serde(default)]` notwithstanding. Every
+//! such change bumps the kind in [`DataBlock::SCHEMA_VERSIONS`] and keeps the
+//! previous layout here as a mirror struct converted into the current one.
+//!
+//! The version recorded in an RPG8 frame index is only a hint: blocks written
+//! before versions were bumped are all labeled 1, whatever their layout. So
+//! the recorded version is tried first, then the other versions of the kind.
+//! RPG6/RPG7 frames and WAL entries record nothing; their snapshots are
+//! decoded in the current layout, then in those of earlier builds
+//! ([`EARLIER_LAYOUTS`]).
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use super::postgres::PgStatActivityInfo;
+use super::process::{
+    ProcessBlkLatInfo, ProcessCpuInfo, ProcessDskInfo, ProcessInfo, ProcessMemInfo,
+};
+use super::snapshot::{AgentPhaseTiming, AgentStatsInfo, DataBlock, PgInstanceBlocks, Snapshot};
+
+const PROCESSES: usize = 0;
+const PG_STAT_ACTIVITY: usize = 1;
+const PG_INSTANCE: usize = 33;
+const AGENT_STATS: usize = 35;
+
+/// Kind versions that differ from the current ones in data written by
+/// earlier builds, newest change first. The layout before a change also
+/// carries every change listed after it.
+const EARLIER_LAYOUTS: &[&[(usize, u8)]] = &[
+    // Before the pg_stat_ssl flag
+    &[(PG_STAT_ACTIVITY, 2), (PG_INSTANCE, 2)],
+    // Before backend_xid / backend_xmin_age
+    &[(PG_STAT_ACTIVITY, 1), (PG_INSTANCE, 1)],
+    // Before agent query timings and warnings
+    &[(AGENT_STATS, 1)],
+    // Before smaps_rollup memory
+    &[(PROCESSES, 2)],
+    // Before block I/O latency
+    &[(PROCESSES, 1)],
+];
+
+/// Decodes a block of kind `kind` recorded with schema `version` that fills
+/// `bytes`, falling back to the other layouts of the kind, newest first.
+/// The error is that of the recorded version.
+pub(crate) fn decode_block(kind: usize, version: u8, bytes: &[u8]) -> postcard::Result<DataBlock> {
+    let current = DataBlock::SCHEMA_VERSIONS.get(kind).copied().unwrap_or(1);
+    let mut first_err = None;
+    let others = (1..=current).rev().filter(|&v| v != version);
+    for v in std::iter::once(version).chain(others) {
+        let mut layout = DataBlock::SCHEMA_VERSIONS.to_vec();
+        if let Some(slot) = layout.get_mut(kind) {
+            *slot = v;
+        }
+        match take_block(bytes, &layout) {
+            Ok((block, [])) => return Ok(block),
+            Ok(_) => first_err.get_or_insert(postcard::Error::DeserializeBadEncoding),
+            Err(e) => first_err.get_or_insert(e),
+        };
+    }
+    Err(first_err.unwrap_or(postcard::Error::DeserializeBadEncoding))
+}
+
+/// Decodes `postcard(Snapshot)` at the front of `bytes` followed by what
+/// `rest` accepts (nothing in a chunk frame, the interner in a WAL entry):
+/// in the current layout, then in those of earlier builds. The error is
+/// that of the current layout.
+pub(crate) fn take_snapshot<'a, T>(
+    bytes: &'a [u8],
+    mut rest: impl FnMut(&'a [u8]) -> Option<T>,
+) -> postcard::Result<(Snapshot, T)> {
+    let mut first_err = None;
+    let mut layout = DataBlock::SCHEMA_VERSIONS.to_vec();
+    for overrides in std::iter::once(&[][..]).chain(EARLIER_LAYOUTS.iter().copied()) {
+        for &(kind, version) in overrides {
+            layout[kind] = version;
+        }
+        match take_snapshot_in(bytes, &layout) {
+            Ok((snapshot, tail)) => match rest(tail) {
+                Some(value) => return Ok((snapshot, value)),
+                None => first_err.get_or_insert(postcard::Error::DeserializeBadEncoding),
+            },
+            Err(e) => first_err.get_or_insert(e),
+        };
+    }
+    Err(first_err.unwrap_or(postcard::Error::DeserializeBadEncoding))
+}
+
+/// Decodes `postcard(Snapshot)` at the front of `bytes`, each block in the
+/// version `layout` gives for its kind.
+fn take_snapshot_in<'a>(bytes: &'a [u8], layout: &[u8]) -> postcard::Result<(Snapshot, &'a [u8])> {
+    let (timestamp, rest) = postcard::take_from_bytes::<i64>(bytes)?;
+    let (count, mut rest) = postcard::take_from_bytes::<usize>(rest)?;
+    // Bounded: the count comes from the data
+    let mut blocks = Vec::with_capacity(count.min(64));
+    for _ in 0..count {
+        let (block, tail) = take_block(rest, layout)?;
+        blocks.push(block);
+        rest = tail;
+    }
+    Ok((Snapshot { timestamp, blocks }, rest))
+}
+
+/// Decodes `postcard(DataBlock)` at the front of `bytes` in the version
+/// `layout` gives for its kind.
+fn take_block<'a>(bytes: &'a [u8], layout: &[u8]) -> postcard::Result<(DataBlock, &'a [u8])> {
+    let (kind, rest) = postcard::take_from_bytes::<u32>(bytes)?;
+    let kind = kind as usize;
+    let current = DataBlock::SCHEMA_VERSIONS.get(kind).copied();
+    let version = layout.get(kind).copied();
+    if version == current {
+        return postcard::take_from_bytes(bytes);
+    }
+    match (kind, version) {
+        (PROCESSES, Some(1)) => {
+            let (rows, rest) = take_rows::<ProcessInfoV1, _>(rest)?;
+            Ok((DataBlock::Processes(rows), rest))
+        }
+        (PROCESSES, Some(2)) => {
+            let (rows, rest) = take_rows::<ProcessInfoV2, _>(rest)?;
+            Ok((DataBlock::Processes(rows), rest))
+        }
+        (PG_STAT_ACTIVITY, Some(1)) => {
+            let (rows, rest) = take_rows::<PgStatActivityInfoV1, _>(rest)?;
+            Ok((DataBlock::PgStatActivity(rows), rest))
+        }
+        (PG_STAT_ACTIVITY, Some(2)) => {
+            let (rows, rest) = take_rows::<PgStatActivityInfoV2, _>(rest)?;
+            Ok((DataBlock::PgStatActivity(rows), rest))
+        }
+        (PG_INSTANCE, Some(version)) if Some(version) < current => {
+            take_instance(rest, layout, version)
+        }
+        (AGENT_STATS, Some(1)) => {
+            let (stats, rest) = postcard::take_from_bytes::<AgentStatsInfoV1>(rest)?;
+            Ok((DataBlock::AgentStats(stats.into()), rest))
+        }
+        _ => Err(postcard::Error::DeserializeBadEncoding),
+    }
+}
+
+/// Decodes the payload of a `pg_instance` block of an earlier `version`.
+///
+/// The version of `pg_instance` moves with that of `pg_stat_activity`: its
+/// nested blocks are in the layout of the same build.
+fn take_instance<'a>(
+    bytes: &'a [u8],
+    layout: &[u8],
+    version: u8,
+) -> postcard::Result<(DataBlock, &'a [u8])> {
+    let (name, rest) = postcard::take_from_bytes::<String>(bytes)?;
+    let (port, rest) = postcard::take_from_bytes::<u16>(rest)?;
+    let (count, mut rest) = postcard::take_from_bytes::<usize>(rest)?;
+    let mut nested = layout.to_vec();
+    nested[PG_STAT_ACTIVITY] = version;
+    let mut blocks = Vec::with_capacity(count.min(64));
+    for _ in 0..count {
+        // No nesting, as in deserialize_instance_blocks
+        let (kind, _) = postcard::take_from_bytes::<u32>(rest)?;
+        if kind as usize == PG_INSTANCE {
+            return Err(postcard::Error::DeserializeBadEncoding);
+        }
+        let (block, tail) = take_block(rest, &nested)?;
+        blocks.push(block);
+        rest = tail;
+    }
+    let instance = PgInstanceBlocks { name, port, blocks };
+    Ok((DataBlock::PgInstance(instance), rest))
+}
+
+/// Decodes a sequence of `Old` rows and converts them into the current type.
+fn take_rows<Old, New>(bytes: &[u8]) -> postcard::Result<(Vec<New>, &[u8])>
+where
+    Old: DeserializeOwned + Into<New>,
+{
+    let (rows, rest) = postcard::take_from_bytes::<Vec<Old>>(bytes)?;
+    Ok((rows.into_iter().map(Into::into).collect(), rest))
+}
+
+/// [`ProcessMemInfo`] before `pshared` / `pswap` (smaps_rollup).
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+struct ProcessMemInfoV1 {
+    minflt: u64,
+    majflt: u64,
+    vexec: u64,
+    vmem: u64,
+    rmem: u64,
+    pmem: u64,
+    vdata: u64,
+    vstack: u64,
+    vlibs: u64,
+    vswap: u64,
+    vlock: u64,
+}
+
+impl From<ProcessMemInfoV1> for ProcessMemInfo {
+    fn from(m: ProcessMemInfoV1) -> Self {
+        Self {
+            minflt: m.minflt,
+            majflt: m.majflt,
+            vexec: m.vexec,
+            vmem: m.vmem,
+            rmem: m.rmem,
+            pmem: m.pmem,
+            vdata: m.vdata,
+            vstack: m.vstack,
+            vlibs: m.vlibs,
+            vswap: m.vswap,
+            vlock: m.vlock,
+            ..Default::default()
+        }
+    }
+}
+
+/// `processes` v1: [`ProcessInfo`] before `blk_lat`, with the v1 memory layout.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+struct ProcessInfoV1 {
+    pid: u32,
+    ppid: u32,
+    uid: u32,
+    euid: u32,
+    gid: u32,
+    egid: u32,
+    tty: u16,
+    state: char,
+    num_threads: u32,
+    exit_signal: i32,
+    btime: u32,
+    name_hash: u64,
+    cmdline_hash: u64,
+    mem: ProcessMemInfoV1,
+    cpu: ProcessCpuInfo,
+    dsk: ProcessDskInfo,
+}
+
+impl From<ProcessInfoV1> for ProcessInfo {
+    fn from(p: ProcessInfoV1) -> Self {
+        Self {
+            pid: p.pid,
+            ppid: p.ppid,
+            uid: p.uid,
+            euid: p.euid,
+            gid: p.gid,
+            egid: p.egid,
+            tty: p.tty,
+            state: p.state,
+            num_threads: p.num_threads,
+            exit_signal: p.exit_signal,
+            btime: p.btime,
+            name_hash: p.name_hash,
+            cmdline_hash: p.cmdline_hash,
+            mem: p.mem.into(),
+            cpu: p.cpu,
+            dsk: p.dsk,
+            blk_lat: None,
+        }
+    }
+}
+
+/// `processes` v2: [`ProcessInfo`] with `blk_lat`, before smaps_rollup memory.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+struct ProcessInfoV2 {
+    pid: u32,
+    ppid: u32,
+    uid: u32,
+    euid: u32,
+    gid: u32,
+    egid: u32,
+    tty: u16,
+    state: char,
+    num_threads: u32,
+    exit_signal: i32,
+    btime: u32,
+    name_hash: u64,
+    cmdline_hash: u64,
+    mem: ProcessMemInfoV1,
+    cpu: ProcessCpuInfo,
+    dsk: ProcessDskInfo,
+    blk_lat: Option<ProcessBlkLatInfo>,
+}
+
+impl From<ProcessInfoV2> for ProcessInfo {
+    fn from(p: ProcessInfoV2) -> Self {
+        Self {
+            pid: p.pid,
+            ppid: p.ppid,
+            uid: p.uid,
+            euid: p.euid,
+            gid: p.gid,
+            egid: p.egid,
+            tty: p.tty,
+            state: p.state,
+            num_threads: p.num_threads,
+            exit_signal: p.exit_signal,
+            btime: p.btime,
+            name_hash: p.name_hash,
+            cmdline_hash: p.cmdline_hash,
+            mem: p.mem.into(),
+            cpu: p.cpu,
+            dsk: p.dsk,
+            blk_lat: p.blk_lat,
+        }
+    }
+}
+
+/// `pg_stat_activity` v1: [`PgStatActivityInfo`] before `backend_xid` /
+/// `backend_xmin_age`.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+struct PgStatActivityInfoV1 {
+    pid: i32,
+    datname_hash: u64,
+    usename_hash: u64,
+    application_name_hash: u64,
+    client_addr: String,
+    state_hash: u64,
+    query_hash: u64,
+    query_id: i64,
+    wait_event_type_hash: u64,
+    wait_event_hash: u64,
+    backend_type_hash: u64,
+    backend_start: f64,
+    xact_start: f64,
+    query_start: f64,
+    collected_at: f64,
+}
+
+impl From<PgStatActivityInfoV1> for PgStatActivityInfo {
+    fn from(a: PgStatActivityInfoV1) -> Self {
+        Self {
+            pid: a.pid,
+            datname_hash: a.datname_hash,
+            usename_hash: a.usename_hash,
+            application_name_hash: a.application_name_hash,
+            client_addr: a.client_addr,
+            state_hash: a.state_hash,
+            query_hash: a.query_hash,
+            query_id: a.query_id,
+            wait_event_type_hash: a.wait_event_type_hash,
+            wait_event_hash: a.wait_event_hash,
+            backend_type_hash: a.backend_type_hash,
+            backend_start: a.backend_start,
+            xact_start: a.xact_start,
+            query_start: a.query_start,
+            collected_at: a.collected_at,
+            ..Default::default()
+        }
+    }
+}
+
+/// `pg_stat_activity` v2: [`PgStatActivityInfo`] before `ssl`.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+struct PgStatActivityInfoV2 {
+    pid: i32,
+    datname_hash: u64,
+    usename_hash: u64,
+    application_name_hash: u64,
+    client_addr: String,
+    state_hash: u64,
+    query_hash: u64,
+    query_id: i64,
+    wait_event_type_hash: u64,
+    wait_event_hash: u64,
+    backend_type_hash: u64,
+    backend_start: f64,
+    xact_start: f64,
+    query_start: f64,
+    collected_at: f64,
+    backend_xid: u32,
+    backend_xmin_age: i32,
+}
+
+impl From<PgStatActivityInfoV2> for PgStatActivityInfo {
+    fn from(a: PgStatActivityInfoV2) -> Self {
+        Self {
+            pid: a.pid,
+            datname_hash: a.datname_hash,
+            usename_hash: a.usename_hash,
+            application_name_hash: a.application_name_hash,
+            client_addr: a.client_addr,
+            state_hash: a.state_hash,
+            query_hash: a.query_hash,
+            query_id: a.query_id,
+            wait_event_type_hash: a.wait_event_type_hash,
+            wait_event_hash: a.wait_event_hash,
+            backend_type_hash: a.backend_type_hash,
+            backend_start: a.backend_start,
+            xact_start: a.xact_start,
+            query_start: a.query_start,
+            collected_at: a.collected_at,
+            backend_xid: a.backend_xid,
+            backend_xmin_age: a.backend_xmin_age,
+            ssl: None,
+        }
+    }
+}
+
+/// `agent_stats` v1: [`AgentStatsInfo`] before `queries` / `warnings`.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+struct AgentStatsInfoV1 {
+    pid: u32,
+    interval_ms: u32,
+    cpu_user_ms: u32,
+    cpu_system_ms: u32,
+    rss_kb: u64,
+    rss_peak_kb: u64,
+    collect_us: u64,
+    phases: Vec<AgentPhaseTiming>,
+    storage_write_us: u64,
+}
+
+impl From<AgentStatsInfoV1> for AgentStatsInfo {
+    fn from(a: AgentStatsInfoV1) -> Self {
+        Self {
+            pid: a.pid,
+            interval_ms: a.interval_ms,
+            cpu_user_ms: a.cpu_user_ms,
+            cpu_system_ms: a.cpu_system_ms,
+            rss_kb: a.rss_kb,
+            rss_peak_kb: a.rss_peak_kb,
+            collect_us: a.collect_us,
+            phases: a.phases,
+            storage_write_us: a.storage_write_us,
+            queries: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::SystemLoadInfo;
+
+    fn process_v1(pid: u32) -> ProcessInfoV1 {
+        ProcessInfoV1 {
+            pid,
+            ppid: 1,
+            uid: 0,
+            euid: 0,
+            gid: 0,
+            egid: 0,
+            tty: 0,
+            state: 'S',
+            num_threads: 1,
+            exit_signal: 17,
+            btime: 0,
+            name_hash: 42,
+            cmdline_hash: 43,
+            mem: ProcessMemInfoV1 {
+                minflt: 0,
+                majflt: 0,
+                vexec: 0,
+                vmem: 100,
+                rmem: 50,
+                pmem: 40,
+                vdata: 0,
+                vstack: 0,
+                vlibs: 0,
+                vswap: 0,
+                vlock: 0,
+            },
+            cpu: ProcessCpuInfo::default(),
+            dsk: ProcessDskInfo::default(),
+        }
+    }
+
+    /// `postcard(DataBlock::Processes)` as written by the baseline build.
+    fn processes_v1_block(pids: &[u32]) -> Vec<u8> {
+        let rows: Vec<_> = pids.iter().map(|&pid| process_v1(pid)).collect();
+        let mut bytes = postcard::to_allocvec(&(PROCESSES as u32)).unwrap();
+        bytes.extend(postcard::to_allocvec(&rows).unwrap());
+        bytes
+    }
+
+    #[test]
+    fn decode_block_reads_processes_v1() {
+        let bytes = processes_v1_block(&[10, 11]);
+        let Ok(DataBlock::Processes(rows)) = decode_block(PROCESSES, 1, &bytes) else {
+            panic!("processes v1 block did not decode");
+        };
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].pid, 11);
+        assert_eq!(rows[0].mem.pmem, 40);
+        assert_eq!(rows[0].mem.pshared, 0);
+        assert!(rows[0].blk_lat.is_none());
+    }
+
+    #[test]
+    fn decode_block_reads_processes_v2() {
+        let p = process_v1(12);
+        let row = ProcessInfoV2 {
+            pid: p.pid,
+            ppid: p.ppid,
+            uid: p.uid,
+            euid: p.euid,
+            gid: p.gid,
+            egid: p.egid,
+            tty: p.tty,
+            state: p.state,
+            num_threads: p.num_threads,
+            exit_signal: p.exit_signal,
+            btime: p.btime,
+            name_hash: p.name_hash,
+            cmdline_hash: p.cmdline_hash,
+            mem: p.mem,
+            cpu: p.cpu,
+            dsk: p.dsk,
+            blk_lat: Some(ProcessBlkLatInfo {
+                count: 2,
+                total_us: 300,
+                slots: vec![0, 2],
+            }),
+        };
+        let mut bytes = postcard::to_allocvec(&(PROCESSES as u32)).unwrap();
+        bytes.extend(postcard::to_allocvec(&vec![row]).unwrap());
+
+        // Recorded as 1 by builds that had not bumped the version yet
+        let Ok(DataBlock::Processes(rows)) = decode_block(PROCESSES, 1, &bytes) else {
+            panic!("processes v2 block did not decode");
+        };
+        assert_eq!(rows[0].pid, 12);
+        assert_eq!(rows[0].blk_lat.as_ref().map(|b| b.count), Some(2));
+        assert_eq!(rows[0].mem.pswap, 0);
+    }
+
+    #[test]
+    fn decode_block_reads_agent_stats_v1() {
+        let stats = AgentStatsInfoV1 {
+            pid: 100,
+            interval_ms: 10_000,
+            cpu_user_ms: 5,
+            cpu_system_ms: 2,
+            rss_kb: 2048,
+            rss_peak_kb: 4096,
+            collect_us: 1500,
+            phases: vec![AgentPhaseTiming {
+                name: "postgres".to_string(),
+                us: 900,
+            }],
+            storage_write_us: 80,
+        };
+        let mut bytes = postcard::to_allocvec(&(AGENT_STATS as u32)).unwrap();
+        bytes.extend(postcard::to_allocvec(&stats).unwrap());
+
+        let Ok(DataBlock::AgentStats(decoded)) = decode_block(AGENT_STATS, 1, &bytes) else {
+            panic!("agent_stats v1 block did not decode");
+        };
+        assert_eq!(decoded.pid, 100);
+        assert_eq!(decoded.phases[0].us, 900);
+        assert_eq!(decoded.storage_write_us, 80);
+        assert!(decoded.queries.is_empty() && decoded.warnings.is_empty());
+    }
+
+    fn activity_v1(pid: i32) -> PgStatActivityInfoV1 {
+        PgStatActivityInfoV1 {
+            pid,
+            datname_hash: 1,
+            usename_hash: 2,
+            application_name_hash: 3,
+            client_addr: "10.0.0.1".to_string(),
+            state_hash: 4,
+            query_hash: 5,
+            query_id: 6,
+            wait_event_type_hash: 0,
+            wait_event_hash: 0,
+            backend_type_hash: 7,
+            backend_start: 1.0,
+            xact_start: 2.0,
+            query_start: 3.0,
+            collected_at: 4.0,
+        }
+    }
+
+    /// `postcard(DataBlock)` of kind `kind` with `payload` serialized after the tag.
+    fn block_bytes<T: serde::Serialize>(kind: usize, payload: &T) -> Vec<u8> {
+        let mut bytes = postcard::to_allocvec(&(kind as u32)).unwrap();
+        bytes.extend(postcard::to_allocvec(payload).unwrap());
+        bytes
+    }
+
+    #[test]
+    fn decode_block_reads_pg_stat_activity_v1() {
+        let bytes = block_bytes(PG_STAT_ACTIVITY, &vec![activity_v1(300)]);
+        let Ok(DataBlock::PgStatActivity(rows)) = decode_block(PG_STAT_ACTIVITY, 1, &bytes) else {
+            panic!("pg_stat_activity v1 block did not decode");
+        };
+        assert_eq!(rows[0].pid, 300);
+        assert_eq!(rows[0].client_addr, "10.0.0.1");
+        assert_eq!(rows[0].collected_at, 4.0);
+        assert_eq!(rows[0].backend_xid, 0);
+    }
+
+    #[test]
+    fn take_snapshot_reads_pg_stat_activity_v2() {
+        let a = activity_v1(302);
+        let row = PgStatActivityInfoV2 {
+            pid: a.pid,
+            datname_hash: a.datname_hash,
+            usename_hash: a.usename_hash,
+            application_name_hash: a.application_name_hash,
+            client_addr: a.client_addr,
+            state_hash: a.state_hash,
+            query_hash: a.query_hash,
+            query_id: a.query_id,
+            wait_event_type_hash: a.wait_event_type_hash,
+            wait_event_hash: a.wait_event_hash,
+            backend_type_hash: a.backend_type_hash,
+            backend_start: a.backend_start,
+            xact_start: a.xact_start,
+            query_start: a.query_start,
+            collected_at: a.collected_at,
+            backend_xid: 777,
+            backend_xmin_age: 12,
+        };
+        let mut bytes = postcard::to_allocvec(&5i64).unwrap();
+        bytes.extend(postcard::to_allocvec(&1usize).unwrap());
+        bytes.extend(block_bytes(PG_STAT_ACTIVITY, &vec![row]));
+
+        let (snapshot, ()) = take_snapshot(&bytes, |rest| rest.is_empty().then_some(())).unwrap();
+        let [DataBlock::PgStatActivity(rows)] = &snapshot.blocks[..] else {
+            panic!("pg_stat_activity v2 block did not decode");
+        };
+        assert_eq!((rows[0].backend_xid, rows[0].backend_xmin_age), (777, 12));
+        assert_eq!(rows[0].ssl, None);
+    }
+
+    #[test]
+    fn decode_block_reads_pg_instance_v1() {
+        // name, port, then the nested blocks in the layout of the same build
+        let mut bytes = block_bytes(PG_INSTANCE, &("replica", 5433u16, 1usize));
+        bytes.extend(block_bytes(PG_STAT_ACTIVITY, &vec![activity_v1(301)]));
+
+        let Ok(DataBlock::PgInstance(instance)) = decode_block(PG_INSTANCE, 1, &bytes) else {
+            panic!("pg_instance v1 block did not decode");
+        };
+        assert_eq!((instance.name.as_str(), instance.port), ("replica", 5433));
+        assert!(
+            matches!(&instance.blocks[..], [DataBlock::PgStatActivity(rows)] if rows[0].pid == 301)
+        );
+    }
+
+    #[test]
+    fn decode_block_falls_back_from_recorded_version() {
+        // Written in the current layout but labeled 1, as before the bump
+        let current = DataBlock::Processes(vec![ProcessInfo {
+            pid: 7,
+            ..Default::default()
+        }]);
+        let bytes = postcard::to_allocvec(&current).unwrap();
+        assert_eq!(decode_block(PROCESSES, 1, &bytes).unwrap(), current);
+    }
+
+    #[test]
+    fn take_snapshot_reads_earlier_layout() {
+        let load = DataBlock::SystemLoad(SystemLoadInfo {
+            lavg1: 1.5,
+            ..Default::default()
+        });
+        let mut bytes = postcard::to_allocvec(&1_700_000_000i64).unwrap();
+        bytes.extend(postcard::to_allocvec(&2usize).unwrap());
+        bytes.extend(processes_v1_block(&[10]));
+        bytes.extend(postcard::to_allocvec(&load).unwrap());
+
+        let (snapshot, ()) = take_snapshot(&bytes, |rest| rest.is_empty().then_some(())).unwrap();
+        assert_eq!(snapshot.timestamp, 1_700_000_000);
+        assert!(matches!(&snapshot.blocks[0], DataBlock::Processes(rows) if rows[0].pid == 10));
+        assert_eq!(snapshot.blocks[1], load);
+
+        // The current layout still goes through the fast path
+        let current = Snapshot {
+            timestamp: 1,
+            blocks: vec![load],
+        };
+        let bytes = postcard::to_allocvec(&current).unwrap();
+        let (snapshot, ()) = take_snapshot(&bytes, |rest| rest.is_empty().then_some(())).unwrap();
+        assert_eq!(snapshot, current);
+    }
+}
@@ -13,24 +13,37 @@
 //! enabling O(1) random access. String interning eliminates duplicate string storage.
 
 mod cgroup;
+pub(crate) mod legacy;
 mod postgres;
 mod process;
 mod snapshot;
 mod system;
 
 // Re-export all public types for convenient access
-pub use cgroup::{CgroupCpuInfo, CgroupInfo, CgroupIoInfo, CgroupMemoryInfo, CgroupPidsInfo};
+pub use cgroup::{
+    CgroupCpuInfo, CgroupInfo, CgroupIoInfo, CgroupMemoryInfo, CgroupPidsInfo, K8sPodInfo,
+};
 pub use postgres::{
-    ActivityFiltered, ErrorCategory, PgLockTreeNode, PgLogEntry, PgLogEventEntry, PgLogEventType,
-    PgLogEventsInfo, PgLogSeverity, PgSettingEntry, PgStatActivityInfo, PgStatBgwriterInfo,
-    PgStatDatabaseInfo, PgStatProgressVacuumInfo, PgStatStatementsInfo, PgStatUserIndexesInfo,
-    PgStatUserTablesInfo, PgStorePlansInfo, ReplicaInfo, ReplicationStatus,
+    ActivityFiltered, CustomColumn, CustomColumnType, CustomQueryInfo, CustomQueryRow,
+    ErrorCategory, PgBufferCacheInfo, PgBufferCacheRelation, PgConnectionChurnInfo,
+    PgConnectionState, PgConnectionStatus, PgExtensionInfo, PgLockTreeNode, PgLogEntry,
+    PgLogEventEntry, PgLogEventType, PgLogEventsInfo, PgLogSeverity, PgRelationKind,
+    PgRelationSizeInfo, PgSequenceInfo, PgSettingEntry, PgSettingMeta, PgSlowPlanInfo,
+    PgStatActivityInfo, PgStatArchiverInfo, PgStatBgwriterInfo, PgStatDatabaseInfo,
+    PgStatProgressVacuumInfo, PgStatStatementsInfo, PgStatUserIndexesInfo, PgStatUserTablesInfo,
+    PgStorePlansInfo, ReplicaInfo, ReplicationStatus,
 };
 #[allow(unused_imports)]
-pub use process::{ProcessCpuInfo, ProcessDskInfo, ProcessInfo, ProcessMemInfo};
-pub use snapshot::{DataBlock, Snapshot};
+pub use process::{
+    ProcessBlkLatInfo, ProcessCpuInfo, ProcessDskInfo, ProcessInfo, ProcessMemInfo, pg_pss_total,
+};
+pub use snapshot::{
+    AgentPhaseTiming, AgentStatsInfo, ClockInfo, CollectionDegradedInfo, DataBlock, DegradedReason,
+    PgInstanceBlocks, Snapshot,
+};
 pub use system::{
-    SystemCpuInfo, SystemDiskInfo, SystemFileInfo, SystemInterruptInfo, SystemLoadInfo,
-    SystemMemInfo, SystemNetInfo, SystemNetSnmpInfo, SystemPsiInfo, SystemSoftirqInfo,
-    SystemStatInfo, SystemVmstatInfo,
+    KernelEventInfo, KernelEventKind, SystemCpuInfo, SystemDiskInfo, SystemFileInfo,
+    SystemInterruptInfo, SystemLoadInfo, SystemMemInfo, SystemNetInfo, SystemNetSnmpInfo,
+    SystemPsiInfo, SystemSoftirqInfo, SystemStatInfo, SystemTcpConnInfo, SystemTcpRemoteInfo,
+    SystemVmstatInfo, SystemdUnitInfo,
 };
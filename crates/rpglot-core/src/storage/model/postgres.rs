@@ -98,6 +98,21 @@ pub struct PgStatActivityInfo {
     /// Used as reference for duration computation to avoid host/PG clock skew.
     #[serde(default)]
     pub collected_at: f64,
+
+    /// Transaction ID assigned to the backend's transaction (0 until it writes).
+    /// Source: `pg_stat_activity.backend_xid`
+    #[serde(default)]
+    pub backend_xid: u32,
+
+    /// Age in transactions of the backend's xmin horizon (0 if none).
+    /// Source: `age(pg_stat_activity.backend_xmin)`
+    /// Note: The session's snapshot keeps vacuum from removing rows newer than it.
+    #[serde(default)]
+    pub backend_xmin_age: i32,
+    /// Connection uses TLS (`pg_stat_ssl.ssl`). `None` in snapshots taken
+    /// before it was collected.
+    #[serde(default)]
+    pub ssl: Option<bool>,
 }
 
 /// Query statistics from pg_stat_statements extension.
@@ -775,6 +790,59 @@ pub struct PgStatBgwriterInfo {
     pub buffers_alloc: i64,
 }
 
+/// WAL archiver statistics.
+///
+/// Source: `pg_stat_archiver` (singleton view), plus the WAL file being
+/// written and `wal_segment_size` to measure the backlog. Collected only
+/// when `archive_mode` is not `off`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct PgStatArchiverInfo {
+    /// WAL files archived (cumulative).
+    pub archived_count: i64,
+    /// Failed archive attempts (cumulative).
+    pub failed_count: i64,
+    /// Name of the last WAL file archived ("" if none).
+    pub last_archived_wal: String,
+    /// Time of the last successful archive (epoch seconds, 0 if none).
+    pub last_archived_time: i64,
+    /// Name of the WAL file of the last failed attempt ("" if none).
+    pub last_failed_wal: String,
+    /// Time of the last failed attempt (epoch seconds, 0 if none).
+    pub last_failed_time: i64,
+    /// WAL file being written (`pg_walfile_name(pg_current_wal_lsn())`),
+    /// "" on a standby.
+    pub current_wal: String,
+    /// WAL segment size in bytes.
+    pub wal_segment_size: u64,
+}
+
+impl PgStatArchiverInfo {
+    /// True if the most recent archive attempt failed.
+    pub fn is_failing(&self) -> bool {
+        self.last_failed_time > self.last_archived_time
+    }
+
+    /// Completed WAL segments written but not archived yet: between the
+    /// last archived file and the one being written. `None` on a standby,
+    /// before the first archived file or after a timeline history file.
+    pub fn pending_segments(&self) -> Option<u64> {
+        let current = wal_segment_number(&self.current_wal, self.wal_segment_size)?;
+        let archived = wal_segment_number(&self.last_archived_wal, self.wal_segment_size)?;
+        Some(current.saturating_sub(archived).saturating_sub(1))
+    }
+}
+
+/// Segment number of a WAL file name (`TTTTTTTTXXXXXXXXYYYYYYYY`, hex
+/// timeline, log and segment), ignoring the timeline.
+fn wal_segment_number(name: &str, segment_size: u64) -> Option<u64> {
+    if segment_size == 0 || name.len() < 24 || !name[..24].bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let log = u64::from_str_radix(&name[8..16], 16).ok()?;
+    let seg = u64::from_str_radix(&name[16..24], 16).ok()?;
+    Some(log * (0x1_0000_0000 / segment_size) + seg)
+}
+
 // ---------------------------------------------------------------------------
 // PostgreSQL log errors
 // ---------------------------------------------------------------------------
@@ -947,6 +1015,25 @@ pub struct PgLogEventEntry {
     pub count: u16,
 }
 
+/// Plan of a slow execution logged by `auto_explain`, grouped per interval.
+///
+/// Grouped by queryid when the log carries it (`Query Identifier:` needs
+/// `auto_explain.log_verbose` and `compute_query_id`), otherwise by the
+/// normalized query text. Keeps the plan of the slowest execution.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct PgSlowPlanInfo {
+    /// Query identifier, 0 if the log line does not carry it.
+    pub queryid: i64,
+    /// Duration of the slowest execution in the interval (ms).
+    pub duration_ms: f64,
+    /// Executions of this query logged in the interval.
+    pub count: u32,
+    /// Query text (`Query Text:`, through StringInterner). 0 if absent.
+    pub query_hash: u64,
+    /// Plan text of the slowest execution (through StringInterner).
+    pub plan_hash: u64,
+}
+
 /// Real-time vacuum progress from pg_stat_progress_vacuum (PG 9.6+).
 ///
 /// Each row represents one currently running VACUUM operation.
@@ -1029,6 +1116,237 @@ pub struct PgSettingEntry {
     pub unit: String,
 }
 
+/// Where a setting belongs and where its value comes from.
+///
+/// Stored in its own block next to [`PgSettingEntry`], matched by name.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct PgSettingMeta {
+    /// Setting name (e.g. "work_mem").
+    pub name: String,
+    /// pg_settings.category (e.g. "Resource Usage / Memory").
+    pub category: String,
+    /// pg_settings.source (e.g. "default", "configuration file", "command line").
+    pub source: String,
+    /// Value the server starts with when nothing else sets it (pg_settings.boot_val).
+    pub boot_val: String,
+}
+
+impl PgSettingMeta {
+    /// Whether the value is set by the configuration (files, command line,
+    /// `ALTER SYSTEM/DATABASE/ROLE`, environment) rather than the built-in or
+    /// computed default or the collector's own session.
+    pub fn is_non_default(&self) -> bool {
+        !matches!(
+            self.source.as_str(),
+            "default" | "override" | "client" | "session"
+        )
+    }
+}
+
+/// Extension installed in one database (`pg_extension`).
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Default)]
+pub struct PgExtensionInfo {
+    /// Database the extension is installed in.
+    pub datname: String,
+    /// Extension name (e.g. "pg_stat_statements").
+    pub name: String,
+    /// Installed version (pg_extension.extversion).
+    pub version: String,
+    /// Schema holding the extension's objects.
+    pub schema: String,
+}
+
+/// Kind of a relation in [`PgRelationSizeInfo`].
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum PgRelationKind {
+    /// Table or materialized view.
+    Table,
+    Index,
+}
+
+impl PgRelationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Table => "table",
+            Self::Index => "index",
+        }
+    }
+}
+
+/// On-disk size of one of the largest relations of a database.
+///
+/// Names are stored as plain strings: the block is written once per hour
+/// and read on its own for growth over a range.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PgRelationSizeInfo {
+    /// Source: `current_database()`
+    pub datname: String,
+    /// Source: `pg_namespace.nspname`
+    pub schemaname: String,
+    /// Source: `pg_class.relname`
+    pub relname: String,
+    /// Source: `pg_class.oid`
+    pub relid: u32,
+    pub kind: PgRelationKind,
+    /// Tables: `pg_total_relation_size` (heap, TOAST and indexes);
+    /// indexes: `pg_relation_size`.
+    pub bytes: u64,
+}
+
+/// A sequence feeding an integer primary key column.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PgSequenceInfo {
+    /// Source: `current_database()`
+    pub datname: String,
+    /// Source: `pg_sequences.schemaname`
+    pub schemaname: String,
+    /// Source: `pg_sequences.sequencename`
+    pub sequencename: String,
+    /// Table of the primary key. Source: `pg_class.relname`
+    pub relname: String,
+    /// Primary key column. Source: `pg_attribute.attname`
+    pub attname: String,
+    /// Column type: `smallint`, `integer` or `bigint`.
+    pub data_type: String,
+    /// Source: `pg_sequences.last_value`
+    pub last_value: i64,
+    /// Largest value the column can take from the sequence: the smaller of
+    /// `pg_sequences.max_value` and the maximum of the column type.
+    pub max_value: i64,
+}
+
+impl PgSequenceInfo {
+    /// Share of the positive range already handed out, 0.0..=1.0.
+    pub fn used_fraction(&self) -> f64 {
+        if self.max_value <= 0 {
+            return 0.0;
+        }
+        (self.last_value.max(0) as f64 / self.max_value as f64).min(1.0)
+    }
+}
+
+/// Contents of shared_buffers, from the `pg_buffercache` extension.
+///
+/// Sampled every 5 minutes; names are stored as plain strings like the other
+/// low-frequency blocks.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct PgBufferCacheInfo {
+    /// Buffers in shared_buffers. Source: `count(*) FROM pg_buffercache`
+    pub total_buffers: u64,
+    /// Buffers holding a page. Source: `count(relfilenode)`
+    pub used_buffers: u64,
+    /// Source: `count(*) FILTER (WHERE isdirty)`
+    pub dirty_buffers: u64,
+    /// Buffers with `usagecount >= 3`, i.e. pages that are re-read before eviction.
+    pub hot_buffers: u64,
+    /// Source: `current_setting('block_size')`
+    pub block_size: u32,
+    /// Relations holding the most buffers, per database with the extension.
+    pub relations: Vec<PgBufferCacheRelation>,
+}
+
+/// Buffers held by one relation.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PgBufferCacheRelation {
+    /// Source: `current_database()`
+    pub datname: String,
+    /// Source: `pg_namespace.nspname`
+    pub schemaname: String,
+    /// Source: `pg_class.relname`
+    pub relname: String,
+    pub kind: PgRelationKind,
+    pub buffers: u64,
+    pub dirty_buffers: u64,
+    /// Mean `pg_buffercache.usagecount` (0..=5) of the relation's buffers.
+    pub avg_usagecount: f64,
+}
+
+/// Health of the collector's connection to PostgreSQL.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum PgConnectionState {
+    /// Connected (recorded once, after (re)connecting).
+    Connected,
+    /// Connection lost or refused; retrying with backoff.
+    Reconnecting,
+    /// Several consecutive attempts failed.
+    Down,
+}
+
+impl PgConnectionState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Connected => "connected",
+            Self::Reconnecting => "reconnecting",
+            Self::Down => "down",
+        }
+    }
+}
+
+/// Connection state of the collector, recorded while the connection is not
+/// healthy and on every state change.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PgConnectionStatus {
+    pub state: PgConnectionState,
+    /// Unix time the connection was established or lost.
+    pub since: i64,
+    /// Consecutive failed connection attempts.
+    pub failures: u32,
+    /// Unix time of the next connection attempt, 0 when connected.
+    pub next_attempt: i64,
+    /// Last connection error, empty when connected.
+    pub error: String,
+}
+
+/// Connection churn for a snapshot interval.
+///
+/// Log-based counters require `log_connections` / `log_disconnections`;
+/// `new_backends` is derived from `pg_stat_activity.backend_start` and is
+/// available even when connection logging is disabled.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct PgConnectionChurnInfo {
+    /// Length of the measured interval in seconds.
+    pub interval_s: f64,
+    /// `connection received` log lines in this interval.
+    pub received: u32,
+    /// `connection authorized` log lines in this interval.
+    pub authorized: u32,
+    /// `disconnection` log lines in this interval.
+    pub disconnections: u32,
+    /// Disconnections with a session time below the short-lived threshold.
+    pub short_lived: u32,
+    /// Sum of session times of disconnected sessions (seconds).
+    pub total_session_s: f64,
+    /// Client backends started during this interval (from pg_stat_activity).
+    pub new_backends: u32,
+}
+
+impl PgConnectionChurnInfo {
+    /// New connections in the interval: log lines when available,
+    /// otherwise backends observed in pg_stat_activity.
+    pub fn connections(&self) -> u32 {
+        self.received.max(self.authorized).max(self.new_backends)
+    }
+
+    /// New connections per second.
+    pub fn connections_per_sec(&self) -> f64 {
+        if self.interval_s > 0.0 {
+            self.connections() as f64 / self.interval_s
+        } else {
+            0.0
+        }
+    }
+
+    /// Fraction (0..1) of disconnected sessions that were short-lived.
+    /// `None` without disconnection logging.
+    pub fn short_lived_ratio(&self) -> Option<f64> {
+        if self.disconnections > 0 {
+            Some(self.short_lived as f64 / self.disconnections as f64)
+        } else {
+            None
+        }
+    }
+}
+
 // ============================================================
 // ActivityFiltered implementations
 // ============================================================
@@ -1104,3 +1422,62 @@ impl ActivityFiltered for PgStatUserIndexesInfo {
             || self.idx_blks_hit != prev.idx_blks_hit
     }
 }
+
+/// How a column of a user-defined query is read and displayed.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomColumnType {
+    /// Row label (text or integer), part of the row key.
+    Key,
+    /// Integer value.
+    Int,
+    /// Floating point value.
+    Float,
+}
+
+/// Column of a user-defined query, as declared in the custom queries file.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CustomColumn {
+    /// Column name in the query result.
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: CustomColumnType,
+}
+
+/// One result row of a user-defined query.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct CustomQueryRow {
+    /// Values of the key columns, in declaration order (empty string for NULL).
+    pub keys: Vec<String>,
+    /// Values of the int/float columns, in declaration order (`None` for NULL).
+    pub values: Vec<Option<f64>>,
+}
+
+/// Result of a user-defined SQL metric query.
+///
+/// Self-describing: the column list is stored with the rows so history
+/// can be rendered without the custom queries file.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CustomQueryInfo {
+    /// Query name from the custom queries file.
+    pub name: String,
+    /// Declared columns (key and value columns interleaved as declared).
+    pub columns: Vec<CustomColumn>,
+    pub rows: Vec<CustomQueryRow>,
+}
+
+impl CustomQueryInfo {
+    /// Key columns in declaration order.
+    pub fn key_columns(&self) -> impl Iterator<Item = &CustomColumn> {
+        self.columns
+            .iter()
+            .filter(|c| c.kind == CustomColumnType::Key)
+    }
+
+    /// Value (int/float) columns in declaration order.
+    pub fn value_columns(&self) -> impl Iterator<Item = &CustomColumn> {
+        self.columns
+            .iter()
+            .filter(|c| c.kind != CustomColumnType::Key)
+    }
+}
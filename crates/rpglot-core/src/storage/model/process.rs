@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 
 /// Memory statistics for a single process.
 ///
-/// Source: `/proc/[pid]/stat`, `/proc/[pid]/status`, `/proc/[pid]/smaps`
+/// Source: `/proc/[pid]/stat`, `/proc/[pid]/status`, `/proc/[pid]/smaps_rollup`
 ///
 /// All memory values are in kilobytes (Kb) unless otherwise noted.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
@@ -33,7 +33,8 @@ pub struct ProcessMemInfo {
     pub rmem: u64,
 
     /// Proportional set size - memory accounting for shared pages (Kb).
-    /// Source: `/proc/[pid]/smaps` (Pss)
+    /// Source: `/proc/[pid]/smaps_rollup` (Pss); 0 when not read (only the
+    /// largest PostgreSQL processes are sampled)
     pub pmem: u64,
 
     /// Virtual memory used for data segment (Kb).
@@ -55,6 +56,34 @@ pub struct ProcessMemInfo {
     /// Locked virtual memory that cannot be swapped (Kb).
     /// Source: `/proc/[pid]/status` (VmLck)
     pub vlock: u64,
+
+    /// Resident memory shared with other processes (Kb), e.g. shared_buffers.
+    /// Source: `/proc/[pid]/smaps_rollup` (Shared_Clean + Shared_Dirty)
+    #[serde(default)]
+    pub pshared: u64,
+
+    /// Proportional share of swapped-out memory (Kb).
+    /// Source: `/proc/[pid]/smaps_rollup` (SwapPss)
+    #[serde(default)]
+    pub pswap: u64,
+}
+
+impl ProcessMemInfo {
+    /// Memory attributable to the process (Kb): PSS when smaps_rollup was
+    /// read, RSS otherwise. RSS counts every shared page (shared_buffers)
+    /// in full for each process mapping it.
+    pub fn resident(&self) -> u64 {
+        if self.pmem > 0 { self.pmem } else { self.rmem }
+    }
+}
+
+/// Memory really used by PostgreSQL (Kb): the PSS sum of the processes whose
+/// smaps_rollup was read, which counts shared_buffers once. `None` when no
+/// process was sampled.
+pub fn pg_pss_total(processes: &[ProcessInfo]) -> Option<u64> {
+    let mut sampled = processes.iter().filter(|p| p.mem.pmem > 0).peekable();
+    sampled.peek()?;
+    Some(sampled.map(|p| p.mem.pmem).sum())
 }
 
 /// CPU statistics for a single process.
@@ -147,6 +176,49 @@ pub struct ProcessDskInfo {
     pub cwsz: u64,
 }
 
+/// Block I/O request latency for a single process.
+///
+/// Source: eBPF tracepoints `block:block_rq_issue` / `block:block_rq_complete`
+/// (requires the `ebpf` feature). Counters cover the interval since the
+/// previous snapshot, not the process lifetime.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct ProcessBlkLatInfo {
+    /// Completed block I/O requests issued by the process.
+    pub count: u64,
+
+    /// Sum of request latencies (microseconds).
+    pub total_us: u64,
+
+    /// Log2 latency histogram: `slots[i]` counts requests that took
+    /// `[2^i, 2^(i+1))` microseconds. Trailing empty slots are omitted.
+    pub slots: Vec<u64>,
+}
+
+impl ProcessBlkLatInfo {
+    /// Mean request latency (microseconds), `None` without requests.
+    pub fn avg_us(&self) -> Option<u64> {
+        (self.count > 0).then(|| self.total_us / self.count)
+    }
+
+    /// Upper bound of the histogram slot holding quantile `q` (0..1),
+    /// in microseconds. `None` without requests.
+    pub fn quantile_us(&self, q: f64) -> Option<u64> {
+        let total: u64 = self.slots.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let target = ((total as f64 * q).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, &n) in self.slots.iter().enumerate() {
+            seen += n;
+            if seen >= target {
+                return Some(1u64 << (i + 1));
+            }
+        }
+        Some(1u64 << self.slots.len())
+    }
+}
+
 /// Complete process information combining identity, memory, CPU, disk, and network stats.
 ///
 /// This is the main structure for storing per-process metrics, similar to
@@ -217,4 +289,9 @@ pub struct ProcessInfo {
 
     /// Disk I/O statistics (see ProcessDskInfo).
     pub dsk: ProcessDskInfo,
+
+    /// Block I/O latency since the previous snapshot (see ProcessBlkLatInfo).
+    /// Only present when the eBPF collector is enabled.
+    #[serde(default)]
+    pub blk_lat: Option<ProcessBlkLatInfo>,
 }
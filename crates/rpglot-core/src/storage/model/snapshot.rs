@@ -4,20 +4,25 @@
 //! Each snapshot is stored as an independent zstd frame within a chunk file,
 //! enabling O(1) random access to any snapshot.
 
-use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 
-use super::cgroup::CgroupInfo;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::cgroup::{CgroupInfo, K8sPodInfo};
 use super::postgres::{
-    PgLockTreeNode, PgLogEntry, PgLogEventEntry, PgLogEventsInfo, PgSettingEntry,
-    PgStatActivityInfo, PgStatBgwriterInfo, PgStatDatabaseInfo, PgStatProgressVacuumInfo,
+    CustomQueryInfo, PgBufferCacheInfo, PgConnectionChurnInfo, PgConnectionStatus, PgExtensionInfo,
+    PgLockTreeNode, PgLogEntry, PgLogEventEntry, PgLogEventsInfo, PgRelationSizeInfo,
+    PgSequenceInfo, PgSettingEntry, PgSettingMeta, PgSlowPlanInfo, PgStatActivityInfo,
+    PgStatArchiverInfo, PgStatBgwriterInfo, PgStatDatabaseInfo, PgStatProgressVacuumInfo,
     PgStatStatementsInfo, PgStatUserIndexesInfo, PgStatUserTablesInfo, PgStorePlansInfo,
     ReplicationStatus,
 };
 use super::process::ProcessInfo;
 use super::system::{
-    SystemCpuInfo, SystemDiskInfo, SystemFileInfo, SystemInterruptInfo, SystemLoadInfo,
-    SystemMemInfo, SystemNetInfo, SystemNetSnmpInfo, SystemPsiInfo, SystemSoftirqInfo,
-    SystemStatInfo, SystemVmstatInfo,
+    KernelEventInfo, SystemCpuInfo, SystemDiskInfo, SystemFileInfo, SystemInterruptInfo,
+    SystemLoadInfo, SystemMemInfo, SystemNetInfo, SystemNetSnmpInfo, SystemPsiInfo,
+    SystemSoftirqInfo, SystemStatInfo, SystemTcpConnInfo, SystemVmstatInfo, SystemdUnitInfo,
 };
 
 /// A block of data of a specific type within a snapshot.
@@ -141,8 +146,470 @@ pub enum DataBlock {
     /// Replication status (primary/standby, replica info).
     /// Source: `pg_is_in_recovery()`, `pg_stat_replication`, `pg_last_xact_replay_timestamp()`
     ReplicationStatus(ReplicationStatus),
+
+    /// Connection churn (new connections, short-lived sessions) for the interval.
+    /// Source: PostgreSQL log files (`log_connections`/`log_disconnections`)
+    /// + `pg_stat_activity.backend_start`
+    PgConnectionChurn(PgConnectionChurnInfo),
+
+    /// Results of user-defined SQL metric queries.
+    /// Source: queries from the `--custom-queries` file
+    CustomQuery(Vec<CustomQueryInfo>),
+
+    /// TCP connections to the PostgreSQL port by state and remote host.
+    /// Source: `/proc/net/tcp`, `/proc/net/tcp6`
+    SystemTcpConn(SystemTcpConnInfo),
+
+    /// Kernel events (OOM kills, I/O errors, read-only remounts) for the interval.
+    /// Source: `/dev/kmsg`
+    KernelEvents(Vec<KernelEventInfo>),
+
+    /// State of the PostgreSQL systemd unit.
+    /// Source: `systemctl show`
+    SystemdUnit(SystemdUnitInfo),
+
+    /// PostgreSQL blocks of an additional instance on the same host.
+    /// Source: the daemon's instance list; the first instance's blocks stay top-level
+    PgInstance(PgInstanceBlocks),
+
+    /// Marker: expensive collectors were skipped because the host was overloaded.
+    /// Source: the collector's adaptive throttling
+    CollectionDegraded(CollectionDegradedInfo),
+
+    /// Resource usage of the collecting daemon itself.
+    /// Source: rpglotd (`/proc/self/stat`, `/proc/self/status`, collector timings)
+    AgentStats(AgentStatsInfo),
+
+    /// Kubernetes pod metadata and declared resources.
+    /// Source: downward API (container mode)
+    K8sPod(K8sPodInfo),
+
+    /// State of the collector's PostgreSQL connection (outages, reconnects).
+    /// Source: the collector's connection supervisor
+    PgConnection(PgConnectionStatus),
+
+    /// Sizes of the largest tables and indexes (collected once per hour).
+    /// Source: `pg_total_relation_size()`, `pg_relation_size()` in every database
+    PgRelationSizes(Vec<PgRelationSizeInfo>),
+
+    /// Sequences of integer primary keys (collected once per hour).
+    /// Source: `pg_sequences` in every database
+    PgSequences(Vec<PgSequenceInfo>),
+
+    /// Relations occupying shared_buffers (collected every 5 minutes).
+    /// Source: `pg_buffercache` in every database with the extension
+    PgBufferCache(PgBufferCacheInfo),
+
+    /// WAL archiver statistics (singleton), when `archive_mode` is on.
+    /// Source: `pg_stat_archiver`
+    PgStatArchiver(PgStatArchiverInfo),
+
+    /// Sequence number and monotonic time of the snapshot.
+    /// Source: the collector (`Instant`), independent of the wall clock
+    Clock(ClockInfo),
+
+    /// Plans of slow executions captured by `auto_explain`.
+    /// Source: PostgreSQL log files (`duration: ... plan:` messages)
+    PgSlowPlans(Vec<PgSlowPlanInfo>),
+
+    /// Names of settings changed in the configuration files that take
+    /// effect only after a restart; absent when nothing is pending.
+    /// Source: `pg_settings` (`pending_restart`)
+    PgPendingRestart(Vec<String>),
+
+    /// Category, source and boot value of every setting, by name.
+    /// Source: `pg_settings`, collected together with [`DataBlock::PgSettings`]
+    PgSettingsMeta(Vec<PgSettingMeta>),
+
+    /// Extensions installed in every database, sampled hourly.
+    /// Source: `pg_extension` via per-database connections
+    PgExtensions(Vec<PgExtensionInfo>),
 }
 
+/// Instance-scoped blocks of an additional PostgreSQL instance.
+///
+/// Strings are interned in the snapshot's interner like top-level blocks.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PgInstanceBlocks {
+    /// Instance name from the daemon configuration.
+    pub name: String,
+    /// PostgreSQL port.
+    pub port: u16,
+    /// Blocks for which [`DataBlock::is_instance_scoped`] is true.
+    #[serde(deserialize_with = "deserialize_instance_blocks")]
+    pub blocks: Vec<DataBlock>,
+}
+
+thread_local! {
+    /// Set while the blocks of a `pg_instance` block are deserialized.
+    static IN_INSTANCE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Deserializes the blocks of an instance, rejecting a `pg_instance` block
+/// nested in another: the daemon never writes one, and unbounded nesting in
+/// a corrupted frame would overflow the stack.
+fn deserialize_instance_blocks<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<DataBlock>, D::Error> {
+    if IN_INSTANCE.get() {
+        return Err(D::Error::custom("nested pg_instance block"));
+    }
+    IN_INSTANCE.set(true);
+    let blocks = Vec::deserialize(deserializer);
+    IN_INSTANCE.set(false);
+    blocks
+}
+
+/// Upper bound for the rows of one block. An order of magnitude above the
+/// largest blocks collected, it rejects garbage lengths in a corrupted frame
+/// before they reach conversion and request handlers.
+pub const MAX_BLOCK_ROWS: usize = 1_000_000;
+
+/// Why expensive collectors were skipped.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum DegradedReason {
+    /// A snapshot took too large a share of the snapshot interval.
+    SlowCollection,
+    /// CPU or I/O pressure (PSI) was critical.
+    Pressure,
+}
+
+/// A snapshot collected without refreshing pg_stat_statements, pg_store_plans
+/// and table / index statistics; their blocks repeat the last collected data.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CollectionDegradedInfo {
+    /// What triggered throttling.
+    pub reason: DegradedReason,
+    /// Collection time of the snapshot that triggered throttling (milliseconds).
+    pub collection_ms: u32,
+    /// Highest CPU / I/O `some avg10` pressure at that time (percent).
+    pub psi_some_avg10: f32,
+    /// Throttled snapshots left after this one, unless load stays high.
+    pub remaining: u32,
+}
+
+/// Resource usage of rpglotd, recorded in every snapshot it writes.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct AgentStatsInfo {
+    /// Daemon process ID.
+    pub pid: u32,
+    /// Wall time since the previous sample (milliseconds, 0 for the first one).
+    pub interval_ms: u32,
+    /// CPU time in user mode since the previous sample (milliseconds).
+    pub cpu_user_ms: u32,
+    /// CPU time in kernel mode since the previous sample (milliseconds).
+    pub cpu_system_ms: u32,
+    /// Resident set size (KiB).
+    pub rss_kb: u64,
+    /// Peak resident set size (KiB).
+    pub rss_peak_kb: u64,
+    /// Collection time of this snapshot (microseconds).
+    pub collect_us: u64,
+    /// Collection time per subsystem, subsystems that ran only.
+    pub phases: Vec<AgentPhaseTiming>,
+    /// Time to write the previous snapshot to storage, WAL included (microseconds).
+    pub storage_write_us: u64,
+    /// Elapsed time of the PostgreSQL collection queries that ran
+    /// (`pg_stat_statements`, `pg_stat_user_tables`, ...).
+    #[serde(default)]
+    pub queries: Vec<AgentPhaseTiming>,
+    /// Collector warnings, e.g. queries paused after repeated timeouts.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Position of a snapshot in its collector run.
+///
+/// The wall-clock `Snapshot::timestamp` can go backwards (NTP stepping the
+/// clock, manual changes); `seq` and `monotonic_ms` never do within a run.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ClockInfo {
+    /// Identifies the collector run: its start time (microseconds since epoch).
+    pub run_id: u64,
+    /// Sequence number of the snapshot in the run, from 1.
+    pub seq: u64,
+    /// Monotonic time since the start of the run (milliseconds).
+    pub monotonic_ms: u64,
+}
+
+impl ClockInfo {
+    /// Milliseconds elapsed since `prev`, if both belong to the same run.
+    pub fn elapsed_ms(&self, prev: &ClockInfo) -> Option<u64> {
+        (self.run_id == prev.run_id && self.seq > prev.seq)
+            .then(|| self.monotonic_ms.saturating_sub(prev.monotonic_ms))
+    }
+}
+
+/// Collection time of one collector subsystem.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct AgentPhaseTiming {
+    /// Subsystem name (`processes`, `pg_statements`, ...).
+    pub name: String,
+    /// Time spent (microseconds).
+    pub us: u64,
+}
+
+impl AgentStatsInfo {
+    /// CPU usage over the interval (percent of one core), `None` for the first sample.
+    pub fn cpu_pct(&self) -> Option<f64> {
+        (self.interval_ms > 0).then(|| {
+            (self.cpu_user_ms + self.cpu_system_ms) as f64 * 100.0 / self.interval_ms as f64
+        })
+    }
+}
+
+impl DataBlock {
+    /// snake_case names of all block kinds, indexed by [`DataBlock::kind_index`].
+    pub const KIND_NAMES: &'static [&'static str] = &[
+        "processes",
+        "pg_stat_activity",
+        "pg_stat_statements",
+        "pg_store_plans",
+        "pg_stat_database",
+        "pg_stat_user_tables",
+        "pg_stat_user_indexes",
+        "pg_lock_tree",
+        "pg_stat_bgwriter",
+        "system_cpu",
+        "system_load",
+        "system_mem",
+        "system_net",
+        "system_disk",
+        "system_psi",
+        "system_vmstat",
+        "system_file",
+        "system_interrupts",
+        "system_softirqs",
+        "system_stat",
+        "system_net_snmp",
+        "cgroup",
+        "pg_log_errors",
+        "pg_log_events",
+        "pg_log_detailed_events",
+        "pg_settings",
+        "pg_stat_progress_vacuum",
+        "replication_status",
+        "pg_connection_churn",
+        "custom_query",
+        "system_tcp_conn",
+        "kernel_events",
+        "systemd_unit",
+        "pg_instance",
+        "collection_degraded",
+        "agent_stats",
+        "k8s_pod",
+        "pg_connection",
+        "pg_relation_sizes",
+        "pg_sequences",
+        "pg_buffercache",
+        "pg_stat_archiver",
+        "clock",
+        "pg_slow_plans",
+        "pg_pending_restart",
+        "pg_settings_meta",
+        "pg_extensions",
+    ];
+
+    /// Position of the variant in the enum. Stable: it is also the postcard tag.
+    pub fn kind_index(&self) -> usize {
+        match self {
+            Self::Processes(_) => 0,
+            Self::PgStatActivity(_) => 1,
+            Self::PgStatStatements(_) => 2,
+            Self::PgStorePlans(_) => 3,
+            Self::PgStatDatabase(_) => 4,
+            Self::PgStatUserTables(_) => 5,
+            Self::PgStatUserIndexes(_) => 6,
+            Self::PgLockTree(_) => 7,
+            Self::PgStatBgwriter(_) => 8,
+            Self::SystemCpu(_) => 9,
+            Self::SystemLoad(_) => 10,
+            Self::SystemMem(_) => 11,
+            Self::SystemNet(_) => 12,
+            Self::SystemDisk(_) => 13,
+            Self::SystemPsi(_) => 14,
+            Self::SystemVmstat(_) => 15,
+            Self::SystemFile(_) => 16,
+            Self::SystemInterrupts(_) => 17,
+            Self::SystemSoftirqs(_) => 18,
+            Self::SystemStat(_) => 19,
+            Self::SystemNetSnmp(_) => 20,
+            Self::Cgroup(_) => 21,
+            Self::PgLogErrors(_) => 22,
+            Self::PgLogEvents(_) => 23,
+            Self::PgLogDetailedEvents(_) => 24,
+            Self::PgSettings(_) => 25,
+            Self::PgStatProgressVacuum(_) => 26,
+            Self::ReplicationStatus(_) => 27,
+            Self::PgConnectionChurn(_) => 28,
+            Self::CustomQuery(_) => 29,
+            Self::SystemTcpConn(_) => 30,
+            Self::KernelEvents(_) => 31,
+            Self::SystemdUnit(_) => 32,
+            Self::PgInstance(_) => 33,
+            Self::CollectionDegraded(_) => 34,
+            Self::AgentStats(_) => 35,
+            Self::K8sPod(_) => 36,
+            Self::PgConnection(_) => 37,
+            Self::PgRelationSizes(_) => 38,
+            Self::PgSequences(_) => 39,
+            Self::PgBufferCache(_) => 40,
+            Self::PgStatArchiver(_) => 41,
+            Self::Clock(_) => 42,
+            Self::PgSlowPlans(_) => 43,
+            Self::PgPendingRestart(_) => 44,
+            Self::PgSettingsMeta(_) => 45,
+            Self::PgExtensions(_) => 46,
+        }
+    }
+
+    /// Schema version of each block kind, indexed by [`DataBlock::kind_index`].
+    ///
+    /// Recorded per block in the chunk frame index. Bump the version of a
+    /// kind whenever its postcard layout changes (a field added to a stored
+    /// struct, a new enum variant): builds that know an older version skip
+    /// such blocks instead of misreading them. Readers of the bumped version
+    /// must keep decoding the old layout (see `legacy`).
+    pub const SCHEMA_VERSIONS: &'static [u8] = &{
+        let mut versions = [1; 47];
+        // 2: ProcessInfo::blk_lat, 3: ProcessMemInfo::pshared / pswap
+        versions[0] = 3;
+        // 2: PgStatActivityInfo::backend_xid / backend_xmin_age, 3: ssl
+        versions[1] = 3;
+        // pg_instance: same as the pg_stat_activity nested in it
+        versions[33] = 3;
+        // 2: AgentStatsInfo::queries / warnings
+        versions[35] = 2;
+        versions
+    };
+
+    /// Schema version of this block's kind (see [`DataBlock::SCHEMA_VERSIONS`]).
+    pub fn schema_version(&self) -> u8 {
+        Self::SCHEMA_VERSIONS[self.kind_index()]
+    }
+
+    /// [`DataBlock::kind_index`] of the kind named `name` (see [`DataBlock::KIND_NAMES`]).
+    pub fn kind_by_name(name: &str) -> Option<usize> {
+        Self::KIND_NAMES.iter().position(|k| *k == name)
+    }
+
+    /// Whether the block describes one PostgreSQL instance (as opposed to the
+    /// host). These blocks are collected per instance.
+    pub fn is_instance_scoped(&self) -> bool {
+        matches!(
+            self,
+            Self::PgStatActivity(_)
+                | Self::PgStatStatements(_)
+                | Self::PgStorePlans(_)
+                | Self::PgStatDatabase(_)
+                | Self::PgStatUserTables(_)
+                | Self::PgStatUserIndexes(_)
+                | Self::PgLockTree(_)
+                | Self::PgStatBgwriter(_)
+                | Self::PgLogErrors(_)
+                | Self::PgLogEvents(_)
+                | Self::PgLogDetailedEvents(_)
+                | Self::PgSettings(_)
+                | Self::PgStatProgressVacuum(_)
+                | Self::ReplicationStatus(_)
+                | Self::PgConnectionChurn(_)
+                | Self::CustomQuery(_)
+                | Self::SystemTcpConn(_)
+                | Self::PgConnection(_)
+                | Self::PgRelationSizes(_)
+                | Self::PgSequences(_)
+                | Self::PgBufferCache(_)
+                | Self::PgStatArchiver(_)
+                | Self::PgSlowPlans(_)
+                | Self::PgPendingRestart(_)
+                | Self::PgSettingsMeta(_)
+                | Self::PgExtensions(_)
+        )
+    }
+
+    /// Removes blocks whose kind bit is set in `mask`, including blocks of
+    /// additional instances.
+    pub fn strip_kinds(blocks: &mut Vec<DataBlock>, mask: u64) {
+        blocks.retain(|b| mask & (1 << b.kind_index()) == 0);
+        for block in blocks.iter_mut() {
+            if let Self::PgInstance(instance) = block {
+                Self::strip_kinds(&mut instance.blocks, mask);
+            }
+        }
+    }
+
+    /// snake_case name of the block kind (e.g. "pg_stat_statements").
+    pub fn kind_name(&self) -> &'static str {
+        Self::KIND_NAMES[self.kind_index()]
+    }
+
+    /// Number of rows of a list block (blocks of an instance); 1 for
+    /// single-value blocks.
+    pub fn row_count(&self) -> usize {
+        match self {
+            Self::Processes(v) => v.len(),
+            Self::PgStatActivity(v) => v.len(),
+            Self::PgStatStatements(v) => v.len(),
+            Self::PgStorePlans(v) => v.len(),
+            Self::PgStatDatabase(v) => v.len(),
+            Self::PgStatUserTables(v) => v.len(),
+            Self::PgStatUserIndexes(v) => v.len(),
+            Self::PgLockTree(v) => v.len(),
+            Self::SystemCpu(v) => v.len(),
+            Self::SystemNet(v) => v.len(),
+            Self::SystemDisk(v) => v.len(),
+            Self::SystemPsi(v) => v.len(),
+            Self::SystemInterrupts(v) => v.len(),
+            Self::SystemSoftirqs(v) => v.len(),
+            Self::PgLogErrors(v) => v.len(),
+            Self::PgLogDetailedEvents(v) => v.len(),
+            Self::PgSettings(v) => v.len(),
+            Self::PgStatProgressVacuum(v) => v.len(),
+            Self::CustomQuery(v) => v.len(),
+            Self::KernelEvents(v) => v.len(),
+            Self::PgRelationSizes(v) => v.len(),
+            Self::PgSequences(v) => v.len(),
+            Self::PgSlowPlans(v) => v.len(),
+            Self::PgPendingRestart(v) => v.len(),
+            Self::PgSettingsMeta(v) => v.len(),
+            Self::PgExtensions(v) => v.len(),
+            Self::PgBufferCache(info) => info.relations.len(),
+            Self::PgInstance(instance) => instance.blocks.len(),
+            _ => 1,
+        }
+    }
+
+    /// Structural checks of a block decoded from disk: row counts within
+    /// [`MAX_BLOCK_ROWS`] and only instance-scoped blocks inside
+    /// `pg_instance`.
+    pub fn validate(&self) -> Result<(), String> {
+        let rows = self.row_count();
+        if rows > MAX_BLOCK_ROWS {
+            return Err(format!(
+                "{}: {} rows exceed the limit",
+                self.kind_name(),
+                rows
+            ));
+        }
+        if let Self::PgInstance(instance) = self {
+            for block in &instance.blocks {
+                if !block.is_instance_scoped() {
+                    return Err(format!(
+                        "pg_instance: unexpected {} block",
+                        block.kind_name()
+                    ));
+                }
+                block.validate()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Block retention stores stripped kinds as a 48-bit mask in the chunk header.
+const _: () = assert!(DataBlock::KIND_NAMES.len() <= 48);
+const _: () = assert!(DataBlock::SCHEMA_VERSIONS.len() == DataBlock::KIND_NAMES.len());
+
 /// A point-in-time capture of all collected metrics.
 ///
 /// Snapshot represents a complete picture of the system state at a given moment.
@@ -162,3 +629,189 @@ pub struct Snapshot {
     /// Not all block types need to be present in every snapshot.
     pub blocks: Vec<DataBlock>,
 }
+
+impl Snapshot {
+    /// [`DataBlock::validate`] for every block.
+    pub fn validate(&self) -> Result<(), String> {
+        self.blocks.iter().try_for_each(DataBlock::validate)
+    }
+
+    /// Sequence number and monotonic time, absent in snapshots written
+    /// before they were recorded.
+    pub fn clock(&self) -> Option<&ClockInfo> {
+        self.blocks.iter().find_map(|b| match b {
+            DataBlock::Clock(clock) => Some(clock),
+            _ => None,
+        })
+    }
+
+    /// Seconds elapsed since `prev` (see [`Snapshot::elapsed_since`]).
+    pub fn elapsed_secs(&self, prev: &Snapshot) -> i64 {
+        self.elapsed_since(prev.timestamp, prev.clock())
+    }
+
+    /// Seconds elapsed since a sample taken at `prev_timestamp`: measured
+    /// by the monotonic clock when both come from the same collector run,
+    /// otherwise the wall-clock difference (negative after the clock was
+    /// stepped back).
+    pub fn elapsed_since(&self, prev_timestamp: i64, prev_clock: Option<&ClockInfo>) -> i64 {
+        match (self.clock(), prev_clock) {
+            (Some(clock), Some(prev)) => match clock.elapsed_ms(prev) {
+                Some(ms) => (ms.saturating_add(500) / 1000).min(i64::MAX as u64) as i64,
+                None => self.timestamp - prev_timestamp,
+            },
+            _ => self.timestamp - prev_timestamp,
+        }
+    }
+
+    /// Names of the additional PostgreSQL instances in this snapshot.
+    pub fn instance_names(&self) -> Vec<&str> {
+        self.blocks
+            .iter()
+            .filter_map(|b| match b {
+                DataBlock::PgInstance(instance) => Some(instance.name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replaces the top-level (first instance) PostgreSQL blocks with the
+    /// blocks of instance `name`, so views, rates and analysis see only that
+    /// instance. Returns false if the snapshot has no such instance; its
+    /// PostgreSQL blocks are removed anyway rather than shown under the
+    /// wrong instance.
+    pub fn select_instance(&mut self, name: &str) -> bool {
+        let selected = self.blocks.iter_mut().find_map(|b| match b {
+            DataBlock::PgInstance(instance) if instance.name == name => {
+                Some(std::mem::take(&mut instance.blocks))
+            }
+            _ => None,
+        });
+        self.blocks.retain(|b| !b.is_instance_scoped());
+        let found = selected.is_some();
+        self.blocks.extend(selected.unwrap_or_default());
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::{PgStatActivityInfo, PgStatBgwriterInfo, SystemInterruptInfo};
+
+    #[test]
+    fn select_instance_swaps_pg_blocks() {
+        let mut snapshot = Snapshot {
+            timestamp: 0,
+            blocks: vec![
+                DataBlock::Processes(Vec::new()),
+                DataBlock::PgStatActivity(vec![PgStatActivityInfo::default()]),
+                DataBlock::PgInstance(PgInstanceBlocks {
+                    name: "billing".to_string(),
+                    port: 5433,
+                    blocks: vec![DataBlock::PgStatBgwriter(PgStatBgwriterInfo::default())],
+                }),
+            ],
+        };
+        assert_eq!(snapshot.instance_names(), vec!["billing"]);
+
+        let mut missing = snapshot.clone();
+        assert!(!missing.select_instance("reports"));
+        assert_eq!(missing.blocks.len(), 2);
+
+        assert!(snapshot.select_instance("billing"));
+        let kinds: Vec<&str> = snapshot.blocks.iter().map(|b| b.kind_name()).collect();
+        assert_eq!(kinds, vec!["processes", "pg_instance", "pg_stat_bgwriter"]);
+        // The instance list is kept for switching back
+        assert_eq!(snapshot.instance_names(), vec!["billing"]);
+    }
+
+    #[test]
+    fn strip_kinds_recurses_into_instances() {
+        let mut blocks = vec![
+            DataBlock::PgStatActivity(Vec::new()),
+            DataBlock::PgInstance(PgInstanceBlocks {
+                name: "billing".to_string(),
+                port: 5433,
+                blocks: vec![
+                    DataBlock::PgStatActivity(Vec::new()),
+                    DataBlock::PgStatBgwriter(PgStatBgwriterInfo::default()),
+                ],
+            }),
+        ];
+        DataBlock::strip_kinds(&mut blocks, 1 << 1);
+        assert_eq!(blocks.len(), 1);
+        let DataBlock::PgInstance(instance) = &blocks[0] else {
+            panic!("instance block stripped");
+        };
+        assert_eq!(instance.blocks.len(), 1);
+    }
+
+    #[test]
+    fn nested_instance_rejected() {
+        let nested = DataBlock::PgInstance(PgInstanceBlocks {
+            name: "outer".to_string(),
+            port: 5433,
+            blocks: vec![DataBlock::PgInstance(PgInstanceBlocks {
+                name: "inner".to_string(),
+                port: 5434,
+                blocks: Vec::new(),
+            })],
+        });
+        let bytes = postcard::to_allocvec(&nested).unwrap();
+        assert!(postcard::from_bytes::<DataBlock>(&bytes).is_err());
+        // The flag is reset after the error
+        let flat = DataBlock::PgInstance(PgInstanceBlocks {
+            name: "billing".to_string(),
+            port: 5433,
+            blocks: vec![DataBlock::PgStatBgwriter(PgStatBgwriterInfo::default())],
+        });
+        let bytes = postcard::to_allocvec(&flat).unwrap();
+        assert_eq!(postcard::from_bytes::<DataBlock>(&bytes).unwrap(), flat);
+    }
+
+    #[test]
+    fn validate_bounds_rows() {
+        let snapshot = Snapshot {
+            timestamp: 0,
+            blocks: vec![DataBlock::SystemInterrupts(vec![
+                SystemInterruptInfo::default();
+                MAX_BLOCK_ROWS + 1
+            ])],
+        };
+        assert!(snapshot.validate().is_err());
+
+        let host_block_in_instance = DataBlock::PgInstance(PgInstanceBlocks {
+            name: "billing".to_string(),
+            port: 5433,
+            blocks: vec![DataBlock::Processes(Vec::new())],
+        });
+        assert!(host_block_in_instance.validate().is_err());
+        assert!(DataBlock::Processes(Vec::new()).validate().is_ok());
+    }
+
+    #[test]
+    fn elapsed_uses_monotonic_clock_within_run() {
+        let at = |timestamp, run_id, seq, monotonic_ms| Snapshot {
+            timestamp,
+            blocks: vec![DataBlock::Clock(ClockInfo {
+                run_id,
+                seq,
+                monotonic_ms,
+            })],
+        };
+        // Clock stepped back by an hour between two snapshots 10s apart
+        let prev = at(10_000, 1, 5, 40_000);
+        let curr = at(6_410, 1, 6, 49_800);
+        assert_eq!(curr.elapsed_secs(&prev), 10);
+
+        // Another run: only the wall clock is comparable
+        let restarted = at(10_030, 2, 1, 0);
+        assert_eq!(restarted.elapsed_secs(&prev), 30);
+        let old = Snapshot {
+            timestamp: 9_990,
+            blocks: Vec::new(),
+        };
+        assert_eq!(prev.elapsed_secs(&old), 10);
+    }
+}
@@ -546,3 +546,133 @@ pub struct SystemNetSnmpInfo {
     /// Source: `TcpExt: TCPSynRetrans` in `/proc/net/netstat`
     pub tcp_syn_retrans: u64,
 }
+
+/// TCP connections to the PostgreSQL listen port, by state and remote host.
+///
+/// Source: `/proc/net/tcp` and `/proc/net/tcp6` (sockets whose local port is
+/// the PostgreSQL port; the listening socket itself is not counted).
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct SystemTcpConnInfo {
+    /// PostgreSQL listen port the counts refer to.
+    pub port: u16,
+
+    /// Connections in state ESTABLISHED.
+    pub established: u32,
+
+    /// Half-open connections in state SYN_RECV (handshake not completed).
+    pub syn_recv: u32,
+
+    /// Closed connections in state TIME_WAIT.
+    pub time_wait: u32,
+
+    /// Connections in state CLOSE_WAIT (client closed, server has not).
+    pub close_wait: u32,
+
+    /// Connections in any other state (FIN_WAIT, LAST_ACK, CLOSING, ...).
+    pub other: u32,
+
+    /// Per remote host counts, largest total first.
+    /// Limited to the top hosts; the totals above include all hosts.
+    pub remotes: Vec<SystemTcpRemoteInfo>,
+}
+
+/// TCP connections from one remote host to the PostgreSQL port.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct SystemTcpRemoteInfo {
+    /// Remote IP address (IPv4-mapped IPv6 addresses are shown as IPv4).
+    pub addr: String,
+
+    /// Connections in state ESTABLISHED.
+    pub established: u32,
+
+    /// Connections in state SYN_RECV.
+    pub syn_recv: u32,
+
+    /// Connections in state TIME_WAIT.
+    pub time_wait: u32,
+
+    /// Connections in any other state (including CLOSE_WAIT).
+    pub other: u32,
+}
+
+impl SystemTcpRemoteInfo {
+    /// Connections from this host in any state.
+    pub fn total(&self) -> u32 {
+        self.established + self.syn_recv + self.time_wait + self.other
+    }
+}
+
+/// Kind of a kernel log event.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum KernelEventKind {
+    /// The OOM killer killed a process (`Out of memory: Killed process ...`).
+    OomKill,
+    /// Block layer or filesystem I/O error.
+    IoError,
+    /// A filesystem was remounted read-only after an error.
+    FsReadOnly,
+}
+
+/// An event from the kernel ring buffer within a snapshot interval.
+///
+/// Source: `/dev/kmsg`
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct KernelEventInfo {
+    /// Type of event.
+    pub kind: KernelEventKind,
+
+    /// Wall-clock time of the event (seconds since epoch), derived from the
+    /// record's monotonic timestamp and the boot time.
+    pub timestamp: i64,
+
+    /// Killed process ID (OOM kills only, 0 otherwise).
+    pub pid: u32,
+
+    /// Killed process name (OOM kills only, empty otherwise).
+    pub comm: String,
+
+    /// Kernel message text.
+    pub message: String,
+
+    /// PostgreSQL role of the killed process: `postmaster` or the
+    /// `pg_stat_activity.backend_type` (e.g. `client backend`).
+    /// `None` when the process did not belong to PostgreSQL.
+    pub pg_role: Option<String>,
+}
+
+impl KernelEventInfo {
+    /// Whether the event killed the postmaster or one of its backends.
+    pub fn is_pg_oom_kill(&self) -> bool {
+        self.kind == KernelEventKind::OomKill && self.pg_role.is_some()
+    }
+}
+
+/// State of the systemd unit running PostgreSQL.
+///
+/// Source: `systemctl show <unit>`
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct SystemdUnitInfo {
+    /// Unit name (e.g. `postgresql@16-main.service`).
+    pub unit: String,
+
+    /// High-level state: active, reloading, inactive, failed, activating, deactivating.
+    pub active_state: String,
+
+    /// Unit type specific state (e.g. running, dead, auto-restart).
+    pub sub_state: String,
+
+    /// Result of the last run: success, exit-code, signal, core-dump, oom-kill, ...
+    pub result: String,
+
+    /// PID of the main process (the postmaster), 0 when not running.
+    pub main_pid: u32,
+
+    /// Automatic restarts performed by systemd (`Restart=`) since the unit was loaded.
+    pub n_restarts: u32,
+
+    /// Time the unit last entered the active state (seconds since epoch, 0 if never).
+    pub active_enter_ts: i64,
+
+    /// Time of the last state change (seconds since epoch, 0 if unknown).
+    pub state_change_ts: i64,
+}
@@ -1,6 +1,8 @@
-//! Generic table widget state: sorting, filtering, diff tracking.
+//! Generic table widget state: sorting, filtering, diff tracking, pinning.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
 
 /// Sort key types for table columns.
 #[derive(Debug, Clone, PartialEq)]
@@ -33,6 +35,42 @@ pub enum DiffStatus {
     Unchanged,
 }
 
+/// Marker drawn before the first cell of a pinned row.
+pub const PIN_MARKER: &str = "▸";
+
+/// Rows pinned to the top of a table with `^` by stable entity key (PID,
+/// queryid, relid, ...). Pins survive refreshes, sorting and view switches.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PinnedRows {
+    ids: HashSet<i128>,
+}
+
+impl PinnedRows {
+    /// Pins `id`, or unpins it if already pinned. Returns whether it is
+    /// pinned now.
+    pub fn toggle(&mut self, id: impl Into<i128>) -> bool {
+        let id = id.into();
+        if self.ids.remove(&id) {
+            false
+        } else {
+            self.ids.insert(id);
+            true
+        }
+    }
+
+    pub fn contains(&self, id: impl Into<i128>) -> bool {
+        self.ids.contains(&id.into())
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
 /// Column type for adaptive width calculation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColumnType {
@@ -91,6 +129,8 @@ pub struct TableState<T: TableRow> {
     pub sort_ascending: bool,
     /// Filter string.
     pub filter: Option<String>,
+    /// Column expression filter (`\` prompt), combined with `filter`.
+    pub row_filter: Option<RowFilter>,
     /// Scroll offset for large tables.
     pub scroll_offset: usize,
     /// Previous items for diff tracking.
@@ -99,6 +139,8 @@ pub struct TableState<T: TableRow> {
     pub diff_status: HashMap<u64, DiffStatus>,
     /// Tracked entity ID — follows the selected row across sort/filter changes.
     pub tracked_id: Option<u64>,
+    /// Rows kept at the top regardless of sorting.
+    pub pinned: PinnedRows,
 }
 
 impl<T: TableRow> Default for TableState<T> {
@@ -115,10 +157,12 @@ impl<T: TableRow> TableState<T> {
             sort_column: 0,
             sort_ascending: false, // Default descending (highest first)
             filter: None,
+            row_filter: None,
             scroll_offset: 0,
             previous: HashMap::new(),
             diff_status: HashMap::new(),
             tracked_id: None,
+            pinned: PinnedRows::default(),
         }
     }
 
@@ -169,6 +213,7 @@ impl<T: TableRow> TableState<T> {
 
     /// Returns filtered and sorted items.
     pub fn filtered_items(&self) -> Vec<&T> {
+        let headers = T::headers();
         self.items
             .iter()
             .filter(|item| {
@@ -177,10 +222,15 @@ impl<T: TableRow> TableState<T> {
                     .map(|f| item.matches_filter(f))
                     .unwrap_or(true)
             })
+            .filter(|item| {
+                self.row_filter
+                    .as_ref()
+                    .is_none_or(|rf| rf.matches(&headers, |col| item.sort_key(col)))
+            })
             .collect()
     }
 
-    /// Applies current sort to items.
+    /// Applies current sort to items; pinned items come first.
     fn apply_sort(&mut self) {
         let col = self.sort_column;
         let asc = self.sort_ascending;
@@ -193,6 +243,24 @@ impl<T: TableRow> TableState<T> {
                 .unwrap_or(std::cmp::Ordering::Equal);
             if asc { cmp } else { cmp.reverse() }
         });
+        self.pin_to_top();
+    }
+
+    /// Moves pinned items to the top, keeping their relative order.
+    pub fn pin_to_top(&mut self) {
+        if !self.pinned.is_empty() {
+            let pinned = &self.pinned;
+            self.items.sort_by_key(|item| !pinned.contains(item.id()));
+        }
+    }
+
+    /// Pins or unpins the selected item. Returns whether it is pinned now,
+    /// or `None` if nothing is selected.
+    pub fn toggle_pin_selected(&mut self) -> Option<bool> {
+        let id = self.filtered_items().get(self.selected)?.id();
+        let pinned = self.pinned.toggle(id);
+        self.tracked_id = Some(id);
+        Some(pinned)
     }
 
     /// Cycles to next sort column.
@@ -214,6 +282,13 @@ impl<T: TableRow> TableState<T> {
         self.scroll_offset = 0;
     }
 
+    /// Sets column expression filter.
+    pub fn set_row_filter(&mut self, row_filter: Option<RowFilter>) {
+        self.row_filter = row_filter;
+        self.selected = 0;
+        self.scroll_offset = 0;
+    }
+
     /// Moves selection up.
     pub fn select_up(&mut self) {
         if self.selected > 0 {
@@ -279,3 +354,442 @@ impl<T: TableRow> TableState<T> {
         }
     }
 }
+
+/// User column layout for one table view, keyed by header name so it
+/// survives column additions between versions.
+///
+/// The first `movable` columns of a view follow `order` (unlisted columns
+/// keep their default relative order after the listed ones). Trailing columns
+/// that fill the remaining width always stay last. Any column can be hidden.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnLayout {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden: Vec<String>,
+}
+
+impl ColumnLayout {
+    /// Returns true if the layout does not change the default column set.
+    pub fn is_default(&self) -> bool {
+        self.order.is_empty() && self.hidden.is_empty()
+    }
+
+    /// All column indices (hidden included) in display order.
+    pub fn ordered_columns(&self, headers: &[&str], movable: usize) -> Vec<usize> {
+        let movable = movable.min(headers.len());
+        let mut cols: Vec<usize> = Vec::with_capacity(headers.len());
+        for name in &self.order {
+            if let Some(idx) = headers[..movable].iter().position(|h| h == name)
+                && !cols.contains(&idx)
+            {
+                cols.push(idx);
+            }
+        }
+        for idx in 0..headers.len() {
+            if !cols.contains(&idx) {
+                cols.push(idx);
+            }
+        }
+        cols
+    }
+
+    /// Visible column indices in display order.
+    pub fn visible_columns(&self, headers: &[&str], movable: usize) -> Vec<usize> {
+        self.ordered_columns(headers, movable)
+            .into_iter()
+            .filter(|&idx| !self.is_hidden(headers[idx]))
+            .collect()
+    }
+
+    pub fn is_hidden(&self, header: &str) -> bool {
+        self.hidden.iter().any(|h| h == header)
+    }
+
+    /// Toggles visibility of a column. The last visible column cannot be hidden.
+    pub fn toggle_hidden(&mut self, headers: &[&str], header: &str) {
+        if let Some(pos) = self.hidden.iter().position(|h| h == header) {
+            self.hidden.remove(pos);
+        } else if headers.iter().any(|h| *h != header && !self.is_hidden(h)) {
+            self.hidden.push(header.to_string());
+        }
+    }
+
+    /// Moves a movable column by `delta` positions among the movable columns.
+    /// Returns the new display position, or `None` if the column cannot move.
+    pub fn move_column(
+        &mut self,
+        headers: &[&str],
+        movable: usize,
+        header: &str,
+        delta: isize,
+    ) -> Option<usize> {
+        let movable = movable.min(headers.len());
+        let mut cols = self.ordered_columns(headers, movable);
+        cols.truncate(movable);
+        let from = cols.iter().position(|&idx| headers[idx] == header)?;
+        let to = from.checked_add_signed(delta)?;
+        if to >= cols.len() {
+            return None;
+        }
+        let col = cols.remove(from);
+        cols.insert(to, col);
+        self.order = cols.iter().map(|&idx| headers[idx].to_string()).collect();
+        // Drop the order entirely once it matches the default again
+        if cols.iter().enumerate().all(|(pos, &idx)| pos == idx) {
+            self.order.clear();
+        }
+        Some(to)
+    }
+}
+
+/// Comparison operator in a row filter term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// Case-insensitive substring (`~`).
+    Contains,
+}
+
+impl FilterOp {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "=" | "==" => Some(FilterOp::Eq),
+            "!=" | "<>" => Some(FilterOp::Ne),
+            ">" => Some(FilterOp::Gt),
+            ">=" => Some(FilterOp::Ge),
+            "<" => Some(FilterOp::Lt),
+            "<=" => Some(FilterOp::Le),
+            "~" => Some(FilterOp::Contains),
+            _ => None,
+        }
+    }
+
+    fn holds(self, ord: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            FilterOp::Eq => ord == Equal,
+            FilterOp::Ne => ord != Equal,
+            FilterOp::Gt => ord == Greater,
+            FilterOp::Ge => ord != Less,
+            FilterOp::Lt => ord == Less,
+            FilterOp::Le => ord != Greater,
+            FilterOp::Contains => false,
+        }
+    }
+}
+
+/// Single term of a row filter expression.
+#[derive(Debug, Clone, PartialEq)]
+enum FilterTerm {
+    /// `column OP value`; column and value are stored lowercase.
+    Compare {
+        column: String,
+        op: FilterOp,
+        value: String,
+    },
+    /// Bare word: lowercase substring matched against every column.
+    Text(String),
+}
+
+/// Parsed row filter expression.
+///
+/// Grammar: terms joined by `AND` / `OR` (case-insensitive, `AND` binds
+/// tighter; adjacent terms without a connector are ANDed). A term is either
+/// `column OP value` with OP one of `= != > >= < <= ~`, or a bare word /
+/// quoted string matched as a substring across all columns.
+///
+/// Column names match table headers case-insensitively with non-alphanumeric
+/// characters folded to `_`, so `calls_s` matches `CALLS/s` and `hit` matches
+/// `HIT%`. Comparisons use the same typed values as sorting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowFilter {
+    source: String,
+    /// OR of AND-groups.
+    groups: Vec<Vec<FilterTerm>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(String),
+}
+
+fn is_op_char(c: char) -> bool {
+    matches!(c, '=' | '!' | '<' | '>' | '~')
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some(ch) if ch == c => break,
+                    Some(ch) => s.push(ch),
+                    None => return Err("unterminated quote".to_string()),
+                }
+            }
+            tokens.push(Token::Quoted(s));
+        } else if is_op_char(c) {
+            let mut s = String::new();
+            while let Some(&ch) = chars.peek() {
+                if !is_op_char(ch) {
+                    break;
+                }
+                s.push(ch);
+                chars.next();
+            }
+            tokens.push(Token::Op(s));
+        } else {
+            let mut s = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() || is_op_char(ch) || ch == '\'' || ch == '"' {
+                    break;
+                }
+                s.push(ch);
+                chars.next();
+            }
+            tokens.push(Token::Word(s));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Folds a column name for matching: lowercase, non-alphanumerics to `_`,
+/// leading/trailing `_` trimmed.
+fn normalize_column(name: &str) -> String {
+    let folded: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    folded.trim_matches('_').to_string()
+}
+
+impl RowFilter {
+    /// Parses a filter expression. Returns an error message for malformed
+    /// input (dangling operator or connector, unterminated quote, empty input).
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err("empty filter".to_string());
+        }
+
+        let mut groups: Vec<Vec<FilterTerm>> = vec![Vec::new()];
+        let mut expect_term = true;
+        let mut iter = tokens.into_iter().peekable();
+        while let Some(token) = iter.next() {
+            match token {
+                Token::Word(w) if w.eq_ignore_ascii_case("and") || w.eq_ignore_ascii_case("or") => {
+                    if expect_term {
+                        return Err(format!("unexpected {}", w.to_uppercase()));
+                    }
+                    if w.eq_ignore_ascii_case("or") {
+                        groups.push(Vec::new());
+                    }
+                    expect_term = true;
+                }
+                Token::Word(w) => {
+                    let term = if matches!(iter.peek(), Some(Token::Op(_))) {
+                        let Some(Token::Op(op)) = iter.next() else {
+                            unreachable!()
+                        };
+                        let op =
+                            FilterOp::parse(&op).ok_or_else(|| format!("unknown operator {op}"))?;
+                        let value = match iter.next() {
+                            Some(Token::Word(v)) | Some(Token::Quoted(v)) => v,
+                            _ => return Err(format!("missing value after {w}")),
+                        };
+                        FilterTerm::Compare {
+                            column: normalize_column(&w),
+                            op,
+                            value: value.to_lowercase(),
+                        }
+                    } else {
+                        FilterTerm::Text(w.to_lowercase())
+                    };
+                    groups.last_mut().expect("non-empty").push(term);
+                    expect_term = false;
+                }
+                Token::Quoted(s) => {
+                    groups
+                        .last_mut()
+                        .expect("non-empty")
+                        .push(FilterTerm::Text(s.to_lowercase()));
+                    expect_term = false;
+                }
+                Token::Op(op) => return Err(format!("missing column before {op}")),
+            }
+        }
+        if expect_term {
+            return Err("expression ends with a connector".to_string());
+        }
+
+        Ok(Self {
+            source: input.trim().to_string(),
+            groups,
+        })
+    }
+
+    /// Original expression text.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluates the filter for one row. `headers` are the column names of
+    /// the current view, `key` returns the typed value of a column.
+    /// Comparisons against columns not present in `headers` never match.
+    pub fn matches(&self, headers: &[&str], key: impl Fn(usize) -> SortKey) -> bool {
+        let columns: Vec<String> = headers.iter().map(|h| normalize_column(h)).collect();
+        self.groups.iter().any(|group| {
+            group.iter().all(|term| match term {
+                FilterTerm::Compare { column, op, value } => columns
+                    .iter()
+                    .position(|c| c == column)
+                    .is_some_and(|idx| compare_key(&key(idx), *op, value)),
+                FilterTerm::Text(text) => (0..columns.len()).any(|idx| match key(idx) {
+                    SortKey::String(s) => s.to_lowercase().contains(text.as_str()),
+                    SortKey::Integer(i) => i.to_string().contains(text.as_str()),
+                    SortKey::Float(_) => false,
+                }),
+            })
+        })
+    }
+}
+
+fn compare_key(key: &SortKey, op: FilterOp, value: &str) -> bool {
+    match key {
+        SortKey::String(s) => {
+            let s = s.to_lowercase();
+            match op {
+                FilterOp::Contains => s.contains(value),
+                _ => op.holds(s.as_str().cmp(value)),
+            }
+        }
+        SortKey::Integer(i) => match op {
+            FilterOp::Contains => i.to_string().contains(value),
+            _ => value
+                .parse::<f64>()
+                .is_ok_and(|v| (*i as f64).partial_cmp(&v).is_some_and(|o| op.holds(o))),
+        },
+        SortKey::Float(f) => match op {
+            FilterOp::Contains => false,
+            _ => value
+                .parse::<f64>()
+                .is_ok_and(|v| f.partial_cmp(&v).is_some_and(|o| op.holds(o))),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADERS: &[&str] = &["CALLS/s", "HIT%", "PID", "DB", "QUERY"];
+
+    fn row(calls_s: f64, hit: f64, pid: i64, db: &str, query: &str) -> Vec<SortKey> {
+        vec![
+            SortKey::Float(calls_s),
+            SortKey::Float(hit),
+            SortKey::Integer(pid),
+            SortKey::String(db.to_string()),
+            SortKey::String(query.to_string()),
+        ]
+    }
+
+    fn matches(expr: &str, r: &[SortKey]) -> bool {
+        RowFilter::parse(expr)
+            .unwrap()
+            .matches(HEADERS, |c| r[c].clone())
+    }
+
+    #[test]
+    fn compares_typed_columns() {
+        let r = row(12.5, 97.0, 4242, "prod", "SELECT 1");
+        assert!(matches("db=prod AND calls_s>10", &r));
+        assert!(matches("DB = 'PROD' and calls/s >= 12.5", &r));
+        assert!(!matches("db=prod AND calls_s>20", &r));
+        assert!(matches("calls_s>20 OR hit<98", &r));
+        assert!(matches("pid!=1 query~select", &r));
+        assert!(!matches("db!=prod", &r));
+        // Unknown column never matches
+        assert!(!matches("nope=1", &r));
+        // Non-numeric value against numeric column
+        assert!(!matches("pid>abc", &r));
+    }
+
+    #[test]
+    fn bare_words_match_any_column() {
+        let r = row(1.0, 100.0, 4242, "prod", "SELECT * FROM orders");
+        assert!(matches("orders", &r));
+        assert!(matches("424", &r));
+        assert!(matches("'from orders'", &r));
+        assert!(!matches("staging", &r));
+        assert!(matches("staging OR orders", &r));
+    }
+
+    #[test]
+    fn column_layout_orders_and_hides() {
+        let headers = ["A", "B", "C", "QUERY"];
+        let mut layout = ColumnLayout::default();
+        assert_eq!(layout.visible_columns(&headers, 3), vec![0, 1, 2, 3]);
+
+        assert_eq!(layout.move_column(&headers, 3, "C", -2), Some(0));
+        assert_eq!(layout.order, vec!["C", "A", "B"]);
+        assert_eq!(layout.visible_columns(&headers, 3), vec![2, 0, 1, 3]);
+        // Fill columns and out-of-range moves are rejected
+        assert_eq!(layout.move_column(&headers, 3, "QUERY", -1), None);
+        assert_eq!(layout.move_column(&headers, 3, "B", 1), None);
+
+        layout.toggle_hidden(&headers, "A");
+        assert_eq!(layout.visible_columns(&headers, 3), vec![2, 1, 3]);
+        layout.toggle_hidden(&headers, "A");
+        assert!(layout.hidden.is_empty());
+
+        // Moving back to the default order clears it
+        layout.move_column(&headers, 3, "C", 2);
+        assert!(layout.is_default());
+    }
+
+    #[test]
+    fn column_layout_ignores_unknown_and_keeps_last_visible() {
+        let headers = ["A", "B"];
+        let mut layout = ColumnLayout {
+            order: vec!["GONE".into(), "B".into()],
+            hidden: vec![],
+        };
+        assert_eq!(layout.visible_columns(&headers, 2), vec![1, 0]);
+        layout.toggle_hidden(&headers, "A");
+        layout.toggle_hidden(&headers, "B");
+        assert_eq!(layout.visible_columns(&headers, 2), vec![1]);
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert!(RowFilter::parse("").is_err());
+        assert!(RowFilter::parse("db=").is_err());
+        assert!(RowFilter::parse("AND db=x").is_err());
+        assert!(RowFilter::parse("db=x OR").is_err());
+        assert!(RowFilter::parse(">5").is_err());
+        assert!(RowFilter::parse("db=>x").is_err());
+        assert!(RowFilter::parse("'open").is_err());
+        assert_eq!(RowFilter::parse(" db=x ").unwrap().as_str(), "db=x");
+    }
+}
@@ -0,0 +1,146 @@
+//! Warning/critical levels of numeric table columns.
+//!
+//! [`COLUMN_THRESHOLDS`] is the single place these levels are configured:
+//! the API schema attaches them to its columns as coloring hints for the
+//! web frontend, and the TUI view models color rows and cells with them.
+
+use serde::Serialize;
+
+/// Severity of a value against its column's thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Warn,
+    Crit,
+}
+
+/// Warning and critical levels of a column. Values at or above a level are
+/// highlighted; with `lower_is_worse`, values below it (hit ratios).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct Thresholds {
+    pub warn: f64,
+    /// Absent for columns that only warn.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crit: Option<f64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub lower_is_worse: bool,
+}
+
+impl Thresholds {
+    /// Higher values are worse.
+    pub const fn above(warn: f64, crit: f64) -> Self {
+        Self {
+            warn,
+            crit: Some(crit),
+            lower_is_worse: false,
+        }
+    }
+
+    /// Lower values are worse.
+    pub const fn below(warn: f64, crit: f64) -> Self {
+        Self {
+            warn,
+            crit: Some(crit),
+            lower_is_worse: true,
+        }
+    }
+
+    /// Higher values are worse; there is no critical level.
+    pub const fn warn_above(warn: f64) -> Self {
+        Self {
+            warn,
+            crit: None,
+            lower_is_worse: false,
+        }
+    }
+
+    pub fn level(&self, value: f64) -> Option<Level> {
+        let reached = |level: f64| {
+            if self.lower_is_worse {
+                value < level
+            } else {
+                value >= level
+            }
+        };
+        if self.crit.is_some_and(reached) {
+            Some(Level::Crit)
+        } else if reached(self.warn) {
+            Some(Level::Warn)
+        } else {
+            None
+        }
+    }
+}
+
+/// Thresholds by tab key and API column key.
+pub const COLUMN_THRESHOLDS: &[(&str, &str, Thresholds)] = &[
+    // PGA: pg_stat_statements hit ratio of the session's query
+    ("pga", "stmt_hit_pct", Thresholds::below(80.0, 50.0)),
+    // PGA: transactions behind the session's xmin horizon
+    (
+        "pga",
+        "backend_xmin_age",
+        Thresholds::above(1_000_000.0, 10_000_000.0),
+    ),
+    // PGS / PGP: execution time and reads per second, temp usage
+    ("pgs", "exec_time_ms_s", Thresholds::above(100.0, 1_000.0)),
+    (
+        "pgs",
+        "shared_blks_read_s",
+        Thresholds::above(1_000.0, 10_000.0),
+    ),
+    ("pgs", "temp_mb_s", Thresholds::above(10.0, 100.0)),
+    ("pgs", "hit_pct", Thresholds::below(98.0, 90.0)),
+    ("pgp", "exec_time_ms_s", Thresholds::above(100.0, 1_000.0)),
+    (
+        "pgp",
+        "shared_blks_read_s",
+        Thresholds::above(1_000.0, 10_000.0),
+    ),
+    ("pgp", "hit_pct", Thresholds::below(98.0, 90.0)),
+    // PGT / PGI: bloat, sequential scans, buffer cache misses
+    ("pgt", "dead_pct", Thresholds::above(5.0, 20.0)),
+    ("pgt", "seq_pct", Thresholds::warn_above(80.0)),
+    ("pgt", "io_hit_pct", Thresholds::below(90.0, 70.0)),
+    ("pgi", "io_hit_pct", Thresholds::below(90.0, 70.0)),
+];
+
+/// Thresholds of column `key` on tab `tab`, if any.
+pub fn column_thresholds(tab: &str, key: &str) -> Option<Thresholds> {
+    COLUMN_THRESHOLDS
+        .iter()
+        .find(|(t, k, _)| *t == tab && *k == key)
+        .map(|&(_, _, thresholds)| thresholds)
+}
+
+/// Level of `value` in column `key` on tab `tab`; `None` if the column has
+/// no thresholds or the value is below them.
+pub fn column_level(tab: &str, key: &str, value: f64) -> Option<Level> {
+    column_thresholds(tab, key)?.level(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_follow_direction() {
+        let time = Thresholds::above(100.0, 1_000.0);
+        assert_eq!(time.level(50.0), None);
+        assert_eq!(time.level(100.0), Some(Level::Warn));
+        assert_eq!(time.level(1_000.0), Some(Level::Crit));
+
+        let hit = Thresholds::below(98.0, 90.0);
+        assert_eq!(hit.level(99.0), None);
+        assert_eq!(hit.level(95.0), Some(Level::Warn));
+        assert_eq!(hit.level(50.0), Some(Level::Crit));
+
+        assert_eq!(Thresholds::warn_above(80.0).level(100.0), Some(Level::Warn));
+    }
+
+    #[test]
+    fn lookup_by_tab_and_column() {
+        assert_eq!(column_level("pgt", "dead_pct", 25.0), Some(Level::Crit));
+        assert_eq!(column_level("pgi", "dead_pct", 25.0), None);
+        assert!(column_thresholds("pgs", "calls_s").is_none());
+    }
+}
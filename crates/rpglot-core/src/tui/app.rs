@@ -1,9 +1,10 @@
 //! Main TUI application.
 
 use std::io;
+use std::path::Path;
 use std::time::Duration;
 
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, MouseButton, MouseEventKind};
 use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
@@ -13,17 +14,28 @@ use ratatui::backend::CrosstermBackend;
 
 use crate::provider::HistoryProvider;
 use crate::provider::SnapshotProvider;
+use crate::storage::bookmarks::BookmarkStore;
+use crate::storage::heatmap::HeatmapBuilder;
 use crate::storage::model::Snapshot;
 use crate::util::parse_time_with_base;
 
+use super::clipboard;
 use super::event::{Event, EventHandler};
 use super::input::{KeyAction, handle_key};
+use super::keymap::KeyMap;
 use super::render::render;
-use super::state::{AppState, InputMode, PopupState, Tab};
+use super::state::{
+    AppState, ExportFormat, ExportTable, InputMode, LayoutConfig, PopupState, QueryHistory, Tab,
+    sample_positions,
+};
+use super::theme::ThemeSet;
 use super::widgets::prc::{
     calculate_cached_widths, extract_processes, get_total_cpu_time, get_total_memory,
     update_prev_cpu, update_prev_dsk, update_prev_mem,
 };
+use super::widgets::summary::summary_sample;
+use crate::api::buffercache::{buffer_cache_at, buffer_cache_report};
+use crate::entity::entity_history;
 use crate::storage::model::DataBlock;
 
 fn elapsed_secs_between_timestamps(current_ts: i64, prev_ts: i64) -> f64 {
@@ -38,19 +50,105 @@ pub struct App {
     provider: Box<dyn SnapshotProvider>,
     state: AppState,
     should_quit: bool,
+    /// Summary entries of live snapshots for the graph view.
+    graph_builder: HeatmapBuilder,
 }
 
 impl App {
     /// Creates a new App with the given provider.
     pub fn new(provider: Box<dyn SnapshotProvider>) -> Self {
         let is_live = provider.is_live();
+        let mut state = AppState::new(is_live);
+
+        // Saved column layouts. A broken file is left untouched (saving disabled).
+        if let Some(path) = LayoutConfig::default_path() {
+            match LayoutConfig::load(&path) {
+                Ok(layouts) => {
+                    state.layouts = layouts;
+                    state.layout_path = Some(path);
+                }
+                Err(e) => {
+                    state.status_message = Some(format!("Ignoring {}: {}", path.display(), e));
+                }
+            }
+        }
+
+        // User color themes; a broken file leaves only the built-in ones.
+        if let Some(path) = ThemeSet::default_path()
+            && let Err(e) = state.themes.load_user_themes(&path)
+        {
+            state.status_message = Some(format!("Ignoring {}: {}", path.display(), e));
+        }
+
+        // User key bindings; a broken file leaves the defaults.
+        if let Some(path) = KeyMap::default_path() {
+            match KeyMap::load(&path) {
+                Ok(keymap) => state.keymap = keymap,
+                Err(e) => {
+                    state.status_message = Some(format!("Ignoring {}: {}", path.display(), e));
+                }
+            }
+        }
+
+        // Bookmarks live next to the history data.
+        if let Some(history) = provider
+            .as_any()
+            .and_then(|a| a.downcast_ref::<HistoryProvider>())
+        {
+            match BookmarkStore::open(history.storage_path()) {
+                Ok(store) => state.bookmarks = Some(store),
+                Err(e) => {
+                    state.status_message = Some(format!("Bookmarks disabled: {}", e));
+                }
+            }
+        }
+
         Self {
             provider,
-            state: AppState::new(is_live),
+            state,
             should_quit: false,
+            graph_builder: HeatmapBuilder::new(),
         }
     }
 
+    /// Sets the export path template (`{tab}`, `{view}`, `{time}`); the
+    /// extension selects CSV or JSON.
+    pub fn set_export_template(&mut self, template: String) {
+        self.state.export_template = template;
+    }
+
+    /// Also copies through a native clipboard tool (wl-copy, xclip, xsel,
+    /// pbcopy), not only the OSC 52 escape sequence.
+    pub fn set_native_clipboard(&mut self, enabled: bool) {
+        self.state.native_clipboard = enabled;
+    }
+
+    /// Sets the minimum relative change (percent) of numeric cells that
+    /// watch mode highlights.
+    pub fn set_watch_threshold(&mut self, pct: f64) {
+        self.state.watch.threshold_pct = pct;
+    }
+
+    /// Sets the step of `,`/`.` in history mode.
+    pub fn set_large_step(&mut self, secs: i64) {
+        self.state.large_step_secs = secs;
+    }
+
+    /// Selects the color theme: a built-in or user theme name, or a path to
+    /// a single-theme TOML file.
+    pub fn set_theme(&mut self, theme: &str) -> Result<(), String> {
+        let path = Path::new(theme);
+        let name = if self.state.themes.get(theme).is_none() && path.is_file() {
+            self.state
+                .themes
+                .load_theme_file(path)
+                .map_err(|e| format!("{}: {}", path.display(), e))?
+        } else {
+            theme.to_string()
+        };
+        self.state.set_theme(&name)
+    }
+
     /// Runs the TUI application.
     pub fn run(mut self, tick_rate: Duration) -> io::Result<()> {
         // Setup terminal
@@ -80,11 +178,22 @@ impl App {
             }
             self.state.popup_was_open = popup_open;
 
+            // Keep the timeline buckets in sync with the cursor, zoom and width
+            self.refresh_timeline();
+
             // Draw UI
             let interner = self.provider.interner();
             let timing = self.provider.collector_timing();
             terminal.draw(|frame| render(frame, &mut self.state, interner, timing))?;
 
+            // Write the table captured by this draw
+            if let Some(table) = self.state.export_table.take() {
+                self.write_export(&table);
+            } else if self.state.export_requested {
+                self.state.export_requested = false;
+                self.state.status_message = Some("Nothing to export on this tab".to_string());
+            }
+
             // Handle events
             match events.next() {
                 Ok(Event::Tick) => {
@@ -99,9 +208,25 @@ impl App {
                         KeyAction::Advance => self.advance(),
                         KeyAction::Rewind => self.rewind(),
                         KeyAction::JumpToTime => self.jump_to_time(),
+                        KeyAction::TimelineStep { forward } => self.timeline_step(forward),
+                        KeyAction::LargeStep { forward } => self.large_step(forward),
+                        KeyAction::JumpTo(ts) => {
+                            self.jump_to_timestamp(ts, false);
+                        }
+                        KeyAction::NextInstance => self.next_instance(),
                         KeyAction::None => {}
                     }
                 }
+                Ok(Event::Mouse(mouse)) => {
+                    if mouse.kind == MouseEventKind::Down(MouseButton::Left)
+                        && let Some(ts) = self
+                            .state
+                            .timeline
+                            .timestamp_at_screen(mouse.column, mouse.row)
+                    {
+                        self.jump_to_timestamp(ts, false);
+                    }
+                }
                 Ok(Event::Resize(width)) => {
                     // Recalculate column widths on resize
                     if self.state.terminal_width != width {
@@ -123,6 +248,46 @@ impl App {
                 self.handle_drill_down();
             }
 
+            // Load metric history for a newly opened query view
+            if self.state.query_view_requested {
+                self.state.query_view_requested = false;
+                self.load_query_history();
+            }
+
+            // Extract the trajectory for a newly opened entity history popup
+            if self.state.entity_history_requested {
+                self.state.entity_history_requested = false;
+                self.load_entity_history();
+            }
+
+            // Load the buffer cache sample for the buffer cache popup
+            if self.state.buffer_cache_requested {
+                self.state.buffer_cache_requested = false;
+                self.load_buffer_cache();
+            }
+
+            // Copy the selected query or row to the clipboard
+            if self.state.copy_requested {
+                self.state.copy_requested = false;
+                self.copy_selection();
+            }
+
+            // Load the series of a newly opened graph view
+            if self.state.graph_requested {
+                self.state.graph_requested = false;
+                self.load_graph();
+            }
+
+            // Persist column layouts after the chooser closes
+            if self.state.layout_save_requested {
+                self.state.layout_save_requested = false;
+                if let Some(path) = &self.state.layout_path
+                    && let Err(e) = self.state.layouts.save(path)
+                {
+                    self.state.status_message = Some(format!("Failed to save layout: {}", e));
+                }
+            }
+
             if self.should_quit {
                 break;
             }
@@ -158,7 +323,7 @@ impl App {
         // Extract process rows with VGROW/RGROW and CPU% calculation
         let interner = self.provider.interner();
         let user_resolver = self.provider.user_resolver();
-        let processes = extract_processes(
+        let mut processes = extract_processes(
             &snapshot,
             interner,
             user_resolver,
@@ -170,6 +335,10 @@ impl App {
             total_mem,
             elapsed_secs,
         );
+        if self.state.process_tree {
+            // Arrange before diffing so tree cells compare with tree cells
+            self.state.sort_processes(&mut processes);
+        }
         self.state.process_table.update(processes);
         self.state.apply_process_sort();
 
@@ -203,6 +372,15 @@ impl App {
         // Accumulate PostgreSQL log errors (used by PGE tab).
         self.state.pge.accumulate_from_snapshot(&snapshot);
 
+        // Extend the query view history with live samples.
+        if self.state.is_live
+            && self.state.live_buffer_position.is_none()
+            && matches!(self.state.popup, PopupState::QueryView { .. })
+            && let Some(history) = self.state.query_history.as_mut()
+        {
+            history.push_snapshot(&snapshot);
+        }
+
         // Update history position for non-live mode
         if !self.state.is_live
             && let Some(hp) = self.provider.as_any().and_then(|a| {
@@ -213,12 +391,80 @@ impl App {
             self.state.history_position = Some(hp);
         }
 
+        // Rolling summary metrics for the summary sparklines.
+        let sample = summary_sample(&snapshot, self.state.previous_snapshot.as_ref());
+        self.state.summary_history.push(sample);
+        self.backfill_summary_history();
+
+        // Live graph: one entry per new snapshot (not when browsing back)
+        if self.state.is_live
+            && self
+                .state
+                .graph
+                .last_live()
+                .is_none_or(|last| snapshot.timestamp > last)
+        {
+            let entry = self.graph_builder.push(&snapshot);
+            let graph = &mut self.state.graph;
+            graph.push_live(snapshot.timestamp, entry);
+            if matches!(self.state.popup, PopupState::Graph) {
+                graph.summary = graph.live.iter().copied().collect();
+            }
+        }
+
+        // Buffer cache: live mode keeps the latest sample (taken every 5
+        // minutes); history mode reloads it for the new position
+        if self.state.is_live {
+            if let Some(info) = snapshot.blocks.iter().find_map(|b| match b {
+                DataBlock::PgBufferCache(info) => Some(info),
+                _ => None,
+            }) {
+                self.state.buffer_cache = Some(buffer_cache_report(snapshot.timestamp, info));
+            }
+        } else if matches!(self.state.popup, PopupState::BufferCache { .. }) {
+            self.state.buffer_cache_requested = true;
+        }
+
         // Close detail popup if the referenced entity disappeared from the new snapshot.
         self.validate_popup(&snapshot);
 
         self.state.current_snapshot = Some(snapshot);
     }
 
+    /// Fills the sparkline window with the snapshots preceding the oldest
+    /// sample (history mode). Samples in the window are consecutive
+    /// positions ending at the cursor, so after a jump the whole window is
+    /// read once and each rewind step reads one more snapshot.
+    fn backfill_summary_history(&mut self) {
+        let Some(history) = self
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+        else {
+            return;
+        };
+        let window = &mut self.state.summary_history;
+        let oldest = history
+            .position()
+            .saturating_sub(window.len().saturating_sub(1));
+        let start = oldest.saturating_sub(window.missing());
+        if start == oldest {
+            return;
+        }
+
+        let mut prev = start.checked_sub(1).and_then(|p| history.snapshot_at(p));
+        let mut samples = Vec::with_capacity(oldest - start);
+        for pos in start..oldest {
+            if let Some(snapshot) = history.snapshot_at(pos) {
+                samples.push(summary_sample(&snapshot, prev.as_ref()));
+                prev = Some(snapshot);
+            }
+        }
+        for sample in samples.into_iter().rev() {
+            window.push_front(sample);
+        }
+    }
+
     /// Closes the detail popup if its target entity is no longer in the snapshot.
     fn validate_popup(&mut self, snapshot: &Snapshot) {
         let close = match &self.state.popup {
@@ -310,6 +556,16 @@ impl App {
 
     /// Advances to next snapshot.
     fn advance(&mut self) {
+        if self.state.live_buffer_position.is_some() {
+            let snapshot = self.provider.advance().cloned();
+            self.show_buffered(snapshot);
+            if self.state.live_buffer_position.is_none() {
+                // Back at the newest snapshot: live again
+                self.state.paused = false;
+            }
+            return;
+        }
+
         // Save previous snapshot for diff
         self.state.previous_snapshot = self.state.current_snapshot.take();
 
@@ -322,8 +578,19 @@ impl App {
         }
     }
 
-    /// Rewinds to previous snapshot (history mode only).
+    /// Rewinds to previous snapshot (history mode, or the live buffer).
     fn rewind(&mut self) {
+        if self.state.is_live {
+            if !self.provider.can_rewind() {
+                self.state.status_message = Some("Live buffer is disabled".to_string());
+                return;
+            }
+            // Collection stops while browsing back
+            self.state.paused = true;
+            let snapshot = self.provider.rewind().cloned();
+            self.show_buffered(snapshot);
+            return;
+        }
         if self.provider.can_rewind() {
             self.state.previous_snapshot = self.state.current_snapshot.take();
 
@@ -339,9 +606,6 @@ impl App {
             return;
         }
 
-        // Keep PostgreSQL error status consistent with other navigation actions
-        self.state.pga.last_error = self.provider.pg_last_error().map(|s| s.to_string());
-
         let input = self.state.time_jump_input.trim();
         if input.is_empty() {
             self.state.time_jump_error = Some("Empty input".to_string());
@@ -363,23 +627,157 @@ impl App {
             }
         };
 
-        let history = self
-            .provider
-            .as_any_mut()
-            .and_then(|a| a.downcast_mut::<HistoryProvider>());
+        // Forward offsets land on the first snapshot at or after the target
+        if self.jump_to_timestamp(target_ts, target_ts > base_ts) {
+            self.state.input_mode = InputMode::Normal;
+            self.state.time_jump_error = None;
+            self.state.time_jump_input.clear();
+        } else {
+            self.state.time_jump_error = Some("No snapshot at target".to_string());
+        }
+    }
+
+    /// Switches to the next PostgreSQL instance: the primary one, then the
+    /// additional instances in snapshot order. Rates restart for the new
+    /// instance; live mode shows it from the next snapshot.
+    fn next_instance(&mut self) {
+        let names: Vec<String> = self
+            .state
+            .current_snapshot
+            .as_ref()
+            .map(|s| s.instance_names().into_iter().map(String::from).collect())
+            .unwrap_or_default();
+        if names.is_empty() {
+            self.state.status_message = Some("No other PostgreSQL instances".to_string());
+            return;
+        }
+        let next = match self.provider.instance() {
+            None => names.first().cloned(),
+            Some(current) => names
+                .iter()
+                .position(|n| n == current)
+                .and_then(|i| names.get(i + 1))
+                .cloned(),
+        };
+        self.provider.set_instance(next.clone());
+        self.state.pgs.rate_state.reset();
+        self.state.pgp.rate_state.reset();
+        self.state.pgt.rate_state.reset();
+        self.state.pgi.rate_state.reset();
+        self.state.pge.clear_accumulated();
+        self.state.status_message = Some(format!(
+            "PostgreSQL instance: {}",
+            next.as_deref().unwrap_or("primary")
+        ));
+        self.state.instance = next;
+
+        if !self.state.is_live
+            && let Some(ts) = self.state.current_snapshot.as_ref().map(|s| s.timestamp)
+        {
+            self.jump_to_timestamp(ts, false);
+        }
+    }
 
-        let Some(history) = history else {
-            self.state.time_jump_error = Some("History provider is not available".to_string());
+    /// Moves one timeline column back or forward from the current snapshot.
+    fn timeline_step(&mut self, forward: bool) {
+        let Some(current) = self.state.current_snapshot.as_ref().map(|s| s.timestamp) else {
             return;
         };
+        let step = (self.state.timeline.column_secs().round() as i64).max(1);
+        let target = if forward {
+            current + step
+        } else {
+            current - step
+        };
+        self.jump_to_timestamp(target, forward);
+    }
+
+    /// Moves by the configured large step (`,`/`.`), landing on the nearest
+    /// snapshot in the direction of travel.
+    fn large_step(&mut self, forward: bool) {
+        let Some(current) = self.state.current_snapshot.as_ref().map(|s| s.timestamp) else {
+            return;
+        };
+        let step = self.state.large_step_secs;
+        let target = if forward {
+            current + step
+        } else {
+            current - step
+        };
+        self.jump_to_timestamp(target, forward);
+        if self.state.current_snapshot.as_ref().map(|s| s.timestamp) == Some(current) {
+            self.state.status_message = Some(
+                if forward {
+                    "Already at the last snapshot"
+                } else {
+                    "Already at the first snapshot"
+                }
+                .to_string(),
+            );
+        }
+    }
+
+    /// Jumps to the snapshot at `target_ts` (the latest one before it, or
+    /// the earliest one after it when `forward`). Returns false without a
+    /// history provider or snapshot.
+    fn jump_to_timestamp(&mut self, target_ts: i64, forward: bool) -> bool {
+        // Keep PostgreSQL error status consistent with other navigation actions
+        self.state.pga.last_error = self.provider.pg_last_error().map(|s| s.to_string());
 
-        history.jump_to_timestamp_floor(target_ts);
+        let Some(history) = self
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+        else {
+            return false;
+        };
+
+        if forward {
+            history.jump_to_timestamp_ceil(target_ts);
+        } else {
+            history.jump_to_timestamp_floor(target_ts);
+        }
         let pos = history.position();
 
         let current = history.current().cloned();
         let prev = pos.checked_sub(1).and_then(|p| history.snapshot_at(p));
 
-        // Reset the diff baseline to the snapshot before the target (if any).
+        self.reset_baseline(prev);
+
+        match current {
+            Some(snapshot) => {
+                self.apply_snapshot(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Shows a snapshot of the live buffer, diffed against the buffered
+    /// snapshot before it.
+    fn show_buffered(&mut self, snapshot: Option<Snapshot>) {
+        let Some(snapshot) = snapshot else {
+            return;
+        };
+        let prev = self.provider.previous().cloned();
+        // PostgreSQL rates restart from the baseline (time may go backwards)
+        self.state.pgs.rate_state.reset();
+        self.state.pgp.rate_state.reset();
+        self.state.pgt.rate_state.reset();
+        self.state.pgi.rate_state.reset();
+        if let Some(prev) = prev.as_ref() {
+            crate::rates::update_pgs_rates(&mut self.state.pgs.rate_state, prev);
+            crate::rates::update_pgp_rates(&mut self.state.pgp.rate_state, prev);
+            crate::rates::update_pgt_rates(&mut self.state.pgt.rate_state, prev);
+            crate::rates::update_pgi_rates(&mut self.state.pgi.rate_state, prev);
+        }
+        self.reset_baseline(prev);
+        self.state.live_buffer_position = self.provider.buffer_position();
+        self.apply_snapshot(snapshot);
+    }
+
+    /// Makes `prev` (the snapshot before the one shown next) the diff baseline.
+    fn reset_baseline(&mut self, prev: Option<Snapshot>) {
         self.state.previous_snapshot = prev;
         self.state.current_snapshot = None;
 
@@ -394,17 +792,218 @@ impl App {
             update_prev_dsk(prev_snapshot, &mut self.state.prev_process_dsk);
             self.state.prev_total_cpu_time = Some(get_total_cpu_time(prev_snapshot));
         }
+    }
 
-        if let Some(snapshot) = current {
-            self.apply_snapshot(snapshot);
-            self.state.input_mode = InputMode::Normal;
-            self.state.time_jump_error = None;
-            self.state.time_jump_input.clear();
-        } else {
-            self.state.time_jump_error = Some("No snapshot at target".to_string());
+    /// Recomputes the visible timeline range and reloads its health buckets
+    /// and gaps when the view or terminal width changed.
+    fn refresh_timeline(&mut self) {
+        let width = self.state.terminal_width as usize;
+        let Some(history) = self
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+        else {
+            return;
+        };
+        let Some(cursor) = history.current_timestamp() else {
+            return;
+        };
+        let timeline = &mut self.state.timeline;
+        timeline.update_view(history.timestamp_range(), cursor);
+        if timeline.needs_reload(width) {
+            let entries = history.load_heatmap_range(timeline.view.0, timeline.view.1);
+            let gaps = history.gaps_in_range(timeline.view.0, timeline.view.1);
+            timeline.set_entries(&entries, &gaps, width);
         }
     }
 
+    /// Builds the metric history for the query shown in the query view.
+    ///
+    /// History mode samples the whole loaded window (at most
+    /// `MAX_QUERY_HISTORY_POINTS` intervals); live mode starts from the
+    /// current snapshot and grows as new snapshots arrive.
+    fn load_query_history(&mut self) {
+        let PopupState::QueryView { queryid, .. } = self.state.popup else {
+            return;
+        };
+        let mut history = QueryHistory::new(queryid);
+
+        let provider = self
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>());
+        match provider {
+            Some(provider) => {
+                for pos in sample_positions(provider.len()) {
+                    if let Some(snapshot) = provider.snapshot_at(pos) {
+                        history.push_snapshot(&snapshot);
+                    }
+                }
+            }
+            None => {
+                if let Some(prev) = self.state.previous_snapshot.as_ref() {
+                    history.push_snapshot(prev);
+                }
+                if let Some(current) = self.state.current_snapshot.as_ref() {
+                    history.push_snapshot(current);
+                }
+            }
+        }
+
+        self.state.query_history = Some(history);
+    }
+
+    /// Extracts the trajectory of the entity shown in the entity history
+    /// popup over the whole loaded history (sampled, see `MAX_ENTITY_POINTS`).
+    fn load_entity_history(&mut self) {
+        let PopupState::EntityHistory { kind, id, .. } = self.state.popup else {
+            return;
+        };
+        let Some(provider) = self
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+        else {
+            return;
+        };
+        let (Some(&start), Some(&end)) =
+            (provider.timestamps().first(), provider.timestamps().last())
+        else {
+            return;
+        };
+        self.state.entity_history = Some(entity_history(provider, kind, id, start, end));
+    }
+
+    /// Loads the latest buffer cache sample at or before the current
+    /// position (history mode).
+    fn load_buffer_cache(&mut self) {
+        let Some(ts) = self.state.current_snapshot.as_ref().map(|s| s.timestamp) else {
+            return;
+        };
+        let Some(provider) = self
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+        else {
+            return;
+        };
+        self.state.buffer_cache = buffer_cache_at(provider, ts);
+    }
+
+    /// Copies the row detail fields (row detail popup) or the query text of
+    /// the selected PGA/PGS/PGP row to the clipboard.
+    fn copy_selection(&mut self) {
+        let text = if matches!(self.state.popup, PopupState::RowDetail { .. }) {
+            let lines: Vec<String> = self
+                .state
+                .row_detail
+                .iter()
+                .map(|(header, value)| format!("{}: {}", header, value))
+                .collect();
+            (!lines.is_empty()).then(|| lines.join("\n"))
+        } else {
+            self.selected_query_text()
+        };
+        let Some(text) = text else {
+            self.state.status_message = Some("Nothing to copy".to_string());
+            return;
+        };
+        self.state.status_message =
+            Some(match clipboard::copy(&text, self.state.native_clipboard) {
+                Ok(method) => format!("Copied {} characters ({})", text.chars().count(), method),
+                Err(e) => format!("Copy failed: {}", e),
+            });
+    }
+
+    /// Full query text of the selected row on PGA, PGS or PGP.
+    fn selected_query_text(&self) -> Option<String> {
+        let snapshot = self.state.current_snapshot.as_ref()?;
+        let statement_query = |queryid: i64| {
+            snapshot.blocks.iter().find_map(|b| match b {
+                DataBlock::PgStatStatements(v) => v
+                    .iter()
+                    .find(|s| s.queryid == queryid)
+                    .map(|s| s.query_hash),
+                _ => None,
+            })
+        };
+        let hash = match self.state.current_tab {
+            Tab::PostgresActive => {
+                let pid = self.state.pga.tracked_pid?;
+                snapshot.blocks.iter().find_map(|b| match b {
+                    DataBlock::PgStatActivity(v) => {
+                        v.iter().find(|a| a.pid == pid).map(|a| a.query_hash)
+                    }
+                    _ => None,
+                })
+            }
+            Tab::PgStatements => statement_query(self.state.pgs.tracked_queryid?),
+            Tab::PgStorePlans => {
+                let planid = self.state.pgp.tracked_planid?;
+                let queryid = snapshot.blocks.iter().find_map(|b| match b {
+                    DataBlock::PgStorePlans(v) => v
+                        .iter()
+                        .find(|p| p.planid == planid)
+                        .map(|p| p.stmt_queryid),
+                    _ => None,
+                })?;
+                statement_query(queryid)
+            }
+            _ => None,
+        }?;
+        self.provider
+            .interner()?
+            .resolve(hash)
+            .filter(|q| !q.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Writes an exported table to the templated path and reports the result
+    /// in the status line.
+    fn write_export(&mut self, table: &ExportTable) {
+        let timestamp = self
+            .state
+            .current_snapshot
+            .as_ref()
+            .map_or_else(|| chrono::Utc::now().timestamp(), |s| s.timestamp);
+        let path = table.path(&self.state.export_template, timestamp);
+        let content = table.render(ExportFormat::from_path(&path));
+        self.state.status_message = Some(match std::fs::write(&path, content) {
+            Ok(()) => format!("Exported {} rows to {}", table.rows.len(), path.display()),
+            Err(e) => format!("Export to {} failed: {}", path.display(), e),
+        });
+    }
+
+    /// Loads the graph series: summary entries of the whole loaded history
+    /// (the live entries in live mode) and, in history mode, the trajectory
+    /// of the selected row.
+    fn load_graph(&mut self) {
+        let Some(provider) = self
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+        else {
+            let graph = &mut self.state.graph;
+            graph.summary = graph.live.iter().copied().collect();
+            if graph.entity.is_some() {
+                self.state.status_message =
+                    Some("Row metrics are graphed in history mode".to_string());
+            }
+            return;
+        };
+        let (Some(&start), Some(&end)) =
+            (provider.timestamps().first(), provider.timestamps().last())
+        else {
+            return;
+        };
+        let graph = &mut self.state.graph;
+        graph.summary = provider.load_heatmap_range(start, end);
+        graph.entity_history = graph
+            .entity
+            .map(|(kind, id)| entity_history(provider, kind, id, start, end))
+            .filter(|h| !h.points.is_empty());
+    }
+
     /// Handles drill-down navigation between tabs.
     /// PRC -> PGA: Navigate to PostgreSQL session by selected process PID.
     /// PGA -> PGS: Navigate to statement stats by query_id.
@@ -519,6 +1118,9 @@ impl App {
             Tab::PgErrors => {
                 // No drill-down from PGE
             }
+            Tab::PgRoles | Tab::Custom | Tab::PgSettings | Tab::Agent => {
+                // No drill-down from PGR / CUS / PGC / AGT
+            }
             Tab::PgStorePlans => {
                 // No further drill-down from PGP
             }
@@ -0,0 +1,77 @@
+//! Clipboard for `y`: an OSC 52 escape sequence, which the terminal turns
+//! into a clipboard write (works over SSH and inside tmux), and optionally a
+//! native clipboard tool on the local machine.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// Native clipboard tools, tried in order; the first that runs wins.
+const NATIVE_TOOLS: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("pbcopy", &[]),
+];
+
+/// Copies `text` via OSC 52 and, if `native` is set, a native tool.
+/// Returns how the text was copied.
+pub fn copy(text: &str, native: bool) -> io::Result<&'static str> {
+    let tmux = std::env::var_os("TMUX").is_some();
+    let mut out = io::stdout();
+    out.write_all(osc52_sequence(text, tmux).as_bytes())?;
+    out.flush()?;
+    if native && let Some(tool) = copy_native(text) {
+        return Ok(tool);
+    }
+    Ok("OSC 52")
+}
+
+/// OSC 52 "set clipboard" sequence; inside tmux it is wrapped in a DCS
+/// passthrough so it reaches the outer terminal.
+fn osc52_sequence(text: &str, tmux: bool) -> String {
+    let osc = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    if tmux {
+        format!("\x1bPtmux;\x1b{}\x1b\\", osc)
+    } else {
+        osc
+    }
+}
+
+/// Pipes `text` into the first native clipboard tool that runs.
+fn copy_native(text: &str) -> Option<&'static str> {
+    NATIVE_TOOLS.iter().find_map(|&(tool, args)| {
+        let mut child = Command::new(tool)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        let ok = child.wait().is_ok_and(|s| s.success());
+        (written && ok).then_some(tool)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osc52_encodes_text() {
+        assert_eq!(
+            osc52_sequence("SELECT 1", false),
+            "\x1b]52;c;U0VMRUNUIDE=\x07"
+        );
+        assert_eq!(
+            osc52_sequence("SELECT 1", true),
+            "\x1bPtmux;\x1b\x1b]52;c;U0VMRUNUIDE=\x07\x1b\\"
+        );
+    }
+}
@@ -6,7 +6,7 @@ use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
 use std::time::Duration;
 
-use crossterm::event::{self, Event as CrosstermEvent, KeyEvent};
+use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent, MouseEventKind};
 
 /// Application events.
 #[derive(Debug)]
@@ -15,6 +15,8 @@ pub enum Event {
     Tick,
     /// Keyboard input.
     Key(KeyEvent),
+    /// Mouse input (clicks on the timeline bar).
+    Mouse(MouseEvent),
     /// Terminal resize (width).
     Resize(u16),
 }
@@ -39,6 +41,12 @@ impl EventHandler {
                     if let Ok(evt) = event::read() {
                         let event = match evt {
                             CrosstermEvent::Key(key) => Event::Key(key),
+                            // Clicks only; motion events would just force redraws
+                            CrosstermEvent::Mouse(mouse)
+                                if matches!(mouse.kind, MouseEventKind::Down(_)) =>
+                            {
+                                Event::Mouse(mouse)
+                            }
                             CrosstermEvent::Resize(w, _) => Event::Resize(w),
                             _ => continue,
                         };
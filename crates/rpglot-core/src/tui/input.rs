@@ -2,8 +2,11 @@
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
+use crate::entity::EntityKind;
+
+use super::keymap::{parse_key, search_actions};
 use super::navigable::NavigableTable;
-use super::state::{AppState, InputMode, PopupState, ProcessViewMode, Tab};
+use super::state::{AppState, InputMode, PopupState, ProcessViewMode, RowFilter, Tab};
 
 /// Result of handling a key event.
 #[derive(Debug, PartialEq, Eq)]
@@ -18,6 +21,14 @@ pub enum KeyAction {
     Rewind,
     /// Jump to a specific time (history mode, `b`).
     JumpToTime,
+    /// Move one timeline column back or forward (history mode, `[`/`]`).
+    TimelineStep { forward: bool },
+    /// Move by the large step back or forward (history mode, `,`/`.`).
+    LargeStep { forward: bool },
+    /// Jump to the snapshot at a timestamp (history mode, bookmarks).
+    JumpTo(i64),
+    /// Switch to the next PostgreSQL instance (`@`).
+    NextInstance,
 }
 
 /// Navigation action for unified scroll/selection dispatch.
@@ -48,7 +59,12 @@ fn dispatch_navigation(state: &mut AppState, action: NavAction) {
         | PopupState::PgiDetail { scroll, .. }
         | PopupState::PgpDetail { scroll, .. }
         | PopupState::PgeDetail { scroll, .. }
-        | PopupState::PglDetail { scroll, .. } => match action {
+        | PopupState::PglDetail { scroll, .. }
+        | PopupState::QueryView { scroll, .. }
+        | PopupState::PlanDiff { scroll, .. }
+        | PopupState::EntityHistory { scroll, .. }
+        | PopupState::BufferCache { scroll }
+        | PopupState::RowDetail { scroll } => match action {
             NavAction::Up => *scroll = scroll.saturating_sub(1),
             NavAction::Down => *scroll = scroll.saturating_add(1),
             NavAction::PageUp(n) => *scroll = scroll.saturating_sub(n),
@@ -79,6 +95,10 @@ fn dispatch_navigation(state: &mut AppState, action: NavAction) {
                     Tab::PgIndexes => &mut state.pgi,
                     Tab::PgErrors => &mut state.pge,
                     Tab::PgLocks => &mut state.pgl,
+                    Tab::PgRoles => &mut state.pgr,
+                    Tab::Custom => &mut state.cus,
+                    Tab::PgSettings => &mut state.pgc,
+                    Tab::Agent => return,
                     Tab::Processes => unreachable!(),
                 };
                 match action {
@@ -99,11 +119,122 @@ pub fn handle_key(state: &mut AppState, key: KeyEvent) -> KeyAction {
     if matches!(state.popup, super::state::PopupState::QuitConfirm) {
         return handle_quit_confirm(state, key);
     }
+    if matches!(state.popup, PopupState::ColumnChooser { .. }) {
+        return handle_column_chooser(state, key);
+    }
+    if matches!(state.popup, PopupState::Bookmarks { .. }) {
+        return handle_bookmark_menu(state, key);
+    }
+    if matches!(state.popup, PopupState::Graph) {
+        return handle_graph(state, key);
+    }
     match state.input_mode {
-        InputMode::Normal => handle_normal_mode(state, key),
+        InputMode::Normal => {
+            let key = state.keymap.translate(key);
+            handle_normal_mode(state, key)
+        }
         InputMode::Filter => handle_filter_mode(state, key),
+        InputMode::RowFilter => handle_row_filter_mode(state, key),
         InputMode::TimeJump => handle_time_jump_mode(state, key),
+        InputMode::BookmarkLabel => handle_bookmark_label_mode(state, key),
+        InputMode::Palette => handle_palette_mode(state, key),
+    }
+}
+
+/// Handles keys in the column chooser popup. Changes apply immediately;
+/// the layout file is written when the chooser closes.
+fn handle_column_chooser(state: &mut AppState, key: KeyEvent) -> KeyAction {
+    let PopupState::ColumnChooser { cursor, changed } = state.popup else {
+        return KeyAction::None;
+    };
+    let Some(schema) = state
+        .column_schema
+        .clone()
+        .filter(|s| s.tab == state.current_tab)
+    else {
+        state.popup = PopupState::None;
+        return KeyAction::None;
+    };
+    let names: Vec<&str> = schema.headers.iter().map(String::as_str).collect();
+    let layout = state.layouts.entry(schema.tab, schema.mode);
+    let before = layout.clone();
+    let ordered = layout.ordered_columns(&names, schema.movable);
+    let last = ordered.len().saturating_sub(1);
+    let cursor = cursor.min(last);
+    let current = names
+        .get(ordered.get(cursor).copied().unwrap_or(0))
+        .copied();
+
+    let mut new_cursor = cursor;
+    match key.code {
+        KeyCode::Esc
+        | KeyCode::Enter
+        | KeyCode::Char('o')
+        | KeyCode::Char('O')
+        | KeyCode::Char('q') => {
+            state.layouts.prune();
+            state.layout_save_requested = changed;
+            state.popup = PopupState::None;
+            return KeyAction::None;
+        }
+        KeyCode::Up | KeyCode::Char('k') => new_cursor = cursor.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => new_cursor = (cursor + 1).min(last),
+        KeyCode::Char(' ') => {
+            if let Some(header) = current {
+                layout.toggle_hidden(&names, header);
+            }
+        }
+        KeyCode::Char('K') | KeyCode::Char('-') => {
+            if let Some(pos) =
+                current.and_then(|h| layout.move_column(&names, schema.movable, h, -1))
+            {
+                new_cursor = pos;
+            }
+        }
+        KeyCode::Char('J') | KeyCode::Char('+') => {
+            if let Some(pos) =
+                current.and_then(|h| layout.move_column(&names, schema.movable, h, 1))
+            {
+                new_cursor = pos;
+            }
+        }
+        KeyCode::Char('r') | KeyCode::Char('R') => *layout = Default::default(),
+        _ => {}
+    }
+    let changed = changed || *layout != before;
+    state.popup = PopupState::ColumnChooser {
+        cursor: new_cursor,
+        changed,
+    };
+    KeyAction::None
+}
+
+/// Handles keys in the graph view.
+fn handle_graph(state: &mut AppState, key: KeyEvent) -> KeyAction {
+    let graph = &mut state.graph;
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            return KeyAction::Quit;
+        }
+        KeyCode::Esc | KeyCode::Char('g') | KeyCode::Char('q') => state.popup = PopupState::None,
+        KeyCode::Left | KeyCode::Char('h') => graph.move_cursor(-1),
+        KeyCode::Right | KeyCode::Char('l') => graph.move_cursor(1),
+        KeyCode::PageUp => graph.move_cursor(-10),
+        KeyCode::PageDown => graph.move_cursor(10),
+        KeyCode::Home => graph.cursor_to_end(false),
+        KeyCode::End => graph.cursor_to_end(true),
+        KeyCode::Char('+') | KeyCode::Char('=') => {
+            graph.zoom_in();
+        }
+        KeyCode::Char('-') => {
+            graph.zoom_out();
+        }
+        KeyCode::Tab | KeyCode::Down | KeyCode::Char('j') => graph.next_metric(true),
+        KeyCode::BackTab | KeyCode::Up | KeyCode::Char('k') => graph.next_metric(false),
+        KeyCode::Char('s') => graph.next_secondary(),
+        _ => {}
     }
+    KeyAction::None
 }
 
 fn handle_quit_confirm(state: &mut AppState, key: KeyEvent) -> KeyAction {
@@ -145,6 +276,9 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
         | KeyCode::Char('6')
         | KeyCode::Char('7')
         | KeyCode::Char('8')
+        | KeyCode::Char('9')
+        | KeyCode::Char('0')
+        | KeyCode::Char('~')
             if state.any_popup_open() =>
         {
             state.status_message = Some("Close popup (Esc) before switching tabs".to_string());
@@ -190,6 +324,28 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
             state.switch_tab(Tab::PgLocks);
             KeyAction::None
         }
+        KeyCode::Char('9') => {
+            state.switch_tab(Tab::PgRoles);
+            KeyAction::None
+        }
+        KeyCode::Char('0') => {
+            state.switch_tab(Tab::Custom);
+            KeyAction::None
+        }
+        KeyCode::Char('&') => {
+            state.switch_tab(Tab::PgSettings);
+            KeyAction::None
+        }
+        // Hidden rpglotd self-monitoring tab
+        KeyCode::Char('~') => {
+            if state.current_tab == Tab::Agent {
+                state.switch_tab(state.agent_return_tab);
+            } else {
+                state.agent_return_tab = state.current_tab;
+                state.switch_tab(Tab::Agent);
+            }
+            KeyAction::None
+        }
 
         // Row navigation (or popup scroll if popup is open)
         KeyCode::Up | KeyCode::Char('k') => {
@@ -228,6 +384,10 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
                 Tab::PgIndexes => state.pgi.next_sort_column(),
                 Tab::PgErrors => state.pge.next_sort_column(),
                 Tab::PgLocks => {} // tree order, no sorting
+                Tab::PgRoles => state.pgr.next_sort_column(),
+                Tab::Custom => state.cus.next_sort_column(),
+                Tab::PgSettings => state.pgc.next_sort_column(),
+                Tab::Agent => {}
             }
             KeyAction::None
         }
@@ -241,6 +401,10 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
                 Tab::PgIndexes => state.pgi.toggle_sort_direction(),
                 Tab::PgErrors => state.pge.toggle_sort_direction(),
                 Tab::PgLocks => {} // tree order, no sorting
+                Tab::PgRoles => state.pgr.toggle_sort_direction(),
+                Tab::Custom => state.cus.toggle_sort_direction(),
+                Tab::PgSettings => state.pgc.toggle_sort_direction(),
+                Tab::Agent => {}
             }
             KeyAction::None
         }
@@ -252,32 +416,144 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
             KeyAction::None
         }
 
-        // Jump to time (history mode only)
+        // Column chooser: show/hide and reorder columns of the current view
+        KeyCode::Char('o') | KeyCode::Char('O') => {
+            if state.popup.is_open() {
+                state.status_message =
+                    Some("Close popup (Esc) before choosing columns".to_string());
+            } else if state.column_schema.as_ref().is_some_and(|s| {
+                s.tab == state.current_tab && s.mode == state.current_view_mode_name()
+            }) {
+                state.popup = PopupState::ColumnChooser {
+                    cursor: 0,
+                    changed: false,
+                };
+            }
+            KeyAction::None
+        }
+
+        // Column expression filter: edit the current tab's expression
+        KeyCode::Char('\\') => {
+            state.input_mode = InputMode::RowFilter;
+            state.row_filter_input = state
+                .current_row_filter()
+                .map(|rf| rf.as_str().to_string())
+                .unwrap_or_default();
+            state.row_filter_error = None;
+            KeyAction::None
+        }
+
+        // Jump to time (history mode); step back through the buffer (live mode)
         KeyCode::Char('b') | KeyCode::Char('B') => {
-            if !state.is_live {
-                state.input_mode = InputMode::TimeJump;
-                state.time_jump_input.clear();
-                state.time_jump_error = None;
+            if state.is_live {
+                return KeyAction::Rewind;
             }
+            state.input_mode = InputMode::TimeJump;
+            state.time_jump_input.clear();
+            state.time_jump_error = None;
             KeyAction::None
         }
 
-        // History navigation (arrows or t/T)
-        KeyCode::Left | KeyCode::Char('T') => {
-            if !state.is_live {
-                KeyAction::Rewind
+        // Copy the selected query (PGA/PGS/PGP) or the row detail fields
+        KeyCode::Char('y')
+            if matches!(state.popup, PopupState::RowDetail { .. })
+                || matches!(
+                    state.current_tab,
+                    Tab::PostgresActive | Tab::PgStatements | Tab::PgStorePlans
+                ) =>
+        {
+            state.copy_requested = true;
+            KeyAction::None
+        }
+
+        // Bookmarks (history mode): y marks the current snapshot (a in the
+        // list on PGA/PGS/PGP), Y lists bookmarks, {/} jump to the previous/next one
+        KeyCode::Char('y') | KeyCode::Char('Y') if state.bookmarks.is_none() => {
+            if state.is_live {
+                state.status_message = Some("Bookmarks are available in history mode".to_string());
+            }
+            KeyAction::None
+        }
+        KeyCode::Char('y') => {
+            state.start_bookmark();
+            KeyAction::None
+        }
+        KeyCode::Char('Y') => {
+            if state.popup.is_open() {
+                state.status_message =
+                    Some("Close popup (Esc) before opening bookmarks".to_string());
             } else {
-                KeyAction::None
+                state.open_bookmarks();
+            }
+            KeyAction::None
+        }
+        KeyCode::Char('{') | KeyCode::Char('}') => {
+            let forward = key.code == KeyCode::Char('}');
+            match state.adjacent_bookmark(forward) {
+                Some(ts) => KeyAction::JumpTo(ts),
+                None => {
+                    if state.bookmarks.is_some() {
+                        state.status_message = Some(
+                            if forward {
+                                "No later bookmark"
+                            } else {
+                                "No earlier bookmark"
+                            }
+                            .to_string(),
+                        );
+                    }
+                    KeyAction::None
+                }
             }
         }
+
+        // History navigation (arrows or t/T); in live mode back through the
+        // buffer and forward again up to the newest snapshot
+        KeyCode::Left | KeyCode::Char('T') => KeyAction::Rewind,
         KeyCode::Right => {
-            if !state.is_live {
+            if state.can_step_forward() {
                 KeyAction::Advance
             } else {
                 KeyAction::None
             }
         }
 
+        // Timeline bar: step by one column, zoom around the cursor
+        KeyCode::Char('[') | KeyCode::Char(']') => {
+            if !state.is_live {
+                KeyAction::TimelineStep {
+                    forward: key.code == KeyCode::Char(']'),
+                }
+            } else {
+                KeyAction::None
+            }
+        }
+        // Large steps (history mode): `,`/`.` are `<`/`>` without Shift
+        KeyCode::Char(',') | KeyCode::Char('.') => {
+            if state.is_live {
+                state.status_message =
+                    Some("Large steps are available in history mode".to_string());
+                KeyAction::None
+            } else {
+                KeyAction::LargeStep {
+                    forward: key.code == KeyCode::Char('.'),
+                }
+            }
+        }
+
+        KeyCode::Char('+') | KeyCode::Char('=') => {
+            if !state.is_live && !state.timeline.zoom_in() {
+                state.status_message = Some("Timeline: maximum zoom".to_string());
+            }
+            KeyAction::None
+        }
+        KeyCode::Char('-') => {
+            if !state.is_live {
+                state.timeline.zoom_out();
+            }
+            KeyAction::None
+        }
+
         // PGS view mode: t/c/i/e (context-sensitive, overrides history 't' on PGS tab)
         KeyCode::Char('t') => {
             if state.current_tab == Tab::PgStatements {
@@ -294,7 +570,7 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
                     super::state::PgStorePlansViewMode::Time.default_sort_column();
                 state.pgp.sort_ascending = false;
                 KeyAction::None
-            } else if !state.is_live {
+            } else if state.can_step_forward() {
                 KeyAction::Advance
             } else {
                 KeyAction::None
@@ -307,8 +583,31 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
             KeyAction::None
         }
 
-        // Process view modes (atop-style) and PGA view modes
-        KeyCode::Char('g') | KeyCode::Char('G') => {
+        // Graph view: summary metrics and the selected row's trajectory
+        KeyCode::Char('g') => {
+            if state.popup.is_open() {
+                return KeyAction::None;
+            }
+            let entity = match state.current_tab {
+                Tab::Processes => state
+                    .process_table
+                    .tracked_id
+                    .map(|pid| (EntityKind::Pid, pid as i64)),
+                Tab::PostgresActive => state
+                    .pga
+                    .tracked_pid
+                    .map(|pid| (EntityKind::Pid, pid as i64)),
+                Tab::PgStatements => state.pgs.tracked_queryid.map(|q| (EntityKind::Queryid, q)),
+                _ => None,
+            };
+            state.graph.open(entity);
+            state.popup = PopupState::Graph;
+            state.graph_requested = true;
+            KeyAction::None
+        }
+
+        // Process view modes (atop-style) and PGA view modes; g opens the graph
+        KeyCode::Char('G') => {
             if state.current_tab == Tab::Processes {
                 state.process_view_mode = ProcessViewMode::Generic;
                 state.horizontal_scroll = 0;
@@ -356,34 +655,74 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
             KeyAction::None
         }
 
-        // Horizontal scroll for wide tables
+        // Horizontal scroll for wide tables (first column stays pinned;
+        // the upper bound is clamped while rendering)
         KeyCode::Char('h') => {
-            if state.current_tab == Tab::Processes && state.horizontal_scroll > 0 {
-                state.horizontal_scroll -= 1;
-            }
+            state.horizontal_scroll = state.horizontal_scroll.saturating_sub(1);
             KeyAction::None
         }
         KeyCode::Char('l') => {
-            if state.current_tab == Tab::Processes {
-                state.horizontal_scroll += 1;
-            }
+            state.horizontal_scroll += 1;
+            KeyAction::None
+        }
+
+        // Command palette: every action by name, fuzzy search
+        KeyCode::Char(':') => {
+            state.input_mode = InputMode::Palette;
+            state.palette_input.clear();
+            state.palette_cursor = 0;
+            KeyAction::None
+        }
+
+        // Cycle PostgreSQL instances
+        KeyCode::Char('@') => KeyAction::NextInstance,
+
+        // Cycle color themes (built-in, then user themes)
+        KeyCode::Char('L') => {
+            state.cycle_theme();
             KeyAction::None
         }
 
-        // PGA view mode: v for Stats view
+        // Row detail: every column of the selected row, one per line
+        KeyCode::Char('z') | KeyCode::Char('Z') => {
+            state.popup = match state.popup {
+                PopupState::RowDetail { .. } => PopupState::None,
+                PopupState::None => PopupState::RowDetail { scroll: 0 },
+                ref other => other.clone(),
+            };
+            KeyAction::None
+        }
+
+        // PGA view mode: v cycles Stats/Transactions views; PGE: v toggles Errors/Events; PGR: roles/apps/clients;
+        // CUS: next query; PGC: all/non-default; PRC: process tree
         KeyCode::Char('v') | KeyCode::Char('V') => {
-            if state.current_tab == Tab::PostgresActive {
-                // Toggle between Generic and Stats view
+            if state.current_tab == Tab::Processes {
+                state.process_tree = !state.process_tree;
+                state.apply_process_sort();
+            } else if state.current_tab == Tab::PostgresActive {
+                // Cycle Generic -> Stats -> Transactions
                 state.pga.view_mode = match state.pga.view_mode {
                     super::state::PgActivityViewMode::Generic => {
                         super::state::PgActivityViewMode::Stats
                     }
                     super::state::PgActivityViewMode::Stats => {
+                        super::state::PgActivityViewMode::Transactions
+                    }
+                    super::state::PgActivityViewMode::Transactions => {
                         super::state::PgActivityViewMode::Generic
                     }
                 };
                 state.pga.sort_column = state.pga.view_mode.default_sort_column();
                 state.pga.sort_ascending = false;
+            } else if state.current_tab == Tab::PgErrors {
+                // Toggle between Errors and Events view
+                state.pge.toggle_view_mode();
+            } else if state.current_tab == Tab::PgRoles {
+                state.pgr.toggle_view_mode();
+            } else if state.current_tab == Tab::Custom {
+                state.cus.next_query();
+            } else if state.current_tab == Tab::PgSettings {
+                state.pgc.toggle_view_mode();
             }
             KeyAction::None
         }
@@ -419,8 +758,36 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
             KeyAction::None
         }
 
+        // Pin the selected row to the top of the table
+        KeyCode::Char('^') => {
+            let msg = match state.toggle_pin() {
+                Some(true) => "Row pinned to the top",
+                Some(false) => "Row unpinned",
+                None => "Nothing to pin on this tab",
+            };
+            state.status_message = Some(msg.to_string());
+            KeyAction::None
+        }
+
+        // Watch mode: highlight cells changed since the last refresh
+        KeyCode::Char('*') => {
+            let msg = if state.watch.toggle() {
+                "Watch mode on: changed cells are highlighted"
+            } else {
+                "Watch mode off"
+            };
+            state.status_message = Some(msg.to_string());
+            KeyAction::None
+        }
+
+        // Export the current table; the next render captures it
+        KeyCode::Char('E') => {
+            state.export_requested = true;
+            KeyAction::None
+        }
+
         // PGS Temp view
-        KeyCode::Char('e') | KeyCode::Char('E') => {
+        KeyCode::Char('e') => {
             if state.current_tab == Tab::PgStatements {
                 state.pgs.view_mode = super::state::PgStatementsViewMode::Temp;
                 state.pgs.selected = 0;
@@ -456,6 +823,8 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
                 state.pgt.selected = 0;
                 state.pgt.sort_column = super::state::PgTablesViewMode::Scans.default_sort_column();
                 state.pgt.sort_ascending = false;
+            } else if state.current_tab == Tab::PgErrors {
+                state.pge.expanded = !state.pge.expanded;
             }
             KeyAction::None
         }
@@ -499,6 +868,58 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
             KeyAction::None
         }
 
+        // Full-screen query view (PGS/PGP): SQL, plans, metric history
+        KeyCode::Char('f') | KeyCode::Char('F') => {
+            if matches!(state.popup, PopupState::QueryView { .. }) {
+                state.popup = PopupState::None;
+            } else if state.is_live && state.live_buffer_position.is_some() {
+                // Browsing the live buffer: f steps forward on every tab
+                return KeyAction::Advance;
+            } else if state.current_tab == Tab::PgStatements
+                && let Some(queryid) = state.pgs.tracked_queryid
+            {
+                state.popup = PopupState::QueryView {
+                    queryid,
+                    planid: None,
+                    scroll: 0,
+                };
+                state.query_view_requested = true;
+            } else if state.current_tab == Tab::PgStorePlans
+                && let Some((queryid, planid)) = tracked_plan(state)
+            {
+                state.popup = PopupState::QueryView {
+                    queryid,
+                    planid: Some(planid),
+                    scroll: 0,
+                };
+                state.query_view_requested = true;
+            } else if state.can_step_forward() {
+                return KeyAction::Advance;
+            }
+            KeyAction::None
+        }
+
+        // Plan diff (PGP): selected plan against another plan of its queryid
+        KeyCode::Char('|') => {
+            if matches!(state.popup, PopupState::PlanDiff { .. }) {
+                state.popup = PopupState::None;
+            } else if state.current_tab == Tab::PgStorePlans
+                && let Some((queryid, planid)) = tracked_plan(state)
+            {
+                let plans = plans_of_query(state, queryid);
+                if plans > 1 {
+                    state.popup = PopupState::PlanDiff {
+                        queryid,
+                        planid,
+                        scroll: 0,
+                    };
+                } else {
+                    state.status_message = Some(format!("Query {queryid} has a single plan"));
+                }
+            }
+            KeyAction::None
+        }
+
         // Help: toggle inline help in detail popups, or open/close global help
         KeyCode::Char('?') | KeyCode::Char('H') => {
             match &mut state.popup {
@@ -533,9 +954,52 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
             KeyAction::None
         }
 
-        // Detail popup (Enter on PRC or PGA tab)
+        // Entity history (history mode): trajectory of the selected PID or queryid
+        KeyCode::Char('#') => {
+            if matches!(state.popup, PopupState::EntityHistory { .. }) {
+                state.popup = PopupState::None;
+                return KeyAction::None;
+            }
+            let entity = match state.current_tab {
+                Tab::PostgresActive => state
+                    .pga
+                    .tracked_pid
+                    .map(|pid| (EntityKind::Pid, pid as i64)),
+                Tab::PgStatements => state.pgs.tracked_queryid.map(|q| (EntityKind::Queryid, q)),
+                _ => None,
+            };
+            if let Some((kind, id)) = entity {
+                if state.is_live {
+                    state.status_message =
+                        Some("Entity history is available in history mode".to_string());
+                } else {
+                    state.popup = PopupState::EntityHistory {
+                        kind,
+                        id,
+                        scroll: 0,
+                    };
+                    state.entity_history_requested = true;
+                }
+            }
+            KeyAction::None
+        }
+
+        // Buffer cache composition (PGT/PGI)
+        KeyCode::Char('%') => {
+            if matches!(state.popup, PopupState::BufferCache { .. }) {
+                state.popup = PopupState::None;
+            } else if matches!(state.current_tab, Tab::PgTables | Tab::PgIndexes) {
+                state.popup = PopupState::BufferCache { scroll: 0 };
+                state.buffer_cache_requested = !state.is_live;
+            }
+            KeyAction::None
+        }
+
+        // Detail popup of the tab; row detail on tabs without one
         KeyCode::Enter => {
-            if state.current_tab == Tab::Processes && !state.process_table.items.is_empty() {
+            if matches!(state.popup, PopupState::RowDetail { .. }) {
+                state.popup = PopupState::None;
+            } else if state.current_tab == Tab::Processes && !state.process_table.items.is_empty() {
                 state.popup = match state.popup {
                     PopupState::ProcessDetail { .. } => PopupState::None,
                     _ => {
@@ -625,7 +1089,9 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
                         }
                     }
                 };
-            } else if state.current_tab == Tab::PgErrors {
+            } else if state.current_tab == Tab::PgErrors
+                && state.pge.view_mode == super::state::PgErrorsViewMode::Errors
+            {
                 state.popup = match state.popup {
                     PopupState::PgeDetail { .. } => PopupState::None,
                     _ => {
@@ -655,6 +1121,9 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
                         }
                     }
                 };
+            } else if state.current_tab != Tab::Agent {
+                // No dedicated detail popup (PGE events, PGR, CUS, PGC): show the raw row
+                state.popup = PopupState::RowDetail { scroll: 0 };
             }
             KeyAction::None
         }
@@ -686,6 +1155,34 @@ fn handle_normal_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
     }
 }
 
+/// Queryid and planid of the selected PGP row.
+fn tracked_plan(state: &AppState) -> Option<(i64, i64)> {
+    let planid = state.pgp.tracked_planid?;
+    current_plans(state)?
+        .iter()
+        .find(|p| p.planid == planid)
+        .map(|p| (p.stmt_queryid, planid))
+}
+
+/// Number of plans of `queryid` in the current snapshot.
+fn plans_of_query(state: &AppState, queryid: i64) -> usize {
+    current_plans(state).map_or(0, |plans| {
+        plans.iter().filter(|p| p.stmt_queryid == queryid).count()
+    })
+}
+
+fn current_plans(state: &AppState) -> Option<&[crate::storage::model::PgStorePlansInfo]> {
+    state
+        .current_snapshot
+        .as_ref()?
+        .blocks
+        .iter()
+        .find_map(|b| match b {
+            crate::storage::model::DataBlock::PgStorePlans(plans) => Some(plans.as_slice()),
+            _ => None,
+        })
+}
+
 fn handle_time_jump_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
     match key.code {
         KeyCode::Esc => {
@@ -715,55 +1212,423 @@ fn handle_time_jump_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::tui::state::PgStatementsViewMode;
-    use crossterm::event::{KeyEvent, KeyEventKind, KeyEventState};
-
-    fn key(code: KeyCode) -> KeyEvent {
-        KeyEvent {
-            code,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::NONE,
+/// Handles keys in the bookmark label prompt.
+fn handle_bookmark_label_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
+    match key.code {
+        KeyCode::Esc => {
+            state.input_mode = InputMode::Normal;
+            state.bookmark_input.clear();
         }
+        KeyCode::Enter => state.save_bookmark(),
+        KeyCode::Backspace => {
+            state.bookmark_input.pop();
+        }
+        KeyCode::Char(c)
+            if !key.modifiers.contains(KeyModifiers::CONTROL)
+                && !key.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            state.bookmark_input.push(c);
+        }
+        _ => {}
     }
+    KeyAction::None
+}
 
-    #[test]
-    fn pgs_tab_switches_with_3() {
-        let mut state = AppState::new(true);
-        assert_eq!(state.current_tab, Tab::Processes);
-
-        let action = handle_key(&mut state, key(KeyCode::Char('3')));
-        assert_eq!(action, KeyAction::None);
-        assert_eq!(state.current_tab, Tab::PgStatements);
+/// Handles keys in the command palette. Enter runs the selected action as
+/// its default key in normal mode.
+fn handle_palette_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
+    let matches = search_actions(&state.palette_input);
+    match key.code {
+        KeyCode::Esc => state.input_mode = InputMode::Normal,
+        KeyCode::Enter => {
+            state.input_mode = InputMode::Normal;
+            let action = matches
+                .get(state.palette_cursor)
+                .and_then(|a| parse_key(a.key).ok());
+            if let Some(action) = action {
+                return handle_normal_mode(state, action);
+            }
+        }
+        KeyCode::Up => state.palette_cursor = state.palette_cursor.saturating_sub(1),
+        KeyCode::Down => {
+            state.palette_cursor = (state.palette_cursor + 1).min(matches.len().saturating_sub(1));
+        }
+        KeyCode::Backspace => {
+            state.palette_input.pop();
+            state.palette_cursor = 0;
+        }
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            return KeyAction::Quit;
+        }
+        KeyCode::Char(c)
+            if !key.modifiers.contains(KeyModifiers::CONTROL)
+                && !key.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            state.palette_input.push(c);
+            state.palette_cursor = 0;
+        }
+        _ => {}
     }
+    KeyAction::None
+}
 
-    #[test]
-    fn pgs_view_mode_keys_switch_modes_and_set_defaults() {
-        let mut state = AppState::new(true);
-        state.current_tab = Tab::PgStatements;
-
-        let _ = handle_key(&mut state, key(KeyCode::Char('c')));
-        assert_eq!(state.pgs.view_mode, PgStatementsViewMode::Calls);
-        assert_eq!(state.pgs.sort_column, 0);
-        assert!(!state.pgs.sort_ascending);
-
-        let _ = handle_key(&mut state, key(KeyCode::Char('i')));
-        assert_eq!(state.pgs.view_mode, PgStatementsViewMode::Io);
-        assert_eq!(state.pgs.sort_column, 1);
-
-        let _ = handle_key(&mut state, key(KeyCode::Char('e')));
-        assert_eq!(state.pgs.view_mode, PgStatementsViewMode::Temp);
-        assert_eq!(state.pgs.sort_column, 3);
+/// Handles keys in the bookmark list: Enter jumps, d deletes.
+fn handle_bookmark_menu(state: &mut AppState, key: KeyEvent) -> KeyAction {
+    let PopupState::Bookmarks { cursor } = state.popup else {
+        return KeyAction::None;
+    };
+    let bookmarks = state.bookmarks.as_ref().map_or(&[][..], |s| s.list());
+    let last = bookmarks.len().saturating_sub(1);
+    let selected = bookmarks.get(cursor).map(|b| b.ts);
 
-        let _ = handle_key(&mut state, key(KeyCode::Char('t')));
-        assert_eq!(state.pgs.view_mode, PgStatementsViewMode::Time);
-        assert_eq!(state.pgs.sort_column, 1);
-    }
+    let mut new_cursor = cursor;
+    let action = match key.code {
+        KeyCode::Esc | KeyCode::Char('Y') | KeyCode::Char('q') => {
+            state.popup = PopupState::None;
+            return KeyAction::None;
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            new_cursor = cursor.saturating_sub(1);
+            KeyAction::None
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            new_cursor = (cursor + 1).min(last);
+            KeyAction::None
+        }
+        KeyCode::Home => {
+            new_cursor = 0;
+            KeyAction::None
+        }
+        KeyCode::End => {
+            new_cursor = last;
+            KeyAction::None
+        }
+        KeyCode::Enter => match selected {
+            Some(ts) => {
+                state.popup = PopupState::None;
+                return KeyAction::JumpTo(ts);
+            }
+            None => KeyAction::None,
+        },
+        KeyCode::Char('a') => {
+            state.popup = PopupState::None;
+            state.start_bookmark();
+            return KeyAction::None;
+        }
+        KeyCode::Char('d') | KeyCode::Delete => {
+            if let Some(ts) = selected {
+                state.delete_bookmark(ts);
+            }
+            let len = state.bookmarks.as_ref().map_or(0, |s| s.list().len());
+            new_cursor = cursor.min(len.saturating_sub(1));
+            KeyAction::None
+        }
+        _ => KeyAction::None,
+    };
+    state.popup = PopupState::Bookmarks { cursor: new_cursor };
+    action
+}
 
-    #[test]
+/// Handles keys in filter mode.
+fn handle_filter_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
+    match key.code {
+        KeyCode::Esc => {
+            // Cancel filter
+            state.input_mode = InputMode::Normal;
+            state.filter_input.clear();
+            match state.current_tab {
+                Tab::Processes => state.process_table.set_filter(None),
+                Tab::PostgresActive => state.pga.filter = None,
+                Tab::PgStatements => state.pgs.filter = None,
+                Tab::PgStorePlans => state.pgp.filter = None,
+                Tab::PgTables => state.pgt.filter = None,
+                Tab::PgIndexes => state.pgi.filter = None,
+                Tab::PgErrors => state.pge.filter = None,
+                Tab::PgLocks => state.pgl.filter = None,
+                Tab::PgRoles => state.pgr.filter = None,
+                Tab::Custom => state.cus.filter = None,
+                Tab::PgSettings => state.pgc.filter = None,
+                Tab::Agent => {}
+            }
+            KeyAction::None
+        }
+        KeyCode::Enter => {
+            // Confirm filter and return to normal mode
+            state.input_mode = InputMode::Normal;
+            // Filter is already applied in real-time, just switch mode
+            KeyAction::None
+        }
+        KeyCode::Backspace => {
+            state.filter_input.pop();
+            // Apply filter in real-time
+            apply_current_filter(state);
+            KeyAction::None
+        }
+        KeyCode::Char(c) => {
+            state.filter_input.push(c);
+            // Apply filter in real-time
+            apply_current_filter(state);
+            KeyAction::None
+        }
+        _ => KeyAction::None,
+    }
+}
+
+/// Handles keys in column expression filter mode.
+/// The expression is applied on Enter; an empty expression clears it.
+fn handle_row_filter_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
+    match key.code {
+        KeyCode::Esc => {
+            // Cancel editing, keep the previous expression
+            state.input_mode = InputMode::Normal;
+            state.row_filter_input.clear();
+            state.row_filter_error = None;
+        }
+        KeyCode::Enter => {
+            if state.row_filter_input.trim().is_empty() {
+                state.set_current_row_filter(None);
+                state.input_mode = InputMode::Normal;
+            } else {
+                match RowFilter::parse(&state.row_filter_input) {
+                    Ok(rf) => {
+                        state.set_current_row_filter(Some(rf));
+                        state.input_mode = InputMode::Normal;
+                        state.row_filter_input.clear();
+                    }
+                    Err(e) => state.row_filter_error = Some(e),
+                }
+            }
+        }
+        KeyCode::Backspace => {
+            state.row_filter_input.pop();
+            state.row_filter_error = None;
+        }
+        KeyCode::Char(c) => {
+            state.row_filter_input.push(c);
+            state.row_filter_error = None;
+        }
+        _ => {}
+    }
+    KeyAction::None
+}
+
+/// Applies the current filter_input to the appropriate table.
+fn apply_current_filter(state: &mut AppState) {
+    let filter = if state.filter_input.is_empty() {
+        None
+    } else {
+        Some(state.filter_input.clone())
+    };
+    match state.current_tab {
+        Tab::Processes => state.process_table.set_filter(filter),
+        Tab::PostgresActive => state.pga.filter = filter,
+        Tab::PgStatements => state.pgs.filter = filter,
+        Tab::PgStorePlans => state.pgp.filter = filter,
+        Tab::PgTables => state.pgt.filter = filter,
+        Tab::PgIndexes => state.pgi.filter = filter,
+        Tab::PgErrors => state.pge.filter = filter,
+        Tab::PgLocks => state.pgl.filter = filter,
+        Tab::PgRoles => state.pgr.filter = filter,
+        Tab::Custom => state.cus.filter = filter,
+        Tab::PgSettings => state.pgc.filter = filter,
+        Tab::Agent => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::bookmarks::BookmarkStore;
+    use crate::tui::state::{
+        PgActivityViewMode, PgErrorsViewMode, PgSettingsViewMode, PgStatementsViewMode, ProcessRow,
+    };
+    use crossterm::event::{KeyEvent, KeyEventKind, KeyEventState};
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent {
+            code,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }
+    }
+
+    #[test]
+    fn pgs_tab_switches_with_3() {
+        let mut state = AppState::new(true);
+        assert_eq!(state.current_tab, Tab::Processes);
+
+        let action = handle_key(&mut state, key(KeyCode::Char('3')));
+        assert_eq!(action, KeyAction::None);
+        assert_eq!(state.current_tab, Tab::PgStatements);
+    }
+
+    #[test]
+    fn pga_view_key_cycles_through_transactions_view() {
+        let mut state = AppState::new(true);
+        state.current_tab = Tab::PostgresActive;
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('v')));
+        assert_eq!(state.pga.view_mode, PgActivityViewMode::Stats);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('v')));
+        assert_eq!(state.pga.view_mode, PgActivityViewMode::Transactions);
+        assert_eq!(state.pga.sort_column, 6);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('v')));
+        assert_eq!(state.pga.view_mode, PgActivityViewMode::Generic);
+    }
+
+    #[test]
+    fn pgs_view_mode_keys_switch_modes_and_set_defaults() {
+        let mut state = AppState::new(true);
+        state.current_tab = Tab::PgStatements;
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('c')));
+        assert_eq!(state.pgs.view_mode, PgStatementsViewMode::Calls);
+        assert_eq!(state.pgs.sort_column, 0);
+        assert!(!state.pgs.sort_ascending);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('i')));
+        assert_eq!(state.pgs.view_mode, PgStatementsViewMode::Io);
+        assert_eq!(state.pgs.sort_column, 1);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('e')));
+        assert_eq!(state.pgs.view_mode, PgStatementsViewMode::Temp);
+        assert_eq!(state.pgs.sort_column, 3);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('t')));
+        assert_eq!(state.pgs.view_mode, PgStatementsViewMode::Time);
+        assert_eq!(state.pgs.sort_column, 1);
+    }
+
+    #[test]
+    fn live_buffer_keys_step_back_and_forward() {
+        let mut state = AppState::new(true);
+
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char('b'))),
+            KeyAction::Rewind
+        );
+        assert_eq!(state.input_mode, InputMode::Normal);
+        // At the newest snapshot, forward would collect: not navigation
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char('f'))),
+            KeyAction::None
+        );
+
+        state.live_buffer_position = Some((3, 10));
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char('f'))),
+            KeyAction::Advance
+        );
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Right)),
+            KeyAction::Advance
+        );
+
+        // A selected statement does not take f over while rewound
+        state.current_tab = Tab::PgStatements;
+        state.pgs.tracked_queryid = Some(42);
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char('f'))),
+            KeyAction::Advance
+        );
+        assert!(matches!(state.popup, PopupState::None));
+
+        state.live_buffer_position = None;
+        let _ = handle_key(&mut state, key(KeyCode::Char('f')));
+        assert!(matches!(state.popup, PopupState::QueryView { .. }));
+    }
+
+    #[test]
+    fn entity_history_opens_for_tracked_pid_in_history_mode() {
+        let mut state = AppState::new(false);
+        state.current_tab = Tab::PostgresActive;
+        handle_key(&mut state, key(KeyCode::Char('#')));
+        assert_eq!(state.popup, PopupState::None);
+
+        state.pga.tracked_pid = Some(42);
+        handle_key(&mut state, key(KeyCode::Char('#')));
+        assert_eq!(
+            state.popup,
+            PopupState::EntityHistory {
+                kind: EntityKind::Pid,
+                id: 42,
+                scroll: 0
+            }
+        );
+        assert!(state.entity_history_requested);
+        handle_key(&mut state, key(KeyCode::Char('#')));
+        assert_eq!(state.popup, PopupState::None);
+
+        let mut live = AppState::new(true);
+        live.current_tab = Tab::PgStatements;
+        live.pgs.tracked_queryid = Some(7);
+        handle_key(&mut live, key(KeyCode::Char('#')));
+        assert_eq!(live.popup, PopupState::None);
+        assert!(live.status_message.is_some());
+    }
+
+    #[test]
+    fn buffer_cache_popup_opens_on_pgt_and_pgi() {
+        let mut state = AppState::new(false);
+        state.current_tab = Tab::PostgresActive;
+        handle_key(&mut state, key(KeyCode::Char('%')));
+        assert_eq!(state.popup, PopupState::None);
+
+        state.current_tab = Tab::PgTables;
+        handle_key(&mut state, key(KeyCode::Char('%')));
+        assert_eq!(state.popup, PopupState::BufferCache { scroll: 0 });
+        assert!(state.buffer_cache_requested);
+        handle_key(&mut state, key(KeyCode::Char('%')));
+        assert_eq!(state.popup, PopupState::None);
+
+        // Live mode shows the sample kept from snapshots
+        let mut live = AppState::new(true);
+        live.current_tab = Tab::PgIndexes;
+        handle_key(&mut live, key(KeyCode::Char('%')));
+        assert_eq!(live.popup, PopupState::BufferCache { scroll: 0 });
+        assert!(!live.buffer_cache_requested);
+    }
+
+    #[test]
+    fn plan_diff_needs_a_second_plan() {
+        use crate::storage::model::{DataBlock, PgStorePlansInfo, Snapshot};
+        let plan = |planid| PgStorePlansInfo {
+            stmt_queryid: 7,
+            planid,
+            ..Default::default()
+        };
+        let mut state = AppState::new(true);
+        state.current_tab = Tab::PgStorePlans;
+        state.pgp.tracked_planid = Some(1);
+        state.current_snapshot = Some(Snapshot {
+            timestamp: 0,
+            blocks: vec![DataBlock::PgStorePlans(vec![plan(1)])],
+        });
+        handle_key(&mut state, key(KeyCode::Char('|')));
+        assert_eq!(state.popup, PopupState::None);
+        assert!(state.status_message.is_some());
+
+        state.current_snapshot = Some(Snapshot {
+            timestamp: 0,
+            blocks: vec![DataBlock::PgStorePlans(vec![plan(1), plan(2)])],
+        });
+        handle_key(&mut state, key(KeyCode::Char('|')));
+        assert_eq!(
+            state.popup,
+            PopupState::PlanDiff {
+                queryid: 7,
+                planid: 1,
+                scroll: 0
+            }
+        );
+        handle_key(&mut state, key(KeyCode::Char('|')));
+        assert_eq!(state.popup, PopupState::None);
+    }
+
+    #[test]
     fn filter_mode_applies_to_pgs_filter() {
         let mut state = AppState::new(true);
         state.current_tab = Tab::PgStatements;
@@ -783,6 +1648,238 @@ mod tests {
         assert_eq!(state.pgs.filter, None);
     }
 
+    #[test]
+    fn column_chooser_edits_layout_of_current_view() {
+        let mut state = AppState::new(true);
+        state.current_tab = Tab::PgStatements;
+        let headers: Vec<String> = ["CALLS/s", "TIME/s", "MEAN", "QUERY"]
+            .iter()
+            .map(|h| h.to_string())
+            .collect();
+        // Rendering records the schema
+        assert_eq!(state.visible_columns(&headers, 3), vec![0, 1, 2, 3]);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('o')));
+        assert!(matches!(state.popup, PopupState::ColumnChooser { .. }));
+
+        // Hide TIME/s, move MEAN to the front
+        let _ = handle_key(&mut state, key(KeyCode::Down));
+        let _ = handle_key(&mut state, key(KeyCode::Char(' ')));
+        let _ = handle_key(&mut state, key(KeyCode::Down));
+        let _ = handle_key(&mut state, key(KeyCode::Char('K')));
+        let _ = handle_key(&mut state, key(KeyCode::Char('K')));
+        assert!(matches!(
+            state.popup,
+            PopupState::ColumnChooser { cursor: 0, .. }
+        ));
+        // Keys do not leak to the table while the chooser is open
+        assert_eq!(state.current_tab, Tab::PgStatements);
+
+        let _ = handle_key(&mut state, key(KeyCode::Esc));
+        assert_eq!(state.popup, PopupState::None);
+        assert!(state.layout_save_requested);
+        assert_eq!(state.visible_columns(&headers, 3), vec![2, 0, 3]);
+
+        // Other view modes keep the default layout
+        state.pgs.view_mode = PgStatementsViewMode::Calls;
+        assert_eq!(state.visible_columns(&headers, 3), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn horizontal_scroll_pins_first_column_and_clamps() {
+        let mut state = AppState::new(true);
+        state.current_tab = Tab::PgStatements;
+        let headers: Vec<String> = ["CALLS/s", "TIME/s", "MEAN", "QUERY"]
+            .iter()
+            .map(|h| h.to_string())
+            .collect();
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('l')));
+        assert_eq!(state.visible_columns(&headers, 3), vec![0, 2, 3]);
+
+        // Scrolling past the end keeps one scrollable column visible
+        for _ in 0..5 {
+            let _ = handle_key(&mut state, key(KeyCode::Char('l')));
+        }
+        assert_eq!(state.visible_columns(&headers, 3), vec![0, 2, 3]);
+        assert_eq!(state.horizontal_scroll, 1);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('h')));
+        assert_eq!(state.visible_columns(&headers, 3), vec![0, 1, 2, 3]);
+
+        // Switching tabs resets the offset
+        let _ = handle_key(&mut state, key(KeyCode::Char('l')));
+        let _ = handle_key(&mut state, key(KeyCode::Char('5')));
+        assert_eq!(state.horizontal_scroll, 0);
+    }
+
+    #[test]
+    fn theme_key_cycles_themes() {
+        let mut state = AppState::new(true);
+        assert_eq!(state.theme, "dark");
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('L')));
+        assert_eq!(state.theme, "light");
+        assert_eq!(state.status_message.as_deref(), Some("Theme: light"));
+        let _ = handle_key(&mut state, key(KeyCode::Char('L')));
+        let _ = handle_key(&mut state, key(KeyCode::Char('L')));
+        assert_eq!(state.theme, "dark");
+
+        assert!(state.set_theme("sepia").is_err());
+        assert_eq!(state.theme, "dark");
+    }
+
+    #[test]
+    fn bookmarks_add_list_jump_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = AppState::new(false);
+        state.bookmarks = Some(BookmarkStore::open(dir.path()).unwrap());
+        let at = |state: &mut AppState, timestamp: i64| {
+            state.current_snapshot = Some(crate::storage::Snapshot {
+                timestamp,
+                blocks: Vec::new(),
+            });
+        };
+
+        for (ts, label) in [(100, "deploy"), (300, "spike")] {
+            at(&mut state, ts);
+            let _ = handle_key(&mut state, key(KeyCode::Char('y')));
+            assert_eq!(state.input_mode, InputMode::BookmarkLabel);
+            for c in label.chars() {
+                let _ = handle_key(&mut state, key(KeyCode::Char(c)));
+            }
+            let _ = handle_key(&mut state, key(KeyCode::Enter));
+        }
+        assert_eq!(state.input_mode, InputMode::Normal);
+        assert_eq!(
+            BookmarkStore::open(dir.path()).unwrap().list()[1].label,
+            "spike"
+        );
+
+        // Re-bookmarking prefills the existing label
+        let _ = handle_key(&mut state, key(KeyCode::Char('y')));
+        assert_eq!(state.bookmark_input, "spike");
+        let _ = handle_key(&mut state, key(KeyCode::Esc));
+
+        at(&mut state, 200);
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char('}'))),
+            KeyAction::JumpTo(300)
+        );
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char('{'))),
+            KeyAction::JumpTo(100)
+        );
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('Y')));
+        assert_eq!(state.popup, PopupState::Bookmarks { cursor: 1 });
+        let _ = handle_key(&mut state, key(KeyCode::Char('d')));
+        assert_eq!(state.popup, PopupState::Bookmarks { cursor: 0 });
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Enter)),
+            KeyAction::JumpTo(100)
+        );
+        assert_eq!(state.popup, PopupState::None);
+        assert_eq!(BookmarkStore::open(dir.path()).unwrap().list().len(), 1);
+
+        // Live mode has no bookmarks
+        let mut live = AppState::new(true);
+        let _ = handle_key(&mut live, key(KeyCode::Char('y')));
+        assert_eq!(live.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn timeline_keys_only_in_history_mode() {
+        let mut live = AppState::new(true);
+        assert_eq!(
+            handle_key(&mut live, key(KeyCode::Char(']'))),
+            KeyAction::None
+        );
+
+        let mut state = AppState::new(false);
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char('['))),
+            KeyAction::TimelineStep { forward: false }
+        );
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char(']'))),
+            KeyAction::TimelineStep { forward: true }
+        );
+
+        state.timeline.update_view((0, 3600), 0);
+        let _ = handle_key(&mut state, key(KeyCode::Char('+')));
+        assert_eq!(state.timeline.zoom, 1);
+        let _ = handle_key(&mut state, key(KeyCode::Char('-')));
+        let _ = handle_key(&mut state, key(KeyCode::Char('-')));
+        assert_eq!(state.timeline.zoom, 0);
+    }
+
+    #[test]
+    fn row_detail_popup_toggles_and_captures_fields() {
+        let mut state = AppState::new(true);
+        state.current_tab = Tab::PgStatements;
+
+        // Nothing is captured while the popup is closed
+        state.capture_row_detail(|| vec![("MEAN".to_string(), "1.5".to_string())]);
+        assert!(state.row_detail.is_empty());
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('z')));
+        assert_eq!(state.popup, PopupState::RowDetail { scroll: 0 });
+        state.capture_row_detail(|| vec![("MEAN".to_string(), "1.5".to_string())]);
+        assert_eq!(state.row_detail.len(), 1);
+
+        let _ = handle_key(&mut state, key(KeyCode::Down));
+        assert_eq!(state.popup, PopupState::RowDetail { scroll: 1 });
+
+        // Enter closes the row detail instead of opening the tab's detail popup
+        let _ = handle_key(&mut state, key(KeyCode::Enter));
+        assert_eq!(state.popup, PopupState::None);
+
+        // Tabs without a detail popup open the row detail on Enter
+        state.current_tab = Tab::PgRoles;
+        let _ = handle_key(&mut state, key(KeyCode::Enter));
+        assert_eq!(state.popup, PopupState::RowDetail { scroll: 0 });
+    }
+
+    #[test]
+    fn row_filter_prompt_applies_on_enter_and_persists_per_tab() {
+        let mut state = AppState::new(true);
+        state.current_tab = Tab::PgStatements;
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('\\')));
+        assert_eq!(state.input_mode, InputMode::RowFilter);
+
+        // Malformed expression keeps the prompt open with an error
+        for c in "db=".chars() {
+            let _ = handle_key(&mut state, key(KeyCode::Char(c)));
+        }
+        let _ = handle_key(&mut state, key(KeyCode::Enter));
+        assert_eq!(state.input_mode, InputMode::RowFilter);
+        assert!(state.row_filter_error.is_some());
+        assert!(state.pgs.row_filter.is_none());
+
+        for c in "prod".chars() {
+            let _ = handle_key(&mut state, key(KeyCode::Char(c)));
+        }
+        let _ = handle_key(&mut state, key(KeyCode::Enter));
+        assert_eq!(state.input_mode, InputMode::Normal);
+        assert_eq!(state.pgs.row_filter.as_ref().unwrap().as_str(), "db=prod");
+
+        // Other tabs are unaffected; the expression comes back on return
+        state.switch_tab(Tab::PgTables);
+        assert!(state.current_row_filter().is_none());
+        state.switch_tab(Tab::PgStatements);
+        let _ = handle_key(&mut state, key(KeyCode::Char('\\')));
+        assert_eq!(state.row_filter_input, "db=prod");
+
+        // Empty expression clears
+        for _ in 0..7 {
+            let _ = handle_key(&mut state, key(KeyCode::Backspace));
+        }
+        let _ = handle_key(&mut state, key(KeyCode::Enter));
+        assert!(state.pgs.row_filter.is_none());
+    }
+
     #[test]
     fn quit_requires_confirmation_and_quits_on_qq() {
         let mut state = AppState::new(true);
@@ -820,6 +1917,30 @@ mod tests {
         assert!(matches!(state.popup, PopupState::None));
     }
 
+    #[test]
+    fn ampersand_opens_settings_tab() {
+        let mut state = AppState::new(true);
+        let _ = handle_key(&mut state, key(KeyCode::Char('&')));
+        assert_eq!(state.current_tab, Tab::PgSettings);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('v')));
+        assert_eq!(state.pgc.view_mode, PgSettingsViewMode::NonDefault);
+        assert_eq!(state.current_tab.next(), Tab::Processes);
+    }
+
+    #[test]
+    fn tilde_toggles_hidden_agent_tab() {
+        let mut state = AppState::new(true);
+        state.switch_tab(Tab::PgLocks);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('~')));
+        assert_eq!(state.current_tab, Tab::Agent);
+        assert!(!Tab::all().contains(&Tab::Agent));
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('~')));
+        assert_eq!(state.current_tab, Tab::PgLocks);
+    }
+
     #[test]
     fn tab_switch_blocked_when_popup_open() {
         let mut state = AppState::new(true);
@@ -862,64 +1983,208 @@ mod tests {
         assert!(!state.drill_down_requested);
         assert!(state.status_message.is_some());
     }
-}
 
-/// Handles keys in filter mode.
-fn handle_filter_mode(state: &mut AppState, key: KeyEvent) -> KeyAction {
-    match key.code {
-        KeyCode::Esc => {
-            // Cancel filter
-            state.input_mode = InputMode::Normal;
-            state.filter_input.clear();
-            match state.current_tab {
-                Tab::Processes => state.process_table.set_filter(None),
-                Tab::PostgresActive => state.pga.filter = None,
-                Tab::PgStatements => state.pgs.filter = None,
-                Tab::PgStorePlans => state.pgp.filter = None,
-                Tab::PgTables => state.pgt.filter = None,
-                Tab::PgIndexes => state.pgi.filter = None,
-                Tab::PgErrors => state.pge.filter = None,
-                Tab::PgLocks => state.pgl.filter = None,
-            }
-            KeyAction::None
-        }
-        KeyCode::Enter => {
-            // Confirm filter and return to normal mode
-            state.input_mode = InputMode::Normal;
-            // Filter is already applied in real-time, just switch mode
-            KeyAction::None
-        }
-        KeyCode::Backspace => {
-            state.filter_input.pop();
-            // Apply filter in real-time
-            apply_current_filter(state);
-            KeyAction::None
-        }
-        KeyCode::Char(c) => {
-            state.filter_input.push(c);
-            // Apply filter in real-time
-            apply_current_filter(state);
-            KeyAction::None
+    #[test]
+    fn test_pge_view_toggle_and_expand() {
+        let mut state = AppState::new(true);
+        let _ = handle_key(&mut state, key(KeyCode::Char('7')));
+        assert_eq!(state.current_tab, Tab::PgErrors);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('v')));
+        assert_eq!(state.pge.view_mode, PgErrorsViewMode::Events);
+        let _ = handle_key(&mut state, key(KeyCode::Char('x')));
+        assert!(state.pge.expanded);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('v')));
+        assert_eq!(state.pge.view_mode, PgErrorsViewMode::Errors);
+        let _ = handle_key(&mut state, key(KeyCode::Char('x')));
+        assert!(!state.pge.expanded);
+    }
+
+    #[test]
+    fn process_tree_toggle_arranges_rows() {
+        let mut state = AppState::new(true);
+        state.process_table.items = vec![
+            ProcessRow {
+                pid: 2,
+                ppid: 1,
+                ..Default::default()
+            },
+            ProcessRow {
+                pid: 1,
+                ..Default::default()
+            },
+        ];
+        let _ = handle_key(&mut state, key(KeyCode::Char('v')));
+        assert!(state.process_tree);
+        let tree: Vec<_> = state
+            .process_table
+            .items
+            .iter()
+            .map(|r| (r.pid, r.tree_prefix.clone()))
+            .collect();
+        assert_eq!(
+            tree,
+            vec![(1, Some(String::new())), (2, Some("└─ ".to_string()))]
+        );
+
+        let _ = handle_key(&mut state, key(KeyCode::Char('v')));
+        assert!(!state.process_tree);
+        assert!(
+            state
+                .process_table
+                .items
+                .iter()
+                .all(|r| r.tree_prefix.is_none())
+        );
+    }
+
+    #[test]
+    fn graph_opens_and_closes() {
+        let mut state = AppState::new(false);
+        let _ = handle_key(&mut state, key(KeyCode::Char('g')));
+        assert_eq!(state.popup, PopupState::Graph);
+        assert!(state.graph_requested);
+
+        // Keys inside the graph do not reach the table
+        let _ = handle_key(&mut state, key(KeyCode::Char('v')));
+        assert!(!state.process_tree);
+
+        let _ = handle_key(&mut state, key(KeyCode::Esc));
+        assert_eq!(state.popup, PopupState::None);
+    }
+
+    #[test]
+    fn export_captures_layout_columns_on_next_render() {
+        let mut state = AppState::new(false);
+        state.current_tab = Tab::PgStatements;
+        state.horizontal_scroll = 1;
+        let _ = handle_key(&mut state, key(KeyCode::Char('E')));
+        assert!(state.export_requested);
+        // Lowercase e keeps switching PGS to the Temp view
+        let _ = handle_key(&mut state, key(KeyCode::Char('e')));
+        assert_eq!(state.pgs.view_mode, PgStatementsViewMode::Temp);
+
+        let headers = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        state.capture_export(&headers, 3, || {
+            vec![vec!["1".into(), "2".into(), "3".into()]]
+        });
+        assert!(!state.export_requested);
+        let table = state.export_table.take().unwrap();
+        assert_eq!((table.tab, table.view), ("PGS", "temp"));
+        // Horizontal scroll does not drop columns from the export
+        assert_eq!(table.headers, headers);
+        assert_eq!(table.rows, vec![vec!["1", "2", "3"]]);
+
+        // Without a request nothing is captured
+        state.capture_export(&headers, 3, || unreachable!());
+        assert!(state.export_table.is_none());
+    }
+
+    #[test]
+    fn y_copies_on_query_tabs_and_bookmarks_elsewhere() {
+        let mut state = AppState::new(false);
+        state.current_tab = Tab::PgStatements;
+        let _ = handle_key(&mut state, key(KeyCode::Char('y')));
+        assert!(state.copy_requested);
+        assert_eq!(state.input_mode, InputMode::Normal);
+
+        state.copy_requested = false;
+        state.current_tab = Tab::PgTables;
+        let _ = handle_key(&mut state, key(KeyCode::Char('y')));
+        assert!(!state.copy_requested);
+
+        // Row detail copies on any tab
+        let _ = handle_key(&mut state, key(KeyCode::Char('z')));
+        let _ = handle_key(&mut state, key(KeyCode::Char('y')));
+        assert!(state.copy_requested);
+    }
+
+    #[test]
+    fn palette_runs_selected_action() {
+        let mut state = AppState::new(false);
+        let _ = handle_key(&mut state, key(KeyCode::Char(':')));
+        assert_eq!(state.input_mode, InputMode::Palette);
+        for c in "export".chars() {
+            let _ = handle_key(&mut state, key(KeyCode::Char(c)));
         }
-        _ => KeyAction::None,
+        // Typed characters do not trigger their normal-mode actions
+        assert!(!state.export_requested);
+        let _ = handle_key(&mut state, key(KeyCode::Enter));
+        assert_eq!(state.input_mode, InputMode::Normal);
+        assert!(state.export_requested);
+
+        let _ = handle_key(&mut state, key(KeyCode::Char(':')));
+        let _ = handle_key(&mut state, key(KeyCode::Esc));
+        assert_eq!(state.input_mode, InputMode::Normal);
     }
-}
 
-/// Applies the current filter_input to the appropriate table.
-fn apply_current_filter(state: &mut AppState) {
-    let filter = if state.filter_input.is_empty() {
-        None
-    } else {
-        Some(state.filter_input.clone())
-    };
-    match state.current_tab {
-        Tab::Processes => state.process_table.set_filter(filter),
-        Tab::PostgresActive => state.pga.filter = filter,
-        Tab::PgStatements => state.pgs.filter = filter,
-        Tab::PgStorePlans => state.pgp.filter = filter,
-        Tab::PgTables => state.pgt.filter = filter,
-        Tab::PgIndexes => state.pgi.filter = filter,
-        Tab::PgErrors => state.pge.filter = filter,
-        Tab::PgLocks => state.pgl.filter = filter,
+    #[test]
+    fn user_binding_runs_action() {
+        let mut state = AppState::new(false);
+        state.keymap.bind("graph", "F5").unwrap();
+        let _ = handle_key(&mut state, key(KeyCode::F(5)));
+        assert_eq!(state.popup, PopupState::Graph);
+    }
+
+    #[test]
+    fn history_time_prompt_and_large_steps() {
+        let mut state = AppState::new(false);
+        let _ = handle_key(&mut state, key(KeyCode::Char('b')));
+        assert_eq!(state.input_mode, InputMode::TimeJump);
+        let _ = handle_key(&mut state, key(KeyCode::Esc));
+
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char('.'))),
+            KeyAction::LargeStep { forward: true }
+        );
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char(','))),
+            KeyAction::LargeStep { forward: false }
+        );
+        // t keeps stepping forward, > keeps drilling down
+        assert_eq!(
+            handle_key(&mut state, key(KeyCode::Char('t'))),
+            KeyAction::Advance
+        );
+        let _ = handle_key(&mut state, key(KeyCode::Char('>')));
+        assert!(state.drill_down_requested);
+
+        // The prompt is reachable where t switches the PGS view
+        state.current_tab = Tab::PgStatements;
+        let _ = handle_key(&mut state, key(KeyCode::Char('b')));
+        assert_eq!(state.input_mode, InputMode::TimeJump);
+    }
+
+    #[test]
+    fn star_toggles_watch_mode() {
+        let mut state = AppState::new(false);
+        let _ = handle_key(&mut state, key(KeyCode::Char('*')));
+        assert!(state.watch.enabled);
+        assert!(
+            state
+                .status_message
+                .as_deref()
+                .unwrap()
+                .starts_with("Watch mode on")
+        );
+        let _ = handle_key(&mut state, key(KeyCode::Char('*')));
+        assert!(!state.watch.enabled);
+    }
+
+    #[test]
+    fn caret_pins_selected_row() {
+        let mut state = AppState::new(false);
+        state.current_tab = Tab::PgStatements;
+        let _ = handle_key(&mut state, key(KeyCode::Char('^')));
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Nothing to pin on this tab")
+        );
+        state.pgs.tracked_queryid = Some(42);
+        let _ = handle_key(&mut state, key(KeyCode::Char('^')));
+        assert!(state.pgs.pinned.contains(42));
+        let _ = handle_key(&mut state, key(KeyCode::Char('^')));
+        assert!(state.pgs.pinned.is_empty());
     }
 }
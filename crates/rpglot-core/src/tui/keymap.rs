@@ -0,0 +1,331 @@
+//! Key bindings and the command palette (`:`).
+//!
+//! Every action the palette offers has a default key. User bindings in
+//! `~/.config/rpglot/keys.toml` map action names to additional keys:
+//!
+//! ```toml
+//! graph = "ctrl-g"
+//! export = "X"
+//! time_jump = "F5"
+//! ```
+//!
+//! A bound key is translated into the action's default key before normal
+//! mode handles it, so the default keys keep working.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::state::config_dir;
+
+/// An action reachable from the command palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Action {
+    /// Name used in `keys.toml`.
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Default key, in `keys.toml` syntax.
+    pub key: &'static str,
+}
+
+const fn action(name: &'static str, description: &'static str, key: &'static str) -> Action {
+    Action {
+        name,
+        description,
+        key,
+    }
+}
+
+/// All palette actions, in the order they are listed.
+pub const ACTIONS: &[Action] = &[
+    action(
+        "time_jump",
+        "Jump to timestamp (history) / step back (live)",
+        "b",
+    ),
+    action("step_back", "Previous snapshot", "Left"),
+    action("step_forward", "Next snapshot", "Right"),
+    action(
+        "large_step_back",
+        "Step back by --large-step (history)",
+        ",",
+    ),
+    action(
+        "large_step_forward",
+        "Step forward by --large-step (history)",
+        ".",
+    ),
+    action("pause", "Pause or resume live updates", "Space"),
+    action("sort_next", "Sort by the next column", "s"),
+    action("sort_reverse", "Reverse the sort order", "r"),
+    action("filter", "Filter rows by text", "/"),
+    action("column_filter", "Filter rows by column expression", "\\"),
+    action("columns", "Show, hide and reorder columns", "o"),
+    action("scroll_left", "Scroll columns left", "h"),
+    action("scroll_right", "Scroll columns right", "l"),
+    action(
+        "switch_view",
+        "Switch view (PRC tree, PGA stats, PGE events, PGR)",
+        "v",
+    ),
+    action("row_detail", "Show every column of the selected row", "z"),
+    action("details", "Open details of the selected row", "Enter"),
+    action("query_view", "Full-screen query view (PGS/PGP)", "f"),
+    action(
+        "plan_diff",
+        "Diff the selected plan with another plan (PGP)",
+        "|",
+    ),
+    action(
+        "entity_history",
+        "History of the selected PID or queryid",
+        "#",
+    ),
+    action("buffer_cache", "Contents of shared_buffers (PGT/PGI)", "%"),
+    action("graph", "Graph metrics over time", "g"),
+    action("pin", "Pin the selected row to the top", "^"),
+    action(
+        "watch",
+        "Highlight cells changed since the last refresh",
+        "*",
+    ),
+    action("export", "Export the table to CSV/JSON", "E"),
+    action("copy", "Copy query text or row to the clipboard", "y"),
+    action("bookmarks", "List bookmarks", "Y"),
+    action("bookmark_prev", "Previous bookmark", "{"),
+    action("bookmark_next", "Next bookmark", "}"),
+    action("timeline_zoom_in", "Zoom the timeline in", "+"),
+    action("timeline_zoom_out", "Zoom the timeline out", "-"),
+    action("next_tab", "Next tab", "Tab"),
+    action("prev_tab", "Previous tab", "BackTab"),
+    action("settings_tab", "PostgreSQL settings tab (PGC)", "&"),
+    action("agent_tab", "Toggle rpglotd self-monitoring tab (AGT)", "~"),
+    action("next_instance", "Next PostgreSQL instance", "@"),
+    action("theme", "Next color theme", "L"),
+    action("debug", "Collector timing (live)", "!"),
+    action("help", "Help", "?"),
+    action("quit", "Quit", "q"),
+];
+
+/// Looks up a palette action by name.
+pub fn find_action(name: &str) -> Option<&'static Action> {
+    ACTIONS.iter().find(|a| a.name == name)
+}
+
+/// Parses a key like `"G"`, `"ctrl-g"`, `"alt-x"`, `"Enter"` or `"F5"`.
+pub fn parse_key(spec: &str) -> Result<KeyEvent, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = spec;
+    // A trailing '-' is the minus key itself, not a separator
+    while let Some((prefix, tail)) = rest.split_once('-').filter(|(_, t)| !t.is_empty()) {
+        modifiers |= match prefix.to_ascii_lowercase().as_str() {
+            "ctrl" | "c" => KeyModifiers::CONTROL,
+            "alt" | "a" | "m" => KeyModifiers::ALT,
+            "shift" | "s" => KeyModifiers::SHIFT,
+            _ => break,
+        };
+        rest = tail;
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_ascii_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            f if f.starts_with('f') => match f[1..].parse::<u8>() {
+                Ok(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("unknown key '{spec}'")),
+            },
+            _ => return Err(format!("unknown key '{spec}'")),
+        },
+    };
+    Ok(normalize(KeyEvent::new(code, modifiers)))
+}
+
+/// Drops SHIFT from characters (terminals report `G` with or without it)
+/// and from BackTab.
+fn normalize(key: KeyEvent) -> KeyEvent {
+    let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+    if !matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab) {
+        modifiers |= key.modifiers & KeyModifiers::SHIFT;
+    }
+    KeyEvent::new(key.code, modifiers)
+}
+
+/// User key bindings: bound key -> default key of the action.
+#[derive(Debug, Clone, Default)]
+pub struct KeyMap {
+    bindings: HashMap<(KeyCode, KeyModifiers), KeyEvent>,
+    /// Action name -> user key, as written in the config.
+    labels: HashMap<&'static str, String>,
+}
+
+impl KeyMap {
+    /// `$XDG_CONFIG_HOME/rpglot/keys.toml` (see [`config_dir`]).
+    pub fn default_path() -> Option<PathBuf> {
+        Some(config_dir()?.join("keys.toml"))
+    }
+
+    /// Loads bindings from `path`. A missing file yields no bindings.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let table: toml::Table = toml::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+        let mut keymap = Self::default();
+        for (name, value) in &table {
+            let key = value
+                .as_str()
+                .ok_or_else(|| invalid(format!("key for '{name}' must be a string")))?;
+            keymap.bind(name, key).map_err(invalid)?;
+        }
+        Ok(keymap)
+    }
+
+    /// Binds `key` to the action called `name`.
+    pub fn bind(&mut self, name: &str, key: &str) -> Result<(), String> {
+        let action = find_action(name).ok_or_else(|| format!("unknown action '{name}'"))?;
+        let bound = parse_key(key)?;
+        let default = parse_key(action.key)?;
+        self.bindings.insert((bound.code, bound.modifiers), default);
+        self.labels.insert(action.name, key.to_string());
+        Ok(())
+    }
+
+    /// Translates a bound key into its action's default key; other keys
+    /// pass through unchanged.
+    pub fn translate(&self, key: KeyEvent) -> KeyEvent {
+        let normalized = normalize(key);
+        self.bindings
+            .get(&(normalized.code, normalized.modifiers))
+            .copied()
+            .unwrap_or(key)
+    }
+
+    /// Keys of an action for display: the user binding and the default.
+    pub fn label(&self, action: &Action) -> String {
+        match self.labels.get(action.name) {
+            Some(user) => format!("{user} / {}", action.key),
+            None => action.key.to_string(),
+        }
+    }
+}
+
+/// Palette actions matching `query`, best first. Every query character must
+/// appear in order in the action name or description; fewer skipped
+/// characters rank higher.
+pub fn search_actions(query: &str) -> Vec<&'static Action> {
+    let query = query.trim().to_lowercase();
+    let mut scored: Vec<(usize, usize, &'static Action)> = ACTIONS
+        .iter()
+        .enumerate()
+        .filter_map(|(i, a)| {
+            let by_name = fuzzy_score(&query, a.name);
+            let by_description = fuzzy_score(&query, &a.description.to_lowercase());
+            let score = match (by_name, by_description) {
+                (Some(n), Some(d)) => n.min(d),
+                (score, None) | (None, score) => score?,
+            };
+            Some((score, i, a))
+        })
+        .collect();
+    scored.sort_by_key(|&(score, i, _)| (score, i));
+    scored.into_iter().map(|(_, _, a)| a).collect()
+}
+
+/// Characters of `text` skipped while matching `query` as a subsequence,
+/// or `None` if it does not match.
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let mut skipped = 0;
+    let mut text = text.chars();
+    for q in query.chars() {
+        loop {
+            let c = text.next()?;
+            if c == q || (q == ' ' && c == '_') {
+                break;
+            }
+            skipped += 1;
+        }
+    }
+    Some(skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keys() {
+        let key = |code, mods| KeyEvent::new(code, mods);
+        assert_eq!(
+            parse_key("ctrl-g"),
+            Ok(key(KeyCode::Char('g'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse_key("G"),
+            Ok(key(KeyCode::Char('G'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("-"),
+            Ok(key(KeyCode::Char('-'), KeyModifiers::NONE))
+        );
+        assert_eq!(parse_key("F5"), Ok(key(KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(
+            parse_key("shift-tab"),
+            Ok(key(KeyCode::BackTab, KeyModifiers::NONE))
+        );
+        assert!(parse_key("hyper-x").is_err());
+        assert!(parse_key("F13").is_err());
+        // Every default key parses
+        for action in ACTIONS {
+            assert!(parse_key(action.key).is_ok(), "{}", action.name);
+        }
+    }
+
+    #[test]
+    fn bound_key_translates_to_default() {
+        let mut keymap = KeyMap::default();
+        keymap.bind("export", "ctrl-x").unwrap();
+        keymap.bind("graph", "X").unwrap();
+        assert!(keymap.bind("nope", "x").is_err());
+
+        let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.translate(ctrl_x).code, KeyCode::Char('E'));
+        // Terminals may report uppercase letters with SHIFT
+        let shift_x = KeyEvent::new(KeyCode::Char('X'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.translate(shift_x).code, KeyCode::Char('g'));
+        let other = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+        assert_eq!(keymap.translate(other), other);
+        assert_eq!(keymap.label(find_action("export").unwrap()), "ctrl-x / E");
+    }
+
+    #[test]
+    fn search_ranks_closer_matches_first() {
+        let names = |q: &str| -> Vec<&str> { search_actions(q).iter().map(|a| a.name).collect() };
+        assert_eq!(names("").len(), ACTIONS.len());
+        assert_eq!(names("export")[0], "export");
+        assert_eq!(names("jump")[0], "time_jump");
+        assert_eq!(names("time jump")[0], "time_jump");
+        assert!(names("zzzz").is_empty());
+    }
+}
@@ -4,13 +4,17 @@
 //! system metrics in real-time or from historical data.
 
 mod app;
+mod clipboard;
 mod event;
 mod input;
+pub(crate) mod keymap;
 pub mod navigable;
 mod render;
 pub(crate) mod state;
 pub(crate) mod style;
+pub(crate) mod theme;
 mod widgets;
 
 pub use app::App;
 pub use state::{AppState, PopupState, Tab};
+pub use widgets::plain_summary;
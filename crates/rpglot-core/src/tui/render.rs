@@ -8,11 +8,14 @@ use crate::storage::StringInterner;
 
 use super::state::{AppState, InputMode, PopupState, Tab};
 use super::widgets::{
-    calculate_summary_height, render_debug_popup, render_header, render_help, render_pg_detail,
-    render_pg_errors, render_pg_indexes, render_pg_locks, render_pg_statements,
+    calculate_summary_height, render_agent, render_bookmarks, render_buffercache,
+    render_column_chooser, render_custom, render_debug_popup, render_entity_history, render_graph,
+    render_header, render_help, render_palette, render_pg_detail, render_pg_errors,
+    render_pg_indexes, render_pg_locks, render_pg_roles, render_pg_settings, render_pg_statements,
     render_pg_store_plans, render_pg_tables, render_pge_detail, render_pgi_detail,
-    render_pgl_detail, render_pgp_detail, render_pgs_detail, render_pgt_detail, render_postgres,
-    render_process_detail, render_processes, render_quit_confirm, render_summary, render_time_jump,
+    render_pgl_detail, render_pgp_detail, render_pgs_detail, render_pgt_detail, render_plan_diff,
+    render_postgres, render_process_detail, render_processes, render_query_view,
+    render_quit_confirm, render_row_detail, render_summary, render_time_jump, render_timeline,
 };
 
 /// Main render function.
@@ -27,11 +30,15 @@ pub fn render(
     // Calculate summary height dynamically based on content
     let summary_height = calculate_summary_height(state.current_snapshot.as_ref());
 
-    // Main layout: header, summary, content
+    // Timeline bar and its labels (history mode only)
+    let timeline_height = if state.is_live { 0 } else { 2 };
+
+    // Main layout: header, summary, content, timeline
     let chunks = Layout::vertical([
-        Constraint::Length(1),              // Header
+        Constraint::Length(1),               // Header
         Constraint::Length(summary_height), // Summary (dynamic: MEM, SWP, DSK×N, NET×N | CPL, CPU, cpu×N, Help)
         Constraint::Min(10),                // Content area
+        Constraint::Length(timeline_height), // Timeline
     ])
     .split(area);
 
@@ -44,12 +51,17 @@ pub fn render(
         chunks[1],
         state.current_snapshot.as_ref(),
         state.previous_snapshot.as_ref(),
+        &state.summary_history,
         state.current_tab,
     );
 
     // Content based on tab
     render_content(frame, chunks[2], state, interner);
 
+    if !state.is_live {
+        render_timeline(frame, chunks[3], state);
+    }
+
     // Popups (rendered last to overlay everything).
     // Determine which popup to render first, then call render functions
     // (avoids borrow conflicts between &mut state.popup and &mut state).
@@ -65,6 +77,14 @@ pub fn render(
         PgiDetail,
         PgeDetail,
         PglDetail,
+        QueryView,
+        PlanDiff,
+        EntityHistory,
+        BufferCache,
+        Graph,
+        ColumnChooser,
+        RowDetail,
+        Bookmarks,
         Debug,
         QuitConfirm,
     }
@@ -93,6 +113,28 @@ pub fn render(
             ActivePopup::PgeDetail
         }
         PopupState::PglDetail { .. } if state.current_tab == Tab::PgLocks => ActivePopup::PglDetail,
+        PopupState::QueryView { .. }
+            if matches!(state.current_tab, Tab::PgStatements | Tab::PgStorePlans) =>
+        {
+            ActivePopup::QueryView
+        }
+        PopupState::PlanDiff { .. } if state.current_tab == Tab::PgStorePlans => {
+            ActivePopup::PlanDiff
+        }
+        PopupState::EntityHistory { .. }
+            if matches!(state.current_tab, Tab::PostgresActive | Tab::PgStatements) =>
+        {
+            ActivePopup::EntityHistory
+        }
+        PopupState::BufferCache { .. }
+            if matches!(state.current_tab, Tab::PgTables | Tab::PgIndexes) =>
+        {
+            ActivePopup::BufferCache
+        }
+        PopupState::Graph => ActivePopup::Graph,
+        PopupState::ColumnChooser { .. } => ActivePopup::ColumnChooser,
+        PopupState::RowDetail { .. } => ActivePopup::RowDetail,
+        PopupState::Bookmarks { .. } => ActivePopup::Bookmarks,
         PopupState::Debug if state.is_live => ActivePopup::Debug,
         PopupState::QuitConfirm => ActivePopup::QuitConfirm,
         _ => ActivePopup::None,
@@ -121,6 +163,14 @@ pub fn render(
         ActivePopup::PgiDetail => render_pgi_detail(frame, area, state, interner),
         ActivePopup::PgeDetail => render_pge_detail(frame, area, state, interner),
         ActivePopup::PglDetail => render_pgl_detail(frame, area, state, interner),
+        ActivePopup::QueryView => render_query_view(frame, area, state, interner),
+        ActivePopup::PlanDiff => render_plan_diff(frame, area, state, interner),
+        ActivePopup::EntityHistory => render_entity_history(frame, area, state),
+        ActivePopup::BufferCache => render_buffercache(frame, area, state),
+        ActivePopup::Graph => render_graph(frame, area, state),
+        ActivePopup::ColumnChooser => render_column_chooser(frame, area, state),
+        ActivePopup::RowDetail => render_row_detail(frame, area, state),
+        ActivePopup::Bookmarks => render_bookmarks(frame, area, state),
         ActivePopup::Debug => render_debug_popup(frame, area, state, timing),
         ActivePopup::QuitConfirm => render_quit_confirm(frame, area),
         ActivePopup::None => {}
@@ -135,6 +185,11 @@ pub fn render(
             state.time_jump_error.as_deref(),
         );
     }
+
+    // Command palette (`:`) - an InputMode like the time jump prompt
+    if state.input_mode == InputMode::Palette && !matches!(state.popup, PopupState::QuitConfirm) {
+        render_palette(frame, area, state);
+    }
 }
 
 /// Renders content based on current tab.
@@ -153,5 +208,9 @@ fn render_content(
         Tab::PgIndexes => render_pg_indexes(frame, area, state, interner),
         Tab::PgErrors => render_pg_errors(frame, area, state, interner),
         Tab::PgLocks => render_pg_locks(frame, area, state, interner),
+        Tab::PgRoles => render_pg_roles(frame, area, state, interner),
+        Tab::Custom => render_custom(frame, area, state),
+        Tab::PgSettings => render_pg_settings(frame, area, state),
+        Tab::Agent => render_agent(frame, area, state),
     }
 }
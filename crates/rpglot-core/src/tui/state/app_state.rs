@@ -2,28 +2,56 @@
 
 use ratatui::widgets::TableState as RatatuiTableState;
 use std::collections::HashMap;
+use std::hash::Hash;
+use std::path::PathBuf;
 
+use crate::api::buffercache::BufferCacheReport;
+use crate::entity::EntityHistory;
 use crate::storage::Snapshot;
+use crate::storage::bookmarks::BookmarkStore;
+use crate::table::PinnedRows;
+use crate::tui::keymap::KeyMap;
+use crate::tui::style::set_palette;
+use crate::tui::theme::{DEFAULT_THEME, ThemeSet};
+use crate::view::common::TableViewModel;
 
 use super::{
-    CachedWidths, InputMode, PgActivityTabState, PgErrorsTabState, PgIndexesTabState,
-    PgLocksTabState, PgStatementsTabState, PgStorePlansTabState, PgTablesTabState, PopupState,
-    ProcessRow, ProcessViewMode, Tab, TableState,
+    CachedWidths, ColumnSchema, CustomTabState, DEFAULT_EXPORT_TEMPLATE, ExportTable, GraphState,
+    InputMode, LayoutConfig, PgActivityTabState, PgActivityViewMode, PgErrorsTabState,
+    PgErrorsViewMode, PgIndexesTabState, PgIndexesViewMode, PgLocksTabState, PgRolesTabState,
+    PgRolesViewMode, PgSettingsTabState, PgSettingsViewMode, PgStatementsTabState,
+    PgStatementsViewMode, PgStorePlansTabState, PgStorePlansViewMode, PgTablesTabState,
+    PgTablesViewMode, PopupState, ProcessRow, ProcessViewMode, QueryHistory, RowFilter,
+    SummaryHistory, Tab, TableRow, TableState, TimelineState, WatchState, arrange_process_tree,
+    clear_process_tree,
 };
 
+/// Default step of `<`/`>` in history mode (15 minutes).
+pub const DEFAULT_LARGE_STEP_SECS: i64 = 900;
+
 /// Main application state.
 #[derive(Debug)]
 pub struct AppState {
     /// Current active tab.
     pub current_tab: Tab,
+    /// Tab to return to when the hidden AGT tab is closed with `~`.
+    pub agent_return_tab: Tab,
     /// Input mode.
     pub input_mode: InputMode,
     /// Filter input buffer.
     pub filter_input: String,
+    /// Column expression filter input buffer (`\`).
+    pub row_filter_input: String,
+    /// Last expression parse error to display in the header.
+    pub row_filter_error: Option<String>,
     /// Time jump input buffer (history mode, `b`).
     pub time_jump_input: String,
     /// Last time jump parse/seek error to display in popup.
     pub time_jump_error: Option<String>,
+    /// Bookmarks of the history data directory (history mode only).
+    pub bookmarks: Option<BookmarkStore>,
+    /// Bookmark label input buffer (`y`).
+    pub bookmark_input: String,
     /// Process table state.
     pub process_table: TableState<ProcessRow>,
     /// Current snapshot.
@@ -34,10 +62,15 @@ pub struct AppState {
     pub paused: bool,
     /// History position info (current/total).
     pub history_position: Option<(usize, usize)>,
+    /// Position in the live snapshot buffer (index/buffered) while browsing
+    /// back, `None` at the newest snapshot.
+    pub live_buffer_position: Option<(usize, usize)>,
     /// Is live mode.
     pub is_live: bool,
     /// Process view mode (g/c/m keys).
     pub process_view_mode: ProcessViewMode,
+    /// Processes tab arranged as a parent/child tree with subtree totals (v key).
+    pub process_tree: bool,
     /// Previous memory values for VGROW/RGROW calculation: pid -> (vsize, rsize).
     pub prev_process_mem: HashMap<u32, (u64, u64)>,
     /// Previous CPU values for CPU% calculation: pid -> (utime, stime).
@@ -46,7 +79,8 @@ pub struct AppState {
     pub prev_process_dsk: HashMap<u32, (u64, u64, u64)>,
     /// Previous total system CPU time for CPU% normalization.
     pub prev_total_cpu_time: Option<u64>,
-    /// Horizontal scroll offset for wide tables.
+    /// Horizontal scroll offset for wide tables (columns skipped after the
+    /// pinned key column). Clamped while rendering.
     pub horizontal_scroll: usize,
     /// Cached column widths (calculated on first snapshot).
     pub cached_widths: Option<CachedWidths>,
@@ -59,6 +93,67 @@ pub struct AppState {
     /// Flag set when user requests drill-down navigation (>/J keys).
     /// Cleared after processing by app.rs.
     pub drill_down_requested: bool,
+    /// Flag set when the full-screen query view is opened (f key).
+    /// app.rs loads the metric history for it and clears the flag.
+    pub query_view_requested: bool,
+    /// Metric history of the query shown in the query view.
+    pub query_history: Option<QueryHistory>,
+    /// Flag set when the entity history popup is opened (# key).
+    /// app.rs extracts the trajectory and clears the flag.
+    pub entity_history_requested: bool,
+    /// Trajectory shown in the entity history popup.
+    pub entity_history: Option<EntityHistory>,
+    /// Flag set when the buffer cache popup needs a sample for the current
+    /// position (% key, history mode). app.rs loads it and clears the flag.
+    pub buffer_cache_requested: bool,
+    /// Latest pg_buffercache sample: kept from live snapshots, loaded on
+    /// request in history mode.
+    pub buffer_cache: Option<BufferCacheReport>,
+    /// Set when the graph view opens; the app loads its series.
+    pub graph_requested: bool,
+    /// Graph view: metrics, series, zoom and cursor.
+    pub graph: GraphState,
+    /// Saved column layouts for all tabs and view modes.
+    pub layouts: LayoutConfig,
+    /// Where `layouts` is persisted (`None` disables saving).
+    pub layout_path: Option<PathBuf>,
+    /// Columns of the table rendered last, for the column chooser.
+    pub column_schema: Option<ColumnSchema>,
+    /// Flag set when the column chooser closes with changes.
+    /// app.rs writes `layouts` to `layout_path` and clears the flag.
+    pub layout_save_requested: bool,
+    /// All fields of the selected row, captured while rendering the table
+    /// when the row detail popup is open.
+    pub row_detail: Vec<(String, String)>,
+    /// Set by `E`; the next render captures the current table.
+    pub export_requested: bool,
+    /// Table captured for export; app.rs writes it after the draw.
+    pub export_table: Option<ExportTable>,
+    /// Export path template with `{tab}`, `{view}` and `{time}`.
+    pub export_template: String,
+    /// Set by `y` on PGA/PGS/PGP or in the row detail popup; app.rs copies
+    /// the query text (or the row) to the clipboard and clears the flag.
+    pub copy_requested: bool,
+    /// Also copy through a native clipboard tool, not only OSC 52.
+    pub native_clipboard: bool,
+    /// Step of `<`/`>` in history mode, seconds.
+    pub large_step_secs: i64,
+    /// Watch mode (`*`): cells changed since the last refresh.
+    pub watch: WatchState,
+    /// User key bindings from keys.toml.
+    pub keymap: KeyMap,
+    /// Command palette search text.
+    pub palette_input: String,
+    /// Selected entry among the palette matches.
+    pub palette_cursor: usize,
+    /// Recent summary metrics for the summary sparklines.
+    pub summary_history: SummaryHistory,
+    /// Timeline bar at the bottom of history mode.
+    pub timeline: TimelineState,
+    /// Built-in and user color themes.
+    pub themes: ThemeSet,
+    /// Name of the active theme (`L` cycles).
+    pub theme: String,
     /// pg_stat_statements (PGS) tab state.
     pub pgs: PgStatementsTabState,
     /// pg_store_plans (PGP) tab state.
@@ -71,8 +166,15 @@ pub struct AppState {
     pub pge: PgErrorsTabState,
     /// pg_locks tree (PGL) tab state.
     pub pgl: PgLocksTabState,
+    pub pgr: PgRolesTabState,
+    /// User-defined queries (CUS) tab state.
+    pub cus: CustomTabState,
+    /// pg_settings (PGC) tab state.
+    pub pgc: PgSettingsTabState,
     /// Temporary status message shown in the header (e.g., why an action was blocked).
     pub status_message: Option<String>,
+    /// Selected PostgreSQL instance (`@` cycles); `None` = primary.
+    pub instance: Option<String>,
     /// Ratatui table state for PRC tab (enables auto-scrolling).
     pub prc_ratatui_state: RatatuiTableState,
     /// Whether a popup was open on the previous frame.
@@ -90,17 +192,24 @@ impl AppState {
     pub fn new(is_live: bool) -> Self {
         Self {
             current_tab: Tab::Processes,
+            agent_return_tab: Tab::Processes,
             input_mode: InputMode::Normal,
             filter_input: String::new(),
+            row_filter_input: String::new(),
+            row_filter_error: None,
             time_jump_input: String::new(),
             time_jump_error: None,
+            bookmarks: None,
+            bookmark_input: String::new(),
             process_table: TableState::new(),
             current_snapshot: None,
             previous_snapshot: None,
             paused: false,
             history_position: None,
+            live_buffer_position: None,
             is_live,
             process_view_mode: ProcessViewMode::Generic,
+            process_tree: false,
             prev_process_mem: HashMap::new(),
             prev_process_cpu: HashMap::new(),
             prev_process_dsk: HashMap::new(),
@@ -111,13 +220,44 @@ impl AppState {
             popup: PopupState::None,
             pga: PgActivityTabState::default(),
             drill_down_requested: false,
+            query_view_requested: false,
+            entity_history_requested: false,
+            entity_history: None,
+            buffer_cache_requested: false,
+            buffer_cache: None,
+            graph_requested: false,
+            graph: GraphState::default(),
+            query_history: None,
+            layouts: LayoutConfig::default(),
+            layout_path: None,
+            column_schema: None,
+            layout_save_requested: false,
+            row_detail: Vec::new(),
+            export_requested: false,
+            export_table: None,
+            export_template: DEFAULT_EXPORT_TEMPLATE.to_string(),
+            copy_requested: false,
+            native_clipboard: false,
+            large_step_secs: DEFAULT_LARGE_STEP_SECS,
+            watch: WatchState::default(),
+            keymap: KeyMap::default(),
+            palette_input: String::new(),
+            palette_cursor: 0,
+            summary_history: SummaryHistory::default(),
+            timeline: TimelineState::default(),
+            themes: ThemeSet::default(),
+            theme: DEFAULT_THEME.to_string(),
             pgs: PgStatementsTabState::default(),
             pgp: PgStorePlansTabState::default(),
             pgt: PgTablesTabState::default(),
             pgi: PgIndexesTabState::default(),
             pge: PgErrorsTabState::default(),
             pgl: PgLocksTabState::default(),
+            pgr: PgRolesTabState::default(),
+            cus: CustomTabState::default(),
+            pgc: PgSettingsTabState::default(),
             status_message: None,
+            instance: None,
             prc_ratatui_state: RatatuiTableState::default(),
             popup_was_open: false,
         }
@@ -139,7 +279,373 @@ impl AppState {
             Tab::PgIndexes => self.pgi.filter.clone(),
             Tab::PgErrors => self.pge.filter.clone(),
             Tab::PgLocks => self.pgl.filter.clone(),
+            Tab::PgRoles => self.pgr.filter.clone(),
+            Tab::Custom => self.cus.filter.clone(),
+            Tab::PgSettings => self.pgc.filter.clone(),
+            Tab::Agent => None,
+        }
+    }
+
+    /// Returns the column expression filter for the current tab.
+    pub fn current_row_filter(&self) -> Option<&RowFilter> {
+        match self.current_tab {
+            Tab::Processes => self.process_table.row_filter.as_ref(),
+            Tab::PostgresActive => self.pga.row_filter.as_ref(),
+            Tab::PgStatements => self.pgs.row_filter.as_ref(),
+            Tab::PgStorePlans => self.pgp.row_filter.as_ref(),
+            Tab::PgTables => self.pgt.row_filter.as_ref(),
+            Tab::PgIndexes => self.pgi.row_filter.as_ref(),
+            Tab::PgErrors => self.pge.row_filter.as_ref(),
+            Tab::PgLocks => self.pgl.row_filter.as_ref(),
+            Tab::PgRoles => self.pgr.row_filter.as_ref(),
+            Tab::Custom => self.cus.row_filter.as_ref(),
+            Tab::PgSettings => self.pgc.row_filter.as_ref(),
+            Tab::Agent => None,
+        }
+    }
+
+    /// Sets the column expression filter for the current tab.
+    pub fn set_current_row_filter(&mut self, row_filter: Option<RowFilter>) {
+        match self.current_tab {
+            Tab::Processes => self.process_table.set_row_filter(row_filter),
+            Tab::PostgresActive => self.pga.row_filter = row_filter,
+            Tab::PgStatements => self.pgs.row_filter = row_filter,
+            Tab::PgStorePlans => self.pgp.row_filter = row_filter,
+            Tab::PgTables => self.pgt.row_filter = row_filter,
+            Tab::PgIndexes => self.pgi.row_filter = row_filter,
+            Tab::PgErrors => self.pge.row_filter = row_filter,
+            Tab::PgLocks => self.pgl.row_filter = row_filter,
+            Tab::PgRoles => self.pgr.row_filter = row_filter,
+            Tab::Custom => self.cus.row_filter = row_filter,
+            Tab::PgSettings => self.pgc.row_filter = row_filter,
+            Tab::Agent => {}
+        }
+    }
+
+    /// Name of the current tab's view mode, used as the layout key.
+    pub fn current_view_mode_name(&self) -> &'static str {
+        match self.current_tab {
+            Tab::Processes => match self.process_view_mode {
+                ProcessViewMode::Generic => "generic",
+                ProcessViewMode::Command => "command",
+                ProcessViewMode::Memory => "memory",
+                ProcessViewMode::Disk => "disk",
+            },
+            Tab::PostgresActive => match self.pga.view_mode {
+                PgActivityViewMode::Generic => "generic",
+                PgActivityViewMode::Stats => "stats",
+                PgActivityViewMode::Transactions => "transactions",
+            },
+            Tab::PgStatements => match self.pgs.view_mode {
+                PgStatementsViewMode::Time => "time",
+                PgStatementsViewMode::Calls => "calls",
+                PgStatementsViewMode::Io => "io",
+                PgStatementsViewMode::Temp => "temp",
+            },
+            Tab::PgStorePlans => match self.pgp.view_mode {
+                PgStorePlansViewMode::Time => "time",
+                PgStorePlansViewMode::Io => "io",
+                PgStorePlansViewMode::Regression => "regression",
+            },
+            Tab::PgTables => match self.pgt.view_mode {
+                PgTablesViewMode::Reads => "reads",
+                PgTablesViewMode::Writes => "writes",
+                PgTablesViewMode::Scans => "scans",
+                PgTablesViewMode::Maintenance => "maintenance",
+                PgTablesViewMode::Io => "io",
+            },
+            Tab::PgIndexes => match self.pgi.view_mode {
+                PgIndexesViewMode::Usage => "usage",
+                PgIndexesViewMode::Unused => "unused",
+                PgIndexesViewMode::Io => "io",
+            },
+            Tab::PgErrors => match self.pge.view_mode {
+                PgErrorsViewMode::Errors => "errors",
+                PgErrorsViewMode::Events => "events",
+            },
+            Tab::PgLocks => "tree",
+            Tab::PgRoles => match self.pgr.view_mode {
+                PgRolesViewMode::Roles => "roles",
+                PgRolesViewMode::Applications => "applications",
+                PgRolesViewMode::Clients => "clients",
+            },
+            Tab::Custom => "custom",
+            Tab::PgSettings => match self.pgc.view_mode {
+                PgSettingsViewMode::All => "all",
+                PgSettingsViewMode::NonDefault => "non_default",
+            },
+            Tab::Agent => "agent",
+        }
+    }
+
+    /// Records the columns of the table being rendered and returns the
+    /// visible column indices in display order per the saved layout.
+    /// `movable` is the number of leading columns that can be reordered.
+    pub fn visible_columns(&mut self, headers: &[String], movable: usize) -> Vec<usize> {
+        let mode = self.current_view_mode_name();
+        let cols = self.layout_columns(headers, movable);
+        self.column_schema = Some(ColumnSchema {
+            tab: self.current_tab,
+            mode,
+            headers: headers.to_vec(),
+            movable,
+        });
+        self.apply_horizontal_scroll(cols, movable)
+    }
+
+    /// Visible column indices in display order per the saved layout,
+    /// without horizontal scroll.
+    fn layout_columns(&self, headers: &[String], movable: usize) -> Vec<usize> {
+        let names: Vec<&str> = headers.iter().map(String::as_str).collect();
+        match self
+            .layouts
+            .get(self.current_tab, self.current_view_mode_name())
+        {
+            Some(layout) => layout.visible_columns(&names, movable),
+            None => (0..headers.len()).collect(),
+        }
+    }
+
+    /// Drops `horizontal_scroll` fixed-width columns after the first one,
+    /// which stays pinned as the row key. Fill columns are never scrolled
+    /// away, and at least one scrollable column remains visible.
+    fn apply_horizontal_scroll(&mut self, cols: Vec<usize>, movable: usize) -> Vec<usize> {
+        let scrollable = cols.iter().skip(1).filter(|&&c| c < movable).count();
+        self.horizontal_scroll = self.horizontal_scroll.min(scrollable.saturating_sub(1));
+        let mut skip = self.horizontal_scroll;
+        cols.into_iter()
+            .enumerate()
+            .filter(|&(pos, c)| {
+                if pos == 0 || c >= movable || skip == 0 {
+                    return true;
+                }
+                skip -= 1;
+                false
+            })
+            .map(|(_, c)| c)
+            .collect()
+    }
+
+    /// Stores the selected row's fields for the row detail popup.
+    /// `fields` is only evaluated while the popup is open.
+    pub fn capture_row_detail(&mut self, fields: impl FnOnce() -> Vec<(String, String)>) {
+        if matches!(self.popup, PopupState::RowDetail { .. }) {
+            self.row_detail = fields();
+        }
+    }
+
+    /// Stores the table being rendered for export when `E` was pressed.
+    /// `rows` yields every column of the displayed rows and is only
+    /// evaluated on request; columns follow the saved layout.
+    pub fn capture_export(
+        &mut self,
+        headers: &[String],
+        movable: usize,
+        rows: impl FnOnce() -> Vec<Vec<String>>,
+    ) {
+        if !self.export_requested {
+            return;
         }
+        self.export_requested = false;
+        let cols = self.layout_columns(headers, movable);
+        let rows = rows()
+            .into_iter()
+            .map(|row| {
+                cols.iter()
+                    .map(|&c| row.get(c).cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        self.export_table = Some(ExportTable {
+            tab: self.current_tab.name(),
+            view: self.current_view_mode_name(),
+            headers: cols.iter().map(|&c| headers[c].clone()).collect(),
+            rows,
+        });
+    }
+
+    /// Pins or unpins the selected row of the current tab by its entity key.
+    /// Returns whether the row is pinned now, or `None` if the tab has no
+    /// selected row or does not support pinning.
+    pub fn toggle_pin(&mut self) -> Option<bool> {
+        match self.current_tab {
+            Tab::Processes => {
+                let pinned = self.process_table.toggle_pin_selected()?;
+                self.apply_process_sort();
+                Some(pinned)
+            }
+            Tab::PostgresActive => self.pga.tracked_pid.map(|id| self.pga.pinned.toggle(id)),
+            Tab::PgStatements => self
+                .pgs
+                .tracked_queryid
+                .map(|id| self.pgs.pinned.toggle(id)),
+            Tab::PgStorePlans => self.pgp.tracked_planid.map(|id| self.pgp.pinned.toggle(id)),
+            Tab::PgTables => self.pgt.tracked_relid.map(|id| self.pgt.pinned.toggle(id)),
+            Tab::PgIndexes => self
+                .pgi
+                .tracked_indexrelid
+                .map(|id| self.pgi.pinned.toggle(id)),
+            Tab::PgErrors => self
+                .pge
+                .tracked_pattern_hash
+                .map(|id| self.pge.pinned.toggle(id)),
+            Tab::PgRoles => self
+                .pgr
+                .tracked_name_hash
+                .map(|id| self.pgr.pinned.toggle(id)),
+            Tab::Custom => self.cus.tracked_row.map(|id| self.cus.pinned.toggle(id)),
+            Tab::PgSettings => self
+                .pgc
+                .tracked_name_hash
+                .map(|id| self.pgc.pinned.toggle(id)),
+            // The lock tree keeps blockers above the sessions they block
+            Tab::PgLocks | Tab::Agent => None,
+        }
+    }
+
+    /// Pinned rows of the current tab; `None` if it pins no table rows.
+    fn current_pinned(&self) -> Option<&PinnedRows> {
+        match self.current_tab {
+            Tab::PostgresActive => Some(&self.pga.pinned),
+            Tab::PgStatements => Some(&self.pgs.pinned),
+            Tab::PgStorePlans => Some(&self.pgp.pinned),
+            Tab::PgTables => Some(&self.pgt.pinned),
+            Tab::PgIndexes => Some(&self.pgi.pinned),
+            Tab::PgErrors => Some(&self.pge.pinned),
+            Tab::PgRoles => Some(&self.pgr.pinned),
+            Tab::Custom => Some(&self.cus.pinned),
+            Tab::PgSettings => Some(&self.pgc.pinned),
+            Tab::Processes | Tab::PgLocks | Tab::Agent => None,
+        }
+    }
+
+    /// Highlights cells of `vm` changed since the last refresh when watch
+    /// mode is on. Call before columns are selected.
+    fn apply_watch<Id: Hash>(&mut self, vm: &mut TableViewModel<Id>) {
+        let Some(timestamp) = self.current_snapshot.as_ref().map(|s| s.timestamp) else {
+            return;
+        };
+        let (tab, view) = (self.current_tab, self.current_view_mode_name());
+        self.watch.apply(tab, view, timestamp, vm);
+    }
+
+    /// Prepares the table view model of the current tab for rendering, once
+    /// its pinned rows are moved to the top and its selection is resolved to
+    /// `selected`: captures every column for the row detail popup and export,
+    /// highlights watched changes, then applies the saved column layout
+    /// (hidden/reordered columns), pin markers and horizontal scroll.
+    pub fn prepare_table_view<Id: Copy + Hash + Into<i128>>(
+        &mut self,
+        vm: &mut TableViewModel<Id>,
+        selected: usize,
+    ) {
+        self.capture_row_detail(|| vm.row_fields(selected));
+        self.capture_export(&vm.headers, vm.widths.len(), || vm.text_rows());
+        self.apply_watch(vm);
+        let cols = self.visible_columns(&vm.headers, vm.widths.len());
+        vm.select_columns(&cols);
+        if let Some(pinned) = self.current_pinned() {
+            vm.mark_pinned(pinned);
+        }
+        if self.horizontal_scroll > 0 {
+            vm.title = format!("{}← scroll: {} ", vm.title, self.horizontal_scroll);
+        }
+    }
+
+    /// Whether a step forward is navigation: always in history mode, in live
+    /// mode only while browsing the buffer (otherwise it would collect).
+    pub fn can_step_forward(&self) -> bool {
+        !self.is_live || self.live_buffer_position.is_some()
+    }
+
+    /// Activates the theme called `name`.
+    pub fn set_theme(&mut self, name: &str) -> Result<(), String> {
+        let theme = self.themes.get(name).ok_or_else(|| {
+            format!(
+                "unknown theme '{}' (available: {})",
+                name,
+                self.themes.names().join(", ")
+            )
+        })?;
+        set_palette(theme.palette);
+        self.theme = theme.name.clone();
+        Ok(())
+    }
+
+    /// Switches to the next theme and reports it in the header.
+    pub fn cycle_theme(&mut self) {
+        let next = self.themes.next_after(&self.theme);
+        set_palette(next.palette);
+        self.theme = next.name.clone();
+        self.status_message = Some(format!("Theme: {}", self.theme));
+    }
+
+    /// Starts the label prompt for a bookmark at the current snapshot,
+    /// prefilled with the existing label when it is already bookmarked.
+    pub fn start_bookmark(&mut self) {
+        let (Some(store), Some(snapshot)) = (&self.bookmarks, &self.current_snapshot) else {
+            return;
+        };
+        self.bookmark_input = store
+            .list()
+            .iter()
+            .find(|b| b.ts == snapshot.timestamp)
+            .map(|b| b.label.clone())
+            .unwrap_or_default();
+        self.input_mode = InputMode::BookmarkLabel;
+    }
+
+    /// Saves the bookmark prompt for the current snapshot.
+    pub fn save_bookmark(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let label = std::mem::take(&mut self.bookmark_input);
+        let (Some(store), Some(snapshot)) = (&mut self.bookmarks, &self.current_snapshot) else {
+            return;
+        };
+        let now = chrono::Utc::now().timestamp();
+        self.status_message = Some(match store.add(snapshot.timestamp, &label, now) {
+            Ok(()) => format!("Bookmarked ({} total)", store.list().len()),
+            Err(e) => format!("Failed to save bookmark: {}", e),
+        });
+    }
+
+    /// Opens the bookmark list with fresh data from disk.
+    pub fn open_bookmarks(&mut self) {
+        let Some(store) = &mut self.bookmarks else {
+            return;
+        };
+        if let Err(e) = store.reload() {
+            self.status_message = Some(format!("Failed to read bookmarks: {}", e));
+        }
+        // Start at the bookmark closest to the current snapshot
+        let ts = self.current_snapshot.as_ref().map_or(0, |s| s.timestamp);
+        let cursor = store
+            .list()
+            .partition_point(|b| b.ts < ts)
+            .min(store.list().len().saturating_sub(1));
+        self.popup = PopupState::Bookmarks { cursor };
+    }
+
+    /// Deletes the bookmark at `ts`.
+    pub fn delete_bookmark(&mut self, ts: i64) {
+        let Some(store) = &mut self.bookmarks else {
+            return;
+        };
+        if let Err(e) = store.remove(ts) {
+            self.status_message = Some(format!("Failed to delete bookmark: {}", e));
+        }
+    }
+
+    /// Timestamp of the bookmark before or after the current snapshot.
+    pub fn adjacent_bookmark(&self, forward: bool) -> Option<i64> {
+        let store = self.bookmarks.as_ref()?;
+        let ts = self.current_snapshot.as_ref()?.timestamp;
+        let bookmark = if forward {
+            store.next_after(ts)
+        } else {
+            store.prev_before(ts)
+        };
+        bookmark.map(|b| b.ts)
     }
 
     /// Switches to a new tab, clearing tracked entities on the old tab
@@ -169,9 +675,19 @@ impl AppState {
                 Tab::PgLocks => {
                     self.pgl.tracked_pid = None;
                 }
-                Tab::Processes => {}
+                Tab::PgRoles => {
+                    self.pgr.tracked_name_hash = None;
+                }
+                Tab::Custom => {
+                    self.cus.tracked_row = None;
+                }
+                Tab::PgSettings => {
+                    self.pgc.tracked_name_hash = None;
+                }
+                Tab::Processes | Tab::Agent => {}
             }
             self.current_tab = new_tab;
+            self.horizontal_scroll = 0;
             // Sync filter_input from the new tab's filter
             self.filter_input = self.get_current_filter().unwrap_or_default();
         }
@@ -192,17 +708,36 @@ impl AppState {
 
     /// Applies sort to process table using the current view mode.
     pub fn apply_process_sort(&mut self) {
+        let mut items = std::mem::take(&mut self.process_table.items);
+        self.sort_processes(&mut items);
+        self.process_table.items = items;
+    }
+
+    /// Orders process rows by the current sort column with pinned processes
+    /// first, or as a tree when [`Self::process_tree`] is set.
+    pub fn sort_processes(&self, rows: &mut Vec<ProcessRow>) {
         let col = self.process_table.sort_column;
         let asc = self.process_table.sort_ascending;
         let mode = self.process_view_mode;
 
-        self.process_table.items.sort_by(|a, b| {
+        let cmp = |a: &ProcessRow, b: &ProcessRow| {
             let key_a = a.sort_key_for_mode(col, mode);
             let key_b = b.sort_key_for_mode(col, mode);
             let cmp = key_a
                 .partial_cmp(&key_b)
                 .unwrap_or(std::cmp::Ordering::Equal);
             if asc { cmp } else { cmp.reverse() }
-        });
+        };
+        if self.process_tree {
+            // Siblings are sorted; parents always precede their children
+            arrange_process_tree(rows, cmp);
+        } else {
+            clear_process_tree(rows);
+            rows.sort_by(cmp);
+            let pinned = &self.process_table.pinned;
+            if !pinned.is_empty() {
+                rows.sort_by_key(|row| !pinned.contains(row.id()));
+            }
+        }
     }
 }
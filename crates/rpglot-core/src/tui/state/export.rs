@@ -0,0 +1,151 @@
+//! Table export (`E`): the visible rows of the current table as CSV or JSON.
+
+use std::path::PathBuf;
+
+use crate::fmt::format_timestamp;
+
+/// Default export path: current directory, CSV.
+pub const DEFAULT_EXPORT_TEMPLATE: &str = "rpglot_{tab}_{view}_{time}.csv";
+
+/// Output format, chosen by the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// `.json` exports JSON, anything else CSV.
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+}
+
+/// A table captured while rendering: columns in layout order (hidden
+/// columns dropped, horizontal scroll ignored) and the filtered, sorted rows
+/// as displayed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExportTable {
+    pub tab: &'static str,
+    pub view: &'static str,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl ExportTable {
+    /// Expands `{tab}`, `{view}` and `{time}` (`timestamp` in the configured
+    /// time zone) in the path template.
+    pub fn path(&self, template: &str, timestamp: i64) -> PathBuf {
+        let time =
+            format_timestamp(timestamp, "%Y%m%d-%H%M%S").unwrap_or_else(|| timestamp.to_string());
+        PathBuf::from(
+            template
+                .replace("{tab}", &self.tab.to_lowercase())
+                .replace("{view}", self.view)
+                .replace("{time}", &time),
+        )
+    }
+
+    pub fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Csv => self.to_csv(),
+            ExportFormat::Json => self.to_json(),
+        }
+    }
+
+    /// RFC 4180 CSV with a header line.
+    fn to_csv(&self) -> String {
+        let mut out = String::new();
+        for line in std::iter::once(&self.headers).chain(&self.rows) {
+            let fields: Vec<String> = line.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&fields.join(","));
+            out.push_str("\r\n");
+        }
+        out
+    }
+
+    /// Array of objects keyed by header, in column order.
+    fn to_json(&self) -> String {
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = self
+                    .headers
+                    .iter()
+                    .zip(row)
+                    .map(|(h, v)| format!("{}: {}", json_string(h), json_string(v)))
+                    .collect();
+                format!("  {{{}}}", fields.join(", "))
+            })
+            .collect();
+        if rows.is_empty() {
+            "[]\n".to_string()
+        } else {
+            format!("[\n{}\n]\n", rows.join(",\n"))
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn json_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn table() -> ExportTable {
+        ExportTable {
+            tab: "PGS",
+            view: "time",
+            headers: vec!["QUERY".to_string(), "CALLS/s".to_string()],
+            rows: vec![
+                vec!["SELECT a, \"b\" FROM t".to_string(), "1.5".to_string()],
+                vec!["SELECT 1".to_string(), "2".to_string()],
+            ],
+        }
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_separators() {
+        assert_eq!(
+            table().render(ExportFormat::Csv),
+            "QUERY,CALLS/s\r\n\"SELECT a, \"\"b\"\" FROM t\",1.5\r\nSELECT 1,2\r\n"
+        );
+    }
+
+    #[test]
+    fn json_keeps_column_order() {
+        let json = table().render(ExportFormat::Json);
+        assert!(json.contains(r#"{"QUERY": "SELECT 1", "CALLS/s": "2"}"#));
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.as_array().unwrap().len(), 2);
+        assert_eq!(parsed[0]["QUERY"], "SELECT a, \"b\" FROM t");
+    }
+
+    #[test]
+    fn path_template_and_format() {
+        let path = table().path("/tmp/{tab}-{view}.json", 0);
+        assert_eq!(path, Path::new("/tmp/pgs-time.json"));
+        assert_eq!(ExportFormat::from_path(&path), ExportFormat::Json);
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.CSV")),
+            ExportFormat::Csv
+        );
+        let default = table().path(DEFAULT_EXPORT_TEMPLATE, 0);
+        assert!(default.to_str().unwrap().starts_with("rpglot_pgs_time_"));
+    }
+}
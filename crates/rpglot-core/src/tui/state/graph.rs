@@ -0,0 +1,420 @@
+//! Graph view state (`G`): metric catalogue, loaded series, zoom and cursor.
+//!
+//! Instance-wide metrics come from heatmap entries (the sidecar files in
+//! history mode, entries built from each new snapshot in live mode); metrics
+//! of the selected row come from its [`EntityHistory`].
+
+use std::collections::VecDeque;
+
+use crate::entity::{EntityHistory, EntityKind, EntityPoint};
+use crate::fmt::{FmtStyle, format_bytes_rate, format_kb, format_ms, format_rate};
+use crate::storage::heatmap::HeatmapEntry;
+
+/// Summary entries kept for the live graph (24h at the default 10s interval).
+pub const LIVE_GRAPH_LEN: usize = 8640;
+
+/// Smallest visible span (seconds) zooming in can reach.
+pub const GRAPH_MIN_SPAN: i64 = 60;
+
+/// Where the values of a metric come from.
+#[derive(Clone, Copy)]
+enum Source {
+    Summary(fn(&HeatmapEntry) -> f64),
+    Entity(fn(&EntityPoint) -> Option<f64>),
+}
+
+/// A graphable metric.
+#[derive(Clone, Copy)]
+pub struct GraphMetric {
+    pub label: &'static str,
+    source: Source,
+    /// Formats a value for axis labels and the cursor readout.
+    pub format: fn(f64) -> String,
+}
+
+fn pct(v: f64) -> String {
+    format!("{v:.1}%")
+}
+
+fn count(v: f64) -> String {
+    format!("{v:.0}")
+}
+
+fn rate(v: f64) -> String {
+    format_rate(v, FmtStyle::Compact)
+}
+
+fn bytes_rate(v: f64) -> String {
+    format_bytes_rate(v, FmtStyle::Compact)
+}
+
+fn ms(v: f64) -> String {
+    format_ms(v, FmtStyle::Compact)
+}
+
+const SUMMARY_METRICS: [GraphMetric; 9] = [
+    GraphMetric {
+        label: "cpu %",
+        source: Source::Summary(|e| e.cpu_pct_x10 as f64 / 10.0),
+        format: pct,
+    },
+    GraphMetric {
+        label: "tps",
+        source: Source::Summary(|e| e.tps as f64),
+        format: rate,
+    },
+    GraphMetric {
+        label: "active sessions",
+        source: Source::Summary(|e| e.active_sessions as f64),
+        format: count,
+    },
+    GraphMetric {
+        label: "sessions",
+        source: Source::Summary(|e| e.total_sessions as f64),
+        format: count,
+    },
+    GraphMetric {
+        label: "disk util %",
+        source: Source::Summary(|e| e.io_pct_x10 as f64 / 10.0),
+        format: pct,
+    },
+    GraphMetric {
+        label: "wal/s",
+        source: Source::Summary(|e| e.wal_kib_per_sec as f64 * 1024.0),
+        format: bytes_rate,
+    },
+    GraphMetric {
+        label: "health",
+        source: Source::Summary(|e| e.health_score as f64),
+        format: count,
+    },
+    GraphMetric {
+        label: "cgroup cpu %",
+        source: Source::Summary(|e| e.cgroup_cpu_pct_x10 as f64 / 10.0),
+        format: pct,
+    },
+    GraphMetric {
+        label: "cgroup mem %",
+        source: Source::Summary(|e| e.cgroup_mem_pct_x10 as f64 / 10.0),
+        format: pct,
+    },
+];
+
+const PID_METRICS: [GraphMetric; 4] = [
+    GraphMetric {
+        label: "pid cpu %",
+        source: Source::Entity(|p| p.cpu_pct),
+        format: pct,
+    },
+    GraphMetric {
+        label: "pid rss",
+        source: Source::Entity(|p| p.rss_kb.map(|v| v as f64)),
+        format: |v| format_kb(v as u64),
+    },
+    GraphMetric {
+        label: "pid read/s",
+        source: Source::Entity(|p| p.read_bytes_s),
+        format: bytes_rate,
+    },
+    GraphMetric {
+        label: "pid write/s",
+        source: Source::Entity(|p| p.write_bytes_s),
+        format: bytes_rate,
+    },
+];
+
+const QUERY_METRICS: [GraphMetric; 4] = [
+    GraphMetric {
+        label: "query calls/s",
+        source: Source::Entity(|p| p.calls_s),
+        format: rate,
+    },
+    GraphMetric {
+        label: "query time ms/s",
+        source: Source::Entity(|p| p.exec_time_ms_s),
+        format: ms,
+    },
+    GraphMetric {
+        label: "query mean",
+        source: Source::Entity(|p| p.mean_ms),
+        format: ms,
+    },
+    GraphMetric {
+        label: "query active",
+        source: Source::Entity(|p| p.active_sessions.map(f64::from)),
+        format: count,
+    },
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct GraphState {
+    /// Row selected when the graph was opened (PID or queryid).
+    pub entity: Option<(EntityKind, i64)>,
+    /// Index into [`Self::metrics`] of the left-axis metric.
+    pub primary: usize,
+    /// Index of the right-axis metric, if comparing two.
+    pub secondary: Option<usize>,
+    /// Each zoom level halves the visible span.
+    pub zoom: u32,
+    /// Cursor timestamp; `None` follows the last point.
+    pub cursor: Option<i64>,
+    /// Summary entries of the graphed range.
+    pub summary: Vec<(i64, HeatmapEntry)>,
+    /// Trajectory of [`Self::entity`] (history mode).
+    pub entity_history: Option<EntityHistory>,
+    /// Summary entries built from live snapshots.
+    pub live: VecDeque<(i64, HeatmapEntry)>,
+}
+
+impl GraphState {
+    /// Resets the view for a newly opened graph of `entity`.
+    pub fn open(&mut self, entity: Option<(EntityKind, i64)>) {
+        self.entity = entity;
+        self.primary = 0;
+        self.secondary = None;
+        self.zoom = 0;
+        self.cursor = None;
+        self.summary.clear();
+        self.entity_history = None;
+    }
+
+    /// Records the summary entry of a new live snapshot.
+    pub fn push_live(&mut self, timestamp: i64, entry: HeatmapEntry) {
+        if self.live.len() == LIVE_GRAPH_LEN {
+            self.live.pop_front();
+        }
+        self.live.push_back((timestamp, entry));
+    }
+
+    /// Timestamp of the last live entry.
+    pub fn last_live(&self) -> Option<i64> {
+        self.live.back().map(|(ts, _)| *ts)
+    }
+
+    /// Metrics available for the loaded data: the selected row's first.
+    pub fn metrics(&self) -> Vec<GraphMetric> {
+        let entity: &[GraphMetric] = match self.entity_history.as_ref().map(|h| h.kind) {
+            Some(EntityKind::Pid) => &PID_METRICS,
+            Some(EntityKind::Queryid) => &QUERY_METRICS,
+            None => &[],
+        };
+        entity.iter().chain(&SUMMARY_METRICS).copied().collect()
+    }
+
+    /// `(timestamp, value)` points of `metric`, in timestamp order.
+    pub fn series(&self, metric: &GraphMetric) -> Vec<(f64, f64)> {
+        match metric.source {
+            Source::Summary(value) => self
+                .summary
+                .iter()
+                .map(|(ts, e)| (*ts as f64, value(e)))
+                .collect(),
+            Source::Entity(value) => self
+                .entity_history
+                .iter()
+                .flat_map(|h| &h.points)
+                .filter_map(|p| value(p).map(|v| (p.timestamp as f64, v)))
+                .collect(),
+        }
+    }
+
+    pub fn primary_metric(&self) -> Option<GraphMetric> {
+        self.metrics().get(self.primary).copied()
+    }
+
+    pub fn secondary_metric(&self) -> Option<GraphMetric> {
+        self.secondary.and_then(|i| self.metrics().get(i).copied())
+    }
+
+    /// Cycles the left-axis metric.
+    pub fn next_metric(&mut self, forward: bool) {
+        let n = self.metrics().len();
+        if n == 0 {
+            return;
+        }
+        self.primary = if forward {
+            (self.primary + 1) % n
+        } else {
+            (self.primary + n - 1) % n
+        };
+        if self.secondary == Some(self.primary) {
+            self.secondary = None;
+        }
+    }
+
+    /// Cycles the right-axis metric through the other metrics, then off.
+    pub fn next_secondary(&mut self) {
+        let n = self.metrics().len();
+        let mut next = self.secondary.map_or(0, |i| i + 1);
+        if next == self.primary {
+            next += 1;
+        }
+        self.secondary = (next < n).then_some(next);
+    }
+
+    /// Timestamps of the left-axis series.
+    fn timestamps(&self) -> Vec<i64> {
+        self.primary_metric()
+            .map(|m| self.series(&m).iter().map(|(ts, _)| *ts as i64).collect())
+            .unwrap_or_default()
+    }
+
+    /// Cursor timestamp, defaulting to the last point.
+    pub fn cursor_ts(&self) -> Option<i64> {
+        self.cursor.or_else(|| self.timestamps().last().copied())
+    }
+
+    /// Moves the cursor `step` points of the left-axis series.
+    pub fn move_cursor(&mut self, step: isize) {
+        let timestamps = self.timestamps();
+        let Some(cursor) = self.cursor_ts() else {
+            return;
+        };
+        let idx = timestamps.partition_point(|&ts| ts < cursor);
+        let last = timestamps.len().saturating_sub(1);
+        let idx = idx.saturating_add_signed(step).min(last);
+        self.cursor = (idx < last).then(|| timestamps[idx]);
+    }
+
+    /// Moves the cursor to the first (`end = false`) or last point.
+    pub fn cursor_to_end(&mut self, end: bool) {
+        self.cursor = if end {
+            None
+        } else {
+            self.timestamps().first().copied()
+        };
+    }
+
+    /// Halves the visible span. Returns false at the smallest span.
+    pub fn zoom_in(&mut self) -> bool {
+        let (start, end) = self.range();
+        if (end - start) >> (self.zoom + 1) < GRAPH_MIN_SPAN {
+            return false;
+        }
+        self.zoom += 1;
+        true
+    }
+
+    pub fn zoom_out(&mut self) -> bool {
+        if self.zoom == 0 {
+            return false;
+        }
+        self.zoom -= 1;
+        true
+    }
+
+    /// First and last timestamp of the left-axis series.
+    pub fn range(&self) -> (i64, i64) {
+        let timestamps = self.timestamps();
+        match (timestamps.first(), timestamps.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => (0, 0),
+        }
+    }
+
+    /// Visible part of [`Self::range`], centered on the cursor when zoomed.
+    pub fn view(&self) -> (i64, i64) {
+        let range = self.range();
+        let span = (range.1 - range.0) >> self.zoom;
+        if self.zoom == 0 || span == 0 {
+            return range;
+        }
+        let cursor = self.cursor_ts().unwrap_or(range.1);
+        let start = (cursor - span / 2).clamp(range.0, range.1 - span);
+        (start, start + span)
+    }
+}
+
+/// Points of `points` inside `view`, reduced to at most `max` by keeping the
+/// largest value of each bucket so spikes survive.
+pub fn downsample(points: &[(f64, f64)], view: (f64, f64), max: usize) -> Vec<(f64, f64)> {
+    let visible: Vec<(f64, f64)> = points
+        .iter()
+        .copied()
+        .filter(|(x, _)| *x >= view.0 && *x <= view.1)
+        .collect();
+    if max == 0 || visible.len() <= max {
+        return visible;
+    }
+    let per_bucket = visible.len().div_ceil(max);
+    visible
+        .chunks(per_bucket)
+        .filter_map(|bucket| bucket.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(n: i64) -> GraphState {
+        GraphState {
+            summary: (0..n)
+                .map(|i| {
+                    (
+                        1000 + i * 10,
+                        HeatmapEntry {
+                            tps: i as u32,
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn cursor_moves_over_points() {
+        let mut g = graph(10);
+        assert_eq!(g.cursor_ts(), Some(1090));
+        g.move_cursor(-2);
+        assert_eq!(g.cursor, Some(1070));
+        g.move_cursor(-100);
+        assert_eq!(g.cursor, Some(1000));
+        // Reaching the last point follows new points again
+        g.move_cursor(100);
+        assert_eq!(g.cursor, None);
+    }
+
+    #[test]
+    fn zoom_keeps_cursor_in_view() {
+        let mut g = graph(100);
+        assert_eq!(g.view(), (1000, 1990));
+        g.cursor = Some(1100);
+        assert!(g.zoom_in());
+        assert_eq!(g.view(), (1000, 1495));
+        assert!(g.zoom_in());
+        let (start, end) = g.view();
+        assert!(start <= 1100 && 1100 <= end);
+        while g.zoom_in() {}
+        assert!((g.range().1 - g.range().0) >> g.zoom >= GRAPH_MIN_SPAN);
+        assert!(g.zoom_out());
+    }
+
+    #[test]
+    fn metrics_and_secondary_cycle() {
+        let mut g = graph(3);
+        assert_eq!(g.metrics()[0].label, "cpu %");
+        assert_eq!(g.series(&g.metrics()[1])[2], (1020.0, 2.0));
+        g.next_secondary();
+        assert_eq!(g.secondary, Some(1));
+        g.next_metric(true);
+        // The left axis took the right-axis metric
+        assert_eq!((g.primary, g.secondary), (1, None));
+        g.next_metric(false);
+        g.next_metric(false);
+        assert_eq!(g.primary, SUMMARY_METRICS.len() - 1);
+    }
+
+    #[test]
+    fn downsample_keeps_spikes() {
+        let points: Vec<(f64, f64)> = (0..100)
+            .map(|i| (i as f64, if i == 37 { 50.0 } else { 1.0 }))
+            .collect();
+        let reduced = downsample(&points, (0.0, 99.0), 10);
+        assert_eq!(reduced.len(), 10);
+        assert!(reduced.contains(&(37.0, 50.0)));
+        assert_eq!(downsample(&points, (10.0, 19.0), 100).len(), 10);
+    }
+}
@@ -0,0 +1,143 @@
+//! Saved column layouts for TUI tables (`~/.config/rpglot/layout.toml`).
+//!
+//! Layouts are keyed by tab and view mode:
+//!
+//! ```toml
+//! [pgs.time]
+//! order = ["MEAN", "CALLS/s", "TIME/s"]
+//! hidden = ["ROWS/s"]
+//! ```
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::Tab;
+use crate::table::ColumnLayout;
+
+/// Column headers of the table currently on screen, recorded at render time
+/// so the column chooser can list them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnSchema {
+    pub tab: Tab,
+    pub mode: &'static str,
+    pub headers: Vec<String>,
+    /// Number of leading columns that can be reordered.
+    pub movable: usize,
+}
+
+/// `$XDG_CONFIG_HOME/rpglot`, falling back to `$HOME/.config/rpglot`.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("rpglot"))
+}
+
+/// All saved column layouts: tab -> view mode -> layout.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LayoutConfig {
+    tabs: BTreeMap<String, BTreeMap<String, ColumnLayout>>,
+}
+
+impl LayoutConfig {
+    /// `$XDG_CONFIG_HOME/rpglot/layout.toml`, falling back to
+    /// `$HOME/.config/rpglot/layout.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(config_dir()?.join("layout.toml"))
+    }
+
+    /// Loads layouts from `path`. A missing file yields an empty config.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                toml::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes layouts to `path`, creating the parent directory if needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let text = toml::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, text)
+    }
+
+    /// Layout for a tab/view mode, if customized.
+    pub fn get(&self, tab: Tab, mode: &str) -> Option<&ColumnLayout> {
+        self.tabs
+            .get(&tab_key(tab))
+            .and_then(|modes| modes.get(mode))
+    }
+
+    /// Mutable layout for a tab/view mode, created on demand.
+    pub fn entry(&mut self, tab: Tab, mode: &str) -> &mut ColumnLayout {
+        self.tabs
+            .entry(tab_key(tab))
+            .or_default()
+            .entry(mode.to_string())
+            .or_default()
+    }
+
+    /// Removes layouts equal to the default so the file only lists changes.
+    pub fn prune(&mut self) {
+        for modes in self.tabs.values_mut() {
+            modes.retain(|_, layout| !layout.is_default());
+        }
+        self.tabs.retain(|_, modes| !modes.is_empty());
+    }
+}
+
+fn tab_key(tab: Tab) -> String {
+    tab.name().to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("layout.toml");
+        assert_eq!(LayoutConfig::load(&path).unwrap(), LayoutConfig::default());
+
+        let mut config = LayoutConfig::default();
+        config
+            .entry(Tab::PgStatements, "time")
+            .hidden
+            .push("ROWS/s".into());
+        config.entry(Tab::PgStatements, "time").order = vec!["MEAN".into(), "CALLS/s".into()];
+        config.entry(Tab::Processes, "generic");
+        config.prune();
+        config.save(&path).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("[pgs.time]"));
+        assert!(!text.contains("prc"));
+
+        let loaded = LayoutConfig::load(&path).unwrap();
+        assert_eq!(loaded, config);
+        assert_eq!(
+            loaded.get(Tab::PgStatements, "time").unwrap().hidden,
+            vec!["ROWS/s".to_string()]
+        );
+    }
+
+    #[test]
+    fn invalid_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layout.toml");
+        std::fs::write(&path, "[pgs.time]\norder = 5\n").unwrap();
+        assert!(LayoutConfig::load(&path).is_err());
+    }
+}
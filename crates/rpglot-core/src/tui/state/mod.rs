@@ -4,10 +4,24 @@ pub use crate::models::*;
 pub use crate::table::*;
 
 mod app_state;
+mod export;
+mod graph;
+mod layout;
+mod query_history;
+mod summary_history;
 mod tab_states;
+mod timeline;
+mod watch;
 
 pub use app_state::*;
+pub use export::*;
+pub use graph::*;
+pub use layout::*;
+pub use query_history::*;
+pub use summary_history::*;
 pub use tab_states::*;
+pub use timeline::*;
+pub use watch::*;
 
 /// Available tabs in the TUI.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -21,6 +35,11 @@ pub enum Tab {
     PgIndexes,
     PgErrors,
     PgLocks,
+    PgRoles,
+    Custom,
+    PgSettings,
+    /// rpglotd self-monitoring; hidden from the tab bar, toggled with `~`.
+    Agent,
 }
 
 impl Tab {
@@ -34,6 +53,9 @@ impl Tab {
             Tab::PgIndexes,
             Tab::PgErrors,
             Tab::PgLocks,
+            Tab::PgRoles,
+            Tab::Custom,
+            Tab::PgSettings,
         ]
     }
 }
@@ -50,6 +72,10 @@ impl Tab {
             Tab::PgIndexes => "PGI",
             Tab::PgErrors => "PGE",
             Tab::PgLocks => "PGL",
+            Tab::PgRoles => "PGR",
+            Tab::Custom => "CUS",
+            Tab::PgSettings => "PGC",
+            Tab::Agent => "AGT",
         }
     }
 
@@ -63,14 +89,17 @@ impl Tab {
             Tab::PgTables => Tab::PgIndexes,
             Tab::PgIndexes => Tab::PgErrors,
             Tab::PgErrors => Tab::PgLocks,
-            Tab::PgLocks => Tab::Processes,
+            Tab::PgLocks => Tab::PgRoles,
+            Tab::PgRoles => Tab::Custom,
+            Tab::Custom => Tab::PgSettings,
+            Tab::PgSettings | Tab::Agent => Tab::Processes,
         }
     }
 
     /// Returns the previous tab.
     pub fn prev(&self) -> Tab {
         match self {
-            Tab::Processes => Tab::PgLocks,
+            Tab::Processes => Tab::PgSettings,
             Tab::PostgresActive => Tab::Processes,
             Tab::PgStatements => Tab::PostgresActive,
             Tab::PgStorePlans => Tab::PgStatements,
@@ -78,6 +107,10 @@ impl Tab {
             Tab::PgIndexes => Tab::PgTables,
             Tab::PgErrors => Tab::PgIndexes,
             Tab::PgLocks => Tab::PgErrors,
+            Tab::PgRoles => Tab::PgLocks,
+            Tab::Custom => Tab::PgRoles,
+            Tab::PgSettings => Tab::Custom,
+            Tab::Agent => Tab::PgSettings,
         }
     }
 }
@@ -88,7 +121,13 @@ pub enum InputMode {
     #[default]
     Normal,
     Filter,
+    /// Column expression filter prompt (`\`).
+    RowFilter,
     TimeJump,
+    /// Bookmark label prompt (history mode, `y`).
+    BookmarkLabel,
+    /// Command palette (`:`).
+    Palette,
 }
 
 /// Active popup state. Only one popup can be open at a time.
@@ -151,6 +190,44 @@ pub enum PopupState {
         scroll: usize,
         show_help: bool,
     },
+    /// Full-screen query text viewer (PGS/PGP tabs): SQL, plans, metric history.
+    QueryView {
+        queryid: i64,
+        /// Plan to show first (opened from PGP).
+        planid: Option<i64>,
+        scroll: usize,
+    },
+    /// Diff of the selected plan against the most-called other plan of its
+    /// queryid (`|` on PGP).
+    PlanDiff {
+        queryid: i64,
+        /// Plan shown as the new one.
+        planid: i64,
+        scroll: usize,
+    },
+    /// Column chooser for the current tab and view mode.
+    ColumnChooser {
+        /// Selected entry in display order (hidden columns included).
+        cursor: usize,
+        /// Layout was modified since the chooser opened.
+        changed: bool,
+    },
+    /// All fields of the selected table row, one per line.
+    RowDetail { scroll: usize },
+    /// Bookmark list (history mode, `Y`).
+    Bookmarks { cursor: usize },
+    /// Trajectory of a backend PID (PGA) or queryid (PGS) over the loaded
+    /// history (history mode, `#`).
+    EntityHistory {
+        kind: crate::entity::EntityKind,
+        id: i64,
+        scroll: usize,
+    },
+    /// Contents of shared_buffers from the latest pg_buffercache sample
+    /// (`%` on PGT/PGI).
+    BufferCache { scroll: usize },
+    /// Full-screen metric graph over the loaded history (`G`).
+    Graph,
 }
 
 impl PopupState {
@@ -171,6 +248,11 @@ impl PopupState {
                 | Self::PgiDetail { .. }
                 | Self::PglDetail { .. }
                 | Self::PgeDetail { .. }
+                | Self::QueryView { .. }
+                | Self::PlanDiff { .. }
+                | Self::RowDetail { .. }
+                | Self::EntityHistory { .. }
+                | Self::BufferCache { .. }
         )
     }
 }
@@ -0,0 +1,188 @@
+//! Per-queryid metric history for the full-screen query view.
+//!
+//! Rates are computed from cumulative pg_stat_statements counters between
+//! consecutive samples, so samples do not need to be adjacent snapshots
+//! (history mode samples the loaded window sparsely).
+
+use crate::storage::model::{DataBlock, PgStatStatementsInfo, Snapshot};
+
+/// Maximum number of history points kept (and sampled in history mode).
+pub const MAX_QUERY_HISTORY_POINTS: usize = 120;
+
+/// Cumulative counters of one pg_stat_statements entry at a point in time.
+#[derive(Debug, Clone, Copy)]
+struct QueryCounters {
+    timestamp: i64,
+    calls: i64,
+    total_exec_time: f64,
+    shared_blks_read: i64,
+}
+
+/// Interval rates for a queryid over the history window.
+#[derive(Debug, Default)]
+pub struct QueryHistory {
+    pub queryid: i64,
+    /// Interval end timestamps, one per point.
+    pub timestamps: Vec<i64>,
+    pub calls_s: Vec<f64>,
+    pub exec_time_ms_s: Vec<f64>,
+    /// Mean execution time of calls within the interval (ms).
+    pub mean_ms: Vec<f64>,
+    pub shared_blks_read_s: Vec<f64>,
+    prev: Option<QueryCounters>,
+}
+
+impl QueryHistory {
+    pub fn new(queryid: i64) -> Self {
+        Self {
+            queryid,
+            ..Default::default()
+        }
+    }
+
+    /// Adds a snapshot as the next sample.
+    ///
+    /// Snapshots not newer than the previous sample are ignored. If the
+    /// queryid is absent, the sample breaks the series (no rate across it).
+    pub fn push_snapshot(&mut self, snapshot: &Snapshot) {
+        if self.prev.is_some_and(|p| snapshot.timestamp <= p.timestamp) {
+            return;
+        }
+        let Some(stmt) = find_statement(snapshot, self.queryid) else {
+            self.prev = None;
+            return;
+        };
+        let cur = QueryCounters {
+            timestamp: snapshot.timestamp,
+            calls: stmt.calls,
+            total_exec_time: stmt.total_exec_time,
+            shared_blks_read: stmt.shared_blks_read,
+        };
+
+        if let Some(prev) = self.prev {
+            let dt = (cur.timestamp - prev.timestamp) as f64;
+            let d_calls = cur.calls - prev.calls;
+            // Counter reset (pg_stat_statements_reset or eviction): skip interval
+            if dt > 0.0 && d_calls >= 0 {
+                let d_time = (cur.total_exec_time - prev.total_exec_time).max(0.0);
+                let d_blks = (cur.shared_blks_read - prev.shared_blks_read).max(0);
+                self.timestamps.push(cur.timestamp);
+                self.calls_s.push(d_calls as f64 / dt);
+                self.exec_time_ms_s.push(d_time / dt);
+                self.mean_ms.push(if d_calls > 0 {
+                    d_time / d_calls as f64
+                } else {
+                    0.0
+                });
+                self.shared_blks_read_s.push(d_blks as f64 / dt);
+                self.trim();
+            }
+        }
+        self.prev = Some(cur);
+    }
+
+    /// Number of points in the series.
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Returns true if there are no points yet.
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    fn trim(&mut self) {
+        let excess = self
+            .timestamps
+            .len()
+            .saturating_sub(MAX_QUERY_HISTORY_POINTS);
+        if excess > 0 {
+            self.timestamps.drain(..excess);
+            self.calls_s.drain(..excess);
+            self.exec_time_ms_s.drain(..excess);
+            self.mean_ms.drain(..excess);
+            self.shared_blks_read_s.drain(..excess);
+        }
+    }
+}
+
+/// Evenly spaced positions for sampling `len` snapshots into at most
+/// `MAX_QUERY_HISTORY_POINTS + 1` samples (one extra for the first interval).
+pub fn sample_positions(len: usize) -> Vec<usize> {
+    let points = MAX_QUERY_HISTORY_POINTS + 1;
+    if len <= points {
+        return (0..len).collect();
+    }
+    let step = (len - 1) as f64 / (points - 1) as f64;
+    let mut positions: Vec<usize> = (0..points)
+        .map(|i| ((i as f64 * step).round() as usize).min(len - 1))
+        .collect();
+    positions.dedup();
+    positions
+}
+
+fn find_statement(snapshot: &Snapshot, queryid: i64) -> Option<&PgStatStatementsInfo> {
+    snapshot.blocks.iter().find_map(|b| {
+        if let DataBlock::PgStatStatements(v) = b {
+            v.iter().find(|s| s.queryid == queryid)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snap(timestamp: i64, calls: i64, total_exec_time: f64) -> Snapshot {
+        Snapshot {
+            timestamp,
+            blocks: vec![DataBlock::PgStatStatements(vec![PgStatStatementsInfo {
+                queryid: 7,
+                calls,
+                total_exec_time,
+                rows: calls,
+                shared_blks_read: calls * 2,
+                ..Default::default()
+            }])],
+        }
+    }
+
+    #[test]
+    fn rates_from_sparse_samples() {
+        let mut h = QueryHistory::new(7);
+        h.push_snapshot(&snap(100, 10, 100.0));
+        assert!(h.is_empty());
+        h.push_snapshot(&snap(110, 30, 300.0));
+        // 60s gap (sparse sampling) — rate still per second
+        h.push_snapshot(&snap(170, 90, 900.0));
+
+        assert_eq!(h.len(), 2);
+        assert!((h.calls_s[0] - 2.0).abs() < 1e-9);
+        assert!((h.calls_s[1] - 1.0).abs() < 1e-9);
+        assert!((h.mean_ms[1] - 10.0).abs() < 1e-9);
+        assert!((h.exec_time_ms_s[1] - 10.0).abs() < 1e-9);
+        assert!((h.shared_blks_read_s[1] - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn counter_reset_and_stale_samples_are_skipped() {
+        let mut h = QueryHistory::new(7);
+        h.push_snapshot(&snap(100, 50, 100.0));
+        h.push_snapshot(&snap(110, 5, 10.0)); // reset
+        h.push_snapshot(&snap(105, 100, 10.0)); // older than last sample
+        h.push_snapshot(&snap(120, 15, 30.0));
+        assert_eq!(h.len(), 1);
+        assert!((h.calls_s[0] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sample_positions_cover_window() {
+        assert_eq!(sample_positions(3), vec![0, 1, 2]);
+        let p = sample_positions(10_000);
+        assert_eq!(p.len(), MAX_QUERY_HISTORY_POINTS + 1);
+        assert_eq!(p[0], 0);
+        assert_eq!(*p.last().unwrap(), 9_999);
+    }
+}
@@ -0,0 +1,142 @@
+//! Rolling window of summary metrics for the summary sparklines.
+
+use std::collections::VecDeque;
+
+/// Number of snapshots kept for the summary sparklines.
+pub const SUMMARY_HISTORY_LEN: usize = 120;
+
+/// Summary metrics of one snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SummarySample {
+    pub timestamp: i64,
+    /// Total CPU busy percent (100 - idle).
+    pub cpu: f64,
+    /// PostgreSQL transactions per second (0 without pg_stat_database).
+    pub tps: f64,
+    /// Utilization percent of the busiest disk.
+    pub disk_util: f64,
+}
+
+/// Ring buffer of the last [`SUMMARY_HISTORY_LEN`] summary samples in
+/// timestamp order, ending at the snapshot on screen.
+#[derive(Debug, Clone, Default)]
+pub struct SummaryHistory {
+    samples: VecDeque<SummarySample>,
+}
+
+impl SummaryHistory {
+    /// Records the sample of the snapshot now on screen.
+    ///
+    /// Newer samples are appended. Stepping back to a sample already in the
+    /// window keeps it (its rates were computed against the right baseline)
+    /// and drops everything after it; any other jump restarts the window.
+    pub fn push(&mut self, sample: SummarySample) {
+        if self
+            .samples
+            .back()
+            .is_some_and(|last| last.timestamp >= sample.timestamp)
+        {
+            match self
+                .samples
+                .iter()
+                .position(|s| s.timestamp == sample.timestamp)
+            {
+                Some(pos) => {
+                    self.samples.truncate(pos + 1);
+                    return;
+                }
+                None => self.samples.clear(),
+            }
+        }
+        if self.samples.len() == SUMMARY_HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Adds an older sample at the front (history backfill). Ignored when
+    /// the window is full or the sample is not older than the first one.
+    pub fn push_front(&mut self, sample: SummarySample) {
+        if self.samples.len() < SUMMARY_HISTORY_LEN
+            && self
+                .samples
+                .front()
+                .is_none_or(|first| sample.timestamp < first.timestamp)
+        {
+            self.samples.push_front(sample);
+        }
+    }
+
+    /// Number of older samples that still fit in the window.
+    pub fn missing(&self) -> usize {
+        SUMMARY_HISTORY_LEN - self.samples.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// One metric over the window, oldest first.
+    pub fn values(&self, metric: impl Fn(&SummarySample) -> f64) -> Vec<f64> {
+        self.samples.iter().map(metric).collect()
+    }
+
+    /// The most recent sample.
+    pub fn last(&self) -> Option<&SummarySample> {
+        self.samples.back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: i64) -> SummarySample {
+        SummarySample {
+            timestamp,
+            cpu: timestamp as f64,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn push_keeps_window_in_timestamp_order() {
+        let mut history = SummaryHistory::default();
+        for ts in 0..(SUMMARY_HISTORY_LEN as i64 + 5) {
+            history.push(sample(ts));
+        }
+        assert_eq!(history.len(), SUMMARY_HISTORY_LEN);
+        assert_eq!(history.values(|s| s.cpu)[0], 5.0);
+
+        // Stepping back truncates to the revisited sample and keeps it
+        history.push(SummarySample {
+            cpu: -1.0,
+            ..sample(100)
+        });
+        assert_eq!(history.last(), Some(&sample(100)));
+        assert_eq!(history.len(), 96);
+
+        // Jumping outside the window restarts it
+        history.push(sample(3));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.missing(), SUMMARY_HISTORY_LEN - 1);
+    }
+
+    #[test]
+    fn push_front_backfills_older_samples_only() {
+        let mut history = SummaryHistory::default();
+        history.push(sample(10));
+        history.push_front(sample(9));
+        history.push_front(sample(12));
+        history.push_front(sample(8));
+        assert_eq!(history.values(|s| s.cpu), vec![8.0, 9.0, 10.0]);
+    }
+}
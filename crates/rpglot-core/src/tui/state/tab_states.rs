@@ -1,12 +1,12 @@
 //! Per-tab state: PGA (pg_stat_activity), PGS (pg_stat_statements),
 //! PGT (pg_stat_user_tables), PGI (pg_stat_user_indexes).
 
-use crate::storage::model::{DataBlock, PgLogSeverity, Snapshot};
+use crate::storage::model::{DataBlock, PgLogEntry, PgLogEventEntry, PgLogSeverity, Snapshot};
 use ratatui::widgets::TableState as RatatuiTableState;
 
 use super::{
-    PgActivityViewMode, PgIndexesViewMode, PgStatementsViewMode, PgStorePlansViewMode,
-    PgTablesViewMode,
+    PgActivityViewMode, PgErrorsViewMode, PgIndexesViewMode, PgRolesViewMode, PgSettingsViewMode,
+    PgStatementsViewMode, PgStorePlansViewMode, PgTablesViewMode, PinnedRows, RowFilter,
 };
 use crate::tui::navigable::NavigableTable;
 
@@ -55,6 +55,7 @@ pub fn resolve_selection_by_id<K: Eq + Copy>(
 pub struct PgLocksTabState {
     pub selected: usize,
     pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
     pub tracked_pid: Option<i32>,
     pub ratatui_state: RatatuiTableState,
 }
@@ -83,6 +84,249 @@ impl PgLocksTabState {
     }
 }
 
+// ===========================================================================
+// PGR (workload by role / application) tab state
+// ===========================================================================
+
+/// State for the PostgreSQL workload (PGR) tab.
+#[derive(Debug)]
+pub struct PgRolesTabState {
+    pub selected: usize,
+    pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
+    pub sort_column: usize,
+    pub sort_ascending: bool,
+    pub view_mode: PgRolesViewMode,
+    /// Name hash of the selected role or application.
+    pub tracked_name_hash: Option<u64>,
+    pub pinned: PinnedRows,
+    pub ratatui_state: RatatuiTableState,
+}
+
+impl Default for PgRolesTabState {
+    fn default() -> Self {
+        Self {
+            selected: 0,
+            filter: None,
+            row_filter: None,
+            sort_column: PgRolesViewMode::Roles.default_sort_column(),
+            sort_ascending: false,
+            view_mode: PgRolesViewMode::Roles,
+            tracked_name_hash: None,
+            pinned: PinnedRows::default(),
+            ratatui_state: RatatuiTableState::default(),
+        }
+    }
+}
+
+impl NavigableTable for PgRolesTabState {
+    fn selected(&self) -> usize {
+        self.selected
+    }
+    fn selected_mut(&mut self) -> &mut usize {
+        &mut self.selected
+    }
+    fn clear_tracked(&mut self) {
+        self.tracked_name_hash = None;
+    }
+}
+
+impl PgRolesTabState {
+    pub fn next_sort_column(&mut self) {
+        self.sort_column = (self.sort_column + 1) % self.view_mode.column_count();
+    }
+
+    /// Cycle grouping: role -> application -> client host.
+    pub fn toggle_view_mode(&mut self) {
+        self.view_mode = match self.view_mode {
+            PgRolesViewMode::Roles => PgRolesViewMode::Applications,
+            PgRolesViewMode::Applications => PgRolesViewMode::Clients,
+            PgRolesViewMode::Clients => PgRolesViewMode::Roles,
+        };
+        self.selected = 0;
+        self.tracked_name_hash = None;
+        self.sort_column = self.view_mode.default_sort_column();
+        self.sort_ascending = false;
+    }
+
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort_ascending = !self.sort_ascending;
+    }
+
+    pub fn resolve_selection(&mut self, row_hashes: &[u64]) {
+        resolve_selection_by_id(
+            &mut self.selected,
+            &mut self.tracked_name_hash,
+            &mut None,
+            &mut self.ratatui_state,
+            row_hashes,
+        );
+    }
+}
+
+// ===========================================================================
+// CUS (custom queries) tab state
+// ===========================================================================
+
+/// State for the user-defined queries (CUS) tab.
+///
+/// Shows one query at a time; `query` is the index among the queries of the
+/// current snapshot.
+#[derive(Debug)]
+pub struct CustomTabState {
+    pub selected: usize,
+    pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
+    pub sort_column: usize,
+    pub sort_ascending: bool,
+    pub query: usize,
+    /// Columns of the shown query (updated on render).
+    pub column_count: usize,
+    /// Hash of the key values of the selected row.
+    pub tracked_row: Option<u64>,
+    pub pinned: PinnedRows,
+    pub ratatui_state: RatatuiTableState,
+}
+
+impl Default for CustomTabState {
+    fn default() -> Self {
+        Self {
+            selected: 0,
+            filter: None,
+            row_filter: None,
+            sort_column: 0,
+            sort_ascending: true,
+            query: 0,
+            column_count: 0,
+            tracked_row: None,
+            pinned: PinnedRows::default(),
+            ratatui_state: RatatuiTableState::default(),
+        }
+    }
+}
+
+impl NavigableTable for CustomTabState {
+    fn selected(&self) -> usize {
+        self.selected
+    }
+    fn selected_mut(&mut self) -> &mut usize {
+        &mut self.selected
+    }
+    fn clear_tracked(&mut self) {
+        self.tracked_row = None;
+    }
+}
+
+impl CustomTabState {
+    /// Cycles sort over the columns of the shown query.
+    pub fn next_sort_column(&mut self) {
+        self.sort_column = (self.sort_column + 1) % self.column_count.max(1);
+    }
+
+    /// Switches to the next query, resetting selection and sort.
+    pub fn next_query(&mut self) {
+        *self = Self {
+            query: self.query + 1,
+            filter: self.filter.take(),
+            ..Self::default()
+        };
+    }
+
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort_ascending = !self.sort_ascending;
+    }
+
+    pub fn resolve_selection(&mut self, row_hashes: &[u64]) {
+        resolve_selection_by_id(
+            &mut self.selected,
+            &mut self.tracked_row,
+            &mut None,
+            &mut self.ratatui_state,
+            row_hashes,
+        );
+    }
+}
+
+// ===========================================================================
+// PGC (pg_settings) tab state
+// ===========================================================================
+
+/// State for the PostgreSQL settings (PGC) tab.
+#[derive(Debug)]
+pub struct PgSettingsTabState {
+    pub selected: usize,
+    pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
+    pub sort_column: usize,
+    pub sort_ascending: bool,
+    pub view_mode: PgSettingsViewMode,
+    /// Name hash of the selected setting.
+    pub tracked_name_hash: Option<u64>,
+    pub pinned: PinnedRows,
+    pub ratatui_state: RatatuiTableState,
+}
+
+impl Default for PgSettingsTabState {
+    fn default() -> Self {
+        Self {
+            selected: 0,
+            filter: None,
+            row_filter: None,
+            sort_column: 0,
+            sort_ascending: true,
+            view_mode: PgSettingsViewMode::All,
+            tracked_name_hash: None,
+            pinned: PinnedRows::default(),
+            ratatui_state: RatatuiTableState::default(),
+        }
+    }
+}
+
+impl NavigableTable for PgSettingsTabState {
+    fn selected(&self) -> usize {
+        self.selected
+    }
+    fn selected_mut(&mut self) -> &mut usize {
+        &mut self.selected
+    }
+    fn clear_tracked(&mut self) {
+        self.tracked_name_hash = None;
+    }
+}
+
+impl PgSettingsTabState {
+    /// Number of columns: CATEGORY, NAME, SETTING, UNIT, SOURCE, DEFAULT.
+    const COLUMN_COUNT: usize = 6;
+
+    pub fn next_sort_column(&mut self) {
+        self.sort_column = (self.sort_column + 1) % Self::COLUMN_COUNT;
+    }
+
+    /// Toggle between all settings and non-default ones.
+    pub fn toggle_view_mode(&mut self) {
+        self.view_mode = match self.view_mode {
+            PgSettingsViewMode::All => PgSettingsViewMode::NonDefault,
+            PgSettingsViewMode::NonDefault => PgSettingsViewMode::All,
+        };
+        self.selected = 0;
+        self.tracked_name_hash = None;
+    }
+
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort_ascending = !self.sort_ascending;
+    }
+
+    pub fn resolve_selection(&mut self, row_hashes: &[u64]) {
+        resolve_selection_by_id(
+            &mut self.selected,
+            &mut self.tracked_name_hash,
+            &mut None,
+            &mut self.ratatui_state,
+            row_hashes,
+        );
+    }
+}
+
 // ===========================================================================
 // PGE (pg_log_errors) tab state
 // ===========================================================================
@@ -94,23 +338,50 @@ pub struct AccumulatedError {
     pub severity: PgLogSeverity,
     pub count: u32,
     pub sample_hash: u64,
+    /// Last seen STATEMENT attached to this pattern (0 if none).
+    pub statement_hash: u64,
     /// Timestamp of last occurrence.
     pub last_seen: i64,
 }
 
+/// Log event (checkpoint, autovacuum, slow query) seen within the current hour.
+#[derive(Debug, Clone)]
+pub struct AccumulatedEvent {
+    /// Timestamp of the snapshot that carried the event.
+    pub timestamp: i64,
+    pub entry: PgLogEventEntry,
+}
+
+/// Log blocks of one applied snapshot within the current hour.
+#[derive(Debug)]
+struct HourSnapshot {
+    timestamp: i64,
+    errors: Vec<PgLogEntry>,
+    events: Vec<PgLogEventEntry>,
+}
+
 /// State for the PostgreSQL Errors (PGE) tab.
 #[derive(Debug, Default)]
 pub struct PgErrorsTabState {
     pub selected: usize,
     pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
     pub sort_column: usize,
     pub sort_ascending: bool,
+    pub view_mode: PgErrorsViewMode,
+    /// Show sample message and STATEMENT (or full event message) under the selected row.
+    pub expanded: bool,
     pub tracked_pattern_hash: Option<u64>,
+    pub pinned: PinnedRows,
     pub ratatui_state: RatatuiTableState,
-    /// Accumulated errors within the current hour.
+    /// Accumulated errors within the current hour, up to the current snapshot.
     pub accumulated: Vec<AccumulatedError>,
+    /// Log events within the current hour, up to the current snapshot (newest first).
+    pub events: Vec<AccumulatedEvent>,
     /// Hour boundary (epoch of hour start) for reset detection.
     pub current_hour_start: i64,
+    /// Snapshots applied within the current hour, oldest first.
+    hour_snapshots: Vec<HourSnapshot>,
 }
 
 impl NavigableTable for PgErrorsTabState {
@@ -127,8 +398,19 @@ impl NavigableTable for PgErrorsTabState {
 
 impl PgErrorsTabState {
     pub fn next_sort_column(&mut self) {
-        // 4 columns: SEVERITY, COUNT, PATTERN, SAMPLE
-        self.sort_column = (self.sort_column + 1) % 4;
+        self.sort_column = (self.sort_column + 1) % self.view_mode.column_count();
+    }
+
+    /// Switch between errors and events views.
+    pub fn toggle_view_mode(&mut self) {
+        self.view_mode = match self.view_mode {
+            PgErrorsViewMode::Errors => PgErrorsViewMode::Events,
+            PgErrorsViewMode::Events => PgErrorsViewMode::Errors,
+        };
+        self.selected = 0;
+        self.tracked_pattern_hash = None;
+        self.sort_column = self.view_mode.default_sort_column();
+        self.sort_ascending = false;
     }
 
     pub fn toggle_sort_direction(&mut self) {
@@ -145,44 +427,104 @@ impl PgErrorsTabState {
         );
     }
 
-    /// Accumulate errors from a snapshot into the current hour buffer.
-    /// Resets accumulator when the hour boundary changes.
+    /// Accumulate log errors and events from a snapshot into the current hour buffer.
+    ///
+    /// Resets the accumulator when the hour boundary changes. A snapshot newer
+    /// than the last applied one is added on top; otherwise (rewinding or
+    /// jumping back through history) snapshots at or after it are dropped and
+    /// the hour is rebuilt, so it shows as of the current position instead of
+    /// counting snapshots twice.
     pub fn accumulate_from_snapshot(&mut self, snapshot: &Snapshot) {
         let hour_start = (snapshot.timestamp / 3600) * 3600;
         if hour_start != self.current_hour_start {
+            self.hour_snapshots.clear();
             self.accumulated.clear();
+            self.events.clear();
             self.current_hour_start = hour_start;
         }
-
-        let entries = snapshot.blocks.iter().find_map(|b| {
-            if let DataBlock::PgLogErrors(v) = b {
-                Some(v.as_slice())
-            } else {
-                None
+        let rewound = self
+            .hour_snapshots
+            .last()
+            .is_some_and(|s| s.timestamp >= snapshot.timestamp);
+
+        let mut errors = Vec::new();
+        let mut events = Vec::new();
+        for b in &snapshot.blocks {
+            match b {
+                DataBlock::PgLogErrors(v) => errors = v.clone(),
+                DataBlock::PgLogDetailedEvents(v) => events = v.clone(),
+                _ => {}
             }
-        });
-
-        let Some(entries) = entries else { return };
-
-        for entry in entries {
-            if let Some(acc) = self
-                .accumulated
-                .iter_mut()
-                .find(|a| a.pattern_hash == entry.pattern_hash)
-            {
-                acc.count += entry.count;
-                acc.sample_hash = entry.sample_hash;
-                acc.last_seen = snapshot.timestamp;
-            } else {
-                self.accumulated.push(AccumulatedError {
-                    pattern_hash: entry.pattern_hash,
-                    severity: entry.severity,
-                    count: entry.count,
-                    sample_hash: entry.sample_hash,
-                    last_seen: snapshot.timestamp,
+        }
+        let snap = HourSnapshot {
+            timestamp: snapshot.timestamp,
+            errors,
+            events,
+        };
+
+        if rewound {
+            self.hour_snapshots
+                .retain(|s| s.timestamp < snapshot.timestamp);
+            self.hour_snapshots.push(snap);
+            self.rebuild_accumulated();
+        } else {
+            add_errors(&mut self.accumulated, &snap);
+            // Newest first
+            let new_events = snap.events.iter().rev().map(|entry| AccumulatedEvent {
+                timestamp: snap.timestamp,
+                entry: entry.clone(),
+            });
+            self.events.splice(0..0, new_events);
+            self.hour_snapshots.push(snap);
+        }
+    }
+
+    /// Drops the accumulated hour (e.g. after switching PostgreSQL instance).
+    pub fn clear_accumulated(&mut self) {
+        self.hour_snapshots.clear();
+        self.rebuild_accumulated();
+    }
+
+    fn rebuild_accumulated(&mut self) {
+        self.accumulated.clear();
+        self.events.clear();
+
+        for snap in &self.hour_snapshots {
+            add_errors(&mut self.accumulated, snap);
+            for entry in &snap.events {
+                self.events.push(AccumulatedEvent {
+                    timestamp: snap.timestamp,
+                    entry: entry.clone(),
                 });
             }
         }
+        self.events.reverse();
+    }
+}
+
+/// Adds the errors of one snapshot to the per-pattern totals.
+fn add_errors(accumulated: &mut Vec<AccumulatedError>, snap: &HourSnapshot) {
+    for entry in &snap.errors {
+        if let Some(acc) = accumulated
+            .iter_mut()
+            .find(|a| a.pattern_hash == entry.pattern_hash)
+        {
+            acc.count += entry.count;
+            acc.sample_hash = entry.sample_hash;
+            if entry.statement_hash != 0 {
+                acc.statement_hash = entry.statement_hash;
+            }
+            acc.last_seen = snap.timestamp;
+        } else {
+            accumulated.push(AccumulatedError {
+                pattern_hash: entry.pattern_hash,
+                severity: entry.severity,
+                count: entry.count,
+                sample_hash: entry.sample_hash,
+                statement_hash: entry.statement_hash,
+                last_seen: snap.timestamp,
+            });
+        }
     }
 }
 
@@ -191,6 +533,7 @@ impl PgErrorsTabState {
 pub struct PgActivityTabState {
     pub selected: usize,
     pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
     pub sort_column: usize,
     pub sort_ascending: bool,
     pub hide_idle: bool,
@@ -198,6 +541,7 @@ pub struct PgActivityTabState {
     pub view_mode: PgActivityViewMode,
     pub navigate_to_pid: Option<i32>,
     pub tracked_pid: Option<i32>,
+    pub pinned: PinnedRows,
     pub ratatui_state: RatatuiTableState,
     pub last_error: Option<String>,
 }
@@ -207,6 +551,7 @@ impl Default for PgActivityTabState {
         Self {
             selected: 0,
             filter: None,
+            row_filter: None,
             sort_column: PgActivityViewMode::Generic.default_sort_column(),
             sort_ascending: false,
             hide_idle: false,
@@ -214,6 +559,7 @@ impl Default for PgActivityTabState {
             view_mode: PgActivityViewMode::Generic,
             navigate_to_pid: None,
             tracked_pid: None,
+            pinned: PinnedRows::default(),
             ratatui_state: RatatuiTableState::default(),
             last_error: None,
         }
@@ -257,11 +603,13 @@ impl PgActivityTabState {
 pub struct PgStatementsTabState {
     pub selected: usize,
     pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
     pub sort_column: usize,
     pub sort_ascending: bool,
     pub view_mode: PgStatementsViewMode,
     pub navigate_to_queryid: Option<i64>,
     pub tracked_queryid: Option<i64>,
+    pub pinned: PinnedRows,
     pub ratatui_state: RatatuiTableState,
     pub rate_state: crate::rates::PgsRateState,
 }
@@ -271,11 +619,13 @@ impl Default for PgStatementsTabState {
         Self {
             selected: 0,
             filter: None,
+            row_filter: None,
             sort_column: PgStatementsViewMode::Time.default_sort_column(),
             sort_ascending: false,
             view_mode: PgStatementsViewMode::Time,
             navigate_to_queryid: None,
             tracked_queryid: None,
+            pinned: PinnedRows::default(),
             ratatui_state: RatatuiTableState::default(),
             rate_state: crate::rates::PgsRateState::default(),
         }
@@ -327,10 +677,12 @@ impl PgStatementsTabState {
 pub struct PgStorePlansTabState {
     pub selected: usize,
     pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
     pub sort_column: usize,
     pub sort_ascending: bool,
     pub view_mode: PgStorePlansViewMode,
     pub tracked_planid: Option<i64>,
+    pub pinned: PinnedRows,
     pub ratatui_state: RatatuiTableState,
     pub rate_state: crate::rates::PgpRateState,
 }
@@ -340,10 +692,12 @@ impl Default for PgStorePlansTabState {
         Self {
             selected: 0,
             filter: None,
+            row_filter: None,
             sort_column: PgStorePlansViewMode::Time.default_sort_column(),
             sort_ascending: false,
             view_mode: PgStorePlansViewMode::Time,
             tracked_planid: None,
+            pinned: PinnedRows::default(),
             ratatui_state: RatatuiTableState::default(),
             rate_state: crate::rates::PgpRateState::default(),
         }
@@ -395,10 +749,12 @@ impl PgStorePlansTabState {
 pub struct PgTablesTabState {
     pub selected: usize,
     pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
     pub sort_column: usize,
     pub sort_ascending: bool,
     pub view_mode: PgTablesViewMode,
     pub tracked_relid: Option<u32>,
+    pub pinned: PinnedRows,
     pub ratatui_state: RatatuiTableState,
     pub rate_state: crate::rates::PgtRateState,
 }
@@ -408,10 +764,12 @@ impl Default for PgTablesTabState {
         Self {
             selected: 0,
             filter: None,
+            row_filter: None,
             sort_column: PgTablesViewMode::Io.default_sort_column(),
             sort_ascending: false,
             view_mode: PgTablesViewMode::Io,
             tracked_relid: None,
+            pinned: PinnedRows::default(),
             ratatui_state: RatatuiTableState::default(),
             rate_state: crate::rates::PgtRateState::default(),
         }
@@ -463,12 +821,14 @@ impl PgTablesTabState {
 pub struct PgIndexesTabState {
     pub selected: usize,
     pub filter: Option<String>,
+    pub row_filter: Option<RowFilter>,
     /// When set, only show indexes belonging to this table (drill-down from PGT).
     pub filter_relid: Option<u32>,
     pub sort_column: usize,
     pub sort_ascending: bool,
     pub view_mode: PgIndexesViewMode,
     pub tracked_indexrelid: Option<u32>,
+    pub pinned: PinnedRows,
     pub navigate_to_indexrelid: Option<u32>,
     pub ratatui_state: RatatuiTableState,
     pub rate_state: crate::rates::PgiRateState,
@@ -479,11 +839,13 @@ impl Default for PgIndexesTabState {
         Self {
             selected: 0,
             filter: None,
+            row_filter: None,
             filter_relid: None,
             sort_column: PgIndexesViewMode::Io.default_sort_column(),
             sort_ascending: false,
             view_mode: PgIndexesViewMode::Io,
             tracked_indexrelid: None,
+            pinned: PinnedRows::default(),
             navigate_to_indexrelid: None,
             ratatui_state: RatatuiTableState::default(),
             rate_state: crate::rates::PgiRateState::default(),
@@ -526,3 +888,68 @@ impl PgIndexesTabState {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::{ErrorCategory, PgLogEventType};
+
+    fn error_snapshot(timestamp: i64, count: u32, statement_hash: u64) -> Snapshot {
+        Snapshot {
+            timestamp,
+            blocks: vec![
+                DataBlock::PgLogErrors(vec![PgLogEntry {
+                    pattern_hash: 1,
+                    severity: PgLogSeverity::Error,
+                    count,
+                    sample_hash: 2,
+                    statement_hash,
+                    category: ErrorCategory::default(),
+                }]),
+                DataBlock::PgLogDetailedEvents(vec![PgLogEventEntry {
+                    event_type: PgLogEventType::Autovacuum,
+                    message: format!("vacuum at {timestamp}"),
+                    table_name: "db.public.t".to_string(),
+                    elapsed_s: 1.0,
+                    extra_num1: 0,
+                    extra_num2: 0,
+                    buffer_hits: 0,
+                    buffer_misses: 0,
+                    buffer_dirtied: 0,
+                    avg_read_rate_mbs: 0.0,
+                    avg_write_rate_mbs: 0.0,
+                    cpu_user_s: 0.0,
+                    cpu_system_s: 0.0,
+                    wal_records: 0,
+                    wal_fpi: 0,
+                    wal_bytes: 0,
+                    extra_num3: 0,
+                    count: 0,
+                }]),
+            ],
+        }
+    }
+
+    #[test]
+    fn pge_accumulation_follows_history_position() {
+        let mut state = PgErrorsTabState::default();
+        state.accumulate_from_snapshot(&error_snapshot(7200, 2, 0));
+        state.accumulate_from_snapshot(&error_snapshot(7210, 3, 42));
+        assert_eq!(state.accumulated[0].count, 5);
+        assert_eq!(state.accumulated[0].statement_hash, 42);
+        assert_eq!(state.events.len(), 2);
+        assert_eq!(state.events[0].timestamp, 7210, "newest event first");
+        assert_eq!(state.events[1].timestamp, 7200);
+
+        // Rewind: the later snapshot no longer counts
+        state.accumulate_from_snapshot(&error_snapshot(7200, 2, 0));
+        assert_eq!(state.accumulated[0].count, 2);
+        assert_eq!(state.accumulated[0].statement_hash, 0);
+        assert_eq!(state.events.len(), 1);
+
+        // New hour resets
+        state.accumulate_from_snapshot(&error_snapshot(10800, 1, 0));
+        assert_eq!(state.accumulated[0].count, 1);
+        assert_eq!(state.events.len(), 1);
+    }
+}
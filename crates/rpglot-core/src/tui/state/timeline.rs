@@ -0,0 +1,213 @@
+//! Timeline bar state (history mode): visible range, zoom and per-column
+//! health buckets built from the heatmap sidecar files.
+
+use crate::storage::gaps::TimelineGap;
+use crate::storage::heatmap::HeatmapEntry;
+
+/// Smallest visible span (seconds) zooming in can reach.
+pub const TIMELINE_MIN_SPAN: i64 = 60;
+
+/// Health score at or above which a column is healthy (same as the web UI).
+pub const HEALTH_OK: u8 = 80;
+/// Health score at or above which a column is degraded rather than critical.
+pub const HEALTH_WARN: u8 = 50;
+
+#[derive(Debug, Clone, Default)]
+pub struct TimelineState {
+    /// First and last snapshot timestamps of the loaded history.
+    pub range: (i64, i64),
+    /// Visible part of `range`; each zoom level halves it.
+    pub view: (i64, i64),
+    pub zoom: u32,
+    /// Worst health score per bar column (`None`: no snapshots there).
+    pub health: Vec<Option<u8>>,
+    /// Columns without snapshots that lie in a gap of the history
+    /// (collector down), as opposed to sparse data.
+    pub gap: Vec<bool>,
+    /// Screen position of the bar as (x, y, width), recorded while rendering.
+    pub bar: Option<(u16, u16, u16)>,
+    /// View and column count `health` was built for.
+    loaded: Option<((i64, i64), usize)>,
+}
+
+impl TimelineState {
+    /// Sets the full range and keeps `cursor` inside the visible range.
+    /// A zoomed view only moves when the cursor leaves it.
+    pub fn update_view(&mut self, range: (i64, i64), cursor: i64) {
+        self.range = range;
+        let span = self.span_at(self.zoom);
+        if self.zoom == 0 || span == 0 {
+            self.view = range;
+            return;
+        }
+        let (start, end) = self.view;
+        if end - start == span
+            && start >= range.0
+            && end <= range.1
+            && start <= cursor
+            && cursor <= end
+        {
+            return;
+        }
+        let start = (cursor - span / 2).clamp(range.0, range.1 - span);
+        self.view = (start, start + span);
+    }
+
+    /// Halves the visible span. Returns false at the smallest span.
+    pub fn zoom_in(&mut self) -> bool {
+        if self.span_at(self.zoom + 1) < TIMELINE_MIN_SPAN {
+            return false;
+        }
+        self.zoom += 1;
+        true
+    }
+
+    /// Doubles the visible span. Returns false when the full range is shown.
+    pub fn zoom_out(&mut self) -> bool {
+        if self.zoom == 0 {
+            return false;
+        }
+        self.zoom -= 1;
+        true
+    }
+
+    fn span_at(&self, zoom: u32) -> i64 {
+        (self.range.1 - self.range.0).max(0) >> zoom.min(62)
+    }
+
+    /// Whether the health buckets are stale for the view and `width`.
+    pub fn needs_reload(&self, width: usize) -> bool {
+        self.loaded != Some((self.view, width))
+    }
+
+    /// Rebuilds the health buckets from heatmap entries of the view and
+    /// marks empty columns covered by `gaps`.
+    pub fn set_entries(
+        &mut self,
+        entries: &[(i64, HeatmapEntry)],
+        gaps: &[TimelineGap],
+        width: usize,
+    ) {
+        self.health = bucket_health(entries, self.view, width);
+        self.loaded = Some((self.view, width));
+        let column_secs = self.column_secs();
+        self.gap = (0..self.health.len())
+            .map(|col| {
+                let mid = self.view.0 + ((col as f64 + 0.5) * column_secs) as i64;
+                self.health[col].is_none() && gaps.iter().any(|g| g.start < mid && mid < g.end)
+            })
+            .collect();
+    }
+
+    /// Seconds covered by one bar column.
+    pub fn column_secs(&self) -> f64 {
+        let width = self.health.len().max(1);
+        (self.view.1 - self.view.0).max(1) as f64 / width as f64
+    }
+
+    /// Bar column holding `ts`, if it is in the view.
+    pub fn column_of(&self, ts: i64) -> Option<usize> {
+        let width = self.health.len();
+        if width == 0 || ts < self.view.0 || ts > self.view.1 {
+            return None;
+        }
+        Some((((ts - self.view.0) as f64 / self.column_secs()) as usize).min(width - 1))
+    }
+
+    /// Middle of the column under a screen position on the bar.
+    pub fn timestamp_at_screen(&self, x: u16, y: u16) -> Option<i64> {
+        let (bar_x, bar_y, bar_width) = self.bar?;
+        if y != bar_y || x < bar_x || x >= bar_x + bar_width {
+            return None;
+        }
+        let column = (x - bar_x) as f64 + 0.5;
+        Some(self.view.0 + (column * self.column_secs()) as i64)
+    }
+}
+
+/// Worst health score per column for `width` equal slices of `view`.
+fn bucket_health(
+    entries: &[(i64, HeatmapEntry)],
+    view: (i64, i64),
+    width: usize,
+) -> Vec<Option<u8>> {
+    let mut health = vec![None; width];
+    if width == 0 {
+        return health;
+    }
+    let span = (view.1 - view.0).max(1) as f64;
+    for (ts, entry) in entries {
+        if *ts < view.0 || *ts > view.1 {
+            continue;
+        }
+        let idx = (((ts - view.0) as f64 / span * width as f64) as usize).min(width - 1);
+        let worst = health[idx].map_or(entry.health_score, |h: u8| h.min(entry.health_score));
+        health[idx] = Some(worst);
+    }
+    health
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(ts: i64, health_score: u8) -> (i64, HeatmapEntry) {
+        (
+            ts,
+            HeatmapEntry {
+                health_score,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn buckets_keep_worst_health_and_gaps() {
+        let mut timeline = TimelineState::default();
+        timeline.update_view((0, 400), 0);
+        assert!(timeline.needs_reload(4));
+        let gaps = [TimelineGap {
+            start: 50,
+            end: 220,
+        }];
+        timeline.set_entries(&[entry(10, 90), entry(50, 40), entry(350, 100)], &gaps, 4);
+        assert!(!timeline.needs_reload(4));
+        assert!(timeline.needs_reload(5));
+        assert_eq!(timeline.health, vec![Some(40), None, None, Some(100)]);
+        // Only the empty column inside the gap is shaded
+        assert_eq!(timeline.gap, vec![false, true, false, false]);
+
+        assert_eq!(timeline.column_of(399), Some(3));
+        assert_eq!(timeline.column_of(400), Some(3));
+        assert_eq!(timeline.column_of(401), None);
+
+        timeline.bar = Some((10, 20, 4));
+        assert_eq!(timeline.timestamp_at_screen(11, 20), Some(150));
+        assert_eq!(timeline.timestamp_at_screen(14, 20), None);
+        assert_eq!(timeline.timestamp_at_screen(11, 21), None);
+    }
+
+    #[test]
+    fn zoom_follows_cursor() {
+        let mut timeline = TimelineState::default();
+        timeline.update_view((0, 1000), 900);
+        assert_eq!(timeline.view, (0, 1000));
+
+        assert!(timeline.zoom_in());
+        timeline.update_view((0, 1000), 900);
+        assert_eq!(timeline.view, (500, 1000));
+
+        // Moving inside the view keeps it, leaving it re-centers
+        timeline.update_view((0, 1000), 600);
+        assert_eq!(timeline.view, (500, 1000));
+        timeline.update_view((0, 1000), 300);
+        assert_eq!(timeline.view, (50, 550));
+
+        // Zooming stops at the smallest span
+        while timeline.zoom_in() {}
+        assert_eq!(timeline.zoom, 4);
+        while timeline.zoom_out() {}
+        timeline.update_view((0, 1000), 300);
+        assert_eq!(timeline.view, (0, 1000));
+    }
+}
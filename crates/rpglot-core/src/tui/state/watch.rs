@@ -0,0 +1,254 @@
+//! Watch mode (`*`): highlights table cells whose values changed since the
+//! previous refresh. The highlight fades over the next few refreshes.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::view::common::{RowStyleClass, TableViewModel};
+
+use super::Tab;
+
+/// Refreshes a changed cell stays dimly highlighted after the change.
+pub const WATCH_FADE_REFRESHES: u32 = 3;
+
+/// Last seen cells of a row.
+#[derive(Debug, Clone, Default)]
+struct WatchRow {
+    cells: Vec<String>,
+    /// Refreshes since each cell last changed; `None` if it has not changed
+    /// since watch mode started.
+    ages: Vec<Option<u32>>,
+}
+
+/// Cell change tracking of the table on screen.
+#[derive(Debug, Clone, Default)]
+pub struct WatchState {
+    pub enabled: bool,
+    /// Minimum relative change of numeric cells, in percent. `0` highlights
+    /// every change; non-numeric cells always highlight any change.
+    pub threshold_pct: f64,
+    /// Tab, view and headers of the tracked table.
+    table: Option<(Tab, &'static str, Vec<String>)>,
+    /// Snapshot timestamp the tracked cells belong to.
+    timestamp: Option<i64>,
+    /// Hashed row id -> last seen cells.
+    rows: HashMap<u64, WatchRow>,
+}
+
+impl WatchState {
+    /// Toggles watch mode, forgetting tracked cells. Returns the new state.
+    pub fn toggle(&mut self) -> bool {
+        self.enabled = !self.enabled;
+        self.reset();
+        self.enabled
+    }
+
+    fn reset(&mut self) {
+        self.table = None;
+        self.timestamp = None;
+        self.rows.clear();
+    }
+
+    /// Records the cells of `vm` for a new snapshot and styles the cells
+    /// that changed recently. Must run before columns are selected so cell
+    /// indices are stable. Switching tab, view or columns starts over.
+    pub fn apply<Id: Hash>(
+        &mut self,
+        tab: Tab,
+        view: &'static str,
+        timestamp: i64,
+        vm: &mut TableViewModel<Id>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let same_table = self
+            .table
+            .as_ref()
+            .is_some_and(|(t, v, h)| *t == tab && *v == view && *h == vm.headers);
+        if !same_table {
+            self.reset();
+            self.table = Some((tab, view, vm.headers.clone()));
+        }
+        if self.timestamp != Some(timestamp) {
+            self.update(timestamp, vm);
+        }
+
+        for row in &mut vm.rows {
+            let Some(watched) = self.rows.get(&row_key(&row.id)) else {
+                continue;
+            };
+            for (cell, age) in row.cells.iter_mut().zip(&watched.ages) {
+                match *age {
+                    Some(0) => cell.style = Some(RowStyleClass::Changed),
+                    Some(age) if age <= WATCH_FADE_REFRESHES => {
+                        cell.style = Some(RowStyleClass::ChangedRecently)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        vm.title = format!("{}[watch] ", vm.title);
+    }
+
+    /// Compares the rows of a new snapshot with the tracked ones.
+    fn update<Id: Hash>(&mut self, timestamp: i64, vm: &TableViewModel<Id>) {
+        // Rows appearing after the first refresh are new, hence changed
+        let baseline = self.timestamp.is_some();
+        self.timestamp = Some(timestamp);
+        let mut rows = HashMap::with_capacity(vm.rows.len());
+        for row in &vm.rows {
+            let key = row_key(&row.id);
+            let cells: Vec<String> = row.cells.iter().map(|c| c.text.clone()).collect();
+            let ages = match self.rows.get(&key) {
+                Some(prev) => cells
+                    .iter()
+                    .enumerate()
+                    .map(|(i, text)| match prev.cells.get(i) {
+                        Some(old) if !self.changed(old, text) => {
+                            prev.ages[i].map(|age| age.saturating_add(1))
+                        }
+                        _ => Some(0),
+                    })
+                    .collect(),
+                None if baseline => vec![Some(0); cells.len()],
+                None => vec![None; cells.len()],
+            };
+            rows.insert(key, WatchRow { cells, ages });
+        }
+        self.rows = rows;
+    }
+
+    /// Whether a cell changed from `old` to `new` beyond the threshold.
+    fn changed(&self, old: &str, new: &str) -> bool {
+        if old == new {
+            return false;
+        }
+        if self.threshold_pct <= 0.0 {
+            return true;
+        }
+        match (split_number(old), split_number(new)) {
+            (Some((a, unit_a)), Some((b, unit_b))) if unit_a == unit_b => {
+                (a - b).abs() > self.threshold_pct / 100.0 * a.abs().max(b.abs())
+            }
+            _ => true,
+        }
+    }
+}
+
+fn row_key<Id: Hash>(id: &Id) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Leading number of a cell and its unit suffix: `"12.5ms"` -> `(12.5, "ms")`.
+/// Accepts a decimal comma as well.
+fn split_number(text: &str) -> Option<(f64, &str)> {
+    let text = text.trim();
+    let end = text
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || c == ',' || (i == 0 && c == '-')))
+        .map_or(text.len(), |(i, _)| i);
+    let value = text[..end].replace(',', ".").parse().ok()?;
+    Some((value, &text[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::common::{ViewCell, ViewRow};
+
+    fn table(rows: &[(u32, &str, &str)]) -> TableViewModel<u32> {
+        TableViewModel {
+            title: " T ".to_string(),
+            headers: vec!["A".to_string(), "B".to_string()],
+            widths: vec![10, 10],
+            rows: rows
+                .iter()
+                .map(|&(id, a, b)| ViewRow {
+                    id,
+                    cells: vec![
+                        ViewCell::plain(a.to_string()),
+                        ViewCell::plain(b.to_string()),
+                    ],
+                    style: RowStyleClass::Normal,
+                })
+                .collect(),
+            sort_column: 0,
+            sort_ascending: true,
+        }
+    }
+
+    fn styles(
+        watch: &mut WatchState,
+        ts: i64,
+        rows: &[(u32, &str, &str)],
+    ) -> Vec<Vec<Option<RowStyleClass>>> {
+        let mut vm = table(rows);
+        watch.apply(Tab::PgStatements, "time", ts, &mut vm);
+        vm.rows
+            .iter()
+            .map(|r| r.cells.iter().map(|c| c.style).collect())
+            .collect()
+    }
+
+    fn enabled(threshold_pct: f64) -> WatchState {
+        WatchState {
+            enabled: true,
+            threshold_pct,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn changed_cells_highlight_and_fade() {
+        let mut watch = enabled(0.0);
+        assert_eq!(
+            styles(&mut watch, 1, &[(1, "a", "1")]),
+            vec![vec![None, None]]
+        );
+        let changed = styles(&mut watch, 2, &[(1, "a", "2"), (2, "b", "1")]);
+        assert_eq!(changed[0], vec![None, Some(RowStyleClass::Changed)]);
+        assert_eq!(changed[1], vec![Some(RowStyleClass::Changed); 2]);
+        // Re-rendering the same snapshot keeps the highlight
+        let again = styles(&mut watch, 2, &[(1, "a", "2")]);
+        assert_eq!(again[0][1], Some(RowStyleClass::Changed));
+
+        for ts in 3..3 + WATCH_FADE_REFRESHES as i64 {
+            let faded = styles(&mut watch, ts, &[(1, "a", "2")]);
+            assert_eq!(faded[0][1], Some(RowStyleClass::ChangedRecently));
+        }
+        let gone = styles(&mut watch, 10, &[(1, "a", "2")]);
+        assert_eq!(gone[0], vec![None, None]);
+    }
+
+    #[test]
+    fn threshold_ignores_small_numeric_changes() {
+        let mut watch = enabled(10.0);
+        styles(&mut watch, 1, &[(1, "x", "100ms")]);
+        let small = styles(&mut watch, 2, &[(1, "y", "105ms")]);
+        assert_eq!(small[0], vec![Some(RowStyleClass::Changed), None]);
+        let large = styles(&mut watch, 3, &[(1, "y", "120ms")]);
+        assert_eq!(large[0][1], Some(RowStyleClass::Changed));
+        // A different unit is always a change
+        let unit = styles(&mut watch, 4, &[(1, "y", "120s")]);
+        assert_eq!(unit[0][1], Some(RowStyleClass::Changed));
+    }
+
+    #[test]
+    fn disabled_or_other_table_starts_over() {
+        let mut watch = WatchState::default();
+        assert_eq!(
+            styles(&mut watch, 1, &[(1, "a", "1")]),
+            vec![vec![None, None]]
+        );
+        assert!(watch.toggle());
+        styles(&mut watch, 1, &[(1, "a", "1")]);
+        let mut vm = table(&[(1, "a", "2")]);
+        watch.apply(Tab::PgTables, "reads", 2, &mut vm);
+        // First refresh of another table is the baseline
+        assert!(vm.rows[0].cells.iter().all(|c| c.style.is_none()));
+        assert!(vm.title.ends_with("[watch] "));
+    }
+}
@@ -1,34 +1,128 @@
-//! Color scheme and styles (atop-style).
+//! Color palettes and styles (atop-style).
 
-use ratatui::style::{Color, Modifier, Style};
+use std::sync::RwLock;
 
-/// Atop-style color palette.
-pub struct Theme;
+use ratatui::style::{Color, Modifier, Style};
 
-impl Theme {
+/// Colors of the active theme. Every [`Styles`] helper reads from it,
+/// so switching themes restyles the whole UI on the next frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
     // Background colors
-    pub const BG: Color = Color::Reset;
-    pub const HEADER_BG: Color = Color::Blue;
-    pub const SELECTED_BG: Color = Color::DarkGray;
+    pub bg: Color,
+    pub header_bg: Color,
+    pub selected_bg: Color,
+    pub popup_bg: Color,
 
     // Foreground colors
-    pub const FG: Color = Color::White;
-    pub const FG_DIM: Color = Color::DarkGray;
-    pub const HEADER_FG: Color = Color::White;
+    pub fg: Color,
+    pub fg_dim: Color,
+    pub header_fg: Color,
+    pub popup_fg: Color,
+    pub border: Color,
 
-    // Highlight colors
-    pub const HIGHLIGHT_NEW: Color = Color::Green;
-    pub const HIGHLIGHT_MODIFIED: Color = Color::Yellow;
-    pub const HIGHLIGHT_CRITICAL: Color = Color::Red;
+    // Highlight and severity colors (diffs, lock/wait rows, thresholds)
+    pub highlight_new: Color,
+    pub warning: Color,
+    pub critical: Color,
+    pub active: Color,
+    pub accent: Color,
+    pub section: Color,
 
     // Tab colors
-    pub const TAB_ACTIVE: Color = Color::Cyan;
-    pub const TAB_INACTIVE: Color = Color::DarkGray;
+    pub tab_active: Color,
+    pub tab_inactive: Color,
 
     // Metrics colors
-    pub const CPU_COLOR: Color = Color::Cyan;
-    pub const MEM_COLOR: Color = Color::Magenta;
-    pub const DISK_COLOR: Color = Color::Yellow;
+    pub cpu: Color,
+    pub mem: Color,
+    pub disk: Color,
+}
+
+impl Palette {
+    /// Atop-style palette for dark terminals (default).
+    pub const DARK: Self = Self {
+        bg: Color::Reset,
+        header_bg: Color::Blue,
+        selected_bg: Color::DarkGray,
+        popup_bg: Color::Black,
+        fg: Color::White,
+        fg_dim: Color::DarkGray,
+        header_fg: Color::White,
+        popup_fg: Color::White,
+        border: Color::Cyan,
+        highlight_new: Color::Green,
+        warning: Color::Yellow,
+        critical: Color::Red,
+        active: Color::Green,
+        accent: Color::Cyan,
+        section: Color::Yellow,
+        tab_active: Color::Cyan,
+        tab_inactive: Color::DarkGray,
+        cpu: Color::Cyan,
+        mem: Color::Magenta,
+        disk: Color::Yellow,
+    };
+
+    /// Palette for light terminal backgrounds: no yellow/white text.
+    pub const LIGHT: Self = Self {
+        bg: Color::Reset,
+        header_bg: Color::Blue,
+        selected_bg: Color::Rgb(200, 200, 200),
+        popup_bg: Color::White,
+        fg: Color::Black,
+        fg_dim: Color::Rgb(110, 110, 110),
+        header_fg: Color::White,
+        popup_fg: Color::Black,
+        border: Color::Blue,
+        highlight_new: Color::Rgb(0, 120, 0),
+        warning: Color::Rgb(175, 95, 0),
+        critical: Color::Rgb(190, 0, 0),
+        active: Color::Rgb(0, 120, 0),
+        accent: Color::Blue,
+        section: Color::Rgb(175, 95, 0),
+        tab_active: Color::Blue,
+        tab_inactive: Color::Rgb(110, 110, 110),
+        cpu: Color::Blue,
+        mem: Color::Magenta,
+        disk: Color::Rgb(175, 95, 0),
+    };
+
+    /// Bright colors on black, no dim grays.
+    pub const HIGH_CONTRAST: Self = Self {
+        bg: Color::Black,
+        header_bg: Color::White,
+        selected_bg: Color::Blue,
+        popup_bg: Color::Black,
+        fg: Color::White,
+        fg_dim: Color::Gray,
+        header_fg: Color::Black,
+        popup_fg: Color::White,
+        border: Color::White,
+        highlight_new: Color::LightGreen,
+        warning: Color::LightYellow,
+        critical: Color::LightRed,
+        active: Color::LightGreen,
+        accent: Color::LightCyan,
+        section: Color::LightYellow,
+        tab_active: Color::LightCyan,
+        tab_inactive: Color::Gray,
+        cpu: Color::LightCyan,
+        mem: Color::LightMagenta,
+        disk: Color::LightYellow,
+    };
+}
+
+static PALETTE: RwLock<Palette> = RwLock::new(Palette::DARK);
+
+/// Returns the active palette.
+pub fn palette() -> Palette {
+    *PALETTE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Makes `palette` the active palette.
+pub fn set_palette(palette: Palette) {
+    *PALETTE.write().unwrap_or_else(|e| e.into_inner()) = palette;
 }
 
 /// Pre-defined styles.
@@ -37,103 +131,139 @@ pub struct Styles;
 impl Styles {
     /// Default text style.
     pub fn default() -> Style {
-        Style::default().fg(Theme::FG).bg(Theme::BG)
+        let p = palette();
+        Style::default().fg(p.fg).bg(p.bg)
     }
 
     /// Header bar style.
     pub fn header() -> Style {
+        let p = palette();
         Style::default()
-            .fg(Theme::HEADER_FG)
-            .bg(Theme::HEADER_BG)
+            .fg(p.header_fg)
+            .bg(p.header_bg)
             .add_modifier(Modifier::BOLD)
     }
 
     /// Selected row style.
     pub fn selected() -> Style {
         Style::default()
-            .bg(Theme::SELECTED_BG)
+            .bg(palette().selected_bg)
             .add_modifier(Modifier::BOLD)
     }
 
     /// Table header style.
     pub fn table_header() -> Style {
+        let p = palette();
         Style::default()
-            .fg(Theme::HEADER_FG)
-            .bg(Theme::HEADER_BG)
+            .fg(p.header_fg)
+            .bg(p.header_bg)
             .add_modifier(Modifier::BOLD)
     }
 
     /// New item style (green).
     pub fn new_item() -> Style {
-        Style::default().fg(Theme::HIGHLIGHT_NEW)
+        Style::default().fg(palette().highlight_new)
     }
 
     /// Modified item style (yellow).
     pub fn modified_item() -> Style {
-        Style::default().fg(Theme::HIGHLIGHT_MODIFIED)
+        Style::default().fg(palette().warning)
     }
 
     /// Critical value style (red).
     pub fn critical() -> Style {
         Style::default()
-            .fg(Theme::HIGHLIGHT_CRITICAL)
+            .fg(palette().critical)
             .add_modifier(Modifier::BOLD)
     }
 
     /// Active tab style.
     pub fn tab_active() -> Style {
         Style::default()
-            .fg(Theme::TAB_ACTIVE)
+            .fg(palette().tab_active)
             .add_modifier(Modifier::BOLD)
     }
 
     /// Inactive tab style.
     pub fn tab_inactive() -> Style {
-        Style::default().fg(Theme::TAB_INACTIVE)
+        Style::default().fg(palette().tab_inactive)
     }
 
     /// Dimmed text style.
     pub fn dim() -> Style {
-        Style::default().fg(Theme::FG_DIM)
+        Style::default().fg(palette().fg_dim)
     }
 
     /// CPU metric style.
     pub fn cpu() -> Style {
-        Style::default().fg(Theme::CPU_COLOR)
+        Style::default().fg(palette().cpu)
     }
 
     /// Memory metric style.
     pub fn mem() -> Style {
-        Style::default().fg(Theme::MEM_COLOR)
+        Style::default().fg(palette().mem)
     }
 
     /// Disk metric style.
     pub fn disk() -> Style {
-        Style::default().fg(Theme::DISK_COLOR)
+        Style::default().fg(palette().disk)
     }
 
     /// Filter input style.
     pub fn filter_input() -> Style {
         Style::default()
-            .fg(Theme::FG)
+            .fg(palette().fg)
             .add_modifier(Modifier::UNDERLINED)
     }
 
     /// Section header style for detail popups.
     pub fn section_header() -> Style {
         Style::default()
-            .fg(Color::Yellow)
+            .fg(palette().section)
             .add_modifier(Modifier::BOLD)
     }
 
+    /// Emphasized text (help headings, key names in prompts).
+    pub fn emphasis() -> Style {
+        Style::default().fg(palette().section)
+    }
+
+    /// Accent text (labels, keys of key-value lines).
+    pub fn accent() -> Style {
+        Style::default().fg(palette().accent)
+    }
+
+    /// Positive/active value style (green).
+    pub fn active() -> Style {
+        Style::default().fg(palette().active)
+    }
+
+    /// Error text without bold (red).
+    pub fn error() -> Style {
+        Style::default().fg(palette().critical)
+    }
+
+    /// Popup body style.
+    pub fn popup() -> Style {
+        let p = palette();
+        Style::default().fg(p.popup_fg).bg(p.popup_bg)
+    }
+
+    /// Popup border style.
+    pub fn popup_border() -> Style {
+        Style::default().fg(palette().border)
+    }
+
     /// Help text style.
     pub fn help() -> Style {
-        Style::default().fg(Theme::FG_DIM)
+        Style::default().fg(palette().fg_dim)
     }
 
     /// Help key style (highlighted keys in help line).
     pub fn help_key() -> Style {
-        Style::default().fg(Theme::FG).add_modifier(Modifier::BOLD)
+        Style::default()
+            .fg(palette().fg)
+            .add_modifier(Modifier::BOLD)
     }
 
     /// Maps a UI-agnostic [`RowStyleClass`] to a ratatui [`Style`].
@@ -143,12 +273,14 @@ impl Styles {
             RowStyleClass::Normal => Self::default(),
             RowStyleClass::Warning => Self::modified_item(),
             RowStyleClass::Critical => Self::critical(),
-            RowStyleClass::CriticalBold => {
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
-            }
-            RowStyleClass::Active => Style::default().fg(Color::Green),
-            RowStyleClass::Dimmed => Style::default().fg(Color::DarkGray),
-            RowStyleClass::Accent => Style::default().fg(Color::Cyan),
+            RowStyleClass::CriticalBold => Self::critical(),
+            RowStyleClass::Active => Self::active(),
+            RowStyleClass::Dimmed => Self::dim(),
+            RowStyleClass::Accent => Self::accent(),
+            RowStyleClass::Changed => Self::modified_item()
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::REVERSED),
+            RowStyleClass::ChangedRecently => Self::modified_item(),
         }
     }
 }
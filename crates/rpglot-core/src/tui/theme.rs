@@ -0,0 +1,257 @@
+//! Color themes: built-in palettes plus user themes from TOML.
+//!
+//! User themes live in `~/.config/rpglot/themes.toml`, one table per theme.
+//! A theme starts from a built-in `base` and overrides individual colors
+//! (names like `"lightred"`, `"#rrggbb"` or a 256-color index):
+//!
+//! ```toml
+//! [solarized]
+//! base = "light"
+//! bg = "#fdf6e3"
+//! critical = "#dc322f"
+//! ```
+//!
+//! `--theme` also accepts a path to a file holding a single theme with the
+//! same keys at the top level; it is named after the file stem.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ratatui::style::Color;
+
+use super::state::config_dir;
+use super::style::Palette;
+
+/// Name of the theme used when none is selected.
+pub const DEFAULT_THEME: &str = "dark";
+
+/// A named palette.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub palette: Palette,
+}
+
+impl Theme {
+    fn new(name: &str, palette: Palette) -> Self {
+        Self {
+            name: name.to_string(),
+            palette,
+        }
+    }
+
+    /// Parses one theme table: optional `base` plus color overrides.
+    fn from_table(name: &str, table: &toml::Table) -> Result<Self, String> {
+        let mut palette = match table.get("base") {
+            None => Palette::DARK,
+            Some(base) => {
+                let base = base
+                    .as_str()
+                    .ok_or_else(|| format!("theme '{name}': base must be a string"))?;
+                builtin_palette(base)
+                    .ok_or_else(|| format!("theme '{name}': unknown base theme '{base}'"))?
+            }
+        };
+        for (key, value) in table {
+            if key == "base" {
+                continue;
+            }
+            let slot = color_slot(&mut palette, key)
+                .ok_or_else(|| format!("theme '{name}': unknown color '{key}'"))?;
+            let text = value
+                .as_str()
+                .ok_or_else(|| format!("theme '{name}': {key} must be a string"))?;
+            *slot = Color::from_str(text)
+                .map_err(|_| format!("theme '{name}': invalid color '{text}' for {key}"))?;
+        }
+        Ok(Self::new(name, palette))
+    }
+}
+
+fn builtin_palette(name: &str) -> Option<Palette> {
+    match name {
+        "dark" => Some(Palette::DARK),
+        "light" => Some(Palette::LIGHT),
+        "high-contrast" => Some(Palette::HIGH_CONTRAST),
+        _ => None,
+    }
+}
+
+/// Maps a TOML key to the palette color it overrides.
+fn color_slot<'a>(palette: &'a mut Palette, key: &str) -> Option<&'a mut Color> {
+    Some(match key {
+        "bg" => &mut palette.bg,
+        "header_bg" => &mut palette.header_bg,
+        "selected_bg" => &mut palette.selected_bg,
+        "popup_bg" => &mut palette.popup_bg,
+        "fg" => &mut palette.fg,
+        "fg_dim" => &mut palette.fg_dim,
+        "header_fg" => &mut palette.header_fg,
+        "popup_fg" => &mut palette.popup_fg,
+        "border" => &mut palette.border,
+        "new" => &mut palette.highlight_new,
+        "warning" => &mut palette.warning,
+        "critical" => &mut palette.critical,
+        "active" => &mut palette.active,
+        "accent" => &mut palette.accent,
+        "section" => &mut palette.section,
+        "tab_active" => &mut palette.tab_active,
+        "tab_inactive" => &mut palette.tab_inactive,
+        "cpu" => &mut palette.cpu,
+        "mem" => &mut palette.mem,
+        "disk" => &mut palette.disk,
+        _ => return None,
+    })
+}
+
+/// Available themes in cycling order: built-ins first, then user themes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeSet {
+    themes: Vec<Theme>,
+}
+
+impl Default for ThemeSet {
+    fn default() -> Self {
+        Self {
+            themes: vec![
+                Theme::new("dark", Palette::DARK),
+                Theme::new("light", Palette::LIGHT),
+                Theme::new("high-contrast", Palette::HIGH_CONTRAST),
+            ],
+        }
+    }
+}
+
+impl ThemeSet {
+    /// `$XDG_CONFIG_HOME/rpglot/themes.toml` (see [`config_dir`]).
+    pub fn default_path() -> Option<PathBuf> {
+        Some(config_dir()?.join("themes.toml"))
+    }
+
+    /// Adds the themes defined in `path`. A missing file adds nothing.
+    pub fn load_user_themes(&mut self, path: &Path) -> io::Result<()> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let table: toml::Table = toml::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+        for (name, value) in &table {
+            let theme_table = value
+                .as_table()
+                .ok_or_else(|| invalid(format!("theme '{name}' must be a table")))?;
+            self.insert(Theme::from_table(name, theme_table).map_err(invalid)?);
+        }
+        Ok(())
+    }
+
+    /// Loads a single-theme file named after its stem and returns the name.
+    pub fn load_theme_file(&mut self, path: &Path) -> io::Result<String> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let text = std::fs::read_to_string(path)?;
+        let table: toml::Table = toml::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "custom".to_string());
+        self.insert(Theme::from_table(&name, &table).map_err(invalid)?);
+        Ok(name)
+    }
+
+    /// Adds a theme, replacing any theme with the same name.
+    fn insert(&mut self, theme: Theme) {
+        match self.themes.iter_mut().find(|t| t.name == theme.name) {
+            Some(existing) => *existing = theme,
+            None => self.themes.push(theme),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Theme> {
+        self.themes.iter().find(|t| t.name == name)
+    }
+
+    /// Theme following `name` in cycling order (wraps around).
+    pub fn next_after(&self, name: &str) -> &Theme {
+        let pos = self.themes.iter().position(|t| t.name == name);
+        let next = pos.map_or(0, |p| (p + 1) % self.themes.len());
+        &self.themes[next]
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.themes.iter().map(|t| t.name.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_themes_extend_builtin_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("themes.toml");
+        std::fs::write(
+            &path,
+            "[solarized]\nbase = \"light\"\nbg = \"#fdf6e3\"\ncritical = \"lightred\"\n\n\
+             [dark]\nwarning = \"208\"\n",
+        )
+        .unwrap();
+
+        let mut themes = ThemeSet::default();
+        themes.load_user_themes(&path).unwrap();
+        assert_eq!(
+            themes.names(),
+            vec!["dark", "light", "high-contrast", "solarized"]
+        );
+
+        let solarized = themes.get("solarized").unwrap().palette;
+        assert_eq!(solarized.bg, Color::Rgb(0xfd, 0xf6, 0xe3));
+        assert_eq!(solarized.critical, Color::LightRed);
+        assert_eq!(solarized.fg, Palette::LIGHT.fg);
+
+        // Built-ins can be overridden by name
+        assert_eq!(
+            themes.get("dark").unwrap().palette.warning,
+            Color::Indexed(208)
+        );
+
+        assert_eq!(themes.next_after("high-contrast").name, "solarized");
+        assert_eq!(themes.next_after("solarized").name, "dark");
+    }
+
+    #[test]
+    fn invalid_themes_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("themes.toml");
+        let mut themes = ThemeSet::default();
+
+        // Missing file is not an error
+        themes.load_user_themes(&path).unwrap();
+
+        for text in [
+            "[x]\nbase = \"sepia\"\n",
+            "[x]\nforeground = \"red\"\n",
+            "[x]\nfg = \"not-a-color\"\n",
+            "fg = \"red\"\n",
+        ] {
+            std::fs::write(&path, text).unwrap();
+            let err = themes.load_user_themes(&path).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{text}");
+        }
+    }
+
+    #[test]
+    fn single_theme_file_is_named_after_stem() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("night.toml");
+        std::fs::write(&path, "base = \"high-contrast\"\nborder = \"cyan\"\n").unwrap();
+
+        let mut themes = ThemeSet::default();
+        assert_eq!(themes.load_theme_file(&path).unwrap(), "night");
+        let night = themes.get("night").unwrap().palette;
+        assert_eq!(night.border, Color::Cyan);
+        assert_eq!(night.bg, Palette::HIGH_CONTRAST.bg);
+    }
+}
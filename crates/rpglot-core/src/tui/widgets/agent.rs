@@ -0,0 +1,138 @@
+//! Agent diagnostics (AGT) tab widget: resource usage of rpglotd itself.
+//!
+//! Hidden tab, toggled with `~`.
+
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::fmt::{FmtStyle, format_bytes};
+use crate::storage::model::{AgentStatsInfo, DataBlock};
+use crate::tui::state::AppState;
+use crate::tui::style::Styles;
+
+pub fn render_agent(frame: &mut Frame, area: Rect, state: &AppState) {
+    let stats = state.current_snapshot.as_ref().and_then(|s| {
+        s.blocks.iter().find_map(|b| match b {
+            DataBlock::AgentStats(a) => Some(a),
+            _ => None,
+        })
+    });
+
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(" AGT: rpglotd self-monitoring (~) ")
+        .borders(Borders::ALL);
+    let Some(stats) = stats else {
+        let label = if state.current_snapshot.is_none() {
+            "No data available"
+        } else {
+            "No agent stats (recorded by rpglotd)"
+        };
+        frame.render_widget(Paragraph::new(label).block(block), area);
+        return;
+    };
+
+    frame.render_widget(
+        Paragraph::new(agent_lines(stats))
+            .block(block)
+            .alignment(Alignment::Left),
+        area,
+    );
+}
+
+fn agent_lines(stats: &AgentStatsInfo) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(Span::styled(
+        "=== Process ===",
+        Styles::emphasis(),
+    ))];
+    lines.push(info_line("PID", stats.pid.to_string()));
+    lines.push(info_line(
+        "CPU",
+        match stats.cpu_pct() {
+            Some(pct) => format!(
+                "{pct:.1}% (usr {} ms, sys {} ms over {:.1}s)",
+                stats.cpu_user_ms,
+                stats.cpu_system_ms,
+                stats.interval_ms as f64 / 1000.0
+            ),
+            None => "--".to_string(),
+        },
+    ));
+    lines.push(info_line(
+        "RSS",
+        format!(
+            "{} (peak {})",
+            format_bytes(stats.rss_kb * 1024, FmtStyle::Compact),
+            format_bytes(stats.rss_peak_kb * 1024, FmtStyle::Compact)
+        ),
+    ));
+    lines.push(Line::from(""));
+
+    lines.push(Line::from(Span::styled(
+        "=== Snapshot Cost ===",
+        Styles::emphasis(),
+    )));
+    lines.push(timing_line("Collection", stats.collect_us));
+    lines.push(timing_line("Storage write", stats.storage_write_us));
+    lines.push(Line::from(""));
+
+    lines.push(Line::from(Span::styled(
+        "=== Collection by Subsystem ===",
+        Styles::emphasis(),
+    )));
+    let mut phases: Vec<_> = stats.phases.iter().collect();
+    phases.sort_by_key(|p| std::cmp::Reverse(p.us));
+    for phase in phases {
+        lines.push(timing_line(&format!("  {}", phase.name), phase.us));
+    }
+
+    if !stats.queries.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "=== PostgreSQL Queries ===",
+            Styles::emphasis(),
+        )));
+        for query in &stats.queries {
+            lines.push(timing_line(&format!("  {}", query.name), query.us));
+        }
+    }
+
+    if !stats.warnings.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "=== Warnings ===",
+            Styles::emphasis(),
+        )));
+        for warning in &stats.warnings {
+            lines.push(Line::from(Span::styled(
+                format!("  {warning}"),
+                Styles::modified_item(),
+            )));
+        }
+    }
+    lines
+}
+
+fn timing_line(label: &str, us: u64) -> Line<'static> {
+    let ms = us as f64 / 1000.0;
+    let style = if ms > 100.0 {
+        Styles::error()
+    } else if ms > 10.0 {
+        Styles::modified_item()
+    } else {
+        Styles::popup()
+    };
+    Line::from(vec![
+        Span::styled(format!("{label:20}"), Styles::accent()),
+        Span::styled(format!("{ms:>10.2} ms"), style),
+    ])
+}
+
+fn info_line(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{label:20}"), Styles::accent()),
+        Span::raw(value),
+    ])
+}
@@ -0,0 +1,97 @@
+//! Bookmark list popup (history mode).
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::fmt::format_timestamp;
+use crate::tui::state::{AppState, PopupState};
+use crate::tui::style::Styles;
+
+pub fn render_bookmarks(frame: &mut Frame, area: Rect, state: &AppState) {
+    let PopupState::Bookmarks { cursor } = state.popup else {
+        return;
+    };
+    let Some(store) = state.bookmarks.as_ref() else {
+        return;
+    };
+    let bookmarks = store.list();
+    let current_ts = state.current_snapshot.as_ref().map(|s| s.timestamp);
+
+    let popup_width = (area.width * 60 / 100).clamp(50, 100).min(area.width);
+    let popup_height = (bookmarks.len().max(1) as u16 + 4).min(area.height);
+    let popup_area = Rect::new(
+        area.x + (area.width.saturating_sub(popup_width)) / 2,
+        area.y + (area.height.saturating_sub(popup_height)) / 2,
+        popup_width,
+        popup_height,
+    );
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(format!(" Bookmarks ({}) ", bookmarks.len()))
+        .borders(Borders::ALL)
+        .border_style(Styles::popup_border())
+        .style(Styles::popup());
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).split(inner);
+
+    let lines: Vec<Line> = if bookmarks.is_empty() {
+        vec![Line::from(Span::styled(
+            "No bookmarks yet. Press a to bookmark the current snapshot.",
+            Styles::dim(),
+        ))]
+    } else {
+        bookmarks
+            .iter()
+            .enumerate()
+            .map(|(pos, bookmark)| {
+                let marker = if Some(bookmark.ts) == current_ts {
+                    "▶ "
+                } else {
+                    "  "
+                };
+                let line = Line::from(vec![
+                    Span::raw(marker),
+                    Span::styled(format_ts(bookmark.ts), Styles::accent()),
+                    Span::raw("  "),
+                    Span::raw(bookmark.label.clone()),
+                ]);
+                if pos == cursor {
+                    line.style(Styles::selected())
+                } else {
+                    line
+                }
+            })
+            .collect()
+    };
+    // Keep the cursor visible in long lists
+    let height = chunks[0].height as usize;
+    let scroll = cursor.saturating_sub(height.saturating_sub(1));
+    frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), chunks[0]);
+
+    let footer = vec![
+        Line::from(vec![
+            Span::styled("Enter", Styles::help_key()),
+            Span::styled(" jump  ", Styles::help()),
+            Span::styled("a", Styles::help_key()),
+            Span::styled(" mark current  ", Styles::help()),
+            Span::styled("d", Styles::help_key()),
+            Span::styled(" delete  ", Styles::help()),
+            Span::styled("{/}", Styles::help_key()),
+            Span::styled(" previous/next outside this list", Styles::help()),
+        ]),
+        Line::from(vec![
+            Span::styled("Esc", Styles::help_key()),
+            Span::styled(" close", Styles::help()),
+        ]),
+    ];
+    frame.render_widget(Paragraph::new(footer), chunks[1]);
+}
+
+fn format_ts(ts: i64) -> String {
+    format_timestamp(ts, "%Y-%m-%d %H:%M:%S").unwrap_or_else(|| "----".to_string())
+}
@@ -0,0 +1,102 @@
+//! Buffer cache popup (`%` on PGT/PGI).
+//!
+//! Contents of shared_buffers from the latest `pg_buffercache` sample:
+//! totals and the relations holding the most buffers.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::api::buffercache::BufferCacheReport;
+use crate::fmt::{FmtStyle, format_bytes, format_timestamp};
+use crate::tui::state::{AppState, PopupState};
+use crate::tui::style::Styles;
+
+use super::detail_common::{kv, section};
+
+pub fn render_buffercache(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(" Buffer cache ")
+        .borders(Borders::ALL)
+        .border_style(Styles::popup_border())
+        .style(Styles::popup());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+
+    let content = match &state.buffer_cache {
+        Some(report) => build_content(report),
+        None => vec![
+            Line::raw("No data available"),
+            Line::styled(
+                "pg_buffercache is sampled every 5 minutes in databases that have it installed",
+                Styles::dim(),
+            ),
+        ],
+    };
+    let max_scroll = content.len().saturating_sub(chunks[0].height as usize);
+    let scroll = match &mut state.popup {
+        PopupState::BufferCache { scroll } => {
+            *scroll = (*scroll).min(max_scroll);
+            *scroll
+        }
+        _ => 0,
+    };
+    frame.render_widget(
+        Paragraph::new(content).scroll((scroll as u16, 0)),
+        chunks[0],
+    );
+
+    let footer = Line::from(vec![
+        Span::styled("↑/↓", Styles::help_key()),
+        Span::styled(" scroll  ", Styles::help()),
+        Span::styled("PgUp/PgDn", Styles::help_key()),
+        Span::styled(" page  ", Styles::help()),
+        Span::styled("%/Esc", Styles::help_key()),
+        Span::styled(" close", Styles::help()),
+    ]);
+    frame.render_widget(Paragraph::new(footer), chunks[1]);
+}
+
+fn build_content(report: &BufferCacheReport) -> Vec<Line<'static>> {
+    let bytes = |b: u64| format_bytes(b, FmtStyle::Detail);
+    let mut lines = vec![
+        kv(
+            "sampled",
+            &format_timestamp(report.ts, "%Y-%m-%d %H:%M:%S").unwrap_or_else(|| "-".into()),
+        ),
+        kv("shared_buffers", &bytes(report.total_bytes)),
+        kv(
+            "used",
+            &format!("{} ({:.1}%)", bytes(report.used_bytes), report.used_pct),
+        ),
+        kv("hot (usage ≥ 3)", &format!("{:.1}%", report.hot_pct)),
+        kv("dirty", &bytes(report.dirty_bytes)),
+        Line::raw(""),
+        section("Largest relations"),
+        Line::styled(
+            format!(
+                "{:>10} {:>6} {:>10} {:>5}  {:<5}  RELATION",
+                "SIZE", "%", "DIRTY", "USAGE", "KIND"
+            ),
+            Styles::dim(),
+        ),
+    ];
+    for rel in &report.relations {
+        lines.push(Line::raw(format!(
+            "{:>10} {:>5.1}% {:>10} {:>5.1}  {:<5}  {}.{}.{}",
+            format_bytes(rel.bytes, FmtStyle::Compact),
+            rel.pct_of_cache,
+            format_bytes(rel.dirty_bytes, FmtStyle::Compact),
+            rel.avg_usagecount,
+            rel.kind,
+            rel.database,
+            rel.schema,
+            rel.name
+        )));
+    }
+    lines
+}
@@ -0,0 +1,86 @@
+//! Column chooser popup: show/hide and reorder columns of the current view.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::tui::state::{AppState, PopupState};
+use crate::tui::style::Styles;
+
+pub fn render_column_chooser(frame: &mut Frame, area: Rect, state: &AppState) {
+    let PopupState::ColumnChooser { cursor, .. } = state.popup else {
+        return;
+    };
+    let Some(schema) = state.column_schema.as_ref() else {
+        return;
+    };
+
+    let names: Vec<&str> = schema.headers.iter().map(String::as_str).collect();
+    let layout = state
+        .layouts
+        .get(schema.tab, schema.mode)
+        .cloned()
+        .unwrap_or_default();
+    let ordered = layout.ordered_columns(&names, schema.movable);
+    let cursor = cursor.min(ordered.len().saturating_sub(1));
+
+    let popup_width = (area.width * 40 / 100).clamp(40, 60).min(area.width);
+    let popup_height = (ordered.len() as u16 + 4).min(area.height);
+    let popup_area = Rect::new(
+        area.x + (area.width.saturating_sub(popup_width)) / 2,
+        area.y + (area.height.saturating_sub(popup_height)) / 2,
+        popup_width,
+        popup_height,
+    );
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(format!(" Columns: {} {} ", schema.tab.name(), schema.mode))
+        .borders(Borders::ALL)
+        .border_style(Styles::popup_border())
+        .style(Styles::popup());
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).split(inner);
+
+    let lines: Vec<Line> = ordered
+        .iter()
+        .enumerate()
+        .map(|(pos, &col)| {
+            let name = names[col];
+            let mark = if layout.is_hidden(name) { "[ ]" } else { "[x]" };
+            let mut spans = vec![Span::raw(format!("{mark} {name}"))];
+            if col >= schema.movable {
+                spans.push(Span::styled("  (fills width, stays last)", Styles::dim()));
+            }
+            let line = Line::from(spans);
+            if pos == cursor {
+                line.style(Styles::selected())
+            } else {
+                line
+            }
+        })
+        .collect();
+    // Keep the cursor visible in long lists
+    let height = chunks[0].height as usize;
+    let scroll = cursor.saturating_sub(height.saturating_sub(1));
+    frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), chunks[0]);
+
+    let footer = vec![
+        Line::from(vec![
+            Span::styled("Space", Styles::help_key()),
+            Span::styled(" show/hide  ", Styles::help()),
+            Span::styled("K/J", Styles::help_key()),
+            Span::styled(" move up/down  ", Styles::help()),
+            Span::styled("r", Styles::help_key()),
+            Span::styled(" reset", Styles::help()),
+        ]),
+        Line::from(vec![
+            Span::styled("Esc", Styles::help_key()),
+            Span::styled(" close and save layout", Styles::help()),
+        ]),
+    ];
+    frame.render_widget(Paragraph::new(footer), chunks[1]);
+}
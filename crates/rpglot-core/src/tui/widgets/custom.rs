@@ -0,0 +1,91 @@
+//! User-defined queries (CUS) tab widget.
+//! Thin TUI wrapper over [`crate::view::custom::build_custom_view`].
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::text::Span;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Row, Table};
+
+use crate::storage::model::DataBlock;
+use crate::tui::state::AppState;
+use crate::tui::style::Styles;
+use crate::view::custom::build_custom_view;
+
+pub fn render_custom(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    let queries = state
+        .current_snapshot
+        .as_ref()
+        .and_then(|s| {
+            s.blocks.iter().find_map(|b| match b {
+                DataBlock::CustomQuery(v) => Some(v.clone()),
+                _ => None,
+            })
+        })
+        .unwrap_or_default();
+
+    let mut vm = match build_custom_view(&queries, &state.cus) {
+        Some(vm) => vm,
+        None => {
+            let label = if state.current_snapshot.is_none() {
+                "No data available"
+            } else if queries.is_empty() {
+                "No custom queries (start the collector with --custom-queries)"
+            } else if state.cus.filter.is_some() {
+                "No matching rows (filter active)"
+            } else {
+                "Query returned no rows"
+            };
+            let msg = Paragraph::new(label)
+                .block(Block::default().title("CUS: Custom").borders(Borders::ALL));
+            frame.render_widget(Clear, area);
+            frame.render_widget(msg, area);
+            return;
+        }
+    };
+    state.cus.column_count = vm.headers.len();
+
+    vm.pin_rows(&state.cus.pinned);
+
+    // Resolve selection
+    let row_hashes: Vec<u64> = vm.rows.iter().map(|r| r.id).collect();
+    state.cus.resolve_selection(&row_hashes);
+    state.prepare_table_view(&mut vm, state.cus.selected);
+
+    // Header
+    let header_cells: Vec<Span> = vm
+        .headers
+        .iter()
+        .map(|h| Span::styled(h.clone(), Styles::table_header()))
+        .collect();
+    let header = Row::new(header_cells).style(Styles::table_header());
+
+    let widths: Vec<Constraint> = vm.widths.iter().map(|&w| Constraint::Length(w)).collect();
+
+    // Rows
+    let rows: Vec<Row> = vm
+        .rows
+        .iter()
+        .map(|vr| {
+            let style = Styles::from_class(vr.style);
+            let cells = vr.cells.iter().map(|c| match c.style {
+                Some(s) => Span::styled(c.text.clone(), Styles::from_class(s)),
+                None => Span::raw(c.text.clone()),
+            });
+            Row::new(cells).style(style)
+        })
+        .collect();
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .title(vm.title)
+                .borders(Borders::ALL)
+                .style(Styles::default()),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Styles::selected());
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut state.cus.ratatui_state);
+}
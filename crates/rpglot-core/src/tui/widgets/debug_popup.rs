@@ -4,12 +4,12 @@
 
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
 use crate::collector::CollectorTiming;
 use crate::tui::state::AppState;
+use crate::tui::style::Styles;
 
 /// Renders the debug popup showing collector timing and rates state.
 pub fn render_debug_popup(
@@ -34,7 +34,7 @@ pub fn render_debug_popup(
     // Section: Collector Timing
     lines.push(Line::from(vec![Span::styled(
         "=== Collector Timing ===",
-        Style::default().fg(Color::Yellow),
+        Styles::emphasis(),
     )]));
 
     if let Some(t) = timing {
@@ -49,12 +49,14 @@ pub fn render_debug_popup(
         lines.push(format_timing_line("  PSI", t.psi));
         lines.push(format_timing_line("  Vmstat", t.vmstat));
         lines.push(format_timing_line("  NetSNMP", t.netsnmp));
+        lines.push(format_timing_line("  TCP Conns", t.tcp_conn));
         lines.push(format_timing_line("  PG Activity", t.pg_activity));
         lines.push(format_timing_line("  PG Statements", t.pg_statements));
         lines.push(format_timing_line("  PG Database", t.pg_database));
         lines.push(format_timing_line("  PG Tables", t.pg_tables));
         lines.push(format_timing_line("  PG Indexes", t.pg_indexes));
         lines.push(format_timing_line("  Cgroup", t.cgroup));
+        lines.push(format_timing_line("  Systemd Unit", t.systemd));
         // Show PG statements caching interval
         if let Some(interval) = t.pg_stmts_cache_interval {
             let interval_str = if interval.is_zero() {
@@ -73,7 +75,7 @@ pub fn render_debug_popup(
     // Section: Rates State (PGS)
     lines.push(Line::from(vec![Span::styled(
         "=== PGS Rates State ===",
-        Style::default().fg(Color::Yellow),
+        Styles::emphasis(),
     )]));
 
     lines.push(format_info_line(
@@ -135,12 +137,9 @@ pub fn render_debug_popup(
         lines.push(Line::from(""));
         lines.push(Line::from(vec![Span::styled(
             "=== PostgreSQL Error ===",
-            Style::default().fg(Color::Red),
-        )]));
-        lines.push(Line::from(vec![Span::styled(
-            err.clone(),
-            Style::default().fg(Color::Red),
+            Styles::error(),
         )]));
+        lines.push(Line::from(vec![Span::styled(err.clone(), Styles::error())]));
     }
 
     let paragraph = Paragraph::new(lines)
@@ -148,7 +147,7 @@ pub fn render_debug_popup(
             Block::default()
                 .title(" Debug Info (!) ")
                 .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Cyan)),
+                .style(Styles::accent()),
         )
         .alignment(Alignment::Left);
 
@@ -157,23 +156,23 @@ pub fn render_debug_popup(
 
 fn format_timing_line(label: &str, duration: std::time::Duration) -> Line<'static> {
     let ms = duration.as_secs_f64() * 1000.0;
-    let color = if ms > 100.0 {
-        Color::Red
+    let style = if ms > 100.0 {
+        Styles::error()
     } else if ms > 10.0 {
-        Color::Yellow
+        Styles::modified_item()
     } else {
-        Color::White
+        Styles::popup()
     };
 
     Line::from(vec![
-        Span::styled(format!("{:16}", label), Style::default().fg(Color::Cyan)),
-        Span::styled(format!("{:>8.2} ms", ms), Style::default().fg(color)),
+        Span::styled(format!("{:16}", label), Styles::accent()),
+        Span::styled(format!("{:>8.2} ms", ms), style),
     ])
 }
 
 fn format_info_line(label: &str, value: String) -> Line<'static> {
     Line::from(vec![
-        Span::styled(format!("{:16}", label), Style::default().fg(Color::Cyan)),
+        Span::styled(format!("{:16}", label), Styles::accent()),
         Span::raw(value),
     ])
 }
@@ -2,7 +2,7 @@
 
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::style::{Color, Style};
+use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 
@@ -50,8 +50,8 @@ pub fn render_popup_frame(
     let block = Block::default()
         .title(format!(" {} ", title))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
-        .style(Style::default().fg(Color::White).bg(Color::Black));
+        .border_style(Styles::popup_border())
+        .style(Styles::popup());
 
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -82,7 +82,7 @@ pub fn render_popup_frame(
         *scroll = max_scroll;
     }
 
-    let bg = Style::default().fg(Color::White).bg(Color::Black);
+    let bg = Styles::popup();
 
     let paragraph = Paragraph::new(content)
         .style(bg)
@@ -162,22 +162,22 @@ pub fn push_help(
 /// Style for i64 delta: green (+), red (-), dark gray (0).
 pub fn delta_style(delta: i64) -> Style {
     if delta > 0 {
-        Style::default().fg(Color::Green)
+        Styles::active()
     } else if delta < 0 {
-        Style::default().fg(Color::Red)
+        Styles::error()
     } else {
-        Style::default().fg(Color::DarkGray)
+        Styles::dim()
     }
 }
 
 /// Style for f64 delta: green (+), red (-), dark gray (~0).
 pub fn delta_style_f64(delta: f64) -> Style {
     if delta > 0.0005 {
-        Style::default().fg(Color::Green)
+        Styles::active()
     } else if delta < -0.0005 {
-        Style::default().fg(Color::Red)
+        Styles::error()
     } else {
-        Style::default().fg(Color::DarkGray)
+        Styles::dim()
     }
 }
 
@@ -0,0 +1,228 @@
+//! Entity history popup (history mode, `#` on PGA/PGS).
+//!
+//! Trajectory of one backend PID or queryid over the loaded history:
+//! sparklines of its metrics and, for a PID, the list of state changes.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Sparkline};
+
+use crate::entity::{EntityHistory, EntityKind, EntityPoint};
+use crate::fmt::{
+    FmtStyle, format_bytes_rate, format_kb, format_ms, format_rate, format_timestamp,
+};
+use crate::tui::state::{AppState, PopupState};
+use crate::tui::style::Styles;
+
+use super::detail_common::{kv, section};
+
+/// Height of one sparkline cell (title + 3 rows).
+const SPARK_CELL_HEIGHT: u16 = 4;
+
+pub fn render_entity_history(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    let (kind, id) = match &state.popup {
+        PopupState::EntityHistory { kind, id, .. } => (*kind, *id),
+        _ => return,
+    };
+    let history = state
+        .entity_history
+        .as_ref()
+        .filter(|h| h.kind == kind && h.id == id);
+
+    frame.render_widget(Clear, area);
+    let title = match kind {
+        EntityKind::Pid => format!(" PID {id} history "),
+        EntityKind::Queryid => format!(" Query {id} history "),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Styles::popup_border())
+        .style(Styles::popup());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let spark_height = if inner.height >= 3 * SPARK_CELL_HEIGHT {
+        2 * SPARK_CELL_HEIGHT
+    } else {
+        0
+    };
+    let chunks = Layout::vertical([
+        Constraint::Length(spark_height),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let content = match history {
+        Some(h) => build_content(h),
+        None => vec![Line::raw("No data available")],
+    };
+    let max_scroll = content.len().saturating_sub(chunks[1].height as usize);
+    let scroll = match &mut state.popup {
+        PopupState::EntityHistory { scroll, .. } => {
+            *scroll = (*scroll).min(max_scroll);
+            *scroll
+        }
+        _ => 0,
+    };
+
+    if spark_height > 0 {
+        render_sparklines(frame, chunks[0], kind, history);
+    }
+    frame.render_widget(
+        Paragraph::new(content).scroll((scroll as u16, 0)),
+        chunks[1],
+    );
+
+    let footer = Line::from(vec![
+        Span::styled("↑/↓", Styles::help_key()),
+        Span::styled(" scroll  ", Styles::help()),
+        Span::styled("PgUp/PgDn", Styles::help_key()),
+        Span::styled(" page  ", Styles::help()),
+        Span::styled("#/Esc", Styles::help_key()),
+        Span::styled(" close", Styles::help()),
+    ]);
+    frame.render_widget(Paragraph::new(footer), chunks[2]);
+}
+
+fn build_content(history: &EntityHistory) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    lines.push(kv("label", history.label.as_deref().unwrap_or("-")));
+    let seen = match (history.points.first(), history.points.last()) {
+        (Some(first), Some(last)) => {
+            format!(
+                "{} .. {}",
+                format_ts(first.timestamp),
+                format_ts(last.timestamp)
+            )
+        }
+        _ => "not found in the loaded history".to_string(),
+    };
+    lines.push(kv("seen", &seen));
+    let samples = if history.sampled {
+        format!("{} (sampled)", history.points.len())
+    } else {
+        history.points.len().to_string()
+    };
+    lines.push(kv("samples", &samples));
+    lines.push(Line::raw(""));
+
+    match history.kind {
+        EntityKind::Pid => {
+            lines.push(section("State changes"));
+            for change in &history.changes {
+                let mut spans = vec![
+                    Span::styled(format_ts(change.timestamp), Styles::accent()),
+                    Span::raw("  "),
+                    Span::raw(change.state.clone()),
+                ];
+                if let Some(wait) = &change.wait_event {
+                    spans.push(Span::styled(format!("  wait {wait}"), Styles::dim()));
+                }
+                if let Some(query_id) = change.query_id {
+                    spans.push(Span::styled(format!("  query {query_id}"), Styles::dim()));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        EntityKind::Queryid => {
+            lines.push(section("Samples"));
+            for p in history.points.iter().rev() {
+                lines.push(Line::from(vec![
+                    Span::styled(format_ts(p.timestamp), Styles::accent()),
+                    Span::raw(format!(
+                        "  calls {}/s  time {}/s  mean {}  active {}",
+                        p.calls_s
+                            .map_or("-".to_string(), |v| format_rate(v, FmtStyle::Compact)),
+                        p.exec_time_ms_s
+                            .map_or("-".to_string(), |v| format_ms(v, FmtStyle::Compact)),
+                        p.mean_ms
+                            .map_or("-".to_string(), |v| format_ms(v, FmtStyle::Compact)),
+                        p.active_sessions.map_or("-".to_string(), |v| v.to_string()),
+                    )),
+                ]));
+            }
+        }
+    }
+    lines
+}
+
+/// A sparkline series: title, value extractor, and last-value formatter.
+type Series = (
+    &'static str,
+    fn(&EntityPoint) -> Option<f64>,
+    fn(f64) -> String,
+);
+
+fn render_sparklines(
+    frame: &mut Frame,
+    area: Rect,
+    kind: EntityKind,
+    history: Option<&EntityHistory>,
+) {
+    let series: [Series; 4] = match kind {
+        EntityKind::Pid => [
+            ("cpu %", |p| p.cpu_pct, |v| format!("{v:.1}")),
+            (
+                "rss",
+                |p| p.rss_kb.map(|v| v as f64),
+                |v| format_kb(v as u64),
+            ),
+            (
+                "read/s",
+                |p| p.read_bytes_s,
+                |v| format_bytes_rate(v, FmtStyle::Compact),
+            ),
+            (
+                "write/s",
+                |p| p.write_bytes_s,
+                |v| format_bytes_rate(v, FmtStyle::Compact),
+            ),
+        ],
+        EntityKind::Queryid => [
+            ("calls/s", |p| p.calls_s, |v| format!("{v:.2}")),
+            ("time ms/s", |p| p.exec_time_ms_s, |v| format!("{v:.2}")),
+            ("mean ms", |p| p.mean_ms, |v| format!("{v:.2}")),
+            (
+                "active",
+                |p| p.active_sessions.map(f64::from),
+                |v| format!("{v:.0}"),
+            ),
+        ],
+    };
+
+    let rows = Layout::vertical([Constraint::Length(SPARK_CELL_HEIGHT); 2]).split(area);
+    let top = Layout::horizontal([Constraint::Percentage(50); 2]).split(rows[0]);
+    let bottom = Layout::horizontal([Constraint::Percentage(50); 2]).split(rows[1]);
+    let cells = [top[0], top[1], bottom[0], bottom[1]];
+
+    for ((label, extract, format), cell) in series.into_iter().zip(cells) {
+        let values: Vec<f64> = history
+            .map(|h| h.points.iter().map(|p| extract(p).unwrap_or(0.0)).collect())
+            .unwrap_or_default();
+        let title = match (values.last(), values.iter().copied().reduce(f64::max)) {
+            (Some(&last), Some(max)) => {
+                format!(" {label}: {} (max {}) ", format(last), format(max))
+            }
+            _ => format!(" {label}: no history "),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::TOP)
+            .border_style(Styles::dim());
+        let visible = &values[values.len().saturating_sub(cell.width as usize)..];
+        // Sparkline takes integers; keep two decimals of precision
+        let data: Vec<u64> = visible.iter().map(|v| (v * 100.0) as u64).collect();
+        let sparkline = Sparkline::default()
+            .block(block)
+            .data(&data)
+            .style(Styles::accent());
+        frame.render_widget(sparkline, cell);
+    }
+}
+
+fn format_ts(ts: i64) -> String {
+    format_timestamp(ts, "%Y-%m-%d %H:%M:%S").unwrap_or_else(|| "----".to_string())
+}
@@ -0,0 +1,243 @@
+//! Full-screen metric graph (`g`).
+//!
+//! One metric on the left axis and optionally a second one on the right
+//! axis; the second series is scaled into the left axis bounds and its own
+//! scale is printed next to the chart.
+
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Axis, Block, Borders, Chart, Clear, Dataset, GraphType, Paragraph};
+
+use crate::entity::EntityKind;
+use crate::fmt::format_timestamp;
+use crate::tui::state::{AppState, GraphMetric, downsample};
+use crate::tui::style::Styles;
+
+/// Width of the right-axis label column.
+const RIGHT_AXIS_WIDTH: u16 = 10;
+
+pub fn render_graph(frame: &mut Frame, area: Rect, state: &AppState) {
+    let graph = &state.graph;
+    let primary = graph.primary_metric();
+    let secondary = graph.secondary_metric();
+
+    frame.render_widget(Clear, area);
+    let subject = match graph.entity_history.as_ref() {
+        Some(h) if h.kind == EntityKind::Pid => format!(" · PID {}", h.id),
+        Some(h) => format!(" · query {}", h.id),
+        None => String::new(),
+    };
+    let title = match (&primary, &secondary) {
+        (Some(p), Some(s)) => format!(" Graph: {} vs {}{subject} ", p.label, s.label),
+        (Some(p), None) => format!(" Graph: {}{subject} ", p.label),
+        (None, _) => " Graph ".to_string(),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Styles::popup_border())
+        .style(Styles::popup());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let footer = Line::from(vec![
+        Span::styled("←/→", Styles::help_key()),
+        Span::styled(" cursor  ", Styles::help()),
+        Span::styled("Home/End", Styles::help_key()),
+        Span::styled(" first/last  ", Styles::help()),
+        Span::styled("+/-", Styles::help_key()),
+        Span::styled(" zoom  ", Styles::help()),
+        Span::styled("Tab/⇧Tab", Styles::help_key()),
+        Span::styled(" metric  ", Styles::help()),
+        Span::styled("s", Styles::help_key()),
+        Span::styled(" second axis  ", Styles::help()),
+        Span::styled("g/Esc", Styles::help_key()),
+        Span::styled(" close", Styles::help()),
+    ]);
+    frame.render_widget(Paragraph::new(footer), chunks[2]);
+
+    let Some(primary) = primary else {
+        frame.render_widget(Paragraph::new("No data available"), chunks[0]);
+        return;
+    };
+    let (view_start, view_end) = graph.view();
+    if view_end <= view_start {
+        let message = if state.is_live {
+            "Collecting data: the graph needs at least two snapshots"
+        } else {
+            "No data available"
+        };
+        frame.render_widget(Paragraph::new(message), chunks[0]);
+        return;
+    }
+    let view = (view_start as f64, view_end as f64);
+
+    let (chart_area, right_area) = if secondary.is_some() {
+        let cols = Layout::horizontal([Constraint::Min(10), Constraint::Length(RIGHT_AXIS_WIDTH)])
+            .split(chunks[0]);
+        (cols[0], Some(cols[1]))
+    } else {
+        (chunks[0], None)
+    };
+    // Braille cells hold two points horizontally
+    let max_points = chart_area.width as usize * 2;
+
+    let primary_points = downsample(&graph.series(&primary), view, max_points);
+    let primary_max = axis_max(&primary_points);
+
+    let secondary_data = secondary.map(|metric| {
+        let points = downsample(&graph.series(&metric), view, max_points);
+        let max = axis_max(&points);
+        // Scale into the left axis bounds
+        let scaled: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(x, y)| (x, y / max * primary_max))
+            .collect();
+        (metric, scaled, max)
+    });
+
+    let cursor_ts = graph.cursor_ts().map(|ts| ts.clamp(view_start, view_end));
+    let cursor_line: Vec<(f64, f64)> = cursor_ts
+        .map(|ts| vec![(ts as f64, 0.0), (ts as f64, primary_max)])
+        .unwrap_or_default();
+
+    let mut datasets = vec![
+        Dataset::default()
+            .name(primary.label)
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Styles::accent())
+            .data(&primary_points),
+    ];
+    if let Some((metric, scaled, _)) = &secondary_data {
+        datasets.push(
+            Dataset::default()
+                .name(format!("{} (right)", metric.label))
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Styles::mem())
+                .data(scaled),
+        );
+    }
+    if !cursor_line.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Styles::dim())
+                .data(&cursor_line),
+        );
+    }
+
+    let x_labels = time_labels(view_start, view_end);
+    let y_labels = value_labels(&primary, primary_max);
+    let chart = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .bounds([view.0, view.1])
+                .labels(x_labels)
+                .style(Styles::dim()),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, primary_max])
+                .labels(y_labels)
+                .style(Styles::dim()),
+        );
+    frame.render_widget(chart, chart_area);
+
+    if let (Some(area), Some((metric, _, max))) = (right_area, &secondary_data) {
+        render_right_axis(frame, area, metric, *max);
+    }
+
+    // Cursor readout: values of both metrics at the cursor
+    let mut readout = vec![Span::styled(
+        cursor_ts.map_or("-".to_string(), format_ts),
+        Styles::emphasis(),
+    )];
+    let mut push_value = |metric: &GraphMetric, style| {
+        let value = cursor_ts.and_then(|ts| value_at(&graph.series(metric), ts));
+        readout.push(Span::raw("  "));
+        readout.push(Span::styled(format!("{}: ", metric.label), Styles::dim()));
+        readout.push(Span::styled(
+            value.map_or("-".to_string(), metric.format),
+            style,
+        ));
+    };
+    push_value(&primary, Styles::accent());
+    if let Some(metric) = &secondary {
+        push_value(metric, Styles::mem());
+    }
+    if graph.zoom > 0 {
+        readout.push(Span::styled(
+            format!("  zoom x{}", 1u64 << graph.zoom),
+            Styles::dim(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(readout)), chunks[1]);
+}
+
+/// Upper axis bound: 10% above the largest value, at least 1.
+fn axis_max(points: &[(f64, f64)]) -> f64 {
+    let max = points.iter().map(|(_, y)| *y).fold(0.0, f64::max);
+    if max > 0.0 { max * 1.1 } else { 1.0 }
+}
+
+/// Value of the last point at or before `ts`.
+fn value_at(points: &[(f64, f64)], ts: i64) -> Option<f64> {
+    let idx = points.partition_point(|(x, _)| *x <= ts as f64);
+    idx.checked_sub(1).map(|i| points[i].1)
+}
+
+fn value_labels(metric: &GraphMetric, max: f64) -> Vec<Line<'static>> {
+    [0.0, max / 2.0, max]
+        .into_iter()
+        .map(|v| Line::from((metric.format)(v)))
+        .collect()
+}
+
+fn render_right_axis(frame: &mut Frame, area: Rect, metric: &GraphMetric, max: f64) {
+    if area.height < 3 {
+        return;
+    }
+    // The x-axis line and labels take the two bottom rows of the chart
+    let plot_height = area.height.saturating_sub(2);
+    let mut lines = vec![Line::raw(""); plot_height as usize];
+    let top = 0;
+    let middle = (plot_height / 2) as usize;
+    let bottom = plot_height.saturating_sub(1) as usize;
+    lines[top] = Line::from((metric.format)(max));
+    lines[middle] = Line::from((metric.format)(max / 2.0));
+    lines[bottom] = Line::from((metric.format)(0.0));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Right)
+            .style(Styles::mem()),
+        area,
+    );
+}
+
+fn time_labels(start: i64, end: i64) -> Vec<Line<'static>> {
+    let format = if end - start > 86_400 {
+        "%m-%d %H:%M"
+    } else {
+        "%H:%M:%S"
+    };
+    [start, start + (end - start) / 2, end]
+        .into_iter()
+        .map(|ts| Line::from(format_timestamp(ts, format).unwrap_or_default()))
+        .collect()
+}
+
+fn format_ts(ts: i64) -> String {
+    format_timestamp(ts, "%Y-%m-%d %H:%M:%S").unwrap_or_else(|| "----".to_string())
+}
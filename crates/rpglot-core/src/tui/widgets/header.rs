@@ -1,12 +1,13 @@
 //! Header widget showing time, mode, and tabs.
 
-use chrono::{DateTime, Local, TimeZone};
+use chrono::Local;
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 
-use crate::storage::model::DataBlock;
+use crate::fmt::format_timestamp;
+use crate::storage::model::{DataBlock, DegradedReason};
 use crate::tui::state::{AppState, InputMode, Tab};
 use crate::tui::style::Styles;
 
@@ -16,7 +17,7 @@ pub fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
         Constraint::Length(22), // Time
         Constraint::Length(26), // Mode + version
         Constraint::Min(20),    // Tabs
-        Constraint::Length(16), // PIDs (container)
+        Constraint::Length(16), // Degraded collection / PIDs (container)
         Constraint::Length(42), // Position/Filter/Status
     ])
     .split(area);
@@ -27,11 +28,8 @@ pub fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
         .as_ref()
         .map(|s| s.timestamp)
         .unwrap_or_else(|| Local::now().timestamp());
-    let time_str = Local
-        .timestamp_opt(timestamp, 0)
-        .single()
-        .map(|dt: DateTime<Local>| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-        .unwrap_or_else(|| "----".to_string());
+    let time_str =
+        format_timestamp(timestamp, "%Y-%m-%d %H:%M:%S").unwrap_or_else(|| "----".to_string());
     let time = Paragraph::new(time_str).style(Styles::header());
     frame.render_widget(time, chunks[0]);
 
@@ -43,13 +41,16 @@ pub fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
     };
     let mode_line = Line::from(vec![
         Span::styled(mode_str, Styles::header()),
-        Span::styled(format!(" {}", crate::VERSION), Styles::dim()),
+        match &state.instance {
+            Some(name) => Span::styled(format!(" @{}", name), Styles::emphasis()),
+            None => Span::styled(format!(" {}", crate::VERSION), Styles::dim()),
+        },
     ]);
     let mode = Paragraph::new(mode_line);
     frame.render_widget(mode, chunks[1]);
 
     // Tabs
-    let tabs: Vec<Span> = Tab::all()
+    let mut tabs: Vec<Span> = Tab::all()
         .iter()
         .enumerate()
         .flat_map(|(i, tab)| {
@@ -58,11 +59,18 @@ pub fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
             } else {
                 Styles::tab_inactive()
             };
-            let num = format!(" {}:", i + 1);
+            let num = match tab {
+                Tab::PgSettings => " &:".to_string(),
+                _ => format!(" {}:", i + 1),
+            };
             let name = format!("{} ", tab.name());
             vec![Span::styled(num, Styles::dim()), Span::styled(name, style)]
         })
         .collect();
+    if state.current_tab == Tab::Agent {
+        tabs.push(Span::styled(" ~:", Styles::dim()));
+        tabs.push(Span::styled("AGT ", Styles::tab_active()));
+    }
     let tabs_line = Line::from(tabs);
     let tabs_widget = Paragraph::new(tabs_line).style(Styles::header());
     frame.render_widget(tabs_widget, chunks[2]);
@@ -84,7 +92,21 @@ pub fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
         })
     });
 
-    let (pids_text, pids_style) = if let Some((current, max)) = pids_info {
+    // Throttled collection explains stale PGS/PGP/PGT/PGI data; shown over PIDs
+    let degraded = state.current_snapshot.as_ref().and_then(|snap| {
+        snap.blocks.iter().find_map(|b| match b {
+            DataBlock::CollectionDegraded(info) => Some(info.reason),
+            _ => None,
+        })
+    });
+
+    let (pids_text, pids_style) = if let Some(reason) = degraded {
+        let text = match reason {
+            DegradedReason::SlowCollection => "DEGRADED (slow)",
+            DegradedReason::Pressure => "DEGRADED (PSI)",
+        };
+        (text.to_string(), Styles::modified_item())
+    } else if let Some((current, max)) = pids_info {
         let style = if max > 0 {
             let pct = (current as f64 / max as f64) * 100.0;
             if pct > 95.0 {
@@ -113,6 +135,10 @@ pub fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
         Tab::PgStorePlans => state.pgp.filter.as_deref(),
         Tab::PgErrors => state.pge.filter.as_deref(),
         Tab::PgLocks => state.pgl.filter.as_deref(),
+        Tab::PgRoles => state.pgr.filter.as_deref(),
+        Tab::Custom => state.cus.filter.as_deref(),
+        Tab::PgSettings => state.pgc.filter.as_deref(),
+        Tab::Agent => None,
     };
     let (right_content, right_style) = if let Some(msg) = &state.status_message {
         (msg.clone(), Styles::modified_item())
@@ -122,18 +148,37 @@ pub fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
                 format!("Filter: {}█", state.filter_input),
                 Styles::filter_input(),
             ),
+            InputMode::RowFilter => match &state.row_filter_error {
+                Some(err) => (
+                    format!("Where: {}█ ({err})", state.row_filter_input),
+                    Styles::critical(),
+                ),
+                None => (
+                    format!("Where: {}█", state.row_filter_input),
+                    Styles::filter_input(),
+                ),
+            },
             InputMode::TimeJump => (
                 format!("Jump: {}█", state.time_jump_input),
                 Styles::filter_input(),
             ),
+            InputMode::BookmarkLabel => (
+                format!("Bookmark: {}█", state.bookmark_input),
+                Styles::filter_input(),
+            ),
+            InputMode::Palette => (format!(":{}█", state.palette_input), Styles::filter_input()),
             InputMode::Normal => {
-                let text = if let Some((pos, total)) = state.history_position {
-                    format!("{}/{}", pos + 1, total)
+                let mut parts = Vec::new();
+                if let Some((pos, total)) = state.history_position.or(state.live_buffer_position) {
+                    parts.push(format!("{}/{}", pos + 1, total));
                 } else if let Some(filter) = current_filter {
-                    format!("/{}", filter)
-                } else {
-                    String::new()
-                };
+                    parts.push(format!("/{}", filter));
+                }
+                // Expression filter stays visible in history mode too
+                if let Some(rf) = state.current_row_filter() {
+                    parts.push(format!("\\{}", rf.as_str()));
+                }
+                let text = parts.join(" ");
                 (text, Styles::header())
             }
         }
@@ -2,7 +2,6 @@
 
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 
@@ -10,6 +9,7 @@ use crate::tui::state::{
     PgIndexesViewMode, PgStatementsViewMode, PgStorePlansViewMode, PgTablesViewMode,
     ProcessViewMode, Tab,
 };
+use crate::tui::style::Styles;
 
 /// Renders the help popup centered on screen with scroll support.
 #[allow(clippy::too_many_arguments)]
@@ -50,7 +50,7 @@ pub fn render_help(
     let block = Block::default()
         .title(format!(" {} ", title))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Styles::popup_border());
 
     let inner = block.inner(popup_area);
 
@@ -77,7 +77,7 @@ pub fn render_help(
     let paragraph = Paragraph::new(content)
         .wrap(Wrap { trim: false })
         .scroll((*scroll as u16, 0))
-        .style(Style::default().fg(Color::White));
+        .style(Styles::popup());
     frame.render_widget(paragraph, chunks[0]);
 
     // Render footer with scroll indicator
@@ -87,15 +87,15 @@ pub fn render_help(
         String::new()
     };
     let footer = Paragraph::new(Line::from(vec![
-        Span::styled("Press ", Style::default().fg(Color::DarkGray)),
-        Span::styled("?", Style::default().fg(Color::Yellow)),
-        Span::styled(" or ", Style::default().fg(Color::DarkGray)),
-        Span::styled("H", Style::default().fg(Color::Yellow)),
-        Span::styled(" to close", Style::default().fg(Color::DarkGray)),
-        Span::styled(", ", Style::default().fg(Color::DarkGray)),
-        Span::styled("↑↓", Style::default().fg(Color::Yellow)),
-        Span::styled(" to scroll", Style::default().fg(Color::DarkGray)),
-        Span::styled(scroll_info, Style::default().fg(Color::DarkGray)),
+        Span::styled("Press ", Styles::dim()),
+        Span::styled("?", Styles::emphasis()),
+        Span::styled(" or ", Styles::dim()),
+        Span::styled("H", Styles::emphasis()),
+        Span::styled(" to close", Styles::dim()),
+        Span::styled(", ", Styles::dim()),
+        Span::styled("↑↓", Styles::emphasis()),
+        Span::styled(" to scroll", Styles::dim()),
+        Span::styled(scroll_info, Styles::dim()),
     ]));
     frame.render_widget(footer, chunks[1]);
 }
@@ -109,7 +109,7 @@ fn get_help_content(
     pgt_view_mode: PgTablesViewMode,
     pgi_view_mode: PgIndexesViewMode,
 ) -> (&'static str, Vec<Line<'static>>) {
-    match tab {
+    let (title, mut lines) = match tab {
         Tab::Processes => get_process_help(view_mode),
         Tab::PostgresActive => ("PostgreSQL Activity Help (PGA)", get_postgres_help()),
         Tab::PgStatements => get_pgs_help(pgs_view_mode),
@@ -118,7 +118,117 @@ fn get_help_content(
         Tab::PgIndexes => get_pgi_help(pgi_view_mode),
         Tab::PgErrors => ("PostgreSQL Events Help (PGE)", get_pge_help()),
         Tab::PgLocks => ("PostgreSQL Lock Tree Help (PGL)", get_pgl_help()),
-    }
+        Tab::PgRoles => ("PostgreSQL Workload Help (PGR)", get_pgr_help()),
+        Tab::Custom => ("Custom Queries Help (CUS)", get_custom_help()),
+        Tab::PgSettings => ("PostgreSQL Settings Help (PGC)", get_pgc_help()),
+        Tab::Agent => ("rpglotd Self-Monitoring Help (AGT)", get_agent_help()),
+    };
+    lines.extend(get_table_help());
+    (title, lines)
+}
+
+/// Column filter and column chooser help, shared by all tabs.
+fn get_table_help() -> Vec<Line<'static>> {
+    vec![
+        Line::from(""),
+        Line::from(Span::styled("Column filter (\\):", Styles::emphasis())),
+        Line::from("Press \\ to edit an expression over the visible columns, Enter to apply"),
+        Line::from("  db=prod AND calls_s>10   - column names as in the header, / and % as _"),
+        Line::from("  Operators: = != > >= < <= ~ (contains); AND binds tighter than OR"),
+        Line::from("  Bare words match any column (substring); empty expression clears"),
+        Line::from("Kept per tab, combined with the / filter"),
+        Line::from(""),
+        Line::from(Span::styled("Columns (o):", Styles::emphasis())),
+        Line::from("Press o to show/hide (Space) and reorder (K/J) columns of this view"),
+        Line::from("  Layouts are saved per tab and view mode to ~/.config/rpglot/layout.toml"),
+        Line::from(""),
+        Line::from(Span::styled("Wide rows (h/l, z):", Styles::emphasis())),
+        Line::from("h/l scroll columns left/right; the first column stays pinned"),
+        Line::from("z shows every column of the selected row, one per line"),
+        Line::from("  (Enter does the same on tabs without a detail popup)"),
+        Line::from(""),
+        Line::from(Span::styled("Command palette (:):", Styles::emphasis())),
+        Line::from(": lists every action with its key; type to search, Enter runs it"),
+        Line::from("  Rebind keys in ~/.config/rpglot/keys.toml: graph = \"ctrl-g\""),
+        Line::from("  (action names are shown in the palette; default keys keep working)"),
+        Line::from(""),
+        Line::from(Span::styled("Pinned rows (^):", Styles::emphasis())),
+        Line::from("^ pins the selected row (by PID, queryid, relid, ...) to the top of the"),
+        Line::from("  table across refreshes and sorting; pinned rows are marked with ▸"),
+        Line::from(""),
+        Line::from(Span::styled("Watch mode (*):", Styles::emphasis())),
+        Line::from("* highlights cells that changed since the last refresh (PGA..PGR, CUS, PGC);"),
+        Line::from("  the highlight fades over 3 refreshes. --watch-threshold 10 ignores"),
+        Line::from("  numeric changes under 10%. PRC always marks changed processes"),
+        Line::from(""),
+        Line::from(Span::styled("Export (E):", Styles::emphasis())),
+        Line::from("E writes the visible columns and filtered, sorted rows of this table to"),
+        Line::from("  rpglot_<tab>_<view>_<time>.csv (--export-path, .json for JSON)"),
+        Line::from(""),
+        Line::from(Span::styled("Copy (y):", Styles::emphasis())),
+        Line::from("y on PGA/PGS/PGP copies the full query text of the selected row;"),
+        Line::from("  in the row detail popup (z) it copies every field. Uses OSC 52, so it"),
+        Line::from("  works over SSH (--native-clipboard also runs wl-copy/xclip/pbcopy)"),
+        Line::from(""),
+        Line::from(Span::styled("Theme (L):", Styles::emphasis())),
+        Line::from(
+            "L cycles dark, light, high-contrast and themes from ~/.config/rpglot/themes.toml",
+        ),
+        Line::from(""),
+        Line::from(Span::styled(
+            "PostgreSQL instances (@):",
+            Styles::emphasis(),
+        )),
+        Line::from("@ cycles the instances recorded by rpglotd (shown as @name in the header)"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "rpglotd self-monitoring (~):",
+            Styles::emphasis(),
+        )),
+        Line::from("~ toggles the hidden AGT tab: daemon CPU, memory and collection cost"),
+        Line::from(""),
+        Line::from(Span::styled("Live buffer (live mode):", Styles::emphasis())),
+        Line::from("b/<-/T step back through the last minutes (pauses), f/->/t forward"),
+        Line::from("  Reaching the newest snapshot again resumes live collection"),
+        Line::from("  While rewound f steps forward on PGS/PGP too, not the query view"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Entity history (history mode):",
+            Styles::emphasis(),
+        )),
+        Line::from("# on PGA (selected PID) or PGS (selected queryid) follows it through history"),
+        Line::from("  PID: state changes, CPU, RSS, disk I/O; queryid: calls/s, time/s, mean ms"),
+        Line::from(""),
+        Line::from(Span::styled("Buffer cache (%):", Styles::emphasis())),
+        Line::from("% on PGT/PGI shows what fills shared_buffers: used and hot (usage ≥ 3)"),
+        Line::from("  share, and the relations holding the most buffers. Needs pg_buffercache;"),
+        Line::from("  sampled every 5 minutes, history mode shows the sample at the cursor"),
+        Line::from(""),
+        Line::from(Span::styled("Graph view:", Styles::emphasis())),
+        Line::from("g charts a summary metric (CPU, TPS, sessions, disk, WAL, health) over"),
+        Line::from("  the loaded history; on PRC/PGA/PGS also the selected PID or queryid"),
+        Line::from("  (history mode). Left/Right cursor, +/- zoom, Tab metric, s second axis"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Time navigation (history mode):",
+            Styles::emphasis(),
+        )),
+        Line::from("b jumps to a time: 14:32, 14:32:10, 2026-05-01 09:00, -15m, +1h"),
+        Line::from("  , / . step back/forward by --large-step (15m)"),
+        Line::from(""),
+        Line::from(Span::styled("Timeline (history mode):", Styles::emphasis())),
+        Line::from("Bottom bar colored by health: green >= 80, yellow >= 50, red below"),
+        Line::from("  [/] step one column back/forward, +/- zoom in/out, click to jump"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Bookmarks (history mode):",
+            Styles::emphasis(),
+        )),
+        Line::from("y bookmarks the current snapshot with a label (a in the Y list on"),
+        Line::from("  PGA/PGS/PGP, where y copies), Y lists bookmarks"),
+        Line::from("  {/} jump to the previous/next bookmark; shared with the web UI"),
+        Line::from("  Stored in bookmarks.json in the data directory"),
+    ]
 }
 
 fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>) {
@@ -126,21 +236,25 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
 
     lines.push(Line::from(Span::styled(
         "View modes: t=Time, c=Calls, i=I/O, e=Temp",
-        Style::default().fg(Color::Cyan),
+        Styles::accent(),
+    )));
+    lines.push(Line::from(Span::styled(
+        "f=full-screen query view (SQL, plans, metric history)",
+        Styles::accent(),
     )));
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Filtering: matches queryid (prefix), DB, USER or QUERY (substring)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "Rates: most columns are per-second (/s) computed from deltas between two real samples",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Title indicators:",
-        Style::default().fg(Color::Yellow),
+        Styles::emphasis(),
     )));
     lines.push(Line::from(
         "  dt=Xs  - sample interval; rates are based on this period",
@@ -151,19 +265,19 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Live mode: fresh data every tick (no caching)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "History mode: data reflects daemon's collection (~30s intervals)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "-- means not enough data yet (first sample or after stats reset)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "Sorting: s=next column, r=reverse direction",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(""));
 
@@ -171,7 +285,7 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
         PgStatementsViewMode::Time => {
             lines.push(Line::from(Span::styled(
                 "Columns (Time):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("CALLS/s - executions per second"),
@@ -186,7 +300,7 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("Sort by TIME/s to find queries consuming most CPU time"),
@@ -200,7 +314,7 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
         PgStatementsViewMode::Calls => {
             lines.push(Line::from(Span::styled(
                 "Columns (Calls):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("CALLS/s - executions per second"),
@@ -213,7 +327,7 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("High CALLS/s = hot path, consider caching or batching"),
@@ -225,7 +339,7 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
         PgStatementsViewMode::Io => {
             lines.push(Line::from(Span::styled(
                 "Columns (I/O):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("BLK_RD/s  - shared blocks read per second"),
@@ -239,7 +353,7 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("HIT% < 99% for OLTP = consider increasing shared_buffers"),
@@ -253,7 +367,7 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
         PgStatementsViewMode::Temp => {
             lines.push(Line::from(Span::styled(
                 "Columns (Temp):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("TMP_RD/s, TMP_WR/s - temp blocks read/written per second"),
@@ -267,7 +381,7 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("TMP_WR/s > 0 = query spills to disk, increase work_mem"),
@@ -286,17 +400,14 @@ fn get_pgs_help(mode: PgStatementsViewMode) -> (&'static str, Vec<Line<'static>>
 fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>) {
     match mode {
         ProcessViewMode::Generic => (
-            "Process Help - Generic (g)",
+            "Process Help - Generic (G)",
             vec![
                 Line::from(Span::styled(
-                    "View modes: g=Generic, c=Command, m=Memory",
-                    Style::default().fg(Color::Cyan),
+                    "View modes: G=Generic, c=Command, m=Memory",
+                    Styles::accent(),
                 )),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "Column Descriptions:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("Column Descriptions:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("PID     - Process ID (unique identifier)"),
                 Line::from("SYSCPU  - System (kernel) CPU time in ticks"),
@@ -316,10 +427,7 @@ fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>)
                 Line::from("CPU     - CPU usage percentage over sample interval"),
                 Line::from("CMD     - Process name (executable name)"),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "Troubleshooting Tips:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("Troubleshooting Tips:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("High RDELAY - CPU saturation, processes wait in run queue"),
                 Line::from("  check CPL avg1 vs num_cpus in summary panel"),
@@ -329,29 +437,29 @@ fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>)
                 Line::from("  compare VGROW and RGROW over time for suspect processes"),
                 Line::from("THR growing - thread leak if count increases without bound"),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "PostgreSQL Integration:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("PostgreSQL Integration:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("When process PID matches pg_stat_activity:"),
                 Line::from("  CMD shows: name [query] or name [backend_type]"),
                 Line::from("  (highlighted in cyan, backend_type if query is empty)"),
                 Line::from("Use > or J to drill-down from PRC to PGA for PG processes"),
+                Line::from(""),
+                Line::from(Span::styled("Tree mode (v):", Styles::emphasis())),
+                Line::from(""),
+                Line::from("Rows follow parent PID; postgres children show their role"),
+                Line::from("  (walwriter, checkpointer, client backend user@db)"),
+                Line::from("CPU, MEM, RSIZE and disk rates include all descendants"),
             ],
         ),
         ProcessViewMode::Command => (
             "Process Help - Command (c)",
             vec![
                 Line::from(Span::styled(
-                    "View modes: g=Generic, c=Command, m=Memory",
-                    Style::default().fg(Color::Cyan),
+                    "View modes: G=Generic, c=Command, m=Memory",
+                    Styles::accent(),
                 )),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "Column Descriptions:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("Column Descriptions:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("PID     - Process ID (unique identifier)"),
                 Line::from("TID     - Thread ID (= PID for main thread)"),
@@ -361,15 +469,12 @@ fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>)
                 Line::from("MEM     - Resident memory size (physical memory used)"),
                 Line::from("CMDLINE - Full command line with arguments"),
                 Line::from(""),
-                Line::from(Span::styled("Tip:", Style::default().fg(Color::Yellow))),
+                Line::from(Span::styled("Tip:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("Command view is useful for identifying processes by full path"),
                 Line::from("  e.g. distinguishing multiple java/python/node instances"),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "PostgreSQL Integration:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("PostgreSQL Integration:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("When process PID matches pg_stat_activity:"),
                 Line::from("  CMDLINE shows: cmdline [query] or cmdline [backend_type]"),
@@ -380,14 +485,11 @@ fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>)
             "Process Help - Memory (m)",
             vec![
                 Line::from(Span::styled(
-                    "View modes: g=Generic, c=Command, m=Memory",
-                    Style::default().fg(Color::Cyan),
+                    "View modes: G=Generic, c=Command, m=Memory",
+                    Styles::accent(),
                 )),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "Column Descriptions:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("Column Descriptions:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("PID     - Process ID (unique identifier)"),
                 Line::from("TID     - Thread ID (= PID for main thread)"),
@@ -400,19 +502,16 @@ fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>)
                 Line::from("LOCKSZ  - Memory locked in RAM (mlock)"),
                 Line::from("VSIZE   - Total virtual memory (address space)"),
                 Line::from("RSIZE   - Resident set size (physical RAM used)"),
-                Line::from("PSIZE   - Proportional set size (shared pages divided)"),
+                Line::from("PSIZE   - Proportional set size (shared pages divided; PG only)"),
                 Line::from("VGROW   - Virtual memory growth since last sample"),
                 Line::from("RGROW   - Resident memory growth since last sample"),
                 Line::from("SWAPSZ  - Memory swapped to disk"),
                 Line::from("RUID    - Real user name (who started process)"),
                 Line::from("EUID    - Effective user name (current privileges)"),
-                Line::from("MEM     - Memory usage as % of total system RAM"),
+                Line::from("MEM     - PSS (RSS if not sampled) as % of total system RAM"),
                 Line::from("CMD     - Process name (executable name)"),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "Troubleshooting Tips:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("Troubleshooting Tips:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("MAJFLT > 0 - process pages are being read from swap/disk"),
                 Line::from("  high MAJFLT = severe performance degradation"),
@@ -422,10 +521,7 @@ fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>)
                 Line::from("RGROW rising without VGROW - process is touching more pages"),
                 Line::from("LOCKSZ > 0 - memory pinned in RAM (e.g. shared_buffers huge pages)"),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "PostgreSQL Integration:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("PostgreSQL Integration:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("When process PID matches pg_stat_activity:"),
                 Line::from("  CMD shows: name [query] or name [backend_type]"),
@@ -436,14 +532,11 @@ fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>)
             "Process Help - Disk I/O (d)",
             vec![
                 Line::from(Span::styled(
-                    "View modes: g=Generic, c=Command, m=Memory, d=Disk",
-                    Style::default().fg(Color::Cyan),
+                    "View modes: G=Generic, c=Command, m=Memory, d=Disk",
+                    Styles::accent(),
                 )),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "Column Descriptions:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("Column Descriptions:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("PID     - Process ID (unique identifier)"),
                 Line::from("RDDSK   - Read throughput (bytes/sec from /proc/[pid]/io)"),
@@ -454,22 +547,19 @@ fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>)
                 Line::from("          Non-zero indicates I/O was started but not completed"),
                 Line::from("DSK     - Disk I/O percentage of total system disk activity"),
                 Line::from("          (RDDSK + WRDSK) / total_system_io * 100"),
+                Line::from("LAT     - Mean block I/O request latency since last sample"),
+                Line::from("LAT99   - 99th percentile latency (log2 histogram bucket bound)"),
+                Line::from("          Both need rpglotd --ebpf-blk-latency, otherwise '-'"),
                 Line::from("CMD     - Process name (executable name)"),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "Data Source:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("Data Source:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("Read from /proc/[pid]/io (requires permissions)"),
                 Line::from("  read_bytes  - Total bytes read from storage"),
                 Line::from("  write_bytes - Total bytes written to storage"),
                 Line::from("  cancelled_write_bytes - Truncated page cache writes"),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "PostgreSQL Integration:",
-                    Style::default().fg(Color::Yellow),
-                )),
+                Line::from(Span::styled("PostgreSQL Integration:", Styles::emphasis())),
                 Line::from(""),
                 Line::from("When process PID matches pg_stat_activity:"),
                 Line::from("  CMD shows: name [query] or name [backend_type]"),
@@ -483,19 +573,23 @@ fn get_process_help(mode: ProcessViewMode) -> (&'static str, Vec<Line<'static>>)
 fn get_postgres_help() -> Vec<Line<'static>> {
     vec![
         Line::from(Span::styled(
-            "View Modes (switch with g/v):",
-            Style::default().fg(Color::Cyan),
+            "View Modes (switch with G/v):",
+            Styles::accent(),
         )),
         Line::from(""),
         Line::from(
-            "g = Generic view: PID, CPU%, RSS, DB, USER, STATE, WAIT, QDUR, XDUR, BDUR, BTYPE, QUERY",
+            "G = Generic view: PID, CPU%, RSS, DB, USER, STATE, WAIT, QDUR, XDUR, BDUR, BTYPE, QUERY",
         ),
         Line::from("v = Stats view:   PID, DB, USER, STATE, QDUR, MEAN, MAX, CALL/s, HIT%, QUERY"),
         Line::from("    (Shows pg_stat_statements metrics linked by query_id)"),
+        Line::from(
+            "v = Transactions: PID, DB, USER, STATE, XID, XMIN_AGE, XDUR, QDUR, BTYPE, QUERY",
+        ),
+        Line::from("    (Press v again; only sessions with an open transaction or snapshot)"),
         Line::from(""),
         Line::from(Span::styled(
-            "Generic View Columns (g):",
-            Style::default().fg(Color::Yellow),
+            "Generic View Columns (G):",
+            Styles::emphasis(),
         )),
         Line::from(""),
         Line::from("PID    - PostgreSQL backend process ID"),
@@ -511,10 +605,7 @@ fn get_postgres_help() -> Vec<Line<'static>> {
         Line::from("BTYPE  - Backend type (client backend, autovacuum, etc.)"),
         Line::from("QUERY  - Current/last query text"),
         Line::from(""),
-        Line::from(Span::styled(
-            "Stats View Columns (v):",
-            Style::default().fg(Color::Yellow),
-        )),
+        Line::from(Span::styled("Stats View Columns (v):", Styles::emphasis())),
         Line::from(""),
         Line::from("PID    - PostgreSQL backend process ID"),
         Line::from("DB     - Database name"),
@@ -527,9 +618,20 @@ fn get_postgres_help() -> Vec<Line<'static>> {
         Line::from("HIT%   - Buffer cache hit percentage from pg_stat_statements"),
         Line::from("QUERY  - Current/last query text"),
         Line::from(""),
+        Line::from(Span::styled(
+            "Transactions View Columns (v, v):",
+            Styles::emphasis(),
+        )),
+        Line::from(""),
+        Line::from("XID      - Transaction ID (backend_xid), assigned on the first write"),
+        Line::from("XMIN_AGE - Age of the session's snapshot (age(backend_xmin)) in transactions;"),
+        Line::from("           VACUUM cannot remove rows newer than it"),
+        Line::from("           Yellow > 1M, Red > 10M"),
+        Line::from("XDUR     - Transaction duration (default sort)"),
+        Line::from(""),
         Line::from(Span::styled(
             "Stats View Anomaly Highlighting:",
-            Style::default().fg(Color::Yellow),
+            Styles::emphasis(),
         )),
         Line::from(""),
         Line::from("Yellow - QDUR > 2× MEAN (slower than usual)"),
@@ -539,22 +641,16 @@ fn get_postgres_help() -> Vec<Line<'static>> {
         Line::from("Red    - HIT% < 50% (excessive disk reads)"),
         Line::from("'--'   - No stats (query_id=0 or not in pg_stat_statements)"),
         Line::from(""),
-        Line::from(Span::styled(
-            "Navigation:",
-            Style::default().fg(Color::Yellow),
-        )),
+        Line::from(Span::styled("Navigation:", Styles::emphasis())),
         Line::from(""),
         Line::from("> or J - Drill-down to PGS (pg_stat_statements) for selected query"),
         Line::from("         (requires query_id, available in PostgreSQL 14+)"),
         Line::from(""),
-        Line::from(Span::styled("Sorting:", Style::default().fg(Color::Yellow))),
+        Line::from(Span::styled("Sorting:", Styles::emphasis())),
         Line::from("Default: non-idle sessions first, sorted by QDUR desc"),
         Line::from("Use s/S to cycle sort column, r/R to reverse"),
         Line::from(""),
-        Line::from(Span::styled(
-            "Filtering:",
-            Style::default().fg(Color::Yellow),
-        )),
+        Line::from(Span::styled("Filtering:", Styles::emphasis())),
         Line::from("Press / or p to filter by PID, query_id, DB, USER, or QUERY"),
         Line::from("  - PID and query_id: prefix match (e.g., '123' matches '12345')"),
         Line::from("  - Text fields: substring match (case-insensitive)"),
@@ -562,17 +658,14 @@ fn get_postgres_help() -> Vec<Line<'static>> {
         Line::from(""),
         Line::from(Span::styled(
             "Color coding (Generic view):",
-            Style::default().fg(Color::Yellow),
+            Styles::emphasis(),
         )),
         Line::from("Green  - active state"),
         Line::from("Yellow - idle in transaction, QDUR > 1min, WAIT event"),
         Line::from("Red    - QDUR > 5min (for active queries)"),
         Line::from("Gray   - idle sessions (shown at bottom)"),
         Line::from(""),
-        Line::from(Span::styled(
-            "Troubleshooting Tips:",
-            Style::default().fg(Color::Yellow),
-        )),
+        Line::from(Span::styled("Troubleshooting Tips:", Styles::emphasis())),
         Line::from(""),
         Line::from("idle in transaction - holds locks, blocks autovacuum/vacuum"),
         Line::from("  long idle-in-transaction = danger of table bloat"),
@@ -585,10 +678,7 @@ fn get_postgres_help() -> Vec<Line<'static>> {
         Line::from("BDUR very long - consider connection pooling (pgbouncer)"),
         Line::from("  long-lived connections use resources even when idle"),
         Line::from(""),
-        Line::from(Span::styled(
-            "Session Detail Popup:",
-            Style::default().fg(Color::Yellow),
-        )),
+        Line::from(Span::styled("Session Detail Popup:", Styles::emphasis())),
         Line::from("Press Enter to open detailed view with:"),
         Line::from("- Session Identity (PID, DB, User, App, Client, Backend Type)"),
         Line::from("- Timing (start times, durations)"),
@@ -604,28 +694,36 @@ fn get_pgp_help(mode: PgStorePlansViewMode) -> (&'static str, Vec<Line<'static>>
 
     lines.push(Line::from(Span::styled(
         "View modes: t=Time, i=I/O, e=Regression",
-        Style::default().fg(Color::Cyan),
+        Styles::accent(),
+    )));
+    lines.push(Line::from(Span::styled(
+        "f=full-screen query view for the parent query (SQL, plans, history)",
+        Styles::accent(),
+    )));
+    lines.push(Line::from(Span::styled(
+        "|=diff with the most-called other plan of the same query",
+        Styles::accent(),
     )));
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Data source: pg_store_plans extension (plan-level statistics)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "Filtering: matches planid, queryid (prefix), DB or PLAN (substring)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "Rates: per-second (/s) computed from cumulative counter deltas",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "-- means not enough data yet (first sample or after stats reset)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "Sorting: s=next column, r=reverse direction",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(""));
 
@@ -633,7 +731,7 @@ fn get_pgp_help(mode: PgStorePlansViewMode) -> (&'static str, Vec<Line<'static>>
         PgStorePlansViewMode::Time => {
             lines.push(Line::from(Span::styled(
                 "Columns (Time):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("CALLS/s - plan executions per second"),
@@ -648,7 +746,7 @@ fn get_pgp_help(mode: PgStorePlansViewMode) -> (&'static str, Vec<Line<'static>>
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("Sort by TIME/s to find plans consuming most CPU time"),
@@ -660,7 +758,7 @@ fn get_pgp_help(mode: PgStorePlansViewMode) -> (&'static str, Vec<Line<'static>>
         PgStorePlansViewMode::Io => {
             lines.push(Line::from(Span::styled(
                 "Columns (I/O):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("CALLS/s   - plan executions per second"),
@@ -674,7 +772,7 @@ fn get_pgp_help(mode: PgStorePlansViewMode) -> (&'static str, Vec<Line<'static>>
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("HIT% < 99% for OLTP = consider increasing shared_buffers"),
@@ -686,7 +784,7 @@ fn get_pgp_help(mode: PgStorePlansViewMode) -> (&'static str, Vec<Line<'static>>
         PgStorePlansViewMode::Regression => {
             lines.push(Line::from(Span::styled(
                 "Columns (Regression):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("CALLS/s - plan executions per second"),
@@ -701,7 +799,7 @@ fn get_pgp_help(mode: PgStorePlansViewMode) -> (&'static str, Vec<Line<'static>>
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("High RATIO = plan regression detected"),
@@ -719,24 +817,24 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
 
     lines.push(Line::from(Span::styled(
         "View modes: a=Reads, w=Writes, x=Scans, n=Maintenance, i=I/O",
-        Style::default().fg(Color::Cyan),
+        Styles::accent(),
     )));
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Filtering: matches schema or table name (substring)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "Rates: per-second (/s) computed from cumulative counter deltas",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "-- means not enough data yet (first sample or after stats reset)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "Sorting: s=next column, r=reverse direction",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(""));
 
@@ -744,7 +842,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
         PgTablesViewMode::Reads => {
             lines.push(Line::from(Span::styled(
                 "Columns (Reads):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("SEQ_RD/s - rows read by sequential scans per second"),
@@ -760,7 +858,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("High SEQ_RD/s + large SIZE = heavy seq reads, check indexes"),
@@ -771,7 +869,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Color coding:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("Red    - dead% > 20% (severe bloat)"),
@@ -782,7 +880,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
         PgTablesViewMode::Writes => {
             lines.push(Line::from(Span::styled(
                 "Columns (Writes):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("INS/s  - rows inserted per second"),
@@ -799,7 +897,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("DEAD >> LIVE = autovacuum falling behind"),
@@ -810,7 +908,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Color coding:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("Red    - dead% > 20% (severe bloat)"),
@@ -821,7 +919,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
         PgTablesViewMode::Scans => {
             lines.push(Line::from(Span::styled(
                 "Columns (Scans):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("SEQ/s     - sequential scans per second"),
@@ -838,7 +936,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("SEQ% > 80% on large table = almost certainly needs an index"),
@@ -850,7 +948,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
         PgTablesViewMode::Maintenance => {
             lines.push(Line::from(Span::styled(
                 "Columns (Maintenance):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("DEAD      - estimated dead tuples"),
@@ -865,7 +963,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("DEAD% > 20% = severe bloat, use pg_repack/pgcompacttable"),
@@ -877,7 +975,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Color coding:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("Red    - dead% > 20% (critical bloat)"),
@@ -888,7 +986,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
         PgTablesViewMode::Io => {
             lines.push(Line::from(Span::styled(
                 "Columns (I/O):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("HEAP_RD/s  - heap disk read throughput in bytes/s (blocks × 8 KB)"),
@@ -903,7 +1001,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("HIT% < 90% = significant disk I/O, consider shared_buffers"),
@@ -915,7 +1013,7 @@ fn get_pgt_help(mode: PgTablesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Color coding:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("Red    - HIT% < 70% (critical, heavy disk I/O)"),
@@ -931,16 +1029,16 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
 
     lines.push(Line::from(Span::styled(
         "View modes: u=Usage, w=Unused, i=I/O",
-        Style::default().fg(Color::Cyan),
+        Styles::accent(),
     )));
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Filtering: matches schema, table, or index name (substring)",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(Span::styled(
         "Sorting: s=next column, r=reverse direction",
-        Style::default().fg(Color::DarkGray),
+        Styles::dim(),
     )));
     lines.push(Line::from(""));
 
@@ -948,7 +1046,7 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
         PgIndexesViewMode::Usage => {
             lines.push(Line::from(Span::styled(
                 "Columns (Usage):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("IDX/s     - index scans per second"),
@@ -963,7 +1061,7 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("TUP_RD/s >> TUP_FT/s = many dead/invisible tuples in index"),
@@ -974,7 +1072,7 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Color coding:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([Line::from(
                 "Yellow - idx_scan = 0 (unused index, wasting resources)",
@@ -984,7 +1082,7 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
         PgIndexesViewMode::Unused => {
             lines.push(Line::from(Span::styled(
                 "Columns (Unused):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("IDX_SCAN - total scans (cumulative, since stats reset)"),
@@ -996,7 +1094,7 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("IDX_SCAN = 0 = index never used since stats reset"),
@@ -1010,7 +1108,7 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Color coding:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([Line::from("Yellow - idx_scan = 0 (unused index)")]);
             ("PostgreSQL Indexes Help (PGI) - Unused (w)", lines)
@@ -1018,7 +1116,7 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
         PgIndexesViewMode::Io => {
             lines.push(Line::from(Span::styled(
                 "Columns (I/O):",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("IDX_RD/s  - index disk read throughput in bytes/s (blocks × 8 KB)"),
@@ -1032,7 +1130,7 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Troubleshooting Tips:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("HIT% < 90% = index not fitting in shared_buffers"),
@@ -1044,7 +1142,7 @@ fn get_pgi_help(mode: PgIndexesViewMode) -> (&'static str, Vec<Line<'static>>) {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Color coding:",
-                Style::default().fg(Color::Yellow),
+                Styles::emphasis(),
             )));
             lines.extend([
                 Line::from("Red    - HIT% < 70% (critical, heavy disk I/O)"),
@@ -1059,31 +1157,45 @@ fn get_pge_help() -> Vec<Line<'static>> {
     vec![
         Line::from(Span::styled(
             "PG Events: PostgreSQL log events (errors, checkpoints, autovacuum)",
-            Style::default().fg(Color::Cyan),
+            Styles::accent(),
         )),
         Line::from(""),
         Line::from(Span::styled(
             "Data source: PostgreSQL stderr log parsing",
-            Style::default().fg(Color::DarkGray),
+            Styles::dim(),
         )),
         Line::from(Span::styled(
-            "Errors are accumulated within the current hour",
-            Style::default().fg(Color::DarkGray),
+            "Errors and events are accumulated within the hour, up to the current snapshot",
+            Styles::dim(),
         )),
         Line::from(""),
-        Line::from(Span::styled("Columns:", Style::default().fg(Color::Yellow))),
+        Line::from(Span::styled("Errors view columns:", Styles::emphasis())),
         Line::from("SEVERITY  - ERROR, FATAL, or PANIC"),
         Line::from("COUNT     - number of occurrences in current hour"),
         Line::from("PATTERN   - normalized error pattern"),
         Line::from("SAMPLE    - one concrete example of the error message"),
         Line::from(""),
-        Line::from(Span::styled(
-            "Color coding:",
-            Style::default().fg(Color::Yellow),
-        )),
+        Line::from(Span::styled("Events view columns:", Styles::emphasis())),
+        Line::from("TIME      - snapshot time the event was logged in"),
+        Line::from("TYPE      - checkpoint, autovacuum, autoanalyze, slow query"),
+        Line::from("ELAPSED   - checkpoint/vacuum time or query duration"),
+        Line::from("COUNT     - occurrences (slow queries grouped by SQL)"),
+        Line::from("TABLE     - vacuumed/analyzed table"),
+        Line::from("MESSAGE   - log message"),
+        Line::from(""),
+        Line::from(Span::styled("Color coding:", Styles::emphasis())),
         Line::from("Red bold  - PANIC (database crash)"),
         Line::from("Red       - FATAL (connection terminated)"),
-        Line::from("Yellow    - ERROR (query failed)"),
+        Line::from("Yellow    - ERROR (query failed) / slow query"),
+        Line::from("Cyan      - checkpoint"),
+        Line::from(""),
+        Line::from(Span::styled("Navigation:", Styles::emphasis())),
+        Line::from("v      - toggle Errors / Events view"),
+        Line::from("x      - expand selected row (sample + STATEMENT, or full message)"),
+        Line::from("Enter  - open detail popup for selected error pattern"),
+        Line::from("/      - filter by pattern, sample, severity, table or type"),
+        Line::from("<- ->  - move through history (or the live buffer)"),
+        Line::from("?      - toggle this help"),
     ]
 }
 
@@ -1091,19 +1203,19 @@ fn get_pgl_help() -> Vec<Line<'static>> {
     vec![
         Line::from(Span::styled(
             "Lock Tree: shows PostgreSQL blocking chains",
-            Style::default().fg(Color::Cyan),
+            Styles::accent(),
         )),
         Line::from(""),
         Line::from(Span::styled(
             "Data source: pg_locks + pg_stat_activity + pg_blocking_pids()",
-            Style::default().fg(Color::DarkGray),
+            Styles::dim(),
         )),
         Line::from(Span::styled(
             "Empty table means no blocking chains detected",
-            Style::default().fg(Color::DarkGray),
+            Styles::dim(),
         )),
         Line::from(""),
-        Line::from(Span::styled("Columns:", Style::default().fg(Color::Yellow))),
+        Line::from(Span::styled("Columns:", Styles::emphasis())),
         Line::from("PID       - PostgreSQL backend PID with depth indentation"),
         Line::from("            dots indicate nesting: .456 = blocked by parent"),
         Line::from("STATE     - backend state (active, idle in transaction, etc.)"),
@@ -1113,27 +1225,18 @@ fn get_pgl_help() -> Vec<Line<'static>> {
         Line::from("TARGET    - locked object (schema.table or lock type)"),
         Line::from("QUERY     - current/last query text"),
         Line::from(""),
-        Line::from(Span::styled(
-            "Color coding:",
-            Style::default().fg(Color::Yellow),
-        )),
+        Line::from(Span::styled("Color coding:", Styles::emphasis())),
         Line::from("Red    - root blocker (depth=1, holds the lock)"),
         Line::from("Yellow - waiting session (lock not yet granted)"),
         Line::from("Default- intermediate session (lock granted)"),
         Line::from(""),
-        Line::from(Span::styled(
-            "Navigation:",
-            Style::default().fg(Color::Yellow),
-        )),
+        Line::from(Span::styled("Navigation:", Styles::emphasis())),
         Line::from("Enter  - open detail popup for selected row"),
         Line::from("> or J - drill-down to PGA for selected PID"),
         Line::from("/      - filter by PID, query, target, or state"),
         Line::from("?      - toggle this help"),
         Line::from(""),
-        Line::from(Span::styled(
-            "Troubleshooting Tips:",
-            Style::default().fg(Color::Yellow),
-        )),
+        Line::from(Span::styled("Troubleshooting Tips:", Styles::emphasis())),
         Line::from(""),
         Line::from("Red row = root blocker causing the chain"),
         Line::from("  check its QUERY and DURATION to understand the cause"),
@@ -1146,3 +1249,148 @@ fn get_pgl_help() -> Vec<Line<'static>> {
         Line::from("  application forgot to COMMIT or ROLLBACK"),
     ]
 }
+
+fn get_custom_help() -> Vec<Line<'static>> {
+    vec![
+        Line::from(Span::styled(
+            "Custom: results of user-defined SQL metric queries",
+            Styles::accent(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Data source: queries from the --custom-queries TOML file",
+            Styles::dim(),
+        )),
+        Line::from(Span::styled(
+            "Each query runs at its own interval; columns come from its declaration",
+            Styles::dim(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Columns:", Styles::emphasis())),
+        Line::from("key   - row label (text or integer)"),
+        Line::from("int   - integer value"),
+        Line::from("float - floating point value"),
+        Line::from("--    - NULL or missing column"),
+        Line::from(""),
+        Line::from(Span::styled("Navigation:", Styles::emphasis())),
+        Line::from("v      - show the next query"),
+        Line::from("Enter  - show every column of the selected row"),
+        Line::from("s/r    - change sort column / direction"),
+        Line::from("/      - filter by key column values"),
+        Line::from("?      - toggle this help"),
+    ]
+}
+
+fn get_pgc_help() -> Vec<Line<'static>> {
+    vec![
+        Line::from(Span::styled(
+            "Settings: server configuration from pg_settings",
+            Styles::accent(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Data source: pg_settings, re-read hourly and after a configuration reload",
+            Styles::dim(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Columns:", Styles::emphasis())),
+        Line::from("CATEGORY - pg_settings.category (rows are grouped by it)"),
+        Line::from("SETTING  - current value in base units (see UNIT)"),
+        Line::from("SOURCE   - where the value comes from (default, configuration file, ...)"),
+        Line::from("DEFAULT  - built-in default (boot_val)"),
+        Line::from(""),
+        Line::from(Span::styled("Highlighting:", Styles::emphasis())),
+        Line::from("cyan   - set by the configuration (non-default source)"),
+        Line::from("yellow - changed in the config files, waits for a restart"),
+        Line::from(""),
+        Line::from(Span::styled("Navigation:", Styles::emphasis())),
+        Line::from("&      - open this tab"),
+        Line::from("v      - toggle all / non-default settings"),
+        Line::from("Enter  - show every column of the selected row"),
+        Line::from("s/r    - change sort column / direction"),
+        Line::from("/      - filter by name, category or value"),
+        Line::from("?      - toggle this help"),
+    ]
+}
+
+fn get_agent_help() -> Vec<Line<'static>> {
+    vec![
+        Line::from(Span::styled(
+            "Agent: resource usage of rpglotd itself",
+            Styles::accent(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Data source: AgentStats block written by rpglotd into every snapshot",
+            Styles::dim(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Fields:", Styles::emphasis())),
+        Line::from("CPU           - daemon CPU time since the previous snapshot (% of one core)"),
+        Line::from("RSS           - resident memory, peak since start"),
+        Line::from("Collection    - time to collect this snapshot, per subsystem below"),
+        Line::from("Storage write - time to write the previous snapshot (WAL, chunk flush)"),
+        Line::from(""),
+        Line::from(Span::styled("Navigation:", Styles::emphasis())),
+        Line::from("~      - return to the previous tab"),
+        Line::from("?      - toggle this help"),
+    ]
+}
+
+fn get_pgr_help() -> Vec<Line<'static>> {
+    vec![
+        Line::from(Span::styled(
+            "Workload: sessions and statement load per role, application or client host",
+            Styles::accent(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Data source: pg_stat_activity + pg_stat_statements",
+            Styles::dim(),
+        )),
+        Line::from(Span::styled(
+            "Applications: statements attributed via query_id of their sessions (PG 14+)",
+            Styles::dim(),
+        )),
+        Line::from(Span::styled(
+            "Clients: TCP connections to the PG port (/proc/net/tcp) + sessions by client_addr",
+            Styles::dim(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Columns:", Styles::emphasis())),
+        Line::from("USER     - role name (roles view)"),
+        Line::from("APPLICATION - application_name (applications view)"),
+        Line::from("SESS     - connected backends"),
+        Line::from("ACTIVE   - backends in state active"),
+        Line::from("IDLE_TX  - backends idle in transaction"),
+        Line::from("WAIT     - backends waiting on a lock"),
+        Line::from("ACT_TIME - total running time of active queries"),
+        Line::from("STMTS    - pg_stat_statements entries of the group"),
+        Line::from("CALLS/s  - calls per second, summed over statements"),
+        Line::from("TIME/s   - execution time per second (ms/s)"),
+        Line::from("ROWS/s   - rows returned per second"),
+        Line::from(
+            "RMT_TIME - part of ACT_TIME waiting for a foreign server (postgres_fdw, dblink)",
+        ),
+        Line::from("CLIENT   - client IP, [local] for Unix sockets (clients view)"),
+        Line::from("ESTAB / SYN_RECV / TIME_WAIT / OTHER - TCP connections by state"),
+        Line::from(""),
+        Line::from(Span::styled("Color coding:", Styles::emphasis())),
+        Line::from("Yellow - group has sessions waiting on a lock"),
+        Line::from("Green  - group has active sessions"),
+        Line::from(""),
+        Line::from(Span::styled("Navigation:", Styles::emphasis())),
+        Line::from("v      - cycle roles / applications / clients view"),
+        Line::from("Enter  - show every column of the selected row"),
+        Line::from("s/r    - change sort column / direction"),
+        Line::from("/      - filter by role or application name"),
+        Line::from("?      - toggle this help"),
+        Line::from(""),
+        Line::from(Span::styled("Troubleshooting Tips:", Styles::emphasis())),
+        Line::from(""),
+        Line::from("Sort by TIME/s to find the role or application loading the server"),
+        Line::from("High IDLE_TX = application holds transactions open"),
+        Line::from("SESS close to max_connections = check pooler settings"),
+        Line::from("Many SYN_RECV or TIME_WAIT from one host = connection storm, no pooling"),
+    ]
+}
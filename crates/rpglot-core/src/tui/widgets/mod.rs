@@ -1,11 +1,20 @@
 //! TUI widgets for rpglot.
 
+mod agent;
+mod bookmarks;
+mod buffercache;
+mod column_chooser;
+mod custom;
 mod debug_popup;
 pub mod detail_common;
+mod entity_history;
+mod graph;
 mod header;
 mod help;
+mod palette;
 mod pga;
 mod pga_detail;
+mod pgc;
 mod pge;
 mod pge_detail;
 mod pgi;
@@ -14,21 +23,35 @@ mod pgl;
 mod pgl_detail;
 mod pgp;
 mod pgp_detail;
+mod pgr;
 mod pgs;
 mod pgs_detail;
 mod pgt;
 mod pgt_detail;
+mod plan_diff;
 pub mod prc;
 mod prc_detail;
+mod query_view;
 mod quit_confirm;
+mod row_detail;
 pub mod summary;
 mod time_jump;
+mod timeline;
 
+pub use agent::render_agent;
+pub use bookmarks::render_bookmarks;
+pub use buffercache::render_buffercache;
+pub use column_chooser::render_column_chooser;
+pub use custom::render_custom;
 pub use debug_popup::render_debug_popup;
+pub use entity_history::render_entity_history;
+pub use graph::render_graph;
 pub use header::render_header;
 pub use help::render_help;
+pub use palette::render_palette;
 pub use pga::render_postgres;
 pub use pga_detail::render_pg_detail;
+pub use pgc::render_pg_settings;
 pub use pge::render_pg_errors;
 pub use pge_detail::render_pge_detail;
 pub use pgi::render_pg_indexes;
@@ -37,12 +60,17 @@ pub use pgl::render_pg_locks;
 pub use pgl_detail::render_pgl_detail;
 pub use pgp::render_pg_store_plans;
 pub use pgp_detail::render_pgp_detail;
+pub use pgr::render_pg_roles;
 pub use pgs::render_pg_statements;
 pub use pgs_detail::render_pgs_detail;
 pub use pgt::render_pg_tables;
 pub use pgt_detail::render_pgt_detail;
+pub use plan_diff::render_plan_diff;
 pub use prc::render_processes;
 pub use prc_detail::render_process_detail;
+pub use query_view::render_query_view;
 pub use quit_confirm::render_quit_confirm;
-pub use summary::{calculate_summary_height, render_summary};
+pub use row_detail::render_row_detail;
+pub use summary::{calculate_summary_height, plain_summary, render_summary};
 pub use time_jump::render_time_jump;
+pub use timeline::render_timeline;
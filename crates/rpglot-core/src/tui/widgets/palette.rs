@@ -0,0 +1,94 @@
+//! Command palette popup (`:`).
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::tui::keymap::search_actions;
+use crate::tui::state::AppState;
+use crate::tui::style::Styles;
+
+/// Width of the key column.
+const KEY_WIDTH: usize = 16;
+
+pub fn render_palette(frame: &mut Frame, area: Rect, state: &AppState) {
+    let matches = search_actions(&state.palette_input);
+
+    let popup_width = (area.width * 60 / 100).clamp(50, 90).min(area.width);
+    let popup_height = (matches.len().max(1) as u16 + 5).min(20).min(area.height);
+    let popup_area = Rect::new(
+        area.x + (area.width.saturating_sub(popup_width)) / 2,
+        area.y + (area.height.saturating_sub(popup_height)) / 3,
+        popup_width,
+        popup_height,
+    );
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Command palette ")
+        .borders(Borders::ALL)
+        .border_style(Styles::popup_border())
+        .style(Styles::popup());
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let prompt = Line::from(vec![
+        Span::styled(": ", Styles::emphasis()),
+        Span::styled(state.palette_input.as_str(), Styles::help_key()),
+        Span::styled("_", Styles::dim()),
+    ]);
+    frame.render_widget(Paragraph::new(prompt), chunks[0]);
+
+    let lines: Vec<Line> = if matches.is_empty() {
+        vec![Line::from(Span::styled(
+            "No matching actions",
+            Styles::dim(),
+        ))]
+    } else {
+        matches
+            .iter()
+            .enumerate()
+            .map(|(pos, action)| {
+                let line = Line::from(vec![
+                    Span::styled(
+                        format!(" {:<width$}", state.keymap.label(action), width = KEY_WIDTH),
+                        Styles::help_key(),
+                    ),
+                    Span::raw(action.description),
+                    Span::styled(format!("  {}", action.name), Styles::dim()),
+                ]);
+                if pos == state.palette_cursor {
+                    line.style(Styles::selected())
+                } else {
+                    line
+                }
+            })
+            .collect()
+    };
+    // Keep the cursor visible in long lists
+    let height = chunks[1].height as usize;
+    let scroll = state
+        .palette_cursor
+        .saturating_sub(height.saturating_sub(1));
+    frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), chunks[1]);
+
+    let footer = Line::from(vec![
+        Span::styled("Enter", Styles::help_key()),
+        Span::styled(" run  ", Styles::help()),
+        Span::styled("↑↓", Styles::help_key()),
+        Span::styled(" select  ", Styles::help()),
+        Span::styled("Esc", Styles::help_key()),
+        Span::styled(" close  ", Styles::help()),
+        Span::styled("keys.toml", Styles::help_key()),
+        Span::styled(" rebinds actions", Styles::help()),
+    ]);
+    frame.render_widget(Paragraph::new(footer), chunks[2]);
+}
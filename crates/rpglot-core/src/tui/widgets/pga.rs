@@ -31,7 +31,7 @@ pub fn render_postgres(
         }
     };
 
-    let vm = match build_activity_view(snapshot, &state.pga, &state.pgs, interner) {
+    let mut vm = match build_activity_view(snapshot, &state.pga, &state.pgs, interner) {
         Some(vm) => vm,
         None => {
             let block = Block::default()
@@ -49,9 +49,12 @@ pub fn render_postgres(
         }
     };
 
+    vm.pin_rows(&state.pga.pinned);
+
     // Resolve selection
     let row_pids: Vec<i32> = vm.rows.iter().map(|r| r.id).collect();
     state.pga.resolve_selection(&row_pids);
+    state.prepare_table_view(&mut vm, state.pga.selected);
 
     // Header with sort indicator
     let headers: Vec<Span> = vm
@@ -96,7 +99,10 @@ pub fn render_postgres(
         .iter()
         .map(|&w| ratatui::layout::Constraint::Length(w))
         .collect();
-    widths.push(ratatui::layout::Constraint::Fill(1));
+    widths.extend(std::iter::repeat_n(
+        ratatui::layout::Constraint::Fill(1),
+        vm.headers.len() - vm.widths.len(),
+    ));
 
     let table = Table::new(rows, widths)
         .header(header)
@@ -3,12 +3,13 @@
 
 use ratatui::Frame;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 
 use crate::storage::StringInterner;
 use crate::storage::model::{DataBlock, PgStatActivityInfo, ProcessInfo, Snapshot};
 use crate::tui::state::{AppState, PopupState};
+use crate::tui::style::Styles;
 
 use super::detail_common::{
     format_bytes, format_bytes_rate, format_duration, format_duration_or_none, format_kb,
@@ -131,9 +132,13 @@ const HELP: &[(&str, &str)] = &[
         "Resident (RSS)",
         "Physical pages currently in RAM; for PostgreSQL backends includes shared_buffers pages",
     ),
+    (
+        "Proportional (PSS)",
+        "RSS with each shared page divided among the processes mapping it; the backend's real memory cost (sampled for the largest PostgreSQL processes)",
+    ),
     (
         "Shared Memory",
-        "Shared pages (PSS-based); largely shared_buffers + other shared memory segments",
+        "Resident pages shared with other processes; largely shared_buffers",
     ),
     (
         "Swap",
@@ -226,7 +231,7 @@ pub fn render_pg_detail(
     // Calculate time interval between snapshots
     let interval_secs = match (&state.current_snapshot, &state.previous_snapshot) {
         (Some(curr), Some(prev)) => {
-            let delta = curr.timestamp - prev.timestamp;
+            let delta = curr.elapsed_secs(prev);
             if delta > 0 { delta as f64 } else { 1.0 }
         }
         _ => 1.0,
@@ -418,7 +423,9 @@ fn build_content<'a>(
         push_help(&mut lines, show_help, HELP, "Virtual Memory");
         lines.push(kv("Resident (RSS)", &format_kb(p.mem.rmem)));
         push_help(&mut lines, show_help, HELP, "Resident (RSS)");
-        lines.push(kv("Shared Memory", &format_kb(p.mem.pmem)));
+        lines.push(kv("Proportional (PSS)", &format_kb(p.mem.pmem)));
+        push_help(&mut lines, show_help, HELP, "Proportional (PSS)");
+        lines.push(kv("Shared Memory", &format_kb(p.mem.pshared)));
         push_help(&mut lines, show_help, HELP, "Shared Memory");
         lines.push(kv("Swap", &format_kb(p.mem.vswap)));
         push_help(&mut lines, show_help, HELP, "Swap");
@@ -461,7 +468,7 @@ fn build_content<'a>(
         lines.push(section("OS Process"));
         lines.push(Line::from(Span::styled(
             "  OS process not found (PID mismatch or access denied)",
-            Style::default().fg(Color::DarkGray),
+            Styles::dim(),
         )));
         lines.push(Line::from(""));
     }
@@ -469,17 +476,14 @@ fn build_content<'a>(
     // Section 5: Query
     lines.push(section("Query"));
     if query.is_empty() || query == "-" {
-        lines.push(Line::from(Span::styled(
-            "  (no query)",
-            Style::default().fg(Color::DarkGray),
-        )));
+        lines.push(Line::from(Span::styled("  (no query)", Styles::dim())));
     } else {
         for line in query.lines() {
             // Replace tabs with spaces to avoid ratatui rendering artifacts
             let sanitized = line.replace('\t', "    ");
             lines.push(Line::from(Span::styled(
                 format!("  {}", sanitized),
-                Style::default().fg(Color::White),
+                Styles::popup(),
             )));
         }
     }
@@ -492,11 +496,7 @@ fn format_timestamp(ts: i64) -> String {
     if ts <= 0 {
         return "-".to_string();
     }
-    use chrono::{TimeZone, Utc};
-    Utc.timestamp_opt(ts, 0)
-        .single()
-        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-        .unwrap_or_else(|| "-".to_string())
+    crate::fmt::format_timestamp(ts, "%Y-%m-%d %H:%M:%S %Z").unwrap_or_else(|| "-".to_string())
 }
 
 /// Format timestamp or "-" for null values.
@@ -514,9 +514,9 @@ fn duration_style(secs: i64, state: &str) -> Style {
     let is_active = lower_state == "active" || lower_state.contains("trans");
 
     if is_active && secs > 300 {
-        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        Styles::critical()
     } else if is_active && secs > 60 {
-        Style::default().fg(Color::Yellow)
+        Styles::emphasis()
     } else {
         Style::default()
     }
@@ -526,11 +526,11 @@ fn duration_style(secs: i64, state: &str) -> Style {
 fn state_style(state: &str) -> Style {
     let lower = state.to_lowercase();
     if lower.contains("idle") && lower.contains("trans") {
-        Style::default().fg(Color::Yellow)
+        Styles::emphasis()
     } else if lower == "active" {
-        Style::default().fg(Color::Green)
+        Styles::active()
     } else if lower.contains("idle") {
-        Style::default().fg(Color::DarkGray)
+        Styles::dim()
     } else {
         Style::default()
     }
@@ -0,0 +1,90 @@
+//! PostgreSQL settings (PGC) tab widget.
+//! Thin TUI wrapper over [`crate::view::pgc::build_settings_view`].
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::text::Span;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Row, Table};
+
+use crate::api::settings::settings_rows;
+use crate::tui::state::{AppState, PgSettingsViewMode};
+use crate::tui::style::Styles;
+use crate::view::pgc::build_settings_view;
+
+pub fn render_pg_settings(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    let settings = state
+        .current_snapshot
+        .as_ref()
+        .map(settings_rows)
+        .unwrap_or_default();
+
+    let mut vm = match build_settings_view(&settings, &state.pgc) {
+        Some(vm) => vm,
+        None => {
+            let label = if state.current_snapshot.is_none() {
+                "No data available"
+            } else if settings.is_empty() {
+                "No pg_settings in this snapshot"
+            } else if state.pgc.filter.is_some() {
+                "No matching rows (filter active)"
+            } else if state.pgc.view_mode == PgSettingsViewMode::NonDefault {
+                "All settings have default values"
+            } else {
+                "No settings"
+            };
+            let msg = Paragraph::new(label).block(
+                Block::default()
+                    .title("PGC: Settings")
+                    .borders(Borders::ALL),
+            );
+            frame.render_widget(Clear, area);
+            frame.render_widget(msg, area);
+            return;
+        }
+    };
+
+    vm.pin_rows(&state.pgc.pinned);
+
+    // Resolve selection
+    let row_hashes: Vec<u64> = vm.rows.iter().map(|r| r.id).collect();
+    state.pgc.resolve_selection(&row_hashes);
+    state.prepare_table_view(&mut vm, state.pgc.selected);
+
+    // Header
+    let header_cells: Vec<Span> = vm
+        .headers
+        .iter()
+        .map(|h| Span::styled(h.clone(), Styles::table_header()))
+        .collect();
+    let header = Row::new(header_cells).style(Styles::table_header());
+
+    let widths: Vec<Constraint> = vm.widths.iter().map(|&w| Constraint::Length(w)).collect();
+
+    // Rows
+    let rows: Vec<Row> = vm
+        .rows
+        .iter()
+        .map(|vr| {
+            let style = Styles::from_class(vr.style);
+            let cells = vr.cells.iter().map(|c| match c.style {
+                Some(s) => Span::styled(c.text.clone(), Styles::from_class(s)),
+                None => Span::raw(c.text.clone()),
+            });
+            Row::new(cells).style(style)
+        })
+        .collect();
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .title(vm.title)
+                .borders(Borders::ALL)
+                .style(Styles::default()),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Styles::selected());
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut state.pgc.ratatui_state);
+}
@@ -1,15 +1,19 @@
 //! PostgreSQL log errors (PGE) tab widget.
-//! Thin TUI wrapper over [`crate::view::pge::build_errors_view`].
+//! Thin TUI wrapper over [`crate::view::pge::build_errors_view`] and
+//! [`crate::view::pge::build_events_view`].
 
 use ratatui::Frame;
-use ratatui::layout::Rect;
-use ratatui::text::Span;
-use ratatui::widgets::{Block, Borders, Clear, Paragraph, Row, Table};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Row, Table, Wrap};
 
 use crate::storage::StringInterner;
-use crate::tui::state::AppState;
+use crate::tui::state::{AppState, PgErrorsViewMode};
 use crate::tui::style::Styles;
-use crate::view::pge::build_errors_view;
+use crate::view::pge::{build_errors_view, build_events_view, event_row_id, event_type_label};
+
+/// Maximum height of the expanded row pane (including borders).
+const EXPANDED_MAX_HEIGHT: u16 = 14;
 
 pub fn render_pg_errors(
     frame: &mut Frame,
@@ -17,7 +21,11 @@ pub fn render_pg_errors(
     state: &mut AppState,
     interner: Option<&StringInterner>,
 ) {
-    let vm = match build_errors_view(&state.pge.accumulated, &state.pge, interner) {
+    let vm = match state.pge.view_mode {
+        PgErrorsViewMode::Errors => build_errors_view(&state.pge.accumulated, &state.pge, interner),
+        PgErrorsViewMode::Events => build_events_view(&state.pge.events, &state.pge),
+    };
+    let mut vm = match vm {
         Some(vm) => vm,
         None => {
             let label = if state.pge.filter.is_some() {
@@ -33,9 +41,30 @@ pub fn render_pg_errors(
         }
     };
 
+    vm.pin_rows(&state.pge.pinned);
+
     // Resolve selection
     let row_hashes: Vec<u64> = vm.rows.iter().map(|r| r.id).collect();
     state.pge.resolve_selection(&row_hashes);
+    state.prepare_table_view(&mut vm, state.pge.selected);
+
+    // Expanded pane for the selected row
+    let expanded = if state.pge.expanded {
+        expanded_lines(state, interner)
+    } else {
+        None
+    };
+    let (table_area, expanded_area) = match &expanded {
+        Some(lines) => {
+            let height = (lines.len() as u16 + 2)
+                .min(EXPANDED_MAX_HEIGHT)
+                .min(area.height / 2);
+            let chunks =
+                Layout::vertical([Constraint::Min(3), Constraint::Length(height)]).split(area);
+            (chunks[0], Some(chunks[1]))
+        }
+        None => (area, None),
+    };
 
     // Header
     let header_cells: Vec<Span> = vm
@@ -46,14 +75,12 @@ pub fn render_pg_errors(
     let header = Row::new(header_cells).style(Styles::table_header());
 
     // Widths
-    let mut widths: Vec<ratatui::layout::Constraint> = vm
-        .widths
-        .iter()
-        .map(|&w| ratatui::layout::Constraint::Length(w))
-        .collect();
-    // PATTERN and SAMPLE get Fill(1) each
-    widths.push(ratatui::layout::Constraint::Fill(1));
-    widths.push(ratatui::layout::Constraint::Fill(1));
+    let mut widths: Vec<Constraint> = vm.widths.iter().map(|&w| Constraint::Length(w)).collect();
+    // Columns without a fixed width (PATTERN/SAMPLE or TABLE/MESSAGE) share the rest
+    widths.extend(std::iter::repeat_n(
+        Constraint::Fill(1),
+        vm.headers.len() - vm.widths.len(),
+    ));
 
     // Rows
     let rows: Vec<Row> = vm
@@ -81,5 +108,71 @@ pub fn render_pg_errors(
         .row_highlight_style(Styles::selected());
 
     frame.render_widget(Clear, area);
-    frame.render_stateful_widget(table, area, &mut state.pge.ratatui_state);
+    frame.render_stateful_widget(table, table_area, &mut state.pge.ratatui_state);
+
+    if let (Some(lines), Some(expanded_area)) = (expanded, expanded_area) {
+        let pane = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(" Selected (x to collapse) ")
+                    .borders(Borders::ALL)
+                    .style(Styles::default()),
+            )
+            .wrap(Wrap { trim: false });
+        frame.render_widget(pane, expanded_area);
+    }
+}
+
+/// Builds the expanded pane content for the selected row:
+/// sample message and STATEMENT for errors, full message for events.
+fn expanded_lines(
+    state: &AppState,
+    interner: Option<&StringInterner>,
+) -> Option<Vec<Line<'static>>> {
+    let tracked = state.pge.tracked_pattern_hash?;
+    let resolve = |hash: u64| -> String {
+        interner
+            .and_then(|i| i.resolve(hash))
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| format!("#{:x}", hash))
+    };
+    let label = |s: &'static str| Span::styled(s, Styles::table_header());
+
+    let mut lines = Vec::new();
+    match state.pge.view_mode {
+        PgErrorsViewMode::Errors => {
+            let acc = state
+                .pge
+                .accumulated
+                .iter()
+                .find(|a| a.pattern_hash == tracked)?;
+            lines.push(Line::from(vec![
+                label("Sample:    "),
+                Span::raw(resolve(acc.sample_hash)),
+            ]));
+            let statement = if acc.statement_hash == 0 {
+                "-".to_string()
+            } else {
+                resolve(acc.statement_hash)
+            };
+            lines.push(Line::from(vec![label("Statement: "), Span::raw(statement)]));
+        }
+        PgErrorsViewMode::Events => {
+            let event = state
+                .pge
+                .events
+                .iter()
+                .find(|e| event_row_id(e) == tracked)?;
+            let mut header = event_type_label(event.entry.event_type).to_string();
+            if !event.entry.table_name.is_empty() {
+                header.push_str(": ");
+                header.push_str(&event.entry.table_name);
+            }
+            lines.push(Line::from(Span::styled(header, Styles::table_header())));
+            for line in event.entry.message.lines() {
+                lines.push(Line::raw(line.replace('\t', "    ")));
+            }
+        }
+    }
+    Some(lines)
 }
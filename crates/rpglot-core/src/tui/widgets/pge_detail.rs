@@ -1,8 +1,6 @@
 //! PostgreSQL log error detail popup widget.
 //! Shows detailed information about a selected error pattern.
 
-use std::time::{SystemTime, UNIX_EPOCH};
-
 use ratatui::Frame;
 use ratatui::layout::Rect;
 use ratatui::text::Line;
@@ -39,6 +37,10 @@ const HELP: &[(&str, &str)] = &[
         "Sample",
         "One concrete example of the error message with actual values",
     ),
+    (
+        "Statement",
+        "SQL statement from the STATEMENT: log line that followed the error (if logged)",
+    ),
 ];
 
 pub fn render_pge_detail(
@@ -94,11 +96,13 @@ pub fn render_pge_detail(
     push_help(&mut lines, show_help, HELP, "Count");
 
     let last_seen = {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or(0);
-        let ago = now - acc.last_seen;
+        // Relative to the displayed snapshot, so history mode shows the same as live did
+        let now = state
+            .current_snapshot
+            .as_ref()
+            .map(|s| s.timestamp)
+            .unwrap_or(acc.last_seen);
+        let ago = (now - acc.last_seen).max(0);
         if ago < 60 {
             format!("{}s ago", ago)
         } else if ago < 3600 {
@@ -128,6 +132,17 @@ pub fn render_pge_detail(
     }
     push_help(&mut lines, show_help, HELP, "Sample");
 
+    // Statement section
+    if acc.statement_hash != 0 {
+        lines.push(section("Statement"));
+        let statement_text = resolve(acc.statement_hash);
+        for line in statement_text.lines() {
+            let sanitized = line.replace('\t', "    ");
+            lines.push(Line::raw(format!("  {}", sanitized)));
+        }
+        push_help(&mut lines, show_help, HELP, "Statement");
+    }
+
     render_popup_frame(
         frame,
         area,
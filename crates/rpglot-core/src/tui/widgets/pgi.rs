@@ -29,7 +29,7 @@ pub fn render_pg_indexes(
         }
     };
 
-    let vm = match build_indexes_view(snapshot, &state.pgi, interner) {
+    let mut vm = match build_indexes_view(snapshot, &state.pgi, interner) {
         Some(vm) => vm,
         None => {
             let block = Block::default()
@@ -46,9 +46,12 @@ pub fn render_pg_indexes(
         }
     };
 
+    vm.pin_rows(&state.pgi.pinned);
+
     // Resolve selection
     let row_indexrelids: Vec<u32> = vm.rows.iter().map(|r| r.id).collect();
     state.pgi.resolve_selection(&row_indexrelids);
+    state.prepare_table_view(&mut vm, state.pgi.selected);
 
     // Header with sort indicator
     let headers: Vec<Span> = vm
@@ -92,7 +95,10 @@ pub fn render_pg_indexes(
         .iter()
         .map(|&w| ratatui::layout::Constraint::Length(w))
         .collect();
-    constraints.push(ratatui::layout::Constraint::Fill(1));
+    constraints.extend(std::iter::repeat_n(
+        ratatui::layout::Constraint::Fill(1),
+        vm.headers.len() - vm.widths.len(),
+    ));
 
     let table = Table::new(rows, constraints)
         .header(header)
@@ -31,7 +31,7 @@ pub fn render_pg_locks(
         }
     };
 
-    let vm = match build_locks_view(snapshot, &state.pgl, interner) {
+    let mut vm = match build_locks_view(snapshot, &state.pgl, interner) {
         Some(vm) => vm,
         None => {
             // Either no nodes at all or filter yielded empty results
@@ -54,6 +54,7 @@ pub fn render_pg_locks(
     // Resolve selection
     let row_pids: Vec<i32> = vm.rows.iter().map(|r| r.id).collect();
     state.pgl.resolve_selection(&row_pids);
+    state.prepare_table_view(&mut vm, state.pgl.selected);
 
     // Header
     let header_cells: Vec<Span> = vm
@@ -69,7 +70,10 @@ pub fn render_pg_locks(
         .iter()
         .map(|&w| ratatui::layout::Constraint::Length(w))
         .collect();
-    widths.push(ratatui::layout::Constraint::Fill(1));
+    widths.extend(std::iter::repeat_n(
+        ratatui::layout::Constraint::Fill(1),
+        vm.headers.len() - vm.widths.len(),
+    ));
 
     // Rows
     let rows: Vec<Row> = vm
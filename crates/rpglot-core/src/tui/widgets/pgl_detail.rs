@@ -3,12 +3,12 @@
 
 use ratatui::Frame;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 
 use crate::storage::StringInterner;
 use crate::storage::model::{DataBlock, PgLockTreeNode, ProcessInfo, Snapshot};
 use crate::tui::state::{AppState, PopupState};
+use crate::tui::style::Styles;
 
 use super::detail_common::{
     format_bytes, format_bytes_rate, format_epoch_age, format_kb, format_rate, format_ticks, kv,
@@ -124,9 +124,13 @@ const HELP: &[(&str, &str)] = &[
         "Resident (RSS)",
         "Physical pages currently in RAM; for PostgreSQL backends includes shared_buffers pages",
     ),
+    (
+        "Proportional (PSS)",
+        "RSS with each shared page divided among the processes mapping it; the backend's real memory cost (sampled for the largest PostgreSQL processes)",
+    ),
     (
         "Shared Memory",
-        "Shared pages (PSS-based); largely shared_buffers + other shared memory segments",
+        "Resident pages shared with other processes; largely shared_buffers",
     ),
     (
         "Swap",
@@ -233,7 +237,7 @@ pub fn render_pgl_detail(
 
     let interval_secs = match (&state.current_snapshot, &state.previous_snapshot) {
         (Some(curr), Some(prev)) => {
-            let delta = curr.timestamp - prev.timestamp;
+            let delta = curr.elapsed_secs(prev);
             if delta > 0 { delta as f64 } else { 1.0 }
         }
         _ => 1.0,
@@ -345,7 +349,9 @@ pub fn render_pgl_detail(
         push_help(&mut lines, show_help, HELP, "Virtual Memory");
         lines.push(kv("Resident (RSS)", &format_kb(p.mem.rmem)));
         push_help(&mut lines, show_help, HELP, "Resident (RSS)");
-        lines.push(kv("Shared Memory", &format_kb(p.mem.pmem)));
+        lines.push(kv("Proportional (PSS)", &format_kb(p.mem.pmem)));
+        push_help(&mut lines, show_help, HELP, "Proportional (PSS)");
+        lines.push(kv("Shared Memory", &format_kb(p.mem.pshared)));
         push_help(&mut lines, show_help, HELP, "Shared Memory");
         lines.push(kv("Swap", &format_kb(p.mem.vswap)));
         push_help(&mut lines, show_help, HELP, "Swap");
@@ -388,7 +394,7 @@ pub fn render_pgl_detail(
         lines.push(section("OS Process"));
         lines.push(Line::from(Span::styled(
             "  OS process not found (PID mismatch or access denied)",
-            Style::default().fg(Color::DarkGray),
+            Styles::dim(),
         )));
         lines.push(Line::from(""));
     }
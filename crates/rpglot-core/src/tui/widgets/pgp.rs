@@ -17,7 +17,7 @@ use crate::storage::StringInterner;
 use crate::storage::model::{DataBlock, PgStorePlansInfo, Snapshot};
 use crate::tui::state::{AppState, PgStorePlansTabState, SortKey};
 use crate::tui::style::Styles;
-use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
+use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow, threshold_class};
 
 // Column headers per view mode
 const PGP_HEADERS_TIME: &[&str] = &[
@@ -57,7 +57,7 @@ pub fn render_pg_store_plans(
         }
     };
 
-    let vm = match build_store_plans_view(snapshot, &state.pgp, interner) {
+    let mut vm = match build_store_plans_view(snapshot, &state.pgp, interner) {
         Some(vm) => vm,
         None => {
             let block = Block::default()
@@ -72,9 +72,12 @@ pub fn render_pg_store_plans(
         }
     };
 
+    vm.pin_rows(&state.pgp.pinned);
+
     // Resolve selection
     let row_planids: Vec<i64> = vm.rows.iter().map(|r| r.id).collect();
     state.pgp.resolve_selection(&row_planids);
+    state.prepare_table_view(&mut vm, state.pgp.selected);
 
     // Header with sort indicator
     let headers: Vec<Span> = vm
@@ -118,7 +121,10 @@ pub fn render_pg_store_plans(
         .iter()
         .map(|&w| ratatui::layout::Constraint::Length(w))
         .collect();
-    constraints.push(ratatui::layout::Constraint::Fill(1));
+    constraints.extend(std::iter::repeat_n(
+        ratatui::layout::Constraint::Fill(1),
+        vm.headers.len() - vm.widths.len(),
+    ));
 
     let table = Table::new(rows, constraints)
         .header(header)
@@ -223,25 +229,13 @@ impl PgStorePlansRowData {
     fn row_style(&self, mode: PgStorePlansViewMode) -> RowStyleClass {
         match mode {
             PgStorePlansViewMode::Time => {
-                let time_ms_s = self.exec_time_ms_s.unwrap_or(0.0);
-                if time_ms_s >= 1_000.0 {
-                    RowStyleClass::Critical
-                } else if time_ms_s >= 100.0 {
-                    RowStyleClass::Warning
-                } else {
-                    RowStyleClass::Normal
-                }
-            }
-            PgStorePlansViewMode::Io => {
-                let rd_s = self.shared_blks_read_s.unwrap_or(0.0);
-                if rd_s >= 10_000.0 {
-                    RowStyleClass::Critical
-                } else if rd_s >= 1_000.0 {
-                    RowStyleClass::Warning
-                } else {
-                    RowStyleClass::Normal
-                }
+                threshold_class("pgp", "exec_time_ms_s", self.exec_time_ms_s.unwrap_or(0.0))
             }
+            PgStorePlansViewMode::Io => threshold_class(
+                "pgp",
+                "shared_blks_read_s",
+                self.shared_blks_read_s.unwrap_or(0.0),
+            ),
             PgStorePlansViewMode::Regression => {
                 let ratio = self.regression_ratio.unwrap_or(0.0);
                 if ratio >= 10.0 {
@@ -273,7 +267,7 @@ impl PgStorePlansRowData {
                 ViewCell::plain(format_opt_f64(self.shared_blks_hit_s, 9, 1)),
                 ViewCell::styled(
                     format_opt_f64(self.hit_pct_s, 5, 1),
-                    hit_pct_style_class(self.hit_pct_s.unwrap_or(0.0)),
+                    threshold_class("pgp", "hit_pct", self.hit_pct_s.unwrap_or(0.0)),
                 ),
                 ViewCell::plain(format_opt_f64(self.shared_blks_written_s, 9, 1)),
                 ViewCell::plain(truncate(&self.db, 20)),
@@ -297,16 +291,6 @@ impl PgStorePlansRowData {
     }
 }
 
-fn hit_pct_style_class(hit_pct: f64) -> RowStyleClass {
-    if hit_pct < 90.0 {
-        RowStyleClass::Critical
-    } else if hit_pct < 98.0 {
-        RowStyleClass::Warning
-    } else {
-        RowStyleClass::Normal
-    }
-}
-
 fn regression_ratio_style(ratio: f64) -> RowStyleClass {
     if ratio >= 10.0 {
         RowStyleClass::Critical
@@ -410,6 +394,21 @@ fn build_store_plans_view(
         });
     }
 
+    let (headers, widths, title_mode) = match mode {
+        PgStorePlansViewMode::Time => (PGP_HEADERS_TIME, PGP_WIDTHS_TIME, "t:time"),
+        PgStorePlansViewMode::Io => (PGP_HEADERS_IO, PGP_WIDTHS_IO, "i:io"),
+        PgStorePlansViewMode::Regression => (
+            PGP_HEADERS_REGRESSION,
+            PGP_WIDTHS_REGRESSION,
+            "r:regression",
+        ),
+    };
+
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| rf.matches(headers, |col| r.sort_key(mode, col)));
+    }
+
     // Sort
     let sort_col = state.sort_column;
     let sort_asc = state.sort_ascending;
@@ -421,16 +420,6 @@ fn build_store_plans_view(
         if sort_asc { cmp } else { cmp.reverse() }
     });
 
-    let (headers, widths, title_mode) = match mode {
-        PgStorePlansViewMode::Time => (PGP_HEADERS_TIME, PGP_WIDTHS_TIME, "t:time"),
-        PgStorePlansViewMode::Io => (PGP_HEADERS_IO, PGP_WIDTHS_IO, "i:io"),
-        PgStorePlansViewMode::Regression => (
-            PGP_HEADERS_REGRESSION,
-            PGP_WIDTHS_REGRESSION,
-            "r:regression",
-        ),
-    };
-
     let rows: Vec<ViewRow<i64>> = rows_data
         .iter()
         .map(|r| ViewRow {
@@ -2,13 +2,13 @@
 
 use ratatui::Frame;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 
 use crate::models::PgStorePlansRates;
 use crate::storage::StringInterner;
 use crate::storage::model::{DataBlock, PgStatStatementsInfo, PgStorePlansInfo, Snapshot};
 use crate::tui::state::{AppState, PopupState};
+use crate::tui::style::Styles;
 
 use super::detail_common::{
     delta_style, format_bytes, format_bytes_signed, format_epoch_age, kv, kv_delta_f64,
@@ -433,10 +433,7 @@ fn kv_blocks(key: &str, current: i64, prev: Option<i64>) -> Line<'static> {
     let mut spans = vec![
         key_span,
         Span::raw(format_bytes(bytes)),
-        Span::styled(
-            format!("  ({} blk)", current),
-            Style::default().fg(Color::DarkGray),
-        ),
+        Span::styled(format!("  ({} blk)", current), Styles::dim()),
     ];
     if let Some(p) = prev {
         let d = current - p;
@@ -0,0 +1,103 @@
+//! PostgreSQL workload (PGR) tab widget.
+//! Thin TUI wrapper over [`crate::view::pgr::build_workload_view`].
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::text::Span;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Row, Table};
+
+use crate::storage::StringInterner;
+use crate::tui::state::{AppState, PgRolesViewMode};
+use crate::tui::style::Styles;
+use crate::view::pgr::{build_clients_view, build_workload_view};
+use crate::workload::{
+    aggregate_applications, aggregate_clients, aggregate_roles, tcp_connections,
+};
+
+pub fn render_pg_roles(
+    frame: &mut Frame,
+    area: Rect,
+    state: &mut AppState,
+    interner: Option<&StringInterner>,
+) {
+    let rates = &state.pgs.rate_state.rates;
+    let vm = match (&state.current_snapshot, state.pgr.view_mode) {
+        (Some(s), PgRolesViewMode::Clients) => build_clients_view(
+            &aggregate_clients(s, interner),
+            tcp_connections(s),
+            &state.pgr,
+        ),
+        (Some(s), PgRolesViewMode::Roles) => {
+            build_workload_view(&aggregate_roles(s, rates, interner), &state.pgr)
+        }
+        (Some(s), PgRolesViewMode::Applications) => {
+            build_workload_view(&aggregate_applications(s, rates, interner), &state.pgr)
+        }
+        (None, _) => None,
+    };
+
+    let mut vm = match vm {
+        Some(vm) => vm,
+        None => {
+            let label = if state.current_snapshot.is_none() {
+                "No data available"
+            } else if state.pgr.filter.is_some() {
+                "No matching rows (filter active)"
+            } else if state.pgr.view_mode == PgRolesViewMode::Clients {
+                "No client connections"
+            } else {
+                "No client sessions or statements"
+            };
+            let msg = Paragraph::new(label)
+                .block(Block::default().title("PGR: Roles").borders(Borders::ALL));
+            frame.render_widget(Clear, area);
+            frame.render_widget(msg, area);
+            return;
+        }
+    };
+
+    vm.pin_rows(&state.pgr.pinned);
+
+    // Resolve selection
+    let row_hashes: Vec<u64> = vm.rows.iter().map(|r| r.id).collect();
+    state.pgr.resolve_selection(&row_hashes);
+    state.prepare_table_view(&mut vm, state.pgr.selected);
+
+    // Header
+    let header_cells: Vec<Span> = vm
+        .headers
+        .iter()
+        .map(|h| Span::styled(h.clone(), Styles::table_header()))
+        .collect();
+    let header = Row::new(header_cells).style(Styles::table_header());
+
+    let widths: Vec<Constraint> = vm.widths.iter().map(|&w| Constraint::Length(w)).collect();
+
+    // Rows
+    let rows: Vec<Row> = vm
+        .rows
+        .iter()
+        .map(|vr| {
+            let style = Styles::from_class(vr.style);
+            let cells = vr.cells.iter().map(|c| match c.style {
+                Some(s) => Span::styled(c.text.clone(), Styles::from_class(s)),
+                None => Span::raw(c.text.clone()),
+            });
+            Row::new(cells).style(style)
+        })
+        .collect();
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .title(vm.title)
+                .borders(Borders::ALL)
+                .style(Styles::default()),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Styles::selected());
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut state.pgr.ratatui_state);
+}
@@ -29,7 +29,7 @@ pub fn render_pg_statements(
         }
     };
 
-    let vm = match build_statements_view(snapshot, &state.pgs, interner, state.is_live) {
+    let mut vm = match build_statements_view(snapshot, &state.pgs, interner, state.is_live) {
         Some(vm) => vm,
         None => {
             let block = Block::default()
@@ -46,9 +46,12 @@ pub fn render_pg_statements(
         }
     };
 
+    vm.pin_rows(&state.pgs.pinned);
+
     // Resolve selection
     let row_queryids: Vec<i64> = vm.rows.iter().map(|r| r.id).collect();
     state.pgs.resolve_selection(&row_queryids);
+    state.prepare_table_view(&mut vm, state.pgs.selected);
 
     // Header with sort indicator
     let headers: Vec<Span> = vm
@@ -92,7 +95,10 @@ pub fn render_pg_statements(
         .iter()
         .map(|&w| ratatui::layout::Constraint::Length(w))
         .collect();
-    constraints.push(ratatui::layout::Constraint::Fill(1));
+    constraints.extend(std::iter::repeat_n(
+        ratatui::layout::Constraint::Fill(1),
+        vm.headers.len() - vm.widths.len(),
+    ));
 
     let table = Table::new(rows, constraints)
         .header(header)
@@ -29,7 +29,7 @@ pub fn render_pg_tables(
         }
     };
 
-    let vm = match build_tables_view(snapshot, &state.pgt, interner) {
+    let mut vm = match build_tables_view(snapshot, &state.pgt, interner) {
         Some(vm) => vm,
         None => {
             let block = Block::default()
@@ -46,9 +46,12 @@ pub fn render_pg_tables(
         }
     };
 
+    vm.pin_rows(&state.pgt.pinned);
+
     // Resolve selection
     let row_relids: Vec<u32> = vm.rows.iter().map(|r| r.id).collect();
     state.pgt.resolve_selection(&row_relids);
+    state.prepare_table_view(&mut vm, state.pgt.selected);
 
     // Header with sort indicator
     let headers: Vec<Span> = vm
@@ -92,7 +95,10 @@ pub fn render_pg_tables(
         .iter()
         .map(|&w| ratatui::layout::Constraint::Length(w))
         .collect();
-    constraints.push(ratatui::layout::Constraint::Fill(1));
+    constraints.extend(std::iter::repeat_n(
+        ratatui::layout::Constraint::Fill(1),
+        vm.headers.len() - vm.widths.len(),
+    ));
 
     let table = Table::new(rows, constraints)
         .header(header)
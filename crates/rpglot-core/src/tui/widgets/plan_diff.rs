@@ -0,0 +1,156 @@
+//! Plan diff popup (`|` on PGP).
+//!
+//! Compares the selected plan with the most-called other plan of the same
+//! queryid: side by side when the popup is wide enough, unified otherwise.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::api::plan_diff::{PlanDiff, PlanDiffKind, PlanDiffLine, PlanDiffSide, plan_diff};
+use crate::storage::StringInterner;
+use crate::tui::state::{AppState, PopupState};
+use crate::tui::style::Styles;
+
+use super::detail_common::kv;
+
+/// Narrowest popup that still shows both plans side by side.
+const MIN_SIDE_BY_SIDE_WIDTH: usize = 100;
+
+pub fn render_plan_diff(
+    frame: &mut Frame,
+    area: Rect,
+    state: &mut AppState,
+    interner: Option<&StringInterner>,
+) {
+    let (queryid, planid) = match &state.popup {
+        PopupState::PlanDiff {
+            queryid, planid, ..
+        } => (*queryid, *planid),
+        _ => return,
+    };
+    let diff = state
+        .current_snapshot
+        .as_ref()
+        .and_then(|s| plan_diff(s, interner, queryid, None, Some(planid)));
+
+    let title = match &diff {
+        Some(d) => format!(" Plan diff {queryid}: {} → {} ", d.from.planid, d.to.planid),
+        None => format!(" Plan diff {queryid} "),
+    };
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Styles::popup_border())
+        .style(Styles::popup());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+
+    let content = match &diff {
+        Some(d) => build_content(d, chunks[0].width as usize),
+        None => vec![Line::raw(
+            "No other plan of this query in the current sample",
+        )],
+    };
+    let max_scroll = content.len().saturating_sub(chunks[0].height as usize);
+    let scroll = match &mut state.popup {
+        PopupState::PlanDiff { scroll, .. } => {
+            *scroll = (*scroll).min(max_scroll);
+            *scroll
+        }
+        _ => 0,
+    };
+    frame.render_widget(
+        Paragraph::new(content).scroll((scroll as u16, 0)),
+        chunks[0],
+    );
+
+    let footer = Line::from(vec![
+        Span::styled("↑/↓", Styles::help_key()),
+        Span::styled(" scroll  ", Styles::help()),
+        Span::styled("PgUp/PgDn", Styles::help_key()),
+        Span::styled(" page  ", Styles::help()),
+        Span::styled("|/Esc", Styles::help_key()),
+        Span::styled(" close", Styles::help()),
+    ]);
+    frame.render_widget(Paragraph::new(footer), chunks[1]);
+}
+
+fn build_content(diff: &PlanDiff, width: usize) -> Vec<Line<'static>> {
+    let side = |label: &str, s: &PlanDiffSide| {
+        kv(
+            label,
+            &format!(
+                "planid {}, {} calls, mean {:.2} ms",
+                s.planid, s.calls, s.mean_time_ms
+            ),
+        )
+    };
+    let mut lines = vec![
+        side("old", &diff.from),
+        side("new", &diff.to),
+        kv("changed lines", &diff.changed_lines().to_string()),
+        Line::raw(""),
+    ];
+    if width >= MIN_SIDE_BY_SIDE_WIDTH {
+        let half = (width - 3) / 2;
+        lines.extend(diff.lines.iter().map(|l| side_by_side(l, half)));
+    } else {
+        lines.extend(diff.lines.iter().map(unified));
+    }
+    lines
+}
+
+fn kind_style(kind: PlanDiffKind) -> Style {
+    match kind {
+        PlanDiffKind::Same => Styles::default(),
+        PlanDiffKind::Changed => Styles::modified_item(),
+        PlanDiffKind::Removed => Styles::error(),
+        PlanDiffKind::Added => Styles::active(),
+    }
+}
+
+fn side_by_side(line: &PlanDiffLine, half: usize) -> Line<'static> {
+    let style = kind_style(line.kind);
+    let marker = match line.kind {
+        PlanDiffKind::Same => " │ ",
+        PlanDiffKind::Changed => " ~ ",
+        PlanDiffKind::Removed => " < ",
+        PlanDiffKind::Added => " > ",
+    };
+    Line::from(vec![
+        Span::styled(fit(line.left.as_deref().unwrap_or(""), half), style),
+        Span::styled(marker, Styles::dim()),
+        Span::styled(fit(line.right.as_deref().unwrap_or(""), half), style),
+    ])
+}
+
+fn unified(line: &PlanDiffLine) -> Line<'static> {
+    let (prefix, text) = match line.kind {
+        PlanDiffKind::Same => (" ", &line.right),
+        PlanDiffKind::Changed => ("~", &line.right),
+        PlanDiffKind::Removed => ("-", &line.left),
+        PlanDiffKind::Added => ("+", &line.right),
+    };
+    Line::styled(
+        format!("{prefix} {}", text.as_deref().unwrap_or("")),
+        kind_style(line.kind),
+    )
+}
+
+/// Pads or cuts `s` to exactly `width` characters.
+fn fit(s: &str, width: usize) -> String {
+    let len = s.chars().count();
+    if len <= width {
+        format!("{s:<width$}")
+    } else {
+        let mut out: String = s.chars().take(width.saturating_sub(1)).collect();
+        out.push('…');
+        out
+    }
+}
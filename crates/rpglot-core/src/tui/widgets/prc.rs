@@ -9,7 +9,8 @@ use ratatui::widgets::{Block, Borders, Clear, Row, Table};
 
 use crate::fmt::normalize_for_display;
 use crate::tui::state::{
-    AppState, CachedWidths, ColumnType, DiffStatus, ProcessRow, ProcessViewMode, TableRow,
+    AppState, CachedWidths, ColumnType, DiffStatus, PIN_MARKER, PopupState, ProcessRow,
+    ProcessSubtree, ProcessViewMode, TableRow, aggregate_process_subtrees,
 };
 use crate::tui::style::Styles;
 use std::collections::HashMap;
@@ -24,12 +25,40 @@ pub fn render_processes(frame: &mut Frame, area: Rect, state: &mut AppState) {
         .prc_ratatui_state
         .select(Some(state.process_table.selected));
 
-    let table_state = &state.process_table;
     let view_mode = state.process_view_mode;
 
     // Get headers for current view mode
     let all_headers = ProcessRow::headers_for_mode(view_mode);
 
+    // Row detail popup shows every column of the selected process
+    let header_names: Vec<String> = all_headers.iter().map(|h| h.to_string()).collect();
+    if matches!(state.popup, PopupState::RowDetail { .. }) {
+        let cells = state
+            .process_table
+            .filtered_items()
+            .get(state.process_table.selected)
+            .map(|item| item.cells_for_mode(view_mode))
+            .unwrap_or_default();
+        state.row_detail = header_names.iter().cloned().zip(cells).collect();
+    }
+    let export_rows = state.export_requested.then(|| {
+        state
+            .process_table
+            .filtered_items()
+            .iter()
+            .map(|item| item.cells_for_mode(view_mode))
+            .collect()
+    });
+    state.capture_export(&header_names, header_names.len(), || {
+        export_rows.unwrap_or_default()
+    });
+
+    // Apply saved column layout and horizontal scroll (PID stays pinned);
+    // all PRC columns have fixed widths
+    let visible_cols = state.visible_columns(&header_names, header_names.len());
+
+    let table_state = &state.process_table;
+
     // Get widths from cache or fallback to defaults
     let all_widths = state
         .cached_widths
@@ -42,19 +71,16 @@ pub fn render_processes(frame: &mut Frame, area: Rect, state: &mut AppState) {
         })
         .unwrap_or_else(|| ProcessRow::widths_for_mode(view_mode));
 
-    // Apply horizontal scroll
-    let scroll = state
-        .horizontal_scroll
-        .min(all_headers.len().saturating_sub(1));
-    let visible_headers: Vec<&str> = all_headers.iter().skip(scroll).copied().collect();
-    let visible_widths: Vec<u16> = all_widths.iter().skip(scroll).copied().collect();
+    let visible_widths: Vec<u16> = visible_cols
+        .iter()
+        .filter_map(|&c| all_widths.get(c).copied())
+        .collect();
 
     // Headers with sort indicator
-    let headers: Vec<Span> = visible_headers
+    let headers: Vec<Span> = visible_cols
         .iter()
-        .enumerate()
-        .map(|(i, h)| {
-            let actual_col = i + scroll;
+        .map(|&actual_col| {
+            let h = all_headers[actual_col];
             let indicator = if actual_col == table_state.sort_column {
                 if table_state.sort_ascending {
                     "▲"
@@ -84,24 +110,28 @@ pub fn render_processes(frame: &mut Frame, area: Rect, state: &mut AppState) {
                 .unwrap_or_default();
 
             let all_cells = item.cells_for_mode(view_mode);
-            let visible_cells: Vec<String> = all_cells.into_iter().skip(scroll).collect();
             let has_pg_query = item.query.is_some();
             let cmd_col_idx = all_headers.len().saturating_sub(1); // CMD is last column
 
-            let styled_cells: Vec<Span> = visible_cells
+            let pinned = table_state.pinned.contains(id);
+
+            let styled_cells: Vec<Span> = visible_cols
                 .iter()
                 .enumerate()
-                .map(|(col_idx, cell)| {
-                    let actual_col = col_idx + scroll;
+                .map(|(pos, &actual_col)| {
+                    let mut cell = all_cells.get(actual_col).cloned().unwrap_or_default();
+                    if pinned && pos == 0 {
+                        cell = format!("{PIN_MARKER}{cell}");
+                    }
                     let mut style =
                         cell_style(&diff_status, actual_col, idx == table_state.selected);
 
                     // Highlight CMD column in cyan if process has PostgreSQL query
                     if has_pg_query && actual_col == cmd_col_idx {
-                        style = style.fg(ratatui::style::Color::Cyan);
+                        style = style.patch(Styles::accent());
                     }
 
-                    Span::styled(cell.clone(), style)
+                    Span::styled(cell, style)
                 })
                 .collect();
 
@@ -126,6 +156,11 @@ pub fn render_processes(frame: &mut Frame, area: Rect, state: &mut AppState) {
         ProcessViewMode::Memory => "MEM",
         ProcessViewMode::Disk => "DSK",
     };
+    let mode_name = if state.process_tree {
+        format!("{mode_name}|TREE")
+    } else {
+        mode_name.to_string()
+    };
 
     let title = if let Some(filter) = &table_state.filter {
         format!(
@@ -140,8 +175,8 @@ pub fn render_processes(frame: &mut Frame, area: Rect, state: &mut AppState) {
     };
 
     // Add scroll indicator if scrolled
-    let title = if scroll > 0 {
-        format!("{}← scroll: {} ", title, scroll)
+    let title = if state.horizontal_scroll > 0 {
+        format!("{}← scroll: {} ", title, state.horizontal_scroll)
     } else {
         title
     };
@@ -174,9 +209,9 @@ fn cell_style(
     };
 
     match diff_status {
-        DiffStatus::New => base.fg(ratatui::style::Color::Green),
+        DiffStatus::New => base.patch(Styles::new_item()),
         DiffStatus::Modified(cols) if cols.contains(&col_idx) => base
-            .fg(ratatui::style::Color::Yellow)
+            .patch(Styles::modified_item())
             .add_modifier(Modifier::BOLD),
         _ => base,
     }
@@ -199,9 +234,10 @@ pub fn extract_processes(
 ) -> Vec<ProcessRow> {
     use crate::storage::model::DataBlock;
 
-    // Build a mapping from PostgreSQL backend PID to (query, backend_type)
+    // Build a mapping from PostgreSQL backend PID to (query, backend_type, user@db)
     // We include all PIDs that have pg_stat_activity entry, even if query is empty
-    let pg_info: HashMap<u32, (Option<String>, Option<String>)> = snapshot
+    #[allow(clippy::type_complexity)]
+    let pg_info: HashMap<u32, (Option<String>, Option<String>, Option<String>)> = snapshot
         .blocks
         .iter()
         .filter_map(|block| {
@@ -219,9 +255,18 @@ pub fn extract_processes(
                         .and_then(|i| i.resolve(pg.backend_type_hash))
                         .map(|s| s.to_string())
                         .filter(|s| !s.is_empty());
+                    let resolve = |hash| {
+                        interner
+                            .and_then(|i| i.resolve(hash))
+                            .filter(|s| !s.is_empty())
+                    };
+                    let user_db = match (resolve(pg.usename_hash), resolve(pg.datname_hash)) {
+                        (Some(user), Some(db)) => Some(format!("{user}@{db}")),
+                        (user, db) => user.or(db).map(str::to_string),
+                    };
                     // Include if at least one of query or backend_type is present
                     if query.is_some() || backend_type.is_some() {
-                        Some((pid, (query, backend_type)))
+                        Some((pid, (query, backend_type, user_db)))
                     } else {
                         None
                     }
@@ -235,7 +280,7 @@ pub fn extract_processes(
 
     for block in &snapshot.blocks {
         if let DataBlock::Processes(processes) = block {
-            return processes
+            let mut rows: Vec<ProcessRow> = processes
                 .iter()
                 .map(|p| {
                     let name = interner
@@ -261,7 +306,7 @@ pub fn extract_processes(
 
                     // Calculate memory percentage
                     let mem_percent = if total_mem_kb > 0 {
-                        (p.mem.rmem as f64 / total_mem_kb as f64) * 100.0
+                        (p.mem.resident() as f64 / total_mem_kb as f64) * 100.0
                     } else {
                         0.0
                     };
@@ -311,14 +356,22 @@ pub fn extract_processes(
                     // Look up PostgreSQL info for this PID
                     // Normalize query text to remove newlines/tabs that would cause
                     // ratatui rendering artifacts (text wrapping into adjacent cells).
-                    let (query, backend_type) = pg_info
+                    let (query, backend_type, user_db) = pg_info
                         .get(&p.pid)
-                        .map(|(q, bt)| (q.clone().map(|s| normalize_for_display(&s)), bt.clone()))
-                        .unwrap_or((None, None));
+                        .map(|(q, bt, ud)| {
+                            (
+                                q.clone().map(|s| normalize_for_display(&s)),
+                                bt.clone(),
+                                ud.clone(),
+                            )
+                        })
+                        .unwrap_or((None, None, None));
+                    let pg_label = pg_role_label(backend_type.as_deref(), user_db, &cmdline);
 
                     ProcessRow {
                         pid: p.pid,
                         tid: p.pid, // TID = PID for main process (threads would have different TID)
+                        ppid: p.ppid,
                         name,
                         cmdline,
 
@@ -362,21 +415,46 @@ pub fn extract_processes(
                         // PostgreSQL integration
                         query,
                         backend_type,
+                        pg_label,
 
                         // Disk I/O metrics
                         rddsk,
                         wrdsk,
                         wcancl,
                         dsk_percent: 0.0, // Calculated after all processes are collected
+                        blk_lat_avg_us: p.blk_lat.as_ref().and_then(|l| l.avg_us()),
+                        blk_lat_p99_us: p.blk_lat.as_ref().and_then(|l| l.quantile_us(0.99)),
+                        subtree: ProcessSubtree::default(),
+                        tree_prefix: None,
                     }
                 })
                 .collect();
+            aggregate_process_subtrees(&mut rows);
+            return rows;
         }
     }
 
     Vec::new()
 }
 
+/// PostgreSQL role of a process for tree mode: the backend type from
+/// pg_stat_activity (client backends with `user@db`), otherwise the process
+/// title of a postgres child (`postgres: logger`).
+fn pg_role_label(
+    backend_type: Option<&str>,
+    user_db: Option<String>,
+    cmdline: &str,
+) -> Option<String> {
+    match (backend_type, user_db) {
+        (Some("client backend"), Some(user_db)) => Some(format!("client backend {user_db}")),
+        (Some(bt), _) => Some(bt.to_string()),
+        (None, _) => cmdline
+            .strip_prefix("postgres: ")
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty()),
+    }
+}
+
 /// Updates the prev_process_mem map with current memory values.
 pub fn update_prev_mem(
     snapshot: &crate::storage::model::Snapshot,
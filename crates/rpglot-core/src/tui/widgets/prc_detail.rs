@@ -225,7 +225,7 @@ pub fn render_process_detail(frame: &mut Frame, area: Rect, state: &mut AppState
     // Calculate time interval between snapshots (seconds)
     let interval_secs = match (&state.current_snapshot, &state.previous_snapshot) {
         (Some(curr), Some(prev)) => {
-            let delta = curr.timestamp - prev.timestamp;
+            let delta = curr.elapsed_secs(prev);
             if delta > 0 { delta as f64 } else { 1.0 }
         }
         _ => 1.0,
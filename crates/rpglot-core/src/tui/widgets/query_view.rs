@@ -0,0 +1,501 @@
+//! Full-screen query viewer (PGS/PGP tabs).
+//!
+//! Shows the complete SQL text with basic syntax highlighting, plans from
+//! pg_store_plans and auto_explain for the same queryid, and per-metric
+//! sparklines over the loaded history window.
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Sparkline, Wrap};
+
+use crate::storage::StringInterner;
+use crate::storage::model::{
+    DataBlock, PgSlowPlanInfo, PgStatStatementsInfo, PgStorePlansInfo, Snapshot,
+};
+use crate::tui::state::{AppState, PopupState, QueryHistory};
+use crate::tui::style::Styles;
+
+use super::detail_common::{kv, resolve_hash, section};
+
+/// SQL keywords highlighted in the query text (uppercase).
+const SQL_KEYWORDS: &[&str] = &[
+    "ALL",
+    "ALTER",
+    "ANALYZE",
+    "AND",
+    "ANY",
+    "AS",
+    "ASC",
+    "BEGIN",
+    "BETWEEN",
+    "BY",
+    "CASE",
+    "CAST",
+    "COMMIT",
+    "CONFLICT",
+    "COPY",
+    "CREATE",
+    "CROSS",
+    "CURRENT",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DO",
+    "DROP",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "EXISTS",
+    "EXPLAIN",
+    "FALSE",
+    "FETCH",
+    "FILTER",
+    "FIRST",
+    "FOR",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "ILIKE",
+    "IN",
+    "INDEX",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "LATERAL",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "LOCK",
+    "NOT",
+    "NOTHING",
+    "NULL",
+    "NULLS",
+    "OFFSET",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OVER",
+    "PARTITION",
+    "RECURSIVE",
+    "RETURNING",
+    "RIGHT",
+    "ROLLBACK",
+    "ROW",
+    "ROWS",
+    "SELECT",
+    "SET",
+    "SHARE",
+    "SKIP",
+    "TABLE",
+    "THEN",
+    "TRUE",
+    "TRUNCATE",
+    "UNION",
+    "UPDATE",
+    "USING",
+    "VACUUM",
+    "VALUES",
+    "WHEN",
+    "WHERE",
+    "WINDOW",
+    "WITH",
+];
+
+/// Height of one sparkline cell (border + 2 rows of bars).
+const SPARK_CELL_HEIGHT: u16 = 4;
+
+fn keyword_style() -> Style {
+    Styles::accent().add_modifier(Modifier::BOLD)
+}
+
+fn string_style() -> Style {
+    Styles::active()
+}
+
+fn number_style() -> Style {
+    Styles::mem()
+}
+
+fn param_style() -> Style {
+    Styles::emphasis()
+}
+
+fn comment_style() -> Style {
+    Styles::dim()
+}
+
+/// Token state that can continue across lines.
+#[derive(Clone, Copy, PartialEq)]
+enum Pending {
+    None,
+    String,
+    BlockComment,
+}
+
+/// Splits SQL into highlighted lines: keywords, string literals, numbers,
+/// `$N` parameters and comments. Tabs are expanded to 4 spaces.
+pub fn highlight_sql(sql: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut pending = Pending::None;
+
+    for raw in sql.lines() {
+        let text: Vec<char> = raw.replace('\t', "    ").chars().collect();
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        let mut i = 0;
+
+        while i < text.len() {
+            let start = i;
+            let c = text[i];
+            let next = text.get(i + 1).copied();
+
+            let style = if pending == Pending::BlockComment || (c == '/' && next == Some('*')) {
+                if pending != Pending::BlockComment {
+                    i += 2;
+                }
+                pending = Pending::BlockComment;
+                while i < text.len() {
+                    if text[i] == '*' && text.get(i + 1) == Some(&'/') {
+                        i += 2;
+                        pending = Pending::None;
+                        break;
+                    }
+                    i += 1;
+                }
+                comment_style()
+            } else if pending == Pending::String || c == '\'' {
+                if pending != Pending::String {
+                    i += 1;
+                }
+                pending = Pending::String;
+                while i < text.len() {
+                    if text[i] == '\'' {
+                        if text.get(i + 1) == Some(&'\'') {
+                            i += 2;
+                            continue;
+                        }
+                        i += 1;
+                        pending = Pending::None;
+                        break;
+                    }
+                    i += 1;
+                }
+                string_style()
+            } else if c == '-' && next == Some('-') {
+                i = text.len();
+                comment_style()
+            } else if c == '$' && next.is_some_and(|n| n.is_ascii_digit()) {
+                i += 1;
+                while i < text.len() && text[i].is_ascii_digit() {
+                    i += 1;
+                }
+                param_style()
+            } else if c == '"' {
+                i += 1;
+                while i < text.len() && text[i] != '"' {
+                    i += 1;
+                }
+                i = (i + 1).min(text.len());
+                Style::default()
+            } else if c.is_ascii_digit() {
+                while i < text.len() && (text[i].is_ascii_alphanumeric() || text[i] == '.') {
+                    i += 1;
+                }
+                number_style()
+            } else if c.is_alphabetic() || c == '_' {
+                while i < text.len() && (text[i].is_alphanumeric() || text[i] == '_') {
+                    i += 1;
+                }
+                let word: String = text[start..i].iter().collect::<String>().to_uppercase();
+                if SQL_KEYWORDS.binary_search(&word.as_str()).is_ok() {
+                    keyword_style()
+                } else {
+                    Style::default()
+                }
+            } else {
+                i += 1;
+                while i < text.len()
+                    && !text[i].is_alphanumeric()
+                    && !matches!(text[i], '_' | '\'' | '"' | '$' | '-' | '/')
+                {
+                    i += 1;
+                }
+                Style::default()
+            };
+
+            let chunk: String = text[start..i].iter().collect();
+            spans.push(Span::styled(chunk, style));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+pub fn render_query_view(
+    frame: &mut Frame,
+    area: Rect,
+    state: &mut AppState,
+    interner: Option<&StringInterner>,
+) {
+    let (queryid, planid) = match &state.popup {
+        PopupState::QueryView {
+            queryid, planid, ..
+        } => (*queryid, *planid),
+        _ => return,
+    };
+
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(format!(" Query {queryid} "))
+        .borders(Borders::ALL)
+        .border_style(Styles::popup_border())
+        .style(Styles::popup());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let spark_height = if inner.height >= 3 * SPARK_CELL_HEIGHT {
+        2 * SPARK_CELL_HEIGHT
+    } else {
+        0
+    };
+    let chunks = Layout::vertical([
+        Constraint::Min(1),
+        Constraint::Length(spark_height),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let content = match state.current_snapshot.as_ref() {
+        Some(snapshot) => build_content(snapshot, queryid, planid, interner),
+        None => vec![Line::raw("No data available")],
+    };
+
+    // Clamp scroll to wrapped content height
+    let width = chunks[0].width as usize;
+    let visual_lines: usize = content
+        .iter()
+        .map(|l| l.width().max(1).div_ceil(width.max(1)))
+        .sum();
+    let max_scroll = visual_lines.saturating_sub(chunks[0].height as usize);
+    let scroll = match &mut state.popup {
+        PopupState::QueryView { scroll, .. } => {
+            *scroll = (*scroll).min(max_scroll);
+            *scroll
+        }
+        _ => 0,
+    };
+
+    let paragraph = Paragraph::new(content)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll as u16, 0));
+    frame.render_widget(paragraph, chunks[0]);
+
+    if spark_height > 0 {
+        render_sparklines(frame, chunks[1], state.query_history.as_ref(), queryid);
+    }
+
+    let footer = Line::from(vec![
+        Span::styled("↑/↓", Styles::help_key()),
+        Span::styled(" scroll  ", Styles::help()),
+        Span::styled("PgUp/PgDn", Styles::help_key()),
+        Span::styled(" page  ", Styles::help()),
+        Span::styled("f/Esc", Styles::help_key()),
+        Span::styled(" close", Styles::help()),
+    ]);
+    frame.render_widget(Paragraph::new(footer), chunks[2]);
+}
+
+fn build_content(
+    snapshot: &Snapshot,
+    queryid: i64,
+    planid: Option<i64>,
+    interner: Option<&StringInterner>,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    let stmt = find_statement(snapshot, queryid);
+    lines.push(section("Query"));
+    match stmt {
+        Some(stmt) => {
+            lines.push(kv("db", &resolve_hash(interner, stmt.datname_hash)));
+            lines.push(kv("user", &resolve_hash(interner, stmt.usename_hash)));
+            lines.push(kv("calls", &stmt.calls.to_string()));
+            lines.push(kv(
+                "mean_exec_time",
+                &format!("{:.3} ms", stmt.mean_exec_time),
+            ));
+            lines.push(Line::raw(""));
+            lines.extend(highlight_sql(&resolve_hash(interner, stmt.query_hash)));
+        }
+        None => lines.push(Line::styled(
+            "Query text not in current snapshot",
+            comment_style(),
+        )),
+    }
+    lines.push(Line::raw(""));
+
+    // Plans for this queryid: the selected plan first, then by calls
+    let mut plans = find_plans(snapshot, queryid);
+    plans.sort_by_key(|p| (Some(p.planid) != planid, std::cmp::Reverse(p.calls)));
+    let captured = find_slow_plan(snapshot, queryid);
+    if plans.is_empty() && captured.is_none() {
+        lines.push(section("Plan"));
+        lines.push(Line::styled(
+            "No pg_store_plans entry for this queryid",
+            comment_style(),
+        ));
+    }
+    for plan in plans {
+        lines.push(section(&format!(
+            "Plan {} ({} calls, mean {:.3} ms)",
+            plan.planid, plan.calls, plan.mean_time
+        )));
+        for line in resolve_hash(interner, plan.plan_hash).lines() {
+            lines.push(Line::raw(line.replace('\t', "    ")));
+        }
+        lines.push(Line::raw(""));
+    }
+    // Plan of the slowest execution logged by auto_explain in this interval
+    if let Some(slow) = captured {
+        lines.push(section(&format!(
+            "Captured plan (auto_explain, {:.3} ms, {} logged)",
+            slow.duration_ms, slow.count
+        )));
+        for line in resolve_hash(interner, slow.plan_hash).lines() {
+            lines.push(Line::raw(line.replace('\t', "    ")));
+        }
+        lines.push(Line::raw(""));
+    }
+
+    lines
+}
+
+fn render_sparklines(frame: &mut Frame, area: Rect, history: Option<&QueryHistory>, queryid: i64) {
+    let history = history.filter(|h| h.queryid == queryid);
+    let rows = Layout::vertical([Constraint::Length(SPARK_CELL_HEIGHT); 2]).split(area);
+    let top = Layout::horizontal([Constraint::Percentage(50); 2]).split(rows[0]);
+    let bottom = Layout::horizontal([Constraint::Percentage(50); 2]).split(rows[1]);
+
+    let empty: &[f64] = &[];
+    let series: [(&str, &[f64], Rect); 4] = [
+        ("calls/s", history.map_or(empty, |h| &h.calls_s), top[0]),
+        (
+            "time ms/s",
+            history.map_or(empty, |h| &h.exec_time_ms_s),
+            top[1],
+        ),
+        ("mean ms", history.map_or(empty, |h| &h.mean_ms), bottom[0]),
+        (
+            "shrd_rd/s",
+            history.map_or(empty, |h| &h.shared_blks_read_s),
+            bottom[1],
+        ),
+    ];
+
+    for (label, values, cell) in series {
+        let title = match (values.last(), values.iter().copied().reduce(f64::max)) {
+            (Some(last), Some(max)) => format!(" {label}: {last:.2} (max {max:.2}) "),
+            _ => format!(" {label}: no history "),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::TOP)
+            .border_style(Styles::dim());
+        let inner_width = cell.width as usize;
+        let visible = &values[values.len().saturating_sub(inner_width)..];
+        // Sparkline takes integers; keep two decimals of precision
+        let data: Vec<u64> = visible.iter().map(|v| (v * 100.0) as u64).collect();
+        let sparkline = Sparkline::default()
+            .block(block)
+            .data(&data)
+            .style(Styles::accent());
+        frame.render_widget(sparkline, cell);
+    }
+}
+
+fn find_statement(snapshot: &Snapshot, queryid: i64) -> Option<&PgStatStatementsInfo> {
+    snapshot.blocks.iter().find_map(|b| {
+        if let DataBlock::PgStatStatements(v) = b {
+            v.iter().find(|s| s.queryid == queryid)
+        } else {
+            None
+        }
+    })
+}
+
+fn find_plans(snapshot: &Snapshot, queryid: i64) -> Vec<&PgStorePlansInfo> {
+    snapshot
+        .blocks
+        .iter()
+        .find_map(|b| {
+            if let DataBlock::PgStorePlans(v) = b {
+                Some(v.iter().filter(|p| p.stmt_queryid == queryid).collect())
+            } else {
+                None
+            }
+        })
+        .unwrap_or_default()
+}
+
+fn find_slow_plan(snapshot: &Snapshot, queryid: i64) -> Option<&PgSlowPlanInfo> {
+    snapshot.blocks.iter().find_map(|b| {
+        if let DataBlock::PgSlowPlans(v) = b {
+            v.iter().find(|p| p.queryid == queryid)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(line: &Line) -> Vec<(String, Style)> {
+        line.spans
+            .iter()
+            .map(|s| (s.content.to_string(), s.style))
+            .collect()
+    }
+
+    #[test]
+    fn keywords_sorted_for_binary_search() {
+        assert!(SQL_KEYWORDS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn highlights_tokens() {
+        let lines =
+            highlight_sql("select id from t where name = 'it''s' and n > 10 and x = $1 -- c");
+        assert_eq!(lines.len(), 1);
+        let s = spans(&lines[0]);
+        assert_eq!(s[0], ("select".to_string(), keyword_style()));
+        assert!(s.contains(&("'it''s'".to_string(), string_style())));
+        assert!(s.contains(&("10".to_string(), number_style())));
+        assert!(s.contains(&("$1".to_string(), param_style())));
+        assert!(s.contains(&("-- c".to_string(), comment_style())));
+        assert!(s.contains(&("id".to_string(), Style::default())));
+        // Text is preserved
+        let text: String = s.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(
+            text,
+            "select id from t where name = 'it''s' and n > 10 and x = $1 -- c"
+        );
+    }
+
+    #[test]
+    fn multiline_string_and_comment() {
+        let lines = highlight_sql("/* a\nb */ SELECT 'x\ny'");
+        assert_eq!(lines.len(), 3);
+        assert_eq!(spans(&lines[0])[0], ("/* a".to_string(), comment_style()));
+        assert_eq!(spans(&lines[1])[0], ("b */".to_string(), comment_style()));
+        assert_eq!(spans(&lines[2])[0], ("y'".to_string(), string_style()));
+    }
+}
@@ -2,10 +2,11 @@
 
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
+use crate::tui::style::Styles;
+
 /// Renders a centered quit confirmation popup.
 pub fn render_quit_confirm(frame: &mut Frame, area: Rect) {
     // Calculate popup size (50% width, fixed height), clamped.
@@ -21,32 +22,32 @@ pub fn render_quit_confirm(frame: &mut Frame, area: Rect) {
     let block = Block::default()
         .title(" Exit rpglot ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Styles::popup_border());
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
     let content = vec![
         Line::from(Span::styled(
             "Are you sure you want to quit?",
-            Style::default().fg(Color::White),
+            Styles::popup(),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("Enter", Style::default().fg(Color::Yellow)),
-            Span::styled(" or ", Style::default().fg(Color::DarkGray)),
-            Span::styled("q", Style::default().fg(Color::Yellow)),
-            Span::styled(" → quit", Style::default().fg(Color::DarkGray)),
+            Span::styled("Enter", Styles::emphasis()),
+            Span::styled(" or ", Styles::dim()),
+            Span::styled("q", Styles::emphasis()),
+            Span::styled(" → quit", Styles::dim()),
         ]),
         Line::from(vec![
-            Span::styled("Esc", Style::default().fg(Color::Yellow)),
-            Span::styled(" or ", Style::default().fg(Color::DarkGray)),
-            Span::styled("n", Style::default().fg(Color::Yellow)),
-            Span::styled(" → cancel", Style::default().fg(Color::DarkGray)),
+            Span::styled("Esc", Styles::emphasis()),
+            Span::styled(" or ", Styles::dim()),
+            Span::styled("n", Styles::emphasis()),
+            Span::styled(" → cancel", Styles::dim()),
         ]),
     ];
 
     let paragraph = Paragraph::new(content)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::White));
+        .style(Styles::popup());
     frame.render_widget(paragraph, inner);
 }
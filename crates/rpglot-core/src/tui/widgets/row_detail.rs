@@ -0,0 +1,28 @@
+//! Row detail popup: every column of the selected table row, one per line.
+//! Works on all tabs, including columns hidden by the layout or scrolled away.
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+
+use crate::tui::state::{AppState, PopupState};
+
+use super::detail_common::{kv, render_popup_frame};
+
+pub fn render_row_detail(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    let title = format!("{} row", state.current_tab.name());
+    let content: Vec<Line<'static>> = if state.row_detail.is_empty() {
+        vec![Line::raw("No row selected")]
+    } else {
+        state
+            .row_detail
+            .iter()
+            .map(|(header, value)| kv(header, value))
+            .collect()
+    };
+
+    let PopupState::RowDetail { scroll } = &mut state.popup else {
+        return;
+    };
+    render_popup_frame(frame, area, &title, content, scroll, false);
+}
@@ -1,15 +1,16 @@
 //! Metric extraction from snapshots.
 
 use crate::storage::model::{
-    DataBlock, PgStatActivityInfo, PgStatBgwriterInfo, PgStatDatabaseInfo, ProcessInfo, Snapshot,
-    SystemCpuInfo, SystemDiskInfo, SystemNetInfo, SystemPsiInfo, SystemStatInfo, SystemVmstatInfo,
+    DataBlock, PgStatActivityInfo, PgStatArchiverInfo, PgStatBgwriterInfo, PgStatDatabaseInfo,
+    ProcessInfo, Snapshot, SystemCpuInfo, SystemDiskInfo, SystemNetInfo, SystemPsiInfo,
+    SystemStatInfo, SystemVmstatInfo, pg_pss_total,
 };
 
 use std::collections::HashSet;
 
 use super::{
-    BgwSummary, CpuMetrics, DiskSummary, NetSummary, PgSummary, PsiSummary, SummaryMetrics,
-    TOP_CPUS, TOP_DISKS, TOP_NETS, VmstatRates,
+    ArcSummary, BgwSummary, CgroupCpuUsage, CpuMetrics, DiskSummary, NetSummary, PgSummary,
+    PsiSummary, SummaryMetrics, TOP_CPUS, TOP_DISKS, TOP_NETS, VmstatRates,
 };
 
 /// Maximum realistic disk throughput (10 GB/s) - values above this indicate data issues
@@ -48,6 +49,7 @@ pub(super) fn extract_metrics(snapshot: &Snapshot, previous: Option<&Snapshot>)
         mem_slab: 0,
         mem_dirty: 0,
         mem_writeback: 0,
+        mem_pg_pss: None,
         swap_total: 0,
         swap_free: 0,
         cgroup_cpu: None,
@@ -60,6 +62,8 @@ pub(super) fn extract_metrics(snapshot: &Snapshot, previous: Option<&Snapshot>)
         vmstat_rates: None,
         pg_summary: None,
         bgw_summary: None,
+        arc_summary: None,
+        k8s_pod: None,
     };
 
     // We compute top disks after the loop because for containers the filtering
@@ -123,6 +127,9 @@ pub(super) fn extract_metrics(snapshot: &Snapshot, previous: Option<&Snapshot>)
                 metrics.cgroup_memory = cg.memory.clone();
                 metrics.cgroup_pids = cg.pids.clone();
             }
+            DataBlock::K8sPod(pod) => {
+                metrics.k8s_pod = Some(pod.clone());
+            }
             DataBlock::SystemPsi(psi_list) => {
                 metrics.psi = extract_psi(psi_list);
             }
@@ -179,6 +186,23 @@ pub(super) fn extract_metrics(snapshot: &Snapshot, previous: Option<&Snapshot>)
                 });
                 metrics.bgw_summary = extract_bgw_summary(bgw, prev_bgw, delta_time);
             }
+            DataBlock::PgStatArchiver(arc) => {
+                let prev_arc = previous.and_then(|p| {
+                    p.blocks.iter().find_map(|b| {
+                        if let DataBlock::PgStatArchiver(v) = b {
+                            Some(v)
+                        } else {
+                            None
+                        }
+                    })
+                });
+                metrics.arc_summary = Some(extract_arc_summary(
+                    arc,
+                    prev_arc,
+                    delta_time,
+                    snapshot.timestamp,
+                ));
+            }
             _ => {}
         }
     }
@@ -198,6 +222,8 @@ pub(super) fn extract_metrics(snapshot: &Snapshot, previous: Option<&Snapshot>)
             extract_top_nets(nets, prev_nets, metrics.delta_time, is_container_snapshot);
     }
 
+    metrics.mem_pg_pss = find_processes(snapshot).and_then(pg_pss_total);
+
     // Compute Backend IO Hit Ratio for PG processes if pg_summary exists
     if let Some(ref mut pg) = metrics.pg_summary {
         pg.backend_io_hit = compute_backend_io_hit(snapshot, previous);
@@ -215,6 +241,14 @@ pub(super) fn extract_metrics(snapshot: &Snapshot, previous: Option<&Snapshot>)
             })
             .sum();
         pg.errors = error_count;
+
+        pg.conn_s = snapshot.blocks.iter().find_map(|b| {
+            if let DataBlock::PgConnectionChurn(churn) = b {
+                Some(churn.connections_per_sec())
+            } else {
+                None
+            }
+        });
     }
 
     metrics
@@ -268,10 +302,76 @@ fn extract_cpu_metrics(cpus: &[SystemCpuInfo], metrics: &mut SummaryMetrics) {
     metrics.top_cpus = per_cpu.into_iter().take(TOP_CPUS).collect();
 }
 
+/// Total CPU busy percent (100 - idle) over the interval since `previous`,
+/// or since boot when there is no previous snapshot.
+pub(super) fn cpu_busy_pct(snapshot: &Snapshot, previous: Option<&Snapshot>) -> f64 {
+    fn total_cpu(snapshot: &Snapshot) -> Option<(u64, u64)> {
+        snapshot.blocks.iter().find_map(|b| match b {
+            DataBlock::SystemCpu(cpus) => cpus.iter().find(|c| c.cpu_id == -1).map(|c| {
+                let total =
+                    c.user + c.nice + c.system + c.idle + c.iowait + c.irq + c.softirq + c.steal;
+                (total, c.idle)
+            }),
+            _ => None,
+        })
+    }
+
+    let Some((total, idle)) = total_cpu(snapshot) else {
+        return 0.0;
+    };
+    let (prev_total, prev_idle) = previous.and_then(total_cpu).unwrap_or((0, 0));
+    let d_total = total.saturating_sub(prev_total);
+    if d_total == 0 {
+        return 0.0;
+    }
+    let d_idle = idle.saturating_sub(prev_idle);
+    (100.0 - d_idle as f64 / d_total as f64 * 100.0).clamp(0.0, 100.0)
+}
+
+/// Container CPU usage against the `cpu.max` quota, when cgroup data is present.
+pub(super) fn cgroup_cpu_usage(metrics: &SummaryMetrics) -> Option<CgroupCpuUsage> {
+    let curr = metrics.cgroup_cpu.as_ref()?;
+
+    // If we got here, quota/period should be valid, but keep it defensive.
+    let limit_cores = if curr.quota > 0 && curr.period > 0 {
+        curr.quota as f64 / curr.period as f64
+    } else {
+        0.0
+    };
+
+    let prev = metrics.cgroup_cpu_prev.as_ref().unwrap_or(curr);
+    let delta_usage = curr.usage_usec.saturating_sub(prev.usage_usec);
+    let delta_user = curr.user_usec.saturating_sub(prev.user_usec);
+    let delta_system = curr.system_usec.saturating_sub(prev.system_usec);
+
+    let used_pct = if metrics.delta_time > 0.0 && limit_cores > 0.0 {
+        let delta_usage_s = delta_usage as f64 / 1_000_000.0;
+        ((delta_usage_s / metrics.delta_time) / limit_cores) * 100.0
+    } else {
+        0.0
+    };
+    let share = |part: u64| {
+        if delta_usage > 0 {
+            (part as f64 / delta_usage as f64) * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    Some(CgroupCpuUsage {
+        limit_cores,
+        used_pct,
+        usr_pct: share(delta_user),
+        sys_pct: share(delta_system),
+        throttled_ms: curr.throttled_usec.saturating_sub(prev.throttled_usec) / 1000,
+        nr_throttled: curr.nr_throttled.saturating_sub(prev.nr_throttled),
+    })
+}
+
 /// Gets delta time between snapshots in seconds.
 fn get_delta_time(current: &Snapshot, previous: Option<&Snapshot>) -> f64 {
     if let Some(prev) = previous {
-        let delta = current.timestamp - prev.timestamp;
+        let delta = current.elapsed_secs(prev);
         if delta > 0 {
             return delta as f64;
         }
@@ -555,6 +655,29 @@ fn extract_bgw_summary(
     })
 }
 
+/// Extracts WAL archiver summary from pg_stat_archiver. The archive rate
+/// needs a previous snapshot; status and backlog do not.
+fn extract_arc_summary(
+    curr: &PgStatArchiverInfo,
+    prev: Option<&PgStatArchiverInfo>,
+    delta_time: f64,
+    timestamp: i64,
+) -> ArcSummary {
+    let prev = prev.filter(|_| delta_time > 0.0);
+    ArcSummary {
+        archived_per_min: prev.map(|p| {
+            curr.archived_count.saturating_sub(p.archived_count).max(0) as f64 / delta_time * 60.0
+        }),
+        failed: prev.map_or(0, |p| {
+            curr.failed_count.saturating_sub(p.failed_count).max(0)
+        }),
+        failing: curr.is_failing(),
+        pending: curr.pending_segments(),
+        last_archived_age_s: (curr.last_archived_time > 0)
+            .then(|| (timestamp - curr.last_archived_time).max(0)),
+    }
+}
+
 /// Extracts aggregated PostgreSQL summary from pg_stat_database.
 /// Returns None if previous snapshot is unavailable (rates need two points).
 fn extract_pg_summary(
@@ -611,7 +734,8 @@ fn extract_pg_summary(
         tup_s: sum_tup as f64 / delta_time,
         tmp_bytes_s: sum_temp_bytes as f64 / delta_time,
         deadlocks: sum_deadlocks,
-        errors: 0, // Filled in extract_metrics() from PgLogErrors
+        errors: 0,    // Filled in extract_metrics() from PgLogErrors
+        conn_s: None, // Filled in extract_metrics() from PgConnectionChurn
     })
 }
 
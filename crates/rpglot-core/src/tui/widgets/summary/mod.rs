@@ -7,6 +7,7 @@
 //! Uses fixed-width metrics with right-aligned values for stable display.
 
 mod extract;
+mod plain;
 mod render_lines;
 
 use ratatui::Frame;
@@ -14,11 +15,14 @@ use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 
-use crate::storage::model::{CgroupCpuInfo, CgroupMemoryInfo, CgroupPidsInfo, DataBlock, Snapshot};
-use crate::tui::state::Tab;
+use crate::storage::model::{
+    CgroupCpuInfo, CgroupMemoryInfo, CgroupPidsInfo, DataBlock, K8sPodInfo, Snapshot,
+};
+use crate::tui::state::{SummaryHistory, SummarySample, Tab};
 use crate::tui::style::Styles;
 
-use extract::extract_metrics;
+use extract::{cpu_busy_pct, extract_metrics};
+pub use plain::plain_summary;
 use render_lines::*;
 
 const TOP_CPUS: usize = 5;
@@ -125,7 +129,7 @@ pub fn calculate_summary_height(snapshot: Option<&Snapshot>) -> u16 {
             })
             .unwrap_or((false, false));
 
-        // Left column: MEM(+SWP) + DSK×N + NET×N + PG
+        // Left column: MEM(+SWP) + DSK×N + NET×N + PG + POD
         // In container mode with memory limit, SWP is hidden.
         let left_base = if cgroup_mem_limited { 1 } else { 2 };
 
@@ -137,10 +141,25 @@ pub fn calculate_summary_height(snapshot: Option<&Snapshot>) -> u16 {
             .blocks
             .iter()
             .any(|b| matches!(b, DataBlock::PgStatBgwriter(_)));
-        let pg_lines =
-            (if has_pg_database { 1 } else { 0 }) + (if has_pg_bgwriter { 1 } else { 0 });
+        let has_pg_archiver = snap
+            .blocks
+            .iter()
+            .any(|b| matches!(b, DataBlock::PgStatArchiver(_)));
+        let pg_lines = (if has_pg_database { 1 } else { 0 })
+            + (if has_pg_bgwriter { 1 } else { 0 })
+            + (if has_pg_archiver { 1 } else { 0 });
+
+        let pod_lines = if snap
+            .blocks
+            .iter()
+            .any(|b| matches!(b, DataBlock::K8sPod(_)))
+        {
+            1
+        } else {
+            0
+        };
 
-        let left_lines = left_base + disk_count + net_count + pg_lines;
+        let left_lines = left_base + disk_count + net_count + pg_lines + pod_lines;
 
         // Right column: CPL + CPU (+ cpu×N) + PSI + VMS
         // In container mode with CPU quota, per-CPU breakdown is not shown.
@@ -162,8 +181,8 @@ pub fn calculate_summary_height(snapshot: Option<&Snapshot>) -> u16 {
         let vmstat_lines = if has_vmstat { 1 } else { 0 };
         let right_lines = 2 + right_cpu_lines + psi_lines + vmstat_lines;
 
-        // Max of both columns + help line
-        (left_lines.max(right_lines) + 1) as u16
+        // Max of both columns + sparkline line + help line
+        (left_lines.max(right_lines) + 2) as u16
     } else {
         // Waiting for data: 2 lines (message + help)
         2
@@ -177,6 +196,7 @@ pub fn render_summary(
     area: Rect,
     snapshot: Option<&Snapshot>,
     previous_snapshot: Option<&Snapshot>,
+    history: &SummaryHistory,
     current_tab: Tab,
 ) {
     if let Some(snap) = snapshot {
@@ -186,9 +206,10 @@ pub fn render_summary(
         let left_width = calculate_left_column_width();
         let right_width = calculate_right_column_width();
 
-        // Split area: left column | separator | right column | help at bottom
+        // Split area: left column | separator | right column, then sparklines and help
         let main_chunks = Layout::vertical([
             Constraint::Min(1),    // Main content
+            Constraint::Length(1), // Sparklines
             Constraint::Length(1), // Help line
         ])
         .split(area);
@@ -242,9 +263,13 @@ pub fn render_summary(
         frame.render_widget(Paragraph::new(separator_lines), columns[1]);
         frame.render_widget(Paragraph::new(right_padded), columns[2]);
 
+        // Sparklines of recent snapshots
+        let history_line = render_history_line(history, area.width as usize);
+        frame.render_widget(Paragraph::new(vec![history_line]), main_chunks[1]);
+
         // Help line
         let help_line = render_help_line(area.width as usize, current_tab);
-        frame.render_widget(Paragraph::new(vec![help_line]), main_chunks[1]);
+        frame.render_widget(Paragraph::new(vec![help_line]), main_chunks[2]);
     } else {
         let lines = vec![
             Line::from("Waiting for data..."),
@@ -254,6 +279,17 @@ pub fn render_summary(
     }
 }
 
+/// Summary metrics of `snapshot` for the sparkline history.
+pub fn summary_sample(snapshot: &Snapshot, previous: Option<&Snapshot>) -> SummarySample {
+    let metrics = extract_metrics(snapshot, previous);
+    SummarySample {
+        timestamp: snapshot.timestamp,
+        cpu: cpu_busy_pct(snapshot, previous),
+        tps: metrics.pg_summary.as_ref().map_or(0.0, |pg| pg.tps),
+        disk_util: metrics.top_disks.iter().map(|d| d.util).fold(0.0, f64::max),
+    }
+}
+
 /// Calculate minimum width for left column (MEM, SWP, DSK, NET).
 fn calculate_left_column_width() -> usize {
     // MEM line is widest: "MEM │ " + metrics
@@ -310,6 +346,16 @@ fn build_left_column(metrics: &SummaryMetrics, width: usize) -> Vec<Line<'static
         lines.push(render_bgw_line(bgw, width));
     }
 
+    // Add WAL archiver line when archive_mode is on
+    if let Some(ref arc) = metrics.arc_summary {
+        lines.push(render_arc_line(arc, width));
+    }
+
+    // Add Kubernetes pod line when running in a pod
+    if let Some(ref pod) = metrics.k8s_pod {
+        lines.push(render_pod_line(pod, metrics, width));
+    }
+
     lines
 }
 
@@ -369,6 +415,8 @@ struct SummaryMetrics {
     mem_slab: u64,
     mem_dirty: u64,
     mem_writeback: u64,
+    /// PSS sum of PostgreSQL processes, when smaps_rollup was read.
+    mem_pg_pss: Option<u64>,
 
     // Swap (in KB)
     swap_total: u64,
@@ -397,6 +445,24 @@ struct SummaryMetrics {
 
     // PostgreSQL bgwriter summary (from pg_stat_bgwriter)
     bgw_summary: Option<BgwSummary>,
+
+    // WAL archiver summary (from pg_stat_archiver, archive_mode on)
+    arc_summary: Option<ArcSummary>,
+
+    // Kubernetes pod metadata (container mode)
+    k8s_pod: Option<K8sPodInfo>,
+}
+
+/// Container CPU usage over the interval, from two cgroup `cpu.stat` reads.
+struct CgroupCpuUsage {
+    /// `cpu.max` quota in cores.
+    limit_cores: f64,
+    /// Usage as percent of the quota.
+    used_pct: f64,
+    usr_pct: f64,
+    sys_pct: f64,
+    throttled_ms: u64,
+    nr_throttled: u64,
 }
 
 /// CPU metrics for a single CPU or total.
@@ -482,6 +548,8 @@ struct PgSummary {
     deadlocks: i64,
     /// Total error count from PgLogErrors in current snapshot.
     errors: u32,
+    /// New connections per second from PgConnectionChurn (None if block absent).
+    conn_s: Option<f64>,
 }
 
 /// PostgreSQL background writer summary (rates from pg_stat_bgwriter).
@@ -501,6 +569,21 @@ struct BgwSummary {
     buffers_alloc_s: f64,
 }
 
+/// WAL archiver summary (pg_stat_archiver).
+#[derive(Clone, Default)]
+struct ArcSummary {
+    /// WAL files archived per minute (None without a previous snapshot).
+    archived_per_min: Option<f64>,
+    /// Failed attempts in this interval.
+    failed: i64,
+    /// The most recent attempt failed.
+    failing: bool,
+    /// Completed segments not archived yet (None on a standby).
+    pending: Option<u64>,
+    /// Seconds since the last successful archive (None if never).
+    last_archived_age_s: Option<i64>,
+}
+
 // ============================================================================
 // Fixed-width metric formatting (Variant 2: right-aligned values)
 // Each metric has a fixed width: "label:" + right-aligned value
@@ -526,6 +609,7 @@ pub(super) mod metric_widths {
     pub const MEM_CACHE: usize = 17; // "cache:" (6) + value (11) = "cache:   1.3 GiB"
     pub const MEM_BUF: usize = 15; // "buf:" (4) + value (11) = "buf:    92 KiB"
     pub const MEM_SLAB: usize = 15; // "slab:" (5) + value (10) = "slab: 742 MiB"
+    pub const MEM_PG: usize = 13; // "pg:" (3) + value (10) = "pg:   3.1 GiB"
     pub const SWP_FREE: usize = 17; // "free:" (5) + value (12) = "free:  16.7 GiB"
     pub const SWP_SWPD: usize = 16; // "swpd:" (5) + value (11) = "swpd:    0 KiB"
     pub const SWP_DIRTY: usize = 16; // "dirty:" (6) + value (10) = "dirty:  60 KiB"
@@ -554,6 +638,7 @@ pub(super) mod metric_widths {
     pub const PG_TMP: usize = 16; // "tmp:" (4) + value (12) — fits "100.0M/s"
     pub const PG_DLOCK: usize = 11; // "dlock:" (6) + value (5)
     pub const PG_ERR: usize = 9; // "err:" (4) + value (5)
+    pub const PG_CONN: usize = 11; // "conn:" (5) + value (6) — fits "12.5/s"
 
     // BGW line: ckpt: 0.0/m  wr:    0ms  be:      0  cln:  224/s  mxw:     0  alloc:  770/s
     // Aligned with PG: 1-space separators, wider fields for stable layout
@@ -563,6 +648,12 @@ pub(super) mod metric_widths {
     pub const BGW_CLN: usize = 13; // "cln:" (4) + value (9) — fits "1.2K/s"
     pub const BGW_MXW: usize = 11; // "mxw:" (4) + value (7) — fits "12345"
     pub const BGW_ALLOC: usize = 15; // "alloc:" (6) + value (9) — fits "5.6K/s"
+
+    // ARC line: arch:  1.2/m  fail:     0  pend:     3  last:   12s
+    pub const ARC_ARCH: usize = 13; // "arch:" (5) + value (8) — fits "12.3/m"
+    pub const ARC_FAIL: usize = 11; // "fail:" (5) + value (6)
+    pub const ARC_PEND: usize = 11; // "pend:" (5) + value (6)
+    pub const ARC_LAST: usize = 12; // "last:" (5) + value (7) — fits "3h12m"
 }
 
 use metric_widths::*;
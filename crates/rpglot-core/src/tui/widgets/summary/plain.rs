@@ -0,0 +1,267 @@
+//! Plain-text summary for `rpglot --plain`.
+//!
+//! The same metrics as the summary panel, one `LABEL key: value ...` line
+//! per group and no styling, so the output can go to a pipe or a log.
+
+use crate::fmt::{self, FmtStyle};
+use crate::storage::model::Snapshot;
+
+use super::extract::{cgroup_cpu_usage, extract_metrics};
+use super::{CpuMetrics, SummaryMetrics};
+
+/// Summary of `snapshot` as plain text lines, rates taken since `previous`.
+pub fn plain_summary(snapshot: &Snapshot, previous: Option<&Snapshot>) -> Vec<String> {
+    let m = extract_metrics(snapshot, previous);
+    let mut lines = vec![
+        fmt::format_timestamp(snapshot.timestamp, "%Y-%m-%d %H:%M:%S %Z")
+            .unwrap_or_else(|| snapshot.timestamp.to_string()),
+    ];
+
+    lines.push(line(
+        "CPL",
+        &[
+            ("avg1", format!("{:.2}", m.load1)),
+            ("avg5", format!("{:.2}", m.load5)),
+            ("avg15", format!("{:.2}", m.load15)),
+            ("procs", m.nr_procs.to_string()),
+            ("run", m.nr_running.to_string()),
+        ],
+    ));
+    match cgroup_cpu_usage(&m).filter(|u| u.limit_cores > 0.0) {
+        Some(u) => lines.push(line(
+            "CPU",
+            &[
+                ("lim", format!("{:.1}", u.limit_cores)),
+                ("used", format!("{:.0}%", u.used_pct.min(999.0))),
+                ("usr", format!("{:.0}%", u.usr_pct)),
+                ("sys", format!("{:.0}%", u.sys_pct)),
+                ("thrtl", format!("{}ms", u.throttled_ms)),
+                ("nr", u.nr_throttled.to_string()),
+            ],
+        )),
+        None => lines.push(cpu_line(&m.cpu_total)),
+    }
+
+    lines.extend(memory_lines(&m));
+    for d in &m.top_disks {
+        lines.push(line(
+            &format!("DSK {}", d.name),
+            &[
+                ("rMB", format!("{:.1}", d.read_mb_s)),
+                ("wMB", format!("{:.1}", d.write_mb_s)),
+                ("rd/s", format!("{:.0}", d.r_iops)),
+                ("wr/s", format!("{:.0}", d.w_iops)),
+                ("aw", format!("{:.1}", d.r_await.max(d.w_await))),
+                ("ut", format!("{:.0}%", d.util)),
+            ],
+        ));
+    }
+    for n in &m.top_nets {
+        lines.push(line(
+            &format!("NET {}", n.name),
+            &[
+                ("rxMB", format!("{:.1}", n.rx_mb_s)),
+                ("txMB", format!("{:.1}", n.tx_mb_s)),
+                ("rxPk", rate(n.rx_pkt_s)),
+                ("txPk", rate(n.tx_pkt_s)),
+                ("er", n.errors.to_string()),
+                ("dr", format!("{:.0}", n.rx_drp_s + n.tx_drp_s)),
+            ],
+        ));
+    }
+    if !m.psi.is_empty() {
+        let fields: Vec<_> = m
+            .psi
+            .iter()
+            .map(|p| (p.name, format!("{:.1}%", p.some)))
+            .collect();
+        lines.push(line("PSI", &fields));
+    }
+    if let Some(ref v) = m.vmstat_rates {
+        lines.push(line(
+            "VMS",
+            &[
+                ("pgin", rate(v.pgpgin_s)),
+                ("pgout", rate(v.pgpgout_s)),
+                ("swin", rate(v.pswpin_s)),
+                ("swout", rate(v.pswpout_s)),
+                ("flt", rate(v.pgfault_s)),
+                ("ctx", rate(v.ctxt_s)),
+            ],
+        ));
+    }
+
+    if let Some(ref pg) = m.pg_summary {
+        let mut fields = vec![
+            ("tps", rate(pg.tps)),
+            ("hit", format!("{:.1}%", pg.hit_ratio)),
+            ("iohr", format!("{:.1}%", pg.backend_io_hit)),
+            ("tup", rate(pg.tup_s)),
+            (
+                "tmp",
+                fmt::format_bytes_rate(pg.tmp_bytes_s, FmtStyle::Compact),
+            ),
+            ("dlock", pg.deadlocks.to_string()),
+            ("err", pg.errors.to_string()),
+        ];
+        if let Some(conn_s) = pg.conn_s {
+            fields.push(("conn", format!("{conn_s:.1}/s")));
+        }
+        lines.push(line("PG", &fields));
+    }
+    if let Some(ref bgw) = m.bgw_summary {
+        lines.push(line(
+            "BGW",
+            &[
+                ("ckpt", format!("{:.1}/m", bgw.checkpoints_per_min)),
+                (
+                    "wr",
+                    fmt::format_ms(bgw.ckpt_write_time_ms, FmtStyle::Compact),
+                ),
+                ("be", rate(bgw.buffers_backend_s)),
+                ("cln", rate(bgw.buffers_clean_s)),
+                ("mxw", bgw.maxwritten_clean.to_string()),
+                ("alloc", rate(bgw.buffers_alloc_s)),
+            ],
+        ));
+    }
+    if let Some(ref arc) = m.arc_summary {
+        let mut arc_line = line(
+            "ARC",
+            &[
+                (
+                    "arch",
+                    arc.archived_per_min
+                        .map_or("-".to_string(), |r| format!("{r:.1}/m")),
+                ),
+                ("fail", arc.failed.to_string()),
+                (
+                    "pend",
+                    arc.pending.map_or("-".to_string(), |p| p.to_string()),
+                ),
+                (
+                    "last",
+                    arc.last_archived_age_s.map_or("-".to_string(), |s| {
+                        fmt::format_duration(s, FmtStyle::Compact)
+                    }),
+                ),
+            ],
+        );
+        if arc.failing {
+            arc_line.push_str("  FAILING");
+        }
+        lines.push(arc_line);
+    }
+    if let Some(ref pod) = m.k8s_pod {
+        let mut pod_line = format!("POD {}/{}", pod.namespace, pod.pod);
+        if !pod.node.is_empty() {
+            pod_line.push_str(&format!("  node: {}", pod.node));
+        }
+        lines.push(pod_line);
+    }
+
+    lines
+}
+
+/// MEM and SWP lines, or the container MEM line when a memory limit is set.
+fn memory_lines(m: &SummaryMetrics) -> Vec<String> {
+    if let Some(mem) = m.cgroup_memory.as_ref().filter(|mem| mem.max != u64::MAX) {
+        return vec![line(
+            "MEM",
+            &[
+                ("lim", bytes(mem.max)),
+                ("used", bytes(mem.current)),
+                ("anon", bytes(mem.anon)),
+                ("file", bytes(mem.file)),
+                ("slab", bytes(mem.slab)),
+                ("oom", mem.oom_kill.to_string()),
+            ],
+        )];
+    }
+
+    let kb = |v: u64| bytes(v * 1024);
+    let mut mem = vec![
+        ("tot", kb(m.mem_total)),
+        ("avail", kb(m.mem_available)),
+        ("cache", kb(m.mem_cached)),
+        ("buf", kb(m.mem_buffers)),
+        ("slab", kb(m.mem_slab)),
+    ];
+    if let Some(pg_pss) = m.mem_pg_pss {
+        mem.push(("pg", kb(pg_pss)));
+    }
+    vec![
+        line("MEM", &mem),
+        line(
+            "SWP",
+            &[
+                ("tot", kb(m.swap_total)),
+                ("swpd", kb(m.swap_total.saturating_sub(m.swap_free))),
+                ("dirty", kb(m.mem_dirty)),
+                ("wback", kb(m.mem_writeback)),
+            ],
+        ),
+    ]
+}
+
+fn cpu_line(cpu: &CpuMetrics) -> String {
+    line(
+        "CPU",
+        &[
+            ("sys", format!("{:.1}%", cpu.sys)),
+            ("usr", format!("{:.1}%", cpu.usr)),
+            ("irq", format!("{:.1}%", cpu.irq)),
+            ("iow", format!("{:.1}%", cpu.iow)),
+            ("idle", format!("{:.1}%", cpu.idle)),
+            ("stl", format!("{:.1}%", cpu.steal)),
+        ],
+    )
+}
+
+fn line(label: &str, fields: &[(&str, String)]) -> String {
+    let mut out = label.to_string();
+    for (key, value) in fields {
+        out.push_str(&format!("  {key}: {value}"));
+    }
+    out
+}
+
+fn rate(v: f64) -> String {
+    fmt::format_rate(v, FmtStyle::Compact)
+}
+
+fn bytes(v: u64) -> String {
+    fmt::format_bytes(v, FmtStyle::Compact)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::{DataBlock, SystemLoadInfo};
+
+    #[test]
+    fn plain_summary_prints_one_line_per_group() {
+        let snapshot = Snapshot {
+            timestamp: 0,
+            blocks: vec![DataBlock::SystemLoad(SystemLoadInfo {
+                lavg1: 1.5,
+                nr_running: 3,
+                ..Default::default()
+            })],
+        };
+        let lines = plain_summary(&snapshot, None);
+
+        assert_eq!(
+            Some(&lines[0]),
+            fmt::format_timestamp(0, "%Y-%m-%d %H:%M:%S %Z").as_ref()
+        );
+        assert_eq!(
+            lines[1],
+            "CPL  avg1: 1.50  avg5: 0.00  avg15: 0.00  procs: 0  run: 3"
+        );
+        assert!(lines[2].starts_with("CPU  sys: 0.0%"));
+        assert!(lines.iter().any(|l| l.starts_with("MEM  tot:")));
+        // No PostgreSQL data, no PG lines
+        assert!(!lines.iter().any(|l| l.starts_with("PG")));
+    }
+}
@@ -4,13 +4,15 @@ use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 
 use crate::fmt::{self, FmtStyle};
-use crate::tui::state::Tab;
+use crate::storage::model::K8sPodInfo;
+use crate::tui::state::{SUMMARY_HISTORY_LEN, SummaryHistory, Tab};
 use crate::tui::style::Styles;
 
+use super::extract::cgroup_cpu_usage;
 use super::metric_widths::*;
 use super::{
-    BgwSummary, CpuMetrics, DiskSummary, NetSummary, PgSummary, PsiSummary, SummaryMetrics,
-    VmstatRates,
+    ArcSummary, BgwSummary, CpuMetrics, DiskSummary, NetSummary, PgSummary, PsiSummary,
+    SummaryMetrics, VmstatRates,
 };
 
 fn line_with_padding(spans: Vec<Span<'static>>, width: usize) -> Line<'static> {
@@ -153,41 +155,10 @@ pub(super) fn render_cpu_line(
 ///
 /// Displayed only when `cpu.max` sets a quota (`quota > 0`).
 pub(super) fn render_cgroup_cpu_line(metrics: &SummaryMetrics, width: usize) -> Line<'static> {
-    let Some(curr) = metrics.cgroup_cpu.as_ref() else {
+    let Some(usage) = cgroup_cpu_usage(metrics) else {
         return render_cpu_line(&metrics.cpu_total, true, -1, width);
     };
-
-    // If we got here, quota/period should be valid, but keep it defensive.
-    let limit_cores = if curr.quota > 0 && curr.period > 0 {
-        curr.quota as f64 / curr.period as f64
-    } else {
-        0.0
-    };
-
-    let prev = metrics.cgroup_cpu_prev.as_ref().unwrap_or(curr);
-    let delta_usage = curr.usage_usec.saturating_sub(prev.usage_usec);
-    let delta_user = curr.user_usec.saturating_sub(prev.user_usec);
-    let delta_system = curr.system_usec.saturating_sub(prev.system_usec);
-    let delta_throttled_ms = curr.throttled_usec.saturating_sub(prev.throttled_usec) / 1000;
-    let delta_nr_throttled = curr.nr_throttled.saturating_sub(prev.nr_throttled);
-
-    let used_pct = if metrics.delta_time > 0.0 && limit_cores > 0.0 {
-        let delta_usage_s = delta_usage as f64 / 1_000_000.0;
-        ((delta_usage_s / metrics.delta_time) / limit_cores) * 100.0
-    } else {
-        0.0
-    };
-
-    let usr_pct = if delta_usage > 0 {
-        (delta_user as f64 / delta_usage as f64) * 100.0
-    } else {
-        0.0
-    };
-    let sys_pct = if delta_usage > 0 {
-        (delta_system as f64 / delta_usage as f64) * 100.0
-    } else {
-        0.0
-    };
+    let delta_throttled_ms = usage.throttled_ms;
 
     let thrtl_style = if delta_throttled_ms > 1000 {
         Styles::critical()
@@ -201,25 +172,25 @@ pub(super) fn render_cgroup_cpu_line(metrics: &SummaryMetrics, width: usize) ->
 
     spans.extend(metric_spans_default(
         "lim",
-        &format!("{:.1}", limit_cores),
+        &format!("{:.1}", usage.limit_cores),
         CG_LIM,
     ));
     spans.push(Span::raw("  "));
     spans.extend(metric_spans_default(
         "used",
-        &format!("{:.0}%", used_pct.min(999.0)),
+        &format!("{:.0}%", usage.used_pct.min(999.0)),
         CPU_PCT,
     ));
     spans.push(Span::raw("  "));
     spans.extend(metric_spans_default(
         "usr",
-        &format!("{:.0}%", usr_pct),
+        &format!("{:.0}%", usage.usr_pct),
         CPU_PCT,
     ));
     spans.push(Span::raw("  "));
     spans.extend(metric_spans_default(
         "sys",
-        &format!("{:.0}%", sys_pct),
+        &format!("{:.0}%", usage.sys_pct),
         CPU_PCT,
     ));
     spans.push(Span::raw("  "));
@@ -232,7 +203,7 @@ pub(super) fn render_cgroup_cpu_line(metrics: &SummaryMetrics, width: usize) ->
     spans.push(Span::raw("  "));
     spans.extend(metric_spans_default(
         "nr",
-        &format!("{}", delta_nr_throttled),
+        &format!("{}", usage.nr_throttled),
         CG_NR,
     ));
 
@@ -322,8 +293,86 @@ pub(super) fn render_vmstat_line(rates: &VmstatRates, width: usize) -> Line<'sta
     line_with_padding(spans, width)
 }
 
+/// Renders sparklines of recent snapshots (CPU busy, TPS, busiest disk util).
+/// Format: HIS │ cpu ⣀⣠⣴⣾  37% tps ⣀⣀⣤⣤  1.2K/s dsk ⣀⣀⣀⣠  26%
+pub(super) fn render_history_line(history: &SummaryHistory, width: usize) -> Line<'static> {
+    const CPU_VALUE: usize = 5;
+    const TPS_VALUE: usize = 8;
+    const DSK_VALUE: usize = 5;
+    // "HIS │ " + three "xxx " labels + values + gaps
+    let fixed = 6 + 3 * 4 + CPU_VALUE + TPS_VALUE + DSK_VALUE + 2 * 2;
+    let graph_width = (width.saturating_sub(fixed) / 3).clamp(4, SUMMARY_HISTORY_LEN / 2);
+
+    let last = history.last().copied().unwrap_or_default();
+    let cpu = history.values(|s| s.cpu);
+    let tps = history.values(|s| s.tps);
+    let dsk = history.values(|s| s.disk_util);
+    let tps_max = tps.iter().copied().fold(0.0, f64::max);
+
+    let mut spans = vec![Span::styled("HIS", Styles::dim()), Span::raw(" │ ")];
+    spans.push(Span::raw("cpu "));
+    spans.push(Span::styled(
+        braille_sparkline(&cpu, 100.0, graph_width),
+        Styles::cpu(),
+    ));
+    spans.push(Span::styled(
+        format!("{:>w$}", format!("{:.0}%", last.cpu), w = CPU_VALUE),
+        style_for_cpu_busy(last.cpu),
+    ));
+    spans.push(Span::raw("  tps "));
+    spans.push(Span::styled(
+        braille_sparkline(&tps, tps_max, graph_width),
+        Styles::active(),
+    ));
+    spans.push(Span::raw(format!(
+        "{:>w$}",
+        fmt::format_rate(last.tps, FmtStyle::Compact),
+        w = TPS_VALUE
+    )));
+    spans.push(Span::raw("  dsk "));
+    spans.push(Span::styled(
+        braille_sparkline(&dsk, 100.0, graph_width),
+        Styles::disk(),
+    ));
+    spans.push(Span::styled(
+        format!("{:>w$}", format!("{:.0}%", last.disk_util), w = DSK_VALUE),
+        style_for_disk_util(last.disk_util),
+    ));
+
+    line_with_padding(spans, width)
+}
+
+/// Renders the last `width * 2` values as a braille sparkline, `width`
+/// characters wide. Each character holds two samples of 0-4 dots scaled to
+/// `max`; missing samples on the left are blank.
+fn braille_sparkline(values: &[f64], max: f64, width: usize) -> String {
+    // Dot bits filled from the bottom row up, for the left and right column.
+    const LEFT: [u32; 4] = [0x40, 0x04, 0x02, 0x01];
+    const RIGHT: [u32; 4] = [0x80, 0x20, 0x10, 0x08];
+
+    let level = |v: f64| -> usize {
+        if max <= 0.0 || v <= 0.0 {
+            0
+        } else {
+            ((v / max) * 4.0).ceil().clamp(1.0, 4.0) as usize
+        }
+    };
+    let shown = &values[values.len().saturating_sub(width * 2)..];
+    let levels: Vec<usize> = std::iter::repeat_n(0, width * 2 - shown.len())
+        .chain(shown.iter().map(|&v| level(v)))
+        .collect();
+    levels
+        .chunks(2)
+        .map(|pair| {
+            let bits: u32 =
+                LEFT[..pair[0]].iter().sum::<u32>() + RIGHT[..pair[1]].iter().sum::<u32>();
+            char::from_u32(0x2800 + bits).unwrap_or(' ')
+        })
+        .collect()
+}
+
 /// Renders PostgreSQL summary line from pg_stat_database.
-/// Format: PG  │ tps:    750/s  hit:  99.3%  iohr:  98.5%  tup: 410.4K/s  tmp:          0  dlock:     0
+/// Format: PG  │ tps:    750/s  hit:  99.3%  iohr:  98.5%  tup: 410.4K/s  tmp:          0  dlock:     0  err:    0  conn:  2.5/s
 pub(super) fn render_pg_line(pg: &PgSummary, width: usize) -> Line<'static> {
     let mut spans = vec![Span::styled(" PG", Styles::cpu()), Span::raw(" │ ")];
 
@@ -411,6 +460,24 @@ pub(super) fn render_pg_line(pg: &PgSummary, width: usize) -> Line<'static> {
         err_style,
     ));
 
+    // Connection churn is only known when the collector produced PgConnectionChurn.
+    if let Some(conn_s) = pg.conn_s {
+        spans.push(Span::raw(" "));
+        let conn_style = if conn_s >= 100.0 {
+            Styles::critical()
+        } else if conn_s >= 20.0 {
+            Styles::modified_item()
+        } else {
+            Styles::default()
+        };
+        spans.extend(metric_spans(
+            "conn",
+            &format!("{conn_s:.1}/s"),
+            PG_CONN,
+            conn_style,
+        ));
+    }
+
     line_with_padding(spans, width)
 }
 
@@ -495,6 +562,120 @@ pub(super) fn render_bgw_line(bgw: &BgwSummary, width: usize) -> Line<'static> {
     line_with_padding(spans, width)
 }
 
+/// Renders WAL archiver line.
+/// Format: ARC │ arch: 1.2/m fail: 0 pend: 3 last: 12s [FAILING]
+pub(super) fn render_arc_line(arc: &ArcSummary, width: usize) -> Line<'static> {
+    let mut spans = vec![Span::styled("ARC", Styles::cpu()), Span::raw(" │ ")];
+
+    spans.extend(metric_spans_default(
+        "arch",
+        &arc.archived_per_min
+            .map_or("-".to_string(), |r| format!("{:.1}/m", r)),
+        ARC_ARCH,
+    ));
+    spans.push(Span::raw(" "));
+
+    let fail_style = if arc.failed > 0 {
+        Styles::critical()
+    } else {
+        Styles::default()
+    };
+    spans.extend(metric_spans(
+        "fail",
+        &arc.failed.to_string(),
+        ARC_FAIL,
+        fail_style,
+    ));
+    spans.push(Span::raw(" "));
+
+    let pend_style = match arc.pending {
+        Some(p) if p > 64 => Styles::critical(),
+        Some(p) if p > 8 => Styles::modified_item(),
+        _ => Styles::default(),
+    };
+    spans.extend(metric_spans(
+        "pend",
+        &arc.pending.map_or("-".to_string(), |p| p.to_string()),
+        ARC_PEND,
+        pend_style,
+    ));
+    spans.push(Span::raw(" "));
+
+    spans.extend(metric_spans_default(
+        "last",
+        &arc.last_archived_age_s.map_or("-".to_string(), |s| {
+            fmt::format_duration(s, FmtStyle::Compact)
+        }),
+        ARC_LAST,
+    ));
+
+    if arc.failing {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled("FAILING", Styles::critical()));
+    }
+
+    line_with_padding(spans, width)
+}
+
+/// Renders Kubernetes pod line.
+/// Format: POD │ db/pg-0  node: worker-3  cpu: 500m/2000m  mem: 1.0 GiB/4.0 GiB
+pub(super) fn render_pod_line(
+    pod: &K8sPodInfo,
+    metrics: &SummaryMetrics,
+    width: usize,
+) -> Line<'static> {
+    let declared = |request: String, limit: String| format!("{request}/{limit}");
+    let millis = |m: u64| {
+        if m == 0 {
+            "-".to_string()
+        } else {
+            format!("{m}m")
+        }
+    };
+    let bytes = |b: u64| {
+        if b == 0 {
+            "-".to_string()
+        } else {
+            format_size_bytes(b)
+        }
+    };
+
+    let mut spans = vec![
+        Span::styled("POD", Styles::dim()),
+        Span::raw(" │ "),
+        Span::styled(format!("{}/{}", pod.namespace, pod.pod), Styles::emphasis()),
+    ];
+    if !pod.node.is_empty() {
+        spans.push(Span::raw("  "));
+        spans.push(Span::raw(format!("node: {}", pod.node)));
+    }
+    spans.push(Span::raw("  "));
+    spans.push(Span::raw(format!(
+        "cpu: {}",
+        declared(millis(pod.cpu_request_millis), millis(pod.cpu_limit_millis))
+    )));
+    spans.push(Span::raw("  "));
+    // Above the request the pod is among the first evicted under node memory pressure
+    let over_request = pod.memory_request > 0
+        && metrics
+            .cgroup_memory
+            .as_ref()
+            .is_some_and(|m| m.current > pod.memory_request);
+    spans.push(Span::styled(
+        format!(
+            "mem: {}",
+            declared(bytes(pod.memory_request), bytes(pod.memory_limit))
+        ),
+        if over_request {
+            Styles::modified_item()
+        } else {
+            Styles::default()
+        },
+    ));
+
+    line_with_padding(spans, width)
+}
+
 /// Renders MEM line - fixed-width metrics.
 pub(super) fn render_cgroup_mem_line(metrics: &SummaryMetrics, width: usize) -> Line<'static> {
     let Some(mem) = metrics.cgroup_memory.as_ref() else {
@@ -599,6 +780,12 @@ pub(super) fn render_mem_line(metrics: &SummaryMetrics, width: usize) -> Line<'s
         style_for_slab(metrics.mem_slab),
     ));
 
+    // PSS of PostgreSQL processes: shared_buffers counted once, unlike RSS
+    if let Some(pg_pss) = metrics.mem_pg_pss {
+        spans.push(Span::raw("  "));
+        spans.extend(metric_spans_default("pg", &format_size_gib(pg_pss), MEM_PG));
+    }
+
     line_with_padding(spans, width)
 }
 
@@ -834,11 +1021,11 @@ pub(super) fn render_help_line(width: usize, tab: Tab) -> Line<'static> {
     // Tab-specific hints
     match tab {
         Tab::Processes => {
-            spans.push(Span::styled("g/c/m/d", Styles::help_key()));
+            spans.push(Span::styled("G/c/m/d", Styles::help_key()));
             spans.push(Span::styled(":view ", Styles::help()));
         }
         Tab::PostgresActive => {
-            spans.push(Span::styled("g/v", Styles::help_key()));
+            spans.push(Span::styled("G/v", Styles::help_key()));
             spans.push(Span::styled(":view ", Styles::help()));
             spans.push(Span::styled("i", Styles::help_key()));
             spans.push(Span::styled(":hide idle ", Styles::help()));
@@ -867,7 +1054,19 @@ pub(super) fn render_help_line(width: usize, tab: Tab) -> Line<'static> {
             spans.push(Span::styled("u/w/i", Styles::help_key()));
             spans.push(Span::styled(":view ", Styles::help()));
         }
-        Tab::PgErrors => {}
+        Tab::PgErrors | Tab::Agent => {}
+        Tab::PgRoles => {
+            spans.push(Span::styled("v", Styles::help_key()));
+            spans.push(Span::styled(":roles/apps/clients ", Styles::help()));
+        }
+        Tab::Custom => {
+            spans.push(Span::styled("v", Styles::help_key()));
+            spans.push(Span::styled(":next query ", Styles::help()));
+        }
+        Tab::PgSettings => {
+            spans.push(Span::styled("v", Styles::help_key()));
+            spans.push(Span::styled(":all/non-default ", Styles::help()));
+        }
         Tab::PgLocks => {
             spans.push(Span::styled(">", Styles::help_key()));
             spans.push(Span::styled(":drill ", Styles::help()));
@@ -1013,6 +1212,17 @@ fn style_for_dirty(dirty_kb: u64) -> Style {
     }
 }
 
+/// Style for CPU busy (%).
+fn style_for_cpu_busy(busy: f64) -> Style {
+    if busy > 90.0 {
+        Styles::critical()
+    } else if busy > 70.0 {
+        Styles::modified_item()
+    } else {
+        Styles::default()
+    }
+}
+
 /// Style for disk utilization%.
 fn style_for_disk_util(util: f64) -> Style {
     if util > 80.0 {
@@ -1078,3 +1288,27 @@ fn format_pkt_rate(pkt_s: f64) -> String {
         format!("{:.0}", pkt_s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn braille_sparkline_scales_and_right_aligns() {
+        // Two characters = four samples; one missing sample on the left
+        let graph = braille_sparkline(&[25.0, 50.0, 100.0], 100.0, 2);
+        let chars: Vec<char> = graph.chars().collect();
+        assert_eq!(chars.len(), 2);
+        // blank | 1 dot  ->  right column bottom dot only
+        assert_eq!(chars[0], '\u{2880}');
+        // 2 dots | 4 dots
+        assert_eq!(chars[1], '\u{28fc}');
+
+        // Only the newest samples are kept; zero max draws nothing
+        assert_eq!(braille_sparkline(&[1.0; 10], 0.0, 1), "\u{2800}");
+        assert_eq!(
+            braille_sparkline(&[0.0, 100.0, 100.0], 100.0, 1),
+            "\u{28ff}"
+        );
+    }
+}
@@ -2,10 +2,11 @@
 
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 
+use crate::tui::style::Styles;
+
 /// Renders a centered time jump popup.
 pub fn render_time_jump(frame: &mut Frame, area: Rect, input: &str, error: Option<&str>) {
     // Calculate popup size (70% width, fixed height), clamped.
@@ -21,62 +22,48 @@ pub fn render_time_jump(frame: &mut Frame, area: Rect, input: &str, error: Optio
     let block = Block::default()
         .title(" Jump to time (UTC) ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Styles::popup_border());
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
     let mut lines = vec![
         Line::from(vec![
-            Span::styled("Input: ", Style::default().fg(Color::Yellow)),
-            Span::styled(
-                input,
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
+            Span::styled("Input: ", Styles::emphasis()),
+            Span::styled(input, Styles::help_key()),
         ]),
         Line::from(""),
+        Line::from(Span::styled("Examples:", Styles::dim())),
         Line::from(Span::styled(
-            "Examples:",
-            Style::default().fg(Color::DarkGray),
-        )),
-        Line::from(Span::styled(
-            "  -1h        (relative to current selected snapshot)",
-            Style::default().fg(Color::DarkGray),
+            "  -15m, +1h  (relative to current selected snapshot)",
+            Styles::dim(),
         )),
         Line::from(Span::styled(
             "  16:00      (time on selected day)",
-            Style::default().fg(Color::DarkGray),
-        )),
-        Line::from(Span::styled(
-            "  2026-02-07T17:00:00",
-            Style::default().fg(Color::DarkGray),
-        )),
-        Line::from(Span::styled(
-            "  1738944000 (unix timestamp)",
-            Style::default().fg(Color::DarkGray),
+            Styles::dim(),
         )),
+        Line::from(Span::styled("  2026-02-07 17:00", Styles::dim())),
+        Line::from(Span::styled("  1738944000 (unix timestamp)", Styles::dim())),
     ];
 
     if let Some(err) = error {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             format!("Error: {err}"),
-            Style::default().fg(Color::Red),
+            Styles::error(),
         )));
     }
 
     lines.push(Line::from(""));
     lines.push(Line::from(vec![
-        Span::styled("Enter", Style::default().fg(Color::Yellow)),
-        Span::styled(" → jump", Style::default().fg(Color::DarkGray)),
-        Span::styled("   Esc", Style::default().fg(Color::Yellow)),
-        Span::styled(" → cancel", Style::default().fg(Color::DarkGray)),
+        Span::styled("Enter", Styles::emphasis()),
+        Span::styled(" → jump", Styles::dim()),
+        Span::styled("   Esc", Styles::emphasis()),
+        Span::styled(" → cancel", Styles::dim()),
     ]));
 
     let paragraph = Paragraph::new(lines)
         .alignment(Alignment::Left)
         .wrap(Wrap { trim: true })
-        .style(Style::default().fg(Color::White));
+        .style(Styles::popup());
     frame.render_widget(paragraph, inner);
 }
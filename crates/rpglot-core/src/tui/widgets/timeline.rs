@@ -0,0 +1,105 @@
+//! Timeline bar (history mode): loaded range colored by health score,
+//! with the cursor, bookmarks and gaps in the history marked.
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+
+use crate::fmt::format_timestamp;
+use crate::tui::state::{AppState, HEALTH_OK, HEALTH_WARN};
+use crate::tui::style::Styles;
+
+/// Renders the bar on the first line of `area` and the range labels on the
+/// second. Records the bar position for mouse clicks.
+pub fn render_timeline(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    if area.height == 0 {
+        return;
+    }
+    let timeline = &mut state.timeline;
+    timeline.bar = Some((area.x, area.y, area.width));
+
+    let cursor = state
+        .current_snapshot
+        .as_ref()
+        .and_then(|s| timeline.column_of(s.timestamp));
+    let marks: Vec<usize> = state
+        .bookmarks
+        .iter()
+        .flat_map(|store| store.list())
+        .filter_map(|b| timeline.column_of(b.ts))
+        .collect();
+    let bar: Vec<Span> = timeline
+        .health
+        .iter()
+        .take(area.width as usize)
+        .enumerate()
+        .map(|(col, health)| {
+            if Some(col) == cursor {
+                return Span::styled("┃", Styles::help_key());
+            }
+            if marks.contains(&col) {
+                return Span::styled("◆", Styles::accent());
+            }
+            match *health {
+                Some(h) => Span::styled("█", health_style(h)),
+                None if timeline.gap.get(col) == Some(&true) => Span::styled("░", Styles::dim()),
+                None => Span::styled("·", Styles::dim()),
+            }
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(Line::from(bar)),
+        Rect::new(area.x, area.y, area.width, 1),
+    );
+
+    if area.height < 2 {
+        return;
+    }
+    let (start, end) = timeline.view;
+    let left = format_ts(start);
+    let right = format_ts(end);
+    let mut middle = String::from("[/] step  +/- zoom  click to jump");
+    if timeline.zoom > 0 {
+        middle = format!("zoom x{}  {middle}", 1u64 << timeline.zoom);
+    }
+    let width = area.width as usize;
+    let used = left.len() + right.len();
+    let gap = width.saturating_sub(used);
+    let labels = if gap >= middle.len() + 2 {
+        let pad_left = (gap - middle.len()) / 2;
+        let pad_right = gap - middle.len() - pad_left;
+        Line::from(vec![
+            Span::styled(left, Styles::dim()),
+            Span::raw(" ".repeat(pad_left)),
+            Span::styled(middle, Styles::help()),
+            Span::raw(" ".repeat(pad_right)),
+            Span::styled(right, Styles::dim()),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled(left, Styles::dim()),
+            Span::raw(" ".repeat(gap)),
+            Span::styled(right, Styles::dim()),
+        ])
+    };
+    frame.render_widget(
+        Paragraph::new(labels),
+        Rect::new(area.x, area.y + 1, area.width, 1),
+    );
+}
+
+fn health_style(health: u8) -> Style {
+    if health >= HEALTH_OK {
+        Styles::active()
+    } else if health >= HEALTH_WARN {
+        Styles::modified_item()
+    } else {
+        Styles::error()
+    }
+}
+
+fn format_ts(ts: i64) -> String {
+    format_timestamp(ts, "%m-%d %H:%M:%S").unwrap_or_else(|| "----".to_string())
+}
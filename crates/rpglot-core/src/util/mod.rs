@@ -5,7 +5,7 @@ pub mod process_io;
 mod time_parser;
 
 pub use container::is_container;
-pub use time_parser::{TimeParseError, parse_time, parse_time_with_base};
+pub use time_parser::{TimeParseError, parse_duration, parse_time, parse_time_with_base};
 
 /// Prints a PostgreSQL connection warning to stderr with ANSI colors.
 pub fn print_pg_warning(error: &str) {
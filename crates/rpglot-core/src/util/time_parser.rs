@@ -3,8 +3,8 @@
 //! Supports multiple formats:
 //! - ISO 8601: `2026-02-07T17:00:00`
 //! - Unix timestamp: `1738944000`
-//! - Relative: `-1h`, `-30m`, `-2d`
-//! - Date+time (UTC): `2026-02-07:07:00` or `2026-02-07:07:00:00`
+//! - Relative: `-1h`, `-30m`, `-2d` (`+1h` too, relative to a base time)
+//! - Date+time (UTC): `2026-02-07:07:00`, `2026-02-07:07:00:00` or `2026-02-07 07:00`
 //! - Time only (current day, UTC): `07:00`
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
@@ -33,7 +33,7 @@ impl std::error::Error for TimeParseError {}
 /// | ISO 8601 | `2026-02-07T17:00:00` | Full datetime |
 /// | Unix timestamp | `1738944000` | Seconds since epoch |
 /// | Relative | `-1h`, `-30m`, `-2d` | Relative to now |
-/// | Date+time | `2026-02-07:07:00` | UTC, colon separator |
+/// | Date+time | `2026-02-07:07:00` | UTC, colon or space separator |
 /// | Date+time+sec | `2026-02-07:07:00:00` | UTC, with seconds |
 /// | Time only | `07:00` | Current day, UTC |
 ///
@@ -84,8 +84,8 @@ pub fn parse_time(input: &str) -> Result<i64, TimeParseError> {
 /// Parses a time expression in UTC using `base_ts` as a reference.
 ///
 /// Supported formats are the same as `parse_time()`, with different semantics for:
-/// - Relative time (`-1h`, `-30m`, ...): relative to `base_ts`.
-/// - Time only (`HH:MM`): interpreted as that time on the day of `base_ts`.
+/// - Relative time (`-1h`, `-30m`, ..., or forward: `+1h`): relative to `base_ts`.
+/// - Time only (`HH:MM` or `HH:MM:SS`): interpreted as that time on the day of `base_ts`.
 pub fn parse_time_with_base(input: &str, base_ts: i64) -> Result<i64, TimeParseError> {
     let input = input.trim();
 
@@ -115,12 +115,38 @@ pub fn parse_time_with_base(input: &str, base_ts: i64) -> Result<i64, TimeParseE
     Err(TimeParseError {
         input: input.to_string(),
         message: "Unrecognized format. Use: ISO 8601 (2026-02-07T17:00:00), \
-                  Unix timestamp (1738944000), relative (-1h, -30m, -2d), \
-                  date:time (2026-02-07:07:00), or time only (07:00)"
+                  Unix timestamp (1738944000), relative (-1h, +30m, -2d), \
+                  date time (2026-02-07 07:00), or time only (07:00)"
             .to_string(),
     })
 }
 
+/// Parses a duration like `15m`, `1h`, `90s`, `2d` or `1w` into seconds.
+pub fn parse_duration(input: &str) -> Result<i64, TimeParseError> {
+    let input = input.trim();
+    unit_seconds(input)
+        .filter(|&secs| secs > 0)
+        .ok_or_else(|| TimeParseError {
+            input: input.to_string(),
+            message: "Expected a positive duration: 90s, 15m, 1h, 2d or 1w".to_string(),
+        })
+}
+
+/// Number followed by a unit (s, m, h, d, w), in seconds.
+fn unit_seconds(input: &str) -> Option<i64> {
+    let unit = input.chars().last()?;
+    let number: i64 = input[..input.len() - unit.len_utf8()].parse().ok()?;
+    let multiplier = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86400,
+        'w' => 604800,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
+
 /// Try to parse as Unix timestamp (plain integer).
 fn try_parse_unix_timestamp(input: &str) -> Option<i64> {
     // Must be all digits (possibly with leading minus for negative, but we don't expect that)
@@ -137,62 +163,20 @@ fn try_parse_relative(input: &str) -> Option<i64> {
         return None;
     }
 
-    let rest = &input[1..];
-    if rest.is_empty() {
-        return None;
-    }
-
-    // Extract number and unit
-    let unit = rest.chars().last()?;
-    let number_str = &rest[..rest.len() - 1];
-
-    if number_str.is_empty() {
-        return None;
-    }
-
-    let number: i64 = number_str.parse().ok()?;
-
-    let seconds = match unit {
-        's' => number,
-        'm' => number * 60,
-        'h' => number * 3600,
-        'd' => number * 86400,
-        'w' => number * 604800,
-        _ => return None,
-    };
+    let seconds = unit_seconds(&input[1..])?;
 
     let now = Utc::now().timestamp();
     Some(now - seconds)
 }
 
-/// Parses relative expression and returns delta seconds (negative value).
+/// Parses a relative expression (`-1h` back, `+1h` forward) and returns
+/// delta seconds.
 fn try_parse_relative_delta_seconds(input: &str) -> Option<i64> {
-    if !input.starts_with('-') {
-        return None;
-    }
-
-    let rest = &input[1..];
-    if rest.is_empty() {
-        return None;
-    }
-
-    let unit = rest.chars().last()?;
-    let number_str = &rest[..rest.len() - 1];
-    if number_str.is_empty() {
-        return None;
+    if let Some(rest) = input.strip_prefix('-') {
+        unit_seconds(rest).map(|s| -s)
+    } else {
+        unit_seconds(input.strip_prefix('+')?)
     }
-    let number: i64 = number_str.parse().ok()?;
-
-    let seconds = match unit {
-        's' => number,
-        'm' => number * 60,
-        'h' => number * 3600,
-        'd' => number * 86400,
-        'w' => number * 604800,
-        _ => return None,
-    };
-
-    Some(-seconds)
 }
 
 /// Try to parse as ISO 8601 datetime.
@@ -218,7 +202,8 @@ fn try_parse_iso8601(input: &str) -> Option<i64> {
     None
 }
 
-/// Try to parse as date:time format (2026-02-07:07:00 or 2026-02-07:07:00:00).
+/// Try to parse as date:time format (2026-02-07:07:00 or 2026-02-07:07:00:00),
+/// also with a space instead of the colon (2026-02-07 07:00).
 fn try_parse_date_colon_time(input: &str) -> Option<i64> {
     // Format: YYYY-MM-DD:HH:MM or YYYY-MM-DD:HH:MM:SS
     // The date part has hyphens, then colon separates date from time
@@ -234,12 +219,12 @@ fn try_parse_date_colon_time(input: &str) -> Option<i64> {
         return None;
     }
 
-    let date_part = &input[..10];
-    if !input[10..].starts_with(':') {
+    let date_part = input.get(..10)?;
+    if !input[10..].starts_with([':', ' ']) {
         return None;
     }
 
-    let time_part = &input[11..];
+    let time_part = input[11..].trim_start();
 
     // Parse date
     let date = NaiveDate::parse_from_str(date_part, "%Y-%m-%d").ok()?;
@@ -276,14 +261,14 @@ fn try_parse_time_only(input: &str) -> Option<i64> {
 }
 
 fn try_parse_time_only_on_base_date(input: &str, base_ts: i64) -> Option<i64> {
-    if input.len() != 5 {
-        return None;
-    }
     if input.chars().nth(2) != Some(':') {
         return None;
     }
-
-    let time = NaiveTime::parse_from_str(input, "%H:%M").ok()?;
+    let time = match input.len() {
+        5 => NaiveTime::parse_from_str(input, "%H:%M").ok()?,
+        8 => NaiveTime::parse_from_str(input, "%H:%M:%S").ok()?,
+        _ => return None,
+    };
     let base_dt = Utc.timestamp_opt(base_ts, 0).single()?;
     let base_date = base_dt.date_naive();
 
@@ -391,5 +376,29 @@ mod tests {
             .unwrap()
             .timestamp();
         assert_eq!(parse_time_with_base("16:00", base).unwrap(), expected_16);
+        assert_eq!(
+            parse_time_with_base("16:00:30", base).unwrap(),
+            expected_16 + 30
+        );
+
+        // Forward offsets and a space between date and time
+        assert_eq!(parse_time_with_base("+15m", base).unwrap(), base + 900);
+        assert_eq!(
+            parse_time_with_base("2026-02-08 16:00", base).unwrap(),
+            expected_16
+        );
+        // Plain parse_time keeps relative times in the past only
+        assert!(parse_time("+1h").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("15m").unwrap(), 900);
+        assert_eq!(parse_duration(" 1h ").unwrap(), 3600);
+        assert_eq!(parse_duration("90s").unwrap(), 90);
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("-5m").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("5").is_err());
     }
 }
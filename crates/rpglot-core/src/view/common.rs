@@ -4,6 +4,9 @@
 //! rendering framework (ratatui, HTML, etc). TUI maps them to ratatui Styles,
 //! a future web frontend would map them to CSS classes.
 
+use crate::table::{PIN_MARKER, PinnedRows};
+use crate::thresholds::{Level, column_level};
+
 /// Row-level style classification.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum RowStyleClass {
@@ -21,6 +24,20 @@ pub enum RowStyleClass {
     Dimmed,
     /// Accent (TUI: cyan). E.g. PostgreSQL-related CMD.
     Accent,
+    /// Value changed since the last refresh (TUI: yellow, bold, reversed).
+    Changed,
+    /// Value changed a few refreshes ago (TUI: yellow).
+    ChangedRecently,
+}
+
+/// Style class of `value` in column `key` (API column key) on tab `tab`,
+/// per the thresholds configured in [`crate::thresholds`].
+pub fn threshold_class(tab: &str, key: &str, value: f64) -> RowStyleClass {
+    match column_level(tab, key, value) {
+        Some(Level::Crit) => RowStyleClass::Critical,
+        Some(Level::Warn) => RowStyleClass::Warning,
+        None => RowStyleClass::Normal,
+    }
 }
 
 /// A single table cell with optional per-cell style override.
@@ -42,6 +59,15 @@ impl ViewCell {
             style: Some(style),
         }
     }
+
+    /// Cell colored by the thresholds of its column (see [`threshold_class`]);
+    /// values within them inherit the row style.
+    pub fn threshold(text: String, tab: &str, key: &str, value: Option<f64>) -> Self {
+        match value.map(|v| threshold_class(tab, key, v)) {
+            Some(RowStyleClass::Normal) | None => Self::plain(text),
+            Some(style) => Self::styled(text, style),
+        }
+    }
 }
 
 /// One table row, parameterized by entity ID type.
@@ -60,3 +86,74 @@ pub struct TableViewModel<Id> {
     pub sort_column: usize,
     pub sort_ascending: bool,
 }
+
+impl<Id> TableViewModel<Id> {
+    /// Keeps only `cols` (indices into the current headers) in that order.
+    ///
+    /// Fixed widths follow their columns; columns without a fixed width fill
+    /// the remaining space and must come last in `cols`. If the sort column
+    /// is hidden, `sort_column` is set past the last column.
+    pub fn select_columns(&mut self, cols: &[usize]) {
+        let fixed = self.widths.len();
+        self.headers = cols.iter().map(|&c| self.headers[c].clone()).collect();
+        self.widths = cols
+            .iter()
+            .filter(|&&c| c < fixed)
+            .map(|&c| self.widths[c])
+            .collect();
+        for row in &mut self.rows {
+            row.cells = cols
+                .iter()
+                .map(|&c| row.cells.get(c).cloned().unwrap_or_default())
+                .collect();
+        }
+        self.sort_column = cols
+            .iter()
+            .position(|&c| c == self.sort_column)
+            .unwrap_or(cols.len());
+    }
+
+    /// Moves pinned rows to the top, keeping their sorted order.
+    pub fn pin_rows(&mut self, pinned: &PinnedRows)
+    where
+        Id: Copy + Into<i128>,
+    {
+        if !pinned.is_empty() {
+            self.rows.sort_by_key(|row| !pinned.contains(row.id));
+        }
+    }
+
+    /// Prefixes the first cell of pinned rows with [`PIN_MARKER`].
+    pub fn mark_pinned(&mut self, pinned: &PinnedRows)
+    where
+        Id: Copy + Into<i128>,
+    {
+        for row in &mut self.rows {
+            if pinned.contains(row.id)
+                && let Some(cell) = row.cells.first_mut()
+            {
+                cell.text = format!("{PIN_MARKER}{}", cell.text);
+            }
+        }
+    }
+
+    /// Cell texts of every row, for exporting the table.
+    pub fn text_rows(&self) -> Vec<Vec<String>> {
+        self.rows
+            .iter()
+            .map(|row| row.cells.iter().map(|c| c.text.clone()).collect())
+            .collect()
+    }
+
+    /// Header/value pairs of row `idx`, for showing a whole row vertically.
+    pub fn row_fields(&self, idx: usize) -> Vec<(String, String)> {
+        let Some(row) = self.rows.get(idx) else {
+            return Vec::new();
+        };
+        self.headers
+            .iter()
+            .zip(&row.cells)
+            .map(|(h, c)| (h.clone(), c.text.clone()))
+            .collect()
+    }
+}
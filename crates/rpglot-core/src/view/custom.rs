@@ -0,0 +1,169 @@
+//! CUS (user-defined queries) view model.
+
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::fmt::{format_opt_f64, truncate};
+use crate::storage::model::{CustomColumnType, CustomQueryInfo, CustomQueryRow};
+use crate::table::SortKey;
+use crate::tui::state::CustomTabState;
+use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
+
+const KEY_WIDTH: u16 = 24;
+const VALUE_WIDTH: u16 = 14;
+
+/// Where a declared column's value lives in a [`CustomQueryRow`].
+#[derive(Clone, Copy)]
+enum Slot {
+    Key(usize),
+    Value { idx: usize, int: bool },
+}
+
+fn slots(query: &CustomQueryInfo) -> Vec<Slot> {
+    let (mut keys, mut values) = (0, 0);
+    query
+        .columns
+        .iter()
+        .map(|c| match c.kind {
+            CustomColumnType::Key => {
+                keys += 1;
+                Slot::Key(keys - 1)
+            }
+            kind => {
+                values += 1;
+                Slot::Value {
+                    idx: values - 1,
+                    int: kind == CustomColumnType::Int,
+                }
+            }
+        })
+        .collect()
+}
+
+fn sort_key(r: &CustomQueryRow, slot: Option<&Slot>) -> SortKey {
+    match slot {
+        Some(Slot::Key(i)) => SortKey::String(r.keys.get(*i).cloned().unwrap_or_default()),
+        Some(Slot::Value { idx, .. }) => {
+            SortKey::Float(r.values.get(*idx).copied().flatten().unwrap_or(f64::MIN))
+        }
+        None => SortKey::Integer(0),
+    }
+}
+
+/// Stable row ID: hash of the key values.
+fn row_id(r: &CustomQueryRow) -> u64 {
+    xxh3_64(r.keys.join("\0").as_bytes())
+}
+
+/// Builds a UI-agnostic view model for the query selected by
+/// `state.query` (wrapping around) among the snapshot's custom queries.
+///
+/// Returns `None` if there is no query or no row to show.
+pub fn build_custom_view(
+    queries: &[CustomQueryInfo],
+    state: &CustomTabState,
+) -> Option<TableViewModel<u64>> {
+    if queries.is_empty() {
+        return None;
+    }
+    let position = state.query % queries.len();
+    let query = &queries[position];
+    let slots = slots(query);
+    let headers: Vec<String> = query
+        .columns
+        .iter()
+        .map(|c| c.name.to_uppercase())
+        .collect();
+    let header_refs: Vec<&str> = headers.iter().map(String::as_str).collect();
+
+    let mut rows_data: Vec<&CustomQueryRow> = query.rows.iter().collect();
+
+    // Apply filter (key columns)
+    if let Some(ref filter) = state.filter {
+        let f = filter.to_lowercase();
+        rows_data.retain(|r| r.keys.iter().any(|k| k.to_lowercase().contains(&f)));
+    }
+
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| rf.matches(&header_refs, |col| sort_key(r, slots.get(col))));
+    }
+
+    if rows_data.is_empty() {
+        return None;
+    }
+
+    // Sort
+    let col = state.sort_column;
+    let asc = state.sort_ascending;
+    rows_data.sort_by(|a, b| {
+        let cmp = sort_key(a, slots.get(col))
+            .partial_cmp(&sort_key(b, slots.get(col)))
+            .unwrap_or(std::cmp::Ordering::Equal);
+        if asc { cmp } else { cmp.reverse() }
+    });
+
+    let rows: Vec<ViewRow<u64>> = rows_data
+        .iter()
+        .map(|r| ViewRow {
+            id: row_id(r),
+            cells: slots
+                .iter()
+                .map(|slot| match *slot {
+                    Slot::Key(i) => {
+                        let key = r.keys.get(i).map(String::as_str).unwrap_or("");
+                        ViewCell::plain(if key.is_empty() {
+                            "-".to_string()
+                        } else {
+                            truncate(key, KEY_WIDTH as usize)
+                        })
+                    }
+                    Slot::Value { idx, int } => ViewCell::plain(format_opt_f64(
+                        r.values.get(idx).copied().flatten(),
+                        VALUE_WIDTH as usize,
+                        if int { 0 } else { 2 },
+                    )),
+                })
+                .collect(),
+            style: RowStyleClass::Normal,
+        })
+        .collect();
+
+    let widths = slots
+        .iter()
+        .map(|s| match s {
+            Slot::Key(_) => KEY_WIDTH,
+            Slot::Value { .. } => VALUE_WIDTH,
+        })
+        .collect();
+
+    let filter_info = state
+        .filter
+        .as_ref()
+        .map(|f| format!(" [filter: {}]", f))
+        .unwrap_or_default();
+
+    let sort_indicator = headers
+        .get(col)
+        .map(|h| h.to_lowercase())
+        .unwrap_or_default();
+    let sort_dir = if asc { "asc" } else { "desc" };
+
+    let title = format!(
+        "CUS: {} [{}/{}] ({} rows, sort: {} {}){filter_info}",
+        query.name,
+        position + 1,
+        queries.len(),
+        rows.len(),
+        sort_indicator,
+        sort_dir,
+    );
+
+    Some(TableViewModel {
+        title,
+        headers,
+        widths,
+        rows,
+        sort_column: col,
+        sort_ascending: asc,
+    })
+}
@@ -5,10 +5,13 @@
 //! widgets for rendering.
 
 pub mod common;
+pub mod custom;
 pub mod pga;
+pub mod pgc;
 pub mod pge;
 pub mod pgi;
 pub mod pgl;
+pub mod pgr;
 pub mod pgs;
 pub mod pgt;
 
@@ -7,7 +7,7 @@ use crate::storage::model::{
     DataBlock, PgStatActivityInfo, PgStatStatementsInfo, ProcessInfo, Snapshot,
 };
 use crate::tui::state::{PgActivityTabState, PgStatementsRates, PgStatementsTabState, SortKey};
-use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
+use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow, threshold_class};
 use std::collections::HashMap;
 
 const PGA_HEADERS_GENERIC: &[&str] = &[
@@ -17,8 +17,13 @@ const PGA_HEADERS_STATS: &[&str] = &[
     "PID", "DB", "USER", "STATE", "QDUR", "MEAN", "MAX", "CALL/s", "HIT%", "QUERY",
 ];
 
+const PGA_HEADERS_TRANSACTIONS: &[&str] = &[
+    "PID", "DB", "USER", "STATE", "XID", "XMIN_AGE", "XDUR", "QDUR", "BTYPE", "QUERY",
+];
+
 const PGA_WIDTHS_GENERIC: &[u16] = &[7, 6, 8, 32, 32, 16, 20, 8, 8, 8, 14];
 const PGA_WIDTHS_STATS: &[u16] = &[7, 32, 32, 16, 8, 8, 8, 8, 6];
+const PGA_WIDTHS_TRANSACTIONS: &[u16] = &[7, 32, 32, 16, 10, 9, 8, 8, 14];
 
 struct PgActivityRowData {
     pid: i32,
@@ -35,6 +40,8 @@ struct PgActivityRowData {
     xact_duration_secs: Option<i64>,
     backend_duration_secs: Option<i64>,
     query_id: i64,
+    backend_xid: u32,
+    backend_xmin_age: i32,
     pgs_mean_exec_time: Option<f64>,
     pgs_max_exec_time: Option<f64>,
     pgs_calls_s: Option<f64>,
@@ -110,6 +117,8 @@ impl PgActivityRowData {
             xact_duration_secs,
             backend_duration_secs,
             query_id: pg.query_id,
+            backend_xid: pg.backend_xid,
+            backend_xmin_age: pg.backend_xmin_age,
             pgs_mean_exec_time: None,
             pgs_max_exec_time: None,
             pgs_calls_s: None,
@@ -161,9 +170,27 @@ impl PgActivityRowData {
                 9 => SortKey::String(self.query.to_lowercase()),
                 _ => SortKey::Integer(0),
             },
+            PgActivityViewMode::Transactions => match col {
+                0 => SortKey::Integer(self.pid as i64),
+                1 => SortKey::String(self.db.to_lowercase()),
+                2 => SortKey::String(self.user.to_lowercase()),
+                3 => SortKey::String(self.state.to_lowercase()),
+                4 => SortKey::Integer(self.backend_xid as i64),
+                5 => SortKey::Integer(self.backend_xmin_age as i64),
+                6 => SortKey::Integer(self.xact_duration_secs.unwrap_or(-1)),
+                7 => SortKey::Integer(self.query_duration_secs.unwrap_or(-1)),
+                8 => SortKey::String(self.backend_type.to_lowercase()),
+                9 => SortKey::String(self.query.to_lowercase()),
+                _ => SortKey::Integer(0),
+            },
         }
     }
 
+    /// Whether the session holds a transaction or a snapshot.
+    fn in_transaction(&self) -> bool {
+        self.xact_duration_secs.is_some() || self.backend_xid != 0 || self.backend_xmin_age > 0
+    }
+
     fn cells_generic(&self) -> Vec<ViewCell> {
         let is_idle = is_idle_state(&self.state);
 
@@ -221,6 +248,37 @@ impl PgActivityRowData {
         ]
     }
 
+    fn cells_transactions(&self) -> Vec<ViewCell> {
+        let xid = if self.backend_xid != 0 {
+            self.backend_xid.to_string()
+        } else {
+            "-".to_string()
+        };
+        let xmin_age = if self.backend_xmin_age > 0 {
+            self.backend_xmin_age.to_string()
+        } else {
+            "-".to_string()
+        };
+        vec![
+            ViewCell::plain(format!("{:>6}", self.pid)),
+            ViewCell::plain(truncate(&self.db, 32)),
+            ViewCell::plain(truncate(&self.user, 32)),
+            ViewCell::styled(truncate(&self.state, 16), styled_state_class(&self.state)),
+            ViewCell::plain(format!("{:>10}", xid)),
+            ViewCell::styled(
+                format!("{:>9}", xmin_age),
+                threshold_class("pga", "backend_xmin_age", self.backend_xmin_age as f64),
+            ),
+            ViewCell::styled(
+                format_duration_or_dash(self.xact_duration_secs),
+                styled_duration_class(self.xact_duration_secs, &self.state),
+            ),
+            ViewCell::plain(format_duration_or_dash(self.query_duration_secs)),
+            ViewCell::plain(truncate(&self.backend_type, 14)),
+            ViewCell::plain(fmt::normalize_query(&self.query)),
+        ]
+    }
+
     fn row_style(&self) -> RowStyleClass {
         if is_idle_state(&self.state) {
             RowStyleClass::Dimmed
@@ -315,9 +373,8 @@ fn styled_qdur_anomaly_class(
 
 fn styled_hit_pct_class(hit_pct: Option<f64>) -> RowStyleClass {
     match hit_pct {
-        Some(pct) if pct < 50.0 => RowStyleClass::Critical,
-        Some(pct) if pct < 80.0 => RowStyleClass::Warning,
-        _ => RowStyleClass::Normal,
+        Some(pct) => threshold_class("pga", "stmt_hit_pct", pct),
+        None => RowStyleClass::Normal,
     }
 }
 
@@ -429,6 +486,11 @@ pub fn build_activity_view(
         });
     }
 
+    // Transactions view: only sessions inside a transaction or holding a snapshot
+    if view_mode == PgActivityViewMode::Transactions {
+        rows_data.retain(|row| row.in_transaction());
+    }
+
     // Enrich with PGS stats (Stats view)
     if view_mode == PgActivityViewMode::Stats {
         let pgs_map = extract_pg_statements_map(snapshot);
@@ -442,6 +504,21 @@ pub fn build_activity_view(
         }
     }
 
+    let (headers, widths, view_indicator) = match view_mode {
+        PgActivityViewMode::Generic => (PGA_HEADERS_GENERIC, PGA_WIDTHS_GENERIC, "g:generic"),
+        PgActivityViewMode::Stats => (PGA_HEADERS_STATS, PGA_WIDTHS_STATS, "v:stats"),
+        PgActivityViewMode::Transactions => (
+            PGA_HEADERS_TRANSACTIONS,
+            PGA_WIDTHS_TRANSACTIONS,
+            "v:transactions",
+        ),
+    };
+
+    // Column expression filter
+    if let Some(rf) = &pga_state.row_filter {
+        rows_data.retain(|r| rf.matches(headers, |col| r.sort_key_for_mode(col, view_mode)));
+    }
+
     // Sort with idle at bottom
     let sort_col = pga_state.sort_column;
     let sort_asc = pga_state.sort_ascending;
@@ -462,17 +539,13 @@ pub fn build_activity_view(
         if sort_asc { cmp } else { cmp.reverse() }
     });
 
-    let (headers, widths, view_indicator) = match view_mode {
-        PgActivityViewMode::Generic => (PGA_HEADERS_GENERIC, PGA_WIDTHS_GENERIC, "g:generic"),
-        PgActivityViewMode::Stats => (PGA_HEADERS_STATS, PGA_WIDTHS_STATS, "v:stats"),
-    };
-
     let rows: Vec<ViewRow<i32>> = rows_data
         .iter()
         .map(|row| {
             let cells = match view_mode {
                 PgActivityViewMode::Generic => row.cells_generic(),
                 PgActivityViewMode::Stats => row.cells_stats(),
+                PgActivityViewMode::Transactions => row.cells_transactions(),
             };
             ViewRow {
                 id: row.pid,
@@ -0,0 +1,153 @@
+//! PGC (pg_settings) view model.
+
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::api::snapshot::PgSettingsRow;
+use crate::fmt::truncate;
+use crate::table::SortKey;
+use crate::tui::state::{PgSettingsTabState, PgSettingsViewMode};
+use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
+
+const HEADERS: &[&str] = &["CATEGORY", "NAME", "SETTING", "UNIT", "SOURCE", "DEFAULT"];
+const WIDTHS: &[u16] = &[32, 36, 20, 5, 18, 20];
+
+fn sort_key(r: &PgSettingsRow, col: usize) -> SortKey {
+    match col {
+        0 => SortKey::String(r.category.clone()),
+        1 => SortKey::String(r.name.clone()),
+        2 => SortKey::String(r.setting.clone()),
+        3 => SortKey::String(r.unit.clone()),
+        4 => SortKey::String(r.source.clone()),
+        _ => SortKey::String(r.boot_val.clone()),
+    }
+}
+
+/// Stable row ID: hash of the setting name.
+pub fn row_id(r: &PgSettingsRow) -> u64 {
+    xxh3_64(r.name.as_bytes())
+}
+
+/// Builds a UI-agnostic view model for the PGC tab from rows produced by
+/// [`crate::api::settings::settings_rows`] (ordered by category and name,
+/// which keeps settings grouped by category on equal sort keys).
+///
+/// Settings pending restart are highlighted as warnings, non-default ones as
+/// accents. Returns `None` if there are no rows to show.
+pub fn build_settings_view(
+    settings: &[PgSettingsRow],
+    state: &PgSettingsTabState,
+) -> Option<TableViewModel<u64>> {
+    let mut rows_data: Vec<&PgSettingsRow> = settings.iter().collect();
+
+    if state.view_mode == PgSettingsViewMode::NonDefault {
+        rows_data.retain(|r| r.non_default || r.pending_restart);
+    }
+
+    // Apply filter (name, category, value)
+    if let Some(ref filter) = state.filter {
+        let f = filter.to_lowercase();
+        rows_data.retain(|r| {
+            r.name.to_lowercase().contains(&f)
+                || r.category.to_lowercase().contains(&f)
+                || r.setting.to_lowercase().contains(&f)
+        });
+    }
+
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| rf.matches(HEADERS, |col| sort_key(r, col)));
+    }
+
+    if rows_data.is_empty() {
+        return None;
+    }
+
+    // Sort (stable: category/name order is kept within equal keys)
+    let col = state.sort_column;
+    let asc = state.sort_ascending;
+    rows_data.sort_by(|a, b| {
+        let cmp = sort_key(a, col)
+            .partial_cmp(&sort_key(b, col))
+            .unwrap_or(std::cmp::Ordering::Equal);
+        if asc { cmp } else { cmp.reverse() }
+    });
+
+    let rows: Vec<ViewRow<u64>> = rows_data
+        .iter()
+        .map(|r| {
+            let style = if r.pending_restart {
+                RowStyleClass::Warning
+            } else if r.non_default {
+                RowStyleClass::Accent
+            } else {
+                RowStyleClass::Normal
+            };
+            let text = |s: &str, width: u16| {
+                ViewCell::plain(if s.is_empty() {
+                    "-".to_string()
+                } else {
+                    truncate(s, width as usize)
+                })
+            };
+            let source = if r.pending_restart {
+                format!("{} (restart)", r.source)
+            } else {
+                r.source.clone()
+            };
+            ViewRow {
+                id: row_id(r),
+                cells: vec![
+                    text(&r.category, WIDTHS[0]),
+                    text(&r.name, WIDTHS[1]),
+                    text(&r.setting, WIDTHS[2]),
+                    ViewCell::plain(r.unit.clone()),
+                    text(source.trim(), WIDTHS[4]),
+                    text(&r.boot_val, WIDTHS[5]),
+                ],
+                style,
+            }
+        })
+        .collect();
+
+    let non_default = rows_data.iter().filter(|r| r.non_default).count();
+    let pending = rows_data.iter().filter(|r| r.pending_restart).count();
+    let pending_info = if pending > 0 {
+        format!(", {pending} pending restart")
+    } else {
+        String::new()
+    };
+
+    let filter_info = state
+        .filter
+        .as_ref()
+        .map(|f| format!(" [filter: {}]", f))
+        .unwrap_or_default();
+
+    let label = match state.view_mode {
+        PgSettingsViewMode::All => "Settings",
+        PgSettingsViewMode::NonDefault => "Non-default settings",
+    };
+    let sort_indicator = HEADERS
+        .get(col)
+        .map(|h| h.to_lowercase())
+        .unwrap_or_default();
+    let sort_dir = if asc { "asc" } else { "desc" };
+
+    let title = format!(
+        "PGC: {} ({} settings, {} non-default{pending_info}, sort: {} {}){filter_info}",
+        label,
+        rows.len(),
+        non_default,
+        sort_indicator,
+        sort_dir,
+    );
+
+    Some(TableViewModel {
+        title,
+        headers: HEADERS.iter().map(|s| s.to_string()).collect(),
+        widths: WIDTHS.to_vec(),
+        rows,
+        sort_column: col,
+        sort_ascending: asc,
+    })
+}
@@ -1,14 +1,20 @@
 //! PGE (pg_log_errors) view model.
 
-use crate::fmt::normalize_query;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::fmt::{format_timestamp, normalize_query};
 use crate::storage::StringInterner;
-use crate::storage::model::PgLogSeverity;
-use crate::tui::state::{AccumulatedError, PgErrorsTabState};
+use crate::storage::model::{PgLogEventType, PgLogSeverity};
+use crate::table::SortKey;
+use crate::tui::state::{AccumulatedError, AccumulatedEvent, PgErrorsTabState};
 use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
 
 const HEADERS: &[&str] = &["SEVERITY", "COUNT", "PATTERN", "SAMPLE"];
 const WIDTHS: &[u16] = &[8, 8];
 
+const EVENT_HEADERS: &[&str] = &["TIME", "TYPE", "ELAPSED", "COUNT", "TABLE", "MESSAGE"];
+const EVENT_WIDTHS: &[u16] = &[8, 11, 8, 5];
+
 /// Builds a UI-agnostic view model for the PGE (errors) tab.
 ///
 /// Returns `None` if there are no accumulated errors.
@@ -72,6 +78,18 @@ pub fn build_errors_view(
         });
     }
 
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| {
+            rf.matches(HEADERS, |col| match col {
+                0 => SortKey::String(r.severity_str.clone()),
+                1 => SortKey::Integer(r.count as i64),
+                2 => SortKey::String(r.pattern.clone()),
+                _ => SortKey::String(r.sample.clone()),
+            })
+        });
+    }
+
     if rows_data.is_empty() {
         return None;
     }
@@ -144,3 +162,145 @@ pub fn build_errors_view(
         sort_ascending: asc,
     })
 }
+
+/// Short label for a log event type.
+pub fn event_type_label(event_type: PgLogEventType) -> &'static str {
+    match event_type {
+        PgLogEventType::CheckpointStarting => "ckpt start",
+        PgLogEventType::CheckpointComplete => "checkpoint",
+        PgLogEventType::Autovacuum => "autovacuum",
+        PgLogEventType::Autoanalyze => "autoanalyze",
+        PgLogEventType::SlowQuery => "slow query",
+    }
+}
+
+/// Stable row ID for a log event (selection tracking across snapshots).
+pub fn event_row_id(event: &AccumulatedEvent) -> u64 {
+    xxh3_64(event.entry.message.as_bytes()) ^ event.timestamp as u64
+}
+
+/// Formats an event timestamp as `HH:MM:SS`.
+fn format_event_time(timestamp: i64) -> String {
+    format_timestamp(timestamp, "%H:%M:%S").unwrap_or_default()
+}
+
+/// Builds a UI-agnostic view model for the PGE events view
+/// (checkpoints, autovacuum/autoanalyze, slow queries).
+///
+/// Returns `None` if there are no events.
+pub fn build_events_view(
+    events: &[AccumulatedEvent],
+    state: &PgErrorsTabState,
+) -> Option<TableViewModel<u64>> {
+    if events.is_empty() {
+        return None;
+    }
+
+    let mut rows_data: Vec<&AccumulatedEvent> = events.iter().collect();
+
+    // Apply filter
+    if let Some(ref filter) = state.filter {
+        let f = filter.to_lowercase();
+        rows_data.retain(|e| {
+            e.entry.message.to_lowercase().contains(&f)
+                || e.entry.table_name.to_lowercase().contains(&f)
+                || event_type_label(e.entry.event_type).contains(&f)
+        });
+    }
+
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|e| {
+            rf.matches(EVENT_HEADERS, |col| match col {
+                0 => SortKey::String(format_event_time(e.timestamp)),
+                1 => SortKey::String(event_type_label(e.entry.event_type).to_string()),
+                2 => SortKey::Float(e.entry.elapsed_s),
+                3 => SortKey::Integer(e.entry.count.max(1) as i64),
+                4 => SortKey::String(e.entry.table_name.clone()),
+                _ => SortKey::String(e.entry.message.clone()),
+            })
+        });
+    }
+
+    if rows_data.is_empty() {
+        return None;
+    }
+
+    // Sort (stable, so equal keys keep newest-first order)
+    let col = state.sort_column;
+    let asc = state.sort_ascending;
+    rows_data.sort_by(|a, b| {
+        let cmp = match col {
+            0 => a.timestamp.cmp(&b.timestamp),
+            1 => event_type_label(a.entry.event_type).cmp(event_type_label(b.entry.event_type)),
+            2 => a.entry.elapsed_s.total_cmp(&b.entry.elapsed_s),
+            3 => a.entry.count.cmp(&b.entry.count),
+            4 => a.entry.table_name.cmp(&b.entry.table_name),
+            5 => a.entry.message.cmp(&b.entry.message),
+            _ => std::cmp::Ordering::Equal,
+        };
+        if asc { cmp } else { cmp.reverse() }
+    });
+
+    let rows: Vec<ViewRow<u64>> = rows_data
+        .iter()
+        .map(|e| {
+            let time = format_event_time(e.timestamp);
+            let style = match e.entry.event_type {
+                PgLogEventType::SlowQuery => RowStyleClass::Warning,
+                PgLogEventType::CheckpointStarting | PgLogEventType::CheckpointComplete => {
+                    RowStyleClass::Accent
+                }
+                _ => RowStyleClass::Normal,
+            };
+            let elapsed = if e.entry.elapsed_s > 0.0 {
+                format!("{:.1}s", e.entry.elapsed_s)
+            } else {
+                "-".to_string()
+            };
+            // Only slow queries are grouped; other events occur once
+            let count = e.entry.count.max(1).to_string();
+
+            ViewRow {
+                id: event_row_id(e),
+                cells: vec![
+                    ViewCell::plain(time),
+                    ViewCell::plain(event_type_label(e.entry.event_type).to_string()),
+                    ViewCell::plain(elapsed),
+                    ViewCell::plain(count),
+                    ViewCell::plain(e.entry.table_name.clone()),
+                    ViewCell::plain(normalize_query(&e.entry.message)),
+                ],
+                style,
+            }
+        })
+        .collect();
+
+    let filter_info = state
+        .filter
+        .as_ref()
+        .map(|f| format!(" [filter: {}]", f))
+        .unwrap_or_default();
+
+    let sort_indicator = EVENT_HEADERS
+        .get(col)
+        .map(|h| h.to_lowercase())
+        .unwrap_or_default();
+    let sort_dir = if asc { "asc" } else { "desc" };
+
+    let title = format!(
+        "PGE: Events ({} events, sort: {} {}){filter_info}",
+        rows.len(),
+        sort_indicator,
+        sort_dir,
+    );
+
+    Some(TableViewModel {
+        title,
+        headers: EVENT_HEADERS.iter().map(|s| s.to_string()).collect(),
+        widths: EVENT_WIDTHS.to_vec(),
+        rows,
+        sort_column: col,
+        sort_ascending: asc,
+    })
+}
@@ -5,7 +5,7 @@ use crate::models::PgIndexesViewMode;
 use crate::storage::StringInterner;
 use crate::storage::model::{DataBlock, PgStatUserIndexesInfo, Snapshot};
 use crate::tui::state::{PgIndexesTabState, SortKey};
-use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
+use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow, threshold_class};
 
 const HEADERS_USAGE: &[&str] = &[
     "IDX/s", "TUP_RD/s", "TUP_FT/s", "HIT%", "DISK/s", "SIZE", "DB", "TABLE", "INDEX",
@@ -123,13 +123,7 @@ impl PgIndexesRowData {
             }
             PgIndexesViewMode::Io => {
                 if let Some(hit) = self.hit_pct {
-                    if hit < 70.0 {
-                        RowStyleClass::Critical
-                    } else if hit < 90.0 {
-                        RowStyleClass::Warning
-                    } else {
-                        RowStyleClass::Normal
-                    }
+                    threshold_class("pgi", "io_hit_pct", hit)
                 } else if self.idx_scan == 0 {
                     RowStyleClass::Warning
                 } else {
@@ -139,13 +133,23 @@ impl PgIndexesRowData {
         }
     }
 
+    /// HIT% cell, colored by the `io_hit_pct` thresholds.
+    fn hit_cell(&self) -> ViewCell {
+        ViewCell::threshold(
+            format_opt_f64(self.hit_pct, 5, 1),
+            "pgi",
+            "io_hit_pct",
+            self.hit_pct,
+        )
+    }
+
     fn cells(&self, mode: PgIndexesViewMode) -> Vec<ViewCell> {
         match mode {
             PgIndexesViewMode::Usage => vec![
                 ViewCell::plain(format_opt_f64(self.idx_scan_s, 9, 1)),
                 ViewCell::plain(format_opt_f64(self.idx_tup_read_s, 9, 1)),
                 ViewCell::plain(format_opt_f64(self.idx_tup_fetch_s, 9, 1)),
-                ViewCell::plain(format_opt_f64(self.hit_pct, 5, 1)),
+                self.hit_cell(),
                 ViewCell::plain(format_blks_rate(self.disk_read_blks_s, 7)),
                 ViewCell::plain(format!(
                     "{:>9}",
@@ -168,7 +172,7 @@ impl PgIndexesRowData {
             PgIndexesViewMode::Io => vec![
                 ViewCell::plain(format_blks_rate(self.idx_blks_read_s, 9)),
                 ViewCell::plain(format_blks_rate(self.idx_blks_hit_s, 9)),
-                ViewCell::plain(format_opt_f64(self.hit_pct, 5, 1)),
+                self.hit_cell(),
                 ViewCell::plain(format_blks_rate(self.disk_read_blks_s, 7)),
                 ViewCell::plain(format!(
                     "{:>9}",
@@ -238,6 +242,17 @@ pub fn build_indexes_view(
         });
     }
 
+    let (headers, widths, title_mode) = match mode {
+        PgIndexesViewMode::Usage => (HEADERS_USAGE, WIDTHS_USAGE, "u:usage"),
+        PgIndexesViewMode::Unused => (HEADERS_UNUSED, WIDTHS_UNUSED, "w:unused"),
+        PgIndexesViewMode::Io => (HEADERS_IO, WIDTHS_IO, "i:io"),
+    };
+
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| rf.matches(headers, |col| r.sort_key(mode, col)));
+    }
+
     // Sort
     let sort_col = state.sort_column;
     let sort_asc = state.sort_ascending;
@@ -249,12 +264,6 @@ pub fn build_indexes_view(
         if sort_asc { cmp } else { cmp.reverse() }
     });
 
-    let (headers, widths, title_mode) = match mode {
-        PgIndexesViewMode::Usage => (HEADERS_USAGE, WIDTHS_USAGE, "u:usage"),
-        PgIndexesViewMode::Unused => (HEADERS_UNUSED, WIDTHS_UNUSED, "w:unused"),
-        PgIndexesViewMode::Io => (HEADERS_IO, WIDTHS_IO, "i:io"),
-    };
-
     // Build view rows
     let rows: Vec<ViewRow<u32>> = rows_data
         .iter()
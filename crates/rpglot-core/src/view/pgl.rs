@@ -3,6 +3,7 @@
 use crate::fmt::{format_epoch_age, normalize_query};
 use crate::storage::StringInterner;
 use crate::storage::model::{DataBlock, PgLockTreeNode, Snapshot};
+use crate::table::SortKey;
 use crate::tui::state::PgLocksTabState;
 use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
 
@@ -148,6 +149,21 @@ pub fn build_locks_view(
         });
     }
 
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| {
+            rf.matches(HEADERS, |col| match col {
+                0 => SortKey::Integer(r.pid as i64),
+                1 => SortKey::String(r.state.clone()),
+                2 => SortKey::String(r.wait.clone()),
+                3 => SortKey::String(r.duration.clone()),
+                4 => SortKey::String(r.lock_mode.clone()),
+                5 => SortKey::String(r.target.clone()),
+                _ => SortKey::String(r.query.clone()),
+            })
+        });
+    }
+
     if rows_data.is_empty() {
         return None;
     }
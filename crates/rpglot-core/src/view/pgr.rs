@@ -0,0 +1,285 @@
+//! PGR (workload by role / application / client host) view model.
+
+use crate::fmt::{format_duration_or_none, format_opt_f64, truncate};
+use crate::storage::model::SystemTcpConnInfo;
+use crate::table::SortKey;
+use crate::tui::state::{PgRolesTabState, PgRolesViewMode};
+use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
+use crate::workload::{ClientHost, WorkloadGroup};
+
+const HEADERS_ROLES: &[&str] = &[
+    "USER", "SESS", "ACTIVE", "IDLE_TX", "WAIT", "ACT_TIME", "STMTS", "CALLS/s", "TIME/s",
+    "ROWS/s", "RMT_TIME",
+];
+const HEADERS_APPLICATIONS: &[&str] = &[
+    "APPLICATION",
+    "SESS",
+    "ACTIVE",
+    "IDLE_TX",
+    "WAIT",
+    "ACT_TIME",
+    "STMTS",
+    "CALLS/s",
+    "TIME/s",
+    "ROWS/s",
+    "RMT_TIME",
+];
+const WIDTHS: &[u16] = &[24, 6, 6, 7, 6, 9, 6, 9, 9, 9, 9];
+const HEADERS_CLIENTS: &[&str] = &[
+    "CLIENT",
+    "ESTAB",
+    "SYN_RECV",
+    "TIME_WAIT",
+    "OTHER",
+    "SESS",
+    "ACTIVE",
+    "IDLE_TX",
+];
+const WIDTHS_CLIENTS: &[u16] = &[39, 6, 8, 9, 6, 6, 6, 7];
+
+/// Row label for a client host; Unix socket sessions have no address.
+fn client_label(addr: &str) -> &str {
+    if addr.is_empty() { "[local]" } else { addr }
+}
+
+fn sort_key(r: &WorkloadGroup, col: usize) -> SortKey {
+    match col {
+        0 => SortKey::String(r.name.clone()),
+        1 => SortKey::Integer(r.sessions as i64),
+        2 => SortKey::Integer(r.active as i64),
+        3 => SortKey::Integer(r.idle_in_transaction as i64),
+        4 => SortKey::Integer(r.waiting as i64),
+        5 => SortKey::Float(r.active_time_s),
+        6 => SortKey::Integer(r.statements as i64),
+        7 => SortKey::Float(r.calls_s.unwrap_or(0.0)),
+        8 => SortKey::Float(r.exec_time_ms_s.unwrap_or(0.0)),
+        9 => SortKey::Float(r.rows_s.unwrap_or(0.0)),
+        _ => SortKey::Float(r.remote_time_s),
+    }
+}
+
+fn client_sort_key(r: &ClientHost, col: usize) -> SortKey {
+    match col {
+        0 => SortKey::String(r.addr.clone()),
+        1 => SortKey::Integer(r.established as i64),
+        2 => SortKey::Integer(r.syn_recv as i64),
+        3 => SortKey::Integer(r.time_wait as i64),
+        4 => SortKey::Integer(r.other as i64),
+        5 => SortKey::Integer(r.sessions as i64),
+        6 => SortKey::Integer(r.active as i64),
+        _ => SortKey::Integer(r.idle_in_transaction as i64),
+    }
+}
+
+/// Builds a UI-agnostic view model for the PGR (workload) tab from groups
+/// produced by [`crate::workload::aggregate_roles`] or
+/// [`crate::workload::aggregate_applications`], per the view mode.
+///
+/// Returns `None` if there are no groups to show.
+pub fn build_workload_view(
+    groups: &[WorkloadGroup],
+    state: &PgRolesTabState,
+) -> Option<TableViewModel<u64>> {
+    let (headers, label) = match state.view_mode {
+        PgRolesViewMode::Roles => (HEADERS_ROLES, "Roles"),
+        PgRolesViewMode::Applications | PgRolesViewMode::Clients => {
+            (HEADERS_APPLICATIONS, "Applications")
+        }
+    };
+    let mut rows_data: Vec<&WorkloadGroup> = groups.iter().collect();
+
+    // Apply filter
+    if let Some(ref filter) = state.filter {
+        let f = filter.to_lowercase();
+        rows_data.retain(|r| r.name.to_lowercase().contains(&f));
+    }
+
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| rf.matches(headers, |col| sort_key(r, col)));
+    }
+
+    if rows_data.is_empty() {
+        return None;
+    }
+
+    // Sort
+    let col = state.sort_column;
+    let asc = state.sort_ascending;
+    rows_data.sort_by(|a, b| {
+        let cmp = sort_key(a, col)
+            .partial_cmp(&sort_key(b, col))
+            .unwrap_or(std::cmp::Ordering::Equal);
+        if asc { cmp } else { cmp.reverse() }
+    });
+
+    let rows: Vec<ViewRow<u64>> = rows_data
+        .iter()
+        .map(|r| {
+            let style = if r.waiting > 0 {
+                RowStyleClass::Warning
+            } else if r.active > 0 {
+                RowStyleClass::Active
+            } else {
+                RowStyleClass::Normal
+            };
+            ViewRow {
+                id: r.name_hash,
+                cells: vec![
+                    ViewCell::plain(if r.name.is_empty() {
+                        "-".to_string()
+                    } else {
+                        truncate(&r.name, 24)
+                    }),
+                    ViewCell::plain(format!("{:>6}", r.sessions)),
+                    ViewCell::plain(format!("{:>6}", r.active)),
+                    ViewCell::plain(format!("{:>7}", r.idle_in_transaction)),
+                    ViewCell::plain(format!("{:>6}", r.waiting)),
+                    ViewCell::plain(format!(
+                        "{:>9}",
+                        format_duration_or_none(r.active_time_s as i64)
+                    )),
+                    ViewCell::plain(format!("{:>6}", r.statements)),
+                    ViewCell::plain(format_opt_f64(r.calls_s, 9, 1)),
+                    ViewCell::plain(format_opt_f64(r.exec_time_ms_s, 9, 1)),
+                    ViewCell::plain(format_opt_f64(r.rows_s, 9, 1)),
+                    ViewCell::plain(format!(
+                        "{:>9}",
+                        format_duration_or_none(r.remote_time_s as i64)
+                    )),
+                ],
+                style,
+            }
+        })
+        .collect();
+
+    let filter_info = state
+        .filter
+        .as_ref()
+        .map(|f| format!(" [filter: {}]", f))
+        .unwrap_or_default();
+
+    let sort_indicator = headers
+        .get(col)
+        .map(|h| h.to_lowercase())
+        .unwrap_or_default();
+    let sort_dir = if asc { "asc" } else { "desc" };
+
+    let title = format!(
+        "PGR: {} ({} groups, sort: {} {}){filter_info}",
+        label,
+        rows.len(),
+        sort_indicator,
+        sort_dir,
+    );
+
+    Some(TableViewModel {
+        title,
+        headers: headers.iter().map(|s| s.to_string()).collect(),
+        widths: WIDTHS.to_vec(),
+        rows,
+        sort_column: col,
+        sort_ascending: asc,
+    })
+}
+
+/// Builds the PGR clients view from hosts produced by
+/// [`crate::workload::aggregate_clients`]. The title carries the port-wide
+/// TCP state totals when the connection table was collected.
+///
+/// Returns `None` if there are no hosts to show.
+pub fn build_clients_view(
+    hosts: &[ClientHost],
+    tcp: Option<&SystemTcpConnInfo>,
+    state: &PgRolesTabState,
+) -> Option<TableViewModel<u64>> {
+    let mut rows_data: Vec<&ClientHost> = hosts.iter().collect();
+
+    // Apply filter
+    if let Some(ref filter) = state.filter {
+        let f = filter.to_lowercase();
+        rows_data.retain(|r| client_label(&r.addr).to_lowercase().contains(&f));
+    }
+
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| rf.matches(HEADERS_CLIENTS, |col| client_sort_key(r, col)));
+    }
+
+    if rows_data.is_empty() {
+        return None;
+    }
+
+    // Sort
+    let col = state.sort_column;
+    let asc = state.sort_ascending;
+    rows_data.sort_by(|a, b| {
+        let cmp = client_sort_key(a, col)
+            .partial_cmp(&client_sort_key(b, col))
+            .unwrap_or(std::cmp::Ordering::Equal);
+        if asc { cmp } else { cmp.reverse() }
+    });
+
+    let rows: Vec<ViewRow<u64>> = rows_data
+        .iter()
+        .map(|r| {
+            let style = if r.syn_recv > 0 {
+                RowStyleClass::Warning
+            } else if r.active > 0 {
+                RowStyleClass::Active
+            } else {
+                RowStyleClass::Normal
+            };
+            ViewRow {
+                id: r.addr_hash,
+                cells: vec![
+                    ViewCell::plain(truncate(client_label(&r.addr), 39)),
+                    ViewCell::plain(format!("{:>6}", r.established)),
+                    ViewCell::plain(format!("{:>8}", r.syn_recv)),
+                    ViewCell::plain(format!("{:>9}", r.time_wait)),
+                    ViewCell::plain(format!("{:>6}", r.other)),
+                    ViewCell::plain(format!("{:>6}", r.sessions)),
+                    ViewCell::plain(format!("{:>6}", r.active)),
+                    ViewCell::plain(format!("{:>7}", r.idle_in_transaction)),
+                ],
+                style,
+            }
+        })
+        .collect();
+
+    let filter_info = state
+        .filter
+        .as_ref()
+        .map(|f| format!(" [filter: {}]", f))
+        .unwrap_or_default();
+    let tcp_info = tcp
+        .map(|t| {
+            format!(
+                " :{} est {} syn {} tw {} cw {}",
+                t.port, t.established, t.syn_recv, t.time_wait, t.close_wait
+            )
+        })
+        .unwrap_or_default();
+
+    let sort_indicator = HEADERS_CLIENTS
+        .get(col)
+        .map(|h| h.to_lowercase())
+        .unwrap_or_default();
+    let sort_dir = if asc { "asc" } else { "desc" };
+
+    let title = format!(
+        "PGR: Clients{tcp_info} ({} hosts, sort: {} {}){filter_info}",
+        rows.len(),
+        sort_indicator,
+        sort_dir,
+    );
+
+    Some(TableViewModel {
+        title,
+        headers: HEADERS_CLIENTS.iter().map(|s| s.to_string()).collect(),
+        widths: WIDTHS_CLIENTS.to_vec(),
+        rows,
+        sort_column: col,
+        sort_ascending: asc,
+    })
+}
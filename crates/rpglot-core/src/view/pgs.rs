@@ -7,7 +7,7 @@ use crate::models::PgStatementsViewMode;
 use crate::storage::StringInterner;
 use crate::storage::model::{DataBlock, PgStatStatementsInfo, Snapshot};
 use crate::tui::state::{PgStatementsTabState, SortKey};
-use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
+use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow, threshold_class};
 
 const PGS_HEADERS_TIME: &[&str] = &["CALLS/s", "TIME/s", "MEAN", "ROWS/s", "DB", "USER", "QUERY"];
 const PGS_HEADERS_CALLS: &[&str] = &["CALLS/s", "ROWS/s", "R/CALL", "MEAN", "DB", "USER", "QUERY"];
@@ -132,34 +132,15 @@ impl PgStatementsRowData {
     fn row_style(&self, mode: PgStatementsViewMode) -> RowStyleClass {
         match mode {
             PgStatementsViewMode::Time | PgStatementsViewMode::Calls => {
-                let time_ms_s = self.exec_time_ms_s.unwrap_or(0.0);
-                if time_ms_s >= 1_000.0 {
-                    RowStyleClass::Critical
-                } else if time_ms_s >= 100.0 {
-                    RowStyleClass::Warning
-                } else {
-                    RowStyleClass::Normal
-                }
-            }
-            PgStatementsViewMode::Io => {
-                let rd_s = self.shared_blks_read_s.unwrap_or(0.0);
-                if rd_s >= 10_000.0 {
-                    RowStyleClass::Critical
-                } else if rd_s >= 1_000.0 {
-                    RowStyleClass::Warning
-                } else {
-                    RowStyleClass::Normal
-                }
+                threshold_class("pgs", "exec_time_ms_s", self.exec_time_ms_s.unwrap_or(0.0))
             }
+            PgStatementsViewMode::Io => threshold_class(
+                "pgs",
+                "shared_blks_read_s",
+                self.shared_blks_read_s.unwrap_or(0.0),
+            ),
             PgStatementsViewMode::Temp => {
-                let tmp_mb_s = self.temp_mb_s.unwrap_or(0.0);
-                if tmp_mb_s >= 100.0 {
-                    RowStyleClass::Critical
-                } else if tmp_mb_s >= 10.0 {
-                    RowStyleClass::Warning
-                } else {
-                    RowStyleClass::Normal
-                }
+                threshold_class("pgs", "temp_mb_s", self.temp_mb_s.unwrap_or(0.0))
             }
         }
     }
@@ -190,7 +171,7 @@ impl PgStatementsRowData {
                 ViewCell::plain(format_opt_f64(self.shared_blks_hit_s, 9, 1)),
                 ViewCell::styled(
                     format_opt_f64(self.hit_pct_s, 5, 1),
-                    hit_pct_style_class(self.hit_pct_s.unwrap_or(0.0)),
+                    threshold_class("pgs", "hit_pct", self.hit_pct_s.unwrap_or(0.0)),
                 ),
                 ViewCell::plain(format_opt_f64(self.shared_blks_dirtied_s, 9, 1)),
                 ViewCell::plain(format_opt_f64(self.shared_blks_written_s, 9, 1)),
@@ -211,17 +192,6 @@ impl PgStatementsRowData {
     }
 }
 
-/// HIT% cell style classification.
-fn hit_pct_style_class(hit_pct: f64) -> RowStyleClass {
-    if hit_pct < 90.0 {
-        RowStyleClass::Critical
-    } else if hit_pct < 98.0 {
-        RowStyleClass::Warning
-    } else {
-        RowStyleClass::Normal
-    }
-}
-
 /// Builds a UI-agnostic view model for the PGS (statements) tab.
 pub fn build_statements_view(
     snapshot: &Snapshot,
@@ -284,6 +254,18 @@ pub fn build_statements_view(
         });
     }
 
+    let (headers, widths, title_mode) = match mode {
+        PgStatementsViewMode::Time => (PGS_HEADERS_TIME, PGS_WIDTHS_TIME, "t:time"),
+        PgStatementsViewMode::Calls => (PGS_HEADERS_CALLS, PGS_WIDTHS_CALLS, "c:calls"),
+        PgStatementsViewMode::Io => (PGS_HEADERS_IO, PGS_WIDTHS_IO, "i:io"),
+        PgStatementsViewMode::Temp => (PGS_HEADERS_TEMP, PGS_WIDTHS_TEMP, "e:temp"),
+    };
+
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| rf.matches(headers, |col| r.sort_key(mode, col)));
+    }
+
     // Sort
     let sort_col = state.sort_column;
     let sort_asc = state.sort_ascending;
@@ -295,13 +277,6 @@ pub fn build_statements_view(
         if sort_asc { cmp } else { cmp.reverse() }
     });
 
-    let (headers, widths, title_mode) = match mode {
-        PgStatementsViewMode::Time => (PGS_HEADERS_TIME, PGS_WIDTHS_TIME, "t:time"),
-        PgStatementsViewMode::Calls => (PGS_HEADERS_CALLS, PGS_WIDTHS_CALLS, "c:calls"),
-        PgStatementsViewMode::Io => (PGS_HEADERS_IO, PGS_WIDTHS_IO, "i:io"),
-        PgStatementsViewMode::Temp => (PGS_HEADERS_TEMP, PGS_WIDTHS_TEMP, "e:temp"),
-    };
-
     let rows: Vec<ViewRow<i64>> = rows_data
         .iter()
         .map(|r| ViewRow {
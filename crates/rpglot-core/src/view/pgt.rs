@@ -5,7 +5,7 @@ use crate::models::PgTablesViewMode;
 use crate::storage::StringInterner;
 use crate::storage::model::{DataBlock, PgStatUserTablesInfo, Snapshot};
 use crate::tui::state::{PgTablesTabState, SortKey};
-use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow};
+use crate::view::common::{RowStyleClass, TableViewModel, ViewCell, ViewRow, threshold_class};
 
 const HEADERS_READS: &[&str] = &[
     "SEQ_RD/s", "IDX_FT/s", "TOT_RD/s", "SEQ/s", "IDX/s", "HIT%", "DISK/s", "SIZE", "DB", "TABLE",
@@ -217,39 +217,31 @@ impl PgTablesRowData {
     fn row_style(&self, mode: PgTablesViewMode) -> RowStyleClass {
         match mode {
             PgTablesViewMode::Reads | PgTablesViewMode::Writes | PgTablesViewMode::Scans => {
-                if self.dead_pct > 20.0 {
-                    RowStyleClass::Critical
-                } else if self.dead_pct > 5.0 || self.seq_pct.unwrap_or(0.0) > 80.0 {
-                    RowStyleClass::Warning
-                } else {
-                    RowStyleClass::Normal
-                }
-            }
-            PgTablesViewMode::Maintenance => {
-                if self.dead_pct > 20.0 {
-                    RowStyleClass::Critical
-                } else if self.dead_pct > 5.0 {
-                    RowStyleClass::Warning
-                } else {
-                    RowStyleClass::Normal
-                }
-            }
-            PgTablesViewMode::Io => {
-                if let Some(hit) = self.hit_pct {
-                    if hit < 70.0 {
-                        RowStyleClass::Critical
-                    } else if hit < 90.0 {
-                        RowStyleClass::Warning
-                    } else {
-                        RowStyleClass::Normal
+                match threshold_class("pgt", "dead_pct", self.dead_pct) {
+                    RowStyleClass::Normal => {
+                        threshold_class("pgt", "seq_pct", self.seq_pct.unwrap_or(0.0))
                     }
-                } else {
-                    RowStyleClass::Normal
+                    dead => dead,
                 }
             }
+            PgTablesViewMode::Maintenance => threshold_class("pgt", "dead_pct", self.dead_pct),
+            PgTablesViewMode::Io => match self.hit_pct {
+                Some(hit) => threshold_class("pgt", "io_hit_pct", hit),
+                None => RowStyleClass::Normal,
+            },
         }
     }
 
+    /// HIT% cell, colored by the `io_hit_pct` thresholds.
+    fn hit_cell(&self) -> ViewCell {
+        ViewCell::threshold(
+            format_opt_f64(self.hit_pct, 5, 1),
+            "pgt",
+            "io_hit_pct",
+            self.hit_pct,
+        )
+    }
+
     fn cells(&self, mode: PgTablesViewMode) -> Vec<ViewCell> {
         match mode {
             PgTablesViewMode::Reads => vec![
@@ -258,7 +250,7 @@ impl PgTablesRowData {
                 ViewCell::plain(format_opt_f64(self.total_read_s, 9, 1)),
                 ViewCell::plain(format_opt_f64(self.seq_scan_s, 9, 1)),
                 ViewCell::plain(format_opt_f64(self.idx_scan_s, 9, 1)),
-                ViewCell::plain(format_opt_f64(self.hit_pct, 5, 1)),
+                self.hit_cell(),
                 ViewCell::plain(format_blks_rate(self.disk_read_blks_s, 7)),
                 ViewCell::plain(format_size(self.size_bytes)),
                 ViewCell::plain(self.database.clone()),
@@ -271,7 +263,7 @@ impl PgTablesRowData {
                 ViewCell::plain(format_opt_f64(self.n_tup_hot_upd_s, 9, 1)),
                 ViewCell::plain(format_i64(self.n_live_tup, 9)),
                 ViewCell::plain(format_i64(self.n_dead_tup, 9)),
-                ViewCell::plain(format_opt_f64(self.hit_pct, 5, 1)),
+                self.hit_cell(),
                 ViewCell::plain(format_blks_rate(self.disk_read_blks_s, 7)),
                 ViewCell::plain(format_size(self.size_bytes)),
                 ViewCell::plain(self.database.clone()),
@@ -282,11 +274,16 @@ impl PgTablesRowData {
                 ViewCell::plain(format_opt_f64(self.seq_tup_read_s, 11, 1)),
                 ViewCell::plain(format_opt_f64(self.idx_scan_s, 9, 1)),
                 ViewCell::plain(format_opt_f64(self.idx_tup_fetch_s, 11, 1)),
-                ViewCell::plain(match self.seq_pct {
-                    Some(v) => format!("{:>5.1}", v),
-                    None => format!("{:>5}", "--"),
-                }),
-                ViewCell::plain(format_opt_f64(self.hit_pct, 5, 1)),
+                ViewCell::threshold(
+                    match self.seq_pct {
+                        Some(v) => format!("{:>5.1}", v),
+                        None => format!("{:>5}", "--"),
+                    },
+                    "pgt",
+                    "seq_pct",
+                    self.seq_pct,
+                ),
+                self.hit_cell(),
                 ViewCell::plain(format_blks_rate(self.disk_read_blks_s, 7)),
                 ViewCell::plain(format_size(self.size_bytes)),
                 ViewCell::plain(self.database.clone()),
@@ -295,7 +292,12 @@ impl PgTablesRowData {
             PgTablesViewMode::Maintenance => vec![
                 ViewCell::plain(format_i64(self.n_dead_tup, 9)),
                 ViewCell::plain(format_i64(self.n_live_tup, 9)),
-                ViewCell::plain(format!("{:>5.1}", self.dead_pct)),
+                ViewCell::threshold(
+                    format!("{:>5.1}", self.dead_pct),
+                    "pgt",
+                    "dead_pct",
+                    Some(self.dead_pct),
+                ),
                 ViewCell::plain(format_opt_f64(self.vacuum_count_s, 7, 2)),
                 ViewCell::plain(format_opt_f64(self.autovacuum_count_s, 7, 2)),
                 ViewCell::plain(format!("{:>9}", format_age(self.last_autovacuum))),
@@ -308,7 +310,7 @@ impl PgTablesRowData {
                 ViewCell::plain(format_blks_rate(self.heap_blks_hit_s, 9)),
                 ViewCell::plain(format_blks_rate(self.idx_blks_read_s, 9)),
                 ViewCell::plain(format_blks_rate(self.idx_blks_hit_s, 9)),
-                ViewCell::plain(format_opt_f64(self.hit_pct, 5, 1)),
+                self.hit_cell(),
                 ViewCell::plain(format_blks_rate(self.disk_read_blks_s, 7)),
                 ViewCell::plain(format_size(self.size_bytes)),
                 ViewCell::plain(self.database.clone()),
@@ -384,6 +386,19 @@ pub fn build_tables_view(
         });
     }
 
+    let (headers, widths, title_mode) = match mode {
+        PgTablesViewMode::Reads => (HEADERS_READS, WIDTHS_READS, "a:reads"),
+        PgTablesViewMode::Writes => (HEADERS_WRITES, WIDTHS_WRITES, "w:writes"),
+        PgTablesViewMode::Scans => (HEADERS_SCANS, WIDTHS_SCANS, "x:scans"),
+        PgTablesViewMode::Maintenance => (HEADERS_MAINTENANCE, WIDTHS_MAINTENANCE, "n:maint"),
+        PgTablesViewMode::Io => (HEADERS_IO, WIDTHS_IO, "i:io"),
+    };
+
+    // Column expression filter
+    if let Some(rf) = &state.row_filter {
+        rows_data.retain(|r| rf.matches(headers, |col| r.sort_key(mode, col)));
+    }
+
     // Sort
     let sort_col = state.sort_column;
     let sort_asc = state.sort_ascending;
@@ -395,14 +410,6 @@ pub fn build_tables_view(
         if sort_asc { cmp } else { cmp.reverse() }
     });
 
-    let (headers, widths, title_mode) = match mode {
-        PgTablesViewMode::Reads => (HEADERS_READS, WIDTHS_READS, "a:reads"),
-        PgTablesViewMode::Writes => (HEADERS_WRITES, WIDTHS_WRITES, "w:writes"),
-        PgTablesViewMode::Scans => (HEADERS_SCANS, WIDTHS_SCANS, "x:scans"),
-        PgTablesViewMode::Maintenance => (HEADERS_MAINTENANCE, WIDTHS_MAINTENANCE, "n:maint"),
-        PgTablesViewMode::Io => (HEADERS_IO, WIDTHS_IO, "i:io"),
-    };
-
     let rows: Vec<ViewRow<u32>> = rows_data
         .iter()
         .map(|r| ViewRow {
@@ -0,0 +1,582 @@
+//! Shared workload aggregation of pg_stat_activity and pg_stat_statements.
+//!
+//! Groups sessions and statement rates by role or by `application_name`.
+//! Both the TUI (PGR tab) and the Web API (`pgr`, `pgw`) delegate to
+//! [`aggregate_roles`] and [`aggregate_applications`]. [`aggregate_clients`]
+//! combines sessions with TCP connection states per client host.
+//! [`foreign_role`] tells postgres_fdw / dblink traffic apart from local work.
+
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::models::PgStatementsRates;
+use crate::storage::StringInterner;
+use crate::storage::model::{DataBlock, PgStatActivityInfo, Snapshot, SystemTcpConnInfo};
+
+/// Activity of one role or application in a snapshot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkloadGroup {
+    /// Interned hash of the group name (stable row ID).
+    pub name_hash: u64,
+    /// Role name or application_name (empty for sessions without one).
+    pub name: String,
+    /// Backends in this group.
+    pub sessions: u32,
+    /// Backends in state `active`.
+    pub active: u32,
+    /// Backends in state `idle in transaction` (including aborted).
+    pub idle_in_transaction: u32,
+    /// Backends waiting on a heavyweight lock.
+    pub waiting: u32,
+    /// Sum of running times of the active queries (seconds).
+    pub active_time_s: f64,
+    /// Active backends waiting for a foreign server (postgres_fdw, dblink).
+    pub remote_waiting: u32,
+    /// Running time of the queries of `remote_waiting` backends (seconds),
+    /// included in `active_time_s`.
+    pub remote_time_s: f64,
+    /// pg_stat_statements entries attributed to this group.
+    pub statements: u32,
+    /// Sum of statement rates; `None` when no statement of the group has a rate yet.
+    pub calls_s: Option<f64>,
+    pub rows_s: Option<f64>,
+    pub exec_time_ms_s: Option<f64>,
+}
+
+impl WorkloadGroup {
+    fn new(name_hash: u64, interner: Option<&StringInterner>) -> Self {
+        Self {
+            name_hash,
+            name: resolve(interner, name_hash).to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn add_session(
+        &mut self,
+        a: &PgStatActivityInfo,
+        timestamp: i64,
+        interner: Option<&StringInterner>,
+    ) {
+        self.sessions += 1;
+        let state = resolve(interner, a.state_hash);
+        if state == "active" {
+            self.active += 1;
+            let remote = foreign_role(
+                resolve(interner, a.application_name_hash),
+                state,
+                resolve(interner, a.wait_event_type_hash),
+                resolve(interner, a.wait_event_hash),
+                resolve(interner, a.query_hash),
+            ) == Some(ForeignRole::Outgoing);
+            if remote {
+                self.remote_waiting += 1;
+            }
+            if a.query_start > 0.0 {
+                let now = if a.collected_at > 0.0 {
+                    a.collected_at
+                } else {
+                    timestamp as f64
+                };
+                let running = (now - a.query_start).max(0.0);
+                self.active_time_s += running;
+                if remote {
+                    self.remote_time_s += running;
+                }
+            }
+        } else if state.starts_with("idle in transaction") {
+            self.idle_in_transaction += 1;
+        }
+        if resolve(interner, a.wait_event_type_hash) == "Lock" {
+            self.waiting += 1;
+        }
+    }
+
+    fn add_rates(&mut self, rates: Option<&PgStatementsRates>) {
+        self.statements += 1;
+        if let Some(rate) = rates {
+            add_rate(&mut self.calls_s, rate.calls_s);
+            add_rate(&mut self.rows_s, rate.rows_s);
+            add_rate(&mut self.exec_time_ms_s, rate.exec_time_ms_s);
+        }
+    }
+}
+
+fn resolve(interner: Option<&StringInterner>, hash: u64) -> &str {
+    interner.and_then(|i| i.resolve(hash)).unwrap_or("")
+}
+
+fn add_rate(total: &mut Option<f64>, rate: Option<f64>) {
+    if let Some(r) = rate {
+        *total = Some(total.unwrap_or(0.0) + r);
+    }
+}
+
+/// Part of a backend in cross-server traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForeignRole {
+    /// Waits for a foreign server: its query runs elsewhere.
+    Outgoing,
+    /// Opened by postgres_fdw or dblink of another server.
+    Incoming,
+}
+
+/// Classifies a backend by its pg_stat_activity columns.
+///
+/// Incoming connections keep the fallback `application_name` of
+/// postgres_fdw and dblink, and postgres_fdw reads through cursors named
+/// `c1`, `c2`, .... Outgoing backends wait on an `Extension` event: named
+/// `PostgresFdw*` / `Dblink*` since PostgreSQL 17, plain `Extension` before;
+/// an active `dblink(...)` call counts between waits.
+pub fn foreign_role(
+    application_name: &str,
+    state: &str,
+    wait_event_type: &str,
+    wait_event: &str,
+    query: &str,
+) -> Option<ForeignRole> {
+    if matches!(application_name, "postgres_fdw" | "dblink") || is_fdw_cursor(query) {
+        return Some(ForeignRole::Incoming);
+    }
+    if state != "active" {
+        return None;
+    }
+    let remote_wait = wait_event_type == "Extension"
+        && (wait_event == "Extension"
+            || wait_event.starts_with("PostgresFdw")
+            || wait_event.starts_with("Dblink"));
+    let query = query.to_ascii_lowercase();
+    if remote_wait || query.contains("dblink(") || query.contains("dblink_exec(") {
+        Some(ForeignRole::Outgoing)
+    } else {
+        None
+    }
+}
+
+/// `DECLARE c<N> CURSOR FOR ...`, as sent by postgres_fdw.
+fn is_fdw_cursor(query: &str) -> bool {
+    query
+        .strip_prefix("DECLARE c")
+        .and_then(|rest| rest.split_once(' '))
+        .is_some_and(|(n, rest)| {
+            !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) && rest.starts_with("CURSOR FOR")
+        })
+}
+
+/// Client sessions of the snapshot; backends without a user (background workers) are skipped.
+fn client_sessions(snapshot: &Snapshot) -> impl Iterator<Item = &PgStatActivityInfo> {
+    snapshot
+        .blocks
+        .iter()
+        .filter_map(|b| match b {
+            DataBlock::PgStatActivity(v) => Some(v),
+            _ => None,
+        })
+        .flatten()
+        .filter(|a| a.usename_hash != 0)
+}
+
+fn sorted(groups: HashMap<u64, WorkloadGroup>) -> Vec<WorkloadGroup> {
+    let mut rows: Vec<WorkloadGroup> = groups.into_values().collect();
+    rows.sort_by(|a, b| a.name.cmp(&b.name).then(a.name_hash.cmp(&b.name_hash)));
+    rows
+}
+
+/// Aggregates the snapshot's sessions and statement rates by role.
+///
+/// Statements are attributed by their `userid`. Rows are sorted by name.
+pub fn aggregate_roles(
+    snapshot: &Snapshot,
+    pgs_rates: &HashMap<i64, PgStatementsRates>,
+    interner: Option<&StringInterner>,
+) -> Vec<WorkloadGroup> {
+    let mut groups: HashMap<u64, WorkloadGroup> = HashMap::new();
+
+    for a in client_sessions(snapshot) {
+        groups
+            .entry(a.usename_hash)
+            .or_insert_with(|| WorkloadGroup::new(a.usename_hash, interner))
+            .add_session(a, snapshot.timestamp, interner);
+    }
+
+    for b in &snapshot.blocks {
+        if let DataBlock::PgStatStatements(stmts) = b {
+            for s in stmts.iter().filter(|s| s.usename_hash != 0) {
+                groups
+                    .entry(s.usename_hash)
+                    .or_insert_with(|| WorkloadGroup::new(s.usename_hash, interner))
+                    .add_rates(pgs_rates.get(&s.queryid));
+            }
+        }
+    }
+
+    sorted(groups)
+}
+
+/// Aggregates the snapshot's sessions and statement rates by `application_name`.
+///
+/// pg_stat_statements has no application column, so statements are attributed
+/// to the applications whose sessions currently run or last ran their
+/// `query_id` (PostgreSQL 14+). A statement shared by several applications
+/// counts for each of them. Rows are sorted by name.
+pub fn aggregate_applications(
+    snapshot: &Snapshot,
+    pgs_rates: &HashMap<i64, PgStatementsRates>,
+    interner: Option<&StringInterner>,
+) -> Vec<WorkloadGroup> {
+    let mut groups: HashMap<u64, WorkloadGroup> = HashMap::new();
+    let mut query_ids: HashMap<u64, HashSet<i64>> = HashMap::new();
+
+    for a in client_sessions(snapshot) {
+        let hash = a.application_name_hash;
+        groups
+            .entry(hash)
+            .or_insert_with(|| WorkloadGroup::new(hash, interner))
+            .add_session(a, snapshot.timestamp, interner);
+        if a.query_id != 0 {
+            query_ids.entry(hash).or_default().insert(a.query_id);
+        }
+    }
+
+    for (hash, ids) in query_ids {
+        let group = groups
+            .get_mut(&hash)
+            .expect("group exists for every session");
+        for id in ids {
+            group.add_rates(pgs_rates.get(&id));
+        }
+    }
+
+    sorted(groups)
+}
+
+/// Active client sessions per `application_name` (empty name for sessions
+/// without one). Used for the stacked application time series.
+pub fn active_sessions_by_application(
+    snapshot: &Snapshot,
+    interner: Option<&StringInterner>,
+) -> HashMap<String, u32> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for a in client_sessions(snapshot) {
+        if resolve(interner, a.state_hash) == "active" {
+            *counts
+                .entry(resolve(interner, a.application_name_hash).to_string())
+                .or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// Connections and sessions of one client host in a snapshot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientHost {
+    /// xxh3 hash of `addr` (stable row ID).
+    pub addr_hash: u64,
+    /// Client IP address; empty for Unix socket sessions.
+    pub addr: String,
+    /// TCP connections to the PostgreSQL port by state.
+    pub established: u32,
+    pub syn_recv: u32,
+    pub time_wait: u32,
+    pub other: u32,
+    /// Backends connected from this host.
+    pub sessions: u32,
+    /// Backends in state `active`.
+    pub active: u32,
+    /// Backends in state `idle in transaction` (including aborted).
+    pub idle_in_transaction: u32,
+}
+
+impl ClientHost {
+    fn new(addr: String) -> Self {
+        Self {
+            addr_hash: xxh3_64(addr.as_bytes()),
+            addr,
+            ..Default::default()
+        }
+    }
+}
+
+/// IPv4-mapped IPv6 addresses (`::ffff:10.0.0.1`) as IPv4, like the TCP table.
+fn normalize_client_addr(addr: &str) -> String {
+    match addr.parse::<IpAddr>() {
+        Ok(IpAddr::V6(v6)) => match v6.to_ipv4_mapped() {
+            Some(v4) => v4.to_string(),
+            None => v6.to_string(),
+        },
+        _ => addr.to_string(),
+    }
+}
+
+/// TCP connection table of the snapshot, if collected.
+pub fn tcp_connections(snapshot: &Snapshot) -> Option<&SystemTcpConnInfo> {
+    snapshot.blocks.iter().find_map(|b| match b {
+        DataBlock::SystemTcpConn(t) => Some(t),
+        _ => None,
+    })
+}
+
+/// Combines TCP connections to the PostgreSQL port and client sessions by
+/// remote host. Hosts outside the stored top of the TCP table still appear
+/// with their sessions. Rows are sorted by address.
+pub fn aggregate_clients(
+    snapshot: &Snapshot,
+    interner: Option<&StringInterner>,
+) -> Vec<ClientHost> {
+    let mut hosts: HashMap<String, ClientHost> = HashMap::new();
+
+    if let Some(tcp) = tcp_connections(snapshot) {
+        for r in &tcp.remotes {
+            let host = hosts
+                .entry(r.addr.clone())
+                .or_insert_with(|| ClientHost::new(r.addr.clone()));
+            host.established += r.established;
+            host.syn_recv += r.syn_recv;
+            host.time_wait += r.time_wait;
+            host.other += r.other;
+        }
+    }
+
+    for a in client_sessions(snapshot) {
+        let addr = normalize_client_addr(&a.client_addr);
+        let host = hosts
+            .entry(addr)
+            .or_insert_with_key(|addr| ClientHost::new(addr.clone()));
+        host.sessions += 1;
+        let state = resolve(interner, a.state_hash);
+        if state == "active" {
+            host.active += 1;
+        } else if state.starts_with("idle in transaction") {
+            host.idle_in_transaction += 1;
+        }
+    }
+
+    let mut rows: Vec<ClientHost> = hosts.into_values().collect();
+    rows.sort_by(|a, b| a.addr.cmp(&b.addr));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::model::{PgStatStatementsInfo, SystemTcpRemoteInfo};
+
+    struct Fixture {
+        interner: StringInterner,
+        snapshot: Snapshot,
+        rates: HashMap<i64, PgStatementsRates>,
+    }
+
+    fn fixture() -> Fixture {
+        let mut interner = StringInterner::new();
+        let app = interner.intern("app");
+        let admin = interner.intern("admin");
+        let web = interner.intern("web");
+        let psql = interner.intern("psql");
+        let active = interner.intern("active");
+        let idle_xact = interner.intern("idle in transaction");
+        let lock = interner.intern("Lock");
+
+        let session =
+            |usename_hash, application_name_hash, state_hash, wait, query_start, query_id| {
+                PgStatActivityInfo {
+                    usename_hash,
+                    application_name_hash,
+                    state_hash,
+                    wait_event_type_hash: wait,
+                    query_start,
+                    query_id,
+                    collected_at: 1000.0,
+                    ..Default::default()
+                }
+            };
+        let stmt = |usename_hash, queryid| PgStatStatementsInfo {
+            usename_hash,
+            queryid,
+            ..Default::default()
+        };
+        let snapshot = Snapshot {
+            timestamp: 1000,
+            blocks: vec![
+                DataBlock::PgStatActivity(vec![
+                    session(app, web, active, 0, 990.0, 1),
+                    session(app, web, active, lock, 995.0, 2),
+                    session(app, web, idle_xact, 0, 900.0, 1),
+                    session(admin, psql, active, 0, 999.5, 3),
+                    // Background worker without a user
+                    session(0, 0, active, 0, 100.0, 0),
+                ]),
+                DataBlock::PgStatStatements(vec![stmt(app, 1), stmt(app, 2), stmt(admin, 3)]),
+            ],
+        };
+        let rate = |calls_s, exec_time_ms_s| PgStatementsRates {
+            calls_s: Some(calls_s),
+            exec_time_ms_s: Some(exec_time_ms_s),
+            ..Default::default()
+        };
+        let rates = HashMap::from([(1, rate(10.0, 50.0)), (2, rate(5.0, 25.0))]);
+        Fixture {
+            interner,
+            snapshot,
+            rates,
+        }
+    }
+
+    #[test]
+    fn aggregates_sessions_and_rates_by_role() {
+        let f = fixture();
+        let rows = aggregate_roles(&f.snapshot, &f.rates, Some(&f.interner));
+        assert_eq!(rows.len(), 2);
+
+        let (admin_row, app_row) = (&rows[0], &rows[1]);
+        assert_eq!(admin_row.name, "admin");
+        assert_eq!(admin_row.sessions, 1);
+        assert_eq!(admin_row.statements, 1);
+        assert_eq!(admin_row.calls_s, None, "no rate yet");
+
+        assert_eq!(app_row.name, "app");
+        assert_eq!(app_row.sessions, 3);
+        assert_eq!(app_row.active, 2);
+        assert_eq!(app_row.idle_in_transaction, 1);
+        assert_eq!(app_row.waiting, 1);
+        assert_eq!(app_row.active_time_s, 15.0);
+        assert_eq!(app_row.statements, 2);
+        assert_eq!(app_row.calls_s, Some(15.0));
+        assert_eq!(app_row.exec_time_ms_s, Some(75.0));
+        assert_eq!(app_row.rows_s, None);
+    }
+
+    #[test]
+    fn aggregates_by_application() {
+        let f = fixture();
+        let rows = aggregate_applications(&f.snapshot, &f.rates, Some(&f.interner));
+        let names: Vec<&str> = rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["psql", "web"]);
+
+        let web = &rows[1];
+        assert_eq!(web.sessions, 3);
+        assert_eq!(web.active, 2);
+        // query_id 1 runs in two sessions but counts once
+        assert_eq!(web.statements, 2);
+        assert_eq!(web.calls_s, Some(15.0));
+
+        let active = active_sessions_by_application(&f.snapshot, Some(&f.interner));
+        assert_eq!(active.get("web"), Some(&2));
+        assert_eq!(active.get("psql"), Some(&1));
+        assert_eq!(active.len(), 2);
+    }
+
+    #[test]
+    fn classifies_foreign_traffic() {
+        use ForeignRole::*;
+        let role =
+            |app, state, wait_type, wait, query| foreign_role(app, state, wait_type, wait, query);
+
+        assert_eq!(
+            role("postgres_fdw", "active", "", "", "FETCH 100 FROM c1"),
+            Some(Incoming)
+        );
+        assert_eq!(
+            role(
+                "etl",
+                "idle in transaction",
+                "Client",
+                "ClientRead",
+                "DECLARE c12 CURSOR FOR SELECT 1"
+            ),
+            Some(Incoming)
+        );
+        assert_eq!(
+            role(
+                "web",
+                "active",
+                "Extension",
+                "PostgresFdwGetResult",
+                "SELECT * FROM remote_orders"
+            ),
+            Some(Outgoing)
+        );
+        assert_eq!(
+            role(
+                "web",
+                "active",
+                "",
+                "",
+                "SELECT * FROM DBLINK('shard1', 'SELECT 1') AS t(x int)"
+            ),
+            Some(Outgoing)
+        );
+        assert_eq!(role("web", "idle", "Extension", "Extension", ""), None);
+        assert_eq!(
+            role(
+                "web",
+                "active",
+                "Lock",
+                "relation",
+                "DECLARE cur CURSOR FOR SELECT 1"
+            ),
+            None
+        );
+
+        // Remote time is split out of the active time
+        let mut f = fixture();
+        let remote = f.interner.intern("Extension");
+        if let DataBlock::PgStatActivity(sessions) = &mut f.snapshot.blocks[0] {
+            sessions[0].wait_event_type_hash = remote;
+            sessions[0].wait_event_hash = remote;
+        }
+        let rows = aggregate_applications(&f.snapshot, &f.rates, Some(&f.interner));
+        assert_eq!(rows[1].remote_waiting, 1);
+        assert_eq!(rows[1].remote_time_s, 10.0);
+        assert_eq!(rows[1].active_time_s, 15.0);
+    }
+
+    #[test]
+    fn aggregates_clients_with_tcp_states() {
+        let mut f = fixture();
+        if let DataBlock::PgStatActivity(sessions) = &mut f.snapshot.blocks[0] {
+            sessions[0].client_addr = "10.0.0.7".to_string();
+            sessions[1].client_addr = "::ffff:10.0.0.7".to_string();
+            sessions[2].client_addr = "10.0.0.9".to_string();
+        }
+        f.snapshot
+            .blocks
+            .push(DataBlock::SystemTcpConn(SystemTcpConnInfo {
+                port: 5432,
+                established: 3,
+                syn_recv: 5,
+                remotes: vec![
+                    SystemTcpRemoteInfo {
+                        addr: "10.0.0.7".to_string(),
+                        established: 2,
+                        ..Default::default()
+                    },
+                    SystemTcpRemoteInfo {
+                        addr: "10.0.0.8".to_string(),
+                        syn_recv: 5,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }));
+
+        let rows = aggregate_clients(&f.snapshot, Some(&f.interner));
+        let addrs: Vec<&str> = rows.iter().map(|r| r.addr.as_str()).collect();
+        // Unix socket session (psql) has an empty address
+        assert_eq!(addrs, ["", "10.0.0.7", "10.0.0.8", "10.0.0.9"]);
+
+        let app_host = &rows[1];
+        assert_eq!(app_host.established, 2);
+        assert_eq!(app_host.sessions, 2);
+        assert_eq!(app_host.active, 2);
+
+        let storm = &rows[2];
+        assert_eq!(storm.syn_recv, 5);
+        assert_eq!(storm.sessions, 0);
+
+        assert_eq!(rows[3].idle_in_transaction, 1);
+        assert_eq!(rows[3].established, 0);
+    }
+}
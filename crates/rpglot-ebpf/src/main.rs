@@ -0,0 +1,147 @@
+//! Kernel-side BPF programs for per-process block I/O latency.
+//!
+//! `block_rq_issue` remembers when a request went to the device and which
+//! process issued it; `block_rq_complete` adds the latency to that process's
+//! log2 histogram in `BLK_LAT`. The userspace side is
+//! `rpglot_core::collector::blk_latency`, which drains the map on every
+//! snapshot; `BlkLatHist` must stay layout-compatible with it.
+//!
+//! # Build
+//!
+//! ```text
+//! cargo install bpf-linker
+//! cd crates/rpglot-ebpf && cargo build --release
+//! # -> target/bpfel-unknown-none/release/rpglot-ebpf
+//! rpglotd --ebpf-blk-latency target/bpfel-unknown-none/release/rpglot-ebpf
+//! ```
+
+#![no_std]
+#![no_main]
+
+use aya_ebpf::EbpfContext;
+use aya_ebpf::helpers::bpf_ktime_get_ns;
+use aya_ebpf::macros::{map, tracepoint};
+use aya_ebpf::maps::HashMap;
+use aya_ebpf::programs::TracePointContext;
+
+const SLOTS: usize = 32;
+
+// Field offsets shared by block_rq_issue and block_rq_complete, see
+// /sys/kernel/tracing/events/block/block_rq_{issue,complete}/format.
+const DEV_OFFSET: usize = 8;
+const SECTOR_OFFSET: usize = 16;
+
+/// In-flight request, identified by device and start sector (the
+/// tracepoints do not expose the request pointer).
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RequestKey {
+    dev: u32,
+    _pad: u32,
+    sector: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RequestStart {
+    ts_ns: u64,
+    tgid: u32,
+    _pad: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct BlkLatHist {
+    count: u64,
+    total_ns: u64,
+    slots: [u64; SLOTS],
+}
+
+#[map]
+static START: HashMap<RequestKey, RequestStart> = HashMap::with_max_entries(16384, 0);
+
+#[map]
+static BLK_LAT: HashMap<u32, BlkLatHist> = HashMap::with_max_entries(16384, 0);
+
+#[tracepoint]
+pub fn block_rq_issue(ctx: TracePointContext) -> u32 {
+    let _ = try_block_rq_issue(&ctx);
+    0
+}
+
+#[tracepoint]
+pub fn block_rq_complete(ctx: TracePointContext) -> u32 {
+    let _ = try_block_rq_complete(&ctx);
+    0
+}
+
+fn request_key(ctx: &TracePointContext) -> Result<RequestKey, i64> {
+    // SAFETY: offsets match the tracepoint format.
+    unsafe {
+        Ok(RequestKey {
+            dev: ctx.read_at::<u32>(DEV_OFFSET)?,
+            _pad: 0,
+            sector: ctx.read_at::<u64>(SECTOR_OFFSET)?,
+        })
+    }
+}
+
+fn try_block_rq_issue(ctx: &TracePointContext) -> Result<(), i64> {
+    let tgid = ctx.tgid();
+    if tgid == 0 {
+        return Ok(());
+    }
+    let start = RequestStart {
+        ts_ns: unsafe { bpf_ktime_get_ns() },
+        tgid,
+        _pad: 0,
+    };
+    START.insert(&request_key(ctx)?, &start, 0)
+}
+
+fn try_block_rq_complete(ctx: &TracePointContext) -> Result<(), i64> {
+    let key = request_key(ctx)?;
+    // SAFETY: the value is copied out before the entry is removed.
+    let start = match unsafe { START.get(&key) } {
+        Some(start) => *start,
+        None => return Ok(()),
+    };
+    START.remove(&key)?;
+
+    let delta_ns = unsafe { bpf_ktime_get_ns() }.saturating_sub(start.ts_ns);
+    let mut us = delta_ns / 1000;
+    let mut slot = 0;
+    while us > 1 && slot < SLOTS - 1 {
+        us >>= 1;
+        slot += 1;
+    }
+
+    if BLK_LAT.get_ptr_mut(&start.tgid).is_none() {
+        let empty = BlkLatHist {
+            count: 0,
+            total_ns: 0,
+            slots: [0; SLOTS],
+        };
+        BLK_LAT.insert(&start.tgid, &empty, 0)?;
+    }
+    if let Some(hist) = BLK_LAT.get_ptr_mut(&start.tgid) {
+        // SAFETY: pointer into the map value; updates from concurrent
+        // completions may race, which only loses a sample.
+        unsafe {
+            (*hist).count += 1;
+            (*hist).total_ns += delta_ns;
+            (*hist).slots[slot] += 1;
+        }
+    }
+    Ok(())
+}
+
+#[unsafe(link_section = "license")]
+#[unsafe(no_mangle)]
+static LICENSE: [u8; 4] = *b"GPL\0";
+
+#[cfg(not(test))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
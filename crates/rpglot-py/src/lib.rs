@@ -0,0 +1,24 @@
+//! rpglot-py — Python bindings for reading rpglot history.
+//!
+//! Built with maturin (`maturin build --release` in this directory), which
+//! enables the `python` feature. The module wraps
+//! [`rpglot_core::client`]:
+//!
+//! ```python
+//! import pandas as pd
+//! import rpglot
+//!
+//! h = rpglot.History("/var/lib/rpglot")
+//! first, last = h.time_range
+//! pgs = pd.DataFrame(h.records("pgs", last - 3600, last))
+//! cpu = pd.DataFrame(h.series("cpu_pct"), columns=["ts", "cpu_pct"])
+//! report = h.analyze(last - 3600, last)
+//! ```
+//!
+//! Snapshots and reports are converted to plain dicts and lists through
+//! their JSON form, so field names match the web API.
+
+pub mod records;
+
+#[cfg(feature = "python")]
+mod python;
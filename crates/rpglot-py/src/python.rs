@@ -0,0 +1,193 @@
+//! The `rpglot` Python module.
+
+use std::ops::Bound as RangeBound;
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyList, PyString};
+use serde_json::Value;
+
+use rpglot_core::client::{self, Client};
+
+use crate::records::{check_table, snapshot_value, table_records};
+
+#[pymodule]
+fn rpglot(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<History>()?;
+    m.add_function(wrap_pyfunction!(metrics, m)?)?;
+    m.add("TABLES", crate::records::TABLES.to_vec())?;
+    Ok(())
+}
+
+/// Metric names accepted by `History.series`.
+#[pyfunction]
+fn metrics() -> Vec<&'static str> {
+    client::metrics().collect()
+}
+
+/// Recorded history of a storage directory written by rpglotd.
+///
+/// Time arguments are epoch seconds; `None` leaves that end open.
+#[pyclass(unsendable, module = "rpglot")]
+struct History {
+    client: Client,
+}
+
+#[pymethods]
+impl History {
+    #[new]
+    fn new(path: PathBuf) -> PyResult<Self> {
+        let client = client::open(&path).map_err(|e| PyOSError::new_err(e.to_string()))?;
+        Ok(Self { client })
+    }
+
+    fn __len__(&self) -> usize {
+        self.client.timestamps().len()
+    }
+
+    /// Timestamps of all snapshots, ascending.
+    #[getter]
+    fn timestamps(&self) -> Vec<i64> {
+        self.client.timestamps().to_vec()
+    }
+
+    /// `(first, last)` stored timestamp.
+    #[getter]
+    fn time_range(&self) -> (i64, i64) {
+        self.client.time_range()
+    }
+
+    /// Picks up data written since opening; returns the number of new snapshots.
+    fn refresh(&mut self) -> PyResult<usize> {
+        self.client
+            .refresh()
+            .map_err(|e| PyOSError::new_err(e.to_string()))
+    }
+
+    /// The last snapshot at or before `ts` as a dict, or `None`.
+    fn snapshot<'py>(&mut self, py: Python<'py>, ts: i64) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let timestamps = self.client.timestamps();
+        let Some(&at) = timestamps[..timestamps.partition_point(|&t| t <= ts)].last() else {
+            return Ok(None);
+        };
+        self.client
+            .snapshots(at..=at)
+            .next()
+            .map(|snapshot| to_py(py, &snapshot_value(&snapshot)))
+            .transpose()
+    }
+
+    /// Snapshots in `[start, end]` as dicts (web API layout).
+    #[pyo3(signature = (start=None, end=None))]
+    fn snapshots<'py>(
+        &mut self,
+        py: Python<'py>,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let list = PyList::empty(py);
+        for snapshot in self.client.snapshots(range(start, end)) {
+            list.append(to_py(py, &snapshot_value(&snapshot))?)?;
+        }
+        Ok(list)
+    }
+
+    /// Rows of one table (`rpglot.TABLES`: "pgs", "pga", "prc", ...) over
+    /// `[start, end]`, each a dict with a `timestamp` key. Ready for
+    /// `pandas.DataFrame`.
+    #[pyo3(signature = (table, start=None, end=None))]
+    fn records<'py>(
+        &mut self,
+        py: Python<'py>,
+        table: &str,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        check_table(table).map_err(PyValueError::new_err)?;
+        let list = PyList::empty(py);
+        for snapshot in self.client.snapshots(range(start, end)) {
+            for row in table_records(&snapshot, table) {
+                list.append(to_py(py, &Value::Object(row))?)?;
+            }
+        }
+        Ok(list)
+    }
+
+    /// `(timestamp, value)` pairs of an instance-wide metric (`rpglot.metrics()`).
+    #[pyo3(signature = (metric, start=None, end=None))]
+    fn series(
+        &mut self,
+        metric: &str,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> PyResult<Vec<(i64, f64)>> {
+        self.client
+            .series(metric, range(start, end))
+            .ok_or_else(|| PyValueError::new_err(format!("unknown metric '{metric}'")))
+    }
+
+    /// Anomaly analysis of `[start, end]`: incidents, groups,
+    /// recommendations and health scores as a dict.
+    #[pyo3(signature = (start=None, end=None))]
+    fn analyze<'py>(
+        &mut self,
+        py: Python<'py>,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let report = self.client.analyze(range(start, end));
+        let value =
+            serde_json::to_value(&report).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        to_py(py, &value)
+    }
+
+    fn __repr__(&self) -> String {
+        let (first, last) = self.client.time_range();
+        format!(
+            "History({} snapshots, {}..{})",
+            self.client.timestamps().len(),
+            first,
+            last
+        )
+    }
+}
+
+fn range(start: Option<i64>, end: Option<i64>) -> (RangeBound<i64>, RangeBound<i64>) {
+    (
+        start.map_or(RangeBound::Unbounded, RangeBound::Included),
+        end.map_or(RangeBound::Unbounded, RangeBound::Included),
+    )
+}
+
+/// JSON value as Python objects: dicts, lists, str, int, float, bool, None.
+fn to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_pyobject(py)?.into_any()
+            } else if let Some(u) = n.as_u64() {
+                u.into_pyobject(py)?.into_any()
+            } else {
+                n.as_f64().unwrap_or(f64::NAN).into_pyobject(py)?.into_any()
+            }
+        }
+        Value::String(s) => PyString::new(py, s).into_any(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(to_py(py, item)?)?;
+            }
+            list.into_any()
+        }
+        Value::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (key, item) in fields {
+                dict.set_item(key, to_py(py, item)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
@@ -0,0 +1,100 @@
+//! Flattening of [`ApiSnapshot`]s into records: one JSON object per table
+//! row, tagged with the snapshot timestamp — the shape a data frame expects.
+
+use serde_json::{Map, Value};
+
+use rpglot_core::api::snapshot::ApiSnapshot;
+
+/// Tables of an [`ApiSnapshot`] (JSON field names): row lists and the
+/// per-snapshot summaries (`system`, `pg`), which yield one row each.
+pub const TABLES: &[&str] = &[
+    "system", "pg", "prc", "pga", "pgs", "pgp", "pgt", "pgi", "pge", "pgl", "pgv", "pgr", "pgw",
+];
+
+/// Key of the snapshot timestamp added to every record.
+pub const TIMESTAMP_KEY: &str = "timestamp";
+
+/// Snapshot as a JSON object.
+pub fn snapshot_value(snapshot: &ApiSnapshot) -> Value {
+    serde_json::to_value(snapshot).unwrap_or(Value::Null)
+}
+
+/// Checks a table name against [`TABLES`].
+pub fn check_table(table: &str) -> Result<(), String> {
+    if TABLES.contains(&table) {
+        Ok(())
+    } else {
+        Err(format!(
+            "unknown table '{}', expected one of: {}",
+            table,
+            TABLES.join(", ")
+        ))
+    }
+}
+
+/// Rows of `table` in `snapshot`, each with [`TIMESTAMP_KEY`] set.
+/// `table` must be one of [`TABLES`].
+pub fn table_records(snapshot: &ApiSnapshot, table: &str) -> Vec<Map<String, Value>> {
+    let rows = match snapshot_value(snapshot) {
+        Value::Object(mut fields) => fields.remove(table),
+        _ => None,
+    };
+    let rows = match rows {
+        Some(Value::Array(rows)) => rows,
+        Some(row @ Value::Object(_)) => vec![row],
+        _ => Vec::new(),
+    };
+    rows.into_iter()
+        .filter_map(|row| match row {
+            Value::Object(mut row) => {
+                row.insert(TIMESTAMP_KEY.to_string(), snapshot.timestamp.into());
+                Some(row)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use rpglot_core::api::convert::{ConvertContext, convert};
+    use rpglot_core::storage::model::{DataBlock, ProcessInfo, Snapshot};
+
+    #[test]
+    fn rows_tagged_with_timestamp() {
+        let snapshot = Snapshot {
+            timestamp: 1000,
+            blocks: vec![DataBlock::Processes(
+                (1..=2)
+                    .map(|pid| ProcessInfo {
+                        pid,
+                        ..ProcessInfo::default()
+                    })
+                    .collect(),
+            )],
+        };
+        let snapshot = convert(&ConvertContext {
+            snapshot: &snapshot,
+            prev_snapshot: None,
+            interner: None,
+            pgs_rates: &HashMap::new(),
+            pgp_rates: &HashMap::new(),
+            pgt_rates: &HashMap::new(),
+            pgi_rates: &HashMap::new(),
+        });
+        let rows = table_records(&snapshot, "prc");
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().any(|row| row["pid"] == 2));
+        assert_eq!(rows[1][TIMESTAMP_KEY], 1000);
+
+        let system = table_records(&snapshot, "system");
+        assert_eq!(system.len(), 1);
+        assert!(table_records(&snapshot, "pga").is_empty());
+
+        assert!(check_table("pgs").is_ok());
+        assert!(check_table("health_score").is_err());
+    }
+}
@@ -6,13 +6,23 @@ import {
   fetchHeatmap,
   fetchAuthConfig,
   fetchAnalysis,
+  fetchBookmarks,
+  addBookmark,
+  deleteBookmark,
+  selectInstance,
 } from "./api/client";
+import { supportsEndpoint } from "./api/capabilities";
 import { useSchema } from "./hooks/useSchema";
 import { useLiveSnapshot, useHistorySnapshot } from "./hooks/useSnapshot";
 import { readUrlState, useUrlSync } from "./hooks/useUrlState";
 import { useTheme } from "./hooks/useTheme";
 import { useTimezone } from "./hooks/useTimezone";
-import { getDatePartsInTz, dateToEpochInTz } from "./utils/formatters";
+import {
+  getDatePartsInTz,
+  dateToEpochInTz,
+  timezoneModeOf,
+  timezoneName,
+} from "./utils/formatters";
 import { TabBar } from "./components/TabBar";
 import { SummaryPanel } from "./components/SummaryPanel";
 import { Timeline } from "./components/Timeline";
@@ -35,6 +45,7 @@ import type {
   AnalysisReport,
   ApiSchema,
   ApiSnapshot,
+  Bookmark,
   InstanceInfo,
   HeatmapBucket,
 } from "./api/types";
@@ -173,7 +184,7 @@ function LiveApp({ schema }: { schema: ApiSchema }) {
   const tabState = useTabState(schema, snapshot);
   const urlSync = useUrlSync();
   const themeHook = useTheme();
-  const timezoneHook = useTimezone();
+  const timezoneHook = useTimezone(timezoneModeOf(schema.format.timezone));
   useDocumentTitle(snapshot, schema.instance);
 
   // Sync pause timestamp to URL
@@ -212,7 +223,12 @@ function LiveApp({ schema }: { schema: ApiSchema }) {
         snapshot={snapshot}
         version={schema.version}
         instance={schema.instance}
+        redaction={schema.redaction}
         authUsername={_authUsername}
+        onInstanceSelect={(name) => {
+          // The stream delivers the selected instance with the next snapshot
+          selectInstance(name).catch(() => {});
+        }}
       />
       {snapshot && <SummaryPanel snapshot={snapshot} schema={schema.summary} />}
       <TabBar
@@ -252,10 +268,11 @@ function HistoryApp({ schema }: { schema: ApiSchema }) {
   const urlState = readUrlState();
   const tabState = useTabState(schema, snapshot);
   const themeHook = useTheme();
-  const timezoneHook = useTimezone();
+  const timezoneHook = useTimezone(timezoneModeOf(schema.format.timezone));
   useDocumentTitle(snapshot, schema.instance);
   const [timeline, setTimeline] = useState(schema.timeline ?? null);
   const [heatmapBuckets, setHeatmapBuckets] = useState<HeatmapBucket[]>([]);
+  const [bookmarks, setBookmarks] = useState<Bookmark[]>([]);
   const snapshotRef = useRef(snapshot);
   snapshotRef.current = snapshot;
   const [playSpeed, setPlaySpeed] = useState<number | null>(null);
@@ -315,7 +332,8 @@ function HistoryApp({ schema }: { schema: ApiSchema }) {
 
   // Load heatmap data for the current hour (and refresh periodically)
   useEffect(() => {
-    if (!hourRange) return;
+    if (!hourRange || !supportsEndpoint(schema, "/api/v1/timeline/heatmap"))
+      return;
     const { start, end } = hourRange;
     let cancelled = false;
     const load = () => {
@@ -332,6 +350,40 @@ function HistoryApp({ schema }: { schema: ApiSchema }) {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [heatmapKey]);
 
+  // Load bookmarks (shared with the TUI) on mount and periodically
+  useEffect(() => {
+    let cancelled = false;
+    const load = () => {
+      fetchBookmarks().then((list) => {
+        if (!cancelled) setBookmarks(list);
+      });
+    };
+    load();
+    const interval = setInterval(load, 30_000);
+    return () => {
+      cancelled = true;
+      clearInterval(interval);
+    };
+  }, []);
+
+  // Bookmark the current snapshot, or delete its bookmark
+  const handleBookmarkToggle = useCallback(async () => {
+    const ts = snapshotRef.current?.timestamp;
+    if (ts == null) return;
+    try {
+      if (bookmarks.some((b) => b.ts === ts)) {
+        await deleteBookmark(ts);
+        setBookmarks(await fetchBookmarks());
+      } else {
+        const label = window.prompt("Bookmark label", "");
+        if (label == null) return;
+        setBookmarks(await addBookmark(ts, label));
+      }
+    } catch {
+      // keep the current list
+    }
+  }, [bookmarks]);
+
   // On mount: jump to URL timestamp
   useEffect(() => {
     if (urlState.timestamp != null) {
@@ -340,12 +392,14 @@ function HistoryApp({ schema }: { schema: ApiSchema }) {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []);
 
-  // Fetch full timeline (with dates) on mount and periodically
+  // Fetch full timeline (with dates in the display time zone) on mount,
+  // periodically and when the time zone changes
   useEffect(() => {
     let cancelled = false;
+    const tz = timezoneName(timezoneHook.timezone);
     const load = async () => {
       try {
-        const tl = await fetchTimeline();
+        const tl = await fetchTimeline(tz);
         if (!cancelled) setTimeline(tl);
       } catch {
         // keep schema.timeline as fallback
@@ -357,7 +411,7 @@ function HistoryApp({ schema }: { schema: ApiSchema }) {
       cancelled = true;
       clearInterval(interval);
     };
-  }, []);
+  }, [timezoneHook.timezone]);
 
   const handleTimestampJump = useCallback(
     (ts: number, direction?: "floor" | "ceil") => {
@@ -377,6 +431,20 @@ function HistoryApp({ schema }: { schema: ApiSchema }) {
     [handleTimestampJump],
   );
 
+  // Switch the PostgreSQL instance and reload the current position
+  const handleInstanceSelect = useCallback(
+    async (name: string | null) => {
+      const ts = snapshotRef.current?.timestamp;
+      try {
+        await selectInstance(name);
+      } catch {
+        return;
+      }
+      if (ts != null) jumpToTimestamp(ts);
+    },
+    [jumpToTimestamp],
+  );
+
   // Play: cycle speed x1 → x2 → x4 → x8 → off
   const handlePlayToggle = useCallback(() => {
     setLiveFollow(false);
@@ -542,12 +610,16 @@ function HistoryApp({ schema }: { schema: ApiSchema }) {
         currentHour={hourRange?.hour}
         version={schema.version}
         instance={schema.instance}
+        redaction={schema.redaction}
         analyzing={analyzing}
         analyzeStartedAt={analyzeStartedAt}
-        onAnalyze={handleAnalyze}
+        onAnalyze={
+          supportsEndpoint(schema, "/api/v1/analysis") ? handleAnalyze : undefined
+        }
         hasReport={!!analysisReport}
         onShowReport={() => setAnalysisModalOpen(true)}
         authUsername={_authUsername}
+        onInstanceSelect={handleInstanceSelect}
       />
       {snapshot && <SummaryPanel snapshot={snapshot} schema={schema.summary} />}
       <TabBar
@@ -589,6 +661,8 @@ function HistoryApp({ schema }: { schema: ApiSchema }) {
           nextTimestamp={snapshot?.next_timestamp}
           timezone={timezoneHook.timezone}
           heatmapBuckets={heatmapBuckets}
+          bookmarks={bookmarks}
+          onBookmarkToggle={handleBookmarkToggle}
           hourStart={hourRange?.start}
           hourEnd={hourRange?.end}
           playSpeed={playSpeed}
@@ -0,0 +1,10 @@
+import type { ApiSchema } from "./types";
+
+// Servers of other versions may lack endpoints this frontend knows about.
+// Servers older than the `capabilities` field are assumed to support
+// everything, as before.
+
+/** Whether the server serves the API path `path` (e.g. "/api/v1/analysis"). */
+export function supportsEndpoint(schema: ApiSchema, path: string): boolean {
+  return schema.capabilities?.endpoints.includes(path) ?? true;
+}
@@ -2,12 +2,18 @@ import type {
   AnalysisReport,
   ApiSchema,
   ApiSnapshot,
+  ApplicationSeries,
+  Bookmark,
+  CalendarDay,
   HeatmapBucket,
   TimelineInfo,
 } from "./types";
 import { getToken, clearToken } from "../auth";
 
-const BASE = "/api/v1";
+// A live server recording with --store serves that history under /history/
+const BASE = window.location.pathname.startsWith("/history/")
+  ? "/history/api/v1"
+  : "/api/v1";
 
 function authHeaders(): HeadersInit {
   const token = getToken();
@@ -22,6 +28,44 @@ export class ForbiddenError extends Error {
   }
 }
 
+/** Error response of the API (RFC 9457 problem details). `code` is
+ *  machine-readable; the message is the server's `detail`. */
+export class ApiError extends Error {
+  status: number;
+  code: string;
+  problem: Record<string, unknown>;
+  constructor(
+    status: number,
+    code: string,
+    detail: string,
+    problem: Record<string, unknown> = {},
+  ) {
+    super(detail);
+    this.status = status;
+    this.code = code;
+    this.problem = problem;
+  }
+}
+
+/** Error for a failed response: the server's problem details, or the bare
+ *  status for responses without them (proxies, older servers). */
+async function apiError(res: Response, what: string): Promise<ApiError> {
+  if (
+    res.headers.get("Content-Type")?.startsWith("application/problem+json")
+  ) {
+    const problem = await res.json().catch(() => null);
+    if (problem && typeof problem.detail === "string") {
+      return new ApiError(
+        res.status,
+        String(problem.code ?? "unknown"),
+        `${what}: ${problem.detail}`,
+        problem,
+      );
+    }
+  }
+  return new ApiError(res.status, "unknown", `${what}: ${res.status}`);
+}
+
 async function authFetch(url: string, init?: RequestInit): Promise<Response> {
   const res = await fetch(url, {
     ...init,
@@ -50,26 +94,53 @@ export async function fetchAuthConfig(): Promise<{
 
 export async function fetchSchema(): Promise<ApiSchema> {
   const res = await authFetch(`${BASE}/schema`);
-  if (!res.ok) throw new Error(`schema: ${res.status}`);
+  if (!res.ok) throw await apiError(res, "schema");
   return res.json();
 }
 
+/** With `fields` / `limit` / `offset` the response is partial (only the
+ *  selected fields, a page of each table, plus `totals`). `tables` sorts and
+ *  pages single tables on the server, e.g. `{ pgs: { sort: "exec_time_ms_s",
+ *  limit: 100 } }`. */
 export async function fetchSnapshot(params?: {
   timestamp?: number;
   direction?: "floor" | "ceil";
+  fields?: string[];
+  limit?: number;
+  offset?: number;
+  tables?: Record<
+    string,
+    { sort?: string; order?: "asc" | "desc"; limit?: number; offset?: number }
+  >;
 }): Promise<ApiSnapshot> {
   const url = new URL(`${BASE}/snapshot`, window.location.origin);
   if (params?.timestamp != null)
     url.searchParams.set("timestamp", String(params.timestamp));
   if (params?.direction) url.searchParams.set("direction", params.direction);
+  if (params?.fields?.length)
+    url.searchParams.set("fields", params.fields.join(","));
+  if (params?.limit != null) url.searchParams.set("limit", String(params.limit));
+  if (params?.offset != null)
+    url.searchParams.set("offset", String(params.offset));
+  for (const [table, p] of Object.entries(params?.tables ?? {})) {
+    if (p.sort) url.searchParams.set(`${table}_sort`, p.sort);
+    if (p.order) url.searchParams.set(`${table}_order`, p.order);
+    if (p.limit != null)
+      url.searchParams.set(`${table}_limit`, String(p.limit));
+    if (p.offset != null)
+      url.searchParams.set(`${table}_offset`, String(p.offset));
+  }
   const res = await authFetch(url.toString());
-  if (!res.ok) throw new Error(`snapshot: ${res.status}`);
+  if (!res.ok) throw await apiError(res, "snapshot");
   return res.json();
 }
 
-export async function fetchTimeline(): Promise<TimelineInfo> {
-  const res = await authFetch(`${BASE}/timeline`);
-  if (!res.ok) throw new Error(`timeline: ${res.status}`);
+/** `tz` sets the day boundaries of `dates` (IANA name). */
+export async function fetchTimeline(tz?: string): Promise<TimelineInfo> {
+  const url = new URL(`${BASE}/timeline`, window.location.origin);
+  if (tz) url.searchParams.set("tz", tz);
+  const res = await authFetch(url.toString());
+  if (!res.ok) throw await apiError(res, "timeline");
   return res.json();
 }
 
@@ -78,7 +149,7 @@ export async function fetchTimelineLatest(): Promise<{
   total_snapshots: number;
 }> {
   const res = await authFetch(`${BASE}/timeline/latest`);
-  if (!res.ok) throw new Error(`timeline/latest: ${res.status}`);
+  if (!res.ok) throw await apiError(res, "timeline/latest");
   return res.json();
 }
 
@@ -96,6 +167,63 @@ export async function fetchHeatmap(
   return res.json();
 }
 
+export async function fetchCalendar(): Promise<CalendarDay[]> {
+  const res = await authFetch(`${BASE}/timeline/calendar`);
+  if (!res.ok) return [];
+  return res.json();
+}
+
+export async function fetchApplicationSeries(
+  start: number,
+  end: number,
+  buckets?: number,
+  top?: number,
+): Promise<ApplicationSeries> {
+  const url = new URL(`${BASE}/timeline/applications`, window.location.origin);
+  url.searchParams.set("start", String(start));
+  url.searchParams.set("end", String(end));
+  if (buckets) url.searchParams.set("buckets", String(buckets));
+  if (top) url.searchParams.set("top", String(top));
+  const res = await authFetch(url.toString());
+  if (!res.ok) return { timestamps: [], series: [] };
+  return res.json();
+}
+
+export async function fetchBookmarks(): Promise<Bookmark[]> {
+  const res = await authFetch(`${BASE}/bookmarks`);
+  if (!res.ok) return [];
+  return res.json();
+}
+
+export async function addBookmark(
+  ts: number,
+  label: string,
+): Promise<Bookmark[]> {
+  const res = await authFetch(`${BASE}/bookmarks`, {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify({ ts, label }),
+  });
+  if (!res.ok) throw await apiError(res, "bookmarks");
+  return res.json();
+}
+
+export async function deleteBookmark(ts: number): Promise<void> {
+  const url = new URL(`${BASE}/bookmarks`, window.location.origin);
+  url.searchParams.set("ts", String(ts));
+  const res = await authFetch(url.toString(), { method: "DELETE" });
+  if (!res.ok && res.status !== 404) throw await apiError(res, "bookmarks");
+}
+
+export async function selectInstance(name: string | null): Promise<void> {
+  const res = await authFetch(`${BASE}/instance`, {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify({ name }),
+  });
+  if (!res.ok) throw await apiError(res, "instance");
+}
+
 export async function fetchAnalysis(
   start: number,
   end: number,
@@ -104,7 +232,7 @@ export async function fetchAnalysis(
   url.searchParams.set("start", String(start));
   url.searchParams.set("end", String(end));
   const res = await authFetch(url.toString());
-  if (!res.ok) throw new Error(`analysis: ${res.status}`);
+  if (!res.ok) throw await apiError(res, "analysis");
   return res.json();
 }
 
@@ -8,6 +8,8 @@ export interface SessionCounts {
   active: number;
   idle: number;
   idle_in_transaction: number;
+  fdw_outgoing: number;
+  fdw_incoming: number;
   total: number;
 }
 
@@ -26,10 +28,37 @@ export interface ApiSnapshot {
   pge: PgEventsRow[];
   pgl: PgLocksRow[];
   pgv: PgProgressVacuumRow[];
+  pgr: PgWorkloadRow[];
+  pgw: PgWorkloadRow[];
+  custom: CustomQuerySection[];
+  pgc: PgSettingsRow[];
   health_score: number;
   health_breakdown: HealthBreakdown;
   session_counts: SessionCounts;
   replication?: ReplicationInfo;
+  encryption?: EncryptionInfo;
+  /** Additional PostgreSQL instances collected by the daemon. */
+  instances?: string[];
+  /** Instance shown in the PG tabs; absent for the primary one. */
+  instance?: string;
+  /** Expensive collectors were skipped under load; PGS/PGP/PGT/PGI are stale. */
+  degraded?: DegradedInfo;
+  /** The collector cannot connect to PostgreSQL; PG data is missing. */
+  pg_connection?: PgConnectionInfo;
+}
+
+export interface DegradedInfo {
+  reason: "slow_collection" | "pressure";
+  collection_ms: number;
+  psi_some_avg10: number;
+}
+
+export interface PgConnectionInfo {
+  state: "reconnecting" | "down";
+  since: number;
+  failures: number;
+  next_attempt: number;
+  error: string;
 }
 
 export interface HealthBreakdown {
@@ -157,6 +186,8 @@ export interface PgSummary {
   errors_critical: number | null;
   errors_warning: number | null;
   errors_info: number | null;
+  connections_s: number | null;
+  short_lived_conn_pct: number | null;
   bgwriter: BgwriterSummary | null;
 }
 
@@ -214,6 +245,8 @@ export interface ApiProcessRow {
   total_read_ops: number;
   total_write_ops: number;
   cancelled_write_bytes: number;
+  blk_lat_avg_ms: number | null;
+  blk_lat_p99_ms: number | null;
   uid: number;
   euid: number;
   gid: number;
@@ -252,6 +285,8 @@ export interface PgActivityRow {
   stmt_max_exec_time_ms: number | null;
   stmt_calls_s: number | null;
   stmt_hit_pct: number | null;
+  backend_xid: number;
+  backend_xmin_age: number;
 }
 
 export interface PgStatementsRow {
@@ -423,6 +458,48 @@ export interface PgLocksRow {
   state_change: number;
 }
 
+export interface PgWorkloadRow {
+  name: string;
+  sessions: number;
+  active: number;
+  idle_in_transaction: number;
+  waiting: number;
+  active_time_s: number;
+  remote_waiting: number;
+  remote_time_s: number;
+  statements: number;
+  calls_s: number | null;
+  rows_s: number | null;
+  exec_time_ms_s: number | null;
+}
+
+export interface CustomQuerySection {
+  name: string;
+  columns: CustomQueryColumn[];
+  rows: CustomQueryResultRow[];
+}
+
+export interface CustomQueryColumn {
+  name: string;
+  type: "key" | "int" | "float";
+}
+
+export interface CustomQueryResultRow {
+  keys: string[];
+  values: (number | null)[];
+}
+
+export interface PgSettingsRow {
+  name: string;
+  setting: string;
+  unit: string;
+  category: string;
+  source: string;
+  boot_val: string;
+  non_default: boolean;
+  pending_restart: boolean;
+}
+
 export interface PgProgressVacuumRow {
   pid: number;
   database: string;
@@ -450,6 +527,27 @@ export interface InstanceInfo {
   pg_version: string;
   is_standby?: boolean;
   hostname?: string;
+  kubernetes?: KubernetesInfo;
+  /** Installed extensions of every database (last hourly sample). */
+  extensions?: ExtensionInfo[];
+}
+
+export interface ExtensionInfo {
+  database: string;
+  name: string;
+  version: string;
+  schema: string;
+}
+
+/** Kubernetes pod metadata; zero resources are not declared. */
+export interface KubernetesInfo {
+  pod: string;
+  namespace: string;
+  node?: string;
+  cpu_request_millis: number;
+  cpu_limit_millis: number;
+  memory_request: number;
+  memory_limit: number;
 }
 
 export interface ReplicaDetail {
@@ -466,6 +564,30 @@ export interface ReplicationInfo {
   connected_replicas: number;
   replicas: ReplicaDetail[];
   sender_host?: string;
+  archiver?: ArchiverInfo;
+}
+
+export interface EncryptionInfo {
+  tls_connections: number;
+  plaintext_connections: number;
+  local_connections: number;
+  plaintext_clients: PlaintextClient[];
+}
+
+export interface PlaintextClient {
+  client_addr: string;
+  connections: number;
+}
+
+export interface ArchiverInfo {
+  archived_count: number;
+  failed_count: number;
+  failing: boolean;
+  last_archived_wal?: string;
+  last_archived_time?: number;
+  last_failed_wal?: string;
+  last_failed_time?: number;
+  pending_segments?: number;
 }
 
 export interface ApiSchema {
@@ -473,8 +595,35 @@ export interface ApiSchema {
   mode: "live" | "history";
   timeline?: TimelineInfo;
   instance?: InstanceInfo;
+  /** Query text redaction applied by the collector; absent when off. */
+  redaction?: "strip" | "hash" | "drop";
+  /** Number and time formatting the server is configured with. */
+  format: FmtConfig;
   summary: SummarySchema;
   tabs: TabsSchema;
+  /** What the server supports; absent on servers older than this field. */
+  capabilities?: Capabilities;
+}
+
+export interface Capabilities {
+  /** Snapshot tables with the JSON fields of their rows. */
+  tables: { name: string; columns: string[] }[];
+  summary: string[];
+  /** API paths served, e.g. "/api/v1/buffercache". */
+  endpoints: string[];
+  /** Chunk format version written by the server. */
+  chunk_format: number;
+  /** Schema version of each stored data block kind. */
+  blocks: Record<string, number>;
+}
+
+export interface FmtConfig {
+  /** Decimal comma instead of a decimal point. */
+  decimal_comma: boolean;
+  /** SI byte units (1 kB = 1000 B) instead of binary ones. */
+  si_units: boolean;
+  /** Server time zone: "local" or an IANA name such as "Europe/Moscow". */
+  timezone: string;
 }
 
 export interface TimelineInfo {
@@ -482,6 +631,30 @@ export interface TimelineInfo {
   end: number;
   total_snapshots: number;
   dates?: DateInfo[];
+  annotations?: Annotation[];
+  /** Intervals without snapshots (collector down, host rebooted). */
+  gaps?: TimelineGap[];
+}
+
+export interface TimelineGap {
+  /** Last snapshot before the gap. */
+  start: number;
+  /** First snapshot after the gap. */
+  end: number;
+}
+
+export interface Annotation {
+  id: number;
+  ts: number;
+  text: string;
+  source: string;
+  created_at: number;
+}
+
+export interface Bookmark {
+  ts: number;
+  label: string;
+  created_at: number;
 }
 
 export interface DateInfo {
@@ -539,6 +712,15 @@ export interface ColumnSchema {
   format?: Format;
   sortable: boolean;
   filterable?: boolean;
+  thresholds?: ColumnThresholds;
+}
+
+/** Warning/critical levels of a column, shared with the TUI. */
+export interface ColumnThresholds {
+  warn: number;
+  crit?: number;
+  /** Values below the levels are worse (hit ratios). */
+  lower_is_worse?: boolean;
 }
 
 export interface ViewSchema {
@@ -592,6 +774,16 @@ export type Format = "bytes" | "duration" | "rate" | "percent" | "age";
 // Heatmap
 // ============================================================
 
+export interface ApplicationSeries {
+  timestamps: number[];
+  series: ApplicationSeriesLine[];
+}
+
+export interface ApplicationSeriesLine {
+  application: string;
+  active: number[];
+}
+
 export interface HeatmapBucket {
   ts: number;
   active: number;
@@ -605,6 +797,21 @@ export interface HeatmapBucket {
   autovacuums: number;
   slow_queries: number;
   health: number;
+  annotations: number;
+  /** Busiest disk utilization * 10 (0..1000). */
+  io: number;
+  sessions: number;
+  tps: number;
+  /** WAL rate, KiB/s. */
+  wal: number;
+}
+
+export interface CalendarDay {
+  date: string;
+  snapshots: number;
+  health_min: number;
+  health_avg: number;
+  critical_incidents: number;
 }
 
 // Tab key type
@@ -646,6 +853,29 @@ export interface AnalysisReport {
   recommendations: AnalysisRecommendation[];
   summary: AnalysisSummary;
   health_scores: HealthPoint[];
+  hotspots: Hotspot[];
+  plan_changes: PlanChange[];
+}
+
+/** Queryid that switched to a slower dominant plan (pg_store_plans). */
+export interface PlanChange {
+  queryid: number;
+  from_planid: number;
+  to_planid: number;
+  switched_at: number;
+  last_ts: number;
+  from_mean_ms: number;
+  to_mean_ms: number;
+  from_plan: string | null;
+  to_plan: string | null;
+}
+
+export interface Hotspot {
+  series: "cpu" | "io" | "sessions" | "tps" | "wal";
+  start_ts: number;
+  end_ts: number;
+  peak_ts: number;
+  peak: number;
 }
 
 export interface AnalysisIncident {
@@ -660,6 +890,8 @@ export interface AnalysisIncident {
   detail: string | null;
   snapshot_count: number;
   entity_id: number | null;
+  /** planid of plan incidents (the new plan of a plan change). */
+  planid: number | null;
 }
 
 export interface AnalysisRecommendation {
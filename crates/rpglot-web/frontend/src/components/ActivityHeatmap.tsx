@@ -27,6 +27,21 @@ export function ActivityHeatmap({
     [buckets],
   );
 
+  // Mini-series: disk I/O% (absolute) and TPS (relative to the range max)
+  const seriesPoints = useMemo(() => {
+    const maxTps = Math.max(1, ...buckets.map((b) => b.tps));
+    const points = (value: (b: HeatmapBucket) => number) =>
+      buckets
+        .map((b, i) => `${i + 0.5},${(24 - value(b) * 22).toFixed(2)}`)
+        .join(" ");
+    return {
+      io: buckets.some((b) => b.io > 0) ? points((b) => b.io / 1000) : null,
+      tps: buckets.some((b) => b.tps > 0)
+        ? points((b) => b.tps / maxTps)
+        : null,
+    };
+  }, [buckets]);
+
   const range = endTs - startTs;
   if (buckets.length === 0 || range <= 0) return null;
 
@@ -60,6 +75,26 @@ export function ActivityHeatmap({
           />
         );
       })}
+      {seriesPoints.io && (
+        <polyline
+          points={seriesPoints.io}
+          fill="none"
+          stroke="var(--status-warning)"
+          strokeWidth={1}
+          vectorEffect="non-scaling-stroke"
+          opacity={0.6}
+        />
+      )}
+      {seriesPoints.tps && (
+        <polyline
+          points={seriesPoints.tps}
+          fill="none"
+          stroke="var(--text-secondary)"
+          strokeWidth={1}
+          vectorEffect="non-scaling-stroke"
+          opacity={0.5}
+        />
+      )}
       {/* Error indicators — tri-color dots by severity (highest wins) */}
       {buckets.map((b, i) =>
         b.errors_critical > 0 ? (
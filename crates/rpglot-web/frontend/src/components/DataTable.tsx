@@ -223,6 +223,7 @@ export function DataTable({
                 key,
                 info.getValue(),
                 info.row.original,
+                schema.thresholds,
               );
               return colorClass ? (
                 <span className={colorClass}>{formatted}</span>
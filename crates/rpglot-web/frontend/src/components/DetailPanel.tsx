@@ -112,6 +112,8 @@ const TAB_SECTIONS: Record<TabKey, Section[]> = {
         "total_read_ops",
         "total_write_ops",
         "cancelled_write_bytes",
+        "blk_lat_avg_ms",
+        "blk_lat_p99_ms",
       ],
     },
     {
@@ -147,6 +149,10 @@ const TAB_SECTIONS: Record<TabKey, Section[]> = {
       title: "State",
       fields: ["state", "wait_event_type", "wait_event"],
     },
+    {
+      title: "Transaction",
+      fields: ["backend_xid", "backend_xmin_age"],
+    },
     {
       title: "OS Process",
       fields: [
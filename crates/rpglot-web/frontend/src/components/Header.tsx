@@ -20,12 +20,16 @@ import {
   ArrowDown,
   ArrowUp,
   ExternalLink,
+  EyeOff,
+  Gauge,
+  Unplug,
 } from "lucide-react";
 import {
   formatTimestamp,
   formatDate,
   getDatePartsInTz,
   dateToEpochInTz,
+  formatValue,
 } from "../utils/formatters";
 import type { TimezoneMode } from "../utils/formatters";
 import { CalendarPopover } from "./CalendarPopover";
@@ -34,7 +38,9 @@ import { RichTooltip } from "./RichTooltip";
 import { healthColor, healthBgColor } from "../utils/healthScore";
 import type {
   ApiSnapshot,
+  ArchiverInfo,
   InstanceInfo,
+  KubernetesInfo,
   TimelineInfo,
   DateInfo,
 } from "../api/types";
@@ -57,6 +63,19 @@ const TZ_DISPLAY: Record<TimezoneMode, string> = {
 };
 
 /** Derive the largest database name from PGT rows (by total size_bytes). */
+/** Tooltip of the pod badge: node and declared requests/limits. */
+function podTitle(k8s: KubernetesInfo): string {
+  const cpu = (m: number) => (m ? `${m}m` : "-");
+  const mem = (b: number) => (b ? formatValue(b, "bytes", "bytes") : "-");
+  const lines = [`Pod: ${k8s.namespace}/${k8s.pod}`];
+  if (k8s.node) lines.push(`Node: ${k8s.node}`);
+  lines.push(
+    `CPU request/limit: ${cpu(k8s.cpu_request_millis)} / ${cpu(k8s.cpu_limit_millis)}`,
+    `Memory request/limit: ${mem(k8s.memory_request)} / ${mem(k8s.memory_limit)}`,
+  );
+  return lines.join("\n");
+}
+
 export function deriveLargestDb(
   snapshot: ApiSnapshot | null | undefined,
 ): string {
@@ -94,12 +113,14 @@ export function Header({
   currentHour,
   version,
   instance,
+  redaction,
   analyzing,
   analyzeStartedAt,
   onAnalyze,
   hasReport,
   onShowReport,
   authUsername,
+  onInstanceSelect,
 }: {
   mode: string;
   timestamp?: number;
@@ -115,12 +136,14 @@ export function Header({
   currentHour?: number;
   version?: string;
   instance?: InstanceInfo;
+  redaction?: string;
   analyzing?: boolean;
   analyzeStartedAt?: number | null;
   onAnalyze?: () => void;
   hasReport?: boolean;
   onShowReport?: () => void;
   authUsername?: string | null;
+  onInstanceSelect?: (name: string | null) => void;
 }) {
   const [calendarOpen, setCalendarOpen] = useState(false);
   const [anchorRect, setAnchorRect] = useState<DOMRect | null>(null);
@@ -226,6 +249,29 @@ export function Header({
             </span>
           );
         })()}
+        {instance?.kubernetes && (
+          <span
+            className="text-xs px-2 py-0.5 rounded-full font-mono bg-[var(--bg-elevated)] text-[var(--text-secondary)] border border-[var(--border-default)]"
+            title={podTitle(instance.kubernetes)}
+          >
+            {instance.kubernetes.namespace}/{instance.kubernetes.pod}
+          </span>
+        )}
+        {snapshot?.instances?.length && onInstanceSelect ? (
+          <select
+            value={snapshot.instance ?? ""}
+            onChange={(e) => onInstanceSelect(e.target.value || null)}
+            className="text-xs px-1.5 py-0.5 rounded bg-[var(--bg-elevated)] text-[var(--text-primary)] border border-[var(--border-default)] focus:outline-none focus:ring-1 focus:ring-[var(--accent)] cursor-pointer"
+            title="PostgreSQL instance shown in the PG tabs"
+          >
+            <option value="">primary</option>
+            {snapshot.instances.map((name) => (
+              <option key={name} value={name}>
+                {name}
+              </option>
+            ))}
+          </select>
+        ) : null}
         <span
           className={`flex items-center gap-1 text-xs px-2 py-0.5 rounded-full font-medium ${
             mode === "live"
@@ -236,6 +282,37 @@ export function Header({
           {mode === "live" ? <Radio size={10} /> : <History size={10} />}
           {mode}
         </span>
+        {redaction && (
+          <span
+            className="flex items-center gap-1 text-xs px-2 py-0.5 rounded-full font-medium bg-[var(--bg-elevated)] text-[var(--text-secondary)] border border-[var(--border-default)]"
+            title={`Query texts are redacted by the collector (${redaction})`}
+          >
+            <EyeOff size={10} />
+            redacted
+          </span>
+        )}
+        {snapshot?.degraded && (
+          <span
+            className="flex items-center gap-1 text-xs px-2 py-0.5 rounded-full font-medium bg-[var(--status-warning-bg)] text-[var(--status-warning)]"
+            title={
+              snapshot.degraded.reason === "pressure"
+                ? `Host under pressure (PSI ${snapshot.degraded.psi_some_avg10.toFixed(0)}%): statements, plans, tables and indexes were not refreshed`
+                : `Collection took ${snapshot.degraded.collection_ms} ms: statements, plans, tables and indexes were not refreshed`
+            }
+          >
+            <Gauge size={10} />
+            degraded
+          </span>
+        )}
+        {snapshot?.pg_connection && (
+          <span
+            className="flex items-center gap-1 text-xs px-2 py-0.5 rounded-full font-medium bg-[var(--status-critical-bg)] text-[var(--status-critical)]"
+            title={`PostgreSQL unreachable since ${formatTimestamp(snapshot.pg_connection.since, tz)} (${snapshot.pg_connection.failures} failed attempts): ${snapshot.pg_connection.error}`}
+          >
+            <Unplug size={10} />
+            pg {snapshot.pg_connection.state}
+          </span>
+        )}
         {mode === "live" && onTogglePause && (
           <button
             onClick={onTogglePause}
@@ -453,6 +530,12 @@ function SessionBadge({ snapshot }: { snapshot: ApiSnapshot }) {
             Active: {sc.active} &middot; Idle: {sc.idle} &middot; IdleTx:{" "}
             {sc.idle_in_transaction}
           </div>
+          {(sc.fdw_outgoing > 0 || sc.fdw_incoming > 0) && (
+            <div className="text-xs text-[var(--text-secondary)]">
+              FDW/dblink: waiting on remote {sc.fdw_outgoing} &middot; from
+              remote {sc.fdw_incoming}
+            </div>
+          )}
         </div>
       }
       side="bottom"
@@ -542,8 +625,15 @@ function ReplicationBadge({ snapshot }: { snapshot: ApiSnapshot }) {
 
   // Primary
   const n = repl.connected_replicas;
-  const color = "var(--status-success)";
-  const bgColor = "var(--status-success-bg)";
+  const arc = repl.archiver;
+  const archiveBehind =
+    arc != null && (arc.failing || (arc.pending_segments ?? 0) > 64);
+  const color = archiveBehind
+    ? "var(--status-critical)"
+    : "var(--status-success)";
+  const bgColor = archiveBehind
+    ? "var(--status-critical-bg)"
+    : "var(--status-success-bg)";
 
   const formatBytes = (b: number | undefined) => {
     if (b == null) return "?";
@@ -617,6 +707,7 @@ function ReplicationBadge({ snapshot }: { snapshot: ApiSnapshot }) {
               No connected replicas
             </div>
           )}
+          {arc && <ArchiverStatus arc={arc} now={snapshot.timestamp} />}
         </div>
       }
       side="bottom"
@@ -627,7 +718,35 @@ function ReplicationBadge({ snapshot }: { snapshot: ApiSnapshot }) {
       >
         <GitBranch size={10} />
         Primary{n > 0 ? ` \u00b7 ${n}R` : ""}
+        {archiveBehind && " \u00b7 archive"}
       </span>
     </RichTooltip>
   );
 }
+
+function ArchiverStatus({ arc, now }: { arc: ArchiverInfo; now: number }) {
+  const age = (ts?: number) =>
+    ts == null ? "never" : `${Math.max(0, now - ts)}s ago`;
+  return (
+    <>
+      <div className="border-t border-[var(--border-default)] my-1.5" />
+      <div className="text-xs text-[var(--text-secondary)]">
+        WAL archiving:{" "}
+        {arc.failing ? (
+          <span className="text-[var(--status-critical)] font-medium">
+            failing on {arc.last_failed_wal}
+          </span>
+        ) : (
+          "ok"
+        )}
+      </div>
+      <div className="text-xs text-[var(--text-tertiary)]">
+        Last archived: {arc.last_archived_wal ?? "-"} ({age(arc.last_archived_time)})
+      </div>
+      <div className="text-xs text-[var(--text-tertiary)]">
+        Pending: {arc.pending_segments ?? "?"} segments &middot; failed{" "}
+        {arc.failed_count} / archived {arc.archived_count}
+      </div>
+    </>
+  );
+}
@@ -48,6 +48,21 @@ export function HeatmapTooltip({
     label: `CPU ${cpuPct}%, ${bucket.active} active`,
   });
 
+  if (bucket.io > 0 || bucket.tps > 0 || bucket.wal > 0) {
+    const parts: string[] = [];
+    if (bucket.io > 0) parts.push(`I/O ${(bucket.io / 10).toFixed(1)}%`);
+    if (bucket.tps > 0) parts.push(`${bucket.tps} TPS`);
+    if (bucket.wal > 0) parts.push(`WAL ${bucket.wal} KiB/s`);
+    if (bucket.sessions > 0) parts.push(`${bucket.sessions} sessions`);
+    items.push({
+      color: "var(--text-secondary)",
+      shape: (
+        <span className="inline-block w-2.5 h-0.5 bg-[var(--text-secondary)]" />
+      ),
+      label: parts.join(", "),
+    });
+  }
+
   if (totalErrors > 0) {
     const parts: string[] = [];
     if (bucket.errors_critical > 0)
@@ -113,6 +128,16 @@ export function HeatmapTooltip({
     });
   }
 
+  if (bucket.annotations > 0) {
+    items.push({
+      color: "var(--accent)",
+      shape: (
+        <span className="inline-block w-0.5 h-2.5 bg-[var(--accent)]" />
+      ),
+      label: `Annotations: ${bucket.annotations}`,
+    });
+  }
+
   // Clamp tooltip to viewport
   const tooltipX = Math.max(90, Math.min(x, window.innerWidth - 90));
 
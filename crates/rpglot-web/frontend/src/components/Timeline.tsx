@@ -1,6 +1,7 @@
 import { useState, useMemo, useCallback, useRef } from "react";
 import { createPortal } from "react-dom";
 import {
+  Bookmark as BookmarkIcon,
   ChevronLeft,
   ChevronRight,
   ChevronsLeft,
@@ -8,7 +9,7 @@ import {
 } from "lucide-react";
 import type { TimezoneMode } from "../utils/formatters";
 import { formatTime } from "../utils/formatters";
-import type { TimelineInfo, HeatmapBucket } from "../api/types";
+import type { Bookmark, TimelineInfo, HeatmapBucket } from "../api/types";
 import { ActivityHeatmap } from "./ActivityHeatmap";
 import { HeatmapTooltip } from "./HeatmapTooltip";
 
@@ -24,6 +25,8 @@ interface TimelineProps {
   nextTimestamp?: number;
   timezone: TimezoneMode;
   heatmapBuckets?: HeatmapBucket[];
+  bookmarks?: Bookmark[];
+  onBookmarkToggle?: () => void;
   hourStart?: number;
   hourEnd?: number;
   playSpeed?: number | null;
@@ -40,6 +43,8 @@ export function Timeline({
   nextTimestamp,
   timezone,
   heatmapBuckets,
+  bookmarks,
+  onBookmarkToggle,
   hourStart,
   hourEnd,
   playSpeed,
@@ -140,6 +145,29 @@ export function Timeline({
     if (ts < timeline.end) onTimestampJump(ts + 3600);
   }, [ts, timeline.end, onTimestampJump]);
 
+  // Bookmarks inside the slider range, positioned in percent
+  const visibleBookmarks = useMemo(() => {
+    if (!bookmarks || sliderMax <= sliderMin) return [];
+    return bookmarks
+      .filter((b) => b.ts >= sliderMin && b.ts <= sliderMax)
+      .map((b) => ({
+        ...b,
+        left: ((b.ts - sliderMin) / (sliderMax - sliderMin)) * 100,
+      }));
+  }, [bookmarks, sliderMin, sliderMax]);
+  const isBookmarked = bookmarks?.some((b) => b.ts === ts) ?? false;
+
+  // Annotations (deployments, maintenance) inside the slider range
+  const visibleAnnotations = useMemo(() => {
+    if (!timeline.annotations || sliderMax <= sliderMin) return [];
+    return timeline.annotations
+      .filter((a) => a.ts >= sliderMin && a.ts <= sliderMax)
+      .map((a) => ({
+        ...a,
+        left: ((a.ts - sliderMin) / (sliderMax - sliderMin)) * 100,
+      }));
+  }, [timeline.annotations, sliderMin, sliderMax]);
+
   return (
     <div className="flex items-center gap-2 px-4 py-1.5 bg-[var(--bg-surface)] border-t border-[var(--border-default)] text-xs">
       {/* Prev snapshot */}
@@ -192,6 +220,24 @@ export function Timeline({
           className="relative z-10 w-full h-1 opacity-80"
           style={{ accentColor: "var(--accent)" }}
         />
+        {visibleBookmarks.map((b) => (
+          <button
+            key={b.ts}
+            onClick={() => onTimestampJump(b.ts)}
+            title={`${formatTime(b.ts, timezone)}${b.label ? ` \u2014 ${b.label}` : ""}`}
+            className="absolute top-0 z-20 w-1.5 h-2 -translate-x-1/2 rounded-sm bg-[var(--accent)]"
+            style={{ left: `${b.left}%` }}
+          />
+        ))}
+        {visibleAnnotations.map((a) => (
+          <button
+            key={a.id}
+            onClick={() => onTimestampJump(a.ts)}
+            title={`${formatTime(a.ts, timezone)} \u2014 ${a.text}${a.source ? ` (${a.source})` : ""}`}
+            className="absolute bottom-0 z-20 w-0.5 h-3 -translate-x-1/2 bg-[var(--status-info,#38bdf8)]"
+            style={{ left: `${a.left}%` }}
+          />
+        ))}
         {hoveredBucket &&
           createPortal(
             <HeatmapTooltip
@@ -230,6 +276,20 @@ export function Timeline({
         <ChevronRight size={14} />
       </StepButton>
 
+      {/* Bookmark current snapshot */}
+      {onBookmarkToggle && (
+        <StepButton
+          onClick={onBookmarkToggle}
+          disabled={ts <= 0}
+          title={isBookmarked ? "Remove bookmark" : "Bookmark this snapshot"}
+        >
+          <BookmarkIcon
+            size={14}
+            fill={isBookmarked ? "currentColor" : "none"}
+          />
+        </StepButton>
+      )}
+
       {/* Play button */}
       {onPlayToggle && (
         <button
@@ -54,6 +54,9 @@ export const RULE_TARGET: Record<string, { tab: TabKey; view?: string }> = {
   // PGS
   stmt_mean_time_spike: { tab: "pgs" },
   stmt_call_spike: { tab: "pgs" },
+  stats_reset: { tab: "pgs" },
+  // PGP
+  plan_change: { tab: "pgp" },
   // PGT
   dead_tuples_high: { tab: "pgt" },
   seq_scan_dominant: { tab: "pgt", view: "scans" },
@@ -70,6 +73,8 @@ export const RULE_TARGET: Record<string, { tab: TabKey; view?: string }> = {
   wait_lock: { tab: "pga" },
   high_active_sessions: { tab: "pga" },
   tps_spike: { tab: "pga" },
+  connection_rate_high: { tab: "pga" },
+  short_lived_connections: { tab: "pga" },
   // PGL
   blocked_sessions: { tab: "pgl" },
   // PGE
@@ -93,6 +98,9 @@ export const RULE_TARGET: Record<string, { tab: TabKey; view?: string }> = {
   network_spike: { tab: "prc" },
   cgroup_throttled: { tab: "prc" },
   cgroup_oom_kill: { tab: "prc" },
+  kernel_oom_kill: { tab: "prc" },
+  kernel_disk_error: { tab: "prc" },
+  pg_service: { tab: "pga" },
 };
 
 /** Column filter for aggregate PGA rules (Group C). */
@@ -121,6 +129,9 @@ export const CATEGORY_LABEL: Record<string, string> = {
   pg_events: "PG Events",
   pg_locks: "PG Locks",
   pg_errors: "PG Errors",
+  annotation: "Annotations",
+  settings: "PG Settings",
+  data_gap: "Data gaps",
 };
 
 /** Human-readable label for each rule_id. Ordered — determines lane order in timeline. */
@@ -139,14 +150,21 @@ export const RULE_LABEL: Record<string, string> = {
   network_spike: "Network",
   cgroup_throttled: "Cgroup thr.",
   cgroup_oom_kill: "OOM kill",
+  kernel_oom_kill: "Kernel OOM",
+  kernel_disk_error: "Disk errors",
   idle_in_transaction: "Idle in tx",
   long_query: "Long query",
   wait_sync_replica: "Sync repl.",
   wait_lock: "Lock wait",
   high_active_sessions: "Active sess.",
   tps_spike: "TPS spike",
+  connection_rate_high: "Conn. storm",
+  short_lived_connections: "Short conns",
   stmt_call_spike: "Query calls",
   stmt_mean_time_spike: "Query time",
+  stats_reset: "Stats reset",
+  plan_change: "Plan change",
+  pg_service: "PG service",
   checkpoint_spike: "Checkpoint",
   backend_buffers_high: "Backend buf.",
   dead_tuples_high: "Dead tuples",
@@ -159,6 +177,9 @@ export const RULE_LABEL: Record<string, string> = {
   blocked_sessions: "Blocked",
   pg_errors: "PG errors",
   pg_fatal_panic: "FATAL/PANIC",
+  annotation: "Annotation",
+  setting_change: "Setting",
+  data_gap: "No data",
 };
 
 /** Ordered list of rule_ids — determines lane order in timeline. */
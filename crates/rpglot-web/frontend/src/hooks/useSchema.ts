@@ -1,6 +1,7 @@
 import { useEffect, useState } from "react";
 import { fetchSchema, ForbiddenError } from "../api/client";
 import type { ApiSchema } from "../api/types";
+import { setFmtConfig } from "../utils/formatters";
 
 export function useSchema() {
   const [schema, setSchema] = useState<ApiSchema | null>(null);
@@ -9,7 +10,10 @@ export function useSchema() {
 
   useEffect(() => {
     fetchSchema()
-      .then(setSchema)
+      .then((schema) => {
+        setFmtConfig(schema.format);
+        setSchema(schema);
+      })
       .catch((e) => {
         if (e instanceof ForbiddenError) {
           setForbiddenUser(e.username);
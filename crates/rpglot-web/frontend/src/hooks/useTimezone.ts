@@ -4,12 +4,13 @@ import type { TimezoneMode } from "../utils/formatters";
 const STORAGE_KEY = "rpglot-timezone";
 const MODES: TimezoneMode[] = ["local", "utc", "moscow"];
 
-export function useTimezone() {
+/** `fallback` applies until the user picks a time zone. */
+export function useTimezone(fallback: TimezoneMode = "local") {
   const [timezone, setTimezone] = useState<TimezoneMode>(() => {
     const stored = localStorage.getItem(STORAGE_KEY);
     return MODES.includes(stored as TimezoneMode)
       ? (stored as TimezoneMode)
-      : "local";
+      : fallback;
   });
 
   const cycle = useCallback(() => {
@@ -29,6 +29,10 @@ const EXTRA_DESCRIPTIONS: Record<string, string> = {
   total_read_ops: "Total read operations (cumulative)",
   total_write_ops: "Total write operations (cumulative)",
   cancelled_write_bytes: "Cancelled write bytes (truncated files)",
+  blk_lat_avg_ms:
+    "Mean block I/O request latency since previous snapshot (eBPF collector)",
+  blk_lat_p99_ms:
+    "99th percentile block I/O latency, log2 bucket bound (eBPF collector)",
   uid: "Real user ID",
   euid: "Effective user ID",
   gid: "Real group ID",
@@ -624,6 +624,20 @@ export const COLUMN_HELP: Record<string, ColumnHelpEntry> = {
     tip: "Long transactions prevent VACUUM from reclaiming dead rows and hold locks",
     docUrl: PG_STAT_ACTIVITY,
   },
+  backend_xid: {
+    label: "Transaction ID",
+    description:
+      "Top-level transaction ID of this session (backend_xid). Assigned on the first write; empty for read-only transactions.",
+    docUrl: PG_STAT_ACTIVITY,
+  },
+  backend_xmin_age: {
+    label: "Xmin Age",
+    description:
+      "Age in transactions of the session's xmin horizon (age(backend_xmin)) \u2014 how far back its snapshot reaches.",
+    thresholds: ">10M critical \u00b7 >1M warning",
+    tip: "VACUUM cannot remove rows newer than the oldest xmin: a long-held snapshot causes bloat and blocks freezing",
+    docUrl: PG_STAT_ACTIVITY,
+  },
   backend_duration_s: {
     label: "Backend Duration",
     description: "Time since this backend process connected (backend_start).",
@@ -1,13 +1,47 @@
-import type { Format, Unit } from "../api/types";
+import type { FmtConfig, Format, Unit } from "../api/types";
 
 export type TimezoneMode = "local" | "utc" | "moscow";
 
+let fmtConfig: FmtConfig = {
+  decimal_comma: false,
+  si_units: false,
+  timezone: "local",
+};
+
+/** Applies the server's formatting options (from the API schema). */
+export function setFmtConfig(config: FmtConfig) {
+  fmtConfig = config;
+}
+
+/** `toFixed` with the configured decimal separator. */
+function fixed(value: number, digits: number): string {
+  const s = value.toFixed(digits);
+  return fmtConfig.decimal_comma ? s.replace(".", ",") : s;
+}
+
 const TZ_LABELS: Record<TimezoneMode, string> = {
   local: "",
   utc: " UTC",
   moscow: " MSK",
 };
 
+/** Display mode the server time zone maps to (before the user picks one). */
+export function timezoneModeOf(name: string): TimezoneMode {
+  switch (name) {
+    case "UTC":
+      return "utc";
+    case "Europe/Moscow":
+      return "moscow";
+    default:
+      return "local";
+  }
+}
+
+/** IANA name of a display mode, sent as `tz=` so day boundaries match. */
+export function timezoneName(tz: TimezoneMode): string {
+  return tzOption(tz) ?? Intl.DateTimeFormat().resolvedOptions().timeZone;
+}
+
 function tzOption(tz: TimezoneMode): string | undefined {
   switch (tz) {
     case "utc":
@@ -82,7 +116,7 @@ export function formatValue(
     return formatRate(num);
   }
   if (format === "percent") {
-    return `${num.toFixed(1)}%`;
+    return `${fixed(num, 1)}%`;
   }
   if (format === "age") {
     if (num === 0) return "-";
@@ -93,30 +127,34 @@ export function formatValue(
   }
 
   // No format — use unit hints
-  if (unit === "ms") return `${num.toFixed(1)} ms`;
-  if (unit === "percent") return `${num.toFixed(1)}%`;
+  if (unit === "ms") return `${fixed(num, 1)} ms`;
+  if (unit === "percent") return `${fixed(num, 1)}%`;
 
   if (Number.isInteger(num)) return String(num);
-  return num.toFixed(2);
+  return fixed(num, 2);
 }
 
 function formatBytes(bytes: number): string {
   if (bytes === 0) return "0 B";
   const abs = Math.abs(bytes);
-  if (abs < 1024) return `${bytes.toFixed(0)} B`;
-  if (abs < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KiB`;
-  if (abs < 1024 * 1024 * 1024)
-    return `${(bytes / (1024 * 1024)).toFixed(1)} MiB`;
-  return `${(bytes / (1024 * 1024 * 1024)).toFixed(2)} GiB`;
+  const base = fmtConfig.si_units ? 1000 : 1024;
+  const [k, m, g] = fmtConfig.si_units
+    ? ["kB", "MB", "GB"]
+    : ["KiB", "MiB", "GiB"];
+  if (abs < base) return `${bytes.toFixed(0)} B`;
+  if (abs < base * base) return `${fixed(bytes / base, 1)} ${k}`;
+  if (abs < base * base * base)
+    return `${fixed(bytes / (base * base), 1)} ${m}`;
+  return `${fixed(bytes / (base * base * base), 2)} ${g}`;
 }
 
 function formatDuration(totalSeconds: number): string {
   if (totalSeconds < 0) return "-";
   if (totalSeconds === 0) return "0s";
   const ms = totalSeconds * 1000;
-  if (ms < 1) return `${ms.toFixed(1)}ms`;
+  if (ms < 1) return `${fixed(ms, 1)}ms`;
   if (totalSeconds < 1) return `${ms.toFixed(0)}ms`;
-  if (totalSeconds < 60) return `${totalSeconds.toFixed(1)}s`;
+  if (totalSeconds < 60) return `${fixed(totalSeconds, 1)}s`;
   if (totalSeconds < 3600) {
     const m = Math.floor(totalSeconds / 60);
     const s = Math.floor(totalSeconds % 60);
@@ -206,10 +244,10 @@ export function formatDate(epochSeconds: number, tz: TimezoneMode): string {
 function formatRate(value: number): string {
   if (value === 0) return "0";
   const abs = Math.abs(value);
-  if (abs < 1) return value.toFixed(2);
-  if (abs < 10) return value.toFixed(1);
+  if (abs < 1) return fixed(value, 2);
+  if (abs < 10) return fixed(value, 1);
   if (abs < 1000) return value.toFixed(0);
-  if (abs < 1_000_000) return `${(value / 1000).toFixed(1)}K`;
-  if (abs < 1_000_000_000) return `${(value / 1_000_000).toFixed(1)}M`;
-  return `${(value / 1_000_000_000).toFixed(1)}G`;
+  if (abs < 1_000_000) return `${fixed(value / 1000, 1)}K`;
+  if (abs < 1_000_000_000) return `${fixed(value / 1_000_000, 1)}M`;
+  return `${fixed(value / 1_000_000_000, 1)}G`;
 }
@@ -6,6 +6,11 @@
 //   good      — var(--status-success)   (healthy)
 //   inactive  — var(--status-inactive)  (zero / no activity)
 //   default   — ""                      (inherit from row)
+//
+// Columns whose schema carries `thresholds` (the levels the TUI uses) take
+// warning/critical from the server; RULES still supply good/inactive.
+
+import type { ColumnThresholds } from "../api/types";
 
 const LEVEL_CLASS: Record<string, string> = {
   critical: "text-[var(--status-critical)]",
@@ -21,6 +26,17 @@ type Classifier = (
 
 // --- Numeric helpers ---
 
+function schemaLevel(t: ColumnThresholds, v: unknown): string | undefined {
+  if (v == null) return undefined;
+  const n = Number(v);
+  if (isNaN(n)) return undefined;
+  const reached = (level: number) =>
+    t.lower_is_worse ? n < level : n >= level;
+  if (t.crit != null && reached(t.crit)) return "critical";
+  if (reached(t.warn)) return "warning";
+  return undefined;
+}
+
 function pctHigh(
   v: unknown,
   goodBelow: number,
@@ -599,10 +615,14 @@ export function getThresholdLevel(
   key: string,
   value: unknown,
   row?: Record<string, unknown>,
+  thresholds?: ColumnThresholds,
 ): string | undefined {
   const rule = RULES[key];
-  if (!rule) return undefined;
-  return rule(value, row ?? {});
+  const level = rule ? rule(value, row ?? {}) : undefined;
+  if (!thresholds) return level;
+  const fromSchema = schemaLevel(thresholds, value);
+  if (fromSchema) return fromSchema;
+  return level === "good" || level === "inactive" ? level : undefined;
 }
 
 /**
@@ -613,8 +633,9 @@ export function getThresholdClass(
   key: string,
   value: unknown,
   row: Record<string, unknown>,
+  thresholds?: ColumnThresholds,
 ): string {
-  const level = getThresholdLevel(key, value, row);
+  const level = getThresholdLevel(key, value, row, thresholds);
   if (!level) return "";
   return LEVEL_CLASS[level] ?? "";
 }
@@ -8,12 +8,14 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Instant;
 
-use axum::body::Body;
 use axum::extract::{Request, State};
-use axum::http::{StatusCode, header};
+use axum::http::{HeaderValue, StatusCode, header};
 use axum::middleware::Next;
+use axum::response::IntoResponse;
 use tracing::{debug, info, warn};
 
+use crate::problem::{ApiError, ErrorCode};
+
 // ============================================================
 // SSO configuration
 // ============================================================
@@ -30,6 +32,29 @@ pub(crate) struct SsoConfig {
     pub(crate) allowed_users: AllowedUsers,
 }
 
+/// URL prefix of the recorded history served next to live data (`--store`).
+pub(crate) const HISTORY_PREFIX: &str = "/history";
+
+/// URL prefix of the Grafana simple JSON datasource endpoints.
+pub(crate) const GRAFANA_PREFIX: &str = "/grafana";
+
+/// Whether `path` is served by the API (including the Grafana datasource)
+/// rather than the frontend.
+pub(crate) fn is_api_path(path: &str) -> bool {
+    path.starts_with("/api/")
+        || path
+            .strip_prefix(GRAFANA_PREFIX)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Request path with the [`HISTORY_PREFIX`] of a history API call removed,
+/// so that both API trees get the same auth and limits.
+pub(crate) fn api_path(path: &str) -> &str {
+    path.strip_prefix(HISTORY_PREFIX)
+        .filter(|p| is_api_path(p))
+        .unwrap_or(path)
+}
+
 // ============================================================
 // SSO middleware (JWT validation)
 // ============================================================
@@ -71,20 +96,22 @@ fn extract_token(req: &Request) -> Option<String> {
 }
 
 fn unauthorized_json() -> axum::response::Response {
-    axum::response::Response::builder()
-        .status(StatusCode::UNAUTHORIZED)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(Body::from(r#"{"error":"unauthorized"}"#))
-        .unwrap()
+    ApiError::new(
+        StatusCode::UNAUTHORIZED,
+        ErrorCode::Unauthorized,
+        "missing, invalid or expired token",
+    )
+    .into_response()
 }
 
 fn forbidden_json(username: &str) -> axum::response::Response {
-    let body = serde_json::json!({"error": "forbidden", "username": username}).to_string();
-    axum::response::Response::builder()
-        .status(StatusCode::FORBIDDEN)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(Body::from(body))
-        .unwrap()
+    ApiError::new(
+        StatusCode::FORBIDDEN,
+        ErrorCode::Forbidden,
+        format!("user {username:?} is not allowed"),
+    )
+    .with("username", username)
+    .into_response()
 }
 
 #[derive(Clone)]
@@ -123,21 +150,21 @@ where
 
     fn call(&mut self, mut req: Request) -> Self::Future {
         // Skip auth for public endpoints
-        let path = req.uri().path();
+        let path = api_path(req.uri().path());
         if path == "/api/v1/auth/config" || path == "/api/v1/health" {
             let mut inner = self.inner.clone();
             return Box::pin(async move { inner.call(req).await });
         }
         // Skip for non-API paths: static assets, index.html, favicon, etc.
         // Frontend handles auth in JS (fetches /api/v1/auth/config, then redirects to SSO).
-        if !path.starts_with("/api/") {
+        if !is_api_path(path) {
             let mut inner = self.inner.clone();
             return Box::pin(async move { inner.call(req).await });
         }
 
         let config = self.config.clone();
         let mut inner = self.inner.clone();
-        let req_path = path.to_owned();
+        let req_path = req.uri().path().to_owned();
 
         Box::pin(async move {
             let token = match extract_token(&req) {
@@ -255,11 +282,16 @@ pub(crate) async fn basic_auth_middleware(
     let path = req.uri().path().to_owned();
 
     let unauthorized = || {
-        axum::response::Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .header(header::WWW_AUTHENTICATE, "Basic realm=\"rpglot\"")
-            .body(Body::from("Unauthorized"))
-            .unwrap()
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized,
+            "missing or invalid credentials",
+        )
+        .with_header(
+            header::WWW_AUTHENTICATE,
+            HeaderValue::from_static("Basic realm=\"rpglot\""),
+        )
+        .into_response()
     };
 
     let auth_header = match req.headers().get(header::AUTHORIZATION) {
@@ -1,23 +1,29 @@
 //! Background processing: tick loop, history refresh/navigation, rate seeding, snapshot conversion.
 
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 
+use rpglot_core::api::buffercache::buffer_cache_report;
 use rpglot_core::api::convert::{ConvertContext, convert, resolve};
 use rpglot_core::api::snapshot::{ApiSnapshot, PgStatementsRow, PgStorePlansRow};
-use rpglot_core::provider::HistoryProvider;
-use rpglot_core::rates;
-use rpglot_core::storage::StringInterner;
+use rpglot_core::collector::RedactMode;
+use rpglot_core::fmt::{FmtStyle, format_bytes};
+use rpglot_core::provider::{HistoryProvider, HybridProvider, SnapshotProvider};
+use rpglot_core::rates::{self, PgiRateState, PgpRateState, PgsRateState, PgtRateState};
+use rpglot_core::storage::chunk::{ChunkReader, write_chunk};
 use rpglot_core::storage::model::{DataBlock, PgStatStatementsInfo, PgStorePlansInfo, Snapshot};
+use rpglot_core::storage::{RotationConfig, StringInterner};
 
+use crate::metrics::METRICS;
 use crate::state::{
-    LAST_CLIENT_ACTIVITY, Mode, SharedState, WebAppInner, now_epoch, release_memory_to_os,
+    LAST_CLIENT_ACTIVITY, Mode, SHUTDOWN, SharedState, WebAppInner, allocator_stats, now_epoch,
+    release_memory_to_os,
 };
 
 // ============================================================
@@ -28,13 +34,33 @@ pub(crate) async fn tick_loop(
     state: SharedState,
     tx: broadcast::Sender<Arc<ApiSnapshot>>,
     interval: Duration,
+    rotation: Option<RotationConfig>,
 ) {
     let mut tick = tokio::time::interval(interval);
     tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
     let mut snapshot_count: u64 = 0;
+    let mut shutdown = SHUTDOWN.subscribe();
+    let mut last_rotation_hour = None;
+    let mut last_refresh_minute = None;
 
     loop {
-        tick.tick().await;
+        // Shutdown is only checked between ticks, so a started tick completes
+        tokio::select! {
+            biased;
+            _ = shutdown.wait_for(|&stop| stop) => break,
+            _ = tick.tick() => {}
+        }
+
+        // Recorded data (--store) is rotated hourly, as rpglotd does
+        let rotate = rotation.clone().filter(|_| {
+            let hour = now_epoch() / 3600;
+            last_rotation_hour.replace(hour) != Some(hour)
+        });
+        // ...and indexed for browsing back once a minute
+        let refresh = rotation.is_some() && {
+            let minute = now_epoch() / 60;
+            last_refresh_minute.replace(minute) != Some(minute)
+        };
 
         // Run blocking provider.advance() off the async runtime
         let state_clone = state.clone();
@@ -42,6 +68,12 @@ pub(crate) async fn tick_loop(
         let result = tokio::task::spawn_blocking(move || {
             let mut inner = state_clone.lock().unwrap();
             advance_and_convert(&mut inner);
+            if let Some(config) = rotate {
+                rotate_store(&mut inner, &config);
+            }
+            if refresh {
+                refresh_recorded(&mut inner);
+            }
             inner.current_snapshot.clone()
         })
         .await;
@@ -91,6 +123,124 @@ pub(crate) async fn tick_loop(
             let _ = tx.send(snap);
         }
     }
+    info!(snapshot_count, "tick loop stopped");
+}
+
+/// Indexes snapshots recorded with `--store` since the last refresh, so
+/// that live-mode navigation reaches past the in-memory buffer.
+fn refresh_recorded(inner: &mut WebAppInner) {
+    let Some(hybrid) = inner
+        .provider
+        .as_any_mut()
+        .and_then(|a| a.downcast_mut::<HybridProvider>())
+    else {
+        return;
+    };
+    match hybrid.refresh_history() {
+        Ok(new) if new > 0 => debug!(new, "recorded history refreshed"),
+        Ok(_) => {}
+        Err(e) => debug!(error = %e, "recorded history not refreshed"),
+    }
+}
+
+/// Applies the retention policy to data recorded with `--store`.
+fn rotate_store(inner: &mut WebAppInner, config: &RotationConfig) {
+    let Some(storage) = inner.provider.storage_mut() else {
+        return;
+    };
+    match storage.rotate(config) {
+        Ok(result)
+            if result.files_removed_by_age > 0
+                || result.files_removed_by_size > 0
+                || result.files_stripped > 0 =>
+        {
+            info!(
+                removed_by_age = result.files_removed_by_age,
+                removed_by_size = result.files_removed_by_size,
+                stripped = result.files_stripped,
+                freed = %format_bytes(result.bytes_freed, FmtStyle::Compact),
+                files = result.files_remaining,
+                "rotation"
+            );
+        }
+        Ok(_) => {}
+        Err(e) => error!(error = %e, "rotation failed"),
+    }
+}
+
+/// Writes snapshots recorded with `--store` still in the WAL to a chunk, so
+/// that the next start has nothing to recover.
+pub(crate) fn flush_store(state: &SharedState) {
+    let mut inner = state.lock().unwrap();
+    let Some(storage) = inner.provider.storage_mut() else {
+        return;
+    };
+    let pending = storage.current_chunk_size();
+    if pending == 0 {
+        return;
+    }
+    match storage.flush_chunk() {
+        Ok(()) => info!(snapshots = pending, "recorded snapshots flushed"),
+        Err(e) => error!(error = %e, "failed to flush recorded snapshots"),
+    }
+}
+
+// ============================================================
+// Live session spool
+// ============================================================
+
+/// Spool file with the last live snapshot, inside `--spool-dir`.
+const SPOOL_FILE: &str = "live-spool.zst";
+
+/// Older spools are ignored: rates over such a gap say little about now.
+const SPOOL_MAX_AGE_SECS: i64 = 600;
+
+/// Writes the last collected snapshot to the spool directory so that the
+/// next start computes rates from its first tick.
+pub(crate) fn write_spool(state: &SharedState, dir: &Path) {
+    let inner = state.lock().unwrap();
+    let (Some(snapshot), Some(interner)) = (&inner.raw_snapshot, inner.provider.interner()) else {
+        return;
+    };
+    let path = dir.join(SPOOL_FILE);
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|_| write_chunk(&path, std::slice::from_ref(snapshot), interner));
+    match result {
+        Ok(()) => {
+            info!(path = %path.display(), timestamp = snapshot.timestamp, "live session spooled")
+        }
+        Err(e) => error!(path = %path.display(), error = %e, "failed to write spool"),
+    }
+}
+
+/// Seeds rate state from a spool left by the previous run, then removes it.
+pub(crate) fn restore_spool(inner: &mut WebAppInner, dir: &Path) {
+    let path = dir.join(SPOOL_FILE);
+    if !path.exists() {
+        return;
+    }
+    let snapshot = ChunkReader::open(&path).and_then(|reader| reader.read_snapshot(0));
+    if let Err(e) = std::fs::remove_file(&path) {
+        warn!(path = %path.display(), error = %e, "failed to remove spool");
+    }
+    let snapshot = match snapshot {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "failed to read spool, ignoring");
+            return;
+        }
+    };
+    let age = now_epoch() - snapshot.timestamp;
+    if !(0..=SPOOL_MAX_AGE_SECS).contains(&age) {
+        info!(age_secs = age, "spool too old, ignoring");
+        return;
+    }
+    seed_pgs_prev(&mut inner.pgs_rate, &snapshot);
+    seed_pgp_prev(&mut inner.pgp_rate, &snapshot);
+    seed_pgt_prev(&mut inner.pgt_rate, &snapshot);
+    seed_pgi_prev(&mut inner.pgi_rate, &snapshot);
+    info!(age_secs = age, "rate baseline restored from spool");
+    inner.raw_snapshot = Some(snapshot);
 }
 
 // ============================================================
@@ -136,41 +286,20 @@ pub(crate) async fn history_refresh_loop(state: SharedState, path: PathBuf) {
                 if has_data || hp_initialized {
                     evict_caches(&mut inner);
                     release_memory_to_os();
-                    info!("idle eviction: all caches cleared");
+                    let memory = allocator_stats();
+                    METRICS.record_eviction(now, memory);
+                    info!(
+                        resident_bytes = memory.map(|m| m.resident_bytes),
+                        "idle eviction: all caches cleared"
+                    );
                 }
                 return Ok::<(usize, usize), rpglot_core::provider::ProviderError>((0, 0));
             }
 
             // Active client — refresh from disk (only if initialized)
-            let hp = inner
-                .provider
-                .as_any_mut()
-                .and_then(|a| a.downcast_mut::<HistoryProvider>());
-            let Some(hp) = hp else {
-                return Ok((0usize, 0usize));
-            };
-            if !hp.is_initialized() {
-                return Ok((0, 0));
-            }
-            let added = hp.refresh(&path_clone)?;
-            let total = hp.len();
-            if added > 0 {
-                let (start, end) = hp.timestamp_range();
-                inner.total_snapshots = Some(total);
-                inner.history_start = Some(start);
-                inner.history_end = Some(end);
-                // Invalidate today's heatmap cache (new data may have been added)
-                let today_days = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs() as i64
-                    / 86400;
-                let d = chrono_free_date(today_days);
-                let today_key = format!("{:04}-{:02}-{:02}", d.0, d.1, d.2);
-                inner.heatmap_cache.remove(&today_key);
-            }
+            let refreshed = refresh_history(&mut inner, &path_clone);
             release_memory_to_os(); // free any chunk decompression buffers
-            Ok::<(usize, usize), rpglot_core::provider::ProviderError>((added, total))
+            refreshed
         })
         .await;
 
@@ -196,6 +325,35 @@ pub(crate) async fn history_refresh_loop(state: SharedState, path: PathBuf) {
     }
 }
 
+/// Pick up new snapshots from disk (no-op until the provider is initialized).
+/// Returns (added, total).
+pub(crate) fn refresh_history(
+    inner: &mut WebAppInner,
+    path: &Path,
+) -> Result<(usize, usize), rpglot_core::provider::ProviderError> {
+    let hp = inner
+        .provider
+        .as_any_mut()
+        .and_then(|a| a.downcast_mut::<HistoryProvider>());
+    let Some(hp) = hp else {
+        return Ok((0, 0));
+    };
+    if !hp.is_initialized() {
+        return Ok((0, 0));
+    }
+    let added = hp.refresh(path)?;
+    let total = hp.len();
+    // The daemon may have been restarted with another --redact-sql
+    inner.redaction = RedactMode::read_marker(path);
+    if added > 0 {
+        let (start, end) = hp.timestamp_range();
+        inner.total_snapshots = Some(total);
+        inner.history_start = Some(start);
+        inner.history_end = Some(end);
+    }
+    Ok((added, total))
+}
+
 /// Evict all cached data from WebAppInner to free memory during idle periods.
 /// Keeps only lightweight metadata (total_snapshots, history_start/end, instance_info).
 fn evict_caches(inner: &mut WebAppInner) {
@@ -218,6 +376,15 @@ fn evict_caches(inner: &mut WebAppInner) {
         .as_any_mut()
         .and_then(|a| a.downcast_mut::<HistoryProvider>())
     {
+        let cache = hp.snapshot_cache_stats();
+        info!(
+            hits = cache.hits,
+            misses = cache.misses,
+            hit_ratio = cache.hit_ratio(),
+            entries = cache.entries,
+            used_bytes = cache.used_bytes,
+            "idle eviction: snapshot cache stats"
+        );
         hp.evict_all();
     }
 }
@@ -236,10 +403,13 @@ pub(crate) fn ensure_history_ready(inner: &mut WebAppInner) -> bool {
         return true;
     }
 
+    let t0 = Instant::now();
     if let Err(e) = hp.ensure_initialized() {
         warn!(error = %e, "failed to initialize history provider");
         return false;
     }
+    let elapsed = t0.elapsed();
+    METRICS.record_provider_init(elapsed);
 
     let total = hp.len();
     let (start, end) = hp.timestamp_range();
@@ -249,6 +419,7 @@ pub(crate) fn ensure_history_ready(inner: &mut WebAppInner) -> bool {
 
     info!(
         snapshots = total,
+        duration_ms = elapsed.as_millis() as u64,
         "history provider initialized on first request"
     );
     true
@@ -385,6 +556,29 @@ fn advance_and_convert(inner: &mut WebAppInner) {
         }
     }
 
+    let k8s_pod = snapshot.blocks.iter().find_map(|b| match b {
+        DataBlock::K8sPod(pod) => Some(pod),
+        _ => None,
+    });
+    if k8s_pod != inner.k8s_pod.as_ref() {
+        inner.k8s_pod = k8s_pod.cloned();
+    }
+
+    if let Some(extensions) = snapshot.blocks.iter().find_map(|b| match b {
+        DataBlock::PgExtensions(v) => Some(v),
+        _ => None,
+    }) && *extensions != inner.extensions
+    {
+        inner.extensions = extensions.clone();
+    }
+
+    if let Some(info) = snapshot.blocks.iter().find_map(|b| match b {
+        DataBlock::PgBufferCache(info) => Some(info),
+        _ => None,
+    }) {
+        inner.buffer_cache = Some(buffer_cache_report(snapshot.timestamp, info));
+    }
+
     // Update rates (must happen before borrowing interner)
     rates::update_pgs_rates(&mut inner.pgs_rate, &snapshot);
     rates::update_pgp_rates(&mut inner.pgp_rate, &snapshot);
@@ -401,7 +595,13 @@ fn advance_and_convert(inner: &mut WebAppInner) {
         hp.map(|hp| (hp.prev_timestamp(), hp.next_timestamp()))
             .unwrap_or((None, None))
     } else {
-        (None, None)
+        // Live: the buffer (or recorded history) can be browsed back from here
+        let prev_ts = inner
+            .provider
+            .as_any()
+            .and_then(|a| a.downcast_ref::<HybridProvider>())
+            .and_then(|hybrid| hybrid.neighbours(snapshot.timestamp).0);
+        (prev_ts, None)
     };
 
     let ctx = ConvertContext {
@@ -416,6 +616,7 @@ fn advance_and_convert(inner: &mut WebAppInner) {
     let mut api_snapshot = convert(&ctx);
     api_snapshot.prev_timestamp = prev_ts;
     api_snapshot.next_timestamp = next_ts;
+    api_snapshot.instance = inner.provider.instance().map(str::to_string);
 
     // Merge stale PGS entries from prev_sample
     if let Some(interner) = inner.provider.interner() {
@@ -482,6 +683,59 @@ pub(crate) fn history_jump_to_timestamp(
     }
 }
 
+/// Converts the live-mode snapshot nearest to `timestamp` (in-memory buffer
+/// or data recorded with `--store`) without touching the live rate state, so
+/// the stream carries on unaffected. Rates come from the adjacent snapshot.
+pub(crate) fn live_snapshot_at(
+    inner: &mut WebAppInner,
+    timestamp: i64,
+    ceil: bool,
+) -> Option<Arc<ApiSnapshot>> {
+    let current_ts = inner.raw_snapshot.as_ref().map(|s| s.timestamp);
+    let hybrid = inner
+        .provider
+        .as_any_mut()
+        .and_then(|a| a.downcast_mut::<HybridProvider>())?;
+    let ts = hybrid.resolve_timestamp(timestamp, ceil)?;
+    if Some(ts) == current_ts {
+        return inner.current_snapshot.clone();
+    }
+    let (prev_ts, next_ts) = hybrid.neighbours(ts);
+    let prev = prev_ts.and_then(|t| hybrid.snapshot_at_timestamp(t));
+    // Loaded last, so a history interner belongs to this snapshot
+    let snapshot = hybrid.snapshot_at_timestamp(ts)?;
+
+    let mut pgs_rate = PgsRateState::default();
+    let mut pgp_rate = PgpRateState::default();
+    let mut pgt_rate = PgtRateState::default();
+    let mut pgi_rate = PgiRateState::default();
+    if let Some(ref prev) = prev {
+        seed_pgs_prev(&mut pgs_rate, prev);
+        seed_pgp_prev(&mut pgp_rate, prev);
+        seed_pgt_prev(&mut pgt_rate, prev);
+        seed_pgi_prev(&mut pgi_rate, prev);
+        rates::update_pgs_rates(&mut pgs_rate, &snapshot);
+        rates::update_pgp_rates(&mut pgp_rate, &snapshot);
+        rates::update_pgt_rates(&mut pgt_rate, &snapshot);
+        rates::update_pgi_rates(&mut pgi_rate, &snapshot);
+    }
+
+    let ctx = ConvertContext {
+        snapshot: &snapshot,
+        prev_snapshot: prev.as_ref(),
+        interner: hybrid.interner_for(ts),
+        pgs_rates: &pgs_rate.rates,
+        pgp_rates: &pgp_rate.rates,
+        pgt_rates: &pgt_rate.rates,
+        pgi_rates: &pgi_rate.rates,
+    };
+    let mut api_snapshot = convert(&ctx);
+    api_snapshot.prev_timestamp = prev_ts;
+    api_snapshot.next_timestamp = next_ts;
+    api_snapshot.instance = hybrid.instance().map(str::to_string);
+    Some(Arc::new(api_snapshot))
+}
+
 /// Extract collected_at timestamp from PgStatStatements block.
 fn extract_pgs_collected_at(snapshot: &Snapshot) -> Option<i64> {
     snapshot.blocks.iter().find_map(|b| {
@@ -523,6 +777,21 @@ fn extract_pgi_collected_at(snapshot: &Snapshot) -> Option<i64> {
     })
 }
 
+/// collected_at of the `kind` block at `pos`. Decodes only that block, so
+/// the lookbacks below don't deserialize whole snapshots.
+fn block_collected_at(
+    hp: &mut HistoryProvider,
+    pos: usize,
+    kind: &str,
+    extract: fn(&Snapshot) -> Option<i64>,
+) -> Option<i64> {
+    let block = hp.snapshot_block_at(pos, kind)?;
+    extract(&Snapshot {
+        timestamp: 0,
+        blocks: vec![block],
+    })
+}
+
 /// Find the nearest previous snapshot with a DIFFERENT PGS collected_at.
 /// Daemon caches pg_stat_statements for ~30s, so adjacent snapshots often
 /// have the same collected_at. We look further back to find a snapshot
@@ -535,11 +804,10 @@ fn find_pgs_prev_snapshot(
     let max_lookback = 30; // PGS cached ~30s, need ~300s / 5min lookback
     let start = pos.saturating_sub(max_lookback);
     for p in (start..pos).rev() {
-        if let Some(snap) = hp.snapshot_at(p)
-            && let Some(ts) = extract_pgs_collected_at(&snap)
-            && ts != current_collected_at
+        if block_collected_at(hp, p, "pg_stat_statements", extract_pgs_collected_at)
+            .is_some_and(|ts| ts != current_collected_at)
         {
-            return Some(snap);
+            return hp.snapshot_at(p);
         }
     }
     None
@@ -555,11 +823,10 @@ fn find_pgp_prev_snapshot(
     let max_lookback = 40;
     let start = pos.saturating_sub(max_lookback);
     for p in (start..pos).rev() {
-        if let Some(snap) = hp.snapshot_at(p)
-            && let Some(ts) = extract_pgp_collected_at(&snap)
-            && ts != current_collected_at
+        if block_collected_at(hp, p, "pg_store_plans", extract_pgp_collected_at)
+            .is_some_and(|ts| ts != current_collected_at)
         {
-            return Some(snap);
+            return hp.snapshot_at(p);
         }
     }
     None
@@ -573,11 +840,10 @@ fn find_pgt_prev_snapshot(
     let max_lookback = 30;
     let start = pos.saturating_sub(max_lookback);
     for p in (start..pos).rev() {
-        if let Some(snap) = hp.snapshot_at(p)
-            && let Some(ts) = extract_pgt_collected_at(&snap)
-            && ts != current_collected_at
+        if block_collected_at(hp, p, "pg_stat_user_tables", extract_pgt_collected_at)
+            .is_some_and(|ts| ts != current_collected_at)
         {
-            return Some(snap);
+            return hp.snapshot_at(p);
         }
     }
     None
@@ -591,11 +857,10 @@ fn find_pgi_prev_snapshot(
     let max_lookback = 30;
     let start = pos.saturating_sub(max_lookback);
     for p in (start..pos).rev() {
-        if let Some(snap) = hp.snapshot_at(p)
-            && let Some(ts) = extract_pgi_collected_at(&snap)
-            && ts != current_collected_at
+        if block_collected_at(hp, p, "pg_stat_user_indexes", extract_pgi_collected_at)
+            .is_some_and(|ts| ts != current_collected_at)
         {
-            return Some(snap);
+            return hp.snapshot_at(p);
         }
     }
     None
@@ -662,7 +927,7 @@ pub(crate) fn reconvert_current(inner: &mut WebAppInner) {
             hp_mut!(inner).and_then(|hp| find_pgt_prev_snapshot(hp, position, curr_ts))
         });
         let pgt_prev = pgt_seed.as_ref().unwrap_or(prev);
-        seed_pgt_prev(inner, pgt_prev);
+        seed_pgt_prev(&mut inner.pgt_rate, pgt_prev);
         rates::update_pgt_rates(&mut inner.pgt_rate, &snapshot);
 
         // PGI
@@ -670,7 +935,7 @@ pub(crate) fn reconvert_current(inner: &mut WebAppInner) {
             hp_mut!(inner).and_then(|hp| find_pgi_prev_snapshot(hp, position, curr_ts))
         });
         let pgi_prev = pgi_seed.as_ref().unwrap_or(prev);
-        seed_pgi_prev(inner, pgi_prev);
+        seed_pgi_prev(&mut inner.pgi_rate, pgi_prev);
         rates::update_pgi_rates(&mut inner.pgi_rate, &snapshot);
 
         // PGS
@@ -678,7 +943,7 @@ pub(crate) fn reconvert_current(inner: &mut WebAppInner) {
             hp_mut!(inner).and_then(|hp| find_pgs_prev_snapshot(hp, position, curr_ts))
         });
         let pgs_prev = pgs_seed.as_ref().unwrap_or(prev);
-        seed_pgs_prev(inner, pgs_prev);
+        seed_pgs_prev(&mut inner.pgs_rate, pgs_prev);
         rates::update_pgs_rates(&mut inner.pgs_rate, &snapshot);
 
         // PGP
@@ -686,7 +951,7 @@ pub(crate) fn reconvert_current(inner: &mut WebAppInner) {
             hp_mut!(inner).and_then(|hp| find_pgp_prev_snapshot(hp, position, curr_ts))
         });
         let pgp_prev = pgp_seed.as_ref().unwrap_or(prev);
-        seed_pgp_prev(inner, pgp_prev);
+        seed_pgp_prev(&mut inner.pgp_rate, pgp_prev);
         rates::update_pgp_rates(&mut inner.pgp_rate, &snapshot);
     }
 
@@ -703,6 +968,7 @@ pub(crate) fn reconvert_current(inner: &mut WebAppInner) {
     let mut api_snapshot = convert(&ctx);
     api_snapshot.prev_timestamp = prev_ts;
     api_snapshot.next_timestamp = next_ts;
+    api_snapshot.instance = inner.provider.instance().map(str::to_string);
 
     inner.prev_snapshot = prev_adjacent;
     inner.raw_snapshot = Some(snapshot);
@@ -714,7 +980,7 @@ pub(crate) fn reconvert_current(inner: &mut WebAppInner) {
 // ============================================================
 
 /// Seed PGS prev_sample state from a snapshot (for rate computation after jump).
-fn seed_pgs_prev(inner: &mut WebAppInner, prev: &Snapshot) {
+fn seed_pgs_prev(state: &mut PgsRateState, prev: &Snapshot) {
     if let Some(stmts) = prev.blocks.iter().find_map(|b| {
         if let DataBlock::PgStatStatements(v) = b {
             Some(v)
@@ -727,13 +993,13 @@ fn seed_pgs_prev(inner: &mut WebAppInner, prev: &Snapshot) {
             .map(|s| s.collected_at)
             .filter(|&t| t > 0)
             .unwrap_or(prev.timestamp);
-        inner.pgs_rate.prev_ts = Some(ts);
-        inner.pgs_rate.prev_sample = stmts.iter().map(|s| (s.queryid, s.clone())).collect();
+        state.prev_ts = Some(ts);
+        state.prev_sample = stmts.iter().map(|s| (s.queryid, s.clone())).collect();
     }
 }
 
 /// Seed PGT prev_sample state from a snapshot (for rate computation after jump).
-fn seed_pgt_prev(inner: &mut WebAppInner, prev: &Snapshot) {
+fn seed_pgt_prev(state: &mut PgtRateState, prev: &Snapshot) {
     if let Some(tables) = prev.blocks.iter().find_map(|b| {
         if let DataBlock::PgStatUserTables(v) = b {
             Some(v)
@@ -746,13 +1012,13 @@ fn seed_pgt_prev(inner: &mut WebAppInner, prev: &Snapshot) {
             .map(|t| t.collected_at)
             .filter(|&t| t > 0)
             .unwrap_or(prev.timestamp);
-        inner.pgt_rate.prev_ts = Some(ts);
-        inner.pgt_rate.prev_sample = tables.iter().map(|t| (t.relid, t.clone())).collect();
+        state.prev_ts = Some(ts);
+        state.prev_sample = tables.iter().map(|t| (t.relid, t.clone())).collect();
     }
 }
 
 /// Seed PGI prev_sample state from a snapshot (for rate computation after jump).
-fn seed_pgi_prev(inner: &mut WebAppInner, prev: &Snapshot) {
+fn seed_pgi_prev(state: &mut PgiRateState, prev: &Snapshot) {
     if let Some(indexes) = prev.blocks.iter().find_map(|b| {
         if let DataBlock::PgStatUserIndexes(v) = b {
             Some(v)
@@ -765,13 +1031,13 @@ fn seed_pgi_prev(inner: &mut WebAppInner, prev: &Snapshot) {
             .map(|i| i.collected_at)
             .filter(|&t| t > 0)
             .unwrap_or(prev.timestamp);
-        inner.pgi_rate.prev_ts = Some(ts);
-        inner.pgi_rate.prev_sample = indexes.iter().map(|i| (i.indexrelid, i.clone())).collect();
+        state.prev_ts = Some(ts);
+        state.prev_sample = indexes.iter().map(|i| (i.indexrelid, i.clone())).collect();
     }
 }
 
 /// Seed PGP prev_sample state from a snapshot (for rate computation after jump).
-fn seed_pgp_prev(inner: &mut WebAppInner, prev: &Snapshot) {
+fn seed_pgp_prev(state: &mut PgpRateState, prev: &Snapshot) {
     if let Some(plans) = prev.blocks.iter().find_map(|b| {
         if let DataBlock::PgStorePlans(v) = b {
             Some(v)
@@ -784,27 +1050,7 @@ fn seed_pgp_prev(inner: &mut WebAppInner, prev: &Snapshot) {
             .map(|p| p.collected_at)
             .filter(|&t| t > 0)
             .unwrap_or(prev.timestamp);
-        inner.pgp_rate.prev_ts = Some(ts);
-        inner.pgp_rate.prev_sample = plans.iter().map(|p| (p.planid, p.clone())).collect();
+        state.prev_ts = Some(ts);
+        state.prev_sample = plans.iter().map(|p| (p.planid, p.clone())).collect();
     }
 }
-
-// ============================================================
-// Date utility
-// ============================================================
-
-/// Convert days-since-epoch to (year, month, day) without chrono crate.
-pub(crate) fn chrono_free_date(days_since_epoch: i64) -> (i32, u32, u32) {
-    // Algorithm from https://howardhinnant.github.io/date_algorithms.html
-    let z = days_since_epoch + 719468;
-    let era = if z >= 0 { z } else { z - 146096 } / 146097;
-    let doe = (z - era * 146097) as u32; // day of era [0, 146096]
-    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // year of era [0, 399]
-    let y = yoe as i64 + era * 400;
-    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // day of year [0, 365]
-    let mp = (5 * doy + 2) / 153; // [0, 11]
-    let d = doy - (153 * mp + 2) / 5 + 1; // day [1, 31]
-    let m = if mp < 10 { mp + 3 } else { mp - 9 }; // month [1, 12]
-    let y = if m <= 2 { y + 1 } else { y };
-    (y as i32, m, d)
-}
@@ -0,0 +1,143 @@
+//! Response encoding negotiated from the `Accept` header.
+//!
+//! JSON is the default; clients that send `Accept: application/msgpack`
+//! (or `application/x-msgpack`) get the same document as MessagePack with
+//! named fields, which is smaller and faster to parse for frequent pollers.
+
+use std::io::{self, Write};
+
+use axum::http::{HeaderMap, HeaderValue, header};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Encoding {
+    Json,
+    MsgPack,
+}
+
+impl Encoding {
+    /// Picks MessagePack if the client accepts it, JSON otherwise.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let accepts_msgpack = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|item| {
+                let mut parts = item.split(';').map(str::trim);
+                let mime = parts.next()?;
+                // `q=0` explicitly refuses the type
+                let refused = parts.any(|p| p == "q=0" || p == "q=0.0");
+                Some((mime, refused))
+            })
+            .any(|(mime, refused)| {
+                !refused
+                    && (mime.eq_ignore_ascii_case("application/msgpack")
+                        || mime.eq_ignore_ascii_case("application/x-msgpack"))
+            });
+        if accepts_msgpack {
+            Self::MsgPack
+        } else {
+            Self::Json
+        }
+    }
+
+    pub(crate) fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::MsgPack => "application/msgpack",
+        }
+    }
+
+    /// Serializes `value` into `writer` without buffering the whole document.
+    pub(crate) fn write<W: Write, T: Serialize + ?Sized>(
+        self,
+        writer: &mut W,
+        value: &T,
+    ) -> io::Result<()> {
+        match self {
+            Self::Json => serde_json::to_writer(writer, value).map_err(io::Error::from),
+            Self::MsgPack => {
+                rmp_serde::encode::write_named(writer, value).map_err(io::Error::other)
+            }
+        }
+    }
+
+    pub(crate) fn to_vec<T: Serialize + ?Sized>(self, value: &T) -> io::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.write(&mut buf, value)?;
+        Ok(buf)
+    }
+}
+
+/// Response body encoded as negotiated; the drop-in counterpart of `Json`.
+pub(crate) struct Encoded<T>(pub Encoding, pub T);
+
+impl<T: Serialize> IntoResponse for Encoded<T> {
+    fn into_response(self) -> Response {
+        let Self(encoding, value) = self;
+        match encoding.to_vec(&value) {
+            Ok(body) => {
+                let mut response = body.into_response();
+                let headers = response.headers_mut();
+                headers.insert(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static(encoding.content_type()),
+                );
+                headers.insert(header::VARY, HeaderValue::from_static("accept"));
+                response
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "response serialization failed");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn negotiates_from_accept_header() {
+        assert_eq!(Encoding::from_headers(&HeaderMap::new()), Encoding::Json);
+        assert_eq!(Encoding::from_headers(&accept("*/*")), Encoding::Json);
+        assert_eq!(
+            Encoding::from_headers(&accept("application/msgpack")),
+            Encoding::MsgPack
+        );
+        assert_eq!(
+            Encoding::from_headers(&accept("application/json;q=0.5, application/x-msgpack")),
+            Encoding::MsgPack
+        );
+        assert_eq!(
+            Encoding::from_headers(&accept("application/msgpack;q=0, application/json")),
+            Encoding::Json
+        );
+    }
+
+    #[test]
+    fn msgpack_keeps_field_names() {
+        #[derive(Serialize)]
+        struct Row {
+            pid: u32,
+            name: Option<String>,
+        }
+        let rows = vec![Row {
+            pid: 42,
+            name: None,
+        }];
+        let bytes = Encoding::MsgPack.to_vec(&rows).unwrap();
+        let decoded: serde_json::Value = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded, serde_json::json!([{"pid": 42, "name": null}]));
+        assert!(bytes.len() < Encoding::Json.to_vec(&rows).unwrap().len());
+    }
+}
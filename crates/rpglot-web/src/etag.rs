@@ -0,0 +1,122 @@
+//! Entity tags for conditional GET requests.
+//!
+//! The tag is a hash of whatever determines the response (snapshot timestamp
+//! and position, timeline bounds, request parameters), so a polling client
+//! that sends `If-None-Match` gets a bodyless 304 without the snapshot being
+//! serialized again.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ETag(String);
+
+impl ETag {
+    /// Tag of a response determined by `key`. The crate version is mixed in
+    /// so that tags from an older server never match after an upgrade.
+    pub(crate) fn new(kind: &str, key: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        key.hash(&mut hasher);
+        Self(format!("\"{kind}-{:016x}\"", hasher.finish()))
+    }
+
+    /// Whether the client's `If-None-Match` already names this tag
+    /// (weak comparison, as RFC 9110 requires for this header).
+    pub(crate) fn matches(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get_all(header::IF_NONE_MATCH)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == self.0)
+    }
+
+    /// Adds the tag to a response. `no-cache` makes browsers revalidate on
+    /// every request instead of reusing a stale body.
+    pub(crate) fn apply(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.0) {
+            headers.insert(header::ETAG, value);
+        }
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    }
+
+    /// Bodyless 304 for a client that already holds the current response.
+    pub(crate) fn not_modified(&self) -> Response {
+        let mut response = StatusCode::NOT_MODIFIED.into_response();
+        self.apply(response.headers_mut());
+        response
+    }
+}
+
+/// Response with an `ETag` header, or a 304 when the client's copy is current.
+pub(crate) struct Tagged<T> {
+    etag: ETag,
+    /// `None` when the client sent a matching `If-None-Match`.
+    body: Option<T>,
+}
+
+impl<T> Tagged<T> {
+    /// Checks `If-None-Match` and builds the body only if it is needed.
+    pub(crate) fn new(etag: ETag, headers: &HeaderMap, body: impl FnOnce() -> T) -> Self {
+        let body = (!etag.matches(headers)).then(body);
+        Self { etag, body }
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for Tagged<T> {
+    fn into_response(self) -> Response {
+        match self.body {
+            Some(body) => {
+                let mut response = body.into_response();
+                if response.status().is_success() {
+                    self.etag.apply(response.headers_mut());
+                }
+                response
+            }
+            None => self.etag.not_modified(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn if_none_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn matches_if_none_match() {
+        let etag = ETag::new("snap", (1_770_508_800_i64, 42_usize));
+        assert_eq!(etag, ETag::new("snap", (1_770_508_800_i64, 42_usize)));
+        assert_ne!(etag, ETag::new("snap", (1_770_508_800_i64, 43_usize)));
+
+        assert!(!etag.matches(&HeaderMap::new()));
+        assert!(etag.matches(&if_none_match(&etag.0)));
+        assert!(etag.matches(&if_none_match(&format!("\"other\", W/{}", etag.0))));
+        assert!(etag.matches(&if_none_match("*")));
+        assert!(!etag.matches(&if_none_match("\"other\"")));
+    }
+
+    #[test]
+    fn tagged_skips_body_when_current() {
+        let etag = ETag::new("schema", 1);
+        let response = Tagged::<&str>::new(etag.clone(), &if_none_match(&etag.0), || {
+            panic!("body must not be built")
+        })
+        .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.0.as_str());
+
+        let response = Tagged::new(etag.clone(), &HeaderMap::new(), || "body").into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], etag.0.as_str());
+    }
+}
@@ -2,28 +2,51 @@
 
 use std::collections::BTreeMap;
 use std::convert::Infallible;
+use std::io::{self, Write};
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
-use std::time::{SystemTime, UNIX_EPOCH};
 
-use axum::body::Body;
+use axum::body::{Body, Bytes};
 use axum::extract::State;
-use axum::http::{StatusCode, Uri, header};
-use axum::response::Json;
+use axum::http::{HeaderMap, StatusCode, Uri, header};
 use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Json};
 use rust_embed::Embed;
 use serde::Deserialize;
 use tracing::{error, info, warn};
 
-use rpglot_core::api::schema::{ApiMode, ApiSchema, DateInfo, InstanceInfo, TimelineInfo};
+use rpglot_core::api::agent::AgentReport;
+use rpglot_core::api::buffercache::{BufferCacheReport, buffer_cache_at};
+use rpglot_core::api::calendar::CalendarDay;
+use rpglot_core::api::grafana;
+use rpglot_core::api::plan_diff::{PlanDiff, plan_diff};
+use rpglot_core::api::relations::{RelationGrowthReport, relation_growth_in_range};
+use rpglot_core::api::schema::{
+    ApiMode, ApiSchema, DateInfo, ExtensionInfo, InstanceInfo, KubernetesInfo, TimelineInfo,
+};
+use rpglot_core::api::select::{SelectedSnapshot, SnapshotSelection};
+use rpglot_core::api::settings::{SettingsHistory, settings_history};
 use rpglot_core::api::snapshot::ApiSnapshot;
-use rpglot_core::provider::HistoryProvider;
-use rpglot_core::storage::heatmap::HeatmapBucket;
+use rpglot_core::api::workload::ApplicationSeries;
+use rpglot_core::entity::{EntityHistory, EntityKind, entity_history};
+use rpglot_core::fmt::DisplayTz;
+use rpglot_core::provider::{HistoryProvider, HybridProvider};
+use rpglot_core::storage::annotations::Annotation;
+use rpglot_core::storage::bookmarks::Bookmark;
+use rpglot_core::storage::gaps::find_gaps;
+use rpglot_core::storage::heatmap::{HeatmapBucket, count_annotations};
 
 use crate::background::{
-    chrono_free_date, ensure_history_ready, history_jump_to_timestamp, reconvert_current,
+    ensure_history_ready, history_jump_to_timestamp, live_snapshot_at, reconvert_current,
+};
+use crate::encoding::{Encoded, Encoding};
+use crate::etag::{ETag, Tagged};
+use crate::metrics::{METRICS, MemoryStatus, ServerStatus, SnapshotCacheStatus, prometheus};
+use crate::problem::{ApiError, ApiJson, ApiQuery, ErrorCode, Problem};
+use crate::state::{
+    AppState, LAST_CLIENT_ACTIVITY, Mode, SHUTDOWN, SSE_CONNECTIONS, WebAppInner, allocator_stats,
+    now_epoch,
 };
-use crate::state::{AppState, LAST_CLIENT_ACTIVITY, Mode, SSE_CONNECTIONS, now_epoch};
 
 // ============================================================
 // Embedded frontend assets
@@ -48,6 +71,91 @@ pub(crate) async fn handle_health() -> &'static str {
     "ok"
 }
 
+// ============================================================
+// Status
+// ============================================================
+
+#[derive(Deserialize)]
+pub(crate) struct StatusQuery {
+    /// "json" (default) or "prometheus".
+    format: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/status",
+    params(
+        ("format" = Option<String>, Query, description = "json (default) or prometheus (text exposition format)")
+    ),
+    responses(
+        (status = 200, description = "Server self-metrics", body = ServerStatus),
+        (status = 400, description = "Unknown format", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_status(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<StatusQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    // Not client activity: a scraper must not keep caches from idle eviction
+    let (mode, snapshot_cache) = {
+        let mut inner = state_tuple.0.lock().unwrap();
+        let mode = inner.mode;
+        let any = inner.provider.as_any_mut();
+        let history = match any {
+            Some(a) if a.is::<HistoryProvider>() => a.downcast_mut::<HistoryProvider>(),
+            Some(a) => a
+                .downcast_mut::<HybridProvider>()
+                .and_then(HybridProvider::history_mut),
+            None => None,
+        };
+        let cache = history.map(|hp| {
+            let stats = hp.snapshot_cache_stats();
+            SnapshotCacheStatus {
+                hits: stats.hits,
+                misses: stats.misses,
+                hit_ratio: stats.hit_ratio(),
+                entries: stats.entries,
+                used_bytes: stats.used_bytes,
+                max_bytes: stats.max_bytes,
+            }
+        });
+        (mode, cache)
+    };
+    let memory = allocator_stats();
+    let status = ServerStatus {
+        version: rpglot_core::VERSION.to_string(),
+        mode: match mode {
+            Mode::Live => "live",
+            Mode::History => "history",
+        }
+        .to_string(),
+        uptime_secs: METRICS.uptime().as_secs(),
+        sse_connections: SSE_CONNECTIONS.load(Ordering::Relaxed),
+        endpoints: METRICS.endpoints(),
+        snapshot_cache,
+        provider_init: METRICS.provider_init(),
+        memory: MemoryStatus {
+            allocated_bytes: memory.map(|m| m.allocated_bytes),
+            resident_bytes: memory.map(|m| m.resident_bytes),
+            after_eviction: METRICS.last_eviction(),
+        },
+        unresolved_strings: rpglot_core::storage::interner::unresolved_count(),
+    };
+    match query.format.as_deref() {
+        None | Some("json") => Ok(Json(status).into_response()),
+        Some("prometheus") => Ok((
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            prometheus(&status),
+        )
+            .into_response()),
+        Some(other) => Err(ApiError::bad_request(
+            ErrorCode::InvalidRequest,
+            format!("unknown format {other:?}, expected json or prometheus"),
+        )
+        .with("format", other)),
+    }
+}
+
 // ============================================================
 // Schema
 // ============================================================
@@ -56,16 +164,37 @@ pub(crate) async fn handle_health() -> &'static str {
     get,
     path = "/api/v1/schema",
     responses(
-        (status = 200, description = "API schema describing snapshot structure", body = ApiSchema)
+        (status = 200, description = "API schema describing snapshot structure", body = ApiSchema),
+        (status = 304, description = "Unchanged since the ETag in If-None-Match")
     )
 )]
-pub(crate) async fn handle_schema(State(state_tuple): AppState) -> Json<ApiSchema> {
+pub(crate) async fn handle_schema(
+    State(state_tuple): AppState,
+    headers: HeaderMap,
+) -> Tagged<Json<ApiSchema>> {
     LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
     let mut inner = state_tuple.0.lock().unwrap();
     // Lazy init: build chunk index on first schema request (frontend's first call)
     if inner.mode == Mode::History {
         ensure_history_ready(&mut inner);
     }
+    let etag = ETag::new(
+        "schema",
+        (
+            inner.mode == Mode::History,
+            inner.history_start,
+            inner.history_end,
+            inner.total_snapshots,
+            &inner.instance_info,
+            &inner.k8s_pod,
+            &inner.extensions,
+            inner.redaction.as_str(),
+        ),
+    );
+    Tagged::new(etag, &headers, || build_schema(&inner))
+}
+
+fn build_schema(inner: &WebAppInner) -> Json<ApiSchema> {
     let mode = match inner.mode {
         Mode::Live => ApiMode::Live,
         Mode::History => ApiMode::History,
@@ -76,6 +205,8 @@ pub(crate) async fn handle_schema(State(state_tuple): AppState) -> Json<ApiSchem
             end: inner.history_end.unwrap_or(0),
             total_snapshots: inner.total_snapshots.unwrap_or(0),
             dates: None, // lightweight — dates available via /api/v1/timeline
+            annotations: None,
+            gaps: None,
         })
     } else {
         None
@@ -93,8 +224,15 @@ pub(crate) async fn handle_schema(State(state_tuple): AppState) -> Json<ApiSchem
             } else {
                 Some(hostname.clone())
             },
+            kubernetes: inner.k8s_pod.as_ref().map(KubernetesInfo::from),
+            extensions: inner.extensions.iter().map(ExtensionInfo::from).collect(),
         });
-    Json(ApiSchema::generate(mode, timeline, instance))
+    let mut schema = ApiSchema::generate(mode, timeline, instance);
+    schema.capabilities.endpoints = crate::API_ENDPOINTS.clone();
+    if inner.redaction.is_active() {
+        schema.redaction = Some(inner.redaction.as_str().to_string());
+    }
+    Json(schema)
 }
 
 // ============================================================
@@ -103,11 +241,60 @@ pub(crate) async fn handle_schema(State(state_tuple): AppState) -> Json<ApiSchem
 
 #[derive(Deserialize, utoipa::IntoParams)]
 pub(crate) struct SnapshotQuery {
-    /// Unix timestamp to navigate to (history mode).
+    /// Unix timestamp to navigate to. In live mode the snapshot comes from
+    /// the in-memory buffer or data recorded with --store, and the live
+    /// stream is not affected.
     timestamp: Option<i64>,
     /// Direction for timestamp lookup: "floor" (default, latest snapshot <= ts)
     /// or "ceil" (earliest snapshot >= ts).
     direction: Option<String>,
+    /// Comma-separated fields to return, e.g. "pgs,summary" (default: all).
+    /// Tables: prc, pga, pgs, pgt, pgi, pge, pgl, pgp, pgv, pgr, pgw, custom, pgc;
+    /// "summary" selects system, pg, health and session fields.
+    fields: Option<String>,
+    /// Maximum rows per table. With limit/offset the response has `totals`.
+    limit: Option<usize>,
+    /// Rows to skip at the start of each table.
+    offset: Option<usize>,
+    // Per-table parameters, e.g. pgs_sort=exec_time_ms_s&pgs_limit=100:
+    // <table>_sort (column), <table>_order (asc|desc, default desc),
+    // <table>_limit, <table>_offset. Parsed from the raw query string.
+}
+
+/// The serialized snapshot is sent to the client in chunks of this size.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// `io::Write` that forwards the serialized snapshot to a streaming response body.
+/// The bounded channel keeps at most a few chunks in memory.
+struct ChunkSender {
+    buf: Vec<u8>,
+    tx: tokio::sync::mpsc::Sender<Bytes>,
+}
+
+impl ChunkSender {
+    fn send(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::replace(&mut self.buf, Vec::with_capacity(STREAM_CHUNK_SIZE));
+        self.tx
+            .blocking_send(Bytes::from(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "client disconnected"))
+    }
+}
+
+impl Write for ChunkSender {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        if self.buf.len() >= STREAM_CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send()
+    }
 }
 
 #[utoipa::path(
@@ -115,19 +302,33 @@ pub(crate) struct SnapshotQuery {
     path = "/api/v1/snapshot",
     params(SnapshotQuery),
     responses(
-        (status = 200, description = "Current or historical snapshot", body = ApiSnapshot),
-        (status = 400, description = "Invalid position or timestamp"),
-        (status = 503, description = "No snapshot available yet")
+        (status = 200, description = "Current or historical snapshot (JSON, or MessagePack with `Accept: application/msgpack`)", body = ApiSnapshot),
+        (status = 304, description = "Unchanged since the ETag in If-None-Match"),
+        (status = 400, description = "Invalid position, timestamp or field", body = Problem, content_type = "application/problem+json"),
+        (status = 503, description = "No snapshot available yet", body = Problem, content_type = "application/problem+json")
     )
 )]
 pub(crate) async fn handle_snapshot(
     State(state_tuple): AppState,
-    axum::extract::Query(query): axum::extract::Query<SnapshotQuery>,
-) -> Result<axum::response::Response, StatusCode> {
+    ApiQuery(query): ApiQuery<SnapshotQuery>,
+    ApiQuery(params): ApiQuery<BTreeMap<String, String>>,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Result<axum::response::Response, ApiError> {
     LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let encoding = Encoding::from_headers(&headers);
+    let selection = SnapshotSelection::parse(query.fields.as_deref(), query.limit, query.offset)
+        .and_then(|mut selection| {
+            selection.apply_table_params(params.iter().map(|(k, v)| (k.as_str(), v.as_str())))?;
+            Ok(selection)
+        })
+        .map_err(|e| {
+            warn!(error = %e, "invalid snapshot field selection");
+            ApiError::bad_request(ErrorCode::InvalidSelection, e)
+        })?;
     let state = state_tuple.0;
     // History navigation may call blocking provider methods — run in spawn_blocking
-    let snap = tokio::task::spawn_blocking(move || {
+    let (snap, position) = tokio::task::spawn_blocking(move || {
         let mut inner = state.lock().unwrap();
 
         // Lazy init: build chunk index if needed (after idle eviction or first request)
@@ -135,14 +336,25 @@ pub(crate) async fn handle_snapshot(
             ensure_history_ready(&mut inner);
         }
 
+        let use_ceil = query.direction.as_deref() == Some("ceil");
+        if inner.mode == Mode::Live
+            && let Some(ts) = query.timestamp
+        {
+            let Some(snap) = live_snapshot_at(&mut inner, ts, use_ceil) else {
+                let timestamps = live_timestamps(&inner);
+                let available = timestamps.first().copied().zip(timestamps.last().copied());
+                return Err(timestamp_out_of_range(ts, use_ceil, available));
+            };
+            return Ok((snap, None));
+        }
+
         // History navigation via query params
         if inner.mode == Mode::History
             && let Some(ts) = query.timestamp
+            && !history_jump_to_timestamp(&mut inner, ts, use_ceil)
         {
-            let use_ceil = query.direction.as_deref() == Some("ceil");
-            if !history_jump_to_timestamp(&mut inner, ts, use_ceil) {
-                return Err(StatusCode::BAD_REQUEST);
-            }
+            let available = inner.history_start.zip(inner.history_end);
+            return Err(timestamp_out_of_range(ts, use_ceil, available));
         }
 
         // Lazy loading after idle eviction: reload current snapshot if needed
@@ -150,21 +362,86 @@ pub(crate) async fn handle_snapshot(
             reconvert_current(&mut inner);
         }
 
-        inner
-            .current_snapshot
-            .clone()
-            .ok_or(StatusCode::SERVICE_UNAVAILABLE)
+        let position = inner
+            .provider
+            .as_any()
+            .and_then(|a| a.downcast_ref::<HistoryProvider>())
+            .map(HistoryProvider::position);
+        let snap = inner.current_snapshot.clone().ok_or_else(|| {
+            ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorCode::NoData,
+                "no snapshot available yet",
+            )
+        })?;
+        Ok((snap, position))
     })
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)??;
+    .await??;
+
+    // Same snapshot, same query, same encoding: same body
+    let etag = ETag::new(
+        "snapshot",
+        (
+            snap.timestamp,
+            position,
+            &snap.instance,
+            uri.query(),
+            encoding,
+        ),
+    );
+    if etag.matches(&headers) {
+        return Ok(etag.not_modified());
+    }
 
-    let json =
-        serde_json::to_string(snap.as_ref()).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    // Serialize on a blocking thread straight into the response body instead
+    // of building the whole document first. Sorting happens there too;
+    // `ready` reports an invalid sort column before the response starts.
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<Bytes>(4);
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
+    tokio::task::spawn_blocking(move || {
+        let mut writer = ChunkSender {
+            buf: Vec::with_capacity(STREAM_CHUNK_SIZE),
+            tx,
+        };
+        let result = if selection.is_full() {
+            let _ = ready_tx.send(Ok(()));
+            encoding.write(&mut writer, snap.as_ref())
+        } else {
+            match SelectedSnapshot::new(&snap, &selection) {
+                Ok(selected) => {
+                    let _ = ready_tx.send(Ok(()));
+                    encoding.write(&mut writer, &selected)
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            }
+        };
+        if let Err(e) = result.and_then(|_| writer.flush()) {
+            warn!(error = %e, "snapshot streaming aborted");
+        }
+    });
+    if let Err(e) = ready_rx
+        .await
+        .map_err(|_| ApiError::internal("snapshot serialization failed"))?
+    {
+        warn!(error = %e, "invalid snapshot sort");
+        return Err(ApiError::bad_request(ErrorCode::InvalidSelection, e));
+    }
+    let stream = async_stream::stream! {
+        while let Some(chunk) = rx.recv().await {
+            yield Ok::<_, Infallible>(chunk);
+        }
+    };
 
-    Ok(axum::response::Response::builder()
-        .header("content-type", "application/json")
-        .body(Body::from(json))
-        .unwrap())
+    let mut response = axum::response::Response::builder()
+        .header(header::CONTENT_TYPE, encoding.content_type())
+        .header(header::VARY, "accept")
+        .body(Body::from_stream(stream))
+        .unwrap();
+    etag.apply(response.headers_mut());
+    Ok(response)
 }
 
 // ============================================================
@@ -174,35 +451,93 @@ pub(crate) async fn handle_snapshot(
 #[utoipa::path(
     get,
     path = "/api/v1/timeline",
+    params(TzQuery),
     responses(
-        (status = 200, description = "History timeline metadata", body = TimelineInfo),
-        (status = 404, description = "Not available in live mode")
+        (status = 200, description = "History timeline metadata (live mode: the in-memory buffer and recorded data)", body = TimelineInfo),
+        (status = 304, description = "Unchanged since the ETag in If-None-Match"),
+        (status = 404, description = "No snapshots yet (live mode)", body = Problem, content_type = "application/problem+json")
     )
 )]
 pub(crate) async fn handle_timeline(
     State(state_tuple): AppState,
-) -> Result<Json<TimelineInfo>, StatusCode> {
+    ApiQuery(query): ApiQuery<TzQuery>,
+    headers: HeaderMap,
+) -> Result<Tagged<Json<TimelineInfo>>, ApiError> {
     LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let tz = query.tz();
     let mut inner = state_tuple.0.lock().unwrap();
-    if inner.mode != Mode::History {
-        return Err(StatusCode::NOT_FOUND);
+    if inner.mode == Mode::Live {
+        let timestamps = live_timestamps(&inner);
+        let (Some(&start), Some(&end)) = (timestamps.first(), timestamps.last()) else {
+            return Err(no_snapshots_yet());
+        };
+        let etag = ETag::new("timeline", (start, end, timestamps.len(), tz.name()));
+        return Ok(Tagged::new(etag, &headers, || {
+            Json(TimelineInfo {
+                start,
+                end,
+                total_snapshots: timestamps.len(),
+                dates: Some(compute_dates_index(&timestamps, tz)),
+                annotations: None,
+                gaps: Some(find_gaps(&timestamps)),
+            })
+        }));
     }
     ensure_history_ready(&mut inner);
-    let dates = {
-        let provider = inner
+    let annotations = inner
+        .annotations
+        .is_some()
+        .then(|| load_annotations(&mut inner));
+    let etag = ETag::new(
+        "timeline",
+        (
+            inner.history_start,
+            inner.history_end,
+            inner.total_snapshots,
+            &annotations,
+            tz.name(),
+        ),
+    );
+    Ok(Tagged::new(etag, &headers, || {
+        let history = inner
             .provider
             .as_any()
             .and_then(|a| a.downcast_ref::<HistoryProvider>());
-        provider.map(compute_dates_index)
-    };
-    Ok(Json(TimelineInfo {
-        start: inner.history_start.unwrap_or(0),
-        end: inner.history_end.unwrap_or(0),
-        total_snapshots: inner.total_snapshots.unwrap_or(0),
-        dates,
+        let dates = history.map(|hp| compute_dates_index(hp.timestamps(), tz));
+        let gaps = history.map(|hp| find_gaps(hp.timestamps()));
+        Json(TimelineInfo {
+            start: inner.history_start.unwrap_or(0),
+            end: inner.history_end.unwrap_or(0),
+            total_snapshots: inner.total_snapshots.unwrap_or(0),
+            dates,
+            annotations,
+            gaps,
+        })
     }))
 }
 
+/// 404 from the live timeline before the first snapshot.
+fn no_snapshots_yet() -> ApiError {
+    ApiError::not_found(ErrorCode::NoData, "no snapshots collected yet")
+}
+
+/// 400 for a timestamp with no snapshot at or around it, with the range
+/// that is available.
+fn timestamp_out_of_range(ts: i64, ceil: bool, available: Option<(i64, i64)>) -> ApiError {
+    let direction = if ceil { "after" } else { "before" };
+    let mut err = ApiError::bad_request(
+        ErrorCode::TimestampOutOfRange,
+        format!("no snapshot at or {direction} {ts}"),
+    )
+    .with("timestamp", ts);
+    if let Some((start, end)) = available {
+        err = err
+            .with("available_start", start)
+            .with("available_end", end);
+    }
+    err
+}
+
 // Lightweight struct for /api/v1/timeline/latest (O(1), no date index computation).
 #[derive(serde::Serialize)]
 pub(crate) struct TimelineLatest {
@@ -212,11 +547,18 @@ pub(crate) struct TimelineLatest {
 
 pub(crate) async fn handle_timeline_latest(
     State(state_tuple): AppState,
-) -> Result<Json<TimelineLatest>, StatusCode> {
+) -> Result<Json<TimelineLatest>, ApiError> {
     LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
     let inner = state_tuple.0.lock().unwrap();
-    if inner.mode != Mode::History {
-        return Err(StatusCode::NOT_FOUND);
+    if inner.mode == Mode::Live {
+        let timestamps = live_timestamps(&inner);
+        return match timestamps.last() {
+            Some(&end) => Ok(Json(TimelineLatest {
+                end,
+                total_snapshots: timestamps.len(),
+            })),
+            None => Err(no_snapshots_yet()),
+        };
     }
     Ok(Json(TimelineLatest {
         end: inner.history_end.unwrap_or(0),
@@ -224,8 +566,35 @@ pub(crate) async fn handle_timeline_latest(
     }))
 }
 
-/// Build a per-date index from HistoryProvider timestamps (no snapshot loading).
-fn compute_dates_index(hp: &HistoryProvider) -> Vec<DateInfo> {
+/// Timestamps browsable in live mode: the in-memory buffer and recorded data.
+fn live_timestamps(inner: &WebAppInner) -> Vec<i64> {
+    inner
+        .provider
+        .as_any()
+        .and_then(|a| a.downcast_ref::<HybridProvider>())
+        .map(HybridProvider::timestamps)
+        .unwrap_or_default()
+}
+
+/// Time zone of day boundaries for timeline dates, the calendar and heatmap
+/// caching.
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct TzQuery {
+    /// `local` or an IANA name such as `Europe/Moscow` (default: the server's `--tz`).
+    #[param(value_type = Option<String>)]
+    tz: Option<DisplayTz>,
+}
+
+impl TzQuery {
+    fn tz(&self) -> DisplayTz {
+        self.tz
+            .unwrap_or_else(|| rpglot_core::fmt::config().timezone)
+    }
+}
+
+/// Build a per-date index from sorted snapshot timestamps (no snapshot loading),
+/// with day boundaries in `tz`.
+fn compute_dates_index(timestamps: &[i64], tz: DisplayTz) -> Vec<DateInfo> {
     struct DateAcc {
         count: usize,
         first_timestamp: i64,
@@ -233,11 +602,9 @@ fn compute_dates_index(hp: &HistoryProvider) -> Vec<DateInfo> {
     }
 
     let mut map: BTreeMap<String, DateAcc> = BTreeMap::new();
-    for &ts in hp.timestamps() {
-        let days = ts / 86400;
-        let date_str = {
-            let d = chrono_free_date(days);
-            format!("{:04}-{:02}-{:02}", d.0, d.1, d.2)
+    for &ts in timestamps {
+        let Some(date_str) = tz.date_key(ts) else {
+            continue;
         };
         map.entry(date_str)
             .and_modify(|acc| {
@@ -272,11 +639,11 @@ pub(crate) struct AnalysisQuery {
 
 pub(crate) async fn handle_analysis(
     State(state_tuple): AppState,
-    axum::extract::Query(query): axum::extract::Query<AnalysisQuery>,
-) -> Result<Json<rpglot_core::analysis::AnalysisReport>, StatusCode> {
+    ApiQuery(query): ApiQuery<AnalysisQuery>,
+) -> Result<Json<rpglot_core::analysis::AnalysisReport>, ApiError> {
     LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
     if query.end <= query.start {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(ApiError::invalid_range(query.start, query.end));
     }
 
     let state = state_tuple.0.clone();
@@ -284,22 +651,22 @@ pub(crate) async fn handle_analysis(
     tokio::task::spawn_blocking(move || {
         let mut inner = state.lock().unwrap();
         if inner.mode != Mode::History {
-            return Err(StatusCode::NOT_FOUND);
+            return Err(ApiError::history_only());
         }
         ensure_history_ready(&mut inner);
 
+        let annotations = load_annotations(&mut inner);
         let provider = inner
             .provider
             .as_any_mut()
             .and_then(|a| a.downcast_mut::<HistoryProvider>())
-            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+            .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
 
         let analyzer = rpglot_core::analysis::Analyzer::new();
-        let report = analyzer.analyze(provider, query.start, query.end);
+        let report = analyzer.analyze(provider, query.start, query.end, &annotations);
         Ok(Json(report))
     })
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .await?
 }
 
 // ============================================================
@@ -314,6 +681,10 @@ pub(crate) struct HeatmapQuery {
     end: i64,
     /// Number of buckets (default: 400, max: 1000).
     buckets: Option<usize>,
+    /// Time zone of the day `start` falls on, for caching: `local` or an
+    /// IANA name (default: the server's `--tz`).
+    #[param(value_type = Option<String>)]
+    tz: Option<DisplayTz>,
 }
 
 /// Get activity heatmap data for a time range (history mode only).
@@ -322,42 +693,42 @@ pub(crate) struct HeatmapQuery {
     path = "/api/v1/timeline/heatmap",
     params(HeatmapQuery),
     responses(
-        (status = 200, description = "Heatmap bucket data"),
-        (status = 404, description = "Not available in live mode")
+        (status = 200, description = "Heatmap bucket data (JSON or MessagePack)"),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
     )
 )]
 pub(crate) async fn handle_heatmap(
     State(state_tuple): AppState,
-    axum::extract::Query(query): axum::extract::Query<HeatmapQuery>,
-) -> Result<Json<Vec<HeatmapBucket>>, StatusCode> {
+    ApiQuery(query): ApiQuery<HeatmapQuery>,
+    headers: HeaderMap,
+) -> Result<Encoded<Vec<HeatmapBucket>>, ApiError> {
     LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let encoding = Encoding::from_headers(&headers);
     let num_buckets = query.buckets.unwrap_or(400).min(1000);
 
     if query.end <= query.start {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(ApiError::invalid_range(query.start, query.end));
     }
 
-    // Derive date key for caching
-    let days = query.start / 86400;
-    let d = chrono_free_date(days);
-    let date_key = format!("{:04}-{:02}-{:02}", d.0, d.1, d.2);
+    // Past days are immutable, so their buckets are cached per day and zone
+    let tz = query
+        .tz
+        .unwrap_or_else(|| rpglot_core::fmt::config().timezone);
+    let day = tz.date(query.start);
+    let is_past_date = day.is_some() && day < tz.date(now_epoch());
+    let date_key = format!("{} {}", tz.date_key(query.start).unwrap_or_default(), tz);
 
     // Check cache first
     {
         let mut inner = state_tuple.0.lock().unwrap();
         if inner.mode != Mode::History {
-            return Err(StatusCode::NOT_FOUND);
+            return Err(ApiError::history_only());
         }
         ensure_history_ready(&mut inner);
-        // Use cached data for past dates (they are immutable)
-        let today_days = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64
-            / 86400;
-        let is_past_date = days < today_days;
         if is_past_date && let Some(cached) = inner.heatmap_cache.get(&date_key) {
-            return Ok(Json(cached.clone()));
+            let mut buckets = cached.clone();
+            annotate_buckets(&mut inner, &mut buckets, query.end);
+            return Ok(Encoded(encoding, buckets));
         }
     }
 
@@ -369,7 +740,7 @@ pub(crate) async fn handle_heatmap(
             .provider
             .as_any_mut()
             .and_then(|a| a.downcast_mut::<HistoryProvider>())
-            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+            .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
 
         let raw = hp.load_heatmap_range(query.start, query.end);
         let buckets = rpglot_core::storage::heatmap::bucket_heatmap(
@@ -379,15 +750,864 @@ pub(crate) async fn handle_heatmap(
             num_buckets,
         );
 
-        // Cache the result
-        inner.heatmap_cache.insert(date_key, buckets.clone());
+        if is_past_date {
+            inner.heatmap_cache.insert(date_key, buckets.clone());
+        }
+
+        // Annotations change independently of the cached data
+        let mut buckets = buckets;
+        annotate_buckets(&mut inner, &mut buckets, query.end);
+        Ok::<_, ApiError>(buckets)
+    })
+    .await??;
+
+    Ok(Encoded(encoding, buckets))
+}
+
+/// Get a per-day health summary of all stored dates, computed from heatmap
+/// files only (history mode only).
+#[utoipa::path(
+    get,
+    path = "/api/v1/timeline/calendar",
+    params(TzQuery),
+    responses(
+        (status = 200, description = "Snapshots, health and critical incidents per day", body = Vec<CalendarDay>),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_calendar(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<TzQuery>,
+) -> Result<Json<Vec<CalendarDay>>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let tz = query.tz();
+    let state = state_tuple.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut inner = state.lock().unwrap();
+        if inner.mode != Mode::History {
+            return Err(ApiError::history_only());
+        }
+        ensure_history_ready(&mut inner);
+        let hp = inner
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+            .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
+        Ok(Json(rpglot_core::api::calendar::calendar(hp, tz)))
+    })
+    .await?
+}
+
+// ============================================================
+// Application series
+// ============================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct ApplicationSeriesQuery {
+    /// Start timestamp (epoch seconds).
+    start: i64,
+    /// End timestamp (epoch seconds).
+    end: i64,
+    /// Number of buckets (default: 200, max: 1000).
+    buckets: Option<usize>,
+    /// Number of applications with their own series (default: 10); the rest are summed.
+    top: Option<usize>,
+}
+
+/// Get active sessions by application_name as a stacked time series (history mode only).
+#[utoipa::path(
+    get,
+    path = "/api/v1/timeline/applications",
+    params(ApplicationSeriesQuery),
+    responses(
+        (status = 200, description = "Active sessions per application per bucket (JSON or MessagePack)", body = ApplicationSeries),
+        (status = 400, description = "Invalid range", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_application_series(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<ApplicationSeriesQuery>,
+    headers: HeaderMap,
+) -> Result<Encoded<ApplicationSeries>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let encoding = Encoding::from_headers(&headers);
+    if query.end <= query.start {
+        return Err(ApiError::invalid_range(query.start, query.end));
+    }
+    let num_buckets = query.buckets.unwrap_or(200).min(1000);
+    let top = query.top.unwrap_or(10);
+
+    let state = state_tuple.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut inner = state.lock().unwrap();
+        if inner.mode != Mode::History {
+            return Err(ApiError::history_only());
+        }
+        ensure_history_ready(&mut inner);
+        let hp = inner
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+            .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
+
+        Ok(Encoded(
+            encoding,
+            rpglot_core::api::workload::application_series(
+                hp,
+                query.start,
+                query.end,
+                num_buckets,
+                top,
+            ),
+        ))
+    })
+    .await?
+}
+
+// ============================================================
+// Entity history
+// ============================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct EntityHistoryQuery {
+    /// Entity kind: `pid` or `queryid`.
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    kind: EntityKind,
+    /// Backend PID or queryid.
+    id: i64,
+    /// Start timestamp (epoch seconds).
+    start: i64,
+    /// End timestamp (epoch seconds).
+    end: i64,
+}
+
+/// Get the metric trajectory of one backend PID or queryid through a time range (history mode only).
+#[utoipa::path(
+    get,
+    path = "/api/v1/entity/history",
+    params(EntityHistoryQuery),
+    responses(
+        (status = 200, description = "Samples and state changes of the entity (JSON or MessagePack)", body = EntityHistory),
+        (status = 400, description = "Invalid type or range", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_entity_history(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<EntityHistoryQuery>,
+    headers: HeaderMap,
+) -> Result<Encoded<EntityHistory>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let encoding = Encoding::from_headers(&headers);
+    if query.end <= query.start {
+        return Err(ApiError::invalid_range(query.start, query.end));
+    }
+
+    let state = state_tuple.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut inner = state.lock().unwrap();
+        if inner.mode != Mode::History {
+            return Err(ApiError::history_only());
+        }
+        ensure_history_ready(&mut inner);
+        let hp = inner
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+            .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
+
+        Ok(Encoded(
+            encoding,
+            entity_history(hp, query.kind, query.id, query.start, query.end),
+        ))
+    })
+    .await?
+}
+
+// ============================================================
+// Relation growth
+// ============================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct RelationGrowthQuery {
+    /// Start timestamp (epoch seconds).
+    start: i64,
+    /// End timestamp (epoch seconds).
+    end: i64,
+}
+
+/// Get sizes of the largest tables and indexes and their growth between the
+/// first and the last hourly size sample in a range (history mode only).
+#[utoipa::path(
+    get,
+    path = "/api/v1/relations/growth",
+    params(RelationGrowthQuery),
+    responses(
+        (status = 200, description = "Relation sizes and growth, fastest growing first", body = RelationGrowthReport),
+        (status = 400, description = "Invalid range", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_relation_growth(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<RelationGrowthQuery>,
+) -> Result<Json<RelationGrowthReport>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    if query.end <= query.start {
+        return Err(ApiError::invalid_range(query.start, query.end));
+    }
+
+    let state = state_tuple.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut inner = state.lock().unwrap();
+        if inner.mode != Mode::History {
+            return Err(ApiError::history_only());
+        }
+        ensure_history_ready(&mut inner);
+        let hp = inner
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+            .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
+
+        Ok(Json(relation_growth_in_range(hp, query.start, query.end)))
+    })
+    .await?
+}
+
+// ============================================================
+// Settings history
+// ============================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct SettingsHistoryQuery {
+    /// Start timestamp (epoch seconds).
+    start: i64,
+    /// End timestamp (epoch seconds).
+    end: i64,
+}
+
+/// List configuration changes (changed, added and removed GUCs, new
+/// pending_restart flags) in a range (history mode only).
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/history",
+    params(SettingsHistoryQuery),
+    responses(
+        (status = 200, description = "Configuration changes in time order", body = SettingsHistory),
+        (status = 400, description = "Invalid range", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_settings_history(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<SettingsHistoryQuery>,
+) -> Result<Json<SettingsHistory>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    if query.end <= query.start {
+        return Err(ApiError::invalid_range(query.start, query.end));
+    }
+
+    let state = state_tuple.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut inner = state.lock().unwrap();
+        if inner.mode != Mode::History {
+            return Err(ApiError::history_only());
+        }
+        ensure_history_ready(&mut inner);
+        let hp = inner
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+            .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
+
+        Ok(Json(settings_history(hp, query.start, query.end)))
+    })
+    .await?
+}
+
+// ============================================================
+// Buffer cache composition
+// ============================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct BufferCacheQuery {
+    /// Moment to show (epoch seconds, history mode); defaults to the current snapshot.
+    ts: Option<i64>,
+}
+
+/// Get the contents of shared_buffers by relation: the latest pg_buffercache
+/// sample (taken every 5 minutes) at or before `ts` in history mode, the
+/// latest one collected in live mode.
+#[utoipa::path(
+    get,
+    path = "/api/v1/buffercache",
+    params(BufferCacheQuery),
+    responses(
+        (status = 200, description = "shared_buffers composition, largest relations first", body = BufferCacheReport),
+        (status = 404, description = "No sample: pg_buffercache is not installed or not sampled yet", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_buffercache(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<BufferCacheQuery>,
+) -> Result<Json<BufferCacheReport>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+
+    let state = state_tuple.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut inner = state.lock().unwrap();
+        let report = if inner.mode == Mode::History {
+            ensure_history_ready(&mut inner);
+            let ts = query
+                .ts
+                .or_else(|| inner.raw_snapshot.as_ref().map(|s| s.timestamp))
+                .ok_or_else(|| {
+                    ApiError::not_found(ErrorCode::NoData, "no snapshot loaded, pass ts")
+                })?;
+            let hp = inner
+                .provider
+                .as_any_mut()
+                .and_then(|a| a.downcast_mut::<HistoryProvider>())
+                .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
+            buffer_cache_at(hp, ts).ok_or_else(|| no_buffercache_sample().with("ts", ts))?
+        } else {
+            inner
+                .buffer_cache
+                .clone()
+                .ok_or_else(no_buffercache_sample)?
+        };
+        Ok(Json(report))
+    })
+    .await?
+}
+
+fn no_buffercache_sample() -> ApiError {
+    ApiError::not_found(
+        ErrorCode::NotFound,
+        "no pg_buffercache sample: the extension is not installed or not sampled yet",
+    )
+}
+
+// ============================================================
+// Plan diff
+// ============================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct PlanDiffQuery {
+    /// Query whose plans are compared.
+    queryid: i64,
+    /// Old plan; defaults to the most-called plan other than `to`.
+    from: Option<i64>,
+    /// New plan; defaults to the most-called plan other than `from`.
+    to: Option<i64>,
+}
+
+/// Diff two pg_store_plans plans of a queryid in the current snapshot.
+/// Lines are aligned by plan node; nodes whose estimates differ are
+/// `changed`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/plans/diff",
+    params(PlanDiffQuery),
+    responses(
+        (status = 200, description = "Aligned plan lines", body = PlanDiff),
+        (status = 404, description = "The query has fewer than two plans or a planid is unknown", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_plan_diff(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<PlanDiffQuery>,
+) -> Result<Json<PlanDiff>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+
+    let state = state_tuple.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let inner = state.lock().unwrap();
+        let snapshot = inner
+            .raw_snapshot
+            .as_ref()
+            .ok_or_else(|| ApiError::not_found(ErrorCode::NoData, "no snapshot loaded"))?;
+        plan_diff(
+            snapshot,
+            inner.provider.interner(),
+            query.queryid,
+            query.from,
+            query.to,
+        )
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError::not_found(
+                ErrorCode::NotFound,
+                "no two plans of this queryid in the current snapshot",
+            )
+            .with("queryid", query.queryid)
+        })
+    })
+    .await?
+}
 
-        Ok::<_, StatusCode>(buckets)
+// ============================================================
+// Agent self-monitoring
+// ============================================================
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct AgentQuery {
+    /// Summary range start (epoch seconds, history mode).
+    start: Option<i64>,
+    /// Summary range end (epoch seconds, history mode).
+    end: Option<i64>,
+}
+
+/// Get rpglotd resource usage: the current snapshot's sample and, in history
+/// mode with a range, averages and maxima over it.
+#[utoipa::path(
+    get,
+    path = "/api/v1/agent",
+    params(AgentQuery),
+    responses(
+        (status = 200, description = "rpglotd resource usage", body = AgentReport),
+        (status = 400, description = "Invalid range", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_agent(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<AgentQuery>,
+) -> Result<Json<AgentReport>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let range = match (query.start, query.end) {
+        (Some(start), Some(end)) if end <= start => {
+            return Err(ApiError::invalid_range(start, end));
+        }
+        (Some(start), Some(end)) => Some((start, end)),
+        _ => None,
+    };
+
+    let state = state_tuple.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut inner = state.lock().unwrap();
+        let current = inner
+            .raw_snapshot
+            .as_ref()
+            .and_then(rpglot_core::api::agent::convert_agent_stats);
+        let summary = match range {
+            Some((start, end)) if inner.mode == Mode::History => {
+                ensure_history_ready(&mut inner);
+                let hp = inner
+                    .provider
+                    .as_any_mut()
+                    .and_then(|a| a.downcast_mut::<HistoryProvider>())
+                    .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
+                Some(rpglot_core::api::agent::agent_summary(hp, start, end))
+            }
+            _ => None,
+        };
+        Ok(Json(AgentReport { current, summary }))
     })
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)??;
+    .await?
+}
+
+// ============================================================
+// Bookmarks
+// ============================================================
 
-    Ok(Json(buckets))
+#[derive(Deserialize, utoipa::ToSchema)]
+pub(crate) struct NewBookmark {
+    /// Snapshot timestamp (epoch seconds).
+    ts: i64,
+    /// Free-form note.
+    #[serde(default)]
+    label: String,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct BookmarkQuery {
+    /// Timestamp of the bookmark to delete (epoch seconds).
+    ts: i64,
+}
+
+/// List bookmarks sorted by timestamp (history mode only).
+#[utoipa::path(
+    get,
+    path = "/api/v1/bookmarks",
+    responses(
+        (status = 200, description = "Bookmarks sorted by timestamp", body = Vec<Bookmark>),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_bookmarks(
+    State(state_tuple): AppState,
+) -> Result<Json<Vec<Bookmark>>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let mut inner = state_tuple.0.lock().unwrap();
+    let store = inner.bookmarks.as_mut().ok_or_else(bookmarks_unavailable)?;
+    // Pick up bookmarks added from the TUI
+    store.reload().map_err(|e| {
+        error!(error = %e, "failed to read bookmarks");
+        ApiError::internal("failed to read bookmarks")
+    })?;
+    Ok(Json(store.list().to_vec()))
+}
+
+/// Add a bookmark, or relabel the one at the same timestamp.
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookmarks",
+    request_body = NewBookmark,
+    responses(
+        (status = 200, description = "Bookmarks after the change", body = Vec<Bookmark>),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_bookmark_add(
+    State(state_tuple): AppState,
+    ApiJson(bookmark): ApiJson<NewBookmark>,
+) -> Result<Json<Vec<Bookmark>>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let mut inner = state_tuple.0.lock().unwrap();
+    let store = inner.bookmarks.as_mut().ok_or_else(bookmarks_unavailable)?;
+    store
+        .add(bookmark.ts, &bookmark.label, now_epoch())
+        .map_err(|e| {
+            error!(error = %e, "failed to save bookmark");
+            ApiError::internal("failed to save bookmark")
+        })?;
+    info!(ts = bookmark.ts, label = %bookmark.label, "bookmark added");
+    Ok(Json(store.list().to_vec()))
+}
+
+/// Delete the bookmark at a timestamp.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/bookmarks",
+    params(BookmarkQuery),
+    responses(
+        (status = 204, description = "Bookmark deleted"),
+        (status = 404, description = "No such bookmark, or live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_bookmark_delete(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<BookmarkQuery>,
+) -> Result<StatusCode, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let mut inner = state_tuple.0.lock().unwrap();
+    let store = inner.bookmarks.as_mut().ok_or_else(bookmarks_unavailable)?;
+    match store.remove(query.ts) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(
+            ApiError::not_found(ErrorCode::NotFound, "no bookmark at this timestamp")
+                .with("ts", query.ts),
+        ),
+        Err(e) => {
+            error!(error = %e, "failed to delete bookmark");
+            Err(ApiError::internal("failed to delete bookmark"))
+        }
+    }
+}
+
+fn bookmarks_unavailable() -> ApiError {
+    ApiError::not_found(
+        ErrorCode::Unavailable,
+        "bookmarks are stored with history data only",
+    )
+}
+
+// ============================================================
+// Annotations
+// ============================================================
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub(crate) struct NewAnnotation {
+    /// Event timestamp (epoch seconds). Defaults to now.
+    ts: Option<i64>,
+    /// What happened ("deployed v2.3", "vacuum full ran").
+    text: String,
+    /// Who reports it ("ci", "deploy-bot", user name).
+    #[serde(default)]
+    source: String,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct AnnotationRangeQuery {
+    /// Only annotations at or after this timestamp (epoch seconds).
+    start: Option<i64>,
+    /// Only annotations at or before this timestamp (epoch seconds).
+    end: Option<i64>,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(crate) struct AnnotationQuery {
+    /// Id of the annotation to delete.
+    id: u64,
+}
+
+/// Re-read annotations (CI may post through another instance) and return them.
+/// A read error keeps the previously loaded list.
+fn load_annotations(inner: &mut WebAppInner) -> Vec<Annotation> {
+    let Some(store) = inner.annotations.as_mut() else {
+        return Vec::new();
+    };
+    if let Err(e) = store.reload() {
+        error!(error = %e, "failed to read annotations");
+    }
+    store.list().to_vec()
+}
+
+fn annotate_buckets(inner: &mut WebAppInner, buckets: &mut [HeatmapBucket], end_ts: i64) {
+    let annotations = load_annotations(inner);
+    count_annotations(buckets, end_ts, annotations.iter().map(|a| a.ts));
+}
+
+/// List annotations sorted by timestamp, optionally limited to a range.
+#[utoipa::path(
+    get,
+    path = "/api/v1/annotations",
+    params(AnnotationRangeQuery),
+    responses(
+        (status = 200, description = "Annotations sorted by timestamp", body = Vec<Annotation>),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_annotations(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<AnnotationRangeQuery>,
+) -> Result<Json<Vec<Annotation>>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let mut inner = state_tuple.0.lock().unwrap();
+    let store = inner
+        .annotations
+        .as_mut()
+        .ok_or_else(annotations_unavailable)?;
+    store.reload().map_err(|e| {
+        error!(error = %e, "failed to read annotations");
+        ApiError::internal("failed to read annotations")
+    })?;
+    let start = query.start.unwrap_or(i64::MIN);
+    let end = query.end.unwrap_or(i64::MAX);
+    Ok(Json(store.in_range(start, end).to_vec()))
+}
+
+/// Add an annotation (deployment, maintenance, config change).
+#[utoipa::path(
+    post,
+    path = "/api/v1/annotations",
+    request_body = NewAnnotation,
+    responses(
+        (status = 200, description = "The created annotation", body = Annotation),
+        (status = 400, description = "Empty text", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Not available in live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_annotation_add(
+    State(state_tuple): AppState,
+    ApiJson(annotation): ApiJson<NewAnnotation>,
+) -> Result<Json<Annotation>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    if annotation.text.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidRequest,
+            "annotation text must not be empty",
+        ));
+    }
+    let mut inner = state_tuple.0.lock().unwrap();
+    let store = inner
+        .annotations
+        .as_mut()
+        .ok_or_else(annotations_unavailable)?;
+    let now = now_epoch();
+    let created = store
+        .add(
+            annotation.ts.unwrap_or(now),
+            &annotation.text,
+            &annotation.source,
+            now,
+        )
+        .map_err(|e| {
+            error!(error = %e, "failed to save annotation");
+            ApiError::internal("failed to save annotation")
+        })?;
+    info!(id = created.id, ts = created.ts, text = %created.text, source = %created.source, "annotation added");
+    Ok(Json(created))
+}
+
+/// Delete an annotation by id.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/annotations",
+    params(AnnotationQuery),
+    responses(
+        (status = 204, description = "Annotation deleted"),
+        (status = 404, description = "No such annotation, or live mode", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_annotation_delete(
+    State(state_tuple): AppState,
+    ApiQuery(query): ApiQuery<AnnotationQuery>,
+) -> Result<StatusCode, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let mut inner = state_tuple.0.lock().unwrap();
+    let store = inner
+        .annotations
+        .as_mut()
+        .ok_or_else(annotations_unavailable)?;
+    match store.remove(query.id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(
+            ApiError::not_found(ErrorCode::NotFound, "no annotation with this id")
+                .with("id", query.id),
+        ),
+        Err(e) => {
+            error!(error = %e, "failed to delete annotation");
+            Err(ApiError::internal("failed to delete annotation"))
+        }
+    }
+}
+
+fn annotations_unavailable() -> ApiError {
+    ApiError::not_found(
+        ErrorCode::Unavailable,
+        "annotations are stored with history data only",
+    )
+}
+
+// ============================================================
+// Grafana simple JSON datasource
+// ============================================================
+
+/// Datasource connection test.
+pub(crate) async fn handle_grafana_test() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Metric names for the panel's metric picker.
+pub(crate) async fn handle_grafana_search(
+    ApiJson(request): ApiJson<grafana::SearchRequest>,
+) -> Json<Vec<&'static str>> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    Json(grafana::search(&request.target))
+}
+
+/// Time series of instance-wide metrics from heatmap data (history mode only).
+pub(crate) async fn handle_grafana_query(
+    State(state_tuple): AppState,
+    ApiJson(request): ApiJson<grafana::QueryRequest>,
+) -> Result<Json<Vec<grafana::TimeSeries>>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let (start, end) = request.range.bounds().ok_or_else(|| {
+        ApiError::bad_request(
+            ErrorCode::InvalidRange,
+            "range.from and range.to must be RFC 3339 times, from before to",
+        )
+    })?;
+
+    let state = state_tuple.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut inner = state.lock().unwrap();
+        if inner.mode != Mode::History {
+            return Err(ApiError::history_only());
+        }
+        ensure_history_ready(&mut inner);
+        let hp = inner
+            .provider
+            .as_any_mut()
+            .and_then(|a| a.downcast_mut::<HistoryProvider>())
+            .ok_or_else(|| ApiError::internal("history provider unavailable"))?;
+
+        let entries = hp.load_heatmap_range(start, end);
+        Ok(Json(grafana::query(&entries, &request)))
+    })
+    .await?
+}
+
+/// Annotations in the dashboard's time range; none without an annotation store.
+pub(crate) async fn handle_grafana_annotations(
+    State(state_tuple): AppState,
+    ApiJson(request): ApiJson<grafana::AnnotationsRequest>,
+) -> Result<Json<Vec<grafana::AnnotationEvent>>, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let (start, end) = request.range.bounds().ok_or_else(|| {
+        ApiError::bad_request(
+            ErrorCode::InvalidRange,
+            "range.from and range.to must be RFC 3339 times, from before to",
+        )
+    })?;
+    let mut inner = state_tuple.0.lock().unwrap();
+    let Some(store) = inner.annotations.as_mut() else {
+        return Ok(Json(Vec::new()));
+    };
+    store.reload().map_err(|e| {
+        error!(error = %e, "failed to read annotations");
+        ApiError::internal("failed to read annotations")
+    })?;
+    Ok(Json(grafana::annotation_events(
+        store.in_range(start, end),
+        &request,
+    )))
+}
+
+// ============================================================
+// PostgreSQL instance selection
+// ============================================================
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub(crate) struct InstanceSelection {
+    /// Instance name from `ApiSnapshot.instances`; `null` selects the primary instance.
+    name: Option<String>,
+}
+
+/// Select the PostgreSQL instance shown in the PG tabs.
+///
+/// Live mode switches from the next snapshot; history mode re-renders the
+/// current position.
+#[utoipa::path(
+    post,
+    path = "/api/v1/instance",
+    request_body = InstanceSelection,
+    responses(
+        (status = 204, description = "Instance selected"),
+        (status = 404, description = "No such instance", body = Problem, content_type = "application/problem+json")
+    )
+)]
+pub(crate) async fn handle_instance_select(
+    State(state_tuple): AppState,
+    ApiJson(selection): ApiJson<InstanceSelection>,
+) -> Result<StatusCode, ApiError> {
+    LAST_CLIENT_ACTIVITY.store(now_epoch(), Ordering::Relaxed);
+    let state = state_tuple.0;
+    tokio::task::spawn_blocking(move || {
+        let mut inner = state.lock().unwrap();
+        if let Some(name) = &selection.name {
+            let instances = inner
+                .current_snapshot
+                .as_ref()
+                .map(|s| s.instances.clone())
+                .unwrap_or_default();
+            if !instances.contains(name) {
+                return Err(ApiError::not_found(
+                    ErrorCode::NotFound,
+                    format!("no instance {name:?}"),
+                )
+                .with("name", name)
+                .with("available", instances));
+            }
+        }
+        inner.provider.set_instance(selection.name);
+        inner.pgs_rate.reset();
+        inner.pgp_rate.reset();
+        inner.pgt_rate.reset();
+        inner.pgi_rate.reset();
+        if inner.mode == Mode::History {
+            reconvert_current(&mut inner);
+        } else {
+            // The previous snapshot holds the other instance's sessions
+            inner.raw_snapshot = None;
+        }
+        Ok(StatusCode::NO_CONTENT)
+    })
+    .await?
 }
 
 // ============================================================
@@ -405,12 +1625,15 @@ impl Drop for SseGuard {
 
 pub(crate) async fn handle_stream(
     State(state_tuple): AppState,
-) -> Result<Sse<impl futures_core::Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+) -> Result<Sse<impl futures_core::Stream<Item = Result<Event, Infallible>>>, ApiError> {
     let (state, tx) = state_tuple;
     {
         let inner = state.lock().unwrap();
         if inner.mode != Mode::Live {
-            return Err(StatusCode::NOT_FOUND);
+            return Err(ApiError::not_found(
+                ErrorCode::LiveOnly,
+                "the snapshot stream is served in live mode only",
+            ));
         }
     }
 
@@ -418,11 +1641,22 @@ pub(crate) async fn handle_stream(
     info!(active_connections = active, "SSE client connected");
 
     let mut rx = tx.subscribe();
+    let mut shutdown = SHUTDOWN.subscribe();
 
     let stream = async_stream::stream! {
         let _guard = SseGuard;
         loop {
-            match rx.recv().await {
+            let received = tokio::select! {
+                biased;
+                _ = shutdown.wait_for(|&stop| stop) => None,
+                received = rx.recv() => Some(received),
+            };
+            let Some(received) = received else {
+                // EventSource reconnects on its own once the server is back
+                yield Ok(Event::default().event("shutdown").data("server shutting down"));
+                break;
+            };
+            match received {
                 Ok(snapshot) => {
                     match serde_json::to_string(snapshot.as_ref()) {
                         Ok(json) => {
@@ -0,0 +1,177 @@
+//! Request limits: per-client rate limiting and a cap on concurrent
+//! expensive requests (range analysis, heatmaps, series).
+//!
+//! Protects a shared history server from one client hammering range
+//! analysis. Rejected requests get 429 with `Retry-After` and a problem body.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use crate::auth::{AuthUser, api_path, is_api_path};
+use crate::problem::{ApiError, ErrorCode};
+
+/// Endpoints that read a whole range of history.
+const EXPENSIVE_PATHS: &[&str] = &[
+    "/api/v1/analysis",
+    "/api/v1/timeline/heatmap",
+    "/api/v1/timeline/calendar",
+    "/api/v1/timeline/applications",
+    "/api/v1/entity/history",
+    "/api/v1/settings/history",
+    "/grafana/query",
+];
+
+/// Client buckets are pruned once there are more than this many.
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+/// Token bucket per client: `per_minute` requests with bursts up to the same
+/// number.
+pub(crate) struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token for `client`, or returns how long until one is available.
+    pub(crate) fn check(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let capacity = self.per_minute as f64;
+        let refill_per_sec = capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > MAX_TRACKED_CLIENTS {
+            // Full buckets carry no state worth keeping
+            buckets.retain(|_, b| {
+                b.tokens + now.duration_since(b.updated).as_secs_f64() * refill_per_sec < capacity
+            });
+        }
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / refill_per_sec,
+            ))
+        }
+    }
+}
+
+/// Limits shared by all requests; either part may be disabled.
+pub(crate) struct Limits {
+    pub(crate) rate: Option<RateLimiter>,
+    pub(crate) expensive: Option<Semaphore>,
+}
+
+fn too_many_requests(retry_after: Duration, detail: &str) -> axum::response::Response {
+    // Round up: retrying early would be rejected again
+    let secs = (retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)).max(1);
+    ApiError::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        ErrorCode::RateLimited,
+        detail,
+    )
+    .with("retry_after", secs)
+    .with_header(header::RETRY_AFTER, HeaderValue::from(secs))
+    .into_response()
+}
+
+/// Authenticated user, or the peer address without auth.
+fn client_key(req: &Request) -> String {
+    if let Some(user) = req.extensions().get::<AuthUser>() {
+        return format!("user:{}", user.0);
+    }
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ci| format!("ip:{}", ci.0.ip()))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+pub(crate) async fn limits_middleware(
+    State(limits): State<Arc<Limits>>,
+    req: Request,
+    next: Next,
+) -> axum::response::Response {
+    let path = api_path(req.uri().path());
+    // Static assets, the health check and monitoring are never limited
+    if !is_api_path(path) || path == "/api/v1/health" || path == "/api/v1/status" {
+        return next.run(req).await;
+    }
+
+    if let Some(rate) = &limits.rate {
+        let client = client_key(&req);
+        if let Err(retry_after) = rate.check(&client, Instant::now()) {
+            warn!(client, path, "rate limit exceeded");
+            return too_many_requests(retry_after, "too many requests, slow down");
+        }
+    }
+
+    let _permit = match &limits.expensive {
+        Some(semaphore) if EXPENSIVE_PATHS.contains(&path) => match semaphore.try_acquire() {
+            Ok(permit) => Some(permit),
+            Err(_) => {
+                warn!(path, "too many concurrent expensive requests");
+                return too_many_requests(
+                    Duration::from_secs(1),
+                    "too many concurrent expensive requests (analysis, heatmap, series)",
+                );
+            }
+        },
+        _ => None,
+    };
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_refills() {
+        let limiter = RateLimiter::new(60);
+        let t0 = Instant::now();
+        for _ in 0..60 {
+            assert!(limiter.check("a", t0).is_ok());
+        }
+        // Burst exhausted: one token per second
+        let wait = limiter.check("a", t0).unwrap_err();
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+        // Other clients have their own bucket
+        assert!(limiter.check("b", t0).is_ok());
+
+        assert!(limiter.check("a", t0 + Duration::from_secs(1)).is_ok());
+        assert!(limiter.check("a", t0 + Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn retry_after_rounds_up() {
+        let response = too_many_requests(Duration::from_millis(1500), "slow down");
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "2");
+    }
+}
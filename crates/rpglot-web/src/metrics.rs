@@ -0,0 +1,487 @@
+//! Server self-metrics for `/api/v1/status`: requests and latencies per
+//! endpoint, snapshot cache effectiveness, history provider init time and
+//! memory left after idle eviction.
+//!
+//! Lets operators monitor the monitor: a slow analysis endpoint or a cache
+//! that never hits shows up here before users complain. The same numbers are
+//! served in Prometheus text format with `?format=prometheus`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::Request;
+use axum::middleware::Next;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::auth::{api_path, is_api_path};
+
+/// Upper bounds (ms) of the latency histogram buckets.
+const LATENCY_BUCKETS_MS: [u64; 11] = [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Key of API paths that match no route (404s), so that scanners cannot
+/// grow the registry without bound.
+const OTHER_ENDPOINT: &str = "other";
+
+pub(crate) static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Process-wide registry, updated by [`metrics_middleware`] and the
+/// background tasks.
+pub(crate) struct Metrics {
+    started: Instant,
+    endpoints: Mutex<BTreeMap<&'static str, EndpointStats>>,
+    provider_inits: AtomicU64,
+    provider_init_last_us: AtomicU64,
+    provider_init_total_us: AtomicU64,
+    evictions: AtomicU64,
+    evicted_at: AtomicI64,
+    allocated_after_eviction: AtomicU64,
+    resident_after_eviction: AtomicU64,
+}
+
+#[derive(Default)]
+struct EndpointStats {
+    requests: u64,
+    client_errors: u64,
+    server_errors: u64,
+    total_us: u64,
+    max_us: u64,
+    /// Requests per [`LATENCY_BUCKETS_MS`] bucket; the last one is +Inf.
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+impl EndpointStats {
+    fn record(&mut self, status: u16, elapsed: Duration) {
+        let us = elapsed.as_micros() as u64;
+        self.requests += 1;
+        match status {
+            400..=499 => self.client_errors += 1,
+            500.. => self.server_errors += 1,
+            _ => {}
+        }
+        self.total_us += us;
+        self.max_us = self.max_us.max(us);
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&le| us <= le * 1000)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[bucket] += 1;
+    }
+
+    /// Upper bound of the bucket holding the `q` quantile (`None` when it
+    /// falls into +Inf).
+    fn quantile_ms(&self, q: f64) -> Option<u64> {
+        let rank = (self.requests as f64 * q).ceil() as u64;
+        let mut seen = 0;
+        for (i, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank.max(1) {
+                return LATENCY_BUCKETS_MS.get(i).copied();
+            }
+        }
+        None
+    }
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            endpoints: Mutex::new(BTreeMap::new()),
+            provider_inits: AtomicU64::new(0),
+            provider_init_last_us: AtomicU64::new(0),
+            provider_init_total_us: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            evicted_at: AtomicI64::new(0),
+            allocated_after_eviction: AtomicU64::new(0),
+            resident_after_eviction: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn record_request(&self, endpoint: &'static str, status: u16, elapsed: Duration) {
+        self.endpoints
+            .lock()
+            .unwrap()
+            .entry(endpoint)
+            .or_default()
+            .record(status, elapsed);
+    }
+
+    /// History provider built its chunk index (first request, or the first
+    /// one after idle eviction).
+    pub(crate) fn record_provider_init(&self, elapsed: Duration) {
+        let us = elapsed.as_micros() as u64;
+        self.provider_inits.fetch_add(1, Ordering::Relaxed);
+        self.provider_init_last_us.store(us, Ordering::Relaxed);
+        self.provider_init_total_us.fetch_add(us, Ordering::Relaxed);
+    }
+
+    /// Idle eviction finished; `memory` is what the allocator still holds.
+    pub(crate) fn record_eviction(&self, at: i64, memory: Option<AllocatorStats>) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+        self.evicted_at.store(at, Ordering::Relaxed);
+        if let Some(memory) = memory {
+            self.allocated_after_eviction
+                .store(memory.allocated_bytes, Ordering::Relaxed);
+            self.resident_after_eviction
+                .store(memory.resident_bytes, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub(crate) fn endpoints(&self) -> Vec<EndpointStatus> {
+        self.endpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(path, s)| EndpointStatus {
+                path: path.to_string(),
+                requests: s.requests,
+                client_errors: s.client_errors,
+                server_errors: s.server_errors,
+                mean_ms: if s.requests > 0 {
+                    s.total_us as f64 / s.requests as f64 / 1000.0
+                } else {
+                    0.0
+                },
+                max_ms: s.max_us as f64 / 1000.0,
+                p95_ms: s.quantile_ms(0.95),
+            })
+            .collect()
+    }
+
+    pub(crate) fn provider_init(&self) -> ProviderInitStatus {
+        ProviderInitStatus {
+            count: self.provider_inits.load(Ordering::Relaxed),
+            last_ms: self.provider_init_last_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            total_ms: self.provider_init_total_us.load(Ordering::Relaxed) as f64 / 1000.0,
+        }
+    }
+
+    pub(crate) fn last_eviction(&self) -> Option<EvictionStatus> {
+        let count = self.evictions.load(Ordering::Relaxed);
+        (count > 0).then(|| EvictionStatus {
+            count,
+            at: self.evicted_at.load(Ordering::Relaxed),
+            allocated_bytes: self.allocated_after_eviction.load(Ordering::Relaxed),
+            resident_bytes: self.resident_after_eviction.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// Counts API requests per route. Frontend assets are not counted; SSE
+/// latency is the time until the stream is opened.
+pub(crate) async fn metrics_middleware(req: Request, next: Next) -> axum::response::Response {
+    let path = api_path(req.uri().path());
+    if !is_api_path(path) {
+        return next.run(req).await;
+    }
+    let endpoint = crate::API_ENDPOINTS
+        .iter()
+        .find(|p| *p == path)
+        .map_or(OTHER_ENDPOINT, String::as_str);
+    let t0 = Instant::now();
+    let response = next.run(req).await;
+    METRICS.record_request(endpoint, response.status().as_u16(), t0.elapsed());
+    response
+}
+
+// ============================================================
+// Status report
+// ============================================================
+
+/// Response of `GET /api/v1/status`.
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct ServerStatus {
+    pub(crate) version: String,
+    /// "live" or "history".
+    pub(crate) mode: String,
+    pub(crate) uptime_secs: u64,
+    pub(crate) sse_connections: usize,
+    /// Requests per API route since start, sorted by path.
+    pub(crate) endpoints: Vec<EndpointStatus>,
+    /// Decoded snapshot cache (history data only).
+    pub(crate) snapshot_cache: Option<SnapshotCacheStatus>,
+    pub(crate) provider_init: ProviderInitStatus,
+    pub(crate) memory: MemoryStatus,
+    /// String hashes read from disk that were missing from their interner
+    /// (a sign of damaged chunks).
+    pub(crate) unresolved_strings: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct EndpointStatus {
+    pub(crate) path: String,
+    pub(crate) requests: u64,
+    /// 4xx responses (including 429 from rate limiting).
+    pub(crate) client_errors: u64,
+    /// 5xx responses.
+    pub(crate) server_errors: u64,
+    pub(crate) mean_ms: f64,
+    pub(crate) max_ms: f64,
+    /// Upper bound of the histogram bucket holding the 95th percentile;
+    /// null above the largest bucket (10 s) or without requests.
+    pub(crate) p95_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct SnapshotCacheStatus {
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+    pub(crate) hit_ratio: f64,
+    pub(crate) entries: usize,
+    pub(crate) used_bytes: usize,
+    pub(crate) max_bytes: usize,
+}
+
+/// Chunk index builds of the history provider.
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct ProviderInitStatus {
+    pub(crate) count: u64,
+    pub(crate) last_ms: f64,
+    pub(crate) total_ms: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct MemoryStatus {
+    /// Bytes allocated by the server now (null without jemalloc).
+    pub(crate) allocated_bytes: Option<u64>,
+    /// Bytes of physical memory held by the allocator now.
+    pub(crate) resident_bytes: Option<u64>,
+    /// Memory right after the last idle eviction: the server's floor.
+    pub(crate) after_eviction: Option<EvictionStatus>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct EvictionStatus {
+    /// Idle evictions since start.
+    pub(crate) count: u64,
+    /// Unix time of the last one.
+    pub(crate) at: i64,
+    pub(crate) allocated_bytes: u64,
+    pub(crate) resident_bytes: u64,
+}
+
+/// Allocator counters, see [`crate::state::allocator_stats`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AllocatorStats {
+    pub(crate) allocated_bytes: u64,
+    pub(crate) resident_bytes: u64,
+}
+
+/// Status in Prometheus text exposition format.
+pub(crate) fn prometheus(status: &ServerStatus) -> String {
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, f64)]| {
+        let _ = writeln!(out, "# HELP rpglot_web_{name} {help}");
+        let _ = writeln!(out, "# TYPE rpglot_web_{name} {kind}");
+        for (labels, value) in samples {
+            let _ = writeln!(out, "rpglot_web_{name}{labels} {value}");
+        }
+    };
+    let endpoint_label = |path: &str| format!("{{endpoint=\"{path}\"}}");
+
+    metric(
+        "uptime_seconds",
+        "gauge",
+        "Seconds since the server started.",
+        &[(String::new(), status.uptime_secs as f64)],
+    );
+    metric(
+        "sse_connections",
+        "gauge",
+        "Open SSE streams.",
+        &[(String::new(), status.sse_connections as f64)],
+    );
+    metric(
+        "requests_total",
+        "counter",
+        "API requests per route.",
+        &status
+            .endpoints
+            .iter()
+            .map(|e| (endpoint_label(&e.path), e.requests as f64))
+            .collect::<Vec<_>>(),
+    );
+    metric(
+        "request_errors_total",
+        "counter",
+        "API responses with 4xx or 5xx status per route.",
+        &status
+            .endpoints
+            .iter()
+            .flat_map(|e| {
+                [
+                    (
+                        format!("{{endpoint=\"{}\",class=\"4xx\"}}", e.path),
+                        e.client_errors as f64,
+                    ),
+                    (
+                        format!("{{endpoint=\"{}\",class=\"5xx\"}}", e.path),
+                        e.server_errors as f64,
+                    ),
+                ]
+            })
+            .collect::<Vec<_>>(),
+    );
+    metric(
+        "request_duration_seconds_sum",
+        "counter",
+        "Total time spent serving API requests per route.",
+        &status
+            .endpoints
+            .iter()
+            .map(|e| {
+                (
+                    endpoint_label(&e.path),
+                    e.mean_ms * e.requests as f64 / 1000.0,
+                )
+            })
+            .collect::<Vec<_>>(),
+    );
+    metric(
+        "request_duration_seconds_max",
+        "gauge",
+        "Slowest API request per route since start.",
+        &status
+            .endpoints
+            .iter()
+            .map(|e| (endpoint_label(&e.path), e.max_ms / 1000.0))
+            .collect::<Vec<_>>(),
+    );
+    if let Some(cache) = &status.snapshot_cache {
+        metric(
+            "snapshot_cache_hits_total",
+            "counter",
+            "Snapshot lookups served from the decoded snapshot cache.",
+            &[(String::new(), cache.hits as f64)],
+        );
+        metric(
+            "snapshot_cache_misses_total",
+            "counter",
+            "Snapshot lookups that decoded a chunk.",
+            &[(String::new(), cache.misses as f64)],
+        );
+        metric(
+            "snapshot_cache_bytes",
+            "gauge",
+            "Memory used by the decoded snapshot cache.",
+            &[(String::new(), cache.used_bytes as f64)],
+        );
+    }
+    metric(
+        "provider_inits_total",
+        "counter",
+        "History provider chunk index builds.",
+        &[(String::new(), status.provider_init.count as f64)],
+    );
+    metric(
+        "provider_init_seconds_last",
+        "gauge",
+        "Duration of the last chunk index build.",
+        &[(String::new(), status.provider_init.last_ms / 1000.0)],
+    );
+    if let Some(bytes) = status.memory.allocated_bytes {
+        metric(
+            "allocated_bytes",
+            "gauge",
+            "Bytes allocated by the server.",
+            &[(String::new(), bytes as f64)],
+        );
+    }
+    if let Some(bytes) = status.memory.resident_bytes {
+        metric(
+            "resident_bytes",
+            "gauge",
+            "Physical memory held by the allocator.",
+            &[(String::new(), bytes as f64)],
+        );
+    }
+    if let Some(eviction) = &status.memory.after_eviction {
+        metric(
+            "resident_bytes_after_eviction",
+            "gauge",
+            "Physical memory held right after the last idle eviction.",
+            &[(String::new(), eviction.resident_bytes as f64)],
+        );
+    }
+    metric(
+        "unresolved_strings_total",
+        "counter",
+        "String hashes missing from their interner.",
+        &[(String::new(), status.unresolved_strings as f64)],
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_stats_bucket_latencies() {
+        let metrics = Metrics::new();
+        for ms in [
+            1, 3, 8, 40, 40, 40, 40, 40, 40, 40, 40, 40, 40, 40, 40, 40, 40, 40, 40, 900,
+        ] {
+            metrics.record_request("/api/v1/snapshot", 200, Duration::from_millis(ms));
+        }
+        metrics.record_request("/api/v1/analysis", 503, Duration::from_secs(20));
+        metrics.record_request(OTHER_ENDPOINT, 404, Duration::from_millis(1));
+
+        let endpoints = metrics.endpoints();
+        let paths: Vec<&str> = endpoints.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["/api/v1/analysis", "/api/v1/snapshot", "other"]);
+
+        let snapshot = &endpoints[1];
+        assert_eq!(snapshot.requests, 20);
+        assert_eq!(snapshot.server_errors, 0);
+        assert_eq!(snapshot.p95_ms, Some(50));
+        assert_eq!(snapshot.max_ms, 900.0);
+
+        let analysis = &endpoints[0];
+        assert_eq!(analysis.server_errors, 1);
+        // Above the largest bucket
+        assert_eq!(analysis.p95_ms, None);
+        assert_eq!(endpoints[2].client_errors, 1);
+    }
+
+    #[test]
+    fn prometheus_format() {
+        let metrics = Metrics::new();
+        metrics.record_request("/api/v1/snapshot", 200, Duration::from_millis(500));
+        metrics.record_request("/api/v1/snapshot", 429, Duration::from_millis(1500));
+        let status = ServerStatus {
+            version: "0.0.0".to_string(),
+            mode: "history".to_string(),
+            uptime_secs: 60,
+            sse_connections: 0,
+            endpoints: metrics.endpoints(),
+            snapshot_cache: None,
+            provider_init: metrics.provider_init(),
+            memory: MemoryStatus {
+                allocated_bytes: None,
+                resident_bytes: None,
+                after_eviction: None,
+            },
+            unresolved_strings: 0,
+        };
+        let text = prometheus(&status);
+        assert!(text.contains("# TYPE rpglot_web_requests_total counter\n"));
+        assert!(text.contains("rpglot_web_requests_total{endpoint=\"/api/v1/snapshot\"} 2\n"));
+        assert!(text.contains(
+            "rpglot_web_request_errors_total{endpoint=\"/api/v1/snapshot\",class=\"4xx\"} 1\n"
+        ));
+        assert!(text.contains(
+            "rpglot_web_request_duration_seconds_sum{endpoint=\"/api/v1/snapshot\"} 2\n"
+        ));
+        assert!(!text.contains("snapshot_cache"));
+        assert!(!text.contains("allocated_bytes"));
+    }
+}
@@ -0,0 +1,304 @@
+//! Chat notifications of incident groups for the webhook alerting path:
+//! Slack block kit and Mattermost markdown messages with severity emojis,
+//! affected categories, peak values and a deep link into the web UI.
+
+use serde_json::{Value, json};
+
+use rpglot_core::analysis::{Category, Incident, IncidentGroup, Severity};
+use rpglot_core::fmt::format_timestamp;
+
+/// Body format of webhook requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WebhookFormat {
+    /// The raw incidents as JSON.
+    Json,
+    /// Slack incoming webhook with block kit sections.
+    Slack,
+    /// Mattermost incoming webhook with a markdown text.
+    Mattermost,
+}
+
+impl WebhookFormat {
+    /// Parses `--webhook-format`.
+    pub(crate) fn parse(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "slack" => Ok(Self::Slack),
+            "mattermost" => Ok(Self::Mattermost),
+            other => Err(format!(
+                "unknown webhook format '{other}' (json, slack, mattermost)"
+            )),
+        }
+    }
+}
+
+/// An incident group with the incidents worth notifying about.
+pub(crate) struct NotifyGroup<'a> {
+    pub(crate) group: &'a IncidentGroup,
+    pub(crate) incidents: Vec<&'a Incident>,
+}
+
+impl NotifyGroup<'_> {
+    /// The incident that defines the group's peak: most severe, then highest.
+    fn peak(&self) -> Option<&Incident> {
+        self.incidents.iter().copied().max_by(|a, b| {
+            a.severity
+                .cmp(&b.severity)
+                .then(a.peak_value.total_cmp(&b.peak_value))
+        })
+    }
+
+    /// Affected categories in order of first appearance.
+    fn categories(&self) -> Vec<Category> {
+        let mut categories = Vec::new();
+        for incident in &self.incidents {
+            if !categories.contains(&incident.category) {
+                categories.push(incident.category);
+            }
+        }
+        categories
+    }
+}
+
+fn severity_emoji(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => ":red_circle:",
+        Severity::Warning => ":large_orange_circle:",
+        Severity::Info => ":large_blue_circle:",
+    }
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "Critical",
+        Severity::Warning => "Warning",
+        Severity::Info => "Info",
+    }
+}
+
+/// Category name as in the analysis API (`pg_statements`).
+fn category_label(category: Category) -> String {
+    serde_json::to_value(category)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Web UI tab showing a category's data.
+fn category_tab(category: Category) -> Option<&'static str> {
+    match category {
+        Category::PgActivity => Some("pga"),
+        Category::PgStatements => Some("pgs"),
+        Category::PgLocks => Some("pgl"),
+        Category::PgTables => Some("pgt"),
+        Category::PgIndexes => Some("pgi"),
+        Category::PgEvents | Category::PgErrors => Some("pge"),
+        Category::Cpu
+        | Category::Memory
+        | Category::Disk
+        | Category::Network
+        | Category::Psi
+        | Category::Cgroup => Some("prc"),
+        Category::PgBgwriter | Category::Annotation | Category::DataGap | Category::Settings => {
+            None
+        }
+    }
+}
+
+fn time(ts: i64) -> String {
+    format_timestamp(ts, "%Y-%m-%d %H:%M:%S").unwrap_or_else(|| ts.to_string())
+}
+
+fn peak_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+/// Web UI link at the peak of `incident`, on the tab of its category.
+pub(crate) fn deep_link(base_url: &str, incident: &Incident) -> String {
+    let mut link = format!(
+        "{}/?ts={}",
+        base_url.trim_end_matches('/'),
+        incident.peak_ts
+    );
+    if let Some(tab) = category_tab(incident.category) {
+        link.push_str("&tab=");
+        link.push_str(tab);
+    }
+    link
+}
+
+fn headline(hostname: &str, groups: &[NotifyGroup]) -> String {
+    let count: usize = groups.iter().map(|g| g.incidents.len()).sum();
+    format!("rpglot on {hostname}: {count} new incident(s)")
+}
+
+/// Summary line of a group and one line per incident, with `bold` and `link`
+/// rendering in the chat's markup.
+fn group_lines(
+    group: &NotifyGroup,
+    base_url: Option<&str>,
+    bold: fn(&str) -> String,
+    link: fn(&str, &str) -> String,
+) -> Vec<String> {
+    let severity = group.group.severity;
+    let categories: Vec<String> = group.categories().into_iter().map(category_label).collect();
+    let mut lines = vec![format!(
+        "{} {} {} - {} · {}",
+        severity_emoji(severity),
+        bold(severity_label(severity)),
+        time(group.group.first_ts),
+        time(group.group.last_ts),
+        categories.join(", ")
+    )];
+    for incident in &group.incidents {
+        lines.push(format!(
+            "• {} (peak {} at {})",
+            incident.title,
+            peak_value(incident.peak_value),
+            time(incident.peak_ts)
+        ));
+    }
+    if let (Some(base_url), Some(peak)) = (base_url, group.peak()) {
+        lines.push(link(
+            &deep_link(base_url, peak),
+            "Open in rpglot at the peak",
+        ));
+    }
+    lines
+}
+
+/// Slack incoming webhook body: a header block and a section per group.
+pub(crate) fn slack_message(
+    hostname: &str,
+    groups: &[NotifyGroup],
+    base_url: Option<&str>,
+) -> Value {
+    let title = headline(hostname, groups);
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": title },
+    })];
+    for group in groups {
+        let lines = group_lines(
+            group,
+            base_url,
+            |s| format!("*{s}*"),
+            |url, text| format!("<{url}|{text}>"),
+        );
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": lines.join("\n") },
+        }));
+    }
+    json!({ "text": title, "blocks": blocks })
+}
+
+/// Mattermost incoming webhook body: one markdown text.
+pub(crate) fn mattermost_message(
+    hostname: &str,
+    groups: &[NotifyGroup],
+    base_url: Option<&str>,
+) -> Value {
+    let mut text = format!("#### {}", headline(hostname, groups));
+    for group in groups {
+        let lines = group_lines(
+            group,
+            base_url,
+            |s| format!("**{s}**"),
+            |url, text| format!("[{text}]({url})"),
+        );
+        text.push_str("\n\n");
+        text.push_str(&lines.join("\n"));
+    }
+    json!({ "text": text })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn incident(category: Category, severity: Severity, peak_value: f64) -> Incident {
+        Incident {
+            rule_id: "rule".to_string(),
+            category,
+            severity,
+            first_ts: 1000,
+            last_ts: 1100,
+            merge_key: None,
+            peak_ts: 1050,
+            peak_value,
+            title: format!("{category:?} high"),
+            detail: None,
+            snapshot_count: 1,
+            entity_id: None,
+            planid: None,
+        }
+    }
+
+    fn group() -> IncidentGroup {
+        IncidentGroup {
+            id: 1,
+            first_ts: 1000,
+            last_ts: 1100,
+            severity: Severity::Critical,
+            persistent: false,
+            incidents: vec![
+                incident(Category::Cpu, Severity::Warning, 95.5),
+                incident(Category::PgStatements, Severity::Critical, 12.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!(WebhookFormat::parse("Slack"), Ok(WebhookFormat::Slack));
+        assert!(WebhookFormat::parse("teams").is_err());
+    }
+
+    #[test]
+    fn links_to_the_peak_tab() {
+        let incident = incident(Category::PgStatements, Severity::Critical, 1.0);
+        assert_eq!(
+            deep_link("https://rpglot.example/", &incident),
+            "https://rpglot.example/?ts=1050&tab=pgs"
+        );
+    }
+
+    #[test]
+    fn slack_blocks_per_group() {
+        let group = group();
+        let groups = [NotifyGroup {
+            group: &group,
+            incidents: group.incidents.iter().collect(),
+        }];
+        let message = slack_message("db1", &groups, Some("https://rpglot.example"));
+        assert_eq!(message["text"], "rpglot on db1: 2 new incident(s)");
+        let section = message["blocks"][1]["text"]["text"].as_str().unwrap();
+        assert!(section.starts_with(":red_circle: *Critical*"));
+        assert!(section.contains("cpu, pg_statements"));
+        assert!(section.contains("(peak 95.5 at "));
+        // The most severe incident defines the peak
+        assert!(section.contains("<https://rpglot.example/?ts=1050&tab=pgs|"));
+    }
+
+    #[test]
+    fn mattermost_markdown() {
+        let group = group();
+        let groups = [NotifyGroup {
+            group: &group,
+            incidents: vec![&group.incidents[1]],
+        }];
+        let text = mattermost_message("db1", &groups, None)["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(text.starts_with("#### rpglot on db1: 1 new incident(s)"));
+        assert!(text.contains("**Critical**"));
+        assert!(!text.contains("Cpu high"));
+        assert!(!text.contains("]("));
+    }
+}
@@ -8,16 +8,48 @@ use utoipa::OpenApi;
 #[openapi(
     paths(
         crate::handlers::handle_health,
+        crate::handlers::handle_status,
         crate::handlers::handle_schema,
         crate::handlers::handle_snapshot,
         crate::handlers::handle_timeline,
         crate::handlers::handle_heatmap,
+        crate::handlers::handle_calendar,
+        crate::handlers::handle_application_series,
+        crate::handlers::handle_entity_history,
+        crate::handlers::handle_relation_growth,
+        crate::handlers::handle_settings_history,
+        crate::handlers::handle_buffercache,
+        crate::handlers::handle_plan_diff,
+        crate::handlers::handle_agent,
+        crate::handlers::handle_bookmarks,
+        crate::handlers::handle_bookmark_add,
+        crate::handlers::handle_bookmark_delete,
+        crate::handlers::handle_annotations,
+        crate::handlers::handle_annotation_add,
+        crate::handlers::handle_annotation_delete,
+        crate::handlers::handle_instance_select,
     ),
     components(schemas(
         ApiSnapshot,
         ApiSchema,
+        rpglot_core::api::capabilities::Capabilities,
+        rpglot_core::api::capabilities::TableCapability,
         TimelineInfo,
         DateInfo,
+        rpglot_core::storage::gaps::TimelineGap,
+        rpglot_core::storage::bookmarks::Bookmark,
+        crate::handlers::NewBookmark,
+        rpglot_core::storage::annotations::Annotation,
+        crate::handlers::NewAnnotation,
+        crate::handlers::InstanceSelection,
+        crate::problem::Problem,
+        crate::problem::ErrorCode,
+        crate::metrics::ServerStatus,
+        crate::metrics::EndpointStatus,
+        crate::metrics::SnapshotCacheStatus,
+        crate::metrics::ProviderInitStatus,
+        crate::metrics::MemoryStatus,
+        crate::metrics::EvictionStatus,
         rpglot_core::api::schema::ApiMode,
         rpglot_core::api::schema::SummarySchema,
         rpglot_core::api::schema::SummarySection,
@@ -30,6 +62,8 @@ use utoipa::OpenApi;
         rpglot_core::api::schema::DataType,
         rpglot_core::api::schema::Unit,
         rpglot_core::api::schema::Format,
+        rpglot_core::thresholds::Thresholds,
+        rpglot_core::fmt::FmtConfig,
         rpglot_core::api::snapshot::SystemSummary,
         rpglot_core::api::snapshot::CpuSummary,
         rpglot_core::api::snapshot::LoadSummary,
@@ -47,8 +81,40 @@ use utoipa::OpenApi;
         rpglot_core::api::snapshot::PgIndexesRow,
         rpglot_core::api::snapshot::PgStorePlansRow,
         rpglot_core::api::snapshot::PgLocksRow,
+        rpglot_core::api::snapshot::PgWorkloadRow,
+        rpglot_core::api::snapshot::CustomQuerySection,
+        rpglot_core::api::snapshot::CustomQueryColumn,
+        rpglot_core::api::snapshot::CustomQueryResultRow,
+        rpglot_core::api::snapshot::PgSettingsRow,
+        rpglot_core::api::workload::ApplicationSeries,
+        rpglot_core::api::workload::ApplicationSeriesLine,
+        rpglot_core::entity::EntityKind,
+        rpglot_core::entity::EntityHistory,
+        rpglot_core::entity::EntityPoint,
+        rpglot_core::entity::EntityStateChange,
+        rpglot_core::api::agent::AgentReport,
+        rpglot_core::api::agent::ApiAgentStats,
+        rpglot_core::api::agent::ApiAgentPhase,
+        rpglot_core::api::agent::AgentSummary,
+        rpglot_core::api::calendar::CalendarDay,
+        rpglot_core::api::relations::RelationGrowthReport,
+        rpglot_core::api::relations::RelationGrowth,
+        rpglot_core::api::settings::SettingsHistory,
+        rpglot_core::api::settings::SettingChange,
+        rpglot_core::api::settings::SettingChangeKind,
+        rpglot_core::api::buffercache::BufferCacheReport,
+        rpglot_core::api::buffercache::BufferCacheRelation,
+        rpglot_core::api::plan_diff::PlanDiff,
+        rpglot_core::api::plan_diff::PlanDiffSide,
+        rpglot_core::api::plan_diff::PlanDiffLine,
+        rpglot_core::api::plan_diff::PlanDiffKind,
         rpglot_core::api::snapshot::ReplicationInfo,
+        rpglot_core::api::snapshot::DegradedInfo,
+        rpglot_core::api::snapshot::PgConnectionInfo,
         rpglot_core::api::snapshot::ReplicaDetail,
+        rpglot_core::api::snapshot::ArchiverInfo,
+        rpglot_core::api::encryption::EncryptionInfo,
+        rpglot_core::api::encryption::PlaintextClient,
     )),
     info(
         title = "rpglot API",
@@ -57,3 +123,20 @@ use utoipa::OpenApi;
     )
 )]
 pub(crate) struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_documented_paths_are_routed() {
+        let endpoints = &*crate::API_ENDPOINTS;
+        for path in ApiDoc::openapi().paths.paths.keys() {
+            assert!(
+                endpoints.contains(path),
+                "{path} is documented but not routed"
+            );
+        }
+        assert!(endpoints.iter().any(|p| p == "/api/v1/analysis"));
+    }
+}
@@ -0,0 +1,253 @@
+//! Error responses as RFC 9457 problem details (`application/problem+json`).
+//!
+//! Every API error carries a machine-readable [`ErrorCode`], a message for
+//! people and, where it helps, context such as the requested range and the
+//! range that is available. The SPA shows the message instead of a bare
+//! status code; API clients branch on `code`.
+
+use axum::extract::rejection::{JsonRejection, QueryRejection};
+use axum::extract::{FromRequest, FromRequestParts, Request};
+use axum::http::request::Parts;
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+
+pub(crate) const CONTENT_TYPE: &str = "application/problem+json";
+
+/// Machine-readable error kind, the `code` member of a problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ErrorCode {
+    /// Malformed query string or request body.
+    InvalidRequest,
+    /// `end` is not after `start`.
+    InvalidRange,
+    /// Unknown field, table or sort column in a snapshot selection.
+    InvalidSelection,
+    /// No snapshot at or around the requested timestamp.
+    TimestampOutOfRange,
+    /// The endpoint needs history data; this server is in live mode.
+    HistoryOnly,
+    /// The endpoint needs live mode; this server serves history.
+    LiveOnly,
+    /// No snapshot collected or loaded yet.
+    NoData,
+    /// No such bookmark, annotation, instance or sample.
+    NotFound,
+    /// The feature has no storage here (bookmarks, annotations).
+    Unavailable,
+    /// Authentication required or failed.
+    Unauthorized,
+    /// Authenticated user is not allowed.
+    Forbidden,
+    /// Rate limit or concurrent request limit exceeded.
+    RateLimited,
+    /// Server-side failure; details are in the server log.
+    Internal,
+}
+
+/// Body of an error response.
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct Problem {
+    /// Always "about:blank": `code` identifies the problem.
+    #[serde(rename = "type")]
+    pub(crate) kind: &'static str,
+    /// HTTP status phrase.
+    pub(crate) title: String,
+    pub(crate) status: u16,
+    pub(crate) code: ErrorCode,
+    /// What went wrong, for people.
+    pub(crate) detail: String,
+    /// Context of the error (e.g. `start`, `end`, `available_start`).
+    #[serde(flatten)]
+    #[schema(value_type = Object)]
+    pub(crate) context: Map<String, Value>,
+}
+
+/// API error, rendered as a [`Problem`].
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    code: ErrorCode,
+    detail: String,
+    context: Map<String, Value>,
+    headers: Vec<(header::HeaderName, HeaderValue)>,
+}
+
+impl ApiError {
+    pub(crate) fn new(status: StatusCode, code: ErrorCode, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            detail: detail.into(),
+            context: Map::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub(crate) fn bad_request(code: ErrorCode, detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, code, detail)
+    }
+
+    pub(crate) fn not_found(code: ErrorCode, detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, code, detail)
+    }
+
+    /// 400 for a range whose end is not after its start.
+    pub(crate) fn invalid_range(start: i64, end: i64) -> Self {
+        Self::bad_request(ErrorCode::InvalidRange, "end must be after start")
+            .with("start", start)
+            .with("end", end)
+    }
+
+    /// 404 from an endpoint that reads stored history.
+    pub(crate) fn history_only() -> Self {
+        Self::not_found(
+            ErrorCode::HistoryOnly,
+            "available with history data only (rpglot-web --history)",
+        )
+    }
+
+    /// 500; the cause is logged by the caller, not sent to the client.
+    pub(crate) fn internal(detail: impl Into<String>) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::Internal,
+            detail,
+        )
+    }
+
+    /// Adds a context member to the problem.
+    pub(crate) fn with(mut self, key: &str, value: impl Serialize) -> Self {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.context.insert(key.to_string(), value);
+        }
+        self
+    }
+
+    pub(crate) fn with_header(mut self, name: header::HeaderName, value: HeaderValue) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    fn problem(&self) -> Problem {
+        Problem {
+            kind: "about:blank",
+            title: self
+                .status
+                .canonical_reason()
+                .unwrap_or("Error")
+                .to_string(),
+            status: self.status.as_u16(),
+            code: self.code,
+            detail: self.detail.clone(),
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = serde_json::to_vec(&self.problem()).unwrap_or_default();
+        let mut response = (self.status, body).into_response();
+        let headers = response.headers_mut();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+        for (name, value) in self.headers {
+            headers.insert(name, value);
+        }
+        response
+    }
+}
+
+/// A blocking task panicked or was cancelled.
+impl From<tokio::task::JoinError> for ApiError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        tracing::error!(error = %e, "request task failed");
+        Self::internal("request failed")
+    }
+}
+
+/// Query string extractor that rejects with a problem instead of plain text.
+pub(crate) struct ApiQuery<T>(pub(crate) T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequestParts<S> for ApiQuery<T> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Query::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Query(value)| Self(value))
+            .map_err(|e: QueryRejection| {
+                ApiError::bad_request(ErrorCode::InvalidRequest, e.body_text())
+            })
+    }
+}
+
+/// JSON body extractor that rejects with a problem instead of plain text.
+pub(crate) struct ApiJson<T>(pub(crate) T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequest<S> for ApiJson<T> {
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        axum::Json::from_request(req, state)
+            .await
+            .map(|axum::Json(value)| Self(value))
+            .map_err(|e: JsonRejection| {
+                ApiError::new(e.status(), ErrorCode::InvalidRequest, e.body_text())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body_json(response: Response) -> Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn renders_problem_json() {
+        let response = ApiError::invalid_range(200, 100).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CONTENT_TYPE);
+        let body = body_json(response).await;
+        assert_eq!(body["type"], "about:blank");
+        assert_eq!(body["title"], "Bad Request");
+        assert_eq!(body["status"], 400);
+        assert_eq!(body["code"], "invalid_range");
+        assert_eq!(body["detail"], "end must be after start");
+        assert_eq!(body["start"], 200);
+        assert_eq!(body["end"], 100);
+    }
+
+    #[tokio::test]
+    async fn query_rejection_is_problem() {
+        #[derive(serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Range {
+            start: i64,
+        }
+        let request = axum::http::Request::builder()
+            .uri("/api/v1/analysis?start=yesterday")
+            .body(())
+            .unwrap();
+        let (mut parts, ()) = request.into_parts();
+        let err = ApiQuery::<Range>::from_request_parts(&mut parts, &())
+            .await
+            .err()
+            .unwrap();
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = body_json(response).await;
+        assert_eq!(body["code"], "invalid_request");
+        assert!(body["detail"].as_str().unwrap().contains("start"));
+    }
+}
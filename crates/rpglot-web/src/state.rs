@@ -27,20 +27,67 @@ pub(crate) fn release_memory_to_os() {
 #[cfg(target_env = "msvc")]
 pub(crate) fn release_memory_to_os() {}
 
+/// Bytes allocated and resident according to jemalloc, refreshed on each call.
+#[cfg(not(target_env = "msvc"))]
+pub(crate) fn allocator_stats() -> Option<AllocatorStats> {
+    fn read(name: &std::ffi::CStr) -> Option<u64> {
+        let mut value: usize = 0;
+        let mut len = std::mem::size_of::<usize>();
+        let rc = unsafe {
+            tikv_jemalloc_sys::mallctl(
+                name.as_ptr(),
+                (&raw mut value).cast(),
+                &mut len,
+                ptr::null_mut(),
+                0,
+            )
+        };
+        (rc == 0).then_some(value as u64)
+    }
+
+    // Statistics are a snapshot taken at the last epoch bump
+    let mut epoch: u64 = 1;
+    let len = std::mem::size_of::<u64>();
+    unsafe {
+        tikv_jemalloc_sys::mallctl(
+            c"epoch".as_ptr(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            (&raw mut epoch).cast(),
+            len,
+        );
+    }
+    Some(AllocatorStats {
+        allocated_bytes: read(c"stats.allocated")?,
+        resident_bytes: read(c"stats.resident")?,
+    })
+}
+
+#[cfg(target_env = "msvc")]
+pub(crate) fn allocator_stats() -> Option<AllocatorStats> {
+    None
+}
+
 use std::collections::HashMap;
 use std::ptr;
 use std::sync::atomic::{AtomicI64, AtomicUsize};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, LazyLock, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use axum::extract::State;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
 
+use rpglot_core::api::buffercache::BufferCacheReport;
 use rpglot_core::api::snapshot::ApiSnapshot;
+use rpglot_core::collector::RedactMode;
 use rpglot_core::provider::SnapshotProvider;
 use rpglot_core::rates::{PgiRateState, PgpRateState, PgsRateState, PgtRateState};
+use rpglot_core::storage::annotations::AnnotationStore;
+use rpglot_core::storage::bookmarks::BookmarkStore;
 use rpglot_core::storage::heatmap::HeatmapBucket;
-use rpglot_core::storage::model::Snapshot;
+use rpglot_core::storage::model::{K8sPodInfo, PgExtensionInfo, Snapshot};
+
+use crate::metrics::AllocatorStats;
 
 #[derive(Clone, Copy, PartialEq)]
 pub(crate) enum Mode {
@@ -72,16 +119,36 @@ pub(crate) struct WebAppInner {
     pub(crate) instance_info: Option<(String, String, Option<bool>)>,
     // Machine hostname, obtained at startup.
     pub(crate) hostname: String,
+    // Kubernetes pod metadata from the latest live snapshot (container mode).
+    pub(crate) k8s_pod: Option<K8sPodInfo>,
+    // Installed extensions from the latest live sample (taken hourly).
+    pub(crate) extensions: Vec<PgExtensionInfo>,
+    // Latest pg_buffercache sample seen in live mode (taken every 5 minutes).
+    pub(crate) buffer_cache: Option<BufferCacheReport>,
+    // Bookmarks sidecar of the history data directory (history mode only).
+    pub(crate) bookmarks: Option<BookmarkStore>,
+    // Annotations sidecar (deployments, maintenance) of the history data directory.
+    pub(crate) annotations: Option<AnnotationStore>,
+    // Query text redaction: collector flag (live) or the daemon's marker (history).
+    pub(crate) redaction: RedactMode,
 }
 
 pub(crate) type SharedState = Arc<Mutex<WebAppInner>>;
 
-pub(crate) type AppState = State<(SharedState, broadcast::Sender<Arc<ApiSnapshot>>)>;
+/// Router state: application state and the live snapshot broadcast.
+pub(crate) type RouterState = (SharedState, broadcast::Sender<Arc<ApiSnapshot>>);
+
+pub(crate) type AppState = State<RouterState>;
 
 pub(crate) static LAST_CLIENT_ACTIVITY: AtomicI64 = AtomicI64::new(0);
 
 pub(crate) static SSE_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
 
+/// Set to `true` on SIGTERM / Ctrl-C: the tick loop stops after the current
+/// tick and SSE streams send a final `shutdown` event and close.
+pub(crate) static SHUTDOWN: LazyLock<watch::Sender<bool>> =
+    LazyLock::new(|| watch::Sender::new(false));
+
 pub(crate) fn now_epoch() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
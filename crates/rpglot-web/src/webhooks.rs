@@ -0,0 +1,306 @@
+//! Webhook alerts: incremental analysis of newly arrived history snapshots,
+//! POSTing new Critical incidents to configured URLs as raw JSON or as
+//! Slack / Mattermost messages (see [`crate::notify`]).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use rpglot_core::analysis::{AnalysisReport, Analyzer, Incident, Severity};
+use rpglot_core::provider::{HistoryProvider, ProviderError};
+
+use crate::background::{ensure_history_ready, refresh_history};
+use crate::notify::{self, NotifyGroup, WebhookFormat};
+use crate::state::SharedState;
+
+/// Snapshots before the new ones that are analyzed again, so EWMA baselines
+/// warm up and incidents spanning the boundary keep their start.
+const WARMUP_SECS: i64 = 600;
+
+/// An incident of the same rule and entity starting within this gap after
+/// the last reported one is a continuation, not a new incident.
+const DEDUP_GAP_SECS: i64 = 300;
+
+/// Incidents not delivered to every URL are looked for again on the next
+/// passes, at most this far back.
+const RETRY_SECS: i64 = 3600;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// JSON body POSTed to every webhook URL.
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    source: &'static str,
+    hostname: &'a str,
+    /// Analyzed range (epoch seconds).
+    start_ts: i64,
+    end_ts: i64,
+    incidents: Vec<&'a Incident>,
+}
+
+/// Remembers incidents reported to one URL per (rule_id, merge key) so an
+/// incident that keeps firing across analysis passes is reported once.
+#[derive(Default)]
+struct IncidentTracker {
+    last_seen: HashMap<(String, Option<String>), i64>,
+}
+
+impl IncidentTracker {
+    /// Returns true if the incident was not reported yet. Extends the known
+    /// end of an already reported incident it continues.
+    fn is_new(&mut self, incident: &Incident) -> bool {
+        let key = (incident.rule_id.clone(), incident.merge_key.clone());
+        let Some(last_ts) = self.last_seen.get_mut(&key) else {
+            return true;
+        };
+        if incident.first_ts > *last_ts + DEDUP_GAP_SECS {
+            return true;
+        }
+        *last_ts = (*last_ts).max(incident.last_ts);
+        false
+    }
+
+    /// Records the incident as delivered.
+    fn mark_reported(&mut self, incident: &Incident) {
+        let key = (incident.rule_id.clone(), incident.merge_key.clone());
+        let last_ts = self.last_seen.entry(key).or_insert(incident.last_ts);
+        *last_ts = (*last_ts).max(incident.last_ts);
+    }
+
+    /// Forgets incidents that ended long before `ts`.
+    fn prune(&mut self, ts: i64) {
+        self.last_seen
+            .retain(|_, last_ts| *last_ts + DEDUP_GAP_SECS + WARMUP_SECS >= ts);
+    }
+}
+
+/// Background loop: every `interval`, refresh history from disk, analyze the
+/// new snapshots and POST new Critical incidents to `urls` in `format`.
+/// Chat messages link to the web UI at `public_url`, if set.
+///
+/// Starts from the latest snapshot at startup (old incidents are not sent).
+/// An incident counts as reported to a URL only after a 2xx response from it;
+/// failed deliveries are retried on the next passes. Keeps the history
+/// provider initialized even without web clients; the analysis itself runs on
+/// a separate provider outside the state lock.
+pub(crate) async fn webhook_loop(
+    state: SharedState,
+    path: PathBuf,
+    urls: Vec<String>,
+    interval: Duration,
+    format: WebhookFormat,
+    public_url: Option<String>,
+) {
+    let client = match reqwest::Client::builder().timeout(SEND_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!(error = %e, "failed to build webhook HTTP client, webhooks disabled");
+            return;
+        }
+    };
+    let mut tick = tokio::time::interval(interval);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut analyzed_until: Option<i64> = None;
+    let mut trackers: Vec<IncidentTracker> =
+        urls.iter().map(|_| IncidentTracker::default()).collect();
+    let mut own_provider: Option<HistoryProvider> = None;
+
+    loop {
+        tick.tick().await;
+
+        let state_clone = state.clone();
+        let path_clone = path.clone();
+        let since = analyzed_until;
+        let provider = own_provider.take();
+        let result = tokio::task::spawn_blocking(move || {
+            let hostname = {
+                let mut inner = state_clone.lock().unwrap();
+                if !ensure_history_ready(&mut inner) {
+                    return (provider, None);
+                }
+                if let Err(e) = refresh_history(&mut inner, &path_clone) {
+                    warn!(error = %e, "webhook: history refresh failed");
+                }
+                inner.hostname.clone()
+            };
+            let mut hp = match analysis_provider(provider, &path_clone) {
+                Ok(hp) => hp,
+                Err(e) => {
+                    warn!(error = %e, "webhook: history provider unavailable");
+                    return (None, None);
+                }
+            };
+            let (_, end_ts) = hp.timestamp_range();
+            let Some(since) = since else {
+                return (Some(hp), Some((end_ts, None)));
+            };
+            if end_ts <= since {
+                return (Some(hp), Some((since, None)));
+            }
+            let start_ts = since - WARMUP_SECS;
+            let report = Analyzer::new().analyze(&mut hp, start_ts, end_ts, &[]);
+            (Some(hp), Some((end_ts, Some((report, hostname, since)))))
+        })
+        .await;
+
+        let Ok((provider, Some((end_ts, analysis)))) = result else {
+            continue;
+        };
+        own_provider = provider;
+        let Some((report, hostname, since)) = analysis else {
+            analyzed_until = Some(end_ts);
+            continue;
+        };
+
+        let mut delivered = true;
+        for (url, tracker) in urls.iter().zip(trackers.iter_mut()) {
+            let groups: Vec<NotifyGroup> = report
+                .groups
+                .iter()
+                .map(|group| NotifyGroup {
+                    group,
+                    incidents: group
+                        .incidents
+                        .iter()
+                        .filter(|i| i.severity == Severity::Critical && i.last_ts > since)
+                        .filter(|i| tracker.is_new(i))
+                        .collect(),
+                })
+                .filter(|g| !g.incidents.is_empty())
+                .collect();
+            if groups.is_empty() {
+                continue;
+            }
+            let count: usize = groups.iter().map(|g| g.incidents.len()).sum();
+
+            let body = webhook_body(format, &report, &hostname, &groups, public_url.as_deref());
+            match client.post(url).json(&body).send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    info!(url = %url, incidents = count, "webhook sent");
+                    for incident in groups.iter().flat_map(|g| g.incidents.iter()) {
+                        tracker.mark_reported(incident);
+                    }
+                }
+                Ok(resp) => {
+                    warn!(url = %url, status = %resp.status(), "webhook rejected");
+                    delivered = false;
+                }
+                Err(e) => {
+                    warn!(url = %url, error = %e, "webhook failed");
+                    delivered = false;
+                }
+            }
+        }
+
+        // Undelivered incidents are analyzed again on the next pass
+        let until = if delivered {
+            end_ts
+        } else {
+            since.max(end_ts - RETRY_SECS)
+        };
+        analyzed_until = Some(until);
+        for tracker in &mut trackers {
+            tracker.prune(until);
+        }
+    }
+}
+
+/// Opens or refreshes the loop's own history provider, so the analysis runs
+/// without holding the shared state lock.
+fn analysis_provider(
+    provider: Option<HistoryProvider>,
+    path: &Path,
+) -> Result<HistoryProvider, ProviderError> {
+    let mut hp = match provider {
+        Some(hp) => hp,
+        None => {
+            let mut hp = HistoryProvider::from_path_lazy(path)?;
+            // Analysis reads each snapshot once per pass
+            hp.set_snapshot_cache_bytes(0);
+            hp
+        }
+    };
+    if hp.is_initialized() {
+        hp.refresh(path)?;
+    } else {
+        hp.ensure_initialized()?;
+    }
+    Ok(hp)
+}
+
+/// Request body for `groups` in the configured format.
+fn webhook_body(
+    format: WebhookFormat,
+    report: &AnalysisReport,
+    hostname: &str,
+    groups: &[NotifyGroup],
+    public_url: Option<&str>,
+) -> serde_json::Value {
+    match format {
+        WebhookFormat::Json => serde_json::to_value(WebhookPayload {
+            source: "rpglot",
+            hostname,
+            start_ts: report.start_ts,
+            end_ts: report.end_ts,
+            incidents: groups.iter().flat_map(|g| g.incidents.clone()).collect(),
+        })
+        .unwrap_or_default(),
+        WebhookFormat::Slack => notify::slack_message(hostname, groups, public_url),
+        WebhookFormat::Mattermost => notify::mattermost_message(hostname, groups, public_url),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpglot_core::analysis::Category;
+
+    fn incident(rule_id: &str, key: &str, first_ts: i64, last_ts: i64) -> Incident {
+        Incident {
+            rule_id: rule_id.to_string(),
+            category: Category::Cpu,
+            severity: Severity::Critical,
+            first_ts,
+            last_ts,
+            merge_key: Some(key.to_string()),
+            peak_ts: first_ts,
+            peak_value: 0.0,
+            title: String::new(),
+            detail: None,
+            snapshot_count: 1,
+            entity_id: None,
+            planid: None,
+        }
+    }
+
+    #[test]
+    fn tracker_reports_each_incident_once() {
+        let mut tracker = IncidentTracker::default();
+        let first = incident("cpu_high", "", 1000, 1100);
+        assert!(tracker.is_new(&first));
+        tracker.mark_reported(&first);
+        // Next pass sees the same incident, extended
+        assert!(!tracker.is_new(&incident("cpu_high", "", 1000, 1300)));
+        // Starts again shortly after: continuation
+        assert!(!tracker.is_new(&incident("cpu_high", "", 1500, 1550)));
+        // Other entity of the same rule is separate
+        assert!(tracker.is_new(&incident("cpu_high", "pid 42", 1500, 1550)));
+        // Well after the last one: new incident
+        assert!(tracker.is_new(&incident("cpu_high", "", 2000, 2010)));
+
+        tracker.prune(10_000);
+        assert!(tracker.last_seen.is_empty());
+    }
+
+    #[test]
+    fn tracker_keeps_undelivered_incident_new() {
+        let mut tracker = IncidentTracker::default();
+        let failed = incident("cpu_high", "", 1000, 1100);
+        assert!(tracker.is_new(&failed));
+        // Delivery failed, not marked: the retry reports it again
+        assert!(tracker.is_new(&incident("cpu_high", "", 1000, 1300)));
+    }
+}
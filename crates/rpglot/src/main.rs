@@ -7,10 +7,12 @@
 //! Usage:
 //!   rpglot              # live mode with 1 second interval
 //!   rpglot 5            # live mode with 5 second interval
+//!   rpglot --plain 10   # text summary every 10 seconds, no TUI
 //!   rpglot -r           # history mode (default: /var/log/rpglot)
 //!   rpglot -r ./data    # history mode with custom path
 //!   rpglot -r -b -1h    # history mode starting from 1 hour ago
 //!   rpglot -r -b 07:00  # history mode starting from today 07:00 UTC
+//!   rpglot report -r ./data --start -2h --format markdown  # top consumers
 
 #[cfg(not(target_env = "msvc"))]
 use tikv_jemallocator::Jemalloc;
@@ -19,6 +21,8 @@ use tikv_jemallocator::Jemalloc;
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
+use std::io::{self, Write};
+use std::path::PathBuf;
 use std::process;
 use std::thread;
 use std::time::Duration;
@@ -26,14 +30,23 @@ use std::time::Duration;
 use clap::Parser;
 use rpglot_core::util::print_pg_warning;
 
+#[cfg(all(
+    not(target_os = "linux"),
+    any(target_os = "freebsd", feature = "sysinfo")
+))]
+use rpglot_core::collector::HostFs;
 #[cfg(target_os = "linux")]
 use rpglot_core::collector::RealFs;
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", feature = "sysinfo")))]
 use rpglot_core::collector::mock::MockFs;
-use rpglot_core::collector::{Collector, PostgresCollector};
+use rpglot_core::collector::{Collector, PostgresCollector, load_custom_queries};
+use rpglot_core::fmt::{DisplayTz, FmtConfig};
 use rpglot_core::provider::{HistoryProvider, LiveProvider, SnapshotProvider};
-use rpglot_core::tui::App;
-use rpglot_core::util::parse_time;
+use rpglot_core::storage::crypto;
+use rpglot_core::tui::{App, plain_summary};
+use rpglot_core::util::{parse_duration, parse_time};
+
+mod report;
 
 /// Default path for history data.
 const DEFAULT_HISTORY_PATH: &str = "/var/log/rpglot";
@@ -41,7 +54,11 @@ const DEFAULT_HISTORY_PATH: &str = "/var/log/rpglot";
 /// Interactive TUI viewer for system metrics.
 #[derive(Parser)]
 #[command(name = "rpglot", about = "System metrics viewer", version = rpglot_core::VERSION)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Update interval in seconds (default: 1).
     /// Only used in live mode.
     #[arg(value_name = "INTERVAL")]
@@ -74,11 +91,102 @@ struct Args {
     /// Useful for testing when container detection fails.
     #[arg(long)]
     force_cgroup: bool,
+
+    /// Print a text summary every interval instead of running the TUI:
+    /// no alternate screen, safe for pipes, `watch` and CI logs.
+    #[arg(long, conflicts_with = "history")]
+    plain: bool,
+
+    /// Color theme: dark, light, high-contrast, a theme defined in
+    /// ~/.config/rpglot/themes.toml, or a path to a theme TOML file.
+    /// Press L in the TUI to cycle themes.
+    #[arg(long, value_name = "NAME|PATH")]
+    theme: Option<String>,
+
+    /// Step of , and . in history mode: 90s, 15m, 1h, 1d.
+    #[arg(long, value_name = "DURATION", default_value = "15m", value_parser = parse_large_step)]
+    large_step: i64,
+
+    /// Where E exports the current table. {tab}, {view} and {time} are
+    /// replaced; a .json extension writes JSON, anything else CSV.
+    #[arg(long, value_name = "TEMPLATE")]
+    export_path: Option<String>,
+
+    /// Besides the OSC 52 escape sequence (works over SSH), also copy with
+    /// y through a local clipboard tool: wl-copy, xclip, xsel or pbcopy.
+    #[arg(long)]
+    native_clipboard: bool,
+
+    /// Minimum relative change, in percent, of a numeric cell for watch
+    /// mode (*) to highlight it. 0 highlights every change.
+    #[arg(long, value_name = "PCT", default_value_t = 0.0)]
+    watch_threshold: f64,
+
+    /// Show numbers with a decimal comma ("1,5G") instead of a point.
+    #[arg(long, global = true)]
+    decimal_comma: bool,
+
+    /// Show sizes in SI units (1 kB = 1000 B) instead of binary ones
+    /// (1 KiB = 1024 B).
+    #[arg(long, global = true)]
+    si_units: bool,
+
+    /// Time zone of displayed timestamps: `local` or an IANA name such as
+    /// `Europe/Moscow`.
+    #[arg(long, global = true, value_name = "ZONE", default_value = "local")]
+    tz: DisplayTz,
+
+    /// Same as `--tz UTC`.
+    #[arg(long, global = true, conflicts_with = "tz")]
+    utc: bool,
+
+    /// AES-256 key for encrypted history data (32 raw bytes or 64 hex
+    /// characters). Defaults to the RPGLOT_ENCRYPTION_KEY variable.
+    #[arg(long, global = true, value_name = "PATH")]
+    encryption_key_file: Option<PathBuf>,
+
+    /// TOML file with user-defined SQL metric queries, shown in the CUS tab
+    /// (live mode). In history mode the daemon's results are shown.
+    #[arg(long, value_name = "PATH")]
+    custom_queries: Option<PathBuf>,
+
+    /// Memory budget in MiB for decoded history snapshots, so stepping back
+    /// and forth does not decompress them again (0 disables the cache).
+    #[arg(long, value_name = "MIB", default_value = "64")]
+    snapshot_cache_mb: usize,
+
+    /// Minutes of live snapshots kept in memory, so b/f (or the arrows) can
+    /// step back and forth without history data (0 disables the buffer).
+    #[arg(long, value_name = "MINUTES", default_value = "5")]
+    live_buffer_minutes: u64,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    Report(report::ReportArgs),
 }
 
 fn main() {
     let args = Args::parse();
 
+    rpglot_core::fmt::set_config(FmtConfig {
+        decimal_comma: args.decimal_comma,
+        si_units: args.si_units,
+        timezone: if args.utc { DisplayTz::UTC } else { args.tz },
+    });
+
+    if let Some(Command::Report(ref report_args)) = args.command {
+        if let Err(e) = crypto::install_from(args.encryption_key_file.as_deref()) {
+            eprintln!("Error loading encryption key: {}", e);
+            process::exit(1);
+        }
+        if let Err(e) = report::run(report_args) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
     // Validate arguments
     if args.history.is_some() && args.interval.is_some() {
         eprintln!("Error: cannot specify interval in history mode");
@@ -106,6 +214,11 @@ fn main() {
         None
     };
 
+    if let Err(e) = crypto::install_from(args.encryption_key_file.as_deref()) {
+        eprintln!("Error loading encryption key: {}", e);
+        process::exit(1);
+    }
+
     // Create provider based on mode
     let provider: Box<dyn SnapshotProvider> = if let Some(ref path_opt) = args.history {
         // History mode
@@ -122,7 +235,10 @@ fn main() {
         };
 
         match provider_result {
-            Ok(p) => Box::new(p),
+            Ok(mut p) => {
+                p.set_snapshot_cache_bytes(args.snapshot_cache_mb * 1024 * 1024);
+                Box::new(p)
+            }
             Err(e) => {
                 eprintln!("Error loading history from '{}': {}", path, e);
                 process::exit(1);
@@ -130,6 +246,20 @@ fn main() {
         }
     } else {
         // Live mode
+        let custom_queries = match args.custom_queries {
+            Some(ref path) => match load_custom_queries(path) {
+                Ok(queries) => queries,
+                Err(e) => {
+                    eprintln!(
+                        "Error loading custom queries from '{}': {}",
+                        path.display(),
+                        e
+                    );
+                    process::exit(1);
+                }
+            },
+            None => Vec::new(),
+        };
         #[cfg(target_os = "linux")]
         let collector = {
             let fs = RealFs::new();
@@ -137,7 +267,12 @@ fn main() {
             // Enable PostgreSQL collection if PGUSER is set
             // In live mode, disable pg_stat_statements caching for real-time data
             if let Ok(pg_collector) = PostgresCollector::from_env() {
-                c = c.with_postgres(pg_collector.with_statements_interval(Duration::ZERO));
+                c = c.with_postgres(
+                    pg_collector
+                        .with_statements_interval(Duration::ZERO)
+                        .with_tables_interval(Duration::ZERO)
+                        .with_custom_queries(custom_queries),
+                );
             }
             // Handle cgroup options
             if let Some(ref cgroup_path) = args.cgroup_path {
@@ -149,12 +284,20 @@ fn main() {
         };
         #[cfg(not(target_os = "linux"))]
         let collector = {
+            #[cfg(any(target_os = "freebsd", feature = "sysinfo"))]
+            let fs = HostFs::new();
+            #[cfg(not(any(target_os = "freebsd", feature = "sysinfo")))]
             let fs = MockFs::typical_system();
             let mut c = Collector::new(fs, &args.proc_path);
             // Enable PostgreSQL collection if PGUSER is set
             // In live mode, disable pg_stat_statements caching for real-time data
             if let Ok(pg_collector) = PostgresCollector::from_env() {
-                c = c.with_postgres(pg_collector.with_statements_interval(Duration::ZERO));
+                c = c.with_postgres(
+                    pg_collector
+                        .with_statements_interval(Duration::ZERO)
+                        .with_tables_interval(Duration::ZERO)
+                        .with_custom_queries(custom_queries),
+                );
             }
             // Handle cgroup options
             if let Some(ref cgroup_path) = args.cgroup_path {
@@ -164,17 +307,46 @@ fn main() {
             }
             c
         };
-        Box::new(LiveProvider::new(collector, None))
+        let capacity = if args.plain {
+            0
+        } else {
+            args.live_buffer_minutes * 60 / args.interval.unwrap_or(1).max(1)
+        };
+        Box::new(LiveProvider::new(collector, None).with_buffer(capacity as usize))
     };
 
+    let tick_rate = Duration::from_secs(args.interval.unwrap_or(1));
+    if args.plain {
+        // A closed pipe (`| head`) just ends the stream
+        if let Err(e) = run_plain(provider, tick_rate)
+            && e.kind() != io::ErrorKind::BrokenPipe
+        {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
     // Check PostgreSQL connection in live mode
     if args.history.is_none() {
         check_postgres_connection();
     }
 
     // Create and run TUI
-    let tick_rate = Duration::from_secs(args.interval.unwrap_or(1));
-    let app = App::new(provider);
+    let mut app = App::new(provider);
+    if let Some(ref theme) = args.theme
+        && let Err(e) = app.set_theme(theme)
+    {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+
+    if let Some(template) = args.export_path {
+        app.set_export_template(template);
+    }
+    app.set_native_clipboard(args.native_clipboard);
+    app.set_large_step(args.large_step);
+    app.set_watch_threshold(args.watch_threshold);
 
     if let Err(e) = app.run(tick_rate) {
         eprintln!("Error running TUI: {}", e);
@@ -182,6 +354,32 @@ fn main() {
     }
 }
 
+/// Prints the summary of a new live snapshot every `interval` until stdout
+/// is closed. The first snapshot is only the baseline for rates.
+fn run_plain(mut provider: Box<dyn SnapshotProvider>, interval: Duration) -> io::Result<()> {
+    let mut previous = provider.advance().cloned();
+    loop {
+        thread::sleep(interval);
+        let Some(snapshot) = provider.advance().cloned() else {
+            if let Some(e) = provider.last_error() {
+                eprintln!("Error: {}", e);
+            }
+            continue;
+        };
+        let mut out = io::stdout().lock();
+        for line in plain_summary(&snapshot, previous.as_ref()) {
+            writeln!(out, "{line}")?;
+        }
+        writeln!(out)?;
+        out.flush()?;
+        previous = Some(snapshot);
+    }
+}
+
+fn parse_large_step(s: &str) -> Result<i64, String> {
+    parse_duration(s).map_err(|e| e.to_string())
+}
+
 /// Checks PostgreSQL connection at startup.
 ///
 /// If connection fails or environment is not configured, prints recommendations
@@ -0,0 +1,286 @@
+//! `rpglot report` — top consumers over a history range, printed once.
+//!
+//! Queries by total time, IO and temp, tables by rows read and modified,
+//! indexes by scans; as an aligned text table, Markdown or JSON.
+
+use std::io::Write;
+
+use rpglot_core::api::top_consumers::{
+    IndexTotals, QueryTotals, TableTotals, TopConsumersReport, top_consumers,
+};
+use rpglot_core::fmt::{FmtStyle, format_bytes, format_duration, format_ms, format_timestamp};
+use rpglot_core::provider::HistoryProvider;
+use rpglot_core::util::parse_time;
+
+use crate::DEFAULT_HISTORY_PATH;
+
+/// Range length when `--start` is not given.
+const DEFAULT_RANGE_SECS: i64 = 3600;
+
+/// Characters of query text shown in text and Markdown output.
+const QUERY_WIDTH: usize = 80;
+
+/// Print the top queries, tables and indexes over a history range.
+#[derive(clap::Args)]
+pub struct ReportArgs {
+    /// Path to history data.
+    #[arg(short = 'r', long = "history", value_name = "PATH", default_value = DEFAULT_HISTORY_PATH)]
+    history: String,
+
+    /// Start of the range, in the formats of --begin.
+    /// Default: one hour before the end.
+    #[arg(long, value_name = "TIME")]
+    start: Option<String>,
+
+    /// End of the range, in the formats of --begin.
+    /// Default: the last recorded snapshot.
+    #[arg(long, value_name = "TIME")]
+    end: Option<String>,
+
+    /// Entries per list.
+    #[arg(long, value_name = "N", default_value_t = 20)]
+    top: usize,
+
+    /// Output format.
+    #[arg(long, value_name = "FORMAT", default_value = "text", value_parser = ["text", "markdown", "json"])]
+    format: String,
+}
+
+/// Builds the report and prints it to stdout.
+pub fn run(args: &ReportArgs) -> Result<(), String> {
+    let mut provider = HistoryProvider::from_path(&args.history)
+        .map_err(|e| format!("loading history from '{}': {}", args.history, e))?;
+    let last = provider.timestamps().last().copied().unwrap_or(0);
+    let end_ts = match args.end {
+        Some(ref s) => parse_time(s).map_err(|e| e.to_string())?,
+        None => last,
+    };
+    let start_ts = match args.start {
+        Some(ref s) => parse_time(s).map_err(|e| e.to_string())?,
+        None => end_ts - DEFAULT_RANGE_SECS,
+    };
+    if start_ts >= end_ts {
+        return Err("--start must be before --end".to_string());
+    }
+
+    let report = top_consumers(&mut provider, start_ts, end_ts, args.top);
+    let out = match args.format.as_str() {
+        "json" => serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?,
+        "markdown" => render_markdown(&report),
+        _ => render_text(&report),
+    };
+    // A closed pipe (`| head`) is not an error for a one-shot report
+    match writeln!(std::io::stdout(), "{out}") {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// One list of the report as rows of formatted cells: `numeric` columns of
+/// numbers (right-aligned), then text columns.
+struct Section {
+    title: &'static str,
+    headers: &'static [&'static str],
+    numeric: usize,
+    rows: Vec<Vec<String>>,
+}
+
+fn sections(report: &TopConsumersReport) -> Vec<Section> {
+    vec![
+        Section {
+            title: "Queries by total time",
+            headers: &["TIME", "CALLS", "MEAN", "ROWS", "DATABASE", "QUERY"],
+            numeric: 4,
+            rows: report
+                .queries_by_time
+                .iter()
+                .map(|q| {
+                    let mean = if q.calls > 0.0 {
+                        q.exec_time_ms / q.calls
+                    } else {
+                        0.0
+                    };
+                    vec![
+                        format_ms(q.exec_time_ms, FmtStyle::Compact),
+                        count(q.calls),
+                        format_ms(mean, FmtStyle::Compact),
+                        count(q.rows),
+                        q.database.clone(),
+                        query_text(q),
+                    ]
+                })
+                .collect(),
+        },
+        Section {
+            title: "Queries by IO",
+            headers: &["READ", "WRITTEN", "TIME", "DATABASE", "QUERY"],
+            numeric: 3,
+            rows: report
+                .queries_by_io
+                .iter()
+                .map(|q| {
+                    vec![
+                        bytes(q.shared_read_bytes),
+                        bytes(q.shared_written_bytes),
+                        format_ms(q.exec_time_ms, FmtStyle::Compact),
+                        q.database.clone(),
+                        query_text(q),
+                    ]
+                })
+                .collect(),
+        },
+        Section {
+            title: "Queries by temp files",
+            headers: &["TEMP", "CALLS", "TIME", "DATABASE", "QUERY"],
+            numeric: 3,
+            rows: report
+                .queries_by_temp
+                .iter()
+                .map(|q| {
+                    vec![
+                        bytes(q.temp_bytes),
+                        count(q.calls),
+                        format_ms(q.exec_time_ms, FmtStyle::Compact),
+                        q.database.clone(),
+                        query_text(q),
+                    ]
+                })
+                .collect(),
+        },
+        Section {
+            title: "Tables by rows read and modified",
+            headers: &[
+                "ROWS READ",
+                "MODIFIED",
+                "SEQ SCANS",
+                "IDX SCANS",
+                "DISK READ",
+                "TABLE",
+            ],
+            numeric: 5,
+            rows: report.tables.iter().map(table_row).collect(),
+        },
+        Section {
+            title: "Indexes by scans",
+            headers: &["SCANS", "TUP READ", "DISK READ", "INDEX"],
+            numeric: 3,
+            rows: report.indexes.iter().map(index_row).collect(),
+        },
+    ]
+}
+
+fn table_row(t: &TableTotals) -> Vec<String> {
+    vec![
+        count(t.seq_tup_read + t.idx_tup_fetch),
+        count(t.tup_modified),
+        count(t.seq_scan),
+        count(t.idx_scan),
+        bytes(t.read_bytes),
+        format!("{}.{}.{}", t.database, t.schema, t.name),
+    ]
+}
+
+fn index_row(i: &IndexTotals) -> Vec<String> {
+    vec![
+        count(i.idx_scan),
+        count(i.idx_tup_read),
+        bytes(i.read_bytes),
+        format!("{}.{}.{} on {}", i.database, i.schema, i.name, i.table),
+    ]
+}
+
+fn heading(report: &TopConsumersReport) -> String {
+    let ts = |ts| format_timestamp(ts, "%Y-%m-%d %H:%M:%S %Z").unwrap_or_else(|| ts.to_string());
+    format!(
+        "Top consumers {} \u{2014} {} (pg_stat_statements covers {})",
+        ts(report.start_ts),
+        ts(report.end_ts),
+        format_duration(report.statements_covered_s as i64, FmtStyle::Detail),
+    )
+}
+
+fn render_text(report: &TopConsumersReport) -> String {
+    let mut out = heading(report);
+    for section in sections(report) {
+        out.push_str(&format!("\n\n{}\n", section.title));
+        if section.rows.is_empty() {
+            out.push_str("  (no data)");
+            continue;
+        }
+        let mut widths: Vec<usize> = section.headers.iter().map(|h| h.len()).collect();
+        for row in &section.rows {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(cell.chars().count());
+            }
+        }
+        let last = widths.len() - 1;
+        let line = |cells: &mut dyn Iterator<Item = &str>| {
+            let mut line = String::from(" ");
+            for (i, cell) in cells.enumerate() {
+                let w = widths[i];
+                match i {
+                    _ if i == last => line.push_str(&format!(" {cell}")),
+                    _ if i < section.numeric => line.push_str(&format!(" {cell:>w$}")),
+                    _ => line.push_str(&format!(" {cell:<w$}")),
+                }
+            }
+            line
+        };
+        out.push_str(&line(&mut section.headers.iter().copied()));
+        for row in &section.rows {
+            out.push('\n');
+            out.push_str(&line(&mut row.iter().map(String::as_str)));
+        }
+    }
+    out
+}
+
+fn render_markdown(report: &TopConsumersReport) -> String {
+    let mut out = format!("# {}", heading(report));
+    for section in sections(report) {
+        out.push_str(&format!("\n\n## {}\n\n", section.title));
+        if section.rows.is_empty() {
+            out.push_str("No data.");
+            continue;
+        }
+        out.push_str(&format!("| {} |\n", section.headers.join(" | ")));
+        let align: Vec<&str> = (0..section.headers.len())
+            .map(|i| if i < section.numeric { "---:" } else { "---" })
+            .collect();
+        out.push_str(&format!("| {} |", align.join(" | ")));
+        for row in &section.rows {
+            let cells: Vec<String> = row.iter().map(|c| c.replace('|', "\\|")).collect();
+            out.push_str(&format!("\n| {} |", cells.join(" | ")));
+        }
+    }
+    out
+}
+
+/// Query text on one line, cut to [`QUERY_WIDTH`] characters.
+fn query_text(q: &QueryTotals) -> String {
+    let text = if q.query.is_empty() {
+        format!("queryid {}", q.queryid)
+    } else {
+        q.query.split_whitespace().collect::<Vec<_>>().join(" ")
+    };
+    if text.chars().count() <= QUERY_WIDTH {
+        return text;
+    }
+    let mut cut: String = text.chars().take(QUERY_WIDTH - 1).collect();
+    cut.push('\u{2026}');
+    cut
+}
+
+fn count(v: f64) -> String {
+    if v >= 1_000_000.0 {
+        format!("{:.1}M", v / 1_000_000.0)
+    } else if v >= 1_000.0 {
+        format!("{:.1}K", v / 1_000.0)
+    } else {
+        format!("{v:.0}")
+    }
+}
+
+fn bytes(v: f64) -> String {
+    format_bytes(v.max(0.0) as u64, FmtStyle::Compact)
+}
@@ -1,15 +1,24 @@
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::OsStr;
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
 use clap::Parser;
 use serde::Serialize;
 
 use rpglot_core::storage::ChunkReader;
+use rpglot_core::storage::chunk::{
+    ChunkWriteOptions, DEFAULT_COMPRESSION_LEVEL, DEFAULT_DICT_SAMPLES, encode_snapshot_frame,
+    migrate_chunk, repair_chunk, train_dictionary,
+};
+use rpglot_core::storage::crypto;
+use rpglot_core::storage::manager::decode_wal_payload;
 use rpglot_core::storage::model::DataBlock;
+use rpglot_core::storage::{Snapshot, StringInterner};
 
 // ── RPG3 chunk format constants (mirrored from rpglot-core::storage::chunk) ──
 
@@ -21,11 +30,12 @@ const INDEX_ENTRY_SIZE: usize = 28;
 
 const WAL_FRAME_HEADER_SIZE: usize = 8;
 const MAX_WAL_ENTRY_SIZE: u32 = 256 * 1024 * 1024;
+const WAL_ENCRYPTED_FLAG: u32 = 1 << 31;
 
 // ── Heatmap format constants ─────────────────────────────────────────────────
 
-const HEATMAP_MAGIC: &[u8; 4] = b"HM04";
-const HEATMAP_ENTRY_SIZE: usize = 15;
+/// Known heatmap versions: (magic, entry size).
+const HEATMAP_FORMATS: [(&[u8; 4], usize); 2] = [(b"HM05", 27), (b"HM04", 15)];
 
 // ── CLI ──────────────────────────────────────────────────────────────────────
 
@@ -42,13 +52,91 @@ struct Cli {
     /// Output as JSON
     #[arg(long)]
     json: bool,
+
+    /// AES-256 key for encrypted storage (32 raw bytes or 64 hex characters).
+    /// Defaults to the RPGLOT_ENCRYPTION_KEY variable.
+    #[arg(long, value_name = "PATH")]
+    encryption_key_file: Option<PathBuf>,
+
+    /// Re-compress a .zst chunk at several zstd levels and report size and
+    /// time for each (rpglotd --compression-level / --dict-samples tuning)
+    #[arg(long)]
+    benchmark_compression: bool,
+
+    /// Levels tried by --benchmark-compression
+    #[arg(long, value_delimiter = ',', default_value = "1,3,6,9,12,19",
+          value_parser = clap::value_parser!(i32).range(1..=22))]
+    levels: Vec<i32>,
+
+    /// Dictionary samples used by --benchmark-compression
+    #[arg(long, default_value_t = DEFAULT_DICT_SAMPLES)]
+    dict_samples: usize,
+
+    /// Print the snapshots of a storage directory, chunk or wal.log as JSON,
+    /// one per line, with interned strings resolved
+    #[arg(long)]
+    extract: bool,
+
+    /// First timestamp (epoch seconds) extracted by --extract
+    #[arg(long, requires = "extract")]
+    start: Option<i64>,
+
+    /// Last timestamp (epoch seconds) extracted by --extract
+    #[arg(long, requires = "extract")]
+    end: Option<i64>,
+
+    /// Blocks printed by --extract, e.g. pg_stat_activity,pg_stat_statements
+    #[arg(long, value_delimiter = ',', requires = "extract")]
+    block: Vec<String>,
+
+    /// Interned string statistics of a storage directory, chunk or wal.log:
+    /// largest strings, duplication across chunks, size by field
+    #[arg(long)]
+    interner: bool,
+
+    /// Largest strings listed by --interner
+    #[arg(long, default_value_t = 20, requires = "interner")]
+    top: usize,
+
+    /// Rewrite damaged chunks from their readable frames and truncate wal.log
+    /// at the first bad frame; originals are kept as .bak. Stop rpglotd first
+    #[arg(long)]
+    repair: bool,
+
+    /// Upgrade chunks written in older formats to the current one, in place
+    /// or into --target-dir. Stop rpglotd first
+    #[arg(long)]
+    migrate: bool,
+
+    /// Write migrated chunks here instead of in place; current chunks,
+    /// heatmaps and wal.log are copied unchanged
+    #[arg(long, value_name = "DIR", requires = "migrate")]
+    target_dir: Option<PathBuf>,
 }
 
 fn main() {
     let cli = Cli::parse();
     let path = cli.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    if let Err(e) = crypto::install_from(cli.encryption_key_file.as_deref()) {
+        eprintln!("Error loading encryption key: {e}");
+        std::process::exit(1);
+    }
 
-    if path.is_dir() {
+    if cli.benchmark_compression {
+        if !has_ext(&path, "zst") {
+            eprintln!("--benchmark-compression expects a .zst chunk");
+            std::process::exit(1);
+        }
+        benchmark_compression(&path, &cli);
+    } else if cli.extract {
+        extract(&path, &cli);
+    } else if cli.interner {
+        interner_stats(&path, &cli);
+    } else if cli.repair {
+        repair(&path);
+    } else if cli.migrate {
+        migrate(&path, cli.target_dir.as_deref());
+    } else if path.is_dir() {
         dump_directory(&path, &cli);
     } else if has_ext(&path, "zst") {
         dump_chunk(&path, &cli);
@@ -66,6 +154,21 @@ fn has_ext(path: &Path, ext: &str) -> bool {
     path.extension().and_then(OsStr::to_str) == Some(ext)
 }
 
+/// Splits a WAL length field into (payload length, encrypted flag).
+fn wal_frame_length(field: u32) -> (u32, bool) {
+    (field & !WAL_ENCRYPTED_FLAG, field & WAL_ENCRYPTED_FLAG != 0)
+}
+
+/// WAL payload ready for postcard, decrypted if needed.
+/// `None` for encrypted frames without a (correct) key.
+fn wal_payload(payload: &[u8], encrypted: bool) -> Option<Cow<'_, [u8]>> {
+    if !encrypted {
+        return Some(Cow::Borrowed(payload));
+    }
+    let key = crypto::installed_key()?;
+    key.decrypt(payload).ok().map(Cow::Owned)
+}
+
 // ── Formatting helpers ───────────────────────────────────────────────────────
 
 fn human_bytes(bytes: u64) -> String {
@@ -136,6 +239,25 @@ fn block_info(block: &DataBlock) -> (&'static str, usize) {
         DataBlock::Cgroup(_) => ("Cgroup", 1),
         DataBlock::PgStorePlans(v) => ("PgStorePlans", v.len()),
         DataBlock::ReplicationStatus(_) => ("ReplicationStatus", 1),
+        DataBlock::PgConnectionChurn(_) => ("PgConnectionChurn", 1),
+        DataBlock::CustomQuery(v) => ("CustomQuery", v.len()),
+        DataBlock::SystemTcpConn(v) => ("SystemTcpConn", v.remotes.len()),
+        DataBlock::KernelEvents(v) => ("KernelEvents", v.len()),
+        DataBlock::SystemdUnit(_) => ("SystemdUnit", 1),
+        DataBlock::PgInstance(i) => ("PgInstance", i.blocks.len()),
+        DataBlock::CollectionDegraded(_) => ("CollectionDegraded", 1),
+        DataBlock::AgentStats(a) => ("AgentStats", a.phases.len()),
+        DataBlock::K8sPod(_) => ("K8sPod", 1),
+        DataBlock::PgConnection(_) => ("PgConnection", 1),
+        DataBlock::PgRelationSizes(v) => ("PgRelationSizes", v.len()),
+        DataBlock::PgSequences(v) => ("PgSequences", v.len()),
+        DataBlock::PgBufferCache(b) => ("PgBufferCache", b.relations.len()),
+        DataBlock::PgStatArchiver(_) => ("PgStatArchiver", 1),
+        DataBlock::Clock(_) => ("Clock", 1),
+        DataBlock::PgSlowPlans(v) => ("PgSlowPlans", v.len()),
+        DataBlock::PgPendingRestart(v) => ("PgPendingRestart", v.len()),
+        DataBlock::PgSettingsMeta(v) => ("PgSettingsMeta", v.len()),
+        DataBlock::PgExtensions(v) => ("PgExtensions", v.len()),
     }
 }
 
@@ -242,6 +364,29 @@ struct BlockJson {
     share_pct: f64,
 }
 
+#[derive(Serialize)]
+struct BenchmarkJson {
+    file: String,
+    file_size: u64,
+    snapshot_count: usize,
+    raw_bytes: u64,
+    dict_samples: usize,
+    dict_train_ms: f64,
+    levels: Vec<LevelJson>,
+}
+
+#[derive(Serialize)]
+struct LevelJson {
+    level: i32,
+    dictionary: u64,
+    snapshot_frames: u64,
+    interner_frame: u64,
+    total: u64,
+    ratio: f64,
+    compress_ms: f64,
+    decompress_ms: f64,
+}
+
 #[derive(Serialize)]
 struct WalJson {
     file: String,
@@ -274,6 +419,36 @@ struct DirectoryJson {
     snapshot_count_wal: u64,
 }
 
+#[derive(Serialize)]
+struct InternerJson {
+    sources: usize,
+    stored_strings: u64,
+    stored_bytes: u64,
+    unique_strings: usize,
+    unique_bytes: u64,
+    shared_strings: usize,
+    duplicate_pct: f64,
+    fields: Vec<InternerFieldJson>,
+    largest: Vec<InternerStringJson>,
+}
+
+#[derive(Serialize)]
+struct InternerFieldJson {
+    field: String,
+    strings: u64,
+    bytes: u64,
+    share_pct: f64,
+}
+
+#[derive(Serialize)]
+struct InternerStringJson {
+    hash: u64,
+    field: String,
+    bytes: usize,
+    sources: usize,
+    text: String,
+}
+
 #[derive(Serialize)]
 struct FileGroupJson {
     count: usize,
@@ -535,6 +710,144 @@ fn stats_json(values: &[u64]) -> StatsJson {
     }
 }
 
+// ── benchmark_compression ────────────────────────────────────────────────────
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// Re-compresses every snapshot of a chunk as rpglotd would write it, once
+/// per level. Sizes are unencrypted; encryption adds a fixed 28 bytes per frame.
+fn benchmark_compression(path: &Path, cli: &Cli) {
+    let file_size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let reader = ChunkReader::open(path).unwrap_or_else(|e| {
+        eprintln!("Error opening chunk: {e}");
+        std::process::exit(1);
+    });
+    let interner = reader.read_interner().unwrap_or_else(|e| {
+        eprintln!("Error reading interner: {e}");
+        std::process::exit(1);
+    });
+    let raw: Vec<Vec<u8>> = (0..reader.snapshot_count())
+        .map(|i| {
+            let snapshot = reader.read_snapshot(i).unwrap_or_else(|e| {
+                eprintln!("Error reading snapshot {i}: {e}");
+                std::process::exit(1);
+            });
+            encode_snapshot_frame(&snapshot).unwrap()
+        })
+        .collect();
+    let raw_interner = postcard::to_allocvec(&interner).unwrap();
+    let raw_bytes = raw.iter().map(|r| r.len() as u64).sum::<u64>() + raw_interner.len() as u64;
+    let header_index_size = (CHUNK_HEADER_SIZE + raw.len() * INDEX_ENTRY_SIZE) as u64;
+
+    // Same sampling as StorageManager when it flushes the WAL
+    let options = ChunkWriteOptions {
+        dict_samples: cli.dict_samples,
+        ..ChunkWriteOptions::default()
+    };
+    let started = Instant::now();
+    let samples: Vec<Vec<u8>> = raw
+        .iter()
+        .step_by(options.sample_interval(raw.len()))
+        .take(options.dict_samples)
+        .cloned()
+        .collect();
+    let dictionary = train_dictionary(&samples);
+    let dict_train = started.elapsed();
+
+    let mut levels = Vec::new();
+    for &level in &cli.levels {
+        let started = Instant::now();
+        let mut compressor = zstd::bulk::Compressor::with_dictionary(level, &dictionary)
+            .unwrap_or_else(|e| {
+                eprintln!("Error creating compressor: {e}");
+                std::process::exit(1);
+            });
+        let frames: Vec<Vec<u8>> = raw
+            .iter()
+            .map(|r| compressor.compress(r).unwrap())
+            .collect();
+        let interner_frame = zstd::encode_all(&raw_interner[..], level).unwrap();
+        let compress = started.elapsed();
+
+        let started = Instant::now();
+        let mut decompressor = zstd::bulk::Decompressor::with_dictionary(&dictionary).unwrap();
+        for (frame, r) in frames.iter().zip(&raw) {
+            decompressor.decompress(frame, r.len()).unwrap();
+        }
+        zstd::decode_all(&interner_frame[..]).unwrap();
+        let decompress = started.elapsed();
+
+        let snapshot_frames: u64 = frames.iter().map(|f| f.len() as u64).sum();
+        let total = header_index_size
+            + dictionary.len() as u64
+            + snapshot_frames
+            + interner_frame.len() as u64;
+        levels.push(LevelJson {
+            level,
+            dictionary: dictionary.len() as u64,
+            snapshot_frames,
+            interner_frame: interner_frame.len() as u64,
+            total,
+            ratio: raw_bytes as f64 / total as f64,
+            compress_ms: ms(compress),
+            decompress_ms: ms(decompress),
+        });
+    }
+
+    if cli.json {
+        let json = BenchmarkJson {
+            file: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into(),
+            file_size,
+            snapshot_count: raw.len(),
+            raw_bytes,
+            dict_samples: samples.len(),
+            dict_train_ms: ms(dict_train),
+            levels,
+        };
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        return;
+    }
+
+    let fname = path.file_name().unwrap_or_default().to_string_lossy();
+    println!("File: {} ({})", fname, human_bytes(file_size));
+    println!(
+        "Snapshots: {}, uncompressed {}",
+        raw.len(),
+        human_bytes(raw_bytes)
+    );
+    println!(
+        "Dictionary: {} from {} samples, trained in {:.1} ms",
+        human_bytes(dictionary.len() as u64),
+        samples.len(),
+        ms(dict_train)
+    );
+    println!(
+        "\n{:>5} {:>12} {:>8} {:>12} {:>12}",
+        "Level", "Size", "Ratio", "Compress", "Decompress"
+    );
+    for l in &levels {
+        println!(
+            "{:>5} {:>12} {:>7.1}x {:>9.1} ms {:>9.1} ms{}",
+            l.level,
+            human_bytes(l.total),
+            l.ratio,
+            l.compress_ms,
+            l.decompress_ms,
+            if l.level == DEFAULT_COMPRESSION_LEVEL {
+                "  (default)"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
 // ── dump_wal ─────────────────────────────────────────────────────────────────
 
 fn dump_wal(path: &Path, cli: &Cli) {
@@ -577,7 +890,8 @@ fn dump_wal(path: &Path, cli: &Cli) {
     let mut snapshots: Vec<rpglot_core::storage::Snapshot> = Vec::new();
 
     while pos + WAL_FRAME_HEADER_SIZE <= data.len() {
-        let length = u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap());
+        let (length, encrypted) =
+            wal_frame_length(u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()));
         let crc = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap());
 
         if length > MAX_WAL_ENTRY_SIZE || pos + WAL_FRAME_HEADER_SIZE + length as usize > data.len()
@@ -592,11 +906,16 @@ fn dump_wal(path: &Path, cli: &Cli) {
             break;
         }
 
+        let Some(payload) = wal_payload(payload, encrypted) else {
+            eprintln!("WAL entry at offset {pos} is encrypted: pass --encryption-key-file");
+            break;
+        };
+
         let frame_total = WAL_FRAME_HEADER_SIZE as u64 + length as u64;
         frame_sizes.push(frame_total);
 
         // Deserialize to get timestamp (and optionally snapshot for --blocks)
-        if let Ok(entry) = postcard::from_bytes::<WalEntryView>(payload) {
+        if let Some(entry) = WalEntryView::decode(&payload) {
             timestamps.push(entry.snapshot.timestamp);
             if cli.blocks {
                 snapshots.push(entry.snapshot);
@@ -667,12 +986,572 @@ fn dump_wal(path: &Path, cli: &Cli) {
     }
 }
 
-/// Minimal deserialization of WalEntry (same layout as rpglot-core's WalEntry).
-#[derive(serde::Deserialize)]
+/// Decoded WAL entry: a snapshot and its string interner.
 struct WalEntryView {
-    snapshot: rpglot_core::storage::Snapshot,
-    #[allow(dead_code)]
-    interner: rpglot_core::storage::StringInterner,
+    snapshot: Snapshot,
+    interner: StringInterner,
+}
+
+impl WalEntryView {
+    /// Decodes a (decrypted) WAL frame payload, also as written by earlier builds.
+    fn decode(payload: &[u8]) -> Option<Self> {
+        let (snapshot, interner) = decode_wal_payload(payload)?;
+        Some(Self { snapshot, interner })
+    }
+}
+
+// ── extract ──────────────────────────────────────────────────────────────────
+
+fn extract(path: &Path, cli: &Cli) {
+    let mut kinds = 0u64;
+    for name in &cli.block {
+        let Some(idx) = DataBlock::kind_by_name(name.trim()) else {
+            eprintln!(
+                "Unknown block '{name}', expected one of: {}",
+                DataBlock::KIND_NAMES.join(", ")
+            );
+            std::process::exit(1);
+        };
+        kinds |= 1 << idx;
+    }
+    let start = cli.start.unwrap_or(i64::MIN);
+    let end = cli.end.unwrap_or(i64::MAX);
+    let in_range = |ts: i64| ts >= start && ts <= end;
+
+    let (chunk_files, wal_file) = storage_sources(path, "--extract");
+
+    let mut stdout = io::stdout().lock();
+    let mut print = |mut snapshot: Snapshot, interner: &StringInterner| {
+        if kinds != 0 {
+            DataBlock::strip_kinds(&mut snapshot.blocks, !kinds);
+        }
+        let mut value = serde_json::to_value(&snapshot).unwrap();
+        resolve_hashes(&mut value, interner);
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("time".into(), fmt_ts(snapshot.timestamp).into());
+        }
+        // A closed pipe (`| head`) ends the output
+        if writeln!(stdout, "{value}").is_err() {
+            std::process::exit(0);
+        }
+    };
+
+    for chunk_path in &chunk_files {
+        let reader = ChunkReader::open(chunk_path).unwrap_or_else(|e| {
+            eprintln!("Error opening {}: {e}", chunk_path.display());
+            std::process::exit(1);
+        });
+        let indices: Vec<usize> = reader
+            .timestamps()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, ts)| in_range(ts))
+            .map(|(i, _)| i)
+            .collect();
+        if indices.is_empty() {
+            continue;
+        }
+        let interner = reader.read_interner().unwrap_or_else(|e| {
+            eprintln!("Error reading interner of {}: {e}", chunk_path.display());
+            std::process::exit(1);
+        });
+        for i in indices {
+            match reader.read_snapshot(i) {
+                Ok(snapshot) => print(snapshot, &interner),
+                Err(e) => eprintln!(
+                    "Error reading snapshot {i} of {}: {e}",
+                    chunk_path.display()
+                ),
+            }
+        }
+    }
+
+    if let Some(ref wal_path) = wal_file {
+        for entry in read_wal_entries(wal_path) {
+            if in_range(entry.snapshot.timestamp) {
+                print(entry.snapshot, &entry.interner);
+            }
+        }
+    }
+}
+
+// ── interner ─────────────────────────────────────────────────────────────────
+
+/// A distinct interned string across all sources.
+struct InternedString {
+    text: String,
+    /// Sources (chunks, WAL) whose interner holds it.
+    sources: usize,
+}
+
+fn interner_stats(path: &Path, cli: &Cli) {
+    let (chunk_files, wal_file) = storage_sources(path, "--interner");
+
+    let mut strings: HashMap<u64, InternedString> = HashMap::new();
+    // Field (`*_hash` key without the suffix) that first referenced a hash
+    let mut fields: HashMap<u64, String> = HashMap::new();
+    let mut stored_strings = 0u64;
+    let mut stored_bytes = 0u64;
+    let mut sources = 0usize;
+
+    let mut add_source = |interner: &StringInterner,
+                          snapshots: &mut dyn Iterator<Item = Snapshot>| {
+        sources += 1;
+        for (hash, text) in interner.iter() {
+            stored_strings += 1;
+            stored_bytes += text.len() as u64;
+            strings
+                .entry(hash)
+                .or_insert_with(|| InternedString {
+                    text: text.to_string(),
+                    sources: 0,
+                })
+                .sources += 1;
+        }
+        for snapshot in snapshots {
+            if let Ok(value) = serde_json::to_value(&snapshot) {
+                collect_hash_fields(&value, &mut fields);
+            }
+        }
+    };
+
+    for chunk_path in &chunk_files {
+        let reader = ChunkReader::open(chunk_path).unwrap_or_else(|e| {
+            eprintln!("Error opening {}: {e}", chunk_path.display());
+            std::process::exit(1);
+        });
+        let interner = reader.read_interner().unwrap_or_else(|e| {
+            eprintln!("Error reading interner of {}: {e}", chunk_path.display());
+            std::process::exit(1);
+        });
+        let mut snapshots =
+            (0..reader.snapshot_count()).filter_map(|i| reader.read_snapshot(i).ok());
+        add_source(&interner, &mut snapshots);
+    }
+    if let Some(ref wal_path) = wal_file {
+        let mut interner = StringInterner::new();
+        let mut snapshots = Vec::new();
+        for entry in read_wal_entries(wal_path) {
+            interner.merge(&entry.interner);
+            snapshots.push(entry.snapshot);
+        }
+        add_source(&interner, &mut snapshots.into_iter());
+    }
+
+    let unique_bytes: u64 = strings.values().map(|s| s.text.len() as u64).sum();
+    let shared = strings.values().filter(|s| s.sources > 1).count();
+    let mut by_field: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+    for (hash, s) in &strings {
+        let field = fields.get(hash).map_or("unreferenced", String::as_str);
+        let entry = by_field.entry(field).or_default();
+        entry.0 += 1;
+        entry.1 += s.text.len() as u64;
+    }
+    let mut by_field: Vec<(&str, u64, u64)> =
+        by_field.into_iter().map(|(f, (n, b))| (f, n, b)).collect();
+    by_field.sort_by_key(|&(_, _, bytes)| std::cmp::Reverse(bytes));
+    let mut largest: Vec<(&u64, &InternedString)> = strings.iter().collect();
+    largest.sort_by_key(|(_, s)| std::cmp::Reverse(s.text.len()));
+    largest.truncate(cli.top);
+    let field_of = |hash: &u64| fields.get(hash).map_or("unreferenced", String::as_str);
+
+    if cli.json {
+        let json = InternerJson {
+            sources,
+            stored_strings,
+            stored_bytes,
+            unique_strings: strings.len(),
+            unique_bytes,
+            shared_strings: shared,
+            duplicate_pct: pct(stored_bytes - unique_bytes, stored_bytes),
+            fields: by_field
+                .iter()
+                .map(|&(field, strings, bytes)| InternerFieldJson {
+                    field: field.to_string(),
+                    strings,
+                    bytes,
+                    share_pct: pct(bytes, unique_bytes),
+                })
+                .collect(),
+            largest: largest
+                .iter()
+                .map(|&(hash, s)| InternerStringJson {
+                    hash: *hash,
+                    field: field_of(hash).to_string(),
+                    bytes: s.text.len(),
+                    sources: s.sources,
+                    text: s.text.clone(),
+                })
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        return;
+    }
+
+    println!(
+        "Interner: {} sources ({} chunks{})",
+        sources,
+        chunk_files.len(),
+        if wal_file.is_some() { " + WAL" } else { "" }
+    );
+    println!(
+        "  Stored:  {} strings, {}",
+        stored_strings,
+        human_bytes(stored_bytes)
+    );
+    println!(
+        "  Unique:  {} strings, {} ({} in several sources)",
+        strings.len(),
+        human_bytes(unique_bytes),
+        shared
+    );
+    println!(
+        "  Duplicated across sources: {:.1}% of stored bytes",
+        pct(stored_bytes - unique_bytes, stored_bytes)
+    );
+
+    println!("\nBy field (unique strings):");
+    println!(
+        "  {:<24} {:>8} {:>10} {:>6}",
+        "Field", "Strings", "Size", "Share"
+    );
+    println!("  {}", "─".repeat(52));
+    for (field, n, bytes) in &by_field {
+        println!(
+            "  {:<24} {:>8} {:>10} {:>5.1}%",
+            field,
+            n,
+            human_bytes(*bytes),
+            pct(*bytes, unique_bytes)
+        );
+    }
+
+    println!("\nLargest strings:");
+    for (hash, s) in &largest {
+        let preview: String = s
+            .text
+            .chars()
+            .take(100)
+            .collect::<String>()
+            .escape_debug()
+            .to_string();
+        let ellipsis = if s.text.chars().count() > 100 {
+            "…"
+        } else {
+            ""
+        };
+        println!(
+            "  {:>10}  {:<16} x{:<4} {preview}{ellipsis}",
+            human_bytes(s.text.len() as u64),
+            field_of(hash),
+            s.sources
+        );
+    }
+}
+
+/// Records the field of every `*_hash` key in `value` (first one wins).
+fn collect_hash_fields(value: &serde_json::Value, fields: &mut HashMap<u64, String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map {
+                if let (Some(field), Some(hash)) = (key.strip_suffix("_hash"), v.as_u64()) {
+                    fields.entry(hash).or_insert_with(|| field.to_string());
+                } else {
+                    collect_hash_fields(v, fields);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_hash_fields(item, fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+// ── repair ───────────────────────────────────────────────────────────────────
+
+fn repair(path: &Path) {
+    let (chunk_files, wal_file) = storage_sources(path, "--repair");
+    let mut failed = false;
+    for chunk_path in &chunk_files {
+        failed |= !repair_chunk_file(chunk_path);
+    }
+    if let Some(ref wal_path) = wal_file {
+        failed |= !repair_wal(wal_path);
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// `path` with `suffix` appended to the file name (`x.zst` → `x.zst.bak`).
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Rewrites a damaged chunk in place, keeping the original as `.bak`.
+/// Returns false if nothing could be salvaged.
+fn repair_chunk_file(path: &Path) -> bool {
+    let fname = path.file_name().unwrap_or_default().to_string_lossy();
+    let repaired = with_suffix(path, ".repaired");
+    let result = match repair_chunk(path, &repaired) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{fname}: cannot repair: {e}");
+            return false;
+        }
+    };
+    if result.recovered == result.indexed && !result.scanned && !result.interner_lost {
+        let _ = fs::remove_file(&repaired);
+        println!("{fname}: ok, {} snapshots", result.indexed);
+        return true;
+    }
+
+    let backup = with_suffix(path, ".bak");
+    if let Err(e) = fs::rename(path, &backup).and_then(|()| fs::rename(&repaired, path)) {
+        eprintln!("{fname}: cannot replace with repaired chunk: {e}");
+        return false;
+    }
+    println!(
+        "{fname}: repaired, {} of {} indexed snapshots recovered{}{}, original kept as {}",
+        result.recovered,
+        result.indexed,
+        if result.scanned {
+            " (index rebuilt by scanning frames)"
+        } else {
+            ""
+        },
+        if result.interner_lost {
+            ", interner lost"
+        } else {
+            ""
+        },
+        backup.display()
+    );
+    true
+}
+
+/// Truncates a WAL at its first corrupt frame, keeping the original as
+/// `.bak`. Returns false on I/O errors.
+fn repair_wal(path: &Path) -> bool {
+    let data = fs::read(path).unwrap_or_else(|e| {
+        eprintln!("Error reading {}: {e}", path.display());
+        std::process::exit(1);
+    });
+    let mut pos = 0usize;
+    let mut entries = 0usize;
+    while pos + WAL_FRAME_HEADER_SIZE <= data.len() {
+        let (length, encrypted) =
+            wal_frame_length(u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()));
+        let crc = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap());
+        if length > MAX_WAL_ENTRY_SIZE || pos + WAL_FRAME_HEADER_SIZE + length as usize > data.len()
+        {
+            break;
+        }
+        let payload =
+            &data[pos + WAL_FRAME_HEADER_SIZE..pos + WAL_FRAME_HEADER_SIZE + length as usize];
+        if crc32fast::hash(payload) != crc {
+            break;
+        }
+        // Encrypted frames without a key are kept: their CRC is valid
+        if let Some(payload) = wal_payload(payload, encrypted)
+            && WalEntryView::decode(&payload).is_none()
+        {
+            break;
+        }
+        entries += 1;
+        pos += WAL_FRAME_HEADER_SIZE + length as usize;
+    }
+
+    if pos == data.len() {
+        println!("wal.log: ok, {entries} entries");
+        return true;
+    }
+    let backup = with_suffix(path, ".bak");
+    let truncated = fs::copy(path, &backup).and_then(|_| {
+        let file = fs::OpenOptions::new().write(true).open(path)?;
+        file.set_len(pos as u64)?;
+        file.sync_all()
+    });
+    if let Err(e) = truncated {
+        eprintln!("wal.log: cannot truncate: {e}");
+        return false;
+    }
+    println!(
+        "wal.log: truncated at offset {pos} after {entries} entries ({} dropped), original kept as {}",
+        human_bytes((data.len() - pos) as u64),
+        backup.display()
+    );
+    true
+}
+
+// ── migrate ──────────────────────────────────────────────────────────────────
+
+fn migrate(path: &Path, target_dir: Option<&Path>) {
+    let (chunk_files, wal_file) = storage_sources(path, "--migrate");
+    if let Some(dir) = target_dir
+        && let Err(e) = fs::create_dir_all(dir)
+    {
+        eprintln!("Error creating {}: {e}", dir.display());
+        std::process::exit(1);
+    }
+
+    let mut failed = false;
+    let mut migrated = 0usize;
+    for chunk_path in &chunk_files {
+        let fname = chunk_path.file_name().unwrap_or_default();
+        let out = target_dir.map_or_else(|| chunk_path.clone(), |dir| dir.join(fname));
+        let fname = fname.to_string_lossy();
+        match migrate_chunk(chunk_path, &out) {
+            Ok(Some(version)) => {
+                migrated += 1;
+                println!("{fname}: migrated from RPG{version}");
+            }
+            Ok(None) => match target_dir {
+                Some(_) => {
+                    if let Err(e) = fs::copy(chunk_path, &out) {
+                        eprintln!("{fname}: cannot copy: {e}");
+                        failed = true;
+                    }
+                }
+                None => println!("{fname}: up to date"),
+            },
+            Err(e) => {
+                eprintln!("{fname}: cannot migrate: {e}");
+                failed = true;
+            }
+        }
+    }
+
+    // Heatmaps and the WAL have no chunk format; carry them over as is
+    if let Some(dir) = target_dir {
+        let mut others: Vec<PathBuf> = wal_file.into_iter().collect();
+        if path.is_dir()
+            && let Ok(entries) = fs::read_dir(path)
+        {
+            others.extend(
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| has_ext(p, "heatmap")),
+            );
+        }
+        for other in &others {
+            let fname = other.file_name().unwrap_or_default();
+            if let Err(e) = fs::copy(other, dir.join(fname)) {
+                eprintln!("{}: cannot copy: {e}", fname.to_string_lossy());
+                failed = true;
+            }
+        }
+    }
+
+    println!("{migrated} of {} chunks migrated", chunk_files.len());
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Chunk files (sorted) and wal.log of `path`: a storage directory, a single
+/// .zst chunk or a wal.log. Exits for other paths.
+fn storage_sources(path: &Path, mode: &str) -> (Vec<PathBuf>, Option<PathBuf>) {
+    let mut chunk_files: Vec<PathBuf> = Vec::new();
+    let mut wal_file: Option<PathBuf> = None;
+    if path.is_dir() {
+        let entries = fs::read_dir(path).unwrap_or_else(|e| {
+            eprintln!("Error reading directory {}: {e}", path.display());
+            std::process::exit(1);
+        });
+        for entry in entries.flatten() {
+            let p = entry.path();
+            if has_ext(&p, "zst") {
+                chunk_files.push(p);
+            } else if p.file_name().and_then(|f| f.to_str()) == Some("wal.log") {
+                wal_file = Some(p);
+            }
+        }
+        chunk_files.sort();
+    } else if has_ext(path, "zst") {
+        chunk_files.push(path.to_path_buf());
+    } else if path.file_name().and_then(|f| f.to_str()) == Some("wal.log") {
+        wal_file = Some(path.to_path_buf());
+    } else {
+        eprintln!("{mode} expects a storage directory, .zst chunk or wal.log");
+        std::process::exit(1);
+    }
+    (chunk_files, wal_file)
+}
+
+/// Decoded entries of a WAL, up to the first corrupt or undecryptable frame.
+fn read_wal_entries(wal_path: &Path) -> Vec<WalEntryView> {
+    let data = fs::read(wal_path).unwrap_or_else(|e| {
+        eprintln!("Error reading {}: {e}", wal_path.display());
+        std::process::exit(1);
+    });
+    let mut entries = Vec::new();
+    let mut pos = 0usize;
+    while pos + WAL_FRAME_HEADER_SIZE <= data.len() {
+        let (length, encrypted) =
+            wal_frame_length(u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()));
+        let crc = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap());
+        if length > MAX_WAL_ENTRY_SIZE || pos + WAL_FRAME_HEADER_SIZE + length as usize > data.len()
+        {
+            break;
+        }
+        let payload =
+            &data[pos + WAL_FRAME_HEADER_SIZE..pos + WAL_FRAME_HEADER_SIZE + length as usize];
+        if crc32fast::hash(payload) != crc {
+            break;
+        }
+        let Some(payload) = wal_payload(payload, encrypted) else {
+            eprintln!("WAL entry at offset {pos} is encrypted: pass --encryption-key-file");
+            break;
+        };
+        let Some(entry) = WalEntryView::decode(&payload) else {
+            break;
+        };
+        entries.push(entry);
+        pos += WAL_FRAME_HEADER_SIZE + length as usize;
+    }
+    entries
+}
+
+/// Replaces interned string hashes (`*_hash` fields) with their strings:
+/// `"datname_hash": 123` becomes `"datname": "postgres"`. Hashes missing
+/// from the interner are kept.
+fn resolve_hashes(value: &mut serde_json::Value, interner: &StringInterner) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let keys: Vec<String> = map.keys().cloned().collect();
+            for key in keys {
+                let resolved = key.strip_suffix("_hash").and_then(|name| {
+                    let hash = map.get(&key)?.as_u64()?;
+                    let s = interner.resolve(hash)?;
+                    (!map.contains_key(name)).then(|| (name.to_string(), s.to_string()))
+                });
+                match resolved {
+                    Some((name, s)) => {
+                        map.remove(&key);
+                        map.insert(name, s.into());
+                    }
+                    None => {
+                        if let Some(v) = map.get_mut(&key) {
+                            resolve_hashes(v, interner);
+                        }
+                    }
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                resolve_hashes(item, interner);
+            }
+        }
+        _ => {}
+    }
 }
 
 // ── dump_heatmap ─────────────────────────────────────────────────────────────
@@ -684,14 +1563,18 @@ fn dump_heatmap(path: &Path, cli: &Cli) {
     });
     let file_size = data.len() as u64;
 
-    if data.len() < 4 || &data[0..4] != HEATMAP_MAGIC {
+    let Some(&(magic, entry_size)) = HEATMAP_FORMATS
+        .iter()
+        .find(|(magic, _)| data.len() >= 4 && &data[0..4] == *magic)
+    else {
         eprintln!("Invalid heatmap file: bad magic");
         std::process::exit(1);
-    }
+    };
+    let format = String::from_utf8_lossy(magic).into_owned();
 
     let payload_len = data.len() - 4;
-    let entries = if payload_len.is_multiple_of(HEATMAP_ENTRY_SIZE) {
-        payload_len / HEATMAP_ENTRY_SIZE
+    let entries = if payload_len.is_multiple_of(entry_size) {
+        payload_len / entry_size
     } else {
         eprintln!("Invalid heatmap file: payload size not aligned to entry size");
         std::process::exit(1);
@@ -705,7 +1588,7 @@ fn dump_heatmap(path: &Path, cli: &Cli) {
                 .to_string_lossy()
                 .into(),
             file_size,
-            format: "HM04".into(),
+            format,
             entries,
         };
         println!("{}", serde_json::to_string_pretty(&json).unwrap());
@@ -713,8 +1596,8 @@ fn dump_heatmap(path: &Path, cli: &Cli) {
         let fname = path.file_name().unwrap_or_default().to_string_lossy();
         println!("File: {} ({} bytes)", fname, file_size);
         println!(
-            "Format: HM03, {} entries ({} B each)",
-            entries, HEATMAP_ENTRY_SIZE
+            "Format: {}, {} entries ({} B each)",
+            format, entries, entry_size
         );
     }
 }
@@ -792,7 +1675,8 @@ fn dump_directory(path: &Path, cli: &Cli) {
     {
         let mut pos = 0usize;
         while pos + WAL_FRAME_HEADER_SIZE <= data.len() {
-            let length = u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap());
+            let (length, encrypted) =
+                wal_frame_length(u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()));
             let crc = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap());
             if length > MAX_WAL_ENTRY_SIZE
                 || pos + WAL_FRAME_HEADER_SIZE + length as usize > data.len()
@@ -804,8 +1688,11 @@ fn dump_directory(path: &Path, cli: &Cli) {
             if crc32fast::hash(payload) != crc {
                 break;
             }
+            let Some(payload) = wal_payload(payload, encrypted) else {
+                break;
+            };
             // Quick timestamp extraction: deserialize just to get timestamp
-            if let Ok(entry) = postcard::from_bytes::<WalEntryView>(payload) {
+            if let Some(entry) = WalEntryView::decode(&payload) {
                 let ts = entry.snapshot.timestamp;
                 all_first_ts = Some(all_first_ts.map_or(ts, |t: i64| t.min(ts)));
                 all_last_ts = Some(all_last_ts.map_or(ts, |t: i64| t.max(ts)));
@@ -0,0 +1,694 @@
+//! TOML configuration file (`--config`).
+//!
+//! ```toml
+//! [intervals]
+//! processes = 10            # snapshot interval: processes and OS metrics
+//! statements = 30           # pg_stat_statements
+//! tables = 60               # pg_stat_user_tables / pg_stat_user_indexes
+//! logs = 10                 # PostgreSQL log reads
+//!
+//! [storage]
+//! output_dir = "/var/lib/rpglot"
+//! max_size = "2G"
+//! max_days = 14
+//! block_retention = ["processes,pg_stat_statements=3"]
+//! durability = "wal-fsync"  # none | wal-fsync | always
+//! wal_fsync_interval = 5    # seconds, wal-fsync only
+//! direct_io = true          # write chunks with O_DIRECT
+//! compression_level = 3     # zstd level of chunks, 1-22
+//! dict_samples = 20         # snapshots sampled for the chunk dictionary
+//!
+//! [postgres]
+//! host = "/var/run/postgresql"
+//! port = 5432
+//! user = "rpglot"
+//! sslmode = "verify-full"   # libpq sslmode; TCP only
+//! sslrootcert = "/etc/rpglot/root.crt"  # or "system"
+//! connect_timeout = 5       # seconds
+//! custom_queries = "/etc/rpglot/queries.toml"
+//!
+//! [[postgres.instances]]    # more clusters on the same host
+//! name = "analytics"
+//! port = 5433
+//!
+//! [postgres.discovery]      # collect postmasters found in the process list
+//! enabled = true
+//! allow = ["/var/lib/postgresql/*"]   # port, data directory or name ("16/main")
+//! deny = ["5439"]
+//!
+//! [logs]
+//! source = "journald"
+//! journald_unit = "postgresql@16-main"
+//!
+//! [redaction]
+//! sql = "strip"
+//!
+//! [throttle]                # skip expensive collectors under load
+//! enabled = true
+//! budget_pct = 50           # collection time, % of the snapshot interval
+//! psi_critical = 40         # CPU / I/O some avg10, %
+//! cycles = 3                # snapshots to skip them for
+//! ```
+//!
+//! Every setting is optional. Flags given explicitly on the command line
+//! override the file; the file overrides flag defaults. Unset connection
+//! parameters fall back to the libpq environment variables (PGHOST, ...).
+//!
+//! `[postgres]` is the primary instance; its blocks stay at the top level of
+//! the snapshot. Each `[[postgres.instances]]` entry is collected into a
+//! separate `pg_instance` block and shares the intervals, redaction and
+//! custom queries of the primary; its log is always read from files.
+//! Discovered instances are added the same way; without an explicit host or
+//! port the first one found becomes the primary.
+//!
+//! The file is re-read on SIGHUP. `output_dir` and `encryption_key_file`
+//! only take effect after a restart.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use rpglot_core::collector::log_collector::tailer::LogSourceConfig;
+use rpglot_core::collector::{
+    DiscoveryFilter, PgConnectionParams, RedactMode, SslMode, ThrottleConfig,
+};
+use rpglot_core::fmt::parse_size;
+use rpglot_core::storage::{BlockRetention, Durability, RotationConfig};
+use serde::Deserialize;
+
+use crate::{Args, parse_log_source};
+
+/// Default caching interval for pg_stat_statements and table / index statistics.
+const DEFAULT_PG_STATS_INTERVAL: u64 = 30;
+
+/// Contents of the configuration file.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DaemonConfig {
+    pub intervals: IntervalsConfig,
+    pub storage: StorageConfig,
+    pub postgres: PostgresConfig,
+    pub logs: LogsConfig,
+    pub redaction: RedactionConfig,
+    pub throttle: ThrottleFileConfig,
+}
+
+/// `[intervals]`: collection intervals in seconds.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct IntervalsConfig {
+    /// Snapshot interval (processes and OS metrics are read every snapshot).
+    pub processes: Option<u64>,
+    /// pg_stat_statements refresh interval.
+    pub statements: Option<u64>,
+    /// pg_stat_user_tables / pg_stat_user_indexes refresh interval.
+    pub tables: Option<u64>,
+    /// PostgreSQL log read interval.
+    pub logs: Option<u64>,
+}
+
+/// `[storage]`: output directory and rotation.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub output_dir: Option<String>,
+    /// Size string like `--max-size` ("1G", "500M").
+    pub max_size: Option<String>,
+    pub max_days: Option<u32>,
+    /// Rules like `--block-retention` ("processes,pg_stat_statements=7").
+    pub block_retention: Option<Vec<String>>,
+    pub encryption_key_file: Option<PathBuf>,
+    /// `none`, `wal-fsync` or `always`.
+    pub durability: Option<String>,
+    pub wal_fsync_interval: Option<u64>,
+    pub direct_io: Option<bool>,
+    /// zstd level, 1-22.
+    pub compression_level: Option<i32>,
+    pub dict_samples: Option<usize>,
+}
+
+/// `[postgres]`: connection target.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PostgresConfig {
+    pub enabled: Option<bool>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub database: Option<String>,
+    /// libpq `sslmode`: `disable`, `allow`, `prefer`, `require`,
+    /// `verify-ca` or `verify-full`.
+    pub sslmode: Option<String>,
+    /// Root certificates PEM file, or `system`.
+    pub sslrootcert: Option<PathBuf>,
+    /// Connection timeout in seconds.
+    pub connect_timeout: Option<u64>,
+    pub custom_queries: Option<PathBuf>,
+    pub instances: Vec<InstanceConfig>,
+    pub discovery: DiscoveryConfig,
+}
+
+/// `[postgres.discovery]`: automatic discovery of local instances.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiscoveryConfig {
+    /// Default: disabled.
+    pub enabled: Option<bool>,
+    /// Patterns of instances to collect (port, data directory or name).
+    pub allow: Option<Vec<String>>,
+    /// Patterns of instances to skip.
+    pub deny: Option<Vec<String>>,
+}
+
+/// `[[postgres.instances]]`: an additional instance on the same host.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct InstanceConfig {
+    /// Name shown in the instance switcher.
+    pub name: String,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub database: Option<String>,
+    pub sslmode: Option<String>,
+    pub sslrootcert: Option<PathBuf>,
+    pub connect_timeout: Option<u64>,
+}
+
+/// `[logs]`: PostgreSQL log source.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogsConfig {
+    /// `file`, `syslog` or `journald`.
+    pub source: Option<String>,
+    pub syslog_listen: Option<String>,
+    pub journald_unit: Option<String>,
+}
+
+/// `[redaction]`: query text redaction.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedactionConfig {
+    /// `strip`, `hash`, `drop` or `off`.
+    pub sql: Option<String>,
+}
+
+/// `[throttle]`: adaptive throttling of expensive collectors.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThrottleFileConfig {
+    /// Default: enabled.
+    pub enabled: Option<bool>,
+    pub budget_pct: Option<u32>,
+    pub psi_critical: Option<f32>,
+    pub cycles: Option<u32>,
+}
+
+impl DaemonConfig {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("invalid config {}: {}", path.display(), e))
+    }
+}
+
+/// Effective daemon settings: command line merged with the config file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub interval: Duration,
+    pub statements_interval: Duration,
+    pub tables_interval: Duration,
+    /// Zero reads logs on every snapshot.
+    pub logs_interval: Duration,
+    pub output_dir: String,
+    pub max_size: u64,
+    pub max_days: u32,
+    pub block_retention: Vec<BlockRetention>,
+    pub encryption_key_file: Option<PathBuf>,
+    pub durability: Durability,
+    pub direct_io: bool,
+    pub compression_level: i32,
+    /// Zero writes chunks without a dictionary.
+    pub dict_samples: usize,
+    pub redact_sql: RedactMode,
+    pub postgres: bool,
+    pub pg_params: PgConnectionParams,
+    /// Additional instances by name.
+    pub pg_instances: Vec<(String, PgConnectionParams)>,
+    /// `None` when instance discovery is disabled.
+    pub pg_discovery: Option<DiscoveryFilter>,
+    pub custom_queries: Option<PathBuf>,
+    pub log_source: LogSourceConfig,
+    /// `None` when throttling is disabled.
+    pub throttle: Option<ThrottleConfig>,
+}
+
+impl Settings {
+    /// Merges `args` with `config`. A flag wins over the file only when it
+    /// was given on the command line (`matches` tells defaults apart).
+    pub fn resolve(
+        args: &Args,
+        matches: &ArgMatches,
+        config: Option<&DaemonConfig>,
+    ) -> Result<Self, String> {
+        let default = DaemonConfig::default();
+        let config = config.unwrap_or(&default);
+        let interval = |name: &str, secs: u64| {
+            if secs == 0 {
+                Err(format!("intervals.{}: must be at least 1 second", name))
+            } else {
+                Ok(Duration::from_secs(secs))
+            }
+        };
+        // The file applies unless the flag was given on the command line
+        let use_file = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+        let string = |id: &str, cli: &String, file: &Option<String>| match file {
+            Some(value) if use_file(id) => value.clone(),
+            _ => cli.clone(),
+        };
+        let (intervals, storage, pg) = (&config.intervals, &config.storage, &config.postgres);
+
+        let max_size = match &storage.max_size {
+            Some(size) if use_file("max_size") => {
+                parse_size(size).map_err(|e| format!("storage.max_size: {}", e))?
+            }
+            _ => args.max_size,
+        };
+        let block_retention = match &storage.block_retention {
+            Some(rules) if use_file("block_retention") => rules
+                .iter()
+                .map(|r| BlockRetention::parse(r))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("storage.block_retention: {}", e))?,
+            _ => args.block_retention.clone(),
+        };
+        let wal_fsync_interval = match storage.wal_fsync_interval {
+            Some(secs) if use_file("wal_fsync_interval") => secs,
+            _ => args.wal_fsync_interval,
+        };
+        let durability = Durability::parse(
+            &string("durability", &args.durability, &storage.durability),
+            Duration::from_secs(wal_fsync_interval),
+        )
+        .map_err(|e| format!("storage.durability: {}", e))?;
+        let compression_level = match storage.compression_level {
+            Some(level) if use_file("compression_level") => level,
+            _ => args.compression_level,
+        };
+        if !(1..=22).contains(&compression_level) {
+            return Err(format!(
+                "storage.compression_level: {} is not in 1-22",
+                compression_level
+            ));
+        }
+        let redact_sql = match &config.redaction.sql {
+            Some(mode) if use_file("redact_sql") => {
+                RedactMode::parse(mode).map_err(|e| format!("redaction.sql: {}", e))?
+            }
+            _ => args.redact_sql,
+        };
+        let logs = &config.logs;
+        let log_source = parse_log_source(
+            &string("log_source", &args.log_source, &logs.source),
+            &string("syslog_listen", &args.syslog_listen, &logs.syslog_listen),
+            &string("journald_unit", &args.journald_unit, &logs.journald_unit),
+        )
+        .map_err(|e| format!("logs: {}", e))?;
+        let sslmode = |section: &str, mode: &Option<String>| {
+            mode.as_deref()
+                .map(SslMode::parse)
+                .transpose()
+                .map_err(|e| format!("{}.sslmode: {}", section, e))
+        };
+        let mut pg_instances: Vec<(String, PgConnectionParams)> = Vec::new();
+        for instance in &pg.instances {
+            if instance.name.is_empty() {
+                return Err("postgres.instances: name must not be empty".to_string());
+            }
+            if pg_instances.iter().any(|(name, _)| *name == instance.name) {
+                return Err(format!(
+                    "postgres.instances: duplicate name '{}'",
+                    instance.name
+                ));
+            }
+            pg_instances.push((
+                instance.name.clone(),
+                PgConnectionParams {
+                    host: instance.host.clone(),
+                    port: instance.port,
+                    user: instance.user.clone(),
+                    password: instance.password.clone(),
+                    database: instance.database.clone(),
+                    sslmode: sslmode("postgres.instances", &instance.sslmode)?,
+                    sslrootcert: instance.sslrootcert.clone(),
+                    connect_timeout: instance.connect_timeout.map(Duration::from_secs),
+                },
+            ));
+        }
+
+        let discovery = &pg.discovery;
+        let patterns = |id: &str, cli: &Vec<String>, file: &Option<Vec<String>>| match file {
+            Some(patterns) if use_file(id) => patterns.clone(),
+            _ => cli.clone(),
+        };
+        let pg_discovery = match discovery.enabled {
+            Some(enabled) if use_file("pg_discover") => enabled,
+            _ => args.pg_discover,
+        }
+        .then(|| DiscoveryFilter {
+            allow: patterns(
+                "pg_discover_allow",
+                &args.pg_discover_allow,
+                &discovery.allow,
+            ),
+            deny: patterns("pg_discover_deny", &args.pg_discover_deny, &discovery.deny),
+        });
+
+        let throttle = &config.throttle;
+        let defaults = ThrottleConfig::default();
+        let throttle = throttle.enabled.unwrap_or(true).then(|| ThrottleConfig {
+            budget_pct: throttle.budget_pct.unwrap_or(defaults.budget_pct),
+            psi_critical: throttle.psi_critical.unwrap_or(defaults.psi_critical),
+            cycles: throttle.cycles.unwrap_or(defaults.cycles),
+        });
+        if let Some(t) = &throttle
+            && (t.budget_pct == 0 || t.cycles == 0)
+        {
+            return Err("throttle: budget_pct and cycles must be positive".to_string());
+        }
+
+        Ok(Self {
+            interval: match intervals.processes {
+                Some(secs) if use_file("interval") => interval("processes", secs)?,
+                _ => interval("processes", args.interval)?,
+            },
+            statements_interval: interval(
+                "statements",
+                intervals.statements.unwrap_or(DEFAULT_PG_STATS_INTERVAL),
+            )?,
+            tables_interval: interval(
+                "tables",
+                intervals.tables.unwrap_or(DEFAULT_PG_STATS_INTERVAL),
+            )?,
+            logs_interval: match intervals.logs {
+                Some(secs) => interval("logs", secs)?,
+                None => Duration::ZERO,
+            },
+            output_dir: string("output_dir", &args.output_dir, &storage.output_dir),
+            max_size,
+            max_days: match storage.max_days {
+                Some(days) if use_file("max_days") => days,
+                _ => args.max_days,
+            },
+            block_retention,
+            encryption_key_file: match &storage.encryption_key_file {
+                Some(path) if use_file("encryption_key_file") => Some(path.clone()),
+                _ => args.encryption_key_file.clone(),
+            },
+            durability,
+            direct_io: match storage.direct_io {
+                Some(direct_io) if use_file("direct_io") => direct_io,
+                _ => args.direct_io,
+            },
+            compression_level,
+            dict_samples: match storage.dict_samples {
+                Some(samples) if use_file("dict_samples") => samples,
+                _ => args.dict_samples,
+            },
+            redact_sql,
+            postgres: match pg.enabled {
+                Some(enabled) if use_file("postgres") => enabled,
+                _ => args.postgres,
+            },
+            pg_params: PgConnectionParams {
+                host: pg.host.clone(),
+                port: pg.port,
+                user: pg.user.clone(),
+                password: pg.password.clone(),
+                database: pg.database.clone(),
+                sslmode: sslmode("postgres", &pg.sslmode)?,
+                sslrootcert: pg.sslrootcert.clone(),
+                connect_timeout: pg.connect_timeout.map(Duration::from_secs),
+            },
+            pg_instances,
+            pg_discovery,
+            custom_queries: match &pg.custom_queries {
+                Some(path) if use_file("custom_queries") => Some(path.clone()),
+                _ => args.custom_queries.clone(),
+            },
+            log_source,
+            throttle,
+        })
+    }
+
+    pub fn rotation(&self) -> RotationConfig {
+        RotationConfig::new(self.max_size, self.max_days)
+            .with_block_retention(self.block_retention.clone())
+    }
+
+    /// Whether the PostgreSQL collector has to be recreated to apply `other`.
+    pub fn postgres_changed(&self, other: &Settings) -> bool {
+        self.postgres != other.postgres
+            || self.pg_params != other.pg_params
+            || self.pg_instances != other.pg_instances
+            || self.pg_discovery != other.pg_discovery
+            || self.custom_queries != other.custom_queries
+            || self.log_source != other.log_source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, FromArgMatches};
+
+    fn resolve(argv: &[&str], config: &str) -> Result<Settings, String> {
+        let matches = Args::command()
+            .try_get_matches_from(std::iter::once("rpglotd").chain(argv.iter().copied()))
+            .unwrap();
+        let args = Args::from_arg_matches(&matches).unwrap();
+        let config = DaemonConfig::parse(config)?;
+        Settings::resolve(&args, &matches, Some(&config))
+    }
+
+    #[test]
+    fn parses_config_sections() {
+        let text = r#"
+            [intervals]
+            processes = 5
+            statements = 60
+            tables = 120
+            logs = 15
+
+            [storage]
+            output_dir = "/var/lib/rpglot"
+            max_size = "2G"
+            max_days = 14
+            block_retention = ["processes=3"]
+
+            [postgres]
+            host = "/var/run/postgresql"
+            port = 5433
+            user = "rpglot"
+
+            [logs]
+            source = "journald"
+            journald_unit = "postgresql@16-main"
+
+            [redaction]
+            sql = "strip"
+        "#;
+        let s = resolve(&[], text).unwrap();
+        assert_eq!(s.interval, Duration::from_secs(5));
+        assert_eq!(s.statements_interval, Duration::from_secs(60));
+        assert_eq!(s.tables_interval, Duration::from_secs(120));
+        assert_eq!(s.logs_interval, Duration::from_secs(15));
+        assert_eq!(s.output_dir, "/var/lib/rpglot");
+        assert_eq!(s.max_size, 2 * 1024 * 1024 * 1024);
+        assert_eq!(s.max_days, 14);
+        assert_eq!(s.block_retention.len(), 1);
+        assert_eq!(s.pg_params.host.as_deref(), Some("/var/run/postgresql"));
+        assert_eq!(s.pg_params.port, Some(5433));
+        assert_eq!(s.pg_params.database, None);
+        assert_eq!(
+            s.log_source,
+            LogSourceConfig::Journald {
+                unit: "postgresql@16-main".to_string()
+            }
+        );
+        assert_eq!(s.redact_sql, RedactMode::Strip);
+    }
+
+    #[test]
+    fn throttle_defaults_and_overrides() {
+        let s = resolve(&[], "").unwrap();
+        assert_eq!(s.throttle, Some(ThrottleConfig::default()));
+
+        let s = resolve(&[], "[throttle]\nbudget_pct = 80\ncycles = 6\n").unwrap();
+        let throttle = s.throttle.unwrap();
+        assert_eq!(throttle.budget_pct, 80);
+        assert_eq!(throttle.cycles, 6);
+        assert_eq!(
+            throttle.psi_critical,
+            ThrottleConfig::default().psi_critical
+        );
+
+        let s = resolve(&[], "[throttle]\nenabled = false\n").unwrap();
+        assert_eq!(s.throttle, None);
+        assert!(resolve(&[], "[throttle]\ncycles = 0\n").is_err());
+    }
+
+    #[test]
+    fn durability_from_flags_and_file() {
+        let s = resolve(&[], "").unwrap();
+        assert_eq!(s.durability, Durability::WalFsync(Duration::ZERO));
+        assert!(!s.direct_io);
+
+        let text =
+            "[storage]\ndurability = \"wal-fsync\"\nwal_fsync_interval = 5\ndirect_io = true\n";
+        let s = resolve(&[], text).unwrap();
+        assert_eq!(s.durability, Durability::WalFsync(Duration::from_secs(5)));
+        assert!(s.direct_io);
+
+        let s = resolve(&["--durability", "always"], text).unwrap();
+        assert_eq!(s.durability, Durability::Always);
+        assert!(resolve(&[], "[storage]\ndurability = \"fast\"\n").is_err());
+    }
+
+    #[test]
+    fn compression_from_flags_and_file() {
+        let s = resolve(&[], "").unwrap();
+        assert_eq!(s.compression_level, 3);
+        assert_eq!(s.dict_samples, 20);
+
+        let text = "[storage]\ncompression_level = 9\ndict_samples = 0\n";
+        let s = resolve(&[], text).unwrap();
+        assert_eq!(s.compression_level, 9);
+        assert_eq!(s.dict_samples, 0);
+
+        let s = resolve(&["--compression-level", "1", "--dict-samples", "40"], text).unwrap();
+        assert_eq!(s.compression_level, 1);
+        assert_eq!(s.dict_samples, 40);
+        assert!(resolve(&[], "[storage]\ncompression_level = 23\n").is_err());
+    }
+
+    #[test]
+    fn defaults_without_config() {
+        let s = resolve(&[], "").unwrap();
+        assert_eq!(s.interval, Duration::from_secs(10));
+        assert_eq!(s.statements_interval, Duration::from_secs(30));
+        assert_eq!(s.logs_interval, Duration::ZERO);
+        assert_eq!(s.output_dir, "./data");
+        assert!(s.postgres);
+        assert_eq!(s.pg_params, PgConnectionParams::default());
+        assert_eq!(s.log_source, LogSourceConfig::File);
+    }
+
+    #[test]
+    fn explicit_flags_override_config() {
+        let text =
+            "[intervals]\nprocesses = 5\n[storage]\nmax_days = 14\n[redaction]\nsql = \"hash\"\n";
+        let s = resolve(&["--interval", "20", "--redact-sql", "drop"], text).unwrap();
+        assert_eq!(s.interval, Duration::from_secs(20));
+        assert_eq!(s.redact_sql, RedactMode::Drop);
+        // Not given on the command line: the file wins over the default
+        assert_eq!(s.max_days, 14);
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        assert!(DaemonConfig::parse("[intervals]\nsnapshot = 5\n").is_err());
+        assert!(DaemonConfig::parse("[storage]\nmax_days = \"week\"\n").is_err());
+        assert!(resolve(&[], "[intervals]\nstatements = 0\n").is_err());
+        assert!(resolve(&[], "[storage]\nmax_size = \"lots\"\n").is_err());
+        assert!(resolve(&[], "[storage]\nblock_retention = [\"nope=1\"]\n").is_err());
+        assert!(resolve(&[], "[redaction]\nsql = \"blur\"\n").is_err());
+        assert!(resolve(&[], "[logs]\nsource = \"kafka\"\n").is_err());
+        // Fails startup instead of falling back to tailing files
+        let syslog = ["--log-source", "syslog", "--syslog-listen", "localhost"];
+        assert!(resolve(&syslog, "").is_err());
+    }
+
+    #[test]
+    fn parses_additional_instances() {
+        let text = r#"
+            [postgres]
+            port = 5432
+
+            [[postgres.instances]]
+            name = "analytics"
+            port = 5433
+            user = "rpglot"
+
+            [[postgres.instances]]
+            name = "queue"
+            host = "/run/postgresql"
+        "#;
+        let s = resolve(&[], text).unwrap();
+        assert_eq!(s.pg_params.port, Some(5432));
+        let names: Vec<&str> = s.pg_instances.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["analytics", "queue"]);
+        assert_eq!(s.pg_instances[0].1.port, Some(5433));
+        assert_eq!(s.pg_instances[0].1.user.as_deref(), Some("rpglot"));
+        assert_eq!(s.pg_instances[1].1.host.as_deref(), Some("/run/postgresql"));
+
+        let defaults = resolve(&[], "").unwrap();
+        assert!(defaults.pg_instances.is_empty());
+        assert!(defaults.postgres_changed(&s));
+
+        let tls = r#"
+            [postgres]
+            sslmode = "verify-full"
+            sslrootcert = "/etc/rpglot/root.crt"
+            connect_timeout = 3
+
+            [[postgres.instances]]
+            name = "replica"
+            sslmode = "require"
+        "#;
+        let s = resolve(&[], tls).unwrap();
+        assert_eq!(s.pg_params.sslmode, Some(SslMode::VerifyFull));
+        assert_eq!(
+            s.pg_params.sslrootcert.as_deref(),
+            Some(Path::new("/etc/rpglot/root.crt"))
+        );
+        assert_eq!(s.pg_params.connect_timeout, Some(Duration::from_secs(3)));
+        assert_eq!(s.pg_instances[0].1.sslmode, Some(SslMode::Require));
+        assert!(resolve(&[], "[postgres]\nsslmode = \"on\"\n").is_err());
+
+        let duplicate =
+            "[[postgres.instances]]\nname = \"a\"\n[[postgres.instances]]\nname = \"a\"\n";
+        assert!(resolve(&[], duplicate).is_err());
+        assert!(DaemonConfig::parse("[[postgres.instances]]\nport = 5433\n").is_err());
+    }
+
+    #[test]
+    fn parses_discovery() {
+        assert_eq!(resolve(&[], "").unwrap().pg_discovery, None);
+
+        let text = "[postgres.discovery]\nenabled = true\nallow = [\"16/*\"]\n";
+        let s = resolve(&[], text).unwrap();
+        let filter = s.pg_discovery.unwrap();
+        assert_eq!(filter.allow, ["16/*"]);
+        assert!(filter.deny.is_empty());
+
+        // Flags given on the command line win over the file
+        let s = resolve(&["--pg-discover", "--pg-discover-deny", "5433,5434"], text).unwrap();
+        let filter = s.pg_discovery.unwrap();
+        assert_eq!(filter.allow, ["16/*"]);
+        assert_eq!(filter.deny, ["5433", "5434"]);
+        let s = resolve(
+            &["--pg-discover-allow", "*"],
+            "[postgres.discovery]\nenabled = true\nallow = [\"16/*\"]\n",
+        )
+        .unwrap();
+        assert_eq!(s.pg_discovery.unwrap().allow, ["*"]);
+    }
+}
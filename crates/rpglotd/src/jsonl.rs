@@ -0,0 +1,207 @@
+//! JSON Lines export (`--jsonl-output`): every collected snapshot is also
+//! appended to a file or named pipe as one JSON document per line, for log
+//! shippers like vector or fluent-bit.
+//!
+//! A line is `{"timestamp": ..., "blocks": [...], "strings": {...}}`:
+//! blocks as stored, optionally limited to `--jsonl-blocks`, and the interned
+//! strings they reference keyed by hash.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use rpglot_core::storage::model::DataBlock;
+use rpglot_core::storage::{Snapshot, StorageManager, StringInterner};
+
+/// One line of the export.
+#[derive(Serialize)]
+struct Record<'a> {
+    timestamp: i64,
+    blocks: &'a [DataBlock],
+    strings: BTreeMap<u64, &'a str>,
+}
+
+/// Parses `--jsonl-blocks`: comma-separated names from
+/// [`DataBlock::KIND_NAMES`] into a kind mask.
+pub(crate) fn parse_blocks(s: &str) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for name in s.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let idx = DataBlock::kind_by_name(name).ok_or_else(|| {
+            format!(
+                "unknown block '{}', expected one of: {}",
+                name,
+                DataBlock::KIND_NAMES.join(", ")
+            )
+        })?;
+        mask |= 1 << idx;
+    }
+    if mask == 0 {
+        return Err(format!("no block names in '{}'", s));
+    }
+    Ok(mask)
+}
+
+/// Appends snapshots to the export file.
+///
+/// A failed write closes the file; it is reopened on the next snapshot, so
+/// a pipe whose reader restarted resumes on its own. Pipes are opened
+/// without blocking: snapshots are skipped while nobody reads.
+pub(crate) struct JsonlExport {
+    path: PathBuf,
+    /// Kinds to export; `None` exports all blocks.
+    blocks: Option<u64>,
+    out: Option<BufWriter<File>>,
+}
+
+impl JsonlExport {
+    pub(crate) fn new(path: PathBuf, blocks: Option<u64>) -> Self {
+        Self {
+            path,
+            blocks,
+            out: None,
+        }
+    }
+
+    /// Appends `snapshot` with the strings of `interner` it references.
+    /// Errors are logged.
+    pub(crate) fn write(&mut self, snapshot: &Snapshot, interner: &StringInterner) {
+        if self.out.is_none() {
+            match open(&self.path) {
+                Ok(file) => {
+                    info!("JSON Lines export: writing to {}", self.path.display());
+                    self.out = Some(BufWriter::new(file));
+                }
+                // No reader on the pipe yet
+                Err(e) if e.raw_os_error() == Some(ENXIO) => return,
+                Err(e) => {
+                    warn!(
+                        "JSON Lines export: cannot open {}: {}",
+                        self.path.display(),
+                        e
+                    );
+                    return;
+                }
+            }
+        }
+        let Some(out) = self.out.as_mut() else {
+            return;
+        };
+        if let Err(e) = write_record(out, snapshot, interner, self.blocks) {
+            warn!("JSON Lines export to {} failed: {}", self.path.display(), e);
+            self.out = None;
+        }
+    }
+}
+
+/// ENXIO: opening a FIFO for writing without blocking while it has no reader.
+#[cfg(unix)]
+const ENXIO: i32 = libc::ENXIO;
+#[cfg(not(unix))]
+const ENXIO: i32 = 6;
+
+/// Opens `path` for appending, creating a regular file if missing. A FIFO
+/// is opened with O_NONBLOCK so a missing reader does not stall collection,
+/// then switched back to blocking writes so lines are never torn.
+fn open(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.append(true).create(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_NONBLOCK);
+    }
+    let file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsRawFd;
+        let fd = file.as_raw_fd();
+        // SAFETY: fcntl on a descriptor owned by `file`.
+        unsafe {
+            let flags = libc::fcntl(fd, libc::F_GETFL);
+            if flags < 0 || libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+    }
+    Ok(file)
+}
+
+/// Writes `snapshot` as one JSON line, keeping only the kinds in `blocks`.
+fn write_record(
+    out: &mut impl Write,
+    snapshot: &Snapshot,
+    interner: &StringInterner,
+    blocks: Option<u64>,
+) -> io::Result<()> {
+    let snapshot = match blocks {
+        Some(mask) => {
+            let mut filtered = snapshot.clone();
+            DataBlock::strip_kinds(&mut filtered.blocks, !mask);
+            Cow::Owned(filtered)
+        }
+        None => Cow::Borrowed(snapshot),
+    };
+    let strings = StorageManager::collect_snapshot_hashes(&snapshot)
+        .into_iter()
+        .filter_map(|hash| interner.resolve(hash).map(|s| (hash, s)))
+        .collect();
+    let record = Record {
+        timestamp: snapshot.timestamp,
+        blocks: &snapshot.blocks,
+        strings,
+    };
+    serde_json::to_writer(&mut *out, &record)?;
+    out.write_all(b"\n")?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpglot_core::storage::model::{PgStatStatementsInfo, ProcessInfo};
+
+    #[test]
+    fn parses_block_names() {
+        let mask = parse_blocks("processes, pg_stat_statements").unwrap();
+        assert_eq!(mask.count_ones(), 2);
+        assert!(parse_blocks("nope").is_err());
+        assert!(parse_blocks(",").is_err());
+    }
+
+    #[test]
+    fn writes_filtered_line_with_strings() {
+        let mut interner = StringInterner::new();
+        let name_hash = interner.intern("postgres");
+        interner.intern("unused");
+        let snapshot = Snapshot {
+            timestamp: 1000,
+            blocks: vec![
+                DataBlock::Processes(vec![ProcessInfo {
+                    name_hash,
+                    ..Default::default()
+                }]),
+                DataBlock::PgStatStatements(vec![PgStatStatementsInfo::default()]),
+            ],
+        };
+
+        let mut out = Vec::new();
+        let blocks = parse_blocks("processes").unwrap();
+        write_record(&mut out, &snapshot, &interner, Some(blocks)).unwrap();
+        let line = String::from_utf8(out).unwrap();
+        assert!(line.ends_with("}\n"));
+        assert_eq!(line.lines().count(), 1);
+
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["timestamp"], 1000);
+        assert_eq!(value["blocks"].as_array().unwrap().len(), 1);
+        assert!(value["blocks"][0].get("Processes").is_some());
+        let strings = value["strings"].as_object().unwrap();
+        assert_eq!(strings.len(), 1);
+        assert_eq!(strings[&name_hash.to_string()], "postgres");
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! Collects system metrics from /proc filesystem and stores them to disk.
 //! Supports hourly file segmentation and automatic rotation by size and age.
+//! Settings can also come from a TOML file (`--config`), re-read on SIGHUP.
+
+mod config;
+mod jsonl;
 
 use rpglot_core::util::print_pg_warning;
 use tikv_jemallocator::Jemalloc;
@@ -24,31 +28,52 @@ fn release_memory_to_os() {
 }
 
 use std::env;
+use std::path::{Path, PathBuf};
+use std::process;
 use std::ptr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use chrono::{Timelike, Utc};
-use clap::Parser;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
 use tracing::{Level, debug, error, info, warn};
 use tracing_subscriber::EnvFilter;
 
+#[cfg(all(
+    not(target_os = "linux"),
+    any(target_os = "freebsd", feature = "sysinfo")
+))]
+use rpglot_core::collector::HostFs;
 #[cfg(target_os = "linux")]
+use rpglot_core::collector::LoadThrottle;
 use rpglot_core::collector::RealFs;
-#[cfg(not(target_os = "linux"))]
+use rpglot_core::collector::log_collector::tailer::{LogSourceConfig, SyslogAddr};
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", feature = "sysinfo")))]
 use rpglot_core::collector::mock::MockFs;
-use rpglot_core::collector::{Collector, PostgresCollector};
-use rpglot_core::fmt::{FmtStyle, format_bytes};
+use rpglot_core::collector::{
+    AgentMonitor, Collector, CustomQueryDef, FileSystem, PostgresCollector, RedactMode,
+    load_custom_queries,
+};
+use rpglot_core::fmt::{FmtStyle, format_bytes, parse_size};
+use rpglot_core::storage::crypto;
 use rpglot_core::storage::model::DataBlock;
-use rpglot_core::storage::{RotationConfig, StorageManager};
+use rpglot_core::storage::{BlockRetention, StorageManager};
 use rpglot_core::util::is_container;
 
+use crate::config::{DaemonConfig, Settings};
+use crate::jsonl::JsonlExport;
+
 /// System metrics collector daemon.
 #[derive(Parser)]
 #[command(name = "rpglotd", about = "System metrics collector daemon", version = rpglot_core::VERSION)]
 struct Args {
+    /// TOML configuration file (intervals, storage, PostgreSQL connection,
+    /// log source, redaction). Explicit flags override it; re-read on SIGHUP.
+    #[arg(short, long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
     /// Collection interval in seconds.
     #[arg(short, long, default_value = "10")]
     interval: u64,
@@ -70,11 +95,109 @@ struct Args {
     #[arg(long, default_value = "7")]
     max_days: u32,
 
+    /// Shorter retention for selected data blocks, e.g. "processes,pg_stat_statements=7".
+    /// Older files are rewritten without these blocks instead of being removed. Repeatable.
+    #[arg(long, value_name = "BLOCKS=DAYS", value_parser = BlockRetention::parse)]
+    block_retention: Vec<BlockRetention>,
+
+    /// When storage writes are fsynced: none (never), wal-fsync (WAL every
+    /// --wal-fsync-interval seconds, chunks when written) or always (every WAL
+    /// frame, chunk and directory entry).
+    #[arg(long, value_name = "MODE", default_value = "wal-fsync", value_parser = ["none", "wal-fsync", "always"])]
+    durability: String,
+
+    /// Seconds between WAL fsyncs with --durability=wal-fsync; 0 fsyncs every snapshot.
+    #[arg(long, value_name = "SECS", default_value = "0")]
+    wal_fsync_interval: u64,
+
+    /// Write chunk files with O_DIRECT so they do not evict PostgreSQL pages
+    /// from the page cache.
+    #[arg(long)]
+    direct_io: bool,
+
+    /// zstd compression level of chunk files (1-22). Lower levels cost less
+    /// CPU when chunks are written, higher levels save disk.
+    #[arg(long, value_name = "LEVEL", default_value = "3", value_parser = clap::value_parser!(i32).range(1..=22))]
+    compression_level: i32,
+
+    /// Snapshots sampled to train each chunk's zstd dictionary; 0 disables
+    /// the dictionary.
+    #[arg(long, value_name = "N", default_value = "20")]
+    dict_samples: usize,
+
+    /// Encrypt chunks and WAL with the AES-256 key in this file (32 raw bytes
+    /// or 64 hex characters). Defaults to the RPGLOT_ENCRYPTION_KEY variable.
+    #[arg(long, value_name = "PATH")]
+    encryption_key_file: Option<PathBuf>,
+
+    /// Redact literals in collected query texts, plans and log statements
+    /// before storing: strip (replace with ?), hash, drop (whole text) or off.
+    #[arg(long, value_name = "MODE", default_value = "off", value_parser = RedactMode::parse)]
+    redact_sql: RedactMode,
+
+    /// TOML file with user-defined SQL metric queries (name, sql, interval,
+    /// column types), stored alongside the built-in PostgreSQL metrics.
+    #[arg(long, value_name = "PATH")]
+    custom_queries: Option<PathBuf>,
+
+    /// Also append every snapshot as a JSON document per line to this file
+    /// or named pipe (for vector, fluent-bit and similar shippers).
+    #[arg(long, value_name = "PATH")]
+    jsonl_output: Option<PathBuf>,
+
+    /// Blocks written to --jsonl-output, e.g. "pg_stat_activity,pg_stat_statements".
+    /// Defaults to all blocks.
+    #[arg(long, value_name = "BLOCKS", value_parser = jsonl::parse_blocks, requires = "jsonl_output")]
+    jsonl_blocks: Option<u64>,
+
+    /// Compiled BPF object (from crates/rpglot-ebpf) for per-process block
+    /// I/O latency histograms. Requires CAP_BPF and CAP_PERFMON.
+    #[cfg(all(feature = "ebpf", target_os = "linux"))]
+    #[arg(long, value_name = "PATH")]
+    ebpf_blk_latency: Option<PathBuf>,
+
+    /// Collect kernel events (OOM kills, I/O errors, read-only remounts) from
+    /// /dev/kmsg. Requires CAP_SYSLOG when kernel.dmesg_restrict=1.
+    #[cfg(target_os = "linux")]
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    kernel_events: bool,
+
+    /// systemd unit running PostgreSQL whose state (active/failed, restarts)
+    /// is recorded: a unit name, "auto" (detect from the postmaster's cgroup)
+    /// or "off".
+    #[cfg(target_os = "linux")]
+    #[arg(long, value_name = "UNIT", default_value = "auto")]
+    systemd_unit: String,
+
+    /// Record Kubernetes pod metadata (name, namespace, resource requests and
+    /// limits) from the downward API when running in a pod.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    k8s_metadata: bool,
+
+    /// Directory of the pod's downwardAPI volume.
+    #[arg(long, value_name = "DIR", default_value = "/etc/podinfo")]
+    k8s_podinfo: PathBuf,
+
     /// Enable PostgreSQL metrics collection.
     /// Uses PGUSER or $USER for connection. Disable with --postgres=false.
     #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
     postgres: bool,
 
+    /// Collect every PostgreSQL instance found in the process list (Linux).
+    /// Without an explicit host or port the first instance found becomes the
+    /// primary one.
+    #[arg(long)]
+    pg_discover: bool,
+
+    /// Only collect discovered instances matching these patterns (port, data
+    /// directory or name like "16/main"; `*` is a wildcard). Comma-separated.
+    #[arg(long, value_name = "PATTERNS", value_delimiter = ',')]
+    pg_discover_allow: Vec<String>,
+
+    /// Never collect discovered instances matching these patterns.
+    #[arg(long, value_name = "PATTERNS", value_delimiter = ',')]
+    pg_discover_deny: Vec<String>,
+
     /// Increase logging verbosity (-v for debug, -vv for trace). Default is info level.
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
@@ -92,30 +215,46 @@ struct Args {
     /// Useful for testing when container detection fails.
     #[arg(long)]
     force_cgroup: bool,
-}
 
-/// Parses a human-readable size string (e.g., "1G", "500M", "1024K") into bytes.
-fn parse_size(s: &str) -> Result<u64, String> {
-    let s = s.trim();
-    if s.is_empty() {
-        return Err("empty size string".to_string());
-    }
+    /// Where to read PostgreSQL logs from: file (pg_current_logfile()),
+    /// syslog (listen for datagrams) or journald (follow a systemd unit).
+    #[arg(long, value_name = "SOURCE", default_value = "file", value_parser = ["file", "syslog", "journald"])]
+    log_source: String,
 
-    let (num_str, multiplier) = if let Some(num) = s.strip_suffix('G') {
-        (num, 1024 * 1024 * 1024)
-    } else if let Some(num) = s.strip_suffix('M') {
-        (num, 1024 * 1024)
-    } else if let Some(num) = s.strip_suffix('K') {
-        (num, 1024)
-    } else {
-        (s, 1)
-    };
+    /// Syslog listen address for --log-source=syslog: UDP "host:port"
+    /// or a unix datagram socket path.
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:5514")]
+    syslog_listen: String,
+
+    /// systemd unit to follow for --log-source=journald.
+    #[arg(long, value_name = "UNIT", default_value = "postgresql")]
+    journald_unit: String,
+}
 
-    num_str
-        .trim()
-        .parse::<u64>()
-        .map(|n| n * multiplier)
-        .map_err(|e| format!("invalid size '{}': {}", s, e))
+/// Builds the log source configuration from --log-source and related options.
+fn parse_log_source(
+    source: &str,
+    syslog_listen: &str,
+    journald_unit: &str,
+) -> Result<LogSourceConfig, String> {
+    match source {
+        "file" => Ok(LogSourceConfig::File),
+        "syslog" => {
+            let addr =
+                if syslog_listen.contains('/') {
+                    SyslogAddr::Unix(syslog_listen.into())
+                } else {
+                    SyslogAddr::Udp(syslog_listen.parse().map_err(|e| {
+                        format!("invalid syslog address '{}': {}", syslog_listen, e)
+                    })?)
+                };
+            Ok(LogSourceConfig::Syslog(addr))
+        }
+        "journald" => Ok(LogSourceConfig::Journald {
+            unit: journald_unit.to_string(),
+        }),
+        other => Err(format!("unknown log source '{}'", other)),
+    }
 }
 
 /// Initializes the tracing subscriber with the appropriate log level.
@@ -142,6 +281,42 @@ fn init_logging(verbose: u8, quiet: bool) {
 }
 
 /// Describes the contents of a snapshot for logging.
+/// Logs the WAL validation result and finalizes snapshots left over from
+/// the previous run.
+fn recover_wal(storage: &mut StorageManager) {
+    let recovery = storage.wal_recovery().clone();
+    if recovery.lost > 0 {
+        warn!(
+            "WAL recovery: {} snapshots recovered, {} lost ({} corrupt bytes truncated: {})",
+            recovery.recovered,
+            recovery.lost,
+            recovery.truncated_bytes,
+            recovery.error.as_deref().unwrap_or("unknown error")
+        );
+    } else if recovery.recovered > 0 {
+        info!(
+            "WAL recovery: {} snapshots recovered, none lost",
+            recovery.recovered
+        );
+    }
+    if recovery.undecryptable {
+        warn!("WAL recovery: encrypted snapshots kept unread, no encryption key installed");
+    }
+
+    match storage.finalize_recovered_wal(Utc::now()) {
+        Ok(Some(path)) => info!(
+            "WAL recovery: {} snapshots finalized into {}",
+            recovery.recovered,
+            path.display()
+        ),
+        Ok(None) => {}
+        Err(e) => error!(
+            "WAL recovery: failed to finalize recovered snapshots: {}",
+            e
+        ),
+    }
+}
+
 fn describe_snapshot(snapshot: &rpglot_core::storage::Snapshot) -> String {
     let mut parts: Vec<String> = Vec::new();
 
@@ -172,6 +347,32 @@ fn describe_snapshot(snapshot: &rpglot_core::storage::Snapshot) -> String {
                 parts.push(format!("{} settings", s.len()))
             }
             DataBlock::ReplicationStatus(_) => parts.push("replication".to_string()),
+            DataBlock::CustomQuery(q) => parts.push(format!("{} custom_queries", q.len())),
+            DataBlock::SystemTcpConn(t) => {
+                parts.push(format!("{} pg_tcp_established", t.established))
+            }
+            DataBlock::KernelEvents(e) => parts.push(format!("{} kernel_events", e.len())),
+            DataBlock::SystemdUnit(u) => parts.push(format!("unit {}", u.active_state)),
+            DataBlock::PgInstance(i) => {
+                parts.push(format!("instance {} ({} blocks)", i.name, i.blocks.len()))
+            }
+            DataBlock::CollectionDegraded(_) => parts.push("degraded".to_string()),
+            DataBlock::AgentStats(a) => parts.push(format!("agent {}KB", a.rss_kb)),
+            DataBlock::K8sPod(p) => parts.push(format!("pod {}/{}", p.namespace, p.pod)),
+            DataBlock::PgConnection(c) => parts.push(format!("pg {}", c.state.as_str())),
+            DataBlock::PgRelationSizes(r) => parts.push(format!("{} relation_sizes", r.len())),
+            DataBlock::PgSequences(s) => parts.push(format!("{} sequences", s.len())),
+            DataBlock::PgBufferCache(b) => parts.push(format!(
+                "buffercache {}/{}",
+                b.used_buffers, b.total_buffers
+            )),
+            DataBlock::PgStatArchiver(_) => parts.push("pg_archiver".to_string()),
+            DataBlock::Clock(c) => parts.push(format!("seq {}", c.seq)),
+            DataBlock::PgSlowPlans(p) => parts.push(format!("{} slow_plans", p.len())),
+            DataBlock::PgPendingRestart(n) => parts.push(format!("{} pending_restart", n.len())),
+            // Same rows as PgSettings
+            DataBlock::PgSettingsMeta(_) => {}
+            DataBlock::PgExtensions(e) => parts.push(format!("{} extensions", e.len())),
             DataBlock::SystemCpu(c) => parts.push(format!("{} cpus", c.len())),
             DataBlock::SystemLoad(_) => parts.push("load".to_string()),
             DataBlock::SystemMem(_) => parts.push("mem".to_string()),
@@ -184,6 +385,9 @@ fn describe_snapshot(snapshot: &rpglot_core::storage::Snapshot) -> String {
             DataBlock::SystemNetSnmp(_) => parts.push("netsnmp".to_string()),
             DataBlock::Cgroup(_) => parts.push("cgroup".to_string()),
             DataBlock::PgLogEvents(_) => parts.push("log_events_counts".to_string()),
+            DataBlock::PgConnectionChurn(c) => {
+                parts.push(format!("{} new_connections", c.connections()))
+            }
             // Empty guards for PgLogErrors/PgLogDetailedEvents/PgStatProgressVacuum/PgSettings
             // fall through here (guard `if !is_empty()` didn't match).
             DataBlock::PgLogErrors(_)
@@ -196,24 +400,325 @@ fn describe_snapshot(snapshot: &rpglot_core::storage::Snapshot) -> String {
     parts.join(", ")
 }
 
+/// Set by the SIGHUP handler, checked once per collection loop.
+#[cfg(unix)]
+static RELOAD_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn handle_sighup(_: libc::c_int) {
+    RELOAD_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Requests a config reload on SIGHUP.
+#[cfg(unix)]
+fn install_sighup_handler() {
+    let handler = handle_sighup as extern "C" fn(libc::c_int);
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe.
+    if unsafe { libc::signal(libc::SIGHUP, handler as libc::sighandler_t) } == libc::SIG_ERR {
+        warn!("Failed to set SIGHUP handler, config reload disabled");
+    }
+}
+
+/// Reads the config file (if any) and merges it with the command line.
+fn load_settings(args: &Args, matches: &ArgMatches) -> Result<Settings, String> {
+    let config = args.config.as_deref().map(DaemonConfig::load).transpose()?;
+    Settings::resolve(args, matches, config.as_ref())
+}
+
+fn load_queries(path: Option<&Path>) -> Result<Vec<CustomQueryDef>, String> {
+    match path {
+        Some(path) => load_custom_queries(path)
+            .map_err(|e| format!("failed to load custom queries {}: {}", path.display(), e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Creates the PostgreSQL collector and tries to connect.
+/// Returns `None` when PostgreSQL collection is disabled or not configured.
+fn build_postgres(
+    settings: &Settings,
+    custom_queries: Vec<CustomQueryDef>,
+) -> Option<PostgresCollector> {
+    if !settings.postgres {
+        debug!("PostgreSQL collector: disabled");
+        return None;
+    }
+    let params = &settings.pg_params;
+    if settings.pg_discovery.is_some()
+        && params.host.is_none()
+        && params.port.is_none()
+        && env::var_os("PGHOST").is_none()
+        && env::var_os("PGPORT").is_none()
+    {
+        info!("PostgreSQL collector: primary instance chosen by discovery");
+        return None;
+    }
+    let pg_host = params
+        .host
+        .clone()
+        .unwrap_or_else(|| env::var("PGHOST").unwrap_or_else(|_| "localhost".to_string()));
+    let pg_port = params
+        .port
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| env::var("PGPORT").unwrap_or_else(|_| "5432".to_string()));
+
+    match PostgresCollector::from_params(params) {
+        Ok(pg_collector) => {
+            debug!("PostgreSQL log source: {:?}", settings.log_source);
+            let mut pg_collector = pg_collector
+                .with_log_source(settings.log_source.clone())
+                .with_redaction(settings.redact_sql)
+                .with_custom_queries(custom_queries)
+                .with_statements_interval(settings.statements_interval)
+                .with_tables_interval(settings.tables_interval);
+            match pg_collector.try_connect() {
+                Ok(()) => {
+                    info!(
+                        "PostgreSQL collector: enabled, connected to {}:{}",
+                        pg_host, pg_port
+                    );
+                }
+                Err(e) => {
+                    warn!("PostgreSQL collector: connection failed ({})", e);
+                    print_pg_warning(&e.to_string());
+                }
+            }
+            Some(pg_collector)
+        }
+        Err(e) => {
+            warn!("PostgreSQL collector: disabled ({})", e);
+            print_pg_warning(&e.to_string());
+            None
+        }
+    }
+}
+
+/// Builds collectors for the additional `[[postgres.instances]]`.
+///
+/// They share intervals, redaction and custom queries with the primary
+/// instance; logs are read from the files of each instance.
+fn build_postgres_instances(
+    settings: &Settings,
+    custom_queries: &[CustomQueryDef],
+) -> Vec<(String, PostgresCollector)> {
+    if !settings.postgres {
+        return Vec::new();
+    }
+    let mut instances = Vec::new();
+    for (name, params) in &settings.pg_instances {
+        let mut pg_collector = match PostgresCollector::from_params(params) {
+            Ok(pg_collector) => pg_collector
+                .with_redaction(settings.redact_sql)
+                .with_custom_queries(custom_queries.to_vec())
+                .with_statements_interval(settings.statements_interval)
+                .with_tables_interval(settings.tables_interval),
+            Err(e) => {
+                warn!("PostgreSQL instance {}: disabled ({})", name, e);
+                continue;
+            }
+        };
+        match pg_collector.try_connect() {
+            Ok(()) => info!("PostgreSQL instance {}: enabled", name),
+            Err(e) => warn!("PostgreSQL instance {}: connection failed ({})", name, e),
+        }
+        instances.push((name.clone(), pg_collector));
+    }
+    instances
+}
+
+/// Instance discovery with the settings of configured instances, `None`
+/// when disabled.
+fn build_discovery(
+    settings: &Settings,
+    custom_queries: &[CustomQueryDef],
+) -> Option<rpglot_core::collector::InstanceDiscovery> {
+    let filter = settings
+        .pg_discovery
+        .clone()
+        .filter(|_| settings.postgres)?;
+    let (redact_sql, statements_interval, tables_interval) = (
+        settings.redact_sql,
+        settings.statements_interval,
+        settings.tables_interval,
+    );
+    let custom_queries = custom_queries.to_vec();
+    info!("PostgreSQL instance discovery: enabled");
+    Some(rpglot_core::collector::InstanceDiscovery::new(
+        filter,
+        Box::new(move |params| {
+            let mut pg_collector = PostgresCollector::from_params(&params)?
+                .with_redaction(redact_sql)
+                .with_custom_queries(custom_queries.clone())
+                .with_statements_interval(statements_interval)
+                .with_tables_interval(tables_interval);
+            if let Err(e) = pg_collector.try_connect() {
+                warn!("Discovered PostgreSQL instance: connection failed ({})", e);
+            }
+            Ok(pg_collector)
+        }),
+    ))
+}
+
+/// Re-reads the config file and applies what changed. On any error the
+/// current settings stay in effect.
+fn reload_settings<F: FileSystem + Clone>(
+    args: &Args,
+    matches: &ArgMatches,
+    current: &mut Settings,
+    collector: &mut Collector<F>,
+    storage: &mut StorageManager,
+) {
+    let new = match load_settings(args, matches) {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Config reload failed, keeping current settings: {}", e);
+            return;
+        }
+    };
+    if new == *current {
+        info!("Config reloaded: no changes");
+        return;
+    }
+    let custom_queries = if new.postgres_changed(current) {
+        match load_queries(new.custom_queries.as_deref()) {
+            Ok(queries) => Some(queries),
+            Err(e) => {
+                error!("Config reload failed, keeping current settings: {}", e);
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    if new.output_dir != current.output_dir {
+        warn!("Config reload: output_dir change takes effect after restart");
+    }
+    if new.encryption_key_file != current.encryption_key_file {
+        warn!("Config reload: encryption_key_file change takes effect after restart");
+    }
+
+    match custom_queries {
+        Some(queries) => {
+            #[cfg(target_os = "linux")]
+            collector.set_discovery(build_discovery(&new, &queries));
+            collector.set_postgres_instances(build_postgres_instances(&new, &queries));
+            collector.set_postgres(build_postgres(&new, queries));
+        }
+        None => {
+            for pg_collector in collector.postgres_collectors_mut() {
+                pg_collector.set_statements_interval(new.statements_interval);
+                pg_collector.set_tables_interval(new.tables_interval);
+                pg_collector.set_redaction(new.redact_sql);
+            }
+        }
+    }
+    collector.set_log_interval(new.logs_interval);
+    if new.throttle != current.throttle || new.interval != current.interval {
+        collector.set_throttle(new.throttle.map(|t| LoadThrottle::new(new.interval, t)));
+    }
+    if new.durability != current.durability || new.direct_io != current.direct_io {
+        info!(
+            "Storage durability: {}{}",
+            new.durability.as_str(),
+            if new.direct_io { ", O_DIRECT" } else { "" }
+        );
+        storage.set_durability(new.durability);
+        storage.set_direct_io(new.direct_io);
+    }
+    if new.compression_level != current.compression_level
+        || new.dict_samples != current.dict_samples
+    {
+        info!(
+            "Chunk compression: level {}, {} dictionary samples",
+            new.compression_level, new.dict_samples
+        );
+        storage.set_compression(new.compression_level, new.dict_samples);
+    }
+    if new.redact_sql != current.redact_sql {
+        info!("SQL text redaction: {}", new.redact_sql.as_str());
+        if let Err(e) = new.redact_sql.write_marker(Path::new(&current.output_dir)) {
+            warn!("Failed to write redaction marker: {}", e);
+        }
+    }
+
+    info!(
+        "Config reloaded: interval={}s, max_size={}, max_days={}",
+        new.interval.as_secs(),
+        format_bytes(new.max_size, FmtStyle::Compact),
+        new.max_days
+    );
+    *current = Settings {
+        output_dir: current.output_dir.clone(),
+        encryption_key_file: current.encryption_key_file.clone(),
+        ..new
+    };
+}
+
 fn main() {
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
     // Initialize logging
     init_logging(args.verbose, args.quiet);
 
-    let rotation_config = RotationConfig::new(args.max_size, args.max_days);
+    let mut settings = match load_settings(&args, &matches) {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Invalid configuration: {}", e);
+            process::exit(1);
+        }
+    };
 
     info!("rpglotd {} starting", rpglot_core::VERSION);
+    if let Some(ref path) = args.config {
+        info!("Config file: {}", path.display());
+    }
     info!(
         "Config: interval={}s, output={}, proc={}",
-        args.interval, args.output_dir, args.proc_path
+        settings.interval.as_secs(),
+        settings.output_dir,
+        args.proc_path
     );
     info!(
         "Rotation policy: max_size={}, max_days={}",
-        format_bytes(args.max_size, FmtStyle::Compact),
-        args.max_days
+        format_bytes(settings.max_size, FmtStyle::Compact),
+        settings.max_days
     );
+    for rule in &settings.block_retention {
+        let names: Vec<&str> = DataBlock::KIND_NAMES
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| rule.blocks & (1 << idx) != 0)
+            .map(|(_, name)| *name)
+            .collect();
+        info!(
+            "Block retention: {} kept {} days",
+            names.join(","),
+            rule.days
+        );
+    }
+    match crypto::install_from(settings.encryption_key_file.as_deref()) {
+        Ok(true) => info!("Storage encryption enabled"),
+        Ok(false) => {}
+        Err(e) => {
+            error!("Failed to load encryption key: {}", e);
+            process::exit(1);
+        }
+    }
+
+    let custom_queries = match load_queries(settings.custom_queries.as_deref()) {
+        Ok(queries) => {
+            if let Some(ref path) = settings.custom_queries {
+                info!("Custom queries: {} from {}", queries.len(), path.display());
+            }
+            queries
+        }
+        Err(e) => {
+            error!("{}", e);
+            process::exit(1);
+        }
+    };
 
     // Create collector
     #[cfg(target_os = "linux")]
@@ -230,6 +735,9 @@ fn main() {
     };
     #[cfg(not(target_os = "linux"))]
     let mut collector = {
+        #[cfg(any(target_os = "freebsd", feature = "sysinfo"))]
+        let fs = HostFs::new();
+        #[cfg(not(any(target_os = "freebsd", feature = "sysinfo")))]
         let fs = MockFs::new();
         let mut c = Collector::new(fs, &args.proc_path);
         // Handle cgroup options
@@ -254,41 +762,99 @@ fn main() {
         debug!("Cgroup collector: disabled (bare metal)");
     }
 
-    // Enable PostgreSQL collector if requested
-    if args.postgres {
-        let pg_host = env::var("PGHOST").unwrap_or_else(|_| "localhost".to_string());
-        let pg_port = env::var("PGPORT").unwrap_or_else(|_| "5432".to_string());
-
-        match PostgresCollector::from_env() {
-            Ok(mut pg_collector) => {
-                match pg_collector.try_connect() {
-                    Ok(()) => {
-                        info!(
-                            "PostgreSQL collector: enabled, connected to {}:{}",
-                            pg_host, pg_port
-                        );
-                    }
-                    Err(e) => {
-                        warn!("PostgreSQL collector: connection failed ({})", e);
-                        print_pg_warning(&e.to_string());
-                    }
-                }
-                collector = collector.with_postgres(pg_collector);
+    #[cfg(all(feature = "ebpf", target_os = "linux"))]
+    if let Some(ref path) = args.ebpf_blk_latency {
+        match rpglot_core::collector::BlkLatencyCollector::load(path) {
+            Ok(blk_latency) => {
+                info!("Block I/O latency collector: enabled ({})", path.display());
+                collector = collector.with_blk_latency(blk_latency);
             }
             Err(e) => {
-                warn!("PostgreSQL collector: disabled ({})", e);
-                print_pg_warning(&e.to_string());
+                error!("Failed to start block I/O latency collector: {}", e);
+                process::exit(1);
             }
         }
-    } else {
-        debug!("PostgreSQL collector: disabled");
     }
 
+    #[cfg(target_os = "linux")]
+    if args.kernel_events {
+        match rpglot_core::collector::KmsgCollector::open() {
+            Ok(kmsg) => {
+                info!("Kernel events collector: enabled");
+                collector = collector.with_kmsg(kmsg);
+            }
+            Err(e) => warn!("Kernel events collector disabled: {}", e),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    match args.systemd_unit.as_str() {
+        "off" => debug!("systemd unit collector: disabled"),
+        "auto" => {
+            info!("systemd unit collector: enabled (unit detected from postmaster cgroup)");
+            collector = collector.with_systemd(rpglot_core::collector::SystemdCollector::new(None));
+        }
+        unit => {
+            info!("systemd unit collector: enabled ({})", unit);
+            collector = collector.with_systemd(rpglot_core::collector::SystemdCollector::new(
+                Some(unit.to_string()),
+            ));
+        }
+    }
+
+    if args.k8s_metadata
+        && let Some(k8s) = rpglot_core::collector::K8sCollector::detect(&args.k8s_podinfo)
+    {
+        info!("Kubernetes metadata collector: enabled");
+        collector = collector.with_k8s(k8s);
+    }
+
+    // Enable PostgreSQL collector if requested
+    if settings.redact_sql.is_active() {
+        info!("SQL text redaction: {}", settings.redact_sql.as_str());
+    }
+    #[cfg(target_os = "linux")]
+    collector.set_discovery(build_discovery(&settings, &custom_queries));
+    collector.set_postgres_instances(build_postgres_instances(&settings, &custom_queries));
+    collector.set_postgres(build_postgres(&settings, custom_queries));
+    collector.set_log_interval(settings.logs_interval);
+    match settings.throttle {
+        Some(t) => info!(
+            "Adaptive throttling: enabled (budget {}% of interval, PSI {}%, {} cycles)",
+            t.budget_pct, t.psi_critical, t.cycles
+        ),
+        None => debug!("Adaptive throttling: disabled"),
+    }
+    collector.set_throttle(
+        settings
+            .throttle
+            .map(|t| LoadThrottle::new(settings.interval, t)),
+    );
+
     // Initialize storage
-    let mut storage = StorageManager::new(&args.output_dir);
-    info!("Storage initialized at {}", args.output_dir);
+    let mut storage = StorageManager::new(&settings.output_dir);
+    storage.set_durability(settings.durability);
+    storage.set_direct_io(settings.direct_io);
+    storage.set_compression(settings.compression_level, settings.dict_samples);
+    info!(
+        "Storage initialized at {} (durability={}{}, zstd level {})",
+        settings.output_dir,
+        settings.durability.as_str(),
+        if settings.direct_io { ", O_DIRECT" } else { "" },
+        settings.compression_level
+    );
+    recover_wal(&mut storage);
+    if let Err(e) = settings
+        .redact_sql
+        .write_marker(Path::new(&settings.output_dir))
+    {
+        warn!("Failed to write redaction marker: {}", e);
+    }
 
-    let interval = Duration::from_secs(args.interval);
+    let mut jsonl = args.jsonl_output.clone().map(|path| {
+        info!("JSON Lines export: {}", path.display());
+        JsonlExport::new(path, args.jsonl_blocks)
+    });
 
     // Setup graceful shutdown
     let running = Arc::new(AtomicBool::new(true));
@@ -300,19 +866,25 @@ fn main() {
     }) {
         warn!("Failed to set Ctrl-C handler: {}", e);
     }
+    #[cfg(unix)]
+    install_sighup_handler();
 
     // Track the last hour when rotation was performed
     let mut last_rotation_hour: Option<u32> = None;
     let mut snapshot_count: u64 = 0;
 
     // Run initial rotation on startup
-    match storage.rotate(&rotation_config) {
+    match storage.rotate(&settings.rotation()) {
         Ok(result) => {
-            if result.files_removed_by_age > 0 || result.files_removed_by_size > 0 {
+            if result.files_removed_by_age > 0
+                || result.files_removed_by_size > 0
+                || result.files_stripped > 0
+            {
                 info!(
-                    "Initial rotation: removed {} by age, {} by size, freed {}",
+                    "Initial rotation: removed {} by age, {} by size, stripped blocks from {}, freed {}",
                     result.files_removed_by_age,
                     result.files_removed_by_size,
+                    result.files_stripped,
                     format_bytes(result.bytes_freed, FmtStyle::Compact)
                 );
             }
@@ -329,12 +901,30 @@ fn main() {
 
     info!("Starting collection loop");
 
+    let mut agent = AgentMonitor::new();
+    let mut last_write = Duration::ZERO;
+
     while running.load(Ordering::SeqCst) {
+        #[cfg(unix)]
+        if RELOAD_REQUESTED.swap(false, Ordering::SeqCst) {
+            match args.config {
+                Some(_) => {
+                    reload_settings(&args, &matches, &mut settings, &mut collector, &mut storage)
+                }
+                None => info!("Received SIGHUP without --config, nothing to reload"),
+            }
+        }
+
         let current_hour = Utc::now().hour();
 
         match collector.collect_snapshot() {
-            Ok(snapshot) => {
+            Ok(mut snapshot) => {
                 snapshot_count += 1;
+                if let Some(timing) = collector.last_timing() {
+                    snapshot
+                        .blocks
+                        .push(DataBlock::AgentStats(agent.sample(timing, last_write)));
+                }
                 let description = describe_snapshot(&snapshot);
                 let serialized_size = postcard::to_allocvec(&snapshot)
                     .map(|s| s.len())
@@ -348,13 +938,19 @@ fn main() {
                 );
 
                 // Log PostgreSQL error if any
-                if args.postgres
+                if settings.postgres
                     && let Some(error) = collector.pg_last_error()
                 {
                     warn!("PostgreSQL: {}", error);
                 }
 
+                if let Some(ref mut jsonl) = jsonl {
+                    jsonl.write(&snapshot, collector.interner());
+                }
+
+                let write_started = Instant::now();
                 let chunk_flushed = storage.add_snapshot(snapshot, collector.interner());
+                last_write = write_started.elapsed();
                 debug!("WAL: {} snapshots pending", storage.current_chunk_size());
 
                 // Clear interner after each snapshot to prevent memory accumulation
@@ -384,13 +980,17 @@ fn main() {
         if last_rotation_hour != Some(current_hour) {
             last_rotation_hour = Some(current_hour);
 
-            match storage.rotate(&rotation_config) {
+            match storage.rotate(&settings.rotation()) {
                 Ok(result) => {
-                    if result.files_removed_by_age > 0 || result.files_removed_by_size > 0 {
+                    if result.files_removed_by_age > 0
+                        || result.files_removed_by_size > 0
+                        || result.files_stripped > 0
+                    {
                         info!(
-                            "Rotation: removed {} by age, {} by size, freed {}, {} files remaining ({})",
+                            "Rotation: removed {} by age, {} by size, stripped blocks from {}, freed {}, {} files remaining ({})",
                             result.files_removed_by_age,
                             result.files_removed_by_size,
+                            result.files_stripped,
                             format_bytes(result.bytes_freed, FmtStyle::Compact),
                             result.files_remaining,
                             format_bytes(result.total_size_after, FmtStyle::Compact)
@@ -405,7 +1005,7 @@ fn main() {
 
         // Sleep with periodic checks for shutdown signal
         let sleep_interval = Duration::from_millis(100);
-        let mut remaining = interval;
+        let mut remaining = settings.interval;
         while remaining > Duration::ZERO && running.load(Ordering::SeqCst) {
             let sleep_time = remaining.min(sleep_interval);
             thread::sleep(sleep_time);
@@ -431,7 +1031,8 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use super::describe_snapshot;
+    use super::{describe_snapshot, parse_log_source};
+    use rpglot_core::collector::log_collector::tailer::{LogSourceConfig, SyslogAddr};
     use rpglot_core::storage::Snapshot;
     use rpglot_core::storage::model::{DataBlock, PgStatActivityInfo, PgStatStatementsInfo};
 
@@ -456,4 +1057,27 @@ mod tests {
         assert!(desc.contains("1 pg_sessions"));
         assert!(desc.contains("2 pg_stat_statements"));
     }
+
+    #[test]
+    fn parse_log_source_variants() {
+        assert_eq!(
+            parse_log_source("file", "127.0.0.1:5514", "postgresql").unwrap(),
+            LogSourceConfig::File
+        );
+        assert_eq!(
+            parse_log_source("syslog", "127.0.0.1:5514", "postgresql").unwrap(),
+            LogSourceConfig::Syslog(SyslogAddr::Udp("127.0.0.1:5514".parse().unwrap()))
+        );
+        assert_eq!(
+            parse_log_source("syslog", "/run/rpglot/syslog.sock", "postgresql").unwrap(),
+            LogSourceConfig::Syslog(SyslogAddr::Unix("/run/rpglot/syslog.sock".into()))
+        );
+        assert_eq!(
+            parse_log_source("journald", "", "postgresql@16-main").unwrap(),
+            LogSourceConfig::Journald {
+                unit: "postgresql@16-main".to_string()
+            }
+        );
+        assert!(parse_log_source("syslog", "localhost", "postgresql").is_err());
+    }
 }